@@ -88,6 +88,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("CreateCollection.optimizers_config", ""),
             ("CreateCollection.vectors_config", ""),
             ("CreateCollection.quantization_config", ""),
+            ("CreateCollection.strict_mode_config", ""),
             ("UpdateCollection.collection_name", "length(min = 1, max = 255)"),
             ("UpdateCollection.optimizers_config", ""),
             ("UpdateCollection.params", ""),
@@ -95,6 +96,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("UpdateCollection.hnsw_config", ""),
             ("UpdateCollection.vectors_config", ""),
             ("UpdateCollection.quantization_config", ""),
+            ("UpdateCollection.strict_mode_config", ""),
             ("DeleteCollection.collection_name", "length(min = 1, max = 255)"),
             ("DeleteCollection.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("CollectionConfig.params", ""),
@@ -106,6 +108,12 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ListCollectionAliasesRequest.collection_name", "length(min = 1, max = 255)"),
             ("HnswConfigDiff.ef_construct", "custom = \"crate::grpc::validate::validate_u64_range_min_4\""),
             ("WalConfigDiff.wal_capacity_mb", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.max_filter_conditions", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.upsert_max_batchsize", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.max_query_limit", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.max_point_payload_bytes", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.max_concurrent_searches", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("StrictModeConfig.max_updates_per_second", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("OptimizersConfigDiff.deleted_threshold", "custom = \"crate::grpc::validate::validate_f64_range_1\""),
             ("OptimizersConfigDiff.vacuum_min_vector_number", "custom = \"crate::grpc::validate::validate_u64_range_min_100\""),
             ("VectorsConfig.config", ""),
@@ -120,6 +128,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("QuantizationConfig.quantization", ""),
             ("QuantizationConfigDiff.quantization", ""),
             ("ScalarQuantization.quantile", "custom = \"crate::grpc::validate::validate_f32_range_min_0_5_max_1\""),
+            ("BinaryQuantization.min_dim", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
         ], &[
             "ListCollectionsRequest",
             "CollectionParamsDiff",
@@ -135,6 +144,14 @@ fn configure_validation(builder: Builder) -> Builder {
         .validates(&[
             ("GetCollectionInfoRequestInternal.get_collection_info_request", ""),
             ("InitiateShardTransferRequest.collection_name", "length(min = 1, max = 255)"),
+            ("GetShardRecoveryPointRequest.collection_name", "length(min = 1, max = 255)"),
+            ("UploadShardSnapshotChunkRequest.collection_name", "length(min = 1, max = 255)"),
+            ("RecoverShardSnapshotRequest.collection_name", "length(min = 1, max = 255)"),
+            ("CreateShardSnapshotRequest.collection_name", "length(min = 1, max = 255)"),
+            ("GetShardSnapshotChunkRequest.collection_name", "length(min = 1, max = 255)"),
+            ("GetShardSnapshotChunkRequest.name", "length(min = 1)"),
+            ("DeleteShardSnapshotRequest.collection_name", "length(min = 1, max = 255)"),
+            ("DeleteShardSnapshotRequest.name", "length(min = 1)"),
         ], &[])
         // Service: points.proto
         .validates(&[
@@ -150,6 +167,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ClearPayloadPoints.collection_name", "length(min = 1, max = 255)"),
             ("UpdateBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("UpdateBatchPoints.operations", "length(min = 1)"),
+            ("UpsertStreamRequest.collection_name", "length(min = 1, max = 255)"),
             ("CreateFieldIndexCollection.collection_name", "length(min = 1, max = 255)"),
             ("CreateFieldIndexCollection.field_name", "length(min = 1)"),
             ("DeleteFieldIndexCollection.collection_name", "length(min = 1, max = 255)"),
@@ -170,6 +188,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("QuantizationSearchParams.oversampling", "custom = \"crate::grpc::validate::validate_f64_range_min_1\""),
             ("ScrollPoints.collection_name", "length(min = 1, max = 255)"),
             ("ScrollPoints.limit", "custom = \"crate::grpc::validate::validate_u32_range_min_1\""),
+            ("ScrollPoints.page_token", "length(min = 1)"),
             ("RecommendPoints.collection_name", "length(min = 1, max = 255)"),
             ("RecommendPoints.params", ""),
             ("RecommendBatchPoints.collection_name", "length(min = 1, max = 255)"),
@@ -179,8 +198,16 @@ fn configure_validation(builder: Builder) -> Builder {
             ("RecommendPointGroups.group_size", "range(min = 1)"),
             ("RecommendPointGroups.limit", "range(min = 1)"),
             ("RecommendPointGroups.params", ""),
+            ("DiscoverPoints.collection_name", "length(min = 1, max = 255)"),
+            ("DiscoverPoints.context", "length(min = 1)"),
+            ("DiscoverPoints.limit", "range(min = 1)"),
+            ("DiscoverPoints.params", ""),
             ("CountPoints.collection_name", "length(min = 1, max = 255)"),
-            ("GeoPolygon.points", "custom = \"crate::grpc::validate::validate_geo_polygon\""),
+            ("FacetCounts.collection_name", "length(min = 1, max = 255)"),
+            ("FacetCounts.key", "length(min = 1)"),
+            ("FacetCounts.limit", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("GeoPolygon.exterior", "custom = \"crate::grpc::validate::validate_geo_polygon_exterior\""),
+            ("GeoPolygon.interiors", "custom = \"crate::grpc::validate::validate_geo_polygon_interiors\""),
         ], &[])
         .type_attribute(".", "#[derive(serde::Serialize)]")
         // Service: points_internal_service.proto
@@ -197,10 +224,18 @@ fn configure_validation(builder: Builder) -> Builder {
             ("SearchPointsInternal.search_points", ""),
             ("SearchBatchPointsInternal.collection_name", "length(min = 1, max = 255)"),
             ("SearchBatchPointsInternal.search_points", ""),
+            ("CoreSearchBatchPointsInternal.collection_name", "length(min = 1, max = 255)"),
+            ("CoreSearchBatchPointsInternal.search_points", ""),
+            ("CoreSearchPoints.collection_name", "length(min = 1, max = 255)"),
+            ("CoreSearchPoints.limit", "range(min = 1)"),
+            ("CoreSearchPoints.params", ""),
+            ("CoreSearchPoints.vector_name", "custom = \"crate::grpc::validate::validate_not_empty\""),
             ("RecommendPointsInternal.recommend_points", ""),
             ("ScrollPointsInternal.scroll_points", ""),
             ("GetPointsInternal.get_points", ""),
             ("CountPointsInternal.count_points", ""),
+            ("FacetCountsInternal.facet_counts", ""),
+            ("UpdateBatchInternal.update_batch", ""),
             ("SyncPointsInternal.sync_points", ""),
             ("SyncPoints.collection_name", "length(min = 1, max = 255)"),
         ], &[])
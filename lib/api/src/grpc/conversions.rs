@@ -19,15 +19,17 @@ use crate::grpc::qdrant::value::Kind;
 use crate::grpc::qdrant::vectors::VectorsOptions;
 use crate::grpc::qdrant::with_payload_selector::SelectorOptions;
 use crate::grpc::qdrant::{
-    with_vectors_selector, CollectionDescription, CollectionOperationResponse, Condition, Distance,
-    FieldCondition, Filter, GeoBoundingBox, GeoPoint, GeoPolygon, GeoRadius, HasIdCondition,
-    HealthCheckReply, HnswConfigDiff, IsEmptyCondition, IsNullCondition, ListCollectionsResponse,
-    ListValue, Match, NamedVectors, NestedCondition, PayloadExcludeSelector,
-    PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo, PayloadSchemaType, PointId,
-    ProductQuantization, QuantizationConfig, QuantizationSearchParams, QuantizationType, Range,
-    RepeatedIntegers, RepeatedStrings, ScalarQuantization, ScoredPoint, SearchParams, Struct,
-    TextIndexParams, TokenizerType, Value, ValuesCount, Vector, Vectors, VectorsSelector,
-    WithPayloadSelector, WithVectorsSelector,
+    facet_value, order_value, with_vectors_selector, BinaryQuantization, CollectionDescription,
+    CollectionOperationResponse, Condition, Datatype, DatetimeRange, Direction, Distance, FacetHit,
+    FacetValue, FieldCondition, Filter, GeoBoundingBox, GeoLineString, GeoPoint, GeoPolygon,
+    GeoRadius, HasIdCondition, HealthCheckReply, HnswConfigDiff, IsEmptyCondition, IsNullCondition,
+    KeywordIndexParams, ListCollectionsResponse, ListValue, Match, MinShould,
+    MultiVectorComparator, MultiVectorConfig, NamedVectors, NestedCondition, OrderBy, OrderValue,
+    PayloadExcludeSelector, PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo,
+    PayloadSchemaType, PointId, ProductQuantization, QuantizationConfig, QuantizationSearchParams,
+    QuantizationType, Range, RepeatedIntegers, RepeatedStrings, ScalarQuantization, ScoredPoint,
+    SearchParams, SearchStats, Struct, TextIndexParams, TokenizerType, UuidIndexParams, Value,
+    ValuesCount, Vector, Vectors, VectorsSelector, WithPayloadSelector, WithVectorsSelector,
 };
 
 pub fn payload_to_proto(payload: segment::types::Payload) -> HashMap<String, Value> {
@@ -126,7 +128,10 @@ impl From<(Instant, CollectionsResponse)> for ListCollectionsResponse {
         let collections = response
             .collections
             .into_iter()
-            .map(|desc| CollectionDescription { name: desc.name })
+            .map(|desc| CollectionDescription {
+                name: desc.name,
+                load_error: desc.load_error,
+            })
             .collect::<Vec<_>>();
         Self {
             collections,
@@ -148,6 +153,16 @@ impl From<segment::data_types::text_index::TokenizerType> for TokenizerType {
     }
 }
 
+impl From<segment::data_types::keyword_index::KeywordIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::keyword_index::KeywordIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::KeywordIndexParams(KeywordIndexParams {
+                on_disk: params.on_disk,
+            })),
+        }
+    }
+}
+
 impl From<segment::data_types::text_index::TextIndexParams> for PayloadIndexParams {
     fn from(params: segment::data_types::text_index::TextIndexParams) -> Self {
         let tokenizer = TokenizerType::from(params.tokenizer);
@@ -162,6 +177,16 @@ impl From<segment::data_types::text_index::TextIndexParams> for PayloadIndexPara
     }
 }
 
+impl From<segment::data_types::uuid_index::UuidIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::uuid_index::UuidIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::UuidIndexParams(UuidIndexParams {
+                skip_invalid: params.skip_invalid,
+            })),
+        }
+    }
+}
+
 impl From<segment::types::PayloadIndexInfo> for PayloadSchemaInfo {
     fn from(schema: segment::types::PayloadIndexInfo) -> Self {
         PayloadSchemaInfo {
@@ -172,12 +197,20 @@ impl From<segment::types::PayloadIndexInfo> for PayloadSchemaInfo {
                 segment::types::PayloadSchemaType::Geo => PayloadSchemaType::Geo,
                 segment::types::PayloadSchemaType::Text => PayloadSchemaType::Text,
                 segment::types::PayloadSchemaType::Bool => PayloadSchemaType::Bool,
+                segment::types::PayloadSchemaType::Datetime => PayloadSchemaType::Datetime,
+                segment::types::PayloadSchemaType::Uuid => PayloadSchemaType::Uuid,
             }
             .into(),
             params: schema.params.map(|params| match params {
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params) => {
+                    keyword_index_params.into()
+                }
                 segment::types::PayloadSchemaParams::Text(text_index_params) => {
                     text_index_params.into()
                 }
+                segment::types::PayloadSchemaParams::Uuid(uuid_index_params) => {
+                    uuid_index_params.into()
+                }
             }),
             points: Some(schema.points as u64),
         }
@@ -216,6 +249,24 @@ impl TryFrom<TextIndexParams> for segment::data_types::text_index::TextIndexPara
     }
 }
 
+impl From<KeywordIndexParams> for segment::data_types::keyword_index::KeywordIndexParams {
+    fn from(params: KeywordIndexParams) -> Self {
+        segment::data_types::keyword_index::KeywordIndexParams {
+            r#type: segment::data_types::keyword_index::KeywordIndexType::Keyword,
+            on_disk: params.on_disk,
+        }
+    }
+}
+
+impl From<UuidIndexParams> for segment::data_types::uuid_index::UuidIndexParams {
+    fn from(params: UuidIndexParams) -> Self {
+        segment::data_types::uuid_index::UuidIndexParams {
+            r#type: segment::data_types::uuid_index::UuidIndexType::Uuid,
+            skip_invalid: params.skip_invalid,
+        }
+    }
+}
+
 impl TryFrom<PayloadIndexParams> for segment::data_types::text_index::TextIndexParams {
     type Error = Status;
     fn try_from(params: PayloadIndexParams) -> Result<Self, Self::Error> {
@@ -224,6 +275,12 @@ impl TryFrom<PayloadIndexParams> for segment::data_types::text_index::TextIndexP
             Some(IndexParams::TextIndexParams(text_index_params)) => {
                 Ok(text_index_params.try_into()?)
             }
+            Some(IndexParams::UuidIndexParams(_)) => Err(Status::invalid_argument(
+                "UUID index params are not text index params",
+            )),
+            Some(IndexParams::KeywordIndexParams(_)) => Err(Status::invalid_argument(
+                "Keyword index params are not text index params",
+            )),
         }
     }
 }
@@ -236,6 +293,12 @@ impl TryFrom<IndexParams> for segment::types::PayloadSchemaParams {
             IndexParams::TextIndexParams(text_index_params) => Ok(
                 segment::types::PayloadSchemaParams::Text(text_index_params.try_into()?),
             ),
+            IndexParams::UuidIndexParams(uuid_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Uuid(uuid_index_params.into()),
+            ),
+            IndexParams::KeywordIndexParams(keyword_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            ),
         }
     }
 }
@@ -257,6 +320,8 @@ impl TryFrom<PayloadSchemaInfo> for segment::types::PayloadIndexInfo {
                 PayloadSchemaType::Geo => segment::types::PayloadSchemaType::Geo,
                 PayloadSchemaType::Text => segment::types::PayloadSchemaType::Text,
                 PayloadSchemaType::Bool => segment::types::PayloadSchemaType::Bool,
+                PayloadSchemaType::Datetime => segment::types::PayloadSchemaType::Datetime,
+                PayloadSchemaType::Uuid => segment::types::PayloadSchemaType::Uuid,
                 PayloadSchemaType::UnknownType => {
                     return Err(Status::invalid_argument(
                         "Malformed payload schema".to_string(),
@@ -435,6 +500,16 @@ impl From<segment::types::ScoredPoint> for ScoredPoint {
     }
 }
 
+impl From<segment::common::search_stats::SearchStats> for SearchStats {
+    fn from(stats: segment::common::search_stats::SearchStats) -> Self {
+        Self {
+            graph_hops: stats.graph_hops as u64,
+            points_scored: stats.points_scored as u64,
+            filter_checks: stats.filter_checks as u64,
+        }
+    }
+}
+
 impl From<segment::data_types::groups::GroupId> for GroupId {
     fn from(key: segment::data_types::groups::GroupId) -> Self {
         match key {
@@ -599,6 +674,27 @@ impl TryFrom<ProductQuantization> for segment::types::ProductQuantization {
     }
 }
 
+impl From<segment::types::BinaryQuantization> for BinaryQuantization {
+    fn from(value: segment::types::BinaryQuantization) -> Self {
+        let config = value.binary;
+        BinaryQuantization {
+            min_dim: config.min_dim.map(|min_dim| min_dim as u64),
+            always_ram: config.always_ram,
+        }
+    }
+}
+
+impl From<BinaryQuantization> for segment::types::BinaryQuantization {
+    fn from(value: BinaryQuantization) -> Self {
+        segment::types::BinaryQuantization {
+            binary: segment::types::BinaryQuantizationConfig {
+                min_dim: value.min_dim.map(|min_dim| min_dim as usize),
+                always_ram: value.always_ram,
+            },
+        }
+    }
+}
+
 impl From<segment::types::QuantizationConfig> for QuantizationConfig {
     fn from(value: segment::types::QuantizationConfig) -> Self {
         match value {
@@ -612,6 +708,11 @@ impl From<segment::types::QuantizationConfig> for QuantizationConfig {
                     product.into(),
                 )),
             },
+            segment::types::QuantizationConfig::Binary(binary) => Self {
+                quantization: Some(super::qdrant::quantization_config::Quantization::Binary(
+                    binary.into(),
+                )),
+            },
         }
     }
 }
@@ -630,6 +731,9 @@ impl TryFrom<QuantizationConfig> for segment::types::QuantizationConfig {
             super::qdrant::quantization_config::Quantization::Product(config) => Ok(
                 segment::types::QuantizationConfig::Product(config.try_into()?),
             ),
+            super::qdrant::quantization_config::Quantization::Binary(config) => {
+                Ok(segment::types::QuantizationConfig::Binary(config.into()))
+            }
         }
     }
 }
@@ -667,6 +771,7 @@ impl TryFrom<Filter> for segment::types::Filter {
     fn try_from(value: Filter) -> Result<Self, Self::Error> {
         Ok(Self {
             should: conditions_helper_from_grpc(value.should)?,
+            min_should: value.min_should.map(TryInto::try_into).transpose()?,
             must: conditions_helper_from_grpc(value.must)?,
             must_not: conditions_helper_from_grpc(value.must_not)?,
         })
@@ -679,6 +784,30 @@ impl From<segment::types::Filter> for Filter {
             should: conditions_helper_to_grpc(value.should),
             must: conditions_helper_to_grpc(value.must),
             must_not: conditions_helper_to_grpc(value.must_not),
+            min_should: value.min_should.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<MinShould> for segment::types::MinShould {
+    type Error = Status;
+
+    fn try_from(value: MinShould) -> Result<Self, Self::Error> {
+        let conditions = value
+            .conditions
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+        segment::types::MinShould::new(conditions, value.min_count as usize)
+            .map_err(|err| Status::invalid_argument(err.to_string()))
+    }
+}
+
+impl From<segment::types::MinShould> for MinShould {
+    fn from(value: segment::types::MinShould) -> Self {
+        Self {
+            conditions: value.conditions.into_iter().map(Into::into).collect(),
+            min_count: value.min_count as u64,
         }
     }
 }
@@ -822,17 +951,30 @@ impl TryFrom<FieldCondition> for segment::types::FieldCondition {
             geo_bounding_box,
             geo_radius,
             values_count,
-            // geo_polygon,
+            geo_polygon,
+            datetime_range,
         } = value;
 
         let geo_bounding_box =
             geo_bounding_box.map_or_else(|| Ok(None), |g| g.try_into().map(Some))?;
         let geo_radius = geo_radius.map_or_else(|| Ok(None), |g| g.try_into().map(Some))?;
-        let geo_polygon = None; // geo_polygon.map_or_else(|| Ok(None), |g| g.try_into().map(Some))?;
+        let geo_polygon = geo_polygon.map_or_else(|| Ok(None), |g| g.try_into().map(Some))?;
+        let range = match (range, datetime_range) {
+            (Some(_), Some(_)) => {
+                return Err(Status::invalid_argument(
+                    "Only one of range and datetime_range can be set",
+                ))
+            }
+            (Some(range), None) => Some(segment::types::RangeInterface::Float(range.into())),
+            (None, Some(range)) => {
+                Some(segment::types::RangeInterface::DateTime(range.try_into()?))
+            }
+            (None, None) => None,
+        };
         Ok(Self {
             key,
             r#match: r#match.map_or_else(|| Ok(None), |m| m.try_into().map(Some))?,
-            range: range.map(Into::into),
+            range,
             geo_bounding_box,
             geo_radius,
             geo_polygon,
@@ -849,20 +991,27 @@ impl From<segment::types::FieldCondition> for FieldCondition {
             range,
             geo_bounding_box,
             geo_radius,
-            geo_polygon: _geo_polygon,
+            geo_polygon,
             values_count,
         } = value;
 
         let geo_bounding_box = geo_bounding_box.map(Into::into);
         let geo_radius = geo_radius.map(Into::into);
+        let geo_polygon = geo_polygon.map(Into::into);
+        let (range, datetime_range) = match range {
+            Some(segment::types::RangeInterface::Float(range)) => (Some(range.into()), None),
+            Some(segment::types::RangeInterface::DateTime(range)) => (None, Some(range.into())),
+            None => (None, None),
+        };
         Self {
             key,
             r#match: r#match.map(Into::into),
-            range: range.map(Into::into),
+            range,
             geo_bounding_box,
             geo_radius,
-            // geo_polygon: geo_polygon.map(Into::into),
+            geo_polygon,
             values_count: values_count.map(Into::into),
+            datetime_range,
         }
     }
 }
@@ -919,25 +1068,55 @@ impl From<segment::types::GeoRadius> for GeoRadius {
     }
 }
 
+impl From<GeoLineString> for segment::types::GeoLineString {
+    fn from(value: GeoLineString) -> Self {
+        Self {
+            points: value.points.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<segment::types::GeoLineString> for GeoLineString {
+    fn from(value: segment::types::GeoLineString) -> Self {
+        Self {
+            points: value.points.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
 impl TryFrom<GeoPolygon> for segment::types::GeoPolygon {
     type Error = Status;
 
     fn try_from(value: GeoPolygon) -> Result<Self, Self::Error> {
-        if value.points.is_empty() {
-            return Err(Status::invalid_argument("Empty GeoPolygon"));
-        }
+        let GeoPolygon {
+            exterior,
+            interiors,
+        } = value;
 
-        let points: Vec<segment::types::GeoPoint> =
-            value.points.into_iter().map(Into::into).collect();
+        let exterior = exterior
+            .ok_or_else(|| Status::invalid_argument("Malformed GeoPolygon type"))?
+            .into();
+        let interiors = if interiors.is_empty() {
+            None
+        } else {
+            Some(interiors.into_iter().map(Into::into).collect())
+        };
 
-        Ok(Self { points })
+        segment::types::GeoPolygon::new(exterior, interiors)
+            .map_err(|err| Status::invalid_argument(err.to_string()))
     }
 }
 
 impl From<segment::types::GeoPolygon> for GeoPolygon {
     fn from(value: segment::types::GeoPolygon) -> Self {
         Self {
-            points: value.points.into_iter().map(Into::into).collect(),
+            exterior: Some(value.exterior.into()),
+            interiors: value
+                .interiors
+                .into_iter()
+                .flatten()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -973,6 +1152,161 @@ impl From<segment::types::Range> for Range {
     }
 }
 
+impl TryFrom<protobuf::Timestamp> for segment::types::DateTimePayloadType {
+    type Error = Status;
+
+    fn try_from(value: protobuf::Timestamp) -> Result<Self, Self::Error> {
+        value
+            .seconds
+            .checked_mul(1_000_000)
+            .and_then(|micros| micros.checked_add(i64::from(value.nanos) / 1_000))
+            .and_then(Self::from_timestamp)
+            .ok_or_else(|| Status::invalid_argument(format!("Malformed timestamp: {value:?}")))
+    }
+}
+
+impl From<segment::types::DateTimePayloadType> for protobuf::Timestamp {
+    fn from(value: segment::types::DateTimePayloadType) -> Self {
+        let micros = value.timestamp();
+        Self {
+            seconds: micros.div_euclid(1_000_000),
+            nanos: micros.rem_euclid(1_000_000) as i32 * 1_000,
+        }
+    }
+}
+
+impl TryFrom<DatetimeRange> for segment::types::DatetimeRange {
+    type Error = Status;
+
+    fn try_from(value: DatetimeRange) -> Result<Self, Self::Error> {
+        let datetime = |timestamp: Option<protobuf::Timestamp>| timestamp.map(TryInto::try_into);
+        Ok(Self {
+            lt: datetime(value.lt).transpose()?,
+            gt: datetime(value.gt).transpose()?,
+            gte: datetime(value.gte).transpose()?,
+            lte: datetime(value.lte).transpose()?,
+        })
+    }
+}
+
+impl From<segment::types::DatetimeRange> for DatetimeRange {
+    fn from(value: segment::types::DatetimeRange) -> Self {
+        Self {
+            lt: value.lt.map(Into::into),
+            gt: value.gt.map(Into::into),
+            gte: value.gte.map(Into::into),
+            lte: value.lte.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<OrderValue> for segment::data_types::order_by::OrderValue {
+    type Error = Status;
+
+    fn try_from(value: OrderValue) -> Result<Self, Self::Error> {
+        match value.variant {
+            Some(order_value::Variant::Int(value)) => Ok(Self::Int(value)),
+            Some(order_value::Variant::Float(value)) => Ok(Self::Float(value)),
+            None => Err(Status::invalid_argument("Order value is empty")),
+        }
+    }
+}
+
+impl From<segment::data_types::order_by::OrderValue> for OrderValue {
+    fn from(value: segment::data_types::order_by::OrderValue) -> Self {
+        use segment::data_types::order_by::OrderValue as SegmentOrderValue;
+        let variant = match value {
+            SegmentOrderValue::Int(value) => order_value::Variant::Int(value),
+            SegmentOrderValue::Float(value) => order_value::Variant::Float(value),
+        };
+        Self {
+            variant: Some(variant),
+        }
+    }
+}
+
+impl TryFrom<FacetValue> for segment::data_types::facets::FacetValue {
+    type Error = Status;
+
+    fn try_from(value: FacetValue) -> Result<Self, Self::Error> {
+        match value.variant {
+            Some(facet_value::Variant::StringValue(value)) => Ok(Self::Keyword(value)),
+            None => Err(Status::invalid_argument("Facet value is empty")),
+        }
+    }
+}
+
+impl From<segment::data_types::facets::FacetValue> for FacetValue {
+    fn from(value: segment::data_types::facets::FacetValue) -> Self {
+        use segment::data_types::facets::FacetValue as SegmentFacetValue;
+        let variant = match value {
+            SegmentFacetValue::Keyword(value) => facet_value::Variant::StringValue(value),
+        };
+        Self {
+            variant: Some(variant),
+        }
+    }
+}
+
+impl TryFrom<FacetHit> for segment::data_types::facets::FacetValueHit {
+    type Error = Status;
+
+    fn try_from(hit: FacetHit) -> Result<Self, Self::Error> {
+        let value = hit
+            .value
+            .ok_or_else(|| Status::invalid_argument("Facet hit value is missing"))?;
+        Ok(Self {
+            value: value.try_into()?,
+            count: hit.count as usize,
+        })
+    }
+}
+
+impl From<segment::data_types::facets::FacetValueHit> for FacetHit {
+    fn from(hit: segment::data_types::facets::FacetValueHit) -> Self {
+        Self {
+            value: Some(hit.value.into()),
+            count: hit.count as u64,
+        }
+    }
+}
+
+impl TryFrom<OrderBy> for segment::data_types::order_by::OrderBy {
+    type Error = Status;
+
+    fn try_from(value: OrderBy) -> Result<Self, Self::Error> {
+        let direction = value
+            .direction
+            .map(|direction| match Direction::from_i32(direction) {
+                Some(Direction::Asc) => Ok(segment::data_types::order_by::Direction::Asc),
+                Some(Direction::Desc) => Ok(segment::data_types::order_by::Direction::Desc),
+                None => Err(Status::invalid_argument(format!(
+                    "Unknown order direction: {direction}"
+                ))),
+            })
+            .transpose()?;
+        Ok(Self {
+            key: value.key,
+            direction,
+            start_from: value.start_from.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+impl From<segment::data_types::order_by::OrderBy> for OrderBy {
+    fn from(value: segment::data_types::order_by::OrderBy) -> Self {
+        let direction = value.direction.map(|direction| match direction {
+            segment::data_types::order_by::Direction::Asc => Direction::Asc,
+            segment::data_types::order_by::Direction::Desc => Direction::Desc,
+        });
+        Self {
+            key: value.key,
+            direction: direction.map(|direction| direction as i32),
+            start_from: value.start_from.map(Into::into),
+        }
+    }
+}
+
 impl From<ValuesCount> for segment::types::ValuesCount {
     fn from(value: ValuesCount) -> Self {
         Self {
@@ -1005,6 +1339,7 @@ impl TryFrom<Match> for segment::types::Match {
                 MatchValue::Integer(int) => int.into(),
                 MatchValue::Boolean(flag) => flag.into(),
                 MatchValue::Text(text) => segment::types::Match::Text(text.into()),
+                MatchValue::Phrase(phrase) => segment::types::Match::Phrase(phrase.into()),
                 MatchValue::Keywords(kwds) => kwds.strings.into(),
                 MatchValue::Integers(ints) => ints.integers.into(),
                 MatchValue::ExceptIntegers(kwds) => {
@@ -1030,6 +1365,9 @@ impl From<segment::types::Match> for Match {
             segment::types::Match::Text(segment::types::MatchText { text }) => {
                 MatchValue::Text(text)
             }
+            segment::types::Match::Phrase(segment::types::MatchPhrase { phrase }) => {
+                MatchValue::Phrase(phrase)
+            }
             segment::types::Match::Any(any) => match any.any {
                 segment::types::AnyVariants::Keywords(strings) => {
                     MatchValue::Keywords(RepeatedStrings { strings })
@@ -1053,6 +1391,19 @@ impl From<segment::types::Match> for Match {
     }
 }
 
+impl From<segment::types::HnswConfig> for HnswConfigDiff {
+    fn from(hnsw_config: segment::types::HnswConfig) -> Self {
+        Self {
+            m: Some(hnsw_config.m as u64),
+            ef_construct: Some(hnsw_config.ef_construct as u64),
+            full_scan_threshold: Some(hnsw_config.full_scan_threshold as u64),
+            max_indexing_threads: Some(hnsw_config.max_indexing_threads as u64),
+            on_disk: hnsw_config.on_disk,
+            payload_m: hnsw_config.payload_m.map(|v| v as u64),
+        }
+    }
+}
+
 impl From<HnswConfigDiff> for segment::types::HnswConfig {
     fn from(hnsw_config: HnswConfigDiff) -> Self {
         Self {
@@ -1086,6 +1437,8 @@ impl TryFrom<Distance> for segment::types::Distance {
             Distance::Cosine => segment::types::Distance::Cosine,
             Distance::Euclid => segment::types::Distance::Euclid,
             Distance::Dot => segment::types::Distance::Dot,
+            Distance::Manhattan => segment::types::Distance::Manhattan,
+            Distance::Hamming => segment::types::Distance::Hamming,
         })
     }
 }
@@ -1098,3 +1451,55 @@ pub fn from_grpc_dist(dist: i32) -> Result<segment::types::Distance, Status> {
         Some(grpc_distance) => Ok(grpc_distance.try_into()?),
     }
 }
+
+impl From<segment::types::VectorStorageDatatype> for Datatype {
+    fn from(value: segment::types::VectorStorageDatatype) -> Self {
+        match value {
+            segment::types::VectorStorageDatatype::Float32 => Datatype::Float32,
+            segment::types::VectorStorageDatatype::Float16 => Datatype::Float16,
+            segment::types::VectorStorageDatatype::Uint8 => Datatype::Uint8,
+        }
+    }
+}
+
+pub fn from_grpc_datatype(
+    datatype: i32,
+) -> Result<Option<segment::types::VectorStorageDatatype>, Status> {
+    match Datatype::from_i32(datatype) {
+        None => Err(Status::invalid_argument(format!(
+            "Malformed datatype parameter, unexpected value: {datatype}"
+        ))),
+        Some(Datatype::Default) => Ok(None),
+        Some(Datatype::Float32) => Ok(Some(segment::types::VectorStorageDatatype::Float32)),
+        Some(Datatype::Float16) => Ok(Some(segment::types::VectorStorageDatatype::Float16)),
+        Some(Datatype::Uint8) => Ok(Some(segment::types::VectorStorageDatatype::Uint8)),
+    }
+}
+
+impl From<segment::types::MultiVectorConfig> for MultiVectorConfig {
+    fn from(value: segment::types::MultiVectorConfig) -> Self {
+        let comparator = match value.comparator {
+            segment::types::MultiVectorComparator::MaxSim => MultiVectorComparator::MaxSim,
+        };
+        Self {
+            comparator: comparator.into(),
+        }
+    }
+}
+
+impl TryFrom<MultiVectorConfig> for segment::types::MultiVectorConfig {
+    type Error = Status;
+
+    fn try_from(value: MultiVectorConfig) -> Result<Self, Self::Error> {
+        let comparator = match MultiVectorComparator::from_i32(value.comparator) {
+            None => {
+                return Err(Status::invalid_argument(format!(
+                    "Malformed multi-vector comparator, unexpected value: {}",
+                    value.comparator
+                )))
+            }
+            Some(MultiVectorComparator::MaxSim) => segment::types::MultiVectorComparator::MaxSim,
+        };
+        Ok(Self { comparator })
+    }
+}
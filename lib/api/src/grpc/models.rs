@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
 use schemars::JsonSchema;
+use segment::common::search_stats::SearchStats;
 use serde;
 use serde::{Deserialize, Serialize};
 
@@ -46,12 +47,19 @@ pub struct ApiResponse<D: Serialize + Debug> {
     pub result: Option<D>,
     pub status: ApiStatus,
     pub time: f64,
+    /// Work done by the search, if requested with `with_stats`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<SearchStats>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct CollectionDescription {
     pub name: String,
+    /// Error, due to which the collection failed to load on this peer.
+    /// Such collection is not available, until it is recovered or dropped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_error: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -59,3 +67,11 @@ pub struct CollectionDescription {
 pub struct CollectionsResponse {
     pub collections: Vec<CollectionDescription>,
 }
+
+/// State of existence of a collection,
+/// true = exists, false = does not exist
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionExistence {
+    pub exists: bool,
+}
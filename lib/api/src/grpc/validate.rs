@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use serde::Serialize;
 use validator::{Validate, ValidationError, ValidationErrors};
 
-use super::qdrant::{GeoPoint, NamedVectors};
+use super::qdrant::{GeoLineString, GeoPoint, NamedVectors};
 
 pub trait ValidateExt {
     fn validate(&self) -> Result<(), ValidationErrors>;
@@ -97,6 +97,7 @@ impl Validate for crate::grpc::qdrant::quantization_config::Quantization {
         match self {
             Quantization::Scalar(scalar) => scalar.validate(),
             Quantization::Product(product) => product.validate(),
+            Quantization::Binary(binary) => binary.validate(),
         }
     }
 }
@@ -107,6 +108,7 @@ impl Validate for crate::grpc::qdrant::quantization_config_diff::Quantization {
         match self {
             Quantization::Scalar(scalar) => scalar.validate(),
             Quantization::Product(product) => product.validate(),
+            Quantization::Binary(binary) => binary.validate(),
             Quantization::Disabled(_) => Ok(()),
         }
     }
@@ -230,8 +232,22 @@ pub fn validate_collection_name(value: &str) -> Result<(), ValidationError> {
     }
 }
 
-/// Validate a polygon has at least 4 points and is closed.
-pub fn validate_geo_polygon(points: &Vec<GeoPoint>) -> Result<(), ValidationError> {
+/// Validate the exterior ring of a polygon has at least 4 points and is closed.
+pub fn validate_geo_polygon_exterior(exterior: &GeoLineString) -> Result<(), ValidationError> {
+    validate_geo_polygon_line(&exterior.points)
+}
+
+/// Validate every interior ring of a polygon has at least 4 points and is closed.
+pub fn validate_geo_polygon_interiors(
+    interiors: &Vec<GeoLineString>,
+) -> Result<(), ValidationError> {
+    interiors
+        .iter()
+        .try_for_each(|interior| validate_geo_polygon_line(&interior.points))
+}
+
+/// Validate a polygon ring has at least 4 points and is closed.
+fn validate_geo_polygon_line(points: &[GeoPoint]) -> Result<(), ValidationError> {
     let min_length = 4;
     if points.len() < min_length {
         let mut err = ValidationError::new("min_polygon_length");
@@ -254,8 +270,8 @@ mod tests {
     use validator::Validate;
 
     use crate::grpc::qdrant::{
-        CreateCollection, CreateFieldIndexCollection, GeoPoint, GeoPolygon, SearchPoints,
-        UpdateCollection,
+        CreateCollection, CreateFieldIndexCollection, GeoLineString, GeoPoint, GeoPolygon,
+        SearchPoints, UpdateCollection,
     };
 
     #[test]
@@ -371,18 +387,25 @@ mod tests {
 
     #[test]
     fn test_geo_polygon() {
-        let bad_polygon = GeoPolygon { points: vec![] };
+        let line = |points: &[(f64, f64)]| GeoLineString {
+            points: points
+                .iter()
+                .map(|&(lon, lat)| GeoPoint { lon, lat })
+                .collect(),
+        };
+
+        let bad_polygon = GeoPolygon {
+            exterior: Some(line(&[])),
+            interiors: vec![],
+        };
         assert!(
             bad_polygon.validate().is_err(),
             "bad polygon should error on validation"
         );
 
         let bad_polygon = GeoPolygon {
-            points: vec![
-                GeoPoint { lat: 1., lon: 1. },
-                GeoPoint { lat: 2., lon: 2. },
-                GeoPoint { lat: 3., lon: 3. },
-            ],
+            exterior: Some(line(&[(1., 1.), (2., 2.), (3., 3.)])),
+            interiors: vec![],
         };
         assert!(
             bad_polygon.validate().is_err(),
@@ -390,30 +413,39 @@ mod tests {
         );
 
         let bad_polygon = GeoPolygon {
-            points: vec![
-                GeoPoint { lat: 1., lon: 1. },
-                GeoPoint { lat: 2., lon: 2. },
-                GeoPoint { lat: 3., lon: 3. },
-                GeoPoint { lat: 4., lon: 4. },
-            ],
+            exterior: Some(line(&[(1., 1.), (2., 2.), (3., 3.), (4., 4.)])),
+            interiors: vec![],
         };
-
         assert!(
             bad_polygon.validate().is_err(),
             "bad polygon should error on validation"
         );
 
+        let bad_polygon = GeoPolygon {
+            exterior: Some(line(&[(1., 1.), (2., 2.), (3., 3.), (1., 1.)])),
+            interiors: vec![line(&[(1.5, 1.5), (2., 2.), (1.5, 1.5)])],
+        };
+        assert!(
+            bad_polygon.validate().is_err(),
+            "bad polygon interior should error on validation"
+        );
+
         let good_polygon = GeoPolygon {
-            points: vec![
-                GeoPoint { lat: 1., lon: 1. },
-                GeoPoint { lat: 2., lon: 2. },
-                GeoPoint { lat: 3., lon: 3. },
-                GeoPoint { lat: 1., lon: 1. },
-            ],
+            exterior: Some(line(&[(1., 1.), (2., 2.), (3., 3.), (1., 1.)])),
+            interiors: vec![],
         };
         assert!(
             good_polygon.validate().is_ok(),
             "good polygon should not error on validation"
         );
+
+        let good_polygon = GeoPolygon {
+            exterior: Some(line(&[(0., 0.), (4., 0.), (4., 4.), (0., 4.), (0., 0.)])),
+            interiors: vec![line(&[(1., 1.), (2., 1.), (2., 2.), (1., 1.)])],
+        };
+        assert!(
+            good_polygon.validate().is_ok(),
+            "good polygon with hole should not error on validation"
+        );
     }
 }
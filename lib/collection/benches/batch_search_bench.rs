@@ -6,7 +6,9 @@ use std::sync::Arc;
 
 use collection::config::{CollectionConfig, CollectionParams, WalConfig};
 use collection::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
-use collection::operations::types::{SearchRequest, SearchRequestBatch, VectorParams};
+use collection::operations::types::{
+    CoreSearchRequestBatch, SearchRequest, SearchRequestBatch, VectorParams,
+};
 use collection::operations::CollectionUpdateOperations;
 use collection::optimizers_builder::OptimizersConfig;
 use collection::shards::local_shard::LocalShard;
@@ -54,6 +56,7 @@ fn batch_search_bench(c: &mut Criterion) {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -63,12 +66,19 @@ fn batch_search_bench(c: &mut Criterion) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
+            multivector_config: None,
         }
         .into(),
         shard_number: NonZeroU32::new(1).expect("Shard number can not be zero"),
+        sharding_method: None,
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
     };
 
     let collection_config = CollectionConfig {
@@ -82,10 +92,12 @@ fn batch_search_bench(c: &mut Criterion) {
             indexing_threshold: Some(50_000),
             flush_interval_sec: 30,
             max_optimization_threads: 2,
+            merge_buffer_size: None,
         },
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
     };
 
     let shared_config = Arc::new(RwLock::new(collection_config));
@@ -145,11 +157,12 @@ fn batch_search_bench(c: &mut Criterion) {
                             score_threshold: None,
                         };
                         let result = shard
-                            .search(
-                                Arc::new(SearchRequestBatch {
-                                    searches: vec![search_query],
+                            .core_search(
+                                Arc::new(CoreSearchRequestBatch {
+                                    searches: vec![search_query.into()],
                                 }),
                                 search_runtime_handle,
+                                None,
                             )
                             .await
                             .unwrap();
@@ -179,9 +192,12 @@ fn batch_search_bench(c: &mut Criterion) {
                         searches.push(search_query);
                     }
 
-                    let search_query = SearchRequestBatch { searches };
+                    let search_query = SearchRequestBatch {
+                        searches,
+                        fail_fast: None,
+                    };
                     let result = shard
-                        .search(Arc::new(search_query), search_runtime_handle)
+                        .core_search(Arc::new(search_query.into()), search_runtime_handle, None)
                         .await
                         .unwrap();
                     assert!(!result.is_empty());
@@ -0,0 +1,149 @@
+#[cfg(not(target_os = "windows"))]
+mod prof;
+
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Arc;
+use std::time::Duration;
+
+use collection::config::{CollectionConfig, CollectionParams, WalConfig};
+use collection::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
+use collection::operations::shared_storage_config::SharedStorageConfig;
+use collection::operations::types::VectorParams;
+use collection::operations::CollectionUpdateOperations;
+use collection::optimizers_builder::OptimizersConfig;
+use collection::shards::local_shard::LocalShard;
+use collection::shards::shard_trait::ShardOperation;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rand::thread_rng;
+use segment::data_types::vectors::only_default_vector;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::types::Distance;
+use tempfile::Builder;
+use tokio::runtime::Runtime;
+use tokio::sync::RwLock;
+
+const WRITERS: usize = 32;
+const DIM: usize = 100;
+
+fn create_rnd_point_upsert(id: u64) -> CollectionUpdateOperations {
+    let mut rng = thread_rng();
+    let vector = random_vector(&mut rng, DIM);
+    CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(vec![PointStruct {
+            id: id.into(),
+            vector: only_default_vector(&vector).into(),
+            payload: None,
+        }]),
+    ))
+}
+
+fn create_collection_config() -> CollectionConfig {
+    let wal_config = WalConfig {
+        wal_capacity_mb: 32,
+        wal_segments_ahead: 0,
+        ..Default::default()
+    };
+
+    let collection_params = CollectionParams {
+        vectors: VectorParams {
+            size: NonZeroU64::new(DIM as u64).unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype: None,
+            multivector_config: None,
+        }
+        .into(),
+        shard_number: NonZeroU32::new(1).expect("Shard number can not be zero"),
+        sharding_method: None,
+        replication_factor: NonZeroU32::new(1).unwrap(),
+        write_consistency_factor: NonZeroU32::new(1).unwrap(),
+        on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
+    };
+
+    CollectionConfig {
+        params: collection_params,
+        optimizer_config: OptimizersConfig {
+            deleted_threshold: 0.9,
+            vacuum_min_vector_number: 1000,
+            default_segment_number: 2,
+            max_segment_size: Some(100_000),
+            memmap_threshold: Some(100_000),
+            indexing_threshold: Some(50_000),
+            flush_interval_sec: 30,
+            max_optimization_threads: 0,
+            merge_buffer_size: None,
+        },
+        wal_config,
+        hnsw_config: Default::default(),
+        quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+    }
+}
+
+fn update_group_commit_bench(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let handle = runtime.handle().clone();
+
+    let mut group = c.benchmark_group("update-group-commit-bench");
+    group.throughput(Throughput::Elements(WRITERS as u64));
+
+    for (name, window, max_ops) in [
+        // Flush per waiting operation, as without group commit
+        ("no-group-commit", Duration::ZERO, 1),
+        ("group-commit-queued", Duration::ZERO, 64),
+        ("group-commit-1ms", Duration::from_millis(1), 64),
+    ] {
+        let storage_dir = Builder::new().prefix("storage").tempdir().unwrap();
+
+        let storage_config = SharedStorageConfig {
+            update_group_commit_window: window,
+            update_group_commit_max_ops: max_ops,
+            ..Default::default()
+        };
+
+        let shard = handle
+            .block_on(LocalShard::build_local(
+                0,
+                "test_collection".to_string(),
+                storage_dir.path(),
+                Arc::new(RwLock::new(create_collection_config())),
+                Arc::new(storage_config),
+                handle.clone(),
+            ))
+            .unwrap();
+        let shard = Arc::new(shard);
+
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    let writers: Vec<_> = (0..WRITERS)
+                        .map(|writer| {
+                            let shard = shard.clone();
+                            let operation = create_rnd_point_upsert(writer as u64);
+                            tokio::spawn(async move { shard.update(operation, true).await })
+                        })
+                        .collect();
+                    for writer in writers {
+                        writer.await.unwrap().unwrap();
+                    }
+                });
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = update_group_commit_bench,
+}
+
+criterion_main!(benches);
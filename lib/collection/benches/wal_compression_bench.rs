@@ -0,0 +1,67 @@
+#[cfg(not(target_os = "windows"))]
+mod prof;
+
+use collection::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
+use collection::operations::CollectionUpdateOperations;
+use collection::wal::{SerdeWal, WalCompression};
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::thread_rng;
+use segment::data_types::vectors::only_default_vector;
+use segment::fixtures::payload_fixtures::random_vector;
+use tempfile::Builder;
+use wal::WalOptions;
+
+fn create_rnd_batch() -> CollectionUpdateOperations {
+    let mut rng = thread_rng();
+    let num_points = 500;
+    let dim = 1024;
+    let points = (0..num_points)
+        .map(|i| {
+            let vector = random_vector(&mut rng, dim);
+            PointStruct {
+                id: (i as u64).into(),
+                vector: only_default_vector(&vector).into(),
+                payload: None,
+            }
+        })
+        .collect();
+    CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ))
+}
+
+fn wal_compression_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wal-compression-bench");
+
+    let operation = create_rnd_batch();
+
+    for (name, compression) in [
+        ("wal-write-uncompressed", None),
+        ("wal-write-lz4", Some(WalCompression::default())),
+    ] {
+        let wal_dir = Builder::new().prefix("wal").tempdir().unwrap();
+        let wal_options = WalOptions {
+            segment_capacity: 64 * 1024 * 1024,
+            segment_queue_len: 0,
+        };
+        let mut wal: SerdeWal<CollectionUpdateOperations> =
+            SerdeWal::new(wal_dir.path().to_str().unwrap(), wal_options)
+                .unwrap()
+                .with_compression(compression);
+
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let op_num = wal.write(&operation).unwrap();
+                wal.ack(op_num).unwrap();
+            })
+        });
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = wal_compression_bench,
+}
+
+criterion_main!(benches);
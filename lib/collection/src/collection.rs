@@ -4,53 +4,88 @@ use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use futures::future::{join_all, try_join_all};
 use itertools::Itertools;
+use segment::common::operation_time_statistics::{
+    OperationDurationsAggregator, ScopeDurationMeasurer,
+};
+use segment::common::search_stats::SearchStats;
+use segment::common::snapshot_manifest::FileManifest;
 use segment::common::version::StorageVersion;
+use segment::data_types::facets::{merge_facet_counts, top_facet_hits};
+use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
 use segment::spaces::tools::{peek_top_largest_iterable, peek_top_smallest_iterable};
 use segment::types::{
-    ExtendedPointId, Order, QuantizationConfig, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, Order, PayloadKeyType, QuantizationConfig, ScoredPoint, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use semver::Version;
 use tar::Builder as TarBuilder;
 use tokio::fs::{copy, create_dir_all, remove_dir_all, remove_file, rename};
 use tokio::runtime::Handle;
 use tokio::sync::{Mutex, RwLock, RwLockWriteGuard};
+use tracing::Instrument;
 use validator::Validate;
 
+use crate::collection_manager::optimizers::optimization_tasks::{
+    OptimizationTaskId, OptimizationTaskInfo,
+};
 use crate::collection_state::{ShardInfo, State};
+use crate::common::filter_usage::{FilterUsage, PayloadIndexSuggestion, INDEX_SUGGESTIONS_LIMIT};
 use crate::common::is_ready::IsReady;
-use crate::config::CollectionConfig;
+use crate::common::rate_limiting::{
+    rate_limits_telemetry, SearchConcurrencyLimiter, UpdateRateLimiter,
+};
+use crate::common::resource_usage::CollectionResourceUsage;
+use crate::common::search_recall::{
+    run_recall_test, RecallTestReport, RecallTestRequest, RecallTestSegment,
+};
+use crate::common::search_stats::collect_search_stats;
+use crate::common::stoppable_task_async::StoppableAsyncTaskHandle;
+use crate::common::stopping_guard::StoppingGuard;
+use crate::config::{CollectionConfig, ShardingMethod};
 use crate::hash_ring::HashRing;
 use crate::operations::config_diff::{
     CollectionParamsDiff, DiffConfig, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
 };
 use crate::operations::consistency_params::ReadConsistency;
-use crate::operations::point_ops::WriteOrdering;
+use crate::operations::page_token::ScrollPageToken;
+use crate::operations::point_ops::{PointOperations, WriteOrdering};
+use crate::operations::shard_key_selector::ShardSelectorInternal;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::snapshot_ops::{
-    get_snapshot_description, list_snapshots_in_directory, SnapshotDescription,
+    get_snapshot_description, list_snapshots_in_directory, save_snapshot_aliases,
+    save_snapshot_checksum, SnapshotDescription,
 };
+use crate::operations::strict_mode::{operation_filter, StrictModeConfig};
 use crate::operations::types::{
-    CollectionClusterInfo, CollectionError, CollectionInfo, CollectionResult, CountRequest,
-    CountResult, LocalShardInfo, NodeType, PointRequest, Record, RemoteShardInfo, ScrollRequest,
-    ScrollResult, SearchRequest, SearchRequestBatch, UpdateResult, VectorsConfigDiff,
+    CollectionClusterInfo, CollectionError, CollectionInfo, CollectionResult,
+    CoreSearchRequestBatch, CountRequest, CountResult, FacetRequest, FacetResponse, LocalShardInfo,
+    NodeType, PayloadIndexInfo, PointRequest, QueryRequest, Record, RemoteShardInfo, ScrollRequest,
+    ScrollResult, SearchPlanRequest, SearchPlanResponse, SearchRequest, SearchRequestBatch,
+    UpdateResult, UpdateStatus, VectorsConfigDiff, DEFAULT_FACET_LIMIT,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{ClientOperationId, CollectionUpdateOperations, OperationWithClientId};
 use crate::optimizers_builder::OptimizersConfig;
 use crate::shards::channel_service::ChannelService;
 use crate::shards::collection_shard_distribution::CollectionShardDistribution;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::remote_shard::RemoteShard;
-use crate::shards::replica_set::ReplicaState::{Active, Dead, Initializing, Listener};
+use crate::shards::replica_failover::{
+    ClusterLoad, ReplicaFailoverConfig, ReplicaFailoverPlan, ReplicaFailoverState,
+};
+use crate::shards::replica_set::ReplicaState::{Active, Dead, Initializing};
 use crate::shards::replica_set::{
     Change, ChangePeerState, ReplicaState, ShardReplicaSet as ReplicaSetShard,
 }; // TODO rename ReplicaShard to ReplicaSetShard
-use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::resharding::{
+    drop_moved_points, spawn_resharding_task, ReshardingInfo, ReshardingState, ShardSplit,
+};
+use crate::shards::shard::{PeerId, ShardId, ShardKey, ShardsPlacement};
 use crate::shards::shard_config::{self, ShardConfig};
 use crate::shards::shard_holder::{LockedShardHolder, ShardHolder};
 use crate::shards::shard_versioning::versioned_shard_path;
@@ -59,6 +94,9 @@ use crate::shards::transfer::shard_transfer::{
     handle_transferred_shard_proxy, revert_proxy_shard_to_local, spawn_transfer_task,
     ShardTransfer, ShardTransferKey,
 };
+use crate::shards::transfer::snapshot::{
+    read_snapshot_chunk, receive_snapshot_chunk, unpack_snapshot,
+};
 use crate::shards::transfer::transfer_tasks_pool::{TaskResult, TransferTasksPool};
 use crate::shards::{replica_set, CollectionId, HASH_RING_SHARD_SCALE};
 use crate::telemetry::CollectionTelemetry;
@@ -67,6 +105,8 @@ pub type VectorLookupFuture<'a> = Box<dyn Future<Output = CollectionResult<Vec<R
 pub type OnTransferFailure = Arc<dyn Fn(ShardTransfer, CollectionId, &str) + Send + Sync>;
 pub type OnTransferSuccess = Arc<dyn Fn(ShardTransfer, CollectionId) + Send + Sync>;
 pub type RequestShardTransfer = Arc<dyn Fn(ShardTransfer) + Send + Sync>;
+pub type OnReshardingFailure = Arc<dyn Fn(ShardSplit, CollectionId, &str) + Send + Sync>;
+pub type OnReshardingSuccess = Arc<dyn Fn(ShardSplit, CollectionId) + Send + Sync>;
 
 struct CollectionVersion;
 
@@ -88,7 +128,6 @@ pub struct Collection {
     channel_service: ChannelService,
     transfer_tasks: Mutex<TransferTasksPool>,
     request_shard_transfer_cb: RequestShardTransfer,
-    #[allow(dead_code)] //Might be useful in case of repartition implementation
     notify_peer_failure_cb: ChangePeerState,
     init_time: Duration,
     // One-way boolean flag that is set to true when the collection is fully initialized
@@ -100,6 +139,28 @@ pub struct Collection {
     updates_lock: RwLock<()>,
     // Update runtime handle.
     update_runtime: Handle,
+    // Search runtime handle.
+    search_runtime: Handle,
+    // Task, which copies points of the split shard into the new shard, if this peer drives resharding.
+    resharding_task: Mutex<Option<StoppableAsyncTaskHandle<bool>>>,
+    // Number of points copied into the new shard by the resharding task.
+    resharding_progress: Arc<AtomicUsize>,
+    // Dead replicas, which are replaced by new replicas, if this peer is the consensus leader.
+    replica_failover: parking_lot::Mutex<ReplicaFailoverState>,
+    // Durations of searches and updates, requested from this peer.
+    telemetry_search_durations: Arc<parking_lot::Mutex<OperationDurationsAggregator>>,
+    telemetry_update_durations: Arc<parking_lot::Mutex<OperationDurationsAggregator>>,
+    // Work done by searches of the local shards.
+    telemetry_search_stats: parking_lot::Mutex<SearchStats>,
+    // Payload fields in filters of reads, requested from this peer.
+    filter_usage: parking_lot::Mutex<FilterUsage>,
+    // Last computed resource usage of the local shards, with the time of computing.
+    resource_usage: Mutex<Option<(Instant, CollectionResourceUsage)>>,
+    // Searches and updates, requested from this peer, limited by the strict mode config.
+    search_limiter: SearchConcurrencyLimiter,
+    update_limiter: UpdateRateLimiter,
+    // Held by the running recall self-test, so that tests run one at a time.
+    recall_test_lock: Mutex<()>,
 }
 
 impl Collection {
@@ -124,7 +185,11 @@ impl Collection {
     ) -> Result<Self, CollectionError> {
         let start_time = std::time::Instant::now();
 
-        let mut shard_holder = ShardHolder::new(path, HashRing::fair(HASH_RING_SHARD_SCALE))?;
+        let mut shard_holder = ShardHolder::new(
+            path,
+            HashRing::fair(HASH_RING_SHARD_SCALE),
+            collection_config.params.sharding_method.unwrap_or_default(),
+        )?;
 
         let shared_collection_config = Arc::new(RwLock::new(collection_config.clone()));
         for (shard_id, mut peers) in shard_distribution.shards {
@@ -146,7 +211,7 @@ impl Collection {
             )
             .await?;
 
-            shard_holder.add_shard(shard_id, replica_set);
+            shard_holder.add_shard(shard_id, replica_set, None)?;
         }
 
         let locked_shard_holder = Arc::new(LockedShardHolder::new(shard_holder));
@@ -171,6 +236,18 @@ impl Collection {
             is_initialized: Arc::new(Default::default()),
             updates_lock: RwLock::new(()),
             update_runtime: update_runtime.unwrap_or_else(Handle::current),
+            search_runtime: search_runtime.unwrap_or_else(Handle::current),
+            resharding_task: Mutex::new(None),
+            resharding_progress: Arc::new(AtomicUsize::new(0)),
+            replica_failover: Default::default(),
+            telemetry_search_durations: OperationDurationsAggregator::new(),
+            telemetry_update_durations: OperationDurationsAggregator::new(),
+            telemetry_search_stats: Default::default(),
+            filter_usage: Default::default(),
+            resource_usage: Default::default(),
+            search_limiter: Default::default(),
+            update_limiter: Default::default(),
+            recall_test_lock: Default::default(),
         })
     }
 
@@ -197,6 +274,11 @@ impl Collection {
         true
     }
 
+    /// Load the collection from disk.
+    ///
+    /// Fails, if the version or the config of the collection can't be read or is not supported.
+    /// Failed shards either panic or are replaced by dummy shards,
+    /// depending on `handle_collection_load_errors`.
     #[allow(clippy::too_many_arguments)]
     pub async fn load(
         collection_id: CollectionId,
@@ -209,43 +291,57 @@ impl Collection {
         request_shard_transfer: RequestShardTransfer,
         search_runtime: Option<Handle>,
         update_runtime: Option<Handle>,
-    ) -> Self {
+    ) -> CollectionResult<Self> {
         let start_time = std::time::Instant::now();
-        let stored_version = CollectionVersion::load(path)
-            .expect("Can't read collection version")
+        let stored_version: Version = CollectionVersion::load(path)
+            .map_err(|err| {
+                CollectionError::service_error(format!("Can't read collection version: {err}"))
+            })?
             .parse()
-            .expect("Failed to parse stored collection version as semver");
+            .map_err(|err| {
+                CollectionError::service_error(format!(
+                    "Failed to parse stored collection version as semver: {err}"
+                ))
+            })?;
 
         let app_version: Version = CollectionVersion::current()
             .parse()
             .expect("Failed to parse current collection version as semver");
 
         if stored_version > app_version {
-            panic!("Collection version is greater than application version");
+            return Err(CollectionError::service_error(format!(
+                "Collection version {stored_version} is greater than application version {app_version}"
+            )));
         }
 
         if stored_version != app_version {
             if Self::can_upgrade_storage(&stored_version, &app_version) {
                 log::info!("Migrating collection {stored_version} -> {app_version}");
-                CollectionVersion::save(path)
-                    .unwrap_or_else(|err| panic!("Can't save collection version {err}"));
+                CollectionVersion::save(path).map_err(|err| {
+                    CollectionError::service_error(format!("Can't save collection version {err}"))
+                })?;
             } else {
                 log::error!("Cannot upgrade version {stored_version} to {app_version}.");
-                panic!("Cannot upgrade version {stored_version} to {app_version}. Try to use older version of Qdrant first.");
+                return Err(CollectionError::service_error(format!(
+                    "Cannot upgrade version {stored_version} to {app_version}. Try to use older version of Qdrant first."
+                )));
             }
         }
 
-        let collection_config = CollectionConfig::load(path).unwrap_or_else(|err| {
-            panic!(
-                "Can't read collection config due to {}\nat {}",
-                err,
-                path.to_str().unwrap(),
-            )
-        });
+        let collection_config = CollectionConfig::load(path).map_err(|err| {
+            CollectionError::service_error(format!(
+                "Can't read collection config due to {err}\nat {}",
+                path.display(),
+            ))
+        })?;
         collection_config.validate_and_warn();
 
         let ring = HashRing::fair(HASH_RING_SHARD_SCALE);
-        let mut shard_holder = ShardHolder::new(path, ring).expect("Can not create shard holder");
+        let mut shard_holder = ShardHolder::new(
+            path,
+            ring,
+            collection_config.params.sharding_method.unwrap_or_default(),
+        )?;
 
         let shared_collection_config = Arc::new(RwLock::new(collection_config.clone()));
 
@@ -265,7 +361,7 @@ impl Collection {
 
         let locked_shard_holder = Arc::new(LockedShardHolder::new(shard_holder));
 
-        Self {
+        Ok(Self {
             id: collection_id.clone(),
             shards_holder: locked_shard_holder,
             collection_config: shared_collection_config,
@@ -281,7 +377,19 @@ impl Collection {
             is_initialized: Arc::new(Default::default()),
             updates_lock: RwLock::new(()),
             update_runtime: update_runtime.unwrap_or_else(Handle::current),
-        }
+            search_runtime: search_runtime.unwrap_or_else(Handle::current),
+            resharding_task: Mutex::new(None),
+            resharding_progress: Arc::new(AtomicUsize::new(0)),
+            replica_failover: Default::default(),
+            telemetry_search_durations: OperationDurationsAggregator::new(),
+            telemetry_update_durations: OperationDurationsAggregator::new(),
+            telemetry_search_stats: Default::default(),
+            filter_usage: Default::default(),
+            resource_usage: Default::default(),
+            search_limiter: Default::default(),
+            update_limiter: Default::default(),
+            recall_test_lock: Default::default(),
+        })
     }
 
     /// Return a list of local shards, present on this peer
@@ -296,6 +404,20 @@ impl Collection {
         res
     }
 
+    /// Whether all local shards are ready to serve requests.
+    ///
+    /// Shards are loaded, including WAL replay, before the collection is available.
+    /// Local replicas, which still receive their data from other peers, are not ready.
+    pub async fn is_ready(&self) -> bool {
+        let shards_holder = self.shards_holder.read().await;
+        for (_, replica_set) in shards_holder.get_shards() {
+            if !replica_set.is_local_ready().await {
+                return false;
+            }
+        }
+        true
+    }
+
     pub async fn is_all_active(&self) -> bool {
         let shards_holder = self.shards_holder.read().await;
         for (_, replica_set) in shards_holder.get_shards() {
@@ -409,6 +531,7 @@ impl Collection {
                     from: transfer_from,
                     to: self.this_peer_id,
                     sync: true,
+                    method: None,
                 })
             } else {
                 log::warn!("No alive replicas to recover shard {shard_id}");
@@ -493,6 +616,8 @@ impl Collection {
             transfer.clone(),
             collection_id,
             channel_service,
+            self.shared_storage_config.shard_transfer_wal_delta,
+            self.path.clone(),
             on_finish,
             on_error,
         );
@@ -679,6 +804,226 @@ impl Collection {
             .await
     }
 
+    /// Client ids of up to `count` last operations of the local shard, if there is one
+    pub async fn shard_recovery_point(
+        &self,
+        shard_id: ShardId,
+        count: usize,
+    ) -> CollectionResult<Vec<ClientOperationId>> {
+        let shard_holder_guard = self.shards_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&shard_id) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {shard_id} doesn't exist"
+            )));
+        };
+        replica_set.local_recovery_point(count).await
+    }
+
+    /// Find the replica set of the shard, which is transferred to this peer into a partial replica
+    fn incoming_transfer_replica_set<'a>(
+        &self,
+        shard_holder: &'a ShardHolder,
+        shard_id: ShardId,
+    ) -> CollectionResult<&'a ReplicaSetShard> {
+        let is_incoming = shard_holder
+            .shard_transfers
+            .read()
+            .iter()
+            .any(|transfer| transfer.shard_id == shard_id && transfer.to == self.this_peer_id);
+        let replica_set = shard_holder
+            .get_shard(&shard_id)
+            .filter(|_| is_incoming)
+            .ok_or_else(|| {
+                CollectionError::bad_request(format!(
+                    "Shard {shard_id} is not being transferred to peer {}",
+                    self.this_peer_id
+                ))
+            })?;
+        if replica_set.peer_state(&self.this_peer_id) != Some(ReplicaState::Partial) {
+            return Err(CollectionError::bad_request(format!(
+                "Shard {shard_id} is not partial on peer {}",
+                self.this_peer_id
+            )));
+        }
+        Ok(replica_set)
+    }
+
+    fn shard_snapshot_archive_path(&self, shard_id: ShardId) -> PathBuf {
+        self.path.join(format!("shard-{shard_id}-transfer.tar"))
+    }
+
+    /// Receive a chunk of the snapshot archive of the shard, transferred to this peer by snapshot.
+    ///
+    /// Returns the number of received bytes of the archive.
+    pub async fn upload_shard_snapshot_chunk(
+        &self,
+        shard_id: ShardId,
+        offset: u64,
+        data: &[u8],
+        checksum: u64,
+    ) -> CollectionResult<u64> {
+        let shard_holder = self.shards_holder.read().await;
+        self.incoming_transfer_replica_set(&shard_holder, shard_id)?;
+        receive_snapshot_chunk(
+            &self.shard_snapshot_archive_path(shard_id),
+            offset,
+            data,
+            checksum,
+        )
+        .await
+    }
+
+    /// Replace the partial local shard with the shard from the received snapshot archive.
+    ///
+    /// Shard stays partial, until operations applied on the source peer after the snapshot
+    /// are transferred too.
+    pub async fn recover_shard_snapshot(
+        &self,
+        shard_id: ShardId,
+        size: u64,
+        checksum: &str,
+    ) -> CollectionResult<()> {
+        let archive_path = self.shard_snapshot_archive_path(shard_id);
+        let shard_path = self.path.join(format!("shard-{shard_id}-transfer"));
+
+        let result = async {
+            let shard_holder = self.shards_holder.read().await;
+            let replica_set = self.incoming_transfer_replica_set(&shard_holder, shard_id)?;
+            unpack_snapshot(&archive_path, size, checksum, &shard_path).await?;
+            if !replica_set.restore_local_replica_from(&shard_path).await? {
+                return Err(CollectionError::service_error(format!(
+                    "Snapshot of shard {shard_id} does not contain shard data"
+                )));
+            }
+            Ok(())
+        }
+        .await;
+
+        if archive_path.exists() {
+            tokio::fs::remove_file(&archive_path).await?;
+        }
+        if shard_path.exists() {
+            tokio::fs::remove_dir_all(&shard_path).await?;
+        }
+        result
+    }
+
+    /// Path of the archive with the snapshot of the local shard, downloaded by another peer.
+    ///
+    /// Only names created by [`Self::create_shard_snapshot`] are accepted.
+    fn shard_snapshot_download_path(
+        &self,
+        shard_id: ShardId,
+        name: &str,
+    ) -> CollectionResult<PathBuf> {
+        let prefix = format!("shard-{shard_id}-snapshot-");
+        let is_valid = name.starts_with(&prefix)
+            && name.ends_with(".tar")
+            && Path::new(name).file_name() == Some(std::ffi::OsStr::new(name));
+        if !is_valid {
+            return Err(CollectionError::bad_input(format!(
+                "Invalid name of the snapshot archive of shard {shard_id}: {name}"
+            )));
+        }
+        Ok(self.path.join(name))
+    }
+
+    /// Archive the snapshot of the local shard, so that another peer can download it
+    /// for the snapshot of the whole collection.
+    ///
+    /// Returns the name and the manifest of the archive.
+    pub async fn create_shard_snapshot(
+        &self,
+        shard_id: ShardId,
+        temp_dir: &Path,
+    ) -> CollectionResult<(String, FileManifest)> {
+        let name = format!("shard-{shard_id}-snapshot-{}.tar", uuid::Uuid::new_v4());
+        let archive_path = self.path.join(&name);
+        let snapshot_dir = temp_dir.join(name.trim_end_matches(".tar"));
+
+        let result = async {
+            {
+                let shard_holder = self.shards_holder.read().await;
+                let replica_set =
+                    shard_holder
+                        .get_shard(&shard_id)
+                        .ok_or_else(|| CollectionError::NotFound {
+                            what: format!("Shard {shard_id}"),
+                        })?;
+                if !replica_set.has_local_shard().await {
+                    return Err(CollectionError::bad_request(format!(
+                        "Shard {shard_id} has no local replica on peer {}",
+                        self.this_peer_id
+                    )));
+                }
+                // If node is listener, we can save whatever currently is in the storage
+                let save_wal = self.shared_storage_config.node_type != NodeType::Listener;
+                replica_set
+                    .create_snapshot(&snapshot_dir.join("temp"), &snapshot_dir, save_wal)
+                    .await?;
+            }
+
+            let archive_path = archive_path.clone();
+            let snapshot_dir = snapshot_dir.clone();
+            tokio::task::spawn_blocking(move || {
+                // have to use std here, cause TarBuilder is not async
+                let file = std::fs::File::create(&archive_path)?;
+                let mut builder = TarBuilder::new(file);
+                builder.append_dir_all(".", &snapshot_dir)?;
+                builder.into_inner()?.sync_all()?;
+                Ok::<_, CollectionError>(FileManifest::of_file(&archive_path)?)
+            })
+            .await?
+        }
+        .await;
+
+        if snapshot_dir.exists() {
+            remove_dir_all(&snapshot_dir).await?;
+        }
+        match result {
+            Ok(manifest) => Ok((name, manifest)),
+            Err(err) => {
+                if archive_path.exists() {
+                    remove_file(&archive_path).await?;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Read a chunk of the shard snapshot archive, created by [`Self::create_shard_snapshot`]
+    ///
+    /// Returns the chunk and its checksum.
+    pub async fn get_shard_snapshot_chunk(
+        &self,
+        shard_id: ShardId,
+        name: &str,
+        offset: u64,
+    ) -> CollectionResult<(Vec<u8>, u64)> {
+        let archive_path = self.shard_snapshot_download_path(shard_id, name)?;
+        if !archive_path.exists() {
+            return Err(CollectionError::NotFound {
+                what: format!("Snapshot archive {name}"),
+            });
+        }
+        let data = read_snapshot_chunk(&archive_path, offset).await?;
+        let checksum = seahash::hash(&data);
+        Ok((data, checksum))
+    }
+
+    /// Delete the shard snapshot archive, once it is downloaded
+    pub async fn delete_shard_snapshot(
+        &self,
+        shard_id: ShardId,
+        name: &str,
+    ) -> CollectionResult<()> {
+        let archive_path = self.shard_snapshot_download_path(shard_id, name)?;
+        if archive_path.exists() {
+            remove_file(&archive_path).await?;
+        }
+        Ok(())
+    }
+
     /// Initiate local partial shard
     pub fn initiate_shard_transfer(
         &self,
@@ -747,7 +1092,7 @@ impl Collection {
     /// Shard transfer aware.
     pub async fn update_from_peer(
         &self,
-        operation: CollectionUpdateOperations,
+        operation: OperationWithClientId,
         shard_selection: ShardId,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
@@ -768,18 +1113,108 @@ impl Collection {
         }
     }
 
+    /// Handle a batch of collection updates from peers, applied to the shard in order.
+    pub async fn update_batch_from_peer(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        shard_selection: ShardId,
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        let _update_lock = self.updates_lock.read().await;
+        let shard_holder_guard = self.shards_holder.read().await;
+
+        let res = match shard_holder_guard.get_shard(&shard_selection) {
+            None => None,
+            Some(target_shard) => target_shard.update_local(operations, wait).await?,
+        };
+
+        res.ok_or_else(|| {
+            CollectionError::service_error(format!(
+                "No target shard {shard_selection} found for update"
+            ))
+        })
+    }
+
     pub async fn update_from_client(
         &self,
         operation: CollectionUpdateOperations,
         wait: bool,
         ordering: WriteOrdering,
     ) -> CollectionResult<UpdateResult> {
+        self.update_from_client_with_shard_key(operation, None, None, wait, ordering)
+            .await
+    }
+
+    /// Check vector names and dimensions of the operation against the collection config,
+    /// so that incompatible operations are rejected before they are written to WAL
+    async fn check_operation_vectors(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        if let CollectionUpdateOperations::VectorOperation(operation) = operation {
+            operation.check_vectors(&self.collection_config.read().await.params)?;
+        }
+        Ok(())
+    }
+
+    /// Handle collection updates from clients, applied to the shards of the shard key.
+    ///
+    /// Without a shard key, the operation is applied to all shards of a collection with custom
+    /// sharding, but new points can only be inserted with a shard key.
+    ///
+    /// With `client_op_id`, every shard applies the operation only once: a retry returns
+    /// the result of the first attempt, even if the data has changed since then.
+    /// Operations without it get a random id, by which replicas find the same operation
+    /// in their WALs, see [`LocalShard::recovery_point`].
+    ///
+    /// [`LocalShard::recovery_point`]: crate::shards::local_shard::LocalShard::recovery_point
+    pub async fn update_from_client_with_shard_key(
+        &self,
+        operation: CollectionUpdateOperations,
+        shard_key: Option<ShardKey>,
+        client_op_id: Option<ClientOperationId>,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<UpdateResult> {
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_update_durations);
+        timer.set_success(false);
+
         operation.validate()?;
+        self.check_operation_vectors(&operation).await?;
+        if let Some(strict_mode_config) = self.enabled_strict_mode_config().await {
+            if let Some(max_updates_per_second) = strict_mode_config.max_updates_per_second {
+                self.update_limiter
+                    .acquire(
+                        max_updates_per_second,
+                        strict_mode_config.rate_limit_timeout(),
+                    )
+                    .await?;
+            }
+        }
         let _update_lock = self.updates_lock.read().await;
 
-        let mut results = {
+        let client_op_id = client_op_id.unwrap_or_else(ClientOperationId::new_v4);
+
+        let results = {
             let shards_holder = self.shards_holder.read().await;
-            let shard_to_op = shards_holder.split_by_shard(operation);
+
+            let is_insert = matches!(
+                operation,
+                CollectionUpdateOperations::PointOperation(
+                    PointOperations::UpsertPoints(_) | PointOperations::SyncPoints(_)
+                )
+            );
+            if is_insert
+                && shard_key.is_none()
+                && shards_holder.sharding_method() == ShardingMethod::Custom
+            {
+                return Err(CollectionError::bad_request(
+                    "Shard key is required to insert points into collection with custom sharding"
+                        .to_string(),
+                ));
+            }
+
+            let shard_to_op = shards_holder.split_by_shard(operation, shard_key.as_ref())?;
 
             if shard_to_op.is_empty() {
                 return Err(CollectionError::bad_request(
@@ -790,7 +1225,11 @@ impl Collection {
             let shard_requests = shard_to_op
                 .into_iter()
                 .map(move |(replica_set, operation)| {
-                    replica_set.update_with_consistency(operation, wait, ordering)
+                    replica_set.update_with_consistency(
+                        OperationWithClientId::new(operation, Some(client_op_id)),
+                        wait,
+                        ordering,
+                    )
                 });
             join_all(shard_requests).await
         };
@@ -803,6 +1242,23 @@ impl Collection {
         // one request per shard
         let result_len = results.len();
 
+        // version conflicts are expected to be partial, report them for all shards at once
+        let all_conflicts = results
+            .iter()
+            .filter_map(|result| result.as_ref().err())
+            .all(|err| matches!(err, CollectionError::Conflict { .. }));
+
+        if with_error > 0 && all_conflicts {
+            let description = results
+                .into_iter()
+                .filter_map(|result| match result {
+                    Err(CollectionError::Conflict { description }) => Some(description),
+                    _ => None,
+                })
+                .join(", ");
+            return Err(CollectionError::Conflict { description });
+        }
+
         if with_error > 0 {
             let first_err = results
                 .into_iter()
@@ -825,112 +1281,468 @@ impl Collection {
             }
         } else {
             // At least one result is always present.
-            results.pop().unwrap()
+            let results = results.into_iter().collect::<CollectionResult<Vec<_>>>()?;
+            timer.set_success(true);
+            Ok(UpdateResult::merge_shards(results).unwrap())
         }
     }
 
-    pub async fn search_batch(
+    /// Handle a batch of collection updates from a client.
+    ///
+    /// Operations are split by shards, and every shard applies its part of the batch as
+    /// consecutive operations in the order of the batch. Result is reported per operation:
+    /// an operation is `Failed` if any of its shards failed to apply it.
+    ///
+    /// The whole batch takes a single token of `max_updates_per_second`, as one update request.
+    pub async fn update_batch_from_client(
         &self,
-        request: SearchRequestBatch,
-        read_consistency: Option<ReadConsistency>,
-        shard_selection: Option<ShardId>,
-    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        // shortcuts batch if all requests with limit=0
-        if request.searches.iter().all(|s| s.limit == 0) {
-            return Ok(vec![]);
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_update_durations);
+        timer.set_success(false);
+
+        if operations.is_empty() {
+            return Err(CollectionError::bad_request(
+                "Empty update request".to_string(),
+            ));
         }
-        // A factor which determines if we need to use the 2-step search or not
-        // Should be adjusted based on usage statistics.
-        const PAYLOAD_TRANSFERS_FACTOR_THRESHOLD: usize = 10;
+        for operation in &operations {
+            operation.validate()?;
+            self.check_operation_vectors(operation).await?;
+        }
+        if let Some(strict_mode_config) = self.enabled_strict_mode_config().await {
+            if let Some(max_updates_per_second) = strict_mode_config.max_updates_per_second {
+                self.update_limiter
+                    .acquire(
+                        max_updates_per_second,
+                        strict_mode_config.rate_limit_timeout(),
+                    )
+                    .await?;
+            }
+        }
+        let _update_lock = self.updates_lock.read().await;
 
-        let is_payload_required = request.searches.iter().all(|s| {
-            s.with_payload
-                .clone()
-                .map(|p| p.is_required())
-                .unwrap_or_default()
-        });
-        let with_vectors = request.searches.iter().all(|s| {
-            s.with_vector
-                .as_ref()
-                .map(|wv| wv.is_some())
-                .unwrap_or(false)
-        });
+        let operations_count = operations.len();
 
-        let metadata_required = is_payload_required || with_vectors;
+        let shard_results = {
+            let shards_holder = self.shards_holder.read().await;
 
-        let sum_limits: usize = request.searches.iter().map(|s| s.limit).sum();
-        let sum_offsets: usize = request.searches.iter().map(|s| s.offset).sum();
+            // positions of the operations in the batch and their parts, per shard
+            let mut shard_batches: HashMap<ShardId, (&ReplicaSetShard, Vec<(usize, _)>)> =
+                HashMap::new();
 
-        // Number of records we need to retrieve to fill the search result.
-        let require_transfers = self.shards_holder.read().await.len() * (sum_limits + sum_offsets);
-        // Actually used number of records.
-        let used_transfers = sum_limits;
+            for (idx, operation) in operations.into_iter().enumerate() {
+                let is_insert = matches!(
+                    operation,
+                    CollectionUpdateOperations::PointOperation(
+                        PointOperations::UpsertPoints(_) | PointOperations::SyncPoints(_)
+                    )
+                );
+                if is_insert && shards_holder.sharding_method() == ShardingMethod::Custom {
+                    return Err(CollectionError::bad_request(format!(
+                        "Operation {idx} of the batch requires shard key to insert points into collection with custom sharding"
+                    )));
+                }
 
-        let is_required_transfer_large_enough =
-            require_transfers > used_transfers * PAYLOAD_TRANSFERS_FACTOR_THRESHOLD;
+                let shard_to_op = shards_holder.split_by_shard(operation, None)?;
 
-        if metadata_required && is_required_transfer_large_enough {
-            // If there is a significant offset, we need to retrieve the whole result
-            // set without payload first and then retrieve the payload.
-            // It is required to do this because the payload might be too large to send over the
-            // network.
-            let mut without_payload_requests = Vec::with_capacity(request.searches.len());
-            for search in &request.searches {
-                let mut without_payload_request = search.clone();
-                without_payload_request.with_payload = None;
-                without_payload_request.with_vector = None;
-                without_payload_requests.push(without_payload_request);
-            }
-            let without_payload_batch = SearchRequestBatch {
-                searches: without_payload_requests,
-            };
-            let without_payload_results = self
-                ._search_batch(without_payload_batch, read_consistency, shard_selection)
-                .await?;
-            let filled_results = without_payload_results
-                .into_iter()
-                .zip(request.clone().searches.into_iter())
-                .map(|(without_payload_result, req)| {
-                    self.fill_search_result_with_payload(
-                        without_payload_result,
-                        req.with_payload.clone(),
-                        req.with_vector.unwrap_or_default(),
-                        read_consistency,
-                        shard_selection,
-                    )
-                });
-            try_join_all(filled_results).await
-        } else {
-            let result = self
-                ._search_batch(request, read_consistency, shard_selection)
-                .await?;
-            Ok(result)
-        }
-    }
+                if shard_to_op.is_empty() {
+                    return Err(CollectionError::bad_request(format!(
+                        "Operation {idx} of the batch doesn't target any shard"
+                    )));
+                }
 
-    pub async fn _search_batch(
-        &self,
-        request: SearchRequestBatch,
-        read_consistency: Option<ReadConsistency>,
-        shard_selection: Option<ShardId>,
-    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        let batch_size = request.searches.len();
-        let request = Arc::new(request);
+                for (replica_set, operation) in shard_to_op {
+                    shard_batches
+                        .entry(replica_set.shard_id)
+                        .or_insert_with(|| (replica_set, Vec::new()))
+                        .1
+                        .push((idx, operation));
+                }
+            }
 
-        // query all shards concurrently
-        let mut all_searches_res = {
-            let shard_holder = self.shards_holder.read().await;
-            let target_shards = shard_holder.target_shard(shard_selection)?;
-            let all_searches = target_shards
-                .iter()
-                .map(|shard| shard.search(request.clone(), read_consistency));
-            try_join_all(all_searches).await?
+            let shard_requests =
+                shard_batches
+                    .into_values()
+                    .map(|(replica_set, batch)| async move {
+                        let (positions, batch): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+                        let result = replica_set
+                            .update_with_consistency(batch, wait, ordering)
+                            .await;
+                        (positions, result)
+                    });
+            join_all(shard_requests).await
         };
 
-        // merge results from shards in order
-        let mut merged_results: Vec<Vec<ScoredPoint>> = vec![vec![]; batch_size];
-        for shard_searches_results in all_searches_res.iter_mut() {
-            for (index, shard_searches_result) in shard_searches_results.iter_mut().enumerate() {
+        // all shards failed to accept their batches - propagate first error
+        if shard_results.iter().all(|(_, result)| result.is_err()) {
+            let (_, first_err) = shard_results.into_iter().next().unwrap();
+            return first_err;
+        }
+
+        let mut results: Vec<Vec<UpdateResult>> =
+            (0..operations_count).map(|_| Vec::new()).collect();
+        let mut errors: Vec<Vec<String>> = vec![Vec::new(); operations_count];
+
+        for (positions, shard_result) in shard_results {
+            match shard_result {
+                Ok(batch_results) => {
+                    for (idx, result) in positions.into_iter().zip(batch_results) {
+                        if let Some(error) = &result.error {
+                            errors[idx].push(error.clone());
+                        }
+                        results[idx].push(result);
+                    }
+                }
+                Err(err) => {
+                    for idx in positions {
+                        errors[idx].push(err.to_string());
+                    }
+                }
+            }
+        }
+
+        let results = results
+            .into_iter()
+            .zip(errors)
+            .map(|(shard_results, errors)| {
+                let result = UpdateResult::merge_shards(shard_results);
+                if errors.is_empty() {
+                    // every operation targets at least one shard
+                    result.unwrap()
+                } else {
+                    UpdateResult {
+                        operation_id: result.map_or(0, |result| result.operation_id),
+                        status: UpdateStatus::Failed,
+                        error: Some(errors.join("; ")),
+                        deleted_count: None,
+                        deleted_ids: None,
+                    }
+                }
+            })
+            .collect();
+
+        timer.set_success(true);
+        Ok(results)
+    }
+
+    /// Strict mode config of the collection, if strict mode is enabled
+    ///
+    /// The config is read by every request, so updated limits apply to the next requests.
+    async fn enabled_strict_mode_config(&self) -> Option<StrictModeConfig> {
+        self.collection_config
+            .read()
+            .await
+            .strict_mode_config
+            .filter(StrictModeConfig::is_enabled)
+    }
+
+    /// Check filters of a client request against the strict mode limits
+    async fn check_strict_mode_filters<'a>(
+        &self,
+        strict_mode_config: &StrictModeConfig,
+        filters: impl IntoIterator<Item = &'a Filter>,
+        is_update: bool,
+    ) -> CollectionResult<()> {
+        let filters: Vec<_> = filters.into_iter().collect();
+        if filters.is_empty() {
+            return Ok(());
+        }
+        let payload_schema = if strict_mode_config.allows_unindexed_filtering(is_update) {
+            HashMap::new()
+        } else {
+            self.payload_schema().await?
+        };
+        for filter in filters {
+            strict_mode_config
+                .check_filter(filter, is_update, |key| payload_schema.contains_key(key))?;
+        }
+        Ok(())
+    }
+
+    /// Payload indexes of the collection
+    async fn payload_schema(&self) -> CollectionResult<HashMap<PayloadKeyType, PayloadIndexInfo>> {
+        // Payload indexes are created in every shard, so the schema of any shard is used
+        let shards_holder = self.shards_holder.read().await;
+        match shards_holder.all_shards().next() {
+            Some(shard) => Ok(shard.info().await?.payload_schema),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    /// Record payload fields in filters of a client read, to suggest payload indexes
+    async fn record_filter_usage<'a>(&self, filters: impl IntoIterator<Item = &'a Filter>) {
+        let filters: Vec<_> = filters.into_iter().collect();
+        if filters.is_empty() {
+            return;
+        }
+        let payload_schema = match self.payload_schema().await {
+            Ok(payload_schema) => payload_schema,
+            Err(err) => {
+                log::debug!("Filter usage is not recorded, payload schema is unavailable: {err}");
+                return;
+            }
+        };
+        let mut filter_usage = self.filter_usage.lock();
+        for filter in filters {
+            filter_usage.record(filter, |key| payload_schema.contains_key(key));
+        }
+    }
+
+    /// Payload fields, which are filtered without a payload index the most.
+    ///
+    /// Only reads requested from this peer are counted.
+    pub async fn index_suggestions(&self) -> CollectionResult<Vec<PayloadIndexSuggestion>> {
+        let payload_schema = self.payload_schema().await?;
+        Ok(self
+            .filter_usage
+            .lock()
+            .suggestions(INDEX_SUGGESTIONS_LIMIT, |key| {
+                payload_schema.contains_key(key)
+            }))
+    }
+
+    /// Forget the usage of payload fields in filters, which the index suggestions are based on
+    pub fn reset_index_suggestions(&self) {
+        self.filter_usage.lock().reset();
+    }
+
+    /// Check an update operation of a client against the strict mode limits
+    pub async fn check_strict_mode_update(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        let Some(strict_mode_config) = self.enabled_strict_mode_config().await else {
+            return Ok(());
+        };
+        strict_mode_config.check_update(operation)?;
+        self.check_strict_mode_filters(&strict_mode_config, operation_filter(operation), true)
+            .await
+    }
+
+    /// Search for a batch of queries, with a result per each of them
+    ///
+    /// All queries are searched at once. Unless `fail_fast` is set, queries of a batch failed by
+    /// a non-transient error are searched one by one, so an invalid query fails only itself.
+    pub async fn search_batch(
+        &self,
+        request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: impl Into<ShardSelectorInternal>,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<Vec<CollectionResult<Vec<ScoredPoint>>>> {
+        let shard_selection = shard_selection.into();
+        let fail_fast = request.fail_fast.unwrap_or(false);
+        let searches = (!fail_fast).then(|| request.searches.clone());
+
+        let err = match self
+            .core_search_batch(
+                request.into(),
+                read_consistency,
+                shard_selection.clone(),
+                timeout,
+            )
+            .await
+        {
+            Ok(results) => return Ok(results.into_iter().map(Ok).collect()),
+            Err(err) => err,
+        };
+
+        match searches {
+            Some(searches) if !err.is_transient() => {
+                if let [_] = searches.as_slice() {
+                    return Ok(vec![Err(err)]);
+                }
+                let isolated_searches = searches.into_iter().map(|search| {
+                    self.search(search, read_consistency, shard_selection.clone(), timeout)
+                });
+                Ok(join_all(isolated_searches).await)
+            }
+            _ => Err(err),
+        }
+    }
+
+    /// Search for a batch of queries of any kind, see [`CoreSearchRequest`]
+    ///
+    /// Searches of a shard are aborted after the `timeout`, if not set, the default timeout of
+    /// the collection or of the service is used.
+    pub async fn core_search_batch(
+        &self,
+        request: CoreSearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: impl Into<ShardSelectorInternal>,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let shard_selection = shard_selection.into();
+        // shortcuts batch if all requests with limit=0
+        if request.searches.iter().all(|s| s.limit == 0) {
+            return Ok(vec![]);
+        }
+        // A factor which determines if we need to use the 2-step search or not
+        // Should be adjusted based on usage statistics.
+        const PAYLOAD_TRANSFERS_FACTOR_THRESHOLD: usize = 10;
+
+        let is_payload_required = request.searches.iter().all(|s| {
+            s.with_payload
+                .clone()
+                .map(|p| p.is_required())
+                .unwrap_or_default()
+        });
+        let with_vectors = request.searches.iter().all(|s| {
+            s.with_vector
+                .as_ref()
+                .map(|wv| wv.is_some())
+                .unwrap_or(false)
+        });
+
+        let metadata_required = is_payload_required || with_vectors;
+
+        let sum_limits: usize = request.searches.iter().map(|s| s.limit).sum();
+        let sum_offsets: usize = request.searches.iter().map(|s| s.offset).sum();
+
+        // Number of records we need to retrieve to fill the search result.
+        let require_transfers = self.shards_holder.read().await.len() * (sum_limits + sum_offsets);
+        // Actually used number of records.
+        let used_transfers = sum_limits;
+
+        let is_required_transfer_large_enough =
+            require_transfers > used_transfers * PAYLOAD_TRANSFERS_FACTOR_THRESHOLD;
+
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_search_durations);
+        timer.set_success(false);
+
+        let result = if metadata_required && is_required_transfer_large_enough {
+            // If there is a significant offset, we need to retrieve the whole result
+            // set without payload first and then retrieve the payload.
+            // It is required to do this because the payload might be too large to send over the
+            // network.
+            let mut without_payload_requests = Vec::with_capacity(request.searches.len());
+            for search in &request.searches {
+                let mut without_payload_request = search.clone();
+                without_payload_request.with_payload = None;
+                without_payload_request.with_vector = None;
+                without_payload_requests.push(without_payload_request);
+            }
+            let without_payload_batch = CoreSearchRequestBatch {
+                searches: without_payload_requests,
+            };
+            let without_payload_results = self
+                ._core_search_batch(
+                    without_payload_batch,
+                    read_consistency,
+                    shard_selection.clone(),
+                    timeout,
+                )
+                .await?;
+            let filled_results = without_payload_results
+                .into_iter()
+                .zip(request.clone().searches.into_iter())
+                .map(|(without_payload_result, req)| {
+                    self.fill_search_result_with_payload(
+                        without_payload_result,
+                        req.with_payload.clone(),
+                        req.with_vector.unwrap_or_default(),
+                        read_consistency,
+                        shard_selection.clone(),
+                    )
+                });
+            try_join_all(filled_results).await
+        } else {
+            self._core_search_batch(request, read_consistency, shard_selection, timeout)
+                .await
+        };
+
+        timer.set_success(result.is_ok());
+        result
+    }
+
+    pub async fn _core_search_batch(
+        &self,
+        request: CoreSearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: impl Into<ShardSelectorInternal>,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let shard_selection = shard_selection.into();
+        // Malformed query vectors are rejected here, instead of failing in every segment
+        {
+            let collection_config = self.collection_config.read().await;
+            for search in &request.searches {
+                search.query.check_vectors(&collection_config.params)?;
+            }
+        }
+        // Searches of other peers are limited by the peer, which they are requested from
+        let mut _search_permit = None;
+        if !shard_selection.is_shard_id() {
+            if let Some(strict_mode_config) = self.enabled_strict_mode_config().await {
+                for search in &request.searches {
+                    strict_mode_config.check_limit_and_offset(search.limit, search.offset)?;
+                }
+                let filters = request.searches.iter().filter_map(|s| s.filter.as_ref());
+                self.check_strict_mode_filters(&strict_mode_config, filters, false)
+                    .await?;
+                if let Some(max_concurrent_searches) = strict_mode_config.max_concurrent_searches {
+                    let permit = self
+                        .search_limiter
+                        .acquire(
+                            max_concurrent_searches,
+                            strict_mode_config.rate_limit_timeout(),
+                        )
+                        .await?;
+                    _search_permit = Some(permit);
+                }
+            }
+            self.record_filter_usage(request.searches.iter().filter_map(|s| s.filter.as_ref()))
+                .await;
+        }
+
+        let batch_size = request.searches.len();
+        let request = Arc::new(request);
+
+        // query all shards concurrently
+        let (all_searches_res, stats) = collect_search_stats(async {
+            let shard_holder = self.shards_holder.read().await;
+            let target_shards = shard_holder.select_shards(&shard_selection)?;
+            let all_searches = target_shards.iter().map(|shard| {
+                let span = tracing::info_span!(
+                    "shard_search",
+                    collection = %self.id,
+                    shard_id = shard.shard_id,
+                    result_count = tracing::field::Empty,
+                );
+                let search = shard.core_search(request.clone(), read_consistency, timeout);
+                async move {
+                    let res = search.await;
+                    if let Ok(res) = &res {
+                        let result_count = res.iter().map(Vec::len).sum::<usize>();
+                        tracing::Span::current().record("result_count", result_count);
+                    }
+                    res
+                }
+                .instrument(span)
+            });
+            let mut all_searches_res = try_join_all(all_searches).await?;
+            for (shard, shard_searches_results) in target_shards.iter().zip(&mut all_searches_res) {
+                for shard_searches_result in shard_searches_results {
+                    shard_holder.retain_owned_points(
+                        shard.shard_id,
+                        shard_searches_result,
+                        |point| point.id,
+                    );
+                }
+            }
+            CollectionResult::Ok(all_searches_res)
+        })
+        .await;
+        *self.telemetry_search_stats.lock() += stats;
+        let mut all_searches_res = all_searches_res?;
+
+        // merge results from shards in order
+        let mut merged_results: Vec<Vec<ScoredPoint>> = vec![vec![]; batch_size];
+        for shard_searches_results in all_searches_res.iter_mut() {
+            for (index, shard_searches_result) in shard_searches_results.iter_mut().enumerate() {
                 merged_results[index].append(shard_searches_result)
             }
         }
@@ -939,10 +1751,16 @@ impl Collection {
             .into_iter()
             .zip(request.searches.iter())
             .map(|(res, request)| {
-                let distance = collection_params
-                    .get_vector_params(request.vector.get_name())?
-                    .distance;
-                let mut top_res = match distance.distance_order() {
+                let order = if request.query.is_distance_scored() {
+                    collection_params
+                        .get_vector_params(request.query.get_vector_name())?
+                        .distance
+                        .distance_order()
+                } else {
+                    // Scores of custom queries are always "larger is better"
+                    Order::LargeBetter
+                };
+                let mut top_res = match order {
                     Order::LargeBetter => {
                         peek_top_largest_iterable(res, request.limit + request.offset)
                     }
@@ -952,7 +1770,7 @@ impl Collection {
                 };
                 // Remove `offset` from top result only for client requests
                 // to avoid applying `offset` twice in distributed mode.
-                if shard_selection.is_none() && request.offset > 0 {
+                if !shard_selection.is_shard_id() && request.offset > 0 {
                     if top_res.len() >= request.offset {
                         // Panics if the end point > length of the vector.
                         top_res.drain(..request.offset);
@@ -973,7 +1791,7 @@ impl Collection {
         with_payload: Option<WithPayloadInterface>,
         with_vector: WithVector,
         read_consistency: Option<ReadConsistency>,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> CollectionResult<Vec<ScoredPoint>> {
         // short-circuit if not needed
         if let (&Some(WithPayloadInterface::Bool(false)), &WithVector::Bool(false)) =
@@ -993,6 +1811,7 @@ impl Collection {
             ids: search_result.iter().map(|x| x.id).collect(),
             with_payload,
             with_vector,
+            with_version: false,
         };
         let retrieved_records = self
             .retrieve(retrieve_request, read_consistency, shard_selection)
@@ -1021,18 +1840,46 @@ impl Collection {
         &self,
         request: SearchRequest,
         read_consistency: Option<ReadConsistency>,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
+        timeout: Option<Duration>,
     ) -> CollectionResult<Vec<ScoredPoint>> {
         if request.limit == 0 {
             return Ok(vec![]);
         }
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_search_durations);
+        timer.set_success(false);
+
         // search is a special case of search_batch with a single batch
-        let request_batch = SearchRequestBatch {
-            searches: vec![request],
+        let request_batch = CoreSearchRequestBatch {
+            searches: vec![request.into()],
+        };
+        let results = self
+            ._core_search_batch(request_batch, read_consistency, shard_selection, timeout)
+            .await?;
+        timer.set_success(true);
+        Ok(results.into_iter().next().unwrap())
+    }
+
+    /// Look for points by a query, which is executed as a search of shards
+    pub async fn query(
+        &self,
+        request: QueryRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        if request.limit == 0 {
+            return Ok(vec![]);
+        }
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_search_durations);
+        timer.set_success(false);
+
+        let request_batch = CoreSearchRequestBatch {
+            searches: vec![request.into()],
         };
         let results = self
-            ._search_batch(request_batch, read_consistency, shard_selection)
+            ._core_search_batch(request_batch, read_consistency, shard_selection, None)
             .await?;
+        timer.set_success(true);
         Ok(results.into_iter().next().unwrap())
     }
 
@@ -1040,11 +1887,12 @@ impl Collection {
         &self,
         request: ScrollRequest,
         read_consistency: Option<ReadConsistency>,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> CollectionResult<ScrollResult> {
+        let shard_selection = shard_selection.into();
         let default_request = ScrollRequest::default();
 
-        let offset = request.offset;
+        let mut offset = request.offset;
         let limit = request
             .limit
             .unwrap_or_else(|| default_request.limit.unwrap());
@@ -1060,54 +1908,138 @@ impl Collection {
             });
         }
 
+        if !shard_selection.is_shard_id() {
+            if let Some(strict_mode_config) = self.enabled_strict_mode_config().await {
+                strict_mode_config.check_limit_and_offset(limit, 0)?;
+                self.check_strict_mode_filters(&strict_mode_config, request.filter.as_ref(), false)
+                    .await?;
+            }
+            self.record_filter_usage(request.filter.as_ref()).await;
+        }
+
+        let mut order_by = request.order_by;
+        if let Some(order_by) = &order_by {
+            if offset.is_some() && order_by.start_from.is_none() {
+                return Err(CollectionError::BadRequest {
+                    description:
+                        "Offset requires `order_by.start_from` to order points by a payload field"
+                            .to_string(),
+                });
+            }
+        }
+
+        let page_token = request
+            .page_token
+            .as_deref()
+            .map(|token| ScrollPageToken::decode(token, &self.id, order_by.as_ref()))
+            .transpose()?;
+        if let Some(page_token) = &page_token {
+            let start_from = order_by.as_ref().and_then(|order_by| order_by.start_from);
+            if offset.is_some() || start_from.is_some() {
+                return Err(CollectionError::bad_request(
+                    "Page token can't be combined with `offset` and `order_by.start_from`"
+                        .to_string(),
+                ));
+            }
+            // Resume from the last returned point, it is skipped below
+            offset = Some(page_token.last_id());
+            if let Some(order_by) = &mut order_by {
+                order_by.start_from = page_token.order_value();
+            }
+        }
+        let order_by = order_by.as_ref();
+
         // Needed to return next page offset.
         let limit = limit + 1;
+        // The last point of the previous page might be read again
+        let read_limit = limit + usize::from(page_token.is_some());
         let retrieved_points: Vec<_> = {
             let shards_holder = self.shards_holder.read().await;
-            let target_shards = shards_holder.target_shard(shard_selection)?;
-            let scroll_futures = target_shards.into_iter().map(|shard| {
+            let target_shards = shards_holder.select_shards(&shard_selection)?;
+            let scroll_futures = target_shards.iter().map(|shard| {
                 shard.scroll_by(
                     offset,
-                    limit,
+                    read_limit,
                     &with_payload_interface,
                     &with_vector,
                     request.filter.as_ref(),
+                    order_by,
                     read_consistency,
                 )
             });
 
-            try_join_all(scroll_futures).await?
+            let mut retrieved_points = try_join_all(scroll_futures).await?;
+            for (shard, shard_points) in target_shards.iter().zip(&mut retrieved_points) {
+                shards_holder.retain_owned_points(shard.shard_id, shard_points, |point| point.id);
+            }
+            retrieved_points
+        };
+        let after_page_token = |point: &Record| {
+            page_token
+                .as_ref()
+                .map_or(true, |page_token| page_token.precedes(point, order_by))
+        };
+        let mut points: Vec<_> = match order_by {
+            None => retrieved_points
+                .into_iter()
+                .flatten()
+                .filter(after_page_token)
+                .sorted_by_key(|point| point.id)
+                .take(limit)
+                .collect(),
+            Some(order_by) => {
+                // Points of every shard are already ordered
+                let direction = order_by.direction();
+                retrieved_points
+                    .into_iter()
+                    .kmerge_by(|a, b| {
+                        direction
+                            .compare(&(a.order_value, a.id), &(b.order_value, b.id))
+                            .is_lt()
+                    })
+                    .filter(after_page_token)
+                    .take(limit)
+                    .collect()
+            }
         };
-        let mut points: Vec<_> = retrieved_points
-            .into_iter()
-            .flatten()
-            .sorted_by_key(|point| point.id)
-            .take(limit)
-            .collect();
 
-        let next_page_offset = if points.len() < limit {
+        let next_page = if points.len() < limit {
             // This was the last page
             None
         } else {
             // remove extra point, it would be a first point of the next page
-            Some(points.pop().unwrap().id)
+            points.pop()
         };
+        let next_page_token = next_page.as_ref().and_then(|_| {
+            let last_point = points.last()?;
+            Some(ScrollPageToken::new(&self.id, last_point, order_by).encode())
+        });
         Ok(ScrollResult {
             points,
-            next_page_offset,
+            next_page_offset: next_page.as_ref().map(|point| point.id),
+            next_page_start_from: next_page.and_then(|point| point.order_value),
+            next_page_token,
         })
     }
 
     pub async fn count(
         &self,
         request: CountRequest,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> CollectionResult<CountResult> {
+        let shard_selection = shard_selection.into();
+        if !shard_selection.is_shard_id() {
+            if let Some(strict_mode_config) = self.enabled_strict_mode_config().await {
+                self.check_strict_mode_filters(&strict_mode_config, request.filter.as_ref(), false)
+                    .await?;
+            }
+            self.record_filter_usage(request.filter.as_ref()).await;
+        }
         let request = Arc::new(request);
 
         let counts: Vec<_> = {
             let shards_holder = self.shards_holder.read().await;
-            let target_shards = shards_holder.target_shard(shard_selection)?;
+            let target_shards = shards_holder.select_shards(&shard_selection)?;
             let count_futures = target_shards
                 .into_iter()
                 .map(|shard| shard.count(request.clone()));
@@ -1115,16 +2047,76 @@ impl Collection {
         };
 
         let total_count = counts.iter().map(|x| x.count).sum::<usize>();
-        let aggregated_count = CountResult { count: total_count };
+        let aggregated_count = CountResult {
+            count: total_count,
+            exact: counts.iter().all(|x| x.exact),
+        };
         Ok(aggregated_count)
     }
 
+    pub async fn facet(
+        &self,
+        request: FacetRequest,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<FacetResponse> {
+        // Counts of a selected shard are merged with other shards by the requesting peer,
+        // so they are returned without truncation
+        let limit = match shard_selection {
+            Some(_) => usize::MAX,
+            None => request.limit.unwrap_or(DEFAULT_FACET_LIMIT),
+        };
+        if shard_selection.is_none() {
+            if let Some(strict_mode_config) = self.enabled_strict_mode_config().await {
+                strict_mode_config.check_limit_and_offset(limit, 0)?;
+                self.check_strict_mode_filters(&strict_mode_config, request.filter.as_ref(), false)
+                    .await?;
+            }
+            self.record_filter_usage(request.filter.as_ref()).await;
+        }
+        let request = Arc::new(request);
+
+        let shards_counts: Vec<_> = {
+            let shards_holder = self.shards_holder.read().await;
+            let target_shards = shards_holder.select_shards(&shard_selection.into())?;
+            let facet_futures = target_shards
+                .into_iter()
+                .map(|shard| shard.facet(request.clone()));
+            try_join_all(facet_futures).await?
+        };
+
+        let counts = merge_facet_counts(shards_counts.into_iter().map(|response| {
+            response
+                .hits
+                .into_iter()
+                .map(|hit| (hit.value, hit.count))
+                .collect()
+        }));
+        Ok(FacetResponse {
+            hits: top_facet_hits(counts, limit),
+        })
+    }
+
+    /// Explain how segments of the local shards would execute a vector search
+    pub async fn search_plan(
+        &self,
+        request: SearchPlanRequest,
+    ) -> CollectionResult<SearchPlanResponse> {
+        let mut shards = Vec::new();
+        let shards_holder = self.shards_holder.read().await;
+        for replica_set in shards_holder.all_shards() {
+            shards.extend(replica_set.search_plan(&request).await?);
+        }
+        shards.sort_by_key(|shard| shard.shard_id);
+        Ok(SearchPlanResponse { shards })
+    }
+
     pub async fn retrieve(
         &self,
         request: PointRequest,
         read_consistency: Option<ReadConsistency>,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> CollectionResult<Vec<Record>> {
+        let shard_selection = shard_selection.into();
         let with_payload_interface = request
             .with_payload
             .as_ref()
@@ -1133,8 +2125,8 @@ impl Collection {
         let request = Arc::new(request);
         let all_shard_collection_results = {
             let shard_holder = self.shards_holder.read().await;
-            let target_shards = shard_holder.target_shard(shard_selection)?;
-            let retrieve_futures = target_shards.into_iter().map(|shard| {
+            let target_shards = shard_holder.select_shards(&shard_selection)?;
+            let retrieve_futures = target_shards.iter().map(|shard| {
                 shard.retrieve(
                     request.clone(),
                     &with_payload,
@@ -1142,7 +2134,12 @@ impl Collection {
                     read_consistency,
                 )
             });
-            try_join_all(retrieve_futures).await?
+            let mut all_shard_collection_results = try_join_all(retrieve_futures).await?;
+            for (shard, shard_points) in target_shards.iter().zip(&mut all_shard_collection_results)
+            {
+                shard_holder.retain_owned_points(shard.shard_id, shard_points, |point| point.id);
+            }
+            all_shard_collection_results
         };
         let points = all_shard_collection_results.into_iter().flatten().collect();
         Ok(points)
@@ -1182,6 +2179,26 @@ impl Collection {
         Ok(())
     }
 
+    /// Updates strict mode config: Saves new limits on disk
+    ///
+    /// Only the limits specified in the diff are changed. Requests read the limits from the
+    /// config, so the new limits apply to the next requests without recreating anything.
+    pub async fn update_strict_mode_config(
+        &self,
+        strict_mode_diff: StrictModeConfig,
+    ) -> CollectionResult<()> {
+        {
+            let mut config = self.collection_config.write().await;
+            let strict_mode_config = match &config.strict_mode_config {
+                Some(strict_mode_config) => strict_mode_diff.update(strict_mode_config)?,
+                None => strict_mode_diff,
+            };
+            config.strict_mode_config = Some(strict_mode_config);
+        }
+        self.collection_config.read().await.save(&self.path)?;
+        Ok(())
+    }
+
     /// Updates quantization config:
     /// Saves new params on disk
     ///
@@ -1193,21 +2210,29 @@ impl Collection {
     ) -> CollectionResult<()> {
         {
             let mut config = self.collection_config.write().await;
+            let mut updated_config = config.clone();
             match quantization_config_diff {
                 QuantizationConfigDiff::Scalar(scalar) => {
-                    config
+                    updated_config
                         .quantization_config
                         .replace(QuantizationConfig::Scalar(scalar));
                 }
                 QuantizationConfigDiff::Product(product) => {
-                    config
+                    updated_config
                         .quantization_config
                         .replace(QuantizationConfig::Product(product));
                 }
+                QuantizationConfigDiff::Binary(binary) => {
+                    updated_config
+                        .quantization_config
+                        .replace(QuantizationConfig::Binary(binary));
+                }
                 QuantizationConfigDiff::Disabled(_) => {
-                    config.quantization_config = None;
+                    updated_config.quantization_config = None;
                 }
             }
+            updated_config.check_quantization()?;
+            *config = updated_config;
         }
         self.collection_config.read().await.save(&self.path)?;
         Ok(())
@@ -1224,10 +2249,13 @@ impl Collection {
     ) -> CollectionResult<()> {
         let mut config = self.collection_config.write().await;
         update_vectors_diff.check_vector_names(&config.params)?;
-        config
+        let mut updated_config = config.clone();
+        updated_config
             .params
             .update_vectors_from_diff(update_vectors_diff)?;
-        config.save(&self.path)?;
+        updated_config.check_quantization()?;
+        updated_config.save(&self.path)?;
+        *config = updated_config;
         Ok(())
     }
 
@@ -1293,8 +2321,10 @@ impl Collection {
     ) -> CollectionResult<()> {
         {
             let mut config = self.collection_config.write().await;
-            config.optimizer_config =
+            let optimizer_config =
                 DiffConfig::update(optimizer_config_diff, &config.optimizer_config)?;
+            optimizer_config.check_thresholds()?;
+            config.optimizer_config = optimizer_config;
         }
         self.collection_config.read().await.save(&self.path)?;
         Ok(())
@@ -1308,6 +2338,7 @@ impl Collection {
         &self,
         optimizer_config: OptimizersConfig,
     ) -> CollectionResult<()> {
+        optimizer_config.check_thresholds()?;
         {
             let mut config = self.collection_config.write().await;
             config.optimizer_config = optimizer_config;
@@ -1333,17 +2364,145 @@ impl Collection {
         Ok(())
     }
 
+    /// Running and recently finished optimization tasks of the local shards
+    pub async fn optimization_tasks(&self) -> Vec<OptimizationTaskInfo> {
+        let shard_holder = self.shards_holder.read().await;
+        let mut tasks = Vec::new();
+        for replica_set in shard_holder.all_shards() {
+            for shard_tasks in replica_set.optimization_tasks().await {
+                tasks.extend(shard_tasks.list());
+            }
+        }
+        tasks.sort_by_key(|task| task.id);
+        tasks
+    }
+
+    /// Memory and disk usage of the local shards and the snapshots of the collection.
+    ///
+    /// Sizes of the files are read once the cached usage gets older than
+    /// `resource_usage_refresh_interval`, the cached usage is returned otherwise.
+    pub async fn resource_usage(&self) -> CollectionResult<CollectionResourceUsage> {
+        let mut cached = self.resource_usage.lock().await;
+        if let Some((computed, usage)) = &*cached {
+            if computed.elapsed() < self.shared_storage_config.resource_usage_refresh_interval {
+                return Ok(usage.clone());
+            }
+        }
+
+        let mut shards = Vec::new();
+        {
+            let shard_holder = self.shards_holder.read().await;
+            for replica_set in shard_holder.all_shards() {
+                shards.extend(replica_set.resource_usage().await?);
+            }
+        }
+        let snapshots_bytes = if self.snapshots_path.exists() {
+            fs_extra::dir::get_size(&self.snapshots_path).map_err(|err| {
+                CollectionError::service_error(format!(
+                    "Error while reading snapshots size {:?} {err}",
+                    self.snapshots_path,
+                ))
+            })?
+        } else {
+            0
+        };
+
+        let usage = CollectionResourceUsage::new(shards, snapshots_bytes as usize);
+        *cached = Some((Instant::now(), usage.clone()));
+        Ok(usage)
+    }
+
+    /// Measure recall of approximate search on points, sampled from the segments of the local
+    /// shards, by comparing it with exact search.
+    ///
+    /// Tests of the collection wait for each other, the searches of a test are throttled.
+    /// The test is stopped, if the returned future is dropped.
+    pub async fn recall_test(
+        &self,
+        request: RecallTestRequest,
+    ) -> CollectionResult<RecallTestReport> {
+        let vector_name = request
+            .using
+            .clone()
+            .unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_string());
+        let vector_params = self
+            .collection_config
+            .read()
+            .await
+            .params
+            .get_vector_params(&vector_name)?;
+        if vector_params.multivector_config.is_some() {
+            return Err(CollectionError::bad_input(format!(
+                "Recall test of multivector `{vector_name}` is not supported"
+            )));
+        }
+
+        let _running = self.recall_test_lock.lock().await;
+        let mut segments = Vec::new();
+        {
+            let shard_holder = self.shards_holder.read().await;
+            for (shard_id, replica_set) in shard_holder.get_shards() {
+                for (segment_id, segment) in replica_set.local_segments().await {
+                    segments.push(RecallTestSegment {
+                        shard_id: *shard_id,
+                        segment_id,
+                        segment,
+                    });
+                }
+            }
+        }
+
+        let stopping_guard = StoppingGuard::new();
+        run_recall_test(
+            segments,
+            &request,
+            &vector_name,
+            &self.search_runtime,
+            stopping_guard.get_is_stopped(),
+        )
+        .await
+    }
+
+    /// Stop the running optimization task of a local shard.
+    ///
+    /// Optimized segments are kept in their previous form, and are optimized again by the
+    /// following optimizations.
+    pub async fn cancel_optimization_task(&self, id: OptimizationTaskId) -> CollectionResult<()> {
+        let shard_holder = self.shards_holder.read().await;
+        for replica_set in shard_holder.all_shards() {
+            for shard_tasks in replica_set.optimization_tasks().await {
+                if let Some(task) = shard_tasks.get(id) {
+                    return task.cancel();
+                }
+            }
+        }
+        Err(CollectionError::NotFound {
+            what: format!("Optimization task {id}"),
+        })
+    }
+
+    /// Stop updates and optimizations of the local shards and persist the applied operations,
+    /// before the node shuts down
+    pub async fn shutdown(&self) -> CollectionResult<()> {
+        let shard_holder = self.shards_holder.read().await;
+        let shutdowns = shard_holder
+            .all_shards()
+            .map(|replica_set| replica_set.shutdown());
+        try_join_all(shutdowns).await?;
+        Ok(())
+    }
+
     pub async fn info(&self, shard_selection: Option<ShardId>) -> CollectionResult<CollectionInfo> {
         let (all_shard_collection_results, mut info) = {
             let shards_holder = self.shards_holder.read().await;
 
-            let target_shards = shards_holder.target_shard(shard_selection)?;
+            let target_shards = shards_holder.select_shards(&shard_selection.into())?;
 
-            let first_shard = *target_shards.first().ok_or_else(|| {
-                CollectionError::service_error(
-                    "There are no shards for selected collection".to_string(),
-                )
-            })?;
+            let Some(&first_shard) = target_shards.first() else {
+                // Collection with custom sharding has no shards until a shard key is created
+                let collection_config = self.collection_config.read().await.clone();
+                return Ok(CollectionInfo::empty(collection_config));
+            };
 
             let info = first_shard.info().await?;
             let info_futures = target_shards.into_iter().skip(1).map(|shard| shard.info());
@@ -1396,10 +2555,12 @@ impl Collection {
                     .unwrap_or(ReplicaState::Dead);
                 let count_result = replica_set.count_local(count_request.clone()).await?;
                 let points_count = count_result.map(|x| x.count).unwrap_or(0);
+                let wal_recovery = replica_set.wal_recovery_report().await;
                 local_shards.push(LocalShardInfo {
                     shard_id,
                     points_count,
                     state,
+                    wal_recovery,
                 })
             }
             for (peer_id, state) in replica_set.peers().into_iter() {
@@ -1414,6 +2575,14 @@ impl Collection {
             }
         }
         let shard_transfers = shards_holder.get_shard_transfer_info();
+        let resharding = shards_holder
+            .resharding_state()
+            .map(|state| self.resharding_info(state));
+
+        drop(shards_holder);
+
+        let state = self.state().await;
+        let replica_recoveries = self.replica_failover.lock().recoveries(&state);
 
         // sort by shard_id
         local_shards.sort_by_key(|k| k.shard_id);
@@ -1425,6 +2594,8 @@ impl Collection {
             local_shards,
             remote_shards,
             shard_transfers,
+            resharding,
+            replica_recoveries,
         };
         Ok(info)
     }
@@ -1444,9 +2615,26 @@ impl Collection {
                 })
                 .collect(),
             transfers,
+            shards_key_mapping: shards_holder.shard_key_mapping.read().clone(),
+            resharding: shards_holder.resharding_state(),
+            shard_splits: shards_holder.shard_splits.read().clone(),
         }
     }
 
+    /// Plan replacement of the dead replicas of this collection.
+    ///
+    /// Should only be called on the consensus leader, which proposes the planned operations.
+    pub fn plan_replica_failover(
+        &self,
+        state: &State,
+        config: &ReplicaFailoverConfig,
+        load: &mut ClusterLoad,
+    ) -> ReplicaFailoverPlan {
+        self.replica_failover
+            .lock()
+            .plan(state, config, load, Instant::now())
+    }
+
     pub async fn apply_state(
         &self,
         state: State,
@@ -1466,12 +2654,26 @@ impl Collection {
             (shards_telemetry, shards_holder.get_shard_transfer_info())
         };
 
+        let max_updates_per_second = self
+            .enabled_strict_mode_config()
+            .await
+            .and_then(|strict_mode_config| strict_mode_config.max_updates_per_second);
+
         CollectionTelemetry {
             id: self.name(),
             init_time_ms: self.init_time.as_millis() as u64,
             config: self.collection_config.read().await.clone(),
             shards: shards_telemetry,
             transfers,
+            searches: self.telemetry_search_durations.lock().get_statistics(),
+            updates: self.telemetry_update_durations.lock().get_statistics(),
+            search_stats: *self.telemetry_search_stats.lock(),
+            index_suggestions: self.index_suggestions().await.unwrap_or_default(),
+            rate_limits: rate_limits_telemetry(
+                &self.search_limiter,
+                &self.update_limiter,
+                max_updates_per_second,
+            ),
         }
     }
 
@@ -1510,10 +2712,15 @@ impl Collection {
         Ok(snapshot_path)
     }
 
+    /// Create a snapshot of the whole collection.
+    ///
+    /// Shards without a local replica are downloaded from an active replica on another peer.
+    /// `aliases` of the collection are saved in the snapshot too, to be restored with it.
     pub async fn create_snapshot(
         &self,
         temp_dir: &Path,
         this_peer_id: PeerId,
+        aliases: &[String],
     ) -> CollectionResult<SnapshotDescription> {
         let snapshot_name = format!(
             "{}-{}-{}.snapshot",
@@ -1538,18 +2745,38 @@ impl Collection {
             let shards_holder = self.shards_holder.read().await;
             // Create snapshot of each shard
             for (shard_id, replica_set) in shards_holder.get_shards() {
+                // The new shard of the resharding in progress is not complete yet
+                if shards_holder.is_resharding_target(shard_id) {
+                    continue;
+                }
                 let shard_snapshot_path =
                     versioned_shard_path(&snapshot_path_with_tmp_extension, *shard_id, 0);
                 create_dir_all(&shard_snapshot_path).await?;
+                if !replica_set.has_local_shard().await {
+                    if replica_set
+                        .download_snapshot(temp_dir, &shard_snapshot_path)
+                        .await?
+                    {
+                        continue;
+                    }
+                    log::warn!(
+                        "Shard {shard_id} of collection {} has no active replica, \
+                         snapshot contains no data of this shard",
+                        self.name(),
+                    );
+                }
                 // If node is listener, we can save whatever currently is in the storage
                 let save_wal = self.shared_storage_config.node_type != NodeType::Listener;
                 replica_set
                     .create_snapshot(temp_dir, &shard_snapshot_path, save_wal)
                     .await?;
             }
+            shards_holder.save_shard_key_mapping_to(&snapshot_path_with_tmp_extension)?;
+            shards_holder.save_shard_splits_to(&snapshot_path_with_tmp_extension)?;
         }
 
         CollectionVersion::save(&snapshot_path_with_tmp_extension)?;
+        save_snapshot_aliases(&snapshot_path_with_tmp_extension, aliases)?;
         self.collection_config
             .read()
             .await
@@ -1579,6 +2806,7 @@ impl Collection {
         copy(&snapshot_path_with_arc_extension, &snapshot_path_tmp).await?;
         rename(&snapshot_path_tmp, &snapshot_path).await?;
         remove_file(snapshot_path_with_arc_extension).await?;
+        save_snapshot_checksum(&snapshot_path).await?;
 
         log::info!(
             "Collection snapshot {} completed into {:?}",
@@ -1606,6 +2834,40 @@ impl Collection {
             .await
     }
 
+    /// Check that the collection in the unpacked snapshot can be loaded by this version
+    fn check_snapshot_version(snapshot_dir: &Path) -> CollectionResult<()> {
+        if !CollectionVersion::check_exists(snapshot_dir) {
+            return Err(CollectionError::bad_input(
+                "Snapshot has no collection version, it is not a collection snapshot".to_string(),
+            ));
+        }
+        let stored_version = CollectionVersion::load(snapshot_dir)?;
+        let stored_version: Version = stored_version.trim().parse().map_err(|err| {
+            CollectionError::bad_input(format!(
+                "Snapshot has invalid collection version {stored_version}: {err}"
+            ))
+        })?;
+        let app_version: Version = CollectionVersion::current()
+            .parse()
+            .expect("Failed to parse current collection version as semver");
+
+        if stored_version > app_version {
+            return Err(CollectionError::bad_input(format!(
+                "Snapshot of version {stored_version} is created by a newer version of Qdrant, \
+                 it can't be restored on version {app_version}"
+            )));
+        }
+        if stored_version != app_version
+            && !Self::can_upgrade_storage(&stored_version, &app_version)
+        {
+            return Err(CollectionError::bad_input(format!(
+                "Snapshot of version {stored_version} can't be upgraded to version {app_version}, \
+                 restore it on an older version of Qdrant first"
+            )));
+        }
+        Ok(())
+    }
+
     /// Restore collection from snapshot
     ///
     /// This method performs blocking IO.
@@ -1620,11 +2882,24 @@ impl Collection {
         let mut ar = tar::Archive::new(archive_file);
         ar.unpack(target_dir)?;
 
+        Self::check_snapshot_version(target_dir)?;
+
         let config = CollectionConfig::load(target_dir)?;
         config.validate_and_warn();
-        let configured_shards = config.params.shard_number.get();
+        let shard_ids: Vec<ShardId> = match config.params.sharding_method.unwrap_or_default() {
+            ShardingMethod::Auto => (0..config.params.shard_number.get())
+                .chain(
+                    ShardHolder::load_shard_splits(target_dir)?
+                        .into_iter()
+                        .map(|split| split.target_shard_id),
+                )
+                .collect(),
+            ShardingMethod::Custom => ShardHolder::load_shard_key_mapping(target_dir)?
+                .into_keys()
+                .collect(),
+        };
 
-        for shard_id in 0..configured_shards {
+        for shard_id in shard_ids {
             let shard_path = versioned_shard_path(target_dir, shard_id, 0);
             let shard_config_opt = ShardConfig::load(&shard_path)?;
             if let Some(shard_config) = shard_config_opt {
@@ -1662,13 +2937,441 @@ impl Collection {
         Ok(())
     }
 
+    /// Create shards of a new shard key, one shard for each entry of the placement
+    ///
+    /// Shard ids are assigned in the order of operations, so they are the same on all peers.
+    /// Returns ids of the new shards, which are local to this peer.
+    pub async fn create_shard_key(
+        &self,
+        shard_key: ShardKey,
+        placement: ShardsPlacement,
+    ) -> CollectionResult<Vec<ShardId>> {
+        let mut shard_holder = self.shards_holder.write().await;
+        if shard_holder.sharding_method() != ShardingMethod::Custom {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {shard_key} can not be created, \
+                 collection {} is not created with custom sharding method",
+                self.name(),
+            )));
+        }
+        if shard_holder.contains_shard_key(&shard_key) {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {shard_key} already exists"
+            )));
+        }
+        if placement.is_empty() || placement.iter().any(|peers| peers.is_empty()) {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {shard_key} requires at least one shard with at least one replica"
+            )));
+        }
+
+        let first_shard_id = shard_holder
+            .get_shards()
+            .map(|(shard_id, _)| *shard_id + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut local_shards = Vec::new();
+        for (shard_id, peers) in (first_shard_id..).zip(placement) {
+            let is_local = self
+                .add_keyed_shard(&mut shard_holder, shard_id, shard_key.clone(), peers)
+                .await?;
+            if is_local {
+                local_shards.push(shard_id);
+            }
+        }
+        Ok(local_shards)
+    }
+
+    /// Build a replica set of the shard key and add it to the shard holder
+    ///
+    /// Returns `true` if a replica of the shard is on this peer.
+    pub(crate) async fn add_keyed_shard(
+        &self,
+        shard_holder: &mut ShardHolder,
+        shard_id: ShardId,
+        shard_key: ShardKey,
+        peers: impl IntoIterator<Item = PeerId>,
+    ) -> CollectionResult<bool> {
+        let mut peers: HashSet<PeerId> = peers.into_iter().collect();
+        let is_local = peers.remove(&self.this_peer_id);
+
+        let replica_set = ReplicaSetShard::build(
+            shard_id,
+            self.name(),
+            self.this_peer_id,
+            is_local,
+            peers,
+            self.notify_peer_failure_cb.clone(),
+            &self.path,
+            self.collection_config.clone(),
+            self.shared_storage_config.clone(),
+            self.channel_service.clone(),
+            self.update_runtime.clone(),
+            self.search_runtime.clone(),
+        )
+        .await?;
+
+        shard_holder.add_shard(shard_id, replica_set, Some(shard_key))?;
+        Ok(is_local)
+    }
+
+    /// Remove all shards of the shard key, together with their data
+    pub async fn drop_shard_key(&self, shard_key: ShardKey) -> CollectionResult<()> {
+        let shard_ids = {
+            let shard_holder = self.shards_holder.read().await;
+            let shard_ids = shard_holder.shard_ids_by_key(&shard_key)?;
+
+            let related_transfers: Vec<_> = shard_holder
+                .shard_transfers
+                .read()
+                .iter()
+                .filter(|transfer| shard_ids.contains(&transfer.shard_id))
+                .map(ShardTransfer::key)
+                .collect();
+            for transfer_key in related_transfers {
+                self._abort_shard_transfer(transfer_key, &shard_holder)
+                    .await?;
+            }
+            shard_ids
+        };
+
+        let mut shard_holder = self.shards_holder.write().await;
+        for shard_id in shard_ids {
+            let Some(replica_set) = shard_holder.remove_shard(shard_id)? else {
+                continue;
+            };
+            replica_set.remove_local().await?;
+            let shard_path = replica_set.shard_path.clone();
+            drop(replica_set);
+            if shard_path.exists() {
+                remove_dir_all(&shard_path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start splitting a shard, as agreed by consensus
+    ///
+    /// The new shard is created on all peers, with a single replica on the peer, which drives
+    /// the resharding. This peer copies points of the split from its replica of the source
+    /// shard into the new shard, while updates of these points are applied to both shards.
+    pub async fn start_resharding<T, F>(
+        &self,
+        state: ReshardingState,
+        on_finish: T,
+        on_error: F,
+    ) -> CollectionResult<()>
+    where
+        T: Future<Output = ()> + Send + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let ReshardingState { split, peer_id } = state;
+        let mut shard_holder = self.shards_holder.write().await;
+        shard_holder.check_start_resharding(&state)?;
+
+        let is_driver = peer_id == self.this_peer_id;
+        let remotes = if is_driver {
+            HashSet::new()
+        } else {
+            HashSet::from([peer_id])
+        };
+        let target_shard = ReplicaSetShard::build(
+            split.target_shard_id,
+            self.name(),
+            self.this_peer_id,
+            is_driver,
+            remotes,
+            self.notify_peer_failure_cb.clone(),
+            &self.path,
+            self.collection_config.clone(),
+            self.shared_storage_config.clone(),
+            self.channel_service.clone(),
+            self.update_runtime.clone(),
+            self.search_runtime.clone(),
+        )
+        .await?;
+        // New shard does not serve requests, until all points of the split are copied
+        target_shard.set_replica_state(&peer_id, ReplicaState::Partial)?;
+        shard_holder.start_resharding(state, target_shard)?;
+
+        if !is_driver {
+            return Ok(());
+        }
+
+        self.split_and_migrate(&shard_holder, split, on_finish, on_error)
+            .await
+    }
+
+    /// Resume the resharding driven by this peer, e.g. after restart
+    ///
+    /// The resharding state and the new shard are persisted, but points are copied
+    /// by a task, which does not survive the restart. Copying starts over from the first point,
+    /// points copied before the restart are overwritten.
+    /// The resharding is not resumed if the local replica of the split shard is not active,
+    /// as it may miss updates. It is reported as failed by the local state sync then.
+    pub async fn resume_resharding<T, F>(&self, on_finish: T, on_error: F) -> CollectionResult<()>
+    where
+        T: Future<Output = ()> + Send + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let shard_holder = self.shards_holder.read().await;
+        let Some(ReshardingState { split, peer_id }) = shard_holder.resharding_state() else {
+            return Ok(());
+        };
+        if peer_id != self.this_peer_id || self.resharding_task.lock().await.is_some() {
+            return Ok(());
+        }
+        let source_state = shard_holder
+            .get_shard(&split.source_shard_id)
+            .and_then(|replica_set| replica_set.peer_state(&self.this_peer_id));
+        if source_state != Some(ReplicaState::Active) {
+            log::warn!(
+                "Can't resume splitting shard {} into shard {} of collection {}, local replica is {source_state:?}",
+                split.source_shard_id,
+                split.target_shard_id,
+                self.id,
+            );
+            return Ok(());
+        }
+
+        log::info!(
+            "Resuming splitting shard {} into shard {} of collection {}",
+            split.source_shard_id,
+            split.target_shard_id,
+            self.id,
+        );
+        self.split_and_migrate(&shard_holder, split, on_finish, on_error)
+            .await
+    }
+
+    /// Wrap the local replica of the split shard into a split proxy and start copying points
+    /// of the split into the new shard
+    async fn split_and_migrate<T, F>(
+        &self,
+        shard_holder: &ShardHolder,
+        split: ShardSplit,
+        on_finish: T,
+        on_error: F,
+    ) -> CollectionResult<()>
+    where
+        T: Future<Output = ()> + Send + 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let ring = shard_holder.resharding_router(split);
+        let (Some(source_shard), Some(target_shard)) = (
+            shard_holder.get_shard(&split.source_shard_id),
+            shard_holder.get_shard(&split.target_shard_id),
+        ) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {} or shard {} doesn't exist",
+                split.source_shard_id, split.target_shard_id,
+            )));
+        };
+        source_shard.split_local(target_shard, ring).await?;
+
+        self.resharding_progress.store(0, Ordering::Relaxed);
+        let resharding_task = spawn_resharding_task(
+            self.shards_holder.clone(),
+            split,
+            self.resharding_progress.clone(),
+            on_finish,
+            on_error,
+        );
+        if let Some(old_task) = self.resharding_task.lock().await.replace(resharding_task) {
+            old_task.ask_to_stop();
+        }
+        Ok(())
+    }
+
+    /// Finish the resharding, as agreed by consensus
+    ///
+    /// Points of the split are routed into the new shard from now on.
+    /// They are deleted from the local replica of the split shard in background,
+    /// until then they are excluded from the results of the split shard.
+    pub async fn finish_resharding(&self, split: ShardSplit) -> CollectionResult<()> {
+        let mut shard_holder = self.shards_holder.write().await;
+        let state = Self::check_resharding(&shard_holder, split)?;
+
+        if let Some(resharding_task) = self.resharding_task.lock().await.take() {
+            // Don't wait for the task, it may be the one finishing the resharding
+            resharding_task.ask_to_stop();
+        }
+
+        let (Some(source_shard), Some(target_shard)) = (
+            shard_holder.get_shard(&split.source_shard_id),
+            shard_holder.get_shard(&split.target_shard_id),
+        ) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {} or shard {} doesn't exist",
+                split.source_shard_id, split.target_shard_id,
+            )));
+        };
+        if state.peer_id == self.this_peer_id {
+            source_shard
+                .un_split_local(target_shard, Some(ReplicaState::Active))
+                .await?;
+        } else {
+            target_shard.set_replica_state(&state.peer_id, ReplicaState::Active)?;
+        }
+
+        shard_holder.commit_resharding(split)?;
+        drop(shard_holder);
+
+        self.spawn_drop_moved_points(split);
+        Ok(())
+    }
+
+    /// Abort the resharding, as agreed by consensus
+    ///
+    /// The new shard is removed with all its points, the split shard is left untouched.
+    pub async fn abort_resharding(&self, split: ShardSplit) -> CollectionResult<()> {
+        let mut shard_holder = self.shards_holder.write().await;
+        let state = Self::check_resharding(&shard_holder, split)?;
+
+        if let Some(resharding_task) = self.resharding_task.lock().await.take() {
+            // Don't wait for the task, it may be the one aborting the resharding
+            resharding_task.ask_to_stop();
+        }
+
+        if state.peer_id == self.this_peer_id {
+            if let (Some(source_shard), Some(target_shard)) = (
+                shard_holder.get_shard(&split.source_shard_id),
+                shard_holder.get_shard(&split.target_shard_id),
+            ) {
+                source_shard.un_split_local(target_shard, None).await?;
+            }
+        }
+
+        let target_shard = shard_holder.abort_resharding(split)?;
+        drop(shard_holder);
+
+        if let Some(replica_set) = target_shard {
+            replica_set.remove_local().await?;
+            let shard_path = replica_set.shard_path.clone();
+            drop(replica_set);
+            if shard_path.exists() {
+                remove_dir_all(&shard_path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply splits and resharding in progress, e.g. from a consensus snapshot
+    ///
+    /// New shards are created without replicas, replicas are added by the following
+    /// update of the shard info.
+    pub(crate) async fn apply_resharding(
+        &self,
+        shard_splits: Vec<ShardSplit>,
+        resharding: Option<ReshardingState>,
+    ) -> CollectionResult<()> {
+        let mut shard_holder = self.shards_holder.write().await;
+        let new_splits: Vec<_> = {
+            let old_splits = shard_holder.shard_splits.read();
+            shard_splits
+                .iter()
+                .filter(|split| !old_splits.contains(split))
+                .copied()
+                .collect()
+        };
+        let target_shard_ids: Vec<_> = shard_splits
+            .iter()
+            .map(|split| split.target_shard_id)
+            .chain(resharding.map(|state| state.split.target_shard_id))
+            .collect();
+        shard_holder.set_resharding(shard_splits, resharding)?;
+
+        for shard_id in target_shard_ids {
+            if shard_holder.contains_shard(&shard_id) {
+                continue;
+            }
+            let replica_set = ReplicaSetShard::build(
+                shard_id,
+                self.name(),
+                self.this_peer_id,
+                false,
+                HashSet::new(),
+                self.notify_peer_failure_cb.clone(),
+                &self.path,
+                self.collection_config.clone(),
+                self.shared_storage_config.clone(),
+                self.channel_service.clone(),
+                self.update_runtime.clone(),
+                self.search_runtime.clone(),
+            )
+            .await?;
+            shard_holder.add_shard(shard_id, replica_set, None)?;
+        }
+        drop(shard_holder);
+
+        for split in new_splits {
+            self.spawn_drop_moved_points(split);
+        }
+        Ok(())
+    }
+
+    /// Delete points moved by the split from the local replica of the split shard in background
+    fn spawn_drop_moved_points(&self, split: ShardSplit) {
+        let shard_holder = self.shards_holder.clone();
+        tokio::spawn(async move {
+            if let Err(err) = drop_moved_points(shard_holder, split.source_shard_id).await {
+                log::error!(
+                    "Failed to delete points moved from shard {} into shard {}: {err}",
+                    split.source_shard_id,
+                    split.target_shard_id,
+                );
+            }
+        });
+    }
+
+    pub async fn resharding_state(&self) -> Option<ReshardingState> {
+        self.shards_holder.read().await.resharding_state()
+    }
+
+    /// Check if the resharding can be started, before proposing it to consensus
+    pub async fn check_start_resharding(&self, state: &ReshardingState) -> CollectionResult<()> {
+        self.shards_holder
+            .read()
+            .await
+            .check_start_resharding(state)
+    }
+
+    fn check_resharding(
+        shard_holder: &ShardHolder,
+        split: ShardSplit,
+    ) -> CollectionResult<ReshardingState> {
+        shard_holder
+            .resharding_state()
+            .filter(|state| state.split == split)
+            .ok_or_else(|| {
+                CollectionError::bad_request(format!(
+                    "Shard {} is not being split into shard {}",
+                    split.source_shard_id, split.target_shard_id,
+                ))
+            })
+    }
+
+    fn resharding_info(&self, state: ReshardingState) -> ReshardingInfo {
+        let migrated_points = (state.peer_id == self.this_peer_id)
+            .then(|| self.resharding_progress.load(Ordering::Relaxed));
+        ReshardingInfo {
+            shard_id: state.split.source_shard_id,
+            target_shard_id: state.split.target_shard_id,
+            peer_id: state.peer_id,
+            migrated_points,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn sync_local_state(
         &self,
         on_transfer_failure: OnTransferFailure,
         on_transfer_success: OnTransferSuccess,
+        on_resharding_failure: OnReshardingFailure,
+        on_resharding_success: OnReshardingSuccess,
         on_finish_init: ChangePeerState,
         on_convert_to_listener: ChangePeerState,
-        on_convert_from_listener: ChangePeerState,
     ) -> CollectionResult<()> {
         // Check for disabled replicas
         let shard_holder = self.shards_holder.read().await;
@@ -1707,6 +3410,34 @@ impl Collection {
             }
         }
 
+        // Check for un-reported finished resharding, e.g. it could not be resumed after restart
+        if let Some(state) = shard_holder.resharding_state() {
+            if state.peer_id == self.this_peer_id {
+                let resharding_task = self.resharding_task.lock().await;
+                match resharding_task.as_ref() {
+                    Some(task) if !task.is_finished() => {}
+                    Some(task) if task.get_result() == Some(true) => {
+                        log::debug!(
+                            "Resharding {:?} is finished successfully, but not reported. Reporting now.",
+                            state.split
+                        );
+                        on_resharding_success(state.split, self.name());
+                    }
+                    _ => {
+                        log::debug!(
+                            "Resharding {:?} is not running, but not reported as failed. Reporting now.",
+                            state.split
+                        );
+                        on_resharding_failure(
+                            state.split,
+                            self.name(),
+                            "resharding is not running",
+                        );
+                    }
+                }
+            }
+        }
+
         // Check for proper replica states
         for replica_set in shard_holder.all_shards() {
             let this_peer_id = &replica_set.this_peer_id();
@@ -1723,15 +3454,14 @@ impl Collection {
                 continue;
             }
 
-            if self.shared_storage_config.node_type == NodeType::Listener {
-                if this_peer_state == Some(Active) && !is_last_active {
-                    // Convert active node from active to listener
-                    on_convert_to_listener(*this_peer_id, shard_id);
-                    continue;
-                }
-            } else if this_peer_state == Some(Listener) {
-                // Convert listener node to active
-                on_convert_from_listener(*this_peer_id, shard_id);
+            // Replicas of listener nodes are listeners.
+            // On other nodes, replicas become listeners and back only by explicit operations.
+            if self.shared_storage_config.node_type == NodeType::Listener
+                && this_peer_state == Some(Active)
+                && !is_last_active
+            {
+                // Convert active node from active to listener
+                on_convert_to_listener(*this_peer_id, shard_id);
                 continue;
             }
 
@@ -1749,6 +3479,7 @@ impl Collection {
                     to: *this_peer_id,
                     shard_id,
                     sync: true,
+                    method: None,
                 };
                 if check_transfer_conflicts_strict(&transfer, transfers.iter()).is_some() {
                     continue; // this transfer won't work
@@ -212,6 +212,8 @@ mod tests {
                 payload,
                 points: Some(points.clone()),
                 filter: None,
+                if_version: None,
+                key: None,
             }),
         )
         .unwrap();
@@ -240,6 +242,7 @@ mod tests {
                 points: Some(vec![3.into()]),
                 keys: vec!["color".to_string(), "empty".to_string()],
                 filter: None,
+                if_version: None,
             }),
         )
         .unwrap();
@@ -203,6 +203,7 @@ pub(crate) fn get_merge_optimizer(
             max_segment_size: 100_000,
             memmap_threshold: 1000000,
             indexing_threshold: 1000000,
+            merge_buffer_size: 1024,
         },
         segment_path.to_owned(),
         collection_temp_dir.to_owned(),
@@ -213,11 +214,18 @@ pub(crate) fn get_merge_optimizer(
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
+                multivector_config: None,
             }),
             shard_number: NonZeroU32::new(1).unwrap(),
+            sharding_method: None,
             on_disk_payload: false,
             replication_factor: NonZeroU32::new(1).unwrap(),
             write_consistency_factor: NonZeroU32::new(1).unwrap(),
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         },
         Default::default(),
         Default::default(),
@@ -234,6 +242,7 @@ pub(crate) fn get_indexing_optimizer(
             max_segment_size: 100_000,
             memmap_threshold: 100,
             indexing_threshold: 100,
+            merge_buffer_size: 1024,
         },
         segment_path.to_owned(),
         collection_temp_dir.to_owned(),
@@ -244,11 +253,18 @@ pub(crate) fn get_indexing_optimizer(
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
+                multivector_config: None,
             }),
             shard_number: NonZeroU32::new(1).unwrap(),
+            sharding_method: None,
             on_disk_payload: false,
             replication_factor: NonZeroU32::new(1).unwrap(),
             write_consistency_factor: NonZeroU32::new(1).unwrap(),
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         },
         Default::default(),
         Default::default(),
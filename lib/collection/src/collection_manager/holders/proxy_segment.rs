@@ -1,19 +1,24 @@
 use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
+use segment::data_types::facets::{merge_facet_counts, FacetParams, FacetValue};
 use segment::data_types::named_vectors::NamedVectors;
-use segment::data_types::vectors::VectorElementType;
+use segment::data_types::order_by::{OrderBy, OrderValue};
+use segment::data_types::search_plan::SegmentSearchPlan;
+use segment::data_types::vectors::{QueryVector, VectorElementType};
 use segment::entry::entry_point::{OperationResult, SegmentEntry, SegmentFailedState};
+use segment::id_tracker::PointVersions;
 use segment::index::field_index::CardinalityEstimation;
+use segment::index::hnsw_index::build_progress::BuildProgress;
 use segment::telemetry::SegmentTelemetry;
 use segment::types::{
-    Condition, Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType, WithPayload,
-    WithVector,
+    Condition, Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef,
+    PayloadSelector, PointIdType, ScoreType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo,
+    SegmentResourceUsage, SegmentType, SeqNumberType, WithPayload, WithVector,
 };
 
 use crate::collection_manager::holders::segment_holder::LockedSegment;
@@ -35,6 +40,8 @@ pub struct ProxySegment {
     deleted_indexes: LockedFieldsSet,
     created_indexes: LockedFieldsMap,
     last_flushed_version: Arc<RwLock<Option<SeqNumberType>>>,
+    /// Progress of building the optimized segment, reported in telemetry
+    build_progress: Option<Arc<BuildProgress>>,
 }
 
 impl ProxySegment {
@@ -52,9 +59,14 @@ impl ProxySegment {
             created_indexes,
             deleted_indexes,
             last_flushed_version: Arc::new(RwLock::new(None)),
+            build_progress: None,
         }
     }
 
+    pub fn set_build_progress(&mut self, build_progress: Arc<BuildProgress>) {
+        self.build_progress = Some(build_progress);
+    }
+
     /// Ensure that write segment have same indexes as wrapped segment
     pub fn replicate_field_indexes(&mut self, op_num: SeqNumberType) -> OperationResult<()> {
         let existing_indexes = self.write_segment.get().read().get_indexed_fields();
@@ -110,6 +122,10 @@ impl ProxySegment {
             wrapped_segment_guard.all_vectors(point_id)?,
             wrapped_segment_guard.payload(point_id)?,
         );
+        // Moved point keeps versions of its components, only the operation itself raises
+        // the version of the component it changes
+        let versions = wrapped_segment_guard.point_versions(point_id);
+        let move_version = versions.map_or(op_num, |versions| max(versions.point(), op_num));
 
         {
             let mut deleted_points_write = RwLockUpgradableReadGuard::upgrade(deleted_points_guard);
@@ -119,8 +135,11 @@ impl ProxySegment {
         let segment_arc = self.write_segment.get();
         let mut write_segment = segment_arc.write();
 
-        write_segment.upsert_point(op_num, point_id, all_vectors)?;
-        write_segment.set_full_payload(op_num, point_id, &payload)?;
+        write_segment.upsert_point(move_version, point_id, all_vectors)?;
+        write_segment.set_full_payload(move_version, point_id, &payload)?;
+        if let Some(versions) = versions {
+            write_segment.set_point_versions(point_id, versions)?;
+        }
 
         Ok(true)
     }
@@ -168,6 +187,26 @@ impl SegmentEntry for ProxySegment {
             .or_else(|| self.wrapped_segment.get().read().point_version(point_id))
     }
 
+    fn point_versions(&self, point_id: PointIdType) -> Option<PointVersions> {
+        self.write_segment
+            .get()
+            .read()
+            .point_versions(point_id)
+            .or_else(|| self.wrapped_segment.get().read().point_versions(point_id))
+    }
+
+    fn set_point_versions(
+        &mut self,
+        point_id: PointIdType,
+        versions: PointVersions,
+    ) -> OperationResult<()> {
+        self.move_if_exists(versions.point(), point_id)?;
+        self.write_segment
+            .get()
+            .write()
+            .set_point_versions(point_id, versions)
+    }
+
     fn search(
         &self,
         vector_name: &str,
@@ -233,11 +272,12 @@ impl SegmentEntry for ProxySegment {
     fn search_batch(
         &self,
         vector_name: &str,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         with_payload: &WithPayload,
         with_vector: &WithVector,
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
     ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
@@ -256,33 +296,36 @@ impl SegmentEntry for ProxySegment {
 
             self.wrapped_segment.get().read().search_batch(
                 vector_name,
-                vectors,
+                query_vectors,
                 with_payload,
                 with_vector,
                 Some(&wrapped_filter),
                 top,
+                score_threshold,
                 params,
                 is_stopped,
             )?
         } else {
             self.wrapped_segment.get().read().search_batch(
                 vector_name,
-                vectors,
+                query_vectors,
                 with_payload,
                 with_vector,
                 filter,
                 top,
+                score_threshold,
                 params,
                 is_stopped,
             )?
         };
         let mut write_results = self.write_segment.get().read().search_batch(
             vector_name,
-            vectors,
+            query_vectors,
             with_payload,
             with_vector,
             filter,
             top,
+            score_threshold,
             params,
             is_stopped,
         )?;
@@ -292,6 +335,37 @@ impl SegmentEntry for ProxySegment {
         Ok(wrapped_results)
     }
 
+    fn search_plan(
+        &self,
+        vector_name: &str,
+        filter: Option<&Filter>,
+        params: Option<&SearchParams>,
+    ) -> OperationResult<Vec<SegmentSearchPlan>> {
+        let deleted_points = self.deleted_points.read();
+        let mut plans = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .search_plan(vector_name, filter, params)?
+        } else {
+            // Same filter as in `search_batch`, which excludes points deleted from the proxy
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment.get().read().search_plan(
+                vector_name,
+                Some(&wrapped_filter),
+                params,
+            )?
+        };
+        plans.extend(
+            self.write_segment
+                .get()
+                .read()
+                .search_plan(vector_name, filter, params)?,
+        );
+        Ok(plans)
+    }
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -375,6 +449,20 @@ impl SegmentEntry for ProxySegment {
             .set_payload(op_num, point_id, payload)
     }
 
+    fn set_payload_by_key(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        payload: &Payload,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        self.move_if_exists(op_num, point_id)?;
+        self.write_segment
+            .get()
+            .write()
+            .set_payload_by_key(op_num, point_id, payload, key)
+    }
+
     fn delete_payload(
         &mut self,
         op_num: SeqNumberType,
@@ -457,6 +545,31 @@ impl SegmentEntry for ProxySegment {
         };
     }
 
+    fn payload_selected(
+        &self,
+        point_id: PointIdType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        return if self.deleted_points.read().contains(&point_id) {
+            self.write_segment
+                .get()
+                .read()
+                .payload_selected(point_id, selector)
+        } else {
+            {
+                let write_segment = self.write_segment.get();
+                let segment_guard = write_segment.read();
+                if segment_guard.has_point(point_id) {
+                    return segment_guard.payload_selected(point_id, selector);
+                }
+            }
+            self.wrapped_segment
+                .get()
+                .read()
+                .payload_selected(point_id, selector)
+        };
+    }
+
     /// Not implemented for proxy
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_> {
         // iter_points is not available for Proxy implementation
@@ -494,6 +607,90 @@ impl SegmentEntry for ProxySegment {
         read_points
     }
 
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        offset: Option<PointIdType>,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>> {
+        let deleted_points = self.deleted_points.read();
+        let mut read_points = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .read_ordered_filtered(offset, limit, filter, order_by)?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment.get().read().read_ordered_filtered(
+                offset,
+                limit,
+                Some(&wrapped_filter),
+                order_by,
+            )?
+        };
+        let mut write_segment_points = self
+            .write_segment
+            .get()
+            .read()
+            .read_ordered_filtered(offset, limit, filter, order_by)?;
+        read_points.append(&mut write_segment_points);
+        let direction = order_by.direction();
+        read_points.sort_unstable_by(|a, b| direction.compare(a, b));
+        Ok(read_points)
+    }
+
+    fn approximate_facet(
+        &self,
+        request: &FacetParams,
+    ) -> OperationResult<HashMap<FacetValue, usize>> {
+        let deleted_points = self.deleted_points.read();
+        let wrapped_counts = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .approximate_facet(request)?
+        } else {
+            let wrapped_request = FacetParams {
+                filter: Some(self.add_deleted_points_condition_to_filter(
+                    request.filter.as_ref(),
+                    &deleted_points,
+                )),
+                ..request.clone()
+            };
+            self.wrapped_segment
+                .get()
+                .read()
+                .approximate_facet(&wrapped_request)?
+        };
+        let write_segment_counts = self.write_segment.get().read().approximate_facet(request)?;
+        Ok(merge_facet_counts([wrapped_counts, write_segment_counts]))
+    }
+
+    fn facet_values(
+        &self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&Filter>,
+    ) -> OperationResult<BTreeSet<FacetValue>> {
+        let deleted_points = self.deleted_points.read();
+        let mut values = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .facet_values(key, filter)?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment
+                .get()
+                .read()
+                .facet_values(key, Some(&wrapped_filter))?
+        };
+        values.extend(self.write_segment.get().read().facet_values(key, filter)?);
+        Ok(values)
+    }
+
     /// Read points in [from; to) range
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType> {
         let deleted_points = self.deleted_points.read();
@@ -593,6 +790,12 @@ impl SegmentEntry for ProxySegment {
         }
     }
 
+    fn resource_usage(&self) -> OperationResult<SegmentResourceUsage> {
+        let mut usage = self.wrapped_segment.get().read().resource_usage()?;
+        usage.merge(&self.write_segment.get().read().resource_usage()?);
+        Ok(usage)
+    }
+
     fn config(&self) -> SegmentConfig {
         self.wrapped_segment.get().read().config()
     }
@@ -677,6 +880,40 @@ impl SegmentEntry for ProxySegment {
         Ok(true)
     }
 
+    fn start_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+        field_schema: Option<&PayloadFieldSchema>,
+    ) -> OperationResult<bool> {
+        // Write segment is small and shared between proxies, so its index is replaced in place.
+        // The wrapped segment keeps the current index until it is replaced by the optimized one,
+        // which gets the new index from `created_indexes`.
+        self.create_field_index(op_num, key, field_schema)?;
+        Ok(false)
+    }
+
+    fn continue_field_index_rebuild(
+        &self,
+        _key: PayloadKeyTypeRef,
+        _batch_size: usize,
+    ) -> OperationResult<bool> {
+        // Rebuild is never started in a proxy segment
+        Ok(true)
+    }
+
+    fn finish_field_index_rebuild(
+        &mut self,
+        _op_num: SeqNumberType,
+        _key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        Ok(false)
+    }
+
+    fn abort_field_index_rebuild(&mut self, _key: PayloadKeyTypeRef) -> OperationResult<()> {
+        Ok(())
+    }
+
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
         let indexed_fields = self.wrapped_segment.get().read().get_indexed_fields();
         indexed_fields
@@ -759,7 +996,12 @@ impl SegmentEntry for ProxySegment {
     }
 
     fn get_telemetry_data(&self) -> SegmentTelemetry {
-        self.wrapped_segment.get().read().get_telemetry_data()
+        let mut telemetry = self.wrapped_segment.get().read().get_telemetry_data();
+        telemetry.optimization_progress = self
+            .build_progress
+            .as_ref()
+            .map(|progress| progress.fraction().unwrap_or(0.0));
+        telemetry
     }
 }
 
@@ -767,6 +1009,7 @@ impl SegmentEntry for ProxySegment {
 mod tests {
     use std::fs::read_dir;
 
+    use itertools::Itertools;
     use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
     use segment::types::{FieldCondition, PayloadSchemaType};
     use serde_json::json;
@@ -845,6 +1088,33 @@ mod tests {
         assert!(proxy_segment.write_segment.get().read().has_point(2.into()))
     }
 
+    #[test]
+    fn test_moved_point_keeps_component_versions() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut original_segment = empty_segment(dir.path());
+        let vector = vec![1.0, 0.0, 0.0, 0.0];
+        original_segment
+            .upsert_point(5, 1.into(), only_default_vector(&vector))
+            .unwrap();
+        original_segment
+            .update_vectors(10, 1.into(), only_default_vector(&vector))
+            .unwrap();
+
+        let mut proxy_segment = wrap_proxy(&dir, LockedSegment::new(original_segment));
+
+        // Payload update is newer than the payload, but older than the vectors of the point
+        let payload: Payload = json!({ "color": "red" }).into();
+        proxy_segment.set_payload(7, 1.into(), &payload).unwrap();
+        assert_eq!(
+            proxy_segment.point_versions(1.into()),
+            Some(PointVersions {
+                vectors: 10,
+                payload: 7,
+            }),
+        );
+        assert_eq!(proxy_segment.payload(1.into()).unwrap(), payload);
+    }
+
     #[test]
     fn test_search_batch_equivalence_single() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -894,12 +1164,13 @@ mod tests {
         let search_batch_result = proxy_segment
             .search_batch(
                 DEFAULT_VECTOR_NAME,
-                &[&query_vector],
+                &[&query_vector.clone().into()],
                 &WithPayload::default(),
                 &false.into(),
                 None,
                 10,
                 None,
+                None,
                 &false.into(),
             )
             .unwrap();
@@ -949,12 +1220,13 @@ mod tests {
         let search_batch_result = proxy_segment
             .search_batch(
                 DEFAULT_VECTOR_NAME,
-                &[&query_vector],
+                &[&query_vector.clone().into()],
                 &WithPayload::default(),
                 &false.into(),
                 None,
                 10,
                 None,
+                None,
                 &false.into(),
             )
             .unwrap();
@@ -1012,15 +1284,20 @@ mod tests {
 
         eprintln!("search_result = {all_single_results:#?}");
 
+        let query_vectors = query_vectors
+            .iter()
+            .map(|vector| QueryVector::from(vector.to_vec()))
+            .collect_vec();
         let search_batch_result = proxy_segment
             .search_batch(
                 DEFAULT_VECTOR_NAME,
-                query_vectors,
+                &query_vectors.iter().collect_vec(),
                 &WithPayload::default(),
                 &false.into(),
                 None,
                 10,
                 None,
+                None,
                 &false.into(),
             )
             .unwrap();
@@ -1299,6 +1576,9 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
                 (
@@ -1309,10 +1589,14 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
             ]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
         let mut original_segment = build_segment(dir.path(), &config, true).unwrap();
         let write_segment = build_segment(dir.path(), &config, true).unwrap();
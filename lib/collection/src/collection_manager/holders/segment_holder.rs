@@ -371,16 +371,18 @@ impl<'s> SegmentHolder {
         let mut applied_points: HashSet<PointIdType> = Default::default();
 
         let _applied_points_count = self.apply_points(ids, |point_id, _idx, write_segment| {
-            if let Some(point_version) = write_segment.point_version(point_id) {
-                if point_version >= op_num {
-                    applied_points.insert(point_id);
-                    return Ok(false);
-                }
-            }
-
             let is_applied = if write_segment.is_appendable() {
+                // Appendable segment compares the operation with the version of the point
+                // component it changes, so older updates of other components are not skipped
                 f(point_id, write_segment)?
             } else {
+                if let Some(point_version) = write_segment.point_version(point_id) {
+                    if point_version >= op_num {
+                        applied_points.insert(point_id);
+                        return Ok(false);
+                    }
+                }
+
                 self.aloha_random_write(
                     &appendable_segments,
                     |_appendable_idx, appendable_write_segment| {
@@ -29,6 +29,7 @@ pub struct ConfigMismatchOptimizer {
     hnsw_config: HnswConfig,
     quantization_config: Option<QuantizationConfig>,
     telemetry_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
+    index_build_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
 }
 
 impl ConfigMismatchOptimizer {
@@ -48,6 +49,7 @@ impl ConfigMismatchOptimizer {
             hnsw_config,
             quantization_config,
             telemetry_durations_aggregator: OperationDurationsAggregator::new(),
+            index_build_durations_aggregator: OperationDurationsAggregator::new(),
         }
     }
 
@@ -213,6 +215,11 @@ impl SegmentOptimizer for ConfigMismatchOptimizer {
 
     fn threshold_config(&self) -> &OptimizerThresholds {
         &self.thresholds_config
+        merge_buffer_size: 1024,
+    }
+
+    fn name(&self) -> &str {
+        "config_mismatch"
     }
 
     fn check_condition(
@@ -230,6 +237,10 @@ impl SegmentOptimizer for ConfigMismatchOptimizer {
     fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
         self.telemetry_durations_aggregator.clone()
     }
+
+    fn get_index_build_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
+        self.index_build_durations_aggregator.clone()
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +285,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            merge_buffer_size: 1024,
         };
         let collection_params = CollectionParams {
             vectors: VectorsConfig::Single(VectorParams {
@@ -282,11 +294,18 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
+                multivector_config: None,
             }),
             shard_number: 1.try_into().unwrap(),
+            sharding_method: None,
             on_disk_payload: false,
             replication_factor: 1.try_into().unwrap(),
             write_consistency_factor: 1.try_into().unwrap(),
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         };
 
         // Base segment
@@ -400,6 +419,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            merge_buffer_size: 1024,
         };
         let hnsw_config_vector1 = HnswConfigDiff {
             m: Some(10),
@@ -417,6 +437,8 @@ mod tests {
                         hnsw_config: Some(hnsw_config_vector1),
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
+                        multivector_config: None,
                     },
                 ),
                 (
@@ -427,13 +449,20 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
+                        multivector_config: None,
                     },
                 ),
             ])),
             shard_number: 1.try_into().unwrap(),
+            sharding_method: None,
             on_disk_payload: false,
             replication_factor: 1.try_into().unwrap(),
             write_consistency_factor: 1.try_into().unwrap(),
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         };
 
         // Base segment
@@ -566,6 +595,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            merge_buffer_size: 1024,
         };
         let quantization_config_vector1 =
             QuantizationConfig::Scalar(segment::types::ScalarQuantization {
@@ -585,6 +615,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: Some(quantization_config_vector1.clone()),
                         on_disk: None,
+                        datatype: None,
+                        multivector_config: None,
                     },
                 ),
                 (
@@ -595,13 +627,20 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
+                        multivector_config: None,
                     },
                 ),
             ])),
             shard_number: 1.try_into().unwrap(),
+            sharding_method: None,
             on_disk_payload: false,
             replication_factor: 1.try_into().unwrap(),
             write_consistency_factor: 1.try_into().unwrap(),
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         };
 
         // Base segment
@@ -30,6 +30,7 @@ pub struct IndexingOptimizer {
     hnsw_config: HnswConfig,
     quantization_config: Option<QuantizationConfig>,
     telemetry_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
+    index_build_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
 }
 
 impl IndexingOptimizer {
@@ -49,6 +50,7 @@ impl IndexingOptimizer {
             hnsw_config,
             quantization_config,
             telemetry_durations_aggregator: OperationDurationsAggregator::new(),
+            index_build_durations_aggregator: OperationDurationsAggregator::new(),
         }
     }
 
@@ -136,7 +138,17 @@ impl IndexingOptimizer {
                         .indexing_threshold
                         .saturating_mul(BYTES_IN_KB);
 
-                let require_indexing = (big_for_mmap && !is_any_on_disk)
+                // Split segments, which have grown over the size limit, e.g. after it was lowered.
+                // A single point can't be split, even if it doesn't fit.
+                let oversized = point_count > 1
+                    && vector_size
+                        > self
+                            .thresholds_config
+                            .max_segment_size
+                            .saturating_mul(BYTES_IN_KB);
+
+                let require_indexing = oversized
+                    || (big_for_mmap && !is_any_on_disk)
                     || (big_for_index && !are_all_vectors_indexed);
 
                 require_indexing.then_some((*idx, vector_size))
@@ -213,6 +225,11 @@ impl SegmentOptimizer for IndexingOptimizer {
 
     fn threshold_config(&self) -> &OptimizerThresholds {
         &self.thresholds_config
+        merge_buffer_size: 1024,
+    }
+
+    fn name(&self) -> &str {
+        "indexing"
     }
 
     fn check_condition(
@@ -230,6 +247,10 @@ impl SegmentOptimizer for IndexingOptimizer {
     fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
         self.telemetry_durations_aggregator.clone()
     }
+
+    fn get_index_build_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
+        self.index_build_durations_aggregator.clone()
+    }
 }
 
 #[cfg(test)]
@@ -298,6 +319,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
+                        multivector_config: None,
                     },
                 )
             })
@@ -308,15 +331,21 @@ mod tests {
                 max_segment_size: 300,
                 memmap_threshold: 1000,
                 indexing_threshold: 1000,
+                merge_buffer_size: 1024,
             },
             segments_dir.path().to_owned(),
             segments_temp_dir.path().to_owned(),
             CollectionParams {
                 vectors: VectorsConfig::Multi(vectors_config),
                 shard_number: NonZeroU32::new(1).unwrap(),
+                sharding_method: None,
                 replication_factor: NonZeroU32::new(1).unwrap(),
                 write_consistency_factor: NonZeroU32::new(1).unwrap(),
                 on_disk_payload: false,
+                tenant_key: None,
+                sparse_vectors: None,
+                store_original_vectors: false,
+                search_timeout_sec: None,
             },
             Default::default(),
             Default::default(),
@@ -403,6 +432,7 @@ mod tests {
                 max_segment_size: 300,
                 memmap_threshold: 1000,
                 indexing_threshold: 1000,
+                merge_buffer_size: 1024,
             },
             segments_dir.path().to_owned(),
             segments_temp_dir.path().to_owned(),
@@ -416,11 +446,18 @@ mod tests {
                     hnsw_config: None,
                     quantization_config: None,
                     on_disk: None,
+                    datatype: None,
+                    multivector_config: None,
                 }),
                 shard_number: NonZeroU32::new(1).unwrap(),
+                sharding_method: None,
                 replication_factor: NonZeroU32::new(1).unwrap(),
                 write_consistency_factor: NonZeroU32::new(1).unwrap(),
                 on_disk_payload: false,
+                tenant_key: None,
+                sparse_vectors: None,
+                store_original_vectors: false,
+                search_timeout_sec: None,
             },
             Default::default(),
             Default::default(),
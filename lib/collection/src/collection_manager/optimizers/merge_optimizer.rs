@@ -32,6 +32,7 @@ pub struct MergeOptimizer {
     hnsw_config: HnswConfig,
     quantization_config: Option<QuantizationConfig>,
     telemetry_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
+    index_build_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
 }
 
 impl MergeOptimizer {
@@ -54,6 +55,7 @@ impl MergeOptimizer {
             hnsw_config,
             quantization_config,
             telemetry_durations_aggregator: OperationDurationsAggregator::new(),
+            index_build_durations_aggregator: OperationDurationsAggregator::new(),
         }
     }
 }
@@ -81,6 +83,11 @@ impl SegmentOptimizer for MergeOptimizer {
 
     fn threshold_config(&self) -> &OptimizerThresholds {
         &self.thresholds_config
+        merge_buffer_size: 1024,
+    }
+
+    fn name(&self) -> &str {
+        "merge"
     }
 
     fn check_condition(
@@ -153,6 +160,10 @@ impl SegmentOptimizer for MergeOptimizer {
     fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
         self.telemetry_durations_aggregator.clone()
     }
+
+    fn get_index_build_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
+        self.index_build_durations_aggregator.clone()
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 pub mod config_mismatch_optimizer;
 pub mod indexing_optimizer;
 pub mod merge_optimizer;
+pub mod optimization_tasks;
 pub mod segment_optimizer;
 pub mod vacuum_optimizer;
@@ -0,0 +1,279 @@
+//! Optimization tasks of a shard, listed with their progress and cancellable through the API.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use segment::index::hnsw_index::build_progress::BuildProgress;
+use serde::{Deserialize, Serialize};
+
+use crate::collection_manager::holders::segment_holder::SegmentId;
+use crate::operations::types::{CollectionError, CollectionResult};
+
+pub type OptimizationTaskId = u64;
+
+/// Ids are unique over all shards of this peer, so that a task can be found by its id only
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Status of an optimization task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizationTaskStatus {
+    /// Segments are being optimized
+    Optimizing,
+    /// Optimized segments replaced the original ones
+    Done,
+    /// Optimization is stopped, the original segments are kept
+    Cancelled,
+    /// Optimization failed
+    Error,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct OptimizationTaskInfo {
+    /// Id of the task, unique on this peer
+    pub id: OptimizationTaskId,
+    /// Name of the optimizer, which runs the task
+    pub optimizer: String,
+    /// Ids of the optimized segments within their shard
+    pub segment_ids: Vec<SegmentId>,
+    pub status: OptimizationTaskStatus,
+    /// Fraction of points linked into HNSW graphs of the optimized segments.
+    /// Absent until building of any graph is started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<f64>,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Reason, why the task is failed or cancelled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+struct TaskState {
+    status: OptimizationTaskStatus,
+    finished: Option<(Instant, DateTime<Utc>)>,
+    error: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct OptimizationTask {
+    id: OptimizationTaskId,
+    optimizer: String,
+    segment_ids: Vec<SegmentId>,
+    started_at: DateTime<Utc>,
+    progress: Arc<BuildProgress>,
+    cancel_requested: AtomicBool,
+    /// Stop flag of the optimization, set once the optimization is spawned
+    stop_flag: Mutex<Weak<AtomicBool>>,
+    state: Mutex<TaskState>,
+}
+
+impl OptimizationTask {
+    pub fn id(&self) -> OptimizationTaskId {
+        self.id
+    }
+
+    /// Progress of vector index building, to be reported by the optimization
+    pub fn progress(&self) -> Arc<BuildProgress> {
+        self.progress.clone()
+    }
+
+    pub fn set_stop_flag(&self, stop_flag: Weak<AtomicBool>) {
+        let mut guard = self.stop_flag.lock();
+        *guard = stop_flag;
+        // Cancelled before the optimization is spawned
+        if self.cancel_requested.load(Ordering::Relaxed) {
+            Self::stop(&guard);
+        }
+    }
+
+    fn stop(stop_flag: &Weak<AtomicBool>) {
+        if let Some(stopped) = stop_flag.upgrade() {
+            stopped.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Ask the optimization to stop, the original segments are kept
+    pub fn cancel(&self) -> CollectionResult<()> {
+        if self.state.lock().status != OptimizationTaskStatus::Optimizing {
+            return Err(CollectionError::bad_input(format!(
+                "Optimization task {} is already finished",
+                self.id,
+            )));
+        }
+        self.cancel_requested.store(true, Ordering::Relaxed);
+        Self::stop(&self.stop_flag.lock());
+        Ok(())
+    }
+
+    /// Whether the task is cancelled by [`OptimizationTask::cancel`], not by a shutdown
+    pub fn is_cancel_requested(&self) -> bool {
+        self.cancel_requested.load(Ordering::Relaxed)
+    }
+
+    /// Record the result of the optimization
+    pub fn finish(&self, result: &CollectionResult<bool>) {
+        let (status, error) = match result {
+            Ok(true) => (OptimizationTaskStatus::Done, None),
+            Ok(false) => (
+                OptimizationTaskStatus::Cancelled,
+                Some("Segments are not available for optimization".to_string()),
+            ),
+            Err(CollectionError::Cancelled { description }) => {
+                (OptimizationTaskStatus::Cancelled, Some(description.clone()))
+            }
+            Err(error) => (OptimizationTaskStatus::Error, Some(error.to_string())),
+        };
+        self.set_finished(status, error);
+    }
+
+    fn set_finished(&self, status: OptimizationTaskStatus, error: Option<String>) {
+        let mut state = self.state.lock();
+        if state.status != OptimizationTaskStatus::Optimizing {
+            return;
+        }
+        *state = TaskState {
+            status,
+            finished: Some((Instant::now(), Utc::now())),
+            error,
+        };
+    }
+
+    fn is_expired(&self, retention: Duration) -> bool {
+        self.state
+            .lock()
+            .finished
+            .map_or(false, |(finished, _)| finished.elapsed() > retention)
+    }
+
+    pub fn info(&self) -> OptimizationTaskInfo {
+        let state = self.state.lock();
+        let progress = match state.status {
+            OptimizationTaskStatus::Done => Some(1.0),
+            _ => self.progress.fraction(),
+        };
+        OptimizationTaskInfo {
+            id: self.id,
+            optimizer: self.optimizer.clone(),
+            segment_ids: self.segment_ids.clone(),
+            status: state.status,
+            progress,
+            started_at: self.started_at,
+            finished_at: state.finished.map(|(_, finished_at)| finished_at),
+            error: state.error.clone(),
+        }
+    }
+}
+
+/// Running and recently finished optimization tasks of a shard
+#[derive(Debug)]
+pub struct OptimizationTasks {
+    /// How long finished tasks are kept
+    retention: Duration,
+    tasks: Mutex<Vec<Arc<OptimizationTask>>>,
+}
+
+impl OptimizationTasks {
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention,
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Register a new task of the `optimizer` for the segments
+    pub fn start(&self, optimizer: &str, segment_ids: Vec<SegmentId>) -> Arc<OptimizationTask> {
+        let task = Arc::new(OptimizationTask {
+            id: NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed),
+            optimizer: optimizer.to_string(),
+            segment_ids,
+            started_at: Utc::now(),
+            progress: Arc::new(BuildProgress::default()),
+            cancel_requested: AtomicBool::new(false),
+            stop_flag: Mutex::new(Weak::new()),
+            state: Mutex::new(TaskState {
+                status: OptimizationTaskStatus::Optimizing,
+                finished: None,
+                error: None,
+            }),
+        });
+        let mut tasks = self.tasks.lock();
+        tasks.retain(|task| !task.is_expired(self.retention));
+        tasks.push(task.clone());
+        task
+    }
+
+    pub fn get(&self, id: OptimizationTaskId) -> Option<Arc<OptimizationTask>> {
+        self.tasks.lock().iter().find(|task| task.id == id).cloned()
+    }
+
+    /// Running and recently finished tasks, in the order of starting
+    pub fn list(&self) -> Vec<OptimizationTaskInfo> {
+        let mut tasks = self.tasks.lock();
+        tasks.retain(|task| !task.is_expired(self.retention));
+        tasks.iter().map(|task| task.info()).collect()
+    }
+
+    /// Mark tasks, which are never started, as cancelled.
+    ///
+    /// Should be called once all started optimizations are finished.
+    pub fn cancel_unfinished(&self, description: &str) {
+        for task in self.tasks.lock().iter() {
+            task.set_finished(
+                OptimizationTaskStatus::Cancelled,
+                Some(description.to_string()),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_and_retention() {
+        let tasks = OptimizationTasks::new(Duration::from_millis(100));
+
+        let done = tasks.start("merge", vec![1, 2]);
+        done.finish(&Ok(true));
+        assert!(done.cancel().is_err());
+
+        // Cancelled before the optimization is spawned
+        let cancelled = tasks.start("indexing", vec![3]);
+        cancelled.cancel().unwrap();
+        let stopped = Arc::new(AtomicBool::new(false));
+        cancelled.set_stop_flag(Arc::downgrade(&stopped));
+        assert!(stopped.load(Ordering::Relaxed));
+        assert!(cancelled.is_cancel_requested());
+        cancelled.finish(&Err(CollectionError::Cancelled {
+            description: "optimization cancelled".to_string(),
+        }));
+
+        let running = tasks.start("vacuum", vec![4]);
+        let statuses = tasks.list().into_iter().map(|info| (info.id, info.status));
+        assert_eq!(
+            statuses.collect::<Vec<_>>(),
+            vec![
+                (done.id(), OptimizationTaskStatus::Done),
+                (cancelled.id(), OptimizationTaskStatus::Cancelled),
+                (running.id(), OptimizationTaskStatus::Optimizing),
+            ],
+        );
+
+        // Only finished tasks expire
+        std::thread::sleep(Duration::from_millis(200));
+        let ids = tasks.list().into_iter().map(|info| info.id);
+        assert_eq!(ids.collect::<Vec<_>>(), vec![running.id()]);
+
+        tasks.cancel_unfinished("optimizations are stopped");
+        let info = tasks.get(running.id()).unwrap().info();
+        assert_eq!(info.status, OptimizationTaskStatus::Cancelled);
+        assert!(info.finished_at.is_some());
+    }
+}
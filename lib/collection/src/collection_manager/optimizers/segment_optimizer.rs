@@ -1,4 +1,5 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Bound;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -10,12 +11,13 @@ use segment::common::operation_time_statistics::{
 };
 use segment::common::version::StorageVersion;
 use segment::entry::entry_point::{check_process_stopped, SegmentEntry};
+use segment::index::hnsw_index::build_progress::BuildProgress;
 use segment::segment::{Segment, SegmentVersion};
 use segment::segment_constructor::build_segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
 use segment::types::{
     HnswConfig, Indexes, PayloadFieldSchema, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, VectorStorageType, VECTOR_ELEMENT_SIZE,
+    QuantizationConfig, SegmentConfig, SparseIndexType, VectorStorageType, VECTOR_ELEMENT_SIZE,
 };
 
 use crate::collection_manager::holders::proxy_segment::ProxySegment;
@@ -33,6 +35,7 @@ pub struct OptimizerThresholds {
     pub max_segment_size: usize,
     pub memmap_threshold: usize,
     pub indexing_threshold: usize,
+    pub merge_buffer_size: usize,
 }
 
 /// SegmentOptimizer - trait implementing common functionality of the optimizers
@@ -62,6 +65,9 @@ pub trait SegmentOptimizer {
     /// Get thresholds configuration for the current optimizer
     fn threshold_config(&self) -> &OptimizerThresholds;
 
+    /// Name of the optimizer, reported with its optimization tasks
+    fn name(&self) -> &str;
+
     /// Checks if segment optimization is required
     fn check_condition(
         &self,
@@ -73,11 +79,15 @@ pub trait SegmentOptimizer {
 
     fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>>;
 
+    /// Durations of HNSW index builds, performed by this optimizer
+    fn get_index_build_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>>;
+
     /// Build temp segment
     fn temp_segment(&self, save_version: bool) -> CollectionResult<LockedSegment> {
         let collection_params = self.collection_params();
         let config = SegmentConfig {
             vector_data: collection_params.into_base_vector_data()?,
+            sparse_vector_data: collection_params.into_sparse_vector_data(),
             payload_storage_type: if collection_params.on_disk_payload {
                 PayloadStorageType::OnDisk
             } else {
@@ -157,24 +167,13 @@ pub trait SegmentOptimizer {
             let collection_quantization = self.quantization_config();
             vector_data.iter_mut().for_each(|(vector_name, config)| {
                 // Assign HNSW index
-                let param_hnsw = collection_params
-                    .vectors
-                    .get_params(vector_name)
-                    .and_then(|params| params.hnsw_config);
-                let vector_hnsw = param_hnsw
-                    .and_then(|c| c.update(collection_hnsw).ok())
-                    .unwrap_or_else(|| collection_hnsw.clone());
+                let vector_hnsw =
+                    collection_params.vector_hnsw_config(vector_name, collection_hnsw);
                 config.index = Indexes::Hnsw(vector_hnsw);
 
                 // Assign quantization config
-                let param_quantization = collection_params
-                    .vectors
-                    .get_params(vector_name)
-                    .and_then(|params| params.quantization_config.as_ref());
-                let vector_quantization = param_quantization
-                    .or(collection_quantization.as_ref())
-                    .cloned();
-                config.quantization_config = vector_quantization;
+                config.quantization_config = collection_params
+                    .vector_quantization_config(vector_name, collection_quantization.as_ref());
             });
         }
 
@@ -185,8 +184,16 @@ pub trait SegmentOptimizer {
             });
         }
 
+        let mut sparse_vector_data = collection_params.into_sparse_vector_data();
+        for (vector_name, config) in sparse_vector_data.iter_mut() {
+            if collection_params.sparse_index_on_disk(vector_name) {
+                config.index.index_type = SparseIndexType::Mmap;
+            }
+        }
+
         let optimized_config = SegmentConfig {
             vector_data,
+            sparse_vector_data,
             payload_storage_type: if collection_params.on_disk_payload {
                 PayloadStorageType::OnDisk
             } else {
@@ -194,11 +201,62 @@ pub trait SegmentOptimizer {
             },
         };
 
-        Ok(SegmentBuilder::new(
-            self.collection_path(),
-            self.temp_path(),
-            &optimized_config,
-        )?)
+        let mut segment_builder =
+            SegmentBuilder::new(self.collection_path(), self.temp_path(), &optimized_config)?;
+        segment_builder.set_defragment_key(collection_params.tenant_key.clone());
+        segment_builder.set_merge_buffer_size(
+            self.threshold_config()
+                .merge_buffer_size
+                .saturating_mul(BYTES_IN_KB),
+        );
+        Ok(segment_builder)
+    }
+
+    /// Ranges of point ids to build optimized segments from, one segment per range
+    ///
+    /// If points of the `optimizing_segments` don't fit into `max_segment_size`, they are split
+    /// into several ranges with equal number of points, each fitting into the limit.
+    fn optimized_point_ranges(
+        &self,
+        optimizing_segments: &[LockedSegment],
+    ) -> CollectionResult<Vec<(Bound<PointIdType>, Bound<PointIdType>)>> {
+        let mut point_ids = BTreeSet::new();
+        let mut point_size = 0;
+        for segment in optimizing_segments {
+            let segment = match segment {
+                LockedSegment::Original(segment) => segment,
+                LockedSegment::Proxy(_) => {
+                    return Err(CollectionError::service_error(
+                        "Proxy segment is not expected here".to_string(),
+                    ))
+                }
+            };
+            let read_segment = segment.read();
+            point_ids.extend(read_segment.iter_points());
+            let max_dim = read_segment.vector_dims().values().max().copied();
+            point_size = point_size.max(max_dim.unwrap_or(0) * VECTOR_ELEMENT_SIZE);
+        }
+
+        let max_segment_size = self
+            .threshold_config()
+            .max_segment_size
+            .saturating_mul(BYTES_IN_KB);
+        let max_segment_points = (max_segment_size / point_size.max(1)).max(1);
+        if point_ids.len() <= max_segment_points {
+            return Ok(vec![(Bound::Unbounded, Bound::Unbounded)]);
+        }
+
+        let point_ids = point_ids.into_iter().collect_vec();
+        let ranges_count = point_ids.len().div_ceil(max_segment_points);
+        let mut ranges = Vec::with_capacity(ranges_count);
+        let mut start = Bound::Unbounded;
+        for range_index in 1..ranges_count {
+            let end = point_ids[range_index * point_ids.len() / ranges_count];
+            ranges.push((start, Bound::Excluded(end)));
+            start = Bound::Included(end);
+        }
+        ranges.push((start, Bound::Unbounded));
+        Ok(ranges)
     }
 
     /// Restores original segments from proxies
@@ -286,31 +344,71 @@ pub trait SegmentOptimizer {
     /// * `proxy_deleted_points` - Holds a set of points, deleted while optimization was running
     /// * `proxy_deleted_indexes` - Holds a set of Indexes, deleted while optimization was running
     /// * `proxy_created_indexes` - Holds a set of Indexes, created while optimization was running
+    /// * `build_progress` - Progress of vector index building, reported by the proxy segments
     /// * `stopped` - flag to check if optimization was cancelled by external thread
     ///
     /// # Result
     ///
-    /// Constructs optimized segment
+    /// Constructs optimized segments. Usually a single one, but more if points don't fit into
+    /// `max_segment_size`, see [`SegmentOptimizer::optimized_point_ranges`].
+    fn build_new_segments(
+        &self,
+        optimizing_segments: &[LockedSegment],
+        proxy_deleted_points: Arc<RwLock<HashSet<PointIdType>>>,
+        proxy_deleted_indexes: Arc<RwLock<HashSet<PayloadKeyType>>>,
+        proxy_created_indexes: Arc<RwLock<HashMap<PayloadKeyType, PayloadFieldSchema>>>,
+        build_progress: Arc<BuildProgress>,
+        stopped: &AtomicBool,
+    ) -> CollectionResult<Vec<Segment>> {
+        let point_ranges = self.optimized_point_ranges(optimizing_segments)?;
+        let is_split = point_ranges.len() > 1;
+        let mut optimized_segments = Vec::with_capacity(point_ranges.len());
+        for point_range in point_ranges {
+            let mut segment_builder = self.optimized_segment_builder(optimizing_segments)?;
+            if is_split {
+                segment_builder.set_point_range(point_range);
+            }
+            optimized_segments.push(self.build_new_segment(
+                segment_builder,
+                optimizing_segments,
+                proxy_deleted_points.clone(),
+                proxy_deleted_indexes.clone(),
+                proxy_created_indexes.clone(),
+                build_progress.clone(),
+                stopped,
+            )?);
+        }
+        Ok(optimized_segments)
+    }
+
+    /// Builds a single optimized segment with the `segment_builder`, see
+    /// [`SegmentOptimizer::build_new_segments`]
+    #[allow(clippy::too_many_arguments)]
     fn build_new_segment(
         &self,
+        mut segment_builder: SegmentBuilder,
         optimizing_segments: &[LockedSegment],
         proxy_deleted_points: Arc<RwLock<HashSet<PointIdType>>>,
         proxy_deleted_indexes: Arc<RwLock<HashSet<PayloadKeyType>>>,
         proxy_created_indexes: Arc<RwLock<HashMap<PayloadKeyType, PayloadFieldSchema>>>,
+        build_progress: Arc<BuildProgress>,
         stopped: &AtomicBool,
     ) -> CollectionResult<Segment> {
-        let mut segment_builder = self.optimized_segment_builder(optimizing_segments)?;
+        segment_builder.set_build_progress(build_progress);
+        segment_builder.set_index_build_telemetry(self.get_index_build_telemetry_counter());
 
         self.check_cancellation(stopped)?;
 
-        for segment in optimizing_segments {
-            match segment {
-                LockedSegment::Original(segment_arc) => {
-                    let segment_guard = segment_arc.read();
-                    segment_builder.update_from(&segment_guard, stopped)?;
-                }
-                LockedSegment::Proxy(_) => panic!("Attempt to optimize segment which is already currently under optimization. Should never happen"),
-            }
+        {
+            let segment_guards: Vec<_> = optimizing_segments
+                .iter()
+                .map(|segment| match segment {
+                    LockedSegment::Original(segment_arc) => segment_arc.read(),
+                    LockedSegment::Proxy(_) => panic!("Attempt to optimize segment which is already currently under optimization. Should never happen"),
+                })
+                .collect();
+            let segments: Vec<&Segment> = segment_guards.iter().map(|guard| &**guard).collect();
+            segment_builder.update(&segments, stopped)?;
         }
 
         for field in proxy_deleted_indexes.read().iter() {
@@ -379,6 +477,18 @@ pub trait SegmentOptimizer {
         segments: LockedSegmentHolder,
         ids: Vec<SegmentId>,
         stopped: &AtomicBool,
+    ) -> CollectionResult<bool> {
+        self.optimize_with_progress(segments, ids, Arc::new(BuildProgress::default()), stopped)
+    }
+
+    /// Same as [`SegmentOptimizer::optimize`], reporting the progress of vector index building
+    /// into `build_progress`
+    fn optimize_with_progress(
+        &self,
+        segments: LockedSegmentHolder,
+        ids: Vec<SegmentId>,
+        build_progress: Arc<BuildProgress>,
+        stopped: &AtomicBool,
     ) -> CollectionResult<bool> {
         check_process_stopped(stopped)?;
 
@@ -431,6 +541,7 @@ pub trait SegmentOptimizer {
                 proxy_created_indexes.clone(),
                 proxy_deleted_indexes.clone(),
             );
+            proxy.set_build_progress(build_progress.clone());
             // Wrapped segment is fresh, so it has no operations
             // Operation with number 0 will be applied
             proxy.replicate_field_indexes(0)?;
@@ -470,14 +581,15 @@ pub trait SegmentOptimizer {
 
         // ---- SLOW PART -----
 
-        let mut optimized_segment = match self.build_new_segment(
+        let mut optimized_segments = match self.build_new_segments(
             &optimizing_segments,
             proxy_deleted_points.clone(),
             proxy_deleted_indexes.clone(),
             proxy_created_indexes.clone(),
+            build_progress,
             stopped,
         ) {
-            Ok(segment) => segment,
+            Ok(segments) => segments,
             Err(error) => {
                 if matches!(error, CollectionError::Cancelled { .. }) {
                     self.handle_cancellation(&segments, &proxy_ids, &tmp_segment);
@@ -492,8 +604,10 @@ pub trait SegmentOptimizer {
         let already_remove_points = {
             let mut all_removed_points: HashSet<_> =
                 proxy_deleted_points.read().iter().cloned().collect();
-            for existing_point in optimized_segment.iter_points() {
-                all_removed_points.remove(&existing_point);
+            for optimized_segment in &optimized_segments {
+                for existing_point in optimized_segment.iter_points() {
+                    all_removed_points.remove(&existing_point);
+                }
             }
             all_removed_points
         };
@@ -509,29 +623,40 @@ pub trait SegmentOptimizer {
             // This block locks all operations with collection. It should be fast
             let mut write_segments_guard = segments.write();
             let deleted_points = proxy_deleted_points.read();
-            let points_diff = deleted_points.difference(&already_remove_points);
-            for &point_id in points_diff {
-                optimized_segment
-                    .delete_point(optimized_segment.version(), point_id)
-                    .unwrap();
-            }
+            let lazy_vectors = self.collection_params().on_disk_vectors();
+            for optimized_segment in &mut optimized_segments {
+                let points_diff = deleted_points.difference(&already_remove_points);
+                for &point_id in points_diff {
+                    optimized_segment
+                        .delete_point(optimized_segment.version(), point_id)
+                        .unwrap();
+                }
 
-            for deleted_field_name in proxy_deleted_indexes.read().iter() {
-                optimized_segment
-                    .delete_field_index(optimized_segment.version(), deleted_field_name)?;
-            }
+                for deleted_field_name in proxy_deleted_indexes.read().iter() {
+                    optimized_segment
+                        .delete_field_index(optimized_segment.version(), deleted_field_name)?;
+                }
 
-            for (created_field_name, schema_type) in proxy_created_indexes.read().iter() {
-                optimized_segment.create_field_index(
-                    optimized_segment.version(),
-                    created_field_name,
-                    Some(schema_type),
-                )?;
-            }
+                for (created_field_name, schema_type) in proxy_created_indexes.read().iter() {
+                    optimized_segment.create_field_index(
+                        optimized_segment.version(),
+                        created_field_name,
+                        Some(schema_type),
+                    )?;
+                }
 
-            optimized_segment.prefault_mmap_pages();
+                optimized_segment.prefault_mmap_pages(&lazy_vectors);
+            }
 
-            let (_, proxies) = write_segments_guard.swap(optimized_segment, &proxy_ids);
+            // The first segment replaces the optimized ones, the rest of the split are added
+            let mut optimized_segments = optimized_segments.into_iter();
+            let first_segment = optimized_segments.next().ok_or_else(|| {
+                CollectionError::service_error("No segments are built by optimization".to_string())
+            })?;
+            let (_, proxies) = write_segments_guard.swap(first_segment, &proxy_ids);
+            for optimized_segment in optimized_segments {
+                write_segments_guard.add(optimized_segment);
+            }
 
             let has_appendable_segments =
                 write_segments_guard.random_appendable_segment().is_some();
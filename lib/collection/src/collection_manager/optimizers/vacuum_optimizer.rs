@@ -36,6 +36,7 @@ pub struct VacuumOptimizer {
     hnsw_config: HnswConfig,
     quantization_config: Option<QuantizationConfig>,
     telemetry_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
+    index_build_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
 }
 
 impl VacuumOptimizer {
@@ -60,6 +61,7 @@ impl VacuumOptimizer {
             hnsw_config,
             quantization_config,
             telemetry_durations_aggregator: OperationDurationsAggregator::new(),
+            index_build_durations_aggregator: OperationDurationsAggregator::new(),
         }
     }
 
@@ -187,6 +189,11 @@ impl SegmentOptimizer for VacuumOptimizer {
 
     fn threshold_config(&self) -> &OptimizerThresholds {
         &self.thresholds_config
+        merge_buffer_size: 1024,
+    }
+
+    fn name(&self) -> &str {
+        "vacuum"
     }
 
     fn check_condition(
@@ -207,6 +214,10 @@ impl SegmentOptimizer for VacuumOptimizer {
     fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
         self.telemetry_durations_aggregator.clone()
     }
+
+    fn get_index_build_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
+        self.index_build_durations_aggregator.clone()
+    }
 }
 
 #[cfg(test)]
@@ -218,8 +229,12 @@ mod tests {
 
     use itertools::Itertools;
     use parking_lot::RwLock;
+    use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
     use segment::entry::entry_point::SegmentEntry;
-    use segment::types::{Distance, PayloadContainer, PayloadSchemaType};
+    use segment::types::{
+        Condition, Distance, FieldCondition, Filter, PayloadContainer, PayloadSchemaType, Range,
+        SearchParams, WithPayload, WithVector,
+    };
     use serde_json::{json, Value};
     use tempfile::Builder;
 
@@ -296,6 +311,7 @@ mod tests {
                 max_segment_size: 1000000,
                 memmap_threshold: 1000000,
                 indexing_threshold: 1000000,
+                merge_buffer_size: 1024,
             },
             dir.path().to_owned(),
             temp_dir.path().to_owned(),
@@ -306,11 +322,18 @@ mod tests {
                     hnsw_config: None,
                     quantization_config: None,
                     on_disk: None,
+                    datatype: None,
+                    multivector_config: None,
                 }),
                 shard_number: NonZeroU32::new(1).unwrap(),
+                sharding_method: None,
                 on_disk_payload: false,
                 replication_factor: NonZeroU32::new(1).unwrap(),
                 write_consistency_factor: NonZeroU32::new(1).unwrap(),
+                tenant_key: None,
+                sparse_vectors: None,
+                store_original_vectors: false,
+                search_timeout_sec: None,
             },
             Default::default(),
             Default::default(),
@@ -388,6 +411,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            merge_buffer_size: 1024,
         };
         let collection_params = CollectionParams {
             vectors: VectorsConfig::Multi(BTreeMap::from([
@@ -399,6 +423,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
+                        multivector_config: None,
                     },
                 ),
                 (
@@ -409,13 +435,20 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        datatype: None,
+                        multivector_config: None,
                     },
                 ),
             ])),
             shard_number: 1.try_into().unwrap(),
+            sharding_method: None,
             on_disk_payload: false,
             replication_factor: 1.try_into().unwrap(),
             write_consistency_factor: 1.try_into().unwrap(),
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         };
 
         // Base segment
@@ -612,4 +645,213 @@ mod tests {
                 });
             });
     }
+
+    /// Deletes 30% of points from an indexed segment and vacuums it.
+    ///
+    /// The rebuilt segment must have no deleted points left, while keeping versions,
+    /// payload index and search results of all other points.
+    #[test]
+    fn test_vacuum_indexed_segment() {
+        let (point_count, dim) = (1000, 4);
+        let thresholds_config = OptimizerThresholds {
+            max_segment_size: std::usize::MAX,
+            memmap_threshold: std::usize::MAX,
+            indexing_threshold: 10,
+            merge_buffer_size: 1024,
+        };
+        let collection_params = CollectionParams {
+            vectors: VectorsConfig::Single(VectorParams {
+                size: NonZeroU64::new(dim as u64).unwrap(),
+                distance: Distance::Dot,
+                hnsw_config: None,
+                quantization_config: None,
+                on_disk: None,
+                datatype: None,
+                multivector_config: None,
+            }),
+            shard_number: NonZeroU32::new(1).unwrap(),
+            sharding_method: None,
+            on_disk_payload: false,
+            replication_factor: NonZeroU32::new(1).unwrap(),
+            write_consistency_factor: NonZeroU32::new(1).unwrap(),
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
+        };
+        let hnsw_config = HnswConfig {
+            m: 16,
+            ef_construct: 100,
+            full_scan_threshold: 1,
+            max_indexing_threads: 2,
+            on_disk: None,
+            payload_m: None,
+        };
+
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut holder = SegmentHolder::default();
+
+        let mut segment = random_segment(dir.path(), 100, point_count, dim);
+        segment
+            .create_field_index(101, "number", Some(&PayloadSchemaType::Integer.into()))
+            .unwrap();
+        let segment_id = holder.add(segment);
+        let locked_holder: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
+
+        let index_optimizer = IndexingOptimizer::new(
+            thresholds_config.clone(),
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params.clone(),
+            hnsw_config.clone(),
+            Default::default(),
+        );
+        let vacuum_optimizer = VacuumOptimizer::new(
+            0.2,
+            100,
+            thresholds_config,
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params,
+            hnsw_config,
+            Default::default(),
+        );
+
+        let changed = index_optimizer
+            .optimize(locked_holder.clone(), vec![segment_id], &false.into())
+            .unwrap();
+        assert!(changed, "optimizer should have rebuilt this segment");
+
+        let indexed_segment = |holder: &SegmentHolder| {
+            holder
+                .iter()
+                .map(|(_, segment)| match segment {
+                    LockedSegment::Original(s) => s.clone(),
+                    LockedSegment::Proxy(_) => unreachable!(),
+                })
+                .find(|segment| segment.read().total_point_count() > 0)
+                .unwrap()
+        };
+
+        let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+            "number",
+            Range {
+                lt: Some(500.0),
+                ..Default::default()
+            },
+        )));
+        let exact = SearchParams {
+            exact: true,
+            ..Default::default()
+        };
+        let queries = (0..10)
+            .map(|i| (0..dim).map(|j| ((i + j) % 3) as f32).collect_vec())
+            .collect_vec();
+        let search = |segment: &dyn SegmentEntry, query: &[f32], params: Option<&SearchParams>| {
+            segment
+                .search(
+                    DEFAULT_VECTOR_NAME,
+                    query,
+                    &WithPayload::from(false),
+                    &WithVector::Bool(false),
+                    None,
+                    10,
+                    params,
+                    &false.into(),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|point| point.id)
+                .collect_vec()
+        };
+
+        // Delete 30% of points, and update payload of some others with a newer version
+        let (deleted_points, expected_versions, expected_filtered, expected_search) = {
+            let segment = indexed_segment(&locked_holder.read());
+            let mut segment = segment.write();
+            let points = segment.iter_points().collect_vec();
+            let deleted_points = points
+                .iter()
+                .enumerate()
+                .filter_map(|(i, point_id)| (i % 10 < 3).then_some(*point_id))
+                .collect_vec();
+            for &point_id in &deleted_points {
+                segment.delete_point(201, point_id).unwrap();
+            }
+            for (i, &point_id) in points.iter().enumerate() {
+                if i % 10 == 5 {
+                    segment
+                        .set_payload(202, point_id, &json!({ "number": [1] }).into())
+                        .unwrap();
+                }
+            }
+            assert_eq!(segment.deleted_point_count(), deleted_points.len());
+
+            let expected_versions = segment
+                .iter_points()
+                .map(|point_id| (point_id, segment.point_version(point_id).unwrap()))
+                .collect_vec();
+            let expected_filtered = segment.read_filtered(None, None, Some(&filter));
+            let expected_search = queries
+                .iter()
+                .map(|query| search(&*segment, query.as_slice(), Some(&exact)))
+                .collect_vec();
+            (
+                deleted_points,
+                expected_versions,
+                expected_filtered,
+                expected_search,
+            )
+        };
+
+        let suggested_to_optimize =
+            vacuum_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize.len(), 1);
+        let changed = vacuum_optimizer
+            .optimize(locked_holder.clone(), suggested_to_optimize, &false.into())
+            .unwrap();
+        assert!(changed, "optimizer should have rebuilt this segment");
+
+        let segment = indexed_segment(&locked_holder.read());
+        let segment = segment.read();
+
+        // No tombstones are left
+        let available_count = point_count as usize - deleted_points.len();
+        assert_eq!(segment.deleted_point_count(), 0);
+        assert_eq!(segment.total_point_count(), available_count);
+        for vector_data in segment.vector_data.values() {
+            let vector_index = vector_data.vector_index.borrow();
+            let vector_storage = vector_data.vector_storage.borrow();
+            assert!(vector_index.is_index());
+            assert_eq!(vector_storage.deleted_vector_count(), 0);
+            assert_eq!(vector_index.indexed_vector_count(), available_count);
+        }
+        for point_id in &deleted_points {
+            assert!(!segment.has_point(*point_id));
+        }
+
+        // Point versions are preserved
+        for (point_id, version) in expected_versions {
+            assert_eq!(segment.point_version(point_id), Some(version));
+        }
+
+        // Payload index is rebuilt for the remaining points only
+        assert_eq!(
+            segment.info().index_schema["number"].points,
+            available_count
+        );
+        assert_eq!(
+            segment.read_filtered(None, None, Some(&filter)),
+            expected_filtered
+        );
+
+        // Searches return the same points, deleted ones are never found
+        for (query, expected) in queries.iter().zip(expected_search) {
+            assert_eq!(search(&*segment, query.as_slice(), Some(&exact)), expected);
+            let found = search(&*segment, query.as_slice(), None);
+            assert_eq!(found.len(), 10);
+            assert!(found.iter().all(|id| !deleted_points.contains(id)));
+        }
+    }
 }
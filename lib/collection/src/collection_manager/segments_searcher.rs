@@ -1,12 +1,15 @@
-use std::collections::HashMap;
-use std::sync::atomic::AtomicBool;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures::future::try_join_all;
+use itertools::Itertools;
 use ordered_float::Float;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use segment::common::search_stats::SearchStats;
 use segment::data_types::named_vectors::NamedVectors;
-use segment::data_types::vectors::VectorElementType;
+use segment::data_types::vectors::QueryVector;
 use segment::entry::entry_point::OperationError;
 use segment::types::{
     Filter, Indexes, PointIdType, ScoreType, ScoredPoint, SearchParams, SegmentConfig,
@@ -14,11 +17,13 @@ use segment::types::{
 };
 use tokio::runtime::Handle;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
-use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
 use crate::collection_manager::probabilistic_segment_search_sampling::find_search_sampling_over_point_distribution;
 use crate::collection_manager::search_result_aggregator::BatchResultAggregator;
-use crate::operations::types::{CollectionResult, Record, SearchRequestBatch};
+use crate::common::search_stats::report_search_stats;
+use crate::operations::types::{CollectionError, CollectionResult, CoreSearchRequestBatch, Record};
 
 type BatchOffset = usize;
 type SegmentOffset = usize;
@@ -54,6 +59,33 @@ impl SegmentsSearcher {
         Ok((search_results_per_segment, further_searches_per_segment))
     }
 
+    /// Same as [`Self::execute_searches`], but the searches are stopped at the `deadline`
+    ///
+    /// The timeout error reports how many of the `total` segment searches were `completed`.
+    async fn execute_searches_until(
+        searches: Vec<JoinHandle<SegmentSearchExecutedResult>>,
+        deadline: Instant,
+        timeout: Duration,
+        is_stopped: &AtomicBool,
+        completed: &AtomicUsize,
+        total: usize,
+    ) -> CollectionResult<(BatchSearchResult, Vec<Vec<bool>>)> {
+        tokio::select! {
+            biased;
+            _ = tokio::time::sleep_until(deadline) => {
+                is_stopped.store(true, Ordering::Relaxed);
+                let completed = completed.load(Ordering::Relaxed);
+                log::debug!("Search timeout reached, {completed} of {total} segment searches completed");
+                Err(CollectionError::Timeout {
+                    description: format!(
+                        "Search timed out after {timeout:?}, {completed} of {total} segment searches completed"
+                    ),
+                })
+            }
+            res = Self::execute_searches(searches) => res,
+        }
+    }
+
     /// Processes search result of [segment_size x batch_size]
     ///
     /// # Arguments
@@ -146,11 +178,18 @@ impl SegmentsSearcher {
 
     pub async fn search(
         segments: &RwLock<SegmentHolder>,
-        batch_request: Arc<SearchRequestBatch>,
+        batch_request: Arc<CoreSearchRequestBatch>,
         runtime_handle: &Handle,
         sampling_enabled: bool,
         is_stopped: Arc<AtomicBool>,
+        timeout: Duration,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let deadline = Instant::now() + timeout;
+        let completed = Arc::new(AtomicUsize::new(0));
+        // Work of all segment searches, reported to the collector of the request
+        let stats = Arc::new(Mutex::new(SearchStats::default()));
+        let parent_span = tracing::Span::current();
+
         // Using { } block to ensure segments variable is dropped in the end of it
         // and is not transferred across the all_searches.await? boundary as it
         // does not impl Send trait
@@ -178,28 +217,46 @@ impl SegmentsSearcher {
 
             segments
                 .iter()
-                .map(|(_id, segment)| {
+                .map(|(&segment_id, segment)| {
                     let search = runtime_handle.spawn_blocking({
                         let (segment, batch_request) = (segment.clone(), batch_request.clone());
                         let is_stopped_clone = is_stopped.clone();
+                        let completed = completed.clone();
+                        let stats = stats.clone();
+                        let span = segment_search_span(&parent_span, segment_id);
                         move || {
-                            search_in_segment(
-                                segment,
-                                batch_request,
-                                available_points_segments,
-                                use_sampling,
-                                &is_stopped_clone,
-                            )
+                            let (res, segment_stats) = SearchStats::collect(|| {
+                                span.in_scope(|| {
+                                    search_in_segment(
+                                        segment,
+                                        batch_request,
+                                        available_points_segments,
+                                        use_sampling,
+                                        &is_stopped_clone,
+                                    )
+                                })
+                            });
+                            *stats.lock() += segment_stats;
+                            completed.fetch_add(1, Ordering::Relaxed);
+                            res
                         }
                     });
-                    (segment.clone(), search)
+                    ((segment_id, segment.clone()), search)
                 })
                 .unzip()
         };
         // perform search on all segments concurrently
         // the resulting Vec is in the same order as the segment searches were provided.
-        let (all_search_results_per_segment, further_results) =
-            Self::execute_searches(searches).await?;
+        let mut total = searches.len();
+        let (all_search_results_per_segment, further_results) = Self::execute_searches_until(
+            searches,
+            deadline,
+            timeout,
+            &is_stopped,
+            &completed,
+            total,
+        )
+        .await?;
         debug_assert!(all_search_results_per_segment.len() == locked_segments.len());
 
         let (mut result_aggregator, searches_to_rerun) = Self::process_search_result_step1(
@@ -221,30 +278,48 @@ impl SegmentsSearcher {
 
             let secondary_searches: Vec<_> = {
                 let mut res = vec![];
-                for (segment_id, batch_ids) in searches_to_rerun.iter() {
-                    let segment = locked_segments[*segment_id].clone();
-                    let partial_batch_request = Arc::new(SearchRequestBatch {
+                for (segment_offset, batch_ids) in searches_to_rerun.iter() {
+                    let (segment_id, segment) = locked_segments[*segment_offset].clone();
+                    let partial_batch_request = Arc::new(CoreSearchRequestBatch {
                         searches: batch_ids
                             .iter()
                             .map(|batch_id| batch_request.searches[*batch_id].clone())
                             .collect(),
                     });
                     let is_stopped_clone = is_stopped.clone();
+                    let completed = completed.clone();
+                    let stats = stats.clone();
+                    let span = segment_search_span(&parent_span, segment_id);
                     res.push(runtime_handle.spawn_blocking(move || {
-                        search_in_segment(
-                            segment,
-                            partial_batch_request,
-                            0,
-                            false,
-                            &is_stopped_clone,
-                        )
+                        let (res, segment_stats) = SearchStats::collect(|| {
+                            span.in_scope(|| {
+                                search_in_segment(
+                                    segment,
+                                    partial_batch_request,
+                                    0,
+                                    false,
+                                    &is_stopped_clone,
+                                )
+                            })
+                        });
+                        *stats.lock() += segment_stats;
+                        completed.fetch_add(1, Ordering::Relaxed);
+                        res
                     }))
                 }
                 res
             };
 
-            let (secondary_search_results_per_segment, _) =
-                Self::execute_searches(secondary_searches).await?;
+            total += secondary_searches.len();
+            let (secondary_search_results_per_segment, _) = Self::execute_searches_until(
+                secondary_searches,
+                deadline,
+                timeout,
+                &is_stopped,
+                &completed,
+                total,
+            )
+            .await?;
 
             result_aggregator.update_point_versions(&secondary_search_results_per_segment);
 
@@ -261,6 +336,8 @@ impl SegmentsSearcher {
             }
         }
 
+        report_search_stats(*stats.lock());
+
         let top_scores: Vec<_> = result_aggregator.into_topk();
         Ok(top_scores)
     }
@@ -286,7 +363,7 @@ impl SegmentsSearcher {
                         id,
                         payload: if with_payload.enable {
                             if let Some(selector) = &with_payload.payload_selector {
-                                Some(selector.process(segment.payload(id)?))
+                                Some(segment.payload_selected(id, selector)?)
                             } else {
                                 Some(segment.payload(id)?)
                             }
@@ -306,6 +383,8 @@ impl SegmentsSearcher {
                                 Some(selected_vectors.into())
                             }
                         },
+                        order_value: None,
+                        version: Some(version),
                     },
                 );
                 point_version.insert(id, version);
@@ -314,6 +393,42 @@ impl SegmentsSearcher {
         })?;
         Ok(point_records.into_values().collect())
     }
+
+    /// Read ids of all points, which satisfy the filter
+    ///
+    /// A point might be present in multiple segments, e.g. while it is moved by the optimizer.
+    /// Only the latest version of every point is checked against the filter.
+    pub fn read_filtered(
+        segments: &RwLock<SegmentHolder>,
+        filter: Option<&Filter>,
+    ) -> CollectionResult<BTreeSet<PointIdType>> {
+        let segments = segments.read();
+
+        // Latest version of every point, which satisfies the filter
+        let mut matched_version: HashMap<PointIdType, SeqNumberType> = Default::default();
+        for (_id, segment) in segments.iter() {
+            let segment_arc = segment.get();
+            let read_segment = segment_arc.read();
+            for id in read_segment.read_filtered(None, None, filter) {
+                let version = read_segment.point_version(id).ok_or_else(|| {
+                    OperationError::service_error(format!("No version for point {id}"))
+                })?;
+                let matched = matched_version.entry(id).or_insert(version);
+                *matched = (*matched).max(version);
+            }
+        }
+
+        // Drop points, which have a later version not satisfying the filter in another segment
+        let matched_ids = matched_version.keys().copied().collect_vec();
+        segments.read_points(&matched_ids, |id, segment| {
+            if segment.point_version(id) > matched_version.get(&id).copied() {
+                matched_version.remove(&id);
+            }
+            Ok(true)
+        })?;
+
+        Ok(matched_version.into_keys().collect())
+    }
 }
 
 #[derive(PartialEq, Default, Debug)]
@@ -323,6 +438,7 @@ struct BatchSearchParams<'a> {
     pub with_payload: WithPayload,
     pub with_vector: WithVector,
     pub top: usize,
+    pub score_threshold: Option<ScoreType>,
     pub params: Option<&'a SearchParams>,
 }
 
@@ -368,7 +484,7 @@ fn effective_limit(limit: usize, ef_limit: usize, poisson_sampling: usize) -> us
 /// * Vector of boolean indicating if the segment have further points to search
 fn search_in_segment(
     segment: LockedSegment,
-    request: Arc<SearchRequestBatch>,
+    request: Arc<CoreSearchRequestBatch>,
     total_points: usize,
     use_sampling: bool,
     is_stopped: &AtomicBool,
@@ -377,7 +493,7 @@ fn search_in_segment(
 
     let mut result: Vec<Vec<ScoredPoint>> = Vec::with_capacity(batch_size);
     let mut further_results: Vec<bool> = Vec::with_capacity(batch_size); // if segment have more points to return
-    let mut vectors_batch: Vec<&[VectorElementType]> = vec![];
+    let mut vectors_batch: Vec<QueryVector> = vec![];
     let mut prev_params = BatchSearchParams::default();
 
     for search_query in &request.searches {
@@ -387,17 +503,18 @@ fn search_in_segment(
             .unwrap_or(&WithPayloadInterface::Bool(false));
 
         let params = BatchSearchParams {
-            vector_name: search_query.vector.get_name(),
+            vector_name: search_query.query.get_vector_name(),
             filter: search_query.filter.as_ref(),
             with_payload: WithPayload::from(with_payload_interface),
             with_vector: search_query.with_vector.clone().unwrap_or_default(),
             top: search_query.limit + search_query.offset,
+            score_threshold: search_query.score_threshold,
             params: search_query.params.as_ref(),
         };
 
         // same params enables batching
         if params == prev_params {
-            vectors_batch.push(search_query.vector.get_vector().as_slice());
+            vectors_batch.push(search_query.query.clone().into());
         } else {
             // different params means different batches
             // execute what has been batched so far
@@ -416,11 +533,12 @@ fn search_in_segment(
 
                 let mut res = read_segment.search_batch(
                     prev_params.vector_name,
-                    &vectors_batch,
+                    &vectors_batch.iter().collect_vec(),
                     &prev_params.with_payload,
                     &prev_params.with_vector,
                     prev_params.filter,
                     top,
+                    prev_params.score_threshold,
                     prev_params.params,
                     is_stopped,
                 )?;
@@ -432,7 +550,7 @@ fn search_in_segment(
                 vectors_batch.clear();
             }
             // start new batch for current search query
-            vectors_batch.push(search_query.vector.get_vector().as_slice());
+            vectors_batch.push(search_query.query.clone().into());
             prev_params = params;
         }
     }
@@ -453,11 +571,12 @@ fn search_in_segment(
         };
         let mut res = read_segment.search_batch(
             prev_params.vector_name,
-            &vectors_batch,
+            &vectors_batch.iter().collect_vec(),
             &prev_params.with_payload,
             &prev_params.with_vector,
             prev_params.filter,
             top,
+            prev_params.score_threshold,
             prev_params.params,
             is_stopped,
         )?;
@@ -467,9 +586,22 @@ fn search_in_segment(
         result.append(&mut res);
     }
 
+    tracing::Span::current().record("result_count", result.iter().map(Vec::len).sum::<usize>());
     Ok((result, further_results))
 }
 
+/// Span of a search in a single segment.
+///
+/// Spans are not propagated into blocking tasks, so the parent span has to be set explicitly.
+fn segment_search_span(parent_span: &tracing::Span, segment_id: SegmentId) -> tracing::Span {
+    tracing::info_span!(
+        parent: parent_span,
+        "segment_search",
+        segment_id,
+        result_count = tracing::field::Empty,
+    )
+}
+
 /// Find the HNSW ef_construct for a named vector
 ///
 /// If the given named vector has no HNSW index, `None` is returned.
@@ -487,11 +619,12 @@ fn get_hnsw_ef_construct(config: SegmentConfig, vector_name: &str) -> Option<usi
 #[cfg(test)]
 mod tests {
     use segment::fixtures::index_fixtures::random_vector;
+    use segment::types::{Condition, FieldCondition};
     use tempfile::Builder;
 
     use super::*;
     use crate::collection_manager::fixtures::{build_test_holder, random_segment};
-    use crate::operations::types::SearchRequest;
+    use crate::operations::types::{SearchRequest, SearchRequestBatch};
 
     #[tokio::test]
     async fn test_segments_search() {
@@ -514,14 +647,16 @@ mod tests {
 
         let batch_request = SearchRequestBatch {
             searches: vec![req],
+            fail_fast: None,
         };
 
         let result = SegmentsSearcher::search(
             &segment_holder,
-            Arc::new(batch_request),
+            Arc::new(batch_request.into()),
             &Handle::current(),
             true,
             Arc::new(AtomicBool::new(false)),
+            Duration::from_secs(60),
         )
         .await
         .unwrap()
@@ -537,6 +672,58 @@ mod tests {
         assert!(result[1].id == 3.into() || result[1].id == 11.into());
     }
 
+    #[tokio::test]
+    async fn test_segments_search_timeout() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segment_holder = build_test_holder(dir.path());
+
+        let batch_request = SearchRequestBatch {
+            searches: vec![SearchRequest {
+                vector: vec![1.0, 0.0, 1.0, 1.0].into(),
+                with_payload: None,
+                with_vector: None,
+                filter: None,
+                params: None,
+                limit: 5,
+                score_threshold: None,
+                offset: 0,
+            }],
+            fail_fast: None,
+        };
+
+        // The only blocking thread is busy, so segment searches don't start before the deadline
+        let search_runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .max_blocking_threads(1)
+            .build()
+            .unwrap();
+        search_runtime.spawn_blocking(|| std::thread::sleep(Duration::from_millis(500)));
+
+        let is_stopped = Arc::new(AtomicBool::new(false));
+        let result = SegmentsSearcher::search(
+            &segment_holder,
+            Arc::new(batch_request.into()),
+            search_runtime.handle(),
+            true,
+            is_stopped.clone(),
+            Duration::from_millis(100),
+        )
+        .await;
+        search_runtime.shutdown_background();
+
+        match result {
+            Err(CollectionError::Timeout { description }) => {
+                assert!(
+                    description.contains("0 of 2 segment searches"),
+                    "{description}"
+                )
+            }
+            other => panic!("Expected timeout error, got {other:?}"),
+        }
+        // Segment searches still running are stopped
+        assert!(is_stopped.load(Ordering::Relaxed));
+    }
+
     #[tokio::test]
     async fn test_segments_search_sampling() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -577,14 +764,16 @@ mod tests {
 
             let batch_request = SearchRequestBatch {
                 searches: vec![req1, req2],
+                fail_fast: None,
             };
 
             let result_no_sampling = SegmentsSearcher::search(
                 &segment_holder,
-                Arc::new(batch_request.clone()),
+                Arc::new(batch_request.clone().into()),
                 &Handle::current(),
                 false,
                 Arc::new(false.into()),
+                Duration::from_secs(60),
             )
             .await
             .unwrap();
@@ -593,10 +782,11 @@ mod tests {
 
             let result_sampling = SegmentsSearcher::search(
                 &segment_holder,
-                Arc::new(batch_request),
+                Arc::new(batch_request.into()),
                 &Handle::current(),
                 true,
                 Arc::new(false.into()),
+                Duration::from_secs(60),
             )
             .await
             .unwrap();
@@ -629,6 +819,60 @@ mod tests {
         assert_eq!(records.len(), 3);
     }
 
+    #[test]
+    fn test_read_filtered() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segment_holder = build_test_holder(dir.path());
+
+        // Points 4 and 5 are present in both segments
+        let all_points = SegmentsSearcher::read_filtered(&segment_holder, None).unwrap();
+        assert_eq!(
+            all_points,
+            [1, 2, 3, 4, 5, 11, 12, 13, 14, 15]
+                .map(PointIdType::from)
+                .into()
+        );
+
+        // Red points 4 and 5 have a later version without payload in the second segment
+        let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "red".to_string().into(),
+        )));
+        let red_points = SegmentsSearcher::read_filtered(&segment_holder, Some(&filter)).unwrap();
+        assert_eq!(red_points, [1, 2].map(PointIdType::from).into());
+
+        // Brute force over the latest versions of all points
+        let records = SegmentsSearcher::retrieve(
+            &segment_holder,
+            &all_points.iter().copied().collect_vec(),
+            &WithPayload::from(true),
+            &false.into(),
+        )
+        .unwrap();
+        let expected: BTreeSet<_> = records
+            .iter()
+            .filter(|record| {
+                let colors = record
+                    .payload
+                    .as_ref()
+                    .and_then(|payload| payload.0.get("color"));
+                colors.is_some_and(|colors| colors.as_array().unwrap().contains(&"red".into()))
+            })
+            .map(|record| record.id)
+            .collect();
+        assert_eq!(red_points, expected);
+
+        // Cardinality estimation used for approximate count bounds the exact count
+        let (min, max) = segment_holder
+            .read()
+            .iter()
+            .map(|(_id, segment)| segment.get().read().estimate_point_count(Some(&filter)))
+            .fold((0, 0), |(min, max), estimation| {
+                (min + estimation.min, max + estimation.max)
+            });
+        assert!((min..=max).contains(&red_points.len()));
+    }
+
     #[test]
     fn test_sampling_limit() {
         assert_eq!(sampling_limit(1000, None, 464530, 35103551), 30);
@@ -1,14 +1,18 @@
 //! A collection of functions for updating points and payloads stored in segments
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
 
 use parking_lot::{RwLock, RwLockWriteGuard};
+use segment::data_types::facets::FacetValue;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::entry::entry_point::{OperationError, OperationResult, SegmentEntry};
 use segment::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
+    AnyVariants, Condition, FieldCondition, Filter, Match, Payload, PayloadFieldSchema,
+    PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaParams, PayloadSchemaType, PointIdType,
     SeqNumberType,
 };
+use serde_json::{Map, Value};
 
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
 use crate::operations::payload_ops::PayloadOps;
@@ -17,6 +21,9 @@ use crate::operations::types::{CollectionError, CollectionResult};
 use crate::operations::vector_ops::{PointVectors, VectorOperations};
 use crate::operations::FieldIndexOperations;
 
+/// Number of points added to a rebuilt payload index, while its segment is locked for reading
+const FIELD_INDEX_REBUILD_BATCH_SIZE: usize = 10_000;
+
 pub(crate) fn check_unprocessed_points(
     points: &[PointIdType],
     processed: &HashSet<PointIdType>,
@@ -121,10 +128,14 @@ pub(crate) fn set_payload(
     op_num: SeqNumberType,
     payload: &Payload,
     points: &[PointIdType],
+    key: Option<&PayloadKeyType>,
 ) -> CollectionResult<usize> {
     let updated_points =
         segments.apply_points_to_appendable(op_num, points, |id, write_segment| {
-            write_segment.set_payload(op_num, id, payload)?;
+            match key {
+                Some(key) => write_segment.set_payload_by_key(op_num, id, payload, key)?,
+                None => write_segment.set_payload(op_num, id, payload)?,
+            };
             Ok(true)
         })?;
 
@@ -150,9 +161,10 @@ pub(crate) fn set_payload_by_filter(
     op_num: SeqNumberType,
     payload: &Payload,
     filter: &Filter,
+    key: Option<&PayloadKeyType>,
 ) -> CollectionResult<usize> {
     let affected_points = points_by_filter(segments, filter)?;
-    set_payload(segments, op_num, payload, &affected_points)
+    set_payload(segments, op_num, payload, &affected_points, key)
 }
 
 pub(crate) fn delete_payload(
@@ -215,17 +227,187 @@ pub(crate) fn clear_payload_by_filter(
     Ok(updated_points.len())
 }
 
+fn abort_field_index_rebuild(
+    segments: &[Arc<RwLock<dyn SegmentEntry>>],
+    field_name: PayloadKeyTypeRef,
+) {
+    for segment in segments {
+        if let Err(err) = segment.write().abort_field_index_rebuild(field_name) {
+            log::error!("Failed to abort rebuild of `{field_name}` index: {err}");
+        }
+    }
+}
+
+/// Build index of the field in all segments, while their current indexes keep serving.
+/// Segments are only locked for reading while a batch of points is added, so updates go on and
+/// are applied to both indexes. Current indexes are replaced once all segments are built,
+/// a failure in any segment leaves them intact.
+fn rebuild_field_index(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    field_name: PayloadKeyTypeRef,
+    field_schema: Option<&PayloadFieldSchema>,
+) -> CollectionResult<usize> {
+    let mut rebuilt_segments = Vec::new();
+    for (_idx, segment) in segments.iter() {
+        let segment = segment.get();
+        let is_started =
+            segment
+                .write()
+                .start_field_index_rebuild(op_num, field_name, field_schema);
+        match is_started {
+            Ok(true) => rebuilt_segments.push(segment),
+            Ok(false) => {}
+            Err(err) => {
+                abort_field_index_rebuild(&rebuilt_segments, field_name);
+                return Err(err.into());
+            }
+        }
+    }
+
+    for segment in &rebuilt_segments {
+        loop {
+            let is_complete = segment
+                .read()
+                .continue_field_index_rebuild(field_name, FIELD_INDEX_REBUILD_BATCH_SIZE);
+            match is_complete {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(err) => {
+                    abort_field_index_rebuild(&rebuilt_segments, field_name);
+                    return Err(err.into());
+                }
+            }
+        }
+    }
+
+    let mut processed_segments = 0;
+    for (i, segment) in rebuilt_segments.iter().enumerate() {
+        let is_applied = segment
+            .write()
+            .finish_field_index_rebuild(op_num, field_name);
+        match is_applied {
+            Ok(is_applied) => processed_segments += is_applied as usize,
+            Err(err) => {
+                abort_field_index_rebuild(&rebuilt_segments[i + 1..], field_name);
+                return Err(err.into());
+            }
+        }
+    }
+    Ok(processed_segments)
+}
+
 pub(crate) fn create_field_index(
     segments: &SegmentHolder,
     op_num: SeqNumberType,
     field_name: PayloadKeyTypeRef,
     field_schema: Option<&PayloadFieldSchema>,
 ) -> CollectionResult<usize> {
-    segments
-        .apply_segments(|write_segment| {
-            write_segment.create_field_index(op_num, field_name, field_schema)
-        })
-        .map_err(Into::into)
+    rebuild_field_index(segments, op_num, field_name, field_schema)
+}
+
+fn parse_bool_keyword(keyword: &str) -> Option<bool> {
+    if keyword.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if keyword.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn bool_from_keywords(value: &Value) -> Value {
+    match value {
+        Value::String(keyword) => {
+            parse_bool_keyword(keyword).map_or_else(|| value.clone(), Value::Bool)
+        }
+        Value::Array(values) => Value::Array(values.iter().map(bool_from_keywords).collect()),
+        _ => value.clone(),
+    }
+}
+
+/// Convert `"true"` and `"false"` strings of a keyword-indexed field, in any casing, into
+/// booleans, so the field can be indexed as bool. Any other keyword fails the conversion
+/// before points are changed.
+fn convert_bool_keywords(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    field_name: PayloadKeyTypeRef,
+) -> CollectionResult<usize> {
+    let mut keywords = BTreeSet::new();
+    for (_idx, segment) in segments.iter() {
+        let segment = segment.get();
+        let segment = segment.read();
+        let is_keyword_indexed = matches!(
+            segment.get_indexed_fields().get(field_name),
+            Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword))
+                | Some(PayloadFieldSchema::FieldParams(
+                    PayloadSchemaParams::Keyword(_)
+                ))
+        );
+        if !is_keyword_indexed {
+            continue;
+        }
+        for value in segment.facet_values(field_name, None)? {
+            let FacetValue::Keyword(keyword) = value;
+            if parse_bool_keyword(&keyword).is_none() {
+                return Err(CollectionError::bad_request(format!(
+                    "Field `{field_name}` can't be indexed as bool, it has value \"{keyword}\""
+                )));
+            }
+            keywords.insert(keyword);
+        }
+    }
+    if keywords.is_empty() {
+        return Ok(0);
+    }
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        field_name,
+        Match::new_any(AnyVariants::Keywords(keywords.into_iter().collect())),
+    )));
+    let points = points_by_filter(segments, &filter)?;
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, &points, |id, write_segment| {
+            let payload = write_segment.payload(id)?;
+            let Some(value) = payload.0.get(field_name) else {
+                return Ok(false);
+            };
+            let converted = Payload(Map::from_iter([(
+                field_name.to_string(),
+                bool_from_keywords(value),
+            )]));
+            write_segment.set_payload(op_num, id, &converted)?;
+            Ok(true)
+        })?;
+    Ok(updated_points.len())
+}
+
+/// Change schema of an existing field index, rebuilding it without blocking the segments.
+/// Keyword index, which is updated into a bool one, has its `"true"` and `"false"` values
+/// converted into booleans first.
+pub(crate) fn update_field_index(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    field_name: PayloadKeyTypeRef,
+    field_schema: Option<&PayloadFieldSchema>,
+) -> CollectionResult<usize> {
+    let is_indexed = segments.iter().any(|(_idx, segment)| {
+        segment
+            .get()
+            .read()
+            .get_indexed_fields()
+            .contains_key(field_name)
+    });
+    if !is_indexed {
+        return Err(CollectionError::bad_request(format!(
+            "Field `{field_name}` is not indexed"
+        )));
+    }
+    if field_schema == Some(&PayloadSchemaType::Bool.into()) {
+        convert_bool_keywords(segments, op_num, field_name)?;
+    }
+    rebuild_field_index(segments, op_num, field_name, field_schema)
 }
 
 pub(crate) fn delete_field_index(
@@ -473,9 +655,9 @@ pub(crate) fn process_payload_operation(
         PayloadOps::SetPayload(sp) => {
             let payload: Payload = sp.payload;
             if let Some(points) = sp.points {
-                set_payload(&segments.read(), op_num, &payload, &points)
+                set_payload(&segments.read(), op_num, &payload, &points, sp.key.as_ref())
             } else if let Some(filter) = sp.filter {
-                set_payload_by_filter(&segments.read(), op_num, &payload, &filter)
+                set_payload_by_filter(&segments.read(), op_num, &payload, &filter, sp.key.as_ref())
             } else {
                 Err(CollectionError::BadRequest {
                     description: "No points or filter specified".to_string(),
@@ -526,6 +708,12 @@ pub(crate) fn process_field_index_operation(
             &index_data.field_name,
             index_data.field_schema.as_ref(),
         ),
+        FieldIndexOperations::UpdateIndex(index_data) => update_field_index(
+            &segments.read(),
+            op_num,
+            &index_data.field_name,
+            index_data.field_schema.as_ref(),
+        ),
         FieldIndexOperations::DeleteIndex(field_name) => {
             delete_field_index(&segments.read(), op_num, field_name)
         }
@@ -7,7 +7,9 @@ use crate::collection::Collection;
 use crate::config::CollectionConfig;
 use crate::operations::types::CollectionResult;
 use crate::shards::replica_set::ReplicaState;
+use crate::shards::resharding::{ReshardingState, ShardSplit};
 use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::shard_holder::ShardKeyMapping;
 use crate::shards::transfer::shard_transfer::ShardTransfer;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -22,6 +24,12 @@ pub struct State {
     pub shards: HashMap<ShardId, ShardInfo>,
     #[serde(default)]
     pub transfers: HashSet<ShardTransfer>,
+    #[serde(default)]
+    pub shards_key_mapping: ShardKeyMapping,
+    #[serde(default)]
+    pub resharding: Option<ReshardingState>,
+    #[serde(default)]
+    pub shard_splits: Vec<ShardSplit>,
 }
 
 impl State {
@@ -34,7 +42,8 @@ impl State {
         Self::apply_config(self.config, collection).await?;
         Self::apply_shard_transfers(self.transfers, collection, this_peer_id, abort_transfer)
             .await?;
-        Self::apply_shard_info(self.shards, collection).await?;
+        Self::apply_resharding(self.resharding, self.shard_splits, collection).await?;
+        Self::apply_shard_info(self.shards, self.shards_key_mapping, collection).await?;
         Ok(())
     }
 
@@ -67,6 +76,24 @@ impl State {
         Ok(())
     }
 
+    async fn apply_resharding(
+        resharding: Option<ReshardingState>,
+        shard_splits: Vec<ShardSplit>,
+        collection: &Collection,
+    ) -> CollectionResult<()> {
+        // Resharding, which was finished or aborted since the state was taken
+        if let Some(old_resharding) = collection.resharding_state().await {
+            if resharding != Some(old_resharding) {
+                if shard_splits.contains(&old_resharding.split) {
+                    collection.finish_resharding(old_resharding.split).await?;
+                } else {
+                    collection.abort_resharding(old_resharding.split).await?;
+                }
+            }
+        }
+        collection.apply_resharding(shard_splits, resharding).await
+    }
+
     async fn apply_config(
         new_config: CollectionConfig,
         collection: &Collection,
@@ -90,9 +117,39 @@ impl State {
 
     async fn apply_shard_info(
         shards: HashMap<ShardId, ShardInfo>,
+        shards_key_mapping: ShardKeyMapping,
         collection: &Collection,
     ) -> CollectionResult<()> {
+        // Shard keys, which were dropped since the state was taken
+        let dropped_shard_keys: HashSet<_> = {
+            let shards_holder = collection.shards_holder.read().await;
+            let shard_key_mapping = shards_holder.shard_key_mapping.read();
+            shard_key_mapping
+                .iter()
+                .filter(|(shard_id, _)| !shards_key_mapping.contains_key(shard_id))
+                .map(|(_, shard_key)| shard_key.clone())
+                .collect()
+        };
+        for shard_key in dropped_shard_keys {
+            collection.drop_shard_key(shard_key).await?;
+        }
+
         for (shard_id, shard_info) in shards {
+            // Shards of shard keys, which were created since the state was taken
+            if let Some(shard_key) = shards_key_mapping.get(&shard_id) {
+                let mut shards_holder = collection.shards_holder.write().await;
+                if !shards_holder.contains_shard(&shard_id) {
+                    collection
+                        .add_keyed_shard(
+                            &mut shards_holder,
+                            shard_id,
+                            shard_key.clone(),
+                            shard_info.replicas.keys().copied(),
+                        )
+                        .await?;
+                }
+            }
+
             let shards_holder = collection.shards_holder.read().await;
             match shards_holder.get_shard(&shard_id) {
                 Some(replica_set) => replica_set.apply_state(shard_info.replicas).await?,
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use segment::types::{
+    AnyVariants, FieldCondition, Filter, Match, PayloadKeyType, PayloadSchemaType, RangeInterface,
+    ValueVariants,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::operations::strict_mode::filtered_fields;
+
+/// Max number of payload fields, which usage in filters is tracked per collection
+pub const FILTER_USAGE_CAPACITY: usize = 256;
+
+/// Max number of suggested payload indexes
+pub const INDEX_SUGGESTIONS_LIMIT: usize = 10;
+
+/// Payload field, which is used in filters without a payload index
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PayloadIndexSuggestion {
+    /// Full path of the payload field
+    pub key: PayloadKeyType,
+    /// Number of filters by the field, while the field had no payload index
+    pub unindexed_hits: usize,
+    /// Number of all filters by the field
+    pub hits: usize,
+    /// Type of the payload index, inferred from the filter conditions by the field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field_schema: Option<PayloadSchemaType>,
+}
+
+#[derive(Debug)]
+struct FieldUsage {
+    hits: usize,
+    unindexed_hits: usize,
+    field_schema: Option<PayloadSchemaType>,
+    /// Tick of the last filter by the field
+    last_used: u64,
+}
+
+/// Usage of payload fields in filters of client requests.
+///
+/// Only the [`FILTER_USAGE_CAPACITY`] most recently used fields are kept.
+#[derive(Debug)]
+pub struct FilterUsage {
+    capacity: usize,
+    fields: HashMap<PayloadKeyType, FieldUsage>,
+    tick: u64,
+}
+
+impl Default for FilterUsage {
+    fn default() -> Self {
+        Self::new(FILTER_USAGE_CAPACITY)
+    }
+}
+
+impl FilterUsage {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            fields: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Record the fields of the `filter`, `is_indexed` tells if a field has a payload index
+    pub fn record(&mut self, filter: &Filter, is_indexed: impl Fn(&str) -> bool) {
+        let mut filter_fields: HashMap<PayloadKeyType, Option<PayloadSchemaType>> = HashMap::new();
+        for (key, condition) in filtered_fields(filter, None) {
+            let field_schema = condition.and_then(infer_field_schema);
+            let recorded_schema = filter_fields.entry(key).or_default();
+            *recorded_schema = field_schema.or(*recorded_schema);
+        }
+
+        for (key, field_schema) in filter_fields {
+            self.tick += 1;
+            if !self.fields.contains_key(&key) && self.fields.len() >= self.capacity {
+                self.evict_least_recently_used();
+            }
+            let is_indexed = is_indexed(&key);
+            let usage = self.fields.entry(key).or_insert(FieldUsage {
+                hits: 0,
+                unindexed_hits: 0,
+                field_schema: None,
+                last_used: 0,
+            });
+            usage.hits += 1;
+            if !is_indexed {
+                usage.unindexed_hits += 1;
+            }
+            usage.field_schema = field_schema.or(usage.field_schema);
+            usage.last_used = self.tick;
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let least_recently_used = self
+            .fields
+            .iter()
+            .min_by_key(|(_, usage)| usage.last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = least_recently_used {
+            self.fields.remove(&key);
+        }
+    }
+
+    /// Up to `limit` fields, filtered without a payload index, the most filtered first.
+    ///
+    /// Fields, which are indexed by now according to `is_indexed`, are not suggested.
+    pub fn suggestions(
+        &self,
+        limit: usize,
+        is_indexed: impl Fn(&str) -> bool,
+    ) -> Vec<PayloadIndexSuggestion> {
+        let mut suggestions: Vec<_> = self
+            .fields
+            .iter()
+            .filter(|(key, usage)| usage.unindexed_hits > 0 && !is_indexed(key))
+            .map(|(key, usage)| PayloadIndexSuggestion {
+                key: key.clone(),
+                unindexed_hits: usage.unindexed_hits,
+                hits: usage.hits,
+                field_schema: usage.field_schema,
+            })
+            .collect();
+        suggestions.sort_unstable_by(|a, b| {
+            b.unindexed_hits
+                .cmp(&a.unindexed_hits)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        suggestions.truncate(limit);
+        suggestions
+    }
+
+    pub fn reset(&mut self) {
+        self.fields.clear();
+    }
+}
+
+/// Type of the payload index, which serves the condition
+fn infer_field_schema(condition: &FieldCondition) -> Option<PayloadSchemaType> {
+    let any_variants_schema = |variants: &AnyVariants| match variants {
+        AnyVariants::Keywords(_) => PayloadSchemaType::Keyword,
+        AnyVariants::Integers(_) => PayloadSchemaType::Integer,
+    };
+    if let Some(r#match) = &condition.r#match {
+        let schema = match r#match {
+            Match::Value(value) => match value.value {
+                ValueVariants::Keyword(_) => PayloadSchemaType::Keyword,
+                ValueVariants::Integer(_) => PayloadSchemaType::Integer,
+                ValueVariants::Bool(_) => PayloadSchemaType::Bool,
+            },
+            Match::Text(_) | Match::Phrase(_) => PayloadSchemaType::Text,
+            Match::Any(any) => any_variants_schema(&any.any),
+            Match::Except(except) => any_variants_schema(&except.except),
+        };
+        return Some(schema);
+    }
+    if let Some(range) = &condition.range {
+        return Some(match range {
+            RangeInterface::Float(_) => PayloadSchemaType::Float,
+            RangeInterface::DateTime(_) => PayloadSchemaType::Datetime,
+        });
+    }
+    if condition.geo_bounding_box.is_some()
+        || condition.geo_radius.is_some()
+        || condition.geo_polygon.is_some()
+    {
+        return Some(PayloadSchemaType::Geo);
+    }
+    // Values count is served by an index of any type
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::{Condition, Range};
+
+    use super::*;
+
+    fn match_keyword(key: &str) -> Condition {
+        Condition::Field(FieldCondition::new_match(key, "value".to_string().into()))
+    }
+
+    fn range(key: &str) -> Condition {
+        Condition::Field(FieldCondition::new_range(
+            key,
+            Range {
+                gte: Some(1.0),
+                ..Default::default()
+            },
+        ))
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let mut usage = FilterUsage::default();
+        let is_indexed = |key: &str| key == "indexed";
+
+        let filter = Filter {
+            should: None,
+            min_should: None,
+            must: Some(vec![match_keyword("city"), range("price"), range("price")]),
+            must_not: Some(vec![match_keyword("indexed")]),
+        };
+        usage.record(&filter, is_indexed);
+        usage.record(&Filter::new_must(match_keyword("city")), is_indexed);
+
+        // Fields are counted once per filter, indexed fields are not suggested
+        assert_eq!(
+            usage.suggestions(10, is_indexed),
+            vec![
+                PayloadIndexSuggestion {
+                    key: "city".to_string(),
+                    unindexed_hits: 2,
+                    hits: 2,
+                    field_schema: Some(PayloadSchemaType::Keyword),
+                },
+                PayloadIndexSuggestion {
+                    key: "price".to_string(),
+                    unindexed_hits: 1,
+                    hits: 1,
+                    field_schema: Some(PayloadSchemaType::Float),
+                },
+            ],
+        );
+        assert_eq!(usage.suggestions(1, is_indexed).len(), 1);
+
+        // Field, indexed since, is not suggested anymore, but keeps its previous hits
+        let is_indexed = |key: &str| key == "indexed" || key == "city";
+        usage.record(&Filter::new_must(match_keyword("city")), is_indexed);
+        let suggestions = usage.suggestions(10, is_indexed);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].key, "price");
+        let suggestions = usage.suggestions(10, |_| false);
+        assert_eq!(suggestions[0].key, "city");
+        assert_eq!(suggestions[0].hits, 3);
+        assert_eq!(suggestions[0].unindexed_hits, 2);
+
+        usage.reset();
+        assert!(usage.suggestions(10, |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_least_recently_used_fields_are_evicted() {
+        let mut usage = FilterUsage::new(2);
+
+        usage.record(&Filter::new_must(match_keyword("a")), |_| false);
+        usage.record(&Filter::new_must(match_keyword("b")), |_| false);
+        usage.record(&Filter::new_must(match_keyword("a")), |_| false);
+        usage.record(&Filter::new_must(match_keyword("c")), |_| false);
+
+        let keys: Vec<_> = usage
+            .suggestions(10, |_| false)
+            .into_iter()
+            .map(|suggestion| suggestion.key)
+            .collect();
+        assert_eq!(keys, vec!["a", "c"]);
+    }
+}
@@ -1,4 +1,9 @@
+pub mod filter_usage;
 pub mod is_ready;
+pub mod rate_limiting;
+pub mod resource_usage;
+pub mod search_recall;
+pub mod search_stats;
 pub mod stoppable_task;
 pub mod stoppable_task_async;
 pub mod stopping_guard;
@@ -0,0 +1,318 @@
+//! Limits of concurrent searches and of the rate of updates of a collection.
+//!
+//! Limits are given on every request, so changes of the strict mode config take effect at once.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Retry hint of rejected searches, the time to finish running searches is unknown
+const SEARCH_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Utilization of the request limits of a collection
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct RateLimitsTelemetry {
+    /// Searches, which are running now
+    pub searches_in_flight: usize,
+    /// Searches, which wait for a running one to finish
+    pub searches_waiting: usize,
+    /// Searches, rejected because of `max_concurrent_searches`
+    pub searches_rejected: usize,
+    /// Updates, which wait for their turn
+    pub updates_waiting: usize,
+    /// Updates, rejected because of `max_updates_per_second`
+    pub updates_rejected: usize,
+    /// Updates, which can be made at once without waiting.
+    /// Absent, if the rate of updates is not limited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_tokens_available: Option<f64>,
+}
+
+/// Number of concurrent searches, limited like a semaphore with a changeable number of permits
+#[derive(Debug, Default)]
+pub struct SearchConcurrencyLimiter {
+    in_flight: AtomicUsize,
+    waiting: AtomicUsize,
+    rejected: AtomicUsize,
+    released: Notify,
+}
+
+/// Slot of a running search, released on drop
+#[must_use]
+pub struct SearchPermit<'a> {
+    limiter: &'a SearchConcurrencyLimiter,
+}
+
+impl Drop for SearchPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.limiter.released.notify_one();
+    }
+}
+
+/// Request counted as waiting until it is dropped, even if its future is cancelled
+struct WaitingGuard<'a> {
+    waiting: &'a AtomicUsize,
+}
+
+impl<'a> WaitingGuard<'a> {
+    fn new(waiting: &'a AtomicUsize) -> Self {
+        waiting.fetch_add(1, Ordering::SeqCst);
+        Self { waiting }
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.waiting.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl SearchConcurrencyLimiter {
+    fn try_acquire(&self, limit: usize) -> Option<SearchPermit<'_>> {
+        self.in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |in_flight| {
+                (in_flight < limit).then_some(in_flight + 1)
+            })
+            .ok()
+            .map(|_| SearchPermit { limiter: self })
+    }
+
+    /// Take a slot of `limit` concurrent searches.
+    ///
+    /// If all slots are taken, waits up to `timeout` for one of them to be released.
+    pub async fn acquire(
+        &self,
+        limit: usize,
+        timeout: Duration,
+    ) -> CollectionResult<SearchPermit<'_>> {
+        if let Some(permit) = self.try_acquire(limit) {
+            return Ok(permit);
+        }
+
+        let waiting = WaitingGuard::new(&self.waiting);
+        let deadline = Instant::now() + timeout;
+        let permit = loop {
+            // Registered before the check, so that a release in between is not missed
+            let released = self.released.notified();
+            if let Some(permit) = self.try_acquire(limit) {
+                break Some(permit);
+            }
+            if tokio::time::timeout_at(deadline, released).await.is_err() {
+                break self.try_acquire(limit);
+            }
+        };
+        drop(waiting);
+
+        permit.ok_or_else(|| {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            CollectionError::rate_limited(
+                format!("Too many concurrent searches, `max_concurrent_searches` is {limit}"),
+                SEARCH_RETRY_AFTER,
+            )
+        })
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens are refilled at this rate per second, up to this number of tokens
+    rate: usize,
+    /// Negative, if the tokens are reserved by waiting updates
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Rate of updates, limited by a token bucket, which holds up to a second of updates
+#[derive(Debug, Default)]
+pub struct UpdateRateLimiter {
+    bucket: Mutex<Option<TokenBucket>>,
+    waiting: AtomicUsize,
+    rejected: AtomicUsize,
+}
+
+impl UpdateRateLimiter {
+    /// Reserve a token, returns how long to wait for it, or how long to wait before retrying,
+    /// if the wait would be longer than `timeout`
+    fn reserve(&self, rate: usize, timeout: Duration) -> Result<Duration, Duration> {
+        let now = Instant::now();
+        let mut bucket = self.bucket.lock();
+        let bucket = match bucket.as_mut() {
+            Some(bucket) if bucket.rate == rate => bucket,
+            // Started or reconfigured with a full bucket
+            _ => bucket.insert(TokenBucket {
+                rate,
+                tokens: rate as f64,
+                updated: now,
+            }),
+        };
+
+        let refilled = now.duration_since(bucket.updated).as_secs_f64() * rate as f64;
+        bucket.tokens = (bucket.tokens + refilled).min(rate as f64);
+        bucket.updated = now;
+
+        let tokens = bucket.tokens - 1.0;
+        let wait = Duration::from_secs_f64((-tokens).max(0.0) / rate as f64);
+        if wait > timeout {
+            return Err(wait);
+        }
+        bucket.tokens = tokens;
+        Ok(wait)
+    }
+
+    /// Take a token of `rate` updates per second.
+    ///
+    /// If no token is left, waits for the next one, unless it comes later than `timeout`.
+    pub async fn acquire(&self, rate: usize, timeout: Duration) -> CollectionResult<()> {
+        match self.reserve(rate, timeout) {
+            Ok(wait) if wait.is_zero() => Ok(()),
+            Ok(wait) => {
+                let _waiting = WaitingGuard::new(&self.waiting);
+                tokio::time::sleep(wait).await;
+                Ok(())
+            }
+            Err(retry_after) => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                Err(CollectionError::rate_limited(
+                    format!("Too many updates, `max_updates_per_second` is {rate}"),
+                    retry_after,
+                ))
+            }
+        }
+    }
+
+    /// Tokens left, if updates were limited by the `rate`
+    fn tokens_available(&self, rate: usize) -> f64 {
+        match self.bucket.lock().as_ref() {
+            Some(bucket) if bucket.rate == rate => {
+                let refilled = bucket.updated.elapsed().as_secs_f64() * rate as f64;
+                (bucket.tokens + refilled).min(rate as f64)
+            }
+            _ => rate as f64,
+        }
+    }
+}
+
+/// Utilization of the limiters, `max_updates_per_second` is the currently configured limit
+pub fn rate_limits_telemetry(
+    searches: &SearchConcurrencyLimiter,
+    updates: &UpdateRateLimiter,
+    max_updates_per_second: Option<usize>,
+) -> RateLimitsTelemetry {
+    RateLimitsTelemetry {
+        searches_in_flight: searches.in_flight.load(Ordering::Relaxed),
+        searches_waiting: searches.waiting.load(Ordering::Relaxed),
+        searches_rejected: searches.rejected.load(Ordering::Relaxed),
+        updates_waiting: updates.waiting.load(Ordering::Relaxed),
+        updates_rejected: updates.rejected.load(Ordering::Relaxed),
+        update_tokens_available: max_updates_per_second.map(|rate| updates.tokens_available(rate)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_update_token_bucket() {
+        let limiter = UpdateRateLimiter::default();
+
+        // A second of updates is allowed at once
+        for _ in 0..10 {
+            limiter.acquire(10, Duration::ZERO).await.unwrap();
+        }
+        let err = limiter.acquire(10, Duration::ZERO).await.unwrap_err();
+        match err {
+            CollectionError::RateLimited { retry_after, .. } => {
+                assert!(retry_after > Duration::from_millis(80), "{retry_after:?}");
+                assert!(retry_after <= Duration::from_millis(100), "{retry_after:?}");
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+
+        // Updates wait for the next token within the timeout
+        let start = Instant::now();
+        limiter
+            .acquire(10, Duration::from_millis(150))
+            .await
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(80));
+
+        // Waiting updates reserve tokens, so the next updates wait longer
+        let (first, second) = tokio::join!(
+            limiter.acquire(10, Duration::from_millis(150)),
+            limiter.acquire(10, Duration::from_millis(150)),
+        );
+        assert!(first.is_ok());
+        assert!(second.is_err());
+
+        // Changed limit starts with a full bucket
+        for _ in 0..20 {
+            limiter.acquire(20, Duration::ZERO).await.unwrap();
+        }
+        assert!(limiter.acquire(20, Duration::ZERO).await.is_err());
+        assert_eq!(limiter.rejected.load(Ordering::Relaxed), 3);
+
+        // Cancelled update is no longer waiting
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            limiter.acquire(20, Duration::from_secs(1)),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        assert_eq!(limiter.waiting.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_concurrency_limit() {
+        let limiter = SearchConcurrencyLimiter::default();
+
+        let first = limiter.acquire(2, Duration::ZERO).await.unwrap();
+        let second = limiter.acquire(2, Duration::ZERO).await.unwrap();
+        assert!(limiter.acquire(2, Duration::ZERO).await.is_err());
+        // Higher limit applies at once
+        let third = limiter.acquire(3, Duration::ZERO).await.unwrap();
+        drop(third);
+
+        // Waiting search takes the slot of a finished one
+        let waiting = limiter.acquire(2, Duration::from_secs(10));
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(first);
+        };
+        let (waited, ()) = tokio::join!(waiting, release);
+        let waited = waited.unwrap();
+
+        let start = Instant::now();
+        assert!(limiter
+            .acquire(2, Duration::from_millis(100))
+            .await
+            .is_err());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let telemetry = rate_limits_telemetry(&limiter, &UpdateRateLimiter::default(), None);
+        assert_eq!(telemetry.searches_in_flight, 2);
+        assert_eq!(telemetry.searches_waiting, 0);
+        assert_eq!(telemetry.searches_rejected, 2);
+        assert_eq!(telemetry.update_tokens_available, None);
+
+        // Cancelled search is no longer waiting
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(10),
+            limiter.acquire(2, Duration::from_secs(1)),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        assert_eq!(limiter.waiting.load(Ordering::Relaxed), 0);
+
+        drop((second, waited));
+        assert_eq!(limiter.in_flight.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,53 @@
+//! Memory and disk usage of collections, reported for capacity planning.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use segment::types::SegmentResourceUsage;
+use serde::{Deserialize, Serialize};
+
+use crate::shards::shard::ShardId;
+
+/// Memory and disk usage of a local shard, in bytes
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct ShardResourceUsage {
+    pub shard_id: ShardId,
+    /// Usage of the segments of the shard
+    pub segments: SegmentResourceUsage,
+    /// Size of the write-ahead log of the shard
+    pub wal_bytes: usize,
+}
+
+/// Memory and disk usage of the local shards of a collection, in bytes
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+pub struct CollectionResourceUsage {
+    /// Usage of the segments of all local shards
+    pub segments: SegmentResourceUsage,
+    /// Size of the write-ahead logs of all local shards
+    pub wal_bytes: usize,
+    /// Size of the collection snapshots, stored on this peer
+    pub snapshots_bytes: usize,
+    /// Usage of each local shard
+    pub shards: Vec<ShardResourceUsage>,
+    /// Time of computing the usage, it is refreshed once the cached value gets older than
+    /// the configured interval
+    pub computed_at: DateTime<Utc>,
+}
+
+impl CollectionResourceUsage {
+    pub fn new(mut shards: Vec<ShardResourceUsage>, snapshots_bytes: usize) -> Self {
+        shards.sort_by_key(|shard| shard.shard_id);
+        let mut segments = SegmentResourceUsage::default();
+        let mut wal_bytes = 0;
+        for shard in &shards {
+            segments.merge(&shard.segments);
+            wal_bytes += shard.wal_bytes;
+        }
+        Self {
+            segments,
+            wal_bytes,
+            snapshots_bytes,
+            shards,
+            computed_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,450 @@
+//! Self-test of the recall of approximate search.
+//!
+//! Stored points are taken as queries: each of them is searched exactly and approximately within
+//! its segment, and the neighbours found by both searches are compared. The test is throttled, so
+//! that it doesn't disturb the regular traffic: sampled points are searched one at a time, and
+//! each search is followed by a pause as long as the search itself.
+
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::SeedableRng;
+use schemars::JsonSchema;
+use segment::types::{
+    PointIdType, QuantizationSearchParams, ScoredPoint, SearchParams, SegmentType, WithPayload,
+};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use validator::Validate;
+
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentId};
+use crate::operations::types::CollectionResult;
+use crate::shards::shard::ShardId;
+
+const fn default_sample_size() -> usize {
+    100
+}
+
+const fn default_limit() -> usize {
+    10
+}
+
+/// Recall self-test of approximate search on the stored points of a collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct RecallTestRequest {
+    /// Number of stored points to search with, sampled from all local shards. Default: 100
+    #[serde(default = "default_sample_size")]
+    #[validate(range(min = 1, max = 1000))]
+    pub sample_size: usize,
+    /// Number of nearest neighbours to compare, recall@`limit` is measured. Default: 10
+    #[serde(default = "default_limit")]
+    #[validate(range(min = 1, max = 100))]
+    pub limit: usize,
+    /// Name of the vector to test. Default vector, if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub using: Option<String>,
+    /// Params of the approximate search, e.g. to compare values of `hnsw_ef`.
+    /// Defaults of the collection, if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub params: Option<SearchParams>,
+    /// Seed of the sampling, to repeat the test with the same points. Random, if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+/// Latency of searches, in microseconds
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Default, PartialEq)]
+pub struct LatencyStats {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_micros: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p50_micros: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p95_micros: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_micros: Option<f32>,
+}
+
+impl LatencyStats {
+    fn new(durations: &[Duration]) -> Self {
+        if durations.is_empty() {
+            return Self::default();
+        }
+        let durations = durations.iter().copied().sorted().collect_vec();
+        let micros = |duration: Duration| Some(duration.as_secs_f32() * 1_000_000.0);
+        // Nearest-rank percentile
+        let percentile = |percent: usize| {
+            let rank = (durations.len() * percent + 99) / 100;
+            durations[rank.max(1) - 1]
+        };
+        let total: Duration = durations.iter().sum();
+        Self {
+            avg_micros: micros(total / durations.len() as u32),
+            p50_micros: micros(percentile(50)),
+            p95_micros: micros(percentile(95)),
+            max_micros: micros(durations[durations.len() - 1]),
+        }
+    }
+}
+
+/// Recall of approximate search within a segment of a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct SegmentRecall {
+    pub shard_id: ShardId,
+    /// Id of the segment within the shard
+    pub segment_id: SegmentId,
+    pub segment_type: SegmentType,
+    /// Whether the vector is indexed in the segment. Search of not indexed vectors is exact.
+    pub vector_indexed: bool,
+    /// Number of points in the segment
+    pub points_count: usize,
+    /// Number of sampled points of the segment, which have neighbours in it
+    pub samples: usize,
+    /// Mean recall@`limit` of the samples. Absent, if there are no samples
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recall: Option<f64>,
+    pub exact_latency: LatencyStats,
+    pub approximate_latency: LatencyStats,
+}
+
+/// Recall of approximate search of a collection, measured on the points of its local shards
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct RecallTestReport {
+    /// Name of the tested vector
+    pub using: String,
+    /// Number of compared neighbours
+    pub limit: usize,
+    /// Number of sampled points, which have neighbours in their segments
+    pub samples: usize,
+    /// Mean recall@`limit` of all samples. Absent, if there are no samples
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recall: Option<f64>,
+    pub exact_latency: LatencyStats,
+    pub approximate_latency: LatencyStats,
+    /// Recall of each segment
+    pub segments: Vec<SegmentRecall>,
+}
+
+/// Segment of a local shard to sample points from
+pub(crate) struct RecallTestSegment {
+    pub shard_id: ShardId,
+    pub segment_id: SegmentId,
+    pub segment: LockedSegment,
+}
+
+/// Measurements of a single sampled point
+struct SampleMeasurement {
+    recall: Option<f64>,
+    exact_latency: Duration,
+    approximate_latency: Duration,
+}
+
+/// Measurements of the samples of a segment, or of all of them
+#[derive(Default)]
+struct Measurements {
+    recalls: Vec<f64>,
+    exact_latencies: Vec<Duration>,
+    approximate_latencies: Vec<Duration>,
+}
+
+impl Measurements {
+    fn add(&mut self, measurement: SampleMeasurement) {
+        // Points without neighbours have no recall, but are searched all the same
+        self.recalls.extend(measurement.recall);
+        self.exact_latencies.push(measurement.exact_latency);
+        self.approximate_latencies
+            .push(measurement.approximate_latency);
+    }
+
+    fn extend(&mut self, other: &Measurements) {
+        self.recalls.extend_from_slice(&other.recalls);
+        self.exact_latencies
+            .extend_from_slice(&other.exact_latencies);
+        self.approximate_latencies
+            .extend_from_slice(&other.approximate_latencies);
+    }
+
+    fn mean_recall(&self) -> Option<f64> {
+        (!self.recalls.is_empty())
+            .then(|| self.recalls.iter().sum::<f64>() / self.recalls.len() as f64)
+    }
+}
+
+/// Fraction of the exact neighbours of the `query_id` point, found by approximate search.
+///
+/// The query point itself is excluded from both results, only the first `limit` of the other
+/// points are compared. `None`, if there are no exact neighbours.
+pub fn recall_at_k(
+    query_id: PointIdType,
+    exact: &[ScoredPoint],
+    approximate: &[ScoredPoint],
+    limit: usize,
+) -> Option<f64> {
+    let neighbours = |points: &[ScoredPoint]| -> HashSet<PointIdType> {
+        points
+            .iter()
+            .map(|point| point.id)
+            .filter(|id| *id != query_id)
+            .take(limit)
+            .collect()
+    };
+    let exact = neighbours(exact);
+    if exact.is_empty() {
+        return None;
+    }
+    let found = neighbours(approximate).intersection(&exact).count();
+    Some(found as f64 / exact.len() as f64)
+}
+
+/// Split `sample_size` among segments proportionally to their number of points,
+/// rounding by the largest remainder
+fn split_sample_size(sample_size: usize, points_counts: &[usize]) -> Vec<usize> {
+    let total: usize = points_counts.iter().sum();
+    if total == 0 {
+        return vec![0; points_counts.len()];
+    }
+    let sample_size = sample_size.min(total);
+
+    let mut sizes = points_counts
+        .iter()
+        .map(|count| sample_size * count / total)
+        .collect_vec();
+    let missing = sample_size - sizes.iter().sum::<usize>();
+    let by_remainder = points_counts
+        .iter()
+        .enumerate()
+        .sorted_by_key(|(_, count)| std::cmp::Reverse(sample_size * *count % total))
+        .map(|(idx, _)| idx)
+        .take(missing)
+        .collect_vec();
+    for idx in by_remainder {
+        sizes[idx] += 1;
+    }
+    sizes
+}
+
+/// Search for the neighbours of the stored point exactly and approximately.
+///
+/// `None`, if the point is removed from the segment meanwhile.
+fn measure_sample(
+    segment: &LockedSegment,
+    vector_name: &str,
+    point_id: PointIdType,
+    limit: usize,
+    params: Option<SearchParams>,
+    is_stopped: &AtomicBool,
+) -> CollectionResult<Option<SampleMeasurement>> {
+    let segment = segment.get();
+    let segment = segment.read();
+    let Some(vector) = segment.vector(vector_name, point_id)? else {
+        return Ok(None);
+    };
+
+    // The point itself is found by both searches, one more point is requested instead of it
+    let top = limit + 1;
+    let exact_params = SearchParams {
+        exact: true,
+        quantization: Some(QuantizationSearchParams {
+            ignore: true,
+            rescore: false,
+            oversampling: None,
+        }),
+        ..params.unwrap_or_default()
+    };
+    let search = |params: &SearchParams| -> CollectionResult<(Vec<ScoredPoint>, Duration)> {
+        let started = Instant::now();
+        let result = segment.search(
+            vector_name,
+            &vector,
+            &WithPayload::from(false),
+            &false.into(),
+            None,
+            top,
+            Some(params),
+            is_stopped,
+        )?;
+        Ok((result, started.elapsed()))
+    };
+
+    let (exact, exact_latency) = search(&exact_params)?;
+    let (approximate, approximate_latency) = search(&params.unwrap_or_default())?;
+    Ok(Some(SampleMeasurement {
+        recall: recall_at_k(point_id, &exact, &approximate, limit),
+        exact_latency,
+        approximate_latency,
+    }))
+}
+
+/// Measure recall of approximate search on points, sampled from the `segments`.
+///
+/// Samples are searched on the `search_runtime` one at a time, and each of them is followed by
+/// a pause as long as its searches, so that the test takes at most half of a search thread.
+pub(crate) async fn run_recall_test(
+    segments: Vec<RecallTestSegment>,
+    request: &RecallTestRequest,
+    vector_name: &str,
+    search_runtime: &Handle,
+    is_stopped: Arc<AtomicBool>,
+) -> CollectionResult<RecallTestReport> {
+    let mut rng = match request.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let points_counts = segments
+        .iter()
+        .map(|test_segment| test_segment.segment.get().read().available_point_count())
+        .collect_vec();
+    let sample_sizes = split_sample_size(request.sample_size, &points_counts);
+
+    let mut total = Measurements::default();
+    let mut segment_recalls = Vec::with_capacity(segments.len());
+    for ((test_segment, points_count), sample_size) in
+        segments.into_iter().zip(points_counts).zip(sample_sizes)
+    {
+        let (segment_type, vector_indexed, sample) = {
+            let segment = test_segment.segment.get();
+            let segment = segment.read();
+            let vector_indexed = segment
+                .config()
+                .vector_data
+                .get(vector_name)
+                .is_some_and(|config| config.index.is_indexed());
+            let sample = segment.iter_points().choose_multiple(&mut rng, sample_size);
+            (segment.segment_type(), vector_indexed, sample)
+        };
+
+        let mut measurements = Measurements::default();
+        for point_id in sample {
+            let started = Instant::now();
+            let measurement = search_runtime
+                .spawn_blocking({
+                    let segment = test_segment.segment.clone();
+                    let vector_name = vector_name.to_string();
+                    let limit = request.limit;
+                    let params = request.params;
+                    let is_stopped = is_stopped.clone();
+                    move || {
+                        measure_sample(&segment, &vector_name, point_id, limit, params, &is_stopped)
+                    }
+                })
+                .await??;
+            if let Some(measurement) = measurement {
+                measurements.add(measurement);
+            }
+            tokio::time::sleep(started.elapsed()).await;
+        }
+
+        total.extend(&measurements);
+        segment_recalls.push(SegmentRecall {
+            shard_id: test_segment.shard_id,
+            segment_id: test_segment.segment_id,
+            segment_type,
+            vector_indexed,
+            points_count,
+            samples: measurements.recalls.len(),
+            recall: measurements.mean_recall(),
+            exact_latency: LatencyStats::new(&measurements.exact_latencies),
+            approximate_latency: LatencyStats::new(&measurements.approximate_latencies),
+        });
+    }
+
+    Ok(RecallTestReport {
+        using: vector_name.to_string(),
+        limit: request.limit,
+        samples: total.recalls.len(),
+        recall: total.mean_recall(),
+        exact_latency: LatencyStats::new(&total.exact_latencies),
+        approximate_latency: LatencyStats::new(&total.approximate_latencies),
+        segments: segment_recalls,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::ExtendedPointId;
+
+    use super::*;
+
+    fn scored(ids: &[u64]) -> Vec<ScoredPoint> {
+        ids.iter()
+            .enumerate()
+            .map(|(rank, id)| ScoredPoint {
+                id: ExtendedPointId::from(*id),
+                version: 0,
+                score: -(rank as f32),
+                payload: None,
+                vector: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recall_at_k() {
+        let query = ExtendedPointId::from(0);
+
+        // The query point is excluded, the next point is compared instead of it
+        let exact = scored(&[0, 1, 2, 3, 4]);
+        assert_eq!(
+            recall_at_k(query, &exact, &scored(&[0, 1, 2, 3, 4]), 4),
+            Some(1.0)
+        );
+        assert_eq!(
+            recall_at_k(query, &exact, &scored(&[1, 2, 3, 4, 5]), 4),
+            Some(1.0)
+        );
+        assert_eq!(
+            recall_at_k(query, &exact, &scored(&[0, 1, 3, 5, 6]), 4),
+            Some(0.5)
+        );
+        assert_eq!(
+            recall_at_k(query, &exact, &scored(&[7, 8, 9, 10, 11]), 4),
+            Some(0.0)
+        );
+
+        // Neighbours beyond the limit are not counted
+        assert_eq!(
+            recall_at_k(query, &exact, &scored(&[1, 5, 6, 7, 2]), 2),
+            Some(0.5)
+        );
+
+        // Segments with fewer points than the limit are compared on the points they have
+        let exact = scored(&[0, 1, 2]);
+        assert_eq!(recall_at_k(query, &exact, &scored(&[0, 2]), 10), Some(0.5));
+
+        // Point without neighbours has no recall
+        assert_eq!(recall_at_k(query, &scored(&[0]), &scored(&[0]), 10), None);
+    }
+
+    #[test]
+    fn test_split_sample_size() {
+        assert_eq!(split_sample_size(10, &[50, 30, 20]), vec![5, 3, 2]);
+        assert_eq!(split_sample_size(10, &[1, 1, 1]), vec![1, 1, 1]);
+        assert_eq!(split_sample_size(10, &[0, 0]), vec![0, 0]);
+        assert_eq!(split_sample_size(2, &[10, 5, 5]), vec![1, 1, 0]);
+        assert_eq!(split_sample_size(3, &[7, 7, 0]), vec![2, 1, 0]);
+        let sizes = split_sample_size(100, &[333, 333, 334, 1]);
+        assert_eq!(sizes.iter().sum::<usize>(), 100);
+        assert_eq!(sizes[3], 0);
+    }
+
+    #[test]
+    fn test_latency_stats() {
+        assert_eq!(LatencyStats::new(&[]), LatencyStats::default());
+
+        let durations = (1..=100).rev().map(Duration::from_micros).collect_vec();
+        let stats = LatencyStats::new(&durations);
+        let round = |micros: Option<f32>| micros.map(f32::round);
+        assert!((stats.avg_micros.unwrap() - 50.5).abs() < 0.01);
+        assert_eq!(round(stats.p50_micros), Some(50.0));
+        assert_eq!(round(stats.p95_micros), Some(95.0));
+        assert_eq!(round(stats.max_micros), Some(100.0));
+    }
+}
@@ -0,0 +1,57 @@
+//! Collects the work of segment searches done for a request.
+//!
+//! Segments are searched on blocking threads, which report their [`SearchStats`] back to the
+//! async task of the request. Searches of remote shards are not collected.
+
+use std::cell::Cell;
+use std::future::Future;
+
+use segment::common::search_stats::SearchStats;
+
+tokio::task_local! {
+    static COLLECTED: Cell<SearchStats>;
+}
+
+/// Run the `search`, collecting the stats reported by it within this task.
+///
+/// Collected stats are reported to the enclosing collector too, if any.
+pub async fn collect_search_stats<F: Future>(search: F) -> (F::Output, SearchStats) {
+    let (output, stats) = COLLECTED
+        .scope(Cell::new(SearchStats::default()), async {
+            let output = search.await;
+            (output, COLLECTED.with(Cell::get))
+        })
+        .await;
+    report_search_stats(stats);
+    (output, stats)
+}
+
+/// Add the stats to the collector of this task, if any
+pub fn report_search_stats(stats: SearchStats) {
+    let _ = COLLECTED.try_with(|collected| collected.set(collected.get() + stats));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_collect_nested() {
+        let stats = |graph_hops| SearchStats {
+            graph_hops,
+            ..Default::default()
+        };
+
+        // Not collected
+        report_search_stats(stats(1));
+
+        let ((_, inner), outer) = collect_search_stats(async {
+            report_search_stats(stats(2));
+            tokio::task::yield_now().await;
+            collect_search_stats(async { report_search_stats(stats(3)) }).await
+        })
+        .await;
+        assert_eq!(inner, stats(3));
+        assert_eq!(outer, stats(5));
+    }
+}
@@ -18,6 +18,11 @@ impl<T> StoppableTaskHandle<T> {
         self.join_handle.is_finished()
     }
 
+    /// Flag, which asks the task to stop while the task is not finished
+    pub fn stop_flag(&self) -> Weak<AtomicBool> {
+        self.stopped.clone()
+    }
+
     pub fn ask_to_stop(&self) {
         if let Some(v) = self.stopped.upgrade() {
             v.store(true, Ordering::Relaxed);
@@ -1,28 +1,35 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
 use std::io::{Read, Write};
-use std::num::NonZeroU32;
+use std::num::{NonZeroU32, NonZeroU64};
 use std::path::Path;
 
 use atomicwrites::AtomicFile;
-use atomicwrites::OverwriteBehavior::AllowOverwrite;
+use atomicwrites::OverwriteBehavior::{AllowOverwrite, DisallowOverwrite};
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
-use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
+use segment::common::find_non_finite_element;
+use segment::data_types::vectors::{VectorElementType, DEFAULT_VECTOR_NAME};
 use segment::types::{
-    HnswConfig, Indexes, QuantizationConfig, VectorDataConfig, VectorStorageType,
+    Distance, HnswConfig, Indexes, PayloadKeyType, QuantizationConfig, SparseIndexConfig,
+    SparseIndexType, SparseVectorDataConfig, VectorDataConfig, VectorStorageDatatype,
+    VectorStorageType,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use validator::Validate;
 use wal::WalOptions;
 
+use crate::config_migration::{self, CONFIG_VERSION_KEY, CURRENT_CONFIG_VERSION};
 use crate::operations::config_diff::{DiffConfig, QuantizationConfigDiff};
+use crate::operations::strict_mode::StrictModeConfig;
 use crate::operations::types::{
-    CollectionError, CollectionResult, VectorParams, VectorParamsDiff, VectorsConfig,
-    VectorsConfigDiff,
+    CollectionError, CollectionResult, EffectiveVectorConfig, SparseVectorParams, VectorParams,
+    VectorParamsDiff, VectorsConfig, VectorsConfigDiff,
 };
 use crate::operations::validation;
 use crate::optimizers_builder::OptimizersConfig;
+use crate::wal::{WalCompression, WalRetention};
 
 pub const COLLECTION_CONFIG_FILE: &str = "config.json";
 
@@ -33,6 +40,30 @@ pub struct WalConfig {
     pub wal_capacity_mb: usize,
     /// Number of WAL segments to create ahead of actually used ones
     pub wal_segments_ahead: usize,
+    /// If set - large WAL records are stored in compressed form.
+    /// Reduces disk usage for bulk upserts at the cost of some CPU time.
+    /// Default: disabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_compression: Option<WalCompression>,
+    /// Number of the latest acknowledged operations to keep in WAL.
+    /// Retained operations can be used to catch up replicas or to inspect recent changes.
+    /// Default: 0 - acknowledged operations are removed as soon as possible
+    #[serde(default)]
+    pub wal_retain_ops: u64,
+    /// Keep acknowledged operations in WAL for at least this number of seconds.
+    /// If both `wal_retain_ops` and `wal_retain_secs` are set, operation is kept while any of them requires it.
+    /// Default: 0 - acknowledged operations are removed as soon as possible
+    #[serde(default)]
+    pub wal_retain_secs: u64,
+}
+
+impl From<&WalConfig> for WalRetention {
+    fn from(config: &WalConfig) -> Self {
+        WalRetention {
+            retain_ops: config.wal_retain_ops,
+            retain_secs: config.wal_retain_secs,
+        }
+    }
 }
 
 impl From<&WalConfig> for WalOptions {
@@ -49,19 +80,41 @@ impl Default for WalConfig {
         WalConfig {
             wal_capacity_mb: 32,
             wal_segments_ahead: 0,
+            wal_compression: None,
+            wal_retain_ops: 0,
+            wal_retain_secs: 0,
         }
     }
 }
 
+/// Method of distributing points of a collection among its shards
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardingMethod {
+    /// Points are distributed among all shards of the collection by their ids
+    #[default]
+    Auto,
+    /// Shards are created for shard keys, points are distributed among the shards of the shard
+    /// key, specified with each update
+    Custom,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub struct CollectionParams {
     /// Configuration of the vector storage
     #[validate]
     pub vectors: VectorsConfig,
-    /// Number of shards the collection has
+    /// Configuration of the sparse vector storage, names must differ from names of dense vectors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
+    /// Number of shards the collection has.
+    /// With custom sharding - default number of shards of each shard key
     #[serde(default = "default_shard_number")]
     pub shard_number: NonZeroU32,
+    /// Method of distributing points among the shards. Default is `auto`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sharding_method: Option<ShardingMethod>,
     /// Number of replicas for each shard
     #[serde(default = "default_replication_factor")]
     pub replication_factor: NonZeroU32,
@@ -77,16 +130,37 @@ pub struct CollectionParams {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default = "default_on_disk_payload")]
     pub on_disk_payload: bool,
+    /// Payload key which separates tenants of the collection.
+    /// If set, optimizers store vectors of points with the same value of this key next to each other,
+    /// which makes search filtered by a single tenant read less data from disk.
+    /// Existing segments are reordered when they are optimized next time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_key: Option<PayloadKeyType>,
+    /// If true - vectors are stored and returned exactly as they were inserted.
+    /// Otherwise vectors are stored preprocessed for the distance, e.g. normalized for cosine.
+    /// Search results are the same in both cases, scoring original vectors is slower.
+    #[serde(default)]
+    pub store_original_vectors: bool,
+    /// Default timeout of searches in the collection, in seconds.
+    /// If not set, the search timeout of the service is used.
+    /// A search request may set its own timeout instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_timeout_sec: Option<NonZeroU64>,
 }
 
 impl Anonymize for CollectionParams {
     fn anonymize(&self) -> Self {
         CollectionParams {
             vectors: self.vectors.anonymize(),
+            sparse_vectors: self.sparse_vectors.anonymize(),
             shard_number: self.shard_number,
+            sharding_method: self.sharding_method,
             replication_factor: self.replication_factor,
             write_consistency_factor: self.write_consistency_factor,
             on_disk_payload: self.on_disk_payload,
+            tenant_key: self.tenant_key.clone(),
+            store_original_vectors: self.store_original_vectors,
+            search_timeout_sec: self.search_timeout_sec,
         }
     }
 }
@@ -119,25 +193,61 @@ pub struct CollectionConfig {
     pub wal_config: WalConfig,
     #[serde(default)]
     pub quantization_config: Option<QuantizationConfig>,
+    #[serde(default)]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
 }
 
 impl CollectionConfig {
     pub fn save(&self, path: &Path) -> CollectionResult<()> {
         let config_path = path.join(COLLECTION_CONFIG_FILE);
         let af = AtomicFile::new(&config_path, AllowOverwrite);
-        let state_bytes = serde_json::to_vec(self).unwrap();
+        let mut state = serde_json::to_value(self).unwrap();
+        if let Value::Object(state) = &mut state {
+            state.insert(
+                CONFIG_VERSION_KEY.to_string(),
+                Value::from(CURRENT_CONFIG_VERSION),
+            );
+        }
+        let state_bytes = serde_json::to_vec(&state).unwrap();
         af.write(|f| f.write_all(&state_bytes)).map_err(|err| {
             CollectionError::service_error(format!("Can't write {config_path:?}, error: {err}"))
         })?;
         Ok(())
     }
 
+    /// Load the config, configs of older versions are migrated and written back,
+    /// the original config is kept as a backup next to it
     pub fn load(path: &Path) -> CollectionResult<Self> {
         let config_path = path.join(COLLECTION_CONFIG_FILE);
         let mut contents = String::new();
-        let mut file = File::open(config_path)?;
+        let mut file = File::open(&config_path)?;
         file.read_to_string(&mut contents)?;
-        Ok(serde_json::from_str(&contents)?)
+
+        let mut state: Map<String, Value> = serde_json::from_str(&contents)?;
+        let version = config_migration::take_config_version(&mut state)?;
+        let migrated = config_migration::migrate_config(&mut state, version)?;
+        let config: Self = serde_json::from_value(Value::Object(state))?;
+
+        if migrated {
+            log::info!(
+                "Migrated collection config {config_path:?} from version {version} to {CURRENT_CONFIG_VERSION}"
+            );
+            let backup_path = path.join(config_migration::config_backup_file(version));
+            // The earliest original is kept, if migration was interrupted before
+            if !backup_path.exists() {
+                AtomicFile::new(&backup_path, DisallowOverwrite)
+                    .write(|f| f.write_all(contents.as_bytes()))
+                    .map_err(|err| {
+                        CollectionError::service_error(format!(
+                            "Can't write {backup_path:?}, error: {err}"
+                        ))
+                    })?;
+            }
+            config.save(path)?;
+        }
+
+        Ok(config)
     }
 
     /// Check if collection config exists
@@ -151,6 +261,75 @@ impl CollectionConfig {
             validation::warn_validation_errors("Collection configuration file", errs);
         }
     }
+
+    /// Check that configured quantization can be applied to every vector of the collection
+    pub fn check_quantization(&self) -> CollectionResult<()> {
+        for (vector_name, params) in self.params.vectors.params_iter() {
+            let Some(quantization_config) = params
+                .quantization_config
+                .as_ref()
+                .or(self.quantization_config.as_ref())
+            else {
+                continue;
+            };
+            quantization_config
+                .check_distance(params.distance)
+                .and_then(|()| quantization_config.check_vector_dim(params.size.get() as usize))
+                .map_err(|description| {
+                    CollectionError::bad_input(if vector_name == DEFAULT_VECTOR_NAME {
+                        description
+                    } else {
+                        format!("Vector {vector_name}: {description}")
+                    })
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Configs each vector is indexed with, see [`CollectionParams::vector_hnsw_config`]
+    pub fn effective_vectors_config(&self) -> BTreeMap<String, EffectiveVectorConfig> {
+        self.params
+            .vectors
+            .params_iter()
+            .map(|(vector_name, params)| {
+                let config = EffectiveVectorConfig {
+                    hnsw_config: self
+                        .params
+                        .vector_hnsw_config(vector_name, &self.hnsw_config),
+                    quantization_config: self
+                        .params
+                        .vector_quantization_config(vector_name, self.quantization_config.as_ref()),
+                    on_disk: params.on_disk.unwrap_or_default(),
+                };
+                (vector_name.to_string(), config)
+            })
+            .collect()
+    }
+
+    /// Check that distance of every vector of the collection can be used with its datatype
+    pub fn check_vector_datatypes(&self) -> CollectionResult<()> {
+        for (vector_name, params) in self.params.vectors.params_iter() {
+            let datatype = params.datatype.unwrap_or_default();
+            let supported = match params.distance {
+                Distance::Cosine | Distance::Euclid | Distance::Dot | Distance::Manhattan => true,
+                Distance::Hamming => datatype == VectorStorageDatatype::Uint8,
+            };
+            if !supported {
+                let description = format!(
+                    "{:?} distance can't be used with {datatype:?} vectors, use uint8 datatype",
+                    params.distance,
+                );
+                return Err(CollectionError::bad_input(
+                    if vector_name == DEFAULT_VECTOR_NAME {
+                        description
+                    } else {
+                        format!("Vector {vector_name}: {description}")
+                    },
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl CollectionParams {
@@ -167,6 +346,51 @@ impl CollectionParams {
             })
     }
 
+    /// Check that a vector of a search query can be compared with the named vector of the
+    /// collection: it must have the configured dimension and only finite components.
+    ///
+    /// A vector of zeros is a valid query. With cosine distance it can't be normalized,
+    /// so it scores 0.0 with every point.
+    pub fn check_query_vector(
+        &self,
+        vector_name: &str,
+        vector: &[VectorElementType],
+    ) -> CollectionResult<()> {
+        let params = self.get_vector_params(vector_name)?;
+        let dim = params.size.get() as usize;
+        let described_name = if vector_name == DEFAULT_VECTOR_NAME {
+            "default vector".to_string()
+        } else {
+            format!("vector `{vector_name}`")
+        };
+
+        if vector.is_empty() {
+            return Err(CollectionError::bad_input(format!(
+                "Query vector for {described_name} is empty, expected dim: {dim}",
+            )));
+        }
+        // Multi-vectors consist of any positive number of concatenated sub-vectors
+        let is_valid_dim = if params.multivector_config.is_some() {
+            vector.len() % dim == 0
+        } else {
+            vector.len() == dim
+        };
+        if !is_valid_dim {
+            return Err(CollectionError::bad_input(format!(
+                "Wrong dimension of query vector for {described_name}: expected dim: {dim}, got {}",
+                vector.len(),
+            )));
+        }
+        if let Some(position) = find_non_finite_element(vector) {
+            return Err(CollectionError::bad_input(format!(
+                "Query vector for {described_name} has component {} at position {position}, \
+                 vector components must be finite numbers",
+                vector[position],
+            )));
+        }
+        Ok(())
+    }
+
     fn get_vector_params_mut(&mut self, vector_name: &str) -> CollectionResult<&mut VectorParams> {
         self.vectors
             .get_params_mut(vector_name)
@@ -209,6 +433,9 @@ impl CollectionParams {
                     QuantizationConfigDiff::Product(product) => {
                         Some(QuantizationConfig::Product(product))
                     }
+                    QuantizationConfigDiff::Binary(binary) => {
+                        Some(QuantizationConfig::Binary(binary))
+                    }
                     QuantizationConfigDiff::Disabled(_) => None,
                 }
             }
@@ -220,6 +447,32 @@ impl CollectionParams {
         Ok(())
     }
 
+    /// HNSW config of the vector: its own params, if set, applied to the `collection_hnsw`
+    pub fn vector_hnsw_config(
+        &self,
+        vector_name: &str,
+        collection_hnsw: &HnswConfig,
+    ) -> HnswConfig {
+        self.vectors
+            .get_params(vector_name)
+            .and_then(|params| params.hnsw_config)
+            .and_then(|vector_hnsw| vector_hnsw.update(collection_hnsw).ok())
+            .unwrap_or_else(|| collection_hnsw.clone())
+    }
+
+    /// Quantization config of the vector: its own, if set, or the `collection_quantization`
+    pub fn vector_quantization_config(
+        &self,
+        vector_name: &str,
+        collection_quantization: Option<&QuantizationConfig>,
+    ) -> Option<QuantizationConfig> {
+        self.vectors
+            .get_params(vector_name)
+            .and_then(|params| params.quantization_config.as_ref())
+            .or(collection_quantization)
+            .cloned()
+    }
+
     /// Convert into unoptimized named vector data configs
     ///
     /// It is the job of the segment optimizer to change this configuration with optimized settings
@@ -244,9 +497,57 @@ impl CollectionParams {
                         } else {
                             VectorStorageType::Memory
                         },
+                        datatype: params.datatype,
+                        multivector_config: params.multivector_config,
+                        store_original_vectors: self.store_original_vectors,
                     },
                 )
             })
             .collect())
     }
+
+    /// Convert into sparse vector data configs of appendable segments
+    ///
+    /// Appendable segments always keep the inverted index in RAM, the segment optimizer
+    /// moves it to disk if configured.
+    pub fn into_sparse_vector_data(&self) -> HashMap<String, SparseVectorDataConfig> {
+        self.sparse_vectors
+            .iter()
+            .flatten()
+            .map(|(name, params)| {
+                (
+                    name.clone(),
+                    SparseVectorDataConfig {
+                        index: SparseIndexConfig {
+                            full_scan_threshold: params
+                                .index
+                                .and_then(|index| index.full_scan_threshold),
+                            index_type: SparseIndexType::MutableRam,
+                        },
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Whether the inverted index of the sparse vector should be stored on disk in optimized segments
+    pub fn sparse_index_on_disk(&self, vector_name: &str) -> bool {
+        self.sparse_vectors
+            .as_ref()
+            .and_then(|sparse_vectors| sparse_vectors.get(vector_name))
+            .and_then(|params| params.index)
+            .and_then(|index| index.on_disk)
+            .unwrap_or_default()
+    }
+
+    /// Names of the vectors, configured to be stored on disk
+    ///
+    /// Mmap pages of these vectors are loaded lazily, on the first search against them.
+    pub fn on_disk_vectors(&self) -> HashSet<String> {
+        self.vectors
+            .params_iter()
+            .filter(|(_, params)| params.on_disk.unwrap_or_default())
+            .map(|(vector_name, _)| vector_name.to_owned())
+            .collect()
+    }
 }
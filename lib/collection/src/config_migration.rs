@@ -0,0 +1,128 @@
+//! Versioning of the collection config, stored on disk.
+//!
+//! The stored config carries `config_version`. Configs of older versions are upgraded on load,
+//! one version at a time, by the migrations of the registry below, and are written back in the
+//! current version. Configs, stored before the versioning was introduced, have no version and are
+//! treated as version 0. They may have any of the historical shapes, so every migration only
+//! changes the fields, which are still in their old shape.
+
+use serde_json::{json, Map, Value};
+
+use crate::config::COLLECTION_CONFIG_FILE;
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Key of the version in the stored config
+pub const CONFIG_VERSION_KEY: &str = "config_version";
+
+/// Migration of the config from the version, equal to its position in [`MIGRATIONS`],
+/// to the next one
+type Migration = fn(&mut Map<String, Value>);
+
+const MIGRATIONS: &[Migration] = &[
+    migrate_single_vector_params,
+    migrate_kb_threshold_names,
+    migrate_required_defaults,
+];
+
+/// Version of the config, stored by this build
+pub const CURRENT_CONFIG_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// Default of `max_optimization_threads` in the service config
+const DEFAULT_MAX_OPTIMIZATION_THREADS: usize = 1;
+
+/// Name of the backup of the original config, made before it is overwritten by the migrated one
+pub fn config_backup_file(version: u64) -> String {
+    format!("{COLLECTION_CONFIG_FILE}.v{version}.bak")
+}
+
+/// Remove the version from the stored config and return it
+pub fn take_config_version(config: &mut Map<String, Value>) -> CollectionResult<u64> {
+    match config.remove(CONFIG_VERSION_KEY) {
+        None => Ok(0),
+        Some(version) => version.as_u64().ok_or_else(|| {
+            CollectionError::service_error(format!(
+                "Invalid `{CONFIG_VERSION_KEY}` of collection config: {version}"
+            ))
+        }),
+    }
+}
+
+/// Upgrade the stored config from `version` to the current one.
+///
+/// Returns whether any migration was applied.
+pub fn migrate_config(config: &mut Map<String, Value>, version: u64) -> CollectionResult<bool> {
+    if version > CURRENT_CONFIG_VERSION {
+        return Err(CollectionError::service_error(format!(
+            "Collection config has version {version}, but this version of Qdrant supports \
+             collection configs up to version {CURRENT_CONFIG_VERSION}. \
+             The collection was written by a newer version of Qdrant: upgrade Qdrant to load it, \
+             or restore the collection from a snapshot, made with this version"
+        )));
+    }
+
+    for (from_version, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        log::debug!(
+            "Migrating collection config from version {from_version} to {}",
+            from_version + 1,
+        );
+        migration(config);
+    }
+
+    Ok(version < CURRENT_CONFIG_VERSION)
+}
+
+/// Version 0 has a single unnamed vector, configured by `vector_size` and `distance` in the params
+fn migrate_single_vector_params(config: &mut Map<String, Value>) {
+    let Some(Value::Object(params)) = config.get_mut("params") else {
+        return;
+    };
+    if params.contains_key("vectors") {
+        return;
+    }
+    if let (Some(size), Some(distance)) = (params.remove("vector_size"), params.remove("distance"))
+    {
+        params.insert(
+            "vectors".to_string(),
+            json!({ "size": size, "distance": distance }),
+        );
+    }
+}
+
+/// Version 1 has thresholds in kilobytes, named with the `_kb` suffix
+fn migrate_kb_threshold_names(config: &mut Map<String, Value>) {
+    rename_fields(
+        config,
+        "optimizer_config",
+        &[
+            ("max_segment_size_kb", "max_segment_size"),
+            ("memmap_threshold_kb", "memmap_threshold"),
+            ("indexing_threshold_kb", "indexing_threshold"),
+        ],
+    );
+    rename_fields(
+        config,
+        "hnsw_config",
+        &[("full_scan_threshold_kb", "full_scan_threshold")],
+    );
+}
+
+/// Version 2 may lack the fields, which were added without a default
+fn migrate_required_defaults(config: &mut Map<String, Value>) {
+    if let Some(Value::Object(optimizer_config)) = config.get_mut("optimizer_config") {
+        optimizer_config
+            .entry("max_optimization_threads")
+            .or_insert(json!(DEFAULT_MAX_OPTIMIZATION_THREADS));
+    }
+}
+
+/// Rename fields of the `section` of the config, the canonical name wins if both are present
+fn rename_fields(config: &mut Map<String, Value>, section: &str, renames: &[(&str, &str)]) {
+    let Some(Value::Object(section)) = config.get_mut(section) else {
+        return;
+    };
+    for (old_name, new_name) in renames {
+        if let Some(value) = section.remove(*old_name) {
+            section.entry(*new_name).or_insert(value);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use itertools::Itertools;
+use segment::data_types::vectors::{VectorType, DEFAULT_VECTOR_NAME};
+use segment::types::{Condition, Filter, HasIdCondition, PointIdType, ScoredPoint};
+use segment::vector_storage::query::discovery_query::{ContextPair, DiscoveryQuery};
+use tokio::sync::RwLockReadGuard;
+
+use crate::collection::Collection;
+use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::shard_key_selector::ShardSelectorInternal;
+use crate::operations::types::{
+    CollectionError, CollectionResult, CoreSearchRequest, CoreSearchRequestBatch, DiscoverRequest,
+    NamedQuery, QueryEnum, RecommendExample, Record, UsingVector,
+};
+use crate::recommendations::{
+    check_missed_point_ids, retrieve_points_with_locked_collection, CollectionRefHolder,
+};
+
+fn get_search_vector_name(request: &DiscoverRequest) -> String {
+    match &request.lookup_from {
+        None => match &request.using {
+            None => DEFAULT_VECTOR_NAME.to_owned(),
+            Some(UsingVector::Name(vector_name)) => vector_name.clone(),
+        },
+        Some(lookup_from) => match &lookup_from.vector {
+            None => DEFAULT_VECTOR_NAME.to_owned(),
+            Some(vector_name) => vector_name.clone(),
+        },
+    }
+}
+
+/// Search points in a collection by a target and context pairs of examples, which are points of
+/// this or another collection or raw vectors.
+///
+/// Function works in following stages:
+///
+/// - Retrieves vectors of the example points from the lookup collection
+/// - Constructs a discovery query of the target and the context pairs
+/// - Executes it as a search, excluding the example points from the result
+///
+/// # Arguments
+///
+/// * `request` - discovery request
+/// * `collection` - collection to search in
+/// * `collection_by_name` - function to retrieve collection by name, used to retrieve points from other collections
+///
+pub async fn discover<'a, F, Fut>(
+    request: DiscoverRequest,
+    collection: &Collection,
+    collection_by_name: F,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<Vec<ScoredPoint>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
+{
+    if request.context.is_empty() {
+        return Err(CollectionError::BadRequest {
+            description: "At least one context pair required".to_owned(),
+        });
+    }
+    if request.limit == 0 {
+        return Ok(vec![]);
+    }
+
+    let lookup_vector_name = get_search_vector_name(&request);
+
+    let reference_vectors_ids = std::iter::once(&request.target)
+        .chain(request.context.iter().flat_map(|pair| pair.iter()))
+        .filter_map(RecommendExample::as_point_id)
+        .unique()
+        .collect_vec();
+
+    let lookup_collection = match &request.lookup_from {
+        None => CollectionRefHolder::Ref(collection),
+        Some(lookup_from) => match collection_by_name(lookup_from.collection.clone()).await {
+            Some(other_collection) => CollectionRefHolder::Guard(other_collection),
+            None => {
+                return Err(CollectionError::NotFound {
+                    what: format!("Collection {}", lookup_from.collection),
+                })
+            }
+        },
+    };
+
+    let records: HashMap<PointIdType, Record> = retrieve_points_with_locked_collection(
+        lookup_collection,
+        reference_vectors_ids.clone(),
+        vec![lookup_vector_name.clone()],
+        read_consistency,
+        ShardSelectorInternal::All,
+    )
+    .await?
+    .into_iter()
+    .map(|record| (record.id, record))
+    .collect();
+
+    let missed_point_ids = reference_vectors_ids
+        .iter()
+        .copied()
+        .filter(|point_id| !records.contains_key(point_id))
+        .collect_vec();
+    check_missed_point_ids(&missed_point_ids)?;
+
+    let example_vector = |example: &RecommendExample| -> CollectionResult<VectorType> {
+        match example {
+            RecommendExample::PointId(point_id) => records[point_id]
+                .get_vector_by_name(&lookup_vector_name)
+                .cloned()
+                .ok_or_else(|| CollectionError::BadRequest {
+                    description: format!(
+                        "Point {point_id} has no vector with name {lookup_vector_name}"
+                    ),
+                }),
+            RecommendExample::Vector(vector) => Ok(vector.clone()),
+        }
+    };
+
+    let pairs = request
+        .context
+        .iter()
+        .map(|pair| {
+            Ok(ContextPair::new(
+                example_vector(&pair.positive)?,
+                example_vector(&pair.negative)?,
+            ))
+        })
+        .collect::<CollectionResult<_>>()?;
+
+    let vector_name = match &request.using {
+        None => DEFAULT_VECTOR_NAME,
+        Some(UsingVector::Name(name)) => name,
+    };
+
+    let search_request = CoreSearchRequest {
+        query: QueryEnum::Discover(NamedQuery {
+            query: DiscoveryQuery::new(example_vector(&request.target)?, pairs),
+            using: Some(vector_name.to_string()),
+        }),
+        filter: Some(Filter {
+            should: None,
+            must: request
+                .filter
+                .clone()
+                .map(|filter| vec![Condition::Filter(filter)]),
+            min_should: None,
+            must_not: (!reference_vectors_ids.is_empty()).then(|| {
+                vec![Condition::HasId(HasIdCondition {
+                    has_id: reference_vectors_ids.iter().cloned().collect(),
+                })]
+            }),
+        }),
+        with_payload: request.with_payload,
+        with_vector: request.with_vector,
+        params: request.params,
+        limit: request.limit,
+        score_threshold: None,
+        offset: request.offset,
+    };
+
+    let results = collection
+        .core_search_batch(
+            CoreSearchRequestBatch {
+                searches: vec![search_request],
+            },
+            read_consistency,
+            None,
+            None,
+        )
+        .await?;
+    Ok(results.into_iter().next().unwrap_or_default())
+}
@@ -4,7 +4,6 @@ use std::collections::{HashMap, HashSet};
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
 use segment::data_types::groups::GroupId;
-use segment::spaces::tools::{peek_top_largest_iterable, peek_top_smallest_iterable};
 use segment::types::{
     ExtendedPointId, Order, PayloadContainer, PointIdType, ScoreType, ScoredPoint,
 };
@@ -119,12 +118,15 @@ impl GroupsAggregator {
     }
 
     /// Return `max_groups` number of keys of the groups with the best score
+    ///
+    /// Groups with the same best score are ordered by their key, so the same groups are picked
+    /// every time.
     fn best_group_keys(&self) -> impl Iterator<Item = &GroupId> {
         self.group_best_scores
             .iter()
-            .sorted_by_key(|(_, score)| match self.order {
-                Order::LargeBetter => -OrderedFloat(**score),
-                Order::SmallBetter => OrderedFloat(**score),
+            .sorted_by_key(|(key, score)| match self.order {
+                Order::LargeBetter => (-OrderedFloat(**score), *key),
+                Order::SmallBetter => (OrderedFloat(**score), *key),
             })
             .take(self.max_groups)
             .map(|(k, _)| k)
@@ -156,22 +158,23 @@ impl GroupsAggregator {
         &self.all_ids
     }
 
-    /// Returns the best groups sorted by their best hit. The hits are sorted too.
+    /// Returns the best groups sorted by their best hit. The hits are sorted too, hits with the
+    /// same score are ordered by their id.
     pub(super) fn distill(mut self) -> Vec<Group> {
         let best_groups: Vec<_> = self.best_group_keys().cloned().collect();
         let mut groups = Vec::with_capacity(best_groups.len());
 
         for group_key in best_groups {
             let mut group = self.groups.remove(&group_key).unwrap();
-            let scored_points_iter = group.drain().map(|(_, hit)| hit);
-            let hits = match self.order {
-                Order::LargeBetter => {
-                    peek_top_largest_iterable(scored_points_iter, self.max_group_size)
-                }
-                Order::SmallBetter => {
-                    peek_top_smallest_iterable(scored_points_iter, self.max_group_size)
-                }
-            };
+            let mut hits: Vec<_> = group.drain().map(|(_, hit)| hit).collect();
+            hits.sort_unstable_by(|a, b| {
+                let by_score = match self.order {
+                    Order::LargeBetter => b.cmp(a),
+                    Order::SmallBetter => a.cmp(b),
+                };
+                by_score.then_with(|| a.id.cmp(&b.id))
+            });
+            hits.truncate(self.max_group_size);
             groups.push(Group {
                 hits,
                 key: group_key,
@@ -365,6 +368,44 @@ mod unit_tests {
         }
     }
 
+    #[test]
+    fn test_ties_are_resolved_by_key_and_id() {
+        let scored_points = [
+            point(5, 0.9, json!("c")),
+            point(4, 0.9, json!("b")),
+            point(3, 0.9, json!("a")),
+            point(2, 0.5, json!("a")),
+            point(1, 0.5, json!("a")),
+            point(6, 0.9, json!("b")),
+        ];
+
+        for order in [Order::LargeBetter, Order::SmallBetter] {
+            let expected = match order {
+                Order::LargeBetter => vec![
+                    (GroupId::from("a"), vec![3.into(), 1.into()]),
+                    (GroupId::from("b"), vec![4.into(), 6.into()]),
+                ],
+                // `a` has the smallest score, other groups are tied
+                Order::SmallBetter => vec![
+                    (GroupId::from("a"), vec![1.into(), 2.into()]),
+                    (GroupId::from("b"), vec![4.into(), 6.into()]),
+                ],
+            };
+
+            let mut aggregator = GroupsAggregator::new(2, 2, "docId".to_string(), order);
+            aggregator.add_points(&scored_points);
+            let result = aggregator
+                .distill()
+                .into_iter()
+                .map(|group| {
+                    let ids = group.hits.iter().map(|hit| hit.id).collect_vec();
+                    (group.key, ids)
+                })
+                .collect_vec();
+            assert_eq!(result, expected);
+        }
+    }
+
     #[test]
     fn test_aggregate_less_groups() {
         let mut aggregator = GroupsAggregator::new(3, 2, "docId".to_string(), Order::LargeBetter);
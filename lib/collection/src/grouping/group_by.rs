@@ -4,8 +4,8 @@ use std::future::Future;
 use itertools::Itertools;
 use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
 use segment::types::{
-    AnyVariants, Condition, FieldCondition, Filter, Match, ScoredPoint, WithPayloadInterface,
-    WithVector,
+    AnyVariants, Condition, FieldCondition, Filter, Match, Order, ScoredPoint,
+    WithPayloadInterface, WithVector,
 };
 use serde_json::Value;
 use tokio::sync::RwLockReadGuard;
@@ -16,7 +16,7 @@ use crate::lookup::WithLookup;
 use crate::operations::consistency_params::ReadConsistency;
 use crate::operations::types::{
     BaseGroupRequest, CollectionError, CollectionResult, PointGroup, RecommendGroupsRequest,
-    RecommendRequest, SearchGroupsRequest, SearchRequest, UsingVector,
+    RecommendRequest, RecommendStrategy, SearchGroupsRequest, SearchRequest, UsingVector,
 };
 use crate::recommendations::recommend_by;
 use crate::shards::shard::ShardId;
@@ -44,6 +44,17 @@ impl SourceRequest {
         }
     }
 
+    /// Whether scores of the request are similarities with a single vector, ordered by distance
+    fn is_distance_scored(&self) -> bool {
+        match self {
+            SourceRequest::Search(_) => true,
+            SourceRequest::Recommend(request) => match request.strategy.unwrap_or_default() {
+                RecommendStrategy::AverageVector => true,
+                RecommendStrategy::BestScore => false,
+            },
+        }
+    }
+
     fn merge_filter(&mut self, filter: &Filter) {
         match self {
             SourceRequest::Search(request) => {
@@ -154,7 +165,7 @@ impl GroupRequest {
                 request.with_vector = None;
 
                 collection
-                    .search(request, read_consistency, shard_selection)
+                    .search(request, read_consistency, shard_selection, None)
                     .await
             }
             SourceRequest::Recommend(mut request) => {
@@ -166,7 +177,14 @@ impl GroupRequest {
                 request.with_payload = only_group_by_key;
                 request.with_vector = None;
 
-                recommend_by(request, collection, collection_by_name, read_consistency).await
+                recommend_by(
+                    request,
+                    collection,
+                    collection_by_name,
+                    read_consistency,
+                    shard_selection,
+                )
+                .await
             }
         }
     }
@@ -216,6 +234,7 @@ impl From<RecommendGroupsRequest> for GroupRequest {
         let RecommendGroupsRequest {
             positive,
             negative,
+            strategy,
             filter,
             params,
             with_payload,
@@ -235,6 +254,7 @@ impl From<RecommendGroupsRequest> for GroupRequest {
         let recommend = RecommendRequest {
             positive,
             negative,
+            strategy,
             filter,
             params,
             limit: 0,
@@ -269,11 +289,14 @@ where
     F: Fn(String) -> Fut + Clone,
     Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
 {
-    let score_ordering = {
+    let score_ordering = if request.source.is_distance_scored() {
         let vector_name = request.source.vector_field_name();
         let collection_params = collection.collection_config.read().await;
         let vector_params = collection_params.params.get_vector_params(vector_name)?;
         vector_params.distance.distance_order()
+    } else {
+        // Scores of custom queries are always "larger is better"
+        Order::LargeBetter
     };
 
     let mut aggregator = GroupsAggregator::new(
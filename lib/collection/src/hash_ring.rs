@@ -1,5 +1,10 @@
 use std::hash::Hash;
 
+use segment::types::ExtendedPointId;
+
+use crate::shards::resharding::{apply_splits, ShardSplit};
+use crate::shards::shard::ShardId;
+
 pub enum HashRing<T: Hash + Copy> {
     Raw(hashring::HashRing<T>),
     Fair {
@@ -56,3 +61,48 @@ impl<T: Hash + Copy> HashRing<T> {
         }
     }
 }
+
+/// Routes points to shards: by the hash ring first, and then by the splits of the shards
+pub struct HashRingRouter {
+    ring: HashRing<ShardId>,
+    /// Splits of the shards in the order they were made
+    splits: Vec<ShardSplit>,
+}
+
+impl HashRingRouter {
+    pub fn new(ring: HashRing<ShardId>) -> Self {
+        Self {
+            ring,
+            splits: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, shard: ShardId) {
+        self.ring.add(shard)
+    }
+
+    pub fn remove(&mut self, shard: &ShardId) -> bool {
+        self.ring.remove(shard)
+    }
+
+    pub fn splits(&self) -> &[ShardSplit] {
+        &self.splits
+    }
+
+    pub fn set_splits(&mut self, splits: Vec<ShardSplit>) {
+        self.splits = splits;
+    }
+
+    /// Whether the shard was split, so some of the points it receives from the hash ring
+    /// belong to another shard
+    pub fn is_split_source(&self, shard: &ShardId) -> bool {
+        self.splits
+            .iter()
+            .any(|split| split.source_shard_id == *shard)
+    }
+
+    pub fn get(&self, point_id: &ExtendedPointId) -> Option<ShardId> {
+        let shard = *self.ring.get(point_id)?;
+        Some(apply_splits(shard, &self.splits, point_id))
+    }
+}
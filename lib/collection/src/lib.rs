@@ -3,6 +3,8 @@ pub mod collection_manager;
 pub mod collection_state;
 pub mod common;
 pub mod config;
+pub mod config_migration;
+pub mod discovery;
 pub mod grouping;
 pub mod hash_ring;
 pub mod lookup;
@@ -65,6 +65,7 @@ where
         ids,
         with_payload: request.with_payload,
         with_vector: request.with_vectors.unwrap_or_default(),
+        with_version: false,
     };
 
     let result = collection
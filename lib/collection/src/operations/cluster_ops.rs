@@ -1,8 +1,11 @@
+use std::num::NonZeroU32;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::shard::{PeerId, ShardId, ShardKey};
+use crate::shards::transfer::shard_transfer::ShardTransferMethod;
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -16,6 +19,19 @@ pub enum ClusterOperations {
     AbortTransfer(AbortTransferOperation),
     /// Drop replica of a shard from a peer
     DropReplica(DropReplicaOperation),
+    /// Create shards of a new shard key in a collection with custom sharding
+    CreateShardingKey(CreateShardingKeyOperation),
+    /// Drop all shards of a shard key, with all points in them
+    DropShardingKey(DropShardingKeyOperation),
+    /// Split a shard of the collection into two shards
+    StartResharding(StartReshardingOperation),
+    /// Abort currently running resharding operation
+    AbortResharding(AbortReshardingOperation),
+    /// Make an active replica a listener, which receives updates and serves reads,
+    /// but never leads updates and is not counted toward the write consistency
+    ConvertToListener(ConvertToListenerOperation),
+    /// Make a listener replica active again
+    ConvertToActive(ConvertToActiveOperation),
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -42,12 +58,96 @@ pub struct AbortTransferOperation {
     pub abort_transfer: MoveShard,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CreateShardingKeyOperation {
+    pub create_sharding_key: CreateShardingKey,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct DropShardingKeyOperation {
+    pub drop_sharding_key: DropShardingKey,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct StartReshardingOperation {
+    pub start_resharding: StartResharding,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct AbortReshardingOperation {
+    pub abort_resharding: AbortResharding,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ConvertToListenerOperation {
+    pub convert_to_listener: Replica,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ConvertToActiveOperation {
+    pub convert_to_active: Replica,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CreateShardingKey {
+    pub shard_key: ShardKey,
+    /// How many shards to create for this key
+    /// If not specified, will use the default value from config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shards_number: Option<NonZeroU32>,
+    /// How many replicas to create for each shard
+    /// If not specified, will use the default value from config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replication_factor: Option<NonZeroU32>,
+    /// Placement of shards for this key
+    /// List of peer ids, that can be used to place shards for this key
+    /// If not specified, will be randomly placed among all peers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placement: Option<Vec<PeerId>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct DropShardingKey {
+    pub shard_key: ShardKey,
+}
+
+/// Split a shard into two shards. Only available in the REST API.
+///
+/// The resharding is resumed, if the peer, which copies the points, is restarted.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct StartResharding {
+    /// Shard to split, part of its points is moved into a new shard
+    pub shard_id: ShardId,
+    /// Peer, which copies the points into the new shard.
+    /// It must have an active replica of the shard.
+    /// If not specified, a peer with an active replica is selected automatically
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_id: Option<PeerId>,
+}
+
+/// Abort the resharding in progress. Only available in the REST API.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct AbortResharding {}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct MoveShard {
     pub shard_id: ShardId,
     pub to_peer_id: PeerId,
     pub from_peer_id: PeerId,
+    /// Method for transferring the shard from one node to another
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<ShardTransferMethod>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -64,6 +164,12 @@ impl Validate for ClusterOperations {
             ClusterOperations::ReplicateShard(op) => op.validate(),
             ClusterOperations::AbortTransfer(op) => op.validate(),
             ClusterOperations::DropReplica(op) => op.validate(),
+            ClusterOperations::CreateShardingKey(op) => op.validate(),
+            ClusterOperations::DropShardingKey(op) => op.validate(),
+            ClusterOperations::StartResharding(op) => op.validate(),
+            ClusterOperations::AbortResharding(op) => op.validate(),
+            ClusterOperations::ConvertToListener(op) => op.validate(),
+            ClusterOperations::ConvertToActive(op) => op.validate(),
         }
     }
 }
@@ -1,8 +1,8 @@
-use std::num::NonZeroU32;
+use std::num::{NonZeroU32, NonZeroU64};
 
 use merge::Merge;
 use schemars::JsonSchema;
-use segment::types::{HnswConfig, ProductQuantization, ScalarQuantization};
+use segment::types::{BinaryQuantization, HnswConfig, ProductQuantization, ScalarQuantization};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -11,6 +11,7 @@ use validator::{Validate, ValidationErrors};
 use crate::config::{CollectionParams, WalConfig};
 use crate::operations::types::CollectionResult;
 use crate::optimizers_builder::OptimizersConfig;
+use crate::wal::WalCompression;
 
 // Structures for partial update of collection params
 // TODO: make auto-generated somehow...
@@ -91,6 +92,15 @@ pub struct WalConfigDiff {
     pub wal_capacity_mb: Option<usize>,
     /// Number of WAL segments to create ahead of actually used ones
     pub wal_segments_ahead: Option<usize>,
+    /// If set - large WAL records are stored in compressed form
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_compression: Option<WalCompression>,
+    /// Number of the latest acknowledged operations to keep in WAL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_retain_ops: Option<u64>,
+    /// Keep acknowledged operations in WAL for at least this number of seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_retain_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Merge, PartialEq, Eq, Hash)]
@@ -105,6 +115,15 @@ pub struct CollectionParamsDiff {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default)]
     pub on_disk_payload: Option<bool>,
+    /// Payload key which separates tenants of the collection.
+    /// Vectors of points with the same value of this key are stored next to each other
+    /// after optimization.
+    #[serde(default)]
+    pub tenant_key: Option<String>,
+    /// Default timeout of searches in the collection, in seconds.
+    /// A search request may set its own timeout instead.
+    #[serde(default)]
+    pub search_timeout_sec: Option<NonZeroU64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Merge)]
@@ -125,6 +144,7 @@ pub struct OptimizersConfigDiff {
     /// Do not create segments larger this size (in kilobytes).
     /// Large segments might require disproportionately long indexation times,
     /// therefore it makes sense to limit the size of segments.
+    /// Existing segments larger than this size, e.g. after it is lowered, are split into several.
     ///
     /// If indexation speed have more priority for your - make this parameter lower.
     /// If search speed is more important - make this parameter higher.
@@ -154,6 +174,12 @@ pub struct OptimizersConfigDiff {
     pub flush_interval_sec: Option<u64>,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: Option<usize>,
+    /// Maximum size (in kilobytes) of vectors, which optimizers copy from the optimized segments at once.
+    /// Points are copied by batches of this size, so memory used to merge segments does not depend on their size.
+    ///
+    /// Default value is 65,536 (64MB).
+    #[serde(alias = "merge_buffer_size_kb")]
+    pub merge_buffer_size: Option<usize>,
 }
 
 impl std::hash::Hash for OptimizersConfigDiff {
@@ -166,6 +192,7 @@ impl std::hash::Hash for OptimizersConfigDiff {
         self.indexing_threshold.hash(state);
         self.flush_interval_sec.hash(state);
         self.max_optimization_threads.hash(state);
+        self.merge_buffer_size.hash(state);
     }
 }
 
@@ -180,6 +207,7 @@ impl PartialEq for OptimizersConfigDiff {
             && self.indexing_threshold == other.indexing_threshold
             && self.flush_interval_sec == other.flush_interval_sec
             && self.max_optimization_threads == other.max_optimization_threads
+            && self.merge_buffer_size == other.merge_buffer_size
     }
 }
 
@@ -297,6 +325,7 @@ pub enum Disabled {
 pub enum QuantizationConfigDiff {
     Scalar(ScalarQuantization),
     Product(ProductQuantization),
+    Binary(BinaryQuantization),
     Disabled(Disabled),
 }
 
@@ -311,6 +340,7 @@ impl Validate for QuantizationConfigDiff {
         match self {
             QuantizationConfigDiff::Scalar(scalar) => scalar.validate(),
             QuantizationConfigDiff::Product(product) => product.validate(),
+            QuantizationConfigDiff::Binary(binary) => binary.validate(),
             QuantizationConfigDiff::Disabled(_) => Ok(()),
         }
     }
@@ -318,8 +348,6 @@ impl Validate for QuantizationConfigDiff {
 
 #[cfg(test)]
 mod tests {
-    use std::num::NonZeroU64;
-
     use segment::types::{Distance, HnswConfig};
 
     use super::*;
@@ -335,18 +363,27 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
+                multivector_config: None,
             }
             .into(),
             shard_number: NonZeroU32::new(1).unwrap(),
+            sharding_method: None,
             replication_factor: NonZeroU32::new(1).unwrap(),
             write_consistency_factor: NonZeroU32::new(1).unwrap(),
             on_disk_payload: false,
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         };
 
         let diff = CollectionParamsDiff {
             replication_factor: None,
             write_consistency_factor: Some(NonZeroU32::new(2).unwrap()),
             on_disk_payload: None,
+            tenant_key: None,
+            search_timeout_sec: None,
         };
 
         let new_params = diff.update(&params).unwrap();
@@ -354,6 +391,21 @@ mod tests {
         assert_eq!(new_params.replication_factor.get(), 1);
         assert_eq!(new_params.write_consistency_factor.get(), 2);
         assert!(!new_params.on_disk_payload);
+        assert_eq!(new_params.tenant_key, None);
+
+        let diff = CollectionParamsDiff {
+            replication_factor: None,
+            write_consistency_factor: None,
+            on_disk_payload: None,
+            tenant_key: Some("tenant".to_string()),
+            search_timeout_sec: NonZeroU64::new(5),
+        };
+
+        let new_params = diff.update(&new_params).unwrap();
+
+        assert_eq!(new_params.write_consistency_factor.get(), 2);
+        assert_eq!(new_params.tenant_key.as_deref(), Some("tenant"));
+        assert_eq!(new_params.search_timeout_sec, NonZeroU64::new(5));
     }
 
     #[test]
@@ -375,10 +427,17 @@ mod tests {
             indexing_threshold: Some(50_000),
             flush_interval_sec: 30,
             max_optimization_threads: 1,
+            merge_buffer_size: None,
         };
         let update: OptimizersConfigDiff =
             serde_json::from_str(r#"{ "indexing_threshold": 10000 }"#).unwrap();
         let new_config = update.update(&base_config).unwrap();
+        assert_eq!(new_config.indexing_threshold, Some(10000));
+
+        let update: OptimizersConfigDiff =
+            serde_json::from_str(r#"{ "merge_buffer_size_kb": 1024 }"#).unwrap();
+        let new_config = update.update(&new_config).unwrap();
+        assert_eq!(new_config.merge_buffer_size, Some(1024));
         assert_eq!(new_config.indexing_threshold, Some(10000))
     }
 
@@ -20,6 +20,8 @@ use validator::{Validate, ValidationError as ValidatorError, ValidationErrors};
 ///
 /// * `all` - send requests to all nodes and return points which present on all of them
 ///
+/// Replicas, which return a point of an older version, count as returning its latest version in search results
+///
 /// Default value is `Factor(1)`
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged)]
@@ -1,21 +1,26 @@
 use std::collections::{BTreeMap, HashMap};
 use std::num::{NonZeroU32, NonZeroU64};
 
-use api::grpc::conversions::{from_grpc_dist, payload_to_proto, proto_to_payloads};
+use api::grpc::conversions::{
+    from_grpc_datatype, from_grpc_dist, payload_to_proto, proto_to_payloads,
+};
 use api::grpc::qdrant::quantization_config_diff::Quantization;
 use api::grpc::qdrant::update_collection_cluster_setup_request::Operation as ClusterOperationsPb;
 use itertools::Itertools;
 use segment::data_types::vectors::{NamedVector, VectorStruct, DEFAULT_VECTOR_NAME};
-use segment::types::{Distance, QuantizationConfig};
+use segment::types::{Distance, QuantizationConfig, Sample};
+use segment::vector_storage::query::discovery_query::{ContextPair, DiscoveryQuery};
+use segment::vector_storage::query::reco_query::RecoQuery;
 use tonic::Status;
 
 use super::types::{
-    BaseGroupRequest, GroupsResult, PointGroup, RecommendGroupsRequest, SearchGroupsRequest,
-    VectorParamsDiff, VectorsConfigDiff,
+    BaseGroupRequest, ContextExamplePair, CoreSearchRequest, DiscoverRequest, GroupsResult,
+    NamedQuery, PointGroup, QueryEnum, RecommendExample, RecommendGroupsRequest, RecommendStrategy,
+    SearchGroupsRequest, VectorParamsDiff, VectorsConfigDiff,
 };
 use crate::config::{
     default_replication_factor, default_write_consistency_factor, CollectionConfig,
-    CollectionParams, WalConfig,
+    CollectionParams, ShardingMethod, WalConfig,
 };
 use crate::lookup::types::WithLookupInterface;
 use crate::lookup::WithLookup;
@@ -31,13 +36,19 @@ use crate::operations::point_ops::PointsSelector::PointIdsSelector;
 use crate::operations::point_ops::{
     Batch, FilterSelector, PointIdsList, PointStruct, PointsSelector, WriteOrdering,
 };
+use crate::operations::shard_key_selector::ShardKeySelector;
+use crate::operations::strict_mode::StrictModeConfig;
 use crate::operations::types::{
     AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionStatus, CountResult,
-    LocalShardInfo, LookupLocation, OptimizersStatus, RecommendRequest, Record, RemoteShardInfo,
-    SearchRequest, ShardTransferInfo, UpdateResult, UpdateStatus, VectorParams, VectorsConfig,
+    EffectiveVectorConfig, LocalShardInfo, LookupLocation, OptimizersStatus, RecommendRequest,
+    Record, RemoteShardInfo, SearchRequest, ShardTransferInfo, SparseIndexParams,
+    SparseVectorParams, UpdateResult, UpdateStatus, VectorParams, VectorsConfig,
 };
 use crate::optimizers_builder::OptimizersConfig;
-use crate::shards::remote_shard::CollectionSearchRequest;
+use crate::shards::remote_shard::{CollectionCoreSearchRequest, CollectionSearchRequest};
+use crate::shards::shard::ShardKey;
+use crate::shards::transfer::shard_transfer::ShardTransferMethod;
+use crate::wal::WalCompression;
 
 pub fn write_ordering_to_proto(ordering: WriteOrdering) -> api::grpc::qdrant::WriteOrdering {
     api::grpc::qdrant::WriteOrdering {
@@ -74,6 +85,56 @@ pub fn write_ordering_from_proto(
     })
 }
 
+pub fn sharding_method_to_proto(sharding_method: ShardingMethod) -> i32 {
+    match sharding_method {
+        ShardingMethod::Auto => api::grpc::qdrant::ShardingMethod::Auto as i32,
+        ShardingMethod::Custom => api::grpc::qdrant::ShardingMethod::Custom as i32,
+    }
+}
+
+pub fn sharding_method_from_proto(sharding_method: i32) -> Result<ShardingMethod, Status> {
+    match api::grpc::qdrant::ShardingMethod::from_i32(sharding_method) {
+        None => Err(Status::invalid_argument(format!(
+            "cannot convert sharding method: {sharding_method}"
+        ))),
+        Some(api::grpc::qdrant::ShardingMethod::Auto) => Ok(ShardingMethod::Auto),
+        Some(api::grpc::qdrant::ShardingMethod::Custom) => Ok(ShardingMethod::Custom),
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::ShardKey> for ShardKey {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::ShardKey) -> Result<Self, Self::Error> {
+        match value.key {
+            Some(api::grpc::qdrant::shard_key::Key::Keyword(keyword)) => {
+                Ok(ShardKey::Keyword(keyword))
+            }
+            Some(api::grpc::qdrant::shard_key::Key::Number(number)) => Ok(ShardKey::Number(number)),
+            None => Err(Status::invalid_argument("Shard key is empty")),
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::ShardKeySelector> for ShardKeySelector {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::ShardKeySelector) -> Result<Self, Self::Error> {
+        let mut shard_keys: Vec<ShardKey> = value
+            .shard_keys
+            .into_iter()
+            .map(ShardKey::try_from)
+            .collect::<Result<_, _>>()?;
+        match shard_keys.len() {
+            0 => Err(Status::invalid_argument(
+                "Shard key selector must contain at least one shard key",
+            )),
+            1 => Ok(ShardKeySelector::ShardKey(shard_keys.pop().unwrap())),
+            _ => Ok(ShardKeySelector::ShardKeys(shard_keys)),
+        }
+    }
+}
+
 pub fn try_record_from_grpc(
     point: api::grpc::qdrant::RetrievedPoint,
     with_payload: bool,
@@ -99,6 +160,8 @@ pub fn try_record_from_grpc(
         id,
         payload,
         vector,
+        order_value: point.order_value.map(TryInto::try_into).transpose()?,
+        version: point.version,
     })
 }
 
@@ -133,6 +196,64 @@ impl From<api::grpc::qdrant::WalConfigDiff> for WalConfigDiff {
         Self {
             wal_capacity_mb: value.wal_capacity_mb.map(|v| v as usize),
             wal_segments_ahead: value.wal_segments_ahead.map(|v| v as usize),
+            wal_compression: value.wal_compression.map(WalCompression::from),
+            wal_retain_ops: value.wal_retain_ops,
+            wal_retain_secs: value.wal_retain_secs,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::WalCompression> for WalCompression {
+    fn from(value: api::grpc::qdrant::WalCompression) -> Self {
+        let default = WalCompression::default();
+        Self {
+            threshold: value
+                .threshold
+                .map_or(default.threshold, |threshold| threshold as usize),
+        }
+    }
+}
+
+impl From<WalCompression> for api::grpc::qdrant::WalCompression {
+    fn from(value: WalCompression) -> Self {
+        Self {
+            threshold: Some(value.threshold as u64),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::StrictModeConfig> for StrictModeConfig {
+    fn from(value: api::grpc::qdrant::StrictModeConfig) -> Self {
+        Self {
+            enabled: value.enabled,
+            max_filter_conditions: value.max_filter_conditions.map(|v| v as usize),
+            upsert_max_batchsize: value.upsert_max_batchsize.map(|v| v as usize),
+            max_query_limit: value.max_query_limit.map(|v| v as usize),
+            max_query_offset: value.max_query_offset.map(|v| v as usize),
+            max_point_payload_bytes: value.max_point_payload_bytes.map(|v| v as usize),
+            unindexed_filtering_retrieve: value.unindexed_filtering_retrieve,
+            unindexed_filtering_update: value.unindexed_filtering_update,
+            max_concurrent_searches: value.max_concurrent_searches.map(|v| v as usize),
+            max_updates_per_second: value.max_updates_per_second.map(|v| v as usize),
+            rate_limit_timeout_ms: value.rate_limit_timeout_ms,
+        }
+    }
+}
+
+impl From<StrictModeConfig> for api::grpc::qdrant::StrictModeConfig {
+    fn from(value: StrictModeConfig) -> Self {
+        Self {
+            enabled: value.enabled,
+            max_filter_conditions: value.max_filter_conditions.map(|v| v as u64),
+            upsert_max_batchsize: value.upsert_max_batchsize.map(|v| v as u64),
+            max_query_limit: value.max_query_limit.map(|v| v as u64),
+            max_query_offset: value.max_query_offset.map(|v| v as u64),
+            max_point_payload_bytes: value.max_point_payload_bytes.map(|v| v as u64),
+            unindexed_filtering_retrieve: value.unindexed_filtering_retrieve,
+            unindexed_filtering_update: value.unindexed_filtering_update,
+            max_concurrent_searches: value.max_concurrent_searches.map(|v| v as u64),
+            max_updates_per_second: value.max_updates_per_second.map(|v| v as u64),
+            rate_limit_timeout_ms: value.rate_limit_timeout_ms,
         }
     }
 }
@@ -158,6 +279,14 @@ impl TryFrom<api::grpc::qdrant::CollectionParamsDiff> for CollectionParamsDiff {
                 })
                 .transpose()?,
             on_disk_payload: value.on_disk_payload,
+            tenant_key: value.tenant_key,
+            search_timeout_sec: value
+                .search_timeout_sec
+                .map(|timeout| {
+                    NonZeroU64::new(timeout)
+                        .ok_or_else(|| Status::invalid_argument("`search_timeout_sec` cannot be 0"))
+                })
+                .transpose()?,
         })
     }
 }
@@ -173,6 +302,7 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfigDiff {
             indexing_threshold: value.indexing_threshold.map(|v| v as usize),
             flush_interval_sec: value.flush_interval_sec,
             max_optimization_threads: value.max_optimization_threads.map(|v| v as usize),
+            merge_buffer_size: value.merge_buffer_size.map(|v| v as usize),
         }
     }
 }
@@ -188,6 +318,7 @@ impl TryFrom<api::grpc::qdrant::QuantizationConfigDiff> for QuantizationConfigDi
             Some(quantization) => match quantization {
                 Quantization::Scalar(scalar) => Ok(Self::Scalar(scalar.try_into()?)),
                 Quantization::Product(product) => Ok(Self::Product(product.try_into()?)),
+                Quantization::Binary(binary) => Ok(Self::Binary(binary.into())),
                 Quantization::Disabled(_) => Ok(Self::new_disabled()),
             },
         }
@@ -205,6 +336,7 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
             segments_count,
             config,
             payload_schema,
+            effective_vectors_config,
         } = value;
 
         api::grpc::qdrant::CollectionInfo {
@@ -252,18 +384,19 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                         Some(api::grpc::qdrant::VectorsConfig { config })
                     },
                     shard_number: config.params.shard_number.get(),
+                    sharding_method: config.params.sharding_method.map(sharding_method_to_proto),
                     replication_factor: Some(config.params.replication_factor.get()),
                     on_disk_payload: config.params.on_disk_payload,
+                    tenant_key: config.params.tenant_key,
                     write_consistency_factor: Some(config.params.write_consistency_factor.get()),
+                    sparse_vectors_config: config
+                        .params
+                        .sparse_vectors
+                        .map(sparse_vectors_config_to_proto),
+                    store_original_vectors: config.params.store_original_vectors,
+                    search_timeout_sec: config.params.search_timeout_sec.map(NonZeroU64::get),
                 }),
-                hnsw_config: Some(api::grpc::qdrant::HnswConfigDiff {
-                    m: Some(config.hnsw_config.m as u64),
-                    ef_construct: Some(config.hnsw_config.ef_construct as u64),
-                    full_scan_threshold: Some(config.hnsw_config.full_scan_threshold as u64),
-                    max_indexing_threads: Some(config.hnsw_config.max_indexing_threads as u64),
-                    on_disk: config.hnsw_config.on_disk,
-                    payload_m: config.hnsw_config.payload_m.map(|v| v as u64),
-                }),
+                hnsw_config: Some(config.hnsw_config.into()),
                 optimizer_config: Some(api::grpc::qdrant::OptimizersConfigDiff {
                     deleted_threshold: Some(config.optimizer_config.deleted_threshold),
                     vacuum_min_vector_number: Some(
@@ -282,21 +415,64 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     max_optimization_threads: Some(
                         config.optimizer_config.max_optimization_threads as u64,
                     ),
+                    merge_buffer_size: config.optimizer_config.merge_buffer_size.map(|x| x as u64),
                 }),
                 wal_config: Some(api::grpc::qdrant::WalConfigDiff {
                     wal_capacity_mb: Some(config.wal_config.wal_capacity_mb as u64),
                     wal_segments_ahead: Some(config.wal_config.wal_segments_ahead as u64),
+                    wal_retain_ops: Some(config.wal_config.wal_retain_ops),
+                    wal_retain_secs: Some(config.wal_config.wal_retain_secs),
+                    wal_compression: config.wal_config.wal_compression.map(|x| x.into()),
                 }),
                 quantization_config: config.quantization_config.map(|x| x.into()),
+                strict_mode_config: config.strict_mode_config.map(|x| x.into()),
             }),
             payload_schema: payload_schema
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect(),
+            effective_vectors_config: effective_vectors_config
+                .into_iter()
+                .map(|(k, v)| (k, v.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<EffectiveVectorConfig> for api::grpc::qdrant::EffectiveVectorConfig {
+    fn from(value: EffectiveVectorConfig) -> Self {
+        let EffectiveVectorConfig {
+            hnsw_config,
+            quantization_config,
+            on_disk,
+        } = value;
+        Self {
+            hnsw_config: Some(hnsw_config.into()),
+            quantization_config: quantization_config.map(Into::into),
+            on_disk,
         }
     }
 }
 
+impl TryFrom<api::grpc::qdrant::EffectiveVectorConfig> for EffectiveVectorConfig {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::EffectiveVectorConfig) -> Result<Self, Self::Error> {
+        let api::grpc::qdrant::EffectiveVectorConfig {
+            hnsw_config,
+            quantization_config,
+            on_disk,
+        } = value;
+        Ok(Self {
+            hnsw_config: hnsw_config
+                .ok_or_else(|| Status::invalid_argument("Malformed HnswConfig type"))?
+                .into(),
+            quantization_config: quantization_config.map(TryInto::try_into).transpose()?,
+            on_disk,
+        })
+    }
+}
+
 impl From<Record> for api::grpc::qdrant::RetrievedPoint {
     fn from(record: Record) -> Self {
         let vectors = record.vector.map(|vector_struct| vector_struct.into());
@@ -305,6 +481,8 @@ impl From<Record> for api::grpc::qdrant::RetrievedPoint {
             id: Some(record.id.into()),
             payload: record.payload.map(payload_to_proto).unwrap_or_default(),
             vectors,
+            order_value: record.order_value.map(Into::into),
+            version: record.version,
         }
     }
 }
@@ -338,6 +516,7 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfig {
             max_optimization_threads: optimizer_config
                 .max_optimization_threads
                 .unwrap_or_default() as usize,
+            merge_buffer_size: optimizer_config.merge_buffer_size.map(|x| x as usize),
         }
     }
 }
@@ -347,6 +526,9 @@ impl From<api::grpc::qdrant::WalConfigDiff> for WalConfig {
         Self {
             wal_capacity_mb: wal_config.wal_capacity_mb.unwrap_or_default() as usize,
             wal_segments_ahead: wal_config.wal_segments_ahead.unwrap_or_default() as usize,
+            wal_compression: wal_config.wal_compression.map(WalCompression::from),
+            wal_retain_ops: wal_config.wal_retain_ops.unwrap_or_default(),
+            wal_retain_secs: wal_config.wal_retain_secs.unwrap_or_default(),
         }
     }
 }
@@ -407,6 +589,15 @@ impl TryFrom<api::grpc::qdrant::VectorParams> for VectorParams {
                 .map(grpc_to_segment_quantization_config)
                 .transpose()?,
             on_disk: vector_params.on_disk,
+            datatype: vector_params
+                .datatype
+                .map(from_grpc_datatype)
+                .transpose()?
+                .flatten(),
+            multivector_config: vector_params
+                .multivector_config
+                .map(TryInto::try_into)
+                .transpose()?,
         })
     }
 }
@@ -439,6 +630,9 @@ fn grpc_to_segment_quantization_config(
         api::grpc::qdrant::quantization_config::Quantization::Product(config) => {
             Ok(QuantizationConfig::Product(config.try_into()?))
         }
+        api::grpc::qdrant::quantization_config::Quantization::Binary(config) => {
+            Ok(QuantizationConfig::Binary(config.into()))
+        }
     }
 }
 
@@ -478,7 +672,12 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     },
                     shard_number: NonZeroU32::new(params.shard_number)
                         .ok_or_else(|| Status::invalid_argument("`shard_number` cannot be zero"))?,
+                    sharding_method: params
+                        .sharding_method
+                        .map(sharding_method_from_proto)
+                        .transpose()?,
                     on_disk_payload: params.on_disk_payload,
+                    tenant_key: params.tenant_key,
                     replication_factor: NonZeroU32::new(
                         params
                             .replication_factor
@@ -495,6 +694,18 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     .ok_or_else(|| {
                         Status::invalid_argument("`write_consistency_factor` cannot be zero")
                     })?,
+                    sparse_vectors: params
+                        .sparse_vectors_config
+                        .map(sparse_vectors_config_from_proto),
+                    store_original_vectors: params.store_original_vectors,
+                    search_timeout_sec: params
+                        .search_timeout_sec
+                        .map(|timeout| {
+                            NonZeroU64::new(timeout).ok_or_else(|| {
+                                Status::invalid_argument("`search_timeout_sec` cannot be zero")
+                            })
+                        })
+                        .transpose()?,
                 },
             },
             hnsw_config: match config.hnsw_config {
@@ -516,6 +727,7 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     None
                 }
             },
+            strict_mode_config: config.strict_mode_config.map(Into::into),
         })
     }
 }
@@ -557,6 +769,11 @@ impl TryFrom<api::grpc::qdrant::GetCollectionInfoResponse> for CollectionInfo {
                     .into_iter()
                     .map(|(k, v)| v.try_into().map(|v| (k, v)))
                     .try_collect()?,
+                effective_vectors_config: collection_info_response
+                    .effective_vectors_config
+                    .into_iter()
+                    .map(|(k, v)| v.try_into().map(|v| (k, v)))
+                    .try_collect()?,
             }),
         }
     }
@@ -671,7 +888,16 @@ impl From<UpdateResult> for api::grpc::qdrant::UpdateResult {
             status: match value.status {
                 UpdateStatus::Acknowledged => api::grpc::qdrant::UpdateStatus::Acknowledged as i32,
                 UpdateStatus::Completed => api::grpc::qdrant::UpdateStatus::Completed as i32,
+                UpdateStatus::Failed => api::grpc::qdrant::UpdateStatus::Failed as i32,
             },
+            error: value.error,
+            deleted_count: value.deleted_count.map(|count| count as u64),
+            deleted_ids: value
+                .deleted_ids
+                .unwrap_or_default()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         }
     }
 }
@@ -689,8 +915,24 @@ impl TryFrom<api::grpc::qdrant::UpdateResult> for UpdateResult {
                 status if status == api::grpc::qdrant::UpdateStatus::Completed as i32 => {
                     UpdateStatus::Completed
                 }
+                status if status == api::grpc::qdrant::UpdateStatus::Failed as i32 => {
+                    UpdateStatus::Failed
+                }
                 _ => return Err(Status::invalid_argument("Malformed UpdateStatus type")),
             },
+            error: value.error,
+            deleted_count: value.deleted_count.map(|count| count as usize),
+            // Ids are reported only along with the count
+            deleted_ids: value
+                .deleted_count
+                .map(|_| {
+                    value
+                        .deleted_ids
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()
+                })
+                .transpose()?,
         })
     }
 }
@@ -699,6 +941,7 @@ impl From<api::grpc::qdrant::CountResult> for CountResult {
     fn from(value: api::grpc::qdrant::CountResult) -> Self {
         Self {
             count: value.count as usize,
+            exact: value.exact,
         }
     }
 }
@@ -707,6 +950,7 @@ impl From<CountResult> for api::grpc::qdrant::CountResult {
     fn from(value: CountResult) -> Self {
         Self {
             count: value.count as u64,
+            exact: value.exact,
         }
     }
 }
@@ -731,10 +975,173 @@ impl<'a> From<CollectionSearchRequest<'a>> for api::grpc::qdrant::SearchPoints {
                 vector_name => Some(vector_name.to_string()),
             },
             read_consistency: None,
+            timeout: None,
+            with_stats: None,
+            shard_key_selector: None,
+        }
+    }
+}
+
+impl From<QueryEnum> for api::grpc::qdrant::QueryEnum {
+    fn from(value: QueryEnum) -> Self {
+        let query = match value {
+            QueryEnum::Nearest(vector) => {
+                api::grpc::qdrant::query_enum::Query::NearestNeighbors(api::grpc::qdrant::Vector {
+                    data: vector.get_vector().clone(),
+                })
+            }
+            QueryEnum::RecommendBestScore(named_query) => {
+                let to_grpc = |vectors: Vec<Vec<f32>>| {
+                    vectors
+                        .into_iter()
+                        .map(|data| api::grpc::qdrant::Vector { data })
+                        .collect()
+                };
+                let RecoQuery {
+                    positives,
+                    negatives,
+                } = named_query.query;
+                api::grpc::qdrant::query_enum::Query::RecommendBestScore(
+                    api::grpc::qdrant::RecoQuery {
+                        positives: to_grpc(positives),
+                        negatives: to_grpc(negatives),
+                    },
+                )
+            }
+            QueryEnum::Discover(named_query) => {
+                let DiscoveryQuery { target, pairs } = named_query.query;
+                api::grpc::qdrant::query_enum::Query::Discover(api::grpc::qdrant::DiscoveryQuery {
+                    target: Some(api::grpc::qdrant::Vector { data: target }),
+                    context: pairs
+                        .into_iter()
+                        .map(|pair| api::grpc::qdrant::ContextPair {
+                            positive: Some(api::grpc::qdrant::Vector {
+                                data: pair.positive,
+                            }),
+                            negative: Some(api::grpc::qdrant::Vector {
+                                data: pair.negative,
+                            }),
+                        })
+                        .collect(),
+                })
+            }
+            QueryEnum::Sample(named_query) => {
+                let sample = match named_query.query {
+                    Sample::Random => api::grpc::qdrant::Sample::Random,
+                };
+                api::grpc::qdrant::query_enum::Query::Sample(sample as i32)
+            }
+        };
+        Self { query: Some(query) }
+    }
+}
+
+// Use wrapper type to bundle CollectionId & CoreSearchRequest
+impl<'a> From<CollectionCoreSearchRequest<'a>> for api::grpc::qdrant::CoreSearchPoints {
+    fn from(value: CollectionCoreSearchRequest<'a>) -> Self {
+        let (collection_id, request) = value.0;
+
+        api::grpc::qdrant::CoreSearchPoints {
+            collection_name: collection_id,
+            query: Some(request.query.clone().into()),
+            filter: request.filter.clone().map(|f| f.into()),
+            limit: request.limit as u64,
+            with_vectors: request.with_vector.clone().map(|wv| wv.into()),
+            with_payload: request.with_payload.clone().map(|wp| wp.into()),
+            params: request.params.map(|sp| sp.into()),
+            score_threshold: request.score_threshold,
+            offset: Some(request.offset as u64),
+            vector_name: match request.query.get_vector_name() {
+                DEFAULT_VECTOR_NAME => None,
+                vector_name => Some(vector_name.to_string()),
+            },
+            read_consistency: None,
         }
     }
 }
 
+impl TryFrom<api::grpc::qdrant::CoreSearchPoints> for CoreSearchRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::CoreSearchPoints) -> Result<Self, Self::Error> {
+        let query = match value
+            .query
+            .and_then(|query| query.query)
+            .ok_or_else(|| Status::invalid_argument("Query is missing"))?
+        {
+            api::grpc::qdrant::query_enum::Query::NearestNeighbors(vector) => {
+                QueryEnum::Nearest(match value.vector_name {
+                    Some(vector_name) => NamedVector {
+                        name: vector_name,
+                        vector: vector.data,
+                    }
+                    .into(),
+                    None => vector.data.into(),
+                })
+            }
+            api::grpc::qdrant::query_enum::Query::RecommendBestScore(reco_query) => {
+                let from_grpc = |vectors: Vec<api::grpc::qdrant::Vector>| {
+                    vectors.into_iter().map(|vector| vector.data).collect()
+                };
+                QueryEnum::RecommendBestScore(NamedQuery {
+                    query: RecoQuery::new(
+                        from_grpc(reco_query.positives),
+                        from_grpc(reco_query.negatives),
+                    ),
+                    using: value.vector_name,
+                })
+            }
+            api::grpc::qdrant::query_enum::Query::Discover(discovery_query) => {
+                let vector = |vector: Option<api::grpc::qdrant::Vector>| {
+                    vector
+                        .map(|vector| vector.data)
+                        .ok_or_else(|| Status::invalid_argument("Discovery vector is missing"))
+                };
+                let pairs = discovery_query
+                    .context
+                    .into_iter()
+                    .map(|pair| {
+                        Ok(ContextPair::new(
+                            vector(pair.positive)?,
+                            vector(pair.negative)?,
+                        ))
+                    })
+                    .collect::<Result<_, Status>>()?;
+                QueryEnum::Discover(NamedQuery {
+                    query: DiscoveryQuery::new(vector(discovery_query.target)?, pairs),
+                    using: value.vector_name,
+                })
+            }
+            api::grpc::qdrant::query_enum::Query::Sample(sample) => {
+                let sample = match api::grpc::qdrant::Sample::from_i32(sample) {
+                    Some(api::grpc::qdrant::Sample::Random) => Sample::Random,
+                    None => return Err(Status::invalid_argument("Unknown sample type")),
+                };
+                QueryEnum::Sample(NamedQuery {
+                    query: sample,
+                    using: value.vector_name,
+                })
+            }
+        };
+
+        Ok(CoreSearchRequest {
+            query,
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            params: value.params.map(|p| p.into()),
+            limit: value.limit as usize,
+            offset: value.offset.unwrap_or_default() as usize,
+            with_payload: value.with_payload.map(|wp| wp.try_into()).transpose()?,
+            with_vector: Some(
+                value
+                    .with_vectors
+                    .map(|with_vectors| with_vectors.into())
+                    .unwrap_or_default(),
+            ),
+            score_threshold: value.score_threshold,
+        })
+    }
+}
+
 impl TryFrom<api::grpc::qdrant::WithLookup> for WithLookup {
     type Error = Status;
 
@@ -806,6 +1213,9 @@ impl TryFrom<api::grpc::qdrant::SearchPointGroups> for SearchGroupsRequest {
             offset: None,
             collection_name: String::new(),
             read_consistency: None,
+            timeout: None,
+            with_stats: None,
+            shard_key_selector: None,
         };
 
         let SearchRequest {
@@ -855,21 +1265,99 @@ impl From<api::grpc::qdrant::LookupLocation> for LookupLocation {
     }
 }
 
-impl TryFrom<api::grpc::qdrant::RecommendPoints> for RecommendRequest {
+impl TryFrom<api::grpc::qdrant::VectorExample> for RecommendExample {
     type Error = Status;
 
-    fn try_from(value: api::grpc::qdrant::RecommendPoints) -> Result<Self, Self::Error> {
-        Ok(RecommendRequest {
-            positive: value
-                .positive
+    fn try_from(value: api::grpc::qdrant::VectorExample) -> Result<Self, Self::Error> {
+        match value.example {
+            Some(api::grpc::qdrant::vector_example::Example::Id(id)) => {
+                Ok(RecommendExample::PointId(id.try_into()?))
+            }
+            Some(api::grpc::qdrant::vector_example::Example::Vector(vector)) => {
+                Ok(RecommendExample::Vector(vector.data))
+            }
+            None => Err(Status::invalid_argument("Vector example is missing")),
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::ContextExamplePair> for ContextExamplePair {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::ContextExamplePair) -> Result<Self, Self::Error> {
+        let example = |example: Option<api::grpc::qdrant::VectorExample>| {
+            example
+                .ok_or_else(|| Status::invalid_argument("Context example is missing"))?
+                .try_into()
+        };
+        Ok(ContextExamplePair {
+            positive: example(value.positive)?,
+            negative: example(value.negative)?,
+        })
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::DiscoverPoints> for DiscoverRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::DiscoverPoints) -> Result<Self, Self::Error> {
+        Ok(DiscoverRequest {
+            target: value
+                .target
+                .ok_or_else(|| Status::invalid_argument("Target is missing"))?
+                .try_into()?,
+            context: value
+                .context
                 .into_iter()
-                .map(|p| p.try_into())
+                .map(|pair| pair.try_into())
                 .collect::<Result<_, _>>()?,
-            negative: value
-                .negative
+            filter: value.filter.map(|f| f.try_into()).transpose()?,
+            params: value.params.map(|p| p.into()),
+            limit: value.limit as usize,
+            offset: value.offset.unwrap_or_default() as usize,
+            with_payload: value.with_payload.map(|wp| wp.try_into()).transpose()?,
+            with_vector: Some(
+                value
+                    .with_vectors
+                    .map(|with_vectors| with_vectors.into())
+                    .unwrap_or_default(),
+            ),
+            using: value.using.map(|name| name.into()),
+            lookup_from: value.lookup_from.map(|x| x.into()),
+        })
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::RecommendPoints> for RecommendRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::RecommendPoints) -> Result<Self, Self::Error> {
+        let examples = |ids: Vec<api::grpc::qdrant::PointId>,
+                        vectors: Vec<api::grpc::qdrant::Vector>|
+         -> Result<Vec<RecommendExample>, Status> {
+            let mut examples: Vec<RecommendExample> = ids
                 .into_iter()
-                .map(|p| p.try_into())
-                .collect::<Result<_, _>>()?,
+                .map(|id| id.try_into().map(RecommendExample::PointId))
+                .collect::<Result<_, _>>()?;
+            examples.extend(
+                vectors
+                    .into_iter()
+                    .map(|vector| RecommendExample::Vector(vector.data)),
+            );
+            Ok(examples)
+        };
+
+        Ok(RecommendRequest {
+            positive: examples(value.positive, value.positive_vectors)?,
+            negative: examples(value.negative, value.negative_vectors)?,
+            strategy: value
+                .strategy
+                .map(|strategy| {
+                    api::grpc::qdrant::RecommendStrategy::from_i32(strategy)
+                        .map(RecommendStrategy::from)
+                        .ok_or_else(|| Status::invalid_argument("Unknown recommend strategy"))
+                })
+                .transpose()?,
             filter: value.filter.map(|f| f.try_into()).transpose()?,
             params: value.params.map(|p| p.into()),
             limit: value.limit as usize,
@@ -906,11 +1394,16 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
             limit: 0,
             offset: None,
             collection_name: String::new(),
+            strategy: value.strategy,
+            positive_vectors: value.positive_vectors,
+            negative_vectors: value.negative_vectors,
+            shard_key_selector: None,
         };
 
         let RecommendRequest {
             positive,
             negative,
+            strategy,
             using,
             lookup_from,
             filter,
@@ -925,6 +1418,7 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
         Ok(RecommendGroupsRequest {
             positive,
             negative,
+            strategy,
             using,
             lookup_from,
             filter,
@@ -942,6 +1436,15 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
     }
 }
 
+impl From<api::grpc::qdrant::RecommendStrategy> for RecommendStrategy {
+    fn from(value: api::grpc::qdrant::RecommendStrategy) -> Self {
+        match value {
+            api::grpc::qdrant::RecommendStrategy::AverageVector => RecommendStrategy::AverageVector,
+            api::grpc::qdrant::RecommendStrategy::BestScore => RecommendStrategy::BestScore,
+        }
+    }
+}
+
 impl From<GroupsResult> for api::grpc::qdrant::GroupsResult {
     fn from(value: GroupsResult) -> Self {
         Self {
@@ -958,15 +1461,76 @@ impl From<VectorParams> for api::grpc::qdrant::VectorParams {
                 Distance::Cosine => api::grpc::qdrant::Distance::Cosine,
                 Distance::Euclid => api::grpc::qdrant::Distance::Euclid,
                 Distance::Dot => api::grpc::qdrant::Distance::Dot,
+                Distance::Manhattan => api::grpc::qdrant::Distance::Manhattan,
+                Distance::Hamming => api::grpc::qdrant::Distance::Hamming,
             }
             .into(),
             hnsw_config: value.hnsw_config.map(Into::into),
             quantization_config: value.quantization_config.map(Into::into),
             on_disk: value.on_disk,
+            datatype: value
+                .datatype
+                .map(|datatype| api::grpc::qdrant::Datatype::from(datatype).into()),
+            multivector_config: value.multivector_config.map(Into::into),
+        }
+    }
+}
+
+impl From<SparseIndexParams> for api::grpc::qdrant::SparseIndexConfig {
+    fn from(value: SparseIndexParams) -> Self {
+        api::grpc::qdrant::SparseIndexConfig {
+            full_scan_threshold: value.full_scan_threshold.map(|v| v as u64),
+            on_disk: value.on_disk,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::SparseIndexConfig> for SparseIndexParams {
+    fn from(value: api::grpc::qdrant::SparseIndexConfig) -> Self {
+        SparseIndexParams {
+            full_scan_threshold: value.full_scan_threshold.map(|v| v as usize),
+            on_disk: value.on_disk,
+        }
+    }
+}
+
+impl From<SparseVectorParams> for api::grpc::qdrant::SparseVectorParams {
+    fn from(value: SparseVectorParams) -> Self {
+        api::grpc::qdrant::SparseVectorParams {
+            index: value.index.map(Into::into),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::SparseVectorParams> for SparseVectorParams {
+    fn from(value: api::grpc::qdrant::SparseVectorParams) -> Self {
+        SparseVectorParams {
+            index: value.index.map(Into::into),
         }
     }
 }
 
+pub fn sparse_vectors_config_to_proto(
+    sparse_vectors: BTreeMap<String, SparseVectorParams>,
+) -> api::grpc::qdrant::SparseVectorConfig {
+    api::grpc::qdrant::SparseVectorConfig {
+        map: sparse_vectors
+            .into_iter()
+            .map(|(name, params)| (name, params.into()))
+            .collect(),
+    }
+}
+
+pub fn sparse_vectors_config_from_proto(
+    sparse_vectors_config: api::grpc::qdrant::SparseVectorConfig,
+) -> BTreeMap<String, SparseVectorParams> {
+    sparse_vectors_config
+        .map
+        .into_iter()
+        .map(|(name, params)| (name, params.into()))
+        .collect()
+}
+
 impl From<AliasDescription> for api::grpc::qdrant::AliasDescription {
     fn from(value: AliasDescription) -> Self {
         api::grpc::qdrant::AliasDescription {
@@ -1031,32 +1595,52 @@ impl From<CollectionClusterInfo> for api::grpc::qdrant::CollectionClusterInfoRes
     }
 }
 
-impl From<api::grpc::qdrant::MoveShard> for MoveShard {
-    fn from(value: api::grpc::qdrant::MoveShard) -> Self {
-        Self {
+pub fn shard_transfer_method_from_proto(method: i32) -> Result<ShardTransferMethod, Status> {
+    match api::grpc::qdrant::ShardTransferMethod::from_i32(method) {
+        None => Err(Status::invalid_argument(format!(
+            "cannot convert shard transfer method: {method}"
+        ))),
+        Some(api::grpc::qdrant::ShardTransferMethod::StreamRecords) => {
+            Ok(ShardTransferMethod::StreamRecords)
+        }
+        Some(api::grpc::qdrant::ShardTransferMethod::Snapshot) => Ok(ShardTransferMethod::Snapshot),
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::MoveShard> for MoveShard {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::MoveShard) -> Result<Self, Self::Error> {
+        Ok(Self {
             shard_id: value.shard_id,
             from_peer_id: value.from_peer_id,
             to_peer_id: value.to_peer_id,
-        }
+            method: value
+                .method
+                .map(shard_transfer_method_from_proto)
+                .transpose()?,
+        })
     }
 }
 
-impl From<ClusterOperationsPb> for ClusterOperations {
-    fn from(value: ClusterOperationsPb) -> Self {
-        match value {
+impl TryFrom<ClusterOperationsPb> for ClusterOperations {
+    type Error = Status;
+
+    fn try_from(value: ClusterOperationsPb) -> Result<Self, Self::Error> {
+        Ok(match value {
             ClusterOperationsPb::MoveShard(op) => {
                 ClusterOperations::MoveShard(MoveShardOperation {
-                    move_shard: op.into(),
+                    move_shard: op.try_into()?,
                 })
             }
             ClusterOperationsPb::ReplicateShard(op) => {
                 ClusterOperations::ReplicateShard(ReplicateShardOperation {
-                    replicate_shard: op.into(),
+                    replicate_shard: op.try_into()?,
                 })
             }
             ClusterOperationsPb::AbortTransfer(op) => {
                 ClusterOperations::AbortTransfer(AbortTransferOperation {
-                    abort_transfer: op.into(),
+                    abort_transfer: op.try_into()?,
                 })
             }
             ClusterOperationsPb::DropReplica(op) => {
@@ -1067,6 +1651,6 @@ impl From<ClusterOperationsPb> for ClusterOperations {
                     },
                 })
             }
-        }
+        })
     }
 }
@@ -3,10 +3,13 @@ pub mod config_diff;
 pub mod consistency_params;
 pub mod conversions;
 pub mod operation_effect;
+pub mod page_token;
 pub mod payload_ops;
 pub mod point_ops;
+pub mod shard_key_selector;
 pub mod shared_storage_config;
 pub mod snapshot_ops;
+pub mod strict_mode;
 pub mod types;
 pub mod validation;
 pub mod vector_ops;
@@ -15,9 +18,10 @@ use std::collections::HashMap;
 
 use segment::types::{ExtendedPointId, PayloadFieldSchema};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::Validate;
 
-use crate::hash_ring::HashRing;
+use crate::hash_ring::HashRingRouter;
 use crate::shards::shard::ShardId;
 
 #[derive(Debug, Deserialize, Serialize, Validate, Default, Clone)]
@@ -32,6 +36,8 @@ pub struct CreateIndex {
 pub enum FieldIndexOperations {
     /// Create index for payload field
     CreateIndex(CreateIndex),
+    /// Change schema of an existing payload field index, the index is rebuilt in background
+    UpdateIndex(CreateIndex),
     /// Delete index for the field
     DeleteIndex(String),
 }
@@ -46,6 +52,55 @@ pub enum CollectionUpdateOperations {
     FieldIndexOperation(FieldIndexOperations),
 }
 
+/// Id of an update operation, supplied by the client to make retries of the operation safe
+pub type ClientOperationId = Uuid;
+
+/// Id of the `part`-th part of a client operation, e.g. of a batch of a streamed upsert,
+/// so that every part of a retried operation is applied only once.
+///
+/// Ids are derived by hashing, so parts of different operations don't share ids,
+/// even if the ids of the operations are adjacent.
+pub fn client_op_part_id(client_op_id: ClientOperationId, part: u64) -> ClientOperationId {
+    let mut bytes = [0; 24];
+    bytes[..16].copy_from_slice(client_op_id.as_bytes());
+    bytes[16..].copy_from_slice(&part.to_le_bytes());
+    let high = seahash::hash(&bytes);
+    let low = seahash::hash_seeded(&bytes, 1, 2, 3, 4);
+    Uuid::from_u128((u128::from(high) << 64) | u128::from(low))
+}
+
+/// Update operation, as it is written into WAL of a shard.
+///
+/// Operation without the client id is stored exactly as a plain [`CollectionUpdateOperations`],
+/// so WAL records of both kinds can be read.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OperationWithClientId {
+    #[serde(flatten)]
+    pub operation: CollectionUpdateOperations,
+    /// If set, the operation is applied to the shard only once, repeated operations with
+    /// the same id return the result of the first one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_op_id: Option<ClientOperationId>,
+}
+
+impl OperationWithClientId {
+    pub fn new(
+        operation: CollectionUpdateOperations,
+        client_op_id: Option<ClientOperationId>,
+    ) -> Self {
+        Self {
+            operation,
+            client_op_id,
+        }
+    }
+}
+
+impl From<CollectionUpdateOperations> for OperationWithClientId {
+    fn from(operation: CollectionUpdateOperations) -> Self {
+        Self::new(operation, None)
+    }
+}
+
 /// A mapping of operation to shard.
 /// Is a result of splitting one operation into several shards by corresponding PointIds
 pub enum OperationToShard<O> {
@@ -83,6 +138,7 @@ impl FieldIndexOperations {
     pub fn is_write_operation(&self) -> bool {
         match self {
             FieldIndexOperations::CreateIndex(_) => true,
+            FieldIndexOperations::UpdateIndex(_) => true,
             FieldIndexOperations::DeleteIndex(_) => false,
         }
     }
@@ -92,6 +148,7 @@ impl Validate for FieldIndexOperations {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
             FieldIndexOperations::CreateIndex(create_index) => create_index.validate(),
+            FieldIndexOperations::UpdateIndex(update_index) => update_index.validate(),
             FieldIndexOperations::DeleteIndex(_) => Ok(()),
         }
     }
@@ -108,9 +165,8 @@ impl Validate for CollectionUpdateOperations {
     }
 }
 
-fn point_to_shard(point_id: ExtendedPointId, ring: &HashRing<ShardId>) -> ShardId {
-    *ring
-        .get(&point_id)
+fn point_to_shard(point_id: ExtendedPointId, ring: &HashRingRouter) -> ShardId {
+    ring.get(&point_id)
         .expect("Hash ring is guaranteed to be non-empty")
 }
 
@@ -118,7 +174,7 @@ fn point_to_shard(point_id: ExtendedPointId, ring: &HashRing<ShardId>) -> ShardI
 fn split_iter_by_shard<I, F, O>(
     iter: I,
     id_extractor: F,
-    ring: &HashRing<ShardId>,
+    ring: &HashRingRouter,
 ) -> OperationToShard<Vec<O>>
 where
     I: IntoIterator<Item = O>,
@@ -137,13 +193,13 @@ where
 
 /// Trait for Operation enums to split them by shard.
 pub trait SplitByShard {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self>
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self>
     where
         Self: Sized;
 }
 
 impl SplitByShard for CollectionUpdateOperations {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         match self {
             CollectionUpdateOperations::PointOperation(operation) => operation
                 .split_by_shard(ring)
@@ -176,10 +232,41 @@ impl CollectionUpdateOperations {
             }
         }
     }
+
+    /// Whether the update is only applied, if the points have the expected versions
+    pub fn is_conditional(&self) -> bool {
+        match self {
+            CollectionUpdateOperations::PayloadOperation(
+                payload_ops::PayloadOps::SetPayload(set_payload)
+                | payload_ops::PayloadOps::OverwritePayload(set_payload),
+            ) => set_payload.if_version.is_some(),
+            CollectionUpdateOperations::PayloadOperation(
+                payload_ops::PayloadOps::DeletePayload(delete_payload),
+            ) => delete_payload.if_version.is_some(),
+            CollectionUpdateOperations::VectorOperation(
+                vector_ops::VectorOperations::UpdateVectors(update_vectors),
+            ) => update_vectors
+                .points
+                .iter()
+                .any(|point| point.if_version.is_some()),
+            _ => false,
+        }
+    }
+
+    /// Whether the update deletes the points, selected by a filter
+    pub fn is_delete_by_filter(&self) -> bool {
+        matches!(
+            self,
+            CollectionUpdateOperations::PointOperation(
+                point_ops::PointOperations::DeletePointsByFilter(_)
+            )
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use serde_json;
 
     use super::*;
@@ -194,4 +281,44 @@ mod tests {
         let json = serde_json::to_string_pretty(&op).unwrap();
         println!("{json}")
     }
+
+    #[test]
+    fn test_client_op_part_ids_of_adjacent_operations() {
+        let first = Uuid::from_u128(1000);
+        let second = Uuid::from_u128(1001);
+
+        let first_parts: Vec<_> = (0..10).map(|part| client_op_part_id(first, part)).collect();
+        let second_parts: Vec<_> = (0..10)
+            .map(|part| client_op_part_id(second, part))
+            .collect();
+
+        assert!(first_parts.iter().all(|id| !second_parts.contains(id)));
+        assert!(first_parts.iter().all_unique());
+        // Same parts of a retried operation get the same ids
+        assert_eq!(client_op_part_id(first, 3), first_parts[3]);
+    }
+
+    #[test]
+    fn test_operation_with_client_id_wal_format() {
+        let op =
+            CollectionUpdateOperations::PointOperation(point_ops::PointOperations::DeletePoints {
+                ids: vec![1.into(), 2.into()],
+            });
+
+        // Records, written without client id, are read as operations without it
+        let plain = serde_cbor::to_vec(&op).unwrap();
+        let read: OperationWithClientId = serde_cbor::from_slice(&plain).unwrap();
+        assert!(read.client_op_id.is_none());
+
+        let client_op_id = Uuid::new_v4();
+        let record =
+            serde_cbor::to_vec(&OperationWithClientId::new(op, Some(client_op_id))).unwrap();
+        let read: OperationWithClientId = serde_cbor::from_slice(&record).unwrap();
+        assert_eq!(read.client_op_id, Some(client_op_id));
+        assert!(matches!(
+            read.operation,
+            CollectionUpdateOperations::PointOperation(point_ops::PointOperations::DeletePoints { ids })
+                if ids == vec![1.into(), 2.into()]
+        ));
+    }
 }
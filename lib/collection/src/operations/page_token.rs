@@ -0,0 +1,155 @@
+use std::fmt::Write as _;
+
+use segment::data_types::order_by::{OrderBy, OrderValue};
+use segment::types::{PayloadKeyType, PointIdType};
+use serde::{Deserialize, Serialize};
+
+use crate::operations::types::{CollectionError, CollectionResult, Record};
+
+/// Position of the scroll, right after the last returned point.
+///
+/// The token is self-contained: it holds no server state, so it stays valid across restarts and
+/// changes of the segment layout.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ScrollPageToken {
+    /// Name of the collection, the token was issued for
+    collection: String,
+    /// Id of the last returned point
+    last_id: PointIdType,
+    /// Key and value of the `order_by` field of the last returned point
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    order_by: Option<(PayloadKeyType, OrderValue)>,
+}
+
+impl ScrollPageToken {
+    pub fn new(collection: &str, last_point: &Record, order_by: Option<&OrderBy>) -> Self {
+        Self {
+            collection: collection.to_string(),
+            last_id: last_point.id,
+            order_by: order_by
+                .zip(last_point.order_value)
+                .map(|(order_by, order_value)| (order_by.key.clone(), order_value)),
+        }
+    }
+
+    /// Encode the token into an opaque string
+    pub fn encode(&self) -> String {
+        let bytes = serde_json::to_vec(self).expect("page token is serializable");
+        bytes.iter().fold(String::new(), |mut token, byte| {
+            let _ = write!(token, "{byte:02x}");
+            token
+        })
+    }
+
+    /// Decode the token and check, that it belongs to the collection and to the ordering
+    pub fn decode(
+        token: &str,
+        collection: &str,
+        order_by: Option<&OrderBy>,
+    ) -> CollectionResult<Self> {
+        let invalid_token = || CollectionError::bad_input(format!("Invalid page token {token}"));
+
+        if token.len() % 2 != 0 || !token.is_ascii() {
+            return Err(invalid_token());
+        }
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&token[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid_token())?;
+        let page_token: Self = serde_json::from_slice(&bytes).map_err(|_| invalid_token())?;
+
+        if page_token.collection != collection {
+            return Err(CollectionError::bad_input(format!(
+                "Page token belongs to collection {}, not to {collection}",
+                page_token.collection,
+            )));
+        }
+        let token_order_key = page_token.order_by.as_ref().map(|(key, _)| key);
+        if token_order_key != order_by.map(|order_by| &order_by.key) {
+            return Err(CollectionError::bad_input(
+                "Page token was issued for a different `order_by`".to_string(),
+            ));
+        }
+        Ok(page_token)
+    }
+
+    pub fn last_id(&self) -> PointIdType {
+        self.last_id
+    }
+
+    pub fn order_value(&self) -> Option<OrderValue> {
+        self.order_by.as_ref().map(|(_, order_value)| *order_value)
+    }
+
+    /// Check if the token position precedes the point, so the point goes to the next pages
+    pub fn precedes(&self, point: &Record, order_by: Option<&OrderBy>) -> bool {
+        match (order_by, self.order_value(), point.order_value) {
+            (Some(order_by), Some(last_value), Some(value)) => order_by
+                .direction()
+                .compare(&(value, point.id), &(last_value, self.last_id))
+                .is_gt(),
+            _ => point.id > self.last_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::data_types::order_by::Direction;
+
+    use super::*;
+
+    fn record(id: u64, order_value: Option<i64>) -> Record {
+        Record {
+            id: id.into(),
+            payload: None,
+            vector: None,
+            order_value: order_value.map(OrderValue::Int),
+            version: None,
+        }
+    }
+
+    #[test]
+    fn test_page_token_roundtrip() {
+        let token = ScrollPageToken::new("test", &record(10, None), None);
+        let encoded = token.encode();
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(
+            ScrollPageToken::decode(&encoded, "test", None).unwrap(),
+            token
+        );
+
+        assert!(ScrollPageToken::decode(&encoded, "other", None).is_err());
+        assert!(ScrollPageToken::decode("not a token", "test", None).is_err());
+        assert!(ScrollPageToken::decode(&encoded[1..], "test", None).is_err());
+
+        let order_by = OrderBy {
+            key: "num".to_string(),
+            direction: None,
+            start_from: None,
+        };
+        assert!(ScrollPageToken::decode(&encoded, "test", Some(&order_by)).is_err());
+    }
+
+    #[test]
+    fn test_page_token_position() {
+        let token = ScrollPageToken::new("test", &record(10, None), None);
+        assert!(!token.precedes(&record(9, None), None));
+        assert!(!token.precedes(&record(10, None), None));
+        assert!(token.precedes(&record(11, None), None));
+
+        let order_by = OrderBy {
+            key: "num".to_string(),
+            direction: Some(Direction::Desc),
+            start_from: None,
+        };
+        let token = ScrollPageToken::new("test", &record(10, Some(5)), Some(&order_by));
+        let encoded = token.encode();
+        let token = ScrollPageToken::decode(&encoded, "test", Some(&order_by)).unwrap();
+        assert!(!token.precedes(&record(10, Some(5)), Some(&order_by)));
+        assert!(!token.precedes(&record(1, Some(6)), Some(&order_by)));
+        assert!(token.precedes(&record(9, Some(5)), Some(&order_by)));
+        assert!(token.precedes(&record(20, Some(4)), Some(&order_by)));
+    }
+}
@@ -1,12 +1,13 @@
+use std::borrow::Cow;
+
 use schemars::JsonSchema;
-use segment::types::{Filter, Payload, PayloadKeyType, PointIdType};
+use segment::types::{Filter, Payload, PayloadKeyType, PointIdType, SeqNumberType};
 use serde;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use super::{split_iter_by_shard, OperationToShard, SplitByShard};
-use crate::hash_ring::HashRing;
-use crate::shards::shard::ShardId;
+use crate::hash_ring::HashRingRouter;
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(try_from = "SetPayloadShadow")]
@@ -16,6 +17,15 @@ pub struct SetPayload {
     pub points: Option<Vec<PointIdType>>,
     /// Assigns payload to each point that satisfy this filter condition
     pub filter: Option<Filter>,
+    /// Assigns payload only if all the points have this version.
+    /// Otherwise the update is rejected as a conflict and no point is changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<SeqNumberType>,
+    /// Merges payload into the object at this path instead of the top level of the payload.
+    /// Missing objects on the path are created, e.g. `metadata.stats`, `items[].stats`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub key: Option<PayloadKeyType>,
 }
 
 #[derive(Deserialize)]
@@ -23,6 +33,10 @@ struct SetPayloadShadow {
     pub payload: Payload,
     pub points: Option<Vec<PointIdType>>,
     pub filter: Option<Filter>,
+    #[serde(default)]
+    pub if_version: Option<SeqNumberType>,
+    #[serde(default)]
+    pub key: Option<PayloadKeyType>,
 }
 
 pub struct PointsSelectorValidationError;
@@ -45,6 +59,8 @@ impl TryFrom<SetPayloadShadow> for SetPayload {
                 payload: value.payload,
                 points: value.points,
                 filter: value.filter,
+                if_version: value.if_version,
+                key: value.key,
             })
         } else {
             Err(PointsSelectorValidationError)
@@ -61,6 +77,10 @@ pub struct DeletePayload {
     pub points: Option<Vec<PointIdType>>,
     /// Deletes values from points that satisfy this filter condition
     pub filter: Option<Filter>,
+    /// Deletes values only if all the points have this version.
+    /// Otherwise the update is rejected as a conflict and no point is changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<SeqNumberType>,
 }
 
 #[derive(Deserialize)]
@@ -68,6 +88,8 @@ struct DeletePayloadShadow {
     pub keys: Vec<PayloadKeyType>,
     pub points: Option<Vec<PointIdType>>,
     pub filter: Option<Filter>,
+    #[serde(default)]
+    pub if_version: Option<SeqNumberType>,
 }
 
 impl TryFrom<DeletePayloadShadow> for DeletePayload {
@@ -79,6 +101,7 @@ impl TryFrom<DeletePayloadShadow> for DeletePayload {
                 keys: value.keys,
                 points: value.points,
                 filter: value.filter,
+                if_version: value.if_version,
             })
         } else {
             Err(PointsSelectorValidationError)
@@ -121,13 +144,24 @@ impl Validate for PayloadOps {
             PayloadOps::DeletePayload(operation) => operation.validate(),
             PayloadOps::ClearPayload { .. } => Ok(()),
             PayloadOps::ClearPayloadByFilter(_) => Ok(()),
+            PayloadOps::OverwritePayload(operation) if operation.key.is_some() => {
+                let mut errors = validator::ValidationErrors::new();
+                errors.add("key", {
+                    let mut error = validator::ValidationError::new("overwrite_payload");
+                    error.message.replace(Cow::from(
+                        "key can't be used with overwrite payload operation",
+                    ));
+                    error
+                });
+                Err(errors)
+            }
             PayloadOps::OverwritePayload(operation) => operation.validate(),
         }
     }
 }
 
 impl SplitByShard for PayloadOps {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         match self {
             PayloadOps::SetPayload(operation) => {
                 operation.split_by_shard(ring).map(PayloadOps::SetPayload)
@@ -146,7 +180,7 @@ impl SplitByShard for PayloadOps {
 }
 
 impl SplitByShard for DeletePayload {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         match (&self.points, &self.filter) {
             (Some(_), _) => {
                 split_iter_by_shard(self.points.unwrap(), |id| *id, ring).map(|points| {
@@ -154,6 +188,7 @@ impl SplitByShard for DeletePayload {
                         points: Some(points),
                         keys: self.keys.clone(),
                         filter: self.filter.clone(),
+                        if_version: self.if_version,
                     }
                 })
             }
@@ -164,13 +199,15 @@ impl SplitByShard for DeletePayload {
 }
 
 impl SplitByShard for SetPayload {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         match (&self.points, &self.filter) {
             (Some(_), _) => {
                 split_iter_by_shard(self.points.unwrap(), |id| *id, ring).map(|points| SetPayload {
                     points: Some(points),
                     payload: self.payload.clone(),
                     filter: self.filter.clone(),
+                    if_version: self.if_version,
+                    key: self.key.clone(),
                 })
             }
             (None, Some(_)) => OperationToShard::to_all(self),
@@ -5,15 +5,19 @@ use itertools::izip;
 use schemars::gen::SchemaGenerator;
 use schemars::schema::{ObjectValidation, Schema, SchemaObject, SubschemaValidation};
 use schemars::JsonSchema;
+use segment::common::find_non_finite_element;
 use segment::common::utils::transpose_map_into_named_vector;
 use segment::data_types::named_vectors::NamedVectors;
-use segment::data_types::vectors::{only_default_vector, BatchVectorStruct, VectorStruct};
+use segment::data_types::vectors::{
+    only_default_vector, BatchVectorStruct, VectorElementType, VectorStruct, VectorType,
+    DEFAULT_VECTOR_NAME,
+};
 use segment::types::{Filter, Payload, PointIdType};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use super::{point_to_shard, split_iter_by_shard, OperationToShard, SplitByShard};
-use crate::hash_ring::HashRing;
+use crate::hash_ring::HashRingRouter;
 use crate::operations::types::Record;
 use crate::shards::shard::ShardId;
 
@@ -23,7 +27,7 @@ use crate::shards::shard::ShardId;
 ///
 /// * `medium` - write operations go through dynamically selected leader, may be inconsistent for a short period of time in case of leader change
 ///
-/// * `strong` - Write operations go through the permanent leader, consistent, but may be unavailable if leader is down
+/// * `strong` - Write operations go through the permanent leader and are acknowledged once applied by it, consistent, but may be unavailable if leader is down
 ///
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
@@ -55,6 +59,8 @@ impl TryFrom<Record> for PointStruct {
             id,
             payload,
             vector,
+            order_value: _,
+            version: _,
         } = record;
 
         if vector.is_none() {
@@ -214,23 +220,58 @@ impl JsonSchema for PointInsertOperations {
     }
 }
 
+/// Check that all components of the point vector are finite numbers
+///
+/// Returns a description of the first invalid component.
+pub(crate) fn check_vector_finite(
+    point_id: PointIdType,
+    vector_name: &str,
+    vector: &[VectorElementType],
+) -> Result<(), String> {
+    let Some(position) = find_non_finite_element(vector) else {
+        return Ok(());
+    };
+    let vector_name = if vector_name == DEFAULT_VECTOR_NAME {
+        String::new()
+    } else {
+        format!(" of vector `{vector_name}`")
+    };
+    Err(format!(
+        "point {point_id} has component {} at position {position}{vector_name}, \
+         vector components must be finite numbers",
+        vector[position],
+    ))
+}
+
 impl Validate for PointInsertOperations {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
+        let create_error = |field: &'static str, message: String| -> validator::ValidationErrors {
+            let mut errors = validator::ValidationErrors::new();
+            errors.add(field, {
+                let mut error = validator::ValidationError::new("point_insert_operation");
+                error.message.replace(Cow::from(message));
+                error
+            });
+            errors
+        };
+
         match self {
-            PointInsertOperations::PointsList(_) => Ok(()),
+            PointInsertOperations::PointsList(points) => {
+                // Reject the whole operation, so that none of the points is applied
+                for point in points {
+                    let vectors = point.get_vectors();
+                    for (vector_name, vector) in vectors.iter() {
+                        check_vector_finite(point.id, vector_name, vector)
+                            .map_err(|message| create_error("points", message))?;
+                    }
+                }
+                Ok(())
+            }
             PointInsertOperations::PointsBatch(batch) => {
                 let bad_input_description = |ids: usize, vecs: usize| -> String {
                     format!("number of ids and vectors must be equal ({ids} != {vecs})")
                 };
-                let create_error = |message: String| -> validator::ValidationErrors {
-                    let mut errors = validator::ValidationErrors::new();
-                    errors.add("batch", {
-                        let mut error = validator::ValidationError::new("point_insert_operation");
-                        error.message.replace(Cow::from(message));
-                        error
-                    });
-                    errors
-                };
+                let create_error = |message: String| create_error("batch", message);
 
                 match &batch.vectors {
                     BatchVectorStruct::Single(vectors) => {
@@ -261,6 +302,21 @@ impl Validate for PointInsertOperations {
                         )));
                     }
                 }
+
+                // Reject the whole batch, so that none of the points is applied
+                let named_batches: Vec<(&str, &Vec<VectorType>)> = match &batch.vectors {
+                    BatchVectorStruct::Single(vectors) => vec![(DEFAULT_VECTOR_NAME, vectors)],
+                    BatchVectorStruct::Multi(named_vectors) => named_vectors
+                        .iter()
+                        .map(|(name, vectors)| (name.as_str(), vectors))
+                        .collect(),
+                };
+                for (vector_name, vectors) in named_batches {
+                    for (point_id, vector) in batch.ids.iter().zip(vectors) {
+                        check_vector_finite(*point_id, vector_name, vector)
+                            .map_err(create_error)?;
+                    }
+                }
                 Ok(())
             }
         }
@@ -268,7 +324,7 @@ impl Validate for PointInsertOperations {
 }
 
 impl SplitByShard for PointInsertOperations {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         match self {
             PointInsertOperations::PointsBatch(batch) => batch
                 .split_by_shard(ring)
@@ -328,7 +384,7 @@ impl Validate for PointOperations {
 }
 
 impl SplitByShard for Batch {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         let batch = self;
         let mut batch_by_shard: HashMap<ShardId, Batch> = HashMap::new();
         let Batch {
@@ -426,13 +482,13 @@ impl SplitByShard for Batch {
 }
 
 impl SplitByShard for Vec<PointStruct> {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         split_iter_by_shard(self, |point| point.id, ring)
     }
 }
 
 impl SplitByShard for PointOperations {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         match self {
             PointOperations::UpsertPoints(upsert_points) => upsert_points
                 .split_by_shard(ring)
@@ -499,5 +555,12 @@ mod tests {
             payloads: Some(vec![]),
         });
         assert!(matches!(batch.validate(), Err(_)));
+
+        let batch = PointInsertOperations::PointsBatch(Batch {
+            ids: vec![PointIdType::NumId(0), PointIdType::NumId(1)],
+            vectors: vec![vec![0.1], vec![f32::NAN]].into(),
+            payloads: None,
+        });
+        assert!(matches!(batch.validate(), Err(_)));
     }
 }
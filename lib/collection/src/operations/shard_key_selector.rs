@@ -0,0 +1,67 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::shards::shard::{ShardId, ShardKey};
+
+/// Shard key, or list of shard keys, to select shards of a collection with custom sharding
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ShardKeySelector {
+    ShardKey(ShardKey),
+    ShardKeys(Vec<ShardKey>),
+}
+
+impl From<ShardKey> for ShardKeySelector {
+    fn from(shard_key: ShardKey) -> Self {
+        ShardKeySelector::ShardKey(shard_key)
+    }
+}
+
+/// Shards, which an operation of the collection is executed on
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ShardSelectorInternal {
+    /// No shards are selected by the client.
+    /// Selects all shards of a collection with automatic sharding,
+    /// requests to a collection with custom sharding must select a shard key instead.
+    #[default]
+    Empty,
+    /// All shards of the collection
+    All,
+    /// Shards of the shard key
+    ShardKey(ShardKey),
+    /// Shards of all listed shard keys
+    ShardKeys(Vec<ShardKey>),
+    /// Single shard, selected by another peer of the cluster
+    ShardId(ShardId),
+}
+
+impl ShardSelectorInternal {
+    /// Operation is sent by another peer, for a single shard of this peer
+    pub fn is_shard_id(&self) -> bool {
+        matches!(self, ShardSelectorInternal::ShardId(_))
+    }
+}
+
+impl From<Option<ShardId>> for ShardSelectorInternal {
+    fn from(shard_selection: Option<ShardId>) -> Self {
+        match shard_selection {
+            None => ShardSelectorInternal::All,
+            Some(shard_id) => ShardSelectorInternal::ShardId(shard_id),
+        }
+    }
+}
+
+impl From<ShardKey> for ShardSelectorInternal {
+    fn from(shard_key: ShardKey) -> Self {
+        ShardSelectorInternal::ShardKey(shard_key)
+    }
+}
+
+impl From<ShardKeySelector> for ShardSelectorInternal {
+    fn from(selector: ShardKeySelector) -> Self {
+        match selector {
+            ShardKeySelector::ShardKey(shard_key) => ShardSelectorInternal::ShardKey(shard_key),
+            ShardKeySelector::ShardKeys(shard_keys) => ShardSelectorInternal::ShardKeys(shard_keys),
+        }
+    }
+}
@@ -7,6 +7,12 @@ use crate::operations::types::NodeType;
 const DEFAULT_SEARCH_TIMEOUT: Duration = Duration::from_secs(60);
 const DEFAULT_UPDATE_QUEUE_SIZE: usize = 100;
 const DEFAULT_UPDATE_QUEUE_SIZE_LISTENER: usize = 10_000;
+/// By default only operations, which are already queued, are committed together.
+const DEFAULT_UPDATE_GROUP_COMMIT_WINDOW: Duration = Duration::ZERO;
+const DEFAULT_UPDATE_GROUP_COMMIT_MAX_OPS: usize = 64;
+const DEFAULT_CLIENT_OP_ID_WINDOW: usize = 10_000;
+const DEFAULT_OPTIMIZER_TASKS_RETENTION: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_RESOURCE_USAGE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Storage configuration shared between all collections.
 /// Represents a per-node configuration, which might be changes with restart.
@@ -18,6 +24,18 @@ pub struct SharedStorageConfig {
     pub handle_collection_load_errors: bool,
     pub recovery_mode: Option<String>,
     pub search_timeout: Duration,
+    /// How long update worker waits for more operations to share a single WAL flush with
+    pub update_group_commit_window: Duration,
+    /// Max number of operations committed with a single WAL flush
+    pub update_group_commit_max_ops: usize,
+    /// Try to recover replicas from WAL delta before transferring the whole shard
+    pub shard_transfer_wal_delta: bool,
+    /// Number of recent client operation ids, remembered by each shard to skip retried operations
+    pub client_op_id_window: usize,
+    /// How long finished optimization tasks are listed by shards
+    pub optimizer_tasks_retention: Duration,
+    /// How long the computed resource usage of collections is cached
+    pub resource_usage_refresh_interval: Duration,
 }
 
 impl Default for SharedStorageConfig {
@@ -28,17 +46,30 @@ impl Default for SharedStorageConfig {
             handle_collection_load_errors: false,
             recovery_mode: None,
             search_timeout: DEFAULT_SEARCH_TIMEOUT,
+            update_group_commit_window: DEFAULT_UPDATE_GROUP_COMMIT_WINDOW,
+            update_group_commit_max_ops: DEFAULT_UPDATE_GROUP_COMMIT_MAX_OPS,
+            shard_transfer_wal_delta: false,
+            client_op_id_window: DEFAULT_CLIENT_OP_ID_WINDOW,
+            optimizer_tasks_retention: DEFAULT_OPTIMIZER_TASKS_RETENTION,
+            resource_usage_refresh_interval: DEFAULT_RESOURCE_USAGE_REFRESH_INTERVAL,
         }
     }
 }
 
 impl SharedStorageConfig {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         update_queue_size: Option<usize>,
         node_type: NodeType,
         handle_collection_load_errors: bool,
         recovery_mode: Option<String>,
         search_timeout: Option<Duration>,
+        update_group_commit_window: Option<Duration>,
+        update_group_commit_max_ops: Option<usize>,
+        shard_transfer_wal_delta: bool,
+        client_op_id_window: Option<usize>,
+        optimizer_tasks_retention: Option<Duration>,
+        resource_usage_refresh_interval: Option<Duration>,
     ) -> Self {
         let update_queue_size = update_queue_size.unwrap_or(match node_type {
             NodeType::Normal => DEFAULT_UPDATE_QUEUE_SIZE,
@@ -51,6 +82,17 @@ impl SharedStorageConfig {
             handle_collection_load_errors,
             recovery_mode,
             search_timeout: search_timeout.unwrap_or(DEFAULT_SEARCH_TIMEOUT),
+            update_group_commit_window: update_group_commit_window
+                .unwrap_or(DEFAULT_UPDATE_GROUP_COMMIT_WINDOW),
+            update_group_commit_max_ops: update_group_commit_max_ops
+                .unwrap_or(DEFAULT_UPDATE_GROUP_COMMIT_MAX_OPS)
+                .max(1),
+            shard_transfer_wal_delta,
+            client_op_id_window: client_op_id_window.unwrap_or(DEFAULT_CLIENT_OP_ID_WINDOW),
+            optimizer_tasks_retention: optimizer_tasks_retention
+                .unwrap_or(DEFAULT_OPTIMIZER_TASKS_RETENTION),
+            resource_usage_refresh_interval: resource_usage_refresh_interval
+                .unwrap_or(DEFAULT_RESOURCE_USAGE_REFRESH_INTERVAL),
         }
     }
 }
@@ -1,14 +1,22 @@
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use api::grpc::conversions::date_time_to_proto;
 use chrono::NaiveDateTime;
 use schemars::JsonSchema;
+use segment::common::snapshot_manifest::FileManifest;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use validator::Validate;
 
-use crate::operations::types::CollectionResult;
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// File in the root of the collection snapshot, which lists aliases of the collection
+pub const SNAPSHOT_ALIASES_FILE: &str = "aliases.json";
+
+/// Extension of the file next to the snapshot, which contains the checksum of the snapshot
+const SNAPSHOT_CHECKSUM_EXTENSION: &str = "checksum";
 
 /// Defines source of truth for snapshot recovery
 /// `Snapshot` means - prefer snapshot data over the current state
@@ -40,6 +48,9 @@ pub struct SnapshotDescription {
     pub name: String,
     pub creation_time: Option<NaiveDateTime>,
     pub size: u64,
+    /// Hex encoded seahash of the snapshot file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
 }
 
 impl From<SnapshotDescription> for api::grpc::qdrant::SnapshotDescription {
@@ -48,6 +59,7 @@ impl From<SnapshotDescription> for api::grpc::qdrant::SnapshotDescription {
             name: value.name,
             creation_time: value.creation_time.map(date_time_to_proto),
             size: value.size as i64,
+            checksum: value.checksum,
         }
     }
 }
@@ -64,10 +76,63 @@ pub async fn get_snapshot_description(path: &Path) -> CollectionResult<SnapshotD
             })
     });
     let size = file_meta.len();
+    let checksum = match tokio::fs::read_to_string(snapshot_checksum_path(path)).await {
+        Ok(checksum) => Some(checksum.trim().to_string()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err.into()),
+    };
     Ok(SnapshotDescription {
         name: name.to_string(),
         creation_time,
         size,
+        checksum,
+    })
+}
+
+pub fn snapshot_checksum_path(snapshot_path: &Path) -> PathBuf {
+    let mut path = snapshot_path.as_os_str().to_owned();
+    path.push(".");
+    path.push(SNAPSHOT_CHECKSUM_EXTENSION);
+    path.into()
+}
+
+/// Compute the checksum of the snapshot and save it next to the snapshot
+pub async fn save_snapshot_checksum(snapshot_path: &Path) -> CollectionResult<()> {
+    let snapshot_path = snapshot_path.to_owned();
+    tokio::task::spawn_blocking(move || {
+        let manifest = FileManifest::of_file(&snapshot_path)?;
+        std::fs::write(snapshot_checksum_path(&snapshot_path), manifest.checksum)?;
+        Ok(())
+    })
+    .await?
+}
+
+/// Delete the snapshot together with its checksum file
+pub async fn remove_snapshot_file(snapshot_path: &Path) -> CollectionResult<()> {
+    tokio::fs::remove_file(snapshot_path).await?;
+    match tokio::fs::remove_file(snapshot_checksum_path(snapshot_path)).await {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn save_snapshot_aliases(snapshot_dir: &Path, aliases: &[String]) -> CollectionResult<()> {
+    let file = std::fs::File::create(snapshot_dir.join(SNAPSHOT_ALIASES_FILE))?;
+    serde_json::to_writer(file, aliases).map_err(|err| {
+        CollectionError::service_error(format!("Failed to save snapshot aliases: {err}"))
+    })
+}
+
+/// Load aliases, saved in the collection snapshot. Older snapshots contain no aliases.
+pub fn load_snapshot_aliases(snapshot_dir: &Path) -> CollectionResult<Vec<String>> {
+    let file = match std::fs::File::open(snapshot_dir.join(SNAPSHOT_ALIASES_FILE)) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    serde_json::from_reader(file).map_err(|err| {
+        CollectionError::service_error(format!("Failed to read snapshot aliases: {err}"))
     })
 }
 
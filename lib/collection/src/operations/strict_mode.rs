@@ -0,0 +1,442 @@
+use std::time::Duration;
+
+use merge::Merge;
+use schemars::JsonSchema;
+use segment::common::utils::JsonPathPayload;
+use segment::types::{Condition, FieldCondition, Filter, Payload, PayloadKeyType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::operations::config_diff::DiffConfig;
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{PointInsertOperations, PointOperations};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::vector_ops::VectorOperations;
+use crate::operations::CollectionUpdateOperations;
+
+/// Limits of requests to the collection, which protect the cluster from expensive requests.
+/// All limits are disabled by default, the limits are only checked if strict mode is enabled.
+#[derive(
+    Debug,
+    Default,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    Validate,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Merge,
+    Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct StrictModeConfig {
+    /// Whether strict mode is enabled for the collection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Max number of conditions in a filter, nested conditions included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_filter_conditions: Option<usize>,
+    /// Max number of points in a single upsert or update vectors request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub upsert_max_batchsize: Option<usize>,
+    /// Max `limit` of search, query, scroll and facet requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_query_limit: Option<usize>,
+    /// Max `offset` of search and query requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_query_offset: Option<usize>,
+    /// Max size of the payload of a single point, in bytes of its JSON representation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_point_payload_bytes: Option<usize>,
+    /// If false - searches, scrolls and counts can't be filtered by fields without a payload index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unindexed_filtering_retrieve: Option<bool>,
+    /// If false - updates can't select points by a filter on fields without a payload index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unindexed_filtering_update: Option<bool>,
+    /// Max number of searches, running at once in the collection on this peer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_concurrent_searches: Option<usize>,
+    /// Max number of update requests per second to the collection on this peer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_updates_per_second: Option<usize>,
+    /// Max time in milliseconds a request waits for `max_concurrent_searches` or
+    /// `max_updates_per_second`, before it is rejected. Rejected without waiting by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_timeout_ms: Option<u64>,
+}
+
+impl DiffConfig<StrictModeConfig> for StrictModeConfig {}
+
+impl StrictModeConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(false)
+    }
+
+    /// Whether filters of reads or of updates may use fields without a payload index
+    pub fn allows_unindexed_filtering(&self, is_update: bool) -> bool {
+        let allow_unindexed = if is_update {
+            self.unindexed_filtering_update
+        } else {
+            self.unindexed_filtering_retrieve
+        };
+        allow_unindexed.unwrap_or(true)
+    }
+
+    /// How long a request waits for the rate limits, before it is rejected
+    pub fn rate_limit_timeout(&self) -> Duration {
+        Duration::from_millis(self.rate_limit_timeout_ms.unwrap_or(0))
+    }
+
+    /// Check `limit` and `offset` of a read request
+    pub fn check_limit_and_offset(&self, limit: usize, offset: usize) -> CollectionResult<()> {
+        if let Some(max_query_limit) = self.max_query_limit {
+            if limit > max_query_limit {
+                return Err(CollectionError::strict_mode(format!(
+                    "Limit {limit} exceeds `max_query_limit` of {max_query_limit}"
+                )));
+            }
+        }
+        if let Some(max_query_offset) = self.max_query_offset {
+            if offset > max_query_offset {
+                return Err(CollectionError::strict_mode(format!(
+                    "Offset {offset} exceeds `max_query_offset` of {max_query_offset}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the number of conditions of the filter and whether its fields are indexed
+    ///
+    /// `is_update` selects the limit of unindexed fields to check, `is_indexed` tells if the
+    /// given full path of a payload field has an index.
+    pub fn check_filter(
+        &self,
+        filter: &Filter,
+        is_update: bool,
+        is_indexed: impl Fn(&str) -> bool,
+    ) -> CollectionResult<()> {
+        if let Some(max_filter_conditions) = self.max_filter_conditions {
+            let conditions = count_conditions(filter);
+            if conditions > max_filter_conditions {
+                return Err(CollectionError::strict_mode(format!(
+                    "Filter has {conditions} conditions, which exceeds `max_filter_conditions` of {max_filter_conditions}"
+                )));
+            }
+        }
+        if !self.allows_unindexed_filtering(is_update) {
+            if let Some(key) = filtered_keys(filter, None)
+                .into_iter()
+                .find(|key| !is_indexed(key))
+            {
+                let limit_name = if is_update {
+                    "unindexed_filtering_update"
+                } else {
+                    "unindexed_filtering_retrieve"
+                };
+                return Err(CollectionError::strict_mode(format!(
+                    "Filter by field `{key}` without payload index is forbidden by `{limit_name}`, create an index for the field"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check batch size and payload sizes of an update operation
+    pub fn check_update(&self, operation: &CollectionUpdateOperations) -> CollectionResult<()> {
+        if let Some(upsert_max_batchsize) = self.upsert_max_batchsize {
+            let batch_size = match operation {
+                CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                    PointInsertOperations::PointsBatch(batch),
+                )) => Some(batch.ids.len()),
+                CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                    PointInsertOperations::PointsList(points),
+                )) => Some(points.len()),
+                CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(
+                    update_vectors,
+                )) => Some(update_vectors.points.len()),
+                _ => None,
+            };
+            if let Some(batch_size) = batch_size.filter(|size| *size > upsert_max_batchsize) {
+                return Err(CollectionError::strict_mode(format!(
+                    "Batch of {batch_size} points exceeds `upsert_max_batchsize` of {upsert_max_batchsize}"
+                )));
+            }
+        }
+        if let Some(max_point_payload_bytes) = self.max_point_payload_bytes {
+            for payload in operation_payloads(operation) {
+                let payload_bytes = serde_json::to_vec(payload)?.len();
+                if payload_bytes > max_point_payload_bytes {
+                    return Err(CollectionError::strict_mode(format!(
+                        "Payload of {payload_bytes} bytes exceeds `max_point_payload_bytes` of {max_point_payload_bytes}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Filter of points affected by an update operation, if points are selected by a filter
+pub fn operation_filter(operation: &CollectionUpdateOperations) -> Option<&Filter> {
+    match operation {
+        CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(
+            filter,
+        )) => Some(filter),
+        CollectionUpdateOperations::PointOperation(_) => None,
+        CollectionUpdateOperations::VectorOperation(VectorOperations::DeleteVectorsByFilter(
+            filter,
+            _,
+        )) => Some(filter),
+        CollectionUpdateOperations::VectorOperation(_) => None,
+        CollectionUpdateOperations::PayloadOperation(payload_op) => match payload_op {
+            PayloadOps::SetPayload(set_payload) | PayloadOps::OverwritePayload(set_payload) => {
+                set_payload.filter.as_ref()
+            }
+            PayloadOps::DeletePayload(delete_payload) => delete_payload.filter.as_ref(),
+            PayloadOps::ClearPayload { .. } => None,
+            PayloadOps::ClearPayloadByFilter(filter) => Some(filter),
+        },
+        CollectionUpdateOperations::FieldIndexOperation(_) => None,
+    }
+}
+
+/// Payloads written to points by an update operation
+fn operation_payloads(operation: &CollectionUpdateOperations) -> Vec<&Payload> {
+    match operation {
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsBatch(batch),
+        )) => batch
+            .payloads
+            .iter()
+            .flatten()
+            .filter_map(Option::as_ref)
+            .collect(),
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsList(points),
+        )) => points
+            .iter()
+            .filter_map(|point| point.payload.as_ref())
+            .collect(),
+        CollectionUpdateOperations::PayloadOperation(
+            PayloadOps::SetPayload(set_payload) | PayloadOps::OverwritePayload(set_payload),
+        ) => vec![&set_payload.payload],
+        _ => vec![],
+    }
+}
+
+/// Number of conditions of the filter, conditions of nested filters are counted recursively
+fn count_conditions(filter: &Filter) -> usize {
+    let conditions = filter
+        .should
+        .iter()
+        .chain(filter.must.iter())
+        .chain(filter.must_not.iter())
+        .flatten()
+        .chain(
+            filter
+                .min_should
+                .iter()
+                .flat_map(|min_should| min_should.conditions.iter()),
+        );
+    conditions
+        .map(|condition| match condition {
+            Condition::Filter(filter) => count_conditions(filter),
+            Condition::Nested(nested) => count_conditions(nested.filter()),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Full paths of the payload fields the filter checks
+fn filtered_keys(filter: &Filter, nested_path: Option<&JsonPathPayload>) -> Vec<PayloadKeyType> {
+    filtered_fields(filter, nested_path)
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect()
+}
+
+/// Full paths of the payload fields the filter checks, along with the field condition, if any
+pub(crate) fn filtered_fields<'a>(
+    filter: &'a Filter,
+    nested_path: Option<&JsonPathPayload>,
+) -> Vec<(PayloadKeyType, Option<&'a FieldCondition>)> {
+    let conditions = filter
+        .should
+        .iter()
+        .chain(filter.must.iter())
+        .chain(filter.must_not.iter())
+        .flatten()
+        .chain(
+            filter
+                .min_should
+                .iter()
+                .flat_map(|min_should| min_should.conditions.iter()),
+        );
+    let full_path = |key: &str| JsonPathPayload::extend_or_new(nested_path, key).path;
+    conditions
+        .flat_map(|condition| match condition {
+            Condition::Field(field) => vec![(full_path(&field.key), Some(field))],
+            Condition::IsEmpty(is_empty) => vec![(full_path(&is_empty.is_empty.key), None)],
+            Condition::IsNull(is_null) => vec![(full_path(&is_null.is_null.key), None)],
+            Condition::HasId(_) => vec![],
+            Condition::Nested(nested) => {
+                let nested_path = JsonPathPayload::extend_or_new(nested_path, &nested.array_key());
+                filtered_fields(nested.filter(), Some(&nested_path))
+            }
+            Condition::Filter(filter) => filtered_fields(filter, nested_path),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use segment::types::{FieldCondition, MinShould};
+    use serde_json::json;
+
+    use super::*;
+    use crate::operations::payload_ops::SetPayload;
+    use crate::operations::point_ops::PointStruct;
+
+    fn field(key: &str) -> Condition {
+        Condition::Field(FieldCondition::new_match(key, 1_i64.into()))
+    }
+
+    fn nested_filter() -> Filter {
+        Filter {
+            should: Some(vec![field("a"), field("b")]),
+            min_should: Some(MinShould {
+                conditions: vec![
+                    field("c"),
+                    Condition::HasId(HashSet::from([1_u64.into()]).into()),
+                ],
+                min_count: 1,
+            }),
+            must: Some(vec![Condition::new_nested(
+                "arr",
+                Filter::new_must(Condition::Filter(Filter::new_must_not(field("d")))),
+            )]),
+            must_not: None,
+        }
+    }
+
+    #[test]
+    fn test_count_filter_conditions() {
+        let filter = nested_filter();
+        assert_eq!(count_conditions(&filter), 5);
+        assert_eq!(filtered_keys(&filter, None), vec!["a", "b", "arr[].d", "c"],);
+
+        let config = StrictModeConfig {
+            max_filter_conditions: Some(4),
+            ..Default::default()
+        };
+        let err = config.check_filter(&filter, false, |_| true).unwrap_err();
+        assert!(err.to_string().contains("max_filter_conditions"), "{err}");
+        let config = StrictModeConfig {
+            max_filter_conditions: Some(5),
+            ..Default::default()
+        };
+        config.check_filter(&filter, false, |_| true).unwrap();
+    }
+
+    #[test]
+    fn test_unindexed_filtering() {
+        let filter = nested_filter();
+        let config = StrictModeConfig {
+            unindexed_filtering_retrieve: Some(false),
+            unindexed_filtering_update: Some(true),
+            ..Default::default()
+        };
+        let is_indexed = |key: &str| key != "arr[].d";
+        let err = config.check_filter(&filter, false, is_indexed).unwrap_err();
+        assert!(err.to_string().contains("`arr[].d`"), "{err}");
+        assert!(
+            err.to_string().contains("unindexed_filtering_retrieve"),
+            "{err}"
+        );
+        config.check_filter(&filter, true, is_indexed).unwrap();
+        config.check_filter(&filter, false, |_| true).unwrap();
+    }
+
+    #[test]
+    fn test_check_update() {
+        let config = StrictModeConfig {
+            upsert_max_batchsize: Some(2),
+            max_point_payload_bytes: Some(20),
+            ..Default::default()
+        };
+        let point = |id: u64, payload: serde_json::Value| PointStruct {
+            id: id.into(),
+            vector: vec![1.0, 2.0].into(),
+            payload: Some(payload.into()),
+        };
+        let upsert = |points| {
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(points),
+            ))
+        };
+
+        config
+            .check_update(&upsert(vec![point(1, json!({})), point(2, json!({}))]))
+            .unwrap();
+        let err = config
+            .check_update(&upsert(vec![
+                point(1, json!({})),
+                point(2, json!({})),
+                point(3, json!({})),
+            ]))
+            .unwrap_err();
+        assert!(err.to_string().contains("upsert_max_batchsize"), "{err}");
+
+        let large_payload = json!({ "text": "more than twenty bytes" });
+        let err = config
+            .check_update(&upsert(vec![point(1, large_payload.clone())]))
+            .unwrap_err();
+        assert!(err.to_string().contains("max_point_payload_bytes"), "{err}");
+        let set_payload =
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+                payload: large_payload.into(),
+                points: Some(vec![1.into()]),
+                filter: None,
+                if_version: None,
+                key: None,
+            }));
+        assert!(config.check_update(&set_payload).is_err());
+    }
+
+    #[test]
+    fn test_update_strict_mode_config() {
+        let config = StrictModeConfig {
+            enabled: Some(true),
+            max_query_limit: Some(100),
+            ..Default::default()
+        };
+        let diff = StrictModeConfig {
+            max_query_limit: Some(10),
+            max_query_offset: Some(20),
+            ..Default::default()
+        };
+        let updated = diff.update(&config).unwrap();
+        assert!(updated.is_enabled());
+        assert_eq!(updated.max_query_limit, Some(10));
+        assert_eq!(updated.max_query_offset, Some(20));
+        assert!(!StrictModeConfig::default().is_enabled());
+
+        let err = updated.check_limit_and_offset(11, 0).unwrap_err();
+        assert!(err.to_string().contains("max_query_limit"), "{err}");
+        let err = updated.check_limit_and_offset(10, 21).unwrap_err();
+        assert!(err.to_string().contains("max_query_offset"), "{err}");
+        updated.check_limit_and_offset(10, 20).unwrap();
+    }
+}
@@ -1,10 +1,11 @@
 use std::backtrace::Backtrace;
 use std::collections::{BTreeMap, HashMap};
 use std::error::Error as _;
+use std::fmt;
 use std::fmt::Write as _;
 use std::iter;
 use std::num::NonZeroU64;
-use std::time::SystemTimeError;
+use std::time::{Duration, SystemTimeError};
 
 use api::grpc::transport_channel_pool::RequestError;
 use futures::io;
@@ -12,17 +13,22 @@ use merge::Merge;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use segment::common::file_operations::FileStorageError;
+use segment::data_types::facets::FacetValueHit;
 use segment::data_types::groups::GroupId;
+use segment::data_types::order_by::{OrderBy, OrderValue};
+use segment::data_types::search_plan::SegmentSearchPlan;
 use segment::data_types::vectors::{
-    NamedVectorStruct, VectorStruct, VectorType, DEFAULT_VECTOR_NAME,
+    NamedVector, NamedVectorStruct, QueryVector, VectorStruct, VectorType, DEFAULT_VECTOR_NAME,
 };
 use segment::entry::entry_point::OperationError;
 use segment::types::{
-    Distance, Filter, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType, QuantizationConfig,
-    ScoreType, ScoredPoint, SearchParams, SeqNumberType, WithPayloadInterface, WithVector,
+    Distance, Filter, HnswConfig, MultiVectorConfig, Payload, PayloadIndexInfo, PayloadKeyType,
+    PointIdType, QuantizationConfig, Sample, ScoreType, ScoredPoint, SearchParams, SeqNumberType,
+    VectorStorageDatatype, WithPayloadInterface, WithVector,
 };
-use serde;
-use serde::{Deserialize, Serialize};
+use segment::vector_storage::query::discovery_query::DiscoveryQuery;
+use segment::vector_storage::query::reco_query::RecoQuery;
+use serde::{self, Deserialize, Serialize};
 use serde_json::Error as JsonError;
 use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
@@ -36,8 +42,11 @@ use crate::config::{CollectionConfig, CollectionParams};
 use crate::lookup::types::WithLookupInterface;
 use crate::operations::config_diff::{HnswConfigDiff, QuantizationConfigDiff};
 use crate::save_on_disk;
+use crate::shards::replica_failover::ReplicaRecoveryInfo;
 use crate::shards::replica_set::ReplicaState;
+use crate::shards::resharding::ReshardingInfo;
 use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::telemetry::WalRecoveryReport;
 use crate::wal::WalError;
 
 /// Current state of the collection.
@@ -79,6 +88,31 @@ pub struct Record {
     pub payload: Option<Payload>,
     /// Vector of the point
     pub vector: Option<VectorStruct>,
+    /// Value of the `order_by` payload field of the point, if points are ordered by it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_value: Option<OrderValue>,
+    /// Version of the point, used to update the point on condition that it is not changed since.
+    /// Only returned, if requested with `with_version`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<SeqNumberType>,
+}
+
+/// Point, which is not updated, because its version differs from the expected one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointVersionConflict {
+    pub id: PointIdType,
+    pub expected_version: SeqNumberType,
+    pub current_version: SeqNumberType,
+}
+
+impl fmt::Display for PointVersionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "point {} has version {}, but version {} is expected",
+            self.id, self.current_version, self.expected_version
+        )
+    }
 }
 
 /// Current statistics and configuration of the collection
@@ -108,6 +142,37 @@ pub struct CollectionInfo {
     pub config: CollectionConfig,
     /// Types of stored payload
     pub payload_schema: HashMap<PayloadKeyType, PayloadIndexInfo>,
+    /// Index configs of each vector, with the collection-level configs applied
+    pub effective_vectors_config: BTreeMap<String, EffectiveVectorConfig>,
+}
+
+impl CollectionInfo {
+    /// Info of a collection without shards, e.g. a collection with custom sharding before the
+    /// first shard key is created
+    pub fn empty(collection_config: CollectionConfig) -> Self {
+        Self {
+            status: CollectionStatus::Green,
+            optimizer_status: OptimizersStatus::Ok,
+            vectors_count: 0,
+            indexed_vectors_count: 0,
+            points_count: 0,
+            segments_count: 0,
+            effective_vectors_config: collection_config.effective_vectors_config(),
+            config: collection_config,
+            payload_schema: HashMap::new(),
+        }
+    }
+}
+
+/// Configs a vector is indexed with: its own params, if set, or the collection-level ones
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct EffectiveVectorConfig {
+    pub hnsw_config: HnswConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization_config: Option<QuantizationConfig>,
+    /// If true - vectors are stored on disk, and loaded lazily on the first search against them
+    #[serde(default)]
+    pub on_disk: bool,
 }
 
 /// Current clustering distribution for the collection
@@ -123,6 +188,12 @@ pub struct CollectionClusterInfo {
     pub remote_shards: Vec<RemoteShardInfo>,
     /// Shard transfers
     pub shard_transfers: Vec<ShardTransferInfo>,
+    /// Resharding operation in progress
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resharding: Option<ReshardingInfo>,
+    /// Replacements of dead replicas, only known on the consensus leader
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replica_recoveries: Vec<ReplicaRecoveryInfo>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
@@ -144,6 +215,9 @@ pub struct LocalShardInfo {
     pub points_count: usize,
     /// Is replica active
     pub state: ReplicaState,
+    /// Outcome of the WAL replay, made when the shard was loaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_recovery: Option<WalRecoveryReport>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -159,11 +233,13 @@ pub struct RemoteShardInfo {
 
 /// `Acknowledged` - Request is saved to WAL and will be process in a queue.
 /// `Completed` - Request is completed, changes are actual.
+/// `Failed` - Operation of a batch is not applied, the rest of the batch is still processed.
 #[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateStatus {
     Acknowledged,
     Completed,
+    Failed,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -173,6 +249,48 @@ pub struct UpdateResult {
     pub operation_id: SeqNumberType,
     /// Update status
     pub status: UpdateStatus,
+    /// Error of the failed operation of a batch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Number of points deleted by the filter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_count: Option<usize>,
+    /// Ids of the points deleted by the filter, if requested and not more than 10000 points
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_ids: Option<Vec<PointIdType>>,
+}
+
+/// Maximal number of deleted point ids, reported in the result of a delete by filter
+pub const DELETED_IDS_LIMIT: usize = 10_000;
+
+impl UpdateResult {
+    /// Report the points, which a delete by filter resolved to
+    pub fn set_deleted_points(&mut self, ids: Vec<PointIdType>) {
+        self.deleted_count = Some(ids.len());
+        self.deleted_ids = (ids.len() <= DELETED_IDS_LIMIT).then_some(ids);
+    }
+
+    /// Combine results of an operation, applied to multiple shards.
+    ///
+    /// The last result is reported, with the points deleted by filter summed up over all shards.
+    /// Ids are reported only if every shard reported them, and within the limit.
+    pub fn merge_shards(mut results: Vec<UpdateResult>) -> Option<UpdateResult> {
+        let deleted: Vec<_> = results
+            .iter_mut()
+            .filter_map(|result| Some((result.deleted_count?, result.deleted_ids.take())))
+            .collect();
+        let mut result = results.pop()?;
+        if !deleted.is_empty() {
+            result.deleted_count = Some(deleted.iter().map(|(count, _)| count).sum());
+            result.deleted_ids = deleted
+                .into_iter()
+                .map(|(_, ids)| ids)
+                .collect::<Option<Vec<_>>>()
+                .map(|ids| ids.concat())
+                .filter(|ids| ids.len() <= DELETED_IDS_LIMIT);
+        }
+        Some(result)
+    }
 }
 
 /// Scroll request - paginate over all points which matches given condition
@@ -191,6 +309,17 @@ pub struct ScrollRequest {
     /// Whether to return the point vector with the result?
     #[serde(default, alias = "with_vectors")]
     pub with_vector: WithVector,
+    /// Order points by a payload field, instead of by their ids.
+    /// Points with the same value are ordered by id, and `offset` selects the first of the points
+    /// with the `order_by.start_from` value.
+    #[validate]
+    pub order_by: Option<OrderBy>,
+    /// Continue scrolling right after the last point of the previous page, using
+    /// `next_page_token` of the previous response. Can't be combined with `offset` and
+    /// `order_by.start_from`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1))]
+    pub page_token: Option<String>,
 }
 
 impl Default for ScrollRequest {
@@ -201,6 +330,8 @@ impl Default for ScrollRequest {
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(false),
+            order_by: None,
+            page_token: None,
         }
     }
 }
@@ -213,6 +344,13 @@ pub struct ScrollResult {
     pub points: Vec<Record>,
     /// Offset which should be used to retrieve a next page result
     pub next_page_offset: Option<PointIdType>,
+    /// Value which should be used as `order_by.start_from` to retrieve a next page result,
+    /// along with the `next_page_offset`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_start_from: Option<OrderValue>,
+    /// Opaque token, which should be used as `page_token` to retrieve a next page result.
+    /// Unlike the offset, it is not affected by changes of the points between the requests
+    pub next_page_token: Option<String>,
 }
 
 /// Search request.
@@ -254,6 +392,167 @@ pub struct SearchRequest {
 pub struct SearchRequestBatch {
     #[validate]
     pub searches: Vec<SearchRequest>,
+    /// If true, an error of any search fails the whole batch. Otherwise, errors are reported per
+    /// search, along with the results of other searches. Default: false
+    pub fail_fast: Option<bool>,
+}
+
+/// Result of a search of a batch
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(untagged)]
+pub enum BatchSearchResult {
+    /// Points found by the search
+    Points(Vec<ScoredPoint>),
+    /// Error of the search, which did not fail other searches of the batch
+    Error { error: String },
+}
+
+/// Query of a search request, as executed by shards
+#[derive(Debug, Clone)]
+pub enum QueryEnum {
+    /// Points closest to the vector
+    Nearest(NamedVectorStruct),
+    /// Points closest to the best positive example and far from the best negative one
+    RecommendBestScore(NamedQuery<RecoQuery<VectorType>>),
+    /// Points closest to the target, within the region defined by context pairs
+    Discover(NamedQuery<DiscoveryQuery<VectorType>>),
+    /// Sample of points with the vector, scored by random keys
+    Sample(NamedQuery<Sample>),
+}
+
+impl QueryEnum {
+    pub fn get_vector_name(&self) -> &str {
+        match self {
+            QueryEnum::Nearest(vector) => vector.get_name(),
+            QueryEnum::RecommendBestScore(reco_query) => reco_query.get_name(),
+            QueryEnum::Discover(discovery_query) => discovery_query.get_name(),
+            QueryEnum::Sample(sample) => sample.get_name(),
+        }
+    }
+
+    /// All vectors the query compares points with
+    pub fn vectors(&self) -> Vec<&VectorType> {
+        match self {
+            QueryEnum::Nearest(vector) => vec![vector.get_vector()],
+            QueryEnum::RecommendBestScore(reco_query) => reco_query.query.iter_examples().collect(),
+            QueryEnum::Discover(discovery_query) => discovery_query.query.iter_examples().collect(),
+            QueryEnum::Sample(_) => vec![],
+        }
+    }
+
+    /// Check that all vectors of the query can be compared with the queried vector of the
+    /// collection, see [`CollectionParams::check_query_vector`]
+    pub fn check_vectors(&self, collection_params: &CollectionParams) -> CollectionResult<()> {
+        let vector_name = self.get_vector_name();
+        for vector in self.vectors() {
+            collection_params.check_query_vector(vector_name, vector)?;
+        }
+        Ok(())
+    }
+
+    /// Whether scores of the query are similarities with a single vector, see
+    /// [`QueryVector::is_distance_scored`]
+    pub fn is_distance_scored(&self) -> bool {
+        match self {
+            QueryEnum::Nearest(_) => true,
+            QueryEnum::RecommendBestScore(_) | QueryEnum::Discover(_) | QueryEnum::Sample(_) => {
+                false
+            }
+        }
+    }
+}
+
+impl From<NamedVectorStruct> for QueryEnum {
+    fn from(vector: NamedVectorStruct) -> Self {
+        QueryEnum::Nearest(vector)
+    }
+}
+
+impl From<QueryEnum> for QueryVector {
+    fn from(query: QueryEnum) -> Self {
+        match query {
+            QueryEnum::Nearest(vector) => QueryVector::Nearest(NamedVector::from(vector).vector),
+            QueryEnum::RecommendBestScore(reco_query) => QueryVector::Recommend(reco_query.query),
+            QueryEnum::Discover(discovery_query) => QueryVector::Discovery(discovery_query.query),
+            QueryEnum::Sample(sample) => QueryVector::Sample(sample.query),
+        }
+    }
+}
+
+/// Query together with the name of the vector it should be compared with
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedQuery<TQuery> {
+    pub query: TQuery,
+    /// Name of the vector, default vector if not specified
+    pub using: Option<String>,
+}
+
+impl<TQuery> NamedQuery<TQuery> {
+    pub fn get_name(&self) -> &str {
+        self.using.as_deref().unwrap_or(DEFAULT_VECTOR_NAME)
+    }
+}
+
+/// Search request with a query of any kind.
+///
+/// Internal form of all search-like requests, which is executed by shards.
+#[derive(Debug, Clone)]
+pub struct CoreSearchRequest {
+    /// Defines how points are scored
+    pub query: QueryEnum,
+    /// Look only for points which satisfies this conditions
+    pub filter: Option<Filter>,
+    /// Additional search params
+    pub params: Option<SearchParams>,
+    /// Max number of result to return
+    pub limit: usize,
+    /// Offset of the first result to return
+    pub offset: usize,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    pub with_vector: Option<WithVector>,
+    /// Minimal score of the returned points.
+    /// In the distance scale for [`QueryEnum::Nearest`], compared as is for other queries.
+    pub score_threshold: Option<ScoreType>,
+}
+
+impl From<SearchRequest> for CoreSearchRequest {
+    fn from(request: SearchRequest) -> Self {
+        let SearchRequest {
+            vector,
+            filter,
+            params,
+            limit,
+            offset,
+            with_payload,
+            with_vector,
+            score_threshold,
+        } = request;
+        Self {
+            query: QueryEnum::Nearest(vector),
+            filter,
+            params,
+            limit,
+            offset,
+            with_payload,
+            with_vector,
+            score_threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreSearchRequestBatch {
+    pub searches: Vec<CoreSearchRequest>,
+}
+
+impl From<SearchRequestBatch> for CoreSearchRequestBatch {
+    fn from(batch: SearchRequestBatch) -> Self {
+        Self {
+            searches: batch.searches.into_iter().map(Into::into).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -296,6 +595,10 @@ pub struct PointRequest {
     /// Whether to return the point vector with the result?
     #[serde(default, alias = "with_vectors")]
     pub with_vector: WithVector,
+    /// Whether to return the version of the points, which conditional updates are checked against.
+    /// Default: false
+    #[serde(default)]
+    pub with_version: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
@@ -323,21 +626,196 @@ pub struct LookupLocation {
     pub vector: Option<String>,
 }
 
+/// Example of a recommendation request: id of a point stored in the collection, or a raw vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RecommendExample {
+    PointId(PointIdType),
+    Vector(VectorType),
+}
+
+impl RecommendExample {
+    pub fn as_point_id(&self) -> Option<PointIdType> {
+        match self {
+            RecommendExample::PointId(point_id) => Some(*point_id),
+            RecommendExample::Vector(_) => None,
+        }
+    }
+}
+
+impl From<PointIdType> for RecommendExample {
+    fn from(point_id: PointIdType) -> Self {
+        RecommendExample::PointId(point_id)
+    }
+}
+
+impl From<u64> for RecommendExample {
+    fn from(id: u64) -> Self {
+        RecommendExample::PointId(id.into())
+    }
+}
+
+impl From<VectorType> for RecommendExample {
+    fn from(vector: VectorType) -> Self {
+        RecommendExample::Vector(vector)
+    }
+}
+
+/// How to use positive and negative examples to find the results
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendStrategy {
+    /// Average positive and negative vectors and create a single query
+    /// with the formula `query = avg_pos + avg_pos - avg_neg`. Then performs normal search.
+    #[default]
+    AverageVector,
+
+    /// Compare every candidate against all examples. Score of the candidate is the score of its
+    /// best positive example minus the score of its best negative example.
+    /// Requires vectors of the collection to be compared with each example during the search,
+    /// so it is slower than the average vector strategy for many examples.
+    BestScore,
+}
+
+/// Pair of examples, defining the region of points more similar to the positive example than to
+/// the negative one
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ContextExamplePair {
+    pub positive: RecommendExample,
+    pub negative: RecommendExample,
+}
+
+impl ContextExamplePair {
+    pub fn iter(&self) -> impl Iterator<Item = &RecommendExample> {
+        [&self.positive, &self.negative].into_iter()
+    }
+}
+
+/// Discovery request.
+/// Looks for the points most similar to the target, which are also more similar to the positive
+/// example than to the negative one of every context pair. Points which are not in the region
+/// of all pairs are ranked lower, by the number of pairs they are outside of.
+///
+/// Examples can be ids of points already stored in the collection or raw vectors.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct DiscoverRequest {
+    /// Look for vectors closest to this
+    pub target: RecommendExample,
+    /// Pairs of { positive, negative } examples to constrain the search
+    #[validate(length(min = 1))]
+    pub context: Vec<ContextExamplePair>,
+    /// Look only for points which satisfies this conditions
+    pub filter: Option<Filter>,
+    /// Additional search params
+    #[validate]
+    pub params: Option<SearchParams>,
+    /// Max number of result to return
+    #[serde(alias = "top")]
+    #[validate(range(min = 1))]
+    pub limit: usize,
+    /// Offset of the first result to return.
+    /// May be used to paginate results.
+    /// Note: large offset values may cause performance issues.
+    #[serde(default)]
+    pub offset: usize,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default, alias = "with_vectors")]
+    pub with_vector: Option<WithVector>,
+    /// Define which vector to use for discovery, if not specified - try to use default vector
+    #[serde(default)]
+    pub using: Option<UsingVector>,
+    /// The location used to lookup vectors. If not specified - use current collection.
+    /// Note: the other collection should have the same vector size as the current collection
+    #[serde(default)]
+    pub lookup_from: Option<LookupLocation>,
+}
+
+/// Query to look for points by, other than similarity with a vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryInterface {
+    /// Sample points, instead of scoring them by vectors
+    Sample(Sample),
+}
+
+/// Query request.
+/// Looks for points by a query, which doesn't score points by similarity with a vector.
+///
+/// Scores of sampled points are random, and only define the order of results.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct QueryRequest {
+    /// Query to look for points by
+    pub query: QueryInterface,
+    /// Look only for points which satisfies this conditions
+    pub filter: Option<Filter>,
+    /// Max number of result to return
+    #[serde(alias = "top")]
+    #[validate(range(min = 1))]
+    pub limit: usize,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default, alias = "with_vectors")]
+    pub with_vector: Option<WithVector>,
+    /// Define which vector the points must have, if not specified - default vector
+    #[serde(default)]
+    pub using: Option<UsingVector>,
+}
+
+impl From<QueryRequest> for CoreSearchRequest {
+    fn from(request: QueryRequest) -> Self {
+        let QueryRequest {
+            query,
+            filter,
+            limit,
+            with_payload,
+            with_vector,
+            using,
+        } = request;
+        let using = using.map(|UsingVector::Name(name)| name);
+        Self {
+            query: match query {
+                QueryInterface::Sample(sample) => QueryEnum::Sample(NamedQuery {
+                    query: sample,
+                    using,
+                }),
+            },
+            filter,
+            params: None,
+            limit,
+            offset: 0,
+            with_payload,
+            with_vector,
+            score_threshold: None,
+        }
+    }
+}
+
 /// Recommendation request.
-/// Provides positive and negative examples of the vectors, which
-/// are already stored in the collection.
+/// Provides positive and negative examples of the vectors, which can be ids of points
+/// already stored in the collection or raw vectors.
 ///
 /// Service should look for the points which are closer to positive examples and at the same time
-/// further to negative examples. The concrete way of how to compare negative and positive distances
-/// is up to implementation in `segment` crate.
+/// further to negative examples. How negative and positive similarities are combined is defined
+/// by the `strategy`.
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Default, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct RecommendRequest {
     /// Look for vectors closest to those
-    pub positive: Vec<PointIdType>,
+    #[serde(default)]
+    pub positive: Vec<RecommendExample>,
     /// Try to avoid vectors like this
     #[serde(default)]
-    pub negative: Vec<PointIdType>,
+    pub negative: Vec<RecommendExample>,
+    /// How to use positive and negative examples to find the results.
+    /// Default: `average_vector`
+    #[serde(default)]
+    pub strategy: Option<RecommendStrategy>,
     /// Look only for points which satisfies this conditions
     pub filter: Option<Filter>,
     /// Additional search params
@@ -381,11 +859,17 @@ pub struct RecommendRequestBatch {
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 pub struct RecommendGroupsRequest {
     /// Look for vectors closest to those
-    pub positive: Vec<PointIdType>,
+    #[serde(default)]
+    pub positive: Vec<RecommendExample>,
 
     /// Try to avoid vectors like this
     #[serde(default)]
-    pub negative: Vec<PointIdType>,
+    pub negative: Vec<RecommendExample>,
+
+    /// How to use positive and negative examples to find the results.
+    /// Default: `average_vector`
+    #[serde(default)]
+    pub strategy: Option<RecommendStrategy>,
 
     /// Look only for points which satisfies this conditions
     pub filter: Option<Filter>,
@@ -459,6 +943,67 @@ pub const fn default_exact_count() -> bool {
 pub struct CountResult {
     /// Number of points which satisfy the conditions
     pub count: usize,
+    /// If true, the count is exact. If false, the count is estimated by the payload index
+    pub exact: bool,
+}
+
+pub const DEFAULT_FACET_LIMIT: usize = 10;
+
+/// Facet Request
+/// Counts the number of points for each value of a keyword-indexed payload field.
+/// Only values with at least one matching point are returned, most frequent values first.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetRequest {
+    /// Payload key to count values of. Must have a keyword index
+    pub key: PayloadKeyType,
+    /// Max number of values to return. Default: 10
+    #[validate(range(min = 1))]
+    pub limit: Option<usize>,
+    /// Count only points which satisfy this conditions
+    pub filter: Option<Filter>,
+    /// If true, count exact number of points per value. If false, estimate counts faster using the payload index.
+    /// Default: true
+    #[serde(default = "default_exact_count")]
+    pub exact: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetResponse {
+    /// Values with the number of points having them, most frequent values first
+    pub hits: Vec<FacetValueHit>,
+}
+
+/// Search Plan Request
+/// Explains how every segment of the shards, stored on this peer, would execute a vector search
+/// with the given filter. Intended for debugging of slow filtered searches.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Validate)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchPlanRequest {
+    /// Name of the vector to search, default vector if not specified
+    #[serde(default)]
+    pub using: Option<String>,
+    /// Look only for points which satisfies this conditions
+    pub filter: Option<Filter>,
+    /// Additional search params
+    #[validate]
+    pub params: Option<SearchParams>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardSearchPlan {
+    pub shard_id: ShardId,
+    /// Plan of every segment of the shard
+    pub segments: Vec<SegmentSearchPlan>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SearchPlanResponse {
+    /// Plans of the shards stored on this peer, shards stored only on other peers are not listed
+    pub shards: Vec<ShardSearchPlan>,
 }
 
 #[derive(Error, Debug, Clone)]
@@ -495,6 +1040,16 @@ pub enum CollectionError {
     OutOfMemory { description: String, free: u64 },
     #[error("Timeout error: {description}")]
     Timeout { description: String },
+    #[error("Forbidden by strict mode: {description}")]
+    StrictMode { description: String },
+    #[error("Version conflict: {description}")]
+    Conflict { description: String },
+    #[error("Rate limit exceeded: {description}")]
+    RateLimited {
+        description: String,
+        /// When the request can be retried
+        retry_after: Duration,
+    },
 }
 
 impl CollectionError {
@@ -522,6 +1077,28 @@ impl CollectionError {
         CollectionError::BadRequest { description }
     }
 
+    pub fn strict_mode(description: String) -> CollectionError {
+        CollectionError::StrictMode { description }
+    }
+
+    pub fn rate_limited(description: String, retry_after: Duration) -> CollectionError {
+        CollectionError::RateLimited {
+            description,
+            retry_after,
+        }
+    }
+
+    /// Conditional update is rejected, because points have other versions than expected
+    pub fn version_conflict(conflicts: &[PointVersionConflict]) -> CollectionError {
+        CollectionError::Conflict {
+            description: conflicts
+                .iter()
+                .map(|conflict| conflict.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
     pub fn bad_shard_selection(description: String) -> CollectionError {
         CollectionError::BadShardSelection { description }
     }
@@ -549,6 +1126,7 @@ impl CollectionError {
             Self::Timeout { .. } => true,
             Self::Cancelled { .. } => true,
             Self::OutOfMemory { .. } => true,
+            Self::RateLimited { .. } => true,
             // Not transient
             Self::BadInput { .. } => false,
             Self::NotFound { .. } => false,
@@ -557,6 +1135,8 @@ impl CollectionError {
             Self::BadShardSelection { .. } => false,
             Self::InconsistentShardFailure { .. } => false,
             Self::ForwardProxyError { .. } => false,
+            Self::StrictMode { .. } => false,
+            Self::Conflict { .. } => false,
         }
     }
 }
@@ -585,6 +1165,7 @@ impl From<OperationError> for CollectionError {
             OperationError::WrongVector { .. } => Self::BadInput {
                 description: format!("{err}"),
             },
+            OperationError::WrongVectorElement { description } => Self::BadInput { description },
             OperationError::VectorNameNotExists { .. } => Self::BadInput {
                 description: format!("{err}"),
             },
@@ -608,6 +1189,13 @@ impl From<OperationError> for CollectionError {
             OperationError::TypeInferenceError { .. } => Self::BadInput {
                 description: format!("{err}"),
             },
+            OperationError::ValidationError { description } => Self::BadInput { description },
+            OperationError::MissingRangeIndexForOrderBy { .. } => Self::BadInput {
+                description: format!("{err}"),
+            },
+            OperationError::MissingKeywordIndexForFacet { .. } => Self::BadInput {
+                description: format!("{err}"),
+            },
             OperationError::OutOfMemory { description, free } => {
                 Self::OutOfMemory { description, free }
             }
@@ -710,6 +1298,23 @@ impl From<tonic::Status> for CollectionError {
             tonic::Code::DeadlineExceeded => CollectionError::Timeout {
                 description: format!("Deadline Exceeded: {err}"),
             },
+            tonic::Code::PermissionDenied => CollectionError::StrictMode {
+                description: format!("PermissionDenied: {err}"),
+            },
+            tonic::Code::Aborted => CollectionError::Conflict {
+                description: err
+                    .message()
+                    .trim_start_matches("Version conflict: ")
+                    .to_string(),
+            },
+            tonic::Code::ResourceExhausted => CollectionError::RateLimited {
+                description: format!("ResourceExhausted: {err}"),
+                retry_after: err
+                    .metadata()
+                    .get("retry-after")
+                    .and_then(|value| value.to_str().ok()?.parse().ok())
+                    .map_or(Duration::from_secs(1), Duration::from_secs),
+            },
             other => CollectionError::ServiceError {
                 error: format!("Tonic status error: {other}"),
                 backtrace: Some(Backtrace::force_capture().to_string()),
@@ -817,6 +1422,16 @@ pub struct VectorParams {
     /// Default: false
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub on_disk: Option<bool>,
+    /// Data type of the stored vector components. Float16 halves the memory footprint at the cost of precision,
+    /// uint8 quarters it and accepts only integer components in range 0..=255
+    /// Default: float32
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datatype: Option<VectorStorageDatatype>,
+    /// If set, each point holds a list of sub-vectors of `size` dimensions instead of a single vector.
+    /// Sub-vectors of a point are passed and returned concatenated into a single vector.
+    /// Multi-vectors are always kept in RAM and are not quantized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multivector_config: Option<MultiVectorConfig>,
 }
 
 /// Is considered empty if `None` or if diff has no field specified
@@ -833,6 +1448,41 @@ impl Anonymize for VectorParams {
     }
 }
 
+/// Params of single sparse vector data storage
+#[derive(
+    Debug, Hash, Default, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVectorParams {
+    /// Custom params for the inverted index. If none - defaults are used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<SparseIndexParams>,
+}
+
+impl Anonymize for SparseVectorParams {
+    fn anonymize(&self) -> Self {
+        self.clone()
+    }
+}
+
+/// Params of the inverted index of sparse vectors
+#[derive(
+    Debug, Hash, Default, Deserialize, Serialize, JsonSchema, Validate, Copy, Clone, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseIndexParams {
+    /// Filtered search is performed by scoring each matching point instead of traversing the
+    /// inverted index, if the number of matching points is estimated to be less than this threshold.
+    /// Default: 5000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_scan_threshold: Option<usize>,
+    /// If true, the inverted index of optimized segments is served from disk, improving RAM usage
+    /// at the cost of latency
+    /// Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_disk: Option<bool>,
+}
+
 /// Vector params separator for single and multiple vector modes
 /// Single mode:
 ///
@@ -901,6 +1551,15 @@ impl VectorsConfig {
             });
         }
 
+        if self_params.multivector_config != other_params.multivector_config {
+            return Err(CollectionError::BadInput {
+                description: format!(
+                    "Vectors configuration is not compatible: origin vector {} multivector config: {:?}, while other vector multivector config: {:?}",
+                    vector_name, self_params.multivector_config, other_params.multivector_config
+                )
+            });
+        }
+
         Ok(())
     }
 
@@ -1072,9 +1731,10 @@ pub enum NodeType {
     /// Regular node, participates in the cluster
     #[default]
     Normal,
-    /// Node that does only receive data, but is not used for search/read operations
-    /// This is useful for nodes that are only used for writing data
-    /// and backup purposes
+    /// Node that receives data and serves reads, but never leads updates.
+    /// It stays a learner of the consensus and its replicas are listeners,
+    /// which are not counted toward the write consistency.
+    /// This is useful for analytical read-only nodes and backup purposes
     Listener,
 }
 
@@ -102,7 +102,7 @@ fn describe_error(
 
 #[cfg(test)]
 mod tests {
-    use api::grpc::qdrant::{GeoPoint, GeoPolygon};
+    use api::grpc::qdrant::{GeoLineString, GeoPoint, GeoPolygon};
     use validator::Validate;
 
     use super::*;
@@ -153,11 +153,14 @@ mod tests {
         );
 
         let bad_polygon = GeoPolygon {
-            points: vec![
-                GeoPoint { lat: 1., lon: 1. },
-                GeoPoint { lat: 2., lon: 2. },
-                GeoPoint { lat: 1., lon: 1. },
-            ],
+            exterior: Some(GeoLineString {
+                points: vec![
+                    GeoPoint { lat: 1., lon: 1. },
+                    GeoPoint { lat: 2., lon: 2. },
+                    GeoPoint { lat: 1., lon: 1. },
+                ],
+            }),
+            interiors: vec![],
         };
 
         let errors = bad_polygon
@@ -166,16 +169,19 @@ mod tests {
 
         assert_eq!(
             describe_errors(&errors),
-            vec![("points".into(), "size must be at least 4, got 3".into())]
+            vec![("exterior".into(), "size must be at least 4, got 3".into())]
         );
 
         let bad_polygon = GeoPolygon {
-            points: vec![
-                GeoPoint { lat: 1., lon: 1. },
-                GeoPoint { lat: 2., lon: 2. },
-                GeoPoint { lat: 3., lon: 3. },
-                GeoPoint { lat: 4., lon: 4. },
-            ],
+            exterior: Some(GeoLineString {
+                points: vec![
+                    GeoPoint { lat: 1., lon: 1. },
+                    GeoPoint { lat: 2., lon: 2. },
+                    GeoPoint { lat: 3., lon: 3. },
+                    GeoPoint { lat: 4., lon: 4. },
+                ],
+            }),
+            interiors: vec![],
         };
 
         let errors = bad_polygon
@@ -185,7 +191,7 @@ mod tests {
         assert_eq!(
             describe_errors(&errors),
             vec![(
-                "points".into(),
+                "exterior".into(),
                 "the first and the last points should be same to form a closed polygon".into()
             )]
         );
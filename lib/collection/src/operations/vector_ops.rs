@@ -2,21 +2,25 @@ use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 use schemars::JsonSchema;
-use segment::data_types::vectors::VectorStruct;
-use segment::types::{Filter, PointIdType};
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::{only_default_vector, VectorElementType, VectorStruct};
+use segment::entry::entry_point::OperationError;
+use segment::types::{Filter, PointIdType, SeqNumberType};
 use serde::{Deserialize, Serialize};
 use validator::{Validate, ValidationError};
 
-use super::point_ops::PointIdsList;
+use super::point_ops::{check_vector_finite, PointIdsList};
+use super::types::{CollectionResult, VectorParams};
 use super::{point_to_shard, split_iter_by_shard, OperationToShard, SplitByShard};
-use crate::hash_ring::HashRing;
-use crate::shards::shard::ShardId;
+use crate::config::CollectionParams;
+use crate::hash_ring::HashRingRouter;
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 pub struct UpdateVectors {
     /// Points with named vectors
     #[validate]
     #[validate(length(min = 1, message = "must specify points to update"))]
+    #[validate(custom = "validate_point_vectors_finite")]
     pub points: Vec<PointVectors>,
 }
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -30,6 +34,10 @@ pub struct PointVectors {
         message = "must specify vectors to update for point"
     ))]
     pub vector: VectorStruct,
+    /// Update vectors only if the point has this version.
+    /// Otherwise the update of all the points is rejected as a conflict
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub if_version: Option<SeqNumberType>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
@@ -38,7 +46,8 @@ pub struct DeleteVectors {
     pub points: Option<Vec<PointIdType>>,
     /// Deletes values from points that satisfy this filter condition
     pub filter: Option<Filter>,
-    /// Vector names
+    /// Vector names. Deleting all vectors of a point keeps the point with its payload,
+    /// but the point is no longer found by search
     #[serde(alias = "vectors")]
     #[validate(length(min = 1, message = "must specify vector names to delete"))]
     pub vector: HashSet<String>,
@@ -49,7 +58,7 @@ pub struct DeleteVectors {
 pub enum VectorOperations {
     /// Update vectors
     UpdateVectors(UpdateVectors),
-    /// Delete vectors if exists
+    /// Delete vectors if exists, points without vectors left are kept
     DeleteVectors(PointIdsList, Vec<String>),
     /// Delete vectors by given filter criteria
     DeleteVectorsByFilter(Filter, Vec<String>),
@@ -63,6 +72,74 @@ impl VectorOperations {
             VectorOperations::DeleteVectorsByFilter(..) => false,
         }
     }
+
+    /// Check that the vector names and dimensions of this operation are part of the given collection.
+    ///
+    /// Returns an error if incompatible.
+    pub fn check_vectors(&self, collection: &CollectionParams) -> CollectionResult<()> {
+        match self {
+            VectorOperations::UpdateVectors(update_vectors) => {
+                for point in &update_vectors.points {
+                    let vectors = match &point.vector {
+                        VectorStruct::Single(vector) => only_default_vector(vector),
+                        VectorStruct::Multi(vectors) => NamedVectors::from_map_ref(vectors),
+                    };
+                    for (vector_name, vector) in vectors.iter() {
+                        check_vector_params(collection, vector_name, vector)?;
+                    }
+                }
+            }
+            VectorOperations::DeleteVectors(_, vector_names)
+            | VectorOperations::DeleteVectorsByFilter(_, vector_names) => {
+                for vector_name in vector_names {
+                    let is_sparse = collection
+                        .sparse_vectors
+                        .as_ref()
+                        .is_some_and(|sparse| sparse.contains_key(vector_name));
+                    if !is_sparse {
+                        vector_params(collection, vector_name)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn vector_params<'a>(
+    collection: &'a CollectionParams,
+    vector_name: &str,
+) -> CollectionResult<&'a VectorParams> {
+    collection.vectors.get_params(vector_name).ok_or_else(|| {
+        OperationError::VectorNameNotExists {
+            received_name: vector_name.into(),
+        }
+        .into()
+    })
+}
+
+/// Check the vector against the dimension of the named vector, multi-vectors may consist
+/// of any positive number of sub-vectors
+fn check_vector_params(
+    collection: &CollectionParams,
+    vector_name: &str,
+    vector: &[VectorElementType],
+) -> CollectionResult<()> {
+    let params = vector_params(collection, vector_name)?;
+    let dim = params.size.get() as usize;
+    let is_valid = if params.multivector_config.is_some() {
+        !vector.is_empty() && vector.len() % dim == 0
+    } else {
+        vector.len() == dim
+    };
+    if !is_valid {
+        return Err(OperationError::WrongVector {
+            expected_dim: dim,
+            received_dim: vector.len(),
+        }
+        .into());
+    }
+    Ok(())
 }
 
 impl Validate for VectorOperations {
@@ -76,13 +153,13 @@ impl Validate for VectorOperations {
 }
 
 impl SplitByShard for Vec<PointVectors> {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         split_iter_by_shard(self, |point| point.id, ring)
     }
 }
 
 impl SplitByShard for VectorOperations {
-    fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
+    fn split_by_shard(self, ring: &HashRingRouter) -> OperationToShard<Self> {
         match self {
             VectorOperations::UpdateVectors(update_vectors) => {
                 let shard_points = update_vectors
@@ -125,3 +202,23 @@ fn validate_vector_struct_not_empty(value: &VectorStruct) -> Result<(), Validati
     err.add_param(Cow::from("min"), &1);
     Err(err)
 }
+
+/// Validate all vector components of all points are finite numbers.
+///
+/// Rejects the whole operation, so that none of the points is updated.
+fn validate_point_vectors_finite(points: &[PointVectors]) -> Result<(), ValidationError> {
+    for point in points {
+        let vectors = match &point.vector {
+            VectorStruct::Single(vector) => only_default_vector(vector),
+            VectorStruct::Multi(vectors) => NamedVectors::from_map_ref(vectors),
+        };
+        for (vector_name, vector) in vectors.iter() {
+            if let Err(message) = check_vector_finite(point.id, vector_name, vector) {
+                let mut err = ValidationError::new("finite");
+                err.message.replace(Cow::from(message));
+                return Err(err);
+            }
+        }
+    }
+    Ok(())
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 
 use schemars::JsonSchema;
 use segment::common::cpu::get_num_cpus;
+use segment::segment_constructor::segment_builder::DEFAULT_MERGE_BUFFER_SIZE;
 use segment::types::{HnswConfig, QuantizationConfig};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -13,10 +14,12 @@ use crate::collection_manager::optimizers::merge_optimizer::MergeOptimizer;
 use crate::collection_manager::optimizers::segment_optimizer::OptimizerThresholds;
 use crate::collection_manager::optimizers::vacuum_optimizer::VacuumOptimizer;
 use crate::config::CollectionParams;
+use crate::operations::types::{CollectionError, CollectionResult};
 use crate::update_handler::Optimizer;
 
 const DEFAULT_MAX_SEGMENT_PER_CPU_KB: usize = 200_000;
 const DEFAULT_INDEXING_THRESHOLD_KB: usize = 20_000;
+pub const DEFAULT_MERGE_BUFFER_SIZE_KB: usize = DEFAULT_MERGE_BUFFER_SIZE / 1024;
 const SEGMENTS_PATH: &str = "segments";
 const TEMP_SEGMENTS_PATH: &str = "temp_segments";
 
@@ -40,6 +43,7 @@ pub struct OptimizersConfig {
     /// Do not create segments larger this size (in kilobytes).
     /// Large segments might require disproportionately long indexation times,
     /// therefore it makes sense to limit the size of segments.
+    /// Existing segments larger than this size, e.g. after it is lowered, are split into several.
     ///
     /// If indexing speed is more important - make this parameter lower.
     /// If search speed is more important - make this parameter higher.
@@ -73,6 +77,13 @@ pub struct OptimizersConfig {
     pub flush_interval_sec: u64,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: usize,
+    /// Maximum size (in kilobytes) of vectors, which optimizers copy from the optimized segments at once.
+    /// Points are copied by batches of this size, so memory used to merge segments does not depend on their size.
+    ///
+    /// Default value is 65,536 (64MB).
+    #[serde(alias = "merge_buffer_size_kb")]
+    #[serde(default)]
+    pub merge_buffer_size: Option<usize>,
 }
 
 impl OptimizersConfig {
@@ -87,6 +98,7 @@ impl OptimizersConfig {
             indexing_threshold: Some(100_000),
             flush_interval_sec: 60,
             max_optimization_threads: 0,
+            merge_buffer_size: None,
         }
     }
 
@@ -109,6 +121,20 @@ impl OptimizersConfig {
             num_cpus.saturating_mul(DEFAULT_MAX_SEGMENT_PER_CPU_KB)
         }
     }
+
+    /// Check that segments can grow large enough to be indexed
+    pub fn check_thresholds(&self) -> CollectionResult<()> {
+        let indexing_threshold = self
+            .indexing_threshold
+            .unwrap_or(DEFAULT_INDEXING_THRESHOLD_KB);
+        let max_segment_size = self.get_max_segment_size();
+        if indexing_threshold != 0 && max_segment_size < indexing_threshold {
+            return Err(CollectionError::bad_input(format!(
+                "max_segment_size ({max_segment_size} KB) can't be smaller than indexing_threshold ({indexing_threshold} KB), segments would never be indexed",
+            )));
+        }
+        Ok(())
+    }
 }
 
 pub fn clear_temp_segments(shard_path: &Path) {
@@ -150,6 +176,9 @@ pub fn build_optimizers(
         memmap_threshold,
         indexing_threshold,
         max_segment_size: optimizers_config.get_max_segment_size(),
+        merge_buffer_size: optimizers_config
+            .merge_buffer_size
+            .unwrap_or(DEFAULT_MERGE_BUFFER_SIZE_KB),
     };
 
     Arc::new(vec![
@@ -3,17 +3,22 @@ use std::future::Future;
 
 use futures::future::try_join_all;
 use itertools::Itertools;
-use segment::data_types::vectors::{NamedVector, VectorElementType, DEFAULT_VECTOR_NAME};
+use segment::data_types::vectors::{
+    NamedVector, VectorElementType, VectorType, DEFAULT_VECTOR_NAME,
+};
 use segment::types::{
     Condition, Filter, HasIdCondition, PointIdType, ScoredPoint, WithPayloadInterface, WithVector,
 };
+use segment::vector_storage::query::reco_query::RecoQuery;
 use tokio::sync::RwLockReadGuard;
 
 use crate::collection::Collection;
 use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::shard_key_selector::ShardSelectorInternal;
 use crate::operations::types::{
-    CollectionError, CollectionResult, PointRequest, RecommendRequest, RecommendRequestBatch,
-    Record, SearchRequest, SearchRequestBatch, UsingVector,
+    CollectionError, CollectionResult, CoreSearchRequest, CoreSearchRequestBatch, NamedQuery,
+    PointRequest, QueryEnum, RecommendExample, RecommendRequest, RecommendRequestBatch,
+    RecommendStrategy, Record, UsingVector,
 };
 
 fn avg_vectors<'a>(
@@ -44,6 +49,7 @@ pub async fn recommend_by<'a, F, Fut>(
     collection: &Collection,
     collection_by_name: F,
     read_consistency: Option<ReadConsistency>,
+    shard_selection: impl Into<ShardSelectorInternal>,
 ) -> CollectionResult<Vec<ScoredPoint>>
 where
     F: Fn(String) -> Fut,
@@ -61,6 +67,7 @@ where
         collection,
         collection_by_name,
         read_consistency,
+        shard_selection,
     )
     .await?;
     Ok(results.into_iter().next().unwrap())
@@ -71,6 +78,7 @@ async fn retrieve_points(
     ids: Vec<PointIdType>,
     vector_names: Vec<String>,
     read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
 ) -> CollectionResult<Vec<Record>> {
     collection
         .retrieve(
@@ -78,30 +86,39 @@ async fn retrieve_points(
                 ids,
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: WithVector::Selector(vector_names),
+                with_version: false,
             },
             read_consistency,
-            None,
+            shard_selection,
         )
         .await
 }
 
-enum CollectionRefHolder<'a> {
+pub(crate) enum CollectionRefHolder<'a> {
     Ref(&'a Collection),
     Guard(RwLockReadGuard<'a, Collection>),
 }
 
-async fn retrieve_points_with_locked_collection(
+pub(crate) async fn retrieve_points_with_locked_collection(
     collection_holder: CollectionRefHolder<'_>,
     ids: Vec<PointIdType>,
     vector_names: Vec<String>,
     read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
 ) -> CollectionResult<Vec<Record>> {
     match collection_holder {
         CollectionRefHolder::Ref(collection) => {
-            retrieve_points(collection, ids, vector_names, read_consistency).await
+            retrieve_points(
+                collection,
+                ids,
+                vector_names,
+                read_consistency,
+                shard_selection,
+            )
+            .await
         }
         CollectionRefHolder::Guard(guard) => {
-            retrieve_points(&guard, ids, vector_names, read_consistency).await
+            retrieve_points(&guard, ids, vector_names, read_consistency, shard_selection).await
         }
     }
 }
@@ -119,14 +136,15 @@ fn get_search_vector_name(request: &RecommendRequest) -> String {
     }
 }
 
-/// Search points in a collection by already existing points in this or another collection.
+/// Search points in a collection by examples, which are points of this or another collection
+/// or raw vectors.
 ///
 /// Function works in following stages:
 ///
-/// - Constructs queries to retrieve points from the existing collections
+/// - Constructs queries to retrieve example points from the existing collections
 /// - Executes queries in parallel
 /// - Converts retrieve results into lookup table
-/// - Constructs regular search queries, execute them as single batch
+/// - Constructs search queries according to the strategy of each request, execute them as single batch
 ///
 /// # Arguments
 ///
@@ -134,17 +152,21 @@ fn get_search_vector_name(request: &RecommendRequest) -> String {
 /// * `search_runtime_handle` - tokio runtime handle to execute search queries
 /// * `collection` - collection to search in
 /// * `collection_by_name` - function to retrieve collection by name, used to retrieve points from other collections
+/// * `shard_selection` - shards of the collection to search in, examples of this collection are
+///   looked up in the same shards, examples of other collections in all of their shards
 ///
 pub async fn recommend_batch_by<'a, F, Fut>(
     request_batch: RecommendRequestBatch,
     collection: &Collection,
     collection_by_name: F,
     read_consistency: Option<ReadConsistency>,
+    shard_selection: impl Into<ShardSelectorInternal>,
 ) -> CollectionResult<Vec<Vec<ScoredPoint>>>
 where
     F: Fn(String) -> Fut,
     Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
 {
+    let shard_selection = shard_selection.into();
     // shortcuts batch if all requests with limit=0
     if request_batch.searches.iter().all(|s| s.limit == 0) {
         return Ok(vec![]);
@@ -154,11 +176,8 @@ where
     let mut vector_names_per_collection: HashMap<_, HashSet<String>> = Default::default();
 
     for request in &request_batch.searches {
-        if request.positive.is_empty() {
-            return Err(CollectionError::BadRequest {
-                description: "At least one positive vector ID required".to_owned(),
-            });
-        }
+        validate_examples(request)?;
+
         let collection_name = request.lookup_from.as_ref().map(|x| &x.collection);
 
         let reference_vectors_ids = all_reference_vectors_ids
@@ -171,8 +190,8 @@ where
 
         vector_names.insert(get_search_vector_name(request));
 
-        for point_id in request.positive.iter().chain(&request.negative) {
-            reference_vectors_ids.insert(*point_id);
+        for point_id in example_point_ids(request) {
+            reference_vectors_ids.insert(point_id);
         }
     }
 
@@ -194,6 +213,7 @@ where
                 points,
                 vector_names,
                 read_consistency,
+                shard_selection.clone(),
             )),
             Some(name) => {
                 let other_collection = collection_by_name(name.to_string()).await;
@@ -204,6 +224,7 @@ where
                             points,
                             vector_names,
                             read_consistency,
+                            ShardSelectorInternal::All,
                         ))
                     }
                     None => {
@@ -238,63 +259,80 @@ where
 
         let lookup_vector_name = get_search_vector_name(request);
 
-        let reference_vectors_ids = request
-            .positive
-            .iter()
-            .chain(&request.negative)
-            .cloned()
-            .collect_vec();
+        let reference_vectors_ids = example_point_ids(request).collect_vec();
 
         let request_from_collection = request.lookup_from.as_ref().map(|x| &x.collection);
 
-        for &point_id in &reference_vectors_ids {
-            if !all_vectors_records_map.contains_key(&(request_from_collection, point_id)) {
-                return Err(CollectionError::PointNotFound {
-                    missed_point_id: point_id,
-                });
-            }
-        }
+        let missed_point_ids = reference_vectors_ids
+            .iter()
+            .copied()
+            .filter(|point_id| {
+                !all_vectors_records_map.contains_key(&(request_from_collection, *point_id))
+            })
+            .collect_vec();
+        check_missed_point_ids(&missed_point_ids)?;
 
-        let avg_positive = avg_vectors(request.positive.iter().filter_map(|vid| {
-            let rec = all_vectors_records_map
-                .get(&(request_from_collection, *vid))
-                .unwrap();
-            rec.get_vector_by_name(&lookup_vector_name)
-        }));
-
-        let search_vector = if request.negative.is_empty() {
-            avg_positive
-        } else {
-            let avg_negative = avg_vectors(request.negative.iter().filter_map(|vid| {
-                let rec = all_vectors_records_map
-                    .get(&(request_from_collection, *vid))
-                    .unwrap();
-                rec.get_vector_by_name(&lookup_vector_name)
-            }));
-
-            avg_positive
+        let example_vectors = |examples: &[RecommendExample]| -> Vec<VectorType> {
+            examples
                 .iter()
-                .cloned()
-                .zip(avg_negative.iter().cloned())
-                .map(|(pos, neg)| pos + pos - neg)
+                .filter_map(|example| match example {
+                    RecommendExample::PointId(point_id) => all_vectors_records_map
+                        .get(&(request_from_collection, *point_id))
+                        .unwrap()
+                        .get_vector_by_name(&lookup_vector_name)
+                        .cloned(),
+                    RecommendExample::Vector(vector) => Some(vector.clone()),
+                })
                 .collect()
         };
-
-        let search_request = SearchRequest {
-            vector: NamedVector {
-                name: vector_name.to_string(),
-                vector: search_vector,
+        let positive_vectors = example_vectors(&request.positive);
+        let negative_vectors = example_vectors(&request.negative);
+
+        let query = match request.strategy.unwrap_or_default() {
+            RecommendStrategy::AverageVector => {
+                let avg_positive = avg_vectors(positive_vectors.iter());
+
+                let search_vector = if negative_vectors.is_empty() {
+                    avg_positive
+                } else {
+                    let avg_negative = avg_vectors(negative_vectors.iter());
+
+                    avg_positive
+                        .iter()
+                        .cloned()
+                        .zip(avg_negative.iter().cloned())
+                        .map(|(pos, neg)| pos + pos - neg)
+                        .collect()
+                };
+
+                QueryEnum::Nearest(
+                    NamedVector {
+                        name: vector_name.to_string(),
+                        vector: search_vector,
+                    }
+                    .into(),
+                )
             }
-            .into(),
+            RecommendStrategy::BestScore => QueryEnum::RecommendBestScore(NamedQuery {
+                query: RecoQuery::new(positive_vectors, negative_vectors),
+                using: Some(vector_name.to_string()),
+            }),
+        };
+
+        let search_request = CoreSearchRequest {
+            query,
             filter: Some(Filter {
                 should: None,
                 must: request
                     .filter
                     .clone()
                     .map(|filter| vec![Condition::Filter(filter)]),
-                must_not: Some(vec![Condition::HasId(HasIdCondition {
-                    has_id: reference_vectors_ids.iter().cloned().collect(),
-                })]),
+                min_should: None,
+                must_not: (!reference_vectors_ids.is_empty()).then(|| {
+                    vec![Condition::HasId(HasIdCondition {
+                        has_id: reference_vectors_ids.iter().cloned().collect(),
+                    })]
+                }),
             }),
             with_payload: request.with_payload.clone(),
             with_vector: request.with_vector.clone(),
@@ -306,9 +344,57 @@ where
         searches.push(search_request)
     }
 
-    let search_batch_request = SearchRequestBatch { searches };
+    let search_batch_request = CoreSearchRequestBatch { searches };
 
     collection
-        .search_batch(search_batch_request, read_consistency, None)
+        .core_search_batch(
+            search_batch_request,
+            read_consistency,
+            shard_selection,
+            None,
+        )
         .await
 }
+
+/// Error listing all example points, which were not found
+pub(crate) fn check_missed_point_ids(missed_point_ids: &[PointIdType]) -> CollectionResult<()> {
+    match missed_point_ids {
+        [] => Ok(()),
+        [missed_point_id] => Err(CollectionError::PointNotFound {
+            missed_point_id: *missed_point_id,
+        }),
+        _ => Err(CollectionError::NotFound {
+            what: format!("Points with ids {}", missed_point_ids.iter().join(", ")),
+        }),
+    }
+}
+
+/// Check that the request has enough examples for its strategy
+fn validate_examples(request: &RecommendRequest) -> CollectionResult<()> {
+    match request.strategy.unwrap_or_default() {
+        RecommendStrategy::AverageVector => {
+            if request.positive.is_empty() {
+                return Err(CollectionError::BadRequest {
+                    description: "At least one positive vector ID required".to_owned(),
+                });
+            }
+        }
+        RecommendStrategy::BestScore => {
+            if request.positive.is_empty() && request.negative.is_empty() {
+                return Err(CollectionError::BadRequest {
+                    description: "At least one positive or negative example required".to_owned(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ids of the examples, which vectors have to be looked up
+fn example_point_ids(request: &RecommendRequest) -> impl Iterator<Item = PointIdType> + '_ {
+    request
+        .positive
+        .iter()
+        .chain(&request.negative)
+        .filter_map(RecommendExample::as_point_id)
+}
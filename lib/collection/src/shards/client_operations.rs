@@ -0,0 +1,108 @@
+use std::collections::{BTreeMap, HashMap};
+
+use segment::types::SeqNumberType;
+
+use crate::operations::ClientOperationId;
+
+/// Recently applied operations of a shard, by the id supplied by the client.
+///
+/// Keeps up to `window` ids, the least recently used id is forgotten first.
+/// Ids are restored from WAL on shard load, so the window survives restarts as long as
+/// the records are present in WAL.
+#[derive(Debug)]
+pub struct AppliedClientOperations {
+    window: usize,
+    /// Operation number and last use tick of each id
+    operations: HashMap<ClientOperationId, (SeqNumberType, u64)>,
+    /// Ids by last use tick, oldest first
+    recency: BTreeMap<u64, ClientOperationId>,
+    tick: u64,
+}
+
+impl AppliedClientOperations {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            operations: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Operation number, under which the operation with this id was written into WAL
+    pub fn get(&mut self, client_op_id: &ClientOperationId) -> Option<SeqNumberType> {
+        let tick = self.next_tick();
+        let (op_num, last_used) = self.operations.get_mut(client_op_id)?;
+        self.recency.remove(last_used);
+        self.recency.insert(tick, *client_op_id);
+        *last_used = tick;
+        Some(*op_num)
+    }
+
+    /// Same as [`AppliedClientOperations::get`], but does not refresh the id
+    pub fn peek(&self, client_op_id: &ClientOperationId) -> Option<SeqNumberType> {
+        self.operations.get(client_op_id).map(|(op_num, _)| *op_num)
+    }
+
+    pub fn insert(&mut self, client_op_id: ClientOperationId, op_num: SeqNumberType) {
+        if self.window == 0 {
+            return;
+        }
+        let tick = self.next_tick();
+        if let Some((_, last_used)) = self.operations.insert(client_op_id, (op_num, tick)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(tick, client_op_id);
+
+        while self.operations.len() > self.window {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.operations.remove(&oldest);
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_applied_client_operations_window() {
+        let ids: Vec<_> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut applied = AppliedClientOperations::new(3);
+
+        for (op_num, id) in ids.iter().take(3).enumerate() {
+            applied.insert(*id, op_num as SeqNumberType);
+        }
+        assert_eq!(applied.get(&ids[0]), Some(0));
+
+        // The least recently used id is forgotten
+        applied.insert(ids[3], 3);
+        assert_eq!(applied.len(), 3);
+        assert_eq!(applied.get(&ids[1]), None);
+        assert_eq!(applied.get(&ids[0]), Some(0));
+        assert_eq!(applied.get(&ids[2]), Some(2));
+        assert_eq!(applied.get(&ids[3]), Some(3));
+
+        let mut disabled = AppliedClientOperations::new(0);
+        disabled.insert(ids[0], 0);
+        assert!(disabled.is_empty());
+        assert_eq!(disabled.get(&ids[0]), None);
+    }
+}
@@ -1,23 +1,27 @@
 use api::grpc::conversions::payload_to_proto;
 use api::grpc::qdrant::points_selector::PointsSelectorOneOf;
+use api::grpc::qdrant::points_update_operation::{self, Operation, PointStructList};
 use api::grpc::qdrant::{
     ClearPayloadPoints, ClearPayloadPointsInternal, CreateFieldIndexCollection,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
     DeleteFieldIndexCollectionInternal, DeletePayloadPoints, DeletePayloadPointsInternal,
     DeletePointVectors, DeletePoints, DeletePointsInternal, DeleteVectorsInternal, PointVectors,
-    PointsIdsList, PointsSelector, SetPayloadPoints, SetPayloadPointsInternal, SyncPoints,
-    SyncPointsInternal, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
+    PointsIdsList, PointsSelector, PointsUpdateOperation, SetPayloadPoints,
+    SetPayloadPointsInternal, SyncPoints, SyncPointsInternal, UpdateBatchInternal,
+    UpdateBatchPoints, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
     UpsertPointsInternal, VectorsSelector,
 };
 use segment::types::{Filter, PayloadFieldSchema, PayloadSchemaParams, PointIdType, ScoredPoint};
 use tonic::Status;
 
 use crate::operations::conversions::write_ordering_to_proto;
-use crate::operations::payload_ops::{DeletePayload, SetPayload};
-use crate::operations::point_ops::{PointInsertOperations, PointSyncOperation, WriteOrdering};
-use crate::operations::types::CollectionResult;
-use crate::operations::vector_ops::UpdateVectors;
-use crate::operations::CreateIndex;
+use crate::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
+use crate::operations::point_ops::{
+    PointInsertOperations, PointOperations, PointSyncOperation, WriteOrdering,
+};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::vector_ops::{UpdateVectors, VectorOperations};
+use crate::operations::{ClientOperationId, CollectionUpdateOperations, CreateIndex};
 use crate::shards::shard::ShardId;
 
 pub fn internal_sync_points(
@@ -50,6 +54,7 @@ pub fn internal_upsert_points(
     point_insert_operations: PointInsertOperations,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> CollectionResult<UpsertPointsInternal> {
     Ok(UpsertPointsInternal {
         shard_id,
@@ -64,6 +69,8 @@ pub fn internal_upsert_points(
                     .collect::<Result<Vec<_>, Status>>()?,
             },
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
+            shard_key: None,
         }),
     })
 }
@@ -74,6 +81,7 @@ pub fn internal_delete_points(
     ids: Vec<PointIdType>,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> DeletePointsInternal {
     DeletePointsInternal {
         shard_id,
@@ -86,6 +94,8 @@ pub fn internal_delete_points(
                 })),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
+            with_deleted_ids: None,
         }),
     }
 }
@@ -96,6 +106,7 @@ pub fn internal_delete_points_by_filter(
     filter: Filter,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> DeletePointsInternal {
     DeletePointsInternal {
         shard_id,
@@ -106,6 +117,9 @@ pub fn internal_delete_points_by_filter(
                 points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
+            // the leader reports the deleted points back to the peer, which forwarded the delete
+            with_deleted_ids: Some(true),
         }),
     }
 }
@@ -116,6 +130,7 @@ pub fn internal_update_vectors(
     update_vectors: UpdateVectors,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> UpdateVectorsInternal {
     UpdateVectorsInternal {
         shard_id,
@@ -128,9 +143,11 @@ pub fn internal_update_vectors(
                 .map(|point| PointVectors {
                     id: Some(point.id.into()),
                     vectors: Some(point.vector.into()),
+                    if_version: point.if_version,
                 })
                 .collect(),
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
         }),
     }
 }
@@ -142,6 +159,7 @@ pub fn internal_delete_vectors(
     vector_names: Vec<String>,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> DeleteVectorsInternal {
     DeleteVectorsInternal {
         shard_id,
@@ -157,6 +175,7 @@ pub fn internal_delete_vectors(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
         }),
     }
 }
@@ -168,6 +187,7 @@ pub fn internal_delete_vectors_by_filter(
     vector_names: Vec<String>,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> DeleteVectorsInternal {
     DeleteVectorsInternal {
         shard_id,
@@ -181,6 +201,7 @@ pub fn internal_delete_vectors_by_filter(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
         }),
     }
 }
@@ -191,6 +212,7 @@ pub fn internal_set_payload(
     set_payload: SetPayload,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> SetPayloadPointsInternal {
     let points_selector = if let Some(points) = set_payload.points {
         Some(PointsSelector {
@@ -212,6 +234,9 @@ pub fn internal_set_payload(
             payload: payload_to_proto(set_payload.payload),
             points_selector,
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
+            if_version: set_payload.if_version,
+            key: set_payload.key,
         }),
     }
 }
@@ -222,6 +247,7 @@ pub fn internal_delete_payload(
     delete_payload: DeletePayload,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> DeletePayloadPointsInternal {
     let points_selector = if let Some(points) = delete_payload.points {
         Some(PointsSelector {
@@ -243,6 +269,8 @@ pub fn internal_delete_payload(
             keys: delete_payload.keys,
             points_selector,
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
+            if_version: delete_payload.if_version,
         }),
     }
 }
@@ -253,6 +281,7 @@ pub fn internal_clear_payload(
     points: Vec<PointIdType>,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> ClearPayloadPointsInternal {
     ClearPayloadPointsInternal {
         shard_id,
@@ -265,6 +294,7 @@ pub fn internal_clear_payload(
                 })),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
         }),
     }
 }
@@ -275,6 +305,7 @@ pub fn internal_clear_payload_by_filter(
     filter: Filter,
     wait: bool,
     ordering: Option<WriteOrdering>,
+    client_op_id: Option<ClientOperationId>,
 ) -> ClearPayloadPointsInternal {
     ClearPayloadPointsInternal {
         shard_id,
@@ -285,6 +316,7 @@ pub fn internal_clear_payload_by_filter(
                 points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            client_op_id: client_op_id.map(|id| id.to_string()),
         }),
     }
 }
@@ -319,14 +351,28 @@ pub fn internal_create_index(
                     segment::types::PayloadSchemaType::Bool => {
                         api::grpc::qdrant::FieldType::Bool as i32
                     }
+                    segment::types::PayloadSchemaType::Datetime => {
+                        api::grpc::qdrant::FieldType::Datetime as i32
+                    }
+                    segment::types::PayloadSchemaType::Uuid => {
+                        api::grpc::qdrant::FieldType::Uuid as i32
+                    }
                 },
                 None,
             ),
             PayloadFieldSchema::FieldParams(field_params) => match field_params {
+                PayloadSchemaParams::Keyword(keyword_index_params) => (
+                    api::grpc::qdrant::FieldType::Keyword as i32,
+                    Some(keyword_index_params.into()),
+                ),
                 PayloadSchemaParams::Text(text_index_params) => (
                     api::grpc::qdrant::FieldType::Text as i32,
                     Some(text_index_params.into()),
                 ),
+                PayloadSchemaParams::Uuid(uuid_index_params) => (
+                    api::grpc::qdrant::FieldType::Uuid as i32,
+                    Some(uuid_index_params.into()),
+                ),
             },
         })
         .map(|(field_type, field_params)| (Some(field_type), field_params))
@@ -363,6 +409,144 @@ pub fn internal_delete_index(
     }
 }
 
+pub fn internal_update_batch(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    operations: Vec<CollectionUpdateOperations>,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> CollectionResult<UpdateBatchInternal> {
+    Ok(UpdateBatchInternal {
+        shard_id,
+        update_batch: Some(UpdateBatchPoints {
+            collection_name,
+            wait: Some(wait),
+            operations: operations
+                .into_iter()
+                .map(update_operation_to_proto)
+                .collect::<CollectionResult<Vec<_>>>()?,
+            ordering: ordering.map(write_ordering_to_proto),
+        }),
+    })
+}
+
+fn update_operation_to_proto(
+    operation: CollectionUpdateOperations,
+) -> CollectionResult<PointsUpdateOperation> {
+    let operation = match operation {
+        CollectionUpdateOperations::PointOperation(point_ops) => match point_ops {
+            PointOperations::UpsertPoints(point_insert_operations) => {
+                Operation::Upsert(PointStructList {
+                    points: match point_insert_operations {
+                        PointInsertOperations::PointsBatch(batch) => batch.try_into()?,
+                        PointInsertOperations::PointsList(list) => list
+                            .into_iter()
+                            .map(|id| id.try_into())
+                            .collect::<Result<Vec<_>, Status>>()?,
+                    },
+                })
+            }
+            PointOperations::DeletePoints { ids } => Operation::Delete(ids_selector(ids)),
+            PointOperations::DeletePointsByFilter(filter) => {
+                Operation::Delete(filter_selector(filter))
+            }
+            PointOperations::SyncPoints(_) => {
+                return Err(CollectionError::service_error(
+                    "Sync operation can't be a part of a batch".to_string(),
+                ))
+            }
+        },
+        CollectionUpdateOperations::VectorOperation(vector_ops) => match vector_ops {
+            VectorOperations::UpdateVectors(update_vectors) => {
+                Operation::UpdateVectors(points_update_operation::UpdateVectors {
+                    points: update_vectors
+                        .points
+                        .into_iter()
+                        .map(|point| PointVectors {
+                            id: Some(point.id.into()),
+                            vectors: Some(point.vector.into()),
+                            if_version: point.if_version,
+                        })
+                        .collect(),
+                })
+            }
+            VectorOperations::DeleteVectors(ids, vector_names) => {
+                Operation::DeleteVectors(points_update_operation::DeleteVectors {
+                    points_selector: Some(ids_selector(ids.points)),
+                    vectors: Some(VectorsSelector {
+                        names: vector_names,
+                    }),
+                })
+            }
+            VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
+                Operation::DeleteVectors(points_update_operation::DeleteVectors {
+                    points_selector: Some(filter_selector(filter)),
+                    vectors: Some(VectorsSelector {
+                        names: vector_names,
+                    }),
+                })
+            }
+        },
+        CollectionUpdateOperations::PayloadOperation(payload_ops) => match payload_ops {
+            PayloadOps::SetPayload(set_payload) => {
+                Operation::SetPayload(set_payload_to_proto(set_payload))
+            }
+            PayloadOps::OverwritePayload(set_payload) => {
+                Operation::OverwritePayload(set_payload_to_proto(set_payload))
+            }
+            PayloadOps::DeletePayload(delete_payload) => {
+                Operation::DeletePayload(points_update_operation::DeletePayload {
+                    keys: delete_payload.keys,
+                    points_selector: delete_payload
+                        .points
+                        .map(ids_selector)
+                        .or_else(|| delete_payload.filter.map(filter_selector)),
+                    if_version: delete_payload.if_version,
+                })
+            }
+            PayloadOps::ClearPayload { points } => Operation::ClearPayload(ids_selector(points)),
+            PayloadOps::ClearPayloadByFilter(filter) => {
+                Operation::ClearPayload(filter_selector(filter))
+            }
+        },
+        CollectionUpdateOperations::FieldIndexOperation(_) => {
+            return Err(CollectionError::service_error(
+                "Field index operation can't be a part of a batch".to_string(),
+            ))
+        }
+    };
+
+    Ok(PointsUpdateOperation {
+        operation: Some(operation),
+    })
+}
+
+fn set_payload_to_proto(set_payload: SetPayload) -> points_update_operation::SetPayload {
+    points_update_operation::SetPayload {
+        payload: payload_to_proto(set_payload.payload),
+        points_selector: set_payload
+            .points
+            .map(ids_selector)
+            .or_else(|| set_payload.filter.map(filter_selector)),
+        if_version: set_payload.if_version,
+        key: set_payload.key,
+    }
+}
+
+fn ids_selector(ids: Vec<PointIdType>) -> PointsSelector {
+    PointsSelector {
+        points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
+            ids: ids.into_iter().map(|id| id.into()).collect(),
+        })),
+    }
+}
+
+fn filter_selector(filter: Filter) -> PointsSelector {
+    PointsSelector {
+        points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
+    }
+}
+
 pub fn try_scored_point_from_grpc(
     point: api::grpc::qdrant::ScoredPoint,
     with_payload: bool,
@@ -1,17 +1,21 @@
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
+use segment::data_types::search_plan::SegmentSearchPlan;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
+    CountResult, FacetRequest, FacetResponse, PointRequest, Record, SearchPlanRequest,
+    UpdateResult,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{CollectionUpdateOperations, OperationWithClientId};
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
 
@@ -40,14 +44,28 @@ impl DummyShard {
         self.dummy()
     }
 
+    /// Nothing to persist
+    pub async fn shutdown(&self) -> CollectionResult<()> {
+        Ok(())
+    }
+
     pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
         LocalShardTelemetry {
             variant_name: Some("dummy shard".into()),
             segments: vec![],
             optimizations: Default::default(),
+            wal_recovery: None,
+            flush: None,
         }
     }
 
+    pub fn search_plan(
+        &self,
+        _request: &SearchPlanRequest,
+    ) -> CollectionResult<Vec<SegmentSearchPlan>> {
+        Ok(vec![])
+    }
+
     fn dummy<T>(&self) -> CollectionResult<T> {
         Err(CollectionError::service_error(self.message.to_string()))
     }
@@ -59,14 +77,22 @@ impl ShardOperation for DummyShard {
         self.dummy()
     }
 
-    async fn update(
+    async fn update_with_client_id(
         &self,
-        _: CollectionUpdateOperations,
+        _: OperationWithClientId,
         _: bool,
     ) -> CollectionResult<UpdateResult> {
         self.dummy()
     }
 
+    async fn update_batch(
+        &self,
+        _: Vec<CollectionUpdateOperations>,
+        _: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        self.dummy()
+    }
+
     /// Forward read-only `scroll_by` to `wrapped_shard`
     async fn scroll_by(
         &self,
@@ -75,15 +101,17 @@ impl ShardOperation for DummyShard {
         _: &WithPayloadInterface,
         _: &WithVector,
         _: Option<&Filter>,
+        _: Option<&OrderBy>,
         _: &Handle,
     ) -> CollectionResult<Vec<Record>> {
         self.dummy()
     }
 
-    async fn search(
+    async fn core_search(
         &self,
-        _: Arc<SearchRequestBatch>,
+        _: Arc<CoreSearchRequestBatch>,
         _: &Handle,
+        _: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
         self.dummy()
     }
@@ -92,6 +120,10 @@ impl ShardOperation for DummyShard {
         self.dummy()
     }
 
+    async fn facet(&self, _: Arc<FacetRequest>) -> CollectionResult<FacetResponse> {
+        self.dummy()
+    }
+
     async fn retrieve(
         &self,
         _: Arc<PointRequest>,
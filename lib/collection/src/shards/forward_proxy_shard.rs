@@ -1,24 +1,31 @@
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
+use segment::data_types::search_plan::SegmentSearchPlan;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PointIdType, ScoredPoint, SeqNumberType, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::Mutex;
 
 use crate::operations::point_ops::{PointOperations, PointStruct, PointSyncOperation};
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
+    CountResult, FacetRequest, FacetResponse, PointRequest, Record, SearchPlanRequest,
+    UpdateResult,
+};
+use crate::operations::{
+    CollectionUpdateOperations, CreateIndex, FieldIndexOperations, OperationWithClientId,
 };
-use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use crate::shards::local_shard::LocalShard;
 use crate::shards::remote_shard::RemoteShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
+use crate::shards::transfer::wal_delta::{transfer_wal_delta_batch, WalDeltaProgress};
 
 /// ForwardProxyShard
 ///
@@ -81,6 +88,7 @@ impl ForwardProxyShard {
                 &WithPayloadInterface::Bool(true),
                 &true.into(),
                 None,
+                None,
                 runtime_handle,
             )
             .await?;
@@ -117,6 +125,23 @@ impl ForwardProxyShard {
         Ok(next_page_offset)
     }
 
+    /// Move batch of operations from WAL of the wrapped shard to the remote shard.
+    /// Returns an operation number of the next batch to be transferred.
+    pub async fn transfer_wal_delta_batch(
+        &self,
+        from_op_num: SeqNumberType,
+        batch_size: usize,
+    ) -> CollectionResult<WalDeltaProgress> {
+        let _update_lock = self.update_lock.lock().await;
+        transfer_wal_delta_batch(
+            &self.wrapped_shard,
+            &self.remote_shard,
+            from_op_num,
+            batch_size,
+        )
+        .await
+    }
+
     pub fn deconstruct(self) -> (LocalShard, RemoteShard) {
         (self.wrapped_shard, self.remote_shard)
     }
@@ -137,31 +162,63 @@ impl ForwardProxyShard {
         self.wrapped_shard.on_optimizer_config_update().await
     }
 
+    pub async fn shutdown(&self) -> CollectionResult<()> {
+        self.wrapped_shard.shutdown().await
+    }
+
     pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
         self.wrapped_shard.get_telemetry_data()
     }
+
+    pub fn search_plan(
+        &self,
+        request: &SearchPlanRequest,
+    ) -> CollectionResult<Vec<SegmentSearchPlan>> {
+        self.wrapped_shard.search_plan(request)
+    }
 }
 
 #[async_trait]
 impl ShardOperation for ForwardProxyShard {
     /// Update `wrapped_shard` while keeping track of the changed points
-    async fn update(
+    async fn update_with_client_id(
         &self,
-        operation: CollectionUpdateOperations,
+        operation: OperationWithClientId,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
         let _update_lock = self.update_lock.lock().await;
         let local_shard = &self.wrapped_shard;
         // Shard update is within a write lock scope, because we need a way to block the shard updates
         // during the transfer restart and finalization.
-        local_shard.update(operation.clone(), wait).await?;
+        local_shard
+            .update_with_client_id(operation.clone(), wait)
+            .await?;
 
         self.remote_shard
-            .update(operation, false)
+            .update_with_client_id(operation, false)
             .await
             .map_err(|err| CollectionError::forward_proxy_error(self.remote_shard.peer_id, err))
     }
 
+    /// Update `wrapped_shard` with the batch and forward it to the remote shard
+    async fn update_batch(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        let _update_lock = self.update_lock.lock().await;
+        let results = self
+            .wrapped_shard
+            .update_batch(operations.clone(), wait)
+            .await?;
+
+        self.remote_shard
+            .update_batch(operations, false)
+            .await
+            .map_err(|err| CollectionError::forward_proxy_error(self.remote_shard.peer_id, err))?;
+        Ok(results)
+    }
+
     /// Forward read-only `scroll_by` to `wrapped_shard`
     async fn scroll_by(
         &self,
@@ -170,6 +227,7 @@ impl ShardOperation for ForwardProxyShard {
         with_payload_interface: &WithPayloadInterface,
         with_vector: &WithVector,
         filter: Option<&Filter>,
+        order_by: Option<&OrderBy>,
         search_runtime_handle: &Handle,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
@@ -180,6 +238,7 @@ impl ShardOperation for ForwardProxyShard {
                 with_payload_interface,
                 with_vector,
                 filter,
+                order_by,
                 search_runtime_handle,
             )
             .await
@@ -190,13 +249,16 @@ impl ShardOperation for ForwardProxyShard {
         local_shard.info().await
     }
 
-    async fn search(
+    async fn core_search(
         &self,
-        request: Arc<SearchRequestBatch>,
+        request: Arc<CoreSearchRequestBatch>,
         search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
         let local_shard = &self.wrapped_shard;
-        local_shard.search(request, search_runtime_handle).await
+        local_shard
+            .core_search(request, search_runtime_handle, timeout)
+            .await
     }
 
     async fn count(&self, request: Arc<CountRequest>) -> CollectionResult<CountResult> {
@@ -204,6 +266,11 @@ impl ShardOperation for ForwardProxyShard {
         local_shard.count(request).await
     }
 
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<FacetResponse> {
+        let local_shard = &self.wrapped_shard;
+        local_shard.facet(request).await
+    }
+
     async fn retrieve(
         &self,
         request: Arc<PointRequest>,
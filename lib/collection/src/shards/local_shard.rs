@@ -1,23 +1,24 @@
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeSet, HashMap};
-use std::mem::size_of;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread;
+use std::time::Instant;
 
 use arc_swap::ArcSwap;
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
 use parking_lot::{Mutex as ParkingMutex, RwLock};
-use segment::data_types::vectors::VectorElementType;
+use segment::data_types::search_plan::SegmentSearchPlan;
+use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
 use segment::entry::entry_point::SegmentEntry;
 use segment::index::field_index::CardinalityEstimation;
 use segment::segment::Segment;
 use segment::segment_constructor::{build_segment, load_segment};
 use segment::types::{
     CompressionRatio, Filter, PayloadIndexInfo, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, SegmentType,
+    QuantizationConfig, SegmentConfig, SegmentResourceUsage, SegmentType, SeqNumberType,
 };
 use segment::utils::mem::Mem;
 use tokio::fs::{copy, create_dir_all, remove_dir_all};
@@ -28,22 +29,42 @@ use uuid::Uuid;
 use wal::{Wal, WalOptions};
 
 use crate::collection_manager::collection_updater::CollectionUpdater;
-use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
+use crate::collection_manager::optimizers::optimization_tasks::OptimizationTasks;
+use crate::collection_manager::optimizers::segment_optimizer::SegmentOptimizer;
+use crate::collection_manager::segments_searcher::SegmentsSearcher;
+use crate::common::resource_usage::ShardResourceUsage;
 use crate::config::CollectionConfig;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CollectionStatus, OptimizersStatus,
+    SearchPlanRequest,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{ClientOperationId, OperationWithClientId};
 use crate::optimizers_builder::{build_optimizers, clear_temp_segments};
+use crate::shards::client_operations::AppliedClientOperations;
 use crate::shards::shard::ShardId;
 use crate::shards::shard_config::{ShardConfig, SHARD_CONFIG_FILE};
-use crate::shards::telemetry::{LocalShardTelemetry, OptimizerTelemetry};
+use crate::shards::telemetry::{
+    FlushTelemetry, LocalShardTelemetry, OptimizerTelemetry, WalRecoveryReport,
+};
 use crate::shards::CollectionId;
-use crate::update_handler::{Optimizer, UpdateHandler, UpdateSignal};
+use crate::update_handler::{LastFlush, Optimizer, UpdateHandler, UpdateSignal};
 use crate::wal::SerdeWal;
 
-pub type LockedWal = Arc<ParkingMutex<SerdeWal<CollectionUpdateOperations>>>;
+pub type LockedWal = Arc<ParkingMutex<SerdeWal<OperationWithClientId>>>;
+
+/// Keeps operations in WAL of the local shard, until dropped.
+/// See [`LocalShard::retain_wal_from`].
+pub struct WalRetentionGuard {
+    wal: LockedWal,
+}
+
+impl Drop for WalRetentionGuard {
+    fn drop(&mut self) {
+        self.wal.lock().retain_from(None);
+    }
+}
 
 /// LocalShard
 ///
@@ -60,6 +81,14 @@ pub struct LocalShard {
     pub(super) path: PathBuf,
     pub(super) optimizers: Arc<Vec<Arc<Optimizer>>>,
     update_runtime: Handle,
+    /// Result of WAL replay, if the shard was loaded from disk
+    wal_recovery: Option<WalRecoveryReport>,
+    /// Last flush of the update handler, shared with its flush worker
+    last_flush: Arc<ParkingMutex<Option<LastFlush>>>,
+    /// Optimization tasks of the update handler
+    pub(super) optimization_tasks: Arc<OptimizationTasks>,
+    /// Recent operations with client supplied ids, used to skip retried operations
+    pub(super) applied_client_operations: ParkingMutex<AppliedClientOperations>,
 }
 
 /// Shard holds information about segments and WAL.
@@ -103,7 +132,7 @@ impl LocalShard {
         segment_holder: SegmentHolder,
         collection_config: Arc<TokioRwLock<CollectionConfig>>,
         shared_storage_config: Arc<SharedStorageConfig>,
-        wal: SerdeWal<CollectionUpdateOperations>,
+        wal: SerdeWal<OperationWithClientId>,
         optimizers: Arc<Vec<Arc<Optimizer>>>,
         shard_path: &Path,
         update_runtime: Handle,
@@ -125,6 +154,11 @@ impl LocalShard {
         let (update_sender, update_receiver) =
             mpsc::channel(shared_storage_config.update_queue_size);
         update_handler.run_workers(update_receiver);
+        let last_flush = update_handler.last_flush.clone();
+        let optimization_tasks = update_handler.optimization_tasks.clone();
+        let applied_client_operations = ParkingMutex::new(AppliedClientOperations::new(
+            shared_storage_config.client_op_id_window,
+        ));
 
         drop(config); // release `shared_config` from borrow checker
 
@@ -138,6 +172,10 @@ impl LocalShard {
             path: shard_path.to_owned(),
             update_runtime,
             optimizers,
+            wal_recovery: None,
+            last_flush,
+            optimization_tasks,
+            applied_client_operations,
         }
     }
 
@@ -160,11 +198,13 @@ impl LocalShard {
         let segments_path = Self::segments_path(shard_path);
         let mut segment_holder = SegmentHolder::default();
 
-        let wal: SerdeWal<CollectionUpdateOperations> = SerdeWal::new(
+        let wal: SerdeWal<OperationWithClientId> = SerdeWal::new(
             wal_path.to_str().unwrap(),
             (&collection_config_read.wal_config).into(),
         )
-        .map_err(|e| CollectionError::service_error(format!("Wal error: {e}")))?;
+        .map_err(|e| CollectionError::service_error(format!("Wal error: {e}")))?
+        .with_compression(collection_config_read.wal_config.wal_compression)
+        .with_retention((&collection_config_read.wal_config).into());
 
         let segment_dirs = std::fs::read_dir(&segments_path).map_err(|err| {
             CollectionError::service_error(format!(
@@ -225,9 +265,11 @@ impl LocalShard {
             &collection_config_read.quantization_config,
         );
 
+        let lazy_vectors = collection_config_read.params.on_disk_vectors();
+
         drop(collection_config_read); // release `shared_config` from borrow checker
 
-        let collection = LocalShard::new(
+        let mut collection = LocalShard::new(
             segment_holder,
             collection_config,
             shared_storage_config,
@@ -238,7 +280,7 @@ impl LocalShard {
         )
         .await;
 
-        collection.load_from_wal(collection_id)?;
+        collection.wal_recovery = Some(collection.load_from_wal(collection_id)?);
 
         let available_memory_bytes = Mem::new().available_memory_bytes() as usize;
         let vectors_size_bytes = collection.estimate_vector_data_size().await;
@@ -256,7 +298,7 @@ impl LocalShard {
         if do_mmap_prefault {
             for (_, segment) in collection.segments.read().iter() {
                 if let LockedSegment::Original(segment) = segment {
-                    segment.read().prefault_mmap_pages();
+                    segment.read().prefault_mmap_pages(&lazy_vectors);
                 }
             }
         }
@@ -330,12 +372,14 @@ impl LocalShard {
         let mut build_handlers = vec![];
 
         let vector_params = config.params.into_base_vector_data()?;
+        let sparse_vector_params = config.params.into_sparse_vector_data();
         let segment_number = config.optimizer_config.get_number_segments();
 
         for _sid in 0..segment_number {
             let path_clone = segments_path.clone();
             let segment_config = SegmentConfig {
                 vector_data: vector_params.clone(),
+                sparse_vector_data: sparse_vector_params.clone(),
                 payload_storage_type: if config.params.on_disk_payload {
                     PayloadStorageType::OnDisk
                 } else {
@@ -368,8 +412,10 @@ impl LocalShard {
             segment_holder.add(segment);
         }
 
-        let wal: SerdeWal<CollectionUpdateOperations> =
-            SerdeWal::new(wal_path.to_str().unwrap(), (&config.wal_config).into())?;
+        let wal: SerdeWal<OperationWithClientId> =
+            SerdeWal::new(wal_path.to_str().unwrap(), (&config.wal_config).into())?
+                .with_compression(config.wal_config.wal_compression)
+                .with_retention((&config.wal_config).into());
 
         let optimizers = build_optimizers(
             shard_path,
@@ -406,7 +452,14 @@ impl LocalShard {
     }
 
     /// Loads latest collection operations from WAL
-    pub fn load_from_wal(&self, collection_id: CollectionId) -> CollectionResult<()> {
+    ///
+    /// Returns a report about applied and failed operations.
+    pub fn load_from_wal(
+        &self,
+        collection_id: CollectionId,
+    ) -> CollectionResult<WalRecoveryReport> {
+        let start = Instant::now();
+        let mut report = WalRecoveryReport::default();
         let wal = self.wal.lock();
         let bar = ProgressBar::new(wal.len());
 
@@ -431,7 +484,53 @@ impl LocalShard {
         // (`SerdeWal::read_all` may even start reading WAL from some already truncated
         // index *occasionally*), but the storage can handle it.
 
-        for (op_num, update) in wal.read_all() {
+        // Operations up to this version were already seen by the storage before the restart
+        let persisted_version = segments
+            .read()
+            .iter()
+            .map(|(_, segment)| segment.get().read().version())
+            .max()
+            .unwrap_or(0);
+
+        let mut applied_client_operations = self.applied_client_operations.lock();
+
+        // Acknowledged records, kept in WAL by retention, are not replayed,
+        // but retries of their operations are still recognized
+        for (op_num, update) in wal.try_read(wal.first_available_index()) {
+            if op_num >= wal.first_index() {
+                break;
+            }
+            if let Ok(OperationWithClientId {
+                client_op_id: Some(client_op_id),
+                ..
+            }) = update
+            {
+                applied_client_operations.insert(client_op_id, op_num);
+            }
+        }
+
+        for (op_num, update) in wal.try_read_all() {
+            let OperationWithClientId {
+                operation: update,
+                client_op_id,
+            } = match update {
+                Ok(update) => update,
+                Err(err) => {
+                    log::error!(
+                        "Can't read WAL operation: {err}, \
+                         collection: {collection_id}, \
+                         op_num: {op_num}"
+                    );
+                    report.add_failure(op_num, err.to_string());
+                    bar.inc(1);
+                    continue;
+                }
+            };
+
+            if let Some(client_op_id) = client_op_id {
+                applied_client_operations.insert(client_op_id, op_num);
+            }
+
             // Propagate `CollectionError::ServiceError`, but skip other error types.
             match &CollectionUpdater::update(segments, op_num, update) {
                 Err(err @ CollectionError::ServiceError { error, backtrace }) => {
@@ -454,9 +553,16 @@ impl LocalShard {
                     log::error!("{err}");
                     return Err(err.clone());
                 }
-                Err(err @ CollectionError::NotFound { .. }) => log::warn!("{err}"),
-                Err(err) => log::error!("{err}"),
-                Ok(_) => (),
+                Err(err @ CollectionError::NotFound { .. }) => {
+                    log::warn!("{err}");
+                    report.add_failure(op_num, err.to_string());
+                }
+                Err(err) => {
+                    log::error!("{err}");
+                    report.add_failure(op_num, err.to_string());
+                }
+                Ok(_) if op_num <= persisted_version => report.skipped += 1,
+                Ok(_) => report.replayed += 1,
             }
             bar.inc(1);
         }
@@ -464,7 +570,21 @@ impl LocalShard {
         self.segments.read().flush_all(true)?;
         bar.finish();
 
-        Ok(())
+        report.duration_micros = start.elapsed().as_micros() as u64;
+        if report.failed > 0 {
+            log::warn!(
+                "Recovered collection {collection_id} from WAL with {} failed operations, \
+                 shard: {}",
+                report.failed,
+                self.path.display(),
+            );
+        }
+
+        Ok(report)
+    }
+
+    pub fn wal_recovery_report(&self) -> Option<&WalRecoveryReport> {
+        self.wal_recovery.as_ref()
     }
 
     pub async fn on_optimizer_config_update(&self) -> CollectionResult<()> {
@@ -497,7 +617,8 @@ impl LocalShard {
     /// Finishes ongoing update tasks
     pub async fn stop_gracefully(&self) {
         if let Err(err) = self.update_sender.load().send(UpdateSignal::Stop).await {
-            log::warn!("Error sending stop signal to update handler: {}", err);
+            // Update worker is already stopped, e.g. by the shutdown of the node
+            log::debug!("Error sending stop signal to update handler: {}", err);
         }
 
         self.stop_flush_worker().await;
@@ -507,6 +628,25 @@ impl LocalShard {
         }
     }
 
+    /// Stops update workers and persists all applied operations, before the node shuts down.
+    ///
+    /// Queued updates are applied, ongoing optimizations are cancelled at a safe point.
+    /// Then segments are flushed and the flushed version is acknowledged in WAL,
+    /// so the operations are not replayed on the next start.
+    pub async fn shutdown(&self) -> CollectionResult<()> {
+        self.stop_gracefully().await;
+
+        let segments = self.segments.clone();
+        let wal = self.wal.clone();
+        tokio::task::spawn_blocking(move || -> CollectionResult<()> {
+            wal.lock().flush()?;
+            let confirmed_version = UpdateHandler::flush_segments(segments)?;
+            wal.lock().ack(confirmed_version)?;
+            Ok(())
+        })
+        .await?
+    }
+
     pub fn restore_snapshot(snapshot_path: &Path) -> CollectionResult<()> {
         // recover segments
         let segments_path = LocalShard::segments_path(snapshot_path);
@@ -630,6 +770,34 @@ impl LocalShard {
         Ok(())
     }
 
+    /// Segments of the shard, as they are at the moment
+    pub fn locked_segments(&self) -> Vec<(SegmentId, LockedSegment)> {
+        self.segments
+            .read()
+            .iter()
+            .map(|(segment_id, segment)| (*segment_id, segment.clone()))
+            .collect()
+    }
+
+    /// Memory and disk usage of the segments and the WAL of this shard
+    pub fn resource_usage(&self, shard_id: ShardId) -> CollectionResult<ShardResourceUsage> {
+        let mut segments = SegmentResourceUsage::default();
+        for (_idx, segment) in self.segments.read().iter() {
+            segments.merge(&segment.get().read().resource_usage()?);
+        }
+        let wal_path = Self::wal_path(&self.path);
+        let wal_bytes = fs_extra::dir::get_size(&wal_path).map_err(|err| {
+            CollectionError::service_error(format!(
+                "Error while reading WAL size {wal_path:?} {err}"
+            ))
+        })?;
+        Ok(ShardResourceUsage {
+            shard_id,
+            segments,
+            wal_bytes: wal_bytes as usize,
+        })
+    }
+
     pub fn estimate_cardinality<'a>(
         &'a self,
         filter: Option<&'a Filter>,
@@ -654,21 +822,129 @@ impl LocalShard {
         Ok(cardinality)
     }
 
+    /// Explain how every segment of the shard would execute a vector search
+    pub fn search_plan(
+        &self,
+        request: &SearchPlanRequest,
+    ) -> CollectionResult<Vec<SegmentSearchPlan>> {
+        let vector_name = request.using.as_deref().unwrap_or(DEFAULT_VECTOR_NAME);
+        let segments = self.segments().read();
+        let mut plans = Vec::new();
+        for (_id, segment) in segments.iter() {
+            plans.extend(segment.get().read().search_plan(
+                vector_name,
+                request.filter.as_ref(),
+                request.params.as_ref(),
+            )?);
+        }
+        Ok(plans)
+    }
+
     pub fn read_filtered<'a>(
         &'a self,
         filter: Option<&'a Filter>,
     ) -> CollectionResult<BTreeSet<PointIdType>> {
-        let segments = self.segments().read();
-        let some_segment = segments.iter().next();
+        SegmentsSearcher::read_filtered(self.segments(), filter)
+    }
 
-        if some_segment.is_none() {
-            return Ok(Default::default());
+    /// The first operation, which is still stored in WAL of this shard.
+    ///
+    /// Includes acknowledged operations kept by WAL retention,
+    /// so all operations starting from this one can be replayed from WAL.
+    pub fn first_available_op_num(&self) -> u64 {
+        self.wal.lock().first_available_index()
+    }
+
+    /// Keep operations starting from `op_num` in WAL, until the returned guard is dropped,
+    /// so they can be replayed to another replica.
+    pub fn retain_wal_from(&self, op_num: SeqNumberType) -> WalRetentionGuard {
+        self.wal.lock().retain_from(Some(op_num));
+        WalRetentionGuard {
+            wal: self.wal.clone(),
         }
-        let all_points: BTreeSet<_> = segments
+    }
+
+    /// The last operation, which is applied to segments of this shard.
+    pub fn last_applied_op_num(&self) -> SeqNumberType {
+        self.segments
+            .read()
             .iter()
-            .flat_map(|(_id, segment)| segment.get().read().read_filtered(None, None, filter))
-            .collect();
-        Ok(all_points)
+            .map(|(_, segment)| segment.get().read().version())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Read up to `limit` operations from WAL, starting from `from_op_num`.
+    ///
+    /// Returns `None`, if WAL does not contain all operations starting from `from_op_num`,
+    /// e.g. if they were already truncated.
+    pub fn read_wal_delta(
+        &self,
+        from_op_num: SeqNumberType,
+        limit: usize,
+    ) -> CollectionResult<Option<Vec<(SeqNumberType, OperationWithClientId)>>> {
+        let wal = self.wal.lock();
+        let next_op_num = wal.first_index() + wal.len();
+        if from_op_num < wal.first_available_index() || from_op_num > next_op_num {
+            return Ok(None);
+        }
+        wal.try_read(from_op_num)
+            .take(limit)
+            .map(|(op_num, operation)| {
+                operation
+                    .map(|operation| (op_num, operation))
+                    .map_err(|err| {
+                        CollectionError::service_error(format!(
+                            "Can't read operation {op_num} from WAL - {err}"
+                        ))
+                    })
+            })
+            .collect::<CollectionResult<Vec<_>>>()
+            .map(Some)
+    }
+
+    /// Client ids of the last `count` operations in WAL, used as the point to recover this shard
+    /// from WAL of another replica, see [`LocalShard::find_recovery_point`].
+    ///
+    /// Operation numbers are assigned by each replica independently, unlike client ids.
+    /// Returns an empty list if any of these operations has no client id.
+    pub fn recovery_point(&self, count: usize) -> CollectionResult<Vec<ClientOperationId>> {
+        let wal = self.wal.lock();
+        let next_op_num = wal.first_index() + wal.len();
+        let from_op_num = next_op_num
+            .saturating_sub(count as SeqNumberType)
+            .max(wal.first_available_index());
+        let mut client_op_ids = Vec::with_capacity(count);
+        for (op_num, operation) in wal.try_read(from_op_num) {
+            let operation = operation.map_err(|err| {
+                CollectionError::service_error(format!(
+                    "Can't read operation {op_num} from WAL - {err}"
+                ))
+            })?;
+            let Some(client_op_id) = operation.client_op_id else {
+                return Ok(Vec::new());
+            };
+            client_op_ids.push(client_op_id);
+        }
+        Ok(client_op_ids)
+    }
+
+    /// The first operation in WAL, from which the replica with the given recovery point
+    /// can be recovered, see [`LocalShard::recovery_point`].
+    ///
+    /// Operations of the replica may be applied in a different order than here,
+    /// so the delta starts from the oldest of them. Returns `None`, if any of them is unknown.
+    pub fn find_recovery_point(
+        &self,
+        client_op_ids: &[ClientOperationId],
+    ) -> Option<SeqNumberType> {
+        let applied_client_operations = self.applied_client_operations.lock();
+        client_op_ids
+            .iter()
+            .map(|client_op_id| applied_client_operations.peek(client_op_id))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()
     }
 
     pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
@@ -688,6 +964,16 @@ impl LocalShard {
             .iter()
             .map(|optimizer| optimizer.get_telemetry_data())
             .fold(Default::default(), |acc, x| acc + x);
+        let index_builds = self
+            .optimizers
+            .iter()
+            .map(|optimizer| {
+                optimizer
+                    .get_index_build_telemetry_counter()
+                    .lock()
+                    .get_statistics()
+            })
+            .fold(Default::default(), |acc, x| acc + x);
 
         LocalShardTelemetry {
             variant_name: None,
@@ -695,7 +981,27 @@ impl LocalShard {
             optimizations: OptimizerTelemetry {
                 status: optimizer_status,
                 optimizations,
+                index_builds,
             },
+            wal_recovery: self.wal_recovery.clone(),
+            flush: Some(self.flush_telemetry()),
+        }
+    }
+
+    fn flush_telemetry(&self) -> FlushTelemetry {
+        let last_flush = *self.last_flush.lock();
+        let wal = self.wal.lock();
+        let unflushed_operations = match last_flush {
+            Some(last_flush) => wal
+                .last_index()
+                .saturating_sub(last_flush.confirmed_version),
+            // Nothing is flushed since the shard was loaded, all records may require replay
+            None => wal.len(),
+        };
+        FlushTelemetry {
+            last_flush: last_flush.map(|last_flush| last_flush.time),
+            unflushed_operations,
+            wal_length: wal.len(),
         }
     }
 
@@ -726,15 +1032,32 @@ impl LocalShard {
                         CompressionRatio::X32 => vector_size / 8,
                         CompressionRatio::X64 => vector_size / 16,
                     },
+                    Some(QuantizationConfig::Binary(_)) => vector_size / 8,
                 };
 
-                vector_size * size_of::<VectorElementType>() + quantized_size_bytes
+                vector_size * value.datatype.unwrap_or_default().element_size()
+                    + quantized_size_bytes
             })
             .sum();
 
         vector_size * info.points_count
     }
 
+    /// Checks whether any of the current optimizers would select segments to optimize
+    ///
+    /// For example, after the HNSW config of the collection is changed, segments indexed with
+    /// the previous config are pending until the config mismatch optimizer rebuilds them.
+    async fn has_pending_optimizations(&self) -> bool {
+        // Optimizers are replaced in the update handler on config updates
+        let optimizers = self.update_handler.lock().await.optimizers.clone();
+        let excluded_ids = HashSet::new();
+        optimizers.iter().any(|optimizer| {
+            !optimizer
+                .check_condition(self.segments.clone(), &excluded_ids)
+                .is_empty()
+        })
+    }
+
     pub async fn local_shard_info(&self) -> CollectionInfo {
         let collection_config = self.collection_config.read().await.clone();
         let segments = self.segments().read();
@@ -791,6 +1114,12 @@ impl LocalShard {
             None => OptimizersStatus::Ok,
             Some(error) => OptimizersStatus::Error(error.to_string()),
         };
+        drop(segments);
+
+        // Segments still built with a previous config are not converged yet
+        if status == CollectionStatus::Green && self.has_pending_optimizations().await {
+            status = CollectionStatus::Yellow;
+        }
 
         CollectionInfo {
             status,
@@ -799,6 +1128,7 @@ impl LocalShard {
             indexed_vectors_count,
             points_count,
             segments_count,
+            effective_vectors_config: collection_config.effective_vectors_config(),
             config: collection_config,
             payload_schema: schema,
         }
@@ -1,8 +1,12 @@
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use futures::future::try_join_all;
 use itertools::Itertools;
+use segment::data_types::facets::{merge_facet_counts, top_facet_hits, FacetParams, FacetValue};
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
@@ -12,10 +16,10 @@ use tokio::sync::oneshot;
 use crate::collection_manager::segments_searcher::SegmentsSearcher;
 use crate::common::stopping_guard::StoppingGuard;
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult, UpdateStatus,
+    CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest, CountResult,
+    FacetRequest, FacetResponse, PointRequest, Record, UpdateResult, UpdateStatus,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{CollectionUpdateOperations, OperationWithClientId};
 use crate::shards::local_shard::LocalShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::update_handler::{OperationData, UpdateSignal};
@@ -25,9 +29,12 @@ impl ShardOperation for LocalShard {
     /// Imply interior mutability.
     /// Performs update operation on this collection asynchronously.
     /// Explicitly waits for result to be updated.
-    async fn update(
+    ///
+    /// Operation with a client id, which was already written into WAL, is not applied again.
+    /// Instead, the number of the original operation is returned, after it is applied if `wait`.
+    async fn update_with_client_id(
         &self,
-        operation: CollectionUpdateOperations,
+        operation: OperationWithClientId,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
         let (callback_sender, callback_receiver) = if wait {
@@ -37,34 +44,155 @@ impl ShardOperation for LocalShard {
             (None, None)
         };
 
+        // Set if the operation is a retry of an already written operation
+        let mut plunger_receiver = None;
+
         let operation_id = {
             let update_sender = self.update_sender.load();
             let channel_permit = update_sender.reserve().await?;
             let mut wal_lock = self.wal.lock();
-            let operation_id = wal_lock.write(&operation)?;
-            channel_permit.send(UpdateSignal::Operation(OperationData {
-                op_num: operation_id,
-                operation,
-                sender: callback_sender,
-                wait,
-            }));
-            operation_id
+            let mut applied_client_operations = self.applied_client_operations.lock();
+
+            let applied_op_num = operation
+                .client_op_id
+                .and_then(|client_op_id| applied_client_operations.get(&client_op_id));
+
+            match applied_op_num {
+                Some(operation_id) => {
+                    // Original operation is queued before the plunger, so it is applied by then
+                    let (plunger_sender, receiver) = oneshot::channel();
+                    channel_permit.send(UpdateSignal::Plunger(plunger_sender));
+                    plunger_receiver = Some(receiver);
+                    operation_id
+                }
+                None => {
+                    let operation_id = wal_lock.write(&operation)?;
+                    if let Some(client_op_id) = operation.client_op_id {
+                        applied_client_operations.insert(client_op_id, operation_id);
+                    }
+                    channel_permit.send(UpdateSignal::Operation(OperationData {
+                        op_num: operation_id,
+                        operation: operation.operation,
+                        sender: callback_sender,
+                        wait,
+                    }));
+                    operation_id
+                }
+            }
         };
 
+        if let Some(plunger_receiver) = plunger_receiver {
+            let status = if wait {
+                plunger_receiver.await?;
+                UpdateStatus::Completed
+            } else {
+                UpdateStatus::Acknowledged
+            };
+            return Ok(UpdateResult {
+                operation_id,
+                status,
+                error: None,
+                deleted_count: None,
+                deleted_ids: None,
+            });
+        }
+
         if let Some(receiver) = callback_receiver {
             let _res = receiver.await??;
             Ok(UpdateResult {
                 operation_id,
                 status: UpdateStatus::Completed,
+                error: None,
+                deleted_count: None,
+                deleted_ids: None,
             })
         } else {
             Ok(UpdateResult {
                 operation_id,
                 status: UpdateStatus::Acknowledged,
+                error: None,
+                deleted_count: None,
+                deleted_ids: None,
             })
         }
     }
 
+    /// Writes all operations into WAL as consecutive entries and sends them to the update
+    /// worker at once.
+    async fn update_batch(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        let mut pending = Vec::with_capacity(operations.len());
+        {
+            let update_sender = self.update_sender.load();
+            let channel_permit = update_sender.reserve().await?;
+            let mut wal_lock = self.wal.lock();
+            let mut batch = Vec::with_capacity(operations.len());
+            let mut write_result = Ok(());
+            for operation in operations {
+                let operation = OperationWithClientId::from(operation);
+                let op_num = match wal_lock.write(&operation) {
+                    Ok(op_num) => op_num,
+                    Err(err) => {
+                        write_result = Err(err);
+                        break;
+                    }
+                };
+                let (callback_sender, callback_receiver) = if wait {
+                    let (tx, rx) = oneshot::channel();
+                    (Some(tx), Some(rx))
+                } else {
+                    (None, None)
+                };
+                batch.push(OperationData {
+                    op_num,
+                    operation: operation.operation,
+                    sender: callback_sender,
+                    wait,
+                });
+                pending.push((op_num, callback_receiver));
+            }
+            // operations, which are already in WAL, are applied anyway
+            if !batch.is_empty() {
+                channel_permit.send(UpdateSignal::Operations(batch));
+            }
+            write_result?;
+        }
+
+        let mut results = Vec::with_capacity(pending.len());
+        for (operation_id, callback_receiver) in pending {
+            let result = match callback_receiver {
+                Some(receiver) => match receiver.await? {
+                    Ok(_) => UpdateResult {
+                        operation_id,
+                        status: UpdateStatus::Completed,
+                        error: None,
+                        deleted_count: None,
+                        deleted_ids: None,
+                    },
+                    Err(err) => UpdateResult {
+                        operation_id,
+                        status: UpdateStatus::Failed,
+                        error: Some(err.to_string()),
+                        deleted_count: None,
+                        deleted_ids: None,
+                    },
+                },
+                None => UpdateResult {
+                    operation_id,
+                    status: UpdateStatus::Acknowledged,
+                    error: None,
+                    deleted_count: None,
+                    deleted_ids: None,
+                },
+            };
+            results.push(result);
+        }
+        Ok(results)
+    }
+
     async fn scroll_by(
         &self,
         offset: Option<ExtendedPointId>,
@@ -72,8 +200,23 @@ impl ShardOperation for LocalShard {
         with_payload_interface: &WithPayloadInterface,
         with_vector: &WithVector,
         filter: Option<&Filter>,
+        order_by: Option<&OrderBy>,
         search_runtime_handle: &Handle,
     ) -> CollectionResult<Vec<Record>> {
+        if let Some(order_by) = order_by {
+            return self
+                .scroll_by_field(
+                    offset,
+                    limit,
+                    with_payload_interface,
+                    with_vector,
+                    filter,
+                    order_by,
+                    search_runtime_handle,
+                )
+                .await;
+        }
+
         // ToDo: Make faster points selection with a set
         let segments = self.segments();
         let read_handles: Vec<_> = {
@@ -106,6 +249,7 @@ impl ShardOperation for LocalShard {
         let mut points =
             SegmentsSearcher::retrieve(segments, &point_ids, &with_payload, with_vector)?;
         points.sort_by_key(|point| point.id);
+        points.iter_mut().for_each(|point| point.version = None);
 
         Ok(points)
     }
@@ -115,41 +259,54 @@ impl ShardOperation for LocalShard {
         Ok(self.local_shard_info().await)
     }
 
-    async fn search(
+    async fn core_search(
         &self,
-        request: Arc<SearchRequestBatch>,
+        request: Arc<CoreSearchRequestBatch>,
         search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
         let collection_params = self.collection_config.read().await.params.clone();
         // check vector names existing
         for req in &request.searches {
-            collection_params.get_vector_params(req.vector.get_name())?;
+            collection_params.get_vector_params(req.query.get_vector_name())?;
         }
 
+        // Timeout of the request, or the default one of the collection, or of the service
+        let timeout = timeout
+            .or_else(|| {
+                collection_params
+                    .search_timeout_sec
+                    .map(|timeout| Duration::from_secs(timeout.get()))
+            })
+            .unwrap_or(self.shared_storage_config.search_timeout);
+
         let is_stopped = StoppingGuard::new();
 
-        let search_request = SegmentsSearcher::search(
+        let res = SegmentsSearcher::search(
             self.segments(),
             request.clone(),
             search_runtime_handle,
             true,
             is_stopped.get_is_stopped(),
-        );
-        let timeout = self.shared_storage_config.search_timeout;
-        let res: Vec<Vec<ScoredPoint>> = tokio::select! {
-            res = search_request => res,
-            _ = tokio::time::sleep(timeout) => {
-                is_stopped.stop();
-                log::debug!("Search timeout reached: {} seconds", timeout.as_secs());
-                Err(CollectionError::timeout(timeout.as_secs() as usize, "Search"))
-            }
-        }?;
+            timeout,
+        )
+        .await?;
 
         let top_results = res
             .into_iter()
             .zip(request.searches.iter())
             .map(|(vector_res, req)| {
-                let vector_name = req.vector.get_name();
+                if !req.query.is_distance_scored() {
+                    // Custom scores are not related to the distance, and are "larger is better"
+                    return match req.score_threshold {
+                        Some(threshold) => vector_res
+                            .into_iter()
+                            .take_while(|scored_point| scored_point.score >= threshold)
+                            .collect(),
+                        None => vector_res,
+                    };
+                }
+                let vector_name = req.query.get_vector_name();
                 let distance = collection_params
                     .get_vector_params(vector_name)
                     .unwrap()
@@ -180,7 +337,22 @@ impl ShardOperation for LocalShard {
         } else {
             self.estimate_cardinality(request.filter.as_ref())?.exp
         };
-        Ok(CountResult { count: total_count })
+        Ok(CountResult {
+            count: total_count,
+            exact: request.exact,
+        })
+    }
+
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<FacetResponse> {
+        let counts = if request.exact {
+            self.exact_facet(&request)?
+        } else {
+            self.approximate_facet(&request)?
+        };
+        // Values are truncated to the limit only after merging counts of all shards
+        Ok(FacetResponse {
+            hits: top_facet_hits(counts, usize::MAX),
+        })
     }
 
     async fn retrieve(
@@ -189,6 +361,136 @@ impl ShardOperation for LocalShard {
         with_payload: &WithPayload,
         with_vector: &WithVector,
     ) -> CollectionResult<Vec<Record>> {
-        SegmentsSearcher::retrieve(self.segments(), &request.ids, with_payload, with_vector)
+        let mut records =
+            SegmentsSearcher::retrieve(self.segments(), &request.ids, with_payload, with_vector)?;
+        if !request.with_version {
+            records.iter_mut().for_each(|record| record.version = None);
+        }
+        Ok(records)
+    }
+}
+
+impl LocalShard {
+    /// Count points per value by reading the points, which have the value and satisfy the filter.
+    ///
+    /// Points present in multiple segments are counted once, by their latest version.
+    fn exact_facet(&self, request: &FacetRequest) -> CollectionResult<HashMap<FacetValue, usize>> {
+        let values = {
+            let segments = self.segments().read();
+            let mut values = BTreeSet::new();
+            for (_id, segment) in segments.iter() {
+                values.extend(
+                    segment
+                        .get()
+                        .read()
+                        .facet_values(&request.key, request.filter.as_ref())?,
+                );
+            }
+            values
+        };
+
+        values
+            .into_iter()
+            .map(|value| {
+                let value_filter = Filter::new_must(value.match_condition(request.key.clone()));
+                let filter = match &request.filter {
+                    Some(filter) => value_filter.merge(filter),
+                    None => value_filter,
+                };
+                let count = self.read_filtered(Some(&filter))?.len();
+                Ok((value, count))
+            })
+            .collect()
+    }
+
+    /// Sum up counts per value estimated by the payload index of every segment
+    fn approximate_facet(
+        &self,
+        request: &FacetRequest,
+    ) -> CollectionResult<HashMap<FacetValue, usize>> {
+        let params = FacetParams {
+            key: request.key.clone(),
+            filter: request.filter.clone(),
+        };
+        let segments = self.segments().read();
+        let segments_counts = segments
+            .iter()
+            .map(|(_id, segment)| segment.get().read().approximate_facet(&params))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(merge_facet_counts(segments_counts))
+    }
+
+    /// Scroll points in the order of their `order_by` payload values, merging the ordered points
+    /// of every segment.
+    #[allow(clippy::too_many_arguments)]
+    async fn scroll_by_field(
+        &self,
+        offset: Option<ExtendedPointId>,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        order_by: &OrderBy,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Vec<Record>> {
+        let segments = self.segments();
+        let read_handles: Vec<_> = {
+            let segments_guard = segments.read();
+            segments_guard
+                .iter()
+                .map(|(_, segment)| {
+                    let segment = segment.clone();
+                    let filter = filter.cloned();
+                    let order_by = order_by.clone();
+                    search_runtime_handle.spawn_blocking(move || {
+                        segment.get().read().read_ordered_filtered(
+                            offset,
+                            Some(limit),
+                            filter.as_ref(),
+                            &order_by,
+                        )
+                    })
+                })
+                .collect()
+        };
+        let segments_points = try_join_all(read_handles)
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let direction = order_by.direction();
+        let ordered_points = segments_points
+            .into_iter()
+            .kmerge_by(|a, b| direction.compare(a, b).is_lt())
+            .dedup()
+            .take(limit)
+            .collect_vec();
+
+        let point_ids = ordered_points
+            .iter()
+            .map(|(_, point_id)| *point_id)
+            .unique()
+            .collect_vec();
+        let with_payload = WithPayload::from(with_payload_interface);
+        let records: HashMap<_, _> =
+            SegmentsSearcher::retrieve(segments, &point_ids, &with_payload, with_vector)?
+                .into_iter()
+                .map(|record| (record.id, record))
+                .collect();
+
+        // Points with multiple values are returned once per value
+        let points = ordered_points
+            .into_iter()
+            .filter_map(|(order_value, point_id)| {
+                let record = records.get(&point_id)?;
+                Some(Record {
+                    order_value: Some(order_value),
+                    version: None,
+                    ..record.clone()
+                })
+            })
+            .collect();
+
+        Ok(points)
     }
 }
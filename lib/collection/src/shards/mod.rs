@@ -1,4 +1,5 @@
 pub mod channel_service;
+pub mod client_operations;
 pub mod collection_shard_distribution;
 mod conversions;
 pub mod dummy_shard;
@@ -7,14 +8,18 @@ pub mod local_shard;
 pub mod local_shard_operations;
 pub mod proxy_shard;
 pub mod remote_shard;
+pub mod replica_failover;
 #[allow(dead_code)]
 pub mod replica_set;
+pub mod replica_update;
+pub mod resharding;
 pub mod resolve;
 pub mod shard;
 pub mod shard_config;
 pub mod shard_holder;
 pub mod shard_trait;
 pub mod shard_versioning;
+pub mod split_proxy_shard;
 pub mod telemetry;
 pub mod transfer;
 
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
+use segment::data_types::search_plan::SegmentSearchPlan;
 use segment::types::{
     ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
     WithVector,
@@ -17,10 +19,11 @@ use crate::operations::operation_effect::{
     EstimateOperationEffectArea, OperationEffectArea, PointsOperationEffect,
 };
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
+    CountResult, FacetRequest, FacetResponse, PointRequest, Record, SearchPlanRequest,
+    UpdateResult,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{CollectionUpdateOperations, OperationWithClientId};
 use crate::shards::local_shard::LocalShard;
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::LocalShardTelemetry;
@@ -35,7 +38,7 @@ type ChangedPointsSet = Arc<RwLock<HashSet<PointIdType>>>;
 /// It can be used to provide all read and write operations while the wrapped shard is being transferred to another node.
 /// It keeps track of changed points during the shard transfer to assure consistency.
 pub struct ProxyShard {
-    wrapped_shard: LocalShard,
+    pub(crate) wrapped_shard: LocalShard,
     changed_points: ChangedPointsSet,
     pub changed_alot: AtomicBool,
 }
@@ -77,6 +80,10 @@ impl ProxyShard {
         self.wrapped_shard.on_optimizer_config_update().await
     }
 
+    pub async fn shutdown(&self) -> CollectionResult<()> {
+        self.wrapped_shard.shutdown().await
+    }
+
     pub async fn reinit_changelog(&self) -> CollectionResult<()> {
         // Blocks updates in the wrapped shard.
         let mut changed_points_guard = self.changed_points.write().await;
@@ -119,19 +126,22 @@ impl ProxyShard {
     pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
         self.wrapped_shard.get_telemetry_data()
     }
-}
 
-#[async_trait]
-impl ShardOperation for ProxyShard {
-    /// Update `wrapped_shard` while keeping track of the changed points
-    async fn update(
+    pub fn search_plan(
         &self,
-        operation: CollectionUpdateOperations,
-        wait: bool,
-    ) -> CollectionResult<UpdateResult> {
+        request: &SearchPlanRequest,
+    ) -> CollectionResult<Vec<SegmentSearchPlan>> {
+        self.wrapped_shard.search_plan(request)
+    }
+
+    /// Points, which are going to be changed by the operation
+    fn points_operation_effect(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<PointsOperationEffect> {
         let local_shard = &self.wrapped_shard;
         let estimate_effect = operation.estimate_effect_area();
-        let points_operation_effect: PointsOperationEffect = match estimate_effect {
+        let points_operation_effect = match estimate_effect {
             OperationEffectArea::Empty => PointsOperationEffect::Empty,
             OperationEffectArea::Points(points) => PointsOperationEffect::Some(points),
             OperationEffectArea::Filter(filter) => {
@@ -145,25 +155,68 @@ impl ShardOperation for ProxyShard {
                 }
             }
         };
+        Ok(points_operation_effect)
+    }
 
-        {
-            let mut changed_points_guard = self.changed_points.write().await;
-            match points_operation_effect {
-                PointsOperationEffect::Empty => {}
-                PointsOperationEffect::Some(points) => {
-                    for point in points {
-                        // points updates are recorded but never trigger in `changed_alot`
-                        changed_points_guard.insert(point);
-                    }
-                }
-                PointsOperationEffect::Many => {
-                    self.changed_alot
-                        .store(true, std::sync::atomic::Ordering::Relaxed);
+    fn record_changed_points(
+        &self,
+        changed_points: &mut HashSet<PointIdType>,
+        points_operation_effect: PointsOperationEffect,
+    ) {
+        match points_operation_effect {
+            PointsOperationEffect::Empty => {}
+            PointsOperationEffect::Some(points) => {
+                for point in points {
+                    // points updates are recorded but never trigger in `changed_alot`
+                    changed_points.insert(point);
                 }
             }
+            PointsOperationEffect::Many => {
+                self.changed_alot
+                    .store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ShardOperation for ProxyShard {
+    /// Update `wrapped_shard` while keeping track of the changed points
+    async fn update_with_client_id(
+        &self,
+        operation: OperationWithClientId,
+        wait: bool,
+    ) -> CollectionResult<UpdateResult> {
+        let points_operation_effect = self.points_operation_effect(&operation.operation)?;
+
+        {
+            let mut changed_points_guard = self.changed_points.write().await;
+            self.record_changed_points(&mut changed_points_guard, points_operation_effect);
             // Shard update is within a write lock scope, because we need a way to block the shard updates
             // during the transfer restart and finalization.
-            local_shard.update(operation, wait).await
+            self.wrapped_shard
+                .update_with_client_id(operation, wait)
+                .await
+        }
+    }
+
+    /// Update `wrapped_shard` with the batch while keeping track of the changed points
+    async fn update_batch(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        let points_operation_effects = operations
+            .iter()
+            .map(|operation| self.points_operation_effect(operation))
+            .collect::<CollectionResult<Vec<_>>>()?;
+
+        {
+            let mut changed_points_guard = self.changed_points.write().await;
+            for points_operation_effect in points_operation_effects {
+                self.record_changed_points(&mut changed_points_guard, points_operation_effect);
+            }
+            self.wrapped_shard.update_batch(operations, wait).await
         }
     }
 
@@ -175,6 +228,7 @@ impl ShardOperation for ProxyShard {
         with_payload_interface: &WithPayloadInterface,
         with_vector: &WithVector,
         filter: Option<&Filter>,
+        order_by: Option<&OrderBy>,
         search_runtime_handle: &Handle,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
@@ -185,6 +239,7 @@ impl ShardOperation for ProxyShard {
                 with_payload_interface,
                 with_vector,
                 filter,
+                order_by,
                 search_runtime_handle,
             )
             .await
@@ -197,13 +252,16 @@ impl ShardOperation for ProxyShard {
     }
 
     /// Forward read-only `search` to `wrapped_shard`
-    async fn search(
+    async fn core_search(
         &self,
-        request: Arc<SearchRequestBatch>,
+        request: Arc<CoreSearchRequestBatch>,
         search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
         let local_shard = &self.wrapped_shard;
-        local_shard.search(request, search_runtime_handle).await
+        local_shard
+            .core_search(request, search_runtime_handle, timeout)
+            .await
     }
 
     /// Forward read-only `count` to `wrapped_shard`
@@ -212,6 +270,12 @@ impl ShardOperation for ProxyShard {
         local_shard.count(request).await
     }
 
+    /// Forward read-only `facet` to `wrapped_shard`
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<FacetResponse> {
+        let local_shard = &self.wrapped_shard;
+        local_shard.facet(request).await
+    }
+
     /// Forward read-only `retrieve` to `wrapped_shard`
     async fn retrieve(
         &self,
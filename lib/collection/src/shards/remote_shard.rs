@@ -1,19 +1,25 @@
 use std::future::Future;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::grpc::qdrant::collections_internal_client::CollectionsInternalClient;
 use api::grpc::qdrant::points_internal_client::PointsInternalClient;
 use api::grpc::qdrant::{
-    CollectionOperationResponse, CountPoints, CountPointsInternal, GetCollectionInfoRequest,
-    GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal, InitiateShardTransferRequest,
-    ScrollPoints, ScrollPointsInternal, SearchBatchPointsInternal,
+    CollectionOperationResponse, CoreSearchBatchPointsInternal, CountPoints, CountPointsInternal,
+    CreateShardSnapshotRequest, DeleteShardSnapshotRequest, FacetCounts, FacetCountsInternal,
+    GetCollectionInfoRequest, GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal,
+    GetShardRecoveryPointRequest, GetShardSnapshotChunkRequest, InitiateShardTransferRequest,
+    RecoverShardSnapshotRequest, ScrollPoints, ScrollPointsInternal,
+    UploadShardSnapshotChunkRequest,
 };
 use async_trait::async_trait;
 use parking_lot::Mutex;
 use segment::common::operation_time_statistics::{
     OperationDurationsAggregator, ScopeDurationMeasurer,
 };
+use segment::common::snapshot_manifest::FileManifest;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
@@ -28,23 +34,29 @@ use crate::operations::conversions::try_record_from_grpc;
 use crate::operations::payload_ops::PayloadOps;
 use crate::operations::point_ops::{PointOperations, WriteOrdering};
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequest, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CoreSearchRequest, CoreSearchRequestBatch,
+    CountRequest, CountResult, FacetRequest, FacetResponse, PointRequest, Record, SearchRequest,
+    UpdateResult,
 };
 use crate::operations::vector_ops::VectorOperations;
-use crate::operations::{CollectionUpdateOperations, FieldIndexOperations};
+use crate::operations::{
+    ClientOperationId, CollectionUpdateOperations, FieldIndexOperations, OperationWithClientId,
+};
 use crate::shards::channel_service::ChannelService;
 use crate::shards::conversions::{
     internal_clear_payload, internal_clear_payload_by_filter, internal_create_index,
     internal_delete_index, internal_delete_payload, internal_delete_points,
     internal_delete_points_by_filter, internal_set_payload, internal_sync_points,
-    internal_upsert_points, try_scored_point_from_grpc,
+    internal_update_batch, internal_upsert_points, try_scored_point_from_grpc,
 };
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::RemoteShardTelemetry;
 use crate::shards::CollectionId;
 
+/// Recovery of the shard from a snapshot includes loading all of its segments
+const SNAPSHOT_RECOVERY_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
 /// RemoteShard
 ///
 /// Remote Shard is a representation of a shard that is located on a remote peer.
@@ -147,9 +159,170 @@ impl RemoteShard {
         Ok(res)
     }
 
+    /// Client ids of up to `count` last operations of the shard on the remote peer,
+    /// see [`LocalShard::recovery_point`].
+    ///
+    /// Returns an empty list, if there is no local shard of this kind on the remote peer.
+    ///
+    /// [`LocalShard::recovery_point`]: crate::shards::local_shard::LocalShard::recovery_point
+    pub async fn recovery_point(&self, count: usize) -> CollectionResult<Vec<ClientOperationId>> {
+        let res = self
+            .with_collections_client(|mut client| async move {
+                client
+                    .recovery_point(GetShardRecoveryPointRequest {
+                        collection_name: self.collection_id.clone(),
+                        shard_id: self.id,
+                        count: count as u32,
+                    })
+                    .await
+            })
+            .await?
+            .into_inner();
+        res.last_client_op_ids
+            .iter()
+            .map(|client_op_id| {
+                ClientOperationId::parse_str(client_op_id).map_err(|err| {
+                    CollectionError::service_error(format!(
+                        "Invalid client operation id {client_op_id} in recovery point: {err}"
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Upload a chunk of the shard snapshot archive to the remote peer.
+    ///
+    /// Returns the number of bytes of the archive received by the remote peer so far.
+    pub async fn upload_snapshot_chunk(
+        &self,
+        offset: u64,
+        data: &[u8],
+        checksum: u64,
+    ) -> CollectionResult<u64> {
+        let res = self
+            .with_collections_client(|mut client| async move {
+                client
+                    .upload_shard_snapshot_chunk(UploadShardSnapshotChunkRequest {
+                        collection_name: self.collection_id.clone(),
+                        shard_id: self.id,
+                        offset,
+                        data: data.to_vec(),
+                        checksum,
+                    })
+                    .await
+            })
+            .await?
+            .into_inner();
+        Ok(res.received_bytes)
+    }
+
+    /// Recover the shard on the remote peer from the uploaded snapshot archive
+    pub async fn recover_snapshot(
+        &self,
+        size: u64,
+        checksum: &str,
+    ) -> CollectionResult<CollectionOperationResponse> {
+        let current_address = self.current_address()?;
+        let res = self
+            .channel_service
+            .channel_pool
+            .with_channel_timeout(
+                &current_address,
+                |channel| async move {
+                    CollectionsInternalClient::new(channel)
+                        .recover_shard_snapshot(RecoverShardSnapshotRequest {
+                            collection_name: self.collection_id.clone(),
+                            shard_id: self.id,
+                            size,
+                            checksum: checksum.to_string(),
+                        })
+                        .await
+                },
+                Some(SNAPSHOT_RECOVERY_TIMEOUT),
+                0,
+            )
+            .await?
+            .into_inner();
+        Ok(res)
+    }
+
+    /// Create an archive with the snapshot of the shard on the remote peer.
+    ///
+    /// Returns the name of the archive, which is used to download it.
+    pub async fn create_snapshot_archive(&self) -> CollectionResult<(String, FileManifest)> {
+        let current_address = self.current_address()?;
+        let res = self
+            .channel_service
+            .channel_pool
+            .with_channel_timeout(
+                &current_address,
+                |channel| async move {
+                    CollectionsInternalClient::new(channel)
+                        .create_shard_snapshot(CreateShardSnapshotRequest {
+                            collection_name: self.collection_id.clone(),
+                            shard_id: self.id,
+                        })
+                        .await
+                },
+                Some(SNAPSHOT_RECOVERY_TIMEOUT),
+                0,
+            )
+            .await?
+            .into_inner();
+        let manifest = FileManifest {
+            size: res.size,
+            checksum: res.checksum,
+        };
+        Ok((res.name, manifest))
+    }
+
+    /// Download a chunk of the shard snapshot archive from the remote peer.
+    ///
+    /// Returns the contents of the chunk and their checksum.
+    pub async fn get_snapshot_chunk(
+        &self,
+        name: &str,
+        offset: u64,
+    ) -> CollectionResult<(Vec<u8>, u64)> {
+        let res = self
+            .with_collections_client(|mut client| async move {
+                client
+                    .get_shard_snapshot_chunk(GetShardSnapshotChunkRequest {
+                        collection_name: self.collection_id.clone(),
+                        shard_id: self.id,
+                        name: name.to_string(),
+                        offset,
+                    })
+                    .await
+            })
+            .await?
+            .into_inner();
+        Ok((res.data, res.checksum))
+    }
+
+    /// Delete the shard snapshot archive on the remote peer
+    pub async fn delete_snapshot_archive(
+        &self,
+        name: &str,
+    ) -> CollectionResult<CollectionOperationResponse> {
+        let res = self
+            .with_collections_client(|mut client| async move {
+                client
+                    .delete_shard_snapshot(DeleteShardSnapshotRequest {
+                        collection_name: self.collection_id.clone(),
+                        shard_id: self.id,
+                        name: name.to_string(),
+                    })
+                    .await
+            })
+            .await?
+            .into_inner();
+        Ok(res)
+    }
+
     pub async fn forward_update(
         &self,
-        operation: CollectionUpdateOperations,
+        operation: OperationWithClientId,
         wait: bool,
         ordering: WriteOrdering,
     ) -> CollectionResult<UpdateResult> {
@@ -164,17 +337,72 @@ impl RemoteShard {
         .await
     }
 
+    pub async fn forward_update_batch(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        // the target shard is None because the batch is forwarded as if it came from the client
+        self.execute_update_batch(
+            None,
+            self.collection_id.clone(),
+            operations,
+            wait,
+            Some(ordering),
+        )
+        .await
+    }
+
+    pub async fn execute_update_batch(
+        &self,
+        shard_id: Option<ShardId>,
+        collection_name: String,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+        ordering: Option<WriteOrdering>,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_update_durations);
+        timer.set_success(false);
+
+        let request =
+            &internal_update_batch(shard_id, collection_name, operations, wait, ordering)?;
+        let update_batch_response = self
+            .with_points_client(|mut client| async move {
+                client
+                    .update_batch(tonic::Request::new(request.clone()))
+                    .await
+            })
+            .await?
+            .into_inner();
+
+        let result: Result<Vec<UpdateResult>, Status> = update_batch_response
+            .result
+            .into_iter()
+            .map(|update_result| update_result.try_into())
+            .collect();
+
+        let result = result.map_err(CollectionError::from)?;
+        timer.set_success(true);
+        Ok(result)
+    }
+
     pub async fn execute_update_operation(
         &self,
         shard_id: Option<ShardId>,
         collection_name: String,
-        operation: CollectionUpdateOperations,
+        operation: OperationWithClientId,
         wait: bool,
         ordering: Option<WriteOrdering>,
     ) -> CollectionResult<UpdateResult> {
         let mut timer = ScopeDurationMeasurer::new(&self.telemetry_update_durations);
         timer.set_success(false);
 
+        let OperationWithClientId {
+            operation,
+            client_op_id,
+        } = operation;
+
         let point_operation_response = match operation {
             CollectionUpdateOperations::PointOperation(point_ops) => match point_ops {
                 PointOperations::UpsertPoints(point_insert_operations) => {
@@ -184,6 +412,7 @@ impl RemoteShard {
                         point_insert_operations,
                         wait,
                         ordering,
+                        client_op_id,
                     )?;
                     self.with_points_client(|mut client| async move {
                         client.upsert(tonic::Request::new(request.clone())).await
@@ -192,8 +421,14 @@ impl RemoteShard {
                     .into_inner()
                 }
                 PointOperations::DeletePoints { ids } => {
-                    let request =
-                        &internal_delete_points(shard_id, collection_name, ids, wait, ordering);
+                    let request = &internal_delete_points(
+                        shard_id,
+                        collection_name,
+                        ids,
+                        wait,
+                        ordering,
+                        client_op_id,
+                    );
                     self.with_points_client(|mut client| async move {
                         client.delete(tonic::Request::new(request.clone())).await
                     })
@@ -207,6 +442,7 @@ impl RemoteShard {
                         filter,
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client.delete(tonic::Request::new(request.clone())).await
@@ -237,6 +473,7 @@ impl RemoteShard {
                         update_operation,
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client
@@ -254,6 +491,7 @@ impl RemoteShard {
                         vector_names.clone(),
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client
@@ -271,6 +509,7 @@ impl RemoteShard {
                         vector_names.clone(),
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client
@@ -289,6 +528,7 @@ impl RemoteShard {
                         set_payload,
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client
@@ -305,6 +545,7 @@ impl RemoteShard {
                         delete_payload,
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client
@@ -315,8 +556,14 @@ impl RemoteShard {
                     .into_inner()
                 }
                 PayloadOps::ClearPayload { points } => {
-                    let request =
-                        &internal_clear_payload(shard_id, collection_name, points, wait, ordering);
+                    let request = &internal_clear_payload(
+                        shard_id,
+                        collection_name,
+                        points,
+                        wait,
+                        ordering,
+                        client_op_id,
+                    );
                     self.with_points_client(|mut client| async move {
                         client
                             .clear_payload(tonic::Request::new(request.clone()))
@@ -332,6 +579,7 @@ impl RemoteShard {
                         filter,
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client
@@ -348,6 +596,7 @@ impl RemoteShard {
                         set_payload,
                         wait,
                         ordering,
+                        client_op_id,
                     );
                     self.with_points_client(|mut client| async move {
                         client
@@ -376,6 +625,22 @@ impl RemoteShard {
                     .await?
                     .into_inner()
                 }
+                FieldIndexOperations::UpdateIndex(update_index) => {
+                    let request = &internal_create_index(
+                        shard_id,
+                        collection_name,
+                        update_index,
+                        wait,
+                        ordering,
+                    );
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .update_field_index(tonic::Request::new(request.clone()))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
                 FieldIndexOperations::DeleteIndex(delete_index) => {
                     let request = &internal_delete_index(
                         shard_id,
@@ -406,12 +671,15 @@ impl RemoteShard {
 // New-type to own the type in the crate for conversions via From
 pub struct CollectionSearchRequest<'a>(pub(crate) (CollectionId, &'a SearchRequest));
 
+// New-type to own the type in the crate for conversions via From
+pub struct CollectionCoreSearchRequest<'a>(pub(crate) (CollectionId, &'a CoreSearchRequest));
+
 #[async_trait]
 #[allow(unused_variables)]
 impl ShardOperation for RemoteShard {
-    async fn update(
+    async fn update_with_client_id(
         &self,
-        operation: CollectionUpdateOperations,
+        operation: OperationWithClientId,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
         // targets the shard explicitly
@@ -420,6 +688,17 @@ impl ShardOperation for RemoteShard {
             .await
     }
 
+    async fn update_batch(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        // targets the shard explicitly
+        let shard_id = Some(self.id);
+        self.execute_update_batch(shard_id, self.collection_id.clone(), operations, wait, None)
+            .await
+    }
+
     async fn scroll_by(
         &self,
         offset: Option<ExtendedPointId>,
@@ -427,6 +706,7 @@ impl ShardOperation for RemoteShard {
         with_payload_interface: &WithPayloadInterface,
         with_vector: &WithVector,
         filter: Option<&Filter>,
+        order_by: Option<&OrderBy>,
         search_runtime_handle: &Handle,
     ) -> CollectionResult<Vec<Record>> {
         let scroll_points = ScrollPoints {
@@ -437,6 +717,9 @@ impl ShardOperation for RemoteShard {
             with_payload: Some(with_payload_interface.clone().into()),
             with_vectors: Some(with_vector.clone().into()),
             read_consistency: None,
+            order_by: order_by.map(|order_by| order_by.clone().into()),
+            page_token: None,
+            shard_key_selector: None,
         };
         let request = &ScrollPointsInternal {
             scroll_points: Some(scroll_points),
@@ -478,10 +761,11 @@ impl ShardOperation for RemoteShard {
         result.map_err(|e| e.into())
     }
 
-    async fn search(
+    async fn core_search(
         &self,
-        batch_request: Arc<SearchRequestBatch>,
+        batch_request: Arc<CoreSearchRequestBatch>,
         search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
         let mut timer = ScopeDurationMeasurer::new(&self.telemetry_search_durations);
         timer.set_success(false);
@@ -489,18 +773,19 @@ impl ShardOperation for RemoteShard {
         let search_points = batch_request
             .searches
             .iter()
-            .map(|s| CollectionSearchRequest((self.collection_id.clone(), s)).into())
+            .map(|s| CollectionCoreSearchRequest((self.collection_id.clone(), s)).into())
             .collect();
 
-        let request = &SearchBatchPointsInternal {
+        let request = &CoreSearchBatchPointsInternal {
             collection_name: self.collection_id.clone(),
             search_points,
             shard_id: Some(self.id),
+            timeout: timeout.map(|timeout| timeout.as_secs()),
         };
         let search_batch_response = self
             .with_points_client(|mut client| async move {
                 client
-                    .search_batch(tonic::Request::new(request.clone()))
+                    .core_search_batch(tonic::Request::new(request.clone()))
                     .await
             })
             .await?
@@ -535,6 +820,7 @@ impl ShardOperation for RemoteShard {
             collection_name: self.collection_id.clone(),
             filter: request.filter.clone().map(|f| f.into()),
             exact: Some(request.exact),
+            shard_key_selector: None,
         };
 
         let request = &CountPointsInternal {
@@ -557,6 +843,33 @@ impl ShardOperation for RemoteShard {
         )
     }
 
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<FacetResponse> {
+        let facet_counts = FacetCounts {
+            collection_name: self.collection_id.clone(),
+            key: request.key.clone(),
+            filter: request.filter.clone().map(|f| f.into()),
+            limit: request.limit.map(|limit| limit as u64),
+            exact: Some(request.exact),
+        };
+
+        let request = &FacetCountsInternal {
+            facet_counts: Some(facet_counts),
+            shard_id: Some(self.id),
+        };
+        let facet_response = self
+            .with_points_client(|mut client| async move {
+                client.facet(tonic::Request::new(request.clone())).await
+            })
+            .await?
+            .into_inner();
+        let hits = facet_response
+            .hits
+            .into_iter()
+            .map(|hit| hit.try_into())
+            .collect::<Result<_, Status>>()?;
+        Ok(FacetResponse { hits })
+    }
+
     async fn retrieve(
         &self,
         request: Arc<PointRequest>,
@@ -569,6 +882,7 @@ impl ShardOperation for RemoteShard {
             with_payload: request.with_payload.clone().map(|wp| wp.into()),
             with_vectors: Some(with_vector.clone().into()),
             read_consistency: None,
+            with_version: Some(request.with_version),
         };
         let request = &GetPointsInternal {
             get_points: Some(get_points),
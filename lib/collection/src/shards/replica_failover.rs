@@ -0,0 +1,558 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::collection_state::State;
+use crate::shards::replica_set::ReplicaState;
+use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::transfer::shard_transfer::ShardTransfer;
+
+/// How often the consensus leader checks for dead replicas
+pub const FAILOVER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Proposals, which are not applied by consensus within this time, are made again
+const PROPOSAL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Automatic replacement of dead replicas, which restores the replication factor of shards
+#[derive(Debug, Deserialize, Serialize, Validate, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicaFailoverConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a replica has to stay dead, before it is replaced by a new replica on another peer
+    #[serde(default = "default_grace_period_sec")]
+    pub grace_period_sec: u64,
+    /// Max number of concurrent shard transfers from or to a single peer
+    #[serde(default = "default_max_transfers_per_peer")]
+    #[validate(range(min = 1))]
+    pub max_transfers_per_peer: usize,
+    /// Max number of concurrent shard transfers in the whole cluster, including transfers
+    /// started by users
+    #[serde(default = "default_max_transfers")]
+    #[validate(range(min = 1))]
+    pub max_transfers: usize,
+}
+
+impl Default for ReplicaFailoverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            grace_period_sec: default_grace_period_sec(),
+            max_transfers_per_peer: default_max_transfers_per_peer(),
+            max_transfers: default_max_transfers(),
+        }
+    }
+}
+
+const fn default_grace_period_sec() -> u64 {
+    300
+}
+
+const fn default_max_transfers_per_peer() -> usize {
+    1
+}
+
+const fn default_max_transfers() -> usize {
+    2
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplicaRecoveryStatus {
+    /// Replica is dead, waiting for the grace period or for a peer to transfer the new replica to
+    Pending,
+    /// Transfer of the new replica is proposed
+    Planned,
+    /// New replica is being transferred
+    InProgress,
+}
+
+/// Replacement of a dead replica by a new replica on another peer
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ReplicaRecoveryInfo {
+    pub shard_id: ShardId,
+    /// Peer of the dead replica
+    pub dead_peer_id: PeerId,
+    /// Peer, which receives the new replica
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_peer_id: Option<PeerId>,
+    pub status: ReplicaRecoveryStatus,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProposedTransfer {
+    from: PeerId,
+    to: PeerId,
+    proposed_at: Instant,
+}
+
+#[derive(Debug, Clone)]
+struct DeadReplica {
+    since: Instant,
+    transfer: Option<ProposedTransfer>,
+    removal_proposed_at: Option<Instant>,
+}
+
+/// Dead replicas of a collection, tracked by the consensus leader.
+///
+/// Not persisted: the grace period starts over, when another peer becomes the leader.
+#[derive(Debug, Default)]
+pub struct ReplicaFailoverState {
+    dead_replicas: BTreeMap<(ShardId, PeerId), DeadReplica>,
+}
+
+/// Replicas and transfers of all collections, used to respect transfer limits
+/// and to balance new replicas between peers.
+#[derive(Debug, Default)]
+pub struct ClusterLoad {
+    peers: Vec<PeerId>,
+    /// Peers with dead replicas, new replicas are not placed on them
+    unhealthy_peers: HashSet<PeerId>,
+    replicas_per_peer: HashMap<PeerId, usize>,
+    transfers_per_peer: HashMap<PeerId, usize>,
+    transfers: usize,
+}
+
+impl ClusterLoad {
+    pub fn new(peers: impl IntoIterator<Item = PeerId>) -> Self {
+        let mut peers: Vec<_> = peers.into_iter().collect();
+        peers.sort_unstable();
+        Self {
+            peers,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_collection(&mut self, state: &State) {
+        for shard_info in state.shards.values() {
+            for (peer_id, replica_state) in &shard_info.replicas {
+                *self.replicas_per_peer.entry(*peer_id).or_default() += 1;
+                if *replica_state == ReplicaState::Dead {
+                    self.unhealthy_peers.insert(*peer_id);
+                }
+            }
+        }
+        for transfer in &state.transfers {
+            self.add_transfer(transfer);
+        }
+    }
+
+    fn add_transfer(&mut self, transfer: &ShardTransfer) {
+        *self.transfers_per_peer.entry(transfer.from).or_default() += 1;
+        *self.transfers_per_peer.entry(transfer.to).or_default() += 1;
+        self.transfers += 1;
+    }
+
+    fn peer_transfers(&self, peer_id: PeerId) -> usize {
+        self.transfers_per_peer.get(&peer_id).copied().unwrap_or(0)
+    }
+}
+
+/// Consensus proposals, which replace dead replicas of a collection
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReplicaFailoverPlan {
+    /// Transfers of new replicas
+    pub transfers: Vec<ShardTransfer>,
+    /// Dead replicas, which are replaced by active replicas already
+    pub removed_replicas: Vec<(ShardId, PeerId)>,
+}
+
+impl ReplicaFailoverState {
+    /// Plan replacement of the replicas, which stay dead longer than the grace period.
+    ///
+    /// A new replica is transferred from an active replica of the shard to a healthy peer,
+    /// which has no replica of the shard and the least replicas overall. The new replica is
+    /// activated by the transfer, only then the replaced dead replica is removed.
+    pub fn plan(
+        &mut self,
+        state: &State,
+        config: &ReplicaFailoverConfig,
+        load: &mut ClusterLoad,
+        now: Instant,
+    ) -> ReplicaFailoverPlan {
+        let mut plan = ReplicaFailoverPlan::default();
+        let grace_period = Duration::from_secs(config.grace_period_sec);
+        let replication_factor = state.config.params.replication_factor.get() as usize;
+
+        // Replicas, which are not dead anymore, were recovered or removed
+        self.dead_replicas.retain(|(shard_id, peer_id), _| {
+            state
+                .shards
+                .get(shard_id)
+                .and_then(|shard_info| shard_info.replicas.get(peer_id))
+                == Some(&ReplicaState::Dead)
+        });
+
+        // Shards of the resharding in progress are not complete
+        let resharding_shards: HashSet<ShardId> = state
+            .resharding
+            .iter()
+            .flat_map(|resharding| {
+                [
+                    resharding.split.source_shard_id,
+                    resharding.split.target_shard_id,
+                ]
+            })
+            .collect();
+
+        let mut shard_ids: Vec<_> = state.shards.keys().copied().collect();
+        shard_ids.sort_unstable();
+        for shard_id in shard_ids {
+            let replicas = &state.shards[&shard_id].replicas;
+            let mut dead_peers: Vec<_> = replicas
+                .iter()
+                .filter(|(_, replica_state)| **replica_state == ReplicaState::Dead)
+                .map(|(peer_id, _)| *peer_id)
+                .collect();
+            dead_peers.sort_unstable();
+            for peer_id in &dead_peers {
+                self.dead_replicas
+                    .entry((shard_id, *peer_id))
+                    .or_insert(DeadReplica {
+                        since: now,
+                        transfer: None,
+                        removal_proposed_at: None,
+                    });
+            }
+            if dead_peers.is_empty() || resharding_shards.contains(&shard_id) {
+                continue;
+            }
+
+            let is_transferred = |to: PeerId| {
+                state
+                    .transfers
+                    .iter()
+                    .any(|t| t.shard_id == shard_id && t.to == to)
+            };
+
+            // Replicas, which are alive or are going to be
+            let mut replica_count = replicas
+                .values()
+                .filter(|replica_state| {
+                    matches!(replica_state, ReplicaState::Active | ReplicaState::Partial)
+                })
+                .count();
+            // Peers, which are going to receive new replicas of the shard
+            let mut new_replica_peers = HashSet::new();
+
+            for peer_id in &dead_peers {
+                let dead_replica = self.dead_replicas.get_mut(&(shard_id, *peer_id)).unwrap();
+                let Some(transfer) = dead_replica.transfer else {
+                    continue;
+                };
+                match replicas.get(&transfer.to) {
+                    // Replaced already
+                    Some(ReplicaState::Active) => {
+                        let removal_due = dead_replica
+                            .removal_proposed_at
+                            .map_or(true, |at| now.duration_since(at) >= PROPOSAL_TIMEOUT);
+                        if removal_due {
+                            dead_replica.removal_proposed_at = Some(now);
+                            plan.removed_replicas.push((shard_id, *peer_id));
+                        }
+                    }
+                    Some(ReplicaState::Partial) if is_transferred(transfer.to) => {}
+                    // Proposal is not applied yet
+                    None if now.duration_since(transfer.proposed_at) < PROPOSAL_TIMEOUT => {
+                        load.add_transfer(&ShardTransfer {
+                            shard_id,
+                            from: transfer.from,
+                            to: transfer.to,
+                            sync: true,
+                            method: None,
+                        });
+                        *load.replicas_per_peer.entry(transfer.to).or_default() += 1;
+                        new_replica_peers.insert(transfer.to);
+                        replica_count += 1;
+                    }
+                    // Transfer failed or was aborted, plan it again
+                    _ => dead_replica.transfer = None,
+                }
+            }
+
+            for peer_id in &dead_peers {
+                if replica_count >= replication_factor || load.transfers >= config.max_transfers {
+                    break;
+                }
+                let dead_replica = self.dead_replicas.get_mut(&(shard_id, *peer_id)).unwrap();
+                if dead_replica.transfer.is_some()
+                    || now.duration_since(dead_replica.since) < grace_period
+                {
+                    continue;
+                }
+
+                let Some(from) = replicas
+                    .iter()
+                    .filter(|(_, replica_state)| **replica_state == ReplicaState::Active)
+                    .map(|(peer_id, _)| *peer_id)
+                    .filter(|peer_id| load.peer_transfers(*peer_id) < config.max_transfers_per_peer)
+                    .min_by_key(|peer_id| (load.peer_transfers(*peer_id), *peer_id))
+                else {
+                    continue;
+                };
+                let Some(to) = load
+                    .peers
+                    .iter()
+                    .copied()
+                    .filter(|peer_id| {
+                        !replicas.contains_key(peer_id)
+                            && !new_replica_peers.contains(peer_id)
+                            && !load.unhealthy_peers.contains(peer_id)
+                            && load.peer_transfers(*peer_id) < config.max_transfers_per_peer
+                    })
+                    .min_by_key(|peer_id| {
+                        (
+                            load.replicas_per_peer.get(peer_id).copied().unwrap_or(0),
+                            load.peer_transfers(*peer_id),
+                            *peer_id,
+                        )
+                    })
+                else {
+                    continue;
+                };
+
+                let transfer = ShardTransfer {
+                    shard_id,
+                    from,
+                    to,
+                    sync: true,
+                    method: None,
+                };
+                load.add_transfer(&transfer);
+                *load.replicas_per_peer.entry(to).or_default() += 1;
+                new_replica_peers.insert(to);
+                dead_replica.transfer = Some(ProposedTransfer {
+                    from,
+                    to,
+                    proposed_at: now,
+                });
+                replica_count += 1;
+                plan.transfers.push(transfer);
+            }
+        }
+        plan
+    }
+
+    /// Status of the dead replicas, which are not replaced yet
+    pub fn recoveries(&self, state: &State) -> Vec<ReplicaRecoveryInfo> {
+        self.dead_replicas
+            .iter()
+            .filter_map(|((shard_id, dead_peer_id), dead_replica)| {
+                let replicas = &state.shards.get(shard_id)?.replicas;
+                let status = match dead_replica.transfer {
+                    None => ReplicaRecoveryStatus::Pending,
+                    Some(transfer) => match replicas.get(&transfer.to) {
+                        None => ReplicaRecoveryStatus::Planned,
+                        Some(ReplicaState::Active) => return None,
+                        Some(_) => ReplicaRecoveryStatus::InProgress,
+                    },
+                };
+                Some(ReplicaRecoveryInfo {
+                    shard_id: *shard_id,
+                    dead_peer_id: *dead_peer_id,
+                    to_peer_id: dead_replica.transfer.map(|transfer| transfer.to),
+                    status,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::{NonZeroU32, NonZeroU64};
+
+    use segment::types::Distance;
+
+    use super::*;
+    use crate::collection_state::ShardInfo;
+    use crate::config::{CollectionConfig, CollectionParams};
+    use crate::operations::types::{VectorParams, VectorsConfig};
+    use crate::optimizers_builder::OptimizersConfig;
+
+    const CONFIG: ReplicaFailoverConfig = ReplicaFailoverConfig {
+        enabled: true,
+        grace_period_sec: 10,
+        max_transfers_per_peer: 1,
+        max_transfers: 2,
+    };
+
+    fn collection_state(shards: &[&[(PeerId, ReplicaState)]]) -> State {
+        let params = CollectionParams {
+            vectors: VectorsConfig::Single(VectorParams {
+                size: NonZeroU64::new(4).unwrap(),
+                distance: Distance::Dot,
+                hnsw_config: None,
+                quantization_config: None,
+                on_disk: None,
+                datatype: None,
+                multivector_config: None,
+            }),
+            shard_number: NonZeroU32::new(shards.len() as u32).unwrap(),
+            sharding_method: None,
+            replication_factor: NonZeroU32::new(2).unwrap(),
+            write_consistency_factor: NonZeroU32::new(1).unwrap(),
+            on_disk_payload: false,
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
+        };
+        let config = CollectionConfig {
+            params,
+            optimizer_config: OptimizersConfig {
+                deleted_threshold: 0.9,
+                vacuum_min_vector_number: 1000,
+                default_segment_number: 2,
+                max_segment_size: None,
+                memmap_threshold: None,
+                indexing_threshold: Some(50_000),
+                flush_interval_sec: 30,
+                max_optimization_threads: 2,
+                merge_buffer_size: None,
+            },
+            wal_config: Default::default(),
+            hnsw_config: Default::default(),
+            quantization_config: Default::default(),
+            strict_mode_config: Default::default(),
+        };
+        State {
+            config,
+            shards: shards
+                .iter()
+                .enumerate()
+                .map(|(shard_id, replicas)| {
+                    let replicas = replicas.iter().copied().collect();
+                    (shard_id as ShardId, ShardInfo { replicas })
+                })
+                .collect(),
+            transfers: Default::default(),
+            shards_key_mapping: Default::default(),
+            resharding: None,
+            shard_splits: vec![],
+        }
+    }
+
+    #[test]
+    fn test_replace_dead_replicas() {
+        use ReplicaState::{Active, Dead, Partial};
+
+        let mut state = collection_state(&[
+            &[(1, Active), (2, Dead)],
+            &[(2, Dead), (3, Active)],
+            &[(1, Active), (3, Active)],
+        ]);
+        let peers = [1, 2, 3, 4];
+        let mut failover = ReplicaFailoverState::default();
+        let start = Instant::now();
+
+        let mut load = ClusterLoad::new(peers);
+        load.add_collection(&state);
+        assert_eq!(
+            failover.plan(&state, &CONFIG, &mut load, start),
+            Default::default()
+        );
+        let recoveries = failover.recoveries(&state);
+        assert_eq!(recoveries.len(), 2);
+        assert!(recoveries
+            .iter()
+            .all(|recovery| recovery.status == ReplicaRecoveryStatus::Pending));
+
+        // After the grace period, both replicas are replaced by the only healthy peer without
+        // replicas of the shards, but one at a time
+        let now = start + Duration::from_secs(CONFIG.grace_period_sec);
+        let mut load = ClusterLoad::new(peers);
+        load.add_collection(&state);
+        let plan = failover.plan(&state, &CONFIG, &mut load, now);
+        let transfer = ShardTransfer {
+            shard_id: 0,
+            from: 1,
+            to: 4,
+            sync: true,
+            method: None,
+        };
+        assert_eq!(plan.transfers, vec![transfer.clone()]);
+        assert!(plan.removed_replicas.is_empty());
+        assert_eq!(
+            failover.recoveries(&state)[0].status,
+            ReplicaRecoveryStatus::Planned
+        );
+
+        // Proposal is not made again, while it is being applied
+        let mut load = ClusterLoad::new(peers);
+        load.add_collection(&state);
+        assert!(failover
+            .plan(&state, &CONFIG, &mut load, now)
+            .transfers
+            .is_empty());
+
+        // Transfer is started
+        state.transfers.insert(transfer.clone());
+        state
+            .shards
+            .get_mut(&0)
+            .unwrap()
+            .replicas
+            .insert(4, Partial);
+        assert_eq!(
+            failover.recoveries(&state)[0].status,
+            ReplicaRecoveryStatus::InProgress
+        );
+        let mut load = ClusterLoad::new(peers);
+        load.add_collection(&state);
+        assert!(failover
+            .plan(&state, &CONFIG, &mut load, now)
+            .transfers
+            .is_empty());
+
+        // Transfer is finished, dead replica is removed and the next one is replaced
+        state.transfers.clear();
+        state.shards.get_mut(&0).unwrap().replicas.insert(4, Active);
+        let mut load = ClusterLoad::new(peers);
+        load.add_collection(&state);
+        let plan = failover.plan(&state, &CONFIG, &mut load, now);
+        assert_eq!(plan.removed_replicas, vec![(0, 2)]);
+        assert_eq!(
+            plan.transfers,
+            vec![ShardTransfer {
+                shard_id: 1,
+                from: 3,
+                to: 4,
+                sync: true,
+                method: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_failover_transfer_limits() {
+        use ReplicaState::{Active, Dead};
+
+        let state = collection_state(&[
+            &[(1, Active), (2, Dead)],
+            &[(1, Active), (2, Dead)],
+            &[(3, Active), (2, Dead)],
+        ]);
+        let mut failover = ReplicaFailoverState::default();
+        let start = Instant::now();
+        let mut load = ClusterLoad::new([1, 2, 3, 4, 5]);
+        load.add_collection(&state);
+        failover.plan(&state, &CONFIG, &mut load, start);
+
+        let now = start + Duration::from_secs(CONFIG.grace_period_sec);
+        let mut load = ClusterLoad::new([1, 2, 3, 4, 5]);
+        load.add_collection(&state);
+        let plan = failover.plan(&state, &CONFIG, &mut load, now);
+
+        // Peer 1 can only send a single replica at a time, cluster limit allows two transfers
+        assert_eq!(plan.transfers.len(), 2);
+        assert_eq!(plan.transfers[0].shard_id, 0);
+        assert_eq!(plan.transfers[1].shard_id, 2);
+        let targets: HashSet<_> = plan.transfers.iter().map(|t| t.to).collect();
+        assert_eq!(targets, HashSet::from([4, 5]));
+        assert!(plan.transfers.iter().all(|t| t.to != 2));
+    }
+}
@@ -3,6 +3,7 @@ use std::cmp::max;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
 use std::future::Future;
+use std::iter;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -11,39 +12,50 @@ use std::time::Duration;
 use futures::future::{join, join_all};
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt};
-use itertools::Itertools;
 use rand::seq::SliceRandom;
 use schemars::JsonSchema;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PointIdType, ScoredPoint, SeqNumberType, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use serde::{Deserialize, Serialize};
 use tokio::runtime::Handle;
 use tokio::sync::{Mutex, RwLock};
 
-use super::local_shard::LocalShard;
+use super::local_shard::{LocalShard, WalRetentionGuard};
 use super::remote_shard::RemoteShard;
+use super::replica_update::ReplicaUpdate;
 use super::resolve::{Resolve, ResolveCondition};
 use super::{create_shard_dir, CollectionId};
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentId};
+use crate::collection_manager::optimizers::optimization_tasks::OptimizationTasks;
+use crate::common::resource_usage::ShardResourceUsage;
 use crate::config::CollectionConfig;
+use crate::hash_ring::HashRingRouter;
 use crate::operations::consistency_params::{ReadConsistency, ReadConsistencyType};
-use crate::operations::point_ops::WriteOrdering;
+use crate::operations::payload_ops::PayloadOps;
+use crate::operations::point_ops::{PointOperations, WriteOrdering};
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
-    Record, SearchRequestBatch, UpdateResult,
+    CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
+    CountResult, FacetRequest, FacetResponse, PointRequest, PointVersionConflict, Record,
+    SearchPlanRequest, ShardSearchPlan,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::vector_ops::VectorOperations;
+use crate::operations::{ClientOperationId, CollectionUpdateOperations};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
-use crate::shards::shard::Shard::{Dummy, ForwardProxy, Local};
+use crate::shards::shard::Shard::{Dummy, ForwardProxy, Local, SplitProxy};
 use crate::shards::shard::{PeerId, Shard, ShardId};
 use crate::shards::shard_config::ShardConfig;
 use crate::shards::shard_trait::ShardOperation;
-use crate::shards::telemetry::ReplicaSetTelemetry;
+use crate::shards::split_proxy_shard::SplitProxyShard;
+use crate::shards::telemetry::{ReplicaSetTelemetry, WalRecoveryReport};
+use crate::shards::transfer;
+use crate::shards::transfer::wal_delta::WalDeltaProgress;
 
 pub type ActivatePeer = Arc<dyn Fn(PeerId, ShardId) + Send + Sync>;
 pub type ChangePeerState = Arc<dyn Fn(PeerId, ShardId) + Send + Sync>;
@@ -55,6 +67,11 @@ const READ_REMOTE_REPLICAS: u32 = 2;
 
 const REPLICA_STATE_FILE: &str = "replica_state.json";
 
+/// Number of attempts to forward an update to the leader replica
+const FORWARD_UPDATE_ATTEMPTS: u32 = 3;
+/// Delay before retrying to forward an update, grows linearly with every attempt
+const FORWARD_UPDATE_RETRY_DELAY: Duration = Duration::from_millis(200);
+
 //    │    Collection Created
 //    │
 //    ▼
@@ -103,8 +120,9 @@ pub enum ReplicaState {
     Partial,
     // Collection is being created
     Initializing,
-    // A shard which receives data, but is not used for search
-    // Useful for backup shards
+    // A shard which receives data and serves reads, but never leads updates
+    // and is not counted toward the write consistency.
+    // Useful for backup shards and analytical read-only nodes
     Listener,
 }
 
@@ -189,6 +207,15 @@ impl ShardReplicaSet {
         self.local.read().await.is_some()
     }
 
+    /// Whether the local replica, if any, is loaded and has all its data
+    pub async fn is_local_ready(&self) -> bool {
+        match self.peer_state(&self.this_peer_id()) {
+            None => true,
+            Some(ReplicaState::Active | ReplicaState::Listener) => !self.is_dummy().await,
+            Some(ReplicaState::Dead | ReplicaState::Partial | ReplicaState::Initializing) => false,
+        }
+    }
+
     pub fn peers(&self) -> HashMap<PeerId, ReplicaState> {
         self.replica_state.read().peers()
     }
@@ -197,8 +224,15 @@ impl ShardReplicaSet {
         self.replica_state.read().this_peer_id
     }
 
+    /// Highest peer, which may lead updates. Listener replicas never do.
     pub fn highest_replica_peer_id(&self) -> Option<PeerId> {
-        self.replica_state.read().peers.keys().max().cloned()
+        self.replica_state
+            .read()
+            .peers
+            .iter()
+            .filter(|(_, state)| **state != ReplicaState::Listener)
+            .map(|(peer_id, _)| *peer_id)
+            .max()
     }
 
     pub fn highest_alive_replica_peer_id(&self) -> Option<PeerId> {
@@ -677,6 +711,20 @@ impl ShardReplicaSet {
         self.peer_state(peer_id) == Some(ReplicaState::Active) && !self.is_locally_disabled(peer_id)
     }
 
+    /// Check whether a peer is registered as `listener`.
+    pub fn peer_is_listener(&self, peer_id: &PeerId) -> bool {
+        self.peer_state(peer_id) == Some(ReplicaState::Listener)
+    }
+
+    /// Check whether a peer can serve reads, either as `active` or as `listener` replica.
+    pub fn peer_is_readable(&self, peer_id: &PeerId) -> bool {
+        let res = matches!(
+            self.peer_state(peer_id),
+            Some(ReplicaState::Active | ReplicaState::Listener),
+        );
+        res && !self.is_locally_disabled(peer_id)
+    }
+
     pub fn peer_is_active_or_pending(&self, peer_id: &PeerId) -> bool {
         let res = match self.peer_state(peer_id) {
             Some(ReplicaState::Active) => true,
@@ -694,8 +742,9 @@ impl ShardReplicaSet {
     }
 
     /// Execute read op. on replica set:
-    /// 1 - Prefer local replica
+    /// 1 - Prefer local replica, it may be a listener
     /// 2 - Otherwise uses `read_fan_out_ratio` to compute list of active remote shards.
+    ///     Remote listeners are only used, if there are no active remote shards.
     /// 3 - Fallbacks to all remaining shards if the optimisations fails.
     /// It does not report failing peer_ids to the consensus.
     pub async fn execute_read_operation<'a, F, Fut, Res>(
@@ -709,9 +758,9 @@ impl ShardReplicaSet {
         Fut: Future<Output = CollectionResult<Res>>,
     {
         let mut local_result = None;
-        // 1 - prefer the local shard if it is active
+        // 1 - prefer the local shard if it is readable
         if let Some(local) = local {
-            if self.peer_is_active(&self.this_peer_id()) {
+            if self.peer_is_readable(&self.this_peer_id()) {
                 let read_operation_res = read_operation(local.get()).await;
                 match &read_operation_res {
                     Ok(_) => return read_operation_res,
@@ -733,6 +782,13 @@ impl ShardReplicaSet {
             .filter(|rs| self.peer_is_active(&rs.peer_id))
             .collect();
 
+        if active_remote_shards.is_empty() {
+            active_remote_shards = remotes
+                .iter()
+                .filter(|rs| self.peer_is_readable(&rs.peer_id))
+                .collect();
+        }
+
         if active_remote_shards.is_empty() {
             if let Some(local_result) = local_result {
                 return local_result;
@@ -803,6 +859,34 @@ impl ShardReplicaSet {
         captured_error.expect("at this point `captured_error` must be defined by construction")
     }
 
+    /// Execute read operation on the leader of the updates with strong ordering
+    async fn execute_read_on_strong_leader<'a, F, Fut, Res>(
+        &self,
+        read_operation: F,
+        local: &'a Option<Shard>,
+        remotes: &'a [RemoteShard],
+    ) -> CollectionResult<Res>
+    where
+        F: FnOnce(&'a (dyn ShardOperation + Send + Sync)) -> Fut,
+        Fut: Future<Output = CollectionResult<Res>>,
+    {
+        let leader_peer = self.leader_peer_for_update(WriteOrdering::Strong);
+        if leader_peer == Some(self.this_peer_id()) {
+            if let Some(local) = local {
+                return read_operation(local.get()).await;
+            }
+        } else if let Some(remote) = remotes
+            .iter()
+            .find(|remote| Some(remote.peer_id) == leader_peer)
+        {
+            return read_operation(remote).await;
+        }
+        Err(CollectionError::service_error(format!(
+            "Cannot read from shard {}:{}, leader replica {leader_peer:?} is not available",
+            self.collection_id, self.shard_id,
+        )))
+    }
+
     pub async fn execute_and_resolve_read_operation<'a, F, Fut, Res>(
         &self,
         read_operation: F,
@@ -820,7 +904,7 @@ impl ShardReplicaSet {
 
         let active_local = local
             .as_ref()
-            .filter(|_| self.peer_is_active(&self.this_peer_id()));
+            .filter(|_| self.peer_is_readable(&self.this_peer_id()));
 
         let active_remotes_iter = remotes
             .iter()
@@ -950,6 +1034,44 @@ impl ShardReplicaSet {
         }
     }
 
+    pub(crate) async fn optimization_tasks(&self) -> Vec<Arc<OptimizationTasks>> {
+        let read_local = self.local.read().await;
+        read_local
+            .as_ref()
+            .map(Shard::optimization_tasks)
+            .unwrap_or_default()
+    }
+
+    pub(crate) async fn local_segments(&self) -> Vec<(SegmentId, LockedSegment)> {
+        let read_local = self.local.read().await;
+        match &*read_local {
+            Some(shard) => shard.locked_segments(),
+            None => Vec::new(),
+        }
+    }
+
+    pub(crate) async fn wal_recovery_report(&self) -> Option<WalRecoveryReport> {
+        let read_local = self.local.read().await;
+        read_local.as_ref().and_then(Shard::wal_recovery_report)
+    }
+
+    pub(crate) async fn resource_usage(&self) -> CollectionResult<Option<ShardResourceUsage>> {
+        let read_local = self.local.read().await;
+        match &*read_local {
+            Some(shard) => shard.resource_usage(self.shard_id),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) async fn shutdown(&self) -> CollectionResult<()> {
+        let read_local = self.local.read().await;
+        if let Some(shard) = &*read_local {
+            shard.shutdown().await
+        } else {
+            Ok(())
+        }
+    }
+
     pub(crate) async fn get_telemetry_data(&self) -> ReplicaSetTelemetry {
         let local_shard = self.local.read().await;
         let local = local_shard
@@ -969,6 +1091,21 @@ impl ShardReplicaSet {
         }
     }
 
+    /// Search plan of the local shard, `None` if this peer has no replica of the shard
+    pub(crate) async fn search_plan(
+        &self,
+        request: &SearchPlanRequest,
+    ) -> CollectionResult<Option<ShardSearchPlan>> {
+        let local_shard = self.local.read().await;
+        let Some(local_shard) = local_shard.as_ref() else {
+            return Ok(None);
+        };
+        Ok(Some(ShardSearchPlan {
+            shard_id: self.shard_id,
+            segments: local_shard.search_plan(request)?,
+        }))
+    }
+
     /// Returns if local shard was recovered from path
     pub async fn restore_local_replica_from(&self, replica_path: &Path) -> CollectionResult<bool> {
         if LocalShard::check_data(replica_path) {
@@ -1058,6 +1195,28 @@ impl ShardReplicaSet {
         Ok(())
     }
 
+    /// Create a snapshot of the shard, which has no local replica on this peer,
+    /// by downloading it from an active remote replica.
+    ///
+    /// Returns false if there is no active remote replica to download the snapshot from.
+    pub async fn download_snapshot(
+        &self,
+        temp_path: &Path,
+        target_path: &Path,
+    ) -> CollectionResult<bool> {
+        let remotes = self.remotes.read().await;
+        let Some(remote) = remotes
+            .iter()
+            .find(|remote| self.peer_is_active(&remote.peer_id))
+        else {
+            return Ok(false);
+        };
+
+        // Downloaded snapshot keeps the replica state of the remote peer, which has the local data
+        transfer::snapshot::download_snapshot(remote, temp_path, target_path).await?;
+        Ok(true)
+    }
+
     pub async fn proxify_local(&self, remote_shard: RemoteShard) -> CollectionResult<()> {
         let mut local_write = self.local.write().await;
 
@@ -1133,25 +1292,104 @@ impl ShardReplicaSet {
         Ok(())
     }
 
+    /// Wrap the local shard into a split proxy, which copies points of the split
+    /// into the local replica of the target shard.
+    ///
+    /// The local replica of the target shard is moved into the proxy.
+    pub async fn split_local(
+        &self,
+        target: &ShardReplicaSet,
+        ring: HashRingRouter,
+    ) -> CollectionResult<()> {
+        let mut local_write = self.local.write().await;
+        let mut target_write = target.local.write().await;
+
+        match (&*local_write, &*target_write) {
+            (Some(Local(_)), Some(Local(_))) => {
+                // Do nothing, we proceed further
+            }
+            (Some(SplitProxy(_)), None) => return Ok(()),
+            (local, target_local) => {
+                return Err(CollectionError::service_error(format!(
+                    "Cannot split local shard {} into shard {}, unexpected shard types - {} and {}",
+                    self.shard_id,
+                    target.shard_id,
+                    local.as_ref().map_or("none", Shard::variant_name),
+                    target_local.as_ref().map_or("none", Shard::variant_name),
+                )))
+            }
+        }
+
+        if let (Some(Local(local)), Some(Local(target_local))) =
+            (local_write.take(), target_write.take())
+        {
+            let proxy_shard = SplitProxyShard::new(local, target_local, target.shard_id, ring);
+            let _ = local_write.insert(SplitProxy(proxy_shard));
+        }
+
+        Ok(())
+    }
+
+    /// Un-split local shard.
+    ///
+    /// Local replica of the target shard is moved back into the target replica set,
+    /// with the given replica state.
+    pub async fn un_split_local(
+        &self,
+        target: &ShardReplicaSet,
+        state: Option<ReplicaState>,
+    ) -> CollectionResult<()> {
+        let mut local_write = self.local.write().await;
+
+        match &*local_write {
+            Some(SplitProxy(_)) => {
+                // Do nothing, we proceed further
+            }
+            Some(Local(_)) => return Ok(()),
+            Some(shard) => {
+                return Err(CollectionError::service_error(format!(
+                    "Cannot un-split local shard {} because it has unexpected type - {}",
+                    self.shard_id,
+                    shard.variant_name(),
+                )))
+            }
+            None => {
+                return Err(CollectionError::service_error(format!(
+                    "Cannot un-split local shard {} on peer {} because it is not active",
+                    self.shard_id,
+                    self.this_peer_id()
+                )));
+            }
+        };
+
+        if let Some(SplitProxy(proxy)) = local_write.take() {
+            let (local_shard, target_shard) = proxy.deconstruct();
+            let _ = local_write.insert(Local(local_shard));
+            target.set_local(target_shard, state).await?;
+        }
+
+        Ok(())
+    }
+
     /// Update local shard if any without forwarding to remote shards
-    pub async fn update_local(
+    pub async fn update_local<U: ReplicaUpdate>(
         &self,
-        operation: CollectionUpdateOperations,
+        operation: U,
         wait: bool,
-    ) -> CollectionResult<Option<UpdateResult>> {
+    ) -> CollectionResult<Option<U::Output>> {
         if let Some(local_shard) = &*self.local.read().await {
             match self.peer_state(&self.this_peer_id()) {
                 Some(ReplicaState::Active) => {
-                    Ok(Some(local_shard.get().update(operation, wait).await?))
+                    Ok(Some(operation.apply(local_shard.get(), wait).await?))
                 }
                 Some(ReplicaState::Partial) => {
-                    Ok(Some(local_shard.get().update(operation, wait).await?))
+                    Ok(Some(operation.apply(local_shard.get(), wait).await?))
                 }
                 Some(ReplicaState::Initializing) => {
-                    Ok(Some(local_shard.get().update(operation, wait).await?))
+                    Ok(Some(operation.apply(local_shard.get(), wait).await?))
                 }
                 Some(ReplicaState::Listener) => {
-                    Ok(Some(local_shard.get().update(operation, false).await?))
+                    Ok(Some(operation.apply(local_shard.get(), false).await?))
                 }
                 Some(ReplicaState::Dead) | None => Ok(None),
             }
@@ -1179,6 +1417,167 @@ impl ShardReplicaSet {
         }
     }
 
+    /// Custom operation for transferring operations from WAL of local shard to the remote one
+    pub async fn transfer_wal_delta_batch(
+        &self,
+        from_op_num: SeqNumberType,
+        batch_size: usize,
+    ) -> CollectionResult<WalDeltaProgress> {
+        let read_local = self.local.read().await;
+        if let Some(ForwardProxy(proxy)) = &*read_local {
+            proxy
+                .transfer_wal_delta_batch(from_op_num, batch_size)
+                .await
+        } else {
+            Err(CollectionError::service_error(format!(
+                "Cannot transfer WAL delta from shard {} because it is not proxified",
+                self.shard_id
+            )))
+        }
+    }
+
+    /// Find the first operation in WAL of the proxified local shard, which is required to recover
+    /// the replica with the given recovery point, see [`LocalShard::find_recovery_point`]
+    pub async fn find_wal_recovery_point(
+        &self,
+        client_op_ids: &[ClientOperationId],
+    ) -> CollectionResult<Option<SeqNumberType>> {
+        let read_local = self.local.read().await;
+        if let Some(ForwardProxy(proxy)) = &*read_local {
+            Ok(proxy.wrapped_shard.find_recovery_point(client_op_ids))
+        } else {
+            Err(CollectionError::service_error(format!(
+                "Cannot find recovery point in WAL of shard {} because it is not proxified",
+                self.shard_id
+            )))
+        }
+    }
+
+    /// Keep operations of the proxified local shard in WAL, until they are transferred
+    pub async fn retain_wal_from(
+        &self,
+        op_num: SeqNumberType,
+    ) -> CollectionResult<WalRetentionGuard> {
+        let read_local = self.local.read().await;
+        if let Some(ForwardProxy(proxy)) = &*read_local {
+            Ok(proxy.wrapped_shard.retain_wal_from(op_num))
+        } else {
+            Err(CollectionError::service_error(format!(
+                "Cannot retain WAL of shard {} because it is not proxified",
+                self.shard_id
+            )))
+        }
+    }
+
+    /// Custom operation for copying points of the split into the new shard during resharding
+    pub async fn migrate_batch(
+        &self,
+        offset: Option<PointIdType>,
+        batch_size: usize,
+    ) -> CollectionResult<(Option<PointIdType>, usize)> {
+        let read_local = self.local.read().await;
+        if let Some(SplitProxy(proxy)) = &*read_local {
+            proxy
+                .migrate_batch(offset, batch_size, &self.search_runtime)
+                .await
+        } else {
+            Err(CollectionError::service_error(format!(
+                "Cannot migrate batch from shard {} because it is not split",
+                self.shard_id
+            )))
+        }
+    }
+
+    /// Custom operation for copying indexes into the new shard during resharding
+    pub async fn migrate_indexes(&self) -> CollectionResult<()> {
+        let read_local = self.local.read().await;
+        if let Some(SplitProxy(proxy)) = &*read_local {
+            proxy.migrate_indexes().await
+        } else {
+            Err(CollectionError::service_error(format!(
+                "Cannot migrate indexes from shard {} because it is not split",
+                self.shard_id
+            )))
+        }
+    }
+
+    /// Delete points of the batch from the local shard, which should not be retained.
+    /// Returns an offset of the next batch.
+    pub async fn retain_local_batch(
+        &self,
+        offset: Option<PointIdType>,
+        batch_size: usize,
+        retain: impl Fn(&PointIdType) -> bool,
+    ) -> CollectionResult<Option<PointIdType>> {
+        debug_assert!(batch_size > 0);
+        let limit = batch_size + 1;
+        let mut batch = {
+            let read_local = self.local.read().await;
+            let Some(local) = &*read_local else {
+                return Ok(None);
+            };
+            local
+                .get()
+                .scroll_by(
+                    offset,
+                    limit,
+                    &WithPayloadInterface::Bool(false),
+                    &false.into(),
+                    None,
+                    None,
+                    &self.search_runtime,
+                )
+                .await?
+        };
+        let next_page_offset = if batch.len() < limit {
+            // This was the last page
+            None
+        } else {
+            // remove extra point, it would be a first point of the next page
+            Some(batch.pop().unwrap().id)
+        };
+
+        let ids: Vec<_> = batch
+            .into_iter()
+            .map(|point| point.id)
+            .filter(|point_id| !retain(point_id))
+            .collect();
+        if !ids.is_empty() {
+            self.update_local(
+                CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints { ids }),
+                true,
+            )
+            .await?;
+        }
+        Ok(next_page_offset)
+    }
+
+    /// The last operation applied to the local shard, if there is one
+    pub async fn local_last_applied_op_num(&self) -> Option<SeqNumberType> {
+        match &*self.local.read().await {
+            Some(Local(local)) => Some(local.last_applied_op_num()),
+            Some(ForwardProxy(proxy)) => Some(proxy.wrapped_shard.last_applied_op_num()),
+            Some(SplitProxy(proxy)) => Some(proxy.wrapped_shard.last_applied_op_num()),
+            Some(Shard::Proxy(proxy)) => Some(proxy.wrapped_shard.last_applied_op_num()),
+            Some(Dummy(_)) | None => None,
+        }
+    }
+
+    /// Client ids of the last operations of the local shard, if there is one,
+    /// see [`LocalShard::recovery_point`]
+    pub async fn local_recovery_point(
+        &self,
+        count: usize,
+    ) -> CollectionResult<Vec<ClientOperationId>> {
+        match &*self.local.read().await {
+            Some(Local(local)) => local.recovery_point(count),
+            Some(ForwardProxy(proxy)) => proxy.wrapped_shard.recovery_point(count),
+            Some(SplitProxy(proxy)) => proxy.wrapped_shard.recovery_point(count),
+            Some(Shard::Proxy(proxy)) => proxy.wrapped_shard.recovery_point(count),
+            Some(Dummy(_)) | None => Ok(Vec::new()),
+        }
+    }
+
     /// Custom operation for transferring indexes from one shard to another during transfer
     pub async fn transfer_indexes(&self) -> CollectionResult<()> {
         let read_local = self.local.read().await;
@@ -1208,15 +1607,27 @@ impl ShardReplicaSet {
                 err
             );
 
+            // Rejected conditional update is not a failure of the replica
+            if matches!(err, CollectionError::Conflict { .. }) {
+                continue;
+            }
+
             let Some(&peer_state) = state.get_peer_state(peer_id) else {
                 continue;
             };
 
-            if peer_state != ReplicaState::Active && peer_state != ReplicaState::Initializing {
+            if peer_state != ReplicaState::Active
+                && peer_state != ReplicaState::Initializing
+                && peer_state != ReplicaState::Listener
+            {
                 continue;
             }
 
-            if err.is_transient() || peer_state == ReplicaState::Initializing {
+            // Listeners are not counted toward the write consistency, so updates don't wait for
+            // their deactivation. They are recovered, once they are marked as dead.
+            if peer_state != ReplicaState::Listener
+                && (err.is_transient() || peer_state == ReplicaState::Initializing)
+            {
                 // If the error is transient, we should not deactivate the peer
                 // before allowing other operations to continue.
                 // Otherwise, the failed node can become responsive again, before
@@ -1268,51 +1679,258 @@ impl ShardReplicaSet {
         Ok(())
     }
 
-    pub async fn update_with_consistency(
+    /// Replace the delete by filter with a delete of the ids of the points, which the filter
+    /// selects in the local replica.
+    ///
+    /// Called on the leader of the update, under the write ordering lock, right before the update
+    /// is applied, so no other ordered update changes the points in between.
+    /// All replicas then delete the same points, even if their contents differ transiently,
+    /// and the ids are what is written to WAL and replicated.
+    /// Returns the ids, if the operation is a delete by filter.
+    pub async fn resolve_delete_filter(
+        &self,
+        operation: &mut CollectionUpdateOperations,
+    ) -> CollectionResult<Option<Vec<PointIdType>>> {
+        let CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(
+            filter,
+        )) = &*operation
+        else {
+            return Ok(None);
+        };
+
+        let ids = {
+            let local = self.local.read().await;
+            let Some(local) = local.as_ref() else {
+                return Err(CollectionError::service_error(format!(
+                    "Cannot resolve delete by filter of shard {}:{}, there is no local replica",
+                    self.collection_id, self.shard_id,
+                )));
+            };
+            local.get().select_ids(filter, &self.search_runtime).await?
+        };
+
+        *operation = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+            ids: ids.clone(),
+        });
+        Ok(Some(ids))
+    }
+
+    /// Check the versions of the points of a conditional update against the local replica
+    /// and replace it with an unconditional update of the same points.
+    ///
+    /// Called on the leader of the update with strong ordering, under the write ordering lock,
+    /// so the versions are those returned by reads `with_version`, and no other ordered update
+    /// changes the points until the update is applied.
+    /// All replicas then apply the same unconditional update, which is also written to WAL.
+    /// If any of the points has another version, the whole update is rejected.
+    pub async fn resolve_conditional_update(
+        &self,
+        operation: &mut CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        if !operation.is_conditional() {
+            return Ok(());
+        }
+
+        let local = self.local.read().await;
+        let Some(local) = local.as_ref() else {
+            return Err(CollectionError::service_error(format!(
+                "Cannot check point versions of conditional update of shard {}:{}, there is no local replica",
+                self.collection_id, self.shard_id,
+            )));
+        };
+        let shard = local.get();
+
+        let expected_versions = match operation {
+            CollectionUpdateOperations::PayloadOperation(
+                PayloadOps::SetPayload(set_payload) | PayloadOps::OverwritePayload(set_payload),
+            ) => {
+                let if_version = set_payload.if_version.take();
+                self.conditional_points(shard, &mut set_payload.points, &mut set_payload.filter)
+                    .await?
+                    .into_iter()
+                    .zip(iter::repeat(if_version))
+                    .collect()
+            }
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(
+                delete_payload,
+            )) => {
+                let if_version = delete_payload.if_version.take();
+                self.conditional_points(
+                    shard,
+                    &mut delete_payload.points,
+                    &mut delete_payload.filter,
+                )
+                .await?
+                .into_iter()
+                .zip(iter::repeat(if_version))
+                .collect()
+            }
+            CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(
+                update_vectors,
+            )) => update_vectors
+                .points
+                .iter_mut()
+                .map(|point| (point.id, point.if_version.take()))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Self::check_point_versions(shard, expected_versions).await
+    }
+
+    /// Points of a conditional payload update.
+    /// Points selected by the filter are listed explicitly, so every replica updates the same points.
+    async fn conditional_points(
         &self,
-        operation: CollectionUpdateOperations,
+        shard: &(dyn ShardOperation + Send + Sync),
+        points: &mut Option<Vec<PointIdType>>,
+        filter: &mut Option<Filter>,
+    ) -> CollectionResult<Vec<PointIdType>> {
+        if let Some(filter) = filter.take() {
+            if points.is_none() {
+                *points = Some(shard.select_ids(&filter, &self.search_runtime).await?);
+            }
+        }
+        Ok(points.clone().unwrap_or_default())
+    }
+
+    /// Reject the update as a conflict, if any of the existing points has another version,
+    /// than expected. Missing points are not reported here, they fail the update itself.
+    async fn check_point_versions(
+        shard: &(dyn ShardOperation + Send + Sync),
+        expected_versions: Vec<(PointIdType, Option<SeqNumberType>)>,
+    ) -> CollectionResult<()> {
+        let expected_versions: Vec<_> = expected_versions
+            .into_iter()
+            .filter_map(|(id, version)| Some((id, version?)))
+            .collect();
+        if expected_versions.is_empty() {
+            return Ok(());
+        }
+
+        let request = Arc::new(PointRequest {
+            ids: expected_versions.iter().map(|(id, _)| *id).collect(),
+            with_payload: Some(WithPayloadInterface::Bool(false)),
+            with_vector: false.into(),
+            with_version: true,
+        });
+        let current_versions: HashMap<_, _> = shard
+            .retrieve(request, &WithPayload::from(false), &false.into())
+            .await?
+            .into_iter()
+            .filter_map(|record| Some((record.id, record.version?)))
+            .collect();
+
+        let conflicts: Vec<_> = expected_versions
+            .into_iter()
+            .filter_map(|(id, expected_version)| {
+                let current_version = *current_versions.get(&id)?;
+                (current_version != expected_version).then_some(PointVersionConflict {
+                    id,
+                    expected_version,
+                    current_version,
+                })
+            })
+            .collect();
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(CollectionError::version_conflict(&conflicts))
+        }
+    }
+
+    pub async fn update_with_consistency<U: ReplicaUpdate>(
+        &self,
+        operation: U,
         wait: bool,
         ordering: WriteOrdering,
-    ) -> CollectionResult<UpdateResult> {
-        match self.leader_peer_for_update(ordering) {
-            None => Err(CollectionError::service_error(format!(
-                "Cannot update shard {}:{} with {ordering:?} ordering because no leader could be selected",
-                self.collection_id, self.shard_id
-            ))),
-            Some(leader_peer) => {
-                // If we are the leader, run the update from this replica set
-                if leader_peer == self.this_peer_id() {
-                    // lock updates if ordering is medium or strong
-                    let _guard = match ordering {
-                        WriteOrdering::Weak => None, // no locking required
-                        WriteOrdering::Medium | WriteOrdering::Strong => Some(self.write_ordering_lock.lock().await), // one request at a time
-                    };
-                    self.update(operation, wait).await
-                } else {
-                    // forward the update to the designated leader
-                    self.forward_update(leader_peer, operation, wait, ordering)
-                        .await
-                        .map_err(|err| {
-                            if err.is_transient() {
-                                // Deactivate the peer if forwarding failed with transient error
-                                self.locally_disabled_peers.write().insert(leader_peer);
-                                self.notify_peer_failure(leader_peer);
-                                // return service error
-                                CollectionError::service_error(format!(
-                                    "Failed to apply update with {ordering:?} ordering via leader peer {leader_peer}: {err}"
-                                ))
-                            } else {
-                                err
-                            }
-                        })
-                }
+    ) -> CollectionResult<U::Output> {
+        let ordering = match ordering {
+            // versions of the points are checked on the leader with strong ordering,
+            // see `resolve_conditional_update`
+            _ if operation.is_conditional() => WriteOrdering::Strong,
+            // filters are resolved on the leader, which always has a local replica,
+            // see `resolve_delete_filter`
+            WriteOrdering::Weak if operation.has_delete_by_filter() => WriteOrdering::Medium,
+            ordering => ordering,
+        };
+        // strong ordering is acknowledged only once the leader applied the update
+        let wait = wait || matches!(ordering, WriteOrdering::Strong);
+
+        self.update_with_leader(operation, wait, ordering).await
+    }
+
+    /// Apply the update on the leader replica for the `ordering`, forwarding it if needed
+    async fn update_with_leader<U: ReplicaUpdate>(
+        &self,
+        mut operation: U,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<U::Output> {
+        let mut attempt = 1;
+        loop {
+            let Some(leader_peer) = self.leader_peer_for_update(ordering) else {
+                return Err(CollectionError::service_error(format!(
+                    "Cannot update shard {}:{} with {ordering:?} ordering because no leader could be selected",
+                    self.collection_id, self.shard_id
+                )));
+            };
+
+            // If we are the leader, run the update from this replica set
+            if leader_peer == self.this_peer_id() {
+                // lock updates if ordering is medium or strong
+                let _guard = match ordering {
+                    WriteOrdering::Weak => None, // no locking required
+                    // one request at a time
+                    WriteOrdering::Medium | WriteOrdering::Strong => {
+                        Some(self.write_ordering_lock.lock().await)
+                    }
+                };
+                operation.resolve_conditions(self).await?;
+                let deleted_points = operation.resolve_delete_filters(self).await?;
+                let mut output = self.update(operation, wait).await?;
+                U::report_deleted_points(&mut output, deleted_points);
+                return Ok(output);
+            }
+
+            // forward the update to the designated leader
+            let err = match self
+                .forward_update(leader_peer, operation.clone(), wait, ordering)
+                .await
+            {
+                Ok(result) => return Ok(result),
+                Err(err) if !err.is_transient() => return Err(err),
+                Err(err) => err,
+            };
+
+            if attempt < FORWARD_UPDATE_ATTEMPTS {
+                log::warn!(
+                    "Failed to forward update of shard {}:{} to leader peer {leader_peer} (attempt {attempt}), retrying: {err}",
+                    self.collection_id,
+                    self.shard_id,
+                );
+                tokio::time::sleep(FORWARD_UPDATE_RETRY_DELAY * attempt).await;
+                attempt += 1;
+                continue;
             }
+
+            // Deactivate the peer if forwarding failed with transient error
+            self.locally_disabled_peers.write().insert(leader_peer);
+            self.notify_peer_failure(leader_peer);
+            // return service error
+            return Err(CollectionError::service_error(format!(
+                "Failed to apply update with {ordering:?} ordering via leader peer {leader_peer} after {attempt} attempts: {err}"
+            )));
         }
     }
 
     /// Designated a leader replica for the update based on the WriteOrdering
     pub fn leader_peer_for_update(&self, ordering: WriteOrdering) -> Option<PeerId> {
         match ordering {
+            // listener replicas don't lead updates, forward to the highest alive replica instead
+            WriteOrdering::Weak if self.peer_is_listener(&self.this_peer_id()) => {
+                self.highest_alive_replica_peer_id()
+            }
             WriteOrdering::Weak => Some(self.this_peer_id()), // no requirement for consistency
             WriteOrdering::Medium => self.highest_alive_replica_peer_id(), // consistency with highest alive replica
             WriteOrdering::Strong => self.highest_replica_peer_id(), // consistency with highest replica
@@ -1320,22 +1938,18 @@ impl ShardReplicaSet {
     }
 
     /// Forward update to the leader replica
-    pub async fn forward_update(
+    pub async fn forward_update<U: ReplicaUpdate>(
         &self,
         leader_peer: PeerId,
-        operation: CollectionUpdateOperations,
+        operation: U,
         wait: bool,
         ordering: WriteOrdering,
-    ) -> CollectionResult<UpdateResult> {
+    ) -> CollectionResult<U::Output> {
         let remotes_guard = self.remotes.read().await;
         let remote_leader = remotes_guard.iter().find(|r| r.peer_id == leader_peer);
 
         match remote_leader {
-            Some(remote_leader) => {
-                remote_leader
-                    .forward_update(operation, wait, ordering)
-                    .await
-            }
+            Some(remote_leader) => operation.forward(remote_leader, wait, ordering).await,
             None => Err(CollectionError::service_error(format!(
                 "Cannot forward update to shard {} because was removed from the replica set",
                 self.shard_id
@@ -1343,11 +1957,11 @@ impl ShardReplicaSet {
         }
     }
 
-    pub async fn update(
+    pub async fn update<U: ReplicaUpdate>(
         &self,
-        operation: CollectionUpdateOperations,
+        operation: U,
         wait: bool,
-    ) -> CollectionResult<UpdateResult> {
+    ) -> CollectionResult<U::Output> {
         let all_res: Vec<Result<_, _>> = {
             let local = self.local.read().await;
             let remotes = self.remotes.read().await;
@@ -1370,42 +1984,40 @@ impl ShardReplicaSet {
                 )));
             }
 
+            // listeners are not counted toward the write consistency, so they are not waited for
             let mut remote_futures = Vec::new();
             for remote in active_remote_shards {
                 let op = operation.clone();
+                let is_listener = self.peer_is_listener(&remote.peer_id);
                 remote_futures.push(async move {
-                    remote
-                        .update(op, wait)
+                    let res = op
+                        .apply(remote, wait && !is_listener)
                         .await
-                        .map_err(|err| (remote.peer_id, err))
+                        .map_err(|err| (remote.peer_id, err));
+                    (is_listener, res)
                 });
             }
 
             match local.deref() {
                 Some(local) if self.peer_is_active_or_pending(&this_peer_id) => {
-                    let local_wait =
-                        if self.peer_state(&this_peer_id) == Some(ReplicaState::Listener) {
-                            false
-                        } else {
-                            wait
-                        };
+                    let is_listener = self.peer_is_listener(&this_peer_id);
 
                     let local_update = async move {
-                        local
-                            .get()
-                            .update(operation.clone(), local_wait)
+                        let res = operation
+                            .apply(local.get(), wait && !is_listener)
                             .await
                             .map_err(|err| {
                                 let peer_id = err.remote_peer_id().unwrap_or(this_peer_id);
 
                                 (peer_id, err)
-                            })
+                            });
+                        (is_listener, res)
                     };
                     let remote_updates = join_all(remote_futures);
 
                     // run local and remote shards read concurrently
                     let (mut remote_res, local_res): (
-                        Vec<Result<UpdateResult, (PeerId, CollectionError)>>,
+                        Vec<(bool, Result<U::Output, (PeerId, CollectionError)>)>,
                         _,
                     ) = join(remote_updates, local_update).await;
                     // return both remote and local results
@@ -1416,9 +2028,26 @@ impl ShardReplicaSet {
             }
         };
 
-        let total_results = all_res.len();
-
-        let (successes, failures): (Vec<_>, Vec<_>) = all_res.into_iter().partition_result();
+        // Results of listeners are only counted, if there are no other replicas
+        let count_listeners = all_res.iter().all(|(is_listener, _)| *is_listener);
+        let total_results = all_res
+            .iter()
+            .filter(|(is_listener, _)| count_listeners || !is_listener)
+            .count();
+
+        // Counted successes go first, so that one of them is returned
+        let mut successes = Vec::new();
+        let mut listener_successes = Vec::new();
+        let mut failures = Vec::new();
+        for (is_listener, res) in all_res {
+            match res {
+                Ok(output) if count_listeners || !is_listener => successes.push(output),
+                Ok(output) => listener_successes.push(output),
+                Err(failure) => failures.push(failure),
+            }
+        }
+        let counted_successes = successes.len();
+        successes.extend(listener_successes);
 
         // Notify consensus about failures if:
         // 1. There is at least one success, otherwise it might be a problem of sending node
@@ -1470,7 +2099,7 @@ impl ShardReplicaSet {
                 .write_consistency_factor
                 .get() as usize;
             let minimal_success_count = write_consistency_factor.min(total_results);
-            if successes.len() < minimal_success_count {
+            if counted_successes < minimal_success_count {
                 // completely failed - report error to user
                 let (_peer_id, err) = failures.into_iter().next().expect("failures is not empty");
                 return Err(err);
@@ -1492,6 +2121,7 @@ impl ShardReplicaSet {
         with_payload_interface: &WithPayloadInterface,
         with_vector: &WithVector,
         filter: Option<&Filter>,
+        order_by: Option<&OrderBy>,
         read_consistency: Option<ReadConsistency>,
     ) -> CollectionResult<Vec<Record>> {
         let local = self.local.read().await;
@@ -1505,6 +2135,7 @@ impl ShardReplicaSet {
                     with_payload_interface,
                     with_vector,
                     filter,
+                    order_by,
                     &self.search_runtime,
                 )
             },
@@ -1523,16 +2154,17 @@ impl ShardReplicaSet {
             .await
     }
 
-    pub async fn search(
+    pub async fn core_search(
         &self,
-        request: Arc<SearchRequestBatch>,
+        request: Arc<CoreSearchRequestBatch>,
         read_consistency: Option<ReadConsistency>,
+        timeout: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
         let local = self.local.read().await;
         let remotes = self.remotes.read().await;
 
         self.execute_and_resolve_read_operation(
-            |shard| shard.search(request.clone(), &self.search_runtime),
+            |shard| shard.core_search(request.clone(), &self.search_runtime, timeout),
             &local,
             &remotes,
             read_consistency.unwrap_or_default(),
@@ -1559,6 +2191,14 @@ impl ShardReplicaSet {
             .await
     }
 
+    pub async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<FacetResponse> {
+        let local = self.local.read().await;
+        let remotes = self.remotes.read().await;
+
+        self.execute_read_operation(|shard| shard.facet(request.clone()), &local, &remotes)
+            .await
+    }
+
     pub async fn retrieve(
         &self,
         request: Arc<PointRequest>,
@@ -1569,6 +2209,18 @@ impl ShardReplicaSet {
         let local = self.local.read().await;
         let remotes = self.remotes.read().await;
 
+        // Versions of the points differ between replicas,
+        // they are read from the replica, which checks conditional updates
+        if request.with_version {
+            return self
+                .execute_read_on_strong_leader(
+                    |shard| shard.retrieve(request.clone(), with_payload, with_vector),
+                    &local,
+                    &remotes,
+                )
+                .await;
+        }
+
         self.execute_and_resolve_read_operation(
             |shard| shard.retrieve(request.clone(), with_payload, with_vector),
             &local,
@@ -1583,11 +2235,15 @@ impl ShardReplicaSet {
 mod tests {
     use std::num::{NonZeroU32, NonZeroU64};
 
-    use segment::types::Distance;
+    use itertools::Itertools;
+    use segment::types::{Condition, Distance, FieldCondition, Match, Payload};
+    use serde_json::json;
     use tempfile::{Builder, TempDir};
 
     use super::*;
     use crate::config::*;
+    use crate::operations::payload_ops::SetPayload;
+    use crate::operations::point_ops::{Batch, PointOperations};
     use crate::operations::types::{VectorParams, VectorsConfig};
     use crate::optimizers_builder::OptimizersConfig;
 
@@ -1600,6 +2256,7 @@ mod tests {
         indexing_threshold: Some(50_000),
         flush_interval_sec: 30,
         max_optimization_threads: 2,
+        merge_buffer_size: None,
     };
 
     pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -1607,12 +2264,28 @@ mod tests {
     }
 
     async fn new_shard_replica_set(collection_dir: &TempDir) -> ShardReplicaSet {
+        build_shard_replica_set(collection_dir, false, HashSet::from([2, 3, 4, 5])).await
+    }
+
+    /// Replica set with only the local replica, which is active
+    async fn new_local_shard_replica_set(collection_dir: &TempDir) -> ShardReplicaSet {
+        let rs = build_shard_replica_set(collection_dir, true, HashSet::new()).await;
+        rs.set_replica_state(&1, ReplicaState::Active).unwrap();
+        rs
+    }
+
+    async fn build_shard_replica_set(
+        collection_dir: &TempDir,
+        local: bool,
+        remotes: HashSet<PeerId>,
+    ) -> ShardReplicaSet {
         let update_runtime = Handle::current();
         let search_runtime = Handle::current();
 
         let wal_config = WalConfig {
             wal_capacity_mb: 1,
             wal_segments_ahead: 0,
+            ..Default::default()
         };
 
         let collection_params = CollectionParams {
@@ -1622,11 +2295,18 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                datatype: None,
+                multivector_config: None,
             }),
             shard_number: NonZeroU32::new(4).unwrap(),
+            sharding_method: None,
             replication_factor: NonZeroU32::new(3).unwrap(),
             write_consistency_factor: NonZeroU32::new(2).unwrap(),
             on_disk_payload: false,
+            tenant_key: None,
+            sparse_vectors: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
         };
 
         let config = CollectionConfig {
@@ -1635,15 +2315,15 @@ mod tests {
             wal_config,
             hnsw_config: Default::default(),
             quantization_config: None,
+            strict_mode_config: None,
         };
 
         let shared_config = Arc::new(RwLock::new(config.clone()));
-        let remotes = HashSet::from([2, 3, 4, 5]);
         ShardReplicaSet::build(
             1,
             "test_collection".to_string(),
             1,
-            false,
+            local,
             remotes,
             dummy_on_replica_failure(),
             collection_dir.path(),
@@ -1674,4 +2354,284 @@ mod tests {
         assert_eq!(rs.highest_replica_peer_id(), Some(5));
         assert_eq!(rs.highest_alive_replica_peer_id(), Some(4));
     }
+
+    #[tokio::test]
+    async fn test_leader_peer_for_update() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+        let rs = new_shard_replica_set(&collection_dir).await;
+
+        for peer_id in 1..=4 {
+            rs.set_replica_state(&peer_id, ReplicaState::Active)
+                .unwrap();
+        }
+        rs.set_replica_state(&5, ReplicaState::Partial).unwrap();
+
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Weak), Some(1));
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Medium), Some(4));
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Strong), Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_listeners_never_lead_updates() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+        let rs = new_shard_replica_set(&collection_dir).await;
+
+        for peer_id in 1..=3 {
+            rs.set_replica_state(&peer_id, ReplicaState::Active)
+                .unwrap();
+        }
+        rs.set_replica_state(&4, ReplicaState::Listener).unwrap();
+        rs.set_replica_state(&5, ReplicaState::Listener).unwrap();
+
+        assert!(rs.peer_is_readable(&4));
+        assert!(!rs.peer_is_active(&4));
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Weak), Some(1));
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Medium), Some(3));
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Strong), Some(3));
+
+        // Weak updates, received by a listener, are forwarded
+        rs.set_replica_state(&1, ReplicaState::Listener).unwrap();
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Weak), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_update_with_unreachable_leader() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+        let rs = new_shard_replica_set(&collection_dir).await;
+
+        for peer_id in 1..=5 {
+            rs.set_replica_state(&peer_id, ReplicaState::Active)
+                .unwrap();
+        }
+
+        // Remote peers have no known address, so forwarding to them fails
+        let operation = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+            ids: vec![1_u64.into()],
+        });
+        let err = rs
+            .update_with_consistency(operation, false, WriteOrdering::Medium)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(&format!(
+                "via leader peer 5 after {FORWARD_UPDATE_ATTEMPTS} attempts"
+            )),
+            "{err}",
+        );
+
+        // The unreachable leader is not selected for the following updates
+        assert!(rs.is_locally_disabled(&5));
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Medium), Some(4));
+        assert_eq!(rs.leader_peer_for_update(WriteOrdering::Strong), Some(5));
+    }
+
+    /// Upsert points into the local replica with the `group` payload
+    async fn upsert_group(rs: &ShardReplicaSet, ids: &[u64], group: &str) {
+        let operation = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: ids.iter().map(|&id| id.into()).collect(),
+                vectors: ids
+                    .iter()
+                    .map(|&id| vec![id as f32, 0.0, 0.0, 1.0])
+                    .collect_vec()
+                    .into(),
+                payloads: Some(
+                    ids.iter()
+                        .map(|_| Some(Payload::from(json!({ "group": group }))))
+                        .collect(),
+                ),
+            }
+            .into(),
+        );
+        rs.update_local(operation, true).await.unwrap();
+    }
+
+    async fn point_ids(rs: &ShardReplicaSet) -> Vec<PointIdType> {
+        rs.scroll_by(
+            None,
+            1000,
+            &WithPayloadInterface::Bool(false),
+            &WithVector::Bool(false),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|point| point.id)
+        .collect()
+    }
+
+    async fn group_ids(rs: &ShardReplicaSet, group: &str) -> Vec<PointIdType> {
+        rs.scroll_by(
+            None,
+            1000,
+            &WithPayloadInterface::Bool(false),
+            &WithVector::Bool(false),
+            Some(&group_filter(group)),
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|point| point.id)
+        .collect()
+    }
+
+    async fn point_versions(
+        rs: &ShardReplicaSet,
+        ids: &[u64],
+        with_version: bool,
+    ) -> Vec<Option<SeqNumberType>> {
+        let request = PointRequest {
+            ids: ids.iter().map(|&id| id.into()).collect(),
+            with_payload: None,
+            with_vector: false.into(),
+            with_version,
+        };
+        rs.retrieve(
+            Arc::new(request),
+            &WithPayload::from(false),
+            &false.into(),
+            None,
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|record| record.version)
+        .collect()
+    }
+
+    fn group_filter(group: &str) -> Filter {
+        Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "group",
+            Match::from(group.to_string()),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_listener_replica_serves_reads() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+        let rs = new_local_shard_replica_set(&collection_dir).await;
+        upsert_group(&rs, &[1, 2, 3], "a").await;
+        rs.set_replica_state(&1, ReplicaState::Listener).unwrap();
+
+        let info = rs.info().await.unwrap();
+        assert_eq!(info.points_count, 3);
+        assert_eq!(point_ids(&rs).await, vec![1.into(), 2.into(), 3.into()]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_filter_reports_deleted_points() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+        let rs = new_local_shard_replica_set(&collection_dir).await;
+
+        upsert_group(&rs, &[1, 2, 3, 4], "a").await;
+        upsert_group(&rs, &[5, 6], "b").await;
+
+        let operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::DeletePointsByFilter(group_filter("a")),
+        );
+        let result = rs
+            .update_with_consistency(operation, true, WriteOrdering::Weak)
+            .await
+            .unwrap();
+        assert_eq!(result.deleted_count, Some(4));
+        assert_eq!(
+            result.deleted_ids,
+            Some(vec![1.into(), 2.into(), 3.into(), 4.into()]),
+        );
+        assert_eq!(point_ids(&rs).await, vec![5.into(), 6.into()]);
+
+        // Nothing matches anymore
+        let operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::DeletePointsByFilter(group_filter("a")),
+        );
+        let result = rs
+            .update_with_consistency(operation, true, WriteOrdering::Weak)
+            .await
+            .unwrap();
+        assert_eq!(result.deleted_count, Some(0));
+        assert_eq!(point_ids(&rs).await, vec![5.into(), 6.into()]);
+
+        // Deletes by ids don't report the deleted points
+        let operation = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+            ids: vec![5.into()],
+        });
+        let result = rs
+            .update_with_consistency(operation, true, WriteOrdering::Weak)
+            .await
+            .unwrap();
+        assert_eq!(result.deleted_count, None);
+        assert_eq!(result.deleted_ids, None);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_update_is_all_or_nothing() {
+        let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+        let rs = new_local_shard_replica_set(&collection_dir).await;
+        upsert_group(&rs, &[1, 2, 3], "a").await;
+
+        // Versions are only returned on request
+        assert_eq!(point_versions(&rs, &[1], false).await, vec![None]);
+        let versions = point_versions(&rs, &[1, 2, 3], true).await;
+        let version = versions[0].unwrap();
+        assert!(versions.iter().all(|v| *v == Some(version)));
+
+        let set_group = |points: Option<Vec<PointIdType>>, filter, group: &str| {
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+                payload: json!({ "group": group }).into(),
+                points,
+                filter,
+                if_version: Some(version),
+                key: None,
+            }))
+        };
+
+        // Only the first update of the version is applied
+        rs.update_with_consistency(
+            set_group(Some(vec![1.into()]), None, "b"),
+            true,
+            WriteOrdering::Weak,
+        )
+        .await
+        .unwrap();
+        let err = rs
+            .update_with_consistency(
+                set_group(Some(vec![1.into()]), None, "c"),
+                true,
+                WriteOrdering::Weak,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CollectionError::Conflict { .. }), "{err}");
+        assert_eq!(group_ids(&rs, "b").await, vec![1.into()]);
+
+        // Conflict of a single point rejects the update of all the points
+        let err = rs
+            .update_with_consistency(
+                set_group(Some(vec![1.into(), 2.into()]), None, "c"),
+                true,
+                WriteOrdering::Weak,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CollectionError::Conflict { .. }), "{err}");
+        assert!(err.to_string().contains("point 1 "), "{err}");
+        assert!(!err.to_string().contains("point 2 "), "{err}");
+        assert!(group_ids(&rs, "c").await.is_empty());
+
+        // Points selected by the filter are checked, and updated if they all match
+        rs.update_with_consistency(
+            set_group(None, Some(group_filter("a")), "c"),
+            true,
+            WriteOrdering::Weak,
+        )
+        .await
+        .unwrap();
+        assert_eq!(group_ids(&rs, "c").await, vec![2.into(), 3.into()]);
+        assert_eq!(group_ids(&rs, "b").await, vec![1.into()]);
+    }
 }
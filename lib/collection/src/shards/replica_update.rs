@@ -0,0 +1,212 @@
+use async_trait::async_trait;
+use segment::types::PointIdType;
+
+use crate::operations::point_ops::WriteOrdering;
+use crate::operations::types::{CollectionResult, UpdateResult};
+use crate::operations::{CollectionUpdateOperations, OperationWithClientId};
+use crate::shards::remote_shard::RemoteShard;
+use crate::shards::replica_set::ShardReplicaSet;
+use crate::shards::shard_trait::ShardOperation;
+
+/// Update, which is applied to every replica of a shard
+#[async_trait]
+pub trait ReplicaUpdate: Clone + Send + Sync {
+    type Output: Send;
+    /// Points, which the deletes by filter of the update are resolved to
+    type DeletedPoints: Send;
+
+    async fn apply(
+        &self,
+        shard: &(dyn ShardOperation + Send + Sync),
+        wait: bool,
+    ) -> CollectionResult<Self::Output>;
+
+    async fn forward(
+        &self,
+        leader: &RemoteShard,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<Self::Output>;
+
+    /// Whether the update contains a delete by filter
+    fn has_delete_by_filter(&self) -> bool;
+
+    /// Resolve deletes by filter on the leader, before the update is replicated,
+    /// see [`ShardReplicaSet::resolve_delete_filter`]
+    async fn resolve_delete_filters(
+        &mut self,
+        replica_set: &ShardReplicaSet,
+    ) -> CollectionResult<Self::DeletedPoints>;
+
+    fn report_deleted_points(output: &mut Self::Output, deleted_points: Self::DeletedPoints);
+
+    /// Whether the update is only applied, if the points have the expected versions
+    fn is_conditional(&self) -> bool;
+
+    /// Check the versions of the points on the leader, before the update is replicated,
+    /// see [`ShardReplicaSet::resolve_conditional_update`]
+    async fn resolve_conditions(&mut self, replica_set: &ShardReplicaSet) -> CollectionResult<()>;
+}
+
+#[async_trait]
+impl ReplicaUpdate for CollectionUpdateOperations {
+    type Output = UpdateResult;
+    type DeletedPoints = Option<Vec<PointIdType>>;
+
+    async fn apply(
+        &self,
+        shard: &(dyn ShardOperation + Send + Sync),
+        wait: bool,
+    ) -> CollectionResult<UpdateResult> {
+        shard.update(self.clone(), wait).await
+    }
+
+    async fn forward(
+        &self,
+        leader: &RemoteShard,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<UpdateResult> {
+        leader
+            .forward_update(self.clone().into(), wait, ordering)
+            .await
+    }
+
+    fn has_delete_by_filter(&self) -> bool {
+        self.is_delete_by_filter()
+    }
+
+    async fn resolve_delete_filters(
+        &mut self,
+        replica_set: &ShardReplicaSet,
+    ) -> CollectionResult<Option<Vec<PointIdType>>> {
+        replica_set.resolve_delete_filter(self).await
+    }
+
+    fn report_deleted_points(output: &mut UpdateResult, deleted_points: Option<Vec<PointIdType>>) {
+        if let Some(ids) = deleted_points {
+            output.set_deleted_points(ids);
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        CollectionUpdateOperations::is_conditional(self)
+    }
+
+    async fn resolve_conditions(&mut self, replica_set: &ShardReplicaSet) -> CollectionResult<()> {
+        replica_set.resolve_conditional_update(self).await
+    }
+}
+
+/// Update with a client id, so every replica applies it only once
+#[async_trait]
+impl ReplicaUpdate for OperationWithClientId {
+    type Output = UpdateResult;
+    type DeletedPoints = Option<Vec<PointIdType>>;
+
+    async fn apply(
+        &self,
+        shard: &(dyn ShardOperation + Send + Sync),
+        wait: bool,
+    ) -> CollectionResult<UpdateResult> {
+        shard.update_with_client_id(self.clone(), wait).await
+    }
+
+    async fn forward(
+        &self,
+        leader: &RemoteShard,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<UpdateResult> {
+        leader.forward_update(self.clone(), wait, ordering).await
+    }
+
+    fn has_delete_by_filter(&self) -> bool {
+        self.operation.is_delete_by_filter()
+    }
+
+    async fn resolve_delete_filters(
+        &mut self,
+        replica_set: &ShardReplicaSet,
+    ) -> CollectionResult<Option<Vec<PointIdType>>> {
+        replica_set.resolve_delete_filter(&mut self.operation).await
+    }
+
+    fn report_deleted_points(output: &mut UpdateResult, deleted_points: Option<Vec<PointIdType>>) {
+        CollectionUpdateOperations::report_deleted_points(output, deleted_points);
+    }
+
+    fn is_conditional(&self) -> bool {
+        self.operation.is_conditional()
+    }
+
+    async fn resolve_conditions(&mut self, replica_set: &ShardReplicaSet) -> CollectionResult<()> {
+        replica_set
+            .resolve_conditional_update(&mut self.operation)
+            .await
+    }
+}
+
+/// Batch of updates, applied to a replica as consecutive operations
+#[async_trait]
+impl ReplicaUpdate for Vec<CollectionUpdateOperations> {
+    type Output = Vec<UpdateResult>;
+    type DeletedPoints = Vec<Option<Vec<PointIdType>>>;
+
+    async fn apply(
+        &self,
+        shard: &(dyn ShardOperation + Send + Sync),
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        shard.update_batch(self.clone(), wait).await
+    }
+
+    async fn forward(
+        &self,
+        leader: &RemoteShard,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        leader
+            .forward_update_batch(self.clone(), wait, ordering)
+            .await
+    }
+
+    fn has_delete_by_filter(&self) -> bool {
+        self.iter()
+            .any(CollectionUpdateOperations::is_delete_by_filter)
+    }
+
+    async fn resolve_delete_filters(
+        &mut self,
+        replica_set: &ShardReplicaSet,
+    ) -> CollectionResult<Vec<Option<Vec<PointIdType>>>> {
+        let mut deleted_points = Vec::with_capacity(self.len());
+        for operation in self.iter_mut() {
+            deleted_points.push(replica_set.resolve_delete_filter(operation).await?);
+        }
+        Ok(deleted_points)
+    }
+
+    fn report_deleted_points(
+        output: &mut Vec<UpdateResult>,
+        deleted_points: Vec<Option<Vec<PointIdType>>>,
+    ) {
+        for (result, deleted_points) in output.iter_mut().zip(deleted_points) {
+            CollectionUpdateOperations::report_deleted_points(result, deleted_points);
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        self.iter().any(CollectionUpdateOperations::is_conditional)
+    }
+
+    /// Versions are checked before the batch is applied,
+    /// changes of the points by the preceding operations of the batch are not accounted for
+    async fn resolve_conditions(&mut self, replica_set: &ShardReplicaSet) -> CollectionResult<()> {
+        for operation in self.iter_mut() {
+            replica_set.resolve_conditional_update(operation).await?;
+        }
+        Ok(())
+    }
+}
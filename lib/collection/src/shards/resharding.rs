@@ -0,0 +1,230 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use schemars::JsonSchema;
+use segment::types::ExtendedPointId;
+use serde::{Deserialize, Serialize};
+
+use crate::common::stoppable_task_async::{spawn_async_stoppable, StoppableAsyncTaskHandle};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::shard_holder::LockedShardHolder;
+
+const MIGRATION_BATCH_SIZE: usize = 100;
+
+/// Each split selects points by its own bit of the point id hash
+pub const MAX_SHARD_SPLITS: usize = u64::BITS as usize;
+
+/// Split of a shard into two shards.
+///
+/// Points of the source shard, which have the bit of the split set in the hash of their id,
+/// belong to the target shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct ShardSplit {
+    pub source_shard_id: ShardId,
+    pub target_shard_id: ShardId,
+}
+
+/// Resharding operation in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReshardingState {
+    pub split: ShardSplit,
+    /// Peer, which copies points from its replica of the source shard into the target shard
+    pub peer_id: PeerId,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ReshardingInfo {
+    /// Shard, which is split
+    pub shard_id: ShardId,
+    /// New shard, which receives part of the points of the split shard
+    pub target_shard_id: ShardId,
+    /// Peer, which copies points into the new shard
+    pub peer_id: PeerId,
+    /// Number of points copied into the new shard so far.
+    /// Only known on the peer, which copies the points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migrated_points: Option<usize>,
+}
+
+/// Stable hash of the point id, which does not depend on the hash ring.
+///
+/// Bits of the hash select which half of a split shard the point belongs to,
+/// so the hash must be the same on all peers and across versions.
+pub fn split_hash(point_id: &ExtendedPointId) -> u64 {
+    match point_id {
+        ExtendedPointId::NumId(num) => mix64(*num),
+        ExtendedPointId::Uuid(uuid) => {
+            let (high, low) = uuid.as_u64_pair();
+            mix64(high ^ mix64(low))
+        }
+    }
+}
+
+/// Finalizer of SplitMix64
+fn mix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Shard of the point, after the splits are applied to the shard selected by the hash ring
+pub fn apply_splits(
+    shard_id: ShardId,
+    splits: &[ShardSplit],
+    point_id: &ExtendedPointId,
+) -> ShardId {
+    if splits.is_empty() {
+        return shard_id;
+    }
+    let hash = split_hash(point_id);
+    splits
+        .iter()
+        .enumerate()
+        .fold(shard_id, |shard_id, (bit, split)| {
+            if split.source_shard_id == shard_id && hash & (1 << bit) != 0 {
+                split.target_shard_id
+            } else {
+                shard_id
+            }
+        })
+}
+
+/// Copy points of the split from the local replica of the source shard into the target shard
+async fn migrate_points(
+    shard_holder: Arc<LockedShardHolder>,
+    split: ShardSplit,
+    migrated_points: Arc<AtomicUsize>,
+    stopped: Arc<AtomicBool>,
+) -> CollectionResult<()> {
+    let source_shard_id = split.source_shard_id;
+    {
+        let shard_holder_guard = shard_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&source_shard_id) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {source_shard_id} is not found"
+            )));
+        };
+        replica_set.migrate_indexes().await?;
+    }
+
+    let mut offset = None;
+    loop {
+        if stopped.load(Ordering::Relaxed) {
+            return Err(CollectionError::Cancelled {
+                description: "Resharding cancelled".to_string(),
+            });
+        }
+        let shard_holder_guard = shard_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&source_shard_id) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {source_shard_id} is not found"
+            )));
+        };
+
+        let (next_offset, migrated) = replica_set
+            .migrate_batch(offset, MIGRATION_BATCH_SIZE)
+            .await?;
+        migrated_points.fetch_add(migrated, Ordering::Relaxed);
+        offset = next_offset;
+        if offset.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+/// Delete points, which were moved into the new shards, from the local replica of the split shard
+pub async fn drop_moved_points(
+    shard_holder: Arc<LockedShardHolder>,
+    shard_id: ShardId,
+) -> CollectionResult<()> {
+    let mut offset = None;
+    loop {
+        let shard_holder_guard = shard_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&shard_id) else {
+            return Ok(());
+        };
+        offset = replica_set
+            .retain_local_batch(offset, MIGRATION_BATCH_SIZE, |point_id| {
+                shard_holder_guard.is_point_owned(shard_id, point_id)
+            })
+            .await?;
+        if offset.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+pub fn spawn_resharding_task<T, F>(
+    shard_holder: Arc<LockedShardHolder>,
+    split: ShardSplit,
+    migrated_points: Arc<AtomicUsize>,
+    on_finish: T,
+    on_error: F,
+) -> StoppableAsyncTaskHandle<bool>
+where
+    T: Future<Output = ()> + Send + 'static,
+    F: Future<Output = ()> + Send + 'static,
+{
+    spawn_async_stoppable(move |stopped| async move {
+        let result = migrate_points(shard_holder, split, migrated_points, stopped.clone()).await;
+        match result {
+            Ok(()) => {
+                on_finish.await;
+                true
+            }
+            Err(CollectionError::Cancelled { .. }) => false,
+            Err(error) => {
+                log::error!(
+                    "Failed to split shard {} into shard {}: {error}",
+                    split.source_shard_id,
+                    split.target_shard_id,
+                );
+                if !stopped.load(Ordering::Relaxed) {
+                    on_error.await;
+                }
+                false
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_splits() {
+        let splits = [
+            ShardSplit {
+                source_shard_id: 0,
+                target_shard_id: 2,
+            },
+            ShardSplit {
+                source_shard_id: 0,
+                target_shard_id: 3,
+            },
+        ];
+
+        let mut counts = [0usize; 4];
+        for num in 0..10_000u64 {
+            let point_id = ExtendedPointId::NumId(num);
+            let shard_id = apply_splits(0, &splits, &point_id);
+            counts[shard_id as usize] += 1;
+
+            // Splits of other shards do not move the point
+            assert_eq!(apply_splits(1, &splits, &point_id), 1);
+            // Second split only moves points, which are left in the source by the first split
+            if apply_splits(0, &splits[..1], &point_id) == 2 {
+                assert_eq!(shard_id, 2);
+            }
+        }
+        assert_eq!(counts[1], 0);
+        // Roughly a half of the points moves to the first target, a quarter to the second one
+        assert!((4500..5500).contains(&counts[2]), "{counts:?}");
+        assert!((2000..3000).contains(&counts[3]), "{counts:?}");
+    }
+}
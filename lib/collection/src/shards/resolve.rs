@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use std::hash;
 
-use segment::types::{Payload, ScoredPoint};
+use segment::types::{Payload, ScoredPoint, SeqNumberType};
 use tinyvec::TinyVec;
 
 use crate::operations::types::Record;
@@ -34,8 +34,13 @@ impl Resolve for Vec<Vec<ScoredPoint>> {
         batches
             .into_iter()
             .map(|points| {
-                let mut resolved =
-                    Resolver::resolve(points, |point| point.id, scored_point_eq, condition);
+                let mut resolved = Resolver::resolve_latest(
+                    points,
+                    |point| point.id,
+                    scored_point_eq,
+                    |point| point.version,
+                    condition,
+                );
 
                 resolved.sort_unstable();
                 resolved
@@ -92,12 +97,62 @@ where
     Ident: Fn(&Item) -> Id,
     Cmp: Fn(&Item, &Item) -> bool,
 {
+    /// Accept items, which are returned equally by enough replicas
     pub fn resolve(
         items: Vec<Vec<Item>>,
         identify: Ident,
         compare: Cmp,
         condition: ResolveCondition,
     ) -> Vec<Item> {
+        Self::resolve_with(items, identify, compare, condition, |variants, count| {
+            variants
+                .iter()
+                .find(|variant| variant.count >= count)
+                .copied()
+        })
+    }
+
+    /// Accept items, which are returned equally by enough replicas, counting outdated replicas
+    ///
+    /// Replicas, which return an item of a lower version, lag behind the others. They count in
+    /// favor of the variant with the highest version, instead of preventing its acceptance.
+    pub fn resolve_latest<Ver>(
+        items: Vec<Vec<Item>>,
+        identify: Ident,
+        compare: Cmp,
+        version: Ver,
+        condition: ResolveCondition,
+    ) -> Vec<Item>
+    where
+        Ver: Fn(&Item) -> SeqNumberType,
+    {
+        Self::resolve_with(items, identify, compare, condition, |variants, count| {
+            let variant_version = |variant: &ResolverRecord<Item>| variant.item.map(&version);
+            let latest_version = variants.iter().map(variant_version).max()?;
+            let outdated_count: usize = variants
+                .iter()
+                .filter(|&variant| variant_version(variant) < latest_version)
+                .map(|variant| variant.count)
+                .sum();
+            variants
+                .iter()
+                .filter(|&variant| variant_version(variant) == latest_version)
+                .filter(|variant| variant.count + outdated_count >= count)
+                .max_by_key(|variant| variant.count)
+                .copied()
+        })
+    }
+
+    fn resolve_with<Sel>(
+        items: Vec<Vec<Item>>,
+        identify: Ident,
+        compare: Cmp,
+        condition: ResolveCondition,
+        select: Sel,
+    ) -> Vec<Item>
+    where
+        Sel: for<'r> Fn(&[ResolverRecord<'r, Item>], usize) -> Option<ResolverRecord<'r, Item>>,
+    {
         let resolution_count = match condition {
             ResolveCondition::All => items.len(),
             ResolveCondition::Majority => items.len() / 2 + 1,
@@ -111,10 +166,7 @@ where
             .items
             .into_iter()
             .filter_map(|(_, points)| {
-                points
-                    .into_iter()
-                    .find(|point| point.count >= resolution_count)
-                    .map(|point| (point.row, point.index))
+                select(points.as_slice(), resolution_count).map(|point| (point.row, point.index))
             })
             .collect();
 
@@ -381,6 +433,38 @@ mod test {
         );
     }
 
+    fn versioned_point(id: u64, version: SeqNumberType, score: ScoreType) -> ScoredPoint {
+        ScoredPoint {
+            version,
+            ..point(id, score)
+        }
+    }
+
+    /// Point 2 is updated on the first replicas, while the last replicas lag behind
+    fn stale_replicas_input(updated: usize, stale: usize) -> Vec<Vec<Vec<ScoredPoint>>> {
+        let updated_batch = vec![point(1, 0.1), versioned_point(2, 2, 0.5), point(3, 0.3)];
+        let stale_batch = vec![point(1, 0.1), point(2, 0.2), point(3, 0.3)];
+
+        let updated_replicas = (0..updated).map(|_| vec![updated_batch.clone()]);
+        let stale_replicas = (0..stale).map(|_| vec![stale_batch.clone()]);
+        updated_replicas.chain(stale_replicas).collect()
+    }
+
+    #[test]
+    fn resolve_scored_points_stale_replica() {
+        let updated = vec![point(1, 0.1), point(3, 0.3), versioned_point(2, 2, 0.5)];
+        let stale = vec![point(1, 0.1), point(2, 0.2), point(3, 0.3)];
+
+        for condition in [ResolveCondition::All, ResolveCondition::Majority] {
+            test_resolve(stale_replicas_input(2, 1), [updated.clone()], condition);
+            // The latest version is accepted, even if most replicas lag behind
+            test_resolve(stale_replicas_input(1, 2), [updated.clone()], condition);
+        }
+
+        // A single response of the stale replica is not resolved against others
+        test_resolve(stale_replicas_input(0, 1), [stale], ResolveCondition::All);
+    }
+
     fn data_simple() -> [i32; 9] {
         [1, 2, 3, 4, 5, 6, 7, 8, 9]
     }
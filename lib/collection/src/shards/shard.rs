@@ -1,18 +1,70 @@
 use core::marker::{Send, Sync};
+use std::fmt::{Display, Formatter};
 use std::path::Path;
+use std::sync::Arc;
 
-use crate::operations::types::CollectionResult;
+use schemars::JsonSchema;
+use segment::data_types::search_plan::SegmentSearchPlan;
+use serde::{Deserialize, Serialize};
+
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentId};
+use crate::collection_manager::optimizers::optimization_tasks::OptimizationTasks;
+use crate::common::resource_usage::ShardResourceUsage;
+use crate::operations::types::{CollectionResult, SearchPlanRequest};
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::proxy_shard::ProxyShard;
 use crate::shards::shard_trait::ShardOperation;
-use crate::shards::telemetry::LocalShardTelemetry;
+use crate::shards::split_proxy_shard::SplitProxyShard;
+use crate::shards::telemetry::{LocalShardTelemetry, WalRecoveryReport};
 
 pub type ShardId = u32;
 
 pub type PeerId = u64;
 
+/// Peers of the replicas of each shard, one list per shard
+pub type ShardsPlacement = Vec<Vec<PeerId>>;
+
+/// Key of a group of shards in a collection with custom sharding
+///
+/// Points are routed to the shards of the key, specified with the update operation.
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(untagged)]
+pub enum ShardKey {
+    Keyword(String),
+    Number(u64),
+}
+
+impl Display for ShardKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShardKey::Keyword(keyword) => write!(f, "\"{keyword}\""),
+            ShardKey::Number(number) => write!(f, "{number}"),
+        }
+    }
+}
+
+impl From<String> for ShardKey {
+    fn from(keyword: String) -> Self {
+        ShardKey::Keyword(keyword)
+    }
+}
+
+impl From<&str> for ShardKey {
+    fn from(keyword: &str) -> Self {
+        ShardKey::Keyword(keyword.to_string())
+    }
+}
+
+impl From<u64> for ShardKey {
+    fn from(number: u64) -> Self {
+        ShardKey::Number(number)
+    }
+}
+
 /// Shard
 ///
 /// Contains a part of the collection's points
@@ -22,6 +74,7 @@ pub enum Shard {
     Local(LocalShard),
     Proxy(ProxyShard),
     ForwardProxy(ForwardProxyShard),
+    SplitProxy(SplitProxyShard),
     Dummy(DummyShard),
 }
 
@@ -31,6 +84,7 @@ impl Shard {
             Shard::Local(_) => "local shard",
             Shard::Proxy(_) => "proxy shard",
             Shard::ForwardProxy(_) => "forward proxy shard",
+            Shard::SplitProxy(_) => "split proxy shard",
             Shard::Dummy(_) => "dummy shard",
         }
     }
@@ -40,6 +94,7 @@ impl Shard {
             Shard::Local(local_shard) => local_shard,
             Shard::Proxy(proxy_shard) => proxy_shard,
             Shard::ForwardProxy(proxy_shard) => proxy_shard,
+            Shard::SplitProxy(proxy_shard) => proxy_shard,
             Shard::Dummy(dummy_shard) => dummy_shard,
         }
     }
@@ -49,12 +104,26 @@ impl Shard {
             Shard::Local(local_shard) => local_shard.get_telemetry_data(),
             Shard::Proxy(proxy_shard) => proxy_shard.get_telemetry_data(),
             Shard::ForwardProxy(proxy_shard) => proxy_shard.get_telemetry_data(),
+            Shard::SplitProxy(proxy_shard) => proxy_shard.get_telemetry_data(),
             Shard::Dummy(dummy_shard) => dummy_shard.get_telemetry_data(),
         };
         telemetry.variant_name = Some(self.variant_name().to_string());
         telemetry
     }
 
+    pub fn search_plan(
+        &self,
+        request: &SearchPlanRequest,
+    ) -> CollectionResult<Vec<SegmentSearchPlan>> {
+        match self {
+            Shard::Local(local_shard) => local_shard.search_plan(request),
+            Shard::Proxy(proxy_shard) => proxy_shard.search_plan(request),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.search_plan(request),
+            Shard::SplitProxy(proxy_shard) => proxy_shard.search_plan(request),
+            Shard::Dummy(dummy_shard) => dummy_shard.search_plan(request),
+        }
+    }
+
     pub async fn create_snapshot(
         &self,
         temp_path: &Path,
@@ -77,6 +146,11 @@ impl Shard {
                     .create_snapshot(temp_path, target_path, save_wal)
                     .await
             }
+            Shard::SplitProxy(proxy_shard) => {
+                proxy_shard
+                    .create_snapshot(temp_path, target_path, save_wal)
+                    .await
+            }
             Shard::Dummy(dummy_shard) => {
                 dummy_shard
                     .create_snapshot(temp_path, target_path, save_wal)
@@ -90,7 +164,75 @@ impl Shard {
             Shard::Local(local_shard) => local_shard.on_optimizer_config_update().await,
             Shard::Proxy(proxy_shard) => proxy_shard.on_optimizer_config_update().await,
             Shard::ForwardProxy(proxy_shard) => proxy_shard.on_optimizer_config_update().await,
+            Shard::SplitProxy(proxy_shard) => proxy_shard.on_optimizer_config_update().await,
             Shard::Dummy(dummy_shard) => dummy_shard.on_optimizer_config_update().await,
         }
     }
+
+    /// Optimization tasks of the local shards, the ones wrapped by proxies included
+    pub fn optimization_tasks(&self) -> Vec<Arc<OptimizationTasks>> {
+        match self {
+            Shard::Local(local_shard) => vec![local_shard.optimization_tasks.clone()],
+            Shard::Proxy(proxy_shard) => {
+                vec![proxy_shard.wrapped_shard.optimization_tasks.clone()]
+            }
+            Shard::ForwardProxy(proxy_shard) => {
+                vec![proxy_shard.wrapped_shard.optimization_tasks.clone()]
+            }
+            Shard::SplitProxy(proxy_shard) => vec![
+                proxy_shard.wrapped_shard.optimization_tasks.clone(),
+                proxy_shard.target_shard.optimization_tasks.clone(),
+            ],
+            Shard::Dummy(_) => vec![],
+        }
+    }
+
+    /// Segments of the local shard, the one wrapped by a proxy included
+    pub fn locked_segments(&self) -> Vec<(SegmentId, LockedSegment)> {
+        let local_shard = match self {
+            Shard::Local(local_shard) => local_shard,
+            Shard::Proxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::ForwardProxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::SplitProxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::Dummy(_) => return Vec::new(),
+        };
+        local_shard.locked_segments()
+    }
+
+    /// Report of the WAL replay of the local shard, the one wrapped by a proxy included
+    pub fn wal_recovery_report(&self) -> Option<WalRecoveryReport> {
+        let local_shard = match self {
+            Shard::Local(local_shard) => local_shard,
+            Shard::Proxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::ForwardProxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::SplitProxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::Dummy(_) => return None,
+        };
+        local_shard.wal_recovery_report().cloned()
+    }
+
+    /// Memory and disk usage of the local shard, the one wrapped by a proxy included
+    pub fn resource_usage(
+        &self,
+        shard_id: ShardId,
+    ) -> CollectionResult<Option<ShardResourceUsage>> {
+        let local_shard = match self {
+            Shard::Local(local_shard) => local_shard,
+            Shard::Proxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::ForwardProxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::SplitProxy(proxy_shard) => &proxy_shard.wrapped_shard,
+            Shard::Dummy(_) => return Ok(None),
+        };
+        local_shard.resource_usage(shard_id).map(Some)
+    }
+
+    pub async fn shutdown(&self) -> CollectionResult<()> {
+        match self {
+            Shard::Local(local_shard) => local_shard.shutdown().await,
+            Shard::Proxy(proxy_shard) => proxy_shard.shutdown().await,
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.shutdown().await,
+            Shard::SplitProxy(proxy_shard) => proxy_shard.shutdown().await,
+            Shard::Dummy(dummy_shard) => dummy_shard.shutdown().await,
+        }
+    }
 }
@@ -2,53 +2,353 @@ use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
+use segment::types::PointIdType;
 use tokio::runtime::Handle;
 use tokio::sync::RwLock;
 
-use crate::config::CollectionConfig;
-use crate::hash_ring::HashRing;
+use crate::config::{CollectionConfig, ShardingMethod};
+use crate::hash_ring::{HashRing, HashRingRouter};
+use crate::operations::shard_key_selector::ShardSelectorInternal;
 use crate::operations::shared_storage_config::SharedStorageConfig;
-use crate::operations::types::{CollectionResult, ShardTransferInfo};
+use crate::operations::types::{CollectionError, CollectionResult, ShardTransferInfo};
 use crate::operations::{OperationToShard, SplitByShard};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
 use crate::shards::local_shard::LocalShard;
 use crate::shards::replica_set::{ChangePeerState, ReplicaState, ShardReplicaSet};
-use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::resharding::{ReshardingState, ShardSplit, MAX_SHARD_SPLITS};
+use crate::shards::shard::{PeerId, ShardId, ShardKey};
 use crate::shards::shard_config::{ShardConfig, ShardType};
 use crate::shards::shard_versioning::latest_shard_paths;
 use crate::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
-use crate::shards::CollectionId;
+use crate::shards::{CollectionId, HASH_RING_SHARD_SCALE};
 
 const SHARD_TRANSFERS_FILE: &str = "shard_transfers";
+const SHARD_KEY_MAPPING_FILE: &str = "shard_key_mapping.json";
+const SHARD_SPLITS_FILE: &str = "shard_splits.json";
+const RESHARDING_STATE_FILE: &str = "resharding_state.json";
+
+/// Shard key of each shard of a collection with custom sharding
+pub type ShardKeyMapping = HashMap<ShardId, ShardKey>;
 
 pub struct ShardHolder {
     shards: HashMap<ShardId, ShardReplicaSet>,
     pub(crate) shard_transfers: SaveOnDisk<HashSet<ShardTransfer>>,
-    ring: HashRing<ShardId>,
+    pub(crate) shard_key_mapping: SaveOnDisk<ShardKeyMapping>,
+    /// Splits of the shards made by resharding, in the order they were made
+    pub(crate) shard_splits: SaveOnDisk<Vec<ShardSplit>>,
+    /// Resharding operation in progress, if any
+    pub(crate) resharding_state: SaveOnDisk<Option<ReshardingState>>,
+    ring: HashRingRouter,
+    /// Points of a shard key are distributed between shards of this key only
+    key_rings: HashMap<ShardKey, HashRingRouter>,
+    sharding_method: ShardingMethod,
 }
 
 pub type LockedShardHolder = RwLock<ShardHolder>;
 
 impl ShardHolder {
-    pub fn new(collection_path: &Path, hashring: HashRing<ShardId>) -> CollectionResult<Self> {
+    pub fn new(
+        collection_path: &Path,
+        hashring: HashRing<ShardId>,
+        sharding_method: ShardingMethod,
+    ) -> CollectionResult<Self> {
         let shard_transfers = SaveOnDisk::load_or_init(collection_path.join(SHARD_TRANSFERS_FILE))?;
+        let shard_key_mapping =
+            SaveOnDisk::load_or_init(collection_path.join(SHARD_KEY_MAPPING_FILE))?;
+        let shard_splits: SaveOnDisk<Vec<ShardSplit>> =
+            SaveOnDisk::load_or_init(collection_path.join(SHARD_SPLITS_FILE))?;
+        let resharding_state =
+            SaveOnDisk::load_or_init(collection_path.join(RESHARDING_STATE_FILE))?;
+        let mut ring = HashRingRouter::new(hashring);
+        ring.set_splits(shard_splits.read().clone());
         Ok(Self {
             shards: HashMap::new(),
             shard_transfers,
-            ring: hashring,
+            shard_key_mapping,
+            shard_splits,
+            resharding_state,
+            ring,
+            key_rings: HashMap::new(),
+            sharding_method,
         })
     }
 
-    pub fn add_shard(&mut self, shard_id: ShardId, shard: ShardReplicaSet) {
+    pub fn sharding_method(&self) -> ShardingMethod {
+        self.sharding_method
+    }
+
+    /// Add shard to the collection
+    ///
+    /// Shards with a shard key only receive points of this key.
+    /// Shards created by resharding receive points from the shards they are split from.
+    pub fn add_shard(
+        &mut self,
+        shard_id: ShardId,
+        shard: ShardReplicaSet,
+        shard_key: Option<ShardKey>,
+    ) -> CollectionResult<()> {
         self.shards.insert(shard_id, shard);
-        self.ring.add(shard_id);
+        match shard_key {
+            None if self.is_split_target(&shard_id) => {}
+            None => self.ring.add(shard_id),
+            Some(shard_key) => {
+                self.key_rings
+                    .entry(shard_key.clone())
+                    .or_insert_with(|| HashRingRouter::new(HashRing::fair(HASH_RING_SHARD_SCALE)))
+                    .add(shard_id);
+                if self.shard_key_mapping.read().get(&shard_id) != Some(&shard_key) {
+                    self.shard_key_mapping.write(|mapping| {
+                        mapping.insert(shard_id, shard_key);
+                    })?;
+                }
+            }
+        }
+        Ok(())
     }
 
-    pub fn remove_shard(&mut self, shard_id: ShardId) -> Option<ShardReplicaSet> {
+    pub fn remove_shard(&mut self, shard_id: ShardId) -> CollectionResult<Option<ShardReplicaSet>> {
         let shard = self.shards.remove(&shard_id);
         self.ring.remove(&shard_id);
-        shard
+        let shard_key = self.get_shard_key(&shard_id);
+        if let Some(shard_key) = shard_key {
+            self.shard_key_mapping.write(|mapping| {
+                mapping.remove(&shard_id);
+            })?;
+            let key_is_used = self
+                .shard_key_mapping
+                .read()
+                .values()
+                .any(|key| *key == shard_key);
+            if key_is_used {
+                if let Some(key_ring) = self.key_rings.get_mut(&shard_key) {
+                    key_ring.remove(&shard_id);
+                }
+            } else {
+                self.key_rings.remove(&shard_key);
+            }
+        }
+        Ok(shard)
+    }
+
+    /// Whether the shard was created by resharding, either finished or in progress
+    pub fn is_split_target(&self, shard_id: &ShardId) -> bool {
+        self.is_resharding_target(shard_id)
+            || self
+                .shard_splits
+                .read()
+                .iter()
+                .any(|split| split.target_shard_id == *shard_id)
+    }
+
+    /// Whether the shard is being filled by the resharding in progress.
+    /// Such shard does not serve reads and client updates, until the resharding is finished.
+    pub fn is_resharding_target(&self, shard_id: &ShardId) -> bool {
+        self.resharding_state
+            .read()
+            .map_or(false, |state| state.split.target_shard_id == *shard_id)
+    }
+
+    pub fn resharding_state(&self) -> Option<ReshardingState> {
+        *self.resharding_state.read()
+    }
+
+    /// Check that the resharding can be started in the current state of the collection
+    pub fn check_start_resharding(&self, state: &ReshardingState) -> CollectionResult<()> {
+        let ShardSplit {
+            source_shard_id,
+            target_shard_id,
+        } = state.split;
+        if self.sharding_method != ShardingMethod::Auto {
+            return Err(CollectionError::bad_request(format!(
+                "Resharding is only supported for collections with {:?} sharding",
+                ShardingMethod::Auto,
+            )));
+        }
+        if let Some(resharding) = self.resharding_state() {
+            return Err(CollectionError::bad_request(format!(
+                "Shard {} is already being split into shard {}",
+                resharding.split.source_shard_id, resharding.split.target_shard_id,
+            )));
+        }
+        if self.shard_splits.read().len() >= MAX_SHARD_SPLITS {
+            return Err(CollectionError::bad_request(format!(
+                "Collection can not be resharded more than {MAX_SHARD_SPLITS} times"
+            )));
+        }
+        let Some(replica_set) = self.get_shard(&source_shard_id) else {
+            return Err(CollectionError::bad_request(format!(
+                "Shard {source_shard_id} does not exist"
+            )));
+        };
+        if replica_set.peer_state(&state.peer_id) != Some(ReplicaState::Active) {
+            return Err(CollectionError::bad_request(format!(
+                "Peer {} does not have an active replica of shard {source_shard_id}",
+                state.peer_id,
+            )));
+        }
+        if self.contains_shard(&target_shard_id) {
+            return Err(CollectionError::bad_request(format!(
+                "Shard {target_shard_id} already exists"
+            )));
+        }
+        if self
+            .shard_transfers
+            .read()
+            .iter()
+            .any(|transfer| transfer.shard_id == source_shard_id)
+        {
+            return Err(CollectionError::bad_request(format!(
+                "Shard {source_shard_id} can not be split while it is being transferred"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Register the resharding and add the new shard, which receives points of the split
+    pub fn start_resharding(
+        &mut self,
+        state: ReshardingState,
+        target_shard: ShardReplicaSet,
+    ) -> CollectionResult<()> {
+        self.resharding_state.write(|resharding| {
+            *resharding = Some(state);
+        })?;
+        self.add_shard(state.split.target_shard_id, target_shard, None)
+    }
+
+    /// Route the points of the split into the new shard and forget the resharding
+    pub fn commit_resharding(&mut self, split: ShardSplit) -> CollectionResult<()> {
+        self.shard_splits.write(|splits| {
+            if !splits.contains(&split) {
+                splits.push(split);
+            }
+        })?;
+        self.ring.set_splits(self.shard_splits.read().clone());
+        self.resharding_state.write(|resharding| {
+            *resharding = None;
+        })?;
+        Ok(())
+    }
+
+    /// Forget the resharding and remove the new shard
+    pub fn abort_resharding(
+        &mut self,
+        split: ShardSplit,
+    ) -> CollectionResult<Option<ShardReplicaSet>> {
+        let target_shard = self.remove_shard(split.target_shard_id)?;
+        self.resharding_state.write(|resharding| {
+            *resharding = None;
+        })?;
+        Ok(target_shard)
+    }
+
+    /// Replace splits and resharding in progress, e.g. with the ones from a consensus snapshot
+    pub fn set_resharding(
+        &mut self,
+        shard_splits: Vec<ShardSplit>,
+        resharding: Option<ReshardingState>,
+    ) -> CollectionResult<()> {
+        if *self.shard_splits.read() != shard_splits {
+            self.ring.set_splits(shard_splits.clone());
+            self.shard_splits.write(|splits| *splits = shard_splits)?;
+        }
+        if self.resharding_state() != resharding {
+            self.resharding_state.write(|state| *state = resharding)?;
+        }
+        Ok(())
+    }
+
+    /// Router of the split source shard, which routes the points as if the split is already made
+    pub fn resharding_router(&self, split: ShardSplit) -> HashRingRouter {
+        let mut router = HashRingRouter::new(HashRing::raw());
+        router.add(split.source_shard_id);
+        let mut splits = self.shard_splits.read().clone();
+        splits.push(split);
+        router.set_splits(splits);
+        router
+    }
+
+    /// Whether the point belongs to the shard.
+    ///
+    /// Shards, which are split, may still contain points moved into the new shards,
+    /// until they are deleted from the source shard.
+    pub fn is_point_owned(&self, shard_id: ShardId, point_id: &PointIdType) -> bool {
+        !self.ring.is_split_source(&shard_id) || self.ring.get(point_id) == Some(shard_id)
+    }
+
+    /// Keep only the points, which belong to the shard they are read from
+    pub fn retain_owned_points<T>(
+        &self,
+        shard_id: ShardId,
+        points: &mut Vec<T>,
+        point_id: impl Fn(&T) -> PointIdType,
+    ) {
+        if self.ring.is_split_source(&shard_id) {
+            points.retain(|point| self.is_point_owned(shard_id, &point_id(point)));
+        }
+    }
+
+    /// Save splits of the shards into the directory, e.g. of a collection snapshot
+    pub fn save_shard_splits_to(&self, dir: &Path) -> CollectionResult<()> {
+        self.shard_splits.save_to(dir.join(SHARD_SPLITS_FILE))?;
+        Ok(())
+    }
+
+    /// Load splits of the shards, stored in the collection directory
+    pub fn load_shard_splits(collection_path: &Path) -> CollectionResult<Vec<ShardSplit>> {
+        let shard_splits: SaveOnDisk<Vec<ShardSplit>> =
+            SaveOnDisk::load_or_init(collection_path.join(SHARD_SPLITS_FILE))?;
+        let splits = shard_splits.read().clone();
+        Ok(splits)
+    }
+
+    /// Save shard key mapping into the directory, e.g. of a collection snapshot
+    pub fn save_shard_key_mapping_to(&self, dir: &Path) -> CollectionResult<()> {
+        self.shard_key_mapping
+            .save_to(dir.join(SHARD_KEY_MAPPING_FILE))?;
+        Ok(())
+    }
+
+    /// Load shard key mapping, stored in the collection directory
+    pub fn load_shard_key_mapping(collection_path: &Path) -> CollectionResult<ShardKeyMapping> {
+        let shard_key_mapping: SaveOnDisk<ShardKeyMapping> =
+            SaveOnDisk::load_or_init(collection_path.join(SHARD_KEY_MAPPING_FILE))?;
+        let mapping = shard_key_mapping.read().clone();
+        Ok(mapping)
+    }
+
+    pub fn get_shard_key(&self, shard_id: &ShardId) -> Option<ShardKey> {
+        self.shard_key_mapping.read().get(shard_id).cloned()
+    }
+
+    pub fn contains_shard_key(&self, shard_key: &ShardKey) -> bool {
+        self.key_rings.contains_key(shard_key)
+    }
+
+    /// Ids of the shards, which belong to the shard key
+    pub fn shard_ids_by_key(&self, shard_key: &ShardKey) -> CollectionResult<Vec<ShardId>> {
+        if self.sharding_method != ShardingMethod::Custom {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {shard_key} can not be used with {:?} sharding, \
+                 collection must be created with custom sharding method",
+                self.sharding_method,
+            )));
+        }
+        if !self.contains_shard_key(shard_key) {
+            return Err(CollectionError::bad_request(format!(
+                "Shard key {shard_key} does not exist"
+            )));
+        }
+        let mut shard_ids: Vec<_> = self
+            .shard_key_mapping
+            .read()
+            .iter()
+            .filter(|(_, key)| *key == shard_key)
+            .map(|(shard_id, _)| *shard_id)
+            .collect();
+        shard_ids.sort_unstable();
+        Ok(shard_ids)
     }
 
     /// Take shard
@@ -89,22 +389,59 @@ impl ShardHolder {
         self.shards.values()
     }
 
+    /// Shards, which serve reads and client updates.
+    /// All shards, except the one being filled by resharding.
+    fn serving_shards(&self) -> impl Iterator<Item = &ShardReplicaSet> {
+        self.shards
+            .iter()
+            .filter(|(shard_id, _)| !self.is_resharding_target(shard_id))
+            .map(|(_, shard)| shard)
+    }
+
+    /// Split operation between shards, which should apply it
+    ///
+    /// With a shard key, the operation only goes to the shards of this key.
+    /// Without a shard key, operations of a collection with custom sharding go to all shards.
     pub fn split_by_shard<O: SplitByShard + Clone>(
         &self,
         operation: O,
-    ) -> Vec<(&ShardReplicaSet, O)> {
-        let operation_to_shard = operation.split_by_shard(&self.ring);
-        let shard_ops: Vec<_> = match operation_to_shard {
+        shard_key: Option<&ShardKey>,
+    ) -> CollectionResult<Vec<(&ShardReplicaSet, O)>> {
+        let ring = match shard_key {
+            None => match self.sharding_method {
+                ShardingMethod::Auto => &self.ring,
+                ShardingMethod::Custom => {
+                    return Ok(self
+                        .all_shards()
+                        .map(|shard| (shard, operation.clone()))
+                        .collect());
+                }
+            },
+            Some(shard_key) => {
+                self.shard_ids_by_key(shard_key)?;
+                &self.key_rings[shard_key]
+            }
+        };
+
+        let shard_ops: Vec<_> = match operation.split_by_shard(ring) {
             OperationToShard::ByShard(by_shard) => by_shard
                 .into_iter()
                 .map(|(shard_id, operation)| (self.shards.get(&shard_id).unwrap(), operation))
                 .collect(),
-            OperationToShard::ToAll(operation) => self
-                .all_shards()
-                .map(|shard| (shard, operation.clone()))
-                .collect(),
+            OperationToShard::ToAll(operation) => match shard_key {
+                None => self
+                    .serving_shards()
+                    .map(|shard| (shard, operation.clone()))
+                    .collect(),
+                Some(shard_key) => self
+                    .shard_ids_by_key(shard_key)?
+                    .into_iter()
+                    .filter_map(|shard_id| self.shards.get(&shard_id))
+                    .map(|shard| (shard, operation.clone()))
+                    .collect(),
+            },
         };
-        shard_ops
+        Ok(shard_ops)
     }
 
     pub fn register_start_shard_transfer(&self, transfer: ShardTransfer) -> CollectionResult<bool> {
@@ -153,19 +490,41 @@ impl ShardHolder {
             .collect()
     }
 
-    pub fn target_shard(
+    /// Shards, which a request with the shard selector is executed on
+    pub fn select_shards(
         &self,
-        shard_selection: Option<ShardId>,
+        shard_selector: &ShardSelectorInternal,
     ) -> CollectionResult<Vec<&ShardReplicaSet>> {
-        match shard_selection {
-            None => Ok(self.all_shards().collect()),
-            Some(shard_selection) => {
-                let shard_opt = self.get_shard(&shard_selection);
-                let shards = match shard_opt {
-                    None => vec![],
-                    Some(shard) => vec![shard],
-                };
-                Ok(shards)
+        match shard_selector {
+            ShardSelectorInternal::Empty if self.sharding_method == ShardingMethod::Custom => {
+                Err(CollectionError::bad_request(
+                    "Shard key is required for collection with custom sharding, \
+                     specify a shard key or a list of shard keys"
+                        .to_string(),
+                ))
+            }
+            ShardSelectorInternal::Empty | ShardSelectorInternal::All => {
+                Ok(self.serving_shards().collect())
+            }
+            ShardSelectorInternal::ShardKey(shard_key) => Ok(self
+                .shard_ids_by_key(shard_key)?
+                .into_iter()
+                .filter_map(|shard_id| self.get_shard(&shard_id))
+                .collect()),
+            ShardSelectorInternal::ShardKeys(shard_keys) => {
+                let mut shard_ids = Vec::new();
+                for shard_key in shard_keys {
+                    shard_ids.extend(self.shard_ids_by_key(shard_key)?);
+                }
+                shard_ids.sort_unstable();
+                shard_ids.dedup();
+                Ok(shard_ids
+                    .into_iter()
+                    .filter_map(|shard_id| self.get_shard(&shard_id))
+                    .collect())
+            }
+            ShardSelectorInternal::ShardId(shard_id) => {
+                Ok(self.get_shard(shard_id).into_iter().collect())
             }
         }
     }
@@ -191,9 +550,25 @@ impl ShardHolder {
         update_runtime: Handle,
         search_runtime: Handle,
     ) {
-        let shard_number = collection_config.read().await.params.shard_number.get();
+        let shard_ids: Vec<ShardId> = match self.sharding_method {
+            ShardingMethod::Auto => {
+                let shard_number = collection_config.read().await.params.shard_number.get();
+                let split_targets = self
+                    .shard_splits
+                    .read()
+                    .iter()
+                    .map(|split| split.target_shard_id)
+                    .chain(
+                        self.resharding_state()
+                            .map(|state| state.split.target_shard_id),
+                    )
+                    .collect::<Vec<_>>();
+                (0..shard_number).chain(split_targets).collect()
+            }
+            ShardingMethod::Custom => self.shard_key_mapping.read().keys().copied().collect(),
+        };
         // ToDo: remove after version 0.11.0
-        for shard_id in 0..shard_number {
+        for shard_id in shard_ids {
             for (path, _shard_version, shard_type) in
                 latest_shard_paths(collection_path, shard_id).await.unwrap()
             {
@@ -268,7 +643,8 @@ impl ShardHolder {
                         .map_err(|e| panic!("Failed to save shard config {path:?}: {e}"))
                         .unwrap();
                 }
-                self.add_shard(shard_id, replica_set);
+                let shard_key = self.get_shard_key(&shard_id);
+                self.add_shard(shard_id, replica_set, shard_key).unwrap();
             }
         }
     }
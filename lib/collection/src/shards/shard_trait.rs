@@ -1,16 +1,21 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 
 use crate::operations::types::{
-    CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest, Record,
-    SearchRequestBatch, UpdateResult,
+    CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest, CountResult,
+    FacetRequest, FacetResponse, PointRequest, Record, UpdateResult,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{CollectionUpdateOperations, OperationWithClientId};
+
+/// Number of points, scrolled at once by [`ShardOperation::select_ids`]
+const SELECT_IDS_BATCH_SIZE: usize = 10_000;
 
 #[async_trait]
 pub trait ShardOperation {
@@ -18,8 +23,28 @@ pub trait ShardOperation {
         &self,
         operation: CollectionUpdateOperations,
         wait: bool,
+    ) -> CollectionResult<UpdateResult> {
+        self.update_with_client_id(operation.into(), wait).await
+    }
+
+    /// Applies the operation, if the shard hasn't seen its client id yet.
+    /// Retry of an operation returns the operation number of the original one.
+    async fn update_with_client_id(
+        &self,
+        operation: OperationWithClientId,
+        wait: bool,
     ) -> CollectionResult<UpdateResult>;
 
+    /// Applies operations in order of the batch, no other update is applied in between them.
+    ///
+    /// Returns result of each operation. Failed operation doesn't stop the rest of the batch,
+    /// it is reported with `Failed` status instead.
+    async fn update_batch(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>>;
+
     #[allow(clippy::too_many_arguments)]
     async fn scroll_by(
         &self,
@@ -28,19 +53,58 @@ pub trait ShardOperation {
         with_payload_interface: &WithPayloadInterface,
         with_vector: &WithVector,
         filter: Option<&Filter>,
+        order_by: Option<&OrderBy>,
         search_runtime_handle: &Handle,
     ) -> CollectionResult<Vec<Record>>;
 
+    /// Ids of all points, selected by the `filter`, scrolled in batches
+    async fn select_ids(
+        &self,
+        filter: &Filter,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Vec<ExtendedPointId>> {
+        let mut ids = Vec::new();
+        let mut offset = None;
+        loop {
+            // Extra point is the offset of the next batch
+            let mut batch = self
+                .scroll_by(
+                    offset,
+                    SELECT_IDS_BATCH_SIZE + 1,
+                    &WithPayloadInterface::Bool(false),
+                    &WithVector::Bool(false),
+                    Some(filter),
+                    None,
+                    search_runtime_handle,
+                )
+                .await?;
+            offset = if batch.len() > SELECT_IDS_BATCH_SIZE {
+                batch.pop().map(|point| point.id)
+            } else {
+                None
+            };
+            ids.extend(batch.into_iter().map(|point| point.id));
+            if offset.is_none() {
+                return Ok(ids);
+            }
+        }
+    }
+
     async fn info(&self) -> CollectionResult<CollectionInfo>;
 
-    async fn search(
+    async fn core_search(
         &self,
-        request: Arc<SearchRequestBatch>,
+        request: Arc<CoreSearchRequestBatch>,
         search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>>;
 
     async fn count(&self, request: Arc<CountRequest>) -> CollectionResult<CountResult>;
 
+    /// Count points per value of the faceted field.
+    /// Returns counts of all values in the shard, the `limit` is applied by the collection.
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<FacetResponse>;
+
     async fn retrieve(
         &self,
         request: Arc<PointRequest>,
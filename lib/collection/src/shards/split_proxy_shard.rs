@@ -0,0 +1,296 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
+use segment::data_types::search_plan::SegmentSearchPlan;
+use segment::types::{
+    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
+    WithVector,
+};
+use tokio::runtime::Handle;
+use tokio::sync::Mutex;
+
+use crate::hash_ring::HashRingRouter;
+use crate::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
+use crate::operations::types::{
+    CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest, CountResult,
+    FacetRequest, FacetResponse, PointRequest, Record, SearchPlanRequest, UpdateResult,
+};
+use crate::operations::{
+    CollectionUpdateOperations, CreateIndex, FieldIndexOperations, OperationToShard,
+    OperationWithClientId, SplitByShard,
+};
+use crate::shards::local_shard::LocalShard;
+use crate::shards::shard::ShardId;
+use crate::shards::shard_trait::ShardOperation;
+use crate::shards::telemetry::LocalShardTelemetry;
+
+/// SplitProxyShard
+///
+/// SplitProxyShard is a wrapper type for a LocalShard, which is being split by resharding.
+///
+/// It provides all read and write operations of the wrapped shard, while points of the split
+/// are copied into the target shard. Updates of the points, which belong to the target shard
+/// after the split, are also applied to the target shard.
+pub struct SplitProxyShard {
+    pub(crate) wrapped_shard: LocalShard,
+    pub(crate) target_shard: LocalShard,
+    target_shard_id: ShardId,
+    /// Routes points of the wrapped shard, as if the split is already made
+    ring: HashRingRouter,
+    /// Lock required to protect migration-in-progress updates.
+    /// It should block data updating operations while the batch is being migrated.
+    update_lock: Mutex<()>,
+}
+
+impl SplitProxyShard {
+    pub fn new(
+        wrapped_shard: LocalShard,
+        target_shard: LocalShard,
+        target_shard_id: ShardId,
+        ring: HashRingRouter,
+    ) -> Self {
+        Self {
+            wrapped_shard,
+            target_shard,
+            target_shard_id,
+            ring,
+            update_lock: Mutex::new(()),
+        }
+    }
+
+    /// Part of the operation, which should be applied to the target shard
+    fn target_operation(
+        &self,
+        operation: CollectionUpdateOperations,
+    ) -> Option<CollectionUpdateOperations> {
+        match operation.split_by_shard(&self.ring) {
+            OperationToShard::ByShard(by_shard) => by_shard
+                .into_iter()
+                .find(|(shard_id, _)| *shard_id == self.target_shard_id)
+                .map(|(_, operation)| operation),
+            OperationToShard::ToAll(operation) => Some(operation),
+        }
+    }
+
+    /// Create payload indexes in the target shard same as in the wrapped shard.
+    pub async fn migrate_indexes(&self) -> CollectionResult<()> {
+        let _update_lock = self.update_lock.lock().await;
+        for (index_key, index_type) in self.wrapped_shard.info().await?.payload_schema {
+            self.target_shard
+                .update(
+                    CollectionUpdateOperations::FieldIndexOperation(
+                        FieldIndexOperations::CreateIndex(CreateIndex {
+                            field_name: index_key,
+                            field_schema: Some(index_type.try_into()?),
+                        }),
+                    ),
+                    true,
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Copy points of the batch, which belong to the target shard, into the target shard.
+    /// Returns an offset of the next batch and the number of copied points.
+    pub async fn migrate_batch(
+        &self,
+        offset: Option<PointIdType>,
+        batch_size: usize,
+        runtime_handle: &Handle,
+    ) -> CollectionResult<(Option<PointIdType>, usize)> {
+        debug_assert!(batch_size > 0);
+        let limit = batch_size + 1;
+        let _update_lock = self.update_lock.lock().await;
+        let mut batch = self
+            .wrapped_shard
+            .scroll_by(
+                offset,
+                limit,
+                &WithPayloadInterface::Bool(true),
+                &true.into(),
+                None,
+                None,
+                runtime_handle,
+            )
+            .await?;
+        let next_page_offset = if batch.len() < limit {
+            // This was the last page
+            None
+        } else {
+            // remove extra point, it would be a first point of the next page
+            Some(batch.pop().unwrap().id)
+        };
+
+        let points: Result<Vec<PointStruct>, String> = batch
+            .into_iter()
+            .filter(|point| self.ring.get(&point.id) == Some(self.target_shard_id))
+            .map(|point| point.try_into())
+            .collect();
+        let points = points?;
+        let migrated = points.len();
+
+        if !points.is_empty() {
+            let insert_points_operation = CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPoints(PointInsertOperations::PointsList(points)),
+            );
+            self.target_shard
+                .update(insert_points_operation, true)
+                .await?;
+        }
+
+        Ok((next_page_offset, migrated))
+    }
+
+    pub fn deconstruct(self) -> (LocalShard, LocalShard) {
+        (self.wrapped_shard, self.target_shard)
+    }
+
+    /// Forward `create_snapshot` to `wrapped_shard`
+    pub async fn create_snapshot(
+        &self,
+        temp_path: &Path,
+        target_path: &Path,
+        save_wal: bool,
+    ) -> CollectionResult<()> {
+        self.wrapped_shard
+            .create_snapshot(temp_path, target_path, save_wal)
+            .await
+    }
+
+    pub async fn on_optimizer_config_update(&self) -> CollectionResult<()> {
+        self.wrapped_shard.on_optimizer_config_update().await?;
+        self.target_shard.on_optimizer_config_update().await
+    }
+
+    pub async fn shutdown(&self) -> CollectionResult<()> {
+        self.wrapped_shard.shutdown().await?;
+        self.target_shard.shutdown().await
+    }
+
+    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
+        self.wrapped_shard.get_telemetry_data()
+    }
+
+    pub fn search_plan(
+        &self,
+        request: &SearchPlanRequest,
+    ) -> CollectionResult<Vec<SegmentSearchPlan>> {
+        self.wrapped_shard.search_plan(request)
+    }
+}
+
+#[async_trait]
+impl ShardOperation for SplitProxyShard {
+    /// Update `wrapped_shard` and the part of the update, which belongs to the target shard
+    async fn update_with_client_id(
+        &self,
+        operation: OperationWithClientId,
+        wait: bool,
+    ) -> CollectionResult<UpdateResult> {
+        let _update_lock = self.update_lock.lock().await;
+        let result = self
+            .wrapped_shard
+            .update_with_client_id(operation.clone(), wait)
+            .await?;
+
+        let OperationWithClientId {
+            operation,
+            client_op_id,
+        } = operation;
+        if let Some(target_operation) = self.target_operation(operation) {
+            self.target_shard
+                .update_with_client_id(
+                    OperationWithClientId::new(target_operation, client_op_id),
+                    wait,
+                )
+                .await?;
+        }
+        Ok(result)
+    }
+
+    /// Update `wrapped_shard` and the target shard with the parts of the batch, which belong to it
+    async fn update_batch(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+        wait: bool,
+    ) -> CollectionResult<Vec<UpdateResult>> {
+        let _update_lock = self.update_lock.lock().await;
+        let results = self
+            .wrapped_shard
+            .update_batch(operations.clone(), wait)
+            .await?;
+
+        let target_operations: Vec<_> = operations
+            .into_iter()
+            .filter_map(|operation| self.target_operation(operation))
+            .collect();
+        if !target_operations.is_empty() {
+            self.target_shard
+                .update_batch(target_operations, wait)
+                .await?;
+        }
+        Ok(results)
+    }
+
+    /// Forward read-only `scroll_by` to `wrapped_shard`
+    async fn scroll_by(
+        &self,
+        offset: Option<ExtendedPointId>,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        order_by: Option<&OrderBy>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Vec<Record>> {
+        self.wrapped_shard
+            .scroll_by(
+                offset,
+                limit,
+                with_payload_interface,
+                with_vector,
+                filter,
+                order_by,
+                search_runtime_handle,
+            )
+            .await
+    }
+
+    async fn info(&self) -> CollectionResult<CollectionInfo> {
+        self.wrapped_shard.info().await
+    }
+
+    async fn core_search(
+        &self,
+        request: Arc<CoreSearchRequestBatch>,
+        search_runtime_handle: &Handle,
+        timeout: Option<Duration>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        self.wrapped_shard
+            .core_search(request, search_runtime_handle, timeout)
+            .await
+    }
+
+    async fn count(&self, request: Arc<CountRequest>) -> CollectionResult<CountResult> {
+        self.wrapped_shard.count(request).await
+    }
+
+    async fn facet(&self, request: Arc<FacetRequest>) -> CollectionResult<FacetResponse> {
+        self.wrapped_shard.facet(request).await
+    }
+
+    async fn retrieve(
+        &self,
+        request: Arc<PointRequest>,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+    ) -> CollectionResult<Vec<Record>> {
+        self.wrapped_shard
+            .retrieve(request, with_payload, with_vector)
+            .await
+    }
+}
@@ -1,6 +1,7 @@
 use std::cmp::max;
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use segment::common::operation_time_statistics::OperationDurationStatistics;
@@ -32,12 +33,64 @@ pub struct LocalShardTelemetry {
     pub variant_name: Option<String>,
     pub segments: Vec<SegmentTelemetry>,
     pub optimizations: OptimizerTelemetry,
+    /// Result of WAL replay on the last shard load
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wal_recovery: Option<WalRecoveryReport>,
+    /// State of the background flusher
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flush: Option<FlushTelemetry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+pub struct FlushTelemetry {
+    /// Time of the last successful flush, if any since the shard was loaded
+    pub last_flush: Option<DateTime<Utc>>,
+    /// Number of operations in WAL, which are not yet persisted in segments
+    pub unflushed_operations: u64,
+    /// Number of operations currently kept in WAL
+    #[serde(default)]
+    pub wal_length: u64,
+}
+
+/// Maximal number of failed operations listed in [`WalRecoveryReport`]
+pub const MAX_REPORTED_WAL_RECOVERY_FAILURES: usize = 32;
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+pub struct WalRecoveryReport {
+    /// Number of operations applied during WAL replay
+    pub replayed: usize,
+    /// Number of operations, already applied to the storage before the restart
+    pub skipped: usize,
+    /// Number of operations, which could not be read or applied
+    pub failed: usize,
+    /// First failed operations, up to a fixed limit
+    pub failures: Vec<WalRecoveryFailure>,
+    /// Time spent on WAL replay
+    pub duration_micros: u64,
+}
+
+impl WalRecoveryReport {
+    pub fn add_failure(&mut self, op_num: u64, error: String) {
+        self.failed += 1;
+        if self.failures.len() < MAX_REPORTED_WAL_RECOVERY_FAILURES {
+            self.failures.push(WalRecoveryFailure { op_num, error });
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
+pub struct WalRecoveryFailure {
+    pub op_num: u64,
+    pub error: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default)]
 pub struct OptimizerTelemetry {
     pub status: OptimizersStatus,
     pub optimizations: OperationDurationStatistics,
+    /// Builds of HNSW indexes during optimizations
+    #[serde(default)]
+    pub index_builds: OperationDurationStatistics,
 }
 
 impl std::ops::Add for OptimizerTelemetry {
@@ -47,6 +100,7 @@ impl std::ops::Add for OptimizerTelemetry {
         Self {
             status: max(self.status, other.status),
             optimizations: self.optimizations + other.optimizations,
+            index_builds: self.index_builds + other.index_builds,
         }
     }
 }
@@ -56,6 +110,7 @@ impl Anonymize for OptimizerTelemetry {
         Self {
             status: self.status.clone(),
             optimizations: self.optimizations.anonymize(),
+            index_builds: self.index_builds.anonymize(),
         }
     }
 }
@@ -66,6 +121,26 @@ impl Anonymize for LocalShardTelemetry {
             variant_name: self.variant_name.clone(),
             segments: self.segments.anonymize(),
             optimizations: self.optimizations.anonymize(),
+            wal_recovery: self.wal_recovery.anonymize(),
+            flush: self.flush.anonymize(),
+        }
+    }
+}
+
+impl Anonymize for FlushTelemetry {
+    fn anonymize(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl Anonymize for WalRecoveryReport {
+    fn anonymize(&self) -> Self {
+        WalRecoveryReport {
+            replayed: self.replayed,
+            skipped: self.skipped,
+            failed: self.failed,
+            failures: Default::default(),
+            duration_micros: self.duration_micros,
         }
     }
 }
@@ -1,2 +1,4 @@
 pub mod shard_transfer;
+pub mod snapshot;
 pub mod transfer_tasks_pool;
+pub mod wal_delta;
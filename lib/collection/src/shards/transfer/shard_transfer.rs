@@ -1,11 +1,13 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 
 use schemars::JsonSchema;
+use segment::types::SeqNumberType;
 use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 
@@ -16,6 +18,8 @@ use crate::shards::remote_shard::RemoteShard;
 use crate::shards::replica_set::ReplicaState;
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_holder::{LockedShardHolder, ShardHolder};
+use crate::shards::transfer::snapshot::transfer_snapshot;
+use crate::shards::transfer::wal_delta::{WalDeltaProgress, RECOVERY_POINT_OPERATIONS};
 use crate::shards::CollectionId;
 
 const TRANSFER_BATCH_SIZE: usize = 100;
@@ -30,6 +34,21 @@ pub struct ShardTransfer {
     /// If this flag is true, the is a replication related transfer of shard from 1 peer to another
     /// Shard on original peer will not be deleted in this case
     pub sync: bool,
+    /// Method to transfer the shard with, streaming records by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<ShardTransferMethod>,
+}
+
+/// Methods for transferring a shard from one node to another.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardTransferMethod {
+    /// Stream all shard records in batches until the whole shard is transferred.
+    #[default]
+    StreamRecords,
+    /// Snapshot the shard, transfer and restore it on the receiver.
+    /// Falls back to streaming records if the snapshot transfer fails.
+    Snapshot,
 }
 
 /// Unique identifier of a transfer
@@ -107,6 +126,49 @@ async fn transfer_batches(
     Ok(())
 }
 
+/// Transfer operations missed by the remote shard from WAL of the local shard.
+///
+/// Returns `false` if WAL does not contain all required operations anymore,
+/// so the whole shard should be transferred instead.
+pub(super) async fn transfer_wal_delta(
+    shard_holder: Arc<LockedShardHolder>,
+    shard_id: ShardId,
+    from_op_num: SeqNumberType,
+    stopped: Arc<AtomicBool>,
+) -> CollectionResult<bool> {
+    let mut op_num = from_op_num;
+    loop {
+        if stopped.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err(CollectionError::Cancelled {
+                description: "Transfer cancelled".to_string(),
+            });
+        }
+        let shard_holder_guard = shard_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&shard_id) else {
+            // Forward proxy gone?!
+            // That would be a programming error.
+            return Err(CollectionError::service_error(format!(
+                "Shard {shard_id} is not found"
+            )));
+        };
+
+        match replica_set
+            .transfer_wal_delta_batch(op_num, TRANSFER_BATCH_SIZE)
+            .await?
+        {
+            WalDeltaProgress::Continue(next_op_num) => op_num = next_op_num,
+            WalDeltaProgress::Finished => return Ok(true),
+            WalDeltaProgress::Unavailable if op_num == from_op_num => return Ok(false),
+            WalDeltaProgress::Unavailable => {
+                // WAL was truncated in the middle of the transfer, retry will do the full transfer
+                return Err(CollectionError::service_error(format!(
+                    "Operation {op_num} of shard {shard_id} is not available in WAL anymore"
+                )));
+            }
+        }
+    }
+}
+
 /// Return local shard back from the forward proxy
 pub async fn revert_proxy_shard_to_local(
     shard_holder: &ShardHolder,
@@ -192,23 +254,41 @@ pub async fn handle_transferred_shard_proxy(
     Ok(true)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn transfer_shard(
     shard_holder: Arc<LockedShardHolder>,
     shard_id: ShardId,
     collection_id: CollectionId,
     peer_id: PeerId,
     channel_service: ChannelService,
+    wal_delta: bool,
+    method: ShardTransferMethod,
+    temp_dir: &Path,
     stopped: Arc<AtomicBool>,
 ) -> CollectionResult<()> {
     // Initiate shard on a remote peer
-    let remote_shard = RemoteShard::new(shard_id, collection_id.clone(), peer_id, channel_service);
-
-    // ToDo: Initial fast file-based transfer (optional)
-    // * Create shard snapshot - save the latest version of point updates in the snapshot
-    // * Initiate shard, use snapshot link for initialization
-    // * Transfer difference between snapshot and current shard state
+    let remote_shard = RemoteShard::new(
+        shard_id,
+        collection_id.clone(),
+        peer_id,
+        channel_service.clone(),
+    );
 
     remote_shard.initiate_transfer().await?;
+
+    // Remote shard must report its state before it receives forwarded updates
+    let recovery_point = if wal_delta {
+        remote_shard
+            .recovery_point(RECOVERY_POINT_OPERATIONS)
+            .await
+            .unwrap_or_else(|err| {
+                log::warn!("Can't get recovery point of shard {shard_id} on peer {peer_id}: {err}");
+                Vec::new()
+            })
+    } else {
+        Vec::new()
+    };
+
     {
         let shard_holder_guard = shard_holder.read().await;
         let transferring_shard = shard_holder_guard.get_shard(&shard_id);
@@ -220,6 +300,61 @@ pub async fn transfer_shard(
             )));
         }
     };
+
+    if !recovery_point.is_empty() {
+        let from_op_num = {
+            let shard_holder_guard = shard_holder.read().await;
+            let Some(replica_set) = shard_holder_guard.get_shard(&shard_id) else {
+                return Err(CollectionError::service_error(format!(
+                    "Shard {shard_id} is not found"
+                )));
+            };
+            replica_set.find_wal_recovery_point(&recovery_point).await?
+        };
+        match from_op_num {
+            Some(from_op_num) => {
+                if transfer_wal_delta(shard_holder.clone(), shard_id, from_op_num, stopped.clone())
+                    .await?
+                {
+                    log::debug!("Shard {shard_id} is recovered on peer {peer_id} from WAL delta");
+                    return Ok(());
+                }
+                log::info!(
+                    "WAL of shard {shard_id} does not contain operations starting from {from_op_num}, \
+                     transferring the whole shard to peer {peer_id}"
+                );
+            }
+            None => log::info!(
+                "Last operations of shard {shard_id} on peer {peer_id} are not found in WAL, \
+                 transferring the whole shard"
+            ),
+        }
+    }
+
+    if method == ShardTransferMethod::Snapshot {
+        // Proxy owns the first remote shard, use another one to upload the snapshot
+        let remote_shard = RemoteShard::new(shard_id, collection_id, peer_id, channel_service);
+        let result = transfer_snapshot(
+            shard_holder.clone(),
+            shard_id,
+            &remote_shard,
+            temp_dir,
+            stopped.clone(),
+        )
+        .await;
+        match result {
+            Ok(()) => {
+                log::debug!("Shard {shard_id} is recovered on peer {peer_id} from snapshot");
+                return Ok(());
+            }
+            Err(err @ CollectionError::Cancelled { .. }) => return Err(err),
+            Err(err) => log::warn!(
+                "Failed to transfer shard {shard_id} to peer {peer_id} by snapshot, \
+                 streaming records instead: {err}"
+            ),
+        }
+    }
+
     // Transfer contents batch by batch
     transfer_batches(shard_holder.clone(), shard_id, stopped.clone()).await
 }
@@ -453,6 +588,8 @@ pub fn spawn_transfer_task<T, F>(
     transfer: ShardTransfer,
     collection_id: CollectionId,
     channel_service: ChannelService,
+    wal_delta: bool,
+    temp_dir: PathBuf,
     on_finish: T,
     on_error: F,
 ) -> StoppableAsyncTaskHandle<bool>
@@ -470,6 +607,9 @@ where
                 collection_id.clone(),
                 transfer.to,
                 channel_service.clone(),
+                wal_delta,
+                transfer.method.unwrap_or_default(),
+                &temp_dir,
                 stopped.clone(),
             )
             .await;
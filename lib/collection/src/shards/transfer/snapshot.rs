@@ -0,0 +1,372 @@
+use std::io::{self, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use segment::common::snapshot_manifest::FileManifest;
+use tar::Builder as TarBuilder;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::local_shard::LocalShard;
+use crate::shards::remote_shard::RemoteShard;
+use crate::shards::shard::ShardId;
+use crate::shards::shard_holder::LockedShardHolder;
+use crate::shards::transfer::shard_transfer::transfer_wal_delta;
+
+/// Size of the snapshot archive chunks, sent between peers
+const SNAPSHOT_CHUNK_SIZE: usize = 1024 * 1024;
+const CHUNK_RETRY_TIMEOUT: Duration = Duration::from_secs(1);
+const CHUNK_RETRY_COUNT: usize = 3;
+
+/// Transfer the shard to the remote peer by snapshot.
+///
+/// The snapshot of the local shard is uploaded to the remote peer in chunks and recovered there.
+/// Operations applied to the local shard after the snapshot was taken are transferred from WAL.
+///
+/// Local shard must be wrapped into a forward proxy to `remote_shard` already,
+/// so that all new updates are forwarded to the remote peer during the transfer.
+pub(super) async fn transfer_snapshot(
+    shard_holder: Arc<LockedShardHolder>,
+    shard_id: ShardId,
+    remote_shard: &RemoteShard,
+    temp_dir: &Path,
+    stopped: Arc<AtomicBool>,
+) -> CollectionResult<()> {
+    let snapshot_dir = temp_dir.join(format!("shard-{shard_id}-snapshot"));
+    let archive_path = snapshot_dir.with_extension("tar");
+
+    let result = send_snapshot(
+        shard_holder,
+        shard_id,
+        remote_shard,
+        &snapshot_dir,
+        &archive_path,
+        stopped,
+    )
+    .await;
+
+    if snapshot_dir.exists() {
+        if let Err(err) = tokio::fs::remove_dir_all(&snapshot_dir).await {
+            log::warn!("Failed to remove shard snapshot directory {snapshot_dir:?}: {err}");
+        }
+    }
+    if archive_path.exists() {
+        if let Err(err) = tokio::fs::remove_file(&archive_path).await {
+            log::warn!("Failed to remove shard snapshot archive {archive_path:?}: {err}");
+        }
+    }
+
+    result
+}
+
+async fn send_snapshot(
+    shard_holder: Arc<LockedShardHolder>,
+    shard_id: ShardId,
+    remote_shard: &RemoteShard,
+    snapshot_dir: &Path,
+    archive_path: &Path,
+    stopped: Arc<AtomicBool>,
+) -> CollectionResult<()> {
+    let shard_snapshot_dir = snapshot_dir.join("shard");
+
+    let (from_op_num, _wal_retention) = {
+        let shard_holder_guard = shard_holder.read().await;
+        let Some(replica_set) = shard_holder_guard.get_shard(&shard_id) else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {shard_id} is not found"
+            )));
+        };
+
+        // Snapshot contains all operations up to the last applied one, the following operations
+        // are transferred from WAL. The last applied one is replayed too, since it may be applied
+        // to some of the segments only. Replayed operations get new numbers on the remote shard,
+        // so operations already in the snapshot are applied again, in the same order as before.
+        let Some(from_op_num) = replica_set.local_last_applied_op_num().await else {
+            return Err(CollectionError::service_error(format!(
+                "Shard {shard_id} has no local replica to snapshot"
+            )));
+        };
+        let wal_retention = replica_set.retain_wal_from(from_op_num).await?;
+
+        replica_set
+            .create_snapshot(&snapshot_dir.join("temp"), &shard_snapshot_dir, false)
+            .await?;
+        (from_op_num, wal_retention)
+    };
+
+    let manifest = {
+        let archive_path = archive_path.to_owned();
+        tokio::task::spawn_blocking(move || {
+            // have to use std here, cause TarBuilder is not async
+            let file = std::fs::File::create(&archive_path)?;
+            let mut builder = TarBuilder::new(file);
+            builder.append_dir_all(".", &shard_snapshot_dir)?;
+            builder.into_inner()?.sync_all()?;
+            Ok::<_, CollectionError>(FileManifest::of_file(&archive_path)?)
+        })
+        .await??
+    };
+
+    upload_archive(remote_shard, archive_path, manifest.size, &stopped).await?;
+    remote_shard
+        .recover_snapshot(manifest.size, &manifest.checksum)
+        .await?;
+
+    if !transfer_wal_delta(shard_holder, shard_id, from_op_num, stopped).await? {
+        return Err(CollectionError::service_error(format!(
+            "Operation {from_op_num} of shard {shard_id} is not available in WAL anymore"
+        )));
+    }
+    Ok(())
+}
+
+/// Upload the archive chunk by chunk.
+///
+/// Remote peer reports how many bytes of the archive it has received,
+/// the upload is resumed from that position after a failed chunk.
+async fn upload_archive(
+    remote_shard: &RemoteShard,
+    archive_path: &Path,
+    size: u64,
+    stopped: &AtomicBool,
+) -> CollectionResult<()> {
+    let mut archive = tokio::fs::File::open(archive_path).await?;
+    let mut buffer = vec![0; SNAPSHOT_CHUNK_SIZE];
+    let mut offset = 0;
+    let mut failures = 0;
+
+    while offset < size {
+        if stopped.load(Ordering::Relaxed) {
+            return Err(CollectionError::Cancelled {
+                description: "Transfer cancelled".to_string(),
+            });
+        }
+
+        let chunk = &mut buffer[..(size - offset).min(SNAPSHOT_CHUNK_SIZE as u64) as usize];
+        archive.seek(SeekFrom::Start(offset)).await?;
+        archive.read_exact(chunk).await?;
+
+        let error = match remote_shard
+            .upload_snapshot_chunk(offset, chunk, seahash::hash(chunk))
+            .await
+        {
+            Ok(received) if received > size => {
+                return Err(CollectionError::service_error(format!(
+                    "Peer {} received {received} bytes of the snapshot of {size} bytes",
+                    remote_shard.peer_id
+                )));
+            }
+            Ok(received) if received > offset => {
+                offset = received;
+                failures = 0;
+                continue;
+            }
+            Ok(received) => {
+                // Remote peer lost a part of the archive, resume from the end of the received part
+                let error = CollectionError::service_error(format!(
+                    "Peer {} expects the snapshot chunk at {received}, but not at {offset}",
+                    remote_shard.peer_id
+                ));
+                offset = received;
+                error
+            }
+            Err(err @ CollectionError::Cancelled { .. }) => return Err(err),
+            Err(err) => err,
+        };
+
+        failures += 1;
+        if failures > CHUNK_RETRY_COUNT {
+            return Err(error);
+        }
+        log::debug!("Retrying snapshot chunk upload at {offset}: {error}");
+        tokio::time::sleep(CHUNK_RETRY_TIMEOUT * failures as u32).await;
+    }
+    Ok(())
+}
+
+/// Write the chunk of the snapshot archive, uploaded by the source peer.
+///
+/// A chunk overwrites everything received after its offset, so a chunk can be safely resent
+/// if the response was lost. Chunks after a gap are not written.
+///
+/// Returns the number of received bytes, the next chunk is expected at this offset.
+pub async fn receive_snapshot_chunk(
+    archive_path: &Path,
+    offset: u64,
+    data: &[u8],
+    checksum: u64,
+) -> CollectionResult<u64> {
+    if seahash::hash(data) != checksum {
+        return Err(CollectionError::bad_input(format!(
+            "Checksum mismatch of the snapshot chunk at {offset}"
+        )));
+    }
+
+    let received = match tokio::fs::metadata(archive_path).await {
+        Ok(metadata) => metadata.len(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => 0,
+        Err(err) => return Err(err.into()),
+    };
+    if offset > received {
+        return Ok(received);
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(archive_path)
+        .await?;
+    file.set_len(offset).await?;
+    file.seek(SeekFrom::Start(offset)).await?;
+    file.write_all(data).await?;
+    file.sync_data().await?;
+    Ok(offset + data.len() as u64)
+}
+
+/// Verify the received snapshot archive and unpack the shard from it into `shard_path`
+pub async fn unpack_snapshot(
+    archive_path: &Path,
+    size: u64,
+    checksum: &str,
+    shard_path: &Path,
+) -> CollectionResult<()> {
+    unpack_archive(archive_path, size, checksum, shard_path).await?;
+
+    let shard_path = shard_path.to_owned();
+    tokio::task::spawn_blocking(move || LocalShard::restore_snapshot(&shard_path)).await?
+}
+
+/// Verify the archive and unpack it into `target_path` as is
+async fn unpack_archive(
+    archive_path: &Path,
+    size: u64,
+    checksum: &str,
+    target_path: &Path,
+) -> CollectionResult<()> {
+    let archive_path = archive_path.to_owned();
+    let checksum = checksum.to_string();
+    let target_path: PathBuf = target_path.to_owned();
+
+    tokio::task::spawn_blocking(move || {
+        let manifest = FileManifest::of_file(&archive_path)?;
+        if manifest.size != size || manifest.checksum != checksum {
+            return Err(CollectionError::bad_input(format!(
+                "Snapshot archive is corrupted: expected {size} bytes with checksum {checksum}, \
+                 received {} bytes with checksum {}",
+                manifest.size, manifest.checksum
+            )));
+        }
+
+        if target_path.exists() {
+            std::fs::remove_dir_all(&target_path)?;
+        }
+        let mut archive = tar::Archive::new(std::fs::File::open(&archive_path)?);
+        archive.unpack(&target_path)?;
+        Ok(())
+    })
+    .await?
+}
+
+/// Read the chunk of the snapshot archive at `offset`, which is downloaded by another peer.
+///
+/// Returns an empty chunk at the end of the archive.
+pub async fn read_snapshot_chunk(archive_path: &Path, offset: u64) -> CollectionResult<Vec<u8>> {
+    let mut archive = tokio::fs::File::open(archive_path).await?;
+    let size = archive.metadata().await?.len();
+    if offset > size {
+        return Err(CollectionError::bad_input(format!(
+            "Snapshot chunk at {offset} is out of the archive of {size} bytes"
+        )));
+    }
+
+    let mut chunk = vec![0; (size - offset).min(SNAPSHOT_CHUNK_SIZE as u64) as usize];
+    archive.seek(SeekFrom::Start(offset)).await?;
+    archive.read_exact(&mut chunk).await?;
+    Ok(chunk)
+}
+
+/// Download the snapshot of the shard from the remote peer and unpack it into `target_path`.
+///
+/// Segments are left archived, same as in the snapshot of the local shard.
+pub async fn download_snapshot(
+    remote_shard: &RemoteShard,
+    temp_dir: &Path,
+    target_path: &Path,
+) -> CollectionResult<()> {
+    let (name, manifest) = remote_shard.create_snapshot_archive().await?;
+    let archive_path = temp_dir.join(&name);
+
+    let result = async {
+        download_archive(remote_shard, &name, &archive_path, manifest.size).await?;
+        unpack_archive(
+            &archive_path,
+            manifest.size,
+            &manifest.checksum,
+            target_path,
+        )
+        .await
+    }
+    .await;
+
+    if let Err(err) = remote_shard.delete_snapshot_archive(&name).await {
+        log::warn!(
+            "Failed to delete snapshot archive {name} on peer {}: {err}",
+            remote_shard.peer_id
+        );
+    }
+    if archive_path.exists() {
+        if let Err(err) = tokio::fs::remove_file(&archive_path).await {
+            log::warn!("Failed to remove shard snapshot archive {archive_path:?}: {err}");
+        }
+    }
+
+    result
+}
+
+/// Download the archive chunk by chunk, a failed chunk is requested again
+async fn download_archive(
+    remote_shard: &RemoteShard,
+    name: &str,
+    archive_path: &Path,
+    size: u64,
+) -> CollectionResult<()> {
+    let mut archive = tokio::fs::File::create(archive_path).await?;
+    let mut offset = 0;
+    let mut failures = 0;
+
+    while offset < size {
+        let error = match remote_shard.get_snapshot_chunk(name, offset).await {
+            Ok((data, _)) if data.is_empty() => CollectionError::service_error(format!(
+                "Snapshot archive {name} on peer {} ended at {offset} of {size} bytes",
+                remote_shard.peer_id
+            )),
+            Ok((data, checksum)) if seahash::hash(&data) != checksum => {
+                CollectionError::service_error(format!(
+                    "Checksum mismatch of the snapshot chunk at {offset} from peer {}",
+                    remote_shard.peer_id
+                ))
+            }
+            Ok((data, _)) => {
+                archive.write_all(&data).await?;
+                offset += data.len() as u64;
+                failures = 0;
+                continue;
+            }
+            Err(err) => err,
+        };
+
+        failures += 1;
+        if failures > CHUNK_RETRY_COUNT {
+            return Err(error);
+        }
+        log::debug!("Retrying snapshot chunk download at {offset}: {error}");
+        tokio::time::sleep(CHUNK_RETRY_TIMEOUT * failures as u32).await;
+    }
+
+    archive.sync_all().await?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use segment::types::SeqNumberType;
+
+use crate::operations::types::CollectionResult;
+use crate::shards::local_shard::LocalShard;
+use crate::shards::shard_trait::ShardOperation;
+
+/// Number of the last operations of the recovered replica, which are looked up in WAL of the source
+/// replica to find the recovery point. Replicas may apply concurrent operations in different order.
+pub const RECOVERY_POINT_OPERATIONS: usize = 16;
+
+/// Progress of the shard recovery from WAL delta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalDeltaProgress {
+    /// There are more operations to transfer, starting from the given one
+    Continue(SeqNumberType),
+    /// All operations from WAL are transferred
+    Finished,
+    /// WAL of the source shard does not contain required operations anymore
+    Unavailable,
+}
+
+/// Transfer a batch of operations from WAL of the `source` shard to the `target` shard.
+///
+/// Operations are applied in the same order they were written into the WAL,
+/// the last operation of the batch is awaited to be applied.
+/// Operations, which the `target` already has, are skipped by their client ids.
+///
+/// Caller is responsible for blocking updates of the `source` shard during the batch transfer,
+/// so that newer operations are not overwritten by the older ones from WAL.
+pub async fn transfer_wal_delta_batch(
+    source: &LocalShard,
+    target: &(impl ShardOperation + ?Sized),
+    from_op_num: SeqNumberType,
+    batch_size: usize,
+) -> CollectionResult<WalDeltaProgress> {
+    debug_assert!(batch_size > 0);
+    let Some(batch) = source.read_wal_delta(from_op_num, batch_size)? else {
+        return Ok(WalDeltaProgress::Unavailable);
+    };
+
+    let Some(&(last_op_num, _)) = batch.last() else {
+        return Ok(WalDeltaProgress::Finished);
+    };
+    let is_last_batch = batch.len() < batch_size;
+
+    let batch_len = batch.len();
+    for (i, (_op_num, operation)) in batch.into_iter().enumerate() {
+        let wait = i + 1 == batch_len;
+        target.update_with_client_id(operation, wait).await?;
+    }
+
+    if is_last_batch {
+        Ok(WalDeltaProgress::Finished)
+    } else {
+        Ok(WalDeltaProgress::Continue(last_op_num + 1))
+    }
+}
@@ -1,7 +1,11 @@
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
+use segment::common::operation_time_statistics::OperationDurationStatistics;
+use segment::common::search_stats::SearchStats;
 use serde::{Deserialize, Serialize};
 
+use crate::common::filter_usage::PayloadIndexSuggestion;
+use crate::common::rate_limiting::RateLimitsTelemetry;
 use crate::config::CollectionConfig;
 use crate::operations::types::ShardTransferInfo;
 use crate::shards::telemetry::ReplicaSetTelemetry;
@@ -13,6 +17,21 @@ pub struct CollectionTelemetry {
     pub config: CollectionConfig,
     pub shards: Vec<ReplicaSetTelemetry>,
     pub transfers: Vec<ShardTransferInfo>,
+    /// Searches, requested from this peer
+    #[serde(default)]
+    pub searches: OperationDurationStatistics,
+    /// Updates, requested from this peer
+    #[serde(default)]
+    pub updates: OperationDurationStatistics,
+    /// Work done by searches of the local shards
+    #[serde(default, skip_serializing_if = "SearchStats::is_empty")]
+    pub search_stats: SearchStats,
+    /// Payload fields, filtered without a payload index by reads, requested from this peer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub index_suggestions: Vec<PayloadIndexSuggestion>,
+    /// Utilization of `max_concurrent_searches` and `max_updates_per_second` on this peer
+    #[serde(default)]
+    pub rate_limits: RateLimitsTelemetry,
 }
 
 impl CollectionTelemetry {
@@ -34,6 +53,11 @@ impl Anonymize for CollectionTelemetry {
             init_time_ms: self.init_time_ms,
             shards: self.shards.anonymize(),
             transfers: vec![],
+            searches: self.searches.anonymize(),
+            updates: self.updates.anonymize(),
+            search_stats: self.search_stats.anonymize(),
+            index_suggestions: vec![],
+            rate_limits: self.rate_limits.clone(),
         }
     }
 }
@@ -46,6 +70,7 @@ impl Anonymize for CollectionConfig {
             optimizer_config: self.optimizer_config.clone(),
             wal_config: self.wal_config.clone(),
             quantization_config: self.quantization_config.clone(),
+            strict_mode_config: self.strict_mode_config,
         }
     }
 }
@@ -1,4 +1,6 @@
 mod snapshot_test;
+mod snapshot_transfer_test;
+mod wal_delta_transfer_test;
 mod wal_recovery_test;
 
 use std::sync::Arc;
@@ -15,6 +17,9 @@ use crate::collection_manager::fixtures::{
     get_indexing_optimizer, get_merge_optimizer, random_segment,
 };
 use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
+use crate::collection_manager::optimizers::optimization_tasks::{
+    OptimizationTaskStatus, OptimizationTasks,
+};
 use crate::update_handler::{Optimizer, UpdateHandler};
 
 #[tokio::test]
@@ -46,14 +51,24 @@ async fn test_optimization_process() {
     let optimizers = Arc::new(vec![merge_optimizer, indexing_optimizer]);
 
     let segments: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
-    let handles = UpdateHandler::launch_optimization(optimizers.clone(), segments.clone(), |_| {});
+    let optimization_tasks = OptimizationTasks::new(Duration::from_secs(60));
+    let handles = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        segments.clone(),
+        &optimization_tasks,
+        |_| {},
+    );
 
     assert_eq!(handles.len(), 2);
 
     let join_res = join_all(handles.into_iter().map(|x| x.join_handle).collect_vec()).await;
 
-    let handles_2 =
-        UpdateHandler::launch_optimization(optimizers.clone(), segments.clone(), |_| {});
+    let handles_2 = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        segments.clone(),
+        &optimization_tasks,
+        |_| {},
+    );
 
     assert_eq!(handles_2.len(), 0);
 
@@ -62,6 +77,13 @@ async fn test_optimization_process() {
         assert_eq!(res.unwrap(), Some(true));
     }
 
+    let tasks = optimization_tasks.list();
+    assert_eq!(tasks.len(), 2);
+    for task in tasks {
+        assert_eq!(task.status, OptimizationTaskStatus::Done);
+        assert_eq!(task.progress, Some(1.0));
+    }
+
     assert_eq!(segments.read().len(), 4);
 
     assert!(segments.read().get(segment_to_index).is_none());
@@ -91,7 +113,13 @@ async fn test_cancel_optimization() {
     let now = Instant::now();
 
     let segments: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
-    let handles = UpdateHandler::launch_optimization(optimizers.clone(), segments.clone(), |_| {});
+    let optimization_tasks = OptimizationTasks::new(Duration::from_secs(60));
+    let handles = UpdateHandler::launch_optimization(
+        optimizers.clone(),
+        segments.clone(),
+        &optimization_tasks,
+        |_| {},
+    );
 
     sleep(Duration::from_millis(100)).await;
 
@@ -113,6 +141,11 @@ async fn test_cancel_optimization() {
             LockedSegment::Proxy(_) => panic!("segment is not restored"),
         }
     }
+
+    optimization_tasks.cancel_unfinished("optimizations are stopped");
+    for task in optimization_tasks.list() {
+        assert_eq!(task.status, OptimizationTaskStatus::Cancelled);
+    }
 }
 
 #[test]
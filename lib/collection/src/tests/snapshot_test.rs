@@ -23,6 +23,7 @@ pub const TEST_OPTIMIZERS_CONFIG: OptimizersConfig = OptimizersConfig {
     indexing_threshold: Some(50_000),
     flush_interval_sec: 30,
     max_optimization_threads: 2,
+    merge_buffer_size: None,
 };
 
 pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -37,6 +38,7 @@ async fn _test_snapshot_collection(node_type: NodeType) {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -46,11 +48,18 @@ async fn _test_snapshot_collection(node_type: NodeType) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
+            multivector_config: None,
         }),
         shard_number: NonZeroU32::new(4).unwrap(),
+        sharding_method: None,
         replication_factor: NonZeroU32::new(3).unwrap(),
         write_consistency_factor: NonZeroU32::new(2).unwrap(),
         on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
     };
 
     let config = CollectionConfig {
@@ -59,6 +68,7 @@ async fn _test_snapshot_collection(node_type: NodeType) {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
     };
 
     let snapshots_path = Builder::new().prefix("test_snapshots").tempdir().unwrap();
@@ -99,7 +109,7 @@ async fn _test_snapshot_collection(node_type: NodeType) {
 
     let snapshots_temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
     let snapshot_description = collection
-        .create_snapshot(snapshots_temp_dir.path(), 0)
+        .create_snapshot(snapshots_temp_dir.path(), 0, &[])
         .await
         .unwrap();
 
@@ -133,7 +143,8 @@ async fn _test_snapshot_collection(node_type: NodeType) {
         None,
         None,
     )
-    .await;
+    .await
+    .unwrap();
 
     {
         let shards_holder = &recovered_collection.shards_holder.read().await;
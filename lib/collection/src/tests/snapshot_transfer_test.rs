@@ -0,0 +1,304 @@
+use std::num::{NonZeroU32, NonZeroU64};
+use std::path::Path;
+use std::sync::Arc;
+
+use segment::common::snapshot_manifest::FileManifest;
+use segment::types::{Distance, PointIdType, ScoredPoint, WithPayloadInterface};
+use serde_json::json;
+use tempfile::Builder;
+use tokio::runtime::Handle;
+use tokio::sync::RwLock;
+
+use crate::config::{CollectionConfig, CollectionParams, WalConfig};
+use crate::operations::point_ops::{PointOperations, PointStruct};
+use crate::operations::types::{
+    CoreSearchRequestBatch, Record, SearchRequest, VectorParams, VectorsConfig,
+};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::local_shard::LocalShard;
+use crate::shards::shard_trait::ShardOperation;
+use crate::shards::transfer::snapshot::{
+    read_snapshot_chunk, receive_snapshot_chunk, unpack_snapshot,
+};
+use crate::shards::transfer::wal_delta::{transfer_wal_delta_batch, WalDeltaProgress};
+use crate::tests::snapshot_test::TEST_OPTIMIZERS_CONFIG;
+
+const CHUNK_SIZE: usize = 4 * 1024;
+
+fn create_collection_config() -> CollectionConfig {
+    let wal_config = WalConfig {
+        wal_capacity_mb: 1,
+        wal_segments_ahead: 0,
+        wal_retain_ops: 0,
+        ..Default::default()
+    };
+
+    let collection_params = CollectionParams {
+        vectors: VectorsConfig::Single(VectorParams {
+            size: NonZeroU64::new(4).unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype: None,
+            multivector_config: None,
+        }),
+        shard_number: NonZeroU32::new(1).unwrap(),
+        sharding_method: None,
+        replication_factor: NonZeroU32::new(1).unwrap(),
+        write_consistency_factor: NonZeroU32::new(1).unwrap(),
+        on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
+    };
+
+    let mut optimizer_config = TEST_OPTIMIZERS_CONFIG.clone();
+
+    optimizer_config.default_segment_number = 2;
+    optimizer_config.flush_interval_sec = 0;
+
+    CollectionConfig {
+        params: collection_params,
+        optimizer_config,
+        wal_config,
+        hnsw_config: Default::default(),
+        quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+    }
+}
+
+fn upsert_operation(id: u64, version: u64) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(
+        vec![PointStruct {
+            id: id.into(),
+            vector: vec![id as f32, version as f32, 1.0, 2.0].into(),
+            payload: Some(serde_json::from_value(json!({ "version": version })).unwrap()),
+        }]
+        .into(),
+    )
+}
+
+fn delete_operation(id: u64) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: vec![PointIdType::from(id)],
+    })
+}
+
+/// Points of the shard without versions, versions are assigned by each shard separately
+async fn all_points(shard: &LocalShard) -> Vec<Record> {
+    shard
+        .scroll_by(
+            None,
+            1000,
+            &WithPayloadInterface::Bool(true),
+            &true.into(),
+            None,
+            None,
+            &Handle::current(),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|record| Record {
+            version: None,
+            ..record
+        })
+        .collect()
+}
+
+async fn search(shard: &LocalShard) -> Vec<Vec<ScoredPoint>> {
+    let searches = [vec![1.0, 0.0, 0.0, 0.0], vec![0.0, 1.0, 1.0, 1.0]]
+        .into_iter()
+        .map(|vector| {
+            SearchRequest {
+                vector: vector.into(),
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: None,
+                filter: None,
+                params: None,
+                limit: 10,
+                offset: 0,
+                score_threshold: None,
+            }
+            .into()
+        })
+        .collect();
+    shard
+        .core_search(
+            Arc::new(CoreSearchRequestBatch { searches }),
+            &Handle::current(),
+            None,
+        )
+        .await
+        .unwrap()
+}
+
+/// Pack the shard snapshot into an archive, same as the source peer does
+fn pack_snapshot(snapshot_path: &Path, archive_path: &Path) -> FileManifest {
+    let mut builder = tar::Builder::new(std::fs::File::create(archive_path).unwrap());
+    builder.append_dir_all(".", snapshot_path).unwrap();
+    builder.into_inner().unwrap();
+    FileManifest::of_file(archive_path).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot_transfer() {
+    let source_dir = Builder::new().prefix("test_source").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("test_temp").tempdir().unwrap();
+    let target_dir = Builder::new().prefix("test_target").tempdir().unwrap();
+
+    let config = Arc::new(RwLock::new(create_collection_config()));
+    let source = LocalShard::build(
+        0,
+        "test".to_string(),
+        source_dir.path(),
+        config.clone(),
+        Arc::new(Default::default()),
+        Handle::current(),
+    )
+    .await
+    .unwrap();
+
+    for id in 0..100 {
+        source.update(upsert_operation(id, 0), true).await.unwrap();
+    }
+    for id in 0..10 {
+        source.update(delete_operation(id), true).await.unwrap();
+    }
+
+    // Operations after the snapshot must stay in WAL until they are transferred
+    let from_op_num = source.last_applied_op_num();
+    let _wal_retention = source.retain_wal_from(from_op_num);
+
+    let snapshot_path = temp_dir.path().join("shard");
+    source
+        .create_snapshot(&temp_dir.path().join("temp"), &snapshot_path, false)
+        .await
+        .unwrap();
+    let source_archive = temp_dir.path().join("shard.tar");
+    let manifest = pack_snapshot(&snapshot_path, &source_archive);
+
+    // Source shard is updated while the snapshot is transferred
+    for id in 50..150 {
+        source.update(upsert_operation(id, 1), true).await.unwrap();
+    }
+    for id in 10..20 {
+        source.update(delete_operation(id), true).await.unwrap();
+    }
+
+    let archive = std::fs::read(&source_archive).unwrap();
+    assert_eq!(archive.len() as u64, manifest.size);
+    assert!(archive.len() > 2 * CHUNK_SIZE);
+
+    let target_archive = target_dir.path().join("shard.tar");
+    let mut offset = 0;
+    for (i, chunk) in archive.chunks(CHUNK_SIZE).enumerate() {
+        let received = receive_snapshot_chunk(&target_archive, offset, chunk, seahash::hash(chunk))
+            .await
+            .unwrap();
+        assert_eq!(received, offset + chunk.len() as u64);
+
+        if i == 1 {
+            // Chunk is resent, if the response is lost
+            let resent =
+                receive_snapshot_chunk(&target_archive, offset, chunk, seahash::hash(chunk))
+                    .await
+                    .unwrap();
+            assert_eq!(resent, received);
+
+            // Chunk after a gap is not written
+            let skipped = receive_snapshot_chunk(
+                &target_archive,
+                received + CHUNK_SIZE as u64,
+                chunk,
+                seahash::hash(chunk),
+            )
+            .await
+            .unwrap();
+            assert_eq!(skipped, received);
+
+            // Corrupted chunk is rejected
+            assert!(receive_snapshot_chunk(&target_archive, received, chunk, 0)
+                .await
+                .is_err());
+        }
+        offset = received;
+    }
+
+    let target_path = target_dir.path().join("shard");
+    assert!(unpack_snapshot(
+        &target_archive,
+        manifest.size + 1,
+        &manifest.checksum,
+        &target_path
+    )
+    .await
+    .is_err());
+    unpack_snapshot(
+        &target_archive,
+        manifest.size,
+        &manifest.checksum,
+        &target_path,
+    )
+    .await
+    .unwrap();
+
+    let target = LocalShard::load(
+        0,
+        "test".to_string(),
+        &target_path,
+        config.clone(),
+        Arc::new(Default::default()),
+        Handle::current(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(target.info().await.unwrap().points_count, 90);
+
+    // Replay operations applied to the source after the snapshot
+    let mut op_num = from_op_num;
+    loop {
+        match transfer_wal_delta_batch(&source, &target, op_num, 16)
+            .await
+            .unwrap()
+        {
+            WalDeltaProgress::Continue(next_op_num) => op_num = next_op_num,
+            progress => {
+                assert_eq!(progress, WalDeltaProgress::Finished);
+                break;
+            }
+        }
+    }
+
+    let source_points = all_points(&source).await;
+    assert_eq!(source_points.len(), 130);
+    assert_eq!(all_points(&target).await, source_points);
+    assert_eq!(search(&target).await, search(&source).await);
+}
+
+#[tokio::test]
+async fn test_read_snapshot_chunks() {
+    let temp_dir = Builder::new().prefix("test_archive").tempdir().unwrap();
+    let archive_path = temp_dir.path().join("shard.tar");
+    let archive: Vec<u8> = (0..5 * 1024 * 1024 / 2).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&archive_path, &archive).unwrap();
+
+    // Archive is downloaded in chunks until an empty chunk
+    let mut downloaded = Vec::new();
+    loop {
+        let chunk = read_snapshot_chunk(&archive_path, downloaded.len() as u64)
+            .await
+            .unwrap();
+        if chunk.is_empty() {
+            break;
+        }
+        downloaded.extend(chunk);
+    }
+    assert_eq!(downloaded, archive);
+
+    assert!(read_snapshot_chunk(&archive_path, archive.len() as u64 + 1)
+        .await
+        .is_err());
+}
@@ -0,0 +1,378 @@
+use std::num::{NonZeroU32, NonZeroU64};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use segment::types::{Distance, WithPayloadInterface};
+use serde_json::json;
+use tempfile::Builder;
+use tokio::runtime::Handle;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+use crate::config::{CollectionConfig, CollectionParams, WalConfig};
+use crate::operations::point_ops::PointStruct;
+use crate::operations::types::{Record, VectorParams, VectorsConfig};
+use crate::operations::{CollectionUpdateOperations, OperationWithClientId};
+use crate::shards::local_shard::LocalShard;
+use crate::shards::shard_trait::ShardOperation;
+use crate::shards::transfer::wal_delta::{
+    transfer_wal_delta_batch, WalDeltaProgress, RECOVERY_POINT_OPERATIONS,
+};
+use crate::tests::snapshot_test::TEST_OPTIMIZERS_CONFIG;
+
+const BATCH_SIZE: usize = 4;
+
+fn create_collection_config(wal_retain_ops: u64) -> CollectionConfig {
+    let wal_config = WalConfig {
+        wal_capacity_mb: 1,
+        wal_segments_ahead: 0,
+        wal_retain_ops,
+        ..Default::default()
+    };
+
+    let collection_params = CollectionParams {
+        vectors: VectorsConfig::Single(VectorParams {
+            size: NonZeroU64::new(4).unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype: None,
+            multivector_config: None,
+        }),
+        shard_number: NonZeroU32::new(1).unwrap(),
+        sharding_method: None,
+        replication_factor: NonZeroU32::new(1).unwrap(),
+        write_consistency_factor: NonZeroU32::new(1).unwrap(),
+        on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
+    };
+
+    let mut optimizer_config = TEST_OPTIMIZERS_CONFIG.clone();
+
+    optimizer_config.default_segment_number = 1;
+    optimizer_config.flush_interval_sec = 0;
+
+    CollectionConfig {
+        params: collection_params,
+        optimizer_config,
+        wal_config,
+        hnsw_config: Default::default(),
+        quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+    }
+}
+
+async fn build_shard(path: &Path, config: &CollectionConfig) -> LocalShard {
+    LocalShard::build(
+        0,
+        "test".to_string(),
+        path,
+        Arc::new(RwLock::new(config.clone())),
+        Arc::new(Default::default()),
+        Handle::current(),
+    )
+    .await
+    .unwrap()
+}
+
+fn upsert_operation(id: u64, version: u64) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(
+        vec![PointStruct {
+            id: id.into(),
+            vector: vec![version as f32, 1.0, 2.0, 3.0].into(),
+            payload: Some(serde_json::from_value(json!({ "version": version })).unwrap()),
+        }]
+        .into(),
+    )
+}
+
+/// Operation as replicas receive it, with the same client id
+fn replicated_operation(id: u64, version: u64) -> OperationWithClientId {
+    OperationWithClientId::new(upsert_operation(id, version), Some(Uuid::new_v4()))
+}
+
+/// Returns the number of the operation in WAL of `source`
+async fn update_both(
+    source: &LocalShard,
+    target: &LocalShard,
+    operation: OperationWithClientId,
+) -> u64 {
+    let result = source
+        .update_with_client_id(operation.clone(), true)
+        .await
+        .unwrap();
+    target.update_with_client_id(operation, true).await.unwrap();
+    result.operation_id
+}
+
+/// Operation in WAL of `source`, from which `target` is recovered
+fn find_recovery_point(source: &LocalShard, target: &LocalShard) -> Option<u64> {
+    let recovery_point = target.recovery_point(RECOVERY_POINT_OPERATIONS).unwrap();
+    source.find_recovery_point(&recovery_point)
+}
+
+/// Points of the shard without versions, versions are assigned by each shard separately
+async fn all_points(shard: &LocalShard) -> Vec<Record> {
+    shard
+        .scroll_by(
+            None,
+            1000,
+            &WithPayloadInterface::Bool(true),
+            &true.into(),
+            None,
+            None,
+            &Handle::current(),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|record| Record {
+            version: None,
+            ..record
+        })
+        .collect()
+}
+
+async fn transfer_wal_delta(
+    source: &LocalShard,
+    target: &LocalShard,
+    from_op_num: u64,
+    update_lock: &Mutex<()>,
+) -> WalDeltaProgress {
+    let mut op_num = from_op_num;
+    loop {
+        let _update_lock = update_lock.lock().await;
+        match transfer_wal_delta_batch(source, target, op_num, BATCH_SIZE)
+            .await
+            .unwrap()
+        {
+            WalDeltaProgress::Continue(next_op_num) => op_num = next_op_num,
+            progress => return progress,
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wal_delta_transfer() {
+    let source_dir = Builder::new().prefix("test_source").tempdir().unwrap();
+    let target_dir = Builder::new().prefix("test_target").tempdir().unwrap();
+
+    let config = create_collection_config(1000);
+
+    let source = build_shard(source_dir.path(), &config).await;
+    let target = build_shard(target_dir.path(), &config).await;
+
+    // Both replicas receive the same updates
+    for id in 0..10 {
+        update_both(&source, &target, replicated_operation(id, 0)).await;
+    }
+
+    // Target is down, source keeps receiving updates
+    for id in 5..20 {
+        source.update(upsert_operation(id, 1), true).await.unwrap();
+    }
+
+    let recovery_point = find_recovery_point(&source, &target).unwrap();
+    assert!(source.first_available_op_num() <= recovery_point);
+
+    let progress = transfer_wal_delta(&source, &target, recovery_point, &Mutex::new(())).await;
+    assert_eq!(progress, WalDeltaProgress::Finished);
+
+    assert_eq!(all_points(&target).await, all_points(&source).await);
+    assert_eq!(target.info().await.unwrap().points_count, 20);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wal_delta_transfer_truncated_wal() {
+    let source_dir = Builder::new().prefix("test_source").tempdir().unwrap();
+    let target_dir = Builder::new().prefix("test_target").tempdir().unwrap();
+
+    // Acknowledged operations are not retained
+    let config = create_collection_config(0);
+
+    let source = build_shard(source_dir.path(), &config).await;
+    let target = build_shard(target_dir.path(), &config).await;
+
+    update_both(&source, &target, replicated_operation(0, 0)).await;
+    let recovery_point = find_recovery_point(&source, &target).unwrap();
+
+    // Write more than a single WAL segment, so that applied operations can be truncated
+    let large_payload = "a".repeat(64 * 1024);
+    for id in 1..40 {
+        let operation = CollectionUpdateOperations::PointOperation(
+            vec![PointStruct {
+                id: id.into(),
+                vector: vec![1.0, 1.0, 2.0, 3.0].into(),
+                payload: Some(serde_json::from_value(json!({ "data": large_payload })).unwrap()),
+            }]
+            .into(),
+        );
+        source.update(operation, true).await.unwrap();
+    }
+
+    // Flush worker truncates applied operations in background
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+    while source.first_available_op_num() <= recovery_point {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "WAL is not truncated in time"
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let progress = transfer_wal_delta(&source, &target, recovery_point, &Mutex::new(())).await;
+    assert_eq!(progress, WalDeltaProgress::Unavailable);
+
+    // Nothing is transferred, so the whole shard transfer is still required
+    assert_eq!(target.info().await.unwrap().points_count, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wal_delta_transfer_concurrent_updates() {
+    let source_dir = Builder::new().prefix("test_source").tempdir().unwrap();
+    let target_dir = Builder::new().prefix("test_target").tempdir().unwrap();
+
+    let config = create_collection_config(1000);
+
+    let source = Arc::new(build_shard(source_dir.path(), &config).await);
+    let target = Arc::new(build_shard(target_dir.path(), &config).await);
+
+    for id in 0..10 {
+        update_both(&source, &target, replicated_operation(id, 0)).await;
+    }
+    let recovery_point = find_recovery_point(&source, &target).unwrap();
+
+    for version in 1..5 {
+        for id in 0..20 {
+            source
+                .update(upsert_operation(id, version), false)
+                .await
+                .unwrap();
+        }
+    }
+
+    // Same lock is used by forward proxy to block updates during the batch transfer
+    let update_lock = Arc::new(Mutex::new(()));
+
+    // Updates received during the transfer are applied to both shards, same as forward proxy does
+    let writer = {
+        let source = source.clone();
+        let target = target.clone();
+        let update_lock = update_lock.clone();
+        tokio::spawn(async move {
+            for version in 5..10 {
+                for id in 0..20 {
+                    let _update_lock = update_lock.lock().await;
+                    let operation = replicated_operation(id, version);
+                    source
+                        .update_with_client_id(operation.clone(), false)
+                        .await
+                        .unwrap();
+                    target
+                        .update_with_client_id(operation, false)
+                        .await
+                        .unwrap();
+                }
+            }
+        })
+    };
+
+    let progress = transfer_wal_delta(&source, &target, recovery_point, &update_lock).await;
+    assert_eq!(progress, WalDeltaProgress::Finished);
+    writer.await.unwrap();
+
+    // Wait for non-waiting updates to be applied
+    source.update(upsert_operation(100, 0), true).await.unwrap();
+    target.update(upsert_operation(100, 0), true).await.unwrap();
+
+    let source_points = all_points(&source).await;
+    let target_points = all_points(&target).await;
+    assert_eq!(source_points.len(), 21);
+    assert_eq!(target_points, source_points);
+
+    for point in &target_points {
+        if point.id != 100.into() {
+            assert_eq!(point.payload.as_ref().unwrap().0["version"], json!(9));
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wal_delta_transfer_diverged_op_nums() {
+    let source_dir = Builder::new().prefix("test_source").tempdir().unwrap();
+    let target_dir = Builder::new().prefix("test_target").tempdir().unwrap();
+
+    let config = create_collection_config(1000);
+
+    let source = build_shard(source_dir.path(), &config).await;
+    let target = build_shard(target_dir.path(), &config).await;
+
+    // Replicas got the same points by a different number of operations,
+    // e.g. the target was recovered by streaming records before
+    let points: Vec<_> = (0..10)
+        .map(|id| PointStruct {
+            id: id.into(),
+            vector: vec![0.0, 1.0, 2.0, 3.0].into(),
+            payload: Some(serde_json::from_value(json!({ "version": 0 })).unwrap()),
+        })
+        .collect();
+    source
+        .update(
+            CollectionUpdateOperations::PointOperation(points.into()),
+            true,
+        )
+        .await
+        .unwrap();
+    for _ in 0..3 {
+        for id in 0..10 {
+            target.update(upsert_operation(id, 0), true).await.unwrap();
+        }
+    }
+
+    let mut op_nums = Vec::new();
+    for id in 10..30 {
+        op_nums.push(update_both(&source, &target, replicated_operation(id, 0)).await);
+    }
+
+    // Target is down, source keeps receiving updates
+    for id in 0..40 {
+        source.update(upsert_operation(id, 1), true).await.unwrap();
+    }
+
+    // Target numbers its operations ahead of the source
+    let recovery_point = find_recovery_point(&source, &target).unwrap();
+    assert_eq!(
+        recovery_point,
+        op_nums[op_nums.len() - RECOVERY_POINT_OPERATIONS]
+    );
+    assert!(target.last_applied_op_num() > source.last_applied_op_num());
+
+    let progress = transfer_wal_delta(&source, &target, recovery_point, &Mutex::new(())).await;
+    assert_eq!(progress, WalDeltaProgress::Finished);
+
+    assert_eq!(all_points(&target).await, all_points(&source).await);
+    assert_eq!(target.info().await.unwrap().points_count, 40);
+
+    // Last operation of the target is unknown to the source or has no client id,
+    // so the whole shard is transferred instead
+    target
+        .update_with_client_id(replicated_operation(0, 2), true)
+        .await
+        .unwrap();
+    assert!(!target
+        .recovery_point(RECOVERY_POINT_OPERATIONS)
+        .unwrap()
+        .is_empty());
+    assert_eq!(find_recovery_point(&source, &target), None);
+
+    target.update(upsert_operation(0, 3), true).await.unwrap();
+    assert!(target
+        .recovery_point(RECOVERY_POINT_OPERATIONS)
+        .unwrap()
+        .is_empty());
+}
@@ -1,13 +1,16 @@
 use std::num::{NonZeroU32, NonZeroU64};
 use std::sync::Arc;
+use std::time::Duration;
 
 use segment::types::{Distance, PayloadFieldSchema, PayloadSchemaType};
 use tempfile::Builder;
 use tokio::runtime::Handle;
 use tokio::sync::RwLock;
+use wal::Wal;
 
 use crate::config::{CollectionConfig, CollectionParams, WalConfig};
 use crate::operations::point_ops::{PointOperations, PointStruct};
+use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{VectorParams, VectorsConfig};
 use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use crate::shards::local_shard::LocalShard;
@@ -18,6 +21,7 @@ fn create_collection_config() -> CollectionConfig {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -27,11 +31,18 @@ fn create_collection_config() -> CollectionConfig {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
+            multivector_config: None,
         }),
         shard_number: NonZeroU32::new(1).unwrap(),
+        sharding_method: None,
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
     };
 
     let mut optimizer_config = TEST_OPTIMIZERS_CONFIG.clone();
@@ -45,6 +56,7 @@ fn create_collection_config() -> CollectionConfig {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
     }
 }
 
@@ -183,3 +195,203 @@ async fn test_delete_from_indexed_payload() {
     assert_eq!(number_of_indexed_points, 4);
     assert_eq!(number_of_indexed_points_after_load, 3);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wal_recovery_report() {
+    let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+
+    let config = create_collection_config();
+
+    let collection_name = "test".to_string();
+
+    let current_runtime: Handle = Handle::current();
+
+    let shard = LocalShard::build(
+        0,
+        collection_name.clone(),
+        collection_dir.path(),
+        Arc::new(RwLock::new(config.clone())),
+        Arc::new(Default::default()),
+        current_runtime.clone(),
+    )
+    .await
+    .unwrap();
+
+    assert!(shard.wal_recovery_report().is_none());
+
+    shard.update(upsert_operation(), true).await.unwrap();
+    shard.update(delete_point_operation(4), true).await.unwrap();
+
+    drop(shard);
+
+    // Append a broken record followed by a valid one directly to the WAL
+    let corrupted_op_num = {
+        let mut wal = Wal::open(LocalShard::wal_path(collection_dir.path())).unwrap();
+        let corrupted_op_num = wal.append(&b"corrupted".to_vec()).unwrap();
+        wal.append(&serde_cbor::to_vec(&delete_point_operation(5)).unwrap())
+            .unwrap();
+        wal.flush_open_segment().unwrap();
+        corrupted_op_num
+    };
+
+    let shard = LocalShard::load(
+        0,
+        collection_name,
+        collection_dir.path(),
+        Arc::new(RwLock::new(config)),
+        Arc::new(Default::default()),
+        current_runtime,
+    )
+    .await
+    .unwrap();
+
+    let report = shard.wal_recovery_report().unwrap().clone();
+    assert_eq!(report.failed, 1);
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].op_num, corrupted_op_num);
+    assert!(report.replayed + report.skipped >= 1);
+
+    // Operation after the broken one is still applied
+    let info = shard.info().await.unwrap();
+    assert_eq!(info.points_count, 3);
+
+    let telemetry = shard.get_telemetry_data();
+    assert_eq!(telemetry.wal_recovery, Some(report));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_group_commit_recovery() {
+    let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+
+    let config = create_collection_config();
+
+    let collection_name = "test".to_string();
+
+    let current_runtime: Handle = Handle::current();
+
+    let storage_config = SharedStorageConfig {
+        update_group_commit_window: Duration::from_millis(1),
+        update_group_commit_max_ops: 8,
+        ..Default::default()
+    };
+
+    let shard = LocalShard::build(
+        0,
+        collection_name.clone(),
+        collection_dir.path(),
+        Arc::new(RwLock::new(config.clone())),
+        Arc::new(storage_config),
+        current_runtime.clone(),
+    )
+    .await
+    .unwrap();
+    let shard = Arc::new(shard);
+
+    let writers = 32;
+    let ops_per_writer = 10;
+
+    let handles: Vec<_> = (0..writers)
+        .map(|writer| {
+            let shard = shard.clone();
+            tokio::spawn(async move {
+                let mut operation_ids = vec![];
+                for i in 0..ops_per_writer {
+                    let id = (writer * ops_per_writer + i) as u64;
+                    let operation = CollectionUpdateOperations::PointOperation(
+                        vec![PointStruct {
+                            id: id.into(),
+                            vector: vec![id as f32, 1.0, 2.0, 3.0].into(),
+                            payload: None,
+                        }]
+                        .into(),
+                    );
+                    // Mix waiting and non-waiting operations
+                    let result = shard.update(operation, i % 2 == 0).await.unwrap();
+                    operation_ids.push(result.operation_id);
+                }
+                operation_ids
+            })
+        })
+        .collect();
+
+    let mut operation_ids = vec![];
+    for handle in handles {
+        operation_ids.extend(handle.await.unwrap());
+    }
+
+    // Every operation gets its own id
+    operation_ids.sort_unstable();
+    operation_ids.dedup();
+    assert_eq!(operation_ids.len(), writers * ops_per_writer);
+
+    drop(shard);
+
+    let shard = LocalShard::load(
+        0,
+        collection_name,
+        collection_dir.path(),
+        Arc::new(RwLock::new(config)),
+        Arc::new(Default::default()),
+        current_runtime,
+    )
+    .await
+    .unwrap();
+
+    // All acknowledged operations are recovered
+    let info = shard.info().await.unwrap();
+    assert_eq!(info.points_count, writers * ops_per_writer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_background_flush() {
+    let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+
+    let mut config = create_collection_config();
+    config.optimizer_config.flush_interval_sec = 1;
+
+    let collection_name = "test".to_string();
+
+    let current_runtime: Handle = Handle::current();
+
+    let shard = LocalShard::build(
+        0,
+        collection_name.clone(),
+        collection_dir.path(),
+        Arc::new(RwLock::new(config.clone())),
+        Arc::new(Default::default()),
+        current_runtime.clone(),
+    )
+    .await
+    .unwrap();
+
+    shard.update(upsert_operation(), true).await.unwrap();
+    shard.update(delete_point_operation(4), true).await.unwrap();
+
+    // Acknowledged operations are flushed within the interval
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let flush = shard.get_telemetry_data().flush.unwrap();
+    assert!(flush.last_flush.is_some());
+    assert_eq!(flush.unflushed_operations, 0);
+
+    // Operations after the flush are kept in WAL only, until the next one
+    shard.update(delete_point_operation(5), true).await.unwrap();
+    let flush = shard.get_telemetry_data().flush.unwrap();
+    assert!(flush.unflushed_operations <= 1);
+
+    drop(shard);
+
+    let shard = LocalShard::load(
+        0,
+        collection_name,
+        collection_dir.path(),
+        Arc::new(RwLock::new(config)),
+        Arc::new(Default::default()),
+        current_runtime,
+    )
+    .await
+    .unwrap();
+
+    // Nothing acknowledged is lost
+    let info = shard.info().await.unwrap();
+    assert_eq!(info.points_count, 3);
+}
@@ -2,18 +2,22 @@ use std::cmp::min;
 use std::collections::HashSet;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 use log::{debug, error, info, trace, warn};
+use parking_lot::Mutex;
 use segment::entry::entry_point::OperationResult;
 use segment::types::SeqNumberType;
 use tokio::runtime::Handle;
+use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{oneshot, Mutex as TokioMutex};
 use tokio::task::JoinHandle;
-use tokio::time::Duration;
+use tokio::time::{Duration, Instant};
 
 use crate::collection_manager::collection_updater::CollectionUpdater;
 use crate::collection_manager::holders::segment_holder::LockedSegmentHolder;
+use crate::collection_manager::optimizers::optimization_tasks::OptimizationTasks;
 use crate::collection_manager::optimizers::segment_optimizer::SegmentOptimizer;
 use crate::common::stoppable_task::{spawn_stoppable, StoppableTaskHandle};
 use crate::operations::shared_storage_config::SharedStorageConfig;
@@ -42,6 +46,8 @@ pub struct OperationData {
 pub enum UpdateSignal {
     /// Requested operation to perform
     Operation(OperationData),
+    /// Requested operations to perform in order, written into WAL as consecutive entries
+    Operations(Vec<OperationData>),
     /// Stop all optimizers and listening
     Stop,
     /// Empty signal used to trigger optimizers
@@ -61,6 +67,14 @@ pub enum OptimizerSignal {
     Nop,
 }
 
+/// Result of the last successful flush of the shard
+#[derive(Debug, Clone, Copy)]
+pub struct LastFlush {
+    pub time: DateTime<Utc>,
+    /// All operations up to this one are persisted in segments
+    pub confirmed_version: SeqNumberType,
+}
+
 /// Structure, which holds object, required for processing updates of the collection
 pub struct UpdateHandler {
     shared_storage_config: Arc<SharedStorageConfig>,
@@ -77,6 +91,10 @@ pub struct UpdateHandler {
     flush_worker: Option<JoinHandle<()>>,
     /// Sender to stop flush worker
     flush_stop: Option<oneshot::Sender<()>>,
+    /// Last successful flush, updated by the flush worker
+    pub last_flush: Arc<Mutex<Option<LastFlush>>>,
+    /// Running and recently finished optimizations, kept over restarts of the workers
+    pub optimization_tasks: Arc<OptimizationTasks>,
     runtime_handle: Handle,
     /// WAL, required for operations
     wal: LockedWal,
@@ -102,6 +120,10 @@ impl UpdateHandler {
             optimizer_worker: None,
             flush_worker: None,
             flush_stop: None,
+            last_flush: Arc::new(Mutex::new(None)),
+            optimization_tasks: Arc::new(OptimizationTasks::new(
+                shared_storage_config.optimizer_tasks_retention,
+            )),
             runtime_handle,
             wal,
             flush_interval_sec,
@@ -119,6 +141,7 @@ impl UpdateHandler {
             self.segments.clone(),
             self.wal.clone(),
             self.optimization_handles.clone(),
+            self.optimization_tasks.clone(),
             self.max_optimization_threads,
         )));
         self.update_worker = Some(self.runtime_handle.spawn(Self::update_worker_fn(
@@ -126,12 +149,15 @@ impl UpdateHandler {
             tx,
             self.wal.clone(),
             self.segments.clone(),
+            self.shared_storage_config.update_group_commit_window,
+            self.shared_storage_config.update_group_commit_max_ops,
         )));
         let (flush_tx, flush_rx) = oneshot::channel();
         self.flush_worker = Some(self.runtime_handle.spawn(Self::flush_worker(
             self.segments.clone(),
             self.wal.clone(),
             self.flush_interval_sec,
+            self.last_flush.clone(),
             flush_rx,
         )));
         self.flush_stop = Some(flush_tx);
@@ -172,6 +198,10 @@ impl UpdateHandler {
             res.await?;
         }
 
+        // Optimizations, which are stopped before they have started
+        self.optimization_tasks
+            .cancel_unfinished("Optimizations are stopped");
+
         Ok(())
     }
 
@@ -185,7 +215,7 @@ impl UpdateHandler {
             Some(first_failed_op) => {
                 let wal_lock = wal.lock();
                 for (op_num, operation) in wal_lock.read(first_failed_op) {
-                    CollectionUpdater::update(&segments, op_num, operation)?;
+                    CollectionUpdater::update(&segments, op_num, operation.operation)?;
                 }
             }
         };
@@ -193,11 +223,12 @@ impl UpdateHandler {
     }
 
     /// Checks conditions for all optimizers until there is no suggested segment
-    /// Starts a task for each optimization
+    /// Starts a task for each optimization, registered in `optimization_tasks`
     /// Returns handles for started tasks
     pub(crate) fn launch_optimization<F>(
         optimizers: Arc<Vec<Arc<Optimizer>>>,
         segments: LockedSegmentHolder,
+        optimization_tasks: &OptimizationTasks,
         callback: F,
     ) -> Vec<StoppableTaskHandle<bool>>
     where
@@ -221,9 +252,18 @@ impl UpdateHandler {
                         scheduled_segment_ids.insert(*sid);
                     }
                     let callback_cloned = callback.clone();
+                    let task = optimization_tasks.start(optimizer.name(), nsi.clone());
+                    let task_cloned = task.clone();
 
-                    handles.push(spawn_stoppable(move |stopped| {
-                        match optim.as_ref().optimize(segs.clone(), nsi, stopped) {
+                    let handle = spawn_stoppable(move |stopped| {
+                        let result = optim.as_ref().optimize_with_progress(
+                            segs.clone(),
+                            nsi,
+                            task_cloned.progress(),
+                            stopped,
+                        );
+                        task_cloned.finish(&result);
+                        match result {
                             Ok(result) => {
                                 callback_cloned(result); // Perform some actions when optimization if finished
                                 result
@@ -231,6 +271,11 @@ impl UpdateHandler {
                             Err(error) => match error {
                                 CollectionError::Cancelled { description } => {
                                     log::debug!("Optimization cancelled - {}", description);
+                                    // Cancelled through the API, the segments are requeued
+                                    // to be optimized by the following optimizations
+                                    if task_cloned.is_cancel_requested() {
+                                        callback_cloned(false);
+                                    }
                                     false
                                 }
                                 _ => {
@@ -247,7 +292,9 @@ impl UpdateHandler {
                                 }
                             },
                         }
-                    }));
+                    });
+                    task.set_stop_flag(handle.stop_flag());
+                    handles.push(handle);
                 }
             }
         }
@@ -258,11 +305,13 @@ impl UpdateHandler {
         optimizers: Arc<Vec<Arc<Optimizer>>>,
         segments: LockedSegmentHolder,
         optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
+        optimization_tasks: &OptimizationTasks,
         sender: Sender<OptimizerSignal>,
     ) {
         let mut new_handles = Self::launch_optimization(
             optimizers.clone(),
             segments.clone(),
+            optimization_tasks,
             move |_optimization_result| {
                 // After optimization is finished, we still need to check if there are
                 // some further optimizations possible.
@@ -283,6 +332,7 @@ impl UpdateHandler {
         segments: LockedSegmentHolder,
         wal: LockedWal,
         optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
+        optimization_tasks: Arc<OptimizationTasks>,
         max_handles: usize,
     ) {
         while let Some(signal) = receiver.recv().await {
@@ -307,6 +357,7 @@ impl UpdateHandler {
                         optimizers.clone(),
                         segments.clone(),
                         optimization_handles.clone(),
+                        &optimization_tasks,
                         sender.clone(),
                     )
                     .await;
@@ -322,46 +373,40 @@ impl UpdateHandler {
         optimize_sender: Sender<OptimizerSignal>,
         wal: LockedWal,
         segments: LockedSegmentHolder,
+        group_commit_window: Duration,
+        group_commit_max_ops: usize,
     ) {
-        while let Some(signal) = receiver.recv().await {
+        // Signal, which interrupted collecting of the previous batch of operations
+        let mut next_signal = None;
+        loop {
+            let signal = match next_signal.take() {
+                Some(signal) => signal,
+                None => match receiver.recv().await {
+                    Some(signal) => signal,
+                    None => break,
+                },
+            };
             match signal {
-                UpdateSignal::Operation(OperationData {
-                    op_num,
-                    operation,
-                    sender,
-                    wait,
-                }) => {
-                    let flush_res = if wait {
-                        wal.lock().flush().map_err(|err| {
-                            CollectionError::service_error(format!(
-                                "Can't flush WAL before operation {} - {}",
-                                op_num, err
-                            ))
-                        })
-                    } else {
-                        Ok(())
-                    };
-
-                    let operation_result = flush_res
-                        .and_then(|_| CollectionUpdater::update(&segments, op_num, operation));
-
-                    let res = match operation_result {
-                        Ok(update_res) => optimize_sender
-                            .send(OptimizerSignal::Operation(op_num))
-                            .await
-                            .and(Ok(update_res))
-                            .map_err(|send_err| send_err.into()),
-                        Err(err) => Err(err),
-                    };
-
-                    if let Some(feedback) = sender {
-                        feedback.send(res).unwrap_or_else(|_| {
-                            info!(
-                                "Can't report operation {} result. Assume already not required",
-                                op_num
-                            );
-                        });
-                    };
+                UpdateSignal::Operation(operation_data) => {
+                    let mut batch = vec![operation_data];
+                    next_signal = Self::collect_operations(
+                        &mut receiver,
+                        &mut batch,
+                        group_commit_window,
+                        group_commit_max_ops,
+                    )
+                    .await;
+                    Self::apply_operations(batch, &optimize_sender, &wal, &segments).await;
+                }
+                UpdateSignal::Operations(mut batch) => {
+                    next_signal = Self::collect_operations(
+                        &mut receiver,
+                        &mut batch,
+                        group_commit_window,
+                        group_commit_max_ops,
+                    )
+                    .await;
+                    Self::apply_operations(batch, &optimize_sender, &wal, &segments).await;
                 }
                 UpdateSignal::Stop => {
                     optimize_sender
@@ -392,10 +437,101 @@ impl UpdateHandler {
             .unwrap_or_else(|_| debug!("Optimizer already stopped"));
     }
 
+    /// Extends `batch` with operations, which can be committed with a single WAL flush.
+    ///
+    /// Already queued operations are always taken. If some operation of the batch is waiting
+    /// for the result, also waits up to `window` for more operations to arrive.
+    /// Returns non-operation signal, which interrupted the batch, if any.
+    async fn collect_operations(
+        receiver: &mut Receiver<UpdateSignal>,
+        batch: &mut Vec<OperationData>,
+        window: Duration,
+        max_ops: usize,
+    ) -> Option<UpdateSignal> {
+        let deadline = Instant::now() + window;
+        while batch.len() < max_ops {
+            let signal = match receiver.try_recv() {
+                Ok(signal) => signal,
+                Err(TryRecvError::Empty)
+                    if !window.is_zero() && batch.iter().any(|operation| operation.wait) =>
+                {
+                    match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                        Ok(Some(signal)) => signal,
+                        // Window is over or channel is closed
+                        Ok(None) | Err(_) => return None,
+                    }
+                }
+                Err(_) => return None,
+            };
+            match signal {
+                UpdateSignal::Operation(operation_data) => batch.push(operation_data),
+                UpdateSignal::Operations(operations) => batch.extend(operations),
+                signal => return Some(signal),
+            }
+        }
+        None
+    }
+
+    /// Applies a batch of operations, which are already written into WAL.
+    ///
+    /// WAL is flushed once for the whole batch, before any of the waiting operations is applied.
+    /// So results are only reported after the flush covering the operation is completed.
+    async fn apply_operations(
+        batch: Vec<OperationData>,
+        optimize_sender: &Sender<OptimizerSignal>,
+        wal: &LockedWal,
+        segments: &LockedSegmentHolder,
+    ) {
+        let flush_res = if batch.iter().any(|operation| operation.wait) {
+            wal.lock().flush().map_err(|err| err.to_string())
+        } else {
+            Ok(())
+        };
+
+        for OperationData {
+            op_num,
+            operation,
+            sender,
+            wait,
+        } in batch
+        {
+            let operation_result = match &flush_res {
+                Err(err) if wait => Err(CollectionError::service_error(format!(
+                    "Can't flush WAL before operation {} - {}",
+                    op_num, err
+                ))),
+                _ => CollectionUpdater::update(segments, op_num, operation),
+            };
+
+            let res = match operation_result {
+                Ok(update_res) => optimize_sender
+                    .send(OptimizerSignal::Operation(op_num))
+                    .await
+                    .and(Ok(update_res))
+                    .map_err(|send_err| send_err.into()),
+                Err(err) => Err(err),
+            };
+
+            if let Some(feedback) = sender {
+                feedback.send(res).unwrap_or_else(|_| {
+                    info!(
+                        "Can't report operation {} result. Assume already not required",
+                        op_num
+                    );
+                });
+            };
+        }
+    }
+
+    /// Periodically flushes segments and acknowledges flushed operations in WAL
+    ///
+    /// Flushes are coalesced: segments, which are still flushing in background since the
+    /// previous tick, are skipped and report their last persisted version.
     async fn flush_worker(
         segments: LockedSegmentHolder,
         wal: LockedWal,
         flush_interval_sec: u64,
+        last_flush: Arc<Mutex<Option<LastFlush>>>,
         mut stop_receiver: oneshot::Receiver<()>,
     ) {
         loop {
@@ -433,7 +569,12 @@ impl UpdateHandler {
             };
             if let Err(err) = wal.lock().ack(confirmed_version) {
                 segments.write().report_optimizer_error(err);
+                continue;
             }
+            *last_flush.lock() = Some(LastFlush {
+                time: Utc::now(),
+                confirmed_version,
+            });
         }
     }
 
@@ -441,7 +582,7 @@ impl UpdateHandler {
     ///
     /// # Errors
     /// Returns an error on flush failure
-    fn flush_segments(segments: LockedSegmentHolder) -> OperationResult<SeqNumberType> {
+    pub(crate) fn flush_segments(segments: LockedSegmentHolder) -> OperationResult<SeqNumberType> {
         let read_segments = segments.read();
         let flushed_version = read_segments.flush_all(false)?;
         Ok(match read_segments.failed_operation.iter().cloned().min() {
@@ -1,14 +1,18 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::marker::PhantomData;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::result;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
+use schemars::JsonSchema;
 use segment::common::file_operations::{atomic_save_json, read_json};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use wal::{Wal, WalOptions};
+use wal::{Entry, Segment, Wal, WalOptions};
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
@@ -20,6 +24,262 @@ pub enum WalError {
     WriteWalError(String),
     #[error("Can't truncate WAL: {0}")]
     TruncateWalError(String),
+    #[error("Can't read WAL: {0}")]
+    ReadWalError(String),
+    #[error("WAL is opened in read-only mode")]
+    ReadOnly,
+}
+
+/// Prefix of segments, which may still be appended to
+const OPEN_SEGMENT_PREFIX: &str = "open-";
+
+/// Prefix of full segments, followed by the index of their first record
+const CLOSED_SEGMENT_PREFIX: &str = "closed-";
+
+/// Attempts to list segments, if they are renamed or removed by a concurrent writer meanwhile
+const READ_ONLY_OPEN_ATTEMPTS: usize = 5;
+
+/// WAL, opened in place for reading only.
+///
+/// `wal::Wal` always opens its directory for writing: it takes an exclusive lock, recovers
+/// open segments and may create new ones. Reading a WAL of a running node this way either fails
+/// or interferes with the writer. Instead, existing segment files are opened one by one, without
+/// the lock, and are never written to. Only records, committed when the WAL is opened, are
+/// visible: a record, which is being written concurrently, fails its checksum and ends the segment.
+pub struct ReadOnlyWal {
+    path: PathBuf,
+    /// Non-empty segments with the index of their first record, ordered by it
+    segments: Vec<(u64, Segment)>,
+    /// Index of the record, following the last one
+    end_index: u64,
+}
+
+impl ReadOnlyWal {
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        if !path.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("WAL directory {} does not exist", path.display()),
+            ));
+        }
+        let mut attempt = 1;
+        loop {
+            match Self::try_open(path) {
+                Ok(Some(wal)) => return Ok(wal),
+                // Segment is renamed or removed by the writer, list them again
+                Ok(None) if attempt < READ_ONLY_OPEN_ATTEMPTS => {}
+                Err(err)
+                    if err.kind() == std::io::ErrorKind::NotFound
+                        && attempt < READ_ONLY_OPEN_ATTEMPTS => {}
+                Ok(None) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        format!(
+                            "WAL segments in {} are not contiguous, \
+                             they are probably changed by a concurrent writer",
+                            path.display(),
+                        ),
+                    ))
+                }
+                Err(err) => return Err(err),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Open the segments, listed in the directory.
+    ///
+    /// `None`, if closed segments are not contiguous.
+    fn try_open(path: &Path) -> std::io::Result<Option<Self>> {
+        let mut closed_segments = Vec::new();
+        let mut open_segments = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let Some(file_name) = file_name.to_str() else {
+                continue;
+            };
+            let parse_suffix = |prefix: &str| {
+                file_name
+                    .strip_prefix(prefix)
+                    .and_then(|suffix| suffix.parse::<u64>().ok())
+            };
+            if let Some(start_index) = parse_suffix(CLOSED_SEGMENT_PREFIX) {
+                closed_segments.push((start_index, Segment::open(entry.path())?));
+            } else if let Some(segment_id) = parse_suffix(OPEN_SEGMENT_PREFIX) {
+                open_segments.push((segment_id, Segment::open(entry.path())?));
+            }
+        }
+        closed_segments.sort_by_key(|(start_index, _)| *start_index);
+        open_segments.sort_by_key(|(segment_id, _)| *segment_id);
+
+        // Records of open segments follow the closed ones, in the order of segment ids
+        let mut end_index = closed_segments
+            .first()
+            .map_or(0, |(start_index, _)| *start_index);
+        let mut segments = Vec::new();
+        for (start_index, segment) in closed_segments {
+            if start_index != end_index {
+                return Ok(None);
+            }
+            end_index += segment.len() as u64;
+            segments.push((start_index, segment));
+        }
+        for (_, segment) in open_segments {
+            let len = segment.len() as u64;
+            if len > 0 {
+                segments.push((end_index, segment));
+                end_index += len;
+            }
+        }
+
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+            segments,
+            end_index,
+        }))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn first_index(&self) -> u64 {
+        self.segments
+            .first()
+            .map_or(self.end_index, |(start_index, _)| *start_index)
+    }
+
+    pub fn num_entries(&self) -> u64 {
+        self.end_index - self.first_index()
+    }
+
+    /// Same as `wal::Wal::last_index`: the first index, if WAL is empty
+    pub fn last_index(&self) -> u64 {
+        self.end_index.saturating_sub(1).max(self.first_index())
+    }
+
+    pub fn entry(&self, index: u64) -> Option<Entry> {
+        let position = self
+            .segments
+            .partition_point(|(start_index, _)| *start_index <= index);
+        let (start_index, segment) = self.segments.get(position.checked_sub(1)?)?;
+        segment.entry((index - start_index) as usize)
+    }
+}
+
+/// WAL files, opened for writing, or in place for reading only
+pub enum WalFiles {
+    Writable(Wal),
+    ReadOnly(ReadOnlyWal),
+}
+
+impl WalFiles {
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, WalFiles::ReadOnly(_))
+    }
+
+    /// `None`, if WAL is opened for reading only
+    pub fn writable(&mut self) -> Option<&mut Wal> {
+        match self {
+            WalFiles::Writable(wal) => Some(wal),
+            WalFiles::ReadOnly(_) => None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            WalFiles::Writable(wal) => wal.path(),
+            WalFiles::ReadOnly(wal) => wal.path(),
+        }
+    }
+
+    pub fn first_index(&self) -> u64 {
+        match self {
+            WalFiles::Writable(wal) => wal.first_index(),
+            WalFiles::ReadOnly(wal) => wal.first_index(),
+        }
+    }
+
+    pub fn last_index(&self) -> u64 {
+        match self {
+            WalFiles::Writable(wal) => wal.last_index(),
+            WalFiles::ReadOnly(wal) => wal.last_index(),
+        }
+    }
+
+    pub fn num_entries(&self) -> u64 {
+        match self {
+            WalFiles::Writable(wal) => wal.num_entries(),
+            WalFiles::ReadOnly(wal) => wal.num_entries(),
+        }
+    }
+
+    pub fn entry(&self, index: u64) -> Option<Entry> {
+        match self {
+            WalFiles::Writable(wal) => wal.entry(index),
+            WalFiles::ReadOnly(wal) => wal.entry(index),
+        }
+    }
+}
+
+/// Compression of large WAL records
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WalCompression {
+    /// Records larger than this size (in bytes) are compressed with LZ4 before being written.
+    /// Smaller records are stored as is, compression is unlikely to pay off for them.
+    #[serde(default = "default_compression_threshold")]
+    pub threshold: usize,
+}
+
+impl Default for WalCompression {
+    fn default() -> Self {
+        WalCompression {
+            threshold: default_compression_threshold(),
+        }
+    }
+}
+
+const fn default_compression_threshold() -> usize {
+    64 * 1024
+}
+
+/// Marks records stored in compressed form.
+///
+/// Uncompressed records are CBOR (or MessagePack in older versions) encoded structures, which
+/// never start with `0xFF`, so WALs with mixed records can be read without any additional metadata.
+const COMPRESSED_RECORD_FLAG: u8 = 0xFF;
+
+/// Retention of acknowledged WAL records
+///
+/// By default acknowledged records are removed as soon as possible.
+/// With retention configured, records are kept until they fall out of both windows,
+/// so they can still be used for replica catch-up or post-mortem analysis.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WalRetention {
+    /// Keep at least this number of the latest acknowledged records
+    pub retain_ops: u64,
+    /// Keep acknowledged records written within this number of seconds
+    pub retain_secs: u64,
+}
+
+/// Precision of write time tracking used for time-based retention.
+///
+/// Records written within this interval share a single timestamp, which bounds the amount of
+/// tracked timestamps by the retention window instead of the number of records.
+const WRITE_TIME_GRANULARITY: Duration = Duration::from_secs(1);
+
+/// Accumulated sizes of records stored in WAL
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WalStats {
+    /// Number of stored records
+    pub records: u64,
+    /// Number of records stored in compressed form
+    pub compressed_records: u64,
+    /// Total size of records as stored on disk
+    pub stored_bytes: u64,
+    /// Total size of records after decompression
+    pub uncompressed_bytes: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -64,9 +324,17 @@ impl WalState {
 /// for removing old, no longer required, records.
 pub struct SerdeWal<R> {
     record: PhantomData<R>,
-    wal: Wal,
+    wal: WalFiles,
     options: WalOptions,
     first_index: Option<u64>,
+    compression: Option<WalCompression>,
+    retention: WalRetention,
+    /// Sequence numbers of the first records written at given time, ordered by both.
+    /// Only tracked if time-based retention is enabled.
+    write_times: VecDeque<(u64, Instant)>,
+    /// Records starting from this index are kept regardless of retention,
+    /// e.g. while they are pending to be transferred to another replica
+    retained_from: Option<u64>,
 }
 
 const FIRST_INDEX_FILE: &str = "first-index";
@@ -75,8 +343,27 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
     pub fn new(dir: &str, wal_options: WalOptions) -> Result<SerdeWal<R>> {
         let wal = Wal::with_options(dir, &wal_options)
             .map_err(|err| WalError::InitWalError(format!("{err:?}")))?;
+        Self::from_files(WalFiles::Writable(wal), wal_options)
+    }
+
+    /// Open WAL for reading only.
+    ///
+    /// Works for WAL, which is used by a running node: segments are read in place, the directory
+    /// is neither locked nor modified, see [`ReadOnlyWal`]. Records written after this call are
+    /// not visible. Methods, which modify WAL, return [`WalError::ReadOnly`].
+    pub fn open_read_only(dir: &str) -> Result<SerdeWal<R>> {
+        let wal = ReadOnlyWal::open(Path::new(dir))
+            .map_err(|err| WalError::InitWalError(format!("{err:?}")))?;
+        // Segments are never created, so their capacity is irrelevant
+        let wal_options = WalOptions {
+            segment_capacity: 0,
+            segment_queue_len: 0,
+        };
+        Self::from_files(WalFiles::ReadOnly(wal), wal_options)
+    }
 
-        let first_index_path = Path::new(dir).join(FIRST_INDEX_FILE);
+    fn from_files(wal: WalFiles, wal_options: WalOptions) -> Result<SerdeWal<R>> {
+        let first_index_path = wal.path().join(FIRST_INDEX_FILE);
 
         let first_index = if first_index_path.exists() {
             let wal_state: WalState = read_json(&first_index_path).map_err(|err| {
@@ -97,16 +384,107 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
             wal,
             options: wal_options,
             first_index,
+            compression: None,
+            retention: WalRetention::default(),
+            write_times: VecDeque::new(),
+            retained_from: None,
         })
     }
 
+    pub fn is_read_only(&self) -> bool {
+        self.wal.is_read_only()
+    }
+
+    fn writable(&mut self) -> Result<&mut Wal> {
+        self.wal.writable().ok_or(WalError::ReadOnly)
+    }
+
+    /// Enable compression of records larger than configured threshold.
+    ///
+    /// Only affects newly written records, existing records are read regardless of this setting.
+    pub fn with_compression(mut self, compression: Option<WalCompression>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Keep acknowledged records according to the given retention.
+    ///
+    /// Write time of records, which are already stored in WAL, is unknown.
+    /// They are considered to be written at the moment of this call.
+    pub fn with_retention(mut self, retention: WalRetention) -> Self {
+        self.retention = retention;
+        self.write_times.clear();
+        if retention.retain_secs > 0 && self.wal.num_entries() > 0 {
+            self.write_times
+                .push_back((self.wal.first_index(), Instant::now()));
+        }
+        self
+    }
+
+    /// Keep records starting from `index`, even if they are acknowledged.
+    /// `None` removes the restriction.
+    pub fn retain_from(&mut self, index: Option<u64>) {
+        self.retained_from = index;
+    }
+
     /// Write a record to the WAL but does guarantee durability.
     pub fn write(&mut self, entity: &R) -> Result<u64> {
+        if self.is_read_only() {
+            return Err(WalError::ReadOnly);
+        }
         // ToDo: Replace back to faster rmp, once this https://github.com/serde-rs/serde/issues/2055 solved
         let binary_entity = serde_cbor::to_vec(&entity).unwrap();
-        self.wal
+        let binary_entity = match self.compression {
+            Some(compression) if binary_entity.len() > compression.threshold => {
+                compress_record(&binary_entity)
+            }
+            _ => binary_entity,
+        };
+        let op_num = self
+            .writable()?
             .append(&binary_entity)
-            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))
+            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))?;
+        if self.retention.retain_secs > 0 {
+            self.track_write_time(op_num, Instant::now());
+        }
+        Ok(op_num)
+    }
+
+    fn track_write_time(&mut self, op_num: u64, now: Instant) {
+        match self.write_times.back() {
+            Some((_, last_time)) if now < *last_time + WRITE_TIME_GRANULARITY => {}
+            _ => self.write_times.push_back((op_num, now)),
+        }
+    }
+
+    /// Returns the index, up to which acknowledged records can be removed according to retention
+    fn retained_until(&mut self, until_index: u64, now: Instant) -> u64 {
+        let mut truncate_until = until_index.saturating_sub(self.retention.retain_ops);
+
+        if self.retention.retain_secs > 0 {
+            match now.checked_sub(Duration::from_secs(self.retention.retain_secs)) {
+                Some(cutoff) => {
+                    // Forget records, which were all written before the retention window
+                    while let Some((_, time)) = self.write_times.front() {
+                        if *time + WRITE_TIME_GRANULARITY > cutoff {
+                            break;
+                        }
+                        self.write_times.pop_front();
+                    }
+                    if let Some((first_retained, _)) = self.write_times.front() {
+                        truncate_until = truncate_until.min(*first_retained);
+                    }
+                }
+                // Retention window starts before the process did, so keep everything
+                None => truncate_until = truncate_until.min(self.wal.first_index()),
+            }
+        }
+
+        if let Some(retained_from) = self.retained_from {
+            truncate_until = truncate_until.min(retained_from);
+        }
+
+        truncate_until
     }
 
     pub fn read_all(&'s self) -> impl Iterator<Item = (u64, R)> + 's {
@@ -149,6 +527,8 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
 
         (start_from..(first_index + len)).map(move |idx| {
             let record_bin = self.wal.entry(idx).expect("Can't read entry from WAL");
+            let record_bin = decompress_record(&record_bin)
+                .expect("Can't decompress entry, probably corrupted WAL");
             let record: R = serde_cbor::from_slice(&record_bin)
                 .or_else(|_err| rmp_serde::from_slice(&record_bin))
                 .expect("Can't deserialize entry, probably corrupted WAL on version mismatch");
@@ -156,6 +536,32 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         })
     }
 
+    /// Same as [`SerdeWal::read_all`], but does not panic on records, which can't be read.
+    ///
+    /// Broken records are returned as errors, so the caller can decide whether to skip them.
+    pub fn try_read_all(&'s self) -> impl Iterator<Item = (u64, Result<R>)> + 's {
+        self.try_read(self.first_index())
+    }
+
+    /// Same as [`SerdeWal::read`], but does not panic on records, which can't be read.
+    pub fn try_read(&'s self, start_from: u64) -> impl Iterator<Item = (u64, Result<R>)> + 's {
+        let first_index = self.first_index();
+        let len = self.len();
+
+        (start_from..(first_index + len)).map(move |idx| (idx, self.try_read_entry(idx)))
+    }
+
+    fn try_read_entry(&self, idx: u64) -> Result<R> {
+        let record_bin = self
+            .wal
+            .entry(idx)
+            .ok_or_else(|| WalError::ReadWalError(format!("entry {idx} is missing")))?;
+        let record_bin = decompress_record(&record_bin)?;
+        serde_cbor::from_slice(&record_bin)
+            .or_else(|_err| rmp_serde::from_slice(&record_bin))
+            .map_err(|err| WalError::ReadWalError(format!("can't deserialize entry {idx}: {err}")))
+    }
+
     /// Inform WAL, that records older than `until_index` are no longer required.
     /// If it is possible, WAL will remove unused files.
     ///
@@ -164,9 +570,13 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
     /// * `until_index` - the newest no longer required record sequence number
     ///
     pub fn ack(&mut self, until_index: u64) -> Result<()> {
-        // Truncate WAL
-        self.wal
-            .prefix_truncate(until_index)
+        if self.is_read_only() {
+            return Err(WalError::ReadOnly);
+        }
+        // Truncate WAL, keeping acknowledged records required by retention
+        let truncate_until = self.retained_until(until_index, Instant::now());
+        self.writable()?
+            .prefix_truncate(truncate_until)
             .map_err(|err| WalError::TruncateWalError(format!("{err:?}")))?;
 
         // Acknowledge index should not decrease
@@ -187,6 +597,13 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         Ok(())
     }
 
+    /// Remove records starting from `from_index`, e.g. to drop a corrupted tail of WAL.
+    pub fn truncate(&mut self, from_index: u64) -> Result<()> {
+        self.writable()?
+            .truncate(from_index)
+            .map_err(|err| WalError::TruncateWalError(format!("{err:?}")))
+    }
+
     fn flush_first_index(&self) -> Result<()> {
         let Some(first_index) = self.first_index else {
             return Ok(());
@@ -203,14 +620,21 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         Ok(())
     }
 
+    /// Nothing to flush, if WAL is opened for reading only
     pub fn flush(&mut self) -> Result<()> {
-        self.wal
-            .flush_open_segment()
-            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))
+        match self.wal.writable() {
+            Some(wal) => wal
+                .flush_open_segment()
+                .map_err(|err| WalError::WriteWalError(format!("{err:?}"))),
+            None => Ok(()),
+        }
     }
 
     pub fn flush_async(&mut self) -> JoinHandle<std::io::Result<()>> {
-        self.wal.flush_open_segment_async()
+        match self.wal.writable() {
+            Some(wal) => wal.flush_open_segment_async(),
+            None => std::thread::spawn(|| Ok(())),
+        }
     }
 
     pub fn path(&self) -> &Path {
@@ -221,6 +645,14 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         self.first_index.unwrap_or_else(|| self.wal.first_index())
     }
 
+    /// The first record, which is still physically present in WAL.
+    ///
+    /// Unlike [`SerdeWal::first_index`], includes acknowledged records kept by retention.
+    /// Records starting from this index can be read with [`SerdeWal::read`].
+    pub fn first_available_index(&self) -> u64 {
+        self.wal.first_index()
+    }
+
     pub fn last_index(&self) -> u64 {
         self.wal.last_index()
     }
@@ -228,6 +660,44 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
     pub fn segment_capacity(&self) -> usize {
         self.options.segment_capacity
     }
+
+    /// Collect statistics of stored and decompressed record sizes
+    pub fn stats(&self) -> Result<WalStats> {
+        let mut stats = WalStats::default();
+        for idx in self.first_index()..self.first_index() + self.len() {
+            let record_bin = self
+                .wal
+                .entry(idx)
+                .ok_or_else(|| WalError::ReadWalError(format!("entry {idx} is missing")))?;
+            let is_compressed = record_bin.first() == Some(&COMPRESSED_RECORD_FLAG);
+            let uncompressed_len = decompress_record(&record_bin)?.len();
+
+            stats.records += 1;
+            stats.compressed_records += u64::from(is_compressed);
+            stats.stored_bytes += record_bin.len() as u64;
+            stats.uncompressed_bytes += uncompressed_len as u64;
+        }
+        Ok(stats)
+    }
+}
+
+fn compress_record(record: &[u8]) -> Vec<u8> {
+    let compressed = lz4_flex::compress_prepend_size(record);
+    let mut result = Vec::with_capacity(compressed.len() + 1);
+    result.push(COMPRESSED_RECORD_FLAG);
+    result.extend_from_slice(&compressed);
+    result
+}
+
+fn decompress_record(record: &[u8]) -> Result<Cow<[u8]>> {
+    match record.split_first() {
+        Some((&COMPRESSED_RECORD_FLAG, compressed)) => {
+            lz4_flex::decompress_size_prepended(compressed)
+                .map(Cow::Owned)
+                .map_err(|err| WalError::ReadWalError(format!("can't decompress record: {err}")))
+        }
+        _ => Ok(Cow::Borrowed(record)),
+    }
 }
 
 #[cfg(test)]
@@ -237,9 +707,13 @@ mod tests {
     #[cfg(not(target_os = "windows"))]
     use std::os::unix::fs::MetadataExt;
 
+    use itertools::Itertools;
+    use segment::data_types::vectors::only_default_vector;
     use tempfile::Builder;
 
     use super::*;
+    use crate::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
+    use crate::operations::CollectionUpdateOperations;
 
     #[test]
     fn test_wal() {
@@ -293,4 +767,299 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_wal_compression() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let wal_options = WalOptions {
+            segment_capacity: 32 * 1024 * 1024,
+            segment_queue_len: 0,
+        };
+
+        let mut serde_wal: SerdeWal<CollectionUpdateOperations> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options)
+                .unwrap()
+                .with_compression(Some(WalCompression::default()));
+
+        // ~4 MB of vector data
+        let dim = 1024;
+        let points = (0..1000)
+            .map(|idx| PointStruct {
+                id: (idx as u64).into(),
+                vector: only_default_vector(&vec![(idx % 7) as f32 / 7.0; dim]).into(),
+                payload: None,
+            })
+            .collect_vec();
+        let large_operation = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperations::PointsList(points)),
+        );
+        let small_operation =
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                ids: vec![1.into(), 2.into()],
+            });
+
+        serde_wal.write(&large_operation).unwrap();
+        serde_wal.write(&small_operation).unwrap();
+
+        let stats = serde_wal.stats().unwrap();
+        assert_eq!(stats.records, 2);
+        assert_eq!(stats.compressed_records, 1);
+        assert!(stats.stored_bytes < stats.uncompressed_bytes);
+        assert!(stats.uncompressed_bytes > (dim * 1000 * 4) as u64);
+
+        // Records written without compression must be readable from the same WAL
+        let mut serde_wal = serde_wal.with_compression(None);
+        serde_wal.write(&large_operation).unwrap();
+        assert_eq!(serde_wal.stats().unwrap().compressed_records, 1);
+
+        let records = serde_wal.read_all().map(|(_, op)| op).collect_vec();
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            serde_cbor::to_vec(&records[0]).unwrap(),
+            serde_cbor::to_vec(&large_operation).unwrap(),
+        );
+        assert_eq!(
+            serde_cbor::to_vec(&records[1]).unwrap(),
+            serde_cbor::to_vec(&small_operation).unwrap(),
+        );
+        assert_eq!(
+            serde_cbor::to_vec(&records[2]).unwrap(),
+            serde_cbor::to_vec(&large_operation).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_wal_retention() {
+        let open_wal = |dir: &Path, retention: WalRetention| -> SerdeWal<TestRecord> {
+            let wal_options = WalOptions {
+                segment_capacity: 32 * 1024 * 1024,
+                segment_queue_len: 0,
+            };
+            SerdeWal::new(dir.to_str().unwrap(), wal_options)
+                .unwrap()
+                .with_retention(retention)
+        };
+        let record = TestRecord::Struct1(TestInternalStruct1 { data: 10 });
+
+        // Retain by number of operations
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let mut serde_wal = open_wal(
+            dir.path(),
+            WalRetention {
+                retain_ops: 10,
+                retain_secs: 0,
+            },
+        );
+        for _ in 0..30 {
+            serde_wal.write(&record).unwrap();
+        }
+        serde_wal.ack(25).unwrap();
+        assert_eq!(serde_wal.first_index(), 25);
+        assert_eq!(serde_wal.len(), 5);
+        // WAL may keep even more records, as it only removes whole segments
+        assert!(serde_wal.first_available_index() <= 15);
+        assert_eq!(serde_wal.read(15).count(), 15);
+
+        // Retain by time
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let mut serde_wal = open_wal(
+            dir.path(),
+            WalRetention {
+                retain_ops: 0,
+                retain_secs: 60,
+            },
+        );
+        for _ in 0..30 {
+            serde_wal.write(&record).unwrap();
+        }
+        let start = Instant::now();
+        serde_wal.write_times = VecDeque::from([
+            (0, start),
+            (10, start + Duration::from_secs(10)),
+            (20, start + Duration::from_secs(20)),
+        ]);
+        // Records 0..10 might be written up to a second after `start`, so they are still retained
+        assert_eq!(
+            serde_wal.retained_until(25, start + Duration::from_millis(60_500)),
+            0
+        );
+        // Records 0..10 are outside of the retention window
+        assert_eq!(
+            serde_wal.retained_until(25, start + Duration::from_millis(70_500)),
+            10
+        );
+        // Records 0..20 are outside of the retention window
+        assert_eq!(
+            serde_wal.retained_until(25, start + Duration::from_secs(75)),
+            20
+        );
+        // All records are outside of the retention window
+        assert_eq!(
+            serde_wal.retained_until(25, start + Duration::from_secs(200)),
+            25
+        );
+        assert!(serde_wal.write_times.is_empty());
+
+        // Retain by both, larger retention wins
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let mut serde_wal = open_wal(
+            dir.path(),
+            WalRetention {
+                retain_ops: 5,
+                retain_secs: 60,
+            },
+        );
+        for _ in 0..30 {
+            serde_wal.write(&record).unwrap();
+        }
+        serde_wal.write_times = VecDeque::from([
+            (0, start),
+            (10, start + Duration::from_secs(10)),
+            (20, start + Duration::from_secs(20)),
+        ]);
+        assert_eq!(
+            serde_wal.retained_until(25, start + Duration::from_millis(70_500)),
+            10
+        );
+        assert_eq!(
+            serde_wal.retained_until(25, start + Duration::from_secs(200)),
+            20
+        );
+
+        // Records pending to be transferred are kept regardless of retention
+        serde_wal.retain_from(Some(3));
+        assert_eq!(
+            serde_wal.retained_until(25, start + Duration::from_secs(200)),
+            3
+        );
+        serde_wal.retain_from(None);
+
+        // Everything written just now is retained on ack
+        serde_wal.write_times.clear();
+        let op_num = serde_wal.write(&record).unwrap();
+        serde_wal.ack(op_num).unwrap();
+        assert_eq!(serde_wal.first_index(), op_num);
+        assert!(serde_wal.first_available_index() <= op_num - 5);
+    }
+
+    #[test]
+    fn test_wal_read_only() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let wal_options = WalOptions {
+            segment_capacity: 32 * 1024 * 1024,
+            segment_queue_len: 0,
+        };
+
+        // Writer stays open for the whole test
+        let mut writer: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options).unwrap();
+        for data in 0..10 {
+            writer
+                .write(&TestRecord::Struct1(TestInternalStruct1 { data }))
+                .unwrap();
+        }
+        writer.flush().unwrap();
+        writer.ack(2).unwrap();
+
+        let files_before: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .sorted()
+            .collect();
+
+        let mut reader: SerdeWal<TestRecord> =
+            SerdeWal::open_read_only(dir.path().to_str().unwrap()).unwrap();
+        assert!(reader.is_read_only());
+        assert_eq!(reader.first_index(), 2);
+        let records: Vec<_> = reader
+            .read_all()
+            .map(|(idx, record)| match record {
+                TestRecord::Struct1(x) => (idx, x.data),
+                TestRecord::Struct2(_) => panic!("Wrong structure"),
+            })
+            .collect();
+        assert_eq!(
+            records,
+            (2..10).map(|idx| (idx, idx as usize)).collect_vec()
+        );
+
+        let record = TestRecord::Struct1(TestInternalStruct1 { data: 10 });
+        assert!(matches!(reader.write(&record), Err(WalError::ReadOnly)));
+        assert!(matches!(reader.ack(5), Err(WalError::ReadOnly)));
+
+        // Writer is not affected by the reader
+        assert_eq!(writer.write(&record).unwrap(), 10);
+        drop(reader);
+
+        let files_after: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .sorted()
+            .collect();
+        assert_eq!(files_before, files_after);
+    }
+
+    #[test]
+    fn test_wal_read_only_segments() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        // Small segments, so that the WAL has closed segments and a partially written open one
+        let wal_options = WalOptions {
+            segment_capacity: 4096,
+            segment_queue_len: 0,
+        };
+        let record = |data| TestRecord::Struct1(TestInternalStruct1 { data });
+        let read_data = |wal: &SerdeWal<TestRecord>| -> Vec<(u64, usize)> {
+            wal.read_all()
+                .map(|(idx, record)| match record {
+                    TestRecord::Struct1(x) => (idx, x.data),
+                    TestRecord::Struct2(_) => panic!("Wrong structure"),
+                })
+                .collect()
+        };
+
+        // Writer stays open for the whole test
+        let mut writer: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options).unwrap();
+        for data in 0..500 {
+            writer.write(&record(data)).unwrap();
+        }
+        writer.flush().unwrap();
+        writer.ack(200).unwrap();
+        let closed_segments = std::fs::read_dir(dir.path())
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_str().unwrap().starts_with(CLOSED_SEGMENT_PREFIX)
+            })
+            .count();
+        assert!(closed_segments > 1);
+
+        let reader: SerdeWal<TestRecord> =
+            SerdeWal::open_read_only(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(reader.first_index(), 200);
+        assert_eq!(reader.last_index(), 499);
+        assert_eq!(
+            read_data(&reader),
+            (200..500).map(|idx| (idx, idx as usize)).collect_vec()
+        );
+
+        // Records, written after opening, are only visible to a new reader
+        for data in 500..600 {
+            writer.write(&record(data)).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(reader.len(), 300);
+        assert_eq!(read_data(&reader).last(), Some(&(499, 499)));
+
+        let reader: SerdeWal<TestRecord> =
+            SerdeWal::open_read_only(dir.path().to_str().unwrap()).unwrap();
+        assert_eq!(
+            read_data(&reader),
+            (200..600).map(|idx| (idx, idx as usize)).collect_vec()
+        );
+        assert_eq!(
+            reader.first_available_index(),
+            writer.first_available_index()
+        );
+    }
 }
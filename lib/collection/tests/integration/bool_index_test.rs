@@ -0,0 +1,188 @@
+use collection::collection::Collection;
+use collection::operations::payload_ops::{PayloadOps, SetPayload};
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{CollectionError, CountRequest, ScrollRequest};
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use itertools::Itertools;
+use segment::types::{
+    Condition, FieldCondition, Filter, Match, Payload, PayloadSchemaType, PointIdType,
+    ValueVariants,
+};
+use serde_json::{json, Value};
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 100;
+
+/// Booleans indexed as keywords, in various casings, every 5th point has no flag
+fn flag_payload(n: u64) -> Payload {
+    let payload = match n % 5 {
+        0 => json!({ "num": n }),
+        1 => json!({ "num": n, "flag": "true" }),
+        2 => json!({ "num": n, "flag": "False" }),
+        3 => json!({ "num": n, "flag": ["TRUE", "false"] }),
+        _ => json!({ "num": n, "flag": "false" }),
+    };
+    Payload::from(payload)
+}
+
+async fn index_field(
+    collection: &Collection,
+    field_name: &str,
+    field_type: PayloadSchemaType,
+    update: bool,
+) -> Result<(), CollectionError> {
+    let create_index = CreateIndex {
+        field_name: field_name.to_string(),
+        field_schema: Some(field_type.into()),
+    };
+    let operation = if update {
+        FieldIndexOperations::UpdateIndex(create_index)
+    } else {
+        FieldIndexOperations::CreateIndex(create_index)
+    };
+    collection
+        .update_from_client(
+            CollectionUpdateOperations::FieldIndexOperation(operation),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .map(|_| ())
+}
+
+async fn fixture(collection_path: &std::path::Path, shard_number: u32) -> Collection {
+    let collection = simple_collection_fixture(collection_path, shard_number).await;
+    index_field(&collection, "flag", PayloadSchemaType::Keyword, false)
+        .await
+        .unwrap();
+
+    // Small batches, to spread points over segments
+    for batch in &(0..NUM_POINTS).chunks(10) {
+        let ids = batch.collect_vec();
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+                vectors: ids
+                    .iter()
+                    .map(|n| vec![*n as f32, 0.0, 0.0, 1.0])
+                    .collect_vec()
+                    .into(),
+                payloads: Some(ids.iter().map(|n| Some(flag_payload(*n))).collect_vec()),
+            }
+            .into(),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+    collection
+}
+
+async fn count_flag(collection: &Collection, value: bool) -> usize {
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "flag",
+        Match::new_value(ValueVariants::Bool(value)),
+    )));
+    collection
+        .count(
+            CountRequest {
+                filter: Some(filter),
+                exact: true,
+            },
+            None,
+        )
+        .await
+        .unwrap()
+        .count
+}
+
+async fn set_flag(collection: &Collection, value: Value) -> Result<(), CollectionError> {
+    let set_payload =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+            payload: Payload::from(json!({ "flag": value })),
+            points: Some(vec![0.into()]),
+            filter: None,
+            if_version: None,
+            key: None,
+        }));
+    collection
+        .update_from_client(set_payload, true, WriteOrdering::default())
+        .await
+        .map(|_| ())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_migrate_keyword_index_to_bool() {
+    test_migrate_keyword_index_to_bool_with_shards(1).await;
+    test_migrate_keyword_index_to_bool_with_shards(N_SHARDS).await;
+}
+
+async fn test_migrate_keyword_index_to_bool_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    index_field(&collection, "flag", PayloadSchemaType::Bool, true)
+        .await
+        .unwrap();
+    let info = collection.info(None).await.unwrap();
+    assert_eq!(
+        info.payload_schema["flag"].data_type,
+        PayloadSchemaType::Bool
+    );
+
+    // Strings are converted into booleans, other values are kept as is
+    let result = collection
+        .scroll_by(
+            ScrollRequest {
+                limit: Some(NUM_POINTS as usize),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.points.len(), NUM_POINTS as usize);
+    for point in result.points {
+        let payload = point.payload.unwrap().0;
+        let n = payload["num"].as_u64().unwrap();
+        let expected = match n % 5 {
+            0 => None,
+            1 => Some(json!(true)),
+            2 | 4 => Some(json!(false)),
+            _ => Some(json!([true, false])),
+        };
+        assert_eq!(payload.get("flag").cloned(), expected, "{n}");
+    }
+
+    let per_value = NUM_POINTS as usize / 5;
+    assert_eq!(count_flag(&collection, true).await, 2 * per_value);
+    assert_eq!(count_flag(&collection, false).await, 3 * per_value);
+
+    // Bool index accepts only booleans from now on
+    let result = set_flag(&collection, json!("true")).await;
+    assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+    set_flag(&collection, json!(true)).await.unwrap();
+    assert_eq!(count_flag(&collection, true).await, 2 * per_value + 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_migrate_keyword_index_to_bool_rejects_other_keywords() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), 1).await;
+
+    set_flag(&collection, json!("yes")).await.unwrap();
+    let result = index_field(&collection, "flag", PayloadSchemaType::Bool, true).await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+
+    // Nothing is converted, keyword index keeps serving
+    let info = collection.info(None).await.unwrap();
+    assert_eq!(
+        info.payload_schema["flag"].data_type,
+        PayloadSchemaType::Keyword
+    );
+    assert_eq!(count_flag(&collection, true).await, 0);
+}
@@ -0,0 +1,320 @@
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroU64;
+use std::time::Duration;
+
+use collection::collection::Collection;
+use collection::operations::config_diff::{HnswConfigDiff, OptimizersConfigDiff};
+use collection::operations::point_ops::{
+    Batch, PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
+};
+use collection::operations::types::{CollectionStatus, VectorParams, VectorsConfig};
+use collection::operations::CollectionUpdateOperations;
+use collection::optimizers_builder::OptimizersConfig;
+use itertools::Itertools;
+use segment::data_types::named_vectors::NamedVectors;
+use segment::types::{
+    Distance, HnswConfig, Indexes, PointIdType, QuantizationConfig, ScalarQuantization,
+    ScalarQuantizationConfig, ScalarType,
+};
+use tempfile::Builder;
+
+use crate::common::{
+    new_local_collection, optimizers_collection_config, simple_collection_fixture,
+    TEST_OPTIMIZERS_CONFIG,
+};
+
+const NUM_POINTS: u64 = 1_000;
+const CONVERGENCE_TIMEOUT: Duration = Duration::from_secs(60);
+
+async fn wait_for_green(collection: &Collection) {
+    let wait = async {
+        loop {
+            let info = collection.info(None).await.unwrap();
+            assert_ne!(info.status, CollectionStatus::Red);
+            if info.status == CollectionStatus::Green {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+    tokio::time::timeout(CONVERGENCE_TIMEOUT, wait)
+        .await
+        .expect("optimizations are not finished in time");
+}
+
+/// HNSW configs of all indexed segments of the collection
+async fn segment_hnsw_configs(collection: &Collection) -> Vec<HnswConfig> {
+    collection
+        .get_telemetry_data()
+        .await
+        .shards
+        .into_iter()
+        .filter_map(|shard| shard.local)
+        .flat_map(|local| local.segments)
+        .flat_map(|segment| segment.config.vector_data.into_values())
+        .filter_map(|vector_data| match vector_data.index {
+            Indexes::Plain {} => None,
+            Indexes::Hnsw(hnsw_config) => Some(hnsw_config),
+        })
+        .collect()
+}
+
+/// Number of points in each segment of the collection
+async fn segment_point_counts(collection: &Collection) -> Vec<usize> {
+    collection
+        .get_telemetry_data()
+        .await
+        .shards
+        .into_iter()
+        .filter_map(|shard| shard.local)
+        .flat_map(|local| local.segments)
+        .map(|segment| segment.info.num_points)
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_update_hnsw_config_rebuilds_segments() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+    collection
+        .update_optimizer_params(OptimizersConfig {
+            indexing_threshold: Some(1),
+            ..TEST_OPTIMIZERS_CONFIG.clone()
+        })
+        .await
+        .unwrap();
+    collection
+        .update_hnsw_config_from_diff(HnswConfigDiff {
+            m: Some(16),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    collection.recreate_optimizers_blocking().await.unwrap();
+
+    let ids = (0..NUM_POINTS).map(PointIdType::from).collect_vec();
+    let vectors = (0..NUM_POINTS)
+        .map(|n| vec![n as f32, 1.0, (n % 7) as f32, 0.5])
+        .collect_vec();
+    let insert = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids,
+            vectors: vectors.into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    wait_for_green(&collection).await;
+    let hnsw_configs = segment_hnsw_configs(&collection).await;
+    assert!(!hnsw_configs.is_empty());
+    assert!(hnsw_configs.iter().all(|hnsw| hnsw.m == 16));
+
+    // Change the parameter without recreating the collection
+    collection
+        .update_hnsw_config_from_diff(HnswConfigDiff {
+            m: Some(32),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    collection.recreate_optimizers_blocking().await.unwrap();
+
+    // Collection is not green, until all segments are rebuilt with the new parameter
+    wait_for_green(&collection).await;
+    let hnsw_configs = segment_hnsw_configs(&collection).await;
+    assert!(!hnsw_configs.is_empty());
+    assert!(hnsw_configs.iter().all(|hnsw| hnsw.m == 32));
+    assert_eq!(
+        collection.info(None).await.unwrap().points_count,
+        NUM_POINTS as usize
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_hnsw_config_per_vector() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let mut config = optimizers_collection_config(
+        1,
+        OptimizersConfig {
+            indexing_threshold: Some(1),
+            ..TEST_OPTIMIZERS_CONFIG.clone()
+        },
+    );
+    config.hnsw_config.m = 24;
+    let vector_params = |hnsw_config, quantization_config| VectorParams {
+        size: NonZeroU64::new(4).unwrap(),
+        distance: Distance::Dot,
+        hnsw_config,
+        quantization_config,
+        on_disk: None,
+        datatype: None,
+        multivector_config: None,
+    };
+    let quantization_config = QuantizationConfig::Scalar(ScalarQuantization {
+        scalar: ScalarQuantizationConfig {
+            r#type: ScalarType::Int8,
+            quantile: None,
+            always_ram: None,
+        },
+    });
+    config.params.vectors = VectorsConfig::Multi(BTreeMap::from([
+        (
+            "text".to_string(),
+            vector_params(
+                Some(HnswConfigDiff {
+                    m: Some(8),
+                    ..Default::default()
+                }),
+                None,
+            ),
+        ),
+        (
+            "image".to_string(),
+            vector_params(None, Some(quantization_config.clone())),
+        ),
+    ]));
+
+    let snapshot_path = collection_dir.path().join("snapshots");
+    let collection = new_local_collection(
+        "test".to_string(),
+        collection_dir.path(),
+        &snapshot_path,
+        &config,
+    )
+    .await
+    .unwrap();
+
+    let points = (0..NUM_POINTS)
+        .map(|n| {
+            let mut vectors = NamedVectors::default();
+            vectors.insert("text".to_string(), vec![n as f32, 1.0, 0.0, 0.5]);
+            vectors.insert("image".to_string(), vec![0.5, (n % 7) as f32, 1.0, 0.0]);
+            PointStruct {
+                id: n.into(),
+                vector: vectors.into(),
+                payload: None,
+            }
+        })
+        .collect_vec();
+    let insert = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    wait_for_green(&collection).await;
+
+    // Each vector is indexed with its own config, falling back to the collection one
+    let segment_configs = collection
+        .get_telemetry_data()
+        .await
+        .shards
+        .into_iter()
+        .filter_map(|shard| shard.local)
+        .flat_map(|local| local.segments)
+        .flat_map(|segment| segment.config.vector_data)
+        .collect_vec();
+    assert!(!segment_configs.is_empty());
+    for (vector_name, vector_data) in &segment_configs {
+        let expected_m = HashMap::from([("text", 8), ("image", 24)])[vector_name.as_str()];
+        match &vector_data.index {
+            Indexes::Hnsw(hnsw_config) => assert_eq!(hnsw_config.m, expected_m),
+            Indexes::Plain {} => panic!("vector {vector_name} is not indexed"),
+        }
+    }
+
+    let effective = collection
+        .info(None)
+        .await
+        .unwrap()
+        .effective_vectors_config;
+    assert_eq!(effective.len(), 2);
+    assert_eq!(effective["text"].hnsw_config.m, 8);
+    assert_eq!(effective["text"].quantization_config, None);
+    assert_eq!(effective["image"].hnsw_config.m, 24);
+    assert_eq!(
+        effective["image"].quantization_config,
+        Some(quantization_config),
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_lower_max_segment_size_splits_segments() {
+    // Vectors of size 4 take 16 bytes, so 4 KB fit 256 points
+    const MAX_SEGMENT_SIZE_KB: usize = 4;
+    const MAX_SEGMENT_POINTS: usize = 256;
+
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+    let ids = (0..NUM_POINTS).map(PointIdType::from).collect_vec();
+    let vectors = (0..NUM_POINTS)
+        .map(|n| vec![n as f32, 1.0, (n % 7) as f32, 0.5])
+        .collect_vec();
+    let insert = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids,
+            vectors: vectors.into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    wait_for_green(&collection).await;
+    let point_counts = segment_point_counts(&collection).await;
+    assert!(point_counts.iter().any(|&count| count > MAX_SEGMENT_POINTS));
+
+    let optimizers_diff = |max_segment_size, indexing_threshold| OptimizersConfigDiff {
+        deleted_threshold: None,
+        vacuum_min_vector_number: None,
+        default_segment_number: None,
+        max_segment_size: Some(max_segment_size),
+        memmap_threshold: None,
+        indexing_threshold,
+        flush_interval_sec: None,
+        max_optimization_threads: None,
+        merge_buffer_size: None,
+    };
+
+    // Segments smaller than the indexing threshold would never be indexed
+    let result = collection
+        .update_optimizer_params_from_diff(optimizers_diff(MAX_SEGMENT_SIZE_KB, None))
+        .await;
+    assert!(result.is_err());
+
+    // Lower the limit on the populated collection, without recreating it
+    collection
+        .update_optimizer_params_from_diff(optimizers_diff(MAX_SEGMENT_SIZE_KB, Some(2)))
+        .await
+        .unwrap();
+    collection.recreate_optimizers_blocking().await.unwrap();
+
+    // Oversized segments are split, until all of them fit into the new limit
+    wait_for_green(&collection).await;
+    let point_counts = segment_point_counts(&collection).await;
+    assert!(
+        point_counts
+            .iter()
+            .all(|&count| count <= MAX_SEGMENT_POINTS),
+        "{point_counts:?}",
+    );
+    assert!(point_counts.len() >= NUM_POINTS as usize / MAX_SEGMENT_POINTS);
+    assert_eq!(point_counts.iter().sum::<usize>(), NUM_POINTS as usize);
+    assert_eq!(
+        collection.info(None).await.unwrap().points_count,
+        NUM_POINTS as usize
+    );
+}
@@ -90,6 +90,8 @@ async fn test_collection_payload_reloading_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: true.into(),
+                order_by: None,
+                page_token: None,
             },
             None,
             None,
@@ -161,6 +163,8 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Fields(vec![String::from("k2")])),
                 with_vector: true.into(),
+                order_by: None,
+                page_token: None,
             },
             None,
             None,
@@ -195,6 +199,8 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(PayloadSelectorExclude::new(vec!["k1".to_string()]).into()),
                 with_vector: false.into(),
+                order_by: None,
+                page_token: None,
             },
             None,
             None,
@@ -229,3 +235,45 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
         _ => panic!("unexpected type"),
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wal_recovery_in_cluster_info() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    {
+        let collection = simple_collection_fixture(collection_dir.path(), N_SHARDS).await;
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperations::PointsBatch(Batch {
+                ids: vec![0, 1].into_iter().map(|x| x.into()).collect_vec(),
+                vectors: vec![vec![1.0, 0.0, 1.0, 1.0], vec![1.0, 0.0, 1.0, 0.0]].into(),
+                payloads: None,
+            })),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+
+        // Newly created shards have nothing to replay
+        let cluster_info = collection.cluster_info(0).await.unwrap();
+        assert_eq!(cluster_info.local_shards.len(), N_SHARDS as usize);
+        assert!(cluster_info
+            .local_shards
+            .iter()
+            .all(|shard| shard.wal_recovery.is_none()));
+    }
+
+    let collection_path = collection_dir.path();
+    let collection = load_local_collection(
+        "test".to_string(),
+        collection_path,
+        &collection_path.join("snapshots"),
+    )
+    .await;
+    let cluster_info = collection.cluster_info(0).await.unwrap();
+    assert_eq!(cluster_info.local_shards.len(), N_SHARDS as usize);
+    for shard in &cluster_info.local_shards {
+        let report = shard.wal_recovery.as_ref().unwrap();
+        assert_eq!(report.failed, 0);
+        assert!(report.failures.is_empty());
+    }
+}
@@ -1,20 +1,28 @@
 use std::collections::HashSet;
+use std::num::NonZeroU64;
 
 use collection::operations::payload_ops::{PayloadOps, SetPayload};
 use collection::operations::point_ops::{Batch, PointOperations, PointStruct, WriteOrdering};
 use collection::operations::types::{
-    CountRequest, PointRequest, RecommendRequest, ScrollRequest, SearchRequest, UpdateStatus,
+    CollectionError, CollectionResult, CountRequest, PointRequest, RecommendRequest, ScrollRequest,
+    SearchRequest, SearchRequestBatch, UpdateResult, UpdateStatus, VectorParams,
 };
+use collection::operations::vector_ops::{PointVectors, UpdateVectors, VectorOperations};
 use collection::operations::CollectionUpdateOperations;
 use collection::recommendations::recommend_by;
 use itertools::Itertools;
-use segment::data_types::vectors::VectorStruct;
+use segment::data_types::vectors::{NamedVector, NamedVectorStruct, VectorStruct};
 use segment::types::{
-    Condition, FieldCondition, Filter, HasIdCondition, Payload, PointIdType, WithPayloadInterface,
+    Condition, Distance, FieldCondition, Filter, HasIdCondition, Payload, PointIdType, ScoredPoint,
+    WithPayloadInterface,
 };
 use tempfile::Builder;
+use uuid::Uuid;
 
-use crate::common::{load_local_collection, simple_collection_fixture, N_SHARDS};
+use crate::common::{
+    load_local_collection, new_local_collection, optimizers_collection_config,
+    simple_collection_fixture, N_SHARDS, TEST_OPTIMIZERS_CONFIG,
+};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_updater() {
@@ -68,7 +76,7 @@ async fn test_collection_updater_with_shards(shard_number: u32) {
         score_threshold: None,
     };
 
-    let search_res = collection.search(search_request, None, None).await;
+    let search_res = collection.search(search_request, None, None, None).await;
 
     match search_res {
         Ok(res) => {
@@ -125,7 +133,7 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
         score_threshold: None,
     };
 
-    let search_res = collection.search(search_request, None, None).await;
+    let search_res = collection.search(search_request, None, None, None).await;
 
     match search_res {
         Ok(res) => {
@@ -200,6 +208,8 @@ async fn test_collection_loading_with_shards(shard_number: u32) {
                 payload,
                 points: Some(vec![2.into(), 3.into()]),
                 filter: None,
+                if_version: None,
+                key: None,
             }));
 
         collection
@@ -219,6 +229,7 @@ async fn test_collection_loading_with_shards(shard_number: u32) {
         ids: vec![1.into(), 2.into()],
         with_payload: Some(WithPayloadInterface::Bool(true)),
         with_vector: true.into(),
+        with_version: false,
     };
     let retrieved = loaded_collection
         .retrieve(request, None, None)
@@ -331,6 +342,7 @@ async fn test_recommendation_api_with_shards(shard_number: u32) {
         &collection,
         |_name| async { unreachable!("Should not be called in this test") },
         None,
+        None,
     )
     .await
     .unwrap();
@@ -386,6 +398,8 @@ async fn test_read_api_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: false.into(),
+                order_by: None,
+                page_token: None,
             },
             None,
             None,
@@ -397,6 +411,359 @@ async fn test_read_api_with_shards(shard_number: u32) {
     assert_eq!(result.points.len(), 2);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_batch_errors() {
+    test_search_batch_errors_with_shards(1).await;
+    test_search_batch_errors_with_shards(N_SHARDS).await;
+}
+
+async fn test_search_batch_errors_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..10).map(|x| x.into()).collect_vec(),
+            vectors: (0..10)
+                .map(|x| vec![x as f32, 1.0, 0.0, 0.0])
+                .collect_vec()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let search_request = |vector: NamedVectorStruct, limit: usize| SearchRequest {
+        vector,
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit,
+        offset: 0,
+        score_threshold: None,
+    };
+    let searches = vec![
+        search_request(vec![1.0, 0.0, 0.0, 0.0].into(), 3),
+        search_request(
+            NamedVector {
+                name: "missing".to_string(),
+                vector: vec![1.0, 0.0, 0.0, 0.0],
+            }
+            .into(),
+            3,
+        ),
+        search_request(vec![0.0, 1.0, 0.0, 0.0].into(), 5),
+    ];
+
+    // Invalid search fails only itself, other results keep the order of searches
+    let results = collection
+        .search_batch(
+            SearchRequestBatch {
+                searches: searches.clone(),
+                fail_fast: None,
+            },
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().len(), 3);
+    assert_eq!(results[0].as_ref().unwrap()[0].id, 9.into());
+    assert!(matches!(results[1], Err(CollectionError::BadInput { .. })));
+    assert_eq!(results[2].as_ref().unwrap().len(), 5);
+
+    // Invalid search fails the whole batch
+    let result = collection
+        .search_batch(
+            SearchRequestBatch {
+                searches,
+                fail_fast: Some(true),
+            },
+            None,
+            None,
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_malformed_query_vectors() {
+    test_search_malformed_query_vectors_with_shards(1).await;
+    test_search_malformed_query_vectors_with_shards(N_SHARDS).await;
+}
+
+async fn test_search_malformed_query_vectors_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let snapshot_path = collection_dir.path().join("snapshots");
+    let mut config = optimizers_collection_config(shard_number, TEST_OPTIMIZERS_CONFIG.clone());
+    config.params.vectors = VectorParams {
+        size: NonZeroU64::new(4).unwrap(),
+        distance: Distance::Cosine,
+        hnsw_config: None,
+        quantization_config: None,
+        on_disk: None,
+        datatype: None,
+        multivector_config: None,
+    }
+    .into();
+    let collection = new_local_collection(
+        "test".to_string(),
+        collection_dir.path(),
+        &snapshot_path,
+        &config,
+    )
+    .await
+    .unwrap();
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..10).map(|x| x.into()).collect_vec(),
+            vectors: (0..10)
+                .map(|x| vec![x as f32, 1.0, 0.0, 0.0])
+                .collect_vec()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let search_request = |vector: NamedVectorStruct| SearchRequest {
+        vector,
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 3,
+        offset: 0,
+        score_threshold: None,
+    };
+    let assert_rejected = |result: CollectionResult<Vec<ScoredPoint>>, expected: &[&str]| {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, CollectionError::BadInput { .. }),
+            "unexpected error: {err}"
+        );
+        let message = err.to_string();
+        for part in expected {
+            assert!(message.contains(part), "{message}");
+        }
+    };
+
+    let malformed = [
+        (vec![], vec!["default vector", "empty", "expected dim: 4"]),
+        (
+            vec![1.0, 0.0, 0.0],
+            vec!["default vector", "expected dim: 4, got 3"],
+        ),
+        (
+            vec![1.0, 0.0, 0.0, 0.0, 1.0],
+            vec!["default vector", "expected dim: 4, got 5"],
+        ),
+        (
+            vec![1.0, f32::NAN, 0.0, 0.0],
+            vec!["default vector", "position 1"],
+        ),
+    ];
+    for (vector, expected) in &malformed {
+        let result = collection
+            .search(search_request(vector.clone().into()), None, None, None)
+            .await;
+        assert_rejected(result, expected);
+    }
+
+    // Each malformed search of a batch fails on its own
+    let mut searches = vec![search_request(vec![1.0, 0.0, 0.0, 0.0].into())];
+    searches.extend(
+        malformed
+            .iter()
+            .map(|(vector, _)| search_request(vector.clone().into())),
+    );
+    let results = collection
+        .search_batch(
+            SearchRequestBatch {
+                searches,
+                fail_fast: None,
+            },
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(results.len(), malformed.len() + 1);
+    assert_eq!(results[0].as_ref().unwrap()[0].id, 9.into());
+    for (result, (_, expected)) in results.into_iter().skip(1).zip(&malformed) {
+        assert_rejected(result, expected);
+    }
+
+    // Zero vector can't be normalized, it scores 0.0 with every point instead of NaN
+    let result = collection
+        .search(
+            search_request(vec![0.0, 0.0, 0.0, 0.0].into()),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 3);
+    assert!(result.iter().all(|point| point.score == 0.0), "{result:?}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_big_and_uuid_point_ids() {
+    test_collection_big_and_uuid_point_ids_with_shards(1).await;
+    test_collection_big_and_uuid_point_ids_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_big_and_uuid_point_ids_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    // Integer ids above 2^53 can't be represented by f64 exactly
+    let mut num_ids = vec![0, 1, (1 << 53) + 1, (1 << 63) + 1, u64::MAX];
+    let mut uuids = (0..5).map(|_| Uuid::new_v4()).collect_vec();
+    num_ids.sort();
+    uuids.sort();
+    let ids: Vec<PointIdType> = num_ids
+        .iter()
+        .map(|&id| id.into())
+        .chain(uuids.iter().map(|&uuid| PointIdType::Uuid(uuid)))
+        .collect();
+
+    // Upsert in mixed order
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        ids.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &id)| PointStruct {
+                id,
+                vector: vec![i as f32, 1.0, 0.0, 0.0].into(),
+                payload: None,
+            })
+            .collect_vec()
+            .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let retrieve = |ids: Vec<PointIdType>| {
+        collection.retrieve(
+            PointRequest {
+                ids,
+                with_payload: None,
+                with_vector: false.into(),
+                with_version: false,
+            },
+            None,
+            None,
+        )
+    };
+    let records = retrieve(ids.clone()).await.unwrap();
+    let retrieved: HashSet<_> = records.iter().map(|record| record.id).collect();
+    assert_eq!(retrieved, ids.iter().copied().collect());
+
+    let scroll = |offset: Option<PointIdType>, filter: Option<Filter>| {
+        collection.scroll_by(
+            ScrollRequest {
+                offset,
+                limit: Some(3),
+                filter,
+                with_payload: None,
+                with_vector: false.into(),
+                order_by: None,
+                page_token: None,
+            },
+            None,
+            None,
+        )
+    };
+
+    // Pages go through integer ids first, then through UUIDs, in the order of `PointIdType`
+    let mut scrolled = Vec::new();
+    let mut offset = None;
+    loop {
+        let page = scroll(offset, None).await.unwrap();
+        scrolled.extend(page.points.iter().map(|record| record.id));
+        offset = page.next_page_offset;
+        if offset.is_none() {
+            break;
+        }
+    }
+    assert_eq!(scrolled, ids);
+
+    // Offset between UUIDs, or above all integer ids
+    let page = scroll(Some(ids[6]), None).await.unwrap();
+    assert_eq!(page.points.iter().map(|r| r.id).collect_vec(), ids[6..9]);
+    assert_eq!(page.next_page_offset, Some(ids[9]));
+    let page = scroll(Some(u64::MAX.into()), None).await.unwrap();
+    assert_eq!(page.points.iter().map(|r| r.id).collect_vec(), ids[4..7]);
+
+    // Has id condition with integer ids and UUIDs at once
+    let selected = [ids[3], ids[4], ids[7], PointIdType::from((1 << 63) + 2)];
+    let has_id_filter = || {
+        Filter::new_must(Condition::HasId(HasIdCondition::from(
+            selected.iter().copied().collect::<HashSet<_>>(),
+        )))
+    };
+    let page = scroll(None, Some(has_id_filter())).await.unwrap();
+    assert_eq!(
+        page.points.iter().map(|r| r.id).collect_vec(),
+        vec![ids[3], ids[4], ids[7]],
+    );
+    let count = collection
+        .count(
+            CountRequest {
+                filter: Some(has_id_filter()),
+                exact: true,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(count.count, 3);
+
+    // Delete by ids and by the has id condition
+    let delete_points = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: vec![ids[4], ids[9]],
+    });
+    collection
+        .update_from_client(delete_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    let delete_filtered = CollectionUpdateOperations::PointOperation(
+        PointOperations::DeletePointsByFilter(has_id_filter()),
+    );
+    collection
+        .update_from_client(delete_filtered, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let remaining = [ids[0], ids[1], ids[2], ids[5], ids[6], ids[8]];
+    let records = retrieve(ids.clone()).await.unwrap();
+    let retrieved: HashSet<_> = records.iter().map(|record| record.id).collect();
+    assert_eq!(retrieved, remaining.iter().copied().collect());
+    let page = scroll(Some(ids[2]), None).await.unwrap();
+    assert_eq!(
+        page.points.iter().map(|r| r.id).collect_vec(),
+        remaining[2..5],
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_delete_points_by_filter() {
     test_collection_delete_points_by_filter_with_shards(1).await;
@@ -443,6 +810,7 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
     let delete_filter = segment::types::Filter {
         should: None,
         must: Some(vec![Condition::HasId(HasIdCondition::from(to_be_deleted))]),
+        min_should: None,
         must_not: None,
     };
 
@@ -469,6 +837,8 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: false.into(),
+                order_by: None,
+                page_token: None,
             },
             None,
             None,
@@ -482,3 +852,346 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
     assert_eq!(result.points.get(1).unwrap().id, 2.into());
     assert_eq!(result.points.get(2).unwrap().id, 4.into());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_rejects_non_finite_vectors() {
+    test_collection_rejects_non_finite_vectors_with_shards(1).await;
+    test_collection_rejects_non_finite_vectors_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_rejects_non_finite_vectors_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let valid_vectors = vec![
+        vec![1.0, 0.0, 1.0, 1.0],
+        vec![1.0, 0.0, 1.0, 0.0],
+        vec![1.0, 1.0, 1.0, 1.0],
+        vec![1.0, 1.0, 0.0, 1.0],
+        vec![1.0, 0.0, 0.0, 0.0],
+    ];
+    let mut vectors = valid_vectors.clone();
+    vectors[3][2] = f32::NAN;
+
+    let assert_rejected = |result: CollectionResult<UpdateResult>, point_id: u64| {
+        let err = result.unwrap_err();
+        assert!(
+            matches!(err, CollectionError::BadInput { .. }),
+            "unexpected error: {err}"
+        );
+        let message = err.to_string();
+        assert!(message.contains(&format!("point {point_id}")), "{message}");
+        assert!(message.contains("position 2"), "{message}");
+    };
+
+    // Single point
+    let insert_point = CollectionUpdateOperations::PointOperation(
+        vec![PointStruct {
+            id: 3.into(),
+            vector: vectors[3].clone().into(),
+            payload: None,
+        }]
+        .into(),
+    );
+    let result = collection
+        .update_from_client(insert_point, true, WriteOrdering::default())
+        .await;
+    assert_rejected(result, 3);
+
+    // List of points, only one of them is invalid
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        vectors
+            .iter()
+            .enumerate()
+            .map(|(id, vector)| PointStruct {
+                id: (id as u64).into(),
+                vector: vector.clone().into(),
+                payload: None,
+            })
+            .collect_vec()
+            .into(),
+    );
+    let result = collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await;
+    assert_rejected(result, 3);
+
+    // Batch, only one of the points is invalid
+    let insert_batch = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..vectors.len() as u64).map(|x| x.into()).collect_vec(),
+            vectors: vectors.clone().into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    let result = collection
+        .update_from_client(insert_batch, true, WriteOrdering::default())
+        .await;
+    assert_rejected(result, 3);
+
+    // None of the points is applied
+    let count_request = || CountRequest {
+        filter: None,
+        exact: true,
+    };
+    let count_res = collection.count(count_request(), None).await.unwrap();
+    assert_eq!(count_res.count, 0);
+
+    // Valid points can be inserted, but not updated with an infinite vector
+    let insert_batch = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..valid_vectors.len() as u64)
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: valid_vectors.clone().into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_batch, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let update_vectors = CollectionUpdateOperations::VectorOperation(
+        VectorOperations::UpdateVectors(UpdateVectors {
+            points: vec![
+                PointVectors {
+                    id: 0.into(),
+                    vector: vec![0.0, 1.0, 0.0, 0.0].into(),
+                    if_version: None,
+                },
+                PointVectors {
+                    id: 1.into(),
+                    vector: vec![0.0, 1.0, f32::INFINITY, 0.0].into(),
+                    if_version: None,
+                },
+            ],
+        }),
+    );
+    let result = collection
+        .update_from_client(update_vectors, true, WriteOrdering::default())
+        .await;
+    assert_rejected(result, 1);
+
+    let count_res = collection.count(count_request(), None).await.unwrap();
+    assert_eq!(count_res.count, valid_vectors.len());
+
+    let records = collection
+        .retrieve(
+            PointRequest {
+                ids: vec![0.into()],
+                with_payload: None,
+                with_vector: true.into(),
+                with_version: false,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        records[0].vector,
+        Some(VectorStruct::Single(valid_vectors[0].clone()))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_update_batch() {
+    test_collection_update_batch_with_shards(1).await;
+    test_collection_update_batch_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_update_batch_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let set_payload = |point_id: u64, payload: &str| {
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+            payload: serde_json::from_str::<Payload>(payload).unwrap(),
+            points: Some(vec![point_id.into()]),
+            filter: None,
+            if_version: None,
+            key: None,
+        }))
+    };
+
+    let operations = vec![
+        // new points, which the following operations rely on
+        CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: vec![10.into(), 11.into()],
+                vectors: vec![vec![1.0, 0.0, 1.0, 1.0], vec![1.0, 0.0, 1.0, 0.0]].into(),
+                payloads: None,
+            }
+            .into(),
+        ),
+        set_payload(10, r#"{ "k": "v1" }"#),
+        // point doesn't exist, the rest of the batch is still applied
+        CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(
+            UpdateVectors {
+                points: vec![PointVectors {
+                    id: 100.into(),
+                    vector: vec![0.0, 1.0, 0.0, 0.0].into(),
+                    if_version: None,
+                }],
+            },
+        )),
+        set_payload(11, r#"{ "k": "v2" }"#),
+        CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+            ids: vec![11.into()],
+        }),
+    ];
+
+    let results = collection
+        .update_batch_from_client(operations, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let statuses = results.iter().map(|result| &result.status).collect_vec();
+    assert_eq!(
+        statuses,
+        vec![
+            &UpdateStatus::Completed,
+            &UpdateStatus::Completed,
+            &UpdateStatus::Failed,
+            &UpdateStatus::Completed,
+            &UpdateStatus::Completed,
+        ]
+    );
+    assert!(results[2].error.as_ref().unwrap().contains("100"));
+    assert!(results
+        .iter()
+        .enumerate()
+        .all(|(idx, result)| (idx == 2) == result.error.is_some()));
+
+    let records = collection
+        .retrieve(
+            PointRequest {
+                ids: vec![10.into(), 11.into(), 100.into()],
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: false.into(),
+                with_version: false,
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, PointIdType::from(10));
+    let value = records[0].payload.as_ref().unwrap().get_value("k");
+    assert_eq!(value.into_iter().next(), Some(&serde_json::json!("v1")));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_client_operation_id() {
+    test_collection_client_operation_id_with_shards(1).await;
+    test_collection_client_operation_id_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_client_operation_id_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let insert_points = || {
+        CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: vec![0.into(), 1.into(), 2.into()],
+                vectors: vec![
+                    vec![1.0, 0.0, 1.0, 1.0],
+                    vec![1.0, 0.0, 1.0, 0.0],
+                    vec![1.0, 1.0, 1.0, 1.0],
+                ]
+                .into(),
+                payloads: None,
+            }
+            .into(),
+        )
+    };
+    let delete_points = || {
+        CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(
+            Filter::new_must(Condition::HasId(HasIdCondition::from(HashSet::from([
+                PointIdType::from(0),
+                PointIdType::from(1),
+            ])))),
+        ))
+    };
+    let count_all = || CountRequest {
+        filter: None,
+        exact: true,
+    };
+    let client_op_id = Uuid::new_v4();
+
+    {
+        let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+        collection
+            .update_from_client(insert_points(), true, WriteOrdering::default())
+            .await
+            .unwrap();
+
+        let deleted = collection
+            .update_from_client_with_shard_key(
+                delete_points(),
+                None,
+                Some(client_op_id),
+                true,
+                WriteOrdering::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(deleted.status, UpdateStatus::Completed);
+
+        collection
+            .update_from_client(insert_points(), true, WriteOrdering::default())
+            .await
+            .unwrap();
+
+        // Retry is not applied again, points inserted after the original operation are kept
+        let retried = collection
+            .update_from_client_with_shard_key(
+                delete_points(),
+                None,
+                Some(client_op_id),
+                true,
+                WriteOrdering::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(retried.status, UpdateStatus::Completed);
+        assert_eq!(retried.operation_id, deleted.operation_id);
+
+        let count = collection.count(count_all(), None).await.unwrap();
+        assert_eq!(count.count, 3);
+    }
+
+    // Applied ids are restored from WAL
+    let collection_path = collection_dir.path();
+    let loaded_collection = load_local_collection(
+        "test".to_string(),
+        collection_path,
+        &collection_path.join("snapshots"),
+    )
+    .await;
+    loaded_collection
+        .update_from_client_with_shard_key(
+            delete_points(),
+            None,
+            Some(client_op_id),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    let count = loaded_collection.count(count_all(), None).await.unwrap();
+    assert_eq!(count.count, 3);
+
+    // Operation without id is applied
+    loaded_collection
+        .update_from_client(delete_points(), true, WriteOrdering::default())
+        .await
+        .unwrap();
+    let count = loaded_collection.count(count_all(), None).await.unwrap();
+    assert_eq!(count.count, 1);
+}
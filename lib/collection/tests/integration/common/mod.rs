@@ -29,14 +29,53 @@ pub const TEST_OPTIMIZERS_CONFIG: OptimizersConfig = OptimizersConfig {
     indexing_threshold: Some(50_000),
     flush_interval_sec: 30,
     max_optimization_threads: 2,
+    merge_buffer_size: None,
 };
 
 #[cfg(test)]
 #[allow(dead_code)]
 pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32) -> Collection {
+    optimizers_collection_fixture(
+        collection_path,
+        shard_number,
+        TEST_OPTIMIZERS_CONFIG.clone(),
+    )
+    .await
+}
+
+/// Same as `simple_collection_fixture`, but with the given optimizers config
+#[cfg(test)]
+#[allow(dead_code)]
+pub async fn optimizers_collection_fixture(
+    collection_path: &Path,
+    shard_number: u32,
+    optimizer_config: OptimizersConfig,
+) -> Collection {
+    let collection_config = optimizers_collection_config(shard_number, optimizer_config);
+
+    let snapshot_path = collection_path.join("snapshots");
+
+    // Default to a collection with all the shards local
+    new_local_collection(
+        "test".to_string(),
+        collection_path,
+        &snapshot_path,
+        &collection_config,
+    )
+    .await
+    .unwrap()
+}
+
+/// Config of the collection, created by `optimizers_collection_fixture`
+#[allow(dead_code)]
+pub fn optimizers_collection_config(
+    shard_number: u32,
+    optimizer_config: OptimizersConfig,
+) -> CollectionConfig {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -46,33 +85,29 @@ pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
+            multivector_config: None,
         }
         .into(),
         shard_number: NonZeroU32::new(shard_number).expect("Shard number can not be zero"),
+        sharding_method: None,
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
     };
 
-    let collection_config = CollectionConfig {
+    CollectionConfig {
         params: collection_params,
-        optimizer_config: TEST_OPTIMIZERS_CONFIG.clone(),
+        optimizer_config,
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
-    };
-
-    let snapshot_path = collection_path.join("snapshots");
-
-    // Default to a collection with all the shards local
-    new_local_collection(
-        "test".to_string(),
-        collection_path,
-        &snapshot_path,
-        &collection_config,
-    )
-    .await
-    .unwrap()
+        strict_mode_config: Default::default(),
+    }
 }
 
 pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -138,4 +173,5 @@ pub async fn load_local_collection(
         None,
     )
     .await
+    .unwrap()
 }
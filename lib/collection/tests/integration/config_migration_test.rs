@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::num::{NonZeroU32, NonZeroU64};
+use std::path::{Path, PathBuf};
+
+use collection::config::{CollectionConfig, CollectionParams, WalConfig, COLLECTION_CONFIG_FILE};
+use collection::config_migration::{
+    config_backup_file, CONFIG_VERSION_KEY, CURRENT_CONFIG_VERSION,
+};
+use collection::operations::types::{VectorParams, VectorsConfig};
+use collection::optimizers_builder::OptimizersConfig;
+use segment::types::{
+    Distance, HnswConfig, QuantizationConfig, ScalarQuantization, ScalarQuantizationConfig,
+    ScalarType,
+};
+use serde_json::Value;
+use tempfile::Builder;
+
+fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/integration/fixtures/collection_config")
+        .join(name)
+}
+
+fn vector_params(size: u64, distance: Distance) -> VectorParams {
+    VectorParams {
+        size: NonZeroU64::new(size).unwrap(),
+        distance,
+        hnsw_config: None,
+        quantization_config: None,
+        on_disk: None,
+        datatype: None,
+        multivector_config: None,
+    }
+}
+
+fn stored_version(collection_dir: &Path) -> Option<u64> {
+    let contents = fs::read_to_string(collection_dir.join(COLLECTION_CONFIG_FILE)).unwrap();
+    let stored: Value = serde_json::from_str(&contents).unwrap();
+    stored.get(CONFIG_VERSION_KEY).and_then(Value::as_u64)
+}
+
+/// Load the fixture as the config of a collection, check that it is migrated, written back and
+/// backed up, and return the migrated config
+fn load_migrated(fixture: &str) -> CollectionConfig {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let original = fs::read(fixture_path(fixture)).unwrap();
+    fs::write(
+        collection_dir.path().join(COLLECTION_CONFIG_FILE),
+        &original,
+    )
+    .unwrap();
+
+    let config = CollectionConfig::load(collection_dir.path()).unwrap();
+
+    assert_eq!(
+        stored_version(collection_dir.path()),
+        Some(CURRENT_CONFIG_VERSION)
+    );
+    let backup = fs::read(collection_dir.path().join(config_backup_file(0))).unwrap();
+    assert_eq!(backup, original);
+
+    // Migrated config is loaded as is, without another migration
+    fs::remove_file(collection_dir.path().join(config_backup_file(0))).unwrap();
+    assert_eq!(
+        CollectionConfig::load(collection_dir.path()).unwrap(),
+        config
+    );
+    assert!(!collection_dir.path().join(config_backup_file(0)).exists());
+
+    config
+}
+
+#[test]
+fn test_migrate_single_vector_config() {
+    let config = load_migrated("v0_single_vector.json");
+
+    let expected = CollectionConfig {
+        params: CollectionParams {
+            vectors: vector_params(4, Distance::Dot).into(),
+            sparse_vectors: None,
+            shard_number: NonZeroU32::new(1).unwrap(),
+            sharding_method: None,
+            replication_factor: NonZeroU32::new(1).unwrap(),
+            write_consistency_factor: NonZeroU32::new(1).unwrap(),
+            on_disk_payload: false,
+            tenant_key: None,
+            store_original_vectors: false,
+            search_timeout_sec: None,
+        },
+        hnsw_config: HnswConfig {
+            m: 16,
+            ef_construct: 100,
+            full_scan_threshold: 10000,
+            max_indexing_threads: 0,
+            on_disk: None,
+            payload_m: None,
+        },
+        optimizer_config: OptimizersConfig {
+            deleted_threshold: 0.2,
+            vacuum_min_vector_number: 1000,
+            default_segment_number: 0,
+            max_segment_size: Some(200000),
+            memmap_threshold: Some(50000),
+            indexing_threshold: Some(20000),
+            flush_interval_sec: 5,
+            max_optimization_threads: 1,
+            merge_buffer_size: None,
+        },
+        wal_config: WalConfig::default(),
+        quantization_config: None,
+        strict_mode_config: None,
+    };
+    assert_eq!(config, expected);
+}
+
+#[test]
+fn test_migrate_kb_thresholds_config() {
+    let config = load_migrated("v1_kb_thresholds.json");
+
+    let mut text_params = vector_params(8, Distance::Euclid);
+    text_params.on_disk = Some(true);
+    let vectors = VectorsConfig::Multi(BTreeMap::from([
+        ("image".to_string(), vector_params(4, Distance::Cosine)),
+        ("text".to_string(), text_params),
+    ]));
+    assert_eq!(config.params.vectors, vectors);
+    assert_eq!(config.params.shard_number.get(), 2);
+    assert!(config.params.on_disk_payload);
+    assert_eq!(config.hnsw_config.full_scan_threshold, 10000);
+    assert_eq!(config.optimizer_config.max_segment_size, Some(300000));
+    assert_eq!(config.optimizer_config.memmap_threshold, None);
+    assert_eq!(config.optimizer_config.indexing_threshold, Some(20000));
+    assert_eq!(config.optimizer_config.max_optimization_threads, 2);
+}
+
+#[test]
+fn test_migrate_missing_defaults_config() {
+    let config = load_migrated("v2_missing_defaults.json");
+
+    assert_eq!(
+        config.params.vectors,
+        VectorsConfig::Single(vector_params(4, Distance::Dot)),
+    );
+    assert_eq!(config.params.replication_factor.get(), 2);
+    assert_eq!(config.hnsw_config.m, 32);
+    assert_eq!(config.hnsw_config.on_disk, Some(false));
+    assert_eq!(config.optimizer_config.memmap_threshold, Some(20000));
+    assert_eq!(config.optimizer_config.max_optimization_threads, 1);
+    assert_eq!(
+        config.quantization_config,
+        Some(QuantizationConfig::Scalar(ScalarQuantization {
+            scalar: ScalarQuantizationConfig {
+                r#type: ScalarType::Int8,
+                quantile: Some(0.99),
+                always_ram: Some(true),
+            },
+        })),
+    );
+}
+
+#[test]
+fn test_current_config_is_not_migrated() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    fs::write(
+        collection_dir.path().join(COLLECTION_CONFIG_FILE),
+        fs::read(fixture_path("v0_single_vector.json")).unwrap(),
+    )
+    .unwrap();
+    let config = CollectionConfig::load(collection_dir.path()).unwrap();
+
+    let saved_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    config.save(saved_dir.path()).unwrap();
+    assert_eq!(
+        stored_version(saved_dir.path()),
+        Some(CURRENT_CONFIG_VERSION)
+    );
+    assert_eq!(CollectionConfig::load(saved_dir.path()).unwrap(), config);
+    for version in 0..=CURRENT_CONFIG_VERSION {
+        assert!(!saved_dir.path().join(config_backup_file(version)).exists());
+    }
+}
+
+#[test]
+fn test_newer_config_version_is_rejected() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let config_path = collection_dir.path().join(COLLECTION_CONFIG_FILE);
+    let mut stored: serde_json::Map<String, Value> =
+        serde_json::from_slice(&fs::read(fixture_path("v2_missing_defaults.json")).unwrap())
+            .unwrap();
+    stored.insert(
+        CONFIG_VERSION_KEY.to_string(),
+        Value::from(CURRENT_CONFIG_VERSION + 1),
+    );
+    let contents = serde_json::to_vec(&stored).unwrap();
+    fs::write(&config_path, &contents).unwrap();
+
+    let err = CollectionConfig::load(collection_dir.path()).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("newer version of Qdrant"), "{message}");
+    assert!(
+        message.contains(&format!("version {}", CURRENT_CONFIG_VERSION + 1)),
+        "{message}"
+    );
+
+    // Config is left untouched
+    assert_eq!(fs::read(&config_path).unwrap(), contents);
+    assert_eq!(fs::read_dir(collection_dir.path()).unwrap().count(), 1);
+}
@@ -0,0 +1,184 @@
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, PointOperations, WriteOrdering};
+use collection::operations::types::{CountRequest, ScrollRequest};
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use itertools::Itertools;
+use segment::types::{
+    Condition, FieldCondition, Filter, Match, Payload, PayloadFieldSchema, PayloadSchemaType,
+    PointIdType, Range, ValueVariants,
+};
+use serde_json::{json, Value};
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 100;
+
+async fn fixture(collection_path: &std::path::Path, shard_number: u32) -> Collection {
+    let collection = simple_collection_fixture(collection_path, shard_number).await;
+
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "num".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Integer)),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Small batches, to spread points over segments
+    for batch in &(0..NUM_POINTS).chunks(10) {
+        let ids = batch.collect_vec();
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+                vectors: ids
+                    .iter()
+                    .map(|n| vec![*n as f32, 0.0, 0.0, 1.0])
+                    .collect_vec()
+                    .into(),
+                payloads: Some(
+                    ids.iter()
+                        .map(|n| Some(Payload::from(json!({ "num": n % 5, "even": n % 2 == 0 }))))
+                        .collect_vec(),
+                ),
+            }
+            .into(),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+
+    let delete_points = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: (0..NUM_POINTS)
+            .step_by(7)
+            .map(PointIdType::from)
+            .collect_vec(),
+    });
+    collection
+        .update_from_client(delete_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    collection
+}
+
+/// Filter, along with the same condition checked on a payload
+type FilterCase = (Option<Filter>, fn(&Value) -> bool);
+
+fn filters() -> Vec<FilterCase> {
+    vec![
+        (None, |_| true),
+        (
+            Some(Filter::new_must(Condition::Field(
+                FieldCondition::new_match("num", Match::new_value(ValueVariants::Integer(2))),
+            ))),
+            |payload| payload["num"] == 2,
+        ),
+        (
+            Some(Filter::new_must(Condition::Field(
+                FieldCondition::new_range(
+                    "num",
+                    Range {
+                        gte: Some(3.0),
+                        ..Default::default()
+                    },
+                ),
+            ))),
+            |payload| payload["num"].as_i64().unwrap() >= 3,
+        ),
+        (
+            Some(Filter::new_must(Condition::Field(
+                FieldCondition::new_match("even", Match::new_value(ValueVariants::Bool(true))),
+            ))),
+            |payload| payload["even"] == true,
+        ),
+    ]
+}
+
+/// Count points by iterating over all of them
+async fn brute_force_count(collection: &Collection, predicate: fn(&Value) -> bool) -> usize {
+    let result = collection
+        .scroll_by(
+            ScrollRequest {
+                limit: Some(NUM_POINTS as usize),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.next_page_offset, None);
+    result
+        .points
+        .iter()
+        .filter(|point| predicate(&Value::Object(point.payload.clone().unwrap().0)))
+        .count()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_count_exact() {
+    test_count_exact_with_shards(1).await;
+    test_count_exact_with_shards(N_SHARDS).await;
+}
+
+async fn test_count_exact_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    for (filter, predicate) in filters() {
+        let result = collection
+            .count(
+                CountRequest {
+                    filter,
+                    exact: true,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(result.exact);
+        assert_eq!(
+            result.count,
+            brute_force_count(&collection, predicate).await
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_count_approximate() {
+    test_count_approximate_with_shards(1).await;
+    test_count_approximate_with_shards(N_SHARDS).await;
+}
+
+async fn test_count_approximate_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    let total = brute_force_count(&collection, |_| true).await;
+    for (filter, _predicate) in filters() {
+        let is_filtered = filter.is_some();
+        let result = collection
+            .count(
+                CountRequest {
+                    filter,
+                    exact: false,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!result.exact);
+        if is_filtered {
+            assert!(result.count <= total);
+        } else {
+            // Number of all points is known without iterating over them
+            assert_eq!(result.count, total);
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use collection::collection::Collection;
+use collection::discovery::discover;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{
+    CollectionError, ContextExamplePair, DiscoverRequest, RecommendExample,
+};
+use collection::operations::CollectionUpdateOperations;
+use itertools::Itertools;
+use segment::types::{PointIdType, ScoredPoint};
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+async fn fixture(collection_path: &std::path::Path, shard_number: u32) -> Collection {
+    let collection = simple_collection_fixture(collection_path, shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4, 5, 6]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, -1.0, 0.0, 0.0],
+                vec![0.5, 0.5, 0.0, 0.0],
+                vec![0.2, 0.1, 0.0, 1.0],
+                vec![2.0, -0.1, 0.0, 0.0],
+                vec![-1.0, 1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+                vec![0.0, -1.0, 0.0, 0.0],
+            ]
+            .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    collection
+}
+
+fn request(target: RecommendExample, context: Vec<ContextExamplePair>) -> DiscoverRequest {
+    DiscoverRequest {
+        target,
+        context,
+        filter: None,
+        params: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        using: None,
+        lookup_from: None,
+    }
+}
+
+fn pair(positive: RecommendExample, negative: RecommendExample) -> ContextExamplePair {
+    ContextExamplePair { positive, negative }
+}
+
+async fn discover_points(
+    collection: &Collection,
+    request: DiscoverRequest,
+) -> Result<Vec<ScoredPoint>, CollectionError> {
+    discover(
+        request,
+        collection,
+        |_name| async { unreachable!("Should not be called in this test") },
+        None,
+    )
+    .await
+}
+
+fn ids(result: &[ScoredPoint]) -> Vec<PointIdType> {
+    result.iter().map(|point| point.id).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discover_within_context() {
+    test_discover_within_context_with_shards(1).await;
+    test_discover_within_context_with_shards(N_SHARDS).await;
+}
+
+async fn test_discover_within_context_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    // Context region is the half-space of positive second coordinate
+    let result = discover_points(
+        &collection,
+        request(
+            RecommendExample::Vector(vec![1.0, 0.0, 0.0, 0.0]),
+            vec![pair(5.into(), 6.into())],
+        ),
+    )
+    .await
+    .unwrap();
+
+    // Points within the region go first, even if less similar to the target,
+    // and the examples are excluded
+    assert_eq!(
+        ids(&result),
+        vec![1.into(), 2.into(), 4.into(), 3.into(), 0.into()],
+    );
+    assert!(result[..3].iter().all(|point| point.score > 0.0));
+    assert!(result[3..].iter().all(|point| point.score < 0.0));
+
+    // Second pair excludes points with the last coordinate
+    let result = discover_points(
+        &collection,
+        request(
+            RecommendExample::Vector(vec![1.0, 0.0, 0.0, 0.0]),
+            vec![
+                pair(
+                    RecommendExample::Vector(vec![0.0, 1.0, 0.0, 0.0]),
+                    RecommendExample::Vector(vec![0.0, -1.0, 0.0, 0.0]),
+                ),
+                pair(
+                    RecommendExample::Vector(vec![0.0, 0.0, 1.0, 0.0]),
+                    RecommendExample::Vector(vec![0.0, 0.0, 0.0, 1.0]),
+                ),
+            ],
+        ),
+    )
+    .await
+    .unwrap();
+
+    // Raw vectors don't exclude any point
+    assert_eq!(
+        ids(&result),
+        vec![
+            1.into(),
+            5.into(),
+            4.into(),
+            3.into(),
+            0.into(),
+            2.into(),
+            6.into()
+        ],
+    );
+    assert!(result
+        .iter()
+        .tuple_windows()
+        .all(|(a, b)| a.score >= b.score));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discover_invalid_context() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), N_SHARDS).await;
+
+    let result = discover_points(&collection, request(1.into(), vec![])).await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+
+    let result = discover_points(
+        &collection,
+        request(1.into(), vec![pair(5.into(), 100.into())]),
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(CollectionError::PointNotFound { missed_point_id }) if missed_point_id == 100.into()
+    ));
+}
@@ -0,0 +1,339 @@
+use std::num::{NonZeroU32, NonZeroU64};
+use std::path::Path;
+
+use collection::collection::Collection;
+use collection::config::{CollectionConfig, CollectionParams, WalConfig};
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{PointRequest, SearchRequest, UpdateStatus, VectorParams};
+use collection::operations::CollectionUpdateOperations;
+use itertools::Itertools;
+use segment::data_types::vectors::VectorStruct;
+use segment::types::{Distance, PointIdType, ScoredPoint, VectorStorageDatatype};
+use tempfile::Builder;
+
+use crate::common::{new_local_collection, N_SHARDS, TEST_OPTIMIZERS_CONFIG};
+
+fn distance_collection_config(
+    shard_number: u32,
+    distance: Distance,
+    datatype: Option<VectorStorageDatatype>,
+) -> CollectionConfig {
+    let wal_config = WalConfig {
+        wal_capacity_mb: 1,
+        wal_segments_ahead: 0,
+        ..Default::default()
+    };
+
+    let collection_params = CollectionParams {
+        vectors: VectorParams {
+            size: NonZeroU64::new(4).unwrap(),
+            distance,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype,
+            multivector_config: None,
+        }
+        .into(),
+        shard_number: NonZeroU32::new(shard_number).expect("Shard number can not be zero"),
+        sharding_method: None,
+        replication_factor: NonZeroU32::new(1).unwrap(),
+        write_consistency_factor: NonZeroU32::new(1).unwrap(),
+        on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
+    };
+
+    CollectionConfig {
+        params: collection_params,
+        optimizer_config: TEST_OPTIMIZERS_CONFIG.clone(),
+        wal_config,
+        hnsw_config: Default::default(),
+        quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+    }
+}
+
+async fn distance_collection_fixture(
+    collection_path: &Path,
+    shard_number: u32,
+    distance: Distance,
+    datatype: Option<VectorStorageDatatype>,
+) -> Collection {
+    let collection_config = distance_collection_config(shard_number, distance, datatype);
+    collection_from_config(collection_path, &collection_config).await
+}
+
+async fn collection_from_config(
+    collection_path: &Path,
+    collection_config: &CollectionConfig,
+) -> Collection {
+    let snapshot_path = collection_path.join("snapshots");
+
+    new_local_collection(
+        "test".to_string(),
+        collection_path,
+        &snapshot_path,
+        collection_config,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_manhattan_distance() {
+    test_manhattan_distance_with_shards(1).await;
+    test_manhattan_distance_with_shards(N_SHARDS).await;
+}
+
+async fn test_manhattan_distance_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = distance_collection_fixture(
+        collection_dir.path(),
+        shard_number,
+        Distance::Manhattan,
+        None,
+    )
+    .await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, 1.0, 1.0, 1.0],
+                vec![3.0, 0.0, 0.0, 0.0],
+                vec![1.0, -2.0, 0.5, 0.0],
+                vec![0.5, 0.5, 0.5, 0.0],
+                vec![5.0, 5.0, 5.0, 5.0],
+            ]
+            .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+
+    let insert_result = collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(insert_result.status, UpdateStatus::Completed);
+
+    // Distances to the query are 3.0, 2.0, 2.5, 1.5 and 19.0.
+    // Euclidean distance would rank point 0 second, right after point 3.
+    let search_request = SearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 0.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 4,
+        offset: 0,
+        score_threshold: None,
+    };
+
+    let result = collection
+        .search(search_request, None, None, None)
+        .await
+        .unwrap();
+    let ids: Vec<PointIdType> = result.iter().map(|point| point.id).collect();
+    let scores = result.iter().map(|point| point.score).collect_vec();
+    assert_eq!(ids, vec![3.into(), 1.into(), 2.into(), 0.into()]);
+    assert_eq!(scores, vec![1.5, 2.0, 2.5, 3.0]);
+
+    // Smaller distance is better, so only points closer than the threshold are returned
+    let search_request = SearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 0.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 4,
+        offset: 0,
+        score_threshold: Some(2.1),
+    };
+
+    let result = collection
+        .search(search_request, None, None, None)
+        .await
+        .unwrap();
+    let ids: Vec<PointIdType> = result.iter().map(|point| point.id).collect();
+    assert_eq!(ids, vec![3.into(), 1.into()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_hamming_distance() {
+    test_hamming_distance_with_shards(1).await;
+    test_hamming_distance_with_shards(N_SHARDS).await;
+}
+
+async fn test_hamming_distance_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = distance_collection_fixture(
+        collection_dir.path(),
+        shard_number,
+        Distance::Hamming,
+        Some(VectorStorageDatatype::Uint8),
+    )
+    .await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 1.0, 0.0],
+                vec![0.0, 1.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0, 1.0],
+                vec![0.0, 0.0, 0.0, 1.0],
+            ]
+            .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+
+    let insert_result = collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(insert_result.status, UpdateStatus::Completed);
+
+    // Numbers of differing components are 0, 1, 4, 2 and 3
+    let search_request = SearchRequest {
+        vector: vec![1.0, 0.0, 1.0, 0.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 4,
+        offset: 0,
+        score_threshold: None,
+    };
+
+    let result = collection
+        .search(search_request, None, None, None)
+        .await
+        .unwrap();
+    let ids: Vec<PointIdType> = result.iter().map(|point| point.id).collect();
+    let scores = result.iter().map(|point| point.score).collect_vec();
+    assert_eq!(ids, vec![0.into(), 1.into(), 3.into(), 4.into()]);
+    assert_eq!(scores, vec![0.0, 1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn test_hamming_distance_requires_byte_vectors() {
+    let config = distance_collection_config(1, Distance::Hamming, None);
+    assert!(config.check_vector_datatypes().is_err());
+
+    let config =
+        distance_collection_config(1, Distance::Hamming, Some(VectorStorageDatatype::Float16));
+    assert!(config.check_vector_datatypes().is_err());
+
+    let config =
+        distance_collection_config(1, Distance::Hamming, Some(VectorStorageDatatype::Uint8));
+    assert!(config.check_vector_datatypes().is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cosine_store_original_vectors() {
+    let vectors = vec![
+        vec![3.0, 4.0, 0.0, 0.0],
+        vec![0.0, 0.0, 10.0, 0.0],
+        vec![1.0, 1.0, 1.0, 1.0],
+        vec![0.2, 0.1, 0.0, 0.7],
+    ];
+
+    let legacy_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let legacy_config = distance_collection_config(1, Distance::Cosine, None);
+    let legacy_collection = collection_from_config(legacy_dir.path(), &legacy_config).await;
+
+    let original_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let mut original_config = distance_collection_config(1, Distance::Cosine, None);
+    original_config.params.store_original_vectors = true;
+    let original_collection = collection_from_config(original_dir.path(), &original_config).await;
+
+    for collection in [&legacy_collection, &original_collection] {
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: (0..vectors.len() as u64).map(|x| x.into()).collect_vec(),
+                vectors: vectors.clone().into(),
+                payloads: None,
+            }
+            .into(),
+        );
+        let insert_result = collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+        assert_eq!(insert_result.status, UpdateStatus::Completed);
+    }
+
+    // Original vectors are returned exactly as they were inserted
+    assert_eq!(
+        retrieve_vectors(&original_collection, vectors.len()).await,
+        vectors
+    );
+    assert_ne!(
+        retrieve_vectors(&legacy_collection, vectors.len()).await,
+        vectors
+    );
+
+    // Search results are the same as when normalized vectors are stored
+    for query in [vec![1.0, 0.0, 0.0, 0.0], vec![0.5, 2.0, -1.0, 3.0]] {
+        let search_request = SearchRequest {
+            vector: query.into(),
+            with_payload: None,
+            with_vector: None,
+            filter: None,
+            params: None,
+            limit: 4,
+            offset: 0,
+            score_threshold: None,
+        };
+
+        let legacy_result = legacy_collection
+            .search(search_request.clone(), None, None, None)
+            .await
+            .unwrap();
+        let original_result = original_collection
+            .search(search_request, None, None, None)
+            .await
+            .unwrap();
+
+        let to_scores = |result: &[ScoredPoint]| {
+            result
+                .iter()
+                .map(|point| (point.id, point.score))
+                .collect_vec()
+        };
+        assert_eq!(to_scores(&original_result), to_scores(&legacy_result));
+    }
+}
+
+async fn retrieve_vectors(collection: &Collection, count: usize) -> Vec<Vec<f32>> {
+    let request = PointRequest {
+        ids: (0..count as u64).map(|x| x.into()).collect_vec(),
+        with_payload: None,
+        with_vector: true.into(),
+        with_version: false,
+    };
+    let mut records = collection.retrieve(request, None, None).await.unwrap();
+    records.sort_by_key(|record| record.id);
+    records
+        .into_iter()
+        .map(|record| match record.vector.unwrap() {
+            VectorStruct::Single(vector) => vector,
+            VectorStruct::Multi(_) => panic!("expected single vector"),
+        })
+        .collect_vec()
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, PointOperations, WriteOrdering};
+use collection::operations::types::{CollectionError, FacetRequest, ScrollRequest};
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use itertools::Itertools;
+use segment::data_types::facets::{FacetValue, FacetValueHit};
+use segment::types::{
+    Condition, FieldCondition, Filter, Payload, PayloadFieldSchema, PayloadSchemaType, PointIdType,
+    Range,
+};
+use serde_json::{json, Value};
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 100;
+
+const COLORS: [&str; 4] = ["red", "green", "blue", "white"];
+
+/// Payload of every point `n`, every 9th point has no color
+fn point_payload(n: u64) -> Payload {
+    let payload = if n % 9 == 0 {
+        json!({ "num": n % 5 })
+    } else {
+        json!({ "num": n % 5, "color": COLORS[(n % 4) as usize] })
+    };
+    Payload::from(payload)
+}
+
+async fn upsert_points(
+    collection: &Collection,
+    ids: impl Iterator<Item = u64>,
+    payload: impl Fn(u64) -> Payload,
+) {
+    let ids = ids.collect_vec();
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+            vectors: ids
+                .iter()
+                .map(|n| vec![*n as f32, 0.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(ids.iter().map(|n| Some(payload(*n))).collect_vec()),
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+}
+
+async fn fixture(collection_path: &std::path::Path, shard_number: u32) -> Collection {
+    let collection = simple_collection_fixture(collection_path, shard_number).await;
+
+    for (field_name, field_type) in [
+        ("color", PayloadSchemaType::Keyword),
+        ("num", PayloadSchemaType::Integer),
+    ] {
+        let create_index = CollectionUpdateOperations::FieldIndexOperation(
+            FieldIndexOperations::CreateIndex(CreateIndex {
+                field_name: field_name.to_string(),
+                field_schema: Some(PayloadFieldSchema::FieldType(field_type)),
+            }),
+        );
+        collection
+            .update_from_client(create_index, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+
+    // Small batches, to spread points over segments
+    for batch in &(0..NUM_POINTS).chunks(10) {
+        upsert_points(&collection, batch.into_iter(), point_payload).await;
+    }
+
+    // Overwritten values must not be counted anymore
+    upsert_points(&collection, (0..NUM_POINTS).step_by(6), |n| {
+        Payload::from(json!({ "num": n % 5, "color": "black" }))
+    })
+    .await;
+
+    let delete_points = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: (0..NUM_POINTS)
+            .step_by(7)
+            .map(PointIdType::from)
+            .collect_vec(),
+    });
+    collection
+        .update_from_client(delete_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    collection
+}
+
+fn num_filter() -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_range(
+        "num",
+        Range {
+            gte: Some(3.0),
+            ..Default::default()
+        },
+    )))
+}
+
+/// Count colors of points by iterating over all of them
+async fn brute_force_facet(
+    collection: &Collection,
+    predicate: fn(&Value) -> bool,
+) -> Vec<FacetValueHit> {
+    let result = collection
+        .scroll_by(
+            ScrollRequest {
+                limit: Some(NUM_POINTS as usize),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.next_page_offset, None);
+
+    let mut counts: HashMap<FacetValue, usize> = HashMap::new();
+    for point in result.points {
+        let payload = Value::Object(point.payload.unwrap().0);
+        if !predicate(&payload) {
+            continue;
+        }
+        if let Some(color) = payload["color"].as_str() {
+            *counts
+                .entry(FacetValue::Keyword(color.to_string()))
+                .or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(value, count)| FacetValueHit { value, count })
+        .sorted()
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_facet_exact() {
+    test_facet_exact_with_shards(1).await;
+    test_facet_exact_with_shards(N_SHARDS).await;
+}
+
+async fn test_facet_exact_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    let cases: [(Option<Filter>, fn(&Value) -> bool); 2] = [
+        (None, |_| true),
+        (Some(num_filter()), |payload| {
+            payload["num"].as_i64().unwrap() >= 3
+        }),
+    ];
+
+    for (filter, predicate) in cases {
+        let expected = brute_force_facet(&collection, predicate).await;
+        assert_eq!(expected.len(), COLORS.len() + 1);
+
+        let response = collection
+            .facet(
+                FacetRequest {
+                    key: "color".to_string(),
+                    limit: None,
+                    filter: filter.clone(),
+                    exact: true,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.hits, expected);
+
+        let response = collection
+            .facet(
+                FacetRequest {
+                    key: "color".to_string(),
+                    limit: Some(2),
+                    filter,
+                    exact: true,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.hits, expected[..2]);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_facet_approximate() {
+    test_facet_approximate_with_shards(1).await;
+    test_facet_approximate_with_shards(N_SHARDS).await;
+}
+
+async fn test_facet_approximate_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    // Without a filter, counts are the sizes of the index postings
+    let unfiltered = collection
+        .facet(
+            FacetRequest {
+                key: "color".to_string(),
+                limit: None,
+                filter: None,
+                exact: false,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        unfiltered.hits,
+        brute_force_facet(&collection, |_| true).await
+    );
+
+    let filtered = collection
+        .facet(
+            FacetRequest {
+                key: "color".to_string(),
+                limit: None,
+                filter: Some(num_filter()),
+                exact: false,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    for hit in filtered.hits {
+        let unfiltered_hit = unfiltered
+            .hits
+            .iter()
+            .find(|unfiltered_hit| unfiltered_hit.value == hit.value)
+            .unwrap();
+        assert!(hit.count <= unfiltered_hit.count);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_facet_requires_keyword_index() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), 1).await;
+
+    let result = collection
+        .facet(
+            FacetRequest {
+                key: "num".to_string(),
+                limit: None,
+                filter: None,
+                exact: true,
+            },
+            None,
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+}
@@ -2,13 +2,14 @@ use collection::collection::Collection;
 use collection::grouping::group_by::{group_by, GroupRequest, SourceRequest};
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::point_ops::{Batch, WriteOrdering};
-use collection::operations::types::{RecommendRequest, SearchRequest, UpdateStatus};
+use collection::operations::types::{PointGroup, RecommendRequest, SearchRequest, UpdateStatus};
 use collection::operations::CollectionUpdateOperations;
 use collection::shards::shard::ShardId;
 use itertools::Itertools;
 use rand::distributions::Uniform;
 use rand::rngs::ThreadRng;
 use rand::Rng;
+use segment::data_types::groups::GroupId;
 use segment::data_types::vectors::VectorType;
 use segment::types::{Filter, Payload, WithPayloadInterface, WithVector};
 use serde_json::json;
@@ -140,6 +141,7 @@ mod group_by {
                 score_threshold: None,
                 positive: vec![1.into(), 2.into(), 3.into()],
                 negative: Vec::new(),
+                strategy: None,
                 using: None,
                 lookup_from: None,
             }),
@@ -478,6 +480,154 @@ mod group_by {
             assert_eq!(group.hits.len(), group_by_request.group_size);
         }
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn limit_larger_than_distinct_values() {
+        let Resources {
+            collection,
+            read_consistency,
+            shard_selection,
+            ..
+        } = setup(5, 4).await;
+
+        let group_by_request = GroupRequest::with_limit_from_request(
+            SourceRequest::Search(SearchRequest {
+                vector: vec![0.5, 0.5, 0.5, 0.5].into(),
+                filter: None,
+                params: None,
+                limit: 20,
+                offset: 0,
+                with_payload: None,
+                with_vector: None,
+                score_threshold: None,
+            }),
+            "docId".to_string(),
+            3,
+        );
+
+        let result = group_by(
+            group_by_request.clone(),
+            &collection,
+            |_name| async { unreachable!() },
+            read_consistency,
+            shard_selection,
+        )
+        .await
+        .unwrap();
+
+        // Every distinct value makes one full group
+        let mut group_ids = result.iter().map(|group| group.id.clone()).collect_vec();
+        group_ids.sort();
+        assert_eq!(group_ids, (0..5u64).map(GroupId::from).collect_vec());
+        for group in result {
+            assert_eq!(group.hits.len(), group_by_request.group_size);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn points_without_key_and_ties() {
+        let mut rng = rand::thread_rng();
+        let (read_consistency, shard_selection) = (None, None);
+
+        let collection_dir = tempfile::Builder::new()
+            .prefix("collection")
+            .tempdir()
+            .unwrap();
+        let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+        // Chunks of 4 documents, followed by points closest to the query, all with the same score
+        let mut vectors = (0..16).map(|_| rand_vector(&mut rng, 4)).collect_vec();
+        vectors.extend(vec![vec![0.6, 0.6, 0.6, 0.6]; 5]);
+        let mut payloads = (0..16u64)
+            .map(|x| Some(Payload::from(json!({ "docId": x / 4 }))))
+            .collect_vec();
+        payloads.extend([
+            None,
+            Some(Payload::from(json!({ "other_stuff": "foo" }))),
+            Some(Payload::from(json!({ "docId": null }))),
+            Some(Payload::from(json!({ "docId": [1, 2] }))),
+            Some(Payload::from(json!({ "docId": 2 }))),
+        ]);
+        let ids = (0..16).chain(100..105).map(|x: u64| x.into()).collect_vec();
+
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids,
+                vectors: vectors.into(),
+                payloads: payloads.into(),
+            }
+            .into(),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+
+        let group_by_request = GroupRequest::with_limit_from_request(
+            SourceRequest::Search(SearchRequest {
+                vector: vec![0.5, 0.5, 0.5, 0.5].into(),
+                filter: None,
+                params: None,
+                limit: 2,
+                offset: 0,
+                with_payload: None,
+                with_vector: None,
+                score_threshold: None,
+            }),
+            "docId".to_string(),
+            2,
+        );
+
+        let result = group_by(
+            group_by_request.clone(),
+            &collection,
+            |_name| async { unreachable!() },
+            read_consistency,
+            shard_selection,
+        )
+        .await
+        .unwrap();
+
+        // Points without a usable key are skipped, array values contribute to every group.
+        // Tied groups are ordered by key, tied hits by id.
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].id, GroupId::from(1u64));
+        assert_eq!(result[0].hits[0].id, 103.into());
+        assert_eq!(result[1].id, GroupId::from(2u64));
+        let ids = result[1].hits.iter().map(|hit| hit.id).collect_vec();
+        assert_eq!(ids, vec![103.into(), 104.into()]);
+
+        for group in &result {
+            assert_eq!(group.hits.len(), group_by_request.group_size);
+            assert!(group
+                .hits
+                .iter()
+                .all(|hit| hit.id != 100.into() && hit.id != 101.into() && hit.id != 102.into()));
+        }
+
+        // Same request picks the same groups and hits every time
+        for _ in 0..3 {
+            let repeated = group_by(
+                group_by_request.clone(),
+                &collection,
+                |_name| async { unreachable!() },
+                read_consistency,
+                shard_selection,
+            )
+            .await
+            .unwrap();
+            let to_ids = |groups: &[PointGroup]| {
+                groups
+                    .iter()
+                    .map(|group| {
+                        let hits = group.hits.iter().map(|hit| hit.id).collect_vec();
+                        (group.id.clone(), hits)
+                    })
+                    .collect_vec()
+            };
+            assert_eq!(to_ids(&repeated), to_ids(&result));
+        }
+    }
 }
 
 /// Tests out the different features working together. The individual features are already tested in other places.
@@ -0,0 +1,162 @@
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{CountRequest, SearchRequest};
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use itertools::Itertools;
+use segment::types::{
+    Condition, FieldCondition, Filter, Match, Payload, PayloadFieldSchema, PayloadSchemaType,
+    PointIdType, Range,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 20;
+
+async fn fixture(collection_path: &std::path::Path) -> Collection {
+    let collection = simple_collection_fixture(collection_path, N_SHARDS).await;
+
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "num".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Integer)),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let ids = (0..NUM_POINTS).collect_vec();
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+            vectors: ids
+                .iter()
+                .map(|n| vec![*n as f32, 0.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                ids.iter()
+                    .map(|n| {
+                        Some(Payload::from(json!({
+                            "num": n % 5,
+                            "color": if n % 2 == 0 { "red" } else { "blue" },
+                            "price": *n as f64 / 2.0,
+                        })))
+                    })
+                    .collect_vec(),
+            ),
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    collection
+}
+
+fn search_request(filter: Filter) -> SearchRequest {
+    SearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 1.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: Some(filter),
+        params: None,
+        limit: 5,
+        offset: 0,
+        score_threshold: None,
+    }
+}
+
+fn match_filter(key: &str, r#match: Match) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_match(key, r#match)))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_index_suggestions_for_unindexed_filters() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path()).await;
+
+    assert!(collection.index_suggestions().await.unwrap().is_empty());
+
+    for _ in 0..3 {
+        let filter = match_filter("color", Match::from("red".to_string()));
+        collection
+            .search(search_request(filter), None, None, None)
+            .await
+            .unwrap();
+    }
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+        "price",
+        Range {
+            gte: Some(2.0),
+            ..Default::default()
+        },
+    )));
+    collection
+        .search(search_request(filter), None, None, None)
+        .await
+        .unwrap();
+    // Indexed field is not suggested
+    let count_request = CountRequest {
+        filter: Some(match_filter("num", 1_i64.into())),
+        exact: true,
+    };
+    collection.count(count_request, None).await.unwrap();
+
+    let suggestions = collection.index_suggestions().await.unwrap();
+    let suggested = suggestions
+        .iter()
+        .map(|suggestion| {
+            (
+                suggestion.key.as_str(),
+                suggestion.unindexed_hits,
+                suggestion.field_schema,
+            )
+        })
+        .collect_vec();
+    assert_eq!(
+        suggested,
+        vec![
+            ("color", 3, Some(PayloadSchemaType::Keyword)),
+            ("price", 1, Some(PayloadSchemaType::Float)),
+        ],
+    );
+    assert_eq!(
+        collection.get_telemetry_data().await.index_suggestions,
+        suggestions,
+    );
+
+    // Searches of a selected shard are requested by other peers, which count them themselves
+    let filter = match_filter("color", Match::from("blue".to_string()));
+    collection
+        .search(search_request(filter), None, Some(0), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        collection.index_suggestions().await.unwrap()[0].unindexed_hits,
+        3,
+    );
+
+    // Field is not suggested anymore, once it is indexed
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "color".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword)),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    let suggestions = collection.index_suggestions().await.unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].key, "price");
+
+    collection.reset_index_suggestions();
+    assert!(collection.index_suggestions().await.unwrap().is_empty());
+}
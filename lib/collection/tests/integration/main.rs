@@ -1,16 +1,56 @@
 #[cfg(test)]
+pub mod bool_index_test;
+#[cfg(test)]
+pub mod collection_config_update_test;
+#[cfg(test)]
 pub mod collection_restore_test;
 #[cfg(test)]
 pub mod collection_test;
 #[cfg(test)]
 pub mod common;
 #[cfg(test)]
+pub mod config_migration_test;
+#[cfg(test)]
+pub mod count_test;
+#[cfg(test)]
+pub mod discovery_test;
+#[cfg(test)]
+pub mod distance_test;
+#[cfg(test)]
+pub mod facet_test;
+#[cfg(test)]
 pub mod grouping_test;
 #[cfg(test)]
+pub mod index_suggestions_test;
+#[cfg(test)]
 pub mod lookup_test;
 #[cfg(test)]
 pub mod multi_vec_test;
 #[cfg(test)]
+pub mod optimization_tasks_test;
+#[cfg(test)]
+pub mod order_by_test;
+#[cfg(test)]
 pub mod pagination_test;
 #[cfg(test)]
+pub mod query_test;
+#[cfg(test)]
+pub mod rate_limits_test;
+#[cfg(test)]
+pub mod readiness_test;
+#[cfg(test)]
+pub mod recommend_test;
+#[cfg(test)]
+pub mod resharding_test;
+#[cfg(test)]
+pub mod resource_usage_test;
+#[cfg(test)]
+pub mod search_recall_test;
+#[cfg(test)]
+pub mod shard_key_test;
+#[cfg(test)]
 pub mod snapshot_recovery_test;
+#[cfg(test)]
+pub mod strict_mode_test;
+#[cfg(test)]
+pub mod tracing_test;
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::num::{NonZeroU32, NonZeroU64};
 use std::path::Path;
 
@@ -10,11 +10,12 @@ use collection::operations::point_ops::{
 use collection::operations::types::{
     CollectionError, PointRequest, RecommendRequest, SearchRequest, VectorParams, VectorsConfig,
 };
+use collection::operations::vector_ops::{PointVectors, UpdateVectors, VectorOperations};
 use collection::operations::CollectionUpdateOperations;
 use collection::recommendations::recommend_by;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::data_types::vectors::{NamedVector, VectorStruct};
-use segment::types::{Distance, WithPayloadInterface, WithVector};
+use segment::types::{Distance, PointIdType, WithPayloadInterface, WithVector};
 use tempfile::Builder;
 
 use crate::common::{new_local_collection, N_SHARDS, TEST_OPTIMIZERS_CONFIG};
@@ -33,6 +34,7 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let vector_params1 = VectorParams {
@@ -41,6 +43,8 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         hnsw_config: None,
         quantization_config: None,
         on_disk: None,
+        datatype: None,
+        multivector_config: None,
     };
     let vector_params2 = VectorParams {
         size: NonZeroU64::new(4).unwrap(),
@@ -48,6 +52,8 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         hnsw_config: None,
         quantization_config: None,
         on_disk: None,
+        datatype: None,
+        multivector_config: None,
     };
 
     let mut vectors_config = BTreeMap::new();
@@ -58,9 +64,14 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
     let collection_params = CollectionParams {
         vectors: VectorsConfig::Multi(vectors_config),
         shard_number: NonZeroU32::new(shard_number).expect("Shard number can not be zero"),
+        sharding_method: None,
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
     };
 
     let collection_config = CollectionConfig {
@@ -69,6 +80,7 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
     };
 
     let snapshot_path = collection_path.join("snapshots");
@@ -131,7 +143,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
     };
 
     let result = collection
-        .search(full_search_request, None, None)
+        .search(full_search_request, None, None, None)
         .await
         .unwrap();
 
@@ -158,7 +170,9 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         score_threshold: None,
     };
 
-    let result = collection.search(failed_search_request, None, None).await;
+    let result = collection
+        .search(failed_search_request, None, None, None)
+        .await;
 
     assert!(
         matches!(result, Err(CollectionError::BadInput { .. })),
@@ -181,7 +195,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
     };
 
     let result = collection
-        .search(full_search_request, None, None)
+        .search(full_search_request, None, None, None)
         .await
         .unwrap();
 
@@ -201,6 +215,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
                 ids: vec![6.into()],
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: WithVector::Selector(vec![VEC_NAME1.to_string()]),
+                with_version: false,
             },
             None,
             None,
@@ -228,6 +243,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         &collection,
         |_name| async { unreachable!("should not be called in this test") },
         None,
+        None,
     )
     .await;
 
@@ -252,6 +268,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         &collection,
         |_name| async { unreachable!("should not be called in this test") },
         None,
+        None,
     )
     .await
     .unwrap();
@@ -267,3 +284,137 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         }
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_multi_vec_update_and_delete_vectors() {
+    test_multi_vec_update_and_delete_vectors_with_shards(1).await;
+    test_multi_vec_update_and_delete_vectors_with_shards(N_SHARDS).await;
+}
+
+async fn test_multi_vec_update_and_delete_vectors_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_multi_vec_update_vectors")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let points = (0..2)
+        .map(|i| {
+            let mut vectors = NamedVectors::default();
+            vectors.insert(VEC_NAME1.to_string(), vec![1.0, i as f32, 0.0, 0.0]);
+            vectors.insert(VEC_NAME2.to_string(), vec![0.0, 1.0, i as f32, 0.0]);
+            PointStruct {
+                id: i.into(),
+                vector: vectors.into(),
+                payload: Some(serde_json::from_str(r#"{"k": "v"}"#).unwrap()),
+            }
+        })
+        .collect();
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let update_vectors = |vector_name: &str, vector: Vec<f32>| {
+        CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(
+            UpdateVectors {
+                points: vec![PointVectors {
+                    id: 0.into(),
+                    vector: VectorStruct::Multi(HashMap::from([(vector_name.to_string(), vector)])),
+                    if_version: None,
+                }],
+            },
+        ))
+    };
+    let delete_vectors = |vector_names: &[&str]| {
+        CollectionUpdateOperations::VectorOperation(VectorOperations::DeleteVectors(
+            vec![PointIdType::from(1)].into(),
+            vector_names.iter().map(|name| name.to_string()).collect(),
+        ))
+    };
+    let retrieve = |id: u64| {
+        collection.retrieve(
+            PointRequest {
+                ids: vec![id.into()],
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: true.into(),
+                with_version: false,
+            },
+            None,
+            None,
+        )
+    };
+
+    // Unknown vector names and wrong dimensions are rejected before applying the operation
+    for operation in [
+        update_vectors("vec3", vec![0.0, 0.0, 0.0, 1.0]),
+        update_vectors(VEC_NAME2, vec![0.0, 0.0, 1.0]),
+        delete_vectors(&[VEC_NAME1, "vec3"]),
+    ] {
+        let result = collection
+            .update_from_client(operation, true, WriteOrdering::default())
+            .await;
+        assert!(
+            matches!(result, Err(CollectionError::BadInput { .. })),
+            "{result:?}"
+        );
+    }
+
+    // Only the given named vector is replaced, payload and other vectors are intact
+    collection
+        .update_from_client(
+            update_vectors(VEC_NAME2, vec![0.0, 0.0, 0.0, 1.0]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    let records = retrieve(0).await.unwrap();
+    let vector = records[0].vector.as_ref().unwrap();
+    assert_eq!(vector.get(VEC_NAME1), Some(&vec![1.0, 0.0, 0.0, 0.0]));
+    assert_eq!(vector.get(VEC_NAME2), Some(&vec![0.0, 0.0, 0.0, 1.0]));
+    assert!(records[0].payload.is_some());
+
+    // Deleting the last vectors keeps the point with its payload, but it is not found by search
+    collection
+        .update_from_client(
+            delete_vectors(&[VEC_NAME1, VEC_NAME2]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    let records = retrieve(1).await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].vector, Some(VectorStruct::Multi(HashMap::new())));
+    assert!(records[0].payload.is_some());
+
+    let search_request = SearchRequest {
+        vector: NamedVector {
+            name: VEC_NAME1.to_string(),
+            vector: vec![1.0, 1.0, 0.0, 0.0],
+        }
+        .into(),
+        filter: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        params: None,
+        score_threshold: None,
+    };
+    let result = collection
+        .search(search_request, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        result.iter().map(|hit| hit.id).collect::<Vec<_>>(),
+        vec![PointIdType::from(0)]
+    );
+}
@@ -0,0 +1,165 @@
+use std::num::NonZeroU64;
+use std::time::Duration;
+
+use collection::collection::Collection;
+use collection::collection_manager::optimizers::optimization_tasks::{
+    OptimizationTaskInfo, OptimizationTaskStatus,
+};
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{SearchRequest, VectorParams};
+use collection::operations::CollectionUpdateOperations;
+use collection::optimizers_builder::OptimizersConfig;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::types::{Distance, HnswConfig, PointIdType};
+use tempfile::Builder;
+
+use crate::common::{new_local_collection, optimizers_collection_config, TEST_OPTIMIZERS_CONFIG};
+
+const DIM: usize = 128;
+const NUM_POINTS: u64 = 20_000;
+const WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Poll the optimization tasks of the collection, until `find` returns some
+async fn wait_for_task<T>(
+    collection: &Collection,
+    mut find: impl FnMut(&[OptimizationTaskInfo]) -> Option<T>,
+) -> T {
+    let wait = async {
+        loop {
+            if let Some(found) = find(&collection.optimization_tasks().await) {
+                break found;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    };
+    tokio::time::timeout(WAIT_TIMEOUT, wait)
+        .await
+        .expect("optimization tasks don't reach the expected state in time")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cancel_index_build() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let snapshot_path = collection_dir.path().join("snapshots");
+
+    // Large and slow to index fixture, so that the graph building is observed in progress
+    let mut config = optimizers_collection_config(
+        1,
+        OptimizersConfig {
+            indexing_threshold: Some(1_000), // KB
+            ..TEST_OPTIMIZERS_CONFIG.clone()
+        },
+    );
+    config.params.vectors = VectorParams {
+        size: NonZeroU64::new(DIM as u64).unwrap(),
+        distance: Distance::Dot,
+        hnsw_config: None,
+        quantization_config: None,
+        on_disk: None,
+        datatype: None,
+        multivector_config: None,
+    }
+    .into();
+    config.hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 512,
+        full_scan_threshold: 10, // KB
+        max_indexing_threads: 1,
+        on_disk: None,
+        payload_m: None,
+    };
+    let collection = new_local_collection(
+        "test".to_string(),
+        collection_dir.path(),
+        &snapshot_path,
+        &config,
+    )
+    .await
+    .unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(42);
+    let vectors = (0..NUM_POINTS)
+        .map(|_| (0..DIM).map(|_| rnd.gen_range(-1.0f32..1.0)).collect_vec())
+        .collect_vec();
+    let insert = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..NUM_POINTS).map(PointIdType::from).collect_vec(),
+            vectors: vectors.into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Task is progressing
+    let (task_id, first_progress) = wait_for_task(&collection, |tasks| {
+        tasks.iter().find_map(|task| match task.progress {
+            Some(progress) if task.status == OptimizationTaskStatus::Optimizing => {
+                (progress > 0.0).then_some((task.id, progress))
+            }
+            _ => None,
+        })
+    })
+    .await;
+    let task = wait_for_task(&collection, |tasks| {
+        tasks
+            .iter()
+            .find(|task| task.id == task_id && task.progress > Some(first_progress))
+            .cloned()
+    })
+    .await;
+    assert_eq!(task.optimizer, "indexing");
+    assert!(task.progress < Some(1.0), "{task:?}");
+    assert!(task.finished_at.is_none());
+
+    assert!(collection.cancel_optimization_task(u64::MAX).await.is_err());
+    collection.cancel_optimization_task(task_id).await.unwrap();
+
+    let cancelled = wait_for_task(&collection, |tasks| {
+        tasks
+            .iter()
+            .find(|task| task.id == task_id && task.status != OptimizationTaskStatus::Optimizing)
+            .cloned()
+    })
+    .await;
+    assert_eq!(cancelled.status, OptimizationTaskStatus::Cancelled);
+    assert!(cancelled.finished_at.is_some());
+    assert!(cancelled.error.is_some());
+    assert!(collection.cancel_optimization_task(task_id).await.is_err());
+
+    // Segments stay in their form before the optimization, and remain usable
+    let info = collection.info(None).await.unwrap();
+    assert_eq!(info.points_count, NUM_POINTS as usize);
+    assert_eq!(info.indexed_vectors_count, 0);
+
+    let search_request = SearchRequest {
+        vector: vec![0.5; DIM].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 10,
+        offset: 0,
+        score_threshold: None,
+    };
+    let result = collection
+        .search(search_request, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 10);
+
+    // Cancelled segments are requeued for optimization, under new ids after unwrapping
+    let requeued = wait_for_task(&collection, |tasks| {
+        tasks
+            .iter()
+            .find(|task| task.id > task_id && task.optimizer == "indexing")
+            .cloned()
+    })
+    .await;
+    assert_ne!(requeued.status, OptimizationTaskStatus::Error);
+}
@@ -0,0 +1,194 @@
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{CollectionError, ScrollRequest};
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use itertools::Itertools;
+use segment::data_types::order_by::{Direction, OrderBy, OrderValue};
+use segment::types::{Payload, PayloadFieldSchema, PayloadSchemaType, PointIdType};
+use serde_json::json;
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 100;
+
+/// Value of the `num` field of every point `n`, every 11th point has no value
+fn point_value(n: u64) -> Option<i64> {
+    (n % 11 != 0).then_some((n % 10) as i64)
+}
+
+async fn upsert_points(
+    collection: &Collection,
+    ids: impl Iterator<Item = u64>,
+    value: impl Fn(u64) -> Option<i64>,
+) {
+    let ids = ids.collect_vec();
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+            vectors: ids
+                .iter()
+                .map(|n| vec![*n as f32, 0.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                ids.iter()
+                    .map(|n| {
+                        let payload = match value(*n) {
+                            Some(value) => json!({ "num": value, "other": value }),
+                            None => json!({}),
+                        };
+                        Some(Payload::from(payload))
+                    })
+                    .collect_vec(),
+            ),
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+}
+
+async fn fixture(collection_path: &std::path::Path, shard_number: u32) -> Collection {
+    let collection = simple_collection_fixture(collection_path, shard_number).await;
+
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "num".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Integer)),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Small batches, to spread points over segments
+    for batch in &(0..NUM_POINTS).chunks(10) {
+        upsert_points(&collection, batch.into_iter(), point_value).await;
+    }
+
+    collection
+}
+
+fn expected_order(direction: Direction) -> Vec<(OrderValue, PointIdType)> {
+    (0..NUM_POINTS)
+        .filter_map(|n| Some((OrderValue::Int(point_value(n)?), PointIdType::from(n))))
+        .sorted_by(|a, b| direction.compare(a, b))
+        .collect()
+}
+
+fn scroll_request(
+    direction: Direction,
+    start_from: Option<OrderValue>,
+    offset: Option<PointIdType>,
+    limit: usize,
+) -> ScrollRequest {
+    ScrollRequest {
+        offset,
+        limit: Some(limit),
+        with_payload: Some(false.into()),
+        order_by: Some(OrderBy {
+            key: "num".to_string(),
+            direction: Some(direction),
+            start_from,
+        }),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scroll_order_by() {
+    test_scroll_order_by_with_shards(1).await;
+    test_scroll_order_by_with_shards(N_SHARDS).await;
+}
+
+async fn test_scroll_order_by_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    for direction in [Direction::Asc, Direction::Desc] {
+        let result = collection
+            .scroll_by(scroll_request(direction, None, None, 1000), None, None)
+            .await
+            .unwrap();
+        // Points without the value are skipped, points with the same value are ordered by id
+        let points = result
+            .points
+            .iter()
+            .map(|point| (point.order_value.unwrap(), point.id))
+            .collect_vec();
+        assert_eq!(points, expected_order(direction));
+        assert_eq!(result.next_page_offset, None);
+        assert_eq!(result.next_page_start_from, None);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scroll_order_by_pagination() {
+    test_scroll_order_by_pagination_with_shards(1).await;
+    test_scroll_order_by_pagination_with_shards(N_SHARDS).await;
+}
+
+async fn test_scroll_order_by_pagination_with_shards(shard_number: u32) {
+    for direction in [Direction::Asc, Direction::Desc] {
+        let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+        let collection = fixture(collection_dir.path(), shard_number).await;
+
+        let mut points = Vec::new();
+        let mut start_from = None;
+        let mut offset = None;
+        let mut inserted = 0;
+        loop {
+            let result = collection
+                .scroll_by(scroll_request(direction, start_from, offset, 7), None, None)
+                .await
+                .unwrap();
+            points.extend(
+                result
+                    .points
+                    .iter()
+                    .map(|point| (point.order_value.unwrap(), point.id)),
+            );
+            if result.next_page_offset.is_none() {
+                break;
+            }
+            start_from = result.next_page_start_from;
+            offset = result.next_page_offset;
+
+            // Points inserted before the page cursor don't shift the next pages
+            let passed_value = match direction {
+                Direction::Asc => -1,
+                Direction::Desc => 100,
+            };
+            let ids = NUM_POINTS * 10 + inserted..NUM_POINTS * 10 + inserted + 3;
+            inserted += 3;
+            upsert_points(&collection, ids, |_| Some(passed_value)).await;
+        }
+        assert_eq!(points, expected_order(direction));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_scroll_order_by_errors() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), N_SHARDS).await;
+
+    // Field without an integer or float index
+    let mut request = scroll_request(Direction::Asc, None, None, 10);
+    request.order_by.as_mut().unwrap().key = "other".to_string();
+    let result = collection.scroll_by(request, None, None).await;
+    match result {
+        Err(CollectionError::BadInput { description }) => {
+            assert!(description.contains("other"), "{description}")
+        }
+        other => panic!("Expected missing index error, got {other:?}"),
+    }
+
+    // Offset only selects among the points with the start value
+    let request = scroll_request(Direction::Asc, None, Some(5.into()), 10);
+    let result = collection.scroll_by(request, None, None).await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+}
@@ -1,12 +1,23 @@
+use std::time::Duration;
+
+use collection::collection::Collection;
 use collection::operations::point_ops::{
-    PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
+    Batch, PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
+};
+use collection::operations::types::{CollectionError, ScrollRequest, SearchRequest};
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use collection::optimizers_builder::OptimizersConfig;
+use itertools::Itertools;
+use segment::data_types::order_by::{Direction, OrderBy, OrderValue};
+use segment::types::{
+    Payload, PayloadFieldSchema, PayloadSchemaType, PointIdType, WithPayloadInterface,
 };
-use collection::operations::types::SearchRequest;
-use collection::operations::CollectionUpdateOperations;
-use segment::types::WithPayloadInterface;
+use serde_json::json;
 use tempfile::Builder;
 
-use crate::common::{simple_collection_fixture, N_SHARDS};
+use crate::common::{
+    optimizers_collection_fixture, simple_collection_fixture, N_SHARDS, TEST_OPTIMIZERS_CONFIG,
+};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_paginated_search() {
@@ -53,7 +64,7 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
     };
 
     let reference_result = collection
-        .search(full_search_request, None, None)
+        .search(full_search_request, None, None, None)
         .await
         .unwrap();
 
@@ -73,7 +84,10 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
         score_threshold: None,
     };
 
-    let page_1_result = collection.search(page_1_request, None, None).await.unwrap();
+    let page_1_result = collection
+        .search(page_1_request, None, None, None)
+        .await
+        .unwrap();
 
     // Check that the first page is the same as the reference result
     assert_eq!(page_1_result.len(), 10);
@@ -92,7 +106,10 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
         score_threshold: None,
     };
 
-    let page_9_result = collection.search(page_9_request, None, None).await.unwrap();
+    let page_9_result = collection
+        .search(page_9_request, None, None, None)
+        .await
+        .unwrap();
 
     // Check that the 9th page is the same as the reference result
     assert_eq!(page_9_result.len(), 10);
@@ -100,3 +117,173 @@ async fn test_collection_paginated_search_with_shards(shard_number: u32) {
         assert_eq!(page_9_result[i], reference_result[page_size * 9 + i]);
     }
 }
+
+const SCROLL_POINTS: u64 = 1_000;
+const SCROLL_PAGE_SIZE: usize = 50;
+const MERGE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Scroll over the collection with page tokens
+struct PageTokenScroll {
+    order_by: Option<OrderBy>,
+    page_token: Option<String>,
+    points: Vec<(Option<OrderValue>, PointIdType)>,
+    finished: bool,
+}
+
+impl PageTokenScroll {
+    fn new(order_by: Option<OrderBy>) -> Self {
+        Self {
+            order_by,
+            page_token: None,
+            points: Vec::new(),
+            finished: false,
+        }
+    }
+
+    async fn next_page(&mut self, collection: &Collection) {
+        if self.finished {
+            return;
+        }
+        let request = ScrollRequest {
+            limit: Some(SCROLL_PAGE_SIZE),
+            with_payload: Some(false.into()),
+            order_by: self.order_by.clone(),
+            page_token: self.page_token.take(),
+            ..Default::default()
+        };
+        let result = collection.scroll_by(request, None, None).await.unwrap();
+        self.points.extend(
+            result
+                .points
+                .iter()
+                .map(|point| (point.order_value, point.id)),
+        );
+        self.page_token = result.next_page_token;
+        self.finished = self.page_token.is_none();
+    }
+}
+
+async fn segments_count(collection: &Collection) -> usize {
+    collection
+        .get_telemetry_data()
+        .await
+        .shards
+        .into_iter()
+        .filter_map(|shard| shard.local)
+        .map(|local| local.segments.len())
+        .sum()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_scroll_page_token_across_merge() {
+    let collection_dir = Builder::new()
+        .prefix("test_collection_scroll_page_token")
+        .tempdir()
+        .unwrap();
+
+    // Many small segments, to be merged in the middle of the scroll
+    let collection = optimizers_collection_fixture(
+        collection_dir.path(),
+        1,
+        OptimizersConfig {
+            default_segment_number: 6,
+            ..TEST_OPTIMIZERS_CONFIG.clone()
+        },
+    )
+    .await;
+
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "num".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Integer)),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Small batches, to spread points over segments
+    for batch in &(0..SCROLL_POINTS).chunks(10) {
+        let ids = batch.collect_vec();
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+                vectors: ids
+                    .iter()
+                    .map(|n| vec![*n as f32, 0.0, 0.0, 1.0])
+                    .collect_vec()
+                    .into(),
+                payloads: Some(
+                    ids.iter()
+                        .map(|n| Some(Payload::from(json!({ "num": n % 7 }))))
+                        .collect_vec(),
+                ),
+            }
+            .into(),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+    let segments_before_merge = segments_count(&collection).await;
+    assert!(segments_before_merge >= 6);
+
+    let order_by = OrderBy {
+        key: "num".to_string(),
+        direction: Some(Direction::Desc),
+        start_from: None,
+    };
+    let mut by_id = PageTokenScroll::new(None);
+    let mut by_field = PageTokenScroll::new(Some(order_by.clone()));
+    for _ in 0..3 {
+        by_id.next_page(&collection).await;
+        by_field.next_page(&collection).await;
+    }
+
+    // Token can't be combined with the offset
+    let request = ScrollRequest {
+        offset: Some(0.into()),
+        page_token: by_id.page_token.clone(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        collection.scroll_by(request, None, None).await,
+        Err(CollectionError::BadRequest { .. }),
+    ));
+
+    // Merge all segments
+    collection
+        .update_optimizer_params(OptimizersConfig {
+            default_segment_number: 1,
+            ..TEST_OPTIMIZERS_CONFIG.clone()
+        })
+        .await
+        .unwrap();
+    collection.recreate_optimizers_blocking().await.unwrap();
+    let wait_merge = async {
+        while segments_count(&collection).await >= segments_before_merge {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+    tokio::time::timeout(MERGE_TIMEOUT, wait_merge)
+        .await
+        .expect("segments are not merged in time");
+
+    while !by_id.finished || !by_field.finished {
+        by_id.next_page(&collection).await;
+        by_field.next_page(&collection).await;
+    }
+
+    // Every point is returned exactly once, in the requested order
+    let expected_ids = (0..SCROLL_POINTS).map(PointIdType::from).collect_vec();
+    let ids = by_id.points.iter().map(|(_, id)| *id).collect_vec();
+    assert_eq!(ids, expected_ids);
+
+    let expected_order = (0..SCROLL_POINTS)
+        .map(|n| (Some(OrderValue::Int((n % 7) as i64)), PointIdType::from(n)))
+        .sorted_by(|a, b| Direction::Desc.compare(a, b))
+        .collect_vec();
+    assert_eq!(by_field.points, expected_order);
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{QueryInterface, QueryRequest};
+use collection::operations::CollectionUpdateOperations;
+use itertools::Itertools;
+use segment::types::{
+    Condition, FieldCondition, Filter, Match, Payload, PointIdType, Sample, ValueVariants,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 100;
+
+async fn fixture(collection_path: &std::path::Path, shard_number: u32) -> Collection {
+    let collection = simple_collection_fixture(collection_path, shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..NUM_POINTS).map(PointIdType::from).collect_vec(),
+            vectors: (0..NUM_POINTS)
+                .map(|i| vec![i as f32, 0.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                (0..NUM_POINTS)
+                    .map(|i| Some(Payload::from(json!({ "even": i % 2 == 0 }))))
+                    .collect_vec(),
+            ),
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    collection
+}
+
+fn sample_request(limit: usize) -> QueryRequest {
+    QueryRequest {
+        query: QueryInterface::Sample(Sample::Random),
+        filter: Some(Filter::new_must(Condition::Field(
+            FieldCondition::new_match("even", Match::new_value(ValueVariants::Bool(true))),
+        ))),
+        limit,
+        with_payload: None,
+        with_vector: None,
+        using: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sample_points() {
+    test_sample_points_with_shards(1).await;
+    test_sample_points_with_shards(N_SHARDS).await;
+}
+
+async fn test_sample_points_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    let result = collection
+        .query(sample_request(10), None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 10);
+    assert!(result.iter().map(|point| point.id).all_unique());
+    assert!(result.iter().all(|point| match point.id {
+        PointIdType::NumId(id) => id % 2 == 0,
+        PointIdType::Uuid(_) => false,
+    }));
+
+    // Limit above the number of matching points returns all of them
+    let result = collection
+        .query(sample_request(1000), None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.len(), NUM_POINTS as usize / 2);
+
+    // Every matching point is sampled with the same probability, regardless of its shard
+    let attempts = 200;
+    let mut frequencies: HashMap<PointIdType, usize> = HashMap::new();
+    for _ in 0..attempts {
+        let result = collection
+            .query(sample_request(10), None, None)
+            .await
+            .unwrap();
+        for point in result {
+            *frequencies.entry(point.id).or_default() += 1;
+        }
+    }
+    // Expected frequency is 40 for every point
+    assert_eq!(frequencies.len(), NUM_POINTS as usize / 2);
+    assert!(
+        frequencies
+            .values()
+            .all(|&frequency| (10..=80).contains(&frequency)),
+        "{frequencies:?}"
+    );
+}
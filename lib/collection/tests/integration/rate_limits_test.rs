@@ -0,0 +1,181 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::strict_mode::StrictModeConfig;
+use collection::operations::types::{CollectionError, CollectionResult, SearchRequest};
+use collection::operations::CollectionUpdateOperations;
+use futures::future::join_all;
+use itertools::Itertools;
+use segment::types::PointIdType;
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 1_000;
+const BURST: usize = 20;
+
+fn upsert_operation(ids: impl Iterator<Item = u64>) -> CollectionUpdateOperations {
+    let ids = ids.collect_vec();
+    CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+            vectors: ids
+                .iter()
+                .map(|n| vec![*n as f32, 1.0, 0.5, -1.0])
+                .collect_vec()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    )
+}
+
+async fn fixture(collection_path: &std::path::Path) -> Collection {
+    let collection = simple_collection_fixture(collection_path, N_SHARDS).await;
+    collection
+        .update_from_client(
+            upsert_operation(0..NUM_POINTS),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    collection
+}
+
+async fn search(collection: &Collection) -> CollectionResult<()> {
+    let request = SearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 1.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 10,
+        offset: 0,
+        score_threshold: None,
+    };
+    let result = collection.search(request, None, None, None).await?;
+    assert_eq!(result.len(), 10);
+    Ok(())
+}
+
+/// Retry hints of rate limited requests, panics on other errors
+fn retry_hints(results: Vec<CollectionResult<()>>) -> Vec<Duration> {
+    results
+        .into_iter()
+        .filter_map(|result| match result {
+            Ok(()) => None,
+            Err(CollectionError::RateLimited { retry_after, .. }) => Some(retry_after),
+            Err(err) => panic!("unexpected error: {err}"),
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limits_of_collection() {
+    let limited_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let other_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let limited = Arc::new(fixture(limited_dir.path()).await);
+    let other = fixture(other_dir.path()).await;
+
+    // Limits are not applied, until strict mode is enabled
+    limited
+        .update_strict_mode_config(StrictModeConfig {
+            max_concurrent_searches: Some(1),
+            max_updates_per_second: Some(5),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let results = join_all((0..BURST).map(|_| search(&limited))).await;
+    assert!(retry_hints(results).is_empty());
+
+    limited
+        .update_strict_mode_config(StrictModeConfig {
+            enabled: Some(true),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // Concurrent searches over the limit are rejected at once, other collections are not limited
+    let results = join_all((0..BURST).map(|_| search(&limited))).await;
+    let succeeded = results.iter().filter(|result| result.is_ok()).count();
+    let hints = retry_hints(results);
+    assert!(succeeded >= 1);
+    assert!(!hints.is_empty());
+    assert!(hints.iter().all(|hint| *hint > Duration::ZERO));
+    let results = join_all((0..BURST).map(|_| search(&other))).await;
+    assert!(retry_hints(results).is_empty());
+
+    // Updates over the rate are rejected, with the time of the next token as a retry hint
+    let mut update_hints = Vec::new();
+    for n in 0..BURST as u64 {
+        let operation = upsert_operation(n..n + 1);
+        let result = limited
+            .update_from_client(operation, false, WriteOrdering::default())
+            .await
+            .map(|_| ());
+        update_hints.extend(retry_hints(vec![result]));
+        let operation = upsert_operation(n..n + 1);
+        other
+            .update_from_client(operation, false, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+    assert!(update_hints.len() >= BURST - 10, "{update_hints:?}");
+    assert!(update_hints
+        .iter()
+        .all(|hint| *hint <= Duration::from_millis(200)));
+
+    // Saturated collection doesn't slow down searches of other collections
+    let saturate = (0..4)
+        .map(|_| {
+            let limited = limited.clone();
+            tokio::spawn(async move {
+                let deadline = Instant::now() + Duration::from_secs(1);
+                let mut results = Vec::new();
+                while Instant::now() < deadline {
+                    results.push(search(&limited).await);
+                }
+                retry_hints(results).len()
+            })
+        })
+        .collect_vec();
+    let mut max_latency = Duration::ZERO;
+    for _ in 0..50 {
+        let start = Instant::now();
+        search(&other).await.unwrap();
+        max_latency = max_latency.max(start.elapsed());
+    }
+    let rejected: usize = join_all(saturate)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .sum();
+    assert!(rejected > 0);
+    assert!(max_latency < Duration::from_millis(500), "{max_latency:?}");
+
+    let telemetry = limited.get_telemetry_data().await.rate_limits;
+    assert_eq!(telemetry.searches_in_flight, 0);
+    assert!(telemetry.searches_rejected >= hints.len() + rejected);
+    assert_eq!(telemetry.updates_rejected, update_hints.len());
+    assert!(telemetry.update_tokens_available.is_some());
+    let telemetry = other.get_telemetry_data().await.rate_limits;
+    assert_eq!(telemetry.searches_rejected, 0);
+    assert_eq!(telemetry.updates_rejected, 0);
+    assert_eq!(telemetry.update_tokens_available, None);
+
+    // With a queue timeout, searches wait for their turn instead of being rejected
+    limited
+        .update_strict_mode_config(StrictModeConfig {
+            rate_limit_timeout_ms: Some(10_000),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let results = join_all((0..BURST).map(|_| search(&limited))).await;
+    assert!(retry_hints(results).is_empty());
+}
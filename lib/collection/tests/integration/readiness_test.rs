@@ -0,0 +1,96 @@
+use collection::collection::Collection;
+use collection::operations::point_ops::{
+    Batch, PointInsertOperations, PointOperations, WriteOrdering,
+};
+use collection::operations::CollectionUpdateOperations;
+use collection::shards::channel_service::ChannelService;
+use collection::shards::collection_shard_distribution::CollectionShardDistribution;
+use collection::shards::replica_set::ReplicaState;
+use itertools::Itertools;
+use tempfile::Builder;
+
+use crate::common::{
+    dummy_on_replica_failure, dummy_request_shard_transfer, load_local_collection,
+    optimizers_collection_config, simple_collection_fixture, N_SHARDS, TEST_OPTIMIZERS_CONFIG,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_ready_after_all_shards_activated() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let snapshots_dir = Builder::new().prefix("snapshots").tempdir().unwrap();
+
+    let config = optimizers_collection_config(N_SHARDS, TEST_OPTIMIZERS_CONFIG.clone());
+    let collection = Collection::new(
+        "test".to_string(),
+        0,
+        collection_dir.path(),
+        snapshots_dir.path(),
+        &config,
+        Default::default(),
+        CollectionShardDistribution::all_local(Some(N_SHARDS), 0),
+        ChannelService::default(),
+        dummy_on_replica_failure(),
+        dummy_request_shard_transfer(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Replicas are initializing, as if they were still receiving their data
+    assert!(!collection.is_ready().await);
+
+    let local_shards = collection.get_local_shards().await;
+    assert_eq!(local_shards.len(), N_SHARDS as usize);
+    for (i, shard_id) in local_shards.into_iter().enumerate() {
+        assert!(!collection.is_ready().await, "ready before shard #{i}");
+        collection
+            .set_shard_replica_state(shard_id, 0, ReplicaState::Active, None)
+            .await
+            .unwrap();
+    }
+
+    // Ready exactly once the last shard is active
+    assert!(collection.is_ready().await);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_ready_after_wal_replay() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection_path = collection_dir.path();
+    let points_count = 1000;
+
+    {
+        let collection = simple_collection_fixture(collection_path, N_SHARDS).await;
+        assert!(collection.is_ready().await);
+
+        // Segments are not flushed within the flush interval, so the points are replayed from WAL
+        let insert_points = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperations::PointsBatch(Batch {
+                ids: (0..points_count).map(|x| x.into()).collect_vec(),
+                vectors: (0..points_count)
+                    .map(|x| vec![x as f32, 1.0, 0.0, 1.0])
+                    .collect_vec()
+                    .into(),
+                payloads: None,
+            })),
+        );
+        collection
+            .update_from_client(insert_points, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+
+    // Collection is available only after WAL of all shards is replayed, and is ready right away
+    let collection = load_local_collection(
+        "test".to_string(),
+        collection_path,
+        &collection_path.join("snapshots"),
+    )
+    .await;
+    assert!(collection.is_ready().await);
+    assert_eq!(
+        collection.info(None).await.unwrap().points_count,
+        points_count as usize
+    );
+}
@@ -0,0 +1,265 @@
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::{
+    CollectionError, RecommendExample, RecommendRequest, RecommendStrategy,
+};
+use collection::operations::CollectionUpdateOperations;
+use collection::recommendations::recommend_by;
+use itertools::Itertools;
+use segment::types::{PointIdType, ScoredPoint};
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+async fn fixture(collection_path: &std::path::Path, shard_number: u32) -> Collection {
+    let collection = simple_collection_fixture(collection_path, shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4, 5, 6, 7, 8]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![0.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+                vec![0.0, 1.0, 0.0, 0.0],
+                vec![0.0, 0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 1.0, 0.0],
+                vec![0.0, 0.0, 0.0, 1.0],
+                vec![0.0, 0.0, 0.0, 1.0],
+            ]
+            .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    collection
+}
+
+async fn recommend(
+    collection: &Collection,
+    request: RecommendRequest,
+) -> Result<Vec<ScoredPoint>, CollectionError> {
+    recommend_by(
+        request,
+        collection,
+        |_name| async { unreachable!("Should not be called in this test") },
+        None,
+        None,
+    )
+    .await
+}
+
+fn ids(result: &[ScoredPoint]) -> Vec<PointIdType> {
+    result.iter().map(|point| point.id).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recommend_strategies() {
+    test_recommend_strategies_with_shards(1).await;
+    test_recommend_strategies_with_shards(N_SHARDS).await;
+}
+
+async fn test_recommend_strategies_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    // Query is `2 * [1, 0, 0, 0] - [0, 1, 0, 0]`
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            positive: vec![1.into()],
+            negative: vec![3.into()],
+            strategy: Some(RecommendStrategy::AverageVector),
+            limit: 9,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(result[0].id, 2.into());
+    assert_eq!(result[0].score, 2.0);
+    assert_eq!(result.last().unwrap().id, 4.into());
+    assert_eq!(result.last().unwrap().score, -1.0);
+
+    // Score is similarity with point 1 minus similarity with point 3
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            positive: vec![1.into()],
+            negative: vec![3.into()],
+            strategy: Some(RecommendStrategy::BestScore),
+            limit: 9,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.len(), 7);
+    assert_eq!(result[0].id, 2.into());
+    assert_eq!(result[0].score, 1.0);
+    assert_eq!(result.last().unwrap().id, 4.into());
+    assert_eq!(result.last().unwrap().score, -1.0);
+    assert!(result
+        .iter()
+        .tuple_windows()
+        .all(|(a, b)| a.score >= b.score));
+
+    // Examples are excluded from the results
+    assert!(!ids(&result).contains(&1.into()));
+    assert!(!ids(&result).contains(&3.into()));
+
+    // Threshold is compared with the combined score
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            positive: vec![1.into()],
+            negative: vec![3.into()],
+            strategy: Some(RecommendStrategy::BestScore),
+            score_threshold: Some(0.0),
+            limit: 9,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.len(), 6);
+    assert!(result.iter().all(|point| point.score >= 0.0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recommend_only_negative() {
+    test_recommend_only_negative_with_shards(1).await;
+    test_recommend_only_negative_with_shards(N_SHARDS).await;
+}
+
+async fn test_recommend_only_negative_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            negative: vec![7.into()],
+            strategy: Some(RecommendStrategy::AverageVector),
+            limit: 5,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            negative: vec![7.into()],
+            strategy: Some(RecommendStrategy::BestScore),
+            limit: 9,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.len(), 8);
+    // Points similar to the negative example go last
+    assert!(result[..6].iter().all(|point| point.score == 0.0));
+    assert_eq!(
+        ids(&result[6..]).into_iter().sorted().collect_vec(),
+        vec![0.into(), 8.into()],
+    );
+    assert!(result[6..].iter().all(|point| point.score == -1.0));
+
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            strategy: Some(RecommendStrategy::BestScore),
+            limit: 5,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recommend_by_vectors() {
+    test_recommend_by_vectors_with_shards(1).await;
+    test_recommend_by_vectors_with_shards(N_SHARDS).await;
+}
+
+async fn test_recommend_by_vectors_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), shard_number).await;
+
+    for strategy in [
+        RecommendStrategy::AverageVector,
+        RecommendStrategy::BestScore,
+    ] {
+        let result = recommend(
+            &collection,
+            RecommendRequest {
+                positive: vec![RecommendExample::Vector(vec![0.0, 1.0, 0.0, 0.0])],
+                negative: vec![8.into()],
+                strategy: Some(strategy),
+                limit: 2,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Raw vectors are used as is, and don't exclude any point
+        assert_eq!(
+            ids(&result).into_iter().sorted().collect_vec(),
+            vec![3.into(), 4.into()],
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recommend_missing_examples() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path(), N_SHARDS).await;
+
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            positive: vec![1.into(), 100.into()],
+            limit: 5,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(CollectionError::PointNotFound { missed_point_id }) if missed_point_id == 100.into()
+    ));
+
+    let result = recommend(
+        &collection,
+        RecommendRequest {
+            positive: vec![1.into(), 100.into()],
+            negative: vec![101.into()],
+            strategy: Some(RecommendStrategy::BestScore),
+            limit: 5,
+            ..Default::default()
+        },
+    )
+    .await;
+    match result {
+        Err(CollectionError::NotFound { what }) => {
+            assert!(what.contains("100"), "{what}");
+            assert!(what.contains("101"), "{what}");
+        }
+        other => panic!("Expected missing points error, got {other:?}"),
+    }
+}
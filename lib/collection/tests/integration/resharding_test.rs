@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::ops::Range;
+use std::time::Duration;
+
+use collection::collection::Collection;
+use collection::operations::point_ops::{Batch, PointOperations, WriteOrdering};
+use collection::operations::types::{CountRequest, ScrollRequest};
+use collection::operations::CollectionUpdateOperations;
+use collection::shards::resharding::{ReshardingState, ShardSplit};
+use collection::shards::shard::{PeerId, ShardId};
+use itertools::Itertools;
+use segment::types::PointIdType;
+use tempfile::Builder;
+use tokio::sync::mpsc;
+
+use crate::common::{load_local_collection, simple_collection_fixture};
+
+const THIS_PEER_ID: PeerId = 0;
+const NUM_POINTS: u64 = 1_000;
+const RESHARDING_TIMEOUT: Duration = Duration::from_secs(60);
+
+const SPLIT: ShardSplit = ShardSplit {
+    source_shard_id: 0,
+    target_shard_id: 2,
+};
+
+fn upsert_operation(ids: Range<u64>) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.clone().map(PointIdType::from).collect_vec(),
+            vectors: ids
+                .map(|n| vec![n as f32, 0.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    )
+}
+
+fn delete_operation(ids: Range<u64>) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: ids.map(PointIdType::from).collect_vec(),
+    })
+}
+
+async fn update(collection: &Collection, operation: CollectionUpdateOperations) {
+    collection
+        .update_from_client(operation, true, WriteOrdering::default())
+        .await
+        .unwrap();
+}
+
+async fn scroll_ids(collection: &Collection, shard_selection: Option<ShardId>) -> Vec<PointIdType> {
+    let request = ScrollRequest {
+        limit: Some(10 * NUM_POINTS as usize),
+        with_payload: Some(false.into()),
+        ..Default::default()
+    };
+    let result = collection
+        .scroll_by(request, None, shard_selection)
+        .await
+        .unwrap();
+    assert!(result.next_page_offset.is_none());
+    result.points.into_iter().map(|point| point.id).collect()
+}
+
+async fn count(collection: &Collection) -> usize {
+    let request = CountRequest {
+        filter: None,
+        exact: true,
+    };
+    collection.count(request, None).await.unwrap().count
+}
+
+/// Callbacks of the resharding, which report to the returned channel whether it succeeded
+fn resharding_callbacks() -> (
+    impl Future<Output = ()>,
+    impl Future<Output = ()>,
+    mpsc::UnboundedReceiver<bool>,
+) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let on_error_sender = sender.clone();
+    (
+        async move {
+            let _ = sender.send(true);
+        },
+        async move {
+            let _ = on_error_sender.send(false);
+        },
+        receiver,
+    )
+}
+
+/// Start resharding, which reports to the returned channel whether it succeeded
+async fn start_resharding(collection: &Collection) -> mpsc::UnboundedReceiver<bool> {
+    let (on_finish, on_error, receiver) = resharding_callbacks();
+    collection
+        .start_resharding(
+            ReshardingState {
+                split: SPLIT,
+                peer_id: THIS_PEER_ID,
+            },
+            on_finish,
+            on_error,
+        )
+        .await
+        .unwrap();
+    receiver
+}
+
+async fn wait_finished(result: &mut mpsc::UnboundedReceiver<bool>) {
+    let finished = tokio::time::timeout(RESHARDING_TIMEOUT, result.recv()).await;
+    assert_eq!(
+        finished.expect("resharding is not finished in time"),
+        Some(true)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resharding_with_concurrent_updates() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), 2).await;
+
+    update(&collection, upsert_operation(0..NUM_POINTS)).await;
+    let source_ids: HashSet<_> = scroll_ids(&collection, Some(SPLIT.source_shard_id))
+        .await
+        .into_iter()
+        .collect();
+
+    let mut result = start_resharding(&collection).await;
+    assert_eq!(collection.resharding_state().await.unwrap().split, SPLIT);
+
+    // Updates during the migration are applied to the new shard as well
+    let updates = async {
+        for batch in 0..10 {
+            let start = NUM_POINTS + batch * 50;
+            update(&collection, upsert_operation(start..start + 50)).await;
+            update(&collection, delete_operation(batch * 10..batch * 10 + 10)).await;
+        }
+    };
+    let (finished, ()) = tokio::join!(
+        tokio::time::timeout(RESHARDING_TIMEOUT, result.recv()),
+        updates,
+    );
+    assert_eq!(
+        finished.expect("resharding is not finished in time"),
+        Some(true)
+    );
+
+    // New shard is not used until the resharding is finished
+    assert_eq!(count(&collection).await, 1400);
+    collection.finish_resharding(SPLIT).await.unwrap();
+    assert!(collection.resharding_state().await.is_none());
+
+    let expected_ids = (100..NUM_POINTS + 500).map(PointIdType::from).collect_vec();
+    assert_eq!(scroll_ids(&collection, None).await, expected_ids);
+
+    // Every point is returned by exactly one shard
+    let mut shard_ids = Vec::new();
+    for shard_id in [0, 1, SPLIT.target_shard_id] {
+        shard_ids.push(scroll_ids(&collection, Some(shard_id)).await);
+    }
+    let target_ids = &shard_ids[2];
+    assert!(!target_ids.is_empty());
+    assert!(target_ids
+        .iter()
+        .filter(|id| **id < PointIdType::from(NUM_POINTS))
+        .all(|id| source_ids.contains(id)));
+    let all_ids = shard_ids.concat().into_iter().sorted().collect_vec();
+    assert_eq!(all_ids, expected_ids);
+
+    // Moved points are deleted from the split shard in background
+    let cleanup = async {
+        while count(&collection).await != expected_ids.len() {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+    tokio::time::timeout(RESHARDING_TIMEOUT, cleanup)
+        .await
+        .expect("moved points are not deleted in time");
+
+    // Points are routed into the new shard after the resharding
+    update(&collection, upsert_operation(0..100)).await;
+    assert_eq!(count(&collection).await, expected_ids.len() + 100);
+    assert_eq!(
+        collection
+            .cluster_info(THIS_PEER_ID)
+            .await
+            .unwrap()
+            .shard_count,
+        3
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_abort_resharding() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), 2).await;
+
+    update(&collection, upsert_operation(0..NUM_POINTS)).await;
+    let source_ids = scroll_ids(&collection, Some(SPLIT.source_shard_id)).await;
+
+    let _result = start_resharding(&collection).await;
+    update(&collection, upsert_operation(NUM_POINTS..NUM_POINTS + 100)).await;
+    collection.abort_resharding(SPLIT).await.unwrap();
+
+    // Split shard keeps all its points, the new shard is removed
+    assert!(collection.resharding_state().await.is_none());
+    assert_eq!(
+        collection
+            .cluster_info(THIS_PEER_ID)
+            .await
+            .unwrap()
+            .shard_count,
+        2
+    );
+    assert!(!collection_dir
+        .path()
+        .join(SPLIT.target_shard_id.to_string())
+        .exists());
+    let ids = scroll_ids(&collection, None).await;
+    assert_eq!(
+        ids,
+        (0..NUM_POINTS + 100).map(PointIdType::from).collect_vec()
+    );
+    let split_shard_ids: HashSet<_> = scroll_ids(&collection, Some(SPLIT.source_shard_id))
+        .await
+        .into_iter()
+        .collect();
+    assert!(source_ids.iter().all(|id| split_shard_ids.contains(id)));
+
+    // Aborted resharding can be started again
+    let mut result = start_resharding(&collection).await;
+    let finished = tokio::time::timeout(RESHARDING_TIMEOUT, result.recv()).await;
+    assert_eq!(
+        finished.expect("resharding is not finished in time"),
+        Some(true)
+    );
+    collection.finish_resharding(SPLIT).await.unwrap();
+    assert_eq!(scroll_ids(&collection, None).await, ids);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resume_resharding_after_restart() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection_path = collection_dir.path();
+    {
+        let collection = simple_collection_fixture(collection_path, 2).await;
+        update(&collection, upsert_operation(0..NUM_POINTS)).await;
+        // Resharding is not reported as finished to consensus before the restart
+        let mut result = start_resharding(&collection).await;
+        wait_finished(&mut result).await;
+    }
+
+    let collection = load_local_collection(
+        "test".to_string(),
+        collection_path,
+        &collection_path.join("snapshots"),
+    )
+    .await;
+    assert_eq!(collection.resharding_state().await.unwrap().split, SPLIT);
+
+    let (on_finish, on_error, mut result) = resharding_callbacks();
+    collection
+        .resume_resharding(on_finish, on_error)
+        .await
+        .unwrap();
+    update(&collection, upsert_operation(NUM_POINTS..NUM_POINTS + 100)).await;
+    wait_finished(&mut result).await;
+    collection.finish_resharding(SPLIT).await.unwrap();
+
+    let expected_ids = (0..NUM_POINTS + 100).map(PointIdType::from).collect_vec();
+    let mut shard_ids = Vec::new();
+    for shard_id in [0, 1, SPLIT.target_shard_id] {
+        shard_ids.push(scroll_ids(&collection, Some(shard_id)).await);
+    }
+    assert!(!shard_ids[2].is_empty());
+    let all_ids = shard_ids.concat().into_iter().sorted().collect_vec();
+    assert_eq!(all_ids, expected_ids);
+}
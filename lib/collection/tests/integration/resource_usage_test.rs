@@ -0,0 +1,180 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use collection::collection::Collection;
+use collection::common::resource_usage::CollectionResourceUsage;
+use collection::config::CollectionConfig;
+use collection::operations::config_diff::QuantizationConfigDiff;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::shared_storage_config::SharedStorageConfig;
+use collection::operations::types::CollectionStatus;
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use collection::optimizers_builder::OptimizersConfig;
+use collection::shards::channel_service::ChannelService;
+use collection::shards::collection_shard_distribution::CollectionShardDistribution;
+use collection::shards::replica_set::ReplicaState;
+use itertools::Itertools;
+use segment::types::{
+    Payload, PayloadFieldSchema, PayloadSchemaType, PointIdType, ScalarQuantization,
+    ScalarQuantizationConfig, ScalarType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+use crate::common::{
+    dummy_on_replica_failure, dummy_request_shard_transfer, optimizers_collection_config,
+    TEST_OPTIMIZERS_CONFIG,
+};
+
+const NUM_POINTS: u64 = 2_000;
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+const CONVERGENCE_TIMEOUT: Duration = Duration::from_secs(60);
+
+async fn new_collection(collection_dir: &std::path::Path, config: &CollectionConfig) -> Collection {
+    let storage_config = SharedStorageConfig {
+        resource_usage_refresh_interval: REFRESH_INTERVAL,
+        ..Default::default()
+    };
+    let collection = Collection::new(
+        "test".to_string(),
+        0,
+        collection_dir,
+        &collection_dir.join("snapshots"),
+        config,
+        Arc::new(storage_config),
+        CollectionShardDistribution::all_local(Some(config.params.shard_number.into()), 0),
+        ChannelService::default(),
+        dummy_on_replica_failure(),
+        dummy_request_shard_transfer(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    for shard_id in collection.get_local_shards().await {
+        collection
+            .set_shard_replica_state(shard_id, 0, ReplicaState::Active, None)
+            .await
+            .unwrap();
+    }
+    collection
+}
+
+async fn wait_for_green(collection: &Collection) {
+    let wait = async {
+        loop {
+            let info = collection.info(None).await.unwrap();
+            assert_ne!(info.status, CollectionStatus::Red);
+            if info.status == CollectionStatus::Green {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    };
+    tokio::time::timeout(CONVERGENCE_TIMEOUT, wait)
+        .await
+        .expect("optimizations are not finished in time");
+}
+
+/// Usage, computed after the cached one is expired
+async fn fresh_usage(collection: &Collection) -> CollectionResourceUsage {
+    tokio::time::sleep(REFRESH_INTERVAL).await;
+    collection.resource_usage().await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resource_usage_grows_with_data() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let config = optimizers_collection_config(
+        2,
+        OptimizersConfig {
+            indexing_threshold: Some(10), // KB
+            ..TEST_OPTIMIZERS_CONFIG.clone()
+        },
+    );
+    let collection = new_collection(collection_dir.path(), &config).await;
+
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "city".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword)),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let empty = collection.resource_usage().await.unwrap();
+    assert_eq!(empty.shards.len(), 2);
+    assert_eq!(empty.segments.vectors_ram_bytes, 0);
+    assert_eq!(empty.segments.hnsw_links_bytes, 0);
+    assert_eq!(empty.segments.quantized_vectors_bytes, 0);
+    assert!(empty.wal_bytes > 0);
+    assert_eq!(
+        empty.wal_bytes,
+        empty
+            .shards
+            .iter()
+            .map(|shard| shard.wal_bytes)
+            .sum::<usize>(),
+    );
+
+    let insert = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..NUM_POINTS).map(PointIdType::from).collect_vec(),
+            vectors: (0..NUM_POINTS)
+                .map(|n| vec![n as f32, 1.0, 0.5, -1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                (0..NUM_POINTS)
+                    .map(|n| {
+                        let city = format!("city-{}", n % 50);
+                        Some(Payload::from(json!({ "city": city })))
+                    })
+                    .collect_vec(),
+            ),
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Cached usage is returned within the refresh interval
+    let cached = collection.resource_usage().await.unwrap();
+    assert_eq!(cached, empty);
+
+    wait_for_green(&collection).await;
+    let loaded = fresh_usage(&collection).await;
+    assert!(loaded.computed_at > empty.computed_at);
+    assert!(loaded.segments.vectors_ram_bytes >= NUM_POINTS as usize * 4 * 4);
+    assert!(loaded.segments.payload_storage_bytes > empty.segments.payload_storage_bytes);
+    assert!(loaded.segments.payload_index_bytes["city"] > 0);
+    assert!(loaded.segments.hnsw_links_bytes > 0);
+    assert_eq!(loaded.segments.quantized_vectors_bytes, 0);
+    for shard in &loaded.shards {
+        assert!(shard.segments.vectors_ram_bytes > 0);
+    }
+
+    collection
+        .update_quantization_config_from_diff(QuantizationConfigDiff::Scalar(ScalarQuantization {
+            scalar: ScalarQuantizationConfig {
+                r#type: ScalarType::Int8,
+                quantile: None,
+                always_ram: None,
+            },
+        }))
+        .await
+        .unwrap();
+    collection.recreate_optimizers_blocking().await.unwrap();
+    wait_for_green(&collection).await;
+
+    // Quantized vectors are added next to the original ones
+    let quantized = fresh_usage(&collection).await;
+    assert!(quantized.segments.quantized_vectors_bytes >= NUM_POINTS as usize * 4);
+    assert!(quantized.segments.vectors_ram_bytes >= NUM_POINTS as usize * 4 * 4);
+    assert!(quantized.segments.hnsw_links_bytes > 0);
+}
@@ -0,0 +1,110 @@
+use collection::collection::Collection;
+use collection::common::search_recall::{RecallTestReport, RecallTestRequest};
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::CollectionError;
+use collection::operations::CollectionUpdateOperations;
+use itertools::Itertools;
+use segment::types::PointIdType;
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 300;
+
+async fn fixture(collection_path: &std::path::Path) -> Collection {
+    let collection = simple_collection_fixture(collection_path, N_SHARDS).await;
+    let operation = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..NUM_POINTS).map(PointIdType::from).collect_vec(),
+            vectors: (0..NUM_POINTS)
+                .map(|n| vec![(n % 17) as f32, (n % 5) as f32, 0.5, -1.0])
+                .collect_vec()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(operation, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    collection
+}
+
+fn request(sample_size: usize, seed: Option<u64>) -> RecallTestRequest {
+    RecallTestRequest {
+        sample_size,
+        limit: 10,
+        using: None,
+        params: None,
+        seed,
+    }
+}
+
+fn check_report(report: &RecallTestReport, expected_samples: usize) {
+    assert_eq!(report.limit, 10);
+    assert_eq!(report.samples, expected_samples);
+    // Segments of the fixture are not indexed, so approximate search is exact
+    assert_eq!(report.recall, Some(1.0));
+    assert!(report.exact_latency.p50_micros.is_some());
+    assert!(report.approximate_latency.max_micros.is_some());
+
+    assert!(!report.segments.is_empty());
+    assert_eq!(
+        report
+            .segments
+            .iter()
+            .map(|segment| segment.samples)
+            .sum::<usize>(),
+        expected_samples,
+    );
+    assert_eq!(
+        report
+            .segments
+            .iter()
+            .map(|segment| segment.points_count)
+            .sum::<usize>(),
+        NUM_POINTS as usize,
+    );
+    for segment in &report.segments {
+        assert!(!segment.vector_indexed);
+        assert!(segment.shard_id < N_SHARDS);
+        assert_eq!(segment.recall.is_some(), segment.samples > 0);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recall_test_of_collection() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path()).await;
+
+    let report = collection.recall_test(request(50, None)).await.unwrap();
+    assert_eq!(report.using, "");
+    check_report(&report, 50);
+
+    // Sample is capped by the number of points
+    let report = collection.recall_test(request(1000, None)).await.unwrap();
+    check_report(&report, NUM_POINTS as usize);
+
+    // Same seed samples the same points
+    let first = collection.recall_test(request(20, Some(42))).await.unwrap();
+    let second = collection.recall_test(request(20, Some(42))).await.unwrap();
+    let samples_by_segment = |report: &RecallTestReport| {
+        report
+            .segments
+            .iter()
+            .map(|segment| (segment.shard_id, segment.segment_id, segment.samples))
+            .collect_vec()
+    };
+    assert_eq!(samples_by_segment(&first), samples_by_segment(&second));
+
+    // Unknown vector is rejected
+    let err = collection
+        .recall_test(RecallTestRequest {
+            using: Some("missing".to_string()),
+            ..request(10, None)
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, CollectionError::BadInput { .. }), "{err}");
+}
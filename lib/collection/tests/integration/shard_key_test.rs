@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::num::{NonZeroU32, NonZeroU64};
+use std::ops::Range;
+use std::path::Path;
+
+use collection::collection::Collection;
+use collection::config::{CollectionConfig, CollectionParams, ShardingMethod, WalConfig};
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::shard_key_selector::ShardSelectorInternal;
+use collection::operations::types::{
+    CollectionError, CountRequest, RecommendRequest, ScrollRequest, SearchRequest,
+    SearchRequestBatch, VectorParams,
+};
+use collection::operations::CollectionUpdateOperations;
+use collection::recommendations::recommend_by;
+use collection::shards::channel_service::ChannelService;
+use collection::shards::collection_shard_distribution::CollectionShardDistribution;
+use collection::shards::replica_set::ReplicaState;
+use collection::shards::shard::{PeerId, ShardId, ShardKey};
+use itertools::Itertools;
+use segment::types::{Distance, PointIdType};
+use tempfile::Builder;
+
+use crate::common::{
+    dummy_on_replica_failure, dummy_request_shard_transfer, TEST_OPTIMIZERS_CONFIG,
+};
+
+const THIS_PEER_ID: PeerId = 0;
+
+async fn custom_sharding_fixture(collection_path: &Path) -> Collection {
+    let collection_params = CollectionParams {
+        vectors: VectorParams {
+            size: NonZeroU64::new(4).unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype: None,
+            multivector_config: None,
+        }
+        .into(),
+        shard_number: NonZeroU32::new(1).unwrap(),
+        sharding_method: Some(ShardingMethod::Custom),
+        replication_factor: NonZeroU32::new(1).unwrap(),
+        write_consistency_factor: NonZeroU32::new(1).unwrap(),
+        on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
+    };
+
+    let collection_config = CollectionConfig {
+        params: collection_params,
+        optimizer_config: TEST_OPTIMIZERS_CONFIG.clone(),
+        wal_config: WalConfig {
+            wal_capacity_mb: 1,
+            wal_segments_ahead: 0,
+            ..Default::default()
+        },
+        hnsw_config: Default::default(),
+        quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
+    };
+
+    // Collection with custom sharding has no shards, until shard keys are created
+    Collection::new(
+        "test".to_string(),
+        THIS_PEER_ID,
+        collection_path,
+        &collection_path.join("snapshots"),
+        &collection_config,
+        Default::default(),
+        CollectionShardDistribution {
+            shards: HashMap::new(),
+        },
+        ChannelService::default(),
+        dummy_on_replica_failure(),
+        dummy_request_shard_transfer(),
+        None,
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+async fn create_shard_key(
+    collection: &Collection,
+    shard_key: ShardKey,
+    shard_number: usize,
+) -> Vec<ShardId> {
+    let local_shards = collection
+        .create_shard_key(shard_key, vec![vec![THIS_PEER_ID]; shard_number])
+        .await
+        .unwrap();
+    for shard_id in &local_shards {
+        collection
+            .set_shard_replica_state(*shard_id, THIS_PEER_ID, ReplicaState::Active, None)
+            .await
+            .unwrap();
+    }
+    local_shards
+}
+
+fn upsert_operation(ids: Range<u64>) -> CollectionUpdateOperations {
+    CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.clone().map(PointIdType::from).collect_vec(),
+            vectors: ids
+                .map(|n| vec![n as f32, 0.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    )
+}
+
+fn search_request() -> SearchRequest {
+    SearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 1.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 100,
+        offset: 0,
+        score_threshold: None,
+    }
+}
+
+async fn search_ids(
+    collection: &Collection,
+    shard_selection: ShardSelectorInternal,
+) -> Result<Vec<PointIdType>, CollectionError> {
+    let request = search_request();
+    let points = collection
+        .search(request, None, shard_selection, None)
+        .await?;
+    Ok(points.into_iter().map(|point| point.id).sorted().collect())
+}
+
+fn point_ids(ids: Range<u64>) -> Vec<PointIdType> {
+    ids.map(PointIdType::from).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shard_key_routing() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = custom_sharding_fixture(collection_dir.path()).await;
+
+    assert_eq!(collection.info(None).await.unwrap().points_count, 0);
+
+    let keyword_key = ShardKey::from("tenant");
+    let number_key = ShardKey::Number(7);
+    let keyword_shards = create_shard_key(&collection, keyword_key.clone(), 2).await;
+    let number_shards = create_shard_key(&collection, number_key.clone(), 1).await;
+    assert_eq!(keyword_shards, vec![0, 1]);
+    assert_eq!(number_shards, vec![2]);
+    assert!(matches!(
+        collection
+            .create_shard_key(keyword_key.clone(), vec![vec![THIS_PEER_ID]])
+            .await,
+        Err(CollectionError::BadRequest { .. })
+    ));
+
+    // Points are inserted only into the shards of the shard key
+    assert!(matches!(
+        collection
+            .update_from_client(upsert_operation(0..10), true, WriteOrdering::default())
+            .await,
+        Err(CollectionError::BadRequest { .. })
+    ));
+    collection
+        .update_from_client_with_shard_key(
+            upsert_operation(0..10),
+            Some(keyword_key.clone()),
+            None,
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    collection
+        .update_from_client_with_shard_key(
+            upsert_operation(10..15),
+            Some(number_key.clone()),
+            None,
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    let count_request = || CountRequest {
+        filter: None,
+        exact: true,
+    };
+    let mut keyword_count = 0;
+    for shard_id in keyword_shards {
+        keyword_count += collection
+            .count(count_request(), Some(shard_id))
+            .await
+            .unwrap()
+            .count;
+    }
+    assert_eq!(keyword_count, 10);
+
+    // Searches select shards by shard keys
+    assert!(matches!(
+        search_ids(&collection, ShardSelectorInternal::Empty).await,
+        Err(CollectionError::BadRequest { .. })
+    ));
+    assert!(matches!(
+        search_ids(&collection, ShardKey::from("unknown").into()).await,
+        Err(CollectionError::BadRequest { .. })
+    ));
+    assert_eq!(
+        search_ids(&collection, keyword_key.clone().into())
+            .await
+            .unwrap(),
+        point_ids(0..10),
+    );
+    assert_eq!(
+        search_ids(&collection, number_key.clone().into())
+            .await
+            .unwrap(),
+        point_ids(10..15),
+    );
+    assert_eq!(
+        search_ids(
+            &collection,
+            ShardSelectorInternal::ShardKeys(vec![keyword_key.clone(), number_key.clone()]),
+        )
+        .await
+        .unwrap(),
+        point_ids(0..15),
+    );
+
+    // Dropping a shard key removes only the points of this key
+    collection
+        .drop_shard_key(keyword_key.clone())
+        .await
+        .unwrap();
+    assert!(matches!(
+        search_ids(&collection, keyword_key.into()).await,
+        Err(CollectionError::BadRequest { .. })
+    ));
+    assert_eq!(
+        search_ids(&collection, ShardSelectorInternal::All)
+            .await
+            .unwrap(),
+        point_ids(10..15),
+    );
+    assert_eq!(collection.info(None).await.unwrap().points_count, 5);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shard_key_selection_of_reads() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = custom_sharding_fixture(collection_dir.path()).await;
+
+    let keyword_key = ShardKey::from("tenant");
+    let number_key = ShardKey::Number(7);
+    create_shard_key(&collection, keyword_key.clone(), 2).await;
+    create_shard_key(&collection, number_key.clone(), 1).await;
+    for (shard_key, ids) in [(&keyword_key, 0..10), (&number_key, 10..15)] {
+        collection
+            .update_from_client_with_shard_key(
+                upsert_operation(ids),
+                Some(shard_key.clone()),
+                None,
+                true,
+                WriteOrdering::default(),
+            )
+            .await
+            .unwrap();
+    }
+    let both_keys = ShardSelectorInternal::ShardKeys(vec![keyword_key.clone(), number_key.clone()]);
+
+    // Count
+    let count = |shard_selection: ShardSelectorInternal| {
+        let request = CountRequest {
+            filter: None,
+            exact: true,
+        };
+        collection.count(request, shard_selection)
+    };
+    assert!(matches!(
+        count(ShardSelectorInternal::Empty).await,
+        Err(CollectionError::BadRequest { .. })
+    ));
+    assert_eq!(count(keyword_key.clone().into()).await.unwrap().count, 10);
+    assert_eq!(count(number_key.clone().into()).await.unwrap().count, 5);
+    assert_eq!(count(both_keys.clone()).await.unwrap().count, 15);
+
+    // Scroll
+    let scroll_ids = |shard_selection: ShardSelectorInternal| async move {
+        let request = ScrollRequest {
+            limit: Some(100),
+            ..Default::default()
+        };
+        let result = collection.scroll_by(request, None, shard_selection).await?;
+        Ok::<_, CollectionError>(
+            result
+                .points
+                .into_iter()
+                .map(|point| point.id)
+                .collect_vec(),
+        )
+    };
+    assert!(matches!(
+        scroll_ids(ShardSelectorInternal::Empty).await,
+        Err(CollectionError::BadRequest { .. })
+    ));
+    assert_eq!(
+        scroll_ids(number_key.clone().into()).await.unwrap(),
+        point_ids(10..15),
+    );
+    assert_eq!(
+        scroll_ids(both_keys.clone()).await.unwrap(),
+        point_ids(0..15)
+    );
+
+    // Search batch
+    let batch = SearchRequestBatch {
+        searches: vec![search_request(), search_request()],
+        fail_fast: Some(true),
+    };
+    let results = collection
+        .search_batch(batch, None, keyword_key.clone(), None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        let ids = result.unwrap().into_iter().map(|point| point.id).sorted();
+        assert_eq!(ids.collect_vec(), point_ids(0..10));
+    }
+
+    // Recommend looks up the examples in the shards of the shard keys
+    let recommend = |shard_selection: ShardSelectorInternal| {
+        let request = RecommendRequest {
+            positive: vec![0.into()],
+            limit: 100,
+            ..Default::default()
+        };
+        recommend_by(
+            request,
+            &collection,
+            |_name| async { unreachable!("Should not be called in this test") },
+            None,
+            shard_selection,
+        )
+    };
+    assert!(matches!(
+        recommend(number_key.into()).await,
+        Err(CollectionError::PointNotFound { .. })
+    ));
+    let ids = recommend(keyword_key.into())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|point| point.id)
+        .sorted()
+        .collect_vec();
+    assert_eq!(ids, point_ids(1..10));
+}
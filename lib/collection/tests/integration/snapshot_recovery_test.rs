@@ -23,6 +23,7 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -32,11 +33,18 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            datatype: None,
+            multivector_config: None,
         }),
         shard_number: NonZeroU32::new(1).unwrap(),
+        sharding_method: None,
         replication_factor: NonZeroU32::new(1).unwrap(),
         write_consistency_factor: NonZeroU32::new(1).unwrap(),
         on_disk_payload: false,
+        tenant_key: None,
+        sparse_vectors: None,
+        store_original_vectors: false,
+        search_timeout_sec: None,
     };
 
     let config = CollectionConfig {
@@ -45,6 +53,7 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
         wal_config,
         hnsw_config: Default::default(),
         quantization_config: Default::default(),
+        strict_mode_config: Default::default(),
     };
 
     let snapshots_path = Builder::new().prefix("test_snapshots").tempdir().unwrap();
@@ -112,7 +121,7 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
     // Take a snapshot
     let snapshots_temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
     let snapshot_description = collection
-        .create_snapshot(snapshots_temp_dir.path(), 0)
+        .create_snapshot(snapshots_temp_dir.path(), 0, &[])
         .await
         .unwrap();
 
@@ -137,7 +146,8 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
         None,
         None,
     )
-    .await;
+    .await
+    .unwrap();
 
     let query_vector = vec![1.0, 0.0, 0.0, 0.0];
 
@@ -153,12 +163,12 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
     };
 
     let reference_result = collection
-        .search(full_search_request.clone(), None, None)
+        .search(full_search_request.clone(), None, None, None)
         .await
         .unwrap();
 
     let recovered_result = recovered_collection
-        .search(full_search_request, None, None)
+        .search(full_search_request, None, None, None)
         .await
         .unwrap();
 
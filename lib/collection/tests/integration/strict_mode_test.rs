@@ -0,0 +1,348 @@
+use collection::collection::Collection;
+use collection::operations::payload_ops::{PayloadOps, SetPayload};
+use collection::operations::point_ops::{Batch, PointOperations, WriteOrdering};
+use collection::operations::strict_mode::StrictModeConfig;
+use collection::operations::types::{
+    CollectionError, CountRequest, FacetRequest, ScrollRequest, SearchRequest,
+};
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use itertools::Itertools;
+use segment::types::{
+    Condition, FieldCondition, Filter, Match, Payload, PayloadFieldSchema, PayloadSchemaType,
+    PointIdType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const NUM_POINTS: u64 = 20;
+
+async fn fixture(collection_path: &std::path::Path) -> Collection {
+    let collection = simple_collection_fixture(collection_path, N_SHARDS).await;
+
+    for (field_name, schema) in [
+        ("num", PayloadSchemaType::Integer),
+        ("color", PayloadSchemaType::Keyword),
+    ] {
+        let create_index = CollectionUpdateOperations::FieldIndexOperation(
+            FieldIndexOperations::CreateIndex(CreateIndex {
+                field_name: field_name.to_string(),
+                field_schema: Some(PayloadFieldSchema::FieldType(schema)),
+            }),
+        );
+        collection
+            .update_from_client(create_index, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+
+    collection
+        .update_from_client(
+            upsert_operation(0..NUM_POINTS),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    collection
+}
+
+fn upsert_operation(ids: impl Iterator<Item = u64>) -> CollectionUpdateOperations {
+    let ids = ids.collect_vec();
+    CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: ids.iter().map(|n| PointIdType::from(*n)).collect_vec(),
+            vectors: ids
+                .iter()
+                .map(|n| vec![*n as f32, 0.0, 0.0, 1.0])
+                .collect_vec()
+                .into(),
+            payloads: Some(
+                ids.iter()
+                    .map(|n| {
+                        Some(Payload::from(json!({
+                            "num": n % 5,
+                            "color": if n % 2 == 0 { "red" } else { "blue" },
+                            "text": format!("point {n}"),
+                        })))
+                    })
+                    .collect_vec(),
+            ),
+        }
+        .into(),
+    )
+}
+
+fn search_request(limit: usize, offset: usize, filter: Option<Filter>) -> SearchRequest {
+    SearchRequest {
+        vector: vec![1.0, 0.0, 0.0, 1.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter,
+        params: None,
+        limit,
+        offset,
+        score_threshold: None,
+    }
+}
+
+fn match_filter(key: &str, value: &str) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_match(
+        key,
+        Match::from(value.to_string()),
+    )))
+}
+
+fn assert_strict_mode_error<T: std::fmt::Debug>(
+    result: Result<T, CollectionError>,
+    limit_name: &str,
+) {
+    match result {
+        Err(CollectionError::StrictMode { description }) => {
+            assert!(description.contains(limit_name), "{description}")
+        }
+        other => panic!("expected strict mode error for {limit_name}, got {other:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_strict_mode_disabled_by_default() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path()).await;
+
+    let unindexed_filter = match_filter("text", "point 1");
+    collection
+        .search(
+            search_request(1000, 1000, Some(unindexed_filter.clone())),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    collection
+        .check_strict_mode_update(&upsert_operation(0..1000))
+        .await
+        .unwrap();
+
+    // Limits are not checked, until strict mode is enabled
+    collection
+        .update_strict_mode_config(StrictModeConfig {
+            max_query_limit: Some(1),
+            unindexed_filtering_retrieve: Some(false),
+            upsert_max_batchsize: Some(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    collection
+        .search(
+            search_request(10, 0, Some(unindexed_filter.clone())),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    collection
+        .check_strict_mode_update(&upsert_operation(0..10))
+        .await
+        .unwrap();
+
+    collection
+        .update_strict_mode_config(StrictModeConfig {
+            enabled: Some(true),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_strict_mode_error(
+        collection
+            .search(search_request(10, 0, None), None, None, None)
+            .await,
+        "max_query_limit",
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_strict_mode_limits() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = fixture(collection_dir.path()).await;
+
+    collection
+        .update_strict_mode_config(StrictModeConfig {
+            enabled: Some(true),
+            max_query_limit: Some(5),
+            max_query_offset: Some(10),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // Query limit and offset
+    collection
+        .search(search_request(5, 10, None), None, None, None)
+        .await
+        .unwrap();
+    assert_strict_mode_error(
+        collection
+            .search(search_request(6, 0, None), None, None, None)
+            .await,
+        "max_query_limit",
+    );
+    assert_strict_mode_error(
+        collection
+            .search(search_request(5, 11, None), None, None, None)
+            .await,
+        "max_query_offset",
+    );
+    let scroll = |limit| ScrollRequest {
+        limit: Some(limit),
+        ..Default::default()
+    };
+    collection.scroll_by(scroll(5), None, None).await.unwrap();
+    assert_strict_mode_error(
+        collection.scroll_by(scroll(6), None, None).await,
+        "max_query_limit",
+    );
+    let facet = |limit| FacetRequest {
+        key: "color".to_string(),
+        limit: Some(limit),
+        filter: None,
+        exact: true,
+    };
+    collection.facet(facet(5), None).await.unwrap();
+    assert_strict_mode_error(collection.facet(facet(6), None).await, "max_query_limit");
+    // Requests of other peers to a selected shard are checked by the peer they came from
+    collection
+        .search(search_request(6, 0, None), None, Some(0), None)
+        .await
+        .unwrap();
+
+    // Limits are updated without a restart of the collection
+    collection
+        .update_strict_mode_config(StrictModeConfig {
+            max_query_limit: Some(100),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    collection
+        .search(search_request(6, 0, None), None, None, None)
+        .await
+        .unwrap();
+    // Other limits are kept
+    assert_strict_mode_error(
+        collection
+            .search(search_request(5, 11, None), None, None, None)
+            .await,
+        "max_query_offset",
+    );
+
+    // Number of filter conditions
+    collection
+        .update_strict_mode_config(StrictModeConfig {
+            max_filter_conditions: Some(2),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let two_conditions = Filter {
+        should: Some(vec![
+            Condition::Filter(match_filter("color", "red")),
+            Condition::Filter(match_filter("color", "blue")),
+        ]),
+        ..Default::default()
+    };
+    let three_conditions = two_conditions.merge(&match_filter("color", "green"));
+    let count = |filter: &Filter| CountRequest {
+        filter: Some(filter.clone()),
+        exact: true,
+    };
+    collection
+        .count(count(&two_conditions), None)
+        .await
+        .unwrap();
+    assert_strict_mode_error(
+        collection.count(count(&three_conditions), None).await,
+        "max_filter_conditions",
+    );
+
+    // Filters by fields without payload index
+    collection
+        .update_strict_mode_config(StrictModeConfig {
+            unindexed_filtering_retrieve: Some(false),
+            unindexed_filtering_update: Some(false),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let scroll_filtered = |filter| ScrollRequest {
+        filter: Some(filter),
+        ..Default::default()
+    };
+    collection
+        .scroll_by(scroll_filtered(match_filter("color", "red")), None, None)
+        .await
+        .unwrap();
+    assert_strict_mode_error(
+        collection
+            .scroll_by(scroll_filtered(match_filter("text", "point 1")), None, None)
+            .await,
+        "unindexed_filtering_retrieve",
+    );
+    let delete_by_filter = |filter| {
+        CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(filter))
+    };
+    collection
+        .check_strict_mode_update(&delete_by_filter(match_filter("color", "green")))
+        .await
+        .unwrap();
+    assert_strict_mode_error(
+        collection
+            .check_strict_mode_update(&delete_by_filter(match_filter("text", "point 1")))
+            .await,
+        "unindexed_filtering_update",
+    );
+
+    // Upsert batch size and payload size
+    collection
+        .update_strict_mode_config(StrictModeConfig {
+            upsert_max_batchsize: Some(10),
+            max_point_payload_bytes: Some(64),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    collection
+        .check_strict_mode_update(&upsert_operation(0..10))
+        .await
+        .unwrap();
+    assert_strict_mode_error(
+        collection
+            .check_strict_mode_update(&upsert_operation(0..11))
+            .await,
+        "upsert_max_batchsize",
+    );
+    let set_payload = |payload: serde_json::Value| {
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+            payload: payload.into(),
+            points: Some(vec![1.into()]),
+            filter: None,
+            if_version: None,
+            key: None,
+        }))
+    };
+    collection
+        .check_strict_mode_update(&set_payload(json!({ "text": "short" })))
+        .await
+        .unwrap();
+    assert_strict_mode_error(
+        collection
+            .check_strict_mode_update(&set_payload(json!({ "text": "long ".repeat(20) })))
+            .await,
+        "max_point_payload_bytes",
+    );
+}
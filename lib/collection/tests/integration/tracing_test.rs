@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::types::SearchRequest;
+use collection::operations::CollectionUpdateOperations;
+use parking_lot::Mutex;
+use segment::types::{Condition, Filter, HasIdCondition};
+use tempfile::Builder;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Instrument, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::common::{simple_collection_fixture, N_SHARDS};
+
+const ROOT_SPAN: &str = "tracing_test_request";
+
+/// Span captured by the test subscriber
+#[derive(Debug, Clone)]
+struct RecordedSpan {
+    /// Names of the span and its ancestors, starting from the root
+    path: Vec<&'static str>,
+    fields: HashMap<&'static str, String>,
+}
+
+impl RecordedSpan {
+    fn name(&self) -> &'static str {
+        self.path.last().unwrap()
+    }
+
+    fn parent(&self) -> Option<&'static str> {
+        self.path.iter().rev().nth(1).copied()
+    }
+}
+
+struct FieldsVisitor<'a>(&'a mut HashMap<&'static str, String>);
+
+impl Visit for FieldsVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+/// Records every span, created within the root span of the test
+#[derive(Default)]
+struct RecordingLayer {
+    /// Spans by id. Ids are reused after the span is closed, so spans are kept here while open.
+    open: Mutex<HashMap<u64, RecordedSpan>>,
+    closed: Arc<Mutex<Vec<RecordedSpan>>>,
+}
+
+impl<S> Layer<S> for RecordingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.span_scope(id) else {
+            return;
+        };
+        let path: Vec<_> = scope.from_root().map(|span| span.name()).collect();
+        if path.first() != Some(&ROOT_SPAN) {
+            return;
+        }
+        let mut fields = HashMap::new();
+        attrs.record(&mut FieldsVisitor(&mut fields));
+        self.open
+            .lock()
+            .insert(id.into_u64(), RecordedSpan { path, fields });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        if let Some(span) = self.open.lock().get_mut(&id.into_u64()) {
+            values.record(&mut FieldsVisitor(&mut span.fields));
+        }
+    }
+
+    fn on_close(&self, id: Id, _ctx: Context<'_, S>) {
+        if let Some(span) = self.open.lock().remove(&id.into_u64()) {
+            self.closed.lock().push(span);
+        }
+    }
+}
+
+/// Spans of the test requests, recorded by the global subscriber
+fn recorded_spans() -> Arc<Mutex<Vec<RecordedSpan>>> {
+    static CLOSED: OnceLock<Arc<Mutex<Vec<RecordedSpan>>>> = OnceLock::new();
+    CLOSED
+        .get_or_init(|| {
+            let layer = RecordingLayer::default();
+            let closed = layer.closed.clone();
+            tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))
+                .unwrap();
+            closed
+        })
+        .clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_span_hierarchy() {
+    let spans = recorded_spans();
+
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), N_SHARDS).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..10).map(|id| id.into()).collect(),
+            vectors: (0..10)
+                .map(|id| vec![1.0, id as f32, 0.0, 1.0])
+                .collect::<Vec<_>>()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let search_request = SearchRequest {
+        vector: vec![1.0, 1.0, 0.0, 1.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: Some(Filter::new_must(Condition::HasId(HasIdCondition {
+            has_id: (0..5).map(|id| id.into()).collect(),
+        }))),
+        params: None,
+        limit: 3,
+        offset: 0,
+        score_threshold: None,
+    };
+
+    let result = collection
+        .search(search_request, None, None, None)
+        .instrument(tracing::info_span!(ROOT_SPAN))
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 3);
+
+    let spans = spans.lock().clone();
+    let spans_named = |name: &str| spans.iter().filter(move |span| span.name() == name);
+
+    // Every shard is searched in a separate span, under the request span
+    let shard_spans: Vec<_> = spans_named("shard_search").collect();
+    assert_eq!(shard_spans.len(), N_SHARDS as usize);
+    for span in &shard_spans {
+        assert_eq!(span.parent(), Some(ROOT_SPAN));
+        assert_eq!(span.fields["collection"], "test");
+    }
+    let mut shard_ids: Vec<_> = shard_spans
+        .iter()
+        .map(|span| span.fields["shard_id"].clone())
+        .collect();
+    shard_ids.sort();
+    assert_eq!(shard_ids, ["0", "1", "2"]);
+    let shard_result_count: usize = shard_spans
+        .iter()
+        .map(|span| span.fields["result_count"].parse::<usize>().unwrap())
+        .sum();
+    assert!(shard_result_count >= result.len());
+
+    // Segment searches run in blocking threads, but are still children of the shard spans
+    let segment_spans: Vec<_> = spans_named("segment_search").collect();
+    assert!(!segment_spans.is_empty());
+    for span in &segment_spans {
+        assert_eq!(
+            span.path,
+            [ROOT_SPAN, "shard_search", "segment_search"],
+            "unexpected span path"
+        );
+        assert!(span.fields.contains_key("segment_id"));
+        assert!(span.fields.contains_key("result_count"));
+    }
+
+    // Filter is applied inside of the segment search
+    let filter_spans: Vec<_> = spans_named("payload_filter").collect();
+    assert!(!filter_spans.is_empty());
+    for span in &filter_spans {
+        assert_eq!(span.parent(), Some("segment_search"));
+        assert!(span.fields.contains_key("matched_points"));
+    }
+}
@@ -24,8 +24,15 @@ fn hnsw_benchmark(c: &mut Criterion) {
     group.bench_function("hnsw_index", |b| {
         b.iter(|| {
             let mut rng = thread_rng();
-            let mut graph_layers_builder =
-                GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+            let mut graph_layers_builder = GraphLayersBuilder::new(
+                NUM_VECTORS,
+                M,
+                M * 2,
+                EF_CONSTRUCT,
+                10,
+                USE_HEURISTIC,
+                None,
+            );
             let fake_filter_context = FakeFilterContext {};
             for idx in 0..(NUM_VECTORS as PointOffsetType) {
                 let added_vector = vector_holder.vectors.get(idx).to_vec();
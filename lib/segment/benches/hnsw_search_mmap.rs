@@ -0,0 +1,172 @@
+//! HNSW search over mmap vectors which are not in page cache
+//!
+//! Compares the default synchronous scorer, which takes a page fault per cold vector,
+//! with the async scorer, which reads a batch of vectors with io_uring or prefetches them
+//! with `madvise`. Vectors file is evicted from page cache before every search, so at least
+//! the file system of the temp dir must be backed by a real disk to see a difference.
+
+#[cfg(target_os = "linux")]
+mod prof;
+
+#[cfg(target_os = "linux")]
+mod cold_search {
+    use std::fs::File;
+    use std::os::fd::AsRawFd;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use atomic_refcell::AtomicRefCell;
+    use bitvec::vec::BitVec;
+    use criterion::{BatchSize, Criterion};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rayon::prelude::*;
+    use segment::fixtures::index_fixtures::random_vector;
+    use segment::index::hnsw_index::graph_layers::GraphLayers;
+    use segment::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
+    use segment::index::hnsw_index::graph_links::GraphLinksRam;
+    use segment::index::hnsw_index::point_scorer::FilteredScorer;
+    use segment::types::{Distance, PointOffsetType};
+    use segment::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
+    use segment::vector_storage::memmap_vector_storage::open_memmap_vector_storage_with_async_io;
+    use segment::vector_storage::{new_raw_scorer, VectorStorage, VectorStorageEnum};
+    use tempfile::Builder;
+
+    const NUM_VECTORS: usize = 50_000;
+    const DIM: usize = 512;
+    const M: usize = 16;
+    const TOP: usize = 10;
+    const EF_CONSTRUCT: usize = 100;
+    const EF: usize = 100;
+    const DISTANCE: Distance = Distance::Dot;
+
+    /// File name of vector data in [`segment::vector_storage::memmap_vector_storage::MemmapVectorStorage`]
+    const VECTORS_FILE: &str = "matrix.dat";
+
+    fn open_storage(path: &Path, async_io: bool) -> Arc<AtomicRefCell<VectorStorageEnum>> {
+        open_memmap_vector_storage_with_async_io(path, DIM, DISTANCE, async_io).unwrap()
+    }
+
+    /// Drop clean pages of the file from page cache, file must not be mapped at the moment
+    fn evict_from_page_cache(path: &Path) {
+        let file = File::open(path).unwrap();
+        let res = unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+        assert_eq!(res, 0, "posix_fadvise failed with {res}");
+    }
+
+    fn build_storage(path: &Path, rng: &mut StdRng) {
+        let source_dir = Builder::new().prefix("source_storage").tempdir().unwrap();
+        let source =
+            open_appendable_memmap_vector_storage(source_dir.path(), DIM, DISTANCE).unwrap();
+        {
+            let mut source = source.borrow_mut();
+            for idx in 0..NUM_VECTORS as PointOffsetType {
+                source.insert_vector(idx, &random_vector(rng, DIM)).unwrap();
+            }
+        }
+
+        let storage = open_storage(path, false);
+        storage
+            .borrow_mut()
+            .update_from(
+                &source.borrow(),
+                &mut (0..NUM_VECTORS as PointOffsetType),
+                &Default::default(),
+            )
+            .unwrap();
+        storage.borrow().flusher()().unwrap();
+    }
+
+    fn build_graph(path: &Path, deleted: &BitVec, rng: &mut StdRng) -> GraphLayers<GraphLinksRam> {
+        let storage = open_storage(path, false);
+        let storage = storage.borrow();
+
+        let mut graph_layers_builder =
+            GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, true, None);
+        for idx in 0..NUM_VECTORS as PointOffsetType {
+            let level = graph_layers_builder.get_random_layer(rng);
+            graph_layers_builder.set_levels(idx, level);
+        }
+        (0..NUM_VECTORS as PointOffsetType)
+            .into_par_iter()
+            .for_each(|idx| {
+                let raw_scorer =
+                    new_raw_scorer(storage.get_vector(idx).to_vec(), &storage, deleted);
+                let scorer = FilteredScorer::new(raw_scorer.as_ref(), None);
+                graph_layers_builder.link_new_point(idx, scorer);
+            });
+        graph_layers_builder.into_graph_layers(None).unwrap()
+    }
+
+    fn search(
+        graph: &GraphLayers<GraphLinksRam>,
+        storage: &VectorStorageEnum,
+        deleted: &BitVec,
+        query: Vec<f32>,
+    ) -> Vec<(PointOffsetType, u32)> {
+        let raw_scorer = new_raw_scorer(query, storage, deleted);
+        let scorer = FilteredScorer::new(raw_scorer.as_ref(), None);
+        graph
+            .search(TOP, EF, scorer)
+            .into_iter()
+            .map(|scored| (scored.idx, scored.score.to_bits()))
+            .collect()
+    }
+
+    pub fn hnsw_mmap_benchmark(c: &mut Criterion) {
+        let mut rng = StdRng::seed_from_u64(42);
+        let dir = Builder::new().prefix("mmap_storage").tempdir().unwrap();
+        let deleted = BitVec::repeat(false, NUM_VECTORS);
+
+        build_storage(dir.path(), &mut rng);
+        let graph = build_graph(dir.path(), &deleted, &mut rng);
+        let vectors_path = dir.path().join(VECTORS_FILE);
+
+        // Both paths must produce exactly the same results
+        for _ in 0..10 {
+            let query = random_vector(&mut rng, DIM);
+            let sync_storage = open_storage(dir.path(), false);
+            let async_storage = open_storage(dir.path(), true);
+            assert_eq!(
+                search(&graph, &sync_storage.borrow(), &deleted, query.clone()),
+                search(&graph, &async_storage.borrow(), &deleted, query),
+            );
+        }
+
+        let mut group = c.benchmark_group("hnsw-mmap-cold-search-group");
+        for (name, async_io) in [("sync", false), ("async", true)] {
+            group.bench_function(name, |b| {
+                b.iter_batched(
+                    || {
+                        // Storage of previous iteration is dropped already, so pages are not mapped
+                        evict_from_page_cache(&vectors_path);
+                        let storage = open_storage(dir.path(), async_io);
+                        (storage, random_vector(&mut rng, DIM))
+                    },
+                    |(storage, query)| {
+                        search(&graph, &storage.borrow(), &deleted, query);
+                        // Unmapping is not a part of the search
+                        storage
+                    },
+                    BatchSize::PerIteration,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+#[cfg(target_os = "linux")]
+criterion::criterion_group! {
+    name = benches;
+    config = criterion::Criterion::default().with_profiler(prof::FlamegraphProfiler::new(100));
+    targets = cold_search::hnsw_mmap_benchmark
+}
+
+#[cfg(target_os = "linux")]
+criterion::criterion_main!(benches);
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    println!("Page cache eviction is only implemented for Linux");
+}
@@ -0,0 +1,129 @@
+//! Peak memory of merging segments by the segment builder, for different merge buffer sizes.
+//!
+//! Not a criterion benchmark: peak RSS is not a timing, so each merge is run and measured once.
+//! Linux only, as RSS is read from `/proc/self/status`. Run with
+//! `cargo bench --bench segment_builder_merge`, the number of merged points is set by
+//! `MERGE_BENCH_POINTS` (1M by default).
+//!
+//! The unbounded buffer copies each source segment in a single batch, as the builder did before
+//! merges were split by the merge buffer.
+
+#[cfg(target_os = "linux")]
+fn main() {
+    linux::run();
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("segment_builder_merge benchmark is only supported on Linux");
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::sync::atomic::AtomicBool;
+    use std::time::Instant;
+
+    use itertools::Itertools;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+    use segment::entry::entry_point::SegmentEntry;
+    use segment::fixtures::index_fixtures::random_vector;
+    use segment::segment::Segment;
+    use segment::segment_constructor::segment_builder::SegmentBuilder;
+    use segment::segment_constructor::simple_segment_constructor::build_simple_segment;
+    use segment::types::{Distance, VectorStorageType};
+    use tempfile::Builder;
+
+    const DEFAULT_NUM_POINTS: usize = 1_000_000;
+    const DIM: usize = 128;
+    const NUM_SEGMENTS: usize = 4;
+
+    const MERGE_BUFFER_SIZES: [(&str, usize); 3] = [
+        ("1MB", 1024 * 1024),
+        ("64MB", 64 * 1024 * 1024),
+        ("unbounded", usize::MAX),
+    ];
+
+    /// Value of a memory field of `/proc/self/status`, in kilobytes
+    fn status_kb(field: &str) -> usize {
+        let status = std::fs::read_to_string("/proc/self/status").unwrap();
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(field)?.strip_prefix(':'))
+            .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+            .unwrap_or_else(|| panic!("{field} is not found in /proc/self/status"))
+    }
+
+    /// Reset the peak RSS of the process (`VmHWM`) to the current RSS
+    fn reset_peak_rss() {
+        std::fs::write("/proc/self/clear_refs", "5").unwrap();
+    }
+
+    fn source_segments(path: &std::path::Path, num_points: usize) -> Vec<Segment> {
+        let mut rnd = StdRng::seed_from_u64(42);
+        let points_per_segment = num_points.div_ceil(NUM_SEGMENTS);
+        (0..num_points)
+            .chunks(points_per_segment)
+            .into_iter()
+            .map(|ids| {
+                let mut segment = build_simple_segment(path, DIM, Distance::Dot).unwrap();
+                for id in ids {
+                    let vector = random_vector(&mut rnd, DIM);
+                    segment
+                        .upsert_point(id as u64, (id as u64).into(), only_default_vector(&vector))
+                        .unwrap();
+                }
+                segment
+            })
+            .collect()
+    }
+
+    pub fn run() {
+        let num_points = std::env::var("MERGE_BENCH_POINTS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_NUM_POINTS);
+        let stopped = AtomicBool::new(false);
+
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = source_segments(dir.path(), num_points);
+        let segment_refs = segments.iter().collect_vec();
+
+        let mut config = segments[0].segment_config.clone();
+        config
+            .vector_data
+            .get_mut(DEFAULT_VECTOR_NAME)
+            .unwrap()
+            .storage_type = VectorStorageType::Mmap;
+
+        println!(
+            "Merging {num_points} points of {DIM} dimensions from {} segments into mmap storage",
+            segments.len(),
+        );
+        for (name, merge_buffer_size) in MERGE_BUFFER_SIZES {
+            let merged_dir = Builder::new().prefix("merged_dir").tempdir().unwrap();
+            let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+            reset_peak_rss();
+            let rss_before = status_kb("VmRSS");
+            let started = Instant::now();
+
+            let mut builder =
+                SegmentBuilder::new(merged_dir.path(), temp_dir.path(), &config).unwrap();
+            builder.set_merge_buffer_size(merge_buffer_size);
+            assert!(builder.update(&segment_refs, &stopped).unwrap());
+            let merged = builder.build(&stopped).unwrap();
+
+            let elapsed = started.elapsed();
+            let peak_rss = status_kb("VmHWM");
+            assert_eq!(merged.available_point_count(), num_points);
+            drop(merged);
+
+            println!(
+                "merge buffer {name:>9}: peak additional RSS {:>6} MB, took {elapsed:.2?}",
+                peak_rss.saturating_sub(rss_before) / 1024,
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
@@ -8,15 +9,27 @@ use rand::Rng;
 use segment::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
 use segment::data_types::vectors::VectorElementType;
 use segment::fixtures::payload_context_fixture::FixtureIdTracker;
-use segment::id_tracker::IdTrackerSS;
-use segment::types::{Distance, PointOffsetType};
+use segment::id_tracker::{IdTracker, IdTrackerSS};
+use segment::types::{BinaryQuantizationConfig, Distance, PointOffsetType, QuantizationConfig};
+use segment::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
 use segment::vector_storage::simple_vector_storage::open_simple_vector_storage;
-use segment::vector_storage::{new_raw_scorer, VectorStorage, VectorStorageEnum};
+use segment::vector_storage::{
+    new_raw_scorer, peek_top_batch, ScoredPointOffset, VectorStorage, VectorStorageEnum,
+};
 use tempfile::Builder;
 
 const NUM_VECTORS: usize = 100000;
 const DIM: usize = 1024; // Larger dimensionality - greater the SIMD advantage
 
+const PEEK_TOP_NUM_VECTORS: usize = 1_000_000;
+const PEEK_TOP_DIM: usize = 128;
+const PEEK_TOP: usize = 10;
+
+const BATCH_SIZE: usize = 32;
+
+const BINARY_TOP: usize = 10;
+const BINARY_OVERSAMPLING: usize = 4;
+
 fn random_vector(size: usize) -> Vec<VectorElementType> {
     let rng = rand::thread_rng();
 
@@ -98,5 +111,227 @@ fn random_access_benchmark(c: &mut Criterion) {
     eprintln!("total_score = {:?}", total_score);
 }
 
-criterion_group!(benches, benchmark_naive, random_access_benchmark);
+fn peek_top_benchmark(c: &mut Criterion) {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let storage =
+        open_appendable_memmap_vector_storage(dir.path(), PEEK_TOP_DIM, Distance::Dot).unwrap();
+    let id_tracker = FixtureIdTracker::new(PEEK_TOP_NUM_VECTORS);
+    {
+        let mut borrowed_storage = storage.borrow_mut();
+        for i in 0..PEEK_TOP_NUM_VECTORS {
+            borrowed_storage
+                .insert_vector(i as PointOffsetType, &random_vector(PEEK_TOP_DIM))
+                .unwrap();
+        }
+    }
+    let borrowed_storage = storage.borrow();
+
+    // Every 10th point passes the filter
+    let filtered_points: Vec<PointOffsetType> = (0..PEEK_TOP_NUM_VECTORS as PointOffsetType)
+        .step_by(10)
+        .collect();
+
+    let mut group = c.benchmark_group("storage-peek-top-1m");
+
+    group.bench_function("naive score and sort", |b| {
+        b.iter(|| {
+            let scorer = new_raw_scorer(
+                random_vector(PEEK_TOP_DIM),
+                &borrowed_storage,
+                id_tracker.deleted_point_bitslice(),
+            );
+            let mut scores: Vec<_> = (0..PEEK_TOP_NUM_VECTORS as PointOffsetType)
+                .filter(|&point_id| scorer.check_vector(point_id))
+                .map(|point_id| ScoredPointOffset {
+                    idx: point_id,
+                    score: scorer.score_point(point_id),
+                })
+                .collect();
+            scores.sort_unstable_by(|a, b| b.cmp(a));
+            scores.truncate(PEEK_TOP);
+            scores
+        })
+    });
+
+    group.bench_function("peek top all", |b| {
+        b.iter(|| {
+            new_raw_scorer(
+                random_vector(PEEK_TOP_DIM),
+                &borrowed_storage,
+                id_tracker.deleted_point_bitslice(),
+            )
+            .peek_top_all(PEEK_TOP)
+        })
+    });
+
+    group.bench_function("peek top filtered", |b| {
+        b.iter(|| {
+            new_raw_scorer(
+                random_vector(PEEK_TOP_DIM),
+                &borrowed_storage,
+                id_tracker.deleted_point_bitslice(),
+            )
+            .peek_top_iter(&mut filtered_points.iter().copied(), PEEK_TOP)
+        })
+    });
+
+    group.finish();
+}
+
+fn peek_top_batch_benchmark(c: &mut Criterion) {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let storage =
+        open_appendable_memmap_vector_storage(dir.path(), PEEK_TOP_DIM, Distance::Dot).unwrap();
+    let id_tracker = FixtureIdTracker::new(PEEK_TOP_NUM_VECTORS);
+    {
+        let mut borrowed_storage = storage.borrow_mut();
+        for i in 0..PEEK_TOP_NUM_VECTORS {
+            borrowed_storage
+                .insert_vector(i as PointOffsetType, &random_vector(PEEK_TOP_DIM))
+                .unwrap();
+        }
+    }
+    let borrowed_storage = storage.borrow();
+    let is_stopped = AtomicBool::new(false);
+
+    let mut group = c.benchmark_group("storage-peek-top-batch-1m");
+
+    group.bench_function("sequential 32 queries", |b| {
+        b.iter(|| {
+            (0..BATCH_SIZE)
+                .map(|_| {
+                    new_raw_scorer(
+                        random_vector(PEEK_TOP_DIM),
+                        &borrowed_storage,
+                        id_tracker.deleted_point_bitslice(),
+                    )
+                    .peek_top_all(PEEK_TOP)
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("batch of 32 queries", |b| {
+        b.iter(|| {
+            let scorers: Vec<_> = (0..BATCH_SIZE)
+                .map(|_| {
+                    new_raw_scorer(
+                        random_vector(PEEK_TOP_DIM),
+                        &borrowed_storage,
+                        id_tracker.deleted_point_bitslice(),
+                    )
+                })
+                .collect();
+            peek_top_batch(
+                &scorers,
+                &mut (0..PEEK_TOP_NUM_VECTORS as PointOffsetType),
+                PEEK_TOP,
+                &is_stopped,
+            )
+        })
+    });
+
+    group.finish();
+}
+
+fn random_centered_vector(size: usize) -> Vec<VectorElementType> {
+    let mut rng = rand::thread_rng();
+
+    (0..size).map(|_| rng.gen_range(-1.0..1.0)).collect()
+}
+
+fn binary_quantization_benchmark(c: &mut Criterion) {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let quantized_dir = Builder::new().prefix("quantized_dir").tempdir().unwrap();
+
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage = open_simple_vector_storage(db, DB_VECTOR_CF, DIM, Distance::Cosine).unwrap();
+    let id_tracker = FixtureIdTracker::new(NUM_VECTORS);
+    {
+        let mut borrowed_storage = storage.borrow_mut();
+        for i in 0..NUM_VECTORS {
+            borrowed_storage
+                .insert_vector(i as PointOffsetType, &random_centered_vector(DIM))
+                .unwrap();
+        }
+        let quantization_config: QuantizationConfig = BinaryQuantizationConfig {
+            min_dim: None,
+            always_ram: Some(true),
+        }
+        .into();
+        borrowed_storage
+            .quantize(
+                quantized_dir.path(),
+                &quantization_config,
+                1,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+    }
+    let borrowed_storage = storage.borrow();
+    let quantized_storage = borrowed_storage.quantized_storage().unwrap();
+    let is_stopped = AtomicBool::new(false);
+
+    let mut group = c.benchmark_group("binary-quantization-search");
+
+    group.bench_function("f32 score all", |b| {
+        b.iter(|| {
+            new_raw_scorer(
+                random_centered_vector(DIM),
+                &borrowed_storage,
+                id_tracker.deleted_point_bitslice(),
+            )
+            .peek_top_all(BINARY_TOP)
+        })
+    });
+
+    group.bench_function("binary score all", |b| {
+        b.iter(|| {
+            quantized_storage
+                .raw_scorer(
+                    &random_centered_vector(DIM),
+                    id_tracker.deleted_point_bitslice(),
+                    borrowed_storage.deleted_vector_bitslice(),
+                    &is_stopped,
+                )
+                .peek_top_all(BINARY_TOP)
+        })
+    });
+
+    group.bench_function("binary score all with rescoring", |b| {
+        b.iter(|| {
+            let query = random_centered_vector(DIM);
+            let candidates: Vec<_> = quantized_storage
+                .raw_scorer(
+                    &query,
+                    id_tracker.deleted_point_bitslice(),
+                    borrowed_storage.deleted_vector_bitslice(),
+                    &is_stopped,
+                )
+                .peek_top_all(BINARY_TOP * BINARY_OVERSAMPLING)
+                .iter()
+                .map(|scored| scored.idx)
+                .collect();
+            new_raw_scorer(
+                query,
+                &borrowed_storage,
+                id_tracker.deleted_point_bitslice(),
+            )
+            .peek_top_iter(&mut candidates.into_iter(), BINARY_TOP)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_naive,
+    random_access_benchmark,
+    peek_top_benchmark,
+    peek_top_batch_benchmark,
+    binary_quantization_benchmark
+);
 criterion_main!(benches);
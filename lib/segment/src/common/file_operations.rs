@@ -29,6 +29,19 @@ pub fn read_bin<T: DeserializeOwned>(path: &Path) -> Result<T> {
     )?))?)
 }
 
+/// Total size of the files in bytes, files which don't exist are skipped
+pub fn files_size_bytes<P: AsRef<Path>>(files: impl IntoIterator<Item = P>) -> Result<usize> {
+    let mut size = 0;
+    for file in files {
+        match std::fs::metadata(file) {
+            Ok(metadata) => size += metadata.len() as usize,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(size)
+}
+
 pub type FileOperationResult<T> = Result<T>;
 pub type FileStorageError = Error;
 
@@ -6,8 +6,11 @@ pub mod file_operations;
 pub mod mmap_ops;
 pub mod mmap_type;
 pub mod operation_time_statistics;
+pub mod random_sample;
 pub mod rocksdb_buffered_delete_wrapper;
 pub mod rocksdb_wrapper;
+pub mod search_stats;
+pub mod snapshot_manifest;
 pub mod utils;
 pub mod vector_utils;
 pub mod version;
@@ -15,9 +18,10 @@ pub mod version;
 use std::sync::atomic::AtomicBool;
 
 use crate::data_types::named_vectors::NamedVectors;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::sparse_vector::SparseVector;
+use crate::data_types::vectors::{QueryVector, VectorElementType, DEFAULT_VECTOR_NAME};
 use crate::entry::entry_point::{OperationError, OperationResult};
-use crate::types::{SegmentConfig, VectorDataConfig};
+use crate::types::{PointIdType, SegmentConfig, VectorDataConfig};
 
 pub type Flusher = Box<dyn FnOnce() -> OperationResult<()> + Send>;
 
@@ -42,21 +46,38 @@ pub fn check_vector(
     Ok(())
 }
 
-/// Check that the given vector name and elements are compatible with the given segment config.
+/// Check that the given vector name and all vectors of the queries are compatible with the given
+/// segment config.
 ///
 /// Returns an error if incompatible.
-pub fn check_vectors(
+pub fn check_query_vectors(
     vector_name: &str,
-    vectors: &[&[VectorElementType]],
+    query_vectors: &[&QueryVector],
     segment_config: &SegmentConfig,
 ) -> OperationResult<()> {
     let vector_config = get_vector_config_or_error(vector_name, segment_config)?;
-    for vector in vectors {
+    for vector in query_vectors.iter().flat_map(|query| query.vectors()) {
         check_vector_against_config(vector, vector_config)?;
     }
     Ok(())
 }
 
+/// Check that the given sparse vector name is part of the segment config and the vector is valid.
+///
+/// Returns an error if incompatible.
+pub fn check_sparse_vector(
+    vector_name: &str,
+    vector: &SparseVector,
+    segment_config: &SegmentConfig,
+) -> OperationResult<()> {
+    if !segment_config.sparse_vector_data.contains_key(vector_name) {
+        return Err(OperationError::VectorNameNotExists {
+            received_name: vector_name.into(),
+        });
+    }
+    vector.validate()
+}
+
 /// Check that the given named vectors are compatible with the given segment config.
 ///
 /// Returns an error if incompatible.
@@ -70,6 +91,90 @@ pub fn check_named_vectors(
     Ok(())
 }
 
+/// Check that all components of the given point vectors are finite numbers.
+///
+/// NaN and infinite components break scoring, and as a result links of the HNSW graph.
+/// Returns an error describing the first invalid component.
+pub fn check_named_vectors_finite(
+    point_id: PointIdType,
+    vectors: &NamedVectors,
+) -> OperationResult<()> {
+    for (vector_name, vector_data) in vectors.iter() {
+        if let Some(position) = find_non_finite_element(vector_data) {
+            return Err(OperationError::WrongVectorElement {
+                description: format!(
+                    "point {point_id} has component {} at position {position}{}, \
+                     vector components must be finite numbers",
+                    vector_data[position],
+                    describe_vector_name(vector_name),
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Position of the first NaN or infinite component of the vector, if any.
+///
+/// Components are tested by their exponent bits in fixed size chunks without early exit,
+/// so the compiler vectorizes the check and valid vectors are scanned in a single pass.
+pub fn find_non_finite_element(vector: &[VectorElementType]) -> Option<usize> {
+    const CHUNK_SIZE: usize = 16;
+    const EXPONENT_MASK: u32 = 0x7F80_0000;
+
+    let is_non_finite =
+        |value: &VectorElementType| value.to_bits() & EXPONENT_MASK == EXPONENT_MASK;
+    vector
+        .chunks(CHUNK_SIZE)
+        .enumerate()
+        .find_map(|(chunk_index, chunk)| {
+            let has_non_finite = chunk
+                .iter()
+                .fold(false, |found, value| found | is_non_finite(value));
+            if !has_non_finite {
+                return None;
+            }
+            let position = chunk.iter().position(is_non_finite)?;
+            Some(chunk_index * CHUNK_SIZE + position)
+        })
+}
+
+/// Check that all components of the given point vectors can be stored with the configured datatype.
+///
+/// Returns an error describing the first invalid component.
+pub fn check_named_vectors_datatype(
+    point_id: PointIdType,
+    vectors: &NamedVectors,
+    segment_config: &SegmentConfig,
+) -> OperationResult<()> {
+    for (vector_name, vector_data) in vectors.iter() {
+        let datatype = get_vector_config_or_error(vector_name, segment_config)?.datatype();
+        let invalid_element = vector_data
+            .iter()
+            .enumerate()
+            .find(|(_, value)| !datatype.is_valid_element(**value));
+        if let Some((position, value)) = invalid_element {
+            return Err(OperationError::WrongVectorElement {
+                description: format!(
+                    "point {point_id} has component {value} at position {position}{}, \
+                     uint8 vectors accept only integers in range 0..=255",
+                    describe_vector_name(vector_name),
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Name of the vector for error messages, empty for the default vector
+fn describe_vector_name(vector_name: &str) -> String {
+    if vector_name == DEFAULT_VECTOR_NAME {
+        String::new()
+    } else {
+        format!(" of vector `{vector_name}`")
+    }
+}
+
 /// Get the vector config for the given name, or return a name error.
 ///
 /// Returns an error if incompatible.
@@ -92,8 +197,20 @@ fn check_vector_against_config(
     vector: &[VectorElementType],
     vector_config: &VectorDataConfig,
 ) -> OperationResult<()> {
-    // Check dimensionality
     let dim = vector_config.size;
+    // Multi-vectors consist of any positive number of concatenated sub-vectors
+    if vector_config.multivector_config.is_some() {
+        if vector.is_empty() || vector.len() % dim != 0 {
+            return Err(OperationError::WrongVectorElement {
+                description: format!(
+                    "multi-vector must consist of sub-vectors of dim {dim}, got {} components",
+                    vector.len(),
+                ),
+            });
+        }
+        return Ok(());
+    }
+    // Check dimensionality
     if vector.len() != dim {
         return Err(OperationError::WrongVector {
             expected_dim: dim,
@@ -111,3 +228,29 @@ pub fn check_stopped(is_stopped: &AtomicBool) -> OperationResult<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_non_finite_element() {
+        assert_eq!(find_non_finite_element(&[]), None);
+        assert_eq!(
+            find_non_finite_element(&[0.0, -1.0, f32::MAX, f32::MIN]),
+            None
+        );
+        // Subnormal numbers are finite
+        assert_eq!(find_non_finite_element(&[f32::MIN_POSITIVE / 2.0]), None);
+
+        for invalid in [f32::NAN, -f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            // Positions inside of the first chunk, at chunk boundaries and in the remainder
+            for position in [0, 3, 15, 16, 17, 40, 99] {
+                let mut vector = vec![1.0; 100];
+                vector[position] = invalid;
+                vector[99] = invalid;
+                assert_eq!(find_non_finite_element(&vector), Some(position));
+            }
+        }
+    }
+}
@@ -11,6 +11,24 @@ use crate::common::anonymize::Anonymize;
 const AVG_DATASET_LEN: usize = 128;
 const SLIDING_WINDOW_LEN: usize = 8;
 
+/// Upper bounds of the duration histogram buckets, in microseconds.
+///
+/// Covers both fast requests and long running operations, like building of an index.
+pub const DURATION_BUCKETS_MICROS: [f32; 12] = [
+    1_000.0,
+    5_000.0,
+    10_000.0,
+    50_000.0,
+    100_000.0,
+    500_000.0,
+    1_000_000.0,
+    5_000_000.0,
+    10_000_000.0,
+    60_000_000.0,
+    300_000_000.0,
+    1_800_000_000.0,
+];
+
 #[derive(Serialize, Deserialize, Clone, Default, Debug, JsonSchema)]
 pub struct OperationDurationStatistics {
     pub count: usize,
@@ -34,6 +52,15 @@ pub struct OperationDurationStatistics {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub last_responded: Option<DateTime<Utc>>,
+
+    /// Cumulative number of successful operations by upper bound of duration, see
+    /// [`DURATION_BUCKETS_MICROS`]. Only reported in metrics.
+    #[serde(skip)]
+    pub duration_micros_histogram: Vec<(f32, usize)>,
+
+    /// Total duration of successful operations. Only reported in metrics.
+    #[serde(skip)]
+    pub total_duration_micros: u64,
 }
 
 pub struct OperationDurationsAggregator {
@@ -45,6 +72,9 @@ pub struct OperationDurationsAggregator {
     min_value: Option<f32>,
     max_value: Option<f32>,
     last_response_date: Option<DateTime<Utc>>,
+    /// Number of successful operations in each bucket of [`DURATION_BUCKETS_MICROS`]
+    buckets: [usize; DURATION_BUCKETS_MICROS.len()],
+    total_duration_micros: u64,
 }
 
 pub struct ScopeDurationMeasurer {
@@ -59,6 +89,7 @@ impl Anonymize for OperationDurationStatistics {
             count: self.count.anonymize(),
             fail_count: self.fail_count.anonymize(),
             last_responded: self.last_responded.anonymize(),
+            duration_micros_histogram: self.duration_micros_histogram.clone(),
             ..*self
         }
     }
@@ -88,6 +119,11 @@ impl std::ops::Add for OperationDurationStatistics {
                 |a, b| a > b,
             ),
             last_responded: std::cmp::max(self.last_responded, other.last_responded),
+            duration_micros_histogram: Self::merged_histogram(
+                self.duration_micros_histogram,
+                other.duration_micros_histogram,
+            ),
+            total_duration_micros: self.total_duration_micros + other.total_duration_micros,
         }
     }
 }
@@ -116,6 +152,23 @@ impl OperationDurationStatistics {
         }
     }
 
+    fn merged_histogram(
+        histogram1: Vec<(f32, usize)>,
+        histogram2: Vec<(f32, usize)>,
+    ) -> Vec<(f32, usize)> {
+        if histogram1.is_empty() {
+            return histogram2;
+        }
+        if histogram2.is_empty() {
+            return histogram1;
+        }
+        histogram1
+            .into_iter()
+            .zip(histogram2)
+            .map(|((bound, count1), (_, count2))| (bound, count1 + count2))
+            .collect()
+    }
+
     fn compared_duration(
         duration1: Option<f32>,
         duration2: Option<f32>,
@@ -181,12 +234,20 @@ impl OperationDurationsAggregator {
             min_value: None,
             max_value: None,
             last_response_date: Some(Utc::now().round_subsecs(2)),
+            buckets: [0; DURATION_BUCKETS_MICROS.len()],
+            total_duration_micros: 0,
         }))
     }
 
     pub fn add_operation_result(&mut self, success: bool, duration: Duration) {
         if success {
-            let duration = duration.as_micros() as f32;
+            let duration_micros = duration.as_micros();
+            self.total_duration_micros += duration_micros as u64;
+            let duration = duration_micros as f32;
+            let bucket = DURATION_BUCKETS_MICROS.partition_point(|&bound| bound < duration);
+            if let Some(bucket) = self.buckets.get_mut(bucket) {
+                *bucket += 1;
+            }
             self.min_value = Some(match self.min_value {
                 Some(min_value) => min_value.min(duration),
                 None => duration,
@@ -222,6 +283,15 @@ impl OperationDurationsAggregator {
             min_duration_micros: self.min_value,
             max_duration_micros: self.max_value,
             last_responded: self.last_response_date,
+            duration_micros_histogram: DURATION_BUCKETS_MICROS
+                .iter()
+                .zip(&self.buckets)
+                .scan(0, |cumulative, (&bound, &count)| {
+                    *cumulative += count;
+                    Some((bound, *cumulative))
+                })
+                .collect(),
+            total_duration_micros: self.total_duration_micros,
         }
     }
 
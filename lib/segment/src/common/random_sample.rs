@@ -0,0 +1,79 @@
+use rand::{Rng, RngCore};
+
+use crate::types::ScoreType;
+
+/// Scores of `count` points, sampled uniformly at random out of `population` points.
+///
+/// Scores are the largest `count` of `population` independent uniform random keys, in descending
+/// order, generated without drawing keys of the other points. As keys of every point are equally
+/// distributed, the best scores of several samples are a uniform sample of all their populations,
+/// where each sample contributes proportionally to its population size.
+///
+/// Keys are returned as logarithms, which keeps them distinct for large populations.
+pub fn random_sample_scores(
+    count: usize,
+    population: usize,
+    rng: &mut dyn RngCore,
+) -> Vec<ScoreType> {
+    debug_assert!(count <= population);
+    // The largest of `n` uniform keys is distributed as `U^(1/n)`, and all the smaller keys are
+    // uniform keys below it. So the next key is the largest of `n - 1` keys scaled by the previous
+    let mut log_key = 0.0f64;
+    (0..count)
+        .map(|sampled| {
+            let remaining = (population - sampled) as f64;
+            // Uniform in `(0, 1]`, so the logarithm is finite
+            let uniform = 1.0 - rng.gen::<f64>();
+            log_key += uniform.ln() / remaining;
+            log_key as ScoreType
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_scores_descending() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let scores = random_sample_scores(100, 1_000_000, &mut rng);
+        assert_eq!(scores.len(), 100);
+        assert!(scores.iter().all(|score| *score <= 0.0));
+        assert!(scores.iter().tuple_windows().all(|(a, b)| a >= b));
+
+        // Whole population may be sampled
+        let scores = random_sample_scores(10, 10, &mut rng);
+        assert_eq!(scores.len(), 10);
+        assert!(scores.iter().all(|score| score.is_finite()));
+    }
+
+    #[test]
+    fn test_merged_samples_proportional_to_population() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let attempts = 1_000;
+        let count = 10;
+
+        // Merge top scores of a small and a three times larger population
+        let mut small_sampled = 0;
+        for _ in 0..attempts {
+            let small = random_sample_scores(count, 1_000, &mut rng);
+            let large = random_sample_scores(count, 3_000, &mut rng);
+            small_sampled += small
+                .iter()
+                .map(|score| (*score, true))
+                .chain(large.iter().map(|score| (*score, false)))
+                .sorted_by(|a, b| b.0.total_cmp(&a.0))
+                .take(count)
+                .filter(|(_, is_small)| *is_small)
+                .count();
+        }
+
+        let share = small_sampled as f64 / (attempts * count) as f64;
+        assert!((share - 0.25).abs() < 0.02, "{share}");
+    }
+}
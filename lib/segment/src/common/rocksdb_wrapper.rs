@@ -14,10 +14,14 @@ const DB_MAX_LOG_SIZE: usize = 1024 * 1024; // 1 mb
 const DB_MAX_OPEN_FILES: usize = 256;
 
 pub const DB_VECTOR_CF: &str = "vector";
+pub const DB_SPARSE_VECTOR_CF: &str = "sparse_vector";
 pub const DB_PAYLOAD_CF: &str = "payload";
 pub const DB_MAPPING_CF: &str = "mapping";
 pub const DB_VERSIONS_CF: &str = "version";
 
+const DB_SST_FILES_SIZE_PROPERTY: &str = "rocksdb.total-sst-files-size";
+const DB_MEMTABLES_SIZE_PROPERTY: &str = "rocksdb.cur-size-all-mem-tables";
+
 #[derive(Clone)]
 pub struct DatabaseColumnWrapper {
     pub database: Arc<RwLock<DB>>,
@@ -211,6 +215,27 @@ impl DatabaseColumnWrapper {
         Ok(db.cf_handle(&self.column_name).is_some())
     }
 
+    /// Size of the column data in SST files and memtables, in bytes.
+    /// Zero if the column family doesn't exist.
+    pub fn size_bytes(&self) -> OperationResult<usize> {
+        let db = self.database.read();
+        let Some(cf_handle) = db.cf_handle(&self.column_name) else {
+            return Ok(0);
+        };
+        let mut size = 0;
+        for property in [DB_SST_FILES_SIZE_PROPERTY, DB_MEMTABLES_SIZE_PROPERTY] {
+            let value = db
+                .property_int_value_cf(cf_handle, property)
+                .map_err(|err| {
+                    OperationError::service_error(format!(
+                        "RocksDB property_int_value_cf error: {err}"
+                    ))
+                })?;
+            size += value.unwrap_or(0) as usize;
+        }
+        Ok(size)
+    }
+
     fn get_write_options() -> WriteOptions {
         let mut write_options = WriteOptions::default();
         write_options.set_sync(false);
@@ -235,6 +260,13 @@ impl<'a> LockedDatabaseColumnWrapper<'a> {
     pub fn iter(&self) -> OperationResult<DatabaseColumnIterator> {
         DatabaseColumnIterator::new(&self.guard, self.column_name)
     }
+
+    /// Iterate over records, starting from the first key which is not less than `key`
+    pub fn iter_from(&self, key: &[u8]) -> OperationResult<DatabaseColumnIterator> {
+        let mut iter = DatabaseColumnIterator::new(&self.guard, self.column_name)?;
+        iter.iter.seek(key);
+        Ok(iter)
+    }
 }
 
 impl<'a> DatabaseColumnIterator<'a> {
@@ -0,0 +1,122 @@
+//! Counters of the work done by searches.
+//!
+//! Scorers and graph traversals count their work in plain fields and report the totals, once
+//! done, to the collector of the current thread. Reports are dropped, if nothing collects them.
+
+use std::cell::Cell;
+use std::ops::{Add, AddAssign};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::anonymize::Anonymize;
+
+thread_local! {
+    static COLLECTED: Cell<Option<SearchStats>> = const { Cell::new(None) };
+}
+
+/// Work done by a search
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SearchStats {
+    /// Number of HNSW graph points, which links were followed
+    pub graph_hops: usize,
+    /// Number of vectors scored against the query
+    pub points_scored: usize,
+    /// Number of points checked against the filter while scoring
+    pub filter_checks: usize,
+}
+
+impl SearchStats {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Run `f`, collecting the stats reported by it on this thread.
+    ///
+    /// Collected stats are reported to the enclosing collector too, if any.
+    pub fn collect<T>(f: impl FnOnce() -> T) -> (T, SearchStats) {
+        /// Restores the enclosing collector, even if `f` panics
+        struct Restore(Option<SearchStats>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                COLLECTED.with(|collected| collected.set(self.0));
+            }
+        }
+
+        let enclosing = COLLECTED.with(|collected| collected.replace(Some(Self::default())));
+        let restore = Restore(enclosing);
+        let output = f();
+        let stats = COLLECTED.with(Cell::get).unwrap_or_default();
+        drop(restore);
+
+        stats.report();
+        (output, stats)
+    }
+
+    /// Add the stats to the collector of this thread, if any
+    pub fn report(self) {
+        if self.is_empty() {
+            return;
+        }
+        COLLECTED.with(|collected| {
+            if let Some(stats) = collected.get() {
+                collected.set(Some(stats + self));
+            }
+        });
+    }
+}
+
+impl Add for SearchStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            graph_hops: self.graph_hops + other.graph_hops,
+            points_scored: self.points_scored + other.points_scored,
+            filter_checks: self.filter_checks + other.filter_checks,
+        }
+    }
+}
+
+impl AddAssign for SearchStats {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Anonymize for SearchStats {
+    fn anonymize(&self) -> Self {
+        Self {
+            graph_hops: self.graph_hops.anonymize(),
+            points_scored: self.points_scored.anonymize(),
+            filter_checks: self.filter_checks.anonymize(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_nested() {
+        let stats = |points_scored| SearchStats {
+            points_scored,
+            ..Default::default()
+        };
+
+        // Not collected
+        stats(1).report();
+
+        let ((_, inner), outer) = SearchStats::collect(|| {
+            stats(2).report();
+            SearchStats::collect(|| stats(3).report())
+        });
+        assert_eq!(inner, stats(3));
+        assert_eq!(outer, stats(5));
+
+        let (_, empty) = SearchStats::collect(|| ());
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use seahash::SeaHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::entry::entry_point::{OperationError, OperationResult};
+
+/// Size of the chunks files are hashed by
+const CHECKSUM_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Size and checksum of an archived file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileManifest {
+    pub size: u64,
+    /// Hex encoded seahash of the file contents
+    pub checksum: String,
+}
+
+impl FileManifest {
+    pub fn of_file(path: &Path) -> OperationResult<Self> {
+        let mut file = File::open(path).map_err(|err| {
+            OperationError::service_error(format!("failed to open {path:?}: {err}"))
+        })?;
+
+        let mut hasher = SeaHasher::new();
+        let mut buffer = vec![0; CHECKSUM_CHUNK_SIZE];
+        let mut size = 0;
+        loop {
+            let chunk_len = read_chunk(&mut file, &mut buffer).map_err(|err| {
+                OperationError::service_error(format!("failed to read {path:?}: {err}"))
+            })?;
+            if chunk_len == 0 {
+                break;
+            }
+            hasher.write(&buffer[..chunk_len]);
+            size += chunk_len as u64;
+        }
+
+        Ok(FileManifest {
+            size,
+            checksum: format!("{:016x}", hasher.finish()),
+        })
+    }
+}
+
+/// Fill the whole `buffer`, unless the end of the file is reached.
+/// Files are hashed by equal chunks, however short reads of the file are.
+fn read_chunk(file: &mut File, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(len) => filled += len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+/// List of files of a snapshot archive, to validate them before the snapshot is restored
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Archived files by their paths in the archive
+    pub files: BTreeMap<PathBuf, FileManifest>,
+}
+
+impl SnapshotManifest {
+    /// Add `file`, which is archived at `archive_path`
+    pub fn add_file(&mut self, file: &Path, archive_path: &Path) -> OperationResult<()> {
+        let manifest = FileManifest::of_file(file)?;
+        self.files.insert(archive_path.to_path_buf(), manifest);
+        Ok(())
+    }
+
+    /// Add all files of `dir` recursively, which is archived at `archive_dir`
+    pub fn add_dir(&mut self, dir: &Path, archive_dir: &Path) -> OperationResult<()> {
+        let entries = fs::read_dir(dir).map_err(|err| {
+            OperationError::service_error(format!("failed to read directory {dir:?}: {err}"))
+        })?;
+        for entry in entries {
+            let entry = entry?;
+            let archive_path = archive_dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                self.add_dir(&entry.path(), &archive_path)?;
+            } else {
+                self.add_file(&entry.path(), &archive_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every listed file is unpacked into `base` with the same size and contents
+    pub fn validate(&self, base: &Path) -> OperationResult<()> {
+        for (archive_path, expected) in &self.files {
+            let path = base.join(archive_path);
+            if !path.is_file() {
+                return Err(OperationError::service_error(format!(
+                    "Snapshot is corrupted, file {archive_path:?} is missing"
+                )));
+            }
+            let actual = FileManifest::of_file(&path)?;
+            if actual.size != expected.size {
+                return Err(OperationError::service_error(format!(
+                    "Snapshot is corrupted, file {archive_path:?} has size {}, expected {}",
+                    actual.size, expected.size,
+                )));
+            }
+            if actual.checksum != expected.checksum {
+                return Err(OperationError::service_error(format!(
+                    "Snapshot is corrupted, file {archive_path:?} has checksum {}, expected {}",
+                    actual.checksum, expected.checksum,
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_manifest() {
+        let dir = Builder::new().prefix("manifest_dir").tempdir().unwrap();
+        let nested_dir = dir.path().join("nested");
+        fs::create_dir(&nested_dir).unwrap();
+        fs::write(dir.path().join("a"), b"first file").unwrap();
+        // Larger than a checksum chunk
+        fs::write(nested_dir.join("b"), vec![7; CHECKSUM_CHUNK_SIZE + 10]).unwrap();
+
+        let mut manifest = SnapshotManifest::default();
+        manifest.add_dir(dir.path(), Path::new("")).unwrap();
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            vec![Path::new("a"), Path::new("nested/b")],
+        );
+        assert_eq!(
+            manifest.files[Path::new("nested/b")].size,
+            CHECKSUM_CHUNK_SIZE as u64 + 10,
+        );
+        manifest.validate(dir.path()).unwrap();
+
+        // Same size, different contents
+        fs::write(dir.path().join("a"), b"other file").unwrap();
+        assert!(manifest.validate(dir.path()).is_err());
+        fs::write(dir.path().join("a"), b"first file").unwrap();
+        manifest.validate(dir.path()).unwrap();
+
+        fs::remove_file(nested_dir.join("b")).unwrap();
+        assert!(manifest.validate(dir.path()).is_err());
+    }
+}
@@ -67,7 +67,22 @@ impl<T> MultiValue<T> {
     }
 }
 
-impl MultiValue<&Value> {
+impl<'a> MultiValue<&'a Value> {
+    /// All values, with arrays replaced by their elements
+    pub(crate) fn flatten(&self) -> Vec<&'a Value> {
+        let values = match self {
+            Self::Single(value) => value.as_slice(),
+            Self::Multiple(values) => values.as_slice(),
+        };
+        values
+            .iter()
+            .flat_map(|value| match value {
+                Value::Array(values) => values.iter().collect(),
+                value => vec![*value],
+            })
+            .collect()
+    }
+
     pub(crate) fn check_is_empty(&self) -> bool {
         match self {
             Self::Multiple(vec) => vec.iter().all(|x| match x {
@@ -316,6 +331,167 @@ pub fn remove_value_from_json_map(
     }
 }
 
+/// Merge `src` map into `dest` map, `null` values remove the keys
+pub fn merge_json_map(
+    dest: &mut serde_json::Map<String, Value>,
+    src: &serde_json::Map<String, Value>,
+) {
+    for (key, value) in src {
+        match value {
+            Value::Null => dest.remove(key),
+            _ => dest.insert(key.to_owned(), value.to_owned()),
+        };
+    }
+}
+
+/// Merge `src` map into the object at the given path of `dest`
+///
+/// Missing objects on the path are created, and values on the path, which are not objects,
+/// are replaced with objects. Siblings of the path are left intact.
+/// Array path applies to the existing elements only, e.g.
+///
+/// path: "a.b"      => src is merged into `dest["a"]["b"]`
+/// path: "arr[].a"  => src is merged into `a` of every element of `arr`
+/// path: "arr[1].a" => src is merged into `a` of the second element of `arr`
+///
+pub fn set_value_to_json_map(
+    path: &str,
+    dest: &mut serde_json::Map<String, Value>,
+    src: &serde_json::Map<String, Value>,
+) {
+    let (element, rest_path) = match path.split_once('.') {
+        Some((element, rest_path)) => (element, Some(rest_path)),
+        None => (path, None),
+    };
+    match parse_array_path(element) {
+        Some((array_element_path, array_index)) => {
+            if let Some(Value::Array(array)) = dest.get_mut(array_element_path) {
+                for (i, value) in array.iter_mut().enumerate() {
+                    if array_index.map_or(true, |array_index| i == array_index as usize) {
+                        set_value_to_json_value(rest_path, value, src);
+                    }
+                }
+            }
+        }
+        None => {
+            let value = dest
+                .entry(element)
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            set_value_to_json_value(rest_path, value, src);
+        }
+    }
+}
+
+fn set_value_to_json_value(
+    path: Option<&str>,
+    value: &mut Value,
+    src: &serde_json::Map<String, Value>,
+) {
+    if !value.is_object() {
+        *value = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(map) = value {
+        match path {
+            Some(path) => set_value_to_json_map(path, map, src),
+            None => merge_json_map(map, src),
+        }
+    }
+}
+
+/// Split path into the head key, array selector of the head key and the rest of the path
+///
+/// path: "a.b"      => ("a", None, Some("b"))
+/// path: "arr[].a"  => ("arr", Some(None), Some("a"))
+/// path: "arr[1]"   => ("arr", Some(Some(1)), None)
+///
+fn split_path_head(path: &str) -> (&str, Option<Option<u32>>, Option<&str>) {
+    let (element, rest_path) = match path.split_once('.') {
+        Some((element, rest_path)) => (element, Some(rest_path)),
+        None => (path, None),
+    };
+    match parse_array_path(element) {
+        Some((array_element_path, array_index)) => {
+            (array_element_path, Some(array_index), rest_path)
+        }
+        None => (element, None, rest_path),
+    }
+}
+
+/// Top-level key of the path
+///
+/// path: "a.b"     => "a"
+/// path: "arr[].a" => "arr"
+pub fn path_head(path: &str) -> &str {
+    split_path_head(path).0
+}
+
+/// Keep only values at the given paths in the map and drop everything else
+///
+/// Objects on the path are kept with the selected keys only, objects and arrays without selected
+/// values are dropped. Array path selects values in every element of the array, or in the
+/// element with the given index only.
+///
+/// paths: ["a.b"]     => {"a": {"b": ..}}
+/// paths: ["arr[].a"] => {"arr": [{"a": ..}, {"a": ..}]}
+/// paths: ["arr[1]"]  => {"arr": [<second element of arr>]}
+///
+pub fn retain_json_map_paths(paths: &[&str], map: &mut serde_json::Map<String, Value>) {
+    map.retain(|key, value| {
+        let mut sub_paths = Vec::new();
+        for path in paths {
+            match split_path_head(path) {
+                (head, _, _) if head != key => {}
+                // the whole value is selected
+                (_, None | Some(None), None) => return true,
+                (_, array_index, rest_path) => sub_paths.push((array_index, rest_path)),
+            }
+        }
+        if sub_paths.is_empty() {
+            return false;
+        }
+        match value {
+            Value::Object(inner) => {
+                let rest_paths: Vec<_> = sub_paths
+                    .iter()
+                    .filter(|(array_index, _)| array_index.is_none())
+                    .filter_map(|(_, rest_path)| *rest_path)
+                    .collect();
+                retain_json_map_paths(&rest_paths, inner);
+                !inner.is_empty()
+            }
+            Value::Array(array) => {
+                let mut i = 0;
+                array.retain_mut(|element| {
+                    let element_paths: Vec<_> = sub_paths
+                        .iter()
+                        .filter(|(array_index, _)| match array_index {
+                            Some(Some(index)) => *index as usize == i,
+                            Some(None) => true,
+                            None => false,
+                        })
+                        .map(|(_, rest_path)| *rest_path)
+                        .collect();
+                    i += 1;
+                    if element_paths.iter().any(Option::is_none) {
+                        // the whole element is selected
+                        return true;
+                    }
+                    let rest_paths: Vec<_> = element_paths.into_iter().flatten().collect();
+                    match element {
+                        Value::Object(inner) => {
+                            retain_json_map_paths(&rest_paths, inner);
+                            !inner.is_empty()
+                        }
+                        _ => false,
+                    }
+                });
+                !array.is_empty()
+            }
+            _ => false,
+        }
+    });
+}
+
 pub fn transpose_map_into_named_vector(
     map: HashMap<String, Vec<Vec<VectorElementType>>>,
 ) -> Vec<NamedVectors<'static>> {
@@ -626,6 +802,111 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_set_value_to_json_map() {
+        let mut map = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "a": { "b": 1, "c": 2 },
+                "scalar": 3,
+                "arr": [
+                    { "a": 1 },
+                    { "a": { "b": 2 } },
+                    4
+                ]
+            }
+            "#,
+        )
+        .unwrap();
+        let src = |value: Value| value.as_object().unwrap().clone();
+
+        // siblings are intact
+        set_value_to_json_map("a", &mut map, &src(serde_json::json!({ "b": 5 })));
+        assert_eq!(map["a"], serde_json::json!({ "b": 5, "c": 2 }));
+
+        // nested objects are created
+        set_value_to_json_map("x.y.z", &mut map, &src(serde_json::json!({ "v": 1 })));
+        assert_eq!(map["x"], serde_json::json!({ "y": { "z": { "v": 1 } } }));
+
+        // scalar is overwritten with an object
+        set_value_to_json_map("scalar.b", &mut map, &src(serde_json::json!({ "v": 1 })));
+        assert_eq!(map["scalar"], serde_json::json!({ "b": { "v": 1 } }));
+
+        // every element of the array
+        set_value_to_json_map("arr[].a", &mut map, &src(serde_json::json!({ "c": 3 })));
+        assert_eq!(
+            map["arr"],
+            serde_json::json!([
+                { "a": { "c": 3 } },
+                { "a": { "b": 2, "c": 3 } },
+                4
+            ])
+        );
+
+        // element of the array by index, missing arrays are not created
+        set_value_to_json_map("arr[0]", &mut map, &src(serde_json::json!({ "d": 4 })));
+        set_value_to_json_map("missing[].a", &mut map, &src(serde_json::json!({ "d": 4 })));
+        assert_eq!(
+            map["arr"][0],
+            serde_json::json!({ "a": { "c": 3 }, "d": 4 })
+        );
+        assert_eq!(
+            map["arr"][1],
+            serde_json::json!({ "a": { "b": 2, "c": 3 } })
+        );
+        assert!(!map.contains_key("missing"));
+    }
+
+    #[test]
+    fn test_retain_json_map_paths() {
+        let map = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "a": { "b": 1, "c": { "d": 2, "e": 3 } },
+                "scalar": 4,
+                "arr": [
+                    { "a": 1, "b": 2 },
+                    { "a": 3 },
+                    5
+                ]
+            }
+            "#,
+        )
+        .unwrap();
+        let retain = |paths: &[&str]| {
+            let mut map = map.clone();
+            retain_json_map_paths(paths, &mut map);
+            Value::Object(map)
+        };
+
+        assert_eq!(
+            retain(&["a.c.d", "scalar"]),
+            serde_json::json!({ "a": { "c": { "d": 2 } }, "scalar": 4 }),
+        );
+        assert_eq!(
+            retain(&["a.c", "a.c.d"]),
+            serde_json::json!({ "a": { "c": { "d": 2, "e": 3 } } }),
+        );
+
+        // paths through scalars and missing keys select nothing
+        assert_eq!(retain(&["scalar.x", "missing"]), serde_json::json!({}));
+
+        // elements without selected values are dropped
+        assert_eq!(
+            retain(&["arr[].b"]),
+            serde_json::json!({ "arr": [{ "b": 2 }] }),
+        );
+        assert_eq!(
+            retain(&["arr[].a"]),
+            serde_json::json!({ "arr": [{ "a": 1 }, { "a": 3 }] }),
+        );
+        assert_eq!(
+            retain(&["arr[2]", "arr[0].b"]),
+            serde_json::json!({ "arr": [{ "b": 2 }, 5] }),
+        );
+        assert_eq!(retain(&["arr[]"]), serde_json::json!({ "arr": map["arr"] }));
+    }
 }
 
 pub type IndexesMap = HashMap<PayloadKeyType, Vec<FieldIndex>>;
@@ -52,6 +52,9 @@ impl From<SegmentConfigV5> for SegmentConfig {
                     storage_type: (old_data.on_disk == Some(true))
                         .then_some(VectorStorageType::Mmap)
                         .unwrap_or_else(|| old_segment.storage_type.into()),
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 };
 
                 (vector_name, new_data)
@@ -61,6 +64,7 @@ impl From<SegmentConfigV5> for SegmentConfig {
         SegmentConfig {
             vector_data,
             payload_storage_type: old_segment.payload_storage_type,
+            sparse_vector_data: Default::default(),
         }
     }
 }
@@ -291,7 +295,7 @@ mod tests {
                 QuantizationConfig::Scalar(scalar) => {
                     assert_eq!(scalar.scalar.quantile, Some(0.99));
                 }
-                QuantizationConfig::Product(_) => {
+                QuantizationConfig::Product(_) | QuantizationConfig::Binary(_) => {
                     panic!("expected scalar quantization")
                 }
             },
@@ -0,0 +1,125 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Condition, FieldCondition, Filter, PayloadKeyType};
+
+/// Parameters to count points per value of a keyword-indexed payload field
+#[derive(Debug, Clone, PartialEq)]
+pub struct FacetParams {
+    pub key: PayloadKeyType,
+    pub filter: Option<Filter>,
+}
+
+/// Value of the payload field points are counted by
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(untagged)]
+pub enum FacetValue {
+    Keyword(String),
+}
+
+impl FacetValue {
+    /// Condition, which matches points having this value in the `key` field
+    pub fn match_condition(&self, key: PayloadKeyType) -> Condition {
+        match self {
+            FacetValue::Keyword(keyword) => {
+                Condition::Field(FieldCondition::new_match(key, keyword.clone().into()))
+            }
+        }
+    }
+}
+
+/// Value of the payload field and the number of points which have it
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct FacetValueHit {
+    pub value: FacetValue,
+    pub count: usize,
+}
+
+impl Ord for FacetValueHit {
+    /// Hits with higher counts go first, ties are ordered by value
+    fn cmp(&self, other: &Self) -> Ordering {
+        (Reverse(self.count), &self.value).cmp(&(Reverse(other.count), &other.value))
+    }
+}
+
+impl PartialOrd for FacetValueHit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Sum up counts of the same values, e.g. collected from several segments or shards
+pub fn merge_facet_counts(
+    counts: impl IntoIterator<Item = HashMap<FacetValue, usize>>,
+) -> HashMap<FacetValue, usize> {
+    let mut merged: HashMap<FacetValue, usize> = HashMap::new();
+    for (value, count) in counts.into_iter().flatten() {
+        *merged.entry(value).or_default() += count;
+    }
+    merged
+}
+
+/// Select up to `limit` values with the highest counts, skipping values without points
+pub fn top_facet_hits(
+    counts: impl IntoIterator<Item = (FacetValue, usize)>,
+    limit: usize,
+) -> Vec<FacetValueHit> {
+    let mut hits: Vec<_> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(value, count)| FacetValueHit { value, count })
+        .collect();
+    hits.sort_unstable();
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyword(value: &str) -> FacetValue {
+        FacetValue::Keyword(value.to_string())
+    }
+
+    #[test]
+    fn test_top_facet_hits() {
+        let counts = merge_facet_counts([
+            HashMap::from([(keyword("red"), 3), (keyword("green"), 1)]),
+            HashMap::from([(keyword("green"), 2), (keyword("blue"), 3)]),
+            HashMap::from([(keyword("white"), 0)]),
+        ]);
+
+        assert_eq!(
+            top_facet_hits(counts.clone(), 10),
+            vec![
+                FacetValueHit {
+                    value: keyword("blue"),
+                    count: 3,
+                },
+                FacetValueHit {
+                    value: keyword("green"),
+                    count: 3,
+                },
+                FacetValueHit {
+                    value: keyword("red"),
+                    count: 3,
+                },
+            ],
+        );
+
+        assert_eq!(
+            top_facet_hits(counts, 1),
+            vec![FacetValueHit {
+                value: keyword("blue"),
+                count: 3,
+            }],
+        );
+    }
+}
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 /// Value of the group_by key, shared across all the hits in the group
-#[derive(Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Clone, Hash)]
+#[derive(
+    Debug, Serialize, Deserialize, JsonSchema, Eq, PartialEq, Ord, PartialOrd, Clone, Hash,
+)]
 #[serde(untagged)]
 pub enum GroupId {
     String(String),
@@ -1,5 +1,12 @@
+pub mod facets;
 pub mod groups;
+pub mod keyword_index;
 pub mod named_vectors;
+pub mod order_by;
+pub mod primitive;
+pub mod search_plan;
+pub mod sparse_vector;
 pub mod text_index;
 pub mod tiny_map;
+pub mod uuid_index;
 pub mod vectors;
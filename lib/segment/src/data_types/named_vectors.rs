@@ -3,7 +3,7 @@ use std::collections::HashMap;
 
 use super::tiny_map;
 use super::vectors::{VectorElementType, DEFAULT_VECTOR_NAME};
-use crate::types::Distance;
+use crate::types::VectorDataConfig;
 
 type CowKey<'a> = Cow<'a, str>;
 type CowValue<'a> = Cow<'a, [VectorElementType]>;
@@ -95,13 +95,13 @@ impl<'a> NamedVectors<'a> {
         self.map.get(key).map(|v| v.as_ref())
     }
 
-    pub fn preprocess<F>(&mut self, distance_map: F)
+    pub fn preprocess<'b, F>(&mut self, config_map: F)
     where
-        F: Fn(&str) -> Distance,
+        F: Fn(&str) -> &'b VectorDataConfig,
     {
         for (name, vector) in self.map.iter_mut() {
-            let distance = distance_map(name);
-            if let Some(preprocessed_vector) = distance.preprocess_vector(vector) {
+            let config = config_map(name);
+            if let Some(preprocessed_vector) = config.preprocess_vector(vector) {
                 *vector = CowValue::Owned(preprocessed_vector);
             }
         }
@@ -0,0 +1,123 @@
+use std::cmp::Ordering;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::types::{FloatPayloadType, IntPayloadType, PayloadKeyType};
+
+/// Direction to order points by a payload field
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Lowest values first
+    #[default]
+    Asc,
+    /// Highest values first
+    Desc,
+}
+
+impl Direction {
+    /// Compare two items according to this direction, so the first one in order is `Less`
+    pub fn compare<T: Ord>(self, a: &T, b: &T) -> Ordering {
+        match self {
+            Direction::Asc => a.cmp(b),
+            Direction::Desc => b.cmp(a),
+        }
+    }
+}
+
+/// Value of the payload field points are ordered by
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy)]
+#[serde(untagged)]
+pub enum OrderValue {
+    Int(IntPayloadType),
+    Float(FloatPayloadType),
+}
+
+impl OrderValue {
+    pub fn as_f64(self) -> FloatPayloadType {
+        match self {
+            OrderValue::Int(value) => value as FloatPayloadType,
+            OrderValue::Float(value) => value,
+        }
+    }
+}
+
+impl From<IntPayloadType> for OrderValue {
+    fn from(value: IntPayloadType) -> Self {
+        OrderValue::Int(value)
+    }
+}
+
+impl From<FloatPayloadType> for OrderValue {
+    fn from(value: FloatPayloadType) -> Self {
+        OrderValue::Float(value)
+    }
+}
+
+impl Ord for OrderValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (OrderValue::Int(a), OrderValue::Int(b)) => a.cmp(b),
+            _ => self.as_f64().total_cmp(&other.as_f64()),
+        }
+    }
+}
+
+impl PartialOrd for OrderValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OrderValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrderValue {}
+
+/// Order points by a payload field, instead of by their ids
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct OrderBy {
+    /// Payload key to order by. Requires an integer, float or datetime payload index. Datetimes
+    /// are ordered by their timestamps in microseconds
+    pub key: PayloadKeyType,
+    /// Direction of ordering. Default: asc
+    pub direction: Option<Direction>,
+    /// Start with this value including. Points with the same value are ordered by id, use the
+    /// scroll `offset` to choose the first of them
+    pub start_from: Option<OrderValue>,
+}
+
+impl OrderBy {
+    pub fn direction(&self) -> Direction {
+        self.direction.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_value_cmp() {
+        assert!(OrderValue::Int(1) < OrderValue::Int(2));
+        assert!(OrderValue::Int(1) < OrderValue::Float(1.5));
+        assert_eq!(OrderValue::Int(2), OrderValue::Float(2.0));
+        assert!(OrderValue::Float(-0.5) < OrderValue::Int(0));
+
+        // Integers are compared exactly, even beyond float precision
+        assert!(OrderValue::Int(i64::MAX - 1) < OrderValue::Int(i64::MAX));
+    }
+
+    #[test]
+    fn test_direction_compare() {
+        assert_eq!(Direction::Asc.compare(&1, &2), Ordering::Less);
+        assert_eq!(Direction::Desc.compare(&1, &2), Ordering::Greater);
+        assert_eq!(Direction::Desc.compare(&(1, 2), &(1, 3)), Ordering::Greater);
+    }
+}
@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+
+use half::f16;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::data_types::vectors::VectorElementType;
+use crate::types::Distance;
+
+/// Element type, which a dense vector storage keeps vector components in
+///
+/// Vectors enter and leave storages as [`VectorElementType`], conversion happens on this boundary.
+pub trait PrimitiveVectorElement:
+    Copy + Clone + Default + Serialize + DeserializeOwned + Send + Sync + 'static
+{
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]>;
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]>;
+
+    /// Convert stored vector into a form, which quantization expects for the given distance
+    ///
+    /// Original vectors are stored without distance preprocessing, it is applied here.
+    fn quantization_preprocess(
+        distance: Distance,
+        original_vectors: bool,
+        vector: &[Self],
+    ) -> Cow<[VectorElementType]> {
+        let vector = Self::slice_to_float_cow(Cow::Borrowed(vector));
+        if !original_vectors {
+            return vector;
+        }
+        match distance.preprocess_vector(&vector) {
+            Some(preprocessed) => Cow::Owned(preprocessed),
+            None => vector,
+        }
+    }
+}
+
+impl PrimitiveVectorElement for VectorElementType {
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]> {
+        vector
+    }
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]> {
+        vector
+    }
+}
+
+impl PrimitiveVectorElement for f16 {
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]> {
+        Cow::Owned(vector.iter().copied().map(f16::from_f32).collect())
+    }
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]> {
+        Cow::Owned(vector.iter().copied().map(f16::to_f32).collect())
+    }
+}
+
+impl PrimitiveVectorElement for u8 {
+    /// Components are rounded and clamped into the byte range
+    ///
+    /// Stored vectors are validated on upsert, so only queries can be affected.
+    fn slice_from_float_cow(vector: Cow<[VectorElementType]>) -> Cow<[Self]> {
+        Cow::Owned(vector.iter().map(|x| x.round() as u8).collect())
+    }
+
+    fn slice_to_float_cow(vector: Cow<[Self]>) -> Cow<[VectorElementType]> {
+        Cow::Owned(vector.iter().map(|x| VectorElementType::from(*x)).collect())
+    }
+
+    /// Byte vectors are stored without normalization, quantization expects normalized ones for cosine
+    fn quantization_preprocess(
+        distance: Distance,
+        _original_vectors: bool,
+        vector: &[Self],
+    ) -> Cow<[VectorElementType]> {
+        let vector = Self::slice_to_float_cow(Cow::Borrowed(vector));
+        match distance.preprocess_vector(&vector) {
+            Some(preprocessed) => Cow::Owned(preprocessed),
+            None => vector,
+        }
+    }
+}
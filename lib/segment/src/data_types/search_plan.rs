@@ -0,0 +1,89 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::index::field_index::CardinalityEstimation;
+use crate::types::{PayloadKeyType, SegmentType};
+
+/// Way a vector index executes a search
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchPath {
+    /// Exact search is requested, every point matching the filter is scored
+    Exact,
+    /// Every point of the segment is scored, there is no filter and no index worth traversing
+    FullScan,
+    /// Points matching the filter are selected with payload indexes and scored one by one
+    FilteredScan,
+    /// HNSW graph is traversed without a filter
+    Hnsw,
+    /// HNSW graph is traversed, visiting only points which match the filter
+    FilteredHnsw,
+    /// HNSW graph is traversed through all points, only points which match the filter are
+    /// collected
+    FilteredHnswTraversal,
+    /// Sparse inverted index is traversed, points which don't match the filter are skipped
+    InvertedIndex,
+}
+
+/// Type of the payload index, which serves a condition
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldIndexType {
+    Integer,
+    IntegerMap,
+    Keyword,
+    Float,
+    Geo,
+    FullText,
+    Bool,
+    Datetime,
+    Uuid,
+}
+
+/// Condition of the filter, which selects candidate points before the rest of the filter is checked
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct PrimaryClausePlan {
+    /// Payload key of the condition, absent for `has_id` conditions
+    pub key: Option<PayloadKeyType>,
+    /// Payload index, which selects points of the condition.
+    /// Absent if points are selected by ids, or every point is checked against the condition
+    pub index: Option<FieldIndexType>,
+}
+
+/// Estimated number of points matching a filter
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct FilterCardinality {
+    /// Lowest possible number of matching points
+    pub min: usize,
+    /// Expected number of matching points
+    pub exp: usize,
+    /// Highest possible number of matching points
+    pub max: usize,
+}
+
+impl From<&CardinalityEstimation> for FilterCardinality {
+    fn from(estimation: &CardinalityEstimation) -> Self {
+        FilterCardinality {
+            min: estimation.min,
+            exp: estimation.exp,
+            max: estimation.max,
+        }
+    }
+}
+
+/// How a segment would execute a vector search
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SegmentSearchPlan {
+    pub segment_type: SegmentType,
+    /// Number of points in the segment, which are not deleted
+    pub available_points: usize,
+    /// Estimated number of points matching the filter, absent without a filter
+    pub cardinality: Option<FilterCardinality>,
+    /// Conditions of the filter, which select candidate points
+    pub primary_clauses: Vec<PrimaryClausePlan>,
+    /// Way the vector index would execute the search
+    pub search_path: SearchPath,
+}
@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::common::find_non_finite_element;
+use crate::data_types::vectors::VectorElementType;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::types::ScoreType;
+
+/// Index of a sparse vector component
+pub type DimId = u32;
+
+/// Sparse vector, only non-zero components are stored
+///
+/// Components are sorted by index, indices are unique.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVector {
+    /// Indices of non-zero components, strictly increasing
+    pub indices: Vec<DimId>,
+    /// Values of non-zero components, same length as `indices`
+    pub values: Vec<VectorElementType>,
+}
+
+impl SparseVector {
+    /// Create a sparse vector, returns an error if components are not valid, see [`Self::validate`]
+    pub fn new(indices: Vec<DimId>, values: Vec<VectorElementType>) -> OperationResult<Self> {
+        let vector = Self { indices, values };
+        vector.validate()?;
+        Ok(vector)
+    }
+
+    /// Check that every index has a value, indices are strictly increasing and all values are finite
+    pub fn validate(&self) -> OperationResult<()> {
+        if self.indices.len() != self.values.len() {
+            return Err(OperationError::WrongVectorElement {
+                description: format!(
+                    "sparse vector has {} indices but {} values",
+                    self.indices.len(),
+                    self.values.len(),
+                ),
+            });
+        }
+        if let Some(position) = self.indices.windows(2).position(|pair| pair[0] >= pair[1]) {
+            return Err(OperationError::WrongVectorElement {
+                description: format!(
+                    "sparse vector indices must be strictly increasing, got {} after {} at position {}",
+                    self.indices[position + 1],
+                    self.indices[position],
+                    position + 1,
+                ),
+            });
+        }
+        if let Some(position) = find_non_finite_element(&self.values) {
+            return Err(OperationError::WrongVectorElement {
+                description: format!(
+                    "sparse vector value of index {} is {}, values must be finite numbers",
+                    self.indices[position], self.values[position],
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Dot product with the other vector
+    ///
+    /// Returns `None` if vectors have no common indices, so they are not related at all.
+    pub fn score(&self, other: &SparseVector) -> Option<ScoreType> {
+        let mut score = 0.0;
+        let mut overlap = false;
+        let (mut i, mut j) = (0, 0);
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    score += self.values[i] * other.values[j];
+                    overlap = true;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        overlap.then_some(score)
+    }
+
+    /// Iterate over `(index, value)` pairs of non-zero components
+    pub fn iter(&self) -> impl Iterator<Item = (DimId, VectorElementType)> + '_ {
+        self.indices
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(SparseVector::new(vec![], vec![]).is_ok());
+        assert!(SparseVector::new(vec![0, 5, 100], vec![1.0, -2.0, 0.5]).is_ok());
+
+        for (indices, values) in [
+            (vec![1, 2], vec![1.0]),
+            (vec![2, 1], vec![1.0, 2.0]),
+            (vec![1, 1], vec![1.0, 2.0]),
+            (vec![1, 2], vec![1.0, f32::NAN]),
+            (vec![1, 2], vec![f32::INFINITY, 2.0]),
+            (vec![1, 2], vec![1.0, f32::NEG_INFINITY]),
+        ] {
+            let err = SparseVector::new(indices, values).unwrap_err();
+            assert!(
+                matches!(err, OperationError::WrongVectorElement { .. }),
+                "unexpected error: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        let a = SparseVector::new(vec![1, 3, 5, 7], vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        let b = SparseVector::new(vec![0, 3, 7, 9], vec![5.0, 6.0, 7.0, 8.0]).unwrap();
+        let c = SparseVector::new(vec![0, 2], vec![1.0, 1.0]).unwrap();
+
+        assert_eq!(a.score(&b), Some(2.0 * 6.0 + 4.0 * 7.0));
+        assert_eq!(b.score(&a), a.score(&b));
+        assert_eq!(a.score(&c), None);
+        assert_eq!(a.score(&SparseVector::default()), None);
+    }
+}
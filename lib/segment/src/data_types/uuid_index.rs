@@ -0,0 +1,27 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UuidIndexType {
+    #[default]
+    Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct UuidIndexParams {
+    // Required for OpenAPI pattern matching
+    pub r#type: UuidIndexType,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// If true, values which are not valid UUIDs are not indexed. Otherwise they fail the
+    /// indexing request. Default: false
+    pub skip_invalid: Option<bool>,
+}
+
+impl UuidIndexParams {
+    pub fn skip_invalid(&self) -> bool {
+        self.skip_invalid.unwrap_or(false)
+    }
+}
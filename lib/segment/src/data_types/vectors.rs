@@ -5,6 +5,10 @@ use serde::{Deserialize, Serialize};
 
 use super::named_vectors::NamedVectors;
 use crate::common::utils::transpose_map_into_named_vector;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::types::Sample;
+use crate::vector_storage::query::discovery_query::DiscoveryQuery;
+use crate::vector_storage::query::reco_query::RecoQuery;
 
 /// Type of vector element.
 pub type VectorElementType = f32;
@@ -22,6 +26,41 @@ pub fn only_default_vector(vec: &[VectorElementType]) -> NamedVectors {
     NamedVectors::from_ref(DEFAULT_VECTOR_NAME, vec)
 }
 
+/// Type for multi-vector: list of sub-vectors of the same dimension
+pub type MultiDenseVector = Vec<VectorType>;
+
+/// Concatenate sub-vectors of a multi-vector into the flat form accepted by the storage
+///
+/// Returns an error if there are no sub-vectors or any of them has a wrong dimension.
+pub fn flatten_multi_dense_vector(
+    vectors: &[VectorType],
+    dim: usize,
+) -> OperationResult<VectorType> {
+    if vectors.is_empty() {
+        return Err(OperationError::WrongVectorElement {
+            description: "multi-vector must contain at least one sub-vector".to_string(),
+        });
+    }
+    if let Some((position, vector)) = vectors
+        .iter()
+        .enumerate()
+        .find(|(_, vector)| vector.len() != dim)
+    {
+        return Err(OperationError::WrongVectorElement {
+            description: format!(
+                "sub-vector at position {position} has dim {}, expected {dim}",
+                vector.len(),
+            ),
+        });
+    }
+    Ok(vectors.concat())
+}
+
+/// Split flat multi-vector from the storage into sub-vectors of dimension `dim`
+pub fn split_multi_dense_vector(vector: &[VectorElementType], dim: usize) -> MultiDenseVector {
+    vector.chunks_exact(dim).map(<[_]>::to_vec).collect()
+}
+
 /// Full vector data per point separator with single and multiple vector modes
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged, rename_all = "snake_case")]
@@ -151,6 +190,66 @@ impl NamedVectorStruct {
     }
 }
 
+/// Query of a vector search, defines how stored vectors are scored
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryVector {
+    /// Similarity with the vector
+    Nearest(VectorType),
+    /// Similarity with the best positive example, penalized by the best negative one
+    Recommend(RecoQuery<VectorType>),
+    /// Similarity with the target, within the region defined by context pairs
+    Discovery(DiscoveryQuery<VectorType>),
+    /// Sample of points, which are not scored by vectors at all
+    Sample(Sample),
+}
+
+impl QueryVector {
+    /// Whether points are scored by the similarity with a single vector.
+    ///
+    /// Only such scores correspond to the distance function of the vector, and can be
+    /// converted into it. Scores of other queries are always "larger is better".
+    pub fn is_distance_scored(&self) -> bool {
+        match self {
+            QueryVector::Nearest(_) => true,
+            QueryVector::Recommend(_) | QueryVector::Discovery(_) | QueryVector::Sample(_) => false,
+        }
+    }
+
+    /// All vectors the query compares points with
+    pub fn vectors(&self) -> Vec<&VectorType> {
+        match self {
+            QueryVector::Nearest(vector) => vec![vector],
+            QueryVector::Recommend(reco_query) => reco_query.iter_examples().collect(),
+            QueryVector::Discovery(discovery_query) => discovery_query.iter_examples().collect(),
+            QueryVector::Sample(_) => vec![],
+        }
+    }
+}
+
+impl From<VectorType> for QueryVector {
+    fn from(vector: VectorType) -> Self {
+        QueryVector::Nearest(vector)
+    }
+}
+
+impl From<RecoQuery<VectorType>> for QueryVector {
+    fn from(reco_query: RecoQuery<VectorType>) -> Self {
+        QueryVector::Recommend(reco_query)
+    }
+}
+
+impl From<DiscoveryQuery<VectorType>> for QueryVector {
+    fn from(discovery_query: DiscoveryQuery<VectorType>) -> Self {
+        QueryVector::Discovery(discovery_query)
+    }
+}
+
+impl From<Sample> for QueryVector {
+    fn from(sample: Sample) -> Self {
+        QueryVector::Sample(sample)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
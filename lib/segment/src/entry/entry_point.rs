@@ -1,5 +1,5 @@
 use std::backtrace::Backtrace;
-use std::collections::{HashMap, TryReserveError};
+use std::collections::{BTreeSet, HashMap, TryReserveError};
 use std::io::{Error as IoError, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::result;
@@ -11,14 +11,18 @@ use thiserror::Error;
 
 use crate::common::file_operations::FileStorageError;
 use crate::common::mmap_type::Error as MmapError;
+use crate::data_types::facets::{FacetParams, FacetValue};
 use crate::data_types::named_vectors::NamedVectors;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::order_by::{OrderBy, OrderValue};
+use crate::data_types::search_plan::SegmentSearchPlan;
+use crate::data_types::vectors::{QueryVector, VectorElementType};
+use crate::id_tracker::PointVersions;
 use crate::index::field_index::CardinalityEstimation;
 use crate::telemetry::SegmentTelemetry;
 use crate::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
-    ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentType, SeqNumberType, WithPayload,
-    WithVector,
+    Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PayloadSelector,
+    PointIdType, ScoreType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo,
+    SegmentResourceUsage, SegmentType, SeqNumberType, WithPayload, WithVector,
 };
 use crate::utils::mem::Mem;
 
@@ -30,6 +34,8 @@ pub enum OperationError {
         expected_dim: usize,
         received_dim: usize,
     },
+    #[error("Vector inserting error: {description}")]
+    WrongVectorElement { description: String },
     #[error("Not existing vector name error: {received_name}")]
     VectorNameNotExists { received_name: String },
     #[error("Missed vector name error: {received_name}")]
@@ -43,6 +49,8 @@ pub enum OperationError {
     },
     #[error("Unable to infer type for the field '{field_name}'. Please specify `field_type`")]
     TypeInferenceError { field_name: PayloadKeyType },
+    #[error("Validation failed: {description}")]
+    ValidationError { description: String },
     /// Service Error prevents further update of the collection until it is fixed.
     /// Should only be used for hardware, data corruption, IO, or other unexpected internal errors.
     #[error("Service runtime error: {description}")]
@@ -56,6 +64,10 @@ pub enum OperationError {
     OutOfMemory { description: String, free: u64 },
     #[error("Operation cancelled: {description}")]
     Cancelled { description: String },
+    #[error("No integer, float or datetime index for the `order_by` key: {key}. Please create one to order points by it")]
+    MissingRangeIndexForOrderBy { key: PayloadKeyType },
+    #[error("No keyword index for the `facet` key: {key}. Please create one to count points by its values")]
+    MissingKeywordIndexForFacet { key: PayloadKeyType },
 }
 
 impl OperationError {
@@ -197,6 +209,17 @@ pub trait SegmentEntry {
     /// Get version of specified point
     fn point_version(&self, point_id: PointIdType) -> Option<SeqNumberType>;
 
+    /// Get versions of vectors and payload of specified point
+    fn point_versions(&self, point_id: PointIdType) -> Option<PointVersions>;
+
+    /// Overwrite versions of vectors and payload of an existing point,
+    /// e.g. to keep them when the point is moved from another segment
+    fn set_point_versions(
+        &mut self,
+        point_id: PointIdType,
+        versions: PointVersions,
+    ) -> OperationResult<()>;
+
     #[allow(clippy::too_many_arguments)]
     fn search(
         &self,
@@ -210,19 +233,35 @@ pub trait SegmentEntry {
         is_stopped: &AtomicBool,
     ) -> OperationResult<Vec<ScoredPoint>>;
 
+    /// Search for a batch of queries at once.
+    ///
+    /// For queries scored by the distance, `score_threshold` is in the scale of the scores
+    /// returned to the user. Scores of other queries are compared with it as is.
+    /// Points scored worse than the threshold are not returned.
     #[allow(clippy::too_many_arguments)]
     fn search_batch(
         &self,
         vector_name: &str,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         with_payload: &WithPayload,
         with_vector: &WithVector,
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
     ) -> OperationResult<Vec<Vec<ScoredPoint>>>;
 
+    /// Explain how a search of the `vector_name` vector with the given filter would be executed.
+    ///
+    /// Returns a plan for every segment the search would be executed in.
+    fn search_plan(
+        &self,
+        vector_name: &str,
+        filter: Option<&Filter>,
+        params: Option<&SearchParams>,
+    ) -> OperationResult<Vec<SegmentSearchPlan>>;
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -264,6 +303,15 @@ pub trait SegmentEntry {
         full_payload: &Payload,
     ) -> OperationResult<bool>;
 
+    /// Merge payload into the object at the given key path of the point payload
+    fn set_payload_by_key(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        payload: &Payload,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool>;
+
     fn delete_payload(
         &mut self,
         op_num: SeqNumberType,
@@ -287,6 +335,14 @@ pub trait SegmentEntry {
 
     fn payload(&self, point_id: PointIdType) -> OperationResult<Payload>;
 
+    /// Payload of the point, projected with the selector.
+    /// Values, which are not selected, might be not read from the storage at all.
+    fn payload_selected(
+        &self,
+        point_id: PointIdType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload>;
+
     /// Iterator over all points in segment in ascending order.
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_>;
 
@@ -298,6 +354,35 @@ pub trait SegmentEntry {
         filter: Option<&'a Filter>,
     ) -> Vec<PointIdType>;
 
+    /// Paginate over points which satisfies filtering condition in the order of their `order_by`
+    /// payload values, starting with the `order_by.start_from` value and the `offset` id including.
+    ///
+    /// Points with the same value are ordered by id. Points with multiple values are returned
+    /// once per value, and points without values are skipped.
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        offset: Option<PointIdType>,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>>;
+
+    /// Count points per value of the keyword-indexed `request.key`, which satisfy `request.filter`.
+    ///
+    /// Without a filter, counts are the sizes of the index postings.
+    /// With a filter, counts are estimated by the payload index.
+    fn approximate_facet(
+        &self,
+        request: &FacetParams,
+    ) -> OperationResult<HashMap<FacetValue, usize>>;
+
+    /// Values of the keyword-indexed `key`, which at least one point satisfying `filter` has.
+    fn facet_values(
+        &self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&Filter>,
+    ) -> OperationResult<BTreeSet<FacetValue>>;
+
     /// Read points in [from; to) range
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType>;
 
@@ -325,6 +410,11 @@ pub trait SegmentEntry {
     /// Get current stats of the segment
     fn info(&self) -> SegmentInfo;
 
+    /// Memory and disk usage of the segment components.
+    ///
+    /// Reads sizes of the segment files, so should not be called on every request.
+    fn resource_usage(&self) -> OperationResult<SegmentResourceUsage>;
+
     /// Get segment configuration
     fn config(&self) -> SegmentConfig;
 
@@ -358,6 +448,41 @@ pub trait SegmentEntry {
         field_schema: Option<&PayloadFieldSchema>,
     ) -> OperationResult<bool>;
 
+    /// Start building index for a payload field with the given schema, the current index of the
+    /// field, if any, keeps serving reads. Payload updates are applied to both indexes until
+    /// the rebuild is finished or aborted.
+    ///
+    /// Returns `false` if the operation is outdated or there is nothing to rebuild, e.g. the field
+    /// is already indexed with the same schema.
+    fn start_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+        field_schema: Option<&PayloadFieldSchema>,
+    ) -> OperationResult<bool>;
+
+    /// Add the next batch of points to the index started by
+    /// [`SegmentEntry::start_field_index_rebuild`].
+    /// Only requires read access, so the segment can be updated in between batches.
+    ///
+    /// Returns `true` once all points are added.
+    fn continue_field_index_rebuild(
+        &self,
+        key: PayloadKeyTypeRef,
+        batch_size: usize,
+    ) -> OperationResult<bool>;
+
+    /// Replace index of a payload field with the rebuilt one.
+    /// If the rebuild has failed, the current index is left intact.
+    fn finish_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool>;
+
+    /// Drop the index being rebuilt, leaving the current index of the field intact
+    fn abort_field_index_rebuild(&mut self, key: PayloadKeyTypeRef) -> OperationResult<()>;
+
     /// Get indexed fields
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema>;
 
@@ -373,7 +498,8 @@ pub trait SegmentEntry {
 
     /// Take a snapshot of the segment.
     ///
-    /// Creates a tar archive of the segment directory into `snapshot_dir_path`.
+    /// Creates a tar archive of the segment directory into `snapshot_dir_path`,
+    /// with a manifest of sizes and checksums of the archived files to validate them on restore.
     /// Uses `temp_path` to prepare files to archive.
     fn take_snapshot(&self, temp_path: &Path, snapshot_dir_path: &Path)
         -> OperationResult<PathBuf>;
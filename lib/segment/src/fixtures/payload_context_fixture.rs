@@ -12,14 +12,14 @@ use crate::entry::entry_point::OperationResult;
 use crate::fixtures::payload_fixtures::{
     generate_diverse_payload, FLT_KEY, GEO_KEY, INT_KEY, STR_KEY, TEXT_KEY,
 };
-use crate::id_tracker::IdTracker;
+use crate::id_tracker::{IdTracker, PointVersions};
 use crate::index::plain_payload_index::PlainPayloadIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::PayloadIndex;
 use crate::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
 use crate::payload_storage::query_checker::SimpleConditionChecker;
 use crate::payload_storage::PayloadStorage;
-use crate::types::{PayloadSchemaType, PointIdType, PointOffsetType, SeqNumberType};
+use crate::types::{PayloadSchemaType, PointIdType, PointOffsetType};
 
 /// Warn: Use for tests only
 ///
@@ -41,14 +41,14 @@ impl FixtureIdTracker {
 }
 
 impl IdTracker for FixtureIdTracker {
-    fn internal_version(&self, _internal_id: PointOffsetType) -> Option<SeqNumberType> {
-        Some(0)
+    fn internal_versions(&self, _internal_id: PointOffsetType) -> Option<PointVersions> {
+        Some(PointVersions::default())
     }
 
-    fn set_internal_version(
+    fn set_internal_versions(
         &mut self,
         _internal_id: PointOffsetType,
-        _version: SeqNumberType,
+        _versions: PointVersions,
     ) -> OperationResult<()> {
         Ok(())
     }
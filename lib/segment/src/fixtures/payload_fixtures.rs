@@ -8,7 +8,7 @@ use serde_json::{json, Value};
 use crate::data_types::vectors::VectorElementType;
 use crate::types::{
     Condition, ExtendedPointId, FieldCondition, Filter, HasIdCondition, IsEmptyCondition, Match,
-    Payload, PayloadField, Range as RangeCondition, ValuesCount,
+    MinShould, Payload, PayloadField, Range as RangeCondition, ValuesCount,
 };
 
 const ADJECTIVE: &[&str] = &[
@@ -197,6 +197,7 @@ pub fn random_must_filter<R: Rng + ?Sized>(rnd_gen: &mut R, num_conditions: usiz
     Filter {
         should: None,
         must: Some(must_conditions),
+        min_should: None,
         must_not: None,
     }
 }
@@ -228,10 +229,34 @@ pub fn random_filter<R: Rng + ?Sized>(rnd_gen: &mut R, total_conditions: usize)
     Filter {
         should: should_conditions_opt,
         must: must_conditions_opt,
+        min_should: None,
         must_not: None,
     }
 }
 
+/// Filter with `min_should` clause, which may be combined with `must` and `must_not`
+pub fn random_min_should_filter<R: Rng + ?Sized>(rnd_gen: &mut R, num_conditions: usize) -> Filter {
+    let conditions = (0..num_conditions)
+        .map(|_| random_condition(rnd_gen))
+        .collect_vec();
+    // Allow `min_count` above the number of conditions, which never matches
+    let min_count = rnd_gen.gen_range(1..=num_conditions + 1);
+
+    let must = rnd_gen
+        .gen_bool(0.5)
+        .then(|| vec![random_simple_condition(rnd_gen)]);
+    let must_not = rnd_gen
+        .gen_bool(0.5)
+        .then(|| vec![random_simple_condition(rnd_gen)]);
+
+    Filter {
+        should: None,
+        min_should: Some(MinShould::new(conditions, min_count).unwrap()),
+        must,
+        must_not,
+    }
+}
+
 pub fn random_nested_filter<R: Rng + ?Sized>(rnd_gen: &mut R) -> Filter {
     let nested_or_proj: bool = rnd_gen.gen();
     let nested_str_key = if nested_or_proj {
@@ -246,6 +271,7 @@ pub fn random_nested_filter<R: Rng + ?Sized>(rnd_gen: &mut R) -> Filter {
     Filter {
         should: Some(vec![condition]),
         must: None,
+        min_should: None,
         must_not: None,
     }
 }
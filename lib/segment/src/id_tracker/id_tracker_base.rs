@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use bitvec::prelude::BitSlice;
 use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use rand::{Rng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 use crate::common::Flusher;
 use crate::entry::entry_point::OperationResult;
@@ -12,19 +15,81 @@ use crate::types::{PointIdType, PointOffsetType, SeqNumberType};
 /// would otherwise be introduced by HNSW/ID tracker point sampling.
 const SEED: u64 = 0b1011000011011110001110010101001010001011001101001010010001111010;
 
+/// Part of the point, which is versioned separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointComponent {
+    Vectors,
+    Payload,
+}
+
+/// Versions of the point components
+///
+/// Vectors and payload of a point are updated by independent operations. Each of them is only
+/// skipped as already applied, if the same component has a higher version, so updates of
+/// different components are applied regardless of their order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PointVersions {
+    pub vectors: SeqNumberType,
+    pub payload: SeqNumberType,
+}
+
+impl PointVersions {
+    /// Same version for all components
+    pub fn new(version: SeqNumberType) -> Self {
+        Self {
+            vectors: version,
+            payload: version,
+        }
+    }
+
+    /// Version of the point as a whole
+    pub fn point(&self) -> SeqNumberType {
+        self.vectors.max(self.payload)
+    }
+
+    pub fn get(&self, component: PointComponent) -> SeqNumberType {
+        match component {
+            PointComponent::Vectors => self.vectors,
+            PointComponent::Payload => self.payload,
+        }
+    }
+
+    pub fn set(&mut self, component: PointComponent, version: SeqNumberType) {
+        match component {
+            PointComponent::Vectors => self.vectors = version,
+            PointComponent::Payload => self.payload = version,
+        }
+    }
+}
+
 /// Trait for point ids tracker.
 ///
 /// This tracker is used to convert external (i.e. user-facing) point id into internal point id
 /// as well as for keeping track on point version
 /// Internal ids are useful for contiguous-ness
 pub trait IdTracker {
-    fn internal_version(&self, internal_id: PointOffsetType) -> Option<SeqNumberType>;
+    fn internal_versions(&self, internal_id: PointOffsetType) -> Option<PointVersions>;
+
+    fn set_internal_versions(
+        &mut self,
+        internal_id: PointOffsetType,
+        versions: PointVersions,
+    ) -> OperationResult<()>;
 
+    /// Version of the point as a whole, the highest version of its components
+    fn internal_version(&self, internal_id: PointOffsetType) -> Option<SeqNumberType> {
+        self.internal_versions(internal_id)
+            .map(|versions| versions.point())
+    }
+
+    /// Set the same version for all components of the point
     fn set_internal_version(
         &mut self,
         internal_id: PointOffsetType,
         version: SeqNumberType,
-    ) -> OperationResult<()>;
+    ) -> OperationResult<()> {
+        self.set_internal_versions(internal_id, PointVersions::new(version))
+    }
 
     /// Returns internal ID of the point, which is used inside this segment
     fn internal_id(&self, external_id: PointIdType) -> Option<PointOffsetType>;
@@ -135,6 +200,39 @@ pub trait IdTracker {
                 }),
         )
     }
+
+    /// Iterator over all IDs which are not deleted, in uniformly random order
+    ///
+    /// Each ID is returned exactly once, so it can be consumed until enough IDs are found, or until
+    /// all of them are exhausted. A [`BitSlice`] of deleted vectors may optionally be given to also
+    /// consider deleted named vectors.
+    fn random_ids<'a>(
+        &'a self,
+        deleted_vector_bitslice: Option<&'a BitSlice>,
+        rng: &'a mut dyn RngCore,
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
+        let total = self.total_point_count() as PointOffsetType;
+        // Lazy Fisher-Yates shuffle, only positions with swapped IDs are remembered
+        let mut swapped: HashMap<PointOffsetType, PointOffsetType> = HashMap::new();
+        Box::new(
+            (0..total)
+                .map(move |position| {
+                    let chosen = rng.gen_range(position..total);
+                    let chosen_id = swapped.get(&chosen).copied().unwrap_or(chosen);
+                    let position_id = swapped.remove(&position).unwrap_or(position);
+                    if chosen != position {
+                        swapped.insert(chosen, position_id);
+                    }
+                    chosen_id
+                })
+                .filter(move |x| {
+                    !deleted_vector_bitslice
+                        .and_then(|d| d.get(*x as usize).as_deref().copied())
+                        .unwrap_or(false)
+                        && !self.is_deleted_point(*x)
+                }),
+        )
+    }
 }
 
 pub type IdTrackerSS = dyn IdTracker + Sync + Send;
@@ -12,7 +12,7 @@ use crate::common::rocksdb_buffered_delete_wrapper::DatabaseColumnScheduledDelet
 use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_MAPPING_CF, DB_VERSIONS_CF};
 use crate::common::Flusher;
 use crate::entry::entry_point::OperationResult;
-use crate::id_tracker::IdTracker;
+use crate::id_tracker::{IdTracker, PointVersions};
 use crate::types::{ExtendedPointId, PointIdType, PointOffsetType, SeqNumberType};
 
 /// Point Id type used for storing ids internally
@@ -58,7 +58,7 @@ fn external_to_stored_id(point_id: &PointIdType) -> StoredPointId {
 pub struct SimpleIdTracker {
     deleted: BitVec,
     internal_to_external: Vec<PointIdType>,
-    internal_to_version: Vec<SeqNumberType>,
+    internal_to_versions: Vec<PointVersions>,
     external_to_internal_num: BTreeMap<u64, PointOffsetType>,
     external_to_internal_uuid: BTreeMap<Uuid, PointOffsetType>,
     mapping_db_wrapper: DatabaseColumnScheduledDeleteWrapper,
@@ -118,22 +118,22 @@ impl SimpleIdTracker {
             }
         }
 
-        let mut internal_to_version: Vec<SeqNumberType> = Default::default();
+        let mut internal_to_versions: Vec<PointVersions> = Default::default();
         let versions_db_wrapper = DatabaseColumnScheduledDeleteWrapper::new(
             DatabaseColumnWrapper::new(store, DB_VERSIONS_CF),
         );
         for (key, val) in versions_db_wrapper.lock_db().iter()? {
             let external_id = Self::restore_key(&key);
-            let version: SeqNumberType = bincode::deserialize(&val).unwrap();
+            let versions = Self::restore_versions(&val);
             let internal_id = match external_id {
                 PointIdType::NumId(idx) => external_to_internal_num.get(&idx).copied(),
                 PointIdType::Uuid(uuid) => external_to_internal_uuid.get(&uuid).copied(),
             };
             if let Some(internal_id) = internal_id {
-                if internal_id as usize >= internal_to_version.len() {
-                    internal_to_version.resize(internal_id as usize + 1, 0);
+                if internal_id as usize >= internal_to_versions.len() {
+                    internal_to_versions.resize(internal_id as usize + 1, PointVersions::default());
                 }
-                internal_to_version[internal_id as usize] = version;
+                internal_to_versions[internal_id as usize] = versions;
             } else {
                 log::debug!(
                     "Found version without internal id, external id: {}",
@@ -157,7 +157,7 @@ impl SimpleIdTracker {
         Ok(SimpleIdTracker {
             deleted,
             internal_to_external,
-            internal_to_version,
+            internal_to_versions,
             external_to_internal_num,
             external_to_internal_uuid,
             mapping_db_wrapper,
@@ -174,6 +174,24 @@ impl SimpleIdTracker {
         stored_to_external_id(stored_external_id)
     }
 
+    /// Versions are stored as a single number, while all components have the same version.
+    /// This is also the format of segments, created before components were versioned separately.
+    fn store_versions(versions: &PointVersions) -> Vec<u8> {
+        if versions.vectors == versions.payload {
+            bincode::serialize(&versions.vectors).unwrap()
+        } else {
+            bincode::serialize(versions).unwrap()
+        }
+    }
+
+    fn restore_versions(data: &[u8]) -> PointVersions {
+        if data.len() == std::mem::size_of::<SeqNumberType>() {
+            PointVersions::new(bincode::deserialize(data).unwrap())
+        } else {
+            bincode::deserialize(data).unwrap()
+        }
+    }
+
     fn delete_key(&self, external_id: &PointIdType) -> OperationResult<()> {
         self.mapping_db_wrapper
             .remove(Self::store_key(external_id))?;
@@ -191,23 +209,24 @@ impl SimpleIdTracker {
 }
 
 impl IdTracker for SimpleIdTracker {
-    fn internal_version(&self, internal_id: PointOffsetType) -> Option<SeqNumberType> {
-        self.internal_to_version.get(internal_id as usize).copied()
+    fn internal_versions(&self, internal_id: PointOffsetType) -> Option<PointVersions> {
+        self.internal_to_versions.get(internal_id as usize).copied()
     }
 
-    fn set_internal_version(
+    fn set_internal_versions(
         &mut self,
         internal_id: PointOffsetType,
-        version: SeqNumberType,
+        versions: PointVersions,
     ) -> OperationResult<()> {
         if let Some(external_id) = self.external_id(internal_id) {
-            if internal_id as usize >= self.internal_to_version.len() {
-                self.internal_to_version.resize(internal_id as usize + 1, 0);
+            if internal_id as usize >= self.internal_to_versions.len() {
+                self.internal_to_versions
+                    .resize(internal_id as usize + 1, PointVersions::default());
             }
-            self.internal_to_version[internal_id as usize] = version;
+            self.internal_to_versions[internal_id as usize] = versions;
             self.versions_db_wrapper.put(
                 Self::store_key(&external_id),
-                bincode::serialize(&version).unwrap(),
+                Self::store_versions(&versions),
             )?;
         }
         Ok(())
@@ -387,6 +406,8 @@ impl IdTracker for SimpleIdTracker {
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use serde::de::DeserializeOwned;
     use tempfile::Builder;
 
@@ -411,6 +432,30 @@ mod tests {
         check_bincode_serialization(StoredPointId::String("hello".to_string()));
     }
 
+    #[test]
+    fn test_versions_storage() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let mut id_tracker = SimpleIdTracker::open(db.clone()).unwrap();
+        id_tracker.set_link(100.into(), 0).unwrap();
+        id_tracker.set_link(200.into(), 1).unwrap();
+        id_tracker.set_internal_version(0, 5).unwrap();
+        let versions = PointVersions {
+            vectors: 7,
+            payload: 3,
+        };
+        id_tracker.set_internal_versions(1, versions).unwrap();
+        assert_eq!(id_tracker.internal_version(1), Some(7));
+        id_tracker.mapping_flusher()().unwrap();
+        id_tracker.versions_flusher()().unwrap();
+        drop(id_tracker);
+
+        let id_tracker = SimpleIdTracker::open(db).unwrap();
+        assert_eq!(id_tracker.internal_versions(0), Some(PointVersions::new(5)));
+        assert_eq!(id_tracker.internal_versions(1), Some(versions));
+    }
+
     #[test]
     fn test_iterator() {
         let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
@@ -470,4 +515,41 @@ mod tests {
 
         assert_eq!(sorted_from_tracker, values);
     }
+
+    #[test]
+    fn test_random_ids() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let mut id_tracker = SimpleIdTracker::open(db).unwrap();
+        for internal_id in 0..100 {
+            id_tracker
+                .set_link((internal_id as u64).into(), internal_id)
+                .unwrap();
+        }
+        id_tracker.drop(10.into()).unwrap();
+        id_tracker.drop(20.into()).unwrap();
+
+        let mut deleted_vectors = BitVec::repeat(false, 100);
+        deleted_vectors.set(30, true);
+
+        let sample = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            id_tracker
+                .random_ids(Some(&deleted_vectors), &mut rng)
+                .collect_vec()
+        };
+
+        // Every available ID is returned exactly once
+        let ids = sample(42);
+        let expected = (0..100)
+            .filter(|id| ![10, 20, 30].contains(id))
+            .collect_vec();
+        assert_eq!(ids.iter().copied().sorted().collect_vec(), expected);
+        assert_ne!(ids, expected);
+
+        // Order depends on the seed only
+        assert_eq!(sample(42), ids);
+        assert_ne!(sample(43), ids);
+    }
 }
@@ -2,11 +2,13 @@ use std::sync::Arc;
 
 use parking_lot::RwLock;
 use rocksdb::DB;
+use serde_json::Value;
 
 use self::memory::{BinaryItem, BinaryMemory};
 use super::{CardinalityEstimation, PayloadFieldIndex, PrimaryCondition, ValueIndexer};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
-use crate::entry::entry_point::OperationResult;
+use crate::common::utils::MultiValue;
+use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     FieldCondition, Match, MatchValue, PayloadKeyType, PointOffsetType, ValueVariants,
@@ -99,6 +101,9 @@ mod memory {
 
             debug_assert!(self.trues.len() == self.falses.len());
 
+            let previous = self.get(id);
+            let was_indexed = previous.has_true() || previous.has_false();
+
             let has_true = item.has_true();
             let had_true = self.trues.replace(id as usize, has_true);
             match (had_true, has_true) {
@@ -115,7 +120,12 @@ mod memory {
                 _ => {}
             }
 
-            self.indexed_count += 1;
+            let is_indexed = has_true || has_false;
+            match (was_indexed, is_indexed) {
+                (false, true) => self.indexed_count += 1,
+                (true, false) => self.indexed_count -= 1,
+                _ => {}
+            }
         }
 
         /// Removes the point from the index and tries to shrink the vectors if possible. If the index is not within bounds, does nothing
@@ -201,6 +211,19 @@ impl BinaryIndex {
     pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
         self.values_count(point_id) == 0
     }
+
+    /// Only booleans and nulls are accepted. Values of other types are rejected rather than
+    /// skipped, so points with such values are not silently missing from the index.
+    pub fn validate_payload(payload: &MultiValue<&Value>) -> OperationResult<()> {
+        for value in payload.flatten() {
+            if !matches!(value, Value::Bool(_) | Value::Null) {
+                return Err(OperationError::ValidationError {
+                    description: format!("Invalid bool value {value}"),
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl PayloadFieldIndex for BinaryIndex {
@@ -228,6 +251,10 @@ impl PayloadFieldIndex for BinaryIndex {
         self.db_wrapper.flusher()
     }
 
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.db_wrapper.size_bytes()
+    }
+
     fn filter<'a>(
         &'a self,
         condition: &'a crate::types::FieldCondition,
@@ -466,6 +493,34 @@ mod tests {
         assert!(point_offsets.is_empty());
     }
 
+    #[test]
+    fn validate_payload() {
+        for value in [
+            json!(true),
+            json!([true, false]),
+            json!(null),
+            json!([false, null]),
+        ] {
+            assert!(BinaryIndex::validate_payload(&MultiValue::one(&value)).is_ok());
+        }
+        for value in [json!("true"), json!(1), json!([true, "false"]), json!({})] {
+            assert!(BinaryIndex::validate_payload(&MultiValue::one(&value)).is_err());
+        }
+    }
+
+    #[test]
+    fn indexed_count_on_overwrite() {
+        let (_tmp_dir, mut index) = new_binary_index();
+
+        index.add_point(0, &MultiValue::one(&json!(true))).unwrap();
+        index.add_many(0, vec![false]).unwrap();
+        index.add_point(1, &MultiValue::one(&json!(false))).unwrap();
+        assert_eq!(index.count_indexed_points(), 2);
+
+        index.remove_point(0).unwrap();
+        assert_eq!(index.count_indexed_points(), 1);
+    }
+
     #[test]
     fn indexed_count() {
         let (_tmp_dir, mut index) = new_binary_index();
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde_json::Value;
+
+use super::numeric_index::NumericIndex;
+use super::{
+    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
+};
+use crate::common::utils::MultiValue;
+use crate::common::Flusher;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{
+    DateTimePayloadType, DatetimeRange, FieldCondition, FloatPayloadType, IntPayloadType,
+    PayloadKeyType, PointOffsetType, RangeInterface,
+};
+
+/// Index of datetime values, stored as timestamps in microseconds
+pub struct DatetimeIndex {
+    index: NumericIndex<IntPayloadType>,
+}
+
+impl DatetimeIndex {
+    pub fn new(db: Arc<RwLock<DB>>, field: &str) -> Self {
+        Self {
+            index: NumericIndex::new(db, field),
+        }
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.index.recreate()
+    }
+
+    /// Index of the timestamps, which allows to order points by them
+    pub fn timestamp_index(&self) -> &NumericIndex<IntPayloadType> {
+        &self.index
+    }
+
+    /// Timestamps of the point datetimes
+    pub fn get_values(&self, idx: PointOffsetType) -> Option<&Vec<IntPayloadType>> {
+        self.index.get_values(idx)
+    }
+
+    /// Check that all strings in the payload are datetimes, so they are not silently not indexed
+    pub fn validate_payload(payload: &MultiValue<&Value>) -> OperationResult<()> {
+        for value in payload.flatten() {
+            if let Value::String(value) = value {
+                if let Err(err) = value.parse::<DateTimePayloadType>() {
+                    return Err(OperationError::ValidationError {
+                        description: format!("Invalid datetime value {value:?}: {err}"),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        self.index.get_telemetry_data()
+    }
+
+    pub fn values_count(&self, point_id: PointOffsetType) -> usize {
+        self.index.values_count(point_id)
+    }
+
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        self.index.values_is_empty(point_id)
+    }
+}
+
+impl PayloadFieldIndex for DatetimeIndex {
+    fn count_indexed_points(&self) -> usize {
+        self.index.count_indexed_points()
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        self.index.load()
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        PayloadFieldIndex::clear(self.index)
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.index.flusher()
+    }
+
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.index.storage_size_bytes()
+    }
+
+    fn filter(
+        &self,
+        condition: &FieldCondition,
+    ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
+        match condition.range.as_ref()? {
+            RangeInterface::DateTime(range) => {
+                Some(self.index.range_filter(&range.to_timestamp_range()))
+            }
+            RangeInterface::Float(_) => None,
+        }
+    }
+
+    fn estimate_cardinality(&self, condition: &FieldCondition) -> Option<CardinalityEstimation> {
+        let RangeInterface::DateTime(range) = condition.range.as_ref()? else {
+            return None;
+        };
+        let mut cardinality = self.index.range_cardinality(&range.to_timestamp_range());
+        cardinality
+            .primary_clauses
+            .push(PrimaryCondition::Condition(condition.clone()));
+        Some(cardinality)
+    }
+
+    fn payload_blocks(
+        &self,
+        threshold: usize,
+        key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        let datetime = |timestamp: Option<FloatPayloadType>| {
+            timestamp.and_then(|timestamp| DateTimePayloadType::from_timestamp(timestamp as i64))
+        };
+        Box::new(
+            self.index
+                .payload_blocks(threshold, key)
+                .map(move |mut block| {
+                    if let Some(RangeInterface::Float(range)) = block.condition.range {
+                        block.condition.range = Some(RangeInterface::DateTime(DatetimeRange {
+                            lt: datetime(range.lt),
+                            gt: datetime(range.gt),
+                            gte: datetime(range.gte),
+                            lte: datetime(range.lte),
+                        }));
+                    }
+                    block
+                }),
+        )
+    }
+}
+
+impl ValueIndexer<IntPayloadType> for DatetimeIndex {
+    fn add_many(
+        &mut self,
+        id: PointOffsetType,
+        values: Vec<IntPayloadType>,
+    ) -> OperationResult<()> {
+        self.index.add_many_to_list(id, values)
+    }
+
+    fn get_value(&self, value: &Value) -> Option<IntPayloadType> {
+        if let Value::String(value) = value {
+            return value
+                .parse::<DateTimePayloadType>()
+                .ok()
+                .map(|datetime| datetime.timestamp());
+        }
+        None
+    }
+
+    fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        self.index.remove_point(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::{Builder, TempDir};
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+
+    const COLUMN_NAME: &str = "test";
+
+    fn get_index(dir: &TempDir) -> DatetimeIndex {
+        let db = open_db_with_existing_cf(dir.path()).unwrap();
+        DatetimeIndex::new(db, COLUMN_NAME)
+    }
+
+    fn range_condition(range: DatetimeRange) -> FieldCondition {
+        FieldCondition::new_range("test", range)
+    }
+
+    fn datetime(value: &str) -> Option<DateTimePayloadType> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn test_datetime_index_filter() {
+        let temp_dir = Builder::new()
+            .prefix("test_datetime_index")
+            .tempdir()
+            .unwrap();
+        let mut index = get_index(&temp_dir);
+        index.recreate().unwrap();
+
+        let payloads = [
+            json!("2023-01-01"),
+            json!("2023-01-01T12:00:00.5Z"),
+            json!("2023-01-02T01:00:00+03:00"),
+            json!(["2022-12-31T23:59:59", "2023-02-01 00:00"]),
+            json!(42),
+        ];
+        for (idx, payload) in payloads.iter().enumerate() {
+            let payload = MultiValue::one(payload);
+            DatetimeIndex::validate_payload(&payload).unwrap();
+            index.add_point(idx as PointOffsetType, &payload).unwrap();
+        }
+
+        let check = |index: &DatetimeIndex, range: DatetimeRange, expected: &[PointOffsetType]| {
+            let condition = range_condition(range);
+            let mut points = index.filter(&condition).unwrap().collect::<Vec<_>>();
+            points.sort_unstable();
+            points.dedup();
+            assert_eq!(points, expected);
+            let cardinality = index.estimate_cardinality(&condition).unwrap();
+            assert!(cardinality.min <= expected.len() && expected.len() <= cardinality.max);
+        };
+
+        // Date without a time is the start of the day, so it's equal to the same midnight
+        let range = DatetimeRange {
+            gte: datetime("2023-01-01T00:00:00Z"),
+            lte: datetime("2023-01-01T00:00:00Z"),
+            ..Default::default()
+        };
+        check(&index, range, &[0]);
+
+        // Timezone is taken into account: point 2 is 2023-01-01T22:00:00Z
+        let range = DatetimeRange {
+            gt: datetime("2023-01-01"),
+            lt: datetime("2023-01-02"),
+            ..Default::default()
+        };
+        check(&index, range, &[1, 2]);
+
+        let range = DatetimeRange {
+            gte: datetime("2023-01-15"),
+            ..Default::default()
+        };
+        check(&index, range, &[3]);
+
+        index.flusher()().unwrap();
+        drop(index);
+
+        let mut index = get_index(&temp_dir);
+        assert!(index.load().unwrap());
+        assert_eq!(index.count_indexed_points(), 4);
+        let range = DatetimeRange {
+            lt: datetime("2023-01-01"),
+            ..Default::default()
+        };
+        check(&index, range, &[3]);
+    }
+
+    #[test]
+    fn test_datetime_index_invalid_value() {
+        let payload = json!(["2023-01-01", "yesterday"]);
+        let result = DatetimeIndex::validate_payload(&MultiValue::one(&payload));
+        match result {
+            Err(OperationError::ValidationError { description }) => {
+                assert!(description.contains("yesterday"), "{description}")
+            }
+            other => panic!("Expected validation error, got {other:?}"),
+        }
+    }
+}
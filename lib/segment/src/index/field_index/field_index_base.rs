@@ -3,19 +3,61 @@ use smol_str::SmolStr;
 
 use crate::common::utils::MultiValue;
 use crate::common::Flusher;
+use crate::data_types::order_by::{Direction, OrderValue};
+use crate::data_types::search_plan::FieldIndexType;
 use crate::entry::entry_point::OperationResult;
 use crate::index::field_index::binary_index::BinaryIndex;
+use crate::index::field_index::datetime_index::DatetimeIndex;
 use crate::index::field_index::full_text_index::text_index::FullTextIndex;
 use crate::index::field_index::geo_index::GeoMapIndex;
 use crate::index::field_index::map_index::MapIndex;
 use crate::index::field_index::numeric_index::NumericIndex;
+use crate::index::field_index::uuid_index::UuidIndex;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
-    FieldCondition, FloatPayloadType, IntPayloadType, Match, MatchText, PayloadKeyType,
-    PointOffsetType,
+    FieldCondition, FloatPayloadType, IntPayloadType, PayloadKeyType, PointOffsetType,
 };
 
+/// Index of numeric values, which allows to iterate over points in the order of their values
+pub enum NumericFieldIndex<'a> {
+    IntIndex(&'a NumericIndex<IntPayloadType>),
+    FloatIndex(&'a NumericIndex<FloatPayloadType>),
+}
+
+impl<'a> NumericFieldIndex<'a> {
+    /// Iterate over values and their points in the order of `direction`, starting with the
+    /// `start_from` value including.
+    pub fn stream_from(
+        &self,
+        start_from: Option<OrderValue>,
+        direction: Direction,
+    ) -> Box<dyn Iterator<Item = (OrderValue, PointOffsetType)> + 'a> {
+        match *self {
+            NumericFieldIndex::IntIndex(index) => {
+                // Fractional start can't match integers, so round it towards the iteration
+                let start_from = start_from.map(|value| match value {
+                    OrderValue::Int(value) => value,
+                    OrderValue::Float(value) => match direction {
+                        Direction::Asc => value.ceil() as IntPayloadType,
+                        Direction::Desc => value.floor() as IntPayloadType,
+                    },
+                });
+                Box::new(
+                    index
+                        .stream_from(start_from, direction)
+                        .map(|(value, idx)| (OrderValue::Int(value), idx)),
+                )
+            }
+            NumericFieldIndex::FloatIndex(index) => Box::new(
+                index
+                    .stream_from(start_from.map(OrderValue::as_f64), direction)
+                    .map(|(value, idx)| (OrderValue::Float(value), idx)),
+            ),
+        }
+    }
+}
+
 pub trait PayloadFieldIndex {
     /// Return number of points with at least one value indexed in here
     fn count_indexed_points(&self) -> usize;
@@ -29,6 +71,9 @@ pub trait PayloadFieldIndex {
     /// Return function that flushes all pending updates to disk.
     fn flusher(&self) -> Flusher;
 
+    /// Size of the persisted index data, in bytes
+    fn storage_size_bytes(&self) -> OperationResult<usize>;
+
     /// Get iterator over points fitting given `condition`
     /// Return `None` if condition does not match the index type
     fn filter<'a>(
@@ -121,6 +166,8 @@ pub enum FieldIndex {
     GeoIndex(GeoMapIndex),
     FullTextIndex(FullTextIndex),
     BinaryIndex(BinaryIndex),
+    DatetimeIndex(DatetimeIndex),
+    UuidIndex(UuidIndex),
 }
 
 impl FieldIndex {
@@ -141,21 +188,54 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(_) => None,
             FieldIndex::KeywordIndex(_) => None,
             FieldIndex::FloatIndex(_) => None,
+            FieldIndex::DatetimeIndex(_) => None,
             FieldIndex::GeoIndex(_) => None,
             FieldIndex::BinaryIndex(_) => None,
-            FieldIndex::FullTextIndex(full_text_index) => match &condition.r#match {
-                Some(Match::Text(MatchText { text })) => {
-                    let query = full_text_index.parse_query(text);
-                    for value in full_text_index.get_values(payload_value) {
-                        let document = full_text_index.parse_document(&value);
-                        if query.check_match(&document) {
-                            return Some(true);
-                        }
+            FieldIndex::UuidIndex(uuid_index) => {
+                uuid_index.check_condition(condition, payload_value)
+            }
+            FieldIndex::FullTextIndex(full_text_index) => {
+                let query = full_text_index.parse_match(condition.r#match.as_ref()?)?;
+                for value in full_text_index.get_values(payload_value) {
+                    let document = full_text_index.parse_document(&value);
+                    if query.check_match(&document) {
+                        return Some(true);
                     }
-                    Some(false)
                 }
-                _ => None,
-            },
+                Some(false)
+            }
+        }
+    }
+
+    /// Numeric view of this index, if points can be ordered by its values
+    pub fn as_numeric(&self) -> Option<NumericFieldIndex> {
+        match self {
+            FieldIndex::IntIndex(index) => Some(NumericFieldIndex::IntIndex(index)),
+            FieldIndex::FloatIndex(index) => Some(NumericFieldIndex::FloatIndex(index)),
+            FieldIndex::DatetimeIndex(index) => {
+                Some(NumericFieldIndex::IntIndex(index.timestamp_index()))
+            }
+            FieldIndex::IntMapIndex(_)
+            | FieldIndex::KeywordIndex(_)
+            | FieldIndex::GeoIndex(_)
+            | FieldIndex::FullTextIndex(_)
+            | FieldIndex::BinaryIndex(_)
+            | FieldIndex::UuidIndex(_) => None,
+        }
+    }
+
+    /// Keyword view of this index, if points can be counted by its values
+    pub fn as_keyword(&self) -> Option<&MapIndex<SmolStr>> {
+        match self {
+            FieldIndex::KeywordIndex(index) => Some(index),
+            FieldIndex::IntIndex(_)
+            | FieldIndex::IntMapIndex(_)
+            | FieldIndex::FloatIndex(_)
+            | FieldIndex::DatetimeIndex(_)
+            | FieldIndex::GeoIndex(_)
+            | FieldIndex::FullTextIndex(_)
+            | FieldIndex::BinaryIndex(_)
+            | FieldIndex::UuidIndex(_) => None,
         }
     }
 
@@ -165,6 +245,8 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(payload_field_index) => payload_field_index,
             FieldIndex::KeywordIndex(payload_field_index) => payload_field_index,
             FieldIndex::FloatIndex(payload_field_index) => payload_field_index,
+            FieldIndex::DatetimeIndex(payload_field_index) => payload_field_index,
+            FieldIndex::UuidIndex(payload_field_index) => payload_field_index,
             FieldIndex::GeoIndex(payload_field_index) => payload_field_index,
             FieldIndex::BinaryIndex(payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(payload_field_index) => payload_field_index,
@@ -178,6 +260,8 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::KeywordIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::FloatIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::DatetimeIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::UuidIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::BinaryIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index,
@@ -190,6 +274,8 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::KeywordIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::FloatIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::DatetimeIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::UuidIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::BinaryIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index.load(),
@@ -202,6 +288,8 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(index) => index.clear(),
             FieldIndex::KeywordIndex(index) => index.clear(),
             FieldIndex::FloatIndex(index) => index.clear(),
+            FieldIndex::DatetimeIndex(index) => index.clear(),
+            FieldIndex::UuidIndex(index) => index.clear(),
             FieldIndex::GeoIndex(index) => index.clear(),
             FieldIndex::BinaryIndex(index) => index.clear(),
             FieldIndex::FullTextIndex(index) => index.clear(),
@@ -214,6 +302,8 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(index) => index.recreate(),
             FieldIndex::KeywordIndex(index) => index.recreate(),
             FieldIndex::FloatIndex(index) => index.recreate(),
+            FieldIndex::DatetimeIndex(index) => index.recreate(),
+            FieldIndex::UuidIndex(index) => index.recreate(),
             FieldIndex::GeoIndex(index) => index.recreate(),
             FieldIndex::BinaryIndex(index) => index.recreate(),
             FieldIndex::FullTextIndex(index) => index.recreate(),
@@ -228,6 +318,10 @@ impl FieldIndex {
         self.get_payload_field_index().flusher()
     }
 
+    pub fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.get_payload_field_index().storage_size_bytes()
+    }
+
     pub fn filter<'a>(
         &'a self,
         condition: &'a FieldCondition,
@@ -270,10 +364,19 @@ impl FieldIndex {
             FieldIndex::FloatIndex(ref mut payload_field_index) => {
                 payload_field_index.add_point(id, payload)
             }
+            FieldIndex::DatetimeIndex(ref mut payload_field_index) => {
+                DatetimeIndex::validate_payload(payload)?;
+                payload_field_index.add_point(id, payload)
+            }
+            FieldIndex::UuidIndex(ref mut payload_field_index) => {
+                payload_field_index.validate_payload(payload)?;
+                payload_field_index.add_point(id, payload)
+            }
             FieldIndex::GeoIndex(ref mut payload_field_index) => {
                 payload_field_index.add_point(id, payload)
             }
             FieldIndex::BinaryIndex(ref mut payload_field_index) => {
+                BinaryIndex::validate_payload(payload)?;
                 payload_field_index.add_point(id, payload)
             }
             FieldIndex::FullTextIndex(ref mut payload_field_index) => {
@@ -282,24 +385,57 @@ impl FieldIndex {
         }
     }
 
+    /// Check that the payload can be indexed, before any index or storage is modified
+    pub fn validate_payload(&self, payload: &MultiValue<&Value>) -> OperationResult<()> {
+        match self {
+            FieldIndex::DatetimeIndex(_) => DatetimeIndex::validate_payload(payload),
+            FieldIndex::UuidIndex(index) => index.validate_payload(payload),
+            FieldIndex::BinaryIndex(_) => BinaryIndex::validate_payload(payload),
+            FieldIndex::IntIndex(_)
+            | FieldIndex::IntMapIndex(_)
+            | FieldIndex::KeywordIndex(_)
+            | FieldIndex::FloatIndex(_)
+            | FieldIndex::GeoIndex(_)
+            | FieldIndex::FullTextIndex(_) => Ok(()),
+        }
+    }
+
     pub fn remove_point(&mut self, point_id: PointOffsetType) -> OperationResult<()> {
         match self {
             FieldIndex::IntIndex(index) => index.remove_point(point_id),
             FieldIndex::IntMapIndex(index) => index.remove_point(point_id),
             FieldIndex::KeywordIndex(index) => index.remove_point(point_id),
             FieldIndex::FloatIndex(index) => index.remove_point(point_id),
+            FieldIndex::DatetimeIndex(index) => index.remove_point(point_id),
+            FieldIndex::UuidIndex(index) => index.remove_point(point_id),
             FieldIndex::GeoIndex(index) => index.remove_point(point_id),
             FieldIndex::BinaryIndex(index) => index.remove_point(point_id),
             FieldIndex::FullTextIndex(index) => index.remove_point(point_id),
         }
     }
 
+    pub fn index_type(&self) -> FieldIndexType {
+        match self {
+            FieldIndex::IntIndex(_) => FieldIndexType::Integer,
+            FieldIndex::IntMapIndex(_) => FieldIndexType::IntegerMap,
+            FieldIndex::KeywordIndex(_) => FieldIndexType::Keyword,
+            FieldIndex::FloatIndex(_) => FieldIndexType::Float,
+            FieldIndex::DatetimeIndex(_) => FieldIndexType::Datetime,
+            FieldIndex::UuidIndex(_) => FieldIndexType::Uuid,
+            FieldIndex::GeoIndex(_) => FieldIndexType::Geo,
+            FieldIndex::BinaryIndex(_) => FieldIndexType::Bool,
+            FieldIndex::FullTextIndex(_) => FieldIndexType::FullText,
+        }
+    }
+
     pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
         match self {
             FieldIndex::IntIndex(index) => index.get_telemetry_data(),
             FieldIndex::IntMapIndex(index) => index.get_telemetry_data(),
             FieldIndex::KeywordIndex(index) => index.get_telemetry_data(),
             FieldIndex::FloatIndex(index) => index.get_telemetry_data(),
+            FieldIndex::DatetimeIndex(index) => index.get_telemetry_data(),
+            FieldIndex::UuidIndex(index) => index.get_telemetry_data(),
             FieldIndex::GeoIndex(index) => index.get_telemetry_data(),
             FieldIndex::BinaryIndex(index) => index.get_telemetry_data(),
             FieldIndex::FullTextIndex(index) => index.get_telemetry_data(),
@@ -312,6 +448,8 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(index) => index.values_count(point_id),
             FieldIndex::KeywordIndex(index) => index.values_count(point_id),
             FieldIndex::FloatIndex(index) => index.values_count(point_id),
+            FieldIndex::DatetimeIndex(index) => index.values_count(point_id),
+            FieldIndex::UuidIndex(index) => index.values_count(point_id),
             FieldIndex::GeoIndex(index) => index.values_count(point_id),
             FieldIndex::BinaryIndex(index) => index.values_count(point_id),
             FieldIndex::FullTextIndex(index) => index.values_count(point_id),
@@ -324,6 +462,8 @@ impl FieldIndex {
             FieldIndex::IntMapIndex(index) => index.values_is_empty(point_id),
             FieldIndex::KeywordIndex(index) => index.values_is_empty(point_id),
             FieldIndex::FloatIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::DatetimeIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::UuidIndex(index) => index.values_is_empty(point_id),
             FieldIndex::GeoIndex(index) => index.values_is_empty(point_id),
             FieldIndex::BinaryIndex(index) => index.values_is_empty(point_id),
             FieldIndex::FullTextIndex(index) => index.values_is_empty(point_id),
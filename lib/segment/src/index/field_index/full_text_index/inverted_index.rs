@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 
@@ -9,15 +9,24 @@ use crate::types::{FieldCondition, Match, MatchText, PayloadKeyType, PointOffset
 
 pub type TokenId = u32;
 
+/// Position of the word a token is produced from, within the text
+pub type TokenPosition = u32;
+
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
 pub struct Document {
     tokens: Vec<TokenId>,
+    /// Positions of each of the `tokens`, not known for documents stored before they were tracked
+    positions: Option<Vec<Vec<TokenPosition>>>,
 }
 
 impl Document {
-    pub fn new(mut tokens: Vec<TokenId>) -> Self {
-        tokens.sort_unstable();
-        Self { tokens }
+    pub fn new(tokens: BTreeMap<TokenId, Vec<TokenPosition>>) -> Self {
+        let (tokens, positions): (Vec<_>, Vec<Vec<_>>) = tokens.into_iter().unzip();
+        let positions = positions
+            .iter()
+            .all(|token_positions| !token_positions.is_empty())
+            .then_some(positions);
+        Self { tokens, positions }
     }
 
     pub fn len(&self) -> usize {
@@ -35,11 +44,40 @@ impl Document {
     pub fn check(&self, token: TokenId) -> bool {
         self.tokens.binary_search(&token).is_ok()
     }
+
+    /// Check that the phrase tokens are at the same offsets from each other in the document.
+    /// Documents without known positions only have to contain all the tokens.
+    pub fn check_phrase(&self, phrase: &[(TokenId, TokenPosition)]) -> bool {
+        let Some(positions) = &self.positions else {
+            return phrase.iter().all(|(token, _offset)| self.check(*token));
+        };
+        let token_positions = |token: &TokenId| {
+            self.tokens
+                .binary_search(token)
+                .map(|token_idx| positions[token_idx].as_slice())
+                .unwrap_or_default()
+        };
+        let Some(((first_token, first_offset), rest)) = phrase.split_first() else {
+            return true;
+        };
+        token_positions(first_token).iter().any(|first_position| {
+            let Some(start) = first_position.checked_sub(*first_offset) else {
+                return false;
+            };
+            rest.iter().all(|(token, offset)| {
+                token_positions(token)
+                    .binary_search(&(start + offset))
+                    .is_ok()
+            })
+        })
+    }
 }
 
 #[derive(Debug)]
 pub struct ParsedQuery {
     pub tokens: Vec<Option<TokenId>>,
+    /// Tokens with their offsets from the first one, if the query has to match as a phrase
+    pub phrase: Option<Vec<(TokenId, TokenPosition)>>,
 }
 
 impl ParsedQuery {
@@ -48,10 +86,23 @@ impl ParsedQuery {
             return false;
         }
         // Check that all tokens are in document
-        self.tokens
+        let has_tokens = self
+            .tokens
             .iter()
             // unwrap crash safety: all tokens exist in the vocabulary if it passes the above check
-            .all(|query_token| document.check(query_token.unwrap()))
+            .all(|query_token| document.check(query_token.unwrap()));
+        has_tokens
+            && self
+                .phrase
+                .as_ref()
+                .map_or(true, |phrase| document.check_phrase(phrase))
+    }
+
+    /// Phrase of more than one token can match fewer points than all of its tokens do
+    fn has_long_phrase(&self) -> bool {
+        self.phrase
+            .as_ref()
+            .map_or(false, |phrase| phrase.len() > 1)
     }
 }
 
@@ -68,9 +119,12 @@ impl InvertedIndex {
         Default::default()
     }
 
-    pub fn document_from_tokens(&mut self, tokens: &BTreeSet<String>) -> Document {
-        let mut document_tokens = vec![];
-        for token in tokens {
+    pub fn document_from_tokens(
+        &mut self,
+        tokens: &BTreeMap<String, Vec<TokenPosition>>,
+    ) -> Document {
+        let mut document_tokens = BTreeMap::new();
+        for (token, positions) in tokens {
             // check if in vocab
             let vocab_idx = match self.vocab.get(token) {
                 Some(&idx) => idx,
@@ -80,7 +134,7 @@ impl InvertedIndex {
                     next_token_id
                 }
             };
-            document_tokens.push(vocab_idx);
+            document_tokens.insert(vocab_idx, positions.clone());
         }
 
         Document::new(document_tokens)
@@ -133,7 +187,7 @@ impl InvertedIndex {
         Some(removed_doc)
     }
 
-    pub fn filter(&self, query: &ParsedQuery) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+    pub fn filter(&self, query: ParsedQuery) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
         let postings_opt: Option<Vec<_>> = query
             .tokens
             .iter()
@@ -153,7 +207,15 @@ impl InvertedIndex {
             // Empty request -> no matches
             return Box::new(vec![].into_iter());
         }
-        intersect_postings_iterator(postings)
+        let matches = intersect_postings_iterator(postings);
+        match query.phrase {
+            Some(phrase) => Box::new(matches.filter(move |idx| {
+                self.point_to_docs[*idx as usize]
+                    .as_ref()
+                    .map_or(false, |document| document.check_phrase(&phrase))
+            })),
+            None => matches,
+        }
     }
 
     pub fn estimate_cardinality(
@@ -195,7 +257,11 @@ impl InvertedIndex {
         return if postings.len() == 1 {
             CardinalityEstimation {
                 primary_clauses: vec![PrimaryCondition::Condition(condition.clone())],
-                min: smallest_posting,
+                min: if query.has_long_phrase() {
+                    0
+                } else {
+                    smallest_posting
+                },
                 exp: smallest_posting,
                 max: smallest_posting,
             }
@@ -194,3 +194,47 @@ fn test_prefix_search() {
 
     assert_eq!(res.len(), 0);
 }
+
+#[test]
+fn test_prefix_phrase_search() {
+    let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+    let config = TextIndexParams {
+        r#type: TextIndexType::Text,
+        tokenizer: TokenizerType::Prefix,
+        min_token_len: None,
+        max_token_len: None,
+        lowercase: None,
+    };
+
+    let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+    let mut index = FullTextIndex::new(db, config, "text");
+    index.recreate().unwrap();
+
+    let texts = get_texts();
+
+    for (i, text) in texts.iter().enumerate() {
+        index
+            .add_many(i as PointOffsetType, vec![text.to_string()])
+            .unwrap();
+    }
+
+    let query = index.parse_phrase("THE LA");
+    let res: Vec<_> = texts
+        .iter()
+        .enumerate()
+        .filter(|(idx, _text)| query.check_match(index.get_doc(*idx as PointOffsetType).unwrap()))
+        .map(|(_idx, text)| text.as_str())
+        .collect();
+
+    assert_eq!(
+        res,
+        vec!["The Last Answer", "The Last Question", "The Last Trump"]
+    );
+
+    // Both words are prefixes of the others, but not in this order
+    let query = index.parse_phrase("la the");
+    for (idx, _text) in texts.iter().enumerate() {
+        let doc = index.get_doc(idx as PointOffsetType).unwrap();
+        assert!(!query.check_match(doc));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::sync::Arc;
 
 use parking_lot::RwLock;
@@ -11,14 +11,16 @@ use crate::common::Flusher;
 use crate::data_types::text_index::TextIndexParams;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::index::field_index::full_text_index::inverted_index::{
-    Document, InvertedIndex, ParsedQuery,
+    Document, InvertedIndex, ParsedQuery, TokenPosition,
 };
 use crate::index::field_index::full_text_index::tokenizers::Tokenizer;
 use crate::index::field_index::{
     CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, ValueIndexer,
 };
 use crate::telemetry::PayloadIndexTelemetry;
-use crate::types::{FieldCondition, Match, PayloadKeyType, PointOffsetType};
+use crate::types::{
+    FieldCondition, Match, MatchPhrase, MatchText, PayloadKeyType, PointOffsetType,
+};
 
 pub struct FullTextIndex {
     inverted_index: InvertedIndex,
@@ -35,12 +37,17 @@ impl FullTextIndex {
         bincode::deserialize(data).unwrap()
     }
 
-    fn serialize_document_tokens(&self, tokens: BTreeSet<String>) -> OperationResult<Vec<u8>> {
+    fn serialize_document_tokens(
+        &self,
+        tokens: BTreeMap<String, Vec<TokenPosition>>,
+    ) -> OperationResult<Vec<u8>> {
         #[derive(Serialize)]
         struct StoredDocument {
             tokens: BTreeSet<String>,
+            positions: Vec<Vec<TokenPosition>>,
         }
-        let doc = StoredDocument { tokens };
+        let (tokens, positions) = tokens.into_iter().unzip();
+        let doc = StoredDocument { tokens, positions };
         serde_cbor::to_vec(&doc).map_err(|e| {
             OperationError::service_error(format!("Failed to serialize document: {e}"))
         })
@@ -50,12 +57,23 @@ impl FullTextIndex {
         #[derive(Deserialize)]
         struct StoredDocument {
             tokens: BTreeSet<String>,
+            /// Positions of each of the tokens, missing in documents stored before they were tracked
+            #[serde(default)]
+            positions: Vec<Vec<TokenPosition>>,
         }
         serde_cbor::from_slice::<StoredDocument>(data)
             .map_err(|e| {
                 OperationError::service_error(format!("Failed to deserialize document: {e}"))
             })
-            .map(|doc| index.document_from_tokens(&doc.tokens))
+            .map(|doc| {
+                let mut positions = doc.positions.into_iter();
+                let tokens = doc
+                    .tokens
+                    .into_iter()
+                    .map(|token| (token, positions.next().unwrap_or_default()))
+                    .collect();
+                index.document_from_tokens(&tokens)
+            })
     }
 
     fn storage_cf_name(field: &str) -> String {
@@ -99,14 +117,46 @@ impl FullTextIndex {
         });
         ParsedQuery {
             tokens: tokens.into_iter().collect(),
+            phrase: None,
+        }
+    }
+
+    pub fn parse_phrase(&self, text: &str) -> ParsedQuery {
+        let mut tokens = HashSet::new();
+        let mut phrase = vec![];
+        Tokenizer::tokenize_query_with_positions(text, &self.config, |token, position| {
+            let token_id = self.inverted_index.vocab.get(token).copied();
+            tokens.insert(token_id);
+            if let Some(token_id) = token_id {
+                phrase.push((token_id, position));
+            }
+        });
+        // Offsets from the first token, words dropped before it don't have to be in the document
+        if let Some(&(_, first_position)) = phrase.first() {
+            for (_, position) in &mut phrase {
+                *position -= first_position;
+            }
+        }
+        ParsedQuery {
+            tokens: tokens.into_iter().collect(),
+            phrase: Some(phrase),
+        }
+    }
+
+    /// Parse text or phrase match, other conditions are not served by this index
+    pub fn parse_match(&self, r#match: &Match) -> Option<ParsedQuery> {
+        match r#match {
+            Match::Text(MatchText { text }) => Some(self.parse_query(text)),
+            Match::Phrase(MatchPhrase { phrase }) => Some(self.parse_phrase(phrase)),
+            _ => None,
         }
     }
 
     pub fn parse_document(&self, text: &str) -> Document {
-        let mut document_tokens = vec![];
-        Tokenizer::tokenize_doc(text, &self.config, |token| {
+        let mut document_tokens: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        Tokenizer::tokenize_doc_with_positions(text, &self.config, |token, position| {
             if let Some(token_id) = self.inverted_index.vocab.get(token) {
-                document_tokens.push(*token_id);
+                document_tokens.entry(*token_id).or_default().push(position);
             }
         });
         Document::new(document_tokens)
@@ -115,7 +165,7 @@ impl FullTextIndex {
     #[cfg(test)]
     pub fn query(&self, query: &str) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
         let parsed_query = self.parse_query(query);
-        self.inverted_index.filter(&parsed_query)
+        self.inverted_index.filter(parsed_query)
     }
 
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
@@ -134,12 +184,19 @@ impl ValueIndexer<String> for FullTextIndex {
             return Ok(());
         }
 
-        let mut tokens: BTreeSet<String> = BTreeSet::new();
+        let mut tokens: BTreeMap<String, Vec<TokenPosition>> = BTreeMap::new();
 
+        let mut offset = 0;
         for value in values {
-            Tokenizer::tokenize_doc(&value, &self.config, |token| {
-                tokens.insert(token.to_owned());
-            });
+            let positions =
+                Tokenizer::tokenize_doc_with_positions(&value, &self.config, |token, position| {
+                    tokens
+                        .entry(token.to_owned())
+                        .or_default()
+                        .push(offset + position);
+                });
+            // Leave a gap between values, so a phrase doesn't match across them
+            offset += positions + 1;
         }
 
         let document = self.inverted_index.document_from_tokens(&tokens);
@@ -200,26 +257,24 @@ impl PayloadFieldIndex for FullTextIndex {
         self.db_wrapper.flusher()
     }
 
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.db_wrapper.size_bytes()
+    }
+
     fn filter(
         &self,
         condition: &FieldCondition,
     ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
-        if let Some(Match::Text(text_match)) = &condition.r#match {
-            let parsed_query = self.parse_query(&text_match.text);
-            return Some(self.inverted_index.filter(&parsed_query));
-        }
-        None
+        let parsed_query = self.parse_match(condition.r#match.as_ref()?)?;
+        Some(self.inverted_index.filter(parsed_query))
     }
 
     fn estimate_cardinality(&self, condition: &FieldCondition) -> Option<CardinalityEstimation> {
-        if let Some(Match::Text(text_match)) = &condition.r#match {
-            let parsed_query = self.parse_query(&text_match.text);
-            return Some(
-                self.inverted_index
-                    .estimate_cardinality(&parsed_query, condition),
-            );
-        }
-        None
+        let parsed_query = self.parse_match(condition.r#match.as_ref()?)?;
+        Some(
+            self.inverted_index
+                .estimate_cardinality(&parsed_query, condition),
+        )
     }
 
     fn payload_blocks(
@@ -255,6 +310,20 @@ mod tests {
         }
     }
 
+    fn phrase_request(phrase: &str) -> FieldCondition {
+        FieldCondition {
+            key: "text".to_owned(),
+            r#match: Some(Match::Phrase(MatchPhrase {
+                phrase: phrase.to_owned(),
+            })),
+            range: None,
+            geo_bounding_box: None,
+            geo_radius: None,
+            values_count: None,
+            geo_polygon: None,
+        }
+    }
+
     #[test]
     fn test_full_text_indexing() {
         let payloads: Vec<_> = vec![
@@ -345,4 +414,75 @@ mod tests {
             assert_eq!(search_res, vec![0, 1, 3, 4]);
         }
     }
+
+    #[test]
+    fn test_full_text_phrase() {
+        let payloads: Vec<_> = vec![
+            serde_json::json!("The last question was asked for the first time, half in jest"),
+            serde_json::json!("For the first time the question was asked in jest"),
+            serde_json::json!(["The question was asked", "for the first time"]),
+            serde_json::json!("Time for the first question"),
+        ];
+
+        let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+        let config = TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: None,
+            max_token_len: None,
+            lowercase: None,
+        };
+
+        let phrase_search = |index: &FullTextIndex, phrase: &str| -> Vec<_> {
+            let condition = phrase_request(phrase);
+            let result: Vec<_> = index.filter(&condition).unwrap().collect();
+            let estimation = index.estimate_cardinality(&condition).unwrap();
+            assert!(estimation.min <= result.len() && result.len() <= estimation.max);
+            result
+        };
+
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let mut index = FullTextIndex::new(db, config.clone(), "text");
+            index.recreate().unwrap();
+
+            for (idx, payload) in payloads.iter().enumerate() {
+                index
+                    .add_point(idx as PointOffsetType, &MultiValue::one(payload))
+                    .unwrap();
+            }
+
+            // All words of the phrase are in every document
+            let filter_condition = filter_request("first question");
+            let search_res: Vec<_> = index.filter(&filter_condition).unwrap().collect();
+            assert_eq!(search_res, vec![0, 1, 2, 3]);
+
+            assert_eq!(phrase_search(&index, "question was asked"), vec![0, 1, 2]);
+            assert_eq!(phrase_search(&index, "first question"), vec![3]);
+            // Words are in order, but not next to each other
+            assert!(phrase_search(&index, "question asked").is_empty());
+            // Phrase doesn't match across the values
+            assert_eq!(phrase_search(&index, "asked for"), vec![0]);
+            assert_eq!(phrase_search(&index, "Asked, for the FIRST time!"), vec![0]);
+
+            // Updated payload is reindexed
+            let payload = serde_json::json!("Asked for the first time");
+            index.remove_point(1).unwrap();
+            index.add_point(1, &MultiValue::one(&payload)).unwrap();
+            assert_eq!(phrase_search(&index, "asked for the first"), vec![0, 1]);
+            assert_eq!(phrase_search(&index, "question was asked"), vec![0, 2]);
+
+            index.flusher()().unwrap();
+        }
+
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let mut index = FullTextIndex::new(db, config, "text");
+            assert!(index.load().unwrap());
+
+            assert_eq!(phrase_search(&index, "asked for the first"), vec![0, 1]);
+            assert_eq!(phrase_search(&index, "question was asked"), vec![0, 2]);
+            assert_eq!(phrase_search(&index, "asked for"), vec![0, 1]);
+        }
+    }
 }
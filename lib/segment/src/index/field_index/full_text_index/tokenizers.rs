@@ -1,5 +1,8 @@
+use std::cell::Cell;
+
 use charabia::Tokenize;
 
+use super::inverted_index::TokenPosition;
 use crate::data_types::text_index::{TextIndexParams, TokenizerType};
 
 struct WhiteSpaceTokenizer;
@@ -104,34 +107,82 @@ impl Tokenizer {
         }
     }
 
+    #[cfg(test)]
     pub fn tokenize_doc<C: FnMut(&str)>(text: &str, config: &TextIndexParams, mut callback: C) {
-        let token_filter = Self::doc_token_filter(config, &mut callback);
+        Self::tokenize_doc_with_positions(text, config, |token, _position| callback(token));
+    }
+
+    pub fn tokenize_query<C: FnMut(&str)>(text: &str, config: &TextIndexParams, mut callback: C) {
+        Self::tokenize_query_with_positions(text, config, |token, _position| callback(token));
+    }
+
+    /// Tokenize document, along with positions of the words tokens are produced from.
+    /// Words dropped by the token length limits still take their positions.
+    ///
+    /// Returns number of positions in the text
+    pub fn tokenize_doc_with_positions<C: FnMut(&str, TokenPosition)>(
+        text: &str,
+        config: &TextIndexParams,
+        mut callback: C,
+    ) -> TokenPosition {
+        let position = Cell::new(0);
+        let mut token_filter =
+            Self::doc_token_filter(config, |token| callback(token, position.get()));
         match config.tokenizer {
-            TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, token_filter),
-            TokenizerType::Word => WordTokenizer::tokenize(text, token_filter),
-            TokenizerType::Multilingual => MultilingualTokenizer::tokenize(text, token_filter),
-            TokenizerType::Prefix => PrefixTokenizer::tokenize(
-                text,
-                config.min_token_len.unwrap_or(1),
-                config.max_token_len.unwrap_or(usize::MAX),
-                token_filter,
-            ),
+            TokenizerType::Whitespace => {
+                WhiteSpaceTokenizer::tokenize(text, Self::positioned(&position, token_filter))
+            }
+            TokenizerType::Word => {
+                WordTokenizer::tokenize(text, Self::positioned(&position, token_filter))
+            }
+            TokenizerType::Multilingual => {
+                MultilingualTokenizer::tokenize(text, Self::positioned(&position, token_filter))
+            }
+            // All prefixes of a word share its position
+            TokenizerType::Prefix => WordTokenizer::tokenize(text, |word| {
+                PrefixTokenizer::tokenize(
+                    word,
+                    config.min_token_len.unwrap_or(1),
+                    config.max_token_len.unwrap_or(usize::MAX),
+                    &mut token_filter,
+                );
+                position.set(position.get() + 1);
+            }),
         }
+        position.get()
     }
 
-    pub fn tokenize_query<C: FnMut(&str)>(text: &str, config: &TextIndexParams, mut callback: C) {
-        let token_filter = Self::doc_token_filter(config, &mut callback);
+    /// Tokenize query, along with positions of the words tokens are produced from
+    pub fn tokenize_query_with_positions<C: FnMut(&str, TokenPosition)>(
+        text: &str,
+        config: &TextIndexParams,
+        mut callback: C,
+    ) {
+        let position = Cell::new(0);
+        let token_filter = Self::doc_token_filter(config, |token| callback(token, position.get()));
+        let positioned_filter = Self::positioned(&position, token_filter);
         match config.tokenizer {
-            TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, token_filter),
-            TokenizerType::Word => WordTokenizer::tokenize(text, token_filter),
-            TokenizerType::Multilingual => MultilingualTokenizer::tokenize(text, token_filter),
+            TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, positioned_filter),
+            TokenizerType::Word => WordTokenizer::tokenize(text, positioned_filter),
+            TokenizerType::Multilingual => MultilingualTokenizer::tokenize(text, positioned_filter),
             TokenizerType::Prefix => PrefixTokenizer::tokenize_query(
                 text,
                 config.max_token_len.unwrap_or(usize::MAX),
-                token_filter,
+                positioned_filter,
             ),
         }
     }
+
+    /// Advance the position after each word
+    fn positioned<'a, C: FnMut(&str) + 'a>(
+        position: &'a Cell<TokenPosition>,
+        mut callback: C,
+    ) -> impl FnMut(&str) + 'a {
+        move |word: &str| {
+            callback(word);
+            position.set(position.get() + 1);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -267,4 +318,42 @@ mod tests {
         assert_eq!(tokens.get(5), Some(&"ми".to_owned()));
         assert_eq!(tokens.get(6), Some(&"мир".to_owned()));
     }
+
+    #[test]
+    fn test_tokenizer_positions() {
+        let mut config = TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: Some(2),
+            max_token_len: Some(4),
+            lowercase: None,
+        };
+        let text = "Hello, a big World!";
+
+        let mut tokens = Vec::new();
+        let positions = Tokenizer::tokenize_doc_with_positions(text, &config, |token, position| {
+            tokens.push((token.to_owned(), position))
+        });
+        // Words dropped by the length limits still take their positions
+        assert_eq!(positions, 4);
+        assert_eq!(tokens, vec![("big".to_owned(), 2)]);
+
+        config.tokenizer = TokenizerType::Prefix;
+        let mut tokens = Vec::new();
+        Tokenizer::tokenize_doc_with_positions(text, &config, |token, position| {
+            tokens.push((token.to_owned(), position))
+        });
+        let expected = [("he", 0), ("hel", 0), ("hell", 0), ("bi", 2), ("big", 2)]
+            .into_iter()
+            .chain([("wo", 3), ("wor", 3), ("worl", 3)])
+            .map(|(token, position)| (token.to_owned(), position))
+            .collect::<Vec<_>>();
+        assert_eq!(tokens, expected);
+
+        let mut tokens = Vec::new();
+        Tokenizer::tokenize_query_with_positions("big world", &config, |token, position| {
+            tokens.push((token.to_owned(), position))
+        });
+        assert_eq!(tokens, vec![("big".to_owned(), 0), ("worl".to_owned(), 1)]);
+    }
 }
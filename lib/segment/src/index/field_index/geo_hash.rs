@@ -200,15 +200,23 @@ fn check_circle_intersection(geohash: &str, circle: &GeoRadius) -> bool {
 }
 
 /// Check if geohash tile intersects the polygon
+///
+/// Only the exterior ring is taken into account, so the result may include tiles
+/// which are completely covered by one of the holes.
 fn check_polygon_intersection(geohash: &str, polygon: &GeoPolygon) -> bool {
     let precision = geohash.len();
     if precision == 0 {
         return true;
     }
+    if polygon.crosses_antimeridian() {
+        // Planar intersection is not defined for such polygons, rely on the bounding box only
+        return true;
+    }
     let rect = decode_bbox(geohash).unwrap();
 
     #[allow(deprecated)]
     let polygon_ring: Vec<Coordinate<f64>> = polygon
+        .exterior
         .points
         .iter()
         .map(|point| Coordinate {
@@ -339,12 +347,21 @@ fn minimum_bounding_rectangle_for_polygon(polygon: &GeoPolygon) -> GeoBoundingBo
     let mut min_lat = std::f64::MAX;
     let mut max_lat = std::f64::MIN;
 
-    for point in &polygon.points {
-        if point.lon < min_lon {
-            min_lon = point.lon;
+    // Polygons crossing the antimeridian are measured in the shifted `[0; 360)` longitude range,
+    // so `top_left.lon` might end up greater than `bottom_right.lon`
+    let crosses_antimeridian = polygon.crosses_antimeridian();
+
+    for point in &polygon.exterior.points {
+        let lon = if crosses_antimeridian && point.lon < 0.0 {
+            point.lon + 360.0
+        } else {
+            point.lon
+        };
+        if lon < min_lon {
+            min_lon = lon;
         }
-        if point.lon > max_lon {
-            max_lon = point.lon;
+        if lon > max_lon {
+            max_lon = lon;
         }
         if point.lat < min_lat {
             min_lat = point.lat;
@@ -355,11 +372,11 @@ fn minimum_bounding_rectangle_for_polygon(polygon: &GeoPolygon) -> GeoBoundingBo
     }
 
     let top_left = GeoPoint {
-        lon: min_lon,
+        lon: sphere_lon(min_lon),
         lat: max_lat,
     };
     let bottom_right = GeoPoint {
-        lon: max_lon,
+        lon: sphere_lon(max_lon),
         lat: min_lat,
     };
 
@@ -375,6 +392,7 @@ mod tests {
     use rand::{Rng, SeedableRng};
 
     use super::*;
+    use crate::types::GeoLineString;
 
     const BERLIN: GeoPoint = GeoPoint {
         lat: 52.52437,
@@ -534,28 +552,31 @@ mod tests {
         // conversion to lon/lat http://geohash.co/
         // "dr5ruj4477kd"
         let near_nyc_polygon = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -74.00101399,
-                    lat: 40.76517460,
-                },
-                GeoPoint {
-                    lon: -73.98201792,
-                    lat: 40.76517460,
-                },
-                GeoPoint {
-                    lon: -73.98201792,
-                    lat: 40.75078539,
-                },
-                GeoPoint {
-                    lon: -74.00101399,
-                    lat: 40.75078539,
-                },
-                GeoPoint {
-                    lon: -74.00101399,
-                    lat: 40.76517460,
-                },
-            ],
+            exterior: GeoLineString {
+                points: vec![
+                    GeoPoint {
+                        lon: -74.00101399,
+                        lat: 40.76517460,
+                    },
+                    GeoPoint {
+                        lon: -73.98201792,
+                        lat: 40.76517460,
+                    },
+                    GeoPoint {
+                        lon: -73.98201792,
+                        lat: 40.75078539,
+                    },
+                    GeoPoint {
+                        lon: -74.00101399,
+                        lat: 40.75078539,
+                    },
+                    GeoPoint {
+                        lon: -74.00101399,
+                        lat: 40.76517460,
+                    },
+                ],
+            },
+            interiors: None,
         };
 
         let nyc_hashes = polygon_hashes(&near_nyc_polygon, 200);
@@ -576,6 +597,43 @@ mod tests {
         assert_eq!(nyc_hashes, ["dr5ru"]);
     }
 
+    #[test]
+    fn polygon_hashes_crossing_antimeridian() {
+        let ring = [
+            (170.0, -10.0),
+            (-170.0, -10.0),
+            (-170.0, 10.0),
+            (170.0, 10.0),
+        ];
+        let polygon = GeoPolygon::new(
+            GeoLineString {
+                points: ring
+                    .iter()
+                    .chain(ring.first())
+                    .map(|&(lon, lat)| GeoPoint { lon, lat })
+                    .collect(),
+            },
+            None,
+        )
+        .unwrap();
+
+        let bounding_box = minimum_bounding_rectangle_for_polygon(&polygon);
+        assert_eq!(bounding_box.top_left.lon, 170.0);
+        assert_eq!(bounding_box.bottom_right.lon, -170.0);
+
+        let hashes = polygon_hashes(&polygon, 16);
+        assert!(hashes.len() <= 16);
+        for (lon, lat) in [(175.0, 0.0), (-175.0, 0.0), (179.9, 9.0), (-179.9, -9.0)] {
+            let point_hash = encode_max_precision(lon, lat).unwrap();
+            assert!(
+                hashes
+                    .iter()
+                    .any(|hash| point_hash.starts_with(hash.as_str())),
+                "point ({lon}, {lat}) is not covered by {hashes:?}",
+            );
+        }
+    }
+
     #[test]
     fn random_circles() {
         let mut rnd = StdRng::seed_from_u64(42);
@@ -602,140 +660,155 @@ mod tests {
 
         // Test a polygon that intersects with the geohash
         let polygon_1 = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -120.0,
-                    lat: -30.0,
-                },
-                GeoPoint {
-                    lon: -150.0,
-                    lat: -30.0,
-                },
-                GeoPoint {
-                    lon: -150.0,
-                    lat: 40.0,
-                },
-                GeoPoint {
-                    lon: -120.0,
-                    lat: 40.0,
-                },
-                GeoPoint {
-                    lon: -135.0,
-                    lat: -1.0,
-                },
-            ],
+            exterior: GeoLineString {
+                points: vec![
+                    GeoPoint {
+                        lon: -120.0,
+                        lat: -30.0,
+                    },
+                    GeoPoint {
+                        lon: -150.0,
+                        lat: -30.0,
+                    },
+                    GeoPoint {
+                        lon: -150.0,
+                        lat: 40.0,
+                    },
+                    GeoPoint {
+                        lon: -120.0,
+                        lat: 40.0,
+                    },
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: -1.0,
+                    },
+                ],
+            },
+            interiors: None,
         };
         let intersect_1 = check_polygon_intersection(&geohash, &polygon_1);
         assert!(intersect_1);
 
         // Test a polygon that does not intersect with the geohash
         let polygon_2 = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -80.0,
-                    lat: -30.0,
-                },
-                GeoPoint {
-                    lon: -80.0,
-                    lat: 40.0,
-                },
-                GeoPoint {
-                    lon: -20.0,
-                    lat: 40.0,
-                },
-                GeoPoint {
-                    lon: -20.0,
-                    lat: -30.0,
-                },
-                GeoPoint {
-                    lon: -135.0,
-                    lat: -1.0,
-                },
-            ],
+            exterior: GeoLineString {
+                points: vec![
+                    GeoPoint {
+                        lon: -80.0,
+                        lat: -30.0,
+                    },
+                    GeoPoint {
+                        lon: -80.0,
+                        lat: 40.0,
+                    },
+                    GeoPoint {
+                        lon: -20.0,
+                        lat: 40.0,
+                    },
+                    GeoPoint {
+                        lon: -20.0,
+                        lat: -30.0,
+                    },
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: -1.0,
+                    },
+                ],
+            },
+            interiors: None,
         };
         let intersect_2 = check_polygon_intersection(&geohash, &polygon_2);
         assert!(!intersect_2);
 
         // Test a polygon that overlaps with the geohash
         let polygon_3 = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -135.0,
-                    lat: 0.0,
-                },
-                GeoPoint {
-                    lon: -90.0,
-                    lat: 0.0,
-                },
-                GeoPoint {
-                    lon: -90.0,
-                    lat: 45.0,
-                },
-                GeoPoint {
-                    lon: -135.0,
-                    lat: 45.0,
-                },
-                GeoPoint {
-                    lon: -135.0,
-                    lat: -1.0,
-                },
-            ],
+            exterior: GeoLineString {
+                points: vec![
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: 0.0,
+                    },
+                    GeoPoint {
+                        lon: -90.0,
+                        lat: 0.0,
+                    },
+                    GeoPoint {
+                        lon: -90.0,
+                        lat: 45.0,
+                    },
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: 45.0,
+                    },
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: -1.0,
+                    },
+                ],
+            },
+            interiors: None,
         };
         let intersect_3 = check_polygon_intersection(&geohash, &polygon_3);
         assert!(intersect_3);
 
         // Test a polygon that only share one edge
         let polygon_4 = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -135.0,
-                    lat: -1.0,
-                },
-                GeoPoint {
-                    lon: -150.0,
-                    lat: -1.0,
-                },
-                GeoPoint {
-                    lon: -150.0,
-                    lat: 46.0,
-                },
-                GeoPoint {
-                    lon: -135.0,
-                    lat: 46.0,
-                },
-                GeoPoint {
-                    lon: -135.0,
-                    lat: -1.0,
-                },
-            ],
+            exterior: GeoLineString {
+                points: vec![
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: -1.0,
+                    },
+                    GeoPoint {
+                        lon: -150.0,
+                        lat: -1.0,
+                    },
+                    GeoPoint {
+                        lon: -150.0,
+                        lat: 46.0,
+                    },
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: 46.0,
+                    },
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: -1.0,
+                    },
+                ],
+            },
+            interiors: None,
         };
         let intersect_4 = check_polygon_intersection(&geohash, &polygon_4);
         assert!(intersect_4);
 
         // Test a geohash that is within the geohash
         let polygon_5 = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -134.0,
-                    lat: 1.0,
-                },
-                GeoPoint {
-                    lon: -91.0,
-                    lat: 1.0,
-                },
-                GeoPoint {
-                    lon: -91.0,
-                    lat: 44.0,
-                },
-                GeoPoint {
-                    lon: -134.0,
-                    lat: 44.0,
-                },
-                GeoPoint {
-                    lon: -135.0,
-                    lat: -1.0,
-                },
-            ],
+            exterior: GeoLineString {
+                points: vec![
+                    GeoPoint {
+                        lon: -134.0,
+                        lat: 1.0,
+                    },
+                    GeoPoint {
+                        lon: -91.0,
+                        lat: 1.0,
+                    },
+                    GeoPoint {
+                        lon: -91.0,
+                        lat: 44.0,
+                    },
+                    GeoPoint {
+                        lon: -134.0,
+                        lat: 44.0,
+                    },
+                    GeoPoint {
+                        lon: -135.0,
+                        lat: -1.0,
+                    },
+                ],
+            },
+            interiors: None,
         };
         let intersect_5 = check_polygon_intersection(&geohash, &polygon_5);
         assert!(intersect_5);
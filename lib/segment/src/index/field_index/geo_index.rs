@@ -519,6 +519,10 @@ impl PayloadFieldIndex for GeoMapIndex {
         GeoMapIndex::flusher(self)
     }
 
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.db_wrapper.size_bytes()
+    }
+
     fn filter(
         &self,
         condition: &FieldCondition,
@@ -587,6 +591,15 @@ impl PayloadFieldIndex for GeoMapIndex {
             return Some(estimation);
         }
 
+        if let Some(geo_polygon) = &condition.geo_polygon {
+            let geo_hashes = polygon_hashes(geo_polygon, GEO_QUERY_MAX_REGION);
+            let mut estimation = self.match_cardinality(&geo_hashes);
+            estimation
+                .primary_clauses
+                .push(PrimaryCondition::Condition(condition.clone()));
+            return Some(estimation);
+        }
+
         None
     }
 
@@ -620,7 +633,7 @@ mod tests {
     use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
     use crate::common::utils::MultiValue;
     use crate::fixtures::payload_fixtures::random_geo_payload;
-    use crate::types::GeoRadius;
+    use crate::types::{GeoLineString, GeoPolygon, GeoRadius};
 
     const NYC: GeoPoint = GeoPoint {
         lat: 40.75798,
@@ -733,6 +746,86 @@ mod tests {
         assert_eq!(matched_points, indexed_matched_points);
     }
 
+    fn polygon_around_nyc_with_hole() -> GeoPolygon {
+        let ring = |delta: f64| GeoLineString {
+            points: vec![
+                GeoPoint {
+                    lon: NYC.lon - delta,
+                    lat: NYC.lat - delta,
+                },
+                GeoPoint {
+                    lon: NYC.lon + delta,
+                    lat: NYC.lat - delta,
+                },
+                GeoPoint {
+                    lon: NYC.lon + delta,
+                    lat: NYC.lat + delta,
+                },
+                GeoPoint {
+                    lon: NYC.lon - delta,
+                    lat: NYC.lat + delta,
+                },
+                GeoPoint {
+                    lon: NYC.lon - delta,
+                    lat: NYC.lat - delta,
+                },
+            ],
+        };
+        GeoPolygon::new(ring(20.0), Some(vec![ring(5.0)])).unwrap()
+    }
+
+    #[test]
+    fn match_polygon_cardinality() {
+        let geo_polygon = polygon_around_nyc_with_hole();
+
+        let field_index = build_random_index(500, 20);
+
+        let nyc_hashes = polygon_hashes(&geo_polygon, GEO_QUERY_MAX_REGION);
+
+        let exact_points_for_hashes = field_index.get_iterator(nyc_hashes).collect_vec();
+        let real_cardinality = exact_points_for_hashes.len();
+
+        let field_condition = FieldCondition::new_geo_polygon("test".to_string(), geo_polygon);
+        let card = field_index.estimate_cardinality(&field_condition).unwrap();
+
+        assert!(card.min <= real_cardinality);
+        assert!(card.max >= real_cardinality);
+
+        assert!(card.exp >= card.min);
+        assert!(card.exp <= card.max);
+    }
+
+    #[test]
+    fn geo_indexed_polygon_filtering() {
+        let geo_polygon = polygon_around_nyc_with_hole();
+
+        let field_index = build_random_index(1000, 5);
+
+        let mut matched_points = field_index
+            .point_to_values
+            .iter()
+            .enumerate()
+            .filter(|(_idx, geo_points)| {
+                geo_points
+                    .iter()
+                    .any(|geo_point| geo_polygon.check_point(geo_point.lon, geo_point.lat))
+            })
+            .map(|(idx, _geo_points)| idx as PointOffsetType)
+            .collect_vec();
+
+        assert!(!matched_points.is_empty());
+
+        let field_condition = FieldCondition::new_geo_polygon("test".to_string(), geo_polygon);
+
+        let mut indexed_matched_points =
+            field_index.filter(&field_condition).unwrap().collect_vec();
+
+        matched_points.sort_unstable();
+        indexed_matched_points.sort_unstable();
+
+        assert_eq!(matched_points, indexed_matched_points);
+    }
+
     #[test]
     fn test_payload_blocks() {
         let field_index = build_random_index(1000, 5);
@@ -4,6 +4,8 @@ use parking_lot::RwLock;
 use rocksdb::DB;
 
 use super::binary_index::BinaryIndex;
+use super::datetime_index::DatetimeIndex;
+use super::uuid_index::UuidIndex;
 use crate::index::field_index::full_text_index::text_index::FullTextIndex;
 use crate::index::field_index::geo_index::GeoMapIndex;
 use crate::index::field_index::map_index::MapIndex;
@@ -40,11 +42,30 @@ pub fn index_selector(
                 field,
             ))],
             PayloadSchemaType::Bool => vec![FieldIndex::BinaryIndex(BinaryIndex::new(db, field))],
+            PayloadSchemaType::Datetime => {
+                vec![FieldIndex::DatetimeIndex(DatetimeIndex::new(db, field))]
+            }
+            PayloadSchemaType::Uuid => vec![FieldIndex::UuidIndex(UuidIndex::new(
+                db,
+                Default::default(),
+                field,
+            ))],
         },
         PayloadFieldSchema::FieldParams(payload_params) => match payload_params {
+            PayloadSchemaParams::Keyword(keyword_index_params) => {
+                let index = if keyword_index_params.on_disk() {
+                    MapIndex::new_on_disk(db, field)
+                } else {
+                    MapIndex::new(db, field)
+                };
+                vec![FieldIndex::KeywordIndex(index)]
+            }
             PayloadSchemaParams::Text(text_index_params) => vec![FieldIndex::FullTextIndex(
                 FullTextIndex::new(db, text_index_params.clone(), field),
             )],
+            PayloadSchemaParams::Uuid(uuid_index_params) => vec![FieldIndex::UuidIndex(
+                UuidIndex::new(db, uuid_index_params.clone(), field),
+            )],
         },
     }
 }
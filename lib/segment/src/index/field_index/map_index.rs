@@ -2,18 +2,21 @@ use std::collections::{BTreeSet, HashMap};
 use std::fmt::Display;
 use std::hash::Hash;
 use std::iter;
+use std::mem::size_of;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use itertools::Itertools;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rocksdb::DB;
 use serde_json::Value;
 use smol_str::SmolStr;
+use uuid::Uuid;
 
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
 use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::index::field_index::postings_cache::PostingsCache;
 use crate::index::field_index::stat_tools::number_of_selected_points;
 use crate::index::field_index::{
     CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
@@ -22,13 +25,91 @@ use crate::index::query_estimator::combine_should_estimations;
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     AnyVariants, FieldCondition, IntPayloadType, Match, MatchAny, MatchExcept, MatchValue,
-    PayloadKeyType, PointOffsetType, ValueVariants,
+    PayloadKeyType, PointOffsetType, UuidIntType, ValueVariants,
 };
 use crate::vector_storage::div_ceil;
 
+/// Max number of point ids, which an on-disk index keeps cached in RAM
+const ON_DISK_POSTINGS_CACHE_SIZE: usize = 64 * 1024;
+
+/// Point ids of each indexed value
+enum MapIndexPostings<N: Hash + Eq> {
+    /// All point ids are kept in RAM
+    InMemory(HashMap<N, BTreeSet<PointOffsetType>>),
+    /// Only the number of points per value is kept in RAM.
+    /// Point ids are read from the database on demand, the most requested ones are cached.
+    OnDisk {
+        counts: HashMap<N, usize>,
+        cache: Mutex<PostingsCache<N>>,
+    },
+}
+
+impl<N: Hash + Eq + Clone> MapIndexPostings<N> {
+    fn insert(&mut self, value: &N, idx: PointOffsetType) {
+        match self {
+            MapIndexPostings::InMemory(map) => {
+                map.entry(value.clone()).or_default().insert(idx);
+            }
+            MapIndexPostings::OnDisk { counts, cache } => {
+                *counts.entry(value.clone()).or_default() += 1;
+                cache.get_mut().invalidate(value);
+            }
+        }
+    }
+
+    fn remove(&mut self, value: &N, idx: PointOffsetType) {
+        match self {
+            MapIndexPostings::InMemory(map) => {
+                if let Some(vals) = map.get_mut(value) {
+                    vals.remove(&idx);
+                }
+            }
+            MapIndexPostings::OnDisk { counts, cache } => {
+                if let Some(count) = counts.get_mut(value) {
+                    *count = count.saturating_sub(1);
+                }
+                cache.get_mut().invalidate(value);
+            }
+        }
+    }
+
+    /// Number of points which have the value
+    fn count<Q>(&self, value: &Q) -> usize
+    where
+        Q: ?Sized,
+        N: std::borrow::Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        match self {
+            MapIndexPostings::InMemory(map) => map.get(value).map(|p| p.len()).unwrap_or(0),
+            MapIndexPostings::OnDisk { counts, .. } => counts.get(value).copied().unwrap_or(0),
+        }
+    }
+
+    /// Number of distinct values, including the ones which are not used by any point anymore
+    fn len(&self) -> usize {
+        match self {
+            MapIndexPostings::InMemory(map) => map.len(),
+            MapIndexPostings::OnDisk { counts, .. } => counts.len(),
+        }
+    }
+
+    /// Iterate over values and the number of points which have them
+    fn iter_counts(&self) -> Box<dyn Iterator<Item = (&N, usize)> + '_> {
+        match self {
+            MapIndexPostings::InMemory(map) => {
+                Box::new(map.iter().map(|(value, points)| (value, points.len())))
+            }
+            MapIndexPostings::OnDisk { counts, .. } => {
+                Box::new(counts.iter().map(|(value, count)| (value, *count)))
+            }
+        }
+    }
+}
+
 /// HashMap-based type of index
 pub struct MapIndex<N: Hash + Eq + Clone + Display> {
-    map: HashMap<N, BTreeSet<PointOffsetType>>,
+    postings: MapIndexPostings<N>,
     point_to_values: Vec<Vec<N>>,
     /// Amount of point which have at least one indexed payload value
     indexed_points: usize,
@@ -41,7 +122,26 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         let store_cf_name = Self::storage_cf_name(field_name);
         let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
         MapIndex {
-            map: Default::default(),
+            postings: MapIndexPostings::InMemory(Default::default()),
+            point_to_values: Vec::new(),
+            indexed_points: 0,
+            values_count: 0,
+            db_wrapper,
+        }
+    }
+
+    /// Index, which doesn't keep point ids of the values in RAM, but reads them from disk.
+    ///
+    /// It is stored in a separate column family, so switching between in-memory and on-disk
+    /// index builds a new one without touching the data of the index which is currently in use.
+    pub fn new_on_disk(db: Arc<RwLock<DB>>, field_name: &str) -> MapIndex<N> {
+        let store_cf_name = Self::on_disk_storage_cf_name(field_name);
+        let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
+        MapIndex {
+            postings: MapIndexPostings::OnDisk {
+                counts: Default::default(),
+                cache: Mutex::new(PostingsCache::new(ON_DISK_POSTINGS_CACHE_SIZE)),
+            },
             point_to_values: Vec::new(),
             indexed_points: 0,
             values_count: 0,
@@ -53,6 +153,14 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         format!("{field}_map")
     }
 
+    fn on_disk_storage_cf_name(field: &str) -> String {
+        format!("{field}_map_on_disk")
+    }
+
+    pub fn is_on_disk(&self) -> bool {
+        matches!(self.postings, MapIndexPostings::OnDisk { .. })
+    }
+
     pub fn recreate(&self) -> OperationResult<()> {
         self.db_wrapper.recreate_column_family()
     }
@@ -74,8 +182,8 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
                 self.indexed_points += 1;
             }
             self.values_count += 1;
-            self.point_to_values[idx as usize].push(value.clone());
-            self.map.entry(value).or_default().insert(idx);
+            self.postings.insert(&value, idx);
+            self.point_to_values[idx as usize].push(value);
         }
         Ok(true)
     }
@@ -90,7 +198,7 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         N: std::borrow::Borrow<Q>,
         Q: Hash + Eq,
     {
-        let values_count = self.map.get(value).map(|p| p.len()).unwrap_or(0);
+        let values_count = self.postings.count(value);
 
         CardinalityEstimation::exact(values_count)
     }
@@ -99,6 +207,30 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         self.point_to_values.get(idx as usize)
     }
 
+    /// Iterate over indexed values and the number of points which have them,
+    /// skipping values without points
+    pub fn iter_counts_per_value(&self) -> impl Iterator<Item = (&N, usize)> + '_ {
+        self.postings.iter_counts().filter(|(_, count)| *count > 0)
+    }
+
+    fn iter_values(&self) -> impl Iterator<Item = &N> + '_ {
+        self.postings.iter_counts().map(|(value, _)| value)
+    }
+
+    /// Approximate amount of RAM occupied by point ids of the indexed values, in bytes
+    pub fn postings_ram_usage_bytes(&self) -> usize {
+        match &self.postings {
+            MapIndexPostings::InMemory(map) => map
+                .values()
+                .map(|points| points.len() * size_of::<PointOffsetType>())
+                .sum(),
+            MapIndexPostings::OnDisk { counts, cache } => {
+                counts.len() * size_of::<usize>()
+                    + cache.lock().size() * size_of::<PointOffsetType>()
+            }
+        }
+    }
+
     pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
         PayloadIndexTelemetry {
             field_name: None,
@@ -121,9 +253,8 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
             self.point_to_values.resize(idx as usize + 1, Vec::new())
         }
         self.point_to_values[idx as usize] = values.into_iter().map(|v| v.into()).collect();
-        for value in &self.point_to_values[idx as usize] {
-            let entry = self.map.entry(value.clone()).or_default();
-            entry.insert(idx);
+        for value in self.point_to_values[idx as usize].iter().unique() {
+            self.postings.insert(value, idx);
 
             let db_record = Self::encode_db_record(value, idx);
             self.db_wrapper.put(db_record, [])?;
@@ -132,16 +263,69 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         Ok(())
     }
 
-    fn get_iterator<Q>(&self, value: &Q) -> Box<dyn Iterator<Item = PointOffsetType> + '_>
+    /// Iterate over points which have the value, in ascending order
+    pub fn get_iterator<Q>(&self, value: &Q) -> Box<dyn Iterator<Item = PointOffsetType> + '_>
     where
         Q: ?Sized,
         N: std::borrow::Borrow<Q>,
         Q: Hash + Eq,
     {
-        self.map
-            .get(value)
-            .map(|ids| Box::new(ids.iter().copied()) as Box<dyn Iterator<Item = PointOffsetType>>)
-            .unwrap_or_else(|| Box::new(iter::empty::<PointOffsetType>()))
+        match &self.postings {
+            MapIndexPostings::InMemory(map) => map
+                .get(value)
+                .map(|ids| {
+                    Box::new(ids.iter().copied()) as Box<dyn Iterator<Item = PointOffsetType>>
+                })
+                .unwrap_or_else(|| Box::new(iter::empty::<PointOffsetType>())),
+            MapIndexPostings::OnDisk { counts, cache } => {
+                let value = match counts.get_key_value(value) {
+                    Some((value, count)) if *count > 0 => value,
+                    _ => return Box::new(iter::empty::<PointOffsetType>()),
+                };
+                let cached = cache.lock().get(value);
+                let points = match cached {
+                    Some(points) => points,
+                    None => {
+                        let points = match self.read_postings(value) {
+                            Ok(points) => Arc::new(points),
+                            Err(err) => {
+                                log::error!(
+                                    "Failed to read points of value {value} from index: {err}"
+                                );
+                                Arc::default()
+                            }
+                        };
+                        cache.lock().insert(value.clone(), points.clone());
+                        points
+                    }
+                };
+                Box::new((0..points.len()).map(move |i| points[i]))
+            }
+        }
+    }
+
+    /// Read ids of the points which have the value from the database
+    fn read_postings(&self, value: &N) -> OperationResult<Vec<PointOffsetType>> {
+        let prefix = format!("{value}/");
+        let db = self.db_wrapper.lock_db();
+        let mut points = Vec::with_capacity(self.postings.count(value));
+        for (record, _) in db.iter_from(prefix.as_bytes())? {
+            if !record.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            let record = std::str::from_utf8(&record).map_err(|_| {
+                OperationError::service_error("Index read error: UTF8 error while DB parsing")
+            })?;
+            let (record_value, idx) = Self::decode_db_record(record)?;
+            // Records of longer values, which start with this value followed by `/`,
+            // are stored in between
+            if &record_value == value {
+                points.push(idx);
+            }
+        }
+        // Ids are ordered as strings in the database
+        points.sort_unstable();
+        Ok(points)
     }
 
     fn encode_db_record(value: &N, idx: PointOffsetType) -> String {
@@ -177,10 +361,8 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         }
         self.values_count -= removed_values.len();
 
-        for value in &removed_values {
-            if let Some(vals) = self.map.get_mut(value) {
-                vals.remove(&idx);
-            }
+        for value in removed_values.iter().unique() {
+            self.postings.remove(value, idx);
             let key = MapIndex::encode_db_record(value, idx);
             self.db_wrapper.remove(key)?;
         }
@@ -257,12 +439,7 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
 
         // todo
         let excluded_value_counts: Vec<_> = excluded
-            .map(|val| {
-                self.map
-                    .get(val.borrow())
-                    .map(|points| points.len())
-                    .unwrap_or(0)
-            })
+            .map(|val| self.postings.count(val.borrow()))
             .collect();
         let total_excluded_value_count: usize = excluded_value_counts.iter().sum();
 
@@ -270,7 +447,10 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
 
         let non_excluded_values_count =
             self.values_count.saturating_sub(total_excluded_value_count);
-        let max_values_per_point = self.map.len().saturating_sub(excluded_value_counts.len());
+        let max_values_per_point = self
+            .postings
+            .len()
+            .saturating_sub(excluded_value_counts.len());
 
         if max_values_per_point == 0 {
             // All points are excluded, so we can't select any point
@@ -321,8 +501,7 @@ impl<N: Hash + Eq + Clone + Display + FromStr> MapIndex<N> {
         Q: PartialEq<N>,
     {
         let iter = self
-            .map
-            .keys()
+            .iter_values()
             .filter(|key| !excluded.iter().any(|e| e.eq(*key)))
             .flat_map(|key| self.get_iterator(key))
             .unique();
@@ -347,6 +526,10 @@ impl PayloadFieldIndex for MapIndex<SmolStr> {
         MapIndex::flusher(self)
     }
 
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.db_wrapper.size_bytes()
+    }
+
     fn filter<'a>(
         &'a self,
         condition: &'a FieldCondition,
@@ -406,12 +589,12 @@ impl PayloadFieldIndex for MapIndex<SmolStr> {
         key: PayloadKeyType,
     ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
         let iter = self
-            .map
-            .iter()
-            .filter(move |(_value, point_ids)| point_ids.len() > threshold)
-            .map(move |(value, point_ids)| PayloadBlockCondition {
+            .postings
+            .iter_counts()
+            .filter(move |(_value, count)| *count > threshold)
+            .map(move |(value, count)| PayloadBlockCondition {
                 condition: FieldCondition::new_match(key.clone(), value.to_owned().into()),
-                cardinality: point_ids.len(),
+                cardinality: count,
             });
         Box::new(iter)
     }
@@ -434,6 +617,10 @@ impl PayloadFieldIndex for MapIndex<IntPayloadType> {
         MapIndex::flusher(self)
     }
 
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.db_wrapper.size_bytes()
+    }
+
     fn filter<'a>(
         &'a self,
         condition: &'a FieldCondition,
@@ -495,12 +682,140 @@ impl PayloadFieldIndex for MapIndex<IntPayloadType> {
         key: PayloadKeyType,
     ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
         let iter = self
-            .map
-            .iter()
-            .filter(move |(_value, point_ids)| point_ids.len() >= threshold)
-            .map(move |(value, point_ids)| PayloadBlockCondition {
+            .postings
+            .iter_counts()
+            .filter(move |(_value, count)| *count >= threshold)
+            .map(move |(value, count)| PayloadBlockCondition {
                 condition: FieldCondition::new_match(key.clone(), (*value).into()),
-                cardinality: point_ids.len(),
+                cardinality: count,
+            });
+        Box::new(iter)
+    }
+}
+
+/// Parse UUID string into its integer form, as it is stored in the index
+pub fn parse_uuid(value: &str) -> Option<UuidIntType> {
+    Uuid::parse_str(value).ok().map(|uuid| uuid.as_u128())
+}
+
+/// Parse UUID strings of the query once, skipping the ones that can't match any indexed value
+fn parse_uuids(values: &[String]) -> Vec<UuidIntType> {
+    values
+        .iter()
+        .filter_map(|value| parse_uuid(value))
+        .collect()
+}
+
+impl PayloadFieldIndex for MapIndex<UuidIntType> {
+    fn count_indexed_points(&self) -> usize {
+        self.indexed_points
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        MapIndex::load(self)
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.db_wrapper.recreate_column_family()
+    }
+
+    fn flusher(&self) -> Flusher {
+        MapIndex::flusher(self)
+    }
+
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.db_wrapper.size_bytes()
+    }
+
+    fn filter<'a>(
+        &'a self,
+        condition: &'a FieldCondition,
+    ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Keyword(keyword),
+            })) => Some(match parse_uuid(keyword) {
+                Some(uuid) => self.get_iterator(&uuid),
+                None => Box::new(iter::empty()),
+            }),
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Keywords(keywords),
+            })) => {
+                let uuids = parse_uuids(keywords);
+                Some(Box::new(
+                    uuids
+                        .into_iter()
+                        .flat_map(|uuid| self.get_iterator(&uuid))
+                        .unique(),
+                ))
+            }
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Keywords(keywords),
+            })) => {
+                let uuids = parse_uuids(keywords);
+                let iter = self
+                    .iter_values()
+                    .filter(move |key| !uuids.contains(key))
+                    .flat_map(|key| self.get_iterator(key))
+                    .unique();
+                Some(Box::new(iter))
+            }
+            _ => None,
+        }
+    }
+
+    fn estimate_cardinality(&self, condition: &FieldCondition) -> Option<CardinalityEstimation> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Keyword(keyword),
+            })) => {
+                let mut estimation = match parse_uuid(keyword) {
+                    Some(uuid) => self.match_cardinality(&uuid),
+                    None => CardinalityEstimation::exact(0),
+                };
+                estimation
+                    .primary_clauses
+                    .push(PrimaryCondition::Condition(condition.clone()));
+                Some(estimation)
+            }
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Keywords(keywords),
+            })) => {
+                let estimations = parse_uuids(keywords)
+                    .iter()
+                    .map(|uuid| self.match_cardinality(uuid))
+                    .collect::<Vec<_>>();
+                Some(combine_should_estimations(
+                    &estimations,
+                    self.indexed_points,
+                ))
+            }
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Keywords(keywords),
+            })) => {
+                Some(self.except_cardinality::<UuidIntType, UuidIntType>(
+                    parse_uuids(keywords).into_iter(),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn payload_blocks(
+        &self,
+        threshold: usize,
+        key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        let iter = self
+            .postings
+            .iter_counts()
+            .filter(move |(_value, count)| *count > threshold)
+            .map(move |(value, count)| PayloadBlockCondition {
+                condition: FieldCondition::new_match(
+                    key.clone(),
+                    Uuid::from_u128(*value).to_string().into(),
+                ),
+                cardinality: count,
             });
         Box::new(iter)
     }
@@ -544,6 +859,23 @@ impl ValueIndexer<IntPayloadType> for MapIndex<IntPayloadType> {
     }
 }
 
+impl ValueIndexer<UuidIntType> for MapIndex<UuidIntType> {
+    fn add_many(&mut self, id: PointOffsetType, values: Vec<UuidIntType>) -> OperationResult<()> {
+        self.add_many_to_map(id, values)
+    }
+
+    fn get_value(&self, value: &Value) -> Option<UuidIntType> {
+        if let Value::String(keyword) = value {
+            return parse_uuid(keyword);
+        }
+        None
+    }
+
+    fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        self.remove_point(id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -636,4 +968,119 @@ mod tests {
         save_map_index(&data, temp_dir.path());
         load_map_index(&data, temp_dir.path());
     }
+
+    fn keyword_filters(values: &[&str]) -> Vec<FieldCondition> {
+        let mut conditions = values
+            .iter()
+            .map(|value| FieldCondition::new_match(FIELD_NAME, value.to_string().into()))
+            .collect_vec();
+        for window in values.windows(2) {
+            let keywords = window.iter().map(|value| value.to_string()).collect_vec();
+            conditions.push(FieldCondition::new_match(
+                FIELD_NAME,
+                Match::new_any(AnyVariants::Keywords(keywords.clone())),
+            ));
+            conditions.push(FieldCondition::new_match(
+                FIELD_NAME,
+                Match::new_except(AnyVariants::Keywords(keywords)),
+            ));
+        }
+        conditions
+    }
+
+    #[test]
+    fn test_on_disk_keyword_index_matches_in_memory() {
+        // Values with `/` are stored next to the shorter values in the database
+        let values = ["a", "a/1", "a/b", "b", "missing"];
+        let num_points = 1000;
+
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut in_memory = MapIndex::<SmolStr>::new(db.clone(), FIELD_NAME);
+        let mut on_disk = MapIndex::<SmolStr>::new_on_disk(db.clone(), FIELD_NAME);
+        assert!(on_disk.is_on_disk());
+        in_memory.recreate().unwrap();
+        on_disk.recreate().unwrap();
+
+        for idx in 0..num_points {
+            let point_values = (0..idx % 3)
+                .map(|i| values[(idx + i) % (values.len() - 1)].to_string())
+                .collect_vec();
+            for index in [&mut in_memory, &mut on_disk] {
+                index
+                    .add_many(idx as PointOffsetType, point_values.clone())
+                    .unwrap();
+            }
+        }
+        for idx in (0..num_points).step_by(7) {
+            in_memory.remove_point(idx as PointOffsetType).unwrap();
+            on_disk.remove_point(idx as PointOffsetType).unwrap();
+        }
+
+        let check = |in_memory: &MapIndex<SmolStr>, on_disk: &MapIndex<SmolStr>| {
+            for condition in keyword_filters(&values) {
+                // Values of `except` are iterated in arbitrary order
+                let expected = in_memory.filter(&condition).unwrap().sorted().collect_vec();
+                let actual = on_disk.filter(&condition).unwrap().sorted().collect_vec();
+                assert_eq!(expected, actual, "{condition:?}");
+                let expected = in_memory.estimate_cardinality(&condition).unwrap();
+                let actual = on_disk.estimate_cardinality(&condition).unwrap();
+                assert_eq!(
+                    (expected.min, expected.exp, expected.max),
+                    (actual.min, actual.exp, actual.max),
+                );
+            }
+            for idx in 0..num_points as PointOffsetType {
+                assert_eq!(in_memory.get_values(idx), on_disk.get_values(idx));
+            }
+        };
+        check(&in_memory, &on_disk);
+
+        // Loaded index reads the same data
+        on_disk.flusher()().unwrap();
+        let mut loaded = MapIndex::<SmolStr>::new_on_disk(db, FIELD_NAME);
+        assert!(loaded.load().unwrap());
+        check(&in_memory, &loaded);
+    }
+
+    #[test]
+    fn test_on_disk_keyword_index_ram_usage() {
+        let num_points = 300_000;
+        let num_values = 10;
+
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut in_memory = MapIndex::<SmolStr>::new(db.clone(), FIELD_NAME);
+        let mut on_disk = MapIndex::<SmolStr>::new_on_disk(db, FIELD_NAME);
+        in_memory.recreate().unwrap();
+        on_disk.recreate().unwrap();
+
+        for idx in 0..num_points {
+            let value = format!("value-{}", idx % num_values);
+            in_memory
+                .add_many(idx as PointOffsetType, vec![value.clone()])
+                .unwrap();
+            on_disk
+                .add_many(idx as PointOffsetType, vec![value])
+                .unwrap();
+        }
+
+        // Request all values, so the cache is filled as much as possible
+        for value in 0..num_values {
+            let condition = FieldCondition::new_match(FIELD_NAME, format!("value-{value}").into());
+            assert_eq!(
+                on_disk.filter(&condition).unwrap().count(),
+                num_points / num_values,
+            );
+        }
+
+        assert_eq!(
+            in_memory.postings_ram_usage_bytes(),
+            num_points * size_of::<PointOffsetType>(),
+        );
+        let max_on_disk_usage = num_values * size_of::<usize>()
+            + ON_DISK_POSTINGS_CACHE_SIZE * size_of::<PointOffsetType>();
+        assert!(on_disk.postings_ram_usage_bytes() <= max_on_disk_usage);
+        assert!(on_disk.postings_ram_usage_bytes() < in_memory.postings_ram_usage_bytes() / 4);
+    }
 }
@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 use crate::types::{FieldCondition, IsEmptyCondition, IsNullCondition, PointOffsetType};
 
+pub mod datetime_index;
 mod field_index_base;
 pub mod full_text_index;
 pub mod geo_hash;
@@ -10,7 +11,9 @@ mod histogram;
 pub mod index_selector;
 pub mod map_index;
 pub mod numeric_index;
+mod postings_cache;
 mod stat_tools;
+pub mod uuid_index;
 
 pub mod binary_index;
 #[cfg(test)]
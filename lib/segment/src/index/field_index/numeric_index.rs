@@ -10,6 +10,7 @@ use serde_json::Value;
 
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
+use crate::data_types::order_by::Direction;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::index::field_index::histogram::{Histogram, Numericable, Point};
 use crate::index::field_index::stat_tools::estimate_multi_value_selection_cardinality;
@@ -23,6 +24,7 @@ use crate::index::key_encoding::{
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     FieldCondition, FloatPayloadType, IntPayloadType, PayloadKeyType, PointOffsetType, Range,
+    RangeInterface,
 };
 
 const HISTOGRAM_MAX_BUCKET_SIZE: usize = 10_000;
@@ -167,8 +169,78 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         self.point_to_values.get(idx as usize)
     }
 
+    /// Iterate over indexed values and their points in the order of `direction`, starting with
+    /// the `start_from` value including.
+    pub fn stream_from(
+        &self,
+        start_from: Option<T>,
+        direction: Direction,
+    ) -> Box<dyn Iterator<Item = (T, PointOffsetType)> + '_> {
+        let decode = |(key, _): (&Vec<u8>, _)| {
+            let (idx, value) = T::decode_key(key);
+            (value, idx)
+        };
+        match direction {
+            Direction::Asc => {
+                let start_bound = start_from
+                    .map(|value| Included(value.encode_key(PointOffsetType::MIN)))
+                    .unwrap_or(Unbounded);
+                Box::new(self.map.range((start_bound, Unbounded)).map(decode))
+            }
+            Direction::Desc => {
+                let end_bound = start_from
+                    .map(|value| Included(value.encode_key(PointOffsetType::MAX)))
+                    .unwrap_or(Unbounded);
+                Box::new(self.map.range((Unbounded, end_bound)).rev().map(decode))
+            }
+        }
+    }
+
+    /// Iterate over points with values in the `range`
+    pub fn range_filter(&self, range: &Range) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        let start_bound = match range {
+            Range { gt: Some(gt), .. } => {
+                let v: T = T::from_f64(*gt);
+                Excluded(v.encode_key(PointOffsetType::MAX))
+            }
+            Range { gte: Some(gte), .. } => {
+                let v: T = T::from_f64(*gte);
+                Included(v.encode_key(PointOffsetType::MIN))
+            }
+            _ => Unbounded,
+        };
+
+        let end_bound = match range {
+            Range { lt: Some(lt), .. } => {
+                let v: T = T::from_f64(*lt);
+                Excluded(v.encode_key(PointOffsetType::MIN))
+            }
+            Range { lte: Some(lte), .. } => {
+                let v: T = T::from_f64(*lte);
+                Included(v.encode_key(PointOffsetType::MAX))
+            }
+            _ => Unbounded,
+        };
+
+        // map.range
+        // Panics if range start > end. Panics if range start == end and both bounds are Excluded.
+        match (&start_bound, &end_bound) {
+            (Excluded(s), Excluded(e)) if s == e => {
+                // range start and end are equal and excluded in BTreeMap
+                return Box::new(vec![].into_iter());
+            }
+            (Included(s) | Excluded(s), Included(e) | Excluded(e)) if s > e => {
+                //range start is greater than range end
+                return Box::new(vec![].into_iter());
+            }
+            _ => {}
+        }
+
+        Box::new(self.map.range((start_bound, end_bound)).map(|(_, v)| *v))
+    }
+
     #[allow(clippy::manual_clamp)] // false positive
-    fn range_cardinality(&self, range: &Range) -> CardinalityEstimation {
+    pub fn range_cardinality(&self, range: &Range) -> CardinalityEstimation {
         let lbound = if let Some(lte) = range.lte {
             Included(T::from_f64(lte))
         } else if let Some(lt) = range.lt {
@@ -322,63 +394,29 @@ impl<T: Encodable + Numericable> PayloadFieldIndex for NumericIndex<T> {
         NumericIndex::flusher(self)
     }
 
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.db_wrapper.size_bytes()
+    }
+
     fn filter(
         &self,
         condition: &FieldCondition,
     ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
-        let cond_range = condition.range.as_ref()?;
-
-        let start_bound = match cond_range {
-            Range { gt: Some(gt), .. } => {
-                let v: T = T::from_f64(*gt);
-                Excluded(v.encode_key(PointOffsetType::MAX))
-            }
-            Range { gte: Some(gte), .. } => {
-                let v: T = T::from_f64(*gte);
-                Included(v.encode_key(PointOffsetType::MIN))
-            }
-            _ => Unbounded,
-        };
-
-        let end_bound = match cond_range {
-            Range { lt: Some(lt), .. } => {
-                let v: T = T::from_f64(*lt);
-                Excluded(v.encode_key(PointOffsetType::MIN))
-            }
-            Range { lte: Some(lte), .. } => {
-                let v: T = T::from_f64(*lte);
-                Included(v.encode_key(PointOffsetType::MAX))
-            }
-            _ => Unbounded,
-        };
-
-        // map.range
-        // Panics if range start > end. Panics if range start == end and both bounds are Excluded.
-        match (&start_bound, &end_bound) {
-            (Excluded(s), Excluded(e)) if s == e => {
-                // range start and end are equal and excluded in BTreeMap
-                return Some(Box::new(vec![].into_iter()));
-            }
-            (Included(s) | Excluded(s), Included(e) | Excluded(e)) if s > e => {
-                //range start is greater than range end
-                return Some(Box::new(vec![].into_iter()));
-            }
-            _ => {}
+        match condition.range.as_ref()? {
+            RangeInterface::Float(range) => Some(self.range_filter(range)),
+            RangeInterface::DateTime(_) => None,
         }
-
-        Some(Box::new(
-            self.map.range((start_bound, end_bound)).map(|(_, v)| *v),
-        ))
     }
 
     fn estimate_cardinality(&self, condition: &FieldCondition) -> Option<CardinalityEstimation> {
-        condition.range.as_ref().map(|range| {
-            let mut cardinality = self.range_cardinality(range);
-            cardinality
-                .primary_clauses
-                .push(PrimaryCondition::Condition(condition.clone()));
-            cardinality
-        })
+        let RangeInterface::Float(range) = condition.range.as_ref()? else {
+            return None;
+        };
+        let mut cardinality = self.range_cardinality(range);
+        cardinality
+            .primary_clauses
+            .push(PrimaryCondition::Condition(condition.clone()));
+        Some(cardinality)
     }
 
     fn payload_blocks(
@@ -797,7 +835,7 @@ mod tests {
         let condition = FieldCondition {
             key: "".to_string(),
             r#match: None,
-            range: Some(rng),
+            range: Some(rng.into()),
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
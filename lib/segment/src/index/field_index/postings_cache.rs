@@ -0,0 +1,124 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::types::PointOffsetType;
+
+struct CachedPostings {
+    points: Arc<Vec<PointOffsetType>>,
+    hits: usize,
+}
+
+/// Bounded cache of point ids of the indexed values, which are stored on disk.
+///
+/// Total number of cached point ids never exceeds the capacity. If a new value doesn't fit,
+/// the least requested values are evicted first. Hit counters are halved on every eviction,
+/// so values which are not requested anymore are evicted eventually.
+pub struct PostingsCache<N: Hash + Eq> {
+    entries: HashMap<N, CachedPostings>,
+    capacity: usize,
+    size: usize,
+}
+
+impl<N: Hash + Eq + Clone> PostingsCache<N> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+            size: 0,
+        }
+    }
+
+    pub fn get<Q>(&mut self, value: &Q) -> Option<Arc<Vec<PointOffsetType>>>
+    where
+        Q: ?Sized,
+        N: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        let entry = self.entries.get_mut(value)?;
+        entry.hits += 1;
+        Some(entry.points.clone())
+    }
+
+    pub fn insert(&mut self, value: N, points: Arc<Vec<PointOffsetType>>) {
+        self.invalidate(&value);
+        if points.len() > self.capacity {
+            return;
+        }
+        while self.size + points.len() > self.capacity {
+            self.evict();
+        }
+        self.size += points.len();
+        self.entries
+            .insert(value, CachedPostings { points, hits: 1 });
+    }
+
+    /// Drop cached point ids of the value, e.g. because they were changed
+    pub fn invalidate<Q>(&mut self, value: &Q)
+    where
+        Q: ?Sized,
+        N: Borrow<Q>,
+        Q: Hash + Eq,
+    {
+        if let Some(entry) = self.entries.remove(value) {
+            self.size -= entry.points.len();
+        }
+    }
+
+    /// Number of point ids kept in the cache
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn evict(&mut self) {
+        let least_requested = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.hits)
+            .map(|(value, _)| value.clone());
+        if let Some(value) = least_requested {
+            self.invalidate(&value);
+        }
+        for entry in self.entries.values_mut() {
+            entry.hits /= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(count: usize) -> Arc<Vec<PointOffsetType>> {
+        Arc::new((0..count as PointOffsetType).collect())
+    }
+
+    #[test]
+    fn test_postings_cache_evicts_least_requested() {
+        let mut cache = PostingsCache::<String>::new(10);
+
+        cache.insert("hot".to_string(), points(4));
+        cache.insert("cold".to_string(), points(4));
+        for _ in 0..3 {
+            assert!(cache.get("hot").is_some());
+        }
+        assert_eq!(cache.size(), 8);
+
+        // Doesn't fit without evicting the least requested value
+        cache.insert("new".to_string(), points(4));
+        assert_eq!(cache.size(), 8);
+        assert!(cache.get("cold").is_none());
+        assert_eq!(cache.get("hot"), Some(points(4)));
+        assert_eq!(cache.get("new"), Some(points(4)));
+
+        // Values larger than the whole cache are not cached
+        cache.insert("huge".to_string(), points(11));
+        assert!(cache.get("huge").is_none());
+        assert_eq!(cache.size(), 8);
+
+        cache.invalidate("hot");
+        assert!(cache.get("hot").is_none());
+        assert_eq!(cache.size(), 4);
+    }
+}
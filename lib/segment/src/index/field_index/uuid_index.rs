@@ -0,0 +1,329 @@
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde_json::Value;
+
+use super::map_index::{parse_uuid, MapIndex};
+use super::{CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, ValueIndexer};
+use crate::common::utils::MultiValue;
+use crate::common::Flusher;
+use crate::data_types::uuid_index::UuidIndexParams;
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{
+    AnyVariants, FieldCondition, Match, MatchAny, MatchValue, PayloadKeyType, PointOffsetType,
+    UuidIntType, ValueVariants,
+};
+
+/// Index of UUID strings, stored as 16 byte integers instead of the 36 characters long strings
+pub struct UuidIndex {
+    index: MapIndex<UuidIntType>,
+    params: UuidIndexParams,
+}
+
+impl UuidIndex {
+    pub fn new(db: Arc<RwLock<DB>>, params: UuidIndexParams, field: &str) -> Self {
+        Self {
+            index: MapIndex::new(db, field),
+            params,
+        }
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.index.recreate()
+    }
+
+    pub fn get_values(&self, idx: PointOffsetType) -> Option<&Vec<UuidIntType>> {
+        self.index.get_values(idx)
+    }
+
+    /// Check that all strings in the payload are UUIDs, unless invalid ones are skipped
+    pub fn validate_payload(&self, payload: &MultiValue<&Value>) -> OperationResult<()> {
+        if self.params.skip_invalid() {
+            return Ok(());
+        }
+        for value in payload.flatten() {
+            if let Value::String(value) = value {
+                if let Err(err) = value.parse::<uuid::Uuid>() {
+                    return Err(OperationError::ValidationError {
+                        description: format!("Invalid UUID value {value:?}: {err}"),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compare UUIDs of the payload and condition, so they match in any of the UUID formats
+    pub fn check_condition(
+        &self,
+        condition: &FieldCondition,
+        payload_value: &Value,
+    ) -> Option<bool> {
+        let Value::String(stored) = payload_value else {
+            return None;
+        };
+        let stored = parse_uuid(stored)?;
+        match condition.r#match.as_ref()? {
+            Match::Value(MatchValue {
+                value: ValueVariants::Keyword(keyword),
+            }) => Some(parse_uuid(keyword) == Some(stored)),
+            Match::Any(MatchAny {
+                any: AnyVariants::Keywords(keywords),
+            }) => Some(
+                keywords
+                    .iter()
+                    .any(|keyword| parse_uuid(keyword) == Some(stored)),
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        self.index.get_telemetry_data()
+    }
+
+    pub fn values_count(&self, point_id: PointOffsetType) -> usize {
+        self.index.values_count(point_id)
+    }
+
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        self.index.values_is_empty(point_id)
+    }
+}
+
+impl PayloadFieldIndex for UuidIndex {
+    fn count_indexed_points(&self) -> usize {
+        self.index.count_indexed_points()
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        PayloadFieldIndex::load(&mut self.index)
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        PayloadFieldIndex::clear(self.index)
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.index.flusher()
+    }
+
+    fn storage_size_bytes(&self) -> OperationResult<usize> {
+        self.index.storage_size_bytes()
+    }
+
+    fn filter<'a>(
+        &'a self,
+        condition: &'a FieldCondition,
+    ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        self.index.filter(condition)
+    }
+
+    fn estimate_cardinality(&self, condition: &FieldCondition) -> Option<CardinalityEstimation> {
+        self.index.estimate_cardinality(condition)
+    }
+
+    fn payload_blocks(
+        &self,
+        threshold: usize,
+        key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        self.index.payload_blocks(threshold, key)
+    }
+}
+
+impl ValueIndexer<UuidIntType> for UuidIndex {
+    fn add_many(&mut self, id: PointOffsetType, values: Vec<UuidIntType>) -> OperationResult<()> {
+        self.index.add_many(id, values)
+    }
+
+    fn get_value(&self, value: &Value) -> Option<UuidIntType> {
+        self.index.get_value(value)
+    }
+
+    fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        ValueIndexer::remove_point(&mut self.index, id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use itertools::Itertools;
+    use rand::prelude::StdRng;
+    use rand::{Rng, SeedableRng};
+    use serde_json::json;
+    use smol_str::SmolStr;
+    use tempfile::Builder;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+    use crate::types::MatchExcept;
+
+    const FIELD_NAME: &str = "test";
+
+    fn uuid_payloads(num_points: usize) -> Vec<Value> {
+        let mut rng = StdRng::seed_from_u64(42);
+        let uuids = (0..20)
+            .map(|_| Uuid::from_u128(rng.gen()).to_string())
+            .collect_vec();
+        (0..num_points)
+            .map(|_| match rng.gen_range(0..4) {
+                0 => json!(null),
+                1 => json!(uuids[rng.gen_range(0..uuids.len())]),
+                _ => {
+                    let count = rng.gen_range(0..4);
+                    json!((0..count)
+                        .map(|_| &uuids[rng.gen_range(0..uuids.len())])
+                        .collect_vec())
+                }
+            })
+            .collect()
+    }
+
+    fn conditions(payloads: &[Value]) -> Vec<FieldCondition> {
+        let uuids: Vec<String> = payloads
+            .iter()
+            .flat_map(|payload| match payload {
+                Value::Array(values) => values.clone(),
+                value => vec![value.clone()],
+            })
+            .filter_map(|value| value.as_str().map(ToOwned::to_owned))
+            .unique()
+            .sorted()
+            .collect();
+        let missing = Uuid::nil().to_string();
+        vec![
+            FieldCondition::new_match(FIELD_NAME, uuids[0].clone().into()),
+            FieldCondition::new_match(FIELD_NAME, missing.clone().into()),
+            FieldCondition::new_match(FIELD_NAME, "not a uuid".to_string().into()),
+            FieldCondition::new_match(
+                FIELD_NAME,
+                AnyVariants::Keywords(vec![uuids[1].clone(), uuids[2].clone(), missing]).into(),
+            ),
+            FieldCondition::new_match(
+                FIELD_NAME,
+                Match::Except(MatchExcept {
+                    except: AnyVariants::Keywords(uuids[3..].to_vec()),
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_uuid_index_matches_keyword_index() {
+        let temp_dir = Builder::new().prefix("uuid_index").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut uuid_index = UuidIndex::new(db.clone(), Default::default(), FIELD_NAME);
+        let mut keyword_index = MapIndex::<SmolStr>::new(db, "keyword");
+        uuid_index.recreate().unwrap();
+        keyword_index.recreate().unwrap();
+
+        let payloads = uuid_payloads(500);
+        for (idx, payload) in payloads.iter().enumerate() {
+            let payload = MultiValue::one(payload);
+            uuid_index
+                .add_point(idx as PointOffsetType, &payload)
+                .unwrap();
+            keyword_index
+                .add_point(idx as PointOffsetType, &payload)
+                .unwrap();
+        }
+        assert_eq!(
+            uuid_index.count_indexed_points(),
+            keyword_index.count_indexed_points()
+        );
+
+        for condition in conditions(&payloads) {
+            let uuid_points: HashSet<_> = uuid_index.filter(&condition).unwrap().collect();
+            let keyword_points: HashSet<_> = keyword_index.filter(&condition).unwrap().collect();
+            assert_eq!(uuid_points, keyword_points, "{condition:?}");
+
+            let estimation = uuid_index.estimate_cardinality(&condition).unwrap();
+            assert!(estimation.min <= uuid_points.len(), "{condition:?}");
+            assert!(uuid_points.len() <= estimation.max, "{condition:?}");
+        }
+
+        // Same UUID in another format matches too
+        let uuid = payloads
+            .iter()
+            .find_map(|payload| payload.as_str())
+            .unwrap();
+        let simple = Uuid::parse_str(uuid)
+            .unwrap()
+            .simple()
+            .to_string()
+            .to_uppercase();
+        let points = |value: &str| {
+            let condition = FieldCondition::new_match(FIELD_NAME, value.to_string().into());
+            uuid_index
+                .filter(&condition)
+                .unwrap()
+                .collect::<HashSet<_>>()
+        };
+        assert_eq!(points(&simple), points(uuid));
+    }
+
+    #[test]
+    fn test_uuid_index_load() {
+        let temp_dir = Builder::new().prefix("uuid_index").tempdir().unwrap();
+        let payloads = uuid_payloads(100);
+        {
+            let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+            let mut index = UuidIndex::new(db, Default::default(), FIELD_NAME);
+            index.recreate().unwrap();
+            for (idx, payload) in payloads.iter().enumerate() {
+                index
+                    .add_point(idx as PointOffsetType, &MultiValue::one(payload))
+                    .unwrap();
+            }
+            index.flusher()().unwrap();
+        }
+
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let mut index = UuidIndex::new(db, Default::default(), FIELD_NAME);
+        assert!(index.load().unwrap());
+        for (idx, payload) in payloads.iter().enumerate() {
+            let expected: HashSet<_> = ValueIndexer::get_values(&index, payload)
+                .into_iter()
+                .collect();
+            let loaded: HashSet<_> = index
+                .get_values(idx as PointOffsetType)
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            assert_eq!(loaded, expected);
+        }
+    }
+
+    #[test]
+    fn test_uuid_index_invalid_values() {
+        let temp_dir = Builder::new().prefix("uuid_index").tempdir().unwrap();
+        let db = open_db_with_existing_cf(temp_dir.path()).unwrap();
+        let payload = json!(["67e55044-10b1-426f-9247-bb680e5fe0c8", "not a uuid"]);
+        let payload = MultiValue::one(&payload);
+
+        let index = UuidIndex::new(db.clone(), Default::default(), FIELD_NAME);
+        match index.validate_payload(&payload) {
+            Err(OperationError::ValidationError { description }) => {
+                assert!(description.contains("not a uuid"), "{description}")
+            }
+            other => panic!("Expected validation error, got {other:?}"),
+        }
+
+        let params = UuidIndexParams {
+            skip_invalid: Some(true),
+            ..Default::default()
+        };
+        let mut index = UuidIndex::new(db, params, FIELD_NAME);
+        index.recreate().unwrap();
+        index.validate_payload(&payload).unwrap();
+        index.add_point(0, &payload).unwrap();
+        assert_eq!(index.values_count(0), 1);
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Progress of HNSW graph building, shared between graph builders and observers
+///
+/// Indexes of all vectors of a segment may share the same progress,
+/// then the points are counted over all of them.
+#[derive(Debug, Default)]
+pub struct BuildProgress {
+    /// Number of points to link, added as building of each graph starts
+    total_points: AtomicUsize,
+    /// Number of points already linked into the graphs
+    linked_points: AtomicUsize,
+}
+
+impl BuildProgress {
+    pub fn add_total(&self, points: usize) {
+        self.total_points.fetch_add(points, Ordering::Relaxed);
+    }
+
+    pub fn add_linked(&self, points: usize) {
+        self.linked_points.fetch_add(points, Ordering::Relaxed);
+    }
+
+    pub fn total_points(&self) -> usize {
+        self.total_points.load(Ordering::Relaxed)
+    }
+
+    pub fn linked_points(&self) -> usize {
+        self.linked_points.load(Ordering::Relaxed)
+    }
+
+    /// Fraction of linked points, `None` if building of no graph is started yet
+    pub fn fraction(&self) -> Option<f64> {
+        let total = self.total_points();
+        if total == 0 {
+            return None;
+        }
+        Some((self.linked_points() as f64 / total as f64).min(1.0))
+    }
+}
@@ -15,7 +15,7 @@ use crate::index::hnsw_index::graph_links::GraphLinksConverter;
 use crate::index::hnsw_index::point_scorer::FilteredScorer;
 use crate::index::hnsw_index::search_context::SearchContext;
 use crate::index::visited_pool::{VisitedList, VisitedPool};
-use crate::types::PointOffsetType;
+use crate::types::{PointOffsetType, ScoreType};
 use crate::vector_storage::ScoredPointOffset;
 
 pub type LinkContainer = Vec<PointOffsetType>;
@@ -77,6 +77,7 @@ pub trait GraphLayersBase {
                 break;
             }
 
+            points_scorer.stats_mut().graph_hops += 1;
             points_ids.clear();
             self.links_map(candidate.idx, level, |link| {
                 if !visited_list.check_and_update_visited(link) {
@@ -124,6 +125,7 @@ pub trait GraphLayersBase {
             idx: entry_point,
             score: points_scorer.score_point(entry_point),
         };
+        points_scorer.stats_mut().points_scored += 1;
         for level in rev_range(top_level, target_level) {
             let limit = self.get_m(level);
 
@@ -131,6 +133,7 @@ pub trait GraphLayersBase {
             while changed {
                 changed = false;
 
+                points_scorer.stats_mut().graph_hops += 1;
                 links.clear();
                 self.links_map(current_point.idx, level, |link| {
                     links.push(link);
@@ -188,8 +191,75 @@ impl<TGraphLinks: GraphLinks> GraphLayers<TGraphLinks> {
         &self,
         top: usize,
         ef: usize,
+        points_scorer: FilteredScorer,
+    ) -> Vec<ScoredPointOffset> {
+        self.search_with_score_threshold(top, ef, None, points_scorer)
+    }
+
+    /// Search for top closest points, scored not lower than `score_threshold`.
+    ///
+    /// Points below the threshold are never returned. Once `top` qualifying points are found,
+    /// such points are not expanded anymore.
+    pub fn search_with_score_threshold(
+        &self,
+        top: usize,
+        ef: usize,
+        score_threshold: Option<ScoreType>,
+        mut points_scorer: FilteredScorer,
+    ) -> Vec<ScoredPointOffset> {
+        let entry_point = match self
+            .entry_points
+            .get_entry_point(|point_id| points_scorer.check_vector(point_id))
+        {
+            None => return vec![],
+            Some(ep) => ep,
+        };
+
+        let zero_level_entry = self.search_entry(
+            entry_point.point_id,
+            entry_point.level,
+            0,
+            &mut points_scorer,
+        );
+
+        let mut visited_list = self.get_visited_list_from_pool();
+        visited_list.check_and_update_visited(zero_level_entry.idx);
+        let mut search_context = SearchContext::with_score_threshold(
+            zero_level_entry,
+            max(top, ef),
+            score_threshold,
+            top,
+        );
+
+        self._search_on_level(
+            &mut search_context,
+            0,
+            &mut visited_list,
+            &mut points_scorer,
+        );
+
+        self.return_visited_list_to_pool(visited_list);
+        search_context.into_results(top)
+    }
+
+    /// Search for top closest points matching the filter of `points_scorer`, traversing the graph
+    /// through points which don't match it.
+    ///
+    /// With a selective filter, links between matching points alone may not connect the graph.
+    /// Instead of skipping non-matching points, the search follows them like any other point, but
+    /// never adds them to the result. The beam holds `ef` matching points, so the search goes on
+    /// until that many are found.
+    pub fn search_with_filter_traversal(
+        &self,
+        top: usize,
+        ef: usize,
+        score_threshold: Option<ScoreType>,
         mut points_scorer: FilteredScorer,
     ) -> Vec<ScoredPointOffset> {
+        let Some(filter_context) = points_scorer.filter_context.take() else {
+            return self.search_with_score_threshold(top, ef, score_threshold, points_scorer);
+        };
+
         let entry_point = match self
             .entry_points
             .get_entry_point(|point_id| points_scorer.check_vector(point_id))
@@ -205,8 +275,44 @@ impl<TGraphLinks: GraphLinks> GraphLayers<TGraphLinks> {
             &mut points_scorer,
         );
 
-        let nearest = self.search_on_level(zero_level_entry, 0, max(top, ef), &mut points_scorer);
-        nearest.into_iter().take(top).collect_vec()
+        let mut visited_list = self.get_visited_list_from_pool();
+        visited_list.check_and_update_visited(zero_level_entry.idx);
+        let mut search_context = SearchContext::empty(max(top, ef), score_threshold, top);
+        search_context.process_traversed_candidate(
+            zero_level_entry,
+            filter_context.check(zero_level_entry.idx),
+        );
+        points_scorer.stats_mut().filter_checks += 1;
+
+        let limit = self.get_m(0);
+        let mut points_ids: Vec<PointOffsetType> = Vec::with_capacity(2 * limit);
+        while let Some(candidate) = search_context.candidates.pop() {
+            if candidate.score < search_context.lower_bound() {
+                break;
+            }
+
+            points_ids.clear();
+            self.links_map(candidate.idx, 0, |link| {
+                if !visited_list.check_and_update_visited(link) {
+                    points_ids.push(link);
+                }
+            });
+
+            let scores = points_scorer.score_points(&mut points_ids, limit);
+            let filter_checks = scores.len();
+            for &score_point in scores {
+                search_context.process_traversed_candidate(
+                    score_point,
+                    filter_context.check(score_point.idx),
+                );
+            }
+            let stats = points_scorer.stats_mut();
+            stats.graph_hops += 1;
+            stats.filter_checks += filter_checks;
+        }
+
+        self.return_visited_list_to_pool(visited_list);
+        search_context.into_results(top)
     }
 
     pub fn get_path(path: &Path) -> PathBuf {
@@ -2,7 +2,9 @@ use std::cmp::{max, min};
 use std::collections::BinaryHeap;
 use std::path::Path;
 use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
+use bitvec::prelude::BitVec;
 use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
 use parking_lot::{Mutex, MutexGuard, RwLock};
 use rand::distributions::Uniform;
@@ -10,6 +12,7 @@ use rand::Rng;
 
 use super::graph_links::GraphLinks;
 use crate::entry::entry_point::OperationResult;
+use crate::index::hnsw_index::build_progress::BuildProgress;
 use crate::index::hnsw_index::entry_points::EntryPoints;
 use crate::index::hnsw_index::graph_layers::{GraphLayers, GraphLayersBase, LinkContainer};
 use crate::index::hnsw_index::graph_links::GraphLinksConverter;
@@ -38,6 +41,10 @@ pub struct GraphLayersBuilder {
 
     // Fields used on construction phase only
     visited_pool: VisitedPool,
+    /// Deleted points, which get neither levels nor links, and are never entry points
+    deleted: Option<BitVec>,
+    /// Counter of linked points, if the progress of building is observed
+    progress: Option<Arc<BuildProgress>>,
 }
 
 impl GraphLayersBase for GraphLayersBuilder {
@@ -99,6 +106,7 @@ impl GraphLayersBuilder {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new_with_params(
         num_vectors: usize, // Initial number of points in index
         m: usize,           // Expected M for non-first layer
@@ -107,12 +115,13 @@ impl GraphLayersBuilder {
         entry_points_num: usize, // Depends on number of points
         use_heuristic: bool,
         reserve: bool,
+        deleted: Option<BitVec>,
     ) -> Self {
         let mut links_layers: Vec<LockedLayersContainer> = vec![];
 
-        for _i in 0..num_vectors {
+        for point_id in 0..num_vectors {
             let mut links = Vec::new();
-            if reserve {
+            if reserve && !is_deleted(deleted.as_ref(), point_id as PointOffsetType) {
                 links.reserve(m0);
             }
             links_layers.push(vec![RwLock::new(links)]);
@@ -128,6 +137,8 @@ impl GraphLayersBuilder {
             links_layers,
             entry_points: Mutex::new(EntryPoints::new(entry_points_num)),
             visited_pool: VisitedPool::new(),
+            progress: None,
+            deleted,
         }
     }
 
@@ -138,6 +149,7 @@ impl GraphLayersBuilder {
         ef_construct: usize,
         entry_points_num: usize, // Depends on number of points
         use_heuristic: bool,
+        deleted: Option<BitVec>,
     ) -> Self {
         Self::new_with_params(
             num_vectors,
@@ -147,9 +159,15 @@ impl GraphLayersBuilder {
             entry_points_num,
             use_heuristic,
             true,
+            deleted,
         )
     }
 
+    /// Count points, linked with [`GraphLayersBuilder::link_new_point`], in `progress`
+    pub fn set_progress(&mut self, progress: Arc<BuildProgress>) {
+        self.progress = Some(progress);
+    }
+
     pub fn merge_from_other(&mut self, other: GraphLayersBuilder) {
         self.max_level = AtomicUsize::new(std::cmp::max(
             self.max_level.load(std::sync::atomic::Ordering::Relaxed),
@@ -191,6 +209,10 @@ impl GraphLayersBuilder {
         self.links_layers.len()
     }
 
+    fn is_deleted(&self, point_id: PointOffsetType) -> bool {
+        is_deleted(self.deleted.as_ref(), point_id)
+    }
+
     /// Generate random level for a new point, according to geometric distribution
     pub fn get_random_layer<R>(&self, rng: &mut R) -> usize
     where
@@ -206,7 +228,11 @@ impl GraphLayersBuilder {
         self.links_layers[point_id as usize].len() - 1
     }
 
+    /// Allocate levels of the point up to `level`, deleted points are left without levels
     pub fn set_levels(&mut self, point_id: PointOffsetType, level: usize) {
+        if self.is_deleted(point_id) {
+            return;
+        }
         if self.links_layers.len() <= point_id as usize {
             while self.links_layers.len() <= point_id as usize {
                 self.links_layers.push(vec![]);
@@ -267,9 +293,20 @@ impl GraphLayersBuilder {
             if result_list.len() >= m {
                 break;
             }
+            debug_assert!(
+                !current_closest.score.is_nan(),
+                "NaN score of candidate {}, vectors must be validated on insertion",
+                current_closest.idx,
+            );
             let mut is_good = true;
             for &selected_point in &result_list {
                 let dist_to_already_selected = score_internal(current_closest.idx, selected_point);
+                // Comparisons with NaN are always false, which would silently produce bad links
+                debug_assert!(
+                    !dist_to_already_selected.is_nan(),
+                    "NaN score between points {} and {selected_point}, vectors must be validated on insertion",
+                    current_closest.idx,
+                );
                 if dist_to_already_selected > current_closest.score {
                     is_good = false;
                     break;
@@ -325,9 +362,15 @@ impl GraphLayersBuilder {
     }
 
     pub fn link_new_point(&self, point_id: PointOffsetType, mut points_scorer: FilteredScorer) {
+        // Deleted points stay unlinked, so that they are never reached from live points
+        if self.is_deleted(point_id) {
+            return;
+        }
+
         // Check if there is an suitable entry point
         //   - entry point level if higher or equal
         //   - it satisfies filters
+        //   - it is not deleted
 
         let level = self.get_point_level(point_id);
 
@@ -335,7 +378,7 @@ impl GraphLayersBuilder {
             .entry_points
             .lock()
             .new_point(point_id, level, |point_id| {
-                points_scorer.check_vector(point_id)
+                !self.is_deleted(point_id) && points_scorer.check_vector(point_id)
             });
         match entry_point_opt {
             // New point is a new empty entry (for this filter, at least)
@@ -453,9 +496,19 @@ impl GraphLayersBuilder {
                 }
             }
         }
+
+        if let Some(progress) = &self.progress {
+            progress.add_linked(1);
+        }
     }
 }
 
+fn is_deleted(deleted: Option<&BitVec>, point_id: PointOffsetType) -> bool {
+    deleted
+        .and_then(|deleted| deleted.get(point_id as usize).map(|deleted| *deleted))
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -469,7 +522,7 @@ mod tests {
         random_vector, FakeFilterContext, TestRawScorerProducer,
     };
     use crate::index::hnsw_index::graph_links::GraphLinksRam;
-    use crate::index::hnsw_index::tests::create_graph_layer_fixture;
+    use crate::index::hnsw_index::tests::create_graph_layer_builder_fixture;
     use crate::spaces::metric::Metric;
     use crate::spaces::simple::{CosineMetric, EuclidMetric};
 
@@ -504,6 +557,7 @@ mod tests {
             ef_construct,
             entry_points_num,
             use_heuristic,
+            None,
         );
 
         for idx in 0..(num_vectors as PointOffsetType) {
@@ -531,6 +585,7 @@ mod tests {
         dim: usize,
         use_heuristic: bool,
         rng: &mut R,
+        deleted: Option<BitVec>,
     ) -> (TestRawScorerProducer<TMetric>, GraphLayersBuilder)
     where
         R: Rng + ?Sized,
@@ -548,6 +603,7 @@ mod tests {
             ef_construct,
             entry_points_num,
             use_heuristic,
+            deleted,
         );
 
         for idx in 0..(num_vectors as PointOffsetType) {
@@ -635,8 +691,9 @@ mod tests {
         assert_eq!(reference_top.into_vec(), graph_search);
     }
 
-    #[test]
-    fn test_add_points() {
+    /// Build the graph by linking points one by one and with levels, set upfront,
+    /// and check that both graphs are equal and that deleted points are left out of them
+    fn check_add_points(deleted: Option<BitVec>) {
         let num_vectors = 1000;
         let dim = 8;
 
@@ -645,11 +702,23 @@ mod tests {
 
         type M = CosineMetric;
 
+        let is_deleted = |idx: usize| deleted.as_ref().is_some_and(|deleted| deleted[idx]);
+        let num_live = (0..num_vectors).filter(|&idx| !is_deleted(idx)).count();
+
         let (vector_holder, graph_layers_builder) =
-            create_graph_layer::<M, _>(num_vectors, dim, false, &mut rng);
+            create_graph_layer::<M, _>(num_vectors, dim, false, &mut rng, deleted.clone());
 
-        let (_vector_holder_orig, graph_layers_orig) =
-            create_graph_layer_fixture::<M, _>(num_vectors, M, dim, false, &mut rng2, None);
+        let (_vector_holder_orig, graph_layers_orig) = create_graph_layer_builder_fixture::<M, _>(
+            num_vectors,
+            M,
+            dim,
+            false,
+            &mut rng2,
+            deleted.clone(),
+        );
+        let graph_layers_orig = graph_layers_orig
+            .into_graph_layers::<GraphLinksRam>(None)
+            .unwrap();
 
         // check is graph_layers_builder links are equeal to graph_layers_orig
         let orig_len = graph_layers_orig.links.num_points();
@@ -664,6 +733,18 @@ mod tests {
             assert_eq!(links_orig, &link_container_from_builder);
         }
 
+        // Deleted points have no links and no levels above the first one,
+        // and are never linked from live points
+        for (idx, layers) in graph_layers_builder.links_layers.iter().enumerate() {
+            if is_deleted(idx) {
+                assert_eq!(layers.len(), 1);
+                assert!(layers[0].read().is_empty());
+            }
+            for links in layers {
+                assert!(links.read().iter().all(|&link| !is_deleted(link as usize)));
+            }
+        }
+
         let main_entry = graph_layers_builder
             .entry_points
             .lock()
@@ -671,6 +752,7 @@ mod tests {
             .expect("Expect entry point to exists");
 
         assert!(main_entry.level > 0);
+        assert!(!is_deleted(main_entry.point_id as usize));
 
         let num_levels = graph_layers_builder
             .links_layers
@@ -691,13 +773,16 @@ mod tests {
         eprintln!("total_links_0 = {total_links_0:#?}");
         eprintln!("num_vectors = {num_vectors:#?}");
 
-        assert!(total_links_0 as f64 / num_vectors as f64 > M as f64);
+        assert!(total_links_0 as f64 / num_live as f64 > M as f64);
 
         let top = 5;
         let query = random_vector(&mut rng, dim);
         let processed_query = M::preprocess(&query).unwrap_or_else(|| query.clone());
         let mut reference_top = FixedLengthPriorityQueue::new(top);
         for idx in 0..vector_holder.vectors.len() as PointOffsetType {
+            if is_deleted(idx as usize) {
+                continue;
+            }
             let vec = &vector_holder.vectors.get(idx);
             reference_top.push(ScoredPointOffset {
                 idx,
@@ -718,6 +803,18 @@ mod tests {
         assert_eq!(reference_top.into_vec(), graph_search);
     }
 
+    #[test]
+    fn test_add_points() {
+        check_add_points(None);
+    }
+
+    #[test]
+    fn test_add_points_with_deleted() {
+        // Every 10th point is deleted, including the first one
+        let deleted = (0..1000).map(|idx| idx % 10 == 0).collect::<BitVec>();
+        check_add_points(Some(deleted));
+    }
+
     #[test]
     #[ignore]
     fn test_hnsw_graph_properties() {
@@ -731,7 +828,7 @@ mod tests {
 
         let vector_holder = TestRawScorerProducer::<CosineMetric>::new(DIM, NUM_VECTORS, &mut rng);
         let mut graph_layers_builder =
-            GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC);
+            GraphLayersBuilder::new(NUM_VECTORS, M, M * 2, EF_CONSTRUCT, 10, USE_HEURISTIC, None);
         let fake_filter_context = FakeFilterContext {};
         for idx in 0..(NUM_VECTORS as PointOffsetType) {
             let added_vector = vector_holder.vectors.get(idx).to_vec();
@@ -852,7 +949,8 @@ mod tests {
 
         let mut rng = StdRng::seed_from_u64(42);
 
-        let graph_layers_builder = GraphLayersBuilder::new(num_points, m, m, ef_construct, 1, true);
+        let graph_layers_builder =
+            GraphLayersBuilder::new(num_points, m, m, ef_construct, 1, true, None);
         insert_ids.shuffle(&mut rng);
         for &id in &insert_ids {
             let level_m = graph_layers_builder.get_m(0);
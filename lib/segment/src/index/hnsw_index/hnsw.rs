@@ -1,6 +1,6 @@
 use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
@@ -15,10 +15,13 @@ use crate::common::mmap_ops;
 use crate::common::operation_time_statistics::{
     OperationDurationsAggregator, ScopeDurationMeasurer,
 };
-use crate::data_types::vectors::VectorElementType;
+use crate::common::search_stats::SearchStats;
+use crate::data_types::search_plan::SearchPath;
+use crate::data_types::vectors::QueryVector;
 use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::hnsw_index::build_condition_checker::BuildConditionChecker;
+use crate::index::hnsw_index::build_progress::BuildProgress;
 use crate::index::hnsw_index::config::HnswGraphConfig;
 use crate::index::hnsw_index::graph_layers::GraphLayers;
 use crate::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
@@ -31,18 +34,26 @@ use crate::index::visited_pool::VisitedList;
 use crate::index::{PayloadIndex, VectorIndex};
 use crate::telemetry::VectorIndexSearchesTelemetry;
 use crate::types::Condition::Field;
-#[cfg(debug_assertions)]
-use crate::types::PointOffsetType;
 use crate::types::{
-    default_quantization_ignore_value, FieldCondition, Filter, HnswConfig,
-    QuantizationSearchParams, SearchParams, VECTOR_ELEMENT_SIZE,
+    FieldCondition, Filter, HnswConfig, PointOffsetType, QuantizationSearchParams, ScoreType,
+    SearchParams, VECTOR_ELEMENT_SIZE,
 };
 use crate::vector_storage::{
-    new_raw_scorer, new_stoppable_raw_scorer, ScoredPointOffset, VectorStorage, VectorStorageEnum,
+    new_raw_scorer, new_stoppable_query_scorer, peek_top_batch, ScoredPointOffset, VectorStorage,
+    VectorStorageEnum,
 };
 
 const HNSW_USE_HEURISTIC: bool = true;
 const BYTES_IN_KB: usize = 1024;
+/// How many more candidates than requested are found for a discovery query, before reranking
+const DISCOVERY_OVERSAMPLING: usize = 4;
+/// Candidates are re-scored with original vectors in batches of this size. Vectors of a batch
+/// are prefetched together, so that reads of on-disk vectors overlap.
+const RESCORE_BATCH_SIZE: usize = 64;
+/// Filtered graph search only follows links between matching points, if each point is expected
+/// to have at least this many of them on level 0. With fewer links the filter may disconnect the
+/// graph, so the search traverses non-matching points too.
+const MIN_MATCHING_LINKS: usize = 8;
 
 pub struct HNSWIndex<TGraphLinks: GraphLinks> {
     id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
@@ -52,6 +63,8 @@ pub struct HNSWIndex<TGraphLinks: GraphLinks> {
     path: PathBuf,
     graph: Option<GraphLayers<TGraphLinks>>,
     searches_telemetry: SearchesTelemetry,
+    /// Progress of the graph building, if it is observed
+    build_progress: Option<Arc<BuildProgress>>,
 }
 
 struct SearchesTelemetry {
@@ -61,6 +74,12 @@ struct SearchesTelemetry {
     large_cardinality: Arc<Mutex<OperationDurationsAggregator>>,
     exact_filtered: Arc<Mutex<OperationDurationsAggregator>>,
     exact_unfiltered: Arc<Mutex<OperationDurationsAggregator>>,
+    /// Number of graph searches with requested `hnsw_ef` out of the sane range
+    clamped_ef: AtomicUsize,
+    /// Beam width the last search with clamped `hnsw_ef` was done with
+    last_clamped_ef: AtomicUsize,
+    /// Total work done by all searches
+    search_stats: Mutex<SearchStats>,
 }
 
 impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
@@ -114,10 +133,19 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
                 large_cardinality: OperationDurationsAggregator::new(),
                 exact_filtered: OperationDurationsAggregator::new(),
                 exact_unfiltered: OperationDurationsAggregator::new(),
+                clamped_ef: AtomicUsize::new(0),
+                last_clamped_ef: AtomicUsize::new(0),
+                search_stats: Mutex::new(SearchStats::default()),
             },
+            build_progress: None,
         })
     }
 
+    /// Report points, linked into the graph by [`VectorIndex::build_index`], to `progress`
+    pub fn set_build_progress(&mut self, progress: Arc<BuildProgress>) {
+        self.build_progress = Some(progress);
+    }
+
     fn save_config(&self) -> OperationResult<()> {
         let config_path = HnswGraphConfig::get_config_path(&self.path);
         self.config.save(&config_path)
@@ -214,17 +242,36 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         })
     }
 
+    /// Search the graph for the `top` closest points matching the `filter`.
+    ///
+    /// With `traverse_filtered`, points which don't match the filter are followed during the
+    /// search, otherwise only links between matching points are.
+    #[allow(clippy::too_many_arguments)]
     pub fn search_with_graph(
         &self,
-        vector: &[VectorElementType],
+        query_vector: &QueryVector,
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         params: Option<&SearchParams>,
+        traverse_filtered: bool,
         is_stopped: &AtomicBool,
     ) -> Vec<ScoredPointOffset> {
-        let ef = params
-            .and_then(|params| params.hnsw_ef)
-            .unwrap_or(self.config.ef);
+        if let QueryVector::Discovery(discovery_query) = query_vector {
+            // Scores of a discovery query change in steps at the borders of the context pairs,
+            // so they can't guide the graph traversal. Instead, candidates close to the target
+            // are found in the graph, and reranked by the complete query.
+            let candidates = self.search_with_graph(
+                &QueryVector::Nearest(discovery_query.target.clone()),
+                filter,
+                top * DISCOVERY_OVERSAMPLING,
+                None,
+                params,
+                traverse_filtered,
+                is_stopped,
+            );
+            return self.rescore(query_vector, &candidates, top, score_threshold, is_stopped);
+        }
 
         let id_tracker = self.id_tracker.borrow();
         let vector_storage = self.vector_storage.borrow();
@@ -239,8 +286,8 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         let (raw_scorer, quantized) = match quantized_storage {
             // If `quantization_params` is `Some`, then quantization is *not* ignored
             Some(quantized_storage) if !quantization_params.ignore => {
-                let scorer = quantized_storage.raw_scorer(
-                    vector,
+                let scorer = quantized_storage.query_scorer(
+                    query_vector,
                     id_tracker.deleted_point_bitslice(),
                     vector_storage.deleted_vector_bitslice(),
                     is_stopped,
@@ -250,8 +297,8 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
             }
 
             _ => {
-                let scorer = new_stoppable_raw_scorer(
-                    vector.to_owned(),
+                let scorer = new_stoppable_query_scorer(
+                    query_vector,
                     &vector_storage,
                     id_tracker.deleted_point_bitslice(),
                     is_stopped,
@@ -269,53 +316,131 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
             return Vec::new();
         };
 
-        if quantized && quantization_params.rescore {
-            let oversampling = quantization_params.oversampling.unwrap_or(1.0);
+        let ef = match params.and_then(|params| params.hnsw_ef) {
+            Some(requested_ef) => {
+                let ef = effective_ef(requested_ef, top, graph.num_points());
+                if requested_ef == 0 || ef < requested_ef {
+                    debug!("hnsw_ef {requested_ef} is out of range, searching with ef {ef}");
+                    let telemetry = &self.searches_telemetry;
+                    telemetry.clamped_ef.fetch_add(1, Ordering::Relaxed);
+                    telemetry.last_clamped_ef.store(ef, Ordering::Relaxed);
+                }
+                ef
+            }
+            None => effective_ef(self.config.ef, top, graph.num_points()),
+        };
+
+        let span = tracing::info_span!(
+            "hnsw_search",
+            ef,
+            top,
+            traverse_filtered,
+            result_count = tracing::field::Empty,
+        );
+        let _entered = span.enter();
+
+        let result = if quantized && quantization_params.rescore {
+            let oversampled_top = oversampled_top(top, quantization_params.oversampling);
 
-            let oversampled_top = if oversampling > 1.0 {
-                (oversampling * top as f64) as usize
+            // Quantized scores are approximate, so the threshold is only applied after rescoring
+            let search_result = if traverse_filtered {
+                graph.search_with_filter_traversal(oversampled_top, ef, None, points_scorer)
             } else {
-                // Very unlikely this is reached because validation enforces oversampling >= 1.0
-                top
+                graph.search(oversampled_top, ef, points_scorer)
             };
 
-            let search_result = graph.search(oversampled_top, ef, points_scorer);
-
-            let raw_scorer = new_stoppable_raw_scorer(
-                vector.to_owned(),
-                &vector_storage,
-                id_tracker.deleted_point_bitslice(),
+            self.rescore(
+                query_vector,
+                &search_result,
+                top,
+                score_threshold,
                 is_stopped,
-            );
+            )
+        } else if traverse_filtered {
+            graph.search_with_filter_traversal(top, ef, score_threshold, points_scorer)
+        } else {
+            graph.search_with_score_threshold(top, ef, score_threshold, points_scorer)
+        };
+        span.record("result_count", result.len());
+        result
+    }
+
+    /// Best `top` of the `candidates`, scored by the query with original vectors
+    fn rescore(
+        &self,
+        query_vector: &QueryVector,
+        candidates: &[ScoredPointOffset],
+        top: usize,
+        score_threshold: Option<ScoreType>,
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        let id_tracker = self.id_tracker.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let raw_scorer = new_stoppable_query_scorer(
+            query_vector,
+            &vector_storage,
+            id_tracker.deleted_point_bitslice(),
+            is_stopped,
+        );
 
-            let mut ids_iterator = search_result.iter().map(|x| x.idx);
-            let mut re_scored = raw_scorer.score_points_unfiltered(&mut ids_iterator);
+        // Neighbouring offsets share pages of on-disk storages, so they are read in order
+        let mut ids: Vec<_> = candidates.iter().map(|x| x.idx).collect();
+        ids.sort_unstable();
+        ids.dedup();
 
-            re_scored.sort_unstable();
-            re_scored.truncate(top);
-            re_scored
-        } else {
-            graph.search(top, ef, points_scorer)
+        let mut re_scored = Vec::with_capacity(ids.len());
+        for batch in ids.chunks(RESCORE_BATCH_SIZE) {
+            if is_stopped.load(Ordering::Relaxed) {
+                break;
+            }
+            vector_storage.prefetch_vectors(batch);
+            re_scored.extend(raw_scorer.score_points_unfiltered(&mut batch.iter().copied()));
+        }
+        SearchStats {
+            points_scored: re_scored.len(),
+            ..Default::default()
+        }
+        .report();
+
+        // Best scores first
+        re_scored.sort_unstable_by(|a, b| b.cmp(a));
+        re_scored.truncate(top);
+        if let Some(score_threshold) = score_threshold {
+            re_scored.retain(|scored| scored.score >= score_threshold);
         }
+        re_scored
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn search_vectors_with_graph(
         &self,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         params: Option<&SearchParams>,
+        traverse_filtered: bool,
         is_stopped: &AtomicBool,
     ) -> Vec<Vec<ScoredPointOffset>> {
-        vectors
+        query_vectors
             .iter()
-            .map(|vector| self.search_with_graph(vector, filter, top, params, is_stopped))
+            .map(|query_vector| {
+                self.search_with_graph(
+                    query_vector,
+                    filter,
+                    top,
+                    score_threshold,
+                    params,
+                    traverse_filtered,
+                    is_stopped,
+                )
+            })
             .collect()
     }
 
     fn search_vectors_plain(
         &self,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         filter: &Filter,
         top: usize,
         params: Option<&SearchParams>,
@@ -324,112 +449,106 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         let id_tracker = self.id_tracker.borrow();
         let payload_index = self.payload_index.borrow();
         let vector_storage = self.vector_storage.borrow();
-        let filtered_points = payload_index.query_points(filter);
-        let ignore_quantization = params
-            .and_then(|p| p.quantization)
-            .map(|q| q.ignore)
-            .unwrap_or(default_quantization_ignore_value());
-        if ignore_quantization {
-            vectors
-                .iter()
-                .map(|vector| {
-                    new_stoppable_raw_scorer(
-                        vector.to_vec(),
-                        &vector_storage,
-                        id_tracker.deleted_point_bitslice(),
-                        is_stopped,
-                    )
-                    .peek_top_iter(&mut filtered_points.iter().copied(), top)
-                })
-                .collect()
+        let span = tracing::info_span!("payload_filter", matched_points = tracing::field::Empty);
+        let filtered_points = span.in_scope(|| payload_index.query_points(filter));
+        span.record("matched_points", filtered_points.len());
+        let quantization_params = params.and_then(|p| p.quantization).unwrap_or_default();
+        let quantized_storage = if quantization_params.ignore {
+            None
         } else {
-            vectors
-                .iter()
-                .map(|vector| {
-                    if let Some(quantized_storage) = vector_storage.quantized_storage() {
-                        quantized_storage
-                            .raw_scorer(
-                                vector,
-                                id_tracker.deleted_point_bitslice(),
-                                vector_storage.deleted_vector_bitslice(),
-                                is_stopped,
-                            )
-                            .peek_top_iter(&mut filtered_points.iter().copied(), top)
-                    } else {
-                        new_stoppable_raw_scorer(
-                            vector.to_vec(),
-                            &vector_storage,
-                            id_tracker.deleted_point_bitslice(),
-                            is_stopped,
-                        )
-                        .peek_top_iter(&mut filtered_points.iter().copied(), top)
-                    }
-                })
-                .collect()
+            vector_storage.quantized_storage()
+        };
+        let rescore = quantized_storage.is_some() && quantization_params.rescore;
+        let scorers: Vec<_> = query_vectors
+            .iter()
+            .map(|query_vector| match quantized_storage {
+                Some(quantized_storage) => quantized_storage.query_scorer(
+                    query_vector,
+                    id_tracker.deleted_point_bitslice(),
+                    vector_storage.deleted_vector_bitslice(),
+                    is_stopped,
+                ),
+                None => new_stoppable_query_scorer(
+                    query_vector,
+                    &vector_storage,
+                    id_tracker.deleted_point_bitslice(),
+                    is_stopped,
+                ),
+            })
+            .collect();
+        let candidates_top = if rescore {
+            oversampled_top(top, quantization_params.oversampling)
+        } else {
+            top
+        };
+        let candidates = peek_top_batch(
+            &scorers,
+            &mut filtered_points.iter().copied(),
+            candidates_top,
+            is_stopped,
+        );
+        if !rescore {
+            return candidates;
         }
-    }
-}
 
-impl HNSWIndex<GraphLinksMmap> {
-    pub fn prefault_mmap_pages(&self) -> Option<mmap_ops::PrefaultMmapPages> {
-        self.graph.as_ref()?.prefault_mmap_pages(&self.path)
+        query_vectors
+            .iter()
+            .zip(candidates)
+            .map(|(query_vector, candidates)| {
+                self.rescore(query_vector, &candidates, top, None, is_stopped)
+            })
+            .collect()
     }
-}
 
-impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
-    fn search(
+    fn search_vectors(
         &self,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
     ) -> Vec<Vec<ScoredPointOffset>> {
-        let exact = params.map(|params| params.exact).unwrap_or(false);
+        let search_path = self.search_path(filter, params);
         match filter {
             None => {
-                let id_tracker = self.id_tracker.borrow();
-                let vector_storage = self.vector_storage.borrow();
-
-                // Determine whether to do a plain or graph search, and pick search timer aggregator
-                // Because an HNSW graph is built, we'd normally always assume to search the graph.
-                // But because a lot of points may be deleted in this graph, it may just be faster
-                // to do a plain search instead.
-                let plain_search = exact
-                    || vector_storage.available_vector_count() < self.config.full_scan_threshold;
-
-                // Do plain or graph search
-                if plain_search {
-                    let _timer = ScopeDurationMeasurer::new(if exact {
-                        &self.searches_telemetry.exact_unfiltered
-                    } else {
-                        &self.searches_telemetry.unfiltered_plain
-                    });
-                    vectors
-                        .iter()
-                        .map(|vector| {
-                            new_stoppable_raw_scorer(
-                                vector.to_vec(),
-                                &vector_storage,
-                                id_tracker.deleted_point_bitslice(),
-                                is_stopped,
-                            )
-                            .peek_top_all(top)
-                        })
-                        .collect()
-                } else {
+                if search_path == SearchPath::Hnsw {
                     let _timer =
                         ScopeDurationMeasurer::new(&self.searches_telemetry.unfiltered_hnsw);
-                    self.search_vectors_with_graph(vectors, None, top, params, is_stopped)
+                    return self.search_vectors_with_graph(
+                        query_vectors,
+                        None,
+                        top,
+                        score_threshold,
+                        params,
+                        false,
+                        is_stopped,
+                    );
                 }
-            }
-            Some(query_filter) => {
-                // depending on the amount of filtered-out points the optimal strategy could be
-                // - to retrieve possible points and score them after
-                // - to use HNSW index with filtering condition
 
-                // if exact search is requested, we should not use HNSW index
-                if exact {
+                let _timer = ScopeDurationMeasurer::new(if search_path == SearchPath::Exact {
+                    &self.searches_telemetry.exact_unfiltered
+                } else {
+                    &self.searches_telemetry.unfiltered_plain
+                });
+                let id_tracker = self.id_tracker.borrow();
+                let vector_storage = self.vector_storage.borrow();
+                let scorers: Vec<_> = query_vectors
+                    .iter()
+                    .map(|query_vector| {
+                        new_stoppable_query_scorer(
+                            query_vector,
+                            &vector_storage,
+                            id_tracker.deleted_point_bitslice(),
+                            is_stopped,
+                        )
+                    })
+                    .collect();
+                let points_count = vector_storage.total_vector_count() as PointOffsetType;
+                peek_top_batch(&scorers, &mut (0..points_count), top, is_stopped)
+            }
+            Some(query_filter) => match search_path {
+                SearchPath::Exact => {
                     let exact_params = params.map(|params| {
                         let mut params = *params;
                         params.quantization = Some(QuantizationSearchParams {
@@ -441,69 +560,159 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                     });
                     let _timer =
                         ScopeDurationMeasurer::new(&self.searches_telemetry.exact_filtered);
-                    return self.search_vectors_plain(
-                        vectors,
+                    self.search_vectors_plain(
+                        query_vectors,
                         query_filter,
                         top,
                         exact_params.as_ref(),
                         is_stopped,
-                    );
+                    )
                 }
-
-                let payload_index = self.payload_index.borrow();
-                let vector_storage = self.vector_storage.borrow();
-                let id_tracker = self.id_tracker.borrow();
-                let available_vector_count = vector_storage.available_vector_count();
-                let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
-                let query_cardinality = adjust_to_available_vectors(
-                    query_point_cardinality,
-                    available_vector_count,
-                    id_tracker.available_point_count(),
-                );
-
-                if query_cardinality.max < self.config.full_scan_threshold {
-                    // if cardinality is small - use plain index
+                SearchPath::FilteredHnsw | SearchPath::FilteredHnswTraversal => {
                     let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    return self.search_vectors_plain(
-                        vectors,
-                        query_filter,
+                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
+                    self.search_vectors_with_graph(
+                        query_vectors,
+                        filter,
                         top,
+                        score_threshold,
                         params,
+                        search_path == SearchPath::FilteredHnswTraversal,
                         is_stopped,
-                    );
+                    )
                 }
-
-                if query_cardinality.min > self.config.full_scan_threshold {
-                    // if cardinality is high enough - use HNSW index
+                _ => {
                     let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
-                    return self
-                        .search_vectors_with_graph(vectors, filter, top, params, is_stopped);
+                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
+                    self.search_vectors_plain(query_vectors, query_filter, top, params, is_stopped)
                 }
+            },
+        }
+    }
+}
 
-                let filter_context = payload_index.filter_context(query_filter);
+impl HNSWIndex<GraphLinksMmap> {
+    pub fn prefault_mmap_pages(&self) -> Option<mmap_ops::PrefaultMmapPages> {
+        self.graph.as_ref()?.prefault_mmap_pages(&self.path)
+    }
+}
 
-                // Fast cardinality estimation is not enough, do sample estimation of cardinality
-                let id_tracker = self.id_tracker.borrow();
-                if sample_check_cardinality(
-                    id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
-                    |idx| filter_context.check(idx),
-                    self.config.full_scan_threshold,
-                    available_vector_count, // Check cardinality among available vectors
-                ) {
-                    // if cardinality is high enough - use HNSW index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
-                    self.search_vectors_with_graph(vectors, filter, top, params, is_stopped)
-                } else {
-                    // if cardinality is small - use plain index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    self.search_vectors_plain(vectors, query_filter, top, params, is_stopped)
-                }
+/// Number of candidates to find with quantized vectors, before re-scoring the best `top` of them
+fn oversampled_top(top: usize, oversampling: Option<f64>) -> usize {
+    match oversampling {
+        Some(oversampling) if oversampling > 1.0 => (oversampling * top as f64) as usize,
+        // Very unlikely to be lower, because validation enforces oversampling >= 1.0
+        _ => top,
+    }
+}
+
+/// Beam width of the graph search for the requested `ef`
+///
+/// The beam is at least `top` wide, so that enough points are found. It is never wider than the
+/// number of points in the graph, a wider beam can't find more points and only wastes memory.
+fn effective_ef(ef: usize, top: usize, num_points: usize) -> usize {
+    ef.max(top).min(num_points.max(1))
+}
+
+impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
+    fn search_queries(
+        &self,
+        query_vectors: &[&QueryVector],
+        filter: Option<&Filter>,
+        top: usize,
+        score_threshold: Option<ScoreType>,
+        params: Option<&SearchParams>,
+        is_stopped: &AtomicBool,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        let (mut results, stats) = SearchStats::collect(|| {
+            self.search_vectors(
+                query_vectors,
+                filter,
+                top,
+                score_threshold,
+                params,
+                is_stopped,
+            )
+        });
+        *self.searches_telemetry.search_stats.lock() += stats;
+        // Graph search already respects the threshold, plain search is filtered here
+        if let Some(score_threshold) = score_threshold {
+            for result in &mut results {
+                result.retain(|scored| scored.score >= score_threshold);
             }
         }
+        results
+    }
+
+    fn search_path(&self, filter: Option<&Filter>, params: Option<&SearchParams>) -> SearchPath {
+        // if exact search is requested, we should not use HNSW index
+        if params.map(|params| params.exact).unwrap_or(false) {
+            return SearchPath::Exact;
+        }
+
+        let vector_storage = self.vector_storage.borrow();
+        let available_vector_count = vector_storage.available_vector_count();
+
+        let Some(query_filter) = filter else {
+            // Because an HNSW graph is built, we'd normally always assume to search the graph.
+            // But because a lot of points may be deleted in this graph, it may just be faster
+            // to do a plain search instead.
+            return if available_vector_count < self.config.full_scan_threshold {
+                SearchPath::FullScan
+            } else {
+                SearchPath::Hnsw
+            };
+        };
+
+        // depending on the amount of filtered-out points the optimal strategy could be
+        // - to retrieve possible points and score them after
+        // - to use HNSW index with filtering condition
+        let payload_index = self.payload_index.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
+        let query_cardinality = adjust_to_available_vectors(
+            query_point_cardinality,
+            available_vector_count,
+            id_tracker.available_point_count(),
+        );
+
+        let span = tracing::info_span!(
+            "filter_cardinality",
+            estimate = query_cardinality.exp,
+            min = query_cardinality.min,
+            max = query_cardinality.max,
+            use_graph = tracing::field::Empty,
+        );
+        let _entered = span.enter();
+
+        let use_graph = if query_cardinality.max < self.config.full_scan_threshold {
+            // if cardinality is small - use plain index
+            false
+        } else if query_cardinality.min > self.config.full_scan_threshold {
+            // if cardinality is high enough - use HNSW index
+            true
+        } else {
+            // Fast cardinality estimation is not enough, do sample estimation of cardinality
+            let filter_context = payload_index.filter_context(query_filter);
+            sample_check_cardinality(
+                id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
+                |idx| filter_context.check(idx),
+                self.config.full_scan_threshold,
+                available_vector_count, // Check cardinality among available vectors
+            )
+        };
+        span.record("use_graph", use_graph);
+        if !use_graph {
+            return SearchPath::FilteredScan;
+        }
+
+        // Links between matching points are enough to search the graph only if most points match,
+        // with a medium number of matching points the graph is traversed through all of them
+        if query_cardinality.exp * self.config.m0 >= available_vector_count * MIN_MATCHING_LINKS {
+            SearchPath::FilteredHnsw
+        } else {
+            SearchPath::FilteredHnswTraversal
+        }
     }
 
     fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
@@ -528,6 +737,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                 * 10)
                 .max(1),
             HNSW_USE_HEURISTIC,
+            Some(deleted_bitslice.to_bitvec()),
         );
 
         let pool = rayon::ThreadPoolBuilder::new()
@@ -547,6 +757,10 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
             let ids: Vec<_> = id_tracker.iter_ids_excluding(deleted_bitslice).collect();
 
             indexed_vectors = ids.len();
+            if let Some(progress) = &self.build_progress {
+                progress.add_total(ids.len());
+                graph_layers_builder.set_progress(progress.clone());
+            }
 
             pool.install(|| {
                 ids.into_par_iter().try_for_each(|vector_id| {
@@ -615,6 +829,7 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
                         1,
                         HNSW_USE_HEURISTIC,
                         false,
+                        Some(deleted_bitslice.to_bitvec()),
                     );
                     self.build_filtered_graph(
                         &pool,
@@ -667,6 +882,12 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
             filtered_large_cardinality: tm.large_cardinality.lock().get_statistics(),
             filtered_exact: tm.exact_filtered.lock().get_statistics(),
             unfiltered_exact: tm.exact_unfiltered.lock().get_statistics(),
+            clamped_hnsw_ef: tm.clamped_ef.load(Ordering::Relaxed),
+            last_clamped_hnsw_ef: match tm.last_clamped_ef.load(Ordering::Relaxed) {
+                0 => None,
+                ef => Some(ef),
+            },
+            search_stats: *tm.search_stats.lock(),
         }
     }
 
@@ -1,5 +1,6 @@
 mod build_cache;
 pub mod build_condition_checker;
+pub mod build_progress;
 mod config;
 mod entry_points;
 pub mod graph_layers;
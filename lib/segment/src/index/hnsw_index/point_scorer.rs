@@ -1,3 +1,4 @@
+use crate::common::search_stats::SearchStats;
 use crate::payload_storage::FilterContext;
 use crate::types::{PointOffsetType, ScoreType};
 use crate::vector_storage::{RawScorer, ScoredPointOffset};
@@ -6,6 +7,8 @@ pub struct FilteredScorer<'a> {
     pub raw_scorer: &'a dyn RawScorer,
     pub filter_context: Option<&'a dyn FilterContext>,
     points_buffer: Vec<ScoredPointOffset>,
+    /// Work done with the scorer, reported once it is dropped
+    stats: SearchStats,
 }
 
 impl<'a> FilteredScorer<'a> {
@@ -17,9 +20,15 @@ impl<'a> FilteredScorer<'a> {
             raw_scorer,
             filter_context,
             points_buffer: Vec::new(),
+            stats: SearchStats::default(),
         }
     }
 
+    /// Counters of the work done with the scorer, for the search to add its own work to
+    pub fn stats_mut(&mut self) -> &mut SearchStats {
+        &mut self.stats
+    }
+
     pub fn check_vector(&self, point_id: PointOffsetType) -> bool {
         match self.filter_context {
             None => self.raw_scorer.check_vector(point_id),
@@ -47,6 +56,7 @@ impl<'a> FilteredScorer<'a> {
             None => point_ids,
             Some(f) => {
                 let len = point_ids.len();
+                self.stats.filter_checks += len;
                 let mut filtered_len = 0;
                 for i in 0..len {
                     let point_id = point_ids[i];
@@ -68,6 +78,7 @@ impl<'a> FilteredScorer<'a> {
         let count = self
             .raw_scorer
             .score_points(filtered_point_ids, &mut self.points_buffer);
+        self.stats.points_scored += count;
         &self.points_buffer[0..count]
     }
 
@@ -79,3 +90,9 @@ impl<'a> FilteredScorer<'a> {
         self.raw_scorer.score_internal(point_a, point_b)
     }
 }
+
+impl Drop for FilteredScorer<'_> {
+    fn drop(&mut self) {
+        self.stats.report();
+    }
+}
@@ -1,5 +1,4 @@
 use std::collections::BinaryHeap;
-use std::iter::FromIterator;
 
 use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
 use num_traits::float::FloatCore;
@@ -13,34 +12,106 @@ pub struct SearchContext {
     pub nearest: FixedLengthPriorityQueue<ScoredPointOffset>,
     /// Current candidates to process
     pub candidates: BinaryHeap<ScoredPointOffset>,
+    /// Only points scored not lower than the threshold are part of the result
+    score_threshold: Option<ScoreType>,
+    /// Number of points in `nearest` which pass the score threshold
+    qualifying: usize,
+    /// Number of qualifying points, after which points below the threshold are not followed
+    enough: usize,
 }
 
 impl SearchContext {
     pub fn new(entry_point: ScoredPointOffset, ef: usize) -> Self {
-        let mut nearest = FixedLengthPriorityQueue::new(ef);
-        nearest.push(entry_point);
+        Self::with_score_threshold(entry_point, ef, None, ef)
+    }
+
+    /// Search context for points scored not lower than `score_threshold`.
+    ///
+    /// Points below the threshold are followed as usual until `enough` qualifying points are
+    /// found. Afterwards they can't improve the result much, so the search doesn't expand them.
+    pub fn with_score_threshold(
+        entry_point: ScoredPointOffset,
+        ef: usize,
+        score_threshold: Option<ScoreType>,
+        enough: usize,
+    ) -> Self {
+        let mut search_context = Self::empty(ef, score_threshold, enough);
+        search_context.process_candidate(entry_point);
+        search_context
+    }
+
+    /// Search context without any points, the entry point is to be processed by the caller
+    pub fn empty(ef: usize, score_threshold: Option<ScoreType>, enough: usize) -> Self {
         SearchContext {
-            nearest,
-            candidates: BinaryHeap::from_iter([entry_point]),
+            nearest: FixedLengthPriorityQueue::new(ef),
+            candidates: BinaryHeap::new(),
+            score_threshold,
+            qualifying: 0,
+            enough,
         }
     }
 
     pub fn lower_bound(&self) -> ScoreType {
-        match self.nearest.top() {
+        let lower_bound = match self.nearest.top() {
             None => ScoreType::min_value(),
             Some(worst_of_the_best) => worst_of_the_best.score,
+        };
+        match self.score_threshold {
+            Some(threshold) if self.qualifying >= self.enough => lower_bound.max(threshold),
+            _ => lower_bound,
         }
     }
 
+    fn passes_threshold(&self, score: ScoreType) -> bool {
+        self.score_threshold
+            .map_or(true, |threshold| score >= threshold)
+    }
+
     /// Updates search context with new scored point.
     /// If it is closer than existing - also add it to candidates for further search
     pub fn process_candidate(&mut self, score_point: ScoredPointOffset) {
+        if self.qualifying >= self.enough && !self.passes_threshold(score_point.score) {
+            return;
+        }
         let was_added = match self.nearest.push(score_point) {
             None => true,
-            Some(removed) => removed.idx != score_point.idx,
+            Some(removed) => {
+                if removed.idx != score_point.idx && self.passes_threshold(removed.score) {
+                    self.qualifying -= 1;
+                }
+                removed.idx != score_point.idx
+            }
         };
         if was_added {
+            if self.passes_threshold(score_point.score) {
+                self.qualifying += 1;
+            }
             self.candidates.push(score_point);
         }
     }
+
+    /// Updates search context with a point traversed by a filtered search.
+    /// Only points matching the filter become part of the result, other ones are just followed,
+    /// if they are closer than the nearest found points, to reach matching points behind them.
+    pub fn process_traversed_candidate(
+        &mut self,
+        score_point: ScoredPointOffset,
+        is_matching: bool,
+    ) {
+        if is_matching {
+            self.process_candidate(score_point);
+        } else if score_point.score >= self.lower_bound() {
+            self.candidates.push(score_point);
+        }
+    }
+
+    /// Best `top` points passing the score threshold, best first
+    pub fn into_results(self, top: usize) -> Vec<ScoredPointOffset> {
+        let score_threshold = self.score_threshold;
+        self.nearest
+            .into_iter()
+            .filter(|scored| score_threshold.map_or(true, |threshold| scored.score >= threshold))
+            .take(top)
+            .collect()
+    }
 }
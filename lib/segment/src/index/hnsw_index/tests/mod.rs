@@ -2,6 +2,7 @@ mod test_compact_graph_layer;
 
 use std::path::Path;
 
+use bitvec::prelude::BitVec;
 use rand::Rng;
 
 use super::graph_links::GraphLinksRam;
@@ -18,6 +19,7 @@ pub(crate) fn create_graph_layer_builder_fixture<TMetric: Metric, R>(
     dim: usize,
     use_heuristic: bool,
     rng: &mut R,
+    deleted: Option<BitVec>,
 ) -> (TestRawScorerProducer<TMetric>, GraphLayersBuilder)
 where
     R: Rng + ?Sized,
@@ -34,6 +36,7 @@ where
         ef_construct,
         entry_points_num,
         use_heuristic,
+        deleted,
     );
 
     for idx in 0..(num_vectors as PointOffsetType) {
@@ -60,7 +63,7 @@ where
     R: Rng + ?Sized,
 {
     let (vector_holder, graph_layers_builder) =
-        create_graph_layer_builder_fixture(num_vectors, m, dim, use_heuristic, rng);
+        create_graph_layer_builder_fixture(num_vectors, m, dim, use_heuristic, rng, None);
 
     (
         vector_holder,
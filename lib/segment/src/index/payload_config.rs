@@ -13,6 +13,10 @@ pub const PAYLOAD_INDEX_CONFIG_FILE: &str = "config.json";
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct PayloadConfig {
     pub indexed_fields: HashMap<PayloadKeyType, PayloadFieldSchema>,
+    /// Generation of the storage of each field index, incremented every time the index is rebuilt.
+    /// Fields without a generation use the initial one.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub index_generations: HashMap<PayloadKeyType, u32>,
 }
 
 impl PayloadConfig {
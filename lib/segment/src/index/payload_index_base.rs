@@ -10,7 +10,7 @@ use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
 use crate::payload_storage::FilterContext;
 use crate::types::{
     Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
-    PointOffsetType,
+    PayloadSelector, PointOffsetType,
 };
 
 pub trait PayloadIndex {
@@ -67,9 +67,30 @@ pub trait PayloadIndex {
     /// Assign payload to a concrete point with a concrete payload value
     fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()>;
 
+    /// Merge payload into the object at the given key path of the point payload
+    fn assign_by_key(
+        &mut self,
+        point_id: PointOffsetType,
+        payload: &Payload,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<()> {
+        let mut full_payload = self.payload(point_id)?;
+        full_payload.merge_by_key(payload, key);
+        self.assign_all(point_id, &full_payload)
+    }
+
     /// Get payload for point
     fn payload(&self, point_id: PointOffsetType) -> OperationResult<Payload>;
 
+    /// Get payload for point, projected with the selector
+    fn payload_selected(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        Ok(selector.process(self.payload(point_id)?))
+    }
+
     /// Delete payload by key
     fn delete(
         &mut self,
@@ -11,9 +11,11 @@ use schemars::_serde_json::Value;
 use crate::common::operation_time_statistics::{
     OperationDurationStatistics, OperationDurationsAggregator, ScopeDurationMeasurer,
 };
+use crate::common::search_stats::SearchStats;
 use crate::common::utils::JsonPathPayload;
 use crate::common::Flusher;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::search_plan::SearchPath;
+use crate::data_types::vectors::QueryVector;
 use crate::entry::entry_point::OperationResult;
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
@@ -24,9 +26,11 @@ use crate::payload_storage::{ConditionCheckerSS, FilterContext};
 use crate::telemetry::VectorIndexSearchesTelemetry;
 use crate::types::{
     Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
-    PointOffsetType, SearchParams,
+    PointOffsetType, ScoreType, SearchParams,
+};
+use crate::vector_storage::{
+    new_stoppable_query_scorer, peek_top_batch, ScoredPointOffset, VectorStorage, VectorStorageEnum,
 };
-use crate::vector_storage::{new_stoppable_raw_scorer, ScoredPointOffset, VectorStorageEnum};
 
 /// Implementation of `PayloadIndex` which does not really indexes anything.
 ///
@@ -205,6 +209,7 @@ pub struct PlainIndex {
     payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
     filtered_searches_telemetry: Arc<Mutex<OperationDurationsAggregator>>,
     unfiltered_searches_telemetry: Arc<Mutex<OperationDurationsAggregator>>,
+    search_stats: Mutex<SearchStats>,
 }
 
 impl PlainIndex {
@@ -219,57 +224,84 @@ impl PlainIndex {
             payload_index,
             filtered_searches_telemetry: OperationDurationsAggregator::new(),
             unfiltered_searches_telemetry: OperationDurationsAggregator::new(),
+            search_stats: Mutex::new(SearchStats::default()),
         }
     }
 }
 
 impl VectorIndex for PlainIndex {
-    fn search(
+    fn search_queries(
         &self,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         _params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
     ) -> Vec<Vec<ScoredPointOffset>> {
-        match filter {
+        let (mut results, stats) = SearchStats::collect(|| match filter {
             Some(filter) => {
                 let _timer = ScopeDurationMeasurer::new(&self.filtered_searches_telemetry);
                 let id_tracker = self.id_tracker.borrow();
                 let payload_index = self.payload_index.borrow();
                 let vector_storage = self.vector_storage.borrow();
-                let filtered_ids_vec = payload_index.query_points(filter);
-                vectors
+                let span =
+                    tracing::info_span!("payload_filter", matched_points = tracing::field::Empty);
+                let filtered_ids_vec = span.in_scope(|| payload_index.query_points(filter));
+                span.record("matched_points", filtered_ids_vec.len());
+                let scorers: Vec<_> = query_vectors
                     .iter()
-                    .map(|vector| {
-                        new_stoppable_raw_scorer(
-                            vector.to_vec(),
+                    .map(|query_vector| {
+                        new_stoppable_query_scorer(
+                            query_vector,
                             &vector_storage,
                             id_tracker.deleted_point_bitslice(),
                             is_stopped,
                         )
-                        .peek_top_iter(&mut filtered_ids_vec.iter().copied(), top)
                     })
-                    .collect()
+                    .collect();
+                peek_top_batch(
+                    &scorers,
+                    &mut filtered_ids_vec.iter().copied(),
+                    top,
+                    is_stopped,
+                )
             }
             None => {
                 let _timer = ScopeDurationMeasurer::new(&self.unfiltered_searches_telemetry);
                 let vector_storage = self.vector_storage.borrow();
                 let id_tracker = self.id_tracker.borrow();
-                vectors
+                let scorers: Vec<_> = query_vectors
                     .iter()
-                    .map(|vector| {
-                        new_stoppable_raw_scorer(
-                            vector.to_vec(),
+                    .map(|query_vector| {
+                        new_stoppable_query_scorer(
+                            query_vector,
                             &vector_storage,
                             id_tracker.deleted_point_bitslice(),
                             is_stopped,
                         )
-                        .peek_top_all(top)
                     })
-                    .collect()
+                    .collect();
+                let points_count = vector_storage.total_vector_count() as PointOffsetType;
+                peek_top_batch(&scorers, &mut (0..points_count), top, is_stopped)
+            }
+        });
+        *self.search_stats.lock() += stats;
+        if let Some(score_threshold) = score_threshold {
+            for result in &mut results {
+                result.retain(|scored| scored.score >= score_threshold);
             }
         }
+        results
+    }
+
+    fn search_path(&self, filter: Option<&Filter>, params: Option<&SearchParams>) -> SearchPath {
+        // Plain index scores points one by one anyway, so every search is exact
+        match (filter, params.map(|params| params.exact).unwrap_or(false)) {
+            (_, true) => SearchPath::Exact,
+            (Some(_), false) => SearchPath::FilteredScan,
+            (None, false) => SearchPath::FullScan,
+        }
     }
 
     fn build_index(&mut self, _stopped: &AtomicBool) -> OperationResult<()> {
@@ -286,6 +318,9 @@ impl VectorIndex for PlainIndex {
             filtered_large_cardinality: OperationDurationStatistics::default(),
             filtered_exact: OperationDurationStatistics::default(),
             unfiltered_exact: OperationDurationStatistics::default(),
+            clamped_hnsw_ef: 0,
+            last_clamped_hnsw_ef: None,
+            search_stats: *self.search_stats.lock(),
         }
     }
 
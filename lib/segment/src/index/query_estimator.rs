@@ -8,7 +8,7 @@ use std::cmp::{max, min};
 use itertools::Itertools;
 
 use crate::index::field_index::{CardinalityEstimation, PrimaryCondition};
-use crate::types::{Condition, Filter};
+use crate::types::{Condition, Filter, MinShould};
 
 /// Re-estimate cardinality based on number of available vectors
 /// Assuming that deleted vectors are not correlated with the filter
@@ -160,6 +160,84 @@ pub fn combine_must_estimations(
     }
 }
 
+/// Estimate cardinality of points, which match at least `min_count` of the conditions
+///
+/// A point, matching `min_count` conditions, can be missed by at most `n - min_count` of them.
+/// So it is selected by at least one of any `n - min_count + 1` conditions, which gives both
+/// the upper bound and the primary clauses to iterate over.
+pub fn combine_min_should_estimations(
+    estimations: &[CardinalityEstimation],
+    min_count: usize,
+    total: usize,
+) -> CardinalityEstimation {
+    let conditions_count = estimations.len();
+    if min_count == 0 {
+        return CardinalityEstimation::exact(total);
+    }
+    if min_count > conditions_count {
+        // There are not enough conditions to match, nothing to iterate over
+        return CardinalityEstimation::exact(0)
+            .with_primary_clause(PrimaryCondition::Ids(Default::default()));
+    }
+    if min_count == 1 {
+        return combine_should_estimations(estimations, total);
+    }
+    if min_count == conditions_count {
+        return combine_must_estimations(estimations, total);
+    }
+
+    let union_size = conditions_count - min_count + 1;
+
+    let max_estimation = {
+        let sorted_max = estimations.iter().map(|x| x.max).sorted().collect_vec();
+        let matches_max: usize = sorted_max.iter().sum();
+        let union_max: usize = sorted_max.iter().take(union_size).sum();
+        min(total, min(matches_max / min_count, union_max))
+    };
+
+    // Points, which don't match, are counted by at most `min_count - 1` conditions
+    let matches_min: usize = estimations.iter().map(|x| x.min).sum();
+    let min_estimation = matches_min.saturating_sub((min_count - 1) * total) / union_size;
+
+    // Probability of the number of matched conditions, assuming they are independent.
+    // The last element accumulates all `min_count` and more matches.
+    let mut matched_prob = vec![0.0; min_count + 1];
+    matched_prob[0] = 1.0;
+    for estimation in estimations {
+        let hit_prob = estimation.exp as f64 / total as f64;
+        matched_prob[min_count] += matched_prob[min_count - 1] * hit_prob;
+        for matched in (1..min_count).rev() {
+            matched_prob[matched] =
+                matched_prob[matched] * (1.0 - hit_prob) + matched_prob[matched - 1] * hit_prob;
+        }
+        matched_prob[0] *= 1.0 - hit_prob;
+    }
+    let exp_estimation = (matched_prob[min_count] * (total as f64)).round() as usize;
+
+    let indexed = estimations
+        .iter()
+        .filter(|x| !x.primary_clauses.is_empty())
+        .sorted_by_key(|x| x.exp)
+        .take(union_size)
+        .collect_vec();
+    let clauses = if indexed.len() == union_size {
+        indexed
+            .into_iter()
+            .flat_map(|x| x.primary_clauses.iter().cloned())
+            .collect()
+    } else {
+        // If too many conditions are un-indexed - we can't select the candidates
+        vec![]
+    };
+
+    CardinalityEstimation {
+        primary_clauses: clauses,
+        min: min_estimation,
+        exp: exp_estimation.max(min_estimation).min(max_estimation),
+        max: max_estimation,
+    }
+}
+
 fn estimate_condition<F>(
     estimator: &F,
     condition: &Condition,
@@ -196,6 +274,15 @@ where
             }
         }
     }
+    if let Some(MinShould {
+        conditions,
+        min_count,
+    }) = &filter.min_should
+    {
+        filter_estimations.push(estimate_min_should(
+            estimator, conditions, *min_count, total,
+        ));
+    }
     match &filter.must_not {
         None => {}
         Some(conditions) => {
@@ -221,6 +308,20 @@ where
     combine_should_estimations(&should_estimations, total)
 }
 
+fn estimate_min_should<F>(
+    estimator: &F,
+    conditions: &[Condition],
+    min_count: usize,
+    total: usize,
+) -> CardinalityEstimation
+where
+    F: Fn(&Condition) -> CardinalityEstimation,
+{
+    let estimate = |x| estimate_condition(estimator, x, total);
+    let min_should_estimations = conditions.iter().map(estimate).collect_vec();
+    combine_min_should_estimations(&min_should_estimations, min_count, total)
+}
+
 fn estimate_must<F>(estimator: &F, conditions: &[Condition], total: usize) -> CardinalityEstimation
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -347,6 +448,7 @@ mod tests {
                 test_condition("size".to_owned()),
                 test_condition("un-indexed".to_owned()),
             ]),
+            min_should: None,
             must_not: None,
         };
 
@@ -369,6 +471,7 @@ mod tests {
                 test_condition("size".to_owned()),
             ]),
             must: None,
+            min_should: None,
             must_not: None,
         };
 
@@ -388,6 +491,7 @@ mod tests {
                 test_condition("un-indexed".to_owned()),
             ]),
             must: None,
+            min_should: None,
             must_not: None,
         };
 
@@ -409,6 +513,7 @@ mod tests {
                         test_condition("color".to_owned()),
                         test_condition("size".to_owned()),
                     ]),
+                    min_should: None,
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
@@ -417,10 +522,12 @@ mod tests {
                         test_condition("price".to_owned()),
                         test_condition("size".to_owned()),
                     ]),
+                    min_should: None,
                     must_not: None,
                 }),
             ]),
             must: None,
+            min_should: None,
             must_not: Some(vec![Condition::HasId(HasIdCondition {
                 has_id: HashSet::from_iter([1, 2, 3, 4, 5].into_iter().map(|x| x.into())),
             })]),
@@ -444,6 +551,7 @@ mod tests {
                         test_condition("color".to_owned()),
                         test_condition("size".to_owned()),
                     ]),
+                    min_should: None,
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
@@ -452,9 +560,11 @@ mod tests {
                         test_condition("price".to_owned()),
                         test_condition("size".to_owned()),
                     ]),
+                    min_should: None,
                     must_not: None,
                 }),
             ]),
+            min_should: None,
             must_not: Some(vec![Condition::HasId(HasIdCondition {
                 has_id: HashSet::from_iter([1, 2, 3, 4, 5].into_iter().map(|x| x.into())),
             })]),
@@ -473,6 +583,55 @@ mod tests {
         assert!(estimation.min <= estimation.exp);
     }
 
+    #[test]
+    fn min_should_estimation_query_test() {
+        let conditions = vec![
+            test_condition("color".to_owned()),
+            test_condition("size".to_owned()),
+            test_condition("price".to_owned()),
+        ];
+        let query = Filter::new_min_should(MinShould::new(conditions.clone(), 2).unwrap());
+
+        let estimation = estimate_filter(&test_estimator, &query, TOTAL);
+        // Any matching point is selected by one of the two least probable conditions
+        assert_eq!(estimation.primary_clauses.len(), 2);
+        estimation.primary_clauses.iter().for_each(|x| match x {
+            PrimaryCondition::Condition(field) => {
+                assert!(vec!["price".to_owned(), "size".to_owned()].contains(&field.key))
+            }
+            _ => panic!("Should not go here"),
+        });
+        assert_eq!(estimation.max, 120);
+        assert!(estimation.exp <= estimation.max);
+        assert!(estimation.min <= estimation.exp);
+
+        // Too many un-indexed conditions to select candidates
+        let query = Filter::new_min_should(
+            MinShould::new(
+                vec![
+                    test_condition("color".to_owned()),
+                    test_condition("un-indexed".to_owned()),
+                    test_condition("another-un-indexed".to_owned()),
+                ],
+                2,
+            )
+            .unwrap(),
+        );
+        let estimation = estimate_filter(&test_estimator, &query, TOTAL);
+        assert!(estimation.primary_clauses.is_empty());
+        assert!(estimation.min <= estimation.exp);
+        assert!(estimation.exp <= estimation.max);
+
+        // More than the number of conditions can't match anything
+        let query = Filter::new_min_should(MinShould::new(conditions, 4).unwrap());
+        let estimation = estimate_filter(&test_estimator, &query, TOTAL);
+        assert_eq!(estimation.max, 0);
+        assert!(matches!(
+            estimation.primary_clauses.as_slice(),
+            [PrimaryCondition::Ids(ids)] if ids.is_empty()
+        ));
+    }
+
     #[test]
     fn test_combine_must_estimations() {
         let estimations = vec![CardinalityEstimation {
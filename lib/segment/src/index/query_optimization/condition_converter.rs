@@ -1,9 +1,11 @@
 use std::collections::HashSet;
 
+use itertools::Itertools;
 use serde_json::Value;
 
 use crate::common::utils::IndexesMap;
 use crate::id_tracker::IdTrackerSS;
+use crate::index::field_index::map_index::parse_uuid;
 use crate::index::field_index::FieldIndex;
 use crate::index::query_optimization::optimized_filter::ConditionCheckerFn;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
@@ -12,9 +14,9 @@ use crate::payload_storage::query_checker::{
     select_nested_indexes,
 };
 use crate::types::{
-    AnyVariants, Condition, FieldCondition, FloatPayloadType, GeoBoundingBox, GeoRadius, Match,
-    MatchAny, MatchExcept, MatchText, MatchValue, OwnedPayloadRef, PayloadContainer,
-    PointOffsetType, Range, ValueVariants,
+    AnyVariants, Condition, FieldCondition, FloatPayloadType, GeoBoundingBox, GeoPolygon,
+    GeoRadius, Match, MatchAny, MatchExcept, MatchValue, OwnedPayloadRef, PayloadContainer,
+    PointOffsetType, RangeInterface, ValueVariants,
 };
 
 pub fn condition_converter<'a>(
@@ -159,6 +161,14 @@ pub fn field_condition_index<'a>(
         return Some(checker);
     }
 
+    if let Some(checker) = field_condition
+        .geo_polygon
+        .clone()
+        .and_then(|cond| get_geo_polygon_checkers(index, cond))
+    {
+        return Some(checker);
+    }
+
     None
 }
 
@@ -195,25 +205,55 @@ pub fn get_geo_bounding_box_checkers(
     }
 }
 
-pub fn get_range_checkers(index: &FieldIndex, range: Range) -> Option<ConditionCheckerFn> {
+pub fn get_geo_polygon_checkers(
+    index: &FieldIndex,
+    geo_polygon: GeoPolygon,
+) -> Option<ConditionCheckerFn> {
     match index {
-        FieldIndex::IntIndex(num_index) => Some(Box::new(move |point_id: PointOffsetType| {
-            num_index.get_values(point_id).map_or(false, |values| {
+        FieldIndex::GeoIndex(geo_index) => Some(Box::new(move |point_id: PointOffsetType| {
+            geo_index.get_values(point_id).map_or(false, |values| {
                 values
                     .iter()
-                    .copied()
-                    .any(|i| range.check_range(i as FloatPayloadType))
-            })
-        })),
-        FieldIndex::FloatIndex(num_index) => Some(Box::new(move |point_id: PointOffsetType| {
-            num_index.get_values(point_id).map_or(false, |values| {
-                values.iter().copied().any(|i| range.check_range(i))
+                    .any(|geo_point| geo_polygon.check_point(geo_point.lon, geo_point.lat))
             })
         })),
         _ => None,
     }
 }
 
+pub fn get_range_checkers(index: &FieldIndex, range: RangeInterface) -> Option<ConditionCheckerFn> {
+    match (range, index) {
+        (RangeInterface::Float(range), FieldIndex::IntIndex(num_index)) => {
+            Some(Box::new(move |point_id: PointOffsetType| {
+                num_index.get_values(point_id).map_or(false, |values| {
+                    values
+                        .iter()
+                        .copied()
+                        .any(|i| range.check_range(i as FloatPayloadType))
+                })
+            }))
+        }
+        (RangeInterface::Float(range), FieldIndex::FloatIndex(num_index)) => {
+            Some(Box::new(move |point_id: PointOffsetType| {
+                num_index.get_values(point_id).map_or(false, |values| {
+                    values.iter().copied().any(|i| range.check_range(i))
+                })
+            }))
+        }
+        (RangeInterface::DateTime(range), FieldIndex::DatetimeIndex(datetime_index)) => {
+            let range = range.to_timestamp_range();
+            Some(Box::new(move |point_id: PointOffsetType| {
+                datetime_index.get_values(point_id).map_or(false, |values| {
+                    values
+                        .iter()
+                        .any(|&timestamp| range.check_range(timestamp as FloatPayloadType))
+                })
+            }))
+        }
+        _ => None,
+    }
+}
+
 pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<ConditionCheckerFn> {
     match cond_match {
         Match::Value(MatchValue {
@@ -233,11 +273,19 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                         .map_or(false, |values| values.iter().any(|i| i == &value))
                 }))
             }
+            (ValueVariants::Keyword(keyword), FieldIndex::UuidIndex(index)) => {
+                let uuid = parse_uuid(&keyword);
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |values| values.iter().any(|u| Some(*u) == uuid))
+                }))
+            }
             _ => None,
         },
-        Match::Text(MatchText { text }) => match index {
+        Match::Text(_) | Match::Phrase(_) => match index {
             FieldIndex::FullTextIndex(full_text_index) => {
-                let parsed_query = full_text_index.parse_query(&text);
+                let parsed_query = full_text_index.parse_match(&cond_match)?;
                 Some(Box::new(move |point_id: PointOffsetType| {
                     full_text_index
                         .get_doc(point_id)
@@ -263,6 +311,14 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                         .map_or(false, |values| values.iter().any(|i| list.contains(i)))
                 }))
             }
+            (AnyVariants::Keywords(list), FieldIndex::UuidIndex(index)) => {
+                let uuids = list.iter().filter_map(|k| parse_uuid(k)).collect_vec();
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |values| values.iter().any(|u| uuids.contains(u)))
+                }))
+            }
             _ => None,
         },
         Match::Except(MatchExcept { except }) => match (except, index) {
@@ -282,6 +338,14 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                         .map_or(false, |values| values.iter().any(|i| !list.contains(i)))
                 }))
             }
+            (AnyVariants::Keywords(list), FieldIndex::UuidIndex(index)) => {
+                let uuids = list.iter().filter_map(|k| parse_uuid(k)).collect_vec();
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |values| values.iter().any(|u| !uuids.contains(u)))
+                }))
+            }
             (_, index) => Some(Box::new(|point_id: PointOffsetType| {
                 // If there is any other value of any other index, then it's a match
                 index.values_count(point_id) > 0
@@ -0,0 +1,55 @@
+use super::posting_list::{PostingElement, PostingList};
+use crate::data_types::sparse_vector::{DimId, SparseVector};
+use crate::types::PointOffsetType;
+
+/// Read access to posting lists of an inverted index
+pub trait InvertedIndex {
+    /// Posting list of the dimension, `None` if no point has a value in it
+    fn posting_list(&self, dim_id: DimId) -> Option<&[PostingElement]>;
+
+    /// Number of dimensions with a posting list, including empty ones
+    fn dims_count(&self) -> usize;
+}
+
+/// Mutable inverted index in memory
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InvertedIndexRam {
+    /// Posting list of each dimension, indexed by dimension id
+    pub postings: Vec<PostingList>,
+}
+
+impl InvertedIndex for InvertedIndexRam {
+    fn posting_list(&self, dim_id: DimId) -> Option<&[PostingElement]> {
+        self.postings
+            .get(dim_id as usize)
+            .map(|posting_list| posting_list.elements.as_slice())
+    }
+
+    fn dims_count(&self) -> usize {
+        self.postings.len()
+    }
+}
+
+impl InvertedIndexRam {
+    /// Add all non-zero components of the vector of the point
+    ///
+    /// Components of the previous vector of the point must be removed before, see [`Self::remove`].
+    pub fn upsert(&mut self, record_id: PointOffsetType, vector: &SparseVector) {
+        for (dim_id, weight) in vector.iter() {
+            if self.postings.len() <= dim_id as usize {
+                self.postings
+                    .resize_with(dim_id as usize + 1, PostingList::default);
+            }
+            self.postings[dim_id as usize].upsert(PostingElement { record_id, weight });
+        }
+    }
+
+    /// Remove the point from posting lists of all components of its vector
+    pub fn remove(&mut self, record_id: PointOffsetType, vector: &SparseVector) {
+        for dim_id in vector.indices.iter() {
+            if let Some(posting_list) = self.postings.get_mut(*dim_id as usize) {
+                posting_list.remove(record_id);
+            }
+        }
+    }
+}
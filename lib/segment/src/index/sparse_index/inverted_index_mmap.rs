@@ -0,0 +1,181 @@
+use std::io::Write;
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use memmap2::Mmap;
+
+use super::inverted_index::{InvertedIndex, InvertedIndexRam};
+use super::posting_list::PostingElement;
+use crate::common::mmap_ops;
+use crate::data_types::sparse_vector::DimId;
+use crate::entry::entry_point::{OperationError, OperationResult};
+
+/*
+Inverted index file structure:
+┌────────────────────┬──────────────────────────┬───────────────────────────────────┐
+│ header             │ offsets                  │ elements                          │
+│ dims_count: u64    │ u64 * (dims_count + 1)   │ PostingElement * elements_count   │
+│ elements_count: u64│ start of each posting    │ posting lists one after another   │
+└────────────────────┴──────────────────────────┴───────────────────────────────────┘
+Posting list of dimension `d` is `elements[offsets[d]..offsets[d + 1]]`.
+*/
+
+pub const INVERTED_INDEX_FILE: &str = "inverted_index.data";
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+struct InvertedIndexFileHeader {
+    pub dims_count: u64,
+    pub elements_count: u64,
+}
+
+impl InvertedIndexFileHeader {
+    fn offsets_range(&self) -> Range<usize> {
+        let start = size_of::<Self>();
+        start..start + (self.dims_count as usize + 1) * size_of::<u64>()
+    }
+
+    fn elements_range(&self) -> Range<usize> {
+        let start = self.offsets_range().end;
+        start..start + self.elements_count as usize * size_of::<PostingElement>()
+    }
+}
+
+/// Immutable inverted index with posting lists in a mmap file
+pub struct InvertedIndexMmap {
+    path: PathBuf,
+    mmap: Arc<Mmap>,
+    header: InvertedIndexFileHeader,
+}
+
+impl InvertedIndex for InvertedIndexMmap {
+    fn posting_list(&self, dim_id: DimId) -> Option<&[PostingElement]> {
+        let dim_id = dim_id as usize;
+        if dim_id >= self.header.dims_count as usize {
+            return None;
+        }
+        let offsets = self.offsets();
+        let range = offsets[dim_id] as usize..offsets[dim_id + 1] as usize;
+        Some(&self.elements()[range])
+    }
+
+    fn dims_count(&self) -> usize {
+        self.header.dims_count as usize
+    }
+}
+
+impl InvertedIndexMmap {
+    pub fn index_file_path(path: &Path) -> PathBuf {
+        path.join(INVERTED_INDEX_FILE)
+    }
+
+    /// Write posting lists of the RAM index into the file in `path` and open it
+    pub fn convert_and_save(index: &InvertedIndexRam, path: &Path) -> OperationResult<Self> {
+        let header = InvertedIndexFileHeader {
+            dims_count: index.postings.len() as u64,
+            elements_count: index
+                .postings
+                .iter()
+                .map(|posting_list| posting_list.elements.len() as u64)
+                .sum(),
+        };
+
+        let mut offsets = Vec::with_capacity(index.postings.len() + 1);
+        offsets.push(0u64);
+        for posting_list in &index.postings {
+            let last = offsets.last().copied().unwrap_or_default();
+            offsets.push(last + posting_list.elements.len() as u64);
+        }
+
+        let file_path = Self::index_file_path(path);
+        AtomicFile::new(&file_path, OverwriteBehavior::AllowOverwrite).write(|file| {
+            let mut writer = std::io::BufWriter::new(file);
+            writer.write_all(mmap_ops::transmute_to_u8(&header))?;
+            writer.write_all(mmap_ops::transmute_to_u8_slice(&offsets))?;
+            for posting_list in &index.postings {
+                writer.write_all(mmap_ops::transmute_to_u8_slice(&posting_list.elements))?;
+            }
+            writer.flush()?;
+            writer.get_ref().sync_all()
+        })?;
+
+        Self::load(path)
+    }
+
+    /// Open index file in `path`, the file must exist
+    pub fn load(path: &Path) -> OperationResult<Self> {
+        let file_path = Self::index_file_path(path);
+        let mmap = mmap_ops::open_read_mmap(&file_path)?;
+
+        if mmap.len() < size_of::<InvertedIndexFileHeader>() {
+            return Err(OperationError::service_error(format!(
+                "Inverted index file {} is too short",
+                file_path.display(),
+            )));
+        }
+        let header = mmap_ops::transmute_from_u8_to_slice::<InvertedIndexFileHeader>(
+            &mmap[..size_of::<InvertedIndexFileHeader>()],
+        )[0];
+        if mmap.len() != header.elements_range().end {
+            return Err(OperationError::service_error(format!(
+                "Inverted index file {} is corrupted, expected {} bytes, got {}",
+                file_path.display(),
+                header.elements_range().end,
+                mmap.len(),
+            )));
+        }
+
+        Ok(Self {
+            path: file_path,
+            mmap: Arc::new(mmap),
+            header,
+        })
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
+
+    pub fn prefault_mmap_pages(&self) -> mmap_ops::PrefaultMmapPages {
+        mmap_ops::PrefaultMmapPages::new(self.mmap.clone(), Some(&self.path))
+    }
+
+    fn offsets(&self) -> &[u64] {
+        mmap_ops::transmute_from_u8_to_slice(&self.mmap[self.header.offsets_range()])
+    }
+
+    fn elements(&self) -> &[PostingElement] {
+        mmap_ops::transmute_from_u8_to_slice(&self.mmap[self.header.elements_range()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::data_types::sparse_vector::SparseVector;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = Builder::new().prefix("inverted_index").tempdir().unwrap();
+
+        let mut ram_index = InvertedIndexRam::default();
+        ram_index.upsert(0, &SparseVector::new(vec![1, 3], vec![1.0, 3.0]).unwrap());
+        ram_index.upsert(1, &SparseVector::new(vec![3, 7], vec![30.0, 70.0]).unwrap());
+        ram_index.upsert(2, &SparseVector::new(vec![1], vec![10.0]).unwrap());
+
+        let mmap_index = InvertedIndexMmap::convert_and_save(&ram_index, dir.path()).unwrap();
+        let loaded_index = InvertedIndexMmap::load(dir.path()).unwrap();
+
+        for index in [&mmap_index, &loaded_index] {
+            assert_eq!(index.dims_count(), ram_index.dims_count());
+            for dim_id in 0..=ram_index.dims_count() as DimId {
+                assert_eq!(index.posting_list(dim_id), ram_index.posting_list(dim_id));
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Search of sparse vectors with an inverted index
+//!
+//! Every dimension has a posting list of points with a non-zero value in it. Search only visits
+//! posting lists of dimensions present in the query, and sums weights of each visited point.
+
+pub mod inverted_index;
+pub mod inverted_index_mmap;
+pub mod posting_list;
+pub mod search_context;
+pub mod sparse_vector_index;
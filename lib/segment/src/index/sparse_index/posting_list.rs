@@ -0,0 +1,84 @@
+use crate::data_types::vectors::VectorElementType;
+use crate::types::PointOffsetType;
+
+/// Point with a non-zero value in the dimension of the posting list
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostingElement {
+    pub record_id: PointOffsetType,
+    pub weight: VectorElementType,
+}
+
+/// Points with a non-zero value in a single dimension, sorted by internal id
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PostingList {
+    pub elements: Vec<PostingElement>,
+}
+
+impl PostingList {
+    /// Insert the element or replace the weight if the point is already in the list
+    pub fn upsert(&mut self, element: PostingElement) {
+        // Points are mostly inserted in the order of internal ids, `None` is less than any id
+        let last_id = self.elements.last().map(|last| last.record_id);
+        if last_id < Some(element.record_id) {
+            self.elements.push(element);
+            return;
+        }
+        match self
+            .elements
+            .binary_search_by_key(&element.record_id, |e| e.record_id)
+        {
+            Ok(position) => self.elements[position].weight = element.weight,
+            Err(position) => self.elements.insert(position, element),
+        }
+    }
+
+    /// Remove the point from the list, returns `true` if it was present
+    pub fn remove(&mut self, record_id: PointOffsetType) -> bool {
+        match self
+            .elements
+            .binary_search_by_key(&record_id, |e| e.record_id)
+        {
+            Ok(position) => {
+                self.elements.remove(position);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(record_id: PointOffsetType, weight: VectorElementType) -> PostingElement {
+        PostingElement { record_id, weight }
+    }
+
+    #[test]
+    fn test_upsert_and_remove() {
+        let mut posting_list = PostingList::default();
+        for record_id in [1, 5, 3, 9, 0] {
+            posting_list.upsert(element(record_id, record_id as f32));
+        }
+        posting_list.upsert(element(3, 30.0));
+
+        assert_eq!(
+            posting_list.elements,
+            vec![
+                element(0, 0.0),
+                element(1, 1.0),
+                element(3, 30.0),
+                element(5, 5.0),
+                element(9, 9.0),
+            ],
+        );
+
+        assert!(posting_list.remove(5));
+        assert!(!posting_list.remove(5));
+        assert!(!posting_list.remove(100));
+        let ids: Vec<_> = posting_list.elements.iter().map(|e| e.record_id).collect();
+        assert_eq!(ids, vec![0, 1, 3, 9]);
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::inverted_index::InvertedIndex;
+use crate::data_types::sparse_vector::SparseVector;
+use crate::spaces::tools::peek_top_largest_iterable;
+use crate::types::{PointOffsetType, ScoreType};
+use crate::vector_storage::ScoredPointOffset;
+
+/// Top `top` points by dot product with the query, traversing posting lists of query dimensions
+///
+/// Only points sharing at least one dimension with the query are scored. `check` is called once
+/// per visited point, points it rejects are skipped in all subsequent posting lists.
+pub fn search_inverted_index<I: InvertedIndex + ?Sized>(
+    index: &I,
+    query: &SparseVector,
+    top: usize,
+    check: impl Fn(PointOffsetType) -> bool,
+    is_stopped: &AtomicBool,
+) -> Vec<ScoredPointOffset> {
+    // `None` marks points rejected by `check`
+    let mut scores: HashMap<PointOffsetType, Option<ScoreType>> = HashMap::new();
+    for (dim_id, query_weight) in query.iter() {
+        if is_stopped.load(Ordering::Relaxed) {
+            return vec![];
+        }
+        let Some(posting_list) = index.posting_list(dim_id) else {
+            continue;
+        };
+        for element in posting_list {
+            let score = element.weight * query_weight;
+            match scores.entry(element.record_id) {
+                Entry::Occupied(mut entry) => {
+                    if let Some(accumulated) = entry.get_mut() {
+                        *accumulated += score;
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(check(element.record_id).then_some(score));
+                }
+            }
+        }
+    }
+
+    let scored_points = scores
+        .into_iter()
+        .filter_map(|(idx, score)| score.map(|score| ScoredPointOffset { idx, score }));
+    peek_top_largest_iterable(scored_points, top)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::sparse_index::inverted_index::InvertedIndexRam;
+
+    #[test]
+    fn test_search_inverted_index() {
+        let vectors = [
+            SparseVector::new(vec![0, 1, 2], vec![1.0, 1.0, 1.0]).unwrap(),
+            SparseVector::new(vec![1, 2], vec![2.0, 2.0]).unwrap(),
+            SparseVector::new(vec![2, 3], vec![3.0, 3.0]).unwrap(),
+            SparseVector::new(vec![4], vec![10.0]).unwrap(),
+        ];
+        let mut index = InvertedIndexRam::default();
+        for (id, vector) in vectors.iter().enumerate() {
+            index.upsert(id as PointOffsetType, vector);
+        }
+        let stopped = AtomicBool::new(false);
+        let query = SparseVector::new(vec![1, 2, 5], vec![1.0, 2.0, 100.0]).unwrap();
+
+        let result = search_inverted_index(&index, &query, 10, |_| true, &stopped);
+        // Points 1 and 2 have equal scores 6.0, order between them is not defined.
+        // Point 3 has no common dimensions with the query.
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[2], ScoredPointOffset { idx: 0, score: 3.0 });
+        for scored in &result {
+            assert_eq!(
+                vectors[scored.idx as usize].score(&query),
+                Some(scored.score)
+            );
+        }
+
+        let result = search_inverted_index(&index, &query, 1, |idx| idx != 1, &stopped);
+        assert_eq!(result, vec![ScoredPointOffset { idx: 2, score: 6.0 }]);
+    }
+}
@@ -0,0 +1,245 @@
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::BitSlice;
+
+use super::inverted_index::{InvertedIndex, InvertedIndexRam};
+use super::inverted_index_mmap::InvertedIndexMmap;
+use super::search_context::search_inverted_index;
+use crate::common::mmap_ops;
+use crate::data_types::search_plan::SearchPath;
+use crate::data_types::sparse_vector::SparseVector;
+use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
+use crate::id_tracker::IdTrackerSS;
+use crate::index::struct_payload_index::StructPayloadIndex;
+use crate::index::PayloadIndex;
+use crate::spaces::tools::peek_top_largest_iterable;
+use crate::types::{Filter, PointOffsetType, SparseIndexConfig, SparseIndexType};
+use crate::vector_storage::simple_sparse_vector_storage::SimpleSparseVectorStorage;
+use crate::vector_storage::ScoredPointOffset;
+
+pub enum InvertedIndexEnum {
+    Ram(InvertedIndexRam),
+    Mmap(InvertedIndexMmap),
+}
+
+impl InvertedIndexEnum {
+    fn as_inverted_index(&self) -> &dyn InvertedIndex {
+        match self {
+            InvertedIndexEnum::Ram(index) => index,
+            InvertedIndexEnum::Mmap(index) => index,
+        }
+    }
+}
+
+/// Index of sparse vectors, searches top points by dot product with an inverted index
+pub struct SparseVectorIndex {
+    config: SparseIndexConfig,
+    id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    vector_storage: Arc<AtomicRefCell<SimpleSparseVectorStorage>>,
+    payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+    path: PathBuf,
+    inverted_index: InvertedIndexEnum,
+}
+
+impl SparseVectorIndex {
+    /// Open index in `path`
+    ///
+    /// Mutable index is rebuilt from the vector storage. Mmap index is loaded from its file,
+    /// or is built from the vector storage on [`SparseVectorIndex::build_index`] if there is none.
+    pub fn open(
+        config: SparseIndexConfig,
+        id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+        vector_storage: Arc<AtomicRefCell<SimpleSparseVectorStorage>>,
+        payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+        path: &Path,
+    ) -> OperationResult<Self> {
+        create_dir_all(path)?;
+        let has_mmap_file = InvertedIndexMmap::index_file_path(path).exists();
+        let inverted_index = match config.index_type {
+            SparseIndexType::Mmap if has_mmap_file => {
+                InvertedIndexEnum::Mmap(InvertedIndexMmap::load(path)?)
+            }
+            SparseIndexType::Mmap | SparseIndexType::MutableRam => {
+                InvertedIndexEnum::Ram(Self::build_ram_index(
+                    &*id_tracker.borrow(),
+                    &vector_storage.borrow(),
+                    &AtomicBool::new(false),
+                )?)
+            }
+        };
+        Ok(Self {
+            config,
+            id_tracker,
+            vector_storage,
+            payload_index,
+            path: path.to_owned(),
+            inverted_index,
+        })
+    }
+
+    fn build_ram_index(
+        id_tracker: &IdTrackerSS,
+        vector_storage: &SimpleSparseVectorStorage,
+        stopped: &AtomicBool,
+    ) -> OperationResult<InvertedIndexRam> {
+        let mut index = InvertedIndexRam::default();
+        let point_deleted = id_tracker.deleted_point_bitslice();
+        let vector_deleted = vector_storage.deleted_vector_bitslice();
+        for idx in 0..vector_storage.total_vector_count() as PointOffsetType {
+            check_process_stopped(stopped)?;
+            if is_active(point_deleted, vector_deleted, idx) {
+                index.upsert(idx, vector_storage.get_vector(idx));
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn config(&self) -> SparseIndexConfig {
+        self.config
+    }
+
+    /// Whether vectors can be added into this index
+    pub fn is_appendable(&self) -> bool {
+        self.config.index_type == SparseIndexType::MutableRam
+    }
+
+    /// Replace vector of the point in the index. Available for mutable index only.
+    pub fn upsert(
+        &mut self,
+        idx: PointOffsetType,
+        old_vector: &SparseVector,
+        new_vector: &SparseVector,
+    ) -> OperationResult<()> {
+        match &mut self.inverted_index {
+            InvertedIndexEnum::Ram(index) => {
+                index.remove(idx, old_vector);
+                index.upsert(idx, new_vector);
+                Ok(())
+            }
+            InvertedIndexEnum::Mmap(_) => Err(OperationError::service_error(
+                "Cannot update vectors in mmap sparse index",
+            )),
+        }
+    }
+
+    /// Remove vector of the point from the index
+    ///
+    /// Mmap index can't be changed, deleted points are skipped during search there.
+    pub fn remove(&mut self, idx: PointOffsetType, old_vector: &SparseVector) {
+        match &mut self.inverted_index {
+            InvertedIndexEnum::Ram(index) => index.remove(idx, old_vector),
+            InvertedIndexEnum::Mmap(_) => {}
+        }
+    }
+
+    /// Rebuild index from the vector storage, mmap index is saved to disk
+    pub fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
+        let ram_index = Self::build_ram_index(
+            &*self.id_tracker.borrow(),
+            &self.vector_storage.borrow(),
+            stopped,
+        )?;
+        self.inverted_index = match self.config.index_type {
+            SparseIndexType::MutableRam => InvertedIndexEnum::Ram(ram_index),
+            SparseIndexType::Mmap => InvertedIndexEnum::Mmap(InvertedIndexMmap::convert_and_save(
+                &ram_index, &self.path,
+            )?),
+        };
+        Ok(())
+    }
+
+    /// Way [`Self::search`] would execute a search with the given filter
+    pub fn search_path(&self, filter: Option<&Filter>) -> SearchPath {
+        let Some(filter) = filter else {
+            return SearchPath::InvertedIndex;
+        };
+        let query_cardinality = self.payload_index.borrow().estimate_cardinality(filter);
+        if query_cardinality.max < self.config.full_scan_threshold() {
+            // Few points match, it is cheaper to score them one by one
+            SearchPath::FilteredScan
+        } else {
+            SearchPath::InvertedIndex
+        }
+    }
+
+    /// Top `top` points by dot product with the query, among points matching `filter`
+    ///
+    /// Only points sharing at least one non-zero dimension with the query are returned.
+    pub fn search(
+        &self,
+        query: &SparseVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        let id_tracker = self.id_tracker.borrow();
+        let vector_storage = self.vector_storage.borrow();
+        let point_deleted = id_tracker.deleted_point_bitslice();
+        let vector_deleted = vector_storage.deleted_vector_bitslice();
+        let index = self.inverted_index.as_inverted_index();
+
+        let Some(filter) = filter else {
+            return search_inverted_index(
+                index,
+                query,
+                top,
+                |idx| is_active(point_deleted, vector_deleted, idx),
+                is_stopped,
+            );
+        };
+
+        let payload_index = self.payload_index.borrow();
+        if self.search_path(Some(filter)) == SearchPath::FilteredScan {
+            let scores = payload_index
+                .query_points(filter)
+                .into_iter()
+                .take_while(|_| !is_stopped.load(Ordering::Relaxed))
+                .filter(|idx| is_active(point_deleted, vector_deleted, *idx))
+                .filter_map(|idx| {
+                    let score = vector_storage.get_vector(idx).score(query)?;
+                    Some(ScoredPointOffset { idx, score })
+                });
+            return peek_top_largest_iterable(scores, top);
+        }
+
+        let filter_context = payload_index.filter_context(filter);
+        search_inverted_index(
+            index,
+            query,
+            top,
+            |idx| is_active(point_deleted, vector_deleted, idx) && filter_context.check(idx),
+            is_stopped,
+        )
+    }
+
+    /// Number of vectors in the index, including deleted ones in mmap index
+    pub fn indexed_vector_count(&self) -> usize {
+        self.vector_storage.borrow().total_vector_count()
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        match &self.inverted_index {
+            InvertedIndexEnum::Ram(_) => vec![],
+            InvertedIndexEnum::Mmap(index) => index.files(),
+        }
+    }
+
+    pub fn prefault_mmap_pages(&self) -> Option<mmap_ops::PrefaultMmapPages> {
+        match &self.inverted_index {
+            InvertedIndexEnum::Ram(_) => None,
+            InvertedIndexEnum::Mmap(index) => Some(index.prefault_mmap_pages()),
+        }
+    }
+}
+
+/// Whether neither the point nor its sparse vector are deleted
+fn is_active(point_deleted: &BitSlice, vector_deleted: &BitSlice, idx: PointOffsetType) -> bool {
+    // Default to deleted if the point mapping was removed from the ID tracker
+    !point_deleted.get(idx as usize).map(|x| *x).unwrap_or(true)
+        // Default to not deleted if deleted flags of the storage failed to grow
+        && !vector_deleted.get(idx as usize).map(|x| *x).unwrap_or(false)
+}
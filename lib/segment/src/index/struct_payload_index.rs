@@ -6,7 +6,7 @@ use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
 use log::debug;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rocksdb::DB;
 use schemars::_serde_json::Value;
 
@@ -14,6 +14,7 @@ use crate::common::arc_atomic_ref_cell_iterator::ArcAtomicRefCellIterator;
 use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
 use crate::common::utils::{IndexesMap, JsonPathPayload, MultiValue};
 use crate::common::Flusher;
+use crate::data_types::search_plan::PrimaryClausePlan;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::index_selector::index_selector;
@@ -32,11 +33,61 @@ use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     infer_collection_value_type, infer_value_type, Condition, FieldCondition, Filter,
     IsEmptyCondition, IsNullCondition, Payload, PayloadContainer, PayloadField, PayloadFieldSchema,
-    PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType,
+    PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PayloadSelector, PointOffsetType,
 };
 
 pub const PAYLOAD_FIELD_INDEX_PATH: &str = "fields";
 
+/// Name of the storage of a field index.
+/// The initial generation is named after the field, so indexes built before generations are loaded.
+fn index_storage_name(field: PayloadKeyTypeRef, generation: u32) -> String {
+    if generation == 0 {
+        field.to_string()
+    } else {
+        format!("{field}@v{generation}")
+    }
+}
+
+/// Indexes of a field, which are being built to replace the current ones
+struct RebuildingIndex {
+    schema: PayloadFieldSchema,
+    generation: u32,
+    indexes: Vec<FieldIndex>,
+    /// Next existing point to add to the indexes
+    next_point: PointOffsetType,
+    /// Points inserted after the rebuild has started are added by updates, not by the rebuild
+    end_point: PointOffsetType,
+    /// First error of a payload update, the rebuild fails with it
+    error: Option<OperationError>,
+}
+
+impl RebuildingIndex {
+    fn is_complete(&self) -> bool {
+        self.next_point >= self.end_point
+    }
+
+    /// Apply a payload update to the indexes.
+    /// An error doesn't fail the update, as current indexes are still in use, but fails the rebuild.
+    fn update(&mut self, mut f: impl FnMut(&mut FieldIndex) -> OperationResult<()>) {
+        if self.error.is_some() {
+            return;
+        }
+        for index in &mut self.indexes {
+            if let Err(err) = f(index) {
+                self.error = Some(err);
+                return;
+            }
+        }
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        for index in self.indexes {
+            index.clear()?;
+        }
+        Ok(())
+    }
+}
+
 /// `PayloadIndex` implementation, which actually uses index structures for providing faster search
 pub struct StructPayloadIndex {
     /// Payload storage
@@ -51,6 +102,8 @@ pub struct StructPayloadIndex {
     /// Used to select unique point ids
     visited_pool: VisitedPool,
     db: Arc<RwLock<DB>>,
+    /// Indexes of fields, which are being rebuilt with a new schema
+    rebuilding: Mutex<HashMap<PayloadKeyType, RebuildingIndex>>,
 }
 
 impl StructPayloadIndex {
@@ -93,6 +146,33 @@ impl StructPayloadIndex {
         indexes
     }
 
+    /// Describe how points of the primary clause are selected by [`PayloadIndex::query_points`]
+    pub fn primary_clause_plan(&self, clause: &PrimaryCondition) -> PrimaryClausePlan {
+        match clause {
+            PrimaryCondition::Condition(field_condition) => PrimaryClausePlan {
+                key: Some(field_condition.key.clone()),
+                index: self
+                    .field_indexes
+                    .get(&field_condition.key)
+                    .and_then(|indexes| {
+                        indexes
+                            .iter()
+                            .find(|index| index.filter(field_condition).is_some())
+                    })
+                    .map(FieldIndex::index_type),
+            },
+            PrimaryCondition::IsEmpty(IsEmptyCondition { is_empty: field })
+            | PrimaryCondition::IsNull(IsNullCondition { is_null: field }) => PrimaryClausePlan {
+                key: Some(field.key.clone()),
+                index: None,
+            },
+            PrimaryCondition::Ids(_) => PrimaryClausePlan {
+                key: None,
+                index: None,
+            },
+        }
+    }
+
     fn config_path(&self) -> PathBuf {
         PayloadConfig::get_config_path(&self.path)
     }
@@ -118,7 +198,8 @@ impl StructPayloadIndex {
         field: PayloadKeyTypeRef,
         payload_schema: PayloadFieldSchema,
     ) -> OperationResult<Vec<FieldIndex>> {
-        let mut indexes = index_selector(field, &payload_schema, self.db.clone());
+        let mut indexes =
+            self.create_field_indexes(field, &payload_schema, self.index_generation(field));
 
         let mut is_loaded = true;
         for ref mut index in indexes.iter_mut() {
@@ -159,6 +240,7 @@ impl StructPayloadIndex {
             path: path.to_owned(),
             visited_pool: Default::default(),
             db,
+            rebuilding: Default::default(),
         };
 
         if !index.config_path().exists() {
@@ -171,13 +253,47 @@ impl StructPayloadIndex {
         Ok(index)
     }
 
+    /// Check the payload against all field indexes, so an invalid value doesn't leave them
+    /// partially updated
+    fn validate_payload(&self, payload: &Payload) -> OperationResult<()> {
+        for (field, field_indexes) in &self.field_indexes {
+            let field_value = &payload.get_value(field);
+            for index in field_indexes {
+                index.validate_payload(field_value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn index_generation(&self, field: PayloadKeyTypeRef) -> u32 {
+        self.config
+            .index_generations
+            .get(field)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn create_field_indexes(
+        &self,
+        field: PayloadKeyTypeRef,
+        payload_schema: &PayloadFieldSchema,
+        generation: u32,
+    ) -> Vec<FieldIndex> {
+        index_selector(
+            &index_storage_name(field, generation),
+            payload_schema,
+            self.db.clone(),
+        )
+    }
+
     pub fn build_field_indexes(
         &self,
         field: PayloadKeyTypeRef,
         payload_schema: PayloadFieldSchema,
     ) -> OperationResult<Vec<FieldIndex>> {
         let payload_storage = self.payload.borrow();
-        let mut field_indexes = index_selector(field, &payload_schema, self.db.clone());
+        let mut field_indexes =
+            self.create_field_indexes(field, &payload_schema, self.index_generation(field));
         for index in &field_indexes {
             index.recreate()?;
         }
@@ -202,6 +318,130 @@ impl StructPayloadIndex {
         Ok(())
     }
 
+    /// Start building indexes of the field with a new schema, the current ones stay in use.
+    /// Existing points are added by [`Self::continue_rebuild`], while payload updates are applied
+    /// to both the current and the new indexes, until [`Self::finish_rebuild`] replaces them.
+    ///
+    /// Returns `false` if the field is already indexed with the same schema.
+    pub fn start_rebuild(
+        &mut self,
+        field: PayloadKeyTypeRef,
+        payload_schema: PayloadFieldSchema,
+    ) -> OperationResult<bool> {
+        self.abort_rebuild(field)?;
+        if self.config.indexed_fields.get(field) == Some(&payload_schema) {
+            return Ok(false);
+        }
+
+        let generation = self.index_generation(field) + 1;
+        let indexes = self.create_field_indexes(field, &payload_schema, generation);
+        for index in &indexes {
+            index.recreate()?;
+        }
+        let end_point = self.id_tracker.borrow().total_point_count() as PointOffsetType;
+
+        self.rebuilding.get_mut().insert(
+            field.to_owned(),
+            RebuildingIndex {
+                schema: payload_schema,
+                generation,
+                indexes,
+                next_point: 0,
+                end_point,
+                error: None,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Add up to `batch_size` existing points to the indexes started by [`Self::start_rebuild`].
+    ///
+    /// Returns `true` once all points are added.
+    pub fn continue_rebuild(
+        &self,
+        field: PayloadKeyTypeRef,
+        batch_size: usize,
+    ) -> OperationResult<bool> {
+        let mut rebuilding = self.rebuilding.lock();
+        let rebuild = rebuilding.get_mut(field).ok_or_else(|| {
+            OperationError::service_error(format!("Index of `{field}` is not being rebuilt"))
+        })?;
+        if let Some(err) = &rebuild.error {
+            return Err(err.clone());
+        }
+
+        let id_tracker = self.id_tracker.borrow();
+        let payload_storage = self.payload.borrow();
+        let batch_end = rebuild.end_point.min(
+            rebuild
+                .next_point
+                .saturating_add(batch_size as PointOffsetType),
+        );
+        for point_id in rebuild.next_point..batch_end {
+            // Point may be already added by an update of its payload
+            for index in &mut rebuild.indexes {
+                index.remove_point(point_id)?;
+            }
+            if id_tracker.is_deleted_point(point_id) {
+                continue;
+            }
+            let point_payload = payload_storage.payload(point_id)?;
+            let field_value = &point_payload.get_value(field);
+            for index in &mut rebuild.indexes {
+                index.validate_payload(field_value)?;
+                index.add_point(point_id, field_value)?;
+            }
+        }
+        rebuild.next_point = batch_end;
+
+        Ok(rebuild.is_complete())
+    }
+
+    /// Replace indexes of the field with the ones built since [`Self::start_rebuild`].
+    /// If the rebuild has failed, the new indexes are dropped and the current ones stay in use.
+    pub fn finish_rebuild(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
+        let rebuild = self.rebuilding.get_mut().remove(field).ok_or_else(|| {
+            OperationError::service_error(format!("Index of `{field}` is not being rebuilt"))
+        })?;
+        let failure = match &rebuild.error {
+            Some(err) => Some(err.clone()),
+            None if !rebuild.is_complete() => Some(OperationError::service_error(format!(
+                "Rebuild of `{field}` index is not complete"
+            ))),
+            None => None,
+        };
+        if let Some(err) = failure {
+            rebuild.clear()?;
+            return Err(err);
+        }
+
+        // New indexes must be persisted before the config points to them
+        for index in &rebuild.indexes {
+            index.flusher()()?;
+        }
+        let previous_indexes = self.field_indexes.insert(field.to_owned(), rebuild.indexes);
+        self.config
+            .indexed_fields
+            .insert(field.to_owned(), rebuild.schema);
+        self.config
+            .index_generations
+            .insert(field.to_owned(), rebuild.generation);
+        self.save_config()?;
+
+        for index in previous_indexes.into_iter().flatten() {
+            index.clear()?;
+        }
+        Ok(())
+    }
+
+    /// Drop indexes started by [`Self::start_rebuild`], if any
+    pub fn abort_rebuild(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
+        match self.rebuilding.get_mut().remove(field) {
+            Some(rebuild) => rebuild.clear(),
+            None => Ok(()),
+        }
+    }
+
     /// Number of available points
     ///
     /// - excludes soft deleted points
@@ -362,7 +602,9 @@ impl PayloadIndex for StructPayloadIndex {
     }
 
     fn drop_index(&mut self, field: PayloadKeyTypeRef) -> OperationResult<()> {
+        self.abort_rebuild(field)?;
         self.config.indexed_fields.remove(field);
+        self.config.index_generations.remove(field);
         let removed_indexes = self.field_indexes.remove(field);
 
         if let Some(indexes) = removed_indexes {
@@ -477,13 +719,27 @@ impl PayloadIndex for StructPayloadIndex {
         }
     }
 
+    fn assign_all(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        self.validate_payload(payload)?;
+        self.drop(point_id)?;
+        self.assign(point_id, payload)
+    }
+
     fn assign(&mut self, point_id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        self.validate_payload(payload)?;
         for (field, field_index) in &mut self.field_indexes {
             let field_value = &payload.get_value(field);
             for index in field_index {
                 index.add_point(point_id, field_value)?;
             }
         }
+        for (field, rebuild) in self.rebuilding.get_mut().iter_mut() {
+            let field_value = &payload.get_value(field);
+            rebuild.update(|index| {
+                index.validate_payload(field_value)?;
+                index.add_point(point_id, field_value)
+            });
+        }
         self.payload.borrow_mut().assign(point_id, payload)
     }
 
@@ -491,6 +747,14 @@ impl PayloadIndex for StructPayloadIndex {
         self.payload.borrow().payload(point_id)
     }
 
+    fn payload_selected(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        self.payload.borrow().payload_selected(point_id, selector)
+    }
+
     fn delete(
         &mut self,
         point_id: PointOffsetType,
@@ -501,6 +765,9 @@ impl PayloadIndex for StructPayloadIndex {
                 index.remove_point(point_id)?;
             }
         }
+        if let Some(rebuild) = self.rebuilding.get_mut().get_mut(key) {
+            rebuild.update(|index| index.remove_point(point_id));
+        }
         self.payload.borrow_mut().delete(point_id, key)
     }
 
@@ -510,11 +777,17 @@ impl PayloadIndex for StructPayloadIndex {
                 index.remove_point(point_id)?;
             }
         }
+        for rebuild in self.rebuilding.get_mut().values_mut() {
+            rebuild.update(|index| index.remove_point(point_id));
+        }
         self.payload.borrow_mut().drop(point_id)
     }
 
     fn wipe(&mut self) -> OperationResult<()> {
         self.payload.borrow_mut().wipe()?;
+        for (_, rebuild) in self.rebuilding.get_mut().drain() {
+            rebuild.clear()?;
+        }
         for (_, field_indexes) in self.field_indexes.iter_mut() {
             for index in field_indexes.drain(..) {
                 index.clear()?;
@@ -1,13 +1,18 @@
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
+use itertools::Itertools;
+
+use super::hnsw_index::build_progress::BuildProgress;
 use super::hnsw_index::graph_links::{GraphLinksMmap, GraphLinksRam};
 use super::hnsw_index::hnsw::HNSWIndex;
 use super::plain_payload_index::PlainIndex;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::search_plan::SearchPath;
+use crate::data_types::vectors::{QueryVector, VectorElementType};
 use crate::entry::entry_point::OperationResult;
 use crate::telemetry::VectorIndexSearchesTelemetry;
-use crate::types::{Filter, SearchParams};
+use crate::types::{Filter, ScoreType, SearchParams};
 use crate::vector_storage::ScoredPointOffset;
 
 /// Trait for vector searching
@@ -20,8 +25,52 @@ pub trait VectorIndex {
         top: usize,
         params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        let query_vectors = nearest_queries(vectors);
+        let query_vectors = query_vectors.iter().collect_vec();
+        self.search_queries(&query_vectors, filter, top, None, params, is_stopped)
+    }
+
+    /// Same as `search`, but returns only points scored not lower than `score_threshold`.
+    /// Threshold is expected in the internal score scale, where larger score is always better.
+    fn search_with_score_threshold(
+        &self,
+        vectors: &[&[VectorElementType]],
+        filter: Option<&Filter>,
+        top: usize,
+        score_threshold: ScoreType,
+        params: Option<&SearchParams>,
+        is_stopped: &AtomicBool,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        let query_vectors = nearest_queries(vectors);
+        let query_vectors = query_vectors.iter().collect_vec();
+        self.search_queries(
+            &query_vectors,
+            filter,
+            top,
+            Some(score_threshold),
+            params,
+            is_stopped,
+        )
+    }
+
+    /// Return list of Ids best scored by each query.
+    ///
+    /// If `score_threshold` is given, only points scored not lower than it are returned.
+    /// Threshold is expected in the internal score scale, where larger score is always better.
+    fn search_queries(
+        &self,
+        query_vectors: &[&QueryVector],
+        filter: Option<&Filter>,
+        top: usize,
+        score_threshold: Option<ScoreType>,
+        params: Option<&SearchParams>,
+        is_stopped: &AtomicBool,
     ) -> Vec<Vec<ScoredPointOffset>>;
 
+    /// Way the index would execute a search with the given filter and parameters
+    fn search_path(&self, filter: Option<&Filter>, params: Option<&SearchParams>) -> SearchPath;
+
     /// Force internal index rebuild.
     fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()>;
 
@@ -36,6 +85,13 @@ pub trait VectorIndex {
     fn is_appendable(&self) -> bool;
 }
 
+fn nearest_queries(vectors: &[&[VectorElementType]]) -> Vec<QueryVector> {
+    vectors
+        .iter()
+        .map(|vector| QueryVector::Nearest(vector.to_vec()))
+        .collect()
+}
+
 pub enum VectorIndexEnum {
     Plain(PlainIndex),
     HnswRam(HNSWIndex<GraphLinksRam>),
@@ -50,25 +106,60 @@ impl VectorIndexEnum {
             Self::HnswMmap(_) => true,
         }
     }
+
+    /// Report progress of the graph building, plain index has nothing to build
+    pub fn set_build_progress(&mut self, progress: Arc<BuildProgress>) {
+        match self {
+            Self::Plain(_) => {}
+            Self::HnswRam(index) => index.set_build_progress(progress),
+            Self::HnswMmap(index) => index.set_build_progress(progress),
+        }
+    }
 }
 
 impl VectorIndex for VectorIndexEnum {
-    fn search(
+    fn search_queries(
         &self,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
     ) -> Vec<Vec<ScoredPointOffset>> {
         match self {
-            VectorIndexEnum::Plain(index) => index.search(vectors, filter, top, params, is_stopped),
-            VectorIndexEnum::HnswRam(index) => {
-                index.search(vectors, filter, top, params, is_stopped)
-            }
-            VectorIndexEnum::HnswMmap(index) => {
-                index.search(vectors, filter, top, params, is_stopped)
-            }
+            VectorIndexEnum::Plain(index) => index.search_queries(
+                query_vectors,
+                filter,
+                top,
+                score_threshold,
+                params,
+                is_stopped,
+            ),
+            VectorIndexEnum::HnswRam(index) => index.search_queries(
+                query_vectors,
+                filter,
+                top,
+                score_threshold,
+                params,
+                is_stopped,
+            ),
+            VectorIndexEnum::HnswMmap(index) => index.search_queries(
+                query_vectors,
+                filter,
+                top,
+                score_threshold,
+                params,
+                is_stopped,
+            ),
+        }
+    }
+
+    fn search_path(&self, filter: Option<&Filter>, params: Option<&SearchParams>) -> SearchPath {
+        match self {
+            VectorIndexEnum::Plain(index) => index.search_path(filter, params),
+            VectorIndexEnum::HnswRam(index) => index.search_path(filter, params),
+            VectorIndexEnum::HnswMmap(index) => index.search_path(filter, params),
         }
     }
 
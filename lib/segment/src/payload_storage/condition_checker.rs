@@ -3,8 +3,9 @@
 use serde_json::Value;
 
 use crate::types::{
-    AnyVariants, FieldCondition, GeoBoundingBox, GeoRadius, Match, MatchAny, MatchExcept,
-    MatchText, MatchValue, Range, ValueVariants, ValuesCount,
+    AnyVariants, DateTimePayloadType, DatetimeRange, FieldCondition, GeoBoundingBox, GeoPolygon,
+    GeoRadius, Match, MatchAny, MatchExcept, MatchPhrase, MatchText, MatchValue, Range,
+    RangeInterface, ValueVariants, ValuesCount,
 };
 
 pub trait ValueChecker {
@@ -47,6 +48,11 @@ impl ValueChecker for FieldCondition {
                 .geo_bounding_box
                 .as_ref()
                 .map_or(false, |condition| condition.check_match(payload));
+        res = res
+            || self
+                .geo_polygon
+                .as_ref()
+                .map_or(false, |condition| condition.check_match(payload));
         res = res
             || self
                 .values_count
@@ -79,6 +85,10 @@ impl ValueChecker for Match {
                 Value::String(stored) => stored.contains(text),
                 _ => false,
             },
+            Match::Phrase(MatchPhrase { phrase }) => match payload {
+                Value::String(stored) => stored.contains(phrase),
+                _ => false,
+            },
             Match::Any(MatchAny { any }) => match (payload, any) {
                 (Value::String(stored), AnyVariants::Keywords(list)) => list.contains(stored),
                 (Value::Number(stored), AnyVariants::Integers(list)) => stored
@@ -116,6 +126,27 @@ impl ValueChecker for Range {
     }
 }
 
+impl ValueChecker for DatetimeRange {
+    fn check_match(&self, payload: &Value) -> bool {
+        match payload {
+            Value::String(stored) => stored
+                .parse::<DateTimePayloadType>()
+                .map(|datetime| self.check_range(datetime))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+impl ValueChecker for RangeInterface {
+    fn check_match(&self, payload: &Value) -> bool {
+        match self {
+            RangeInterface::Float(range) => range.check_match(payload),
+            RangeInterface::DateTime(range) => range.check_match(payload),
+        }
+    }
+}
+
 impl ValueChecker for GeoBoundingBox {
     fn check_match(&self, payload: &Value) -> bool {
         match payload {
@@ -150,6 +181,23 @@ impl ValueChecker for GeoRadius {
     }
 }
 
+impl ValueChecker for GeoPolygon {
+    fn check_match(&self, payload: &Value) -> bool {
+        match payload {
+            Value::Object(obj) => {
+                let lon_op = obj.get("lon").and_then(|x| x.as_f64());
+                let lat_op = obj.get("lat").and_then(|x| x.as_f64());
+
+                if let (Some(lon), Some(lat)) = (lon_op, lat_op) {
+                    return self.check_point(lon, lat);
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
 impl ValueChecker for ValuesCount {
     fn check_match(&self, payload: &Value) -> bool {
         self.check_count(payload)
@@ -165,7 +213,7 @@ mod tests {
     use serde_json::json;
 
     use super::*;
-    use crate::types::GeoPoint;
+    use crate::types::{GeoLineString, GeoPoint};
 
     #[test]
     fn test_geo_matching() {
@@ -199,6 +247,59 @@ mod tests {
         assert!(!miss_geo_query.check(&berlin_and_moscow));
     }
 
+    #[test]
+    fn test_geo_polygon_matching() {
+        let berlin_and_moscow = json!([
+            {
+                "lat": 52.52197645,
+                "lon": 13.413637435864272
+            },
+            {
+                "lat": 55.7536283,
+                "lon": 37.62137960067377,
+            }
+        ]);
+
+        let ring = |points: &[(f64, f64)]| GeoLineString {
+            points: points
+                .iter()
+                .map(|&(lon, lat)| GeoPoint { lon, lat })
+                .collect(),
+        };
+
+        let around_berlin = GeoPolygon::new(
+            ring(&[
+                (13.0, 52.0),
+                (14.0, 52.0),
+                (14.0, 53.0),
+                (13.0, 53.0),
+                (13.0, 52.0),
+            ]),
+            None,
+        )
+        .unwrap();
+        let berlin_in_hole = GeoPolygon::new(
+            ring(&[
+                (13.0, 52.0),
+                (14.0, 52.0),
+                (14.0, 53.0),
+                (13.0, 53.0),
+                (13.0, 52.0),
+            ]),
+            Some(vec![ring(&[
+                (13.3, 52.4),
+                (13.5, 52.4),
+                (13.5, 52.6),
+                (13.3, 52.6),
+                (13.3, 52.4),
+            ])]),
+        )
+        .unwrap();
+
+        assert!(around_berlin.check(&berlin_and_moscow));
+        assert!(!berlin_in_hole.check(&berlin_and_moscow));
+    }
+
     #[test]
     fn test_value_count() {
         let countries = json!([
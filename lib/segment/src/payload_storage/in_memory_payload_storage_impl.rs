@@ -94,6 +94,7 @@ mod tests {
                     "John Doe".to_string().into(),
                 )),
             ]),
+            min_should: None,
             must_not: None,
         };
 
@@ -1,14 +1,16 @@
+use std::fmt;
 use std::sync::Arc;
 
 use parking_lot::RwLock;
 use rocksdb::DB;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
 use serde_json::Value;
 
 use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_PAYLOAD_CF};
 use crate::common::Flusher;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::payload_storage::PayloadStorage;
-use crate::types::{Payload, PayloadKeyTypeRef, PointOffsetType};
+use crate::types::{Payload, PayloadKeyTypeRef, PayloadSelector, PointOffsetType};
 
 /// On-disk implementation of `PayloadStorage`.
 /// Persists all changes to disk using `store`, does not keep payload in memory
@@ -46,6 +48,23 @@ impl OnDiskPayloadStorage {
             .map_err(OperationError::from)
     }
 
+    /// Read payload of the point, values of the top-level keys, which are not selected, are
+    /// skipped without deserialization
+    pub fn read_payload_selected(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Option<Payload>> {
+        let key = serde_cbor::to_vec(&point_id).unwrap();
+        self.db_wrapper
+            .get_pinned(&key, |raw| {
+                SelectedPayload(selector)
+                    .deserialize(&mut serde_cbor::Deserializer::from_slice(raw))
+            })?
+            .transpose()
+            .map_err(OperationError::from)
+    }
+
     pub fn iter<F>(&self, mut callback: F) -> OperationResult<()>
     where
         F: FnMut(PointOffsetType, &Payload) -> OperationResult<bool>,
@@ -88,6 +107,16 @@ impl PayloadStorage for OnDiskPayloadStorage {
         }
     }
 
+    fn payload_selected(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        let payload = self.read_payload_selected(point_id, selector)?;
+        // Nested paths are applied to the values of the selected keys
+        Ok(selector.process(payload.unwrap_or_default()))
+    }
+
     fn delete(
         &mut self,
         point_id: PointOffsetType,
@@ -121,3 +150,41 @@ impl PayloadStorage for OnDiskPayloadStorage {
         self.db_wrapper.flusher()
     }
 }
+
+/// Deserializes stored payload with values of the not selected top-level keys skipped
+struct SelectedPayload<'a>(&'a PayloadSelector);
+
+impl<'de> DeserializeSeed<'de> for SelectedPayload<'_> {
+    type Value = Payload;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SelectedPayload<'_> {
+    type Value = Payload;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("payload map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut payload = serde_json::Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if self.0.check(&key) {
+                let value = map.next_value()?;
+                payload.insert(key, value);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(Payload(payload))
+    }
+}
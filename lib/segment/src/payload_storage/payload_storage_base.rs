@@ -2,7 +2,7 @@ use serde_json::Value;
 
 use crate::common::Flusher;
 use crate::entry::entry_point::OperationResult;
-use crate::types::{Filter, Payload, PayloadKeyTypeRef, PointOffsetType};
+use crate::types::{Filter, Payload, PayloadKeyTypeRef, PayloadSelector, PointOffsetType};
 
 /// Trait for payload data storage. Should allow filter checks
 pub trait PayloadStorage {
@@ -19,6 +19,15 @@ pub trait PayloadStorage {
     /// Get payload for point
     fn payload(&self, point_id: PointOffsetType) -> OperationResult<Payload>;
 
+    /// Get payload for point, projected with the selector
+    fn payload_selected(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        Ok(selector.process(self.payload(point_id)?))
+    }
+
     /// Delete payload by key
     fn delete(
         &mut self,
@@ -6,7 +6,7 @@ use crate::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
 use crate::payload_storage::PayloadStorage;
-use crate::types::{Payload, PayloadKeyTypeRef, PointOffsetType};
+use crate::types::{Payload, PayloadKeyTypeRef, PayloadSelector, PointOffsetType};
 
 pub enum PayloadStorageEnum {
     InMemoryPayloadStorage(InMemoryPayloadStorage),
@@ -62,6 +62,18 @@ impl PayloadStorage for PayloadStorageEnum {
         }
     }
 
+    fn payload_selected(
+        &self,
+        point_id: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        match self {
+            PayloadStorageEnum::InMemoryPayloadStorage(s) => s.payload_selected(point_id, selector),
+            PayloadStorageEnum::SimplePayloadStorage(s) => s.payload_selected(point_id, selector),
+            PayloadStorageEnum::OnDiskPayloadStorage(s) => s.payload_selected(point_id, selector),
+        }
+    }
+
     fn delete(
         &mut self,
         point_id: PointOffsetType,
@@ -104,8 +116,10 @@ mod tests {
     use tempfile::Builder;
 
     use super::*;
-    use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
-    use crate::types::Payload;
+    use crate::common::rocksdb_wrapper::{
+        open_db, DatabaseColumnWrapper, DB_PAYLOAD_CF, DB_VECTOR_CF,
+    };
+    use crate::types::{Payload, PayloadSelectorExclude, PayloadSelectorInclude};
 
     #[test]
     fn test_storage() {
@@ -188,4 +202,67 @@ mod tests {
             eprintln!("res = {res:#?}");
         }
     }
+
+    #[test]
+    fn test_on_disk_storage_selected_payload() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        // Store the payload with a value, which can't be deserialized into JSON.
+        // Reading of the whole payload fails, so the selected reads below prove,
+        // that the excluded value is skipped without deserialization.
+        let stored = serde_cbor::Value::Map(
+            [
+                ("name", serde_cbor::Value::Text("John Doe".to_string())),
+                ("blob", serde_cbor::Value::Bytes(vec![0; 1024 * 1024])),
+                (
+                    "location",
+                    serde_cbor::Value::Map(
+                        [
+                            ("city", serde_cbor::Value::Text("Melbourne".to_string())),
+                            ("country", serde_cbor::Value::Text("Australia".to_string())),
+                        ]
+                        .into_iter()
+                        .map(|(key, value)| (serde_cbor::Value::Text(key.to_string()), value))
+                        .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .map(|(key, value)| (serde_cbor::Value::Text(key.to_string()), value))
+            .collect(),
+        );
+        let point_id: PointOffsetType = 100;
+        DatabaseColumnWrapper::new(db.clone(), DB_PAYLOAD_CF)
+            .put(
+                serde_cbor::to_vec(&point_id).unwrap(),
+                serde_cbor::to_vec(&stored).unwrap(),
+            )
+            .unwrap();
+
+        let storage: PayloadStorageEnum = OnDiskPayloadStorage::open(db).unwrap().into();
+        assert!(storage.payload(100).is_err());
+
+        let exclude = PayloadSelector::Exclude(PayloadSelectorExclude::new(vec![
+            "blob".to_string(),
+            "location.country".to_string(),
+        ]));
+        let expected: Payload =
+            serde_json::from_str(r#"{"name": "John Doe", "location": {"city": "Melbourne"}}"#)
+                .unwrap();
+        assert_eq!(storage.payload_selected(100, &exclude).unwrap(), expected);
+
+        let include = PayloadSelector::Include(PayloadSelectorInclude::new(vec![
+            "location.country".to_string(),
+        ]));
+        let expected: Payload =
+            serde_json::from_str(r#"{"location": {"country": "Australia"}}"#).unwrap();
+        assert_eq!(storage.payload_selected(100, &include).unwrap(), expected);
+
+        // Missing point has empty payload
+        assert_eq!(
+            storage.payload_selected(200, &include).unwrap(),
+            Default::default()
+        );
+    }
 }
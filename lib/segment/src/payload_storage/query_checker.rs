@@ -12,8 +12,8 @@ use crate::payload_storage::condition_checker::ValueChecker;
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::ConditionChecker;
 use crate::types::{
-    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, OwnedPayloadRef, Payload,
-    PayloadContainer, PayloadKeyType, PointOffsetType,
+    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, MinShould,
+    OwnedPayloadRef, Payload, PayloadContainer, PayloadKeyType, PointOffsetType,
 };
 
 fn check_condition<F>(checker: &F, condition: &Condition) -> bool
@@ -31,6 +31,7 @@ where
     F: Fn(&Condition) -> bool,
 {
     check_should(checker, &filter.should)
+        && check_min_should(checker, &filter.min_should)
         && check_must(checker, &filter.must)
         && check_must_not(checker, &filter.must_not)
 }
@@ -46,6 +47,26 @@ where
     }
 }
 
+fn check_min_should<F>(checker: &F, min_should: &Option<MinShould>) -> bool
+where
+    F: Fn(&Condition) -> bool,
+{
+    match min_should {
+        None => true,
+        Some(MinShould {
+            conditions,
+            min_count,
+        }) => {
+            conditions
+                .iter()
+                .filter(|condition| check_condition(checker, condition))
+                .take(*min_count)
+                .count()
+                == *min_count
+        }
+    }
+}
+
 fn check_must<F>(checker: &F, must: &Option<Vec<Condition>>) -> bool
 where
     F: Fn(&Condition) -> bool,
@@ -447,6 +468,7 @@ mod tests {
         let query = Filter {
             should: None,
             must: Some(vec![match_red.clone()]),
+            min_should: None,
             must_not: None,
         };
         assert!(payload_checker.check(0, &query));
@@ -454,6 +476,7 @@ mod tests {
         let query = Filter {
             should: None,
             must: Some(vec![match_blue.clone()]),
+            min_should: None,
             must_not: None,
         };
         assert!(!payload_checker.check(0, &query));
@@ -461,6 +484,7 @@ mod tests {
         let query = Filter {
             should: None,
             must: None,
+            min_should: None,
             must_not: Some(vec![match_blue.clone()]),
         };
         assert!(payload_checker.check(0, &query));
@@ -468,6 +492,7 @@ mod tests {
         let query = Filter {
             should: None,
             must: None,
+            min_should: None,
             must_not: Some(vec![match_red.clone()]),
         };
         assert!(!payload_checker.check(0, &query));
@@ -475,6 +500,7 @@ mod tests {
         let query = Filter {
             should: Some(vec![match_red.clone(), match_blue.clone()]),
             must: Some(vec![with_delivery.clone(), in_berlin.clone()]),
+            min_should: None,
             must_not: None,
         };
         assert!(payload_checker.check(0, &query));
@@ -482,6 +508,7 @@ mod tests {
         let query = Filter {
             should: Some(vec![match_red.clone(), match_blue.clone()]),
             must: Some(vec![with_delivery, in_moscow.clone()]),
+            min_should: None,
             must_not: None,
         };
         assert!(!payload_checker.check(0, &query));
@@ -491,15 +518,18 @@ mod tests {
                 Condition::Filter(Filter {
                     should: None,
                     must: Some(vec![match_red.clone(), in_moscow.clone()]),
+                    min_should: None,
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
                     should: None,
                     must: Some(vec![match_blue.clone(), in_berlin.clone()]),
+                    min_should: None,
                     must_not: None,
                 }),
             ]),
             must: None,
+            min_should: None,
             must_not: None,
         };
         assert!(!payload_checker.check(0, &query));
@@ -509,15 +539,18 @@ mod tests {
                 Condition::Filter(Filter {
                     should: None,
                     must: Some(vec![match_blue, in_moscow]),
+                    min_should: None,
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
                     should: None,
                     must: Some(vec![match_red, in_berlin]),
+                    min_should: None,
                     must_not: None,
                 }),
             ]),
             must: None,
+            min_should: None,
             must_not: None,
         };
         assert!(payload_checker.check(0, &query));
@@ -525,6 +558,7 @@ mod tests {
         let query = Filter {
             should: None,
             must: None,
+            min_should: None,
             must_not: Some(vec![with_bad_rating]),
         };
         assert!(!payload_checker.check(0, &query));
@@ -534,6 +568,7 @@ mod tests {
         let query = Filter {
             should: None,
             must: None,
+            min_should: None,
             must_not: Some(vec![Condition::HasId(ids.into())]),
         };
         assert!(!payload_checker.check(2, &query));
@@ -543,6 +578,7 @@ mod tests {
         let query = Filter {
             should: None,
             must: None,
+            min_should: None,
             must_not: Some(vec![Condition::HasId(ids.into())]),
         };
         assert!(payload_checker.check(10, &query));
@@ -552,8 +588,78 @@ mod tests {
         let query = Filter {
             should: None,
             must: Some(vec![Condition::HasId(ids.into())]),
+            min_should: None,
             must_not: None,
         };
         assert!(payload_checker.check(2, &query));
+
+        let match_red = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "red".to_owned().into(),
+        ));
+        let match_blue = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "blue".to_owned().into(),
+        ));
+        let with_delivery = Condition::Field(FieldCondition::new_match(
+            "has_delivery".to_string(),
+            true.into(),
+        ));
+        let cheap = Condition::Field(FieldCondition::new_range(
+            "price".to_string(),
+            Range {
+                lt: Some(100.0),
+                ..Default::default()
+            },
+        ));
+        let min_should = |min_count| {
+            MinShould::new(
+                vec![
+                    match_red.clone(),
+                    match_blue.clone(),
+                    with_delivery.clone(),
+                    cheap.clone(),
+                ],
+                min_count,
+            )
+            .unwrap()
+        };
+
+        // Red and with delivery, but neither blue nor cheap
+        assert!(payload_checker.check(0, &Filter::new_min_should(min_should(1))));
+        assert!(payload_checker.check(0, &Filter::new_min_should(min_should(2))));
+        assert!(!payload_checker.check(0, &Filter::new_min_should(min_should(3))));
+        // More than the number of conditions never matches
+        assert!(!payload_checker.check(0, &Filter::new_min_should(min_should(5))));
+
+        let query = Filter {
+            should: None,
+            min_should: Some(min_should(2)),
+            must: Some(vec![match_red.clone()]),
+            must_not: None,
+        };
+        assert!(payload_checker.check(0, &query));
+
+        let query = Filter {
+            should: None,
+            min_should: Some(min_should(2)),
+            must: Some(vec![match_blue.clone()]),
+            must_not: None,
+        };
+        assert!(!payload_checker.check(0, &query));
+
+        let query = Filter {
+            should: None,
+            min_should: Some(min_should(2)),
+            must: None,
+            must_not: Some(vec![with_delivery.clone()]),
+        };
+        assert!(!payload_checker.check(0, &query));
+
+        // `min_should` inside of `must_not` is inverted as a whole
+        let query = Filter::new_must_not(Condition::Filter(Filter::new_min_should(min_should(3))));
+        assert!(payload_checker.check(0, &query));
+
+        assert!(MinShould::new(vec![match_red], 0).is_err());
     }
 }
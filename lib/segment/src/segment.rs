@@ -1,41 +1,55 @@
 use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
 use atomic_refcell::AtomicRefCell;
+use itertools::Itertools;
 use parking_lot::{Mutex, RwLock};
+use rand::RngCore;
 use rocksdb::DB;
 use tar::Builder;
 use uuid::Uuid;
 
-use crate::common::file_operations::{atomic_save_json, read_json};
+use crate::common::file_operations::{atomic_save_json, files_size_bytes, read_json};
+use crate::common::random_sample::random_sample_scores;
+use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_PAYLOAD_CF};
+use crate::common::snapshot_manifest::SnapshotManifest;
 use crate::common::version::{StorageVersion, VERSION_FILE};
 use crate::common::{
-    check_named_vectors, check_stopped, check_vector, check_vector_name, check_vectors, mmap_ops,
+    check_named_vectors, check_named_vectors_datatype, check_named_vectors_finite,
+    check_query_vectors, check_sparse_vector, check_stopped, check_vector, check_vector_name,
+    mmap_ops,
 };
+use crate::data_types::facets::{FacetParams, FacetValue};
 use crate::data_types::named_vectors::NamedVectors;
-use crate::data_types::vectors::VectorElementType;
+use crate::data_types::order_by::{OrderBy, OrderValue};
+use crate::data_types::search_plan::{FilterCardinality, SegmentSearchPlan};
+use crate::data_types::sparse_vector::SparseVector;
+use crate::data_types::vectors::{QueryVector, VectorElementType};
 use crate::entry::entry_point::OperationError::TypeInferenceError;
 use crate::entry::entry_point::{
     get_service_error, OperationError, OperationResult, SegmentEntry, SegmentFailedState,
 };
-use crate::id_tracker::IdTrackerSS;
-use crate::index::field_index::CardinalityEstimation;
+use crate::id_tracker::{IdTrackerSS, PointComponent, PointVersions};
+use crate::index::field_index::{CardinalityEstimation, FieldIndex};
+use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::{PayloadIndex, VectorIndex, VectorIndexEnum};
 use crate::spaces::tools::peek_top_smallest_iterable;
-use crate::telemetry::SegmentTelemetry;
+use crate::telemetry::{HnswTelemetry, SegmentTelemetry, VectorDataTelemetry};
 use crate::types::{
-    Filter, Payload, PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef,
-    PayloadSchemaType, PointIdType, PointOffsetType, ScoredPoint, SearchParams, SegmentConfig,
-    SegmentInfo, SegmentState, SegmentType, SeqNumberType, WithPayload, WithVector,
+    Filter, Indexes, Payload, PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType,
+    PayloadKeyTypeRef, PayloadSchemaType, PayloadSelector, PointIdType, PointOffsetType, Sample,
+    ScoreType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo, SegmentResourceUsage,
+    SegmentState, SegmentType, SeqNumberType, WithPayload, WithVector,
 };
 use crate::utils;
 use crate::utils::fs::find_symlink;
+use crate::vector_storage::simple_sparse_vector_storage::SimpleSparseVectorStorage;
 use crate::vector_storage::{ScoredPointOffset, VectorStorage, VectorStorageEnum};
 
 pub const SEGMENT_STATE_FILE: &str = "segment.json";
@@ -46,6 +60,8 @@ const SNAPSHOT_PATH: &str = "snapshot";
 const DB_BACKUP_PATH: &str = "db_backup";
 const PAYLOAD_DB_BACKUP_PATH: &str = "payload_index_db_backup";
 const SNAPSHOT_FILES_PATH: &str = "files";
+/// Sizes and checksums of all archived files
+const SNAPSHOT_MANIFEST_FILE: &str = "manifest.json";
 
 pub struct SegmentVersion;
 
@@ -72,6 +88,7 @@ pub struct Segment {
     /// Component for mapping external ids to internal and also keeping track of point versions
     pub id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
     pub vector_data: HashMap<String, VectorData>,
+    pub sparse_vector_data: HashMap<String, SparseVectorData>,
     pub payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
     /// Shows if it is possible to insert more points into this segment
     pub appendable_flag: bool,
@@ -88,6 +105,8 @@ pub struct Segment {
 pub struct VectorData {
     pub vector_index: Arc<AtomicRefCell<VectorIndexEnum>>,
     pub vector_storage: Arc<AtomicRefCell<VectorStorageEnum>>,
+    /// Mmap pages are not prefaulted yet, and should be on the first search
+    pub prefault_on_search: AtomicBool,
 }
 
 impl VectorData {
@@ -113,6 +132,30 @@ impl VectorData {
     }
 }
 
+pub struct SparseVectorData {
+    pub vector_index: Arc<AtomicRefCell<SparseVectorIndex>>,
+    pub vector_storage: Arc<AtomicRefCell<SimpleSparseVectorStorage>>,
+}
+
+impl SparseVectorData {
+    /// Whether this sparse vector data can be appended to
+    pub fn is_appendable(&self) -> bool {
+        self.vector_index.borrow().is_appendable()
+    }
+
+    /// Mark sparse vector of the point as deleted and remove it from the index
+    fn delete_vector(&self, internal_id: PointOffsetType) -> OperationResult<bool> {
+        let mut vector_storage = self.vector_storage.borrow_mut();
+        let is_deleted = vector_storage.delete_vector(internal_id)?;
+        if is_deleted {
+            self.vector_index
+                .borrow_mut()
+                .remove(internal_id, vector_storage.get_vector(internal_id));
+        }
+        Ok(is_deleted)
+    }
+}
+
 impl Segment {
     /// Replace vectors in-place
     ///
@@ -208,6 +251,12 @@ impl Segment {
                 }
             }
         }
+        // Keep internal ids of sparse vectors aligned with dense ones
+        for sparse_vector_data in self.sparse_vector_data.values() {
+            let mut vector_storage = sparse_vector_data.vector_storage.borrow_mut();
+            vector_storage.insert_vector(new_index, &SparseVector::default())?;
+            vector_storage.delete_vector(new_index)?;
+        }
         self.id_tracker.borrow_mut().set_link(point_id, new_index)?;
         Ok(new_index)
     }
@@ -221,6 +270,9 @@ impl Segment {
     /// * `op_point_offset` - if operation is point-related, specify this point offset.
     ///     If point offset is specified, handler will use point version for comparison.
     ///     Otherwise, it will use global storage version
+    /// * `op_component` - component of the point, which is changed by the operation.
+    ///     Only the version of this component is compared and updated.
+    ///     If not specified, the operation changes the point as a whole
     /// * `op` - operation to be wrapped. Should return `OperationResult` of bool (which is returned outside)
     ///     and optionally new offset of the changed point.
     ///
@@ -232,6 +284,7 @@ impl Segment {
         &mut self,
         op_num: SeqNumberType,
         op_point_offset: Option<PointOffsetType>,
+        op_component: Option<PointComponent>,
         operation: F,
     ) -> OperationResult<bool>
     where
@@ -252,7 +305,7 @@ impl Segment {
             } // else: Re-try operation
         }
 
-        let res = self.handle_version(op_num, op_point_offset, operation);
+        let res = self.handle_version(op_num, op_point_offset, op_component, operation);
 
         match get_service_error(&res) {
             None => {
@@ -297,6 +350,7 @@ impl Segment {
         &mut self,
         op_num: SeqNumberType,
         op_point_offset: Option<PointOffsetType>,
+        op_component: Option<PointComponent>,
         operation: F,
     ) -> OperationResult<bool>
     where
@@ -311,12 +365,18 @@ impl Segment {
                 }
             }
             Some(point_offset) => {
-                // Check if point not exists or have lower version
+                // Check if point not exists or have lower version of the changed component
                 if self
                     .id_tracker
                     .borrow()
-                    .internal_version(point_offset)
-                    .map_or(false, |current_version| current_version > op_num)
+                    .internal_versions(point_offset)
+                    .map_or(false, |current_versions| {
+                        let current_version = match op_component {
+                            Some(component) => current_versions.get(component),
+                            None => current_versions.point(),
+                        };
+                        current_version > op_num
+                    })
                 {
                     return Ok(false);
                 }
@@ -328,9 +388,19 @@ impl Segment {
         self.version = Some(max(op_num, self.version.unwrap_or(0)));
 
         if let Some(point_id) = point_id {
-            self.id_tracker
-                .borrow_mut()
-                .set_internal_version(point_id, op_num)?;
+            let mut id_tracker = self.id_tracker.borrow_mut();
+            let existing_versions =
+                op_point_offset.and_then(|_| id_tracker.internal_versions(point_id));
+            let versions = match (op_component, existing_versions) {
+                (Some(component), Some(mut versions)) => {
+                    versions.set(component, op_num);
+                    versions
+                }
+                // All components of a point, created by the operation, get its version,
+                // so older updates of the other components are not applied to it on WAL replay
+                _ => PointVersions::new(op_num),
+            };
+            id_tracker.set_internal_versions(point_id, versions)?;
         }
 
         Ok(applied)
@@ -438,6 +508,16 @@ impl Segment {
         self.payload_index.borrow().payload(point_offset)
     }
 
+    fn payload_selected_by_offset(
+        &self,
+        point_offset: PointOffsetType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        self.payload_index
+            .borrow()
+            .payload_selected(point_offset, selector)
+    }
+
     pub fn save_current_state(&self) -> OperationResult<()> {
         Self::save_state(&self.get_state(), &self.current_path)
     }
@@ -470,6 +550,20 @@ impl Segment {
         let snapshot_path = segment_path.join(SNAPSHOT_PATH);
 
         if snapshot_path.exists() {
+            let manifest_path = snapshot_path.join(SNAPSHOT_MANIFEST_FILE);
+            if manifest_path.exists() {
+                let manifest: SnapshotManifest = read_json(&manifest_path)?;
+                if let Err(err) = manifest.validate(&segment_path) {
+                    // Corrupted files must not be loaded as a segment later
+                    if let Err(remove_err) = fs::remove_dir_all(&segment_path) {
+                        log::error!("Failed to remove {segment_path:?} directory: {remove_err}");
+                    }
+                    return Err(err);
+                }
+            } else {
+                log::info!("Segment snapshot has no manifest, its files are not validated");
+            }
+
             let db_backup_path = snapshot_path.join(DB_BACKUP_PATH);
             let payload_index_db_backup = snapshot_path.join(PAYLOAD_DB_BACKUP_PATH);
 
@@ -565,13 +659,13 @@ impl Segment {
                     ))
                 })?;
                 let payload = if with_payload.enable {
-                    let initial_payload = self.payload_by_offset(point_offset)?;
-                    let processed_payload = if let Some(i) = &with_payload.payload_selector {
-                        i.process(initial_payload)
-                    } else {
-                        initial_payload
+                    let payload = match &with_payload.payload_selector {
+                        Some(selector) => {
+                            self.payload_selected_by_offset(point_offset, selector)?
+                        }
+                        None => self.payload_by_offset(point_offset)?,
                     };
-                    Some(processed_payload)
+                    Some(payload)
                 } else {
                     None
                 };
@@ -677,6 +771,9 @@ impl Segment {
                     let mut vector_storage = vector_data.vector_storage.borrow_mut();
                     vector_storage.delete_vector(*internal_id)?;
                 }
+                for sparse_vector_data in self.sparse_vector_data.values() {
+                    sparse_vector_data.delete_vector(*internal_id)?;
+                }
             }
 
             // We do not drop version here, because it is already not loaded into memory.
@@ -705,13 +802,41 @@ impl Segment {
         self.id_tracker.borrow().total_point_count()
     }
 
-    pub fn prefault_mmap_pages(&self) {
-        let tasks: Vec<_> = self
-            .vector_data
-            .values()
-            .flat_map(|data| data.prefault_mmap_pages())
-            .collect();
+    /// Prefault mmap pages of the segment in a background thread
+    ///
+    /// Pages of the `lazy_vectors` are not prefaulted now, but on the first search against them.
+    pub fn prefault_mmap_pages(&self, lazy_vectors: &HashSet<String>) {
+        let mut tasks = Vec::new();
+        for (vector_name, data) in &self.vector_data {
+            if lazy_vectors.contains(vector_name) {
+                data.prefault_on_search.store(true, Ordering::Relaxed);
+            } else {
+                tasks.extend(data.prefault_mmap_pages());
+            }
+        }
+        tasks.extend(
+            self.sparse_vector_data
+                .values()
+                .filter_map(|data| data.vector_index.borrow().prefault_mmap_pages()),
+        );
+        self.spawn_prefault_mmap_pages(tasks);
+    }
+
+    /// Prefault pages of the vector, if it is searched for the first time since
+    /// [`Self::prefault_mmap_pages`]
+    fn prefault_vector_on_search(&self, vector_data: &VectorData) {
+        if vector_data
+            .prefault_on_search
+            .swap(false, Ordering::Relaxed)
+        {
+            self.spawn_prefault_mmap_pages(vector_data.prefault_mmap_pages().collect());
+        }
+    }
 
+    fn spawn_prefault_mmap_pages(&self, tasks: Vec<mmap_ops::PrefaultMmapPages>) {
+        if tasks.is_empty() {
+            return;
+        }
         let _ = thread::Builder::new()
             .name(format!(
                 "segment-{:?}-prefault-mmap-pages",
@@ -719,6 +844,174 @@ impl Segment {
             ))
             .spawn(move || tasks.iter().for_each(mmap_ops::PrefaultMmapPages::exec));
     }
+
+    /// Insert or replace sparse vectors of an existing point
+    ///
+    /// Sparse vectors not specified are kept as-is. Upserting dense vectors of the point
+    /// does not change its sparse vectors either.
+    ///
+    /// # Warning
+    ///
+    /// Available for appendable segments only.
+    pub fn update_sparse_vectors(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        vectors: &HashMap<String, SparseVector>,
+    ) -> OperationResult<bool> {
+        debug_assert!(self.is_appendable());
+        for (vector_name, vector) in vectors {
+            check_sparse_vector(vector_name, vector, &self.segment_config)?;
+        }
+        let internal_id = self.lookup_internal_id(point_id)?;
+        self.handle_version_and_failure(
+            op_num,
+            Some(internal_id),
+            Some(PointComponent::Vectors),
+            |segment| {
+                for (vector_name, vector) in vectors {
+                    let sparse_vector_data = &segment.sparse_vector_data[vector_name];
+                    let mut vector_storage = sparse_vector_data.vector_storage.borrow_mut();
+                    let old_vector = if vector_storage.is_deleted_vector(internal_id) {
+                        SparseVector::default()
+                    } else {
+                        vector_storage.get_vector(internal_id).clone()
+                    };
+                    sparse_vector_data.vector_index.borrow_mut().upsert(
+                        internal_id,
+                        &old_vector,
+                        vector,
+                    )?;
+                    vector_storage.insert_vector(internal_id, vector)?;
+                }
+                Ok((true, Some(internal_id)))
+            },
+        )
+    }
+
+    /// Delete sparse vector of the point, other vectors of the point are kept
+    pub fn delete_sparse_vector(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        vector_name: &str,
+    ) -> OperationResult<bool> {
+        if !self
+            .segment_config
+            .sparse_vector_data
+            .contains_key(vector_name)
+        {
+            return Err(OperationError::VectorNameNotExists {
+                received_name: vector_name.to_string(),
+            });
+        }
+        let internal_id = self.lookup_internal_id(point_id)?;
+        self.handle_version_and_failure(
+            op_num,
+            Some(internal_id),
+            Some(PointComponent::Vectors),
+            |segment| {
+                let is_deleted =
+                    segment.sparse_vector_data[vector_name].delete_vector(internal_id)?;
+                Ok((is_deleted, Some(internal_id)))
+            },
+        )
+    }
+
+    /// Retrieve sparse vector of the point
+    ///
+    /// Returns None if the vector does not exists or deleted
+    pub fn sparse_vector(
+        &self,
+        vector_name: &str,
+        point_id: PointIdType,
+    ) -> OperationResult<Option<SparseVector>> {
+        let sparse_vector_data = self.sparse_vector_data.get(vector_name).ok_or_else(|| {
+            OperationError::VectorNameNotExists {
+                received_name: vector_name.to_string(),
+            }
+        })?;
+        let internal_id = self.lookup_internal_id(point_id)?;
+        let vector_storage = sparse_vector_data.vector_storage.borrow();
+        if vector_storage.is_deleted_vector(internal_id) {
+            return Ok(None);
+        }
+        Ok(Some(vector_storage.get_vector(internal_id).clone()))
+    }
+
+    /// Search top points by dot product of sparse vectors
+    ///
+    /// Only points sharing at least one non-zero dimension with the query are returned.
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_sparse(
+        &self,
+        vector_name: &str,
+        vector: &SparseVector,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>> {
+        check_sparse_vector(vector_name, vector, &self.segment_config)?;
+        let internal_result = self.sparse_vector_data[vector_name]
+            .vector_index
+            .borrow()
+            .search(vector, filter, top, is_stopped);
+
+        check_stopped(is_stopped)?;
+        self.process_search_result(&internal_result, with_payload, with_vector)
+    }
+
+    /// Random sample of up to `top` points, which have the vector and match the filter
+    ///
+    /// Sampled points are scored by random keys, see [`random_sample_scores`], with the number of
+    /// matching points estimated by the payload index. Best keys of several segments are a
+    /// uniform sample of all their matching points.
+    pub fn sample_points(
+        &self,
+        vector_name: &str,
+        filter: Option<&Filter>,
+        top: usize,
+        rng: &mut dyn RngCore,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPointOffset>> {
+        check_vector_name(vector_name, &self.segment_config)?;
+        let id_tracker = self.id_tracker.borrow();
+        let vector_storage = self.vector_data[vector_name].vector_storage.borrow();
+        let payload_index = self.payload_index.borrow();
+        let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+
+        let sampled_ids = id_tracker
+            .random_ids(Some(vector_storage.deleted_vector_bitslice()), rng)
+            .take_while(|_| !is_stopped.load(Ordering::Relaxed))
+            .filter(|internal_id| {
+                filter_context
+                    .as_ref()
+                    .map_or(true, |context| context.check(*internal_id))
+            })
+            .take(top)
+            .collect_vec();
+        check_stopped(is_stopped)?;
+
+        let matching_count = if sampled_ids.len() < top {
+            // All matching points are sampled
+            sampled_ids.len()
+        } else {
+            let estimated = match filter {
+                None => vector_storage.available_vector_count(),
+                Some(filter) => payload_index.estimate_cardinality(filter).exp,
+            };
+            estimated.max(top)
+        };
+
+        let scores = random_sample_scores(sampled_ids.len(), matching_count, rng);
+        Ok(sampled_ids
+            .into_iter()
+            .zip(scores)
+            .map(|(idx, score)| ScoredPointOffset { idx, score })
+            .collect())
+    }
 }
 
 /// This is a basic implementation of `SegmentEntry`,
@@ -735,6 +1028,24 @@ impl SegmentEntry for Segment {
             .and_then(|internal_id| id_tracker.internal_version(internal_id))
     }
 
+    fn point_versions(&self, point_id: PointIdType) -> Option<PointVersions> {
+        let id_tracker = self.id_tracker.borrow();
+        id_tracker
+            .internal_id(point_id)
+            .and_then(|internal_id| id_tracker.internal_versions(internal_id))
+    }
+
+    fn set_point_versions(
+        &mut self,
+        point_id: PointIdType,
+        versions: PointVersions,
+    ) -> OperationResult<()> {
+        let internal_id = self.lookup_internal_id(point_id)?;
+        self.id_tracker
+            .borrow_mut()
+            .set_internal_versions(internal_id, versions)
+    }
+
     fn search(
         &self,
         vector_name: &str,
@@ -748,6 +1059,7 @@ impl SegmentEntry for Segment {
     ) -> OperationResult<Vec<ScoredPoint>> {
         check_vector(vector_name, vector, &self.segment_config)?;
         let vector_data = &self.vector_data[vector_name];
+        self.prefault_vector_on_search(vector_data);
         let internal_result =
             &vector_data
                 .vector_index
@@ -761,20 +1073,74 @@ impl SegmentEntry for Segment {
     fn search_batch(
         &self,
         vector_name: &str,
-        vectors: &[&[VectorElementType]],
+        query_vectors: &[&QueryVector],
         with_payload: &WithPayload,
         with_vector: &WithVector,
         filter: Option<&Filter>,
         top: usize,
+        score_threshold: Option<ScoreType>,
         params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
     ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
-        check_vectors(vector_name, vectors, &self.segment_config)?;
+        check_query_vectors(vector_name, query_vectors, &self.segment_config)?;
         let vector_data = &self.vector_data[vector_name];
-        let internal_results = vector_data
-            .vector_index
-            .borrow()
-            .search(vectors, filter, top, params, is_stopped);
+        self.prefault_vector_on_search(vector_data);
+        let vector_index = vector_data.vector_index.borrow();
+        let distance = self.segment_config.vector_data[vector_name].distance;
+        let internal_threshold = |query_vector: &QueryVector| {
+            score_threshold.map(|score_threshold| {
+                if query_vector.is_distance_scored() {
+                    distance.preprocess_score_threshold(score_threshold)
+                } else {
+                    score_threshold
+                }
+            })
+        };
+        let has_samples = query_vectors
+            .iter()
+            .any(|query_vector| matches!(query_vector, QueryVector::Sample(_)));
+        let internal_results = if !has_samples
+            && query_vectors
+                .iter()
+                .map(|query_vector| internal_threshold(query_vector))
+                .all_equal()
+        {
+            vector_index.search_queries(
+                query_vectors,
+                filter,
+                top,
+                query_vectors
+                    .first()
+                    .and_then(|query_vector| internal_threshold(query_vector)),
+                params,
+                is_stopped,
+            )
+        } else {
+            // Queries of different kinds have different internal thresholds,
+            // and samples are not searched in the index
+            query_vectors
+                .iter()
+                .map(|query_vector| match query_vector {
+                    QueryVector::Sample(Sample::Random) => self.sample_points(
+                        vector_name,
+                        filter,
+                        top,
+                        &mut rand::thread_rng(),
+                        is_stopped,
+                    ),
+                    _ => Ok(vector_index
+                        .search_queries(
+                            &[query_vector],
+                            filter,
+                            top,
+                            internal_threshold(query_vector),
+                            params,
+                            is_stopped,
+                        )
+                        .remove(0)),
+                })
+                .collect::<OperationResult<Vec<_>>>()?
+        };
 
         check_stopped(is_stopped)?;
 
@@ -788,6 +1154,42 @@ impl SegmentEntry for Segment {
         res
     }
 
+    fn search_plan(
+        &self,
+        vector_name: &str,
+        filter: Option<&Filter>,
+        params: Option<&SearchParams>,
+    ) -> OperationResult<Vec<SegmentSearchPlan>> {
+        let search_path = match self.sparse_vector_data.get(vector_name) {
+            Some(sparse_vector_data) => {
+                sparse_vector_data.vector_index.borrow().search_path(filter)
+            }
+            None => {
+                check_vector_name(vector_name, &self.segment_config)?;
+                self.vector_data[vector_name]
+                    .vector_index
+                    .borrow()
+                    .search_path(filter, params)
+            }
+        };
+
+        let payload_index = self.payload_index.borrow();
+        let estimation = filter.map(|filter| payload_index.estimate_cardinality(filter));
+        let primary_clauses = estimation
+            .iter()
+            .flat_map(|estimation| &estimation.primary_clauses)
+            .map(|clause| payload_index.primary_clause_plan(clause))
+            .collect();
+
+        Ok(vec![SegmentSearchPlan {
+            segment_type: self.segment_type(),
+            available_points: self.available_point_count(),
+            cardinality: estimation.as_ref().map(FilterCardinality::from),
+            primary_clauses,
+            search_path,
+        }])
+    }
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -796,17 +1198,24 @@ impl SegmentEntry for Segment {
     ) -> OperationResult<bool> {
         debug_assert!(self.is_appendable());
         check_named_vectors(&vectors, &self.segment_config)?;
-        vectors.preprocess(|name| self.segment_config.vector_data[name].distance);
+        check_named_vectors_finite(point_id, &vectors)?;
+        check_named_vectors_datatype(point_id, &vectors, &self.segment_config)?;
+        vectors.preprocess(|name| &self.segment_config.vector_data[name]);
         let stored_internal_point = self.id_tracker.borrow().internal_id(point_id);
-        self.handle_version_and_failure(op_num, stored_internal_point, |segment| {
-            if let Some(existing_internal_id) = stored_internal_point {
-                segment.replace_all_vectors(existing_internal_id, vectors)?;
-                Ok((true, Some(existing_internal_id)))
-            } else {
-                let new_index = segment.insert_new_vectors(point_id, vectors)?;
-                Ok((false, Some(new_index)))
-            }
-        })
+        self.handle_version_and_failure(
+            op_num,
+            stored_internal_point,
+            Some(PointComponent::Vectors),
+            |segment| {
+                if let Some(existing_internal_id) = stored_internal_point {
+                    segment.replace_all_vectors(existing_internal_id, vectors)?;
+                    Ok((true, Some(existing_internal_id)))
+                } else {
+                    let new_index = segment.insert_new_vectors(point_id, vectors)?;
+                    Ok((false, Some(new_index)))
+                }
+            },
+        )
     }
 
     fn delete_point(
@@ -819,7 +1228,7 @@ impl SegmentEntry for Segment {
             // Point does already not exist anymore
             None => Ok(false),
             Some(internal_id) => {
-                self.handle_version_and_failure(op_num, Some(internal_id), |segment| {
+                self.handle_version_and_failure(op_num, Some(internal_id), None, |segment| {
                     // Mark point as deleted, drop mapping
                     segment.payload_index.borrow_mut().drop(internal_id)?;
                     segment.id_tracker.borrow_mut().drop(point_id)?;
@@ -829,6 +1238,9 @@ impl SegmentEntry for Segment {
                         let mut vector_storage = vector_data.vector_storage.borrow_mut();
                         vector_storage.delete_vector(internal_id)?;
                     }
+                    for sparse_vector_data in segment.sparse_vector_data.values() {
+                        sparse_vector_data.delete_vector(internal_id)?;
+                    }
 
                     Ok((true, Some(internal_id)))
                 })
@@ -843,18 +1255,23 @@ impl SegmentEntry for Segment {
         mut vectors: NamedVectors,
     ) -> OperationResult<bool> {
         check_named_vectors(&vectors, &self.segment_config)?;
-        vectors.preprocess(|name| self.segment_config.vector_data[name].distance);
+        check_named_vectors_finite(point_id, &vectors)?;
+        check_named_vectors_datatype(point_id, &vectors, &self.segment_config)?;
+        vectors.preprocess(|name| &self.segment_config.vector_data[name]);
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
         match internal_id {
             None => Err(OperationError::PointIdError {
                 missed_point_id: point_id,
             }),
-            Some(internal_id) => {
-                self.handle_version_and_failure(op_num, Some(internal_id), |segment| {
+            Some(internal_id) => self.handle_version_and_failure(
+                op_num,
+                Some(internal_id),
+                Some(PointComponent::Vectors),
+                |segment| {
                     segment.update_vectors(internal_id, vectors)?;
                     Ok((true, Some(internal_id)))
-                })
-            }
+                },
+            ),
         }
     }
 
@@ -870,8 +1287,11 @@ impl SegmentEntry for Segment {
             None => Err(OperationError::PointIdError {
                 missed_point_id: point_id,
             }),
-            Some(internal_id) => {
-                self.handle_version_and_failure(op_num, Some(internal_id), |segment| {
+            Some(internal_id) => self.handle_version_and_failure(
+                op_num,
+                Some(internal_id),
+                Some(PointComponent::Vectors),
+                |segment| {
                     let vector_data = segment.vector_data.get(vector_name).ok_or(
                         OperationError::VectorNameNotExists {
                             received_name: vector_name.to_string(),
@@ -880,8 +1300,8 @@ impl SegmentEntry for Segment {
                     let mut vector_storage = vector_data.vector_storage.borrow_mut();
                     let is_deleted = vector_storage.delete_vector(internal_id)?;
                     Ok((is_deleted, Some(internal_id)))
-                })
-            }
+                },
+            ),
         }
     }
 
@@ -892,18 +1312,23 @@ impl SegmentEntry for Segment {
         full_payload: &Payload,
     ) -> OperationResult<bool> {
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
-        self.handle_version_and_failure(op_num, internal_id, |segment| match internal_id {
-            Some(internal_id) => {
-                segment
-                    .payload_index
-                    .borrow_mut()
-                    .assign_all(internal_id, full_payload)?;
-                Ok((true, Some(internal_id)))
-            }
-            None => Err(OperationError::PointIdError {
-                missed_point_id: point_id,
-            }),
-        })
+        self.handle_version_and_failure(
+            op_num,
+            internal_id,
+            Some(PointComponent::Payload),
+            |segment| match internal_id {
+                Some(internal_id) => {
+                    segment
+                        .payload_index
+                        .borrow_mut()
+                        .assign_all(internal_id, full_payload)?;
+                    Ok((true, Some(internal_id)))
+                }
+                None => Err(OperationError::PointIdError {
+                    missed_point_id: point_id,
+                }),
+            },
+        )
     }
 
     fn set_payload(
@@ -913,18 +1338,50 @@ impl SegmentEntry for Segment {
         payload: &Payload,
     ) -> OperationResult<bool> {
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
-        self.handle_version_and_failure(op_num, internal_id, |segment| match internal_id {
-            Some(internal_id) => {
-                segment
-                    .payload_index
-                    .borrow_mut()
-                    .assign(internal_id, payload)?;
-                Ok((true, Some(internal_id)))
-            }
-            None => Err(OperationError::PointIdError {
-                missed_point_id: point_id,
-            }),
-        })
+        self.handle_version_and_failure(
+            op_num,
+            internal_id,
+            Some(PointComponent::Payload),
+            |segment| match internal_id {
+                Some(internal_id) => {
+                    segment
+                        .payload_index
+                        .borrow_mut()
+                        .assign(internal_id, payload)?;
+                    Ok((true, Some(internal_id)))
+                }
+                None => Err(OperationError::PointIdError {
+                    missed_point_id: point_id,
+                }),
+            },
+        )
+    }
+
+    fn set_payload_by_key(
+        &mut self,
+        op_num: SeqNumberType,
+        point_id: PointIdType,
+        payload: &Payload,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        let internal_id = self.id_tracker.borrow().internal_id(point_id);
+        self.handle_version_and_failure(
+            op_num,
+            internal_id,
+            Some(PointComponent::Payload),
+            |segment| match internal_id {
+                Some(internal_id) => {
+                    segment
+                        .payload_index
+                        .borrow_mut()
+                        .assign_by_key(internal_id, payload, key)?;
+                    Ok((true, Some(internal_id)))
+                }
+                None => Err(OperationError::PointIdError {
+                    missed_point_id: point_id,
+                }),
+            },
+        )
     }
 
     fn delete_payload(
@@ -934,18 +1391,23 @@ impl SegmentEntry for Segment {
         key: PayloadKeyTypeRef,
     ) -> OperationResult<bool> {
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
-        self.handle_version_and_failure(op_num, internal_id, |segment| match internal_id {
-            Some(internal_id) => {
-                segment
-                    .payload_index
-                    .borrow_mut()
-                    .delete(internal_id, key)?;
-                Ok((true, Some(internal_id)))
-            }
-            None => Err(OperationError::PointIdError {
-                missed_point_id: point_id,
-            }),
-        })
+        self.handle_version_and_failure(
+            op_num,
+            internal_id,
+            Some(PointComponent::Payload),
+            |segment| match internal_id {
+                Some(internal_id) => {
+                    segment
+                        .payload_index
+                        .borrow_mut()
+                        .delete(internal_id, key)?;
+                    Ok((true, Some(internal_id)))
+                }
+                None => Err(OperationError::PointIdError {
+                    missed_point_id: point_id,
+                }),
+            },
+        )
     }
 
     fn clear_payload(
@@ -954,15 +1416,20 @@ impl SegmentEntry for Segment {
         point_id: PointIdType,
     ) -> OperationResult<bool> {
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
-        self.handle_version_and_failure(op_num, internal_id, |segment| match internal_id {
-            Some(internal_id) => {
-                segment.payload_index.borrow_mut().drop(internal_id)?;
-                Ok((true, Some(internal_id)))
-            }
-            None => Err(OperationError::PointIdError {
-                missed_point_id: point_id,
-            }),
-        })
+        self.handle_version_and_failure(
+            op_num,
+            internal_id,
+            Some(PointComponent::Payload),
+            |segment| match internal_id {
+                Some(internal_id) => {
+                    segment.payload_index.borrow_mut().drop(internal_id)?;
+                    Ok((true, Some(internal_id)))
+                }
+                None => Err(OperationError::PointIdError {
+                    missed_point_id: point_id,
+                }),
+            },
+        )
     }
 
     fn vector(
@@ -991,6 +1458,15 @@ impl SegmentEntry for Segment {
         self.payload_by_offset(internal_id)
     }
 
+    fn payload_selected(
+        &self,
+        point_id: PointIdType,
+        selector: &PayloadSelector,
+    ) -> OperationResult<Payload> {
+        let internal_id = self.lookup_internal_id(point_id)?;
+        self.payload_selected_by_offset(internal_id, selector)
+    }
+
     fn iter_points(&self) -> Box<dyn Iterator<Item = PointIdType> + '_> {
         // Sorry for that, but I didn't find any way easier.
         // If you try simply return iterator - it won't work because AtomicRef should exist
@@ -1057,6 +1533,125 @@ impl SegmentEntry for Segment {
         }
     }
 
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        offset: Option<PointIdType>,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>> {
+        let payload_index = self.payload_index.borrow();
+        let numeric_index = payload_index
+            .field_indexes
+            .get(&order_by.key)
+            .and_then(|indexes| indexes.iter().find_map(FieldIndex::as_numeric))
+            .ok_or_else(|| OperationError::MissingRangeIndexForOrderBy {
+                key: order_by.key.clone(),
+            })?;
+        let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+        let id_tracker = self.id_tracker.borrow();
+
+        let direction = order_by.direction();
+        let limit = limit.unwrap_or(usize::MAX);
+        // Points before `offset` with the `start_from` value were on the previous page
+        let cursor = order_by.start_from.zip(offset);
+
+        let mut points = Vec::new();
+        for (value, internal_id) in numeric_index.stream_from(order_by.start_from, direction) {
+            // Keep reading points with the same value as the last one, to order them by id
+            if points.len() >= limit && points.last().map(|(last, _)| *last) != Some(value) {
+                break;
+            }
+            if id_tracker.is_deleted_point(internal_id) {
+                continue;
+            }
+            if let Some(filter_context) = &filter_context {
+                if !filter_context.check(internal_id) {
+                    continue;
+                }
+            }
+            let Some(external_id) = id_tracker.external_id(internal_id) else {
+                continue;
+            };
+            if let Some(cursor) = &cursor {
+                if direction.compare(&(value, external_id), cursor).is_lt() {
+                    continue;
+                }
+            }
+            points.push((value, external_id));
+        }
+
+        // Index orders points with the same value by internal id
+        points.sort_unstable_by(|a, b| direction.compare(a, b));
+        points.truncate(limit);
+        Ok(points)
+    }
+
+    fn approximate_facet(
+        &self,
+        request: &FacetParams,
+    ) -> OperationResult<HashMap<FacetValue, usize>> {
+        let payload_index = self.payload_index.borrow();
+        let keyword_index = payload_index
+            .field_indexes
+            .get(&request.key)
+            .and_then(|indexes| indexes.iter().find_map(FieldIndex::as_keyword))
+            .ok_or_else(|| OperationError::MissingKeywordIndexForFacet {
+                key: request.key.clone(),
+            })?;
+
+        let counts = keyword_index
+            .iter_counts_per_value()
+            .map(|(value, points_count)| {
+                let value = FacetValue::Keyword(value.to_string());
+                let count = match &request.filter {
+                    None => points_count,
+                    Some(filter) => {
+                        let value_filter =
+                            Filter::new_must(value.match_condition(request.key.clone()));
+                        payload_index
+                            .estimate_cardinality(&value_filter.merge(filter))
+                            .exp
+                    }
+                };
+                (value, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        Ok(counts)
+    }
+
+    fn facet_values(
+        &self,
+        key: PayloadKeyTypeRef,
+        filter: Option<&Filter>,
+    ) -> OperationResult<BTreeSet<FacetValue>> {
+        let payload_index = self.payload_index.borrow();
+        let keyword_index = payload_index
+            .field_indexes
+            .get(key)
+            .and_then(|indexes| indexes.iter().find_map(FieldIndex::as_keyword))
+            .ok_or_else(|| OperationError::MissingKeywordIndexForFacet {
+                key: key.to_owned(),
+            })?;
+        let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+        let id_tracker = self.id_tracker.borrow();
+
+        let values = keyword_index
+            .iter_counts_per_value()
+            .filter(|(value, _)| {
+                keyword_index.get_iterator(*value).any(|internal_id| {
+                    !id_tracker.is_deleted_point(internal_id)
+                        && filter_context
+                            .as_ref()
+                            .map_or(true, |context| context.check(internal_id))
+                })
+            })
+            .map(|(value, _)| FacetValue::Keyword(value.to_string()))
+            .collect();
+        Ok(values)
+    }
+
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType> {
         let id_tracker = self.id_tracker.borrow();
         let iterator = id_tracker.iter_from(from).map(|x| x.0);
@@ -1128,6 +1723,37 @@ impl SegmentEntry for Segment {
         }
     }
 
+    fn resource_usage(&self) -> OperationResult<SegmentResourceUsage> {
+        let mut usage = SegmentResourceUsage::default();
+        for vector_data in self.vector_data.values() {
+            let vector_storage = vector_data.vector_storage.borrow();
+            let quantized_files = vector_storage
+                .quantized_storage()
+                .map(|quantized_vectors| quantized_vectors.files())
+                .unwrap_or_default();
+            let storage_files = vector_storage
+                .files()
+                .into_iter()
+                .filter(|file| !quantized_files.contains(file));
+            usage.vectors_ram_bytes += vector_storage.ram_usage_bytes();
+            usage.vectors_mmap_bytes += files_size_bytes(storage_files)?;
+            usage.quantized_vectors_bytes += files_size_bytes(&quantized_files)?;
+            usage.hnsw_links_bytes += files_size_bytes(vector_data.vector_index.borrow().files())?;
+        }
+
+        usage.payload_storage_bytes =
+            DatabaseColumnWrapper::new(self.database.clone(), DB_PAYLOAD_CF).size_bytes()?;
+        let payload_index = self.payload_index.borrow();
+        for (field, field_indexes) in &payload_index.field_indexes {
+            let mut field_bytes = 0;
+            for field_index in field_indexes {
+                field_bytes += field_index.storage_size_bytes()?;
+            }
+            usage.payload_index_bytes.insert(field.clone(), field_bytes);
+        }
+        Ok(usage)
+    }
+
     fn config(&self) -> SegmentConfig {
         self.segment_config.clone()
     }
@@ -1161,6 +1787,11 @@ impl SegmentEntry for Segment {
             .vector_data
             .values()
             .map(|v| v.vector_storage.borrow().flusher())
+            .chain(
+                self.sparse_vector_data
+                    .values()
+                    .map(|v| v.vector_storage.borrow().flusher()),
+            )
             .collect();
         let state = self.get_state();
         let current_path = self.current_path.clone();
@@ -1280,7 +1911,7 @@ impl SegmentEntry for Segment {
     }
 
     fn delete_field_index(&mut self, op_num: u64, key: PayloadKeyTypeRef) -> OperationResult<bool> {
-        self.handle_version_and_failure(op_num, None, |segment| {
+        self.handle_version_and_failure(op_num, None, None, |segment| {
             segment.payload_index.borrow_mut().drop_index(key)?;
             Ok((true, None))
         })
@@ -1292,7 +1923,7 @@ impl SegmentEntry for Segment {
         key: PayloadKeyTypeRef,
         field_type: Option<&PayloadFieldSchema>,
     ) -> OperationResult<bool> {
-        self.handle_version_and_failure(op_num, None, |segment| match field_type {
+        self.handle_version_and_failure(op_num, None, None, |segment| match field_type {
             Some(schema) => {
                 segment
                     .payload_index
@@ -1315,6 +1946,57 @@ impl SegmentEntry for Segment {
         })
     }
 
+    fn start_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+        field_type: Option<&PayloadFieldSchema>,
+    ) -> OperationResult<bool> {
+        // Check version without updating it, it is updated once the index is replaced
+        if self.version.unwrap_or(0) > op_num {
+            return Ok(false);
+        }
+
+        let schema = match field_type {
+            Some(schema) => schema.clone(),
+            None => match self.infer_from_payload_data(key)? {
+                None => {
+                    return Err(TypeInferenceError {
+                        field_name: key.to_string(),
+                    })
+                }
+                Some(schema_type) => schema_type.into(),
+            },
+        };
+        self.payload_index.borrow_mut().start_rebuild(key, schema)
+    }
+
+    fn continue_field_index_rebuild(
+        &self,
+        key: PayloadKeyTypeRef,
+        batch_size: usize,
+    ) -> OperationResult<bool> {
+        self.payload_index
+            .borrow()
+            .continue_rebuild(key, batch_size)
+    }
+
+    fn finish_field_index_rebuild(
+        &mut self,
+        op_num: SeqNumberType,
+        key: PayloadKeyTypeRef,
+    ) -> OperationResult<bool> {
+        // Version is not checked, as the segment may be updated by later operations during the
+        // rebuild. The rebuild is started by this operation, so it is finished regardless.
+        self.payload_index.borrow_mut().finish_rebuild(key)?;
+        self.version = Some(max(op_num, self.version.unwrap_or(0)));
+        Ok(true)
+    }
+
+    fn abort_field_index_rebuild(&mut self, key: PayloadKeyTypeRef) -> OperationResult<()> {
+        self.payload_index.borrow_mut().abort_rebuild(key)
+    }
+
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema> {
         self.payload_index.borrow().indexed_fields()
     }
@@ -1375,6 +2057,8 @@ impl SegmentEntry for Segment {
 
         // flush segment to capture latest state
         self.flush(true)?;
+        // No flush may change files while they are archived, reads go on meanwhile
+        let _flush_lock = self.lock_flushing()?;
 
         let temp_path = temp_path.join(format!("snapshot-{}", Uuid::new_v4()));
         let db_backup_path = temp_path.join(DB_BACKUP_PATH);
@@ -1405,7 +2089,9 @@ impl SegmentEntry for Segment {
         })?;
 
         let mut builder = Builder::new(file);
+        let mut manifest = SnapshotManifest::default();
 
+        manifest.add_dir(&temp_path, Path::new(SNAPSHOT_PATH))?;
         builder
             .append_dir_all(SNAPSHOT_PATH, &temp_path)
             .map_err(|err| utils::tar::failed_to_append_error(&temp_path, err))?;
@@ -1416,6 +2102,7 @@ impl SegmentEntry for Segment {
             for file in vector_data.vector_index.borrow().files() {
                 utils::tar::append_file_relative_to_base(
                     &mut builder,
+                    &mut manifest,
                     &self.current_path,
                     &file,
                     &files,
@@ -1425,6 +2112,19 @@ impl SegmentEntry for Segment {
             for file in vector_data.vector_storage.borrow().files() {
                 utils::tar::append_file_relative_to_base(
                     &mut builder,
+                    &mut manifest,
+                    &self.current_path,
+                    &file,
+                    &files,
+                )?;
+            }
+        }
+
+        for sparse_vector_data in self.sparse_vector_data.values() {
+            for file in sparse_vector_data.vector_index.borrow().files() {
+                utils::tar::append_file_relative_to_base(
+                    &mut builder,
+                    &mut manifest,
                     &self.current_path,
                     &file,
                     &files,
@@ -1435,22 +2135,27 @@ impl SegmentEntry for Segment {
         for file in self.payload_index.borrow().files() {
             utils::tar::append_file_relative_to_base(
                 &mut builder,
+                &mut manifest,
                 &self.current_path,
                 &file,
                 &files,
             )?;
         }
 
-        utils::tar::append_file(
-            &mut builder,
-            &self.current_path.join(SEGMENT_STATE_FILE),
-            &files.join(SEGMENT_STATE_FILE),
-        )?;
+        for file_name in [SEGMENT_STATE_FILE, VERSION_FILE] {
+            utils::tar::append_file_relative_to_base(
+                &mut builder,
+                &mut manifest,
+                &self.current_path,
+                &self.current_path.join(file_name),
+                &files,
+            )?;
+        }
 
-        utils::tar::append_file(
+        utils::tar::append_data(
             &mut builder,
-            &self.current_path.join(VERSION_FILE),
-            &files.join(VERSION_FILE),
+            &serde_json::to_vec_pretty(&manifest)?,
+            &Path::new(SNAPSHOT_PATH).join(SNAPSHOT_MANIFEST_FILE),
         )?;
 
         builder.finish()?;
@@ -1481,11 +2186,40 @@ impl SegmentEntry for Segment {
             })
             .collect();
 
+        let vector_data = self
+            .vector_data
+            .iter()
+            .map(|(vector_name, data)| {
+                let vector_storage = data.vector_storage.borrow();
+                let config = &self.segment_config.vector_data[vector_name];
+                let vectors_count = vector_storage.total_vector_count();
+                let hnsw = match &config.index {
+                    Indexes::Plain {} => None,
+                    Indexes::Hnsw(hnsw_config) => Some(HnswTelemetry {
+                        m: hnsw_config.m,
+                        ef_construct: hnsw_config.ef_construct,
+                    }),
+                };
+                VectorDataTelemetry {
+                    vector_name: vector_name.clone(),
+                    storage_type: config.storage_type,
+                    vectors_count,
+                    vectors_size_bytes: vectors_count
+                        * vector_storage.vector_dim()
+                        * config.datatype().element_size(),
+                    hnsw_built: data.vector_index.borrow().is_index(),
+                    hnsw,
+                }
+            })
+            .collect();
+
         SegmentTelemetry {
             info: self.info(),
             config: self.config(),
             vector_index_searches,
             payload_field_indices: self.payload_index.borrow().get_telemetry_data(),
+            vector_data,
+            optimization_progress: None,
         }
     }
 }
@@ -1553,9 +2287,13 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 },
             )]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1587,12 +2325,13 @@ mod tests {
         let search_batch_result = segment
             .search_batch(
                 DEFAULT_VECTOR_NAME,
-                &[&query_vector],
+                &[&query_vector.clone().into()],
                 &WithPayload::default(),
                 &false.into(),
                 None,
                 10,
                 None,
+                None,
                 &false.into(),
             )
             .unwrap();
@@ -1625,9 +2364,13 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 },
             )]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
 
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -1716,9 +2459,13 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 },
             )]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -1807,9 +2554,13 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 },
             )]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
 
         let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
@@ -1838,9 +2589,13 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 },
             )]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1935,9 +2690,13 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 },
             )]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -1987,6 +2746,9 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
                 (
@@ -1997,10 +2759,14 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
             ]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2092,6 +2858,9 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
                 (
@@ -2102,10 +2871,14 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
             ]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
 
@@ -2187,7 +2960,7 @@ mod tests {
             segment
                 .search_batch(
                     vector_name,
-                    &[vector, vector],
+                    &[&vector.to_vec().into(), &vector.to_vec().into()],
                     &WithPayload {
                         enable: false,
                         payload_selector: None,
@@ -2196,6 +2969,7 @@ mod tests {
                     None,
                     1,
                     None,
+                    None,
                     &false.into(),
                 )
                 .err()
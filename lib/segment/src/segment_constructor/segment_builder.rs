@@ -1,26 +1,54 @@
 use std::cmp;
 use std::collections::HashMap;
+use std::ops::{Bound, RangeBounds};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use itertools::Itertools;
+use parking_lot::Mutex;
 
 use super::get_vector_storage_path;
 use crate::common::error_logging::LogError;
+use crate::common::operation_time_statistics::{
+    OperationDurationsAggregator, ScopeDurationMeasurer,
+};
 use crate::entry::entry_point::{
     check_process_stopped, OperationError, OperationResult, SegmentEntry,
 };
+use crate::index::hnsw_index::build_progress::BuildProgress;
 use crate::index::hnsw_index::max_rayon_threads;
 use crate::index::{PayloadIndex, VectorIndex};
 use crate::segment::Segment;
 use crate::segment_constructor::{build_segment, load_segment};
-use crate::types::{Indexes, PayloadFieldSchema, PayloadKeyType, SegmentConfig};
+use crate::types::{
+    Indexes, PayloadContainer, PayloadFieldSchema, PayloadKeyType, PointIdType, PointOffsetType,
+    SegmentConfig,
+};
 use crate::vector_storage::VectorStorage;
 
+/// Default size of vectors, which are copied from other segments at once
+pub const DEFAULT_MERGE_BUFFER_SIZE: usize = 64 * 1024 * 1024;
+
 /// Structure for constructing segment out of several other segments
 pub struct SegmentBuilder {
     pub segment: Option<Segment>,
     pub destination_path: PathBuf,
     pub temp_path: PathBuf,
     pub indexed_fields: HashMap<PayloadKeyType, PayloadFieldSchema>,
+    /// If set, points with the same value of this payload key are stored next to each other,
+    /// see [`SegmentBuilder::update`]
+    pub defragment_key: Option<PayloadKeyType>,
+    /// If set, only points with ids in this range are added from other segments
+    point_range: Option<(Bound<PointIdType>, Bound<PointIdType>)>,
+    /// Progress of vector index building, if it is observed
+    build_progress: Option<Arc<BuildProgress>>,
+    /// Durations of HNSW index builds, if they are observed
+    index_build_telemetry: Option<Arc<Mutex<OperationDurationsAggregator>>>,
+    /// Size in bytes of vectors, which are copied from other segments at once.
+    /// Points are copied by batches of this size, so memory used for merging does not depend
+    /// on the number of points.
+    merge_buffer_size: usize,
 }
 
 impl SegmentBuilder {
@@ -39,12 +67,121 @@ impl SegmentBuilder {
             destination_path,
             temp_path,
             indexed_fields: Default::default(),
+            defragment_key: None,
+            point_range: None,
+            build_progress: None,
+            index_build_telemetry: None,
+            merge_buffer_size: DEFAULT_MERGE_BUFFER_SIZE,
         })
     }
 
+    pub fn set_defragment_key(&mut self, defragment_key: Option<PayloadKeyType>) {
+        self.defragment_key = defragment_key;
+    }
+
+    /// Add only points with ids in the `point_range`, so a large segment can be split into
+    /// several, built from the same segments with different ranges
+    pub fn set_point_range(&mut self, point_range: (Bound<PointIdType>, Bound<PointIdType>)) {
+        self.point_range = Some(point_range);
+    }
+
+    pub fn set_build_progress(&mut self, build_progress: Arc<BuildProgress>) {
+        self.build_progress = Some(build_progress);
+    }
+
+    pub fn set_index_build_telemetry(
+        &mut self,
+        index_build_telemetry: Arc<Mutex<OperationDurationsAggregator>>,
+    ) {
+        self.index_build_telemetry = Some(index_build_telemetry);
+    }
+
+    pub fn set_merge_buffer_size(&mut self, merge_buffer_size: usize) {
+        self.merge_buffer_size = merge_buffer_size;
+    }
+
+    /// Number of points of `other` segment, which vectors fit into the merge buffer
+    fn batch_size(&self, other: &Segment) -> usize {
+        let point_size: usize = other
+            .segment_config
+            .vector_data
+            .values()
+            .map(|config| config.size * config.datatype().element_size())
+            .sum();
+        (self.merge_buffer_size / point_size.max(1)).max(1)
+    }
+
+    /// Update current segment builder with all (not deleted) vectors and payload from `segments`
+    ///
+    /// Without [`SegmentBuilder::defragment_key`] segments are added one by one in the given order.
+    /// With it, points of all segments are ordered by the value of the key first, so vectors of
+    /// points with the same value end up in a contiguous range of internal ids, and so are close
+    /// in vector storage files. Filtered search for a single value then reads less pages.
+    /// Points without the key are placed at the end. Otherwise the original order is kept.
+    ///
+    /// # Result
+    ///
+    /// * `bool` - if `true` - data successfully added, if `false` - process was interrupted
+    ///
+    pub fn update(&mut self, segments: &[&Segment], stopped: &AtomicBool) -> OperationResult<bool> {
+        let Some(defragment_key) = self.defragment_key.clone() else {
+            for segment in segments {
+                if !self.update_from(segment, stopped)? {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
+        };
+
+        let mut points = Vec::new();
+        for (segment_index, segment) in segments.iter().enumerate() {
+            let id_tracker = segment.id_tracker.borrow();
+            let payload_index = segment.payload_index.borrow();
+            for internal_id in id_tracker.iter_ids() {
+                check_process_stopped(stopped)?;
+                let payload = payload_index.payload(internal_id)?;
+                let values = payload.get_value(&defragment_key).values();
+                // Serialized values are only used for grouping equal values together
+                let group = if values.is_empty() {
+                    None
+                } else {
+                    Some(serde_json::to_string(&values)?)
+                };
+                points.push((group, segment_index, internal_id));
+            }
+        }
+
+        // Stable sort keeps the original order within a group
+        points.sort_by(|(a, ..), (b, ..)| (a.is_none(), a).cmp(&(b.is_none(), b)));
+
+        let batch_sizes = segments
+            .iter()
+            .map(|segment| self.batch_size(segment))
+            .collect_vec();
+        let mut run_ids = Vec::new();
+        for (index, &(_, segment_index, internal_id)) in points.iter().enumerate() {
+            run_ids.push(internal_id);
+            let next_segment_index = points
+                .get(index + 1)
+                .map(|(_, segment_index, _)| *segment_index);
+            let run_ends = next_segment_index != Some(segment_index)
+                || run_ids.len() >= batch_sizes[segment_index];
+            if run_ends {
+                if !self.update_from_ids(segments[segment_index], &run_ids, stopped)? {
+                    return Ok(false);
+                }
+                run_ids.clear();
+            }
+        }
+
+        Ok(true)
+    }
+
     /// Update current segment builder with all (not deleted) vectors and payload form `other` segment
     /// Perform index building at the end of update
     ///
+    /// Points are copied by batches, which vectors fit into the merge buffer.
+    ///
     /// # Arguments
     ///
     /// * `other` - segment to add into construction
@@ -54,6 +191,29 @@ impl SegmentBuilder {
     /// * `bool` - if `true` - data successfully added, if `false` - process was interrupted
     ///
     pub fn update_from(&mut self, other: &Segment, stopped: &AtomicBool) -> OperationResult<bool> {
+        let batch_size = self.batch_size(other);
+        let other_id_tracker = other.id_tracker.borrow();
+        let mut other_ids = other_id_tracker.iter_ids().peekable();
+        // Empty segment is still added as a single empty batch, to merge its version and indexes
+        loop {
+            let batch = other_ids.by_ref().take(batch_size).collect_vec();
+            if !self.update_from_ids(other, &batch, stopped)? {
+                return Ok(false);
+            }
+            if other_ids.peek().is_none() {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Update current segment builder with points of `other` segment with the given internal ids,
+    /// in the given order
+    fn update_from_ids(
+        &mut self,
+        other: &Segment,
+        other_ids: &[PointOffsetType],
+        stopped: &AtomicBool,
+    ) -> OperationResult<bool> {
         let self_segment = match &mut self.segment {
             Some(segment) => segment,
             None => {
@@ -65,6 +225,22 @@ impl SegmentBuilder {
         self_segment.version = Some(cmp::max(self_segment.version(), other.version()));
 
         let other_id_tracker = other.id_tracker.borrow();
+        let range_ids;
+        let other_ids = match &self.point_range {
+            Some(point_range) => {
+                range_ids = other_ids
+                    .iter()
+                    .copied()
+                    .filter(|&internal_id| {
+                        other_id_tracker
+                            .external_id(internal_id)
+                            .is_some_and(|external_id| point_range.contains(&external_id))
+                    })
+                    .collect_vec();
+                &range_ids
+            }
+            None => other_ids,
+        };
         let other_vector_storages: HashMap<_, _> = other
             .vector_data
             .iter()
@@ -72,6 +248,16 @@ impl SegmentBuilder {
                 (vector_name.to_owned(), vector_data.vector_storage.borrow())
             })
             .collect();
+        let other_sparse_vector_storages: HashMap<_, _> = other
+            .sparse_vector_data
+            .iter()
+            .map(|(vector_name, sparse_vector_data)| {
+                (
+                    vector_name.to_owned(),
+                    sparse_vector_data.vector_storage.borrow(),
+                )
+            })
+            .collect();
         let other_payload_index = other.payload_index.borrow();
 
         let mut id_tracker = self_segment.id_tracker.borrow_mut();
@@ -85,6 +271,16 @@ impl SegmentBuilder {
                 )
             })
             .collect();
+        let mut sparse_vector_storages: HashMap<_, _> = self_segment
+            .sparse_vector_data
+            .iter()
+            .map(|(vector_name, sparse_vector_data)| {
+                (
+                    vector_name.to_owned(),
+                    sparse_vector_data.vector_storage.borrow_mut(),
+                )
+            })
+            .collect();
         let mut payload_index = self_segment.payload_index.borrow_mut();
 
         if vector_storages.len() != other_vector_storages.len() {
@@ -93,7 +289,13 @@ impl SegmentBuilder {
             ));
         }
 
-        let mut new_internal_range = None;
+        if sparse_vector_storages.len() != other_sparse_vector_storages.len() {
+            return Err(OperationError::service_error(
+                format!("Self and other segments have different sparse vector names count. Self count: {}, other count: {}", sparse_vector_storages.len(), other_sparse_vector_storages.len()),
+            ));
+        }
+
+        let mut internal_ranges = Vec::new();
         for (vector_name, vector_storage) in &mut vector_storages {
             check_process_stopped(stopped)?;
             let other_vector_storage = other_vector_storages.get(vector_name).ok_or_else(|| {
@@ -101,25 +303,40 @@ impl SegmentBuilder {
                     "Cannot update from other segment because if missing vector name {vector_name}"
                 ))
             })?;
-            let internal_range = vector_storage.update_from(
+            internal_ranges.push(vector_storage.update_from(
                 other_vector_storage,
-                &mut other_id_tracker.iter_ids(),
+                &mut other_ids.iter().copied(),
                 stopped,
-            )?;
-            match new_internal_range.clone() {
-                Some(new_internal_range) => {
-                    if new_internal_range != internal_range {
-                        return Err(OperationError::service_error(
-                            "Internal ids range mismatch between self segment vectors and other segment vectors",
-                        ));
-                    }
-                }
-                None => new_internal_range = Some(internal_range.clone()),
-            }
+            )?);
+        }
+        for (vector_name, sparse_vector_storage) in &mut sparse_vector_storages {
+            check_process_stopped(stopped)?;
+            let other_sparse_vector_storage = other_sparse_vector_storages
+                .get(vector_name)
+                .ok_or_else(|| {
+                    OperationError::service_error(format!(
+                        "Cannot update from other segment because if missing sparse vector name {vector_name}"
+                    ))
+                })?;
+            internal_ranges.push(sparse_vector_storage.update_from(
+                other_sparse_vector_storage,
+                &mut other_ids.iter().copied(),
+                stopped,
+            )?);
+        }
+
+        let new_internal_range = internal_ranges.first().cloned();
+        if internal_ranges
+            .iter()
+            .any(|internal_range| Some(internal_range) != new_internal_range.as_ref())
+        {
+            return Err(OperationError::service_error(
+                "Internal ids range mismatch between self segment vectors and other segment vectors",
+            ));
         }
 
         if let Some(new_internal_range) = new_internal_range {
-            let internal_id_iter = new_internal_range.zip(other_id_tracker.iter_ids());
+            let internal_id_iter = new_internal_range.zip(other_ids.iter().copied());
 
             for (new_internal_id, old_internal_id) in internal_id_iter {
                 check_process_stopped(stopped)?;
@@ -133,13 +350,14 @@ impl SegmentBuilder {
                         );
                         continue;
                     };
-                let other_version = other_id_tracker.internal_version(old_internal_id).unwrap();
+                let other_versions = other_id_tracker.internal_versions(old_internal_id).unwrap();
+                let other_version = other_versions.point();
 
                 match id_tracker.internal_id(external_id) {
                     None => {
                         // New point, just insert
                         id_tracker.set_link(external_id, new_internal_id)?;
-                        id_tracker.set_internal_version(new_internal_id, other_version)?;
+                        id_tracker.set_internal_versions(new_internal_id, other_versions)?;
                         payload_index.assign(
                             new_internal_id,
                             &other_payload_index.payload(old_internal_id)?,
@@ -154,7 +372,7 @@ impl SegmentBuilder {
                             // Other version is the newest, remove the existing one and replace
                             id_tracker.drop(external_id)?;
                             id_tracker.set_link(external_id, new_internal_id)?;
-                            id_tracker.set_internal_version(new_internal_id, other_version)?;
+                            id_tracker.set_internal_versions(new_internal_id, other_versions)?;
                             payload_index.drop(existing_internal_id)?;
                             payload_index.assign(
                                 new_internal_id,
@@ -169,6 +387,9 @@ impl SegmentBuilder {
                         for vector_storage in vector_storages.values_mut() {
                             vector_storage.delete_vector(remove_id)?;
                         }
+                        for sparse_vector_storage in sparse_vector_storages.values_mut() {
+                            sparse_vector_storage.delete_vector(remove_id)?;
+                        }
                     }
                 }
             }
@@ -195,7 +416,27 @@ impl SegmentBuilder {
             Self::update_quantization(&segment, stopped)?;
 
             for vector_data in segment.vector_data.values_mut() {
-                vector_data.vector_index.borrow_mut().build_index(stopped)?;
+                let mut vector_index = vector_data.vector_index.borrow_mut();
+                if let Some(progress) = &self.build_progress {
+                    vector_index.set_build_progress(progress.clone());
+                }
+                let mut timer = self
+                    .index_build_telemetry
+                    .as_ref()
+                    .filter(|_| vector_index.is_index())
+                    .map(ScopeDurationMeasurer::new);
+                let result = vector_index.build_index(stopped);
+                if let Some(timer) = &mut timer {
+                    timer.set_success(result.is_ok());
+                }
+                result?;
+            }
+
+            for sparse_vector_data in segment.sparse_vector_data.values_mut() {
+                sparse_vector_data
+                    .vector_index
+                    .borrow_mut()
+                    .build_index(stopped)?;
             }
 
             segment.flush(true)?;
@@ -207,6 +448,14 @@ impl SegmentBuilder {
         std::fs::rename(&self.temp_path, &self.destination_path)
             .describe("Moving segment data after optimization")?;
 
+        // Persist the rename, source segments may be removed right after the new one is loaded
+        #[cfg(unix)]
+        if let Some(collection_path) = self.destination_path.parent() {
+            std::fs::File::open(collection_path)
+                .and_then(|dir| dir.sync_all())
+                .describe("Syncing collection directory after optimization")?;
+        }
+
         let loaded_segment = load_segment(&self.destination_path)?.ok_or_else(|| {
             OperationError::service_error(format!(
                 "Segment loading error: {}",
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
@@ -11,7 +12,7 @@ use semver::Version;
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+use crate::common::rocksdb_wrapper::{open_db, DB_SPARSE_VECTOR_CF, DB_VECTOR_CF};
 use crate::common::version::StorageVersion;
 use crate::data_types::vectors::DEFAULT_VECTOR_NAME;
 use crate::entry::entry_point::{OperationError, OperationResult};
@@ -20,23 +21,34 @@ use crate::id_tracker::IdTracker;
 use crate::index::hnsw_index::graph_links::{GraphLinksMmap, GraphLinksRam};
 use crate::index::hnsw_index::hnsw::HNSWIndex;
 use crate::index::plain_payload_index::PlainIndex;
+use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::VectorIndexEnum;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
-use crate::segment::{Segment, SegmentVersion, VectorData, SEGMENT_STATE_FILE};
+use crate::segment::{Segment, SegmentVersion, SparseVectorData, VectorData, SEGMENT_STATE_FILE};
 use crate::types::{
     Distance, Indexes, PayloadStorageType, SegmentConfig, SegmentState, SegmentType, SeqNumberType,
-    VectorStorageType,
+    VectorStorageDatatype, VectorStorageType,
+};
+use crate::vector_storage::appendable_mmap_vector_storage::{
+    open_appendable_memmap_vector_storage, open_appendable_memmap_vector_storage_byte,
+    open_appendable_memmap_vector_storage_half,
+};
+use crate::vector_storage::memmap_vector_storage::{
+    open_memmap_vector_storage, open_memmap_vector_storage_byte, open_memmap_vector_storage_half,
+};
+use crate::vector_storage::simple_multi_vector_storage::open_simple_multi_vector_storage;
+use crate::vector_storage::simple_sparse_vector_storage::open_simple_sparse_vector_storage;
+use crate::vector_storage::simple_vector_storage::{
+    open_simple_vector_storage, open_simple_vector_storage_byte, open_simple_vector_storage_half,
 };
-use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
-use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage;
-use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
 use crate::vector_storage::VectorStorage;
 
 pub const PAYLOAD_INDEX_PATH: &str = "payload_index";
 pub const VECTOR_STORAGE_PATH: &str = "vector_storage";
 pub const VECTOR_INDEX_PATH: &str = "vector_index";
+pub const SPARSE_VECTOR_INDEX_PATH: &str = "sparse_vector_index";
 
 fn sp<T>(t: T) -> Arc<AtomicRefCell<T>> {
     Arc::new(AtomicRefCell::new(t))
@@ -61,6 +73,13 @@ pub fn get_vector_index_path(segment_path: &Path, vector_name: &str) -> PathBuf
     segment_path.join(get_vector_name_with_prefix(VECTOR_INDEX_PATH, vector_name))
 }
 
+pub fn get_sparse_vector_index_path(segment_path: &Path, vector_name: &str) -> PathBuf {
+    segment_path.join(get_vector_name_with_prefix(
+        SPARSE_VECTOR_INDEX_PATH,
+        vector_name,
+    ))
+}
+
 fn create_segment(
     version: Option<SeqNumberType>,
     segment_path: &Path,
@@ -70,6 +89,12 @@ fn create_segment(
         .vector_data
         .keys()
         .map(|vector_name| get_vector_name_with_prefix(DB_VECTOR_CF, vector_name))
+        .chain(
+            config
+                .sparse_vector_data
+                .keys()
+                .map(|vector_name| get_vector_name_with_prefix(DB_SPARSE_VECTOR_CF, vector_name)),
+        )
         .collect();
     let database = open_db(segment_path, &vector_db_names)
         .map_err(|err| OperationError::service_error(format!("RocksDB open error: {err}")))?;
@@ -94,30 +119,101 @@ fn create_segment(
         let vector_index_path = get_vector_index_path(segment_path, vector_name);
 
         // Select suitable vector storage type based on configuration
-        let vector_storage = match vector_config.storage_type {
+        let vector_storage = match (
+            vector_config.multivector_config,
+            vector_config.storage_type,
+            vector_config.datatype(),
+        ) {
+            // Multi-vectors have variable size, they are always kept in memory
+            (Some(multivector_config), _, VectorStorageDatatype::Float32) => {
+                open_simple_multi_vector_storage(
+                    database.clone(),
+                    &get_vector_name_with_prefix(DB_VECTOR_CF, vector_name),
+                    vector_config.size,
+                    vector_config.distance,
+                    multivector_config,
+                )?
+            }
+            (Some(_), _, datatype) => {
+                return Err(OperationError::ValidationError {
+                    description: format!(
+                        "Multi-vector `{vector_name}` can't be stored with {datatype:?} datatype, only float32 is supported"
+                    ),
+                })
+            }
             // In memory
-            VectorStorageType::Memory => {
-                let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+            (None, VectorStorageType::Memory, VectorStorageDatatype::Float32) => {
                 open_simple_vector_storage(
                     database.clone(),
-                    &db_column_name,
+                    &get_vector_name_with_prefix(DB_VECTOR_CF, vector_name),
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (None, VectorStorageType::Memory, VectorStorageDatatype::Float16) => {
+                open_simple_vector_storage_half(
+                    database.clone(),
+                    &get_vector_name_with_prefix(DB_VECTOR_CF, vector_name),
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (None, VectorStorageType::Memory, VectorStorageDatatype::Uint8) => {
+                open_simple_vector_storage_byte(
+                    database.clone(),
+                    &get_vector_name_with_prefix(DB_VECTOR_CF, vector_name),
                     vector_config.size,
                     vector_config.distance,
                 )?
             }
             // Mmap on disk, not appendable
-            VectorStorageType::Mmap => open_memmap_vector_storage(
-                &vector_storage_path,
-                vector_config.size,
-                vector_config.distance,
-            )?,
+            (None, VectorStorageType::Mmap, VectorStorageDatatype::Float32) => {
+                open_memmap_vector_storage(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (None, VectorStorageType::Mmap, VectorStorageDatatype::Float16) => {
+                open_memmap_vector_storage_half(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (None, VectorStorageType::Mmap, VectorStorageDatatype::Uint8) => {
+                open_memmap_vector_storage_byte(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
             // Chunked mmap on disk, appendable
-            VectorStorageType::ChunkedMmap => open_appendable_memmap_vector_storage(
-                &vector_storage_path,
-                vector_config.size,
-                vector_config.distance,
-            )?,
+            (None, VectorStorageType::ChunkedMmap, VectorStorageDatatype::Float32) => {
+                open_appendable_memmap_vector_storage(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (None, VectorStorageType::ChunkedMmap, VectorStorageDatatype::Float16) => {
+                open_appendable_memmap_vector_storage_half(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
+            (None, VectorStorageType::ChunkedMmap, VectorStorageDatatype::Uint8) => {
+                open_appendable_memmap_vector_storage_byte(
+                    &vector_storage_path,
+                    vector_config.size,
+                    vector_config.distance,
+                )?
+            }
         };
+        vector_storage
+            .borrow_mut()
+            .set_original_vectors(vector_config.store_original_vectors);
 
         // Warn when number of points between ID tracker and storage differs
         let point_count = id_tracker.borrow().total_point_count();
@@ -168,6 +264,29 @@ fn create_segment(
             VectorData {
                 vector_storage,
                 vector_index,
+                prefault_on_search: AtomicBool::new(false),
+            },
+        );
+    }
+
+    let mut sparse_vector_data = HashMap::new();
+    for (vector_name, sparse_vector_config) in &config.sparse_vector_data {
+        let vector_storage = open_simple_sparse_vector_storage(
+            database.clone(),
+            &get_vector_name_with_prefix(DB_SPARSE_VECTOR_CF, vector_name),
+        )?;
+        let vector_index = sp(SparseVectorIndex::open(
+            sparse_vector_config.index,
+            id_tracker.clone(),
+            vector_storage.clone(),
+            payload_index.clone(),
+            &get_sparse_vector_index_path(segment_path, vector_name),
+        )?);
+        sparse_vector_data.insert(
+            vector_name.to_owned(),
+            SparseVectorData {
+                vector_index,
+                vector_storage,
             },
         );
     }
@@ -177,7 +296,10 @@ fn create_segment(
     } else {
         SegmentType::Plain
     };
-    let appendable_flag = vector_data.values().all(VectorData::is_appendable);
+    let appendable_flag = vector_data.values().all(VectorData::is_appendable)
+        && sparse_vector_data
+            .values()
+            .all(SparseVectorData::is_appendable);
 
     Ok(Segment {
         version,
@@ -185,6 +307,7 @@ fn create_segment(
         current_path: segment_path.to_owned(),
         id_tracker,
         vector_data,
+        sparse_vector_data,
         segment_type,
         appendable_flag,
         payload_index,
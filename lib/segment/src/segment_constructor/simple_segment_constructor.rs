@@ -29,9 +29,13 @@ pub fn build_simple_segment(
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    datatype: None,
+                    multivector_config: None,
+                    store_original_vectors: false,
                 },
             )]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         },
         true,
     )
@@ -52,6 +56,9 @@ pub fn build_multivec_segment(
             storage_type: VectorStorageType::Memory,
             index: Indexes::Plain {},
             quantization_config: None,
+            datatype: None,
+            multivector_config: None,
+            store_original_vectors: false,
         },
     );
     vectors_config.insert(
@@ -62,6 +69,9 @@ pub fn build_multivec_segment(
             storage_type: VectorStorageType::Memory,
             index: Indexes::Plain {},
             quantization_config: None,
+            datatype: None,
+            multivector_config: None,
+            store_original_vectors: false,
         },
     );
 
@@ -70,6 +80,7 @@ pub fn build_multivec_segment(
         &SegmentConfig {
             vector_data: vectors_config,
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         },
         true,
     )
@@ -1,3 +1,4 @@
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::types::{Distance, ScoreType};
 
@@ -15,3 +16,23 @@ pub trait Metric {
     /// correct metric score for displaying
     fn postprocess(score: ScoreType) -> ScoreType;
 }
+
+/// Defines how to compare vectors stored with element type `T`
+///
+/// Preprocessing and postprocessing are done on [`VectorElementType`] vectors,
+/// before conversion into `T`.
+pub trait ElementMetric<T: PrimitiveVectorElement>: Metric {
+    /// Greater the value - closer the vectors
+    fn element_similarity(v1: &[T], v2: &[T]) -> ScoreType;
+
+    /// Query transformation, which matches preprocessing of the stored vectors
+    fn element_preprocess(vector: Vec<VectorElementType>) -> Vec<VectorElementType> {
+        Self::preprocess(&vector).unwrap_or(vector)
+    }
+}
+
+impl<TMetric: Metric> ElementMetric<VectorElementType> for TMetric {
+    fn element_similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        TMetric::similarity(v1, v2)
+    }
+}
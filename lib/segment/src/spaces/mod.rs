@@ -1,5 +1,7 @@
 pub mod metric;
 pub mod simple;
+pub mod simple_f16;
+pub mod simple_u8;
 pub mod tools;
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -8,5 +10,20 @@ pub mod simple_sse;
 #[cfg(target_arch = "x86_64")]
 pub mod simple_avx;
 
+#[cfg(target_arch = "x86_64")]
+pub mod simple_avx512;
+
+#[cfg(target_arch = "x86_64")]
+pub mod simple_f16_avx;
+
+#[cfg(target_arch = "x86_64")]
+pub mod simple_u8_avx;
+
 #[cfg(target_arch = "aarch64")]
 pub mod simple_neon;
+
+#[cfg(target_arch = "aarch64")]
+pub mod simple_f16_neon;
+
+#[cfg(target_arch = "aarch64")]
+pub mod simple_u8_neon;
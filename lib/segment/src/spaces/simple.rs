@@ -1,6 +1,10 @@
+use once_cell::sync::Lazy;
+
 use super::metric::Metric;
 #[cfg(target_arch = "x86_64")]
 use super::simple_avx::*;
+#[cfg(target_arch = "x86_64")]
+use super::simple_avx512::*;
 #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
 use super::simple_neon::*;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -8,6 +12,9 @@ use super::simple_sse::*;
 use crate::data_types::vectors::VectorElementType;
 use crate::types::{Distance, ScoreType};
 
+#[cfg(target_arch = "x86_64")]
+const MIN_DIM_SIZE_AVX512: usize = 64;
+
 #[cfg(target_arch = "x86_64")]
 const MIN_DIM_SIZE_AVX: usize = 32;
 
@@ -18,46 +25,266 @@ const MIN_DIM_SIZE_AVX: usize = 32;
 ))]
 const MIN_DIM_SIZE_SIMD: usize = 16;
 
+/// If set to `1` or `true`, SIMD is not used for metrics, regardless of CPU features
+pub const FORCE_SCALAR_METRICS_ENV: &str = "QDRANT_FORCE_SCALAR_METRICS";
+
+pub type SimilarityFn = fn(&[VectorElementType], &[VectorElementType]) -> ScoreType;
+
+pub type PreprocessFn = fn(&[VectorElementType]) -> Option<Vec<VectorElementType>>;
+
+/// Metric kernels for a single instruction set
+///
+/// Vectors shorter than the SIMD width of the instruction set are handled by a narrower one.
+#[derive(Clone, Copy)]
+pub struct MetricKernels {
+    pub name: &'static str,
+    pub dot_similarity: SimilarityFn,
+    pub euclid_similarity: SimilarityFn,
+    pub manhattan_similarity: SimilarityFn,
+    pub cosine_preprocess: PreprocessFn,
+}
+
+const SCALAR_KERNELS: MetricKernels = MetricKernels {
+    name: "scalar",
+    dot_similarity,
+    euclid_similarity,
+    manhattan_similarity,
+    cosine_preprocess,
+};
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const SSE_KERNELS: MetricKernels = MetricKernels {
+    name: "sse",
+    dot_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { dot_similarity_sse(v1, v2) }
+        } else {
+            dot_similarity(v1, v2)
+        }
+    },
+    euclid_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { euclid_similarity_sse(v1, v2) }
+        } else {
+            euclid_similarity(v1, v2)
+        }
+    },
+    manhattan_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { manhattan_similarity_sse(v1, v2) }
+        } else {
+            manhattan_similarity(v1, v2)
+        }
+    },
+    cosine_preprocess: |vector| {
+        if vector.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { cosine_preprocess_sse(vector) }
+        } else {
+            cosine_preprocess(vector)
+        }
+    },
+};
+
+#[cfg(target_arch = "x86_64")]
+const AVX_KERNELS: MetricKernels = MetricKernels {
+    name: "avx2+fma",
+    dot_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_AVX {
+            unsafe { dot_similarity_avx(v1, v2) }
+        } else {
+            (SSE_KERNELS.dot_similarity)(v1, v2)
+        }
+    },
+    euclid_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_AVX {
+            unsafe { euclid_similarity_avx(v1, v2) }
+        } else {
+            (SSE_KERNELS.euclid_similarity)(v1, v2)
+        }
+    },
+    manhattan_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_AVX {
+            unsafe { manhattan_similarity_avx(v1, v2) }
+        } else {
+            (SSE_KERNELS.manhattan_similarity)(v1, v2)
+        }
+    },
+    cosine_preprocess: |vector| {
+        if vector.len() >= MIN_DIM_SIZE_AVX {
+            unsafe { cosine_preprocess_avx(vector) }
+        } else {
+            (SSE_KERNELS.cosine_preprocess)(vector)
+        }
+    },
+};
+
+#[cfg(target_arch = "x86_64")]
+const AVX512_KERNELS: MetricKernels = MetricKernels {
+    name: "avx512f",
+    dot_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_AVX512 {
+            unsafe { dot_similarity_avx512(v1, v2) }
+        } else {
+            (AVX_KERNELS.dot_similarity)(v1, v2)
+        }
+    },
+    euclid_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_AVX512 {
+            unsafe { euclid_similarity_avx512(v1, v2) }
+        } else {
+            (AVX_KERNELS.euclid_similarity)(v1, v2)
+        }
+    },
+    manhattan_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_AVX512 {
+            unsafe { manhattan_similarity_avx512(v1, v2) }
+        } else {
+            (AVX_KERNELS.manhattan_similarity)(v1, v2)
+        }
+    },
+    cosine_preprocess: |vector| {
+        if vector.len() >= MIN_DIM_SIZE_AVX512 {
+            unsafe { cosine_preprocess_avx512(vector) }
+        } else {
+            (AVX_KERNELS.cosine_preprocess)(vector)
+        }
+    },
+};
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+const NEON_KERNELS: MetricKernels = MetricKernels {
+    name: "neon",
+    dot_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { dot_similarity_neon(v1, v2) }
+        } else {
+            dot_similarity(v1, v2)
+        }
+    },
+    euclid_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { euclid_similarity_neon(v1, v2) }
+        } else {
+            euclid_similarity(v1, v2)
+        }
+    },
+    manhattan_similarity: |v1, v2| {
+        if v1.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { manhattan_similarity_neon(v1, v2) }
+        } else {
+            manhattan_similarity(v1, v2)
+        }
+    },
+    cosine_preprocess: |vector| {
+        if vector.len() >= MIN_DIM_SIZE_SIMD {
+            unsafe { cosine_preprocess_neon(vector) }
+        } else {
+            cosine_preprocess(vector)
+        }
+    },
+};
+
+static METRIC_KERNELS: Lazy<MetricKernels> = Lazy::new(|| {
+    let force_scalar = std::env::var(FORCE_SCALAR_METRICS_ENV)
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    let kernels = select_metric_kernels(force_scalar);
+    log::debug!("Using {} metric kernels", kernels.name);
+    kernels
+});
+
+fn select_metric_kernels(force_scalar: bool) -> MetricKernels {
+    if force_scalar {
+        return SCALAR_KERNELS;
+    }
+    // Kernels are ordered from the widest SIMD, there is always the scalar one
+    supported_metric_kernels()[0]
+}
+
+/// Metric kernels of all instruction sets supported by the CPU, from the widest SIMD to scalar
+pub fn supported_metric_kernels() -> Vec<MetricKernels> {
+    let mut kernels = Vec::new();
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx512f") {
+            kernels.push(AVX512_KERNELS);
+        }
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            kernels.push(AVX_KERNELS);
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("sse") {
+            kernels.push(SSE_KERNELS);
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            kernels.push(NEON_KERNELS);
+        }
+    }
+
+    kernels.push(SCALAR_KERNELS);
+    kernels
+}
+
+/// Metric kernels selected for this CPU on first use
+pub fn metric_kernels() -> &'static MetricKernels {
+    &METRIC_KERNELS
+}
+
 #[derive(Clone)]
 pub struct DotProductMetric {}
 
 #[derive(Clone)]
 pub struct CosineMetric {}
 
+/// Cosine similarity of vectors, which are stored without normalization
+///
+/// Both vectors are normalized during scoring exactly like [`CosineMetric`] normalizes them
+/// before storing, so scores are the same as of [`CosineMetric`] on normalized vectors.
+#[derive(Clone)]
+pub struct UnnormalizedCosineMetric {}
+
 #[derive(Clone)]
 pub struct EuclidMetric {}
 
+#[derive(Clone)]
+pub struct ManhattanMetric {}
+
+#[derive(Clone)]
+pub struct HammingMetric {}
+
 impl Metric for EuclidMetric {
     fn distance() -> Distance {
         Distance::Euclid
     }
 
     fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && v1.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { euclid_similarity_avx(v1, v2) };
-            }
-        }
+        (metric_kernels().euclid_similarity)(v1, v2)
+    }
 
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { euclid_similarity_sse(v1, v2) };
-            }
-        }
+    fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        None
+    }
 
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { euclid_similarity_neon(v1, v2) };
-            }
-        }
+    fn postprocess(score: ScoreType) -> ScoreType {
+        score.abs().sqrt()
+    }
+}
 
-        euclid_similarity(v1, v2)
+impl Metric for ManhattanMetric {
+    fn distance() -> Distance {
+        Distance::Manhattan
+    }
+
+    fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        (metric_kernels().manhattan_similarity)(v1, v2)
     }
 
     fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
@@ -65,41 +292,35 @@ impl Metric for EuclidMetric {
     }
 
     fn postprocess(score: ScoreType) -> ScoreType {
-        score.abs().sqrt()
+        score.abs()
     }
 }
 
-impl Metric for DotProductMetric {
+impl Metric for HammingMetric {
     fn distance() -> Distance {
-        Distance::Dot
+        Distance::Hamming
     }
 
     fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && v1.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { dot_similarity_avx(v1, v2) };
-            }
-        }
+        hamming_similarity(v1, v2)
+    }
 
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_sse(v1, v2) };
-            }
-        }
+    fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        None
+    }
 
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_neon(v1, v2) };
-            }
-        }
+    fn postprocess(score: ScoreType) -> ScoreType {
+        score.abs()
+    }
+}
 
-        dot_similarity(v1, v2)
+impl Metric for DotProductMetric {
+    fn distance() -> Distance {
+        Distance::Dot
+    }
+
+    fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        (metric_kernels().dot_similarity)(v1, v2)
     }
 
     fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
@@ -117,60 +338,35 @@ impl Metric for CosineMetric {
     }
 
     fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && v1.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { dot_similarity_avx(v1, v2) };
-            }
-        }
-
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_sse(v1, v2) };
-            }
-        }
-
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { dot_similarity_neon(v1, v2) };
-            }
-        }
-
-        dot_similarity(v1, v2)
+        (metric_kernels().dot_similarity)(v1, v2)
     }
 
     fn preprocess(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
-        #[cfg(target_arch = "x86_64")]
-        {
-            if is_x86_feature_detected!("avx")
-                && is_x86_feature_detected!("fma")
-                && vector.len() >= MIN_DIM_SIZE_AVX
-            {
-                return unsafe { cosine_preprocess_avx(vector) };
-            }
-        }
+        (metric_kernels().cosine_preprocess)(vector)
+    }
 
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        {
-            if is_x86_feature_detected!("sse") && vector.len() >= MIN_DIM_SIZE_SIMD {
-                return unsafe { cosine_preprocess_sse(vector) };
-            }
-        }
+    fn postprocess(score: ScoreType) -> ScoreType {
+        score
+    }
+}
 
-        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
-        {
-            if std::arch::is_aarch64_feature_detected!("neon") && vector.len() >= MIN_DIM_SIZE_SIMD
-            {
-                return unsafe { cosine_preprocess_neon(vector) };
-            }
-        }
+impl Metric for UnnormalizedCosineMetric {
+    fn distance() -> Distance {
+        Distance::Cosine
+    }
 
-        cosine_preprocess(vector)
+    fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        let v1_normalized = CosineMetric::preprocess(v1);
+        let v2_normalized = CosineMetric::preprocess(v2);
+        CosineMetric::similarity(
+            v1_normalized.as_deref().unwrap_or(v1),
+            v2_normalized.as_deref().unwrap_or(v2),
+        )
+    }
+
+    /// Query is normalized along with stored vectors during scoring
+    fn preprocess(_vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
+        None
     }
 
     fn postprocess(score: ScoreType) -> ScoreType {
@@ -188,6 +384,20 @@ pub fn euclid_similarity(v1: &[VectorElementType], v2: &[VectorElementType]) ->
     -s
 }
 
+pub fn manhattan_similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+    -v1.iter()
+        .zip(v2)
+        .map(|(a, b)| (a - b).abs())
+        .sum::<ScoreType>()
+}
+
+/// Number of differing components, negated
+///
+/// Hamming distance is meant for byte storage, float vectors are only compared for it in tests.
+pub fn hamming_similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+    -(v1.iter().zip(v2).filter(|(a, b)| a != b).count() as ScoreType)
+}
+
 pub fn cosine_preprocess(vector: &[VectorElementType]) -> Option<Vec<VectorElementType>> {
     let mut length: f32 = vector.iter().map(|x| x * x).sum();
     if length < f32::EPSILON {
@@ -210,4 +420,84 @@ mod tests {
         let res = CosineMetric::preprocess(&[0.0, 0.0, 0.0, 0.0]);
         assert!(res.is_none());
     }
+
+    #[test]
+    fn test_unnormalized_cosine_matches_cosine() {
+        let v1 = [3.0, -1.5, 0.25, 8.0, 2.0];
+        let v2 = [0.5, 4.0, -2.0, 1.0, 0.0];
+        let zero = [0.0; 5];
+        let normalized = |v: &[f32]| CosineMetric::preprocess(v).unwrap_or_else(|| v.to_vec());
+        for (a, b) in [(&v1, &v2), (&v2, &v1), (&v1, &v1), (&v1, &zero)] {
+            assert_eq!(
+                UnnormalizedCosineMetric::similarity(a, b),
+                CosineMetric::similarity(&normalized(a), &normalized(b)),
+            );
+        }
+    }
+
+    fn assert_close(simd: f32, scalar: f32, name: &str, len: usize) {
+        assert!(
+            (simd - scalar).abs() <= scalar.abs() * 1e-5 + 1e-5,
+            "{name} kernel differs from scalar for length {len}: {simd} != {scalar}",
+        );
+    }
+
+    #[test]
+    fn test_supported_kernels_match_scalar() {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        // Lengths around multiples of SIMD widths leave remainders for the scalar tail
+        let lengths = [
+            1, 3, 7, 15, 17, 31, 33, 63, 65, 100, 127, 129, 257, 1000, 1023,
+        ];
+
+        let kernels = supported_metric_kernels();
+        assert_eq!(kernels.last().unwrap().name, SCALAR_KERNELS.name);
+
+        for len in lengths {
+            let v1: Vec<f32> = (0..len).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let v2: Vec<f32> = (0..len).map(|_| rng.gen_range(-1.0..1.0)).collect();
+
+            let dot = dot_similarity(&v1, &v2);
+            let euclid = euclid_similarity(&v1, &v2);
+            let manhattan = manhattan_similarity(&v1, &v2);
+            let cosine = cosine_preprocess(&v1).unwrap();
+
+            for kernel in &kernels {
+                assert_close((kernel.dot_similarity)(&v1, &v2), dot, kernel.name, len);
+                assert_close(
+                    (kernel.euclid_similarity)(&v1, &v2),
+                    euclid,
+                    kernel.name,
+                    len,
+                );
+                assert_close(
+                    (kernel.manhattan_similarity)(&v1, &v2),
+                    manhattan,
+                    kernel.name,
+                    len,
+                );
+                let kernel_cosine = (kernel.cosine_preprocess)(&v1).unwrap();
+                for (simd, scalar) in kernel_cosine.iter().zip(&cosine) {
+                    assert_close(*simd, *scalar, kernel.name, len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_selected_kernels_are_supported() {
+        let selected = metric_kernels().name;
+        assert!(supported_metric_kernels()
+            .iter()
+            .any(|kernel| kernel.name == selected));
+
+        assert_eq!(select_metric_kernels(true).name, SCALAR_KERNELS.name);
+        assert_eq!(
+            select_metric_kernels(false).name,
+            supported_metric_kernels()[0].name,
+        );
+    }
 }
@@ -0,0 +1,187 @@
+use std::arch::x86_64::*;
+
+use crate::data_types::vectors::VectorElementType;
+use crate::types::ScoreType;
+
+#[target_feature(enable = "avx512f")]
+pub(crate) unsafe fn euclid_similarity_avx512(
+    v1: &[VectorElementType],
+    v2: &[VectorElementType],
+) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 64);
+    let mut ptr1: *const f32 = v1.as_ptr();
+    let mut ptr2: *const f32 = v2.as_ptr();
+    let mut sum512_1: __m512 = _mm512_setzero_ps();
+    let mut sum512_2: __m512 = _mm512_setzero_ps();
+    let mut sum512_3: __m512 = _mm512_setzero_ps();
+    let mut sum512_4: __m512 = _mm512_setzero_ps();
+    let mut i: usize = 0;
+    while i < m {
+        let sub512_1: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(0)), _mm512_loadu_ps(ptr2.add(0)));
+        sum512_1 = _mm512_fmadd_ps(sub512_1, sub512_1, sum512_1);
+
+        let sub512_2: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(16)), _mm512_loadu_ps(ptr2.add(16)));
+        sum512_2 = _mm512_fmadd_ps(sub512_2, sub512_2, sum512_2);
+
+        let sub512_3: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(32)), _mm512_loadu_ps(ptr2.add(32)));
+        sum512_3 = _mm512_fmadd_ps(sub512_3, sub512_3, sum512_3);
+
+        let sub512_4: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(48)), _mm512_loadu_ps(ptr2.add(48)));
+        sum512_4 = _mm512_fmadd_ps(sub512_4, sub512_4, sum512_4);
+
+        ptr1 = ptr1.add(64);
+        ptr2 = ptr2.add(64);
+        i += 64;
+    }
+
+    let mut result = _mm512_reduce_add_ps(sum512_1)
+        + _mm512_reduce_add_ps(sum512_2)
+        + _mm512_reduce_add_ps(sum512_3)
+        + _mm512_reduce_add_ps(sum512_4);
+    for i in 0..n - m {
+        result += (*ptr1.add(i) - *ptr2.add(i)).powi(2);
+    }
+    -result
+}
+
+#[target_feature(enable = "avx512f")]
+pub(crate) unsafe fn manhattan_similarity_avx512(
+    v1: &[VectorElementType],
+    v2: &[VectorElementType],
+) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 64);
+    let mut ptr1: *const f32 = v1.as_ptr();
+    let mut ptr2: *const f32 = v2.as_ptr();
+    let mut sum512_1: __m512 = _mm512_setzero_ps();
+    let mut sum512_2: __m512 = _mm512_setzero_ps();
+    let mut sum512_3: __m512 = _mm512_setzero_ps();
+    let mut sum512_4: __m512 = _mm512_setzero_ps();
+    let mut i: usize = 0;
+    while i < m {
+        let sub512_1: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(0)), _mm512_loadu_ps(ptr2.add(0)));
+        sum512_1 = _mm512_add_ps(_mm512_abs_ps(sub512_1), sum512_1);
+
+        let sub512_2: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(16)), _mm512_loadu_ps(ptr2.add(16)));
+        sum512_2 = _mm512_add_ps(_mm512_abs_ps(sub512_2), sum512_2);
+
+        let sub512_3: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(32)), _mm512_loadu_ps(ptr2.add(32)));
+        sum512_3 = _mm512_add_ps(_mm512_abs_ps(sub512_3), sum512_3);
+
+        let sub512_4: __m512 =
+            _mm512_sub_ps(_mm512_loadu_ps(ptr1.add(48)), _mm512_loadu_ps(ptr2.add(48)));
+        sum512_4 = _mm512_add_ps(_mm512_abs_ps(sub512_4), sum512_4);
+
+        ptr1 = ptr1.add(64);
+        ptr2 = ptr2.add(64);
+        i += 64;
+    }
+
+    let mut result = _mm512_reduce_add_ps(sum512_1)
+        + _mm512_reduce_add_ps(sum512_2)
+        + _mm512_reduce_add_ps(sum512_3)
+        + _mm512_reduce_add_ps(sum512_4);
+    for i in 0..n - m {
+        result += (*ptr1.add(i) - *ptr2.add(i)).abs();
+    }
+    -result
+}
+
+#[target_feature(enable = "avx512f")]
+pub(crate) unsafe fn cosine_preprocess_avx512(
+    vector: &[VectorElementType],
+) -> Option<Vec<VectorElementType>> {
+    let n = vector.len();
+    let m = n - (n % 64);
+    let mut ptr: *const f32 = vector.as_ptr();
+    let mut sum512_1: __m512 = _mm512_setzero_ps();
+    let mut sum512_2: __m512 = _mm512_setzero_ps();
+    let mut sum512_3: __m512 = _mm512_setzero_ps();
+    let mut sum512_4: __m512 = _mm512_setzero_ps();
+    let mut i: usize = 0;
+    while i < m {
+        let m512_1 = _mm512_loadu_ps(ptr);
+        sum512_1 = _mm512_fmadd_ps(m512_1, m512_1, sum512_1);
+
+        let m512_2 = _mm512_loadu_ps(ptr.add(16));
+        sum512_2 = _mm512_fmadd_ps(m512_2, m512_2, sum512_2);
+
+        let m512_3 = _mm512_loadu_ps(ptr.add(32));
+        sum512_3 = _mm512_fmadd_ps(m512_3, m512_3, sum512_3);
+
+        let m512_4 = _mm512_loadu_ps(ptr.add(48));
+        sum512_4 = _mm512_fmadd_ps(m512_4, m512_4, sum512_4);
+
+        ptr = ptr.add(64);
+        i += 64;
+    }
+
+    let mut length = _mm512_reduce_add_ps(sum512_1)
+        + _mm512_reduce_add_ps(sum512_2)
+        + _mm512_reduce_add_ps(sum512_3)
+        + _mm512_reduce_add_ps(sum512_4);
+    for i in 0..n - m {
+        length += (*ptr.add(i)).powi(2);
+    }
+    if length < f32::EPSILON {
+        return None;
+    }
+    length = length.sqrt();
+    Some(vector.iter().map(|x| x / length).collect())
+}
+
+#[target_feature(enable = "avx512f")]
+pub(crate) unsafe fn dot_similarity_avx512(
+    v1: &[VectorElementType],
+    v2: &[VectorElementType],
+) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 64);
+    let mut ptr1: *const f32 = v1.as_ptr();
+    let mut ptr2: *const f32 = v2.as_ptr();
+    let mut sum512_1: __m512 = _mm512_setzero_ps();
+    let mut sum512_2: __m512 = _mm512_setzero_ps();
+    let mut sum512_3: __m512 = _mm512_setzero_ps();
+    let mut sum512_4: __m512 = _mm512_setzero_ps();
+    let mut i: usize = 0;
+    while i < m {
+        sum512_1 = _mm512_fmadd_ps(_mm512_loadu_ps(ptr1), _mm512_loadu_ps(ptr2), sum512_1);
+        sum512_2 = _mm512_fmadd_ps(
+            _mm512_loadu_ps(ptr1.add(16)),
+            _mm512_loadu_ps(ptr2.add(16)),
+            sum512_2,
+        );
+        sum512_3 = _mm512_fmadd_ps(
+            _mm512_loadu_ps(ptr1.add(32)),
+            _mm512_loadu_ps(ptr2.add(32)),
+            sum512_3,
+        );
+        sum512_4 = _mm512_fmadd_ps(
+            _mm512_loadu_ps(ptr1.add(48)),
+            _mm512_loadu_ps(ptr2.add(48)),
+            sum512_4,
+        );
+
+        ptr1 = ptr1.add(64);
+        ptr2 = ptr2.add(64);
+        i += 64;
+    }
+
+    let mut result = _mm512_reduce_add_ps(sum512_1)
+        + _mm512_reduce_add_ps(sum512_2)
+        + _mm512_reduce_add_ps(sum512_3)
+        + _mm512_reduce_add_ps(sum512_4);
+
+    for i in 0..n - m {
+        result += (*ptr1.add(i)) * (*ptr2.add(i));
+    }
+    result
+}
@@ -0,0 +1,157 @@
+use half::f16;
+
+use super::metric::{ElementMetric, Metric};
+use super::simple::{
+    CosineMetric, DotProductMetric, EuclidMetric, HammingMetric, ManhattanMetric,
+    UnnormalizedCosineMetric,
+};
+#[cfg(target_arch = "x86_64")]
+use super::simple_f16_avx::*;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use super::simple_f16_neon::*;
+use crate::types::ScoreType;
+
+#[cfg(target_arch = "x86_64")]
+const MIN_DIM_SIZE_AVX: usize = 32;
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+const MIN_DIM_SIZE_SIMD: usize = 16;
+
+impl ElementMetric<f16> for EuclidMetric {
+    fn element_similarity(v1: &[f16], v2: &[f16]) -> ScoreType {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx")
+                && is_x86_feature_detected!("fma")
+                && is_x86_feature_detected!("f16c")
+                && v1.len() >= MIN_DIM_SIZE_AVX
+            {
+                return unsafe { euclid_similarity_half_avx(v1, v2) };
+            }
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+                return unsafe { euclid_similarity_half_neon(v1, v2) };
+            }
+        }
+
+        euclid_similarity_half(v1, v2)
+    }
+}
+
+impl ElementMetric<f16> for ManhattanMetric {
+    fn element_similarity(v1: &[f16], v2: &[f16]) -> ScoreType {
+        manhattan_similarity_half(v1, v2)
+    }
+}
+
+impl ElementMetric<f16> for HammingMetric {
+    fn element_similarity(v1: &[f16], v2: &[f16]) -> ScoreType {
+        -(v1.iter().zip(v2).filter(|(a, b)| a != b).count() as ScoreType)
+    }
+}
+
+impl ElementMetric<f16> for DotProductMetric {
+    fn element_similarity(v1: &[f16], v2: &[f16]) -> ScoreType {
+        dot_similarity_half_dispatch(v1, v2)
+    }
+}
+
+impl ElementMetric<f16> for CosineMetric {
+    fn element_similarity(v1: &[f16], v2: &[f16]) -> ScoreType {
+        // Vectors are normalized before conversion, so cosine is a dot product
+        dot_similarity_half_dispatch(v1, v2)
+    }
+}
+
+impl ElementMetric<f16> for UnnormalizedCosineMetric {
+    fn element_similarity(v1: &[f16], v2: &[f16]) -> ScoreType {
+        // Normalize in single precision, like vectors are normalized before conversion
+        let v1: Vec<_> = v1.iter().map(|x| x.to_f32()).collect();
+        let v2: Vec<_> = v2.iter().map(|x| x.to_f32()).collect();
+        UnnormalizedCosineMetric::similarity(&v1, &v2)
+    }
+}
+
+fn dot_similarity_half_dispatch(v1: &[f16], v2: &[f16]) -> ScoreType {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx")
+            && is_x86_feature_detected!("fma")
+            && is_x86_feature_detected!("f16c")
+            && v1.len() >= MIN_DIM_SIZE_AVX
+        {
+            return unsafe { dot_similarity_half_avx(v1, v2) };
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+            return unsafe { dot_similarity_half_neon(v1, v2) };
+        }
+    }
+
+    dot_similarity_half(v1, v2)
+}
+
+pub fn euclid_similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+    let s: ScoreType = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| (a.to_f32() - b.to_f32()).powi(2))
+        .sum();
+    -s
+}
+
+pub fn manhattan_similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+    let s: ScoreType = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| (a.to_f32() - b.to_f32()).abs())
+        .sum();
+    -s
+}
+
+pub fn dot_similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+    v1.iter()
+        .zip(v2)
+        .map(|(a, b)| a.to_f32() * b.to_f32())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::spaces::simple::{dot_similarity, euclid_similarity, manhattan_similarity};
+
+    #[test]
+    fn test_half_similarity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for dim in [1, 15, 16, 31, 32, 33, 100, 128, 1000] {
+            let v1: Vec<f16> = (0..dim)
+                .map(|_| f16::from_f32(rng.gen_range(-1.0..1.0)))
+                .collect();
+            let v2: Vec<f16> = (0..dim)
+                .map(|_| f16::from_f32(rng.gen_range(-1.0..1.0)))
+                .collect();
+            let f1: Vec<f32> = v1.iter().map(|x| x.to_f32()).collect();
+            let f2: Vec<f32> = v2.iter().map(|x| x.to_f32()).collect();
+
+            // Components are exact in f32, only summation order differs
+            let dot = dot_similarity(&f1, &f2);
+            let euclid = euclid_similarity(&f1, &f2);
+            let manhattan = manhattan_similarity(&f1, &f2);
+            assert!((dot_similarity_half(&v1, &v2) - dot).abs() < 1e-3);
+            assert!((euclid_similarity_half(&v1, &v2) - euclid).abs() < 1e-3);
+            assert!((DotProductMetric::element_similarity(&v1, &v2) - dot).abs() < 1e-3);
+            assert!((EuclidMetric::element_similarity(&v1, &v2) - euclid).abs() < 1e-3);
+            assert!((ManhattanMetric::element_similarity(&v1, &v2) - manhattan).abs() < 1e-3);
+        }
+    }
+}
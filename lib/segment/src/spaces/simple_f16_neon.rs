@@ -0,0 +1,161 @@
+#[cfg(target_feature = "neon")]
+use std::arch::aarch64::*;
+
+#[cfg(target_feature = "neon")]
+use half::f16;
+
+#[cfg(target_feature = "neon")]
+use crate::types::ScoreType;
+
+/// Load 4 half-precision values and widen them into single-precision lanes
+///
+/// Half-precision NEON conversion intrinsics are not usable on stable Rust, so the bits are
+/// rearranged with integer instructions. Magnitude bits are shifted into the single-precision
+/// layout and rescaled by 2^112, which fixes the exponent bias of normal and subnormal values.
+/// Infinity and NaN get the maximal exponent back explicitly.
+#[cfg(target_feature = "neon")]
+#[inline]
+unsafe fn load_half_f32_neon(ptr: *const f16) -> float32x4_t {
+    let bits = vmovl_u16(vld1_u16(ptr as *const u16));
+    let magnitude = vshlq_n_u32::<13>(vandq_u32(bits, vdupq_n_u32(0x7fff)));
+    let scaled = vreinterpretq_u32_f32(vmulq_f32(
+        vreinterpretq_f32_u32(magnitude),
+        vdupq_n_f32(f32::from_bits(0x7780_0000)),
+    ));
+    let exponent = vandq_u32(bits, vdupq_n_u32(0x7c00));
+    let inf_nan = vandq_u32(
+        vceqq_u32(exponent, vdupq_n_u32(0x7c00)),
+        vdupq_n_u32(0x7f80_0000),
+    );
+    let sign = vshlq_n_u32::<16>(vandq_u32(bits, vdupq_n_u32(0x8000)));
+    vreinterpretq_f32_u32(vorrq_u32(vorrq_u32(scaled, inf_nan), sign))
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn euclid_similarity_half_neon(v1: &[f16], v2: &[f16]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const f16 = v1.as_ptr();
+    let mut ptr2: *const f16 = v2.as_ptr();
+    let mut sum1 = vdupq_n_f32(0.);
+    let mut sum2 = vdupq_n_f32(0.);
+    let mut sum3 = vdupq_n_f32(0.);
+    let mut sum4 = vdupq_n_f32(0.);
+
+    let mut i: usize = 0;
+    while i < m {
+        let sub1 = vsubq_f32(load_half_f32_neon(ptr1), load_half_f32_neon(ptr2));
+        sum1 = vfmaq_f32(sum1, sub1, sub1);
+
+        let sub2 = vsubq_f32(
+            load_half_f32_neon(ptr1.add(4)),
+            load_half_f32_neon(ptr2.add(4)),
+        );
+        sum2 = vfmaq_f32(sum2, sub2, sub2);
+
+        let sub3 = vsubq_f32(
+            load_half_f32_neon(ptr1.add(8)),
+            load_half_f32_neon(ptr2.add(8)),
+        );
+        sum3 = vfmaq_f32(sum3, sub3, sub3);
+
+        let sub4 = vsubq_f32(
+            load_half_f32_neon(ptr1.add(12)),
+            load_half_f32_neon(ptr2.add(12)),
+        );
+        sum4 = vfmaq_f32(sum4, sub4, sub4);
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut result = vaddvq_f32(sum1) + vaddvq_f32(sum2) + vaddvq_f32(sum3) + vaddvq_f32(sum4);
+    for i in 0..n - m {
+        result += ((*ptr1.add(i)).to_f32() - (*ptr2.add(i)).to_f32()).powi(2);
+    }
+    -result
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn dot_similarity_half_neon(v1: &[f16], v2: &[f16]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const f16 = v1.as_ptr();
+    let mut ptr2: *const f16 = v2.as_ptr();
+    let mut sum1 = vdupq_n_f32(0.);
+    let mut sum2 = vdupq_n_f32(0.);
+    let mut sum3 = vdupq_n_f32(0.);
+    let mut sum4 = vdupq_n_f32(0.);
+
+    let mut i: usize = 0;
+    while i < m {
+        sum1 = vfmaq_f32(sum1, load_half_f32_neon(ptr1), load_half_f32_neon(ptr2));
+        sum2 = vfmaq_f32(
+            sum2,
+            load_half_f32_neon(ptr1.add(4)),
+            load_half_f32_neon(ptr2.add(4)),
+        );
+        sum3 = vfmaq_f32(
+            sum3,
+            load_half_f32_neon(ptr1.add(8)),
+            load_half_f32_neon(ptr2.add(8)),
+        );
+        sum4 = vfmaq_f32(
+            sum4,
+            load_half_f32_neon(ptr1.add(12)),
+            load_half_f32_neon(ptr2.add(12)),
+        );
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut result = vaddvq_f32(sum1) + vaddvq_f32(sum2) + vaddvq_f32(sum3) + vaddvq_f32(sum4);
+    for i in 0..n - m {
+        result += (*ptr1.add(i)).to_f32() * (*ptr2.add(i)).to_f32();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_feature = "neon")]
+    #[test]
+    fn test_spaces_half_neon() {
+        use half::f16;
+
+        use super::*;
+        use crate::spaces::simple_f16::*;
+
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            let mut values: Vec<f16> = (0..70).map(|x| f16::from_f32(x as f32 / 7.0)).collect();
+            values.extend([
+                f16::MIN_POSITIVE_SUBNORMAL,
+                -f16::MIN_POSITIVE,
+                f16::MAX,
+                f16::INFINITY,
+                f16::NEG_INFINITY,
+                f16::NEG_ZERO,
+            ]);
+            for chunk in values.chunks_exact(4) {
+                let mut lanes = [0f32; 4];
+                unsafe { vst1q_f32(lanes.as_mut_ptr(), load_half_f32_neon(chunk.as_ptr())) };
+                let expected: Vec<f32> = chunk.iter().map(|x| x.to_f32()).collect();
+                assert_eq!(lanes.as_slice(), expected.as_slice());
+            }
+
+            let v1: Vec<f16> = (0..70).map(|x| f16::from_f32(x as f32 / 7.0)).collect();
+            let v2: Vec<f16> = (0..70).map(|x| f16::from_f32(-x as f32 / 3.0)).collect();
+
+            let euclid_simd = unsafe { euclid_similarity_half_neon(&v1, &v2) };
+            let euclid = euclid_similarity_half(&v1, &v2);
+            assert!((euclid_simd - euclid).abs() / euclid.abs() < 1e-5);
+
+            let dot_simd = unsafe { dot_similarity_half_neon(&v1, &v2) };
+            let dot = dot_similarity_half(&v1, &v2);
+            assert!((dot_simd - dot).abs() / dot.abs() < 1e-5);
+        } else {
+            println!("neon test skipped");
+        }
+    }
+}
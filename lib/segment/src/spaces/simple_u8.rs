@@ -0,0 +1,310 @@
+use super::metric::ElementMetric;
+use super::simple::{
+    CosineMetric, DotProductMetric, EuclidMetric, HammingMetric, ManhattanMetric,
+    UnnormalizedCosineMetric,
+};
+#[cfg(target_arch = "x86_64")]
+use super::simple_u8_avx::*;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use super::simple_u8_neon::*;
+use crate::data_types::vectors::VectorElementType;
+use crate::types::ScoreType;
+
+#[cfg(target_arch = "x86_64")]
+const MIN_DIM_SIZE_AVX: usize = 32;
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+const MIN_DIM_SIZE_SIMD: usize = 16;
+
+impl ElementMetric<u8> for EuclidMetric {
+    fn element_similarity(v1: &[u8], v2: &[u8]) -> ScoreType {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") && v1.len() >= MIN_DIM_SIZE_AVX {
+                return unsafe { euclid_similarity_bytes_avx2(v1, v2) };
+            }
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+                return unsafe { euclid_similarity_bytes_neon(v1, v2) };
+            }
+        }
+
+        euclid_similarity_bytes(v1, v2)
+    }
+}
+
+impl ElementMetric<u8> for ManhattanMetric {
+    fn element_similarity(v1: &[u8], v2: &[u8]) -> ScoreType {
+        manhattan_similarity_bytes(v1, v2)
+    }
+}
+
+impl ElementMetric<u8> for HammingMetric {
+    fn element_similarity(v1: &[u8], v2: &[u8]) -> ScoreType {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") && v1.len() >= MIN_DIM_SIZE_AVX {
+                return unsafe { hamming_similarity_bytes_avx2(v1, v2) };
+            }
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+                return unsafe { hamming_similarity_bytes_neon(v1, v2) };
+            }
+        }
+
+        hamming_similarity_bytes(v1, v2)
+    }
+}
+
+impl ElementMetric<u8> for DotProductMetric {
+    fn element_similarity(v1: &[u8], v2: &[u8]) -> ScoreType {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") && v1.len() >= MIN_DIM_SIZE_AVX {
+                return unsafe { dot_similarity_bytes_avx2(v1, v2) };
+            }
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+                return unsafe { dot_similarity_bytes_neon(v1, v2) };
+            }
+        }
+
+        dot_similarity_bytes(v1, v2)
+    }
+}
+
+impl ElementMetric<u8> for CosineMetric {
+    fn element_similarity(v1: &[u8], v2: &[u8]) -> ScoreType {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") && v1.len() >= MIN_DIM_SIZE_AVX {
+                return unsafe { cosine_similarity_bytes_avx2(v1, v2) };
+            }
+        }
+
+        #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+        {
+            if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+                return unsafe { cosine_similarity_bytes_neon(v1, v2) };
+            }
+        }
+
+        cosine_similarity_bytes(v1, v2)
+    }
+
+    /// Byte vectors are stored without normalization, so the query is not normalized either
+    fn element_preprocess(vector: Vec<VectorElementType>) -> Vec<VectorElementType> {
+        vector
+    }
+}
+
+/// Byte vectors are never normalized, cosine similarity is computed in full on them anyway
+impl ElementMetric<u8> for UnnormalizedCosineMetric {
+    fn element_similarity(v1: &[u8], v2: &[u8]) -> ScoreType {
+        <CosineMetric as ElementMetric<u8>>::element_similarity(v1, v2)
+    }
+
+    fn element_preprocess(vector: Vec<VectorElementType>) -> Vec<VectorElementType> {
+        <CosineMetric as ElementMetric<u8>>::element_preprocess(vector)
+    }
+}
+
+pub fn euclid_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let s: i64 = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| (i64::from(*a) - i64::from(*b)).pow(2))
+        .sum();
+    -(s as ScoreType)
+}
+
+pub fn manhattan_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let s: i64 = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| (i64::from(*a) - i64::from(*b)).abs())
+        .sum();
+    -(s as ScoreType)
+}
+
+/// Number of differing bytes, negated
+pub fn hamming_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    -(v1.iter().zip(v2).filter(|(a, b)| a != b).count() as ScoreType)
+}
+
+/// Number of differing bits of two bit vectors packed into bytes, negated
+pub fn hamming_similarity_bits(v1: &[u8], v2: &[u8]) -> ScoreType {
+    -(count_differing_bits(v1, v2) as ScoreType)
+}
+
+/// [`hamming_similarity_bits`] with the fastest popcount available on this CPU
+pub fn hamming_similarity_bits_dispatch(v1: &[u8], v2: &[u8]) -> ScoreType {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("popcnt") {
+            return unsafe { hamming_similarity_bits_popcnt(v1, v2) };
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_DIM_SIZE_SIMD {
+            return unsafe { hamming_similarity_bits_neon(v1, v2) };
+        }
+    }
+
+    hamming_similarity_bits(v1, v2)
+}
+
+/// Popcount of XOR over 8-byte words, inlined into callers to use their target features
+#[inline(always)]
+pub(crate) fn count_differing_bits(v1: &[u8], v2: &[u8]) -> u64 {
+    let words1 = v1.chunks_exact(8);
+    let words2 = v2.chunks_exact(8);
+    let tail: u64 = words1
+        .remainder()
+        .iter()
+        .zip(words2.remainder())
+        .map(|(a, b)| u64::from((a ^ b).count_ones()))
+        .sum();
+    let words: u64 = words1
+        .zip(words2)
+        .map(|(a, b)| {
+            let a = u64::from_le_bytes(a.try_into().unwrap());
+            let b = u64::from_le_bytes(b.try_into().unwrap());
+            u64::from((a ^ b).count_ones())
+        })
+        .sum();
+    words + tail
+}
+
+pub fn dot_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let s: i64 = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| i64::from(*a) * i64::from(*b))
+        .sum();
+    s as ScoreType
+}
+
+pub fn cosine_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let (dot, norm1, norm2) =
+        v1.iter()
+            .zip(v2)
+            .fold((0i64, 0i64, 0i64), |(dot, norm1, norm2), (a, b)| {
+                let (a, b) = (i64::from(*a), i64::from(*b));
+                (dot + a * b, norm1 + a * a, norm2 + b * b)
+            });
+    cosine_from_sums(dot, norm1, norm2)
+}
+
+/// Cosine similarity from the dot product and squared norms of two vectors
+///
+/// Zero vectors have no direction, they are not similar to anything.
+pub(crate) fn cosine_from_sums(dot: i64, norm1: i64, norm2: i64) -> ScoreType {
+    if norm1 == 0 || norm2 == 0 {
+        return 0.0;
+    }
+    (dot as f64 / ((norm1 as f64).sqrt() * (norm2 as f64).sqrt())) as ScoreType
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::spaces::metric::Metric;
+    use crate::spaces::simple::{dot_similarity, euclid_similarity, manhattan_similarity};
+
+    #[test]
+    fn test_bytes_similarity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for dim in [1, 15, 16, 31, 32, 33, 100, 128, 1000] {
+            let v1: Vec<u8> = (0..dim).map(|_| rng.gen()).collect();
+            let v2: Vec<u8> = (0..dim).map(|_| rng.gen()).collect();
+            let f1: Vec<f32> = v1.iter().map(|x| f32::from(*x)).collect();
+            let f2: Vec<f32> = v2.iter().map(|x| f32::from(*x)).collect();
+
+            // Integer sums are exact, f32 reference accumulates rounding errors
+            let dot = dot_similarity(&f1, &f2);
+            let euclid = euclid_similarity(&f1, &f2);
+            let manhattan = manhattan_similarity(&f1, &f2);
+            let cosine = dot_similarity(
+                &CosineMetric::preprocess(&f1).unwrap(),
+                &CosineMetric::preprocess(&f2).unwrap(),
+            );
+
+            let close = |a: ScoreType, b: ScoreType| (a - b).abs() <= b.abs() * 1e-5 + 1e-5;
+            assert!(close(dot_similarity_bytes(&v1, &v2), dot));
+            assert!(close(euclid_similarity_bytes(&v1, &v2), euclid));
+            assert!(close(manhattan_similarity_bytes(&v1, &v2), manhattan));
+            assert!(close(cosine_similarity_bytes(&v1, &v2), cosine));
+            assert!(close(DotProductMetric::element_similarity(&v1, &v2), dot));
+            assert!(close(EuclidMetric::element_similarity(&v1, &v2), euclid));
+            assert!(close(CosineMetric::element_similarity(&v1, &v2), cosine));
+        }
+    }
+
+    #[test]
+    fn test_bytes_similarity_extremes() {
+        let zeros = vec![0u8; 100];
+        let maxes = vec![u8::MAX; 100];
+
+        assert_eq!(dot_similarity_bytes(&maxes, &maxes), 100.0 * 255.0 * 255.0);
+        assert_eq!(
+            euclid_similarity_bytes(&zeros, &maxes),
+            -100.0 * 255.0 * 255.0
+        );
+        assert_eq!(cosine_similarity_bytes(&zeros, &maxes), 0.0);
+        assert!((cosine_similarity_bytes(&maxes, &maxes) - 1.0).abs() < 1e-6);
+
+        assert_eq!(
+            DotProductMetric::element_similarity(&maxes, &maxes),
+            100.0 * 255.0 * 255.0
+        );
+        assert_eq!(
+            EuclidMetric::element_similarity(&zeros, &maxes),
+            -100.0 * 255.0 * 255.0
+        );
+        assert_eq!(CosineMetric::element_similarity(&zeros, &maxes), 0.0);
+        assert_eq!(HammingMetric::element_similarity(&zeros, &maxes), -100.0);
+        assert_eq!(hamming_similarity_bits(&zeros, &maxes), -800.0);
+    }
+
+    #[test]
+    fn test_hamming_similarity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for dim in [1, 7, 8, 9, 15, 16, 31, 32, 33, 100, 128, 1000] {
+            // Few distinct values, so that many components are equal
+            let v1: Vec<u8> = (0..dim).map(|_| rng.gen_range(0..4)).collect();
+            let v2: Vec<u8> = (0..dim).map(|_| rng.gen_range(0..4)).collect();
+
+            let differing_bytes = v1.iter().zip(&v2).filter(|(a, b)| a != b).count();
+            let differing_bits: u32 = v1.iter().zip(&v2).map(|(a, b)| (a ^ b).count_ones()).sum();
+
+            assert_eq!(
+                HammingMetric::element_similarity(&v1, &v2),
+                -(differing_bytes as ScoreType)
+            );
+            assert_eq!(
+                hamming_similarity_bits(&v1, &v2),
+                -(differing_bits as ScoreType)
+            );
+            assert_eq!(
+                hamming_similarity_bits_dispatch(&v1, &v2),
+                -(differing_bits as ScoreType)
+            );
+            assert_eq!(HammingMetric::element_similarity(&v1, &v1), 0.0);
+        }
+    }
+}
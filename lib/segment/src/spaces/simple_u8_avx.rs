@@ -0,0 +1,226 @@
+use std::arch::x86_64::*;
+
+use super::simple_u8::{cosine_from_sums, count_differing_bits};
+use crate::types::ScoreType;
+
+// Products of two bytes are summed pairwise into i32 lanes, each lane takes at most
+// 4 products of 65025 per 32 components, so lanes can't overflow below 2^18 components.
+
+/// Load 32 bytes and widen them into two vectors of 16 i16 lanes
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn load_bytes_epi16_avx2(ptr: *const u8) -> (__m256i, __m256i) {
+    let bytes = _mm256_loadu_si256(ptr as *const __m256i);
+    (
+        _mm256_cvtepu8_epi16(_mm256_castsi256_si128(bytes)),
+        _mm256_cvtepu8_epi16(_mm256_extracti128_si256::<1>(bytes)),
+    )
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn hsum256_epi32_avx2(x: __m256i) -> i64 {
+    let mut lanes = [0i32; 8];
+    _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, x);
+    lanes.iter().map(|lane| i64::from(*lane)).sum()
+}
+
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn euclid_similarity_bytes_avx2(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 32);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut sum256_1: __m256i = _mm256_setzero_si256();
+    let mut sum256_2: __m256i = _mm256_setzero_si256();
+    let mut i: usize = 0;
+    while i < m {
+        let (a_low, a_high) = load_bytes_epi16_avx2(ptr1);
+        let (b_low, b_high) = load_bytes_epi16_avx2(ptr2);
+
+        let sub256_1 = _mm256_sub_epi16(a_low, b_low);
+        sum256_1 = _mm256_add_epi32(sum256_1, _mm256_madd_epi16(sub256_1, sub256_1));
+
+        let sub256_2 = _mm256_sub_epi16(a_high, b_high);
+        sum256_2 = _mm256_add_epi32(sum256_2, _mm256_madd_epi16(sub256_2, sub256_2));
+
+        ptr1 = ptr1.add(32);
+        ptr2 = ptr2.add(32);
+        i += 32;
+    }
+
+    let mut result = hsum256_epi32_avx2(sum256_1) + hsum256_epi32_avx2(sum256_2);
+    for i in 0..n - m {
+        result += (i64::from(*ptr1.add(i)) - i64::from(*ptr2.add(i))).pow(2);
+    }
+    -(result as ScoreType)
+}
+
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn dot_similarity_bytes_avx2(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 32);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut sum256_1: __m256i = _mm256_setzero_si256();
+    let mut sum256_2: __m256i = _mm256_setzero_si256();
+    let mut i: usize = 0;
+    while i < m {
+        let (a_low, a_high) = load_bytes_epi16_avx2(ptr1);
+        let (b_low, b_high) = load_bytes_epi16_avx2(ptr2);
+
+        sum256_1 = _mm256_add_epi32(sum256_1, _mm256_madd_epi16(a_low, b_low));
+        sum256_2 = _mm256_add_epi32(sum256_2, _mm256_madd_epi16(a_high, b_high));
+
+        ptr1 = ptr1.add(32);
+        ptr2 = ptr2.add(32);
+        i += 32;
+    }
+
+    let mut result = hsum256_epi32_avx2(sum256_1) + hsum256_epi32_avx2(sum256_2);
+    for i in 0..n - m {
+        result += i64::from(*ptr1.add(i)) * i64::from(*ptr2.add(i));
+    }
+    result as ScoreType
+}
+
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn cosine_similarity_bytes_avx2(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 32);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut dot256: __m256i = _mm256_setzero_si256();
+    let mut norm256_1: __m256i = _mm256_setzero_si256();
+    let mut norm256_2: __m256i = _mm256_setzero_si256();
+    let mut i: usize = 0;
+    while i < m {
+        let (a_low, a_high) = load_bytes_epi16_avx2(ptr1);
+        let (b_low, b_high) = load_bytes_epi16_avx2(ptr2);
+
+        dot256 = _mm256_add_epi32(
+            dot256,
+            _mm256_add_epi32(
+                _mm256_madd_epi16(a_low, b_low),
+                _mm256_madd_epi16(a_high, b_high),
+            ),
+        );
+        norm256_1 = _mm256_add_epi32(
+            norm256_1,
+            _mm256_add_epi32(
+                _mm256_madd_epi16(a_low, a_low),
+                _mm256_madd_epi16(a_high, a_high),
+            ),
+        );
+        norm256_2 = _mm256_add_epi32(
+            norm256_2,
+            _mm256_add_epi32(
+                _mm256_madd_epi16(b_low, b_low),
+                _mm256_madd_epi16(b_high, b_high),
+            ),
+        );
+
+        ptr1 = ptr1.add(32);
+        ptr2 = ptr2.add(32);
+        i += 32;
+    }
+
+    let mut dot = hsum256_epi32_avx2(dot256);
+    let mut norm1 = hsum256_epi32_avx2(norm256_1);
+    let mut norm2 = hsum256_epi32_avx2(norm256_2);
+    for i in 0..n - m {
+        let a = i64::from(*ptr1.add(i));
+        let b = i64::from(*ptr2.add(i));
+        dot += a * b;
+        norm1 += a * a;
+        norm2 += b * b;
+    }
+    cosine_from_sums(dot, norm1, norm2)
+}
+
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn hamming_similarity_bytes_avx2(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 32);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut equal: u64 = 0;
+    let mut i: usize = 0;
+    while i < m {
+        let a = _mm256_loadu_si256(ptr1 as *const __m256i);
+        let b = _mm256_loadu_si256(ptr2 as *const __m256i);
+        // One bit per byte, set for equal bytes
+        let mask = _mm256_movemask_epi8(_mm256_cmpeq_epi8(a, b)) as u32;
+        equal += u64::from(mask.count_ones());
+
+        ptr1 = ptr1.add(32);
+        ptr2 = ptr2.add(32);
+        i += 32;
+    }
+
+    let mut result = m as u64 - equal;
+    for i in 0..n - m {
+        if *ptr1.add(i) != *ptr2.add(i) {
+            result += 1;
+        }
+    }
+    -(result as ScoreType)
+}
+
+#[target_feature(enable = "popcnt")]
+pub(crate) unsafe fn hamming_similarity_bits_popcnt(v1: &[u8], v2: &[u8]) -> ScoreType {
+    -(count_differing_bits(v1, v2) as ScoreType)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_spaces_bytes_avx2() {
+        use super::*;
+        use crate::spaces::simple_u8::*;
+
+        if is_x86_feature_detected!("avx2") {
+            let v1: Vec<u8> = (0..70).map(|x| (x * 37 % 256) as u8).collect();
+            let mut v2: Vec<u8> = (0..70).map(|x| (255 - x * 11 % 256) as u8).collect();
+            v2[3] = 0;
+            v2[40] = u8::MAX;
+
+            let euclid_simd = unsafe { euclid_similarity_bytes_avx2(&v1, &v2) };
+            assert_eq!(euclid_simd, euclid_similarity_bytes(&v1, &v2));
+
+            let dot_simd = unsafe { dot_similarity_bytes_avx2(&v1, &v2) };
+            assert_eq!(dot_simd, dot_similarity_bytes(&v1, &v2));
+
+            let cosine_simd = unsafe { cosine_similarity_bytes_avx2(&v1, &v2) };
+            assert_eq!(cosine_simd, cosine_similarity_bytes(&v1, &v2));
+
+            let hamming_simd = unsafe { hamming_similarity_bytes_avx2(&v1, &v2) };
+            assert_eq!(hamming_simd, hamming_similarity_bytes(&v1, &v2));
+
+            let maxes = vec![u8::MAX; 64];
+            let zeros = vec![0u8; 64];
+            let hamming_simd = unsafe { hamming_similarity_bytes_avx2(&maxes, &maxes) };
+            assert_eq!(hamming_simd, 0.0);
+            let euclid_simd = unsafe { euclid_similarity_bytes_avx2(&maxes, &zeros) };
+            assert_eq!(euclid_simd, -64.0 * 255.0 * 255.0);
+        } else {
+            println!("avx2 test skipped");
+        }
+    }
+
+    #[test]
+    fn test_hamming_bits_popcnt() {
+        use super::*;
+        use crate::spaces::simple_u8::*;
+
+        if is_x86_feature_detected!("popcnt") {
+            let v1: Vec<u8> = (0..70).map(|x| (x * 37 % 256) as u8).collect();
+            let v2: Vec<u8> = (0..70).map(|x| (255 - x * 11 % 256) as u8).collect();
+
+            let hamming_simd = unsafe { hamming_similarity_bits_popcnt(&v1, &v2) };
+            assert_eq!(hamming_simd, hamming_similarity_bits(&v1, &v2));
+        } else {
+            println!("popcnt test skipped");
+        }
+    }
+}
@@ -0,0 +1,191 @@
+#[cfg(target_feature = "neon")]
+use std::arch::aarch64::*;
+
+#[cfg(target_feature = "neon")]
+use super::simple_u8::cosine_from_sums;
+#[cfg(target_feature = "neon")]
+use crate::types::ScoreType;
+
+// Products of two bytes are widened into u16 and summed pairwise into u32 lanes, each lane
+// takes at most 4 products of 65025 per 16 components, so lanes can't overflow below 2^18 components.
+
+/// Multiply 16 pairs of bytes and accumulate products into 4 u32 lanes
+#[cfg(target_feature = "neon")]
+#[inline]
+unsafe fn mul_acc_bytes_neon(acc: uint32x4_t, a: uint8x16_t, b: uint8x16_t) -> uint32x4_t {
+    let low = vmull_u8(vget_low_u8(a), vget_low_u8(b));
+    let high = vmull_high_u8(a, b);
+    vpadalq_u16(vpadalq_u16(acc, low), high)
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn euclid_similarity_bytes_neon(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut sum = vdupq_n_u32(0);
+
+    let mut i: usize = 0;
+    while i < m {
+        let diff = vabdq_u8(vld1q_u8(ptr1), vld1q_u8(ptr2));
+        sum = mul_acc_bytes_neon(sum, diff, diff);
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut result = vaddlvq_u32(sum) as i64;
+    for i in 0..n - m {
+        result += (i64::from(*ptr1.add(i)) - i64::from(*ptr2.add(i))).pow(2);
+    }
+    -(result as ScoreType)
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn dot_similarity_bytes_neon(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut sum = vdupq_n_u32(0);
+
+    let mut i: usize = 0;
+    while i < m {
+        sum = mul_acc_bytes_neon(sum, vld1q_u8(ptr1), vld1q_u8(ptr2));
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut result = vaddlvq_u32(sum) as i64;
+    for i in 0..n - m {
+        result += i64::from(*ptr1.add(i)) * i64::from(*ptr2.add(i));
+    }
+    result as ScoreType
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn cosine_similarity_bytes_neon(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut dot_sum = vdupq_n_u32(0);
+    let mut norm_sum1 = vdupq_n_u32(0);
+    let mut norm_sum2 = vdupq_n_u32(0);
+
+    let mut i: usize = 0;
+    while i < m {
+        let a = vld1q_u8(ptr1);
+        let b = vld1q_u8(ptr2);
+        dot_sum = mul_acc_bytes_neon(dot_sum, a, b);
+        norm_sum1 = mul_acc_bytes_neon(norm_sum1, a, a);
+        norm_sum2 = mul_acc_bytes_neon(norm_sum2, b, b);
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut dot = vaddlvq_u32(dot_sum) as i64;
+    let mut norm1 = vaddlvq_u32(norm_sum1) as i64;
+    let mut norm2 = vaddlvq_u32(norm_sum2) as i64;
+    for i in 0..n - m {
+        let a = i64::from(*ptr1.add(i));
+        let b = i64::from(*ptr2.add(i));
+        dot += a * b;
+        norm1 += a * a;
+        norm2 += b * b;
+    }
+    cosine_from_sums(dot, norm1, norm2)
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn hamming_similarity_bytes_neon(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut sum = vdupq_n_u32(0);
+
+    let mut i: usize = 0;
+    while i < m {
+        let not_equal = vmvnq_u8(vceqq_u8(vld1q_u8(ptr1), vld1q_u8(ptr2)));
+        // Lanes of differing bytes become 1, others 0
+        sum = vpadalq_u16(sum, vpaddlq_u8(vshrq_n_u8::<7>(not_equal)));
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut result = vaddlvq_u32(sum);
+    for i in 0..n - m {
+        if *ptr1.add(i) != *ptr2.add(i) {
+            result += 1;
+        }
+    }
+    -(result as ScoreType)
+}
+
+#[cfg(target_feature = "neon")]
+pub(crate) unsafe fn hamming_similarity_bits_neon(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let mut ptr1: *const u8 = v1.as_ptr();
+    let mut ptr2: *const u8 = v2.as_ptr();
+    let mut sum = vdupq_n_u32(0);
+
+    let mut i: usize = 0;
+    while i < m {
+        let bits = vcntq_u8(veorq_u8(vld1q_u8(ptr1), vld1q_u8(ptr2)));
+        sum = vpadalq_u16(sum, vpaddlq_u8(bits));
+
+        ptr1 = ptr1.add(16);
+        ptr2 = ptr2.add(16);
+        i += 16;
+    }
+    let mut result = vaddlvq_u32(sum);
+    for i in 0..n - m {
+        result += u64::from((*ptr1.add(i) ^ *ptr2.add(i)).count_ones());
+    }
+    -(result as ScoreType)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_feature = "neon")]
+    #[test]
+    fn test_spaces_bytes_neon() {
+        use super::*;
+        use crate::spaces::simple_u8::*;
+
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            let v1: Vec<u8> = (0..70).map(|x| (x * 37 % 256) as u8).collect();
+            let mut v2: Vec<u8> = (0..70).map(|x| (255 - x * 11 % 256) as u8).collect();
+            v2[3] = 0;
+            v2[40] = u8::MAX;
+
+            let euclid_simd = unsafe { euclid_similarity_bytes_neon(&v1, &v2) };
+            assert_eq!(euclid_simd, euclid_similarity_bytes(&v1, &v2));
+
+            let dot_simd = unsafe { dot_similarity_bytes_neon(&v1, &v2) };
+            assert_eq!(dot_simd, dot_similarity_bytes(&v1, &v2));
+
+            let cosine_simd = unsafe { cosine_similarity_bytes_neon(&v1, &v2) };
+            assert_eq!(cosine_simd, cosine_similarity_bytes(&v1, &v2));
+
+            let hamming_simd = unsafe { hamming_similarity_bytes_neon(&v1, &v2) };
+            assert_eq!(hamming_simd, hamming_similarity_bytes(&v1, &v2));
+
+            let hamming_bits_simd = unsafe { hamming_similarity_bits_neon(&v1, &v2) };
+            assert_eq!(hamming_bits_simd, hamming_similarity_bits(&v1, &v2));
+
+            let maxes = vec![u8::MAX; 64];
+            let zeros = vec![0u8; 64];
+            let euclid_simd = unsafe { euclid_similarity_bytes_neon(&maxes, &zeros) };
+            assert_eq!(euclid_simd, -64.0 * 255.0 * 255.0);
+        } else {
+            println!("neon test skipped");
+        }
+    }
+}
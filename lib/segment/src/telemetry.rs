@@ -3,7 +3,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::common::anonymize::Anonymize;
 use crate::common::operation_time_statistics::OperationDurationStatistics;
-use crate::types::{PayloadIndexInfo, SegmentConfig, SegmentInfo, VectorDataConfig};
+use crate::common::search_stats::SearchStats;
+use crate::types::{
+    PayloadIndexInfo, SegmentConfig, SegmentInfo, SparseVectorDataConfig, VectorDataConfig,
+    VectorStorageType,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct VectorIndexesTelemetry {
@@ -16,6 +20,35 @@ pub struct SegmentTelemetry {
     pub config: SegmentConfig,
     pub vector_index_searches: Vec<VectorIndexSearchesTelemetry>,
     pub payload_field_indices: Vec<PayloadIndexTelemetry>,
+    /// Storage and index of each dense vector
+    #[serde(default)]
+    pub vector_data: Vec<VectorDataTelemetry>,
+    /// Fraction of points, linked into the index of the segment being optimized from this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub optimization_progress: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct VectorDataTelemetry {
+    pub vector_name: String,
+    pub storage_type: VectorStorageType,
+    /// Number of stored vectors, including deleted ones
+    pub vectors_count: usize,
+    /// Size of stored vectors, without quantized vectors and storage overhead
+    pub vectors_size_bytes: usize,
+    /// Whether HNSW graph is built for the vectors
+    pub hnsw_built: bool,
+    /// Parameters of the HNSW graph, if the segment is indexed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub hnsw: Option<HnswTelemetry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+pub struct HnswTelemetry {
+    pub m: usize,
+    pub ef_construct: usize,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -61,6 +94,21 @@ pub struct VectorIndexSearchesTelemetry {
 
     #[serde(skip_serializing_if = "OperationDurationStatistics::is_empty")]
     pub unfiltered_exact: OperationDurationStatistics,
+
+    /// Number of graph searches with requested `hnsw_ef` out of the sane range
+    #[serde(skip_serializing_if = "num_traits::identities::Zero::is_zero")]
+    #[serde(default)]
+    pub clamped_hnsw_ef: usize,
+
+    /// Beam width the last search with out of range `hnsw_ef` was actually done with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub last_clamped_hnsw_ef: Option<usize>,
+
+    /// Total work done by all searches of the index
+    #[serde(skip_serializing_if = "SearchStats::is_empty")]
+    #[serde(default)]
+    pub search_stats: SearchStats,
 }
 
 impl Anonymize for SegmentTelemetry {
@@ -70,6 +118,21 @@ impl Anonymize for SegmentTelemetry {
             config: self.config.anonymize(),
             vector_index_searches: self.vector_index_searches.anonymize(),
             payload_field_indices: self.payload_field_indices.anonymize(),
+            vector_data: self.vector_data.anonymize(),
+            optimization_progress: self.optimization_progress,
+        }
+    }
+}
+
+impl Anonymize for VectorDataTelemetry {
+    fn anonymize(&self) -> Self {
+        VectorDataTelemetry {
+            vector_name: self.vector_name.anonymize(),
+            storage_type: self.storage_type,
+            vectors_count: self.vectors_count.anonymize(),
+            vectors_size_bytes: self.vectors_size_bytes.anonymize(),
+            hnsw_built: self.hnsw_built,
+            hnsw: self.hnsw.clone(),
         }
     }
 }
@@ -107,6 +170,7 @@ impl Anonymize for SegmentConfig {
     fn anonymize(&self) -> Self {
         SegmentConfig {
             vector_data: self.vector_data.anonymize(),
+            sparse_vector_data: self.sparse_vector_data.anonymize(),
             payload_storage_type: self.payload_storage_type,
         }
     }
@@ -120,10 +184,19 @@ impl Anonymize for VectorDataConfig {
             storage_type: self.storage_type,
             index: self.index.clone(),
             quantization_config: None,
+            datatype: None,
+            multivector_config: self.multivector_config,
+            store_original_vectors: self.store_original_vectors,
         }
     }
 }
 
+impl Anonymize for SparseVectorDataConfig {
+    fn anonymize(&self) -> Self {
+        SparseVectorDataConfig { index: self.index }
+    }
+}
+
 impl Anonymize for VectorIndexSearchesTelemetry {
     fn anonymize(&self) -> Self {
         VectorIndexSearchesTelemetry {
@@ -135,6 +208,9 @@ impl Anonymize for VectorIndexSearchesTelemetry {
             filtered_large_cardinality: self.filtered_large_cardinality.anonymize(),
             filtered_exact: self.filtered_exact.anonymize(),
             unfiltered_exact: self.filtered_exact.anonymize(),
+            clamped_hnsw_ef: self.clamped_hnsw_ef.anonymize(),
+            last_clamped_hnsw_ef: self.last_clamped_hnsw_ef.anonymize(),
+            search_stats: self.search_stats.anonymize(),
         }
     }
 }
@@ -7,8 +7,9 @@ use std::ops::Deref;
 use std::rc::Rc;
 use std::str::FromStr;
 
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
 use geo::prelude::HaversineDistance;
-use geo::{Contains, Coord, Point, Polygon};
+use geo::Point;
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
 use schemars::JsonSchema;
@@ -20,10 +21,14 @@ use validator::{Validate, ValidationErrors};
 
 use crate::common::utils;
 use crate::common::utils::MultiValue;
+use crate::data_types::keyword_index::KeywordIndexParams;
 use crate::data_types::text_index::TextIndexParams;
+use crate::data_types::uuid_index::UuidIndexParams;
 use crate::data_types::vectors::{VectorElementType, VectorStruct};
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{
+    CosineMetric, DotProductMetric, EuclidMetric, HammingMetric, ManhattanMetric,
+};
 
 /// Type of point index inside a segment
 pub type PointOffsetType = u32;
@@ -38,10 +43,17 @@ pub type TagType = u64;
 pub type FloatPayloadType = f64;
 /// Type of integer point payload
 pub type IntPayloadType = i64;
+/// Type of UUID point payload, as it is stored in the index
+pub type UuidIntType = u128;
 
 pub const VECTOR_ELEMENT_SIZE: usize = size_of::<VectorElementType>();
 
 /// Type, used for specifying point ID in user interface
+///
+/// Integer IDs may also be given as strings of decimal digits without leading zeros. JSON numbers
+/// above 2^53 lose precision in many clients, so strings keep such IDs exact.
+///
+/// IDs are ordered with all integer IDs before all UUIDs.
 #[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, JsonSchema)]
 #[serde(untagged)]
 pub enum ExtendedPointId {
@@ -94,6 +106,20 @@ impl<'de> serde::Deserialize<'de> for ExtendedPointId {
             return Ok(ExtendedPointId::NumId(num));
         }
 
+        // Integer ID as a string, only decimal digits without leading zeros are accepted.
+        // 32 digits are a UUID in the simple format, even if they fit into an integer.
+        if let serde_value::Value::String(string) = &value {
+            let is_integer = !string.is_empty()
+                && string.len() < 32
+                && (string == "0" || !string.starts_with('0'))
+                && string.bytes().all(|byte| byte.is_ascii_digit());
+            if is_integer {
+                if let Ok(num) = string.parse() {
+                    return Ok(ExtendedPointId::NumId(num));
+                }
+            }
+        }
+
         if let Ok(uuid) = value.clone().deserialize_into() {
             return Ok(ExtendedPointId::Uuid(uuid));
         }
@@ -121,6 +147,10 @@ pub enum Distance {
     Euclid,
     // <https://en.wikipedia.org/wiki/Dot_product>
     Dot,
+    // <https://simple.wikipedia.org/wiki/Manhattan_distance>
+    Manhattan,
+    // <https://en.wikipedia.org/wiki/Hamming_distance>
+    Hamming,
 }
 
 impl Distance {
@@ -132,6 +162,8 @@ impl Distance {
             Distance::Cosine => CosineMetric::preprocess(vector),
             Distance::Euclid => EuclidMetric::preprocess(vector),
             Distance::Dot => DotProductMetric::preprocess(vector),
+            Distance::Manhattan => ManhattanMetric::preprocess(vector),
+            Distance::Hamming => HammingMetric::preprocess(vector),
         }
     }
 
@@ -140,13 +172,29 @@ impl Distance {
             Distance::Cosine => CosineMetric::postprocess(score),
             Distance::Euclid => EuclidMetric::postprocess(score),
             Distance::Dot => DotProductMetric::postprocess(score),
+            Distance::Manhattan => ManhattanMetric::postprocess(score),
+            Distance::Hamming => HammingMetric::postprocess(score),
         }
     }
 
     pub fn distance_order(&self) -> Order {
         match self {
             Distance::Cosine | Distance::Dot => Order::LargeBetter,
-            Distance::Euclid => Order::SmallBetter,
+            Distance::Euclid | Distance::Manhattan | Distance::Hamming => Order::SmallBetter,
+        }
+    }
+
+    /// Converts user facing score threshold into the internal score scale, in which larger is
+    /// always better. Scores passing the converted threshold are not smaller than it.
+    pub fn preprocess_score_threshold(&self, threshold: ScoreType) -> ScoreType {
+        match self {
+            Distance::Cosine | Distance::Dot => threshold,
+            // No distance is below a non positive threshold
+            Distance::Euclid | Distance::Manhattan | Distance::Hamming if threshold <= 0.0 => {
+                ScoreType::INFINITY
+            }
+            Distance::Euclid => -threshold * threshold,
+            Distance::Manhattan | Distance::Hamming => -threshold,
         }
     }
 
@@ -232,11 +280,21 @@ impl PayloadIndexInfo {
                 points: points_count,
             },
             PayloadFieldSchema::FieldParams(schema_params) => match schema_params {
+                PayloadSchemaParams::Keyword(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Keyword,
+                    params: Some(schema_params),
+                    points: points_count,
+                },
                 PayloadSchemaParams::Text(_) => PayloadIndexInfo {
                     data_type: PayloadSchemaType::Text,
                     params: Some(schema_params),
                     points: points_count,
                 },
+                PayloadSchemaParams::Uuid(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Uuid,
+                    params: Some(schema_params),
+                    points: points_count,
+                },
             },
         }
     }
@@ -256,6 +314,38 @@ pub struct SegmentInfo {
     pub index_schema: HashMap<PayloadKeyType, PayloadIndexInfo>,
 }
 
+/// Memory and disk usage of the segment components, in bytes
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SegmentResourceUsage {
+    /// Estimated size of the vectors, held in RAM by in-memory storages
+    pub vectors_ram_bytes: usize,
+    /// Size of the files of mmap vector storages
+    pub vectors_mmap_bytes: usize,
+    /// Size of the quantized vectors
+    pub quantized_vectors_bytes: usize,
+    /// Size of the stored payload
+    pub payload_storage_bytes: usize,
+    /// Size of the payload indexes by indexed field
+    pub payload_index_bytes: BTreeMap<PayloadKeyType, usize>,
+    /// Size of the HNSW graphs, mostly their links
+    pub hnsw_links_bytes: usize,
+}
+
+impl SegmentResourceUsage {
+    /// Add usage of another segment to this one
+    pub fn merge(&mut self, other: &SegmentResourceUsage) {
+        self.vectors_ram_bytes += other.vectors_ram_bytes;
+        self.vectors_mmap_bytes += other.vectors_mmap_bytes;
+        self.quantized_vectors_bytes += other.quantized_vectors_bytes;
+        self.payload_storage_bytes += other.payload_storage_bytes;
+        for (field, bytes) in &other.payload_index_bytes {
+            *self.payload_index_bytes.entry(field.clone()).or_default() += bytes;
+        }
+        self.hnsw_links_bytes += other.hnsw_links_bytes;
+    }
+}
+
 /// Additional parameters of the search
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -294,12 +384,21 @@ pub const fn default_quantization_oversampling_value() -> Option<f64> {
     None
 }
 
+/// How to sample points, instead of scoring them by vector similarity
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Sample {
+    /// Uniformly random points
+    Random,
+}
+
 /// Additional parameters of the search
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct SearchParams {
     /// Params relevant to HNSW index
     /// Size of the beam in a beam-search. Larger the value - more accurate the result, more time required for search.
+    /// Values below the search limit are raised to it, values above the number of points are clamped.
     pub hnsw_ef: Option<usize>,
 
     /// Search without approximation. If set to true, search may run long but with exact results.
@@ -462,6 +561,44 @@ pub struct ProductQuantization {
     pub product: ProductQuantizationConfig,
 }
 
+/// Binary quantization is refused for vectors with fewer dimensions, unless configured otherwise
+pub const DEFAULT_BINARY_QUANTIZATION_MIN_DIM: usize = 512;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct BinaryQuantizationConfig {
+    /// Minimal vector dimension, for which binary quantization is allowed.
+    /// With fewer dimensions, sign bits do not preserve enough information for a reasonable search accuracy.
+    /// Default: 512
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub min_dim: Option<usize>,
+    /// If true - quantized vectors always will be stored in RAM, ignoring the config of main storage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub always_ram: Option<bool>,
+}
+
+impl BinaryQuantizationConfig {
+    /// Detect configuration mismatch against `other` that requires rebuilding
+    ///
+    /// Returns true only if both conditions are met:
+    /// - this configuration does not match `other`
+    /// - to effectively change the configuration, a quantization rebuild is required
+    pub fn mismatch_requires_rebuild(&self, other: &Self) -> bool {
+        self != other
+    }
+
+    pub fn min_dim(&self) -> usize {
+        self.min_dim.unwrap_or(DEFAULT_BINARY_QUANTIZATION_MIN_DIM)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash)]
+pub struct BinaryQuantization {
+    #[validate]
+    pub binary: BinaryQuantizationConfig,
+}
+
 impl std::hash::Hash for ScalarQuantizationConfig {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.always_ram.hash(state);
@@ -477,6 +614,7 @@ impl Eq for ScalarQuantizationConfig {}
 pub enum QuantizationConfig {
     Scalar(ScalarQuantization),
     Product(ProductQuantization),
+    Binary(BinaryQuantization),
 }
 
 impl QuantizationConfig {
@@ -488,6 +626,32 @@ impl QuantizationConfig {
     pub fn mismatch_requires_rebuild(&self, other: &Self) -> bool {
         self != other
     }
+
+    /// Check that quantization can be applied to vectors compared with the given distance
+    pub fn check_distance(&self, distance: Distance) -> Result<(), String> {
+        match distance {
+            Distance::Cosine | Distance::Euclid | Distance::Dot | Distance::Manhattan => Ok(()),
+            Distance::Hamming => Err("Quantization is not supported for Hamming distance".into()),
+        }
+    }
+
+    /// Check that quantization can be applied to vectors of the given dimension
+    pub fn check_vector_dim(&self, dim: usize) -> Result<(), String> {
+        match self {
+            QuantizationConfig::Scalar(_) | QuantizationConfig::Product(_) => Ok(()),
+            QuantizationConfig::Binary(BinaryQuantization { binary }) => {
+                let min_dim = binary.min_dim();
+                if dim < min_dim {
+                    Err(format!(
+                        "Binary quantization requires vectors with at least {min_dim} dimensions, got {dim}. \
+                         Use scalar quantization or lower `min_dim` of binary quantization config"
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
 }
 
 impl Validate for QuantizationConfig {
@@ -495,6 +659,7 @@ impl Validate for QuantizationConfig {
         match self {
             QuantizationConfig::Scalar(scalar) => scalar.validate(),
             QuantizationConfig::Product(product) => product.validate(),
+            QuantizationConfig::Binary(binary) => binary.validate(),
         }
     }
 }
@@ -511,6 +676,12 @@ impl From<ProductQuantizationConfig> for QuantizationConfig {
     }
 }
 
+impl From<BinaryQuantizationConfig> for QuantizationConfig {
+    fn from(config: BinaryQuantizationConfig) -> Self {
+        QuantizationConfig::Binary(BinaryQuantization { binary: config })
+    }
+}
+
 pub const DEFAULT_HNSW_EF_CONSTRUCT: usize = 100;
 
 impl Default for HnswConfig {
@@ -572,6 +743,8 @@ impl PayloadStorageType {
 #[serde(rename_all = "snake_case")]
 pub struct SegmentConfig {
     pub vector_data: HashMap<String, VectorDataConfig>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sparse_vector_data: HashMap<String, SparseVectorDataConfig>,
     /// Defines payload storage type
     pub payload_storage_type: PayloadStorageType,
 }
@@ -636,6 +809,63 @@ impl VectorStorageType {
     }
 }
 
+/// Element type used to store vector components
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Hash, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorStorageDatatype {
+    /// Single-precision floating point, 4 bytes per component
+    #[default]
+    Float32,
+    /// Half-precision floating point, 2 bytes per component
+    ///
+    /// Halves the memory used by vectors at the cost of precision, which is about 3 significant digits.
+    Float16,
+    /// Unsigned byte, 1 byte per component
+    ///
+    /// Intended for models producing byte embeddings, components must be integers in range 0..=255.
+    Uint8,
+}
+
+impl VectorStorageDatatype {
+    /// Size of a single stored vector component in bytes
+    pub fn element_size(&self) -> usize {
+        match self {
+            VectorStorageDatatype::Float32 => size_of::<VectorElementType>(),
+            VectorStorageDatatype::Float16 => size_of::<half::f16>(),
+            VectorStorageDatatype::Uint8 => size_of::<u8>(),
+        }
+    }
+
+    /// Check that a vector component can be stored with this datatype
+    pub fn is_valid_element(&self, value: VectorElementType) -> bool {
+        match self {
+            VectorStorageDatatype::Float32 | VectorStorageDatatype::Float16 => true,
+            VectorStorageDatatype::Uint8 => {
+                value.fract() == 0.0 && (0.0..=u8::MAX as VectorElementType).contains(&value)
+            }
+        }
+    }
+}
+
+/// How to aggregate similarities between sub-vectors of two multi-vectors into a single score
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Hash, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiVectorComparator {
+    /// Sum of the maximal similarities of each query sub-vector with any of the stored sub-vectors,
+    /// as in late interaction models like ColBERT
+    #[default]
+    MaxSim,
+}
+
+/// Config of multi-vectors, where each point holds a number of sub-vectors of the same dimension
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Hash, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct MultiVectorConfig {
+    /// Aggregation of sub-vector similarities
+    #[serde(default)]
+    pub comparator: MultiVectorComparator,
+}
+
 /// Config of single vector data storage
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -650,11 +880,114 @@ pub struct VectorDataConfig {
     pub index: Indexes,
     /// Vector specific quantization config that overrides collection config
     pub quantization_config: Option<QuantizationConfig>,
+    /// Element type of the stored vectors
+    /// Default: float32
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datatype: Option<VectorStorageDatatype>,
+    /// If set, each point holds a list of sub-vectors of `size` dimensions,
+    /// passed as a single vector with all sub-vectors concatenated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multivector_config: Option<MultiVectorConfig>,
+    /// If true - vectors are stored exactly as they were inserted, without distance preprocessing.
+    /// Normalization for cosine similarity is then performed during scoring.
+    #[serde(default)]
+    pub store_original_vectors: bool,
+}
+
+impl VectorDataConfig {
+    pub fn datatype(&self) -> VectorStorageDatatype {
+        self.datatype.unwrap_or_default()
+    }
+
+    /// Transform vector before storing it, see [`Distance::preprocess_vector`]
+    ///
+    /// Original vectors are stored as is. Byte vectors can't hold normalized components, cosine similarity is computed in full on them.
+    pub fn preprocess_vector(
+        &self,
+        vector: &[VectorElementType],
+    ) -> Option<Vec<VectorElementType>> {
+        if self.store_original_vectors {
+            return None;
+        }
+        if self.multivector_config.is_some() {
+            // Every sub-vector is transformed on its own
+            let mut preprocessed: Option<Vec<VectorElementType>> = None;
+            for (index, sub_vector) in vector.chunks(self.size).enumerate() {
+                if let Some(sub_preprocessed) = self.distance.preprocess_vector(sub_vector) {
+                    let offset = index * self.size;
+                    preprocessed.get_or_insert_with(|| vector.to_vec())
+                        [offset..offset + sub_preprocessed.len()]
+                        .copy_from_slice(&sub_preprocessed);
+                }
+            }
+            return preprocessed;
+        }
+        match self.datatype() {
+            VectorStorageDatatype::Float32 | VectorStorageDatatype::Float16 => {
+                self.distance.preprocess_vector(vector)
+            }
+            VectorStorageDatatype::Uint8 => None,
+        }
+    }
+}
+
+/// Type of the inverted index of sparse vectors
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum SparseIndexType {
+    /// Posting lists in memory, rebuilt from the vector storage on load
+    ///
+    /// Supports updates, used in appendable segments.
+    #[default]
+    MutableRam,
+    /// Posting lists in mmap file, not appendable
+    ///
+    /// Built once by the optimizer, loaded without reading the vector storage.
+    Mmap,
+}
+
+/// Config of the inverted index of sparse vectors
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Hash, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseIndexConfig {
+    /// If filtered search matches less than this number of points, matching points are scored
+    /// one by one instead of traversing posting lists.
+    /// Default: 5000
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_scan_threshold: Option<usize>,
+    /// Type of the inverted index
+    #[serde(default)]
+    pub index_type: SparseIndexType,
+}
+
+impl SparseIndexConfig {
+    pub fn full_scan_threshold(&self) -> usize {
+        self.full_scan_threshold
+            .unwrap_or(DEFAULT_SPARSE_FULL_SCAN_THRESHOLD)
+    }
+}
+
+/// Config of sparse vector data storage
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Copy, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVectorDataConfig {
+    /// Type of index used for search
+    #[serde(default)]
+    pub index: SparseIndexConfig,
+}
+
+impl SparseVectorDataConfig {
+    /// Whether vectors can be added to this sparse vector data
+    pub fn is_appendable(&self) -> bool {
+        self.index.index_type == SparseIndexType::MutableRam
+    }
 }
 
 /// Default value based on <https://github.com/google-research/google-research/blob/master/scann/docs/algorithms.md>
 pub const DEFAULT_FULL_SCAN_THRESHOLD: usize = 20_000;
 
+/// Default number of filtered points, below which sparse search scores the points directly
+pub const DEFAULT_SPARSE_FULL_SCAN_THRESHOLD: usize = 5_000;
+
 /// Persistable state of segment configuration
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -730,12 +1063,12 @@ pub struct Payload(pub Map<String, Value>);
 
 impl Payload {
     pub fn merge(&mut self, value: &Payload) {
-        for (key, value) in &value.0 {
-            match value {
-                Value::Null => self.0.remove(key),
-                _ => self.0.insert(key.to_owned(), value.to_owned()),
-            };
-        }
+        utils::merge_json_map(&mut self.0, &value.0)
+    }
+
+    /// Merge payload into the object at the given key path, see [`utils::set_value_to_json_map`]
+    pub fn merge_by_key(&mut self, value: &Payload, key: PayloadKeyTypeRef) {
+        utils::set_value_to_json_map(key, &mut self.0, &value.0)
     }
 
     pub fn remove(&mut self, path: &str) -> Vec<Value> {
@@ -905,6 +1238,8 @@ pub enum PayloadSchemaType {
     Geo,
     Text,
     Bool,
+    Datetime,
+    Uuid,
 }
 
 /// Payload type with parameters
@@ -912,7 +1247,9 @@ pub enum PayloadSchemaType {
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum PayloadSchemaParams {
+    Keyword(KeywordIndexParams),
     Text(TextIndexParams),
+    Uuid(UuidIndexParams),
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
@@ -934,9 +1271,15 @@ impl TryFrom<PayloadIndexInfo> for PayloadFieldSchema {
 
     fn try_from(index_info: PayloadIndexInfo) -> Result<Self, Self::Error> {
         match (index_info.data_type, index_info.params) {
+            (PayloadSchemaType::Keyword, Some(PayloadSchemaParams::Keyword(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(params)),
+            ),
             (PayloadSchemaType::Text, Some(PayloadSchemaParams::Text(params))) => Ok(
                 PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(params)),
             ),
+            (PayloadSchemaType::Uuid, Some(PayloadSchemaParams::Uuid(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Uuid(params)),
+            ),
             (data_type, Some(_)) => Err(format!(
                 "Payload field with type {data_type:?} has unexpected params"
             )),
@@ -948,7 +1291,7 @@ impl TryFrom<PayloadIndexInfo> for PayloadFieldSchema {
 pub fn value_type(value: &Value) -> Option<PayloadSchemaType> {
     match value {
         Value::Null => None,
-        Value::Bool(_) => None,
+        Value::Bool(_) => Some(PayloadSchemaType::Bool),
         Value::Number(num) => {
             if num.is_i64() {
                 Some(PayloadSchemaType::Integer)
@@ -1026,6 +1369,19 @@ impl From<String> for MatchText {
     }
 }
 
+/// Full-text match of the words going one after another, in the given order.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct MatchPhrase {
+    pub phrase: String,
+}
+
+impl From<String> for MatchPhrase {
+    fn from(phrase: String) -> Self {
+        MatchPhrase { phrase }
+    }
+}
+
 /// Exact match on any of the given values
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -1047,6 +1403,7 @@ pub struct MatchExcept {
 pub enum MatchInterface {
     Value(MatchValue),
     Text(MatchText),
+    Phrase(MatchPhrase),
     Any(MatchAny),
     Except(MatchExcept),
 }
@@ -1058,6 +1415,7 @@ pub enum MatchInterface {
 pub enum Match {
     Value(MatchValue),
     Text(MatchText),
+    Phrase(MatchPhrase),
     Any(MatchAny),
     Except(MatchExcept),
 }
@@ -1092,6 +1450,9 @@ impl From<MatchInterface> for Match {
         match value {
             MatchInterface::Value(value) => Self::Value(MatchValue { value: value.value }),
             MatchInterface::Text(text) => Self::Text(MatchText { text: text.text }),
+            MatchInterface::Phrase(phrase) => Self::Phrase(MatchPhrase {
+                phrase: phrase.phrase,
+            }),
             MatchInterface::Any(any) => Self::Any(MatchAny { any: any.any }),
             MatchInterface::Except(except) => Self::Except(MatchExcept {
                 except: except.except,
@@ -1187,6 +1548,131 @@ impl Range {
     }
 }
 
+/// Datetime payload value, in RFC 3339 format. Datetime without a timezone is in UTC, date without
+/// a time is the start of the day.
+#[derive(
+    Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct DateTimePayloadType(#[schemars(with = "DateTime<Utc>")] pub DateTime<Utc>);
+
+impl DateTimePayloadType {
+    /// Formats of datetimes without a timezone, in addition to RFC 3339
+    const NAIVE_FORMATS: [&'static str; 4] = [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ];
+
+    /// Microseconds since the Unix epoch, which datetimes are indexed by
+    pub fn timestamp(&self) -> IntPayloadType {
+        self.0.timestamp_micros()
+    }
+
+    pub fn from_timestamp(timestamp: IntPayloadType) -> Option<Self> {
+        NaiveDateTime::from_timestamp_micros(timestamp)
+            .map(|datetime| Self(Utc.from_utc_datetime(&datetime)))
+    }
+}
+
+impl FromStr for DateTimePayloadType {
+    type Err = chrono::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Self(datetime.with_timezone(&Utc)));
+        }
+        for format in Self::NAIVE_FORMATS {
+            if let Ok(datetime) = NaiveDateTime::parse_from_str(s, format) {
+                return Ok(Self(Utc.from_utc_datetime(&datetime)));
+            }
+        }
+        let date = NaiveDate::parse_from_str(s, "%Y-%m-%d")?;
+        // unwrap is safe: midnight is a valid time
+        Ok(Self(
+            Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap()),
+        ))
+    }
+}
+
+impl TryFrom<String> for DateTimePayloadType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value
+            .parse()
+            .map_err(|err| format!("Invalid datetime {value:?}: {err}"))
+    }
+}
+
+impl From<DateTimePayloadType> for String {
+    fn from(datetime: DateTimePayloadType) -> Self {
+        datetime.to_string()
+    }
+}
+
+impl std::fmt::Display for DateTimePayloadType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+/// Range filter request over datetime values
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct DatetimeRange {
+    /// point.key < range.lt
+    pub lt: Option<DateTimePayloadType>,
+    /// point.key > range.gt
+    pub gt: Option<DateTimePayloadType>,
+    /// point.key >= range.gte
+    pub gte: Option<DateTimePayloadType>,
+    /// point.key <= range.lte
+    pub lte: Option<DateTimePayloadType>,
+}
+
+impl DatetimeRange {
+    pub fn check_range(&self, datetime: DateTimePayloadType) -> bool {
+        self.lt.map_or(true, |x| datetime < x)
+            && self.gt.map_or(true, |x| datetime > x)
+            && self.lte.map_or(true, |x| datetime <= x)
+            && self.gte.map_or(true, |x| datetime >= x)
+    }
+
+    /// Same range over the timestamps of datetimes
+    pub fn to_timestamp_range(&self) -> Range {
+        let timestamp =
+            |datetime: Option<DateTimePayloadType>| datetime.map(|x| x.timestamp() as f64);
+        Range {
+            lt: timestamp(self.lt),
+            gt: timestamp(self.gt),
+            gte: timestamp(self.gte),
+            lte: timestamp(self.lte),
+        }
+    }
+}
+
+/// Range filter request, over numbers or datetimes
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RangeInterface {
+    Float(Range),
+    DateTime(DatetimeRange),
+}
+
+impl From<Range> for RangeInterface {
+    fn from(range: Range) -> Self {
+        RangeInterface::Float(range)
+    }
+}
+
+impl From<DatetimeRange> for RangeInterface {
+    fn from(range: DatetimeRange) -> Self {
+        RangeInterface::DateTime(range)
+    }
+}
+
 /// Values count filter request
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -1256,29 +1742,184 @@ impl GeoRadius {
     }
 }
 
+/// Ordered sequence of GeoPoints representing the line
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct GeoLineString {
+    pub points: Vec<GeoPoint>,
+}
+
+impl GeoLineString {
+    /// Minimal number of points in a closed ring: 3 distinct vertices plus the closing point
+    pub const MIN_RING_POINTS: usize = 4;
+
+    pub fn validate_ring(&self) -> Result<(), GeoPolygonValidationError> {
+        if self.points.len() < Self::MIN_RING_POINTS {
+            return Err(GeoPolygonValidationError::TooFewPoints {
+                found: self.points.len(),
+            });
+        }
+        if self.points.first() != self.points.last() {
+            return Err(GeoPolygonValidationError::NotClosed);
+        }
+        Ok(())
+    }
+
+    /// Iterate over the edges of the ring, including the closing one
+    fn edges(&self) -> impl Iterator<Item = (&GeoPoint, &GeoPoint)> + '_ {
+        self.points
+            .iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .take(self.points.len())
+    }
+
+    /// Locate the point relative to the ring using the even-odd ray casting rule.
+    ///
+    /// If `shift_lon` is set, negative longitudes are moved into `[180; 360)`,
+    /// which makes rings crossing the antimeridian continuous.
+    fn locate_point(&self, lon: f64, lat: f64, shift_lon: bool) -> RingPosition {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let (ax, ay) = (shifted_lon(a.lon, shift_lon), a.lat);
+            let (bx, by) = (shifted_lon(b.lon, shift_lon), b.lat);
+
+            // Point lies on the edge (or coincides with one of its vertices)
+            let cross = (bx - ax) * (lat - ay) - (by - ay) * (lon - ax);
+            if cross.abs() <= GEO_POLYGON_EPS
+                && ax.min(bx) - GEO_POLYGON_EPS <= lon
+                && lon <= ax.max(bx) + GEO_POLYGON_EPS
+                && ay.min(by) - GEO_POLYGON_EPS <= lat
+                && lat <= ay.max(by) + GEO_POLYGON_EPS
+            {
+                return RingPosition::Boundary;
+            }
+
+            // Half-open rule on latitude, so rays passing through a vertex are counted once
+            if (ay > lat) != (by > lat) {
+                let cross_lon = ax + (lat - ay) * (bx - ax) / (by - ay);
+                if lon < cross_lon {
+                    inside = !inside;
+                }
+            }
+        }
+
+        if inside {
+            RingPosition::Inside
+        } else {
+            RingPosition::Outside
+        }
+    }
+
+    fn crosses_antimeridian(&self) -> bool {
+        self.edges().any(|(a, b)| (b.lon - a.lon).abs() > 180.0)
+    }
+}
+
+const GEO_POLYGON_EPS: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RingPosition {
+    Inside,
+    Outside,
+    Boundary,
+}
+
+fn shifted_lon(lon: f64, shift_lon: bool) -> f64 {
+    if shift_lon && lon < 0.0 {
+        lon + 360.0
+    } else {
+        lon
+    }
+}
+
 /// Geo filter request
 ///
-/// Matches coordinates inside the polygon, defined by the given coordinates in order.
+/// Matches coordinates inside the polygon, defined by `exterior` and `interiors`
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
-#[serde(rename_all = "snake_case")]
+#[serde(try_from = "GeoPolygonShadow", rename_all = "snake_case")]
 pub struct GeoPolygon {
-    /// Ordered list of coordinates representing the vertices of a polygon.
-    pub points: Vec<GeoPoint>,
+    /// The exterior line bounds the surface.
+    /// Must consist of a minimum of 4 points, and the first and last points must be the same.
+    pub exterior: GeoLineString,
+    /// Interior lines (if present) bound holes within the surface.
+    /// Each of them must consist of a minimum of 4 points, and the first and last points must be the same.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interiors: Option<Vec<GeoLineString>>,
+}
+
+#[derive(Deserialize)]
+struct GeoPolygonShadow {
+    exterior: GeoLineString,
+    #[serde(default)]
+    interiors: Option<Vec<GeoLineString>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeoPolygonValidationError {
+    TooFewPoints { found: usize },
+    NotClosed,
+}
+
+// The error type has to implement Display
+impl std::fmt::Display for GeoPolygonValidationError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeoPolygonValidationError::TooFewPoints { found } => write!(
+                formatter,
+                "Wrong format of GeoPolygon: each ring must contain at least {} points, got {found}",
+                GeoLineString::MIN_RING_POINTS,
+            ),
+            GeoPolygonValidationError::NotClosed => write!(
+                formatter,
+                "Wrong format of GeoPolygon: the first and the last points of each ring must be the same",
+            ),
+        }
+    }
 }
 
 impl GeoPolygon {
+    pub fn new(
+        exterior: GeoLineString,
+        interiors: Option<Vec<GeoLineString>>,
+    ) -> Result<Self, GeoPolygonValidationError> {
+        exterior.validate_ring()?;
+        for interior in interiors.iter().flatten() {
+            interior.validate_ring()?;
+        }
+        Ok(Self {
+            exterior,
+            interiors,
+        })
+    }
+
+    /// Polygon is considered to cross the antimeridian if any of the exterior edges
+    /// spans more than 180 degrees of longitude.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.exterior.crosses_antimeridian()
+    }
+
+    /// Points strictly inside the exterior ring and strictly outside every interior ring match.
+    /// Points lying on any edge or vertex are not considered to be inside.
     pub fn check_point(&self, lon: f64, lat: f64) -> bool {
-        let polygon_points: Vec<Coord<f64>> = self
-            .points
-            .iter()
-            .map(|p| Coord { x: p.lon, y: p.lat })
-            .collect();
+        let shift_lon = self.crosses_antimeridian();
+        let lon = shifted_lon(lon, shift_lon);
+
+        if self.exterior.locate_point(lon, lat, shift_lon) != RingPosition::Inside {
+            return false;
+        }
 
-        let polygon = Polygon::new(polygon_points.into(), vec![]);
+        self.interiors
+            .iter()
+            .flatten()
+            .all(|interior| interior.locate_point(lon, lat, shift_lon) == RingPosition::Outside)
+    }
+}
 
-        let point = Coord { x: lon, y: lat };
+impl TryFrom<GeoPolygonShadow> for GeoPolygon {
+    type Error = GeoPolygonValidationError;
 
-        polygon.contains(&point)
+    fn try_from(value: GeoPolygonShadow) -> Result<Self, Self::Error> {
+        GeoPolygon::new(value.exterior, value.interiors)
     }
 }
 
@@ -1291,13 +1932,12 @@ pub struct FieldCondition {
     /// Check if point has field with a given value
     pub r#match: Option<Match>,
     /// Check if points value lies in a given range
-    pub range: Option<Range>,
+    pub range: Option<RangeInterface>,
     /// Check if points geo location lies in a given area
     pub geo_bounding_box: Option<GeoBoundingBox>,
     /// Check if geo point is within a given radius
     pub geo_radius: Option<GeoRadius>,
     /// Check if geo point is within a given polygon
-    #[serde(skip)] // TODO: uncomment when geo_polygon is implemented
     pub geo_polygon: Option<GeoPolygon>,
     /// Check number of values of the field
     pub values_count: Option<ValuesCount>,
@@ -1316,11 +1956,11 @@ impl FieldCondition {
         }
     }
 
-    pub fn new_range(key: impl Into<PayloadKeyType>, range: Range) -> Self {
+    pub fn new_range(key: impl Into<PayloadKeyType>, range: impl Into<RangeInterface>) -> Self {
         Self {
             key: key.into(),
             r#match: None,
-            range: Some(range),
+            range: Some(range.into()),
             geo_bounding_box: None,
             geo_radius: None,
             geo_polygon: None,
@@ -1642,17 +2282,32 @@ impl PayloadSelector {
         })
     }
 
-    #[allow(clippy::ptr_arg)]
-    pub fn check(&self, key: &PayloadKeyType) -> bool {
+    /// Check if the top-level key of the payload is selected, fully or partially
+    ///
+    /// Values of not selected keys are not required to process the payload.
+    pub fn check(&self, key: &str) -> bool {
         match self {
-            PayloadSelector::Include(selector) => selector.include.contains(key),
-            PayloadSelector::Exclude(selector) => !selector.exclude.contains(key),
+            PayloadSelector::Include(selector) => selector
+                .include
+                .iter()
+                .any(|path| utils::path_head(path) == key),
+            PayloadSelector::Exclude(selector) => !selector.exclude.iter().any(|path| path == key),
         }
     }
 
     pub fn process(&self, x: Payload) -> Payload {
-        let map: serde_json::Map<String, Value> =
-            x.into_iter().filter(|(key, _)| self.check(key)).collect();
+        let mut map = x.0;
+        match self {
+            PayloadSelector::Include(selector) => {
+                let paths: Vec<_> = selector.include.iter().map(String::as_str).collect();
+                utils::retain_json_map_paths(&paths, &mut map);
+            }
+            PayloadSelector::Exclude(selector) => {
+                for path in &selector.exclude {
+                    utils::remove_value_from_json_map(path, &mut map);
+                }
+            }
+        }
         map.into()
     }
 }
@@ -1667,12 +2322,67 @@ pub struct WithPayload {
     pub payload_selector: Option<PayloadSelector>,
 }
 
+/// At least `min_count` of the conditions must match
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(try_from = "MinShouldShadow")]
+#[serde(rename_all = "snake_case")]
+pub struct MinShould {
+    pub conditions: Vec<Condition>,
+    pub min_count: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MinShouldShadow {
+    conditions: Vec<Condition>,
+    min_count: usize,
+}
+
+#[derive(Debug)]
+pub struct MinShouldValidationError;
+
+// The error type has to implement Display
+impl std::fmt::Display for MinShouldValidationError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "Wrong format of min_should: `min_count` must be at least 1"
+        )
+    }
+}
+
+impl MinShould {
+    pub fn new(
+        conditions: Vec<Condition>,
+        min_count: usize,
+    ) -> Result<Self, MinShouldValidationError> {
+        if min_count == 0 {
+            return Err(MinShouldValidationError);
+        }
+        Ok(MinShould {
+            conditions,
+            min_count,
+        })
+    }
+}
+
+impl TryFrom<MinShouldShadow> for MinShould {
+    type Error = MinShouldValidationError;
+
+    fn try_from(value: MinShouldShadow) -> Result<Self, Self::Error> {
+        MinShould::new(value.conditions, value.min_count)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Default)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "snake_case")]
 pub struct Filter {
     /// At least one of those conditions should match
     pub should: Option<Vec<Condition>>,
+    /// At least minimum amount of given conditions should match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_should: Option<MinShould>,
     /// All conditions must match
     pub must: Option<Vec<Condition>>,
     /// All conditions must NOT match
@@ -1683,6 +2393,16 @@ impl Filter {
     pub fn new_should(condition: Condition) -> Self {
         Filter {
             should: Some(vec![condition]),
+            min_should: None,
+            must: None,
+            must_not: None,
+        }
+    }
+
+    pub fn new_min_should(min_should: MinShould) -> Self {
+        Filter {
+            should: None,
+            min_should: Some(min_should),
             must: None,
             must_not: None,
         }
@@ -1691,6 +2411,7 @@ impl Filter {
     pub fn new_must(condition: Condition) -> Self {
         Filter {
             should: None,
+            min_should: None,
             must: Some(vec![condition]),
             must_not: None,
         }
@@ -1699,6 +2420,7 @@ impl Filter {
     pub fn new_must_not(condition: Condition) -> Self {
         Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![condition]),
         }
@@ -1716,9 +2438,21 @@ impl Filter {
                 }
             }
         };
+        // Only one `min_should` fits into the filter, the other one is kept as a nested filter
+        let (min_should, extra_must) = match (self.min_should.clone(), other.min_should.clone()) {
+            (Some(this), Some(other)) => (
+                Some(this),
+                Some(vec![Condition::Filter(Filter::new_min_should(other))]),
+            ),
+            (this, other) => (this.or(other), None),
+        };
         Filter {
             should: merge_component(self.should.clone(), other.should.clone()),
-            must: merge_component(self.must.clone(), other.must.clone()),
+            min_should,
+            must: merge_component(
+                merge_component(self.must.clone(), other.must.clone()),
+                extra_must,
+            ),
             must_not: merge_component(self.must_not.clone(), other.must_not.clone()),
         }
     }
@@ -1727,8 +2461,7 @@ impl Filter {
 #[cfg(test)]
 mod tests {
     use serde::de::DeserializeOwned;
-    use serde_json;
-    use serde_json::json;
+    use serde_json::{self, json};
 
     use super::*;
     use crate::common::utils::remove_value_from_json_map;
@@ -1753,6 +2486,77 @@ mod tests {
         eprintln!("de_record = {de_record:#?}");
     }
 
+    #[test]
+    fn test_parse_point_id() {
+        let big_id = (1u64 << 63) + 1;
+        let uuid = Uuid::new_v4();
+
+        let parse = |value: serde_json::Value| serde_json::from_value::<PointIdType>(value);
+        assert_eq!(parse(json!(big_id)).unwrap(), PointIdType::NumId(big_id));
+        assert_eq!(
+            parse(json!(u64::MAX)).unwrap(),
+            PointIdType::NumId(u64::MAX)
+        );
+        assert_eq!(
+            parse(json!(big_id.to_string())).unwrap(),
+            PointIdType::NumId(big_id),
+        );
+        assert_eq!(
+            parse(json!(uuid.to_string())).unwrap(),
+            PointIdType::Uuid(uuid),
+        );
+        // UUID in the simple format, which can't be parsed as an integer
+        let digits_uuid = "12345678901234567890123456789012";
+        assert_eq!(
+            parse(json!(digits_uuid)).unwrap(),
+            PointIdType::Uuid(Uuid::parse_str(digits_uuid).unwrap()),
+        );
+        // UUIDs with leading zeros are not integers, even if the value fits into one
+        let nil_uuid = "00000000000000000000000000000000";
+        assert_eq!(
+            parse(json!(nil_uuid)).unwrap(),
+            PointIdType::Uuid(Uuid::nil())
+        );
+        let one_uuid = "00000000000000000000000000000001";
+        assert_eq!(
+            parse(json!(one_uuid)).unwrap(),
+            PointIdType::Uuid(Uuid::from_u128(1)),
+        );
+        assert_eq!(parse(json!("0")).unwrap(), PointIdType::NumId(0));
+        assert!(parse(json!("01")).is_err());
+
+        // Floats are never rounded to IDs
+        assert!(parse(json!(1.0)).is_err());
+        assert!(parse(json!(9.223372036854776e18)).is_err());
+        assert!(parse(json!(-1)).is_err());
+        assert!(parse(json!("+1")).is_err());
+        assert!(parse(json!("18446744073709551616")).is_err());
+
+        // Parsing of JSON text keeps big integers exact
+        let has_id: HasIdCondition = serde_json::from_str(&format!(
+            r#"{{"has_id": [{big_id}, "{}", "{uuid}", 1]}}"#,
+            u64::MAX,
+        ))
+        .unwrap();
+        assert_eq!(
+            has_id.has_id,
+            HashSet::from([
+                PointIdType::NumId(big_id),
+                PointIdType::NumId(u64::MAX),
+                PointIdType::Uuid(uuid),
+                PointIdType::NumId(1),
+            ]),
+        );
+
+        // Integer IDs are serialized as numbers, so they are parsed back to the same IDs
+        for id in has_id.has_id {
+            assert_eq!(parse(serde_json::to_value(id).unwrap()).unwrap(), id);
+        }
+
+        // All integer IDs go before UUIDs
+        assert!(PointIdType::NumId(u64::MAX) < PointIdType::Uuid(Uuid::nil()));
+    }
+
     #[test]
     fn test_geo_radius_check_point() {
         let radius = GeoRadius {
@@ -1789,26 +2593,34 @@ mod tests {
         assert!(!outside_result);
     }
 
+    fn build_polygon(exterior: Vec<(f64, f64)>, interiors: Vec<Vec<(f64, f64)>>) -> GeoPolygon {
+        let to_line = |points: Vec<(f64, f64)>| GeoLineString {
+            points: points
+                .into_iter()
+                .map(|(lon, lat)| GeoPoint { lon, lat })
+                .collect(),
+        };
+        let interiors = if interiors.is_empty() {
+            None
+        } else {
+            Some(interiors.into_iter().map(to_line).collect())
+        };
+        GeoPolygon::new(to_line(exterior), interiors).unwrap()
+    }
+
     #[test]
     fn test_geo_polygon_check_point() {
         // Create a GeoPolygon with a square shape
-        let polygon_1 = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -1.0,
-                    lat: -1.0,
-                },
-                GeoPoint {
-                    lon: 1.0,
-                    lat: -1.0,
-                },
-                GeoPoint { lon: 1.0, lat: 1.0 },
-                GeoPoint {
-                    lon: -1.0,
-                    lat: 1.0,
-                },
+        let polygon_1 = build_polygon(
+            vec![
+                (-1.0, -1.0),
+                (1.0, -1.0),
+                (1.0, 1.0),
+                (-1.0, 1.0),
+                (-1.0, -1.0),
             ],
-        };
+            vec![],
+        );
 
         let inside_result = polygon_1.check_point(0.5, 0.5);
         assert!(inside_result);
@@ -1819,24 +2631,20 @@ mod tests {
         let on_edge_result = polygon_1.check_point(1.0, 0.0);
         assert!(!on_edge_result);
 
+        let on_vertex_result = polygon_1.check_point(1.0, 1.0);
+        assert!(!on_vertex_result);
+
         // Create a GeoPolygon as a `twisted square`
-        let polygon_2 = GeoPolygon {
-            points: vec![
-                GeoPoint {
-                    lon: -1.0,
-                    lat: -1.0,
-                },
-                GeoPoint { lon: 1.0, lat: 1.0 },
-                GeoPoint {
-                    lon: 1.0,
-                    lat: -1.0,
-                },
-                GeoPoint {
-                    lon: -1.0,
-                    lat: 1.0,
-                },
+        let polygon_2 = build_polygon(
+            vec![
+                (-1.0, -1.0),
+                (1.0, 1.0),
+                (1.0, -1.0),
+                (-1.0, 1.0),
+                (-1.0, -1.0),
             ],
-        };
+            vec![],
+        );
 
         let inside_result_2 = polygon_2.check_point(0.5, 0.0);
         assert!(inside_result_2);
@@ -1848,6 +2656,151 @@ mod tests {
         assert!(!on_edge_result_2);
     }
 
+    #[test]
+    fn test_geo_polygon_concave_check_point() {
+        // `U`-shaped polygon with a notch cut from the top
+        let polygon = build_polygon(
+            vec![
+                (0.0, 0.0),
+                (3.0, 0.0),
+                (3.0, 3.0),
+                (2.0, 3.0),
+                (2.0, 1.0),
+                (1.0, 1.0),
+                (1.0, 3.0),
+                (0.0, 3.0),
+                (0.0, 0.0),
+            ],
+            vec![],
+        );
+
+        assert!(polygon.check_point(0.5, 2.5));
+        assert!(polygon.check_point(2.5, 2.5));
+        assert!(polygon.check_point(1.5, 0.5));
+        // Inside the notch
+        assert!(!polygon.check_point(1.5, 2.0));
+        // Ray passes exactly through the notch vertices
+        assert!(polygon.check_point(0.5, 1.0));
+        assert!(!polygon.check_point(1.5, 3.0));
+        // Reflex vertex
+        assert!(!polygon.check_point(2.0, 1.0));
+    }
+
+    #[test]
+    fn test_geo_polygon_with_hole_check_point() {
+        let polygon = build_polygon(
+            vec![
+                (-2.0, -2.0),
+                (2.0, -2.0),
+                (2.0, 2.0),
+                (-2.0, 2.0),
+                (-2.0, -2.0),
+            ],
+            vec![vec![
+                (-1.0, -1.0),
+                (1.0, -1.0),
+                (1.0, 1.0),
+                (-1.0, 1.0),
+                (-1.0, -1.0),
+            ]],
+        );
+
+        assert!(polygon.check_point(1.5, 0.0));
+        assert!(polygon.check_point(-1.5, -1.5));
+        // Inside the hole
+        assert!(!polygon.check_point(0.0, 0.0));
+        // On the edge and on the vertex of the hole
+        assert!(!polygon.check_point(1.0, 0.0));
+        assert!(!polygon.check_point(-1.0, -1.0));
+        // Outside of the exterior
+        assert!(!polygon.check_point(3.0, 0.0));
+    }
+
+    #[test]
+    fn test_geo_polygon_crossing_antimeridian_check_point() {
+        let polygon = build_polygon(
+            vec![
+                (170.0, -10.0),
+                (-170.0, -10.0),
+                (-170.0, 10.0),
+                (170.0, 10.0),
+                (170.0, -10.0),
+            ],
+            vec![],
+        );
+
+        assert!(polygon.crosses_antimeridian());
+        assert!(polygon.check_point(175.0, 0.0));
+        assert!(polygon.check_point(-175.0, 0.0));
+        assert!(polygon.check_point(180.0, 5.0));
+        assert!(polygon.check_point(-180.0, 5.0));
+        assert!(!polygon.check_point(0.0, 0.0));
+        assert!(!polygon.check_point(165.0, 0.0));
+        assert!(!polygon.check_point(-165.0, 0.0));
+    }
+
+    #[test]
+    fn test_parse_geo_polygon_validation() {
+        let polygon: GeoPolygon = serde_json::from_str(
+            r#"{
+                "exterior": {"points": [
+                    {"lon": 0.0, "lat": 0.0},
+                    {"lon": 1.0, "lat": 0.0},
+                    {"lon": 1.0, "lat": 1.0},
+                    {"lon": 0.0, "lat": 0.0}
+                ]}
+            }"#,
+        )
+        .unwrap();
+        assert!(polygon.interiors.is_none());
+        assert!(polygon.check_point(0.8, 0.2));
+
+        let not_closed: Result<GeoPolygon, _> = serde_json::from_str(
+            r#"{
+                "exterior": {"points": [
+                    {"lon": 0.0, "lat": 0.0},
+                    {"lon": 1.0, "lat": 0.0},
+                    {"lon": 1.0, "lat": 1.0},
+                    {"lon": 0.0, "lat": 1.0}
+                ]}
+            }"#,
+        );
+        assert!(not_closed.is_err());
+
+        let too_short: Result<GeoPolygon, _> = serde_json::from_str(
+            r#"{
+                "exterior": {"points": [
+                    {"lon": 0.0, "lat": 0.0},
+                    {"lon": 1.0, "lat": 0.0},
+                    {"lon": 1.0, "lat": 1.0},
+                    {"lon": 0.0, "lat": 0.0}
+                ]},
+                "interiors": [{"points": [
+                    {"lon": 0.1, "lat": 0.1},
+                    {"lon": 0.2, "lat": 0.1},
+                    {"lon": 0.1, "lat": 0.1}
+                ]}]
+            }"#,
+        );
+        assert!(too_short.is_err());
+
+        let condition: FieldCondition = serde_json::from_str(
+            r#"{
+                "key": "location",
+                "geo_polygon": {
+                    "exterior": {"points": [
+                        {"lon": 0.0, "lat": 0.0},
+                        {"lon": 1.0, "lat": 0.0},
+                        {"lon": 1.0, "lat": 1.0},
+                        {"lon": 0.0, "lat": 0.0}
+                    ]}
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(condition.geo_polygon, Some(polygon));
+    }
+
     #[test]
     fn test_serialize_query() {
         let filter = Filter {
@@ -1855,6 +2808,7 @@ mod tests {
                 "hello".to_owned(),
                 "world".to_owned().into(),
             ))]),
+            min_should: None,
             must_not: None,
             should: None,
         };
@@ -1874,6 +2828,37 @@ mod tests {
         assert!(filter.is_err())
     }
 
+    #[test]
+    fn test_parse_min_should_query() {
+        let query = r#"
+        {
+            "min_should": {
+                "conditions": [
+                    { "key": "color", "match": { "value": "red" } },
+                    { "key": "size", "match": { "value": "L" } },
+                    { "has_id": [1, 2] }
+                ],
+                "min_count": 2
+            }
+        }
+        "#;
+        let filter: Filter = serde_json::from_str(query).unwrap();
+        let min_should = filter.min_should.unwrap();
+        assert_eq!(min_should.conditions.len(), 3);
+        assert_eq!(min_should.min_count, 2);
+
+        let query = r#"
+        {
+            "min_should": {
+                "conditions": [{ "key": "color", "match": { "value": "red" } }],
+                "min_count": 0
+            }
+        }
+        "#;
+        let filter: Result<Filter, _> = serde_json::from_str(query);
+        assert!(filter.is_err());
+    }
+
     #[test]
     fn test_parse_match_query() {
         let query = r#"
@@ -1920,6 +2905,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_datetime_parsing() {
+        let parse = |s: &str| s.parse::<DateTimePayloadType>().unwrap();
+
+        // Same instant in different precisions and timezones
+        let midnight = parse("2023-01-01T00:00:00Z");
+        for value in [
+            "2023-01-01",
+            "2023-01-01T00:00",
+            "2023-01-01 00:00",
+            "2023-01-01T00:00:00",
+            "2023-01-01T00:00:00.000000",
+            "2023-01-01T03:00:00+03:00",
+            "2022-12-31T19:00:00-05:00",
+        ] {
+            assert_eq!(parse(value), midnight, "{value}");
+            assert_eq!(parse(value).timestamp(), midnight.timestamp(), "{value}");
+        }
+
+        let precise = parse("2023-01-01T12:30:15.123456Z");
+        assert_eq!(precise.timestamp() - midnight.timestamp(), 45_015_123_456);
+        assert_eq!(
+            DateTimePayloadType::from_timestamp(precise.timestamp()),
+            Some(precise)
+        );
+        assert_eq!(precise.to_string(), "2023-01-01T12:30:15.123456Z");
+
+        for value in [
+            "",
+            "2023-13-01",
+            "2023-01-01T25:00:00Z",
+            "yesterday",
+            "1672531200",
+        ] {
+            assert!(value.parse::<DateTimePayloadType>().is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_parse_range_query() {
+        let condition: FieldCondition =
+            serde_json::from_value(json!({ "key": "num", "range": { "gte": 5, "lt": 7.5 } }))
+                .unwrap();
+        assert_eq!(
+            condition.range.unwrap(),
+            RangeInterface::Float(Range {
+                gte: Some(5.0),
+                lt: Some(7.5),
+                ..Default::default()
+            })
+        );
+
+        let condition: FieldCondition = serde_json::from_value(json!({
+            "key": "date",
+            "range": { "gte": "2023-01-01", "lt": "2023-02-01T00:00:00+01:00" }
+        }))
+        .unwrap();
+        assert_eq!(
+            condition.range.unwrap(),
+            RangeInterface::DateTime(DatetimeRange {
+                gte: Some("2023-01-01T00:00:00Z".parse().unwrap()),
+                lt: Some("2023-01-31T23:00:00Z".parse().unwrap()),
+                ..Default::default()
+            })
+        );
+
+        let result: Result<FieldCondition, _> =
+            serde_json::from_value(json!({ "key": "date", "range": { "gte": "yesterday" } }));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_match_any() {
         let query = r#"
@@ -2247,6 +3303,36 @@ mod tests {
         assert!(filter.is_err());
     }
 
+    #[test]
+    fn test_preprocess_score_threshold() {
+        let distances = [
+            Distance::Cosine,
+            Distance::Euclid,
+            Distance::Dot,
+            Distance::Manhattan,
+            Distance::Hamming,
+        ];
+        let scores = [-100.0, -16.0, -2.25, -1.0, -0.5, 0.0, 0.5, 1.0, 4.0];
+        let thresholds = [-5.0, -1.0, 0.0, 0.3, 1.0, 1.5, 3.0, 10.0];
+        for distance in distances {
+            for threshold in thresholds {
+                let internal_threshold = distance.preprocess_score_threshold(threshold);
+                for score in scores {
+                    // Internal scores of distance metrics are never positive
+                    if matches!(distance.distance_order(), Order::SmallBetter) && score > 0.0 {
+                        continue;
+                    }
+                    let user_score = distance.postprocess_score(score);
+                    assert_eq!(
+                        distance.check_threshold(user_score, threshold),
+                        score > internal_threshold,
+                        "{distance:?}: score {score} against threshold {threshold}",
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_remove_key() {
         let mut payload: Payload = serde_json::from_str(
@@ -1,10 +1,12 @@
 use std::path::Path;
 use std::{fmt, io};
 
+use crate::common::snapshot_manifest::SnapshotManifest;
 use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::utils;
 
-/// Append `file` to the archive under `dest_dir` directory at `file`'s path relative to `base`.
+/// Append `file` to the archive under `dest_dir` directory at `file`'s path relative to `base`,
+/// and list it in the `manifest`.
 ///
 /// E.g.:
 /// - if `base` is `/some/directory/`
@@ -13,14 +15,17 @@ use crate::utils;
 /// - then the `file` will be added to the archive at path `/inside/the/archive/file/path`
 pub fn append_file_relative_to_base(
     builder: &mut tar::Builder<impl io::Write>,
+    manifest: &mut SnapshotManifest,
     base: &Path,
     file: &Path,
     dest_dir: &Path,
 ) -> OperationResult<()> {
     let name =
         utils::path::strip_prefix(file, base).map_err(|err| failed_to_append_error(file, err))?;
+    let dest = dest_dir.join(name);
 
-    append_file(builder, file, &dest_dir.join(name))
+    manifest.add_file(file, &dest)?;
+    append_file(builder, file, &dest)
 }
 
 pub fn append_file(
@@ -33,6 +38,20 @@ pub fn append_file(
         .map_err(|err| failed_to_append_error(file, err))
 }
 
+/// Append file with the given `data` to the archive at `dest`
+pub fn append_data(
+    builder: &mut tar::Builder<impl io::Write>,
+    data: &[u8],
+    dest: &Path,
+) -> OperationResult<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    builder
+        .append_data(&mut header, dest, data)
+        .map_err(|err| failed_to_append_error(dest, err))
+}
+
 /// Create "failed to append `<path>` to the archive" error.
 pub fn failed_to_append_error(path: &Path, err: impl fmt::Display) -> OperationError {
     OperationError::service_error(format!(
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::fs::create_dir_all;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
@@ -6,23 +7,27 @@ use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
 use bitvec::prelude::BitSlice;
+use half::f16;
 
 use crate::common::Flusher;
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::{check_process_stopped, OperationResult};
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
 use crate::vector_storage::chunked_mmap_vectors::ChunkedMmapVectors;
 use crate::vector_storage::dynamic_mmap_flags::DynamicMmapFlags;
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
-use crate::vector_storage::{VectorStorage, VectorStorageEnum};
+use crate::vector_storage::{DenseVectorStorage, VectorStorage, VectorStorageEnum};
 
 const VECTORS_DIR_PATH: &str = "vectors";
 const DELETED_DIR_PATH: &str = "deleted";
 
-pub struct AppendableMmapVectorStorage {
-    vectors: ChunkedMmapVectors,
+pub struct AppendableMmapVectorStorage<T: PrimitiveVectorElement> {
+    vectors: ChunkedMmapVectors<T>,
     deleted: DynamicMmapFlags,
     distance: Distance,
+    /// Vectors are stored without distance preprocessing
+    original_vectors: bool,
     deleted_count: usize,
     quantized_vectors: Option<QuantizedVectors>,
 }
@@ -32,12 +37,47 @@ pub fn open_appendable_memmap_vector_storage(
     dim: usize,
     distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_appendable_memmap_vector_storage_impl(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::AppendableMemmap(Box::new(storage)),
+    )))
+}
+
+/// Open appendable mem-mapped storage, which keeps vectors in half-precision
+pub fn open_appendable_memmap_vector_storage_half(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_appendable_memmap_vector_storage_impl::<f16>(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::AppendableMemmapHalf(Box::new(storage)),
+    )))
+}
+
+/// Open appendable mem-mapped storage, which keeps vectors as bytes
+pub fn open_appendable_memmap_vector_storage_byte(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_appendable_memmap_vector_storage_impl::<u8>(path, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::AppendableMemmapByte(Box::new(storage)),
+    )))
+}
+
+fn open_appendable_memmap_vector_storage_impl<T: PrimitiveVectorElement>(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<AppendableMmapVectorStorage<T>> {
     create_dir_all(path)?;
 
     let vectors_path = path.join(VECTORS_DIR_PATH);
     let deleted_path = path.join(DELETED_DIR_PATH);
 
-    let vectors: ChunkedMmapVectors = ChunkedMmapVectors::open(&vectors_path, dim)?;
+    let vectors: ChunkedMmapVectors<T> = ChunkedMmapVectors::open(&vectors_path, dim)?;
 
     let num_vectors = vectors.len();
 
@@ -51,20 +91,21 @@ pub fn open_appendable_memmap_vector_storage(
         }
     }
 
-    let storage = AppendableMmapVectorStorage {
+    Ok(AppendableMmapVectorStorage {
         vectors,
         deleted,
         distance,
+        original_vectors: false,
         deleted_count,
         quantized_vectors: None,
-    };
-
-    Ok(Arc::new(AtomicRefCell::new(
-        VectorStorageEnum::AppendableMemmap(Box::new(storage)),
-    )))
+    })
 }
 
-impl AppendableMmapVectorStorage {
+impl<T: PrimitiveVectorElement> AppendableMmapVectorStorage<T> {
+    pub fn set_original_vectors(&mut self, original_vectors: bool) {
+        self.original_vectors = original_vectors;
+    }
+
     /// Set deleted flag for given key. Returns previous deleted state.
     #[inline]
     fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> OperationResult<bool> {
@@ -85,7 +126,13 @@ impl AppendableMmapVectorStorage {
     }
 }
 
-impl VectorStorage for AppendableMmapVectorStorage {
+impl<T: PrimitiveVectorElement> DenseVectorStorage<T> for AppendableMmapVectorStorage<T> {
+    fn get_dense(&self, key: PointOffsetType) -> &[T] {
+        self.vectors.get(key)
+    }
+}
+
+impl<T: PrimitiveVectorElement> VectorStorage for AppendableMmapVectorStorage<T> {
     fn vector_dim(&self) -> usize {
         self.vectors.dim()
     }
@@ -94,12 +141,16 @@ impl VectorStorage for AppendableMmapVectorStorage {
         self.distance
     }
 
+    fn stores_original_vectors(&self) -> bool {
+        self.original_vectors
+    }
+
     fn total_vector_count(&self) -> usize {
         self.vectors.len()
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.vectors.get(key)
+    fn get_vector(&self, key: PointOffsetType) -> Cow<'_, [VectorElementType]> {
+        T::slice_to_float_cow(Cow::Borrowed(self.vectors.get(key)))
     }
 
     fn insert_vector(
@@ -107,7 +158,8 @@ impl VectorStorage for AppendableMmapVectorStorage {
         key: PointOffsetType,
         vector: &[VectorElementType],
     ) -> OperationResult<()> {
-        self.vectors.insert(key, vector)?;
+        let vector = T::slice_from_float_cow(Cow::Borrowed(vector));
+        self.vectors.insert(key, &vector)?;
         self.set_deleted(key, false)?;
         Ok(())
     }
@@ -123,8 +175,8 @@ impl VectorStorage for AppendableMmapVectorStorage {
             check_process_stopped(stopped)?;
             // Do not perform preprocessing - vectors should be already processed
             let other_deleted = other.is_deleted_vector(point_id);
-            let other_vector = other.get_vector(point_id);
-            let new_id = self.vectors.push(other_vector)?;
+            let other_vector = T::slice_from_float_cow(other.get_vector(point_id));
+            let new_id = self.vectors.push(&other_vector)?;
             self.set_deleted(new_id, other_deleted)?;
         }
         let end_index = self.vectors.len() as PointOffsetType;
@@ -150,9 +202,18 @@ impl VectorStorage for AppendableMmapVectorStorage {
         max_threads: usize,
         stopped: &AtomicBool,
     ) -> OperationResult<()> {
-        let vector_data_iterator = (0..self.vectors.len() as u32).map(|i| self.vectors.get(i));
+        // Quantization consumes f32 slices, vectors of other types are converted upfront
+        let vectors: Vec<_> = (0..self.vectors.len() as u32)
+            .map(|i| {
+                T::quantization_preprocess(
+                    self.distance,
+                    self.original_vectors,
+                    self.vectors.get(i),
+                )
+            })
+            .collect();
         self.quantized_vectors = Some(QuantizedVectors::create(
-            vector_data_iterator,
+            vectors.iter().map(|vector| vector.as_ref()),
             quantization_config,
             self.distance,
             self.vectors.dim(),
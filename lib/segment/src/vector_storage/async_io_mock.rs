@@ -2,7 +2,8 @@ use std::fs::File;
 
 use crate::entry::entry_point::OperationResult;
 
-// This is a mock implementation of the async_io module for those platforms that don't support io_uring.
+// This is a mock implementation of the async_io module for those platforms that don't support io_uring,
+// or for builds without the `io-uring` feature.
 #[allow(dead_code)]
 pub struct UringReader;
 
@@ -7,7 +7,10 @@ use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationResult;
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{
+    CosineMetric, DotProductMetric, EuclidMetric, HammingMetric, ManhattanMetric,
+    UnnormalizedCosineMetric,
+};
 use crate::types::{Distance, PointOffsetType, ScoreType};
 use crate::vector_storage::memmap_vector_storage::MemmapVectorStorage;
 use crate::vector_storage::mmap_vectors::MmapVectors;
@@ -15,7 +18,7 @@ use crate::vector_storage::{RawScorer, ScoredPointOffset, VectorStorage as _, DE
 
 pub fn new<'a>(
     vector: Vec<VectorElementType>,
-    storage: &'a MemmapVectorStorage,
+    storage: &'a MemmapVectorStorage<VectorElementType>,
     point_deleted: &'a BitSlice,
     is_stopped: &'a AtomicBool,
 ) -> OperationResult<Box<dyn RawScorer + 'a>> {
@@ -27,7 +30,7 @@ pub fn new<'a>(
 pub struct AsyncRawScorerImpl<'a, TMetric: Metric> {
     points_count: PointOffsetType,
     query: Vec<VectorElementType>,
-    storage: &'a MmapVectors,
+    storage: &'a MmapVectors<VectorElementType>,
     point_deleted: &'a BitSlice,
     vec_deleted: &'a BitSlice,
     metric: PhantomData<TMetric>,
@@ -43,7 +46,7 @@ where
     fn new(
         points_count: PointOffsetType,
         vector: Vec<VectorElementType>,
-        storage: &'a MmapVectors,
+        storage: &'a MmapVectors<VectorElementType>,
         point_deleted: &'a BitSlice,
         vec_deleted: &'a BitSlice,
         is_stopped: &'a AtomicBool,
@@ -207,23 +210,25 @@ where
 struct AsyncRawScorerBuilder<'a> {
     points_count: PointOffsetType,
     vector: Vec<VectorElementType>,
-    storage: &'a MmapVectors,
+    storage: &'a MmapVectors<VectorElementType>,
     point_deleted: &'a BitSlice,
     vec_deleted: &'a BitSlice,
     distance: Distance,
+    original_vectors: bool,
     is_stopped: Option<&'a AtomicBool>,
 }
 
 impl<'a> AsyncRawScorerBuilder<'a> {
     pub fn new(
         vector: Vec<VectorElementType>,
-        storage: &'a MemmapVectorStorage,
+        storage: &'a MemmapVectorStorage<VectorElementType>,
         point_deleted: &'a BitSlice,
     ) -> OperationResult<Self> {
         let points_count = storage.total_vector_count() as _;
         let vec_deleted = storage.deleted_vector_bitslice();
 
         let distance = storage.distance();
+        let original_vectors = storage.stores_original_vectors();
         let storage = storage.get_mmap_vectors();
 
         let builder = Self {
@@ -233,6 +238,7 @@ impl<'a> AsyncRawScorerBuilder<'a> {
             vec_deleted,
             storage,
             distance,
+            original_vectors,
             is_stopped: None,
         };
 
@@ -241,9 +247,14 @@ impl<'a> AsyncRawScorerBuilder<'a> {
 
     pub fn build(self) -> Box<dyn RawScorer + 'a> {
         match self.distance {
+            Distance::Cosine if self.original_vectors => {
+                Box::new(self.with_metric::<UnnormalizedCosineMetric>())
+            }
             Distance::Cosine => Box::new(self.with_metric::<CosineMetric>()),
             Distance::Euclid => Box::new(self.with_metric::<EuclidMetric>()),
             Distance::Dot => Box::new(self.with_metric::<DotProductMetric>()),
+            Distance::Manhattan => Box::new(self.with_metric::<ManhattanMetric>()),
+            Distance::Hamming => Box::new(self.with_metric::<HammingMetric>()),
         }
     }
 
@@ -1,5 +1,6 @@
+use std::borrow::Cow;
 use std::fs::{create_dir_all, File, OpenOptions};
-use std::io::{self, Write};
+use std::io::{self, BufWriter, Write};
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
@@ -7,10 +8,12 @@ use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
 use bitvec::prelude::BitSlice;
+use half::f16;
 
 use super::quantized::quantized_vectors::QuantizedVectors;
-use super::VectorStorageEnum;
+use super::{DenseVectorStorage, VectorStorageEnum};
 use crate::common::{mmap_ops, Flusher};
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::{check_process_stopped, OperationResult};
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
@@ -27,11 +30,13 @@ const DELETED_PATH: &str = "deleted.dat";
 /// but possible to mark some vectors as removed
 ///
 /// Mem-mapped storage can only be constructed from another storage
-pub struct MemmapVectorStorage {
+pub struct MemmapVectorStorage<T: PrimitiveVectorElement> {
     vectors_path: PathBuf,
     deleted_path: PathBuf,
-    mmap_store: Option<MmapVectors>,
+    mmap_store: Option<MmapVectors<T>>,
     distance: Distance,
+    /// Vectors are stored without distance preprocessing
+    original_vectors: bool,
 }
 
 pub fn open_memmap_vector_storage(
@@ -48,23 +53,62 @@ pub fn open_memmap_vector_storage_with_async_io(
     distance: Distance,
     with_async_io: bool,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_memmap_vector_storage_impl(path, dim, distance, with_async_io)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Memmap(
+        Box::new(storage),
+    ))))
+}
+
+/// Open mem-mapped storage, which keeps vectors in half-precision
+///
+/// Async scorer reads single-precision vectors only, so it is not used for this storage.
+pub fn open_memmap_vector_storage_half(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_memmap_vector_storage_impl::<f16>(path, dim, distance, false)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::MemmapHalf(
+        Box::new(storage),
+    ))))
+}
+
+/// Open mem-mapped storage, which keeps vectors as bytes
+///
+/// Async scorer reads single-precision vectors only, so it is not used for this storage.
+pub fn open_memmap_vector_storage_byte(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_memmap_vector_storage_impl::<u8>(path, dim, distance, false)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::MemmapByte(
+        Box::new(storage),
+    ))))
+}
+
+fn open_memmap_vector_storage_impl<T: PrimitiveVectorElement>(
+    path: &Path,
+    dim: usize,
+    distance: Distance,
+    with_async_io: bool,
+) -> OperationResult<MemmapVectorStorage<T>> {
     create_dir_all(path)?;
 
     let vectors_path = path.join(VECTORS_PATH);
     let deleted_path = path.join(DELETED_PATH);
     let mmap_store = MmapVectors::open(&vectors_path, &deleted_path, dim, with_async_io)?;
 
-    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Memmap(
-        Box::new(MemmapVectorStorage {
-            vectors_path,
-            deleted_path,
-            mmap_store: Some(mmap_store),
-            distance,
-        }),
-    ))))
+    Ok(MemmapVectorStorage {
+        vectors_path,
+        deleted_path,
+        mmap_store: Some(mmap_store),
+        distance,
+        original_vectors: false,
+    })
 }
 
-impl MemmapVectorStorage {
+impl<T: PrimitiveVectorElement> MemmapVectorStorage<T> {
     pub fn prefault_mmap_pages(&self) -> Option<mmap_ops::PrefaultMmapPages> {
         Some(
             self.mmap_store
@@ -73,7 +117,11 @@ impl MemmapVectorStorage {
         )
     }
 
-    pub fn get_mmap_vectors(&self) -> &MmapVectors {
+    pub fn set_original_vectors(&mut self, original_vectors: bool) {
+        self.original_vectors = original_vectors;
+    }
+
+    pub fn get_mmap_vectors(&self) -> &MmapVectors<T> {
         self.mmap_store.as_ref().unwrap()
     }
 
@@ -85,7 +133,13 @@ impl MemmapVectorStorage {
     }
 }
 
-impl VectorStorage for MemmapVectorStorage {
+impl<T: PrimitiveVectorElement> DenseVectorStorage<T> for MemmapVectorStorage<T> {
+    fn get_dense(&self, key: PointOffsetType) -> &[T] {
+        self.mmap_store.as_ref().unwrap().get_vector(key)
+    }
+}
+
+impl<T: PrimitiveVectorElement> VectorStorage for MemmapVectorStorage<T> {
     fn vector_dim(&self) -> usize {
         self.mmap_store.as_ref().unwrap().dim
     }
@@ -94,12 +148,16 @@ impl VectorStorage for MemmapVectorStorage {
         self.distance
     }
 
+    fn stores_original_vectors(&self) -> bool {
+        self.original_vectors
+    }
+
     fn total_vector_count(&self) -> usize {
         self.mmap_store.as_ref().unwrap().num_vectors
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.mmap_store.as_ref().unwrap().get_vector(key)
+    fn get_vector(&self, key: PointOffsetType) -> Cow<'_, [VectorElementType]> {
+        T::slice_to_float_cow(Cow::Borrowed(self.get_dense(key)))
     }
 
     fn insert_vector(
@@ -127,21 +185,22 @@ impl VectorStorage for MemmapVectorStorage {
             .unwrap_or(get_async_scorer());
 
         // Extend vectors file, write other vectors into it
-        let mut vectors_file = open_append(&self.vectors_path)?;
+        let mut vectors_file = BufWriter::new(open_append(&self.vectors_path)?);
         let mut deleted_ids = vec![];
         for id in other_ids {
             check_process_stopped(stopped)?;
-            let vector = other.get_vector(id);
-            let raw_bites = mmap_ops::transmute_to_u8_slice(vector);
+            let vector = T::slice_from_float_cow(other.get_vector(id));
+            let raw_bites = mmap_ops::transmute_to_u8_slice(vector.as_ref());
             vectors_file.write_all(raw_bites)?;
-            end_index += 1;
 
             // Remember deleted IDs so we can propagate deletions later
             if other.is_deleted_vector(id) {
-                deleted_ids.push((start_index + id) as PointOffsetType);
+                deleted_ids.push(end_index);
             }
+            end_index += 1;
         }
         vectors_file.flush()?;
+        vectors_file.get_ref().sync_all()?;
         drop(vectors_file);
 
         // Load store with updated files
@@ -182,6 +241,7 @@ impl VectorStorage for MemmapVectorStorage {
         let mmap_store = self.mmap_store.as_mut().unwrap();
         mmap_store.quantize(
             self.distance,
+            self.original_vectors,
             data_path,
             quantization_config,
             max_threads,
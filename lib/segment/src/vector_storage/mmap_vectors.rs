@@ -1,6 +1,7 @@
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::mem::{self, size_of, transmute};
+use std::marker::PhantomData;
+use std::mem::{self, size_of};
 use std::path::Path;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -13,12 +14,13 @@ use super::div_ceil;
 use crate::common::error_logging::LogError;
 use crate::common::mmap_type::MmapBitSlice;
 use crate::common::{mmap_ops, Flusher};
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationResult;
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
 use crate::vector_storage::async_io::UringReader;
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(all(target_os = "linux", feature = "io-uring")))]
 use crate::vector_storage::async_io_mock::UringReader;
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 
@@ -26,8 +28,11 @@ const HEADER_SIZE: usize = 4;
 const VECTORS_HEADER: &[u8; HEADER_SIZE] = b"data";
 const DELETED_HEADER: &[u8; HEADER_SIZE] = b"drop";
 
+/// Number of vectors to advise the kernel about before reading them, if io_uring is not used
+const PREFETCH_BATCH_SIZE: usize = 64;
+
 /// Mem-mapped file
-pub struct MmapVectors {
+pub struct MmapVectors<T: PrimitiveVectorElement> {
     pub dim: usize,
     pub num_vectors: usize,
     /// Memory mapped file for vector data
@@ -35,16 +40,17 @@ pub struct MmapVectors {
     /// Has an exact size to fit a header and `num_vectors` of vectors.
     mmap: Arc<Mmap>,
     /// Context for io_uring-base async IO
-    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    #[cfg_attr(not(all(target_os = "linux", feature = "io-uring")), allow(dead_code))]
     uring_reader: Mutex<Option<UringReader>>,
     /// Memory mapped deletion flags
     deleted: MmapBitSlice,
     /// Current number of deleted vectors.
     pub deleted_count: usize,
     pub quantized_vectors: Option<QuantizedVectors>,
+    _phantom: PhantomData<T>,
 }
 
-impl MmapVectors {
+impl<T: PrimitiveVectorElement> MmapVectors<T> {
     pub fn open(
         vectors_path: &Path,
         deleted_path: &Path,
@@ -55,7 +61,7 @@ impl MmapVectors {
         ensure_mmap_file_size(vectors_path, VECTORS_HEADER, None)
             .describe("Create mmap data file")?;
         let mmap = mmap_ops::open_read_mmap(vectors_path).describe("Open mmap for reading")?;
-        let num_vectors = (mmap.len() - HEADER_SIZE) / dim / size_of::<VectorElementType>();
+        let num_vectors = (mmap.len() - HEADER_SIZE) / dim / size_of::<T>();
 
         // Allocate/open deleted mmap
         let deleted_mmap_size = deleted_mmap_size(num_vectors);
@@ -77,7 +83,7 @@ impl MmapVectors {
         let uring_reader = if with_async_io {
             // Keep file handle open for async IO
             let vectors_file = File::open(vectors_path)?;
-            let raw_size = dim * size_of::<VectorElementType>();
+            let raw_size = dim * size_of::<T>();
             Some(UringReader::new(vectors_file, raw_size, HEADER_SIZE)?)
         } else {
             None
@@ -91,6 +97,7 @@ impl MmapVectors {
             deleted,
             deleted_count,
             quantized_vectors: None,
+            _phantom: PhantomData,
         })
     }
 
@@ -105,6 +112,7 @@ impl MmapVectors {
     pub fn quantize(
         &mut self,
         distance: Distance,
+        original_vectors: bool,
         data_path: &Path,
         quantization_config: &QuantizationConfig,
         max_threads: usize,
@@ -117,12 +125,19 @@ impl MmapVectors {
         // speedup is not measured explicitly.
         // See <https://github.com/qdrant/qdrant/pull/1885#issuecomment-1547408116>
 
-        let vector_data_iterator = (0..self.num_vectors as u32).map(|i| {
-            let offset = self.data_offset(i as PointOffsetType).unwrap_or_default();
-            self.raw_vector_offset(offset)
-        });
+        // Quantization consumes f32 slices, vectors of other types are converted upfront
+        let vectors: Vec<_> = (0..self.num_vectors as u32)
+            .map(|i| {
+                let offset = self.data_offset(i as PointOffsetType).unwrap_or_default();
+                T::quantization_preprocess(
+                    distance,
+                    original_vectors,
+                    self.raw_vector_offset(offset),
+                )
+            })
+            .collect();
         self.quantized_vectors = Some(QuantizedVectors::create(
-            vector_data_iterator,
+            vectors.iter().map(|vector| vector.as_ref()),
             quantization_config,
             distance,
             self.dim,
@@ -154,7 +169,7 @@ impl MmapVectors {
     }
 
     pub fn data_offset(&self, key: PointOffsetType) -> Option<usize> {
-        let vector_data_length = self.dim * size_of::<VectorElementType>();
+        let vector_data_length = self.dim * size_of::<T>();
         let offset = (key as usize) * vector_data_length + HEADER_SIZE;
         if key >= (self.num_vectors as PointOffsetType) {
             return None;
@@ -163,21 +178,42 @@ impl MmapVectors {
     }
 
     pub fn raw_size(&self) -> usize {
-        self.dim * size_of::<VectorElementType>()
+        self.dim * size_of::<T>()
     }
 
-    pub fn raw_vector_offset(&self, offset: usize) -> &[VectorElementType] {
+    pub fn raw_vector_offset(&self, offset: usize) -> &[T] {
         let byte_slice = &self.mmap[offset..(offset + self.raw_size())];
-        let arr: &[VectorElementType] = unsafe { transmute(byte_slice) };
-        &arr[0..self.dim]
+        mmap_ops::transmute_from_u8_to_slice(byte_slice)
     }
 
     /// Returns reference to vector data by key
-    pub fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
+    pub fn get_vector(&self, key: PointOffsetType) -> &[T] {
         let offset = self.data_offset(key).unwrap();
         self.raw_vector_offset(offset)
     }
 
+    /// Advise the kernel that vector data by key will be needed soon
+    ///
+    /// The kernel starts reading pages of the vector in background, so a following
+    /// [`Self::get_vector`] does not block on a page fault. On non-Unix platforms this is a no-op.
+    pub fn prefetch_vector(&self, key: PointOffsetType) {
+        let Some(offset) = self.data_offset(key) else {
+            return;
+        };
+
+        #[cfg(unix)]
+        if let Err(err) = self
+            .mmap
+            .advise_range(memmap2::Advice::WillNeed, offset, self.raw_size())
+        {
+            // Only a hint, the vector is still readable if it fails
+            log::trace!("Failed to advise MADV_WILLNEED for vector {key}: {err}");
+        }
+
+        #[cfg(not(unix))]
+        let _ = offset;
+    }
+
     pub fn delete(&mut self, key: PointOffsetType) -> bool {
         if self.num_vectors <= key as usize {
             return false;
@@ -205,8 +241,11 @@ impl MmapVectors {
     pub fn prefault_mmap_pages(&self, path: &Path) -> mmap_ops::PrefaultMmapPages {
         mmap_ops::PrefaultMmapPages::new(self.mmap.clone(), Some(path))
     }
+}
 
-    #[cfg(target_os = "linux")]
+/// Asynchronous IO reads vectors in [`VectorElementType`] only
+impl MmapVectors<VectorElementType> {
+    #[cfg(all(target_os = "linux", feature = "io-uring"))]
     fn process_points_uring(
         &self,
         points: impl Iterator<Item = PointOffsetType>,
@@ -219,35 +258,47 @@ impl MmapVectors {
             .read_stream(points, callback)
     }
 
-    #[cfg(not(target_os = "linux"))]
-    fn process_points_simple(
+    /// Reads vectors for the given ids in batches, advising the kernel to load all vectors of
+    /// a batch before reading any of them. This way page faults on cold mmap pages are served
+    /// by readahead in parallel, instead of one by one.
+    pub fn process_points_prefetch(
         &self,
         points: impl Iterator<Item = PointOffsetType>,
         mut callback: impl FnMut(usize, PointOffsetType, &[VectorElementType]),
     ) -> OperationResult<()> {
-        for (idx, point) in points.enumerate() {
-            let vector = self.get_vector(point);
-            callback(idx, point, vector);
+        let mut batch = Vec::with_capacity(PREFETCH_BATCH_SIZE);
+        let mut points = points.enumerate().peekable();
+        while points.peek().is_some() {
+            batch.clear();
+            batch.extend(points.by_ref().take(PREFETCH_BATCH_SIZE));
+
+            for &(_, point) in &batch {
+                self.prefetch_vector(point);
+            }
+            for &(idx, point) in &batch {
+                callback(idx, point, self.get_vector(point));
+            }
         }
         Ok(())
     }
 
     /// Reads vectors for the given ids and calls the callback for each vector.
     /// Tries to utilize asynchronous IO if possible.
-    /// In particular, uses io_uring on Linux and simple synchronous IO otherwise.
+    /// In particular, uses io_uring on Linux with the `io-uring` feature, and prefetches
+    /// vectors with `madvise` otherwise.
     pub fn read_vectors_async(
         &self,
         points: impl Iterator<Item = PointOffsetType>,
         callback: impl FnMut(usize, PointOffsetType, &[VectorElementType]),
     ) -> OperationResult<()> {
-        #[cfg(target_os = "linux")]
+        #[cfg(all(target_os = "linux", feature = "io-uring"))]
         {
             self.process_points_uring(points, callback)
         }
 
-        #[cfg(not(target_os = "linux"))]
+        #[cfg(not(all(target_os = "linux", feature = "io-uring")))]
         {
-            self.process_points_simple(points, callback)
+            self.process_points_prefetch(points, callback)
         }
     }
 }
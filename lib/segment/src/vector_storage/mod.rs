@@ -1,5 +1,4 @@
 pub mod appendable_mmap_vector_storage;
-#[cfg(target_os = "linux")]
 pub mod async_raw_scorer;
 mod chunked_mmap_vectors;
 mod chunked_utils;
@@ -7,16 +6,22 @@ pub mod chunked_vectors;
 mod dynamic_mmap_flags;
 pub mod memmap_vector_storage;
 mod mmap_vectors;
+pub mod multi_vector_raw_scorer;
 pub mod quantized;
+pub mod query;
+pub mod query_scorer;
 pub mod raw_scorer;
+pub mod simple_multi_vector_storage;
+pub mod simple_sparse_vector_storage;
 pub mod simple_vector_storage;
 mod vector_storage_base;
 
 #[cfg(test)]
 mod tests;
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
 mod async_io;
+#[cfg(not(all(target_os = "linux", feature = "io-uring")))]
 mod async_io_mock;
 pub mod common;
 
@@ -0,0 +1,218 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bitvec::prelude::BitSlice;
+
+use super::simple_multi_vector_storage::SimpleMultiVectorStorage;
+use super::{RawScorer, ScoredPointOffset, VectorStorage};
+use crate::data_types::vectors::VectorElementType;
+use crate::spaces::metric::Metric;
+use crate::spaces::simple::{
+    CosineMetric, DotProductMetric, EuclidMetric, HammingMetric, ManhattanMetric,
+    UnnormalizedCosineMetric,
+};
+use crate::spaces::tools::peek_top_largest_iterable;
+use crate::types::{Distance, MultiVectorComparator, PointOffsetType, ScoreType};
+
+/// Scorer of multi-vectors, which aggregates similarities between query and stored sub-vectors
+pub struct MultiVectorRawScorer<'a, TMetric: Metric> {
+    pub points_count: PointOffsetType,
+    /// Concatenated preprocessed sub-vectors of the query
+    pub query: Vec<VectorElementType>,
+    pub vector_storage: &'a SimpleMultiVectorStorage,
+    /// [`BitSlice`] defining flags for deleted points (and thus these vectors).
+    pub point_deleted: &'a BitSlice,
+    /// [`BitSlice`] defining flags for deleted vectors in this segment.
+    pub vec_deleted: &'a BitSlice,
+    pub metric: PhantomData<TMetric>,
+    /// This flag indicates that the search process is stopped externally,
+    /// the search result is no longer needed and the search process should be stopped as soon as possible.
+    pub is_stopped: &'a AtomicBool,
+}
+
+pub fn multi_vector_raw_scorer<'a>(
+    vector: Vec<VectorElementType>,
+    vector_storage: &'a SimpleMultiVectorStorage,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    match vector_storage.distance() {
+        Distance::Cosine if vector_storage.stores_original_vectors() => {
+            Box::new(MultiVectorRawScorer::<'a, UnnormalizedCosineMetric>::new(
+                vector,
+                vector_storage,
+                point_deleted,
+                is_stopped,
+            ))
+        }
+        Distance::Cosine => Box::new(MultiVectorRawScorer::<'a, CosineMetric>::new(
+            vector,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        )),
+        Distance::Euclid => Box::new(MultiVectorRawScorer::<'a, EuclidMetric>::new(
+            vector,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        )),
+        Distance::Dot => Box::new(MultiVectorRawScorer::<'a, DotProductMetric>::new(
+            vector,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        )),
+        Distance::Manhattan => Box::new(MultiVectorRawScorer::<'a, ManhattanMetric>::new(
+            vector,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        )),
+        Distance::Hamming => Box::new(MultiVectorRawScorer::<'a, HammingMetric>::new(
+            vector,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        )),
+    }
+}
+
+/// Sum of the best similarities of each query sub-vector with any of the stored sub-vectors
+///
+/// Known as late interaction scoring in ColBERT.
+pub fn max_sim<TMetric: Metric>(
+    query: &[VectorElementType],
+    other: &[VectorElementType],
+    dim: usize,
+) -> ScoreType {
+    query
+        .chunks_exact(dim)
+        .map(|query_vector| {
+            other
+                .chunks_exact(dim)
+                .map(|other_vector| TMetric::similarity(query_vector, other_vector))
+                .fold(ScoreType::NEG_INFINITY, ScoreType::max)
+        })
+        .sum()
+}
+
+impl<'a, TMetric: Metric> MultiVectorRawScorer<'a, TMetric> {
+    pub fn new(
+        vector: Vec<VectorElementType>,
+        vector_storage: &'a SimpleMultiVectorStorage,
+        point_deleted: &'a BitSlice,
+        is_stopped: &'a AtomicBool,
+    ) -> Self {
+        let query = vector
+            .chunks_exact(vector_storage.vector_dim())
+            .flat_map(|query_vector| {
+                TMetric::preprocess(query_vector).unwrap_or_else(|| query_vector.to_vec())
+            })
+            .collect();
+        Self {
+            points_count: vector_storage.total_vector_count() as PointOffsetType,
+            query,
+            vector_storage,
+            point_deleted,
+            vec_deleted: vector_storage.deleted_vector_bitslice(),
+            metric: PhantomData,
+            is_stopped,
+        }
+    }
+
+    fn similarity(&self, v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        match self.vector_storage.multivector_config().comparator {
+            MultiVectorComparator::MaxSim => {
+                max_sim::<TMetric>(v1, v2, self.vector_storage.vector_dim())
+            }
+        }
+    }
+
+    fn score(&self, point: PointOffsetType) -> ScoredPointOffset {
+        ScoredPointOffset {
+            idx: point,
+            score: self.score_point(point),
+        }
+    }
+}
+
+impl<'a, TMetric: Metric> RawScorer for MultiVectorRawScorer<'a, TMetric> {
+    fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
+        if self.is_stopped.load(Ordering::Relaxed) {
+            return 0;
+        }
+        let mut size: usize = 0;
+        for point_id in points.iter().copied() {
+            if !self.check_vector(point_id) {
+                continue;
+            }
+            scores[size] = self.score(point_id);
+
+            size += 1;
+            if size == scores.len() {
+                return size;
+            }
+        }
+        size
+    }
+
+    fn score_points_unfiltered(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+    ) -> Vec<ScoredPointOffset> {
+        if self.is_stopped.load(Ordering::Relaxed) {
+            return vec![];
+        }
+        points.map(|point_id| self.score(point_id)).collect()
+    }
+
+    fn check_vector(&self, point: PointOffsetType) -> bool {
+        point < self.points_count
+            // Deleted points propagate to vectors; check vector deletion for possible early return
+            && !self
+                .vec_deleted
+                .get(point as usize)
+                .map(|x| *x)
+                // Default to not deleted if our deleted flags failed grow
+                .unwrap_or(false)
+            // Additionally check point deletion for integrity if delete propagation to vector failed
+            && !self
+                .point_deleted
+                .get(point as usize)
+                .map(|x| *x)
+                // Default to deleted if the point mapping was removed from the ID tracker
+                .unwrap_or(true)
+    }
+
+    fn score_point(&self, point: PointOffsetType) -> ScoreType {
+        self.similarity(&self.query, self.vector_storage.get_multi(point))
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        self.similarity(
+            self.vector_storage.get_multi(point_a),
+            self.vector_storage.get_multi(point_b),
+        )
+    }
+
+    fn peek_top_iter(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        let scores = points
+            .take_while(|_| !self.is_stopped.load(Ordering::Relaxed))
+            .filter(|point_id| self.check_vector(*point_id))
+            .map(|point_id| self.score(point_id));
+        peek_top_largest_iterable(scores, top)
+    }
+
+    fn peek_top_all(&self, top: usize) -> Vec<ScoredPointOffset> {
+        let scores = (0..self.points_count)
+            .take_while(|_| !self.is_stopped.load(Ordering::Relaxed))
+            .filter(|point_id| self.check_vector(*point_id))
+            .map(|point_id| self.score(point_id));
+        peek_top_largest_iterable(scores, top)
+    }
+}
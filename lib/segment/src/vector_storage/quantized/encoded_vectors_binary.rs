@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use quantization::{
+    DistanceType, EncodedStorage, EncodedStorageBuilder, EncodedVectors, EncodingError,
+    VectorParameters,
+};
+use serde::{Deserialize, Serialize};
+
+use super::xor_popcount::xor_popcount;
+use crate::common::file_operations::{atomic_save_json, read_json};
+use crate::vector_storage::div_ceil;
+
+/// Vectors quantized into a single bit per dimension, which is the sign of the component.
+///
+/// Score is approximated by the Hamming distance between bit vectors,
+/// so it only makes sense for high-dimensional vectors with zero-centered components.
+pub struct EncodedVectorsBin<TStorage: EncodedStorage> {
+    encoded_vectors: TStorage,
+    metadata: Metadata,
+}
+
+pub struct EncodedBinQuery {
+    encoded_vector: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Metadata {
+    vector_parameters: VectorParameters,
+}
+
+impl<TStorage: EncodedStorage> EncodedVectorsBin<TStorage> {
+    pub fn encode<'a>(
+        orig_data: impl Iterator<Item = &'a [f32]>,
+        mut storage_builder: impl EncodedStorageBuilder<TStorage>,
+        vector_parameters: &VectorParameters,
+        stop_condition: impl Fn() -> bool,
+    ) -> Result<Self, EncodingError> {
+        for vector in orig_data {
+            if stop_condition() {
+                return Err(EncodingError::Stopped);
+            }
+            storage_builder.push_vector_data(&Self::encode_vector(vector));
+        }
+
+        Ok(Self {
+            encoded_vectors: storage_builder.build(),
+            metadata: Metadata {
+                vector_parameters: vector_parameters.clone(),
+            },
+        })
+    }
+
+    pub fn get_quantized_vector_size(vector_parameters: &VectorParameters) -> usize {
+        div_ceil(vector_parameters.dim, u8::BITS as usize)
+    }
+
+    fn encode_vector(vector: &[f32]) -> Vec<u8> {
+        vector
+            .chunks(u8::BITS as usize)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &value)| value > 0.0)
+                    .fold(0u8, |byte, (bit, _)| byte | (1 << bit))
+            })
+            .collect()
+    }
+
+    fn quantized_vector_size(&self) -> usize {
+        Self::get_quantized_vector_size(&self.metadata.vector_parameters)
+    }
+
+    fn score_bytes(&self, v1: &[u8], v2: &[u8]) -> f32 {
+        let parameters = &self.metadata.vector_parameters;
+        let hamming_distance = xor_popcount(v1, v2);
+        let score = match parameters.distance_type {
+            // Matching signs increase the product, different signs decrease it
+            DistanceType::Dot => parameters.dim as f32 - 2.0 * hamming_distance as f32,
+            DistanceType::L1 | DistanceType::L2 => hamming_distance as f32,
+        };
+        if parameters.invert {
+            -score
+        } else {
+            score
+        }
+    }
+}
+
+impl<TStorage: EncodedStorage> EncodedVectors<EncodedBinQuery> for EncodedVectorsBin<TStorage> {
+    fn save(&self, data_path: &Path, meta_path: &Path) -> std::io::Result<()> {
+        self.encoded_vectors.save_to_file(data_path)?;
+        atomic_save_json(meta_path, &self.metadata)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err.to_string()))
+    }
+
+    fn load(
+        data_path: &Path,
+        meta_path: &Path,
+        vector_parameters: &VectorParameters,
+    ) -> std::io::Result<Self> {
+        let metadata: Metadata = read_json(meta_path)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err.to_string()))?;
+        let quantized_vector_size = Self::get_quantized_vector_size(vector_parameters);
+        let encoded_vectors =
+            TStorage::from_file(data_path, quantized_vector_size, vector_parameters.count)?;
+        Ok(Self {
+            encoded_vectors,
+            metadata,
+        })
+    }
+
+    fn encode_query(&self, query: &[f32]) -> EncodedBinQuery {
+        EncodedBinQuery {
+            encoded_vector: Self::encode_vector(query),
+        }
+    }
+
+    fn score_point(&self, query: &EncodedBinQuery, i: u32) -> f32 {
+        let vector = self
+            .encoded_vectors
+            .get_vector_data(i as usize, self.quantized_vector_size());
+        self.score_bytes(&query.encoded_vector, vector)
+    }
+
+    fn score_internal(&self, i: u32, j: u32) -> f32 {
+        let size = self.quantized_vector_size();
+        let v1 = self.encoded_vectors.get_vector_data(i as usize, size);
+        let v2 = self.encoded_vectors.get_vector_data(j as usize, size);
+        self.score_bytes(v1, v2)
+    }
+}
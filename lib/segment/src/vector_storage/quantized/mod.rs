@@ -1,3 +1,5 @@
+mod encoded_vectors_binary;
 mod quantized_mmap_storage;
 mod quantized_raw_scorer;
 pub mod quantized_vectors;
+mod xor_popcount;
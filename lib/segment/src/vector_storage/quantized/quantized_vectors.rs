@@ -5,18 +5,21 @@ use bitvec::slice::BitSlice;
 use quantization::{EncodedVectors, EncodedVectorsPQ, EncodedVectorsU8};
 use serde::{Deserialize, Serialize};
 
+use super::encoded_vectors_binary::EncodedVectorsBin;
 use super::quantized_raw_scorer::QuantizedRawScorer;
 use crate::common::file_operations::{atomic_save_json, read_json};
 use crate::common::vector_utils::TrySetCapacityExact;
-use crate::data_types::vectors::VectorElementType;
-use crate::entry::entry_point::OperationResult;
+use crate::data_types::vectors::{QueryVector, VectorElementType};
+use crate::entry::entry_point::{OperationError, OperationResult};
 use crate::types::{
-    CompressionRatio, Distance, ProductQuantization, QuantizationConfig, ScalarQuantization,
+    BinaryQuantization, CompressionRatio, Distance, PointOffsetType, ProductQuantization,
+    QuantizationConfig, ScalarQuantization,
 };
 use crate::vector_storage::chunked_vectors::ChunkedVectors;
 use crate::vector_storage::quantized::quantized_mmap_storage::{
     QuantizedMmapStorage, QuantizedMmapStorageBuilder,
 };
+use crate::vector_storage::query_scorer::query_raw_scorer;
 use crate::vector_storage::RawScorer;
 
 pub const QUANTIZED_CONFIG_PATH: &str = "quantized.config.json";
@@ -34,6 +37,8 @@ pub enum QuantizedVectorStorage {
     ScalarMmap(EncodedVectorsU8<QuantizedMmapStorage>),
     PQRam(EncodedVectorsPQ<ChunkedVectors<u8>>),
     PQMmap(EncodedVectorsPQ<QuantizedMmapStorage>),
+    BinaryRam(EncodedVectorsBin<ChunkedVectors<u8>>),
+    BinaryMmap(EncodedVectorsBin<QuantizedMmapStorage>),
 }
 
 pub struct QuantizedVectors {
@@ -96,9 +101,47 @@ impl QuantizedVectors {
                     is_stopped,
                 })
             }
+            QuantizedVectorStorage::BinaryRam(storage) => {
+                let query = storage.encode_query(&query);
+                Box::new(QuantizedRawScorer {
+                    query,
+                    point_deleted,
+                    vec_deleted,
+                    quantized_data: storage,
+                    is_stopped,
+                })
+            }
+            QuantizedVectorStorage::BinaryMmap(storage) => {
+                let query = storage.encode_query(&query);
+                Box::new(QuantizedRawScorer {
+                    query,
+                    point_deleted,
+                    vec_deleted,
+                    quantized_data: storage,
+                    is_stopped,
+                })
+            }
         }
     }
 
+    /// Raw scorer of the query over quantized vectors, see [`QuantizedVectors::raw_scorer`]
+    pub fn query_scorer<'a>(
+        &'a self,
+        query: &QueryVector,
+        point_deleted: &'a BitSlice,
+        vec_deleted: &'a BitSlice,
+        is_stopped: &'a AtomicBool,
+    ) -> Box<dyn RawScorer + 'a> {
+        query_raw_scorer(
+            query,
+            point_deleted.len() as PointOffsetType,
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+            |vector| self.raw_scorer(vector, point_deleted, vec_deleted, is_stopped),
+        )
+    }
+
     pub fn save_to(&self, path: &Path) -> OperationResult<()> {
         let data_path = path.join(QUANTIZED_DATA_PATH);
         let meta_path = path.join(QUANTIZED_META_PATH);
@@ -107,6 +150,8 @@ impl QuantizedVectors {
             QuantizedVectorStorage::ScalarMmap(storage) => storage.save(&data_path, &meta_path)?,
             QuantizedVectorStorage::PQRam(storage) => storage.save(&data_path, &meta_path)?,
             QuantizedVectorStorage::PQMmap(storage) => storage.save(&data_path, &meta_path)?,
+            QuantizedVectorStorage::BinaryRam(storage) => storage.save(&data_path, &meta_path)?,
+            QuantizedVectorStorage::BinaryMmap(storage) => storage.save(&data_path, &meta_path)?,
         };
         Ok(())
     }
@@ -134,6 +179,9 @@ impl QuantizedVectors {
         max_threads: usize,
         stopped: &AtomicBool,
     ) -> OperationResult<Self> {
+        quantization_config
+            .check_distance(distance)
+            .map_err(|description| OperationError::ValidationError { description })?;
         let vector_parameters = Self::construct_vector_parameters(distance, dim, count);
 
         let quantized_storage = match quantization_config {
@@ -158,6 +206,21 @@ impl QuantizedVectors {
                     stopped,
                 )?
             }
+            QuantizationConfig::Binary(BinaryQuantization {
+                binary: binary_config,
+            }) => {
+                quantization_config
+                    .check_vector_dim(dim)
+                    .map_err(|description| OperationError::ValidationError { description })?;
+                Self::crate_binary(
+                    vectors,
+                    &vector_parameters,
+                    binary_config,
+                    path,
+                    on_disk_vector_storage,
+                    stopped,
+                )?
+            }
         };
 
         let quantized_vectors_config = QuantizedVectorsConfig {
@@ -223,6 +286,25 @@ impl QuantizedVectors {
                     )?)
                 }
             }
+            QuantizationConfig::Binary(BinaryQuantization { binary }) => {
+                if Self::is_ram(binary.always_ram, on_disk_vector_storage) {
+                    QuantizedVectorStorage::BinaryRam(
+                        EncodedVectorsBin::<ChunkedVectors<u8>>::load(
+                            &data_path,
+                            &meta_path,
+                            &config.vector_parameters,
+                        )?,
+                    )
+                } else {
+                    QuantizedVectorStorage::BinaryMmap(
+                        EncodedVectorsBin::<QuantizedMmapStorage>::load(
+                            &data_path,
+                            &meta_path,
+                            &config.vector_parameters,
+                        )?,
+                    )
+                }
+            }
         };
 
         Ok(QuantizedVectors {
@@ -318,6 +400,40 @@ impl QuantizedVectors {
         }
     }
 
+    fn crate_binary<'a>(
+        vectors: impl Iterator<Item = &'a [f32]> + Clone,
+        vector_parameters: &quantization::VectorParameters,
+        binary_config: &crate::types::BinaryQuantizationConfig,
+        path: &Path,
+        on_disk_vector_storage: bool,
+        stopped: &AtomicBool,
+    ) -> OperationResult<QuantizedVectorStorage> {
+        let quantized_vector_size =
+            EncodedVectorsBin::<QuantizedMmapStorage>::get_quantized_vector_size(vector_parameters);
+        let in_ram = Self::is_ram(binary_config.always_ram, on_disk_vector_storage);
+        if in_ram {
+            let mut storage_builder = ChunkedVectors::<u8>::new(quantized_vector_size);
+            storage_builder.try_set_capacity_exact(vector_parameters.count)?;
+            Ok(QuantizedVectorStorage::BinaryRam(
+                EncodedVectorsBin::encode(vectors, storage_builder, vector_parameters, || {
+                    stopped.load(Ordering::Relaxed)
+                })?,
+            ))
+        } else {
+            let mmap_data_path = path.join(QUANTIZED_DATA_PATH);
+            let storage_builder = QuantizedMmapStorageBuilder::new(
+                mmap_data_path.as_path(),
+                vector_parameters.count,
+                quantized_vector_size,
+            )?;
+            Ok(QuantizedVectorStorage::BinaryMmap(
+                EncodedVectorsBin::encode(vectors, storage_builder, vector_parameters, || {
+                    stopped.load(Ordering::Relaxed)
+                })?,
+            ))
+        }
+    }
+
     fn is_ram(always_ram: Option<bool>, on_disk_vector_storage: bool) -> bool {
         !on_disk_vector_storage || always_ram == Some(true)
     }
@@ -334,8 +450,12 @@ impl QuantizedVectors {
                 Distance::Cosine => quantization::DistanceType::Dot,
                 Distance::Euclid => quantization::DistanceType::L2,
                 Distance::Dot => quantization::DistanceType::Dot,
+                Distance::Manhattan => quantization::DistanceType::L1,
+                Distance::Hamming => {
+                    unreachable!("quantization is rejected for Hamming distance on creation")
+                }
             },
-            invert: distance == Distance::Euclid,
+            invert: distance == Distance::Euclid || distance == Distance::Manhattan,
         }
     }
 
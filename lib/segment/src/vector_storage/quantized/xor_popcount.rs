@@ -0,0 +1,147 @@
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[cfg(target_arch = "x86_64")]
+const MIN_SIZE_AVX: usize = 32;
+
+#[cfg(target_arch = "aarch64")]
+const MIN_SIZE_NEON: usize = 16;
+
+/// Number of different bits in two equally sized bit vectors
+pub fn xor_popcount(v1: &[u8], v2: &[u8]) -> usize {
+    debug_assert_eq!(v1.len(), v2.len());
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx2") && v1.len() >= MIN_SIZE_AVX {
+            return unsafe { xor_popcount_avx2(v1, v2) };
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") && v1.len() >= MIN_SIZE_NEON {
+            return unsafe { xor_popcount_neon(v1, v2) };
+        }
+    }
+
+    xor_popcount_scalar(v1, v2)
+}
+
+pub(crate) fn xor_popcount_scalar(v1: &[u8], v2: &[u8]) -> usize {
+    let chunks1 = v1.chunks_exact(8);
+    let chunks2 = v2.chunks_exact(8);
+    let tail: usize = chunks1
+        .remainder()
+        .iter()
+        .zip(chunks2.remainder())
+        .map(|(a, b)| (a ^ b).count_ones() as usize)
+        .sum();
+    let body: usize = chunks1
+        .zip(chunks2)
+        .map(|(a, b)| {
+            let a = u64::from_le_bytes(a.try_into().unwrap());
+            let b = u64::from_le_bytes(b.try_into().unwrap());
+            (a ^ b).count_ones() as usize
+        })
+        .sum();
+    body + tail
+}
+
+/// AVX2 has no popcount instruction, so bits are counted per nibble with a lookup table.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn xor_popcount_avx2(v1: &[u8], v2: &[u8]) -> usize {
+    let n = v1.len();
+    let m = n - (n % 32);
+    let ptr1 = v1.as_ptr();
+    let ptr2 = v2.as_ptr();
+
+    #[rustfmt::skip]
+    let lookup = _mm256_setr_epi8(
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+    );
+    let low_mask = _mm256_set1_epi8(0x0f);
+    let zero = _mm256_setzero_si256();
+    let mut acc = _mm256_setzero_si256();
+
+    let mut i: usize = 0;
+    while i < m {
+        let a = _mm256_loadu_si256(ptr1.add(i) as *const __m256i);
+        let b = _mm256_loadu_si256(ptr2.add(i) as *const __m256i);
+        let x = _mm256_xor_si256(a, b);
+        let low = _mm256_and_si256(x, low_mask);
+        let high = _mm256_and_si256(_mm256_srli_epi16(x, 4), low_mask);
+        let counts = _mm256_add_epi8(
+            _mm256_shuffle_epi8(lookup, low),
+            _mm256_shuffle_epi8(lookup, high),
+        );
+        acc = _mm256_add_epi64(acc, _mm256_sad_epu8(counts, zero));
+        i += 32;
+    }
+
+    let mut sums = [0u64; 4];
+    _mm256_storeu_si256(sums.as_mut_ptr() as *mut __m256i, acc);
+    let body: u64 = sums.iter().sum();
+    body as usize + xor_popcount_scalar(&v1[m..], &v2[m..])
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn xor_popcount_neon(v1: &[u8], v2: &[u8]) -> usize {
+    let n = v1.len();
+    let m = n - (n % 16);
+    let ptr1 = v1.as_ptr();
+    let ptr2 = v2.as_ptr();
+
+    let mut body: usize = 0;
+    let mut i: usize = 0;
+    while i < m {
+        let x = veorq_u8(vld1q_u8(ptr1.add(i)), vld1q_u8(ptr2.add(i)));
+        body += vaddlvq_u8(vcntq_u8(x)) as usize;
+        i += 16;
+    }
+
+    body + xor_popcount_scalar(&v1[m..], &v2[m..])
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn naive_xor_popcount(v1: &[u8], v2: &[u8]) -> usize {
+        v1.iter()
+            .zip(v2)
+            .map(|(a, b)| (0..8).filter(|bit| (a ^ b) & (1 << bit) != 0).count())
+            .sum()
+    }
+
+    #[test]
+    fn test_xor_popcount() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for len in [0, 1, 7, 8, 15, 16, 31, 32, 33, 96, 100, 128, 200] {
+            let v1: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let v2: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let expected = naive_xor_popcount(&v1, &v2);
+
+            assert_eq!(xor_popcount_scalar(&v1, &v2), expected);
+            assert_eq!(xor_popcount(&v1, &v2), expected);
+
+            #[cfg(target_arch = "x86_64")]
+            if is_x86_feature_detected!("avx2") {
+                assert_eq!(unsafe { xor_popcount_avx2(&v1, &v2) }, expected);
+            }
+
+            #[cfg(target_arch = "aarch64")]
+            if std::arch::is_aarch64_feature_detected!("neon") {
+                assert_eq!(unsafe { xor_popcount_neon(&v1, &v2) }, expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use super::Query;
+use crate::types::ScoreType;
+
+/// Pair of examples, which splits the space into the region of points more similar to the
+/// positive example, and the region of points more similar to the negative one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextPair<T> {
+    pub positive: T,
+    pub negative: T,
+}
+
+impl<T> ContextPair<T> {
+    pub fn new(positive: T, negative: T) -> Self {
+        Self { positive, negative }
+    }
+
+    /// Same pair, with both examples converted by `f`
+    pub fn transform<U>(&self, mut f: impl FnMut(&T) -> U) -> ContextPair<U> {
+        ContextPair {
+            positive: f(&self.positive),
+            negative: f(&self.negative),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        [&self.positive, &self.negative].into_iter()
+    }
+
+    /// Hinge loss of a point: zero in the positive region, otherwise how much the negative
+    /// example is more similar to the point than the positive one (as a negative number).
+    pub fn loss_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType {
+        (similarity(&self.positive) - similarity(&self.negative)).min(0.0)
+    }
+}
+
+/// Discovery of points similar to the target, constrained by context pairs.
+///
+/// Points are ranked by the number of pairs they have a loss for, see [`ContextPair::loss_by`]:
+/// every such pair costs a whole point of the score. Within the same rank, points are ordered
+/// by their similarity with the target, mapped into `(0, 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryQuery<T> {
+    pub target: T,
+    pub pairs: Vec<ContextPair<T>>,
+}
+
+impl<T> DiscoveryQuery<T> {
+    pub fn new(target: T, pairs: Vec<ContextPair<T>>) -> Self {
+        Self { target, pairs }
+    }
+
+    /// Same query, with every example converted by `f`
+    pub fn transform<U>(&self, mut f: impl FnMut(&T) -> U) -> DiscoveryQuery<U> {
+        DiscoveryQuery {
+            target: f(&self.target),
+            pairs: self
+                .pairs
+                .iter()
+                .map(|pair| pair.transform(&mut f))
+                .collect(),
+        }
+    }
+
+    pub fn iter_examples(&self) -> impl Iterator<Item = &T> {
+        std::iter::once(&self.target).chain(self.pairs.iter().flat_map(ContextPair::iter))
+    }
+}
+
+/// Monotonic mapping of a similarity into `(0, 1)`
+fn sigmoid(similarity: ScoreType) -> ScoreType {
+    0.5 * (similarity / (1.0 + similarity.abs()) + 1.0)
+}
+
+impl<T> Query<T> for DiscoveryQuery<T> {
+    fn score_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType {
+        let violated_pairs = self
+            .pairs
+            .iter()
+            .filter(|pair| pair.loss_by(&similarity) < 0.0)
+            .count();
+        sigmoid(similarity(&self.target)) - violated_pairs as ScoreType
+    }
+
+    fn first_example(&self) -> Option<&T> {
+        Some(&self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_loss() {
+        // Example values act as scores themselves
+        let pair = ContextPair::new(3.0, 1.0);
+        assert_eq!(pair.loss_by(|example| *example), 0.0);
+
+        let pair = ContextPair::new(1.0, 3.5);
+        assert_eq!(pair.loss_by(|example| *example), -2.5);
+
+        // Equal similarities are on the border of the positive region
+        let pair = ContextPair::new(2.0, 2.0);
+        assert_eq!(pair.loss_by(|example| *example), 0.0);
+    }
+
+    #[test]
+    fn test_sigmoid() {
+        assert_eq!(sigmoid(0.0), 0.5);
+        assert_eq!(sigmoid(1.0), 0.75);
+        assert_eq!(sigmoid(-3.0), 0.125);
+    }
+
+    #[test]
+    fn test_score_by_target_within_region() {
+        let query = DiscoveryQuery::new(1.0, vec![ContextPair::new(2.0, -1.0)]);
+        assert_eq!(query.score_by(|example| *example), 0.75);
+
+        // No pairs violated, only the target matters
+        let query = DiscoveryQuery::new(-3.0, vec![ContextPair::new(2.0, -1.0)]);
+        assert_eq!(query.score_by(|example| *example), 0.125);
+    }
+
+    #[test]
+    fn test_score_by_violated_pairs() {
+        let pairs = vec![
+            ContextPair::new(2.0, -1.0),
+            ContextPair::new(-1.0, 2.0),
+            ContextPair::new(0.0, 0.5),
+        ];
+        let query = DiscoveryQuery::new(1.0, pairs);
+        // Two pairs violated
+        assert_eq!(query.score_by(|example| *example), 0.75 - 2.0);
+
+        // Even the least similar target in the region beats the most similar one outside
+        let inside = DiscoveryQuery::new(-1000.0, vec![ContextPair::new(1.0, 0.0)]);
+        let outside = DiscoveryQuery::new(1000.0, vec![ContextPair::new(0.0, 1.0)]);
+        assert!(inside.score_by(|example| *example) > outside.score_by(|example| *example));
+    }
+
+    #[test]
+    fn test_transform() {
+        let query = DiscoveryQuery::new(1, vec![ContextPair::new(2, 3), ContextPair::new(4, 5)]);
+        let transformed = query.transform(|example| example * 10);
+        assert_eq!(
+            transformed,
+            DiscoveryQuery::new(10, vec![ContextPair::new(20, 30), ContextPair::new(40, 50)])
+        );
+        assert_eq!(
+            transformed.iter_examples().copied().collect::<Vec<_>>(),
+            vec![10, 20, 30, 40, 50]
+        );
+    }
+}
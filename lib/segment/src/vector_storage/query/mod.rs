@@ -0,0 +1,13 @@
+pub mod discovery_query;
+pub mod reco_query;
+
+use crate::types::ScoreType;
+
+/// Query, which compares points with several example vectors of type `T`
+pub trait Query<T> {
+    /// Combined score of a point, given a function which compares the point with a single example
+    fn score_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType;
+
+    /// Any example of the query, `None` if the query has no examples
+    fn first_example(&self) -> Option<&T>;
+}
@@ -0,0 +1,82 @@
+use super::Query;
+use crate::types::ScoreType;
+
+/// Recommendation by examples.
+///
+/// Point is scored by its most similar positive example, minus the score of its most similar
+/// negative example. Missing examples of either kind contribute zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoQuery<T> {
+    pub positives: Vec<T>,
+    pub negatives: Vec<T>,
+}
+
+impl<T> RecoQuery<T> {
+    pub fn new(positives: Vec<T>, negatives: Vec<T>) -> Self {
+        Self {
+            positives,
+            negatives,
+        }
+    }
+
+    /// Same query, with every example converted by `f`
+    pub fn transform<U>(&self, mut f: impl FnMut(&T) -> U) -> RecoQuery<U> {
+        RecoQuery {
+            positives: self.positives.iter().map(&mut f).collect(),
+            negatives: self.negatives.iter().map(&mut f).collect(),
+        }
+    }
+
+    pub fn iter_examples(&self) -> impl Iterator<Item = &T> {
+        self.positives.iter().chain(&self.negatives)
+    }
+}
+
+impl<T> Query<T> for RecoQuery<T> {
+    fn score_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType {
+        let best_score = |examples: &[T]| {
+            examples
+                .iter()
+                .map(&similarity)
+                .max_by(|a, b| a.total_cmp(b))
+                .unwrap_or(0.0)
+        };
+        best_score(&self.positives) - best_score(&self.negatives)
+    }
+
+    fn first_example(&self) -> Option<&T> {
+        self.iter_examples().next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_positive_minus_best_negative() {
+        let query = RecoQuery::new(vec![1.0, 3.0], vec![-1.0, 0.5]);
+        // Example values act as scores themselves
+        assert_eq!(query.score_by(|example| *example), 3.0 - 0.5);
+        assert_eq!(query.score_by(|example| example * 2.0), 6.0 - 1.0);
+    }
+
+    #[test]
+    fn test_missing_examples_contribute_zero() {
+        let only_positive = RecoQuery::new(vec![2.0, 1.0], vec![]);
+        assert_eq!(only_positive.score_by(|example| *example), 2.0);
+
+        let only_negative = RecoQuery::new(vec![], vec![2.0, 1.0]);
+        assert_eq!(only_negative.score_by(|example| *example), -2.0);
+    }
+
+    #[test]
+    fn test_transform() {
+        let query = RecoQuery::new(vec![1, 2], vec![3]);
+        let transformed = query.transform(|example| example * 10);
+        assert_eq!(transformed, RecoQuery::new(vec![10, 20], vec![30]));
+        assert_eq!(transformed.iter_examples().count(), 3);
+        assert_eq!(transformed.first_example(), Some(&10));
+        assert_eq!(RecoQuery::<i32>::new(vec![], vec![]).first_example(), None);
+    }
+}
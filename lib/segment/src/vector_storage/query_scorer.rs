@@ -0,0 +1,152 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bitvec::prelude::BitSlice;
+
+use super::query::Query;
+use super::{RawScorer, ScoredPointOffset};
+use crate::data_types::vectors::{QueryVector, VectorElementType};
+use crate::spaces::tools::peek_top_largest_iterable;
+use crate::types::{PointOffsetType, ScoreType};
+
+/// Raw scorer of a custom query.
+///
+/// Holds a raw scorer for every example vector of the query, and combines their scores
+/// according to the query.
+pub struct QueryRawScorer<'a, TQuery>
+where
+    TQuery: Query<Box<dyn RawScorer + 'a>>,
+{
+    pub query: TQuery,
+    pub points_count: PointOffsetType,
+    /// [`BitSlice`] defining flags for deleted points (and thus these vectors).
+    pub point_deleted: &'a BitSlice,
+    /// [`BitSlice`] defining flags for deleted vectors in this segment.
+    pub vec_deleted: &'a BitSlice,
+    /// This flag indicates that the search process is stopped externally,
+    /// the search result is no longer needed and the search process should be stopped as soon as possible.
+    pub is_stopped: &'a AtomicBool,
+}
+
+/// Creates a raw scorer for the given query.
+///
+/// Nearest queries get the raw scorer created by `raw_scorer`, other queries combine raw scorers
+/// created by `raw_scorer` for each of their example vectors. Sample queries can't be scored, and
+/// must be handled by the segment before creating scorers.
+pub fn query_raw_scorer<'a>(
+    query: &QueryVector,
+    points_count: PointOffsetType,
+    point_deleted: &'a BitSlice,
+    vec_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+    mut raw_scorer: impl FnMut(&[VectorElementType]) -> Box<dyn RawScorer + 'a>,
+) -> Box<dyn RawScorer + 'a> {
+    match query {
+        QueryVector::Nearest(vector) => raw_scorer(vector),
+        QueryVector::Recommend(reco_query) => Box::new(QueryRawScorer {
+            query: reco_query.transform(|example| raw_scorer(example)),
+            points_count,
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        }),
+        QueryVector::Discovery(discovery_query) => Box::new(QueryRawScorer {
+            query: discovery_query.transform(|example| raw_scorer(example)),
+            points_count,
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        }),
+        QueryVector::Sample(_) => unreachable!("sampled points are not scored by vectors"),
+    }
+}
+
+impl<'a, TQuery> RawScorer for QueryRawScorer<'a, TQuery>
+where
+    TQuery: Query<Box<dyn RawScorer + 'a>>,
+{
+    fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
+        if self.is_stopped.load(Ordering::Relaxed) {
+            return 0;
+        }
+        let mut size: usize = 0;
+        for point_id in points.iter().copied() {
+            if !self.check_vector(point_id) {
+                continue;
+            }
+            scores[size] = ScoredPointOffset {
+                idx: point_id,
+                score: self.score_point(point_id),
+            };
+            size += 1;
+            if size == scores.len() {
+                return size;
+            }
+        }
+        size
+    }
+
+    fn score_points_unfiltered(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+    ) -> Vec<ScoredPointOffset> {
+        if self.is_stopped.load(Ordering::Relaxed) {
+            return vec![];
+        }
+        points
+            .map(|idx| ScoredPointOffset {
+                idx,
+                score: self.score_point(idx),
+            })
+            .collect()
+    }
+
+    fn check_vector(&self, point: PointOffsetType) -> bool {
+        point < self.points_count
+            // Deleted points propagate to vectors; check vector deletion for possible early return
+            && !self
+                .vec_deleted
+                .get(point as usize)
+                .map(|x| *x)
+                // Default to not deleted if our deleted flags failed grow
+                .unwrap_or(false)
+            // Additionally check point deletion for integrity if delete propagation to vector failed
+            && !self
+                .point_deleted
+                .get(point as usize)
+                .map(|x| *x)
+                // Default to deleted if the point mapping was removed from the ID tracker
+                .unwrap_or(true)
+    }
+
+    fn score_point(&self, point: PointOffsetType) -> ScoreType {
+        self.query
+            .score_by(|example_scorer| example_scorer.score_point(point))
+    }
+
+    /// Similarity of stored points doesn't depend on the query, so any example scorer computes it.
+    /// Query without examples scores every point as zero, so do the stored points.
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        self.query.first_example().map_or(0.0, |example_scorer| {
+            example_scorer.score_internal(point_a, point_b)
+        })
+    }
+
+    fn peek_top_iter(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        let scores = points
+            .take_while(|_| !self.is_stopped.load(Ordering::Relaxed))
+            .filter(|idx| self.check_vector(*idx))
+            .map(|idx| ScoredPointOffset {
+                idx,
+                score: self.score_point(idx),
+            });
+        peek_top_largest_iterable(scores, top)
+    }
+
+    fn peek_top_all(&self, top: usize) -> Vec<ScoredPointOffset> {
+        self.peek_top_iter(&mut (0..self.points_count), top)
+    }
+}
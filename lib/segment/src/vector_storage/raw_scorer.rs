@@ -1,12 +1,21 @@
+use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use bitvec::prelude::BitSlice;
+use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
 
-use super::{ScoredPointOffset, VectorStorage, VectorStorageEnum};
-use crate::data_types::vectors::VectorElementType;
-use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use super::multi_vector_raw_scorer::multi_vector_raw_scorer;
+use super::query_scorer::query_raw_scorer;
+use super::{DenseVectorStorage, ScoredPointOffset, VectorStorage, VectorStorageEnum};
+use crate::common::search_stats::SearchStats;
+use crate::data_types::primitive::PrimitiveVectorElement;
+use crate::data_types::vectors::{QueryVector, VectorElementType};
+use crate::spaces::metric::ElementMetric;
+use crate::spaces::simple::{
+    CosineMetric, DotProductMetric, EuclidMetric, HammingMetric, ManhattanMetric,
+    UnnormalizedCosineMetric,
+};
 use crate::spaces::tools::peek_top_largest_iterable;
 use crate::types::{Distance, PointOffsetType, ScoreType};
 
@@ -51,9 +60,15 @@ pub trait RawScorer {
     fn peek_top_all(&self, top: usize) -> Vec<ScoredPointOffset>;
 }
 
-pub struct RawScorerImpl<'a, TMetric: Metric, TVectorStorage: VectorStorage> {
+pub struct RawScorerImpl<'a, TElement, TMetric, TVectorStorage>
+where
+    TElement: PrimitiveVectorElement,
+    TMetric: ElementMetric<TElement>,
+    TVectorStorage: DenseVectorStorage<TElement>,
+{
     pub points_count: PointOffsetType,
-    pub query: Vec<VectorElementType>,
+    /// Preprocessed query, converted into the element type of the storage
+    pub query: Vec<TElement>,
     pub vector_storage: &'a TVectorStorage,
     /// [`BitSlice`] defining flags for deleted points (and thus these vectors).
     pub point_deleted: &'a BitSlice,
@@ -73,25 +88,39 @@ pub fn new_stoppable_raw_scorer<'a>(
 ) -> Box<dyn RawScorer + 'a> {
     match vector_storage {
         VectorStorageEnum::Simple(vs) => raw_scorer_impl(vector, vs, point_deleted, is_stopped),
+        VectorStorageEnum::SimpleHalf(vs) => raw_scorer_impl(vector, vs, point_deleted, is_stopped),
+        VectorStorageEnum::SimpleByte(vs) => raw_scorer_impl(vector, vs, point_deleted, is_stopped),
 
         VectorStorageEnum::Memmap(vs) => {
             if vs.has_async_reader() {
-                #[cfg(target_os = "linux")]
                 match super::async_raw_scorer::new(vector.clone(), vs, point_deleted, is_stopped) {
                     Ok(raw_scorer) => return raw_scorer,
                     Err(err) => log::error!("failed to initialize async raw scorer: {err}"),
                 }
-
-                #[cfg(not(target_os = "linux"))]
-                log::warn!("async raw scorer is only supported on Linux");
             }
 
             raw_scorer_impl(vector, vs.as_ref(), point_deleted, is_stopped)
         }
+        VectorStorageEnum::MemmapHalf(vs) => {
+            raw_scorer_impl(vector, vs.as_ref(), point_deleted, is_stopped)
+        }
+        VectorStorageEnum::MemmapByte(vs) => {
+            raw_scorer_impl(vector, vs.as_ref(), point_deleted, is_stopped)
+        }
 
         VectorStorageEnum::AppendableMemmap(vs) => {
             raw_scorer_impl(vector, vs.as_ref(), point_deleted, is_stopped)
         }
+        VectorStorageEnum::AppendableMemmapHalf(vs) => {
+            raw_scorer_impl(vector, vs.as_ref(), point_deleted, is_stopped)
+        }
+        VectorStorageEnum::AppendableMemmapByte(vs) => {
+            raw_scorer_impl(vector, vs.as_ref(), point_deleted, is_stopped)
+        }
+
+        VectorStorageEnum::SimpleMulti(vs) => {
+            multi_vector_raw_scorer(vector, vs, point_deleted, is_stopped)
+        }
     }
 }
 
@@ -105,49 +134,180 @@ pub fn new_raw_scorer<'a>(
     new_stoppable_raw_scorer(vector, vector_storage, point_deleted, &DEFAULT_STOPPED)
 }
 
-pub fn raw_scorer_impl<'a, TVectorStorage: VectorStorage>(
+/// Raw scorer of the query, which might compare points with several example vectors
+pub fn new_stoppable_query_scorer<'a>(
+    query: &QueryVector,
+    vector_storage: &'a VectorStorageEnum,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    query_raw_scorer(
+        query,
+        vector_storage.total_vector_count() as PointOffsetType,
+        point_deleted,
+        vector_storage.deleted_vector_bitslice(),
+        is_stopped,
+        |vector| {
+            new_stoppable_raw_scorer(vector.to_vec(), vector_storage, point_deleted, is_stopped)
+        },
+    )
+}
+
+/// Number of points, scored by every scorer of a batch in turn, see [`peek_top_batch`]
+const BATCH_SCORING_CHUNK_SIZE: usize = 256;
+
+/// Find the top points of every scorer of a batch in a single pass over the points
+///
+/// Points are scored in chunks by all scorers in turn, so the vectors of a chunk are read from
+/// storage once and stay in cache for the whole batch. Results are the same as of
+/// [`RawScorer::peek_top_iter`] called for every scorer.
+pub fn peek_top_batch(
+    scorers: &[Box<dyn RawScorer + '_>],
+    points: &mut dyn Iterator<Item = PointOffsetType>,
+    top: usize,
+    is_stopped: &AtomicBool,
+) -> Vec<Vec<ScoredPointOffset>> {
+    if top == 0 {
+        return vec![vec![]; scorers.len()];
+    }
+
+    let mut queues = scorers
+        .iter()
+        .map(|_| FixedLengthPriorityQueue::new(top))
+        .collect::<Vec<_>>();
+    let mut chunk = Vec::with_capacity(BATCH_SCORING_CHUNK_SIZE);
+    let mut scores = vec![ScoredPointOffset::default(); BATCH_SCORING_CHUNK_SIZE];
+    let mut points_scored = 0;
+    loop {
+        chunk.clear();
+        chunk.extend((&mut *points).take(BATCH_SCORING_CHUNK_SIZE));
+        if chunk.is_empty() || is_stopped.load(Ordering::Relaxed) {
+            break;
+        }
+        for (scorer, queue) in scorers.iter().zip(queues.iter_mut()) {
+            let count = scorer.score_points(&chunk, &mut scores);
+            points_scored += count;
+            for scored in &scores[..count] {
+                queue.push(*scored);
+            }
+        }
+    }
+    SearchStats {
+        points_scored,
+        ..Default::default()
+    }
+    .report();
+    queues.into_iter().map(|queue| queue.into_vec()).collect()
+}
+
+pub fn raw_scorer_impl<'a, TElement, TVectorStorage>(
     vector: Vec<VectorElementType>,
     vector_storage: &'a TVectorStorage,
     point_deleted: &'a BitSlice,
     is_stopped: &'a AtomicBool,
-) -> Box<dyn RawScorer + 'a> {
+) -> Box<dyn RawScorer + 'a>
+where
+    TElement: PrimitiveVectorElement,
+    TVectorStorage: DenseVectorStorage<TElement>,
+    CosineMetric: ElementMetric<TElement>,
+    EuclidMetric: ElementMetric<TElement>,
+    DotProductMetric: ElementMetric<TElement>,
+    ManhattanMetric: ElementMetric<TElement>,
+    HammingMetric: ElementMetric<TElement>,
+    UnnormalizedCosineMetric: ElementMetric<TElement>,
+{
     let points_count = vector_storage.total_vector_count() as PointOffsetType;
     let vec_deleted = vector_storage.deleted_vector_bitslice();
     match vector_storage.distance() {
-        Distance::Cosine => Box::new(RawScorerImpl::<'a, CosineMetric, TVectorStorage> {
-            points_count,
-            query: CosineMetric::preprocess(&vector).unwrap_or(vector),
-            vector_storage,
-            point_deleted,
-            vec_deleted,
-            metric: PhantomData,
-            is_stopped,
-        }),
-        Distance::Euclid => Box::new(RawScorerImpl::<'a, EuclidMetric, TVectorStorage> {
-            points_count,
-            query: EuclidMetric::preprocess(&vector).unwrap_or(vector),
-            vector_storage,
-            point_deleted,
-            vec_deleted,
-            metric: PhantomData,
-            is_stopped,
-        }),
-        Distance::Dot => Box::new(RawScorerImpl::<'a, DotProductMetric, TVectorStorage> {
-            points_count,
-            query: DotProductMetric::preprocess(&vector).unwrap_or(vector),
-            vector_storage,
-            point_deleted,
-            vec_deleted,
-            metric: PhantomData,
-            is_stopped,
-        }),
+        Distance::Cosine if vector_storage.stores_original_vectors() => {
+            Box::new(
+                RawScorerImpl::<'a, TElement, UnnormalizedCosineMetric, TVectorStorage> {
+                    points_count,
+                    query: preprocess_query::<TElement, UnnormalizedCosineMetric>(vector),
+                    vector_storage,
+                    point_deleted,
+                    vec_deleted,
+                    metric: PhantomData,
+                    is_stopped,
+                },
+            )
+        }
+        Distance::Cosine => Box::new(
+            RawScorerImpl::<'a, TElement, CosineMetric, TVectorStorage> {
+                points_count,
+                query: preprocess_query::<TElement, CosineMetric>(vector),
+                vector_storage,
+                point_deleted,
+                vec_deleted,
+                metric: PhantomData,
+                is_stopped,
+            },
+        ),
+        Distance::Euclid => Box::new(
+            RawScorerImpl::<'a, TElement, EuclidMetric, TVectorStorage> {
+                points_count,
+                query: preprocess_query::<TElement, EuclidMetric>(vector),
+                vector_storage,
+                point_deleted,
+                vec_deleted,
+                metric: PhantomData,
+                is_stopped,
+            },
+        ),
+        Distance::Dot => Box::new(
+            RawScorerImpl::<'a, TElement, DotProductMetric, TVectorStorage> {
+                points_count,
+                query: preprocess_query::<TElement, DotProductMetric>(vector),
+                vector_storage,
+                point_deleted,
+                vec_deleted,
+                metric: PhantomData,
+                is_stopped,
+            },
+        ),
+        Distance::Manhattan => {
+            Box::new(
+                RawScorerImpl::<'a, TElement, ManhattanMetric, TVectorStorage> {
+                    points_count,
+                    query: preprocess_query::<TElement, ManhattanMetric>(vector),
+                    vector_storage,
+                    point_deleted,
+                    vec_deleted,
+                    metric: PhantomData,
+                    is_stopped,
+                },
+            )
+        }
+        Distance::Hamming => Box::new(
+            RawScorerImpl::<'a, TElement, HammingMetric, TVectorStorage> {
+                points_count,
+                query: preprocess_query::<TElement, HammingMetric>(vector),
+                vector_storage,
+                point_deleted,
+                vec_deleted,
+                metric: PhantomData,
+                is_stopped,
+            },
+        ),
     }
 }
 
-impl<'a, TMetric, TVectorStorage> RawScorer for RawScorerImpl<'a, TMetric, TVectorStorage>
+/// Preprocess query in [`VectorElementType`] and convert it into the storage element type
+fn preprocess_query<TElement, TMetric>(vector: Vec<VectorElementType>) -> Vec<TElement>
+where
+    TElement: PrimitiveVectorElement,
+    TMetric: ElementMetric<TElement>,
+{
+    let vector = TMetric::element_preprocess(vector);
+    TElement::slice_from_float_cow(Cow::Owned(vector)).into_owned()
+}
+
+impl<'a, TElement, TMetric, TVectorStorage> RawScorer
+    for RawScorerImpl<'a, TElement, TMetric, TVectorStorage>
 where
-    TMetric: Metric,
-    TVectorStorage: VectorStorage,
+    TElement: PrimitiveVectorElement,
+    TMetric: ElementMetric<TElement>,
+    TVectorStorage: DenseVectorStorage<TElement>,
 {
     fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
         if self.is_stopped.load(Ordering::Relaxed) {
@@ -158,10 +318,10 @@ where
             if !self.check_vector(point_id) {
                 continue;
             }
-            let other_vector = self.vector_storage.get_vector(point_id);
+            let other_vector = self.vector_storage.get_dense(point_id);
             scores[size] = ScoredPointOffset {
                 idx: point_id,
-                score: TMetric::similarity(&self.query, other_vector),
+                score: TMetric::element_similarity(&self.query, other_vector),
             };
 
             size += 1;
@@ -181,10 +341,10 @@ where
         }
         let mut scores = vec![];
         for point_id in points {
-            let other_vector = self.vector_storage.get_vector(point_id);
+            let other_vector = self.vector_storage.get_dense(point_id);
             scores.push(ScoredPointOffset {
                 idx: point_id,
-                score: TMetric::similarity(&self.query, other_vector),
+                score: TMetric::element_similarity(&self.query, other_vector),
             });
         }
         scores
@@ -209,14 +369,14 @@ where
     }
 
     fn score_point(&self, point: PointOffsetType) -> ScoreType {
-        let other_vector = self.vector_storage.get_vector(point);
-        TMetric::similarity(&self.query, other_vector)
+        let other_vector = self.vector_storage.get_dense(point);
+        TMetric::element_similarity(&self.query, other_vector)
     }
 
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
-        let vector_a = self.vector_storage.get_vector(point_a);
-        let vector_b = self.vector_storage.get_vector(point_b);
-        TMetric::similarity(vector_a, vector_b)
+        let vector_a = self.vector_storage.get_dense(point_a);
+        let vector_b = self.vector_storage.get_dense(point_b);
+        TMetric::element_similarity(vector_a, vector_b)
     }
 
     fn peek_top_iter(
@@ -228,10 +388,10 @@ where
             .take_while(|_| !self.is_stopped.load(Ordering::Relaxed))
             .filter(|point_id| self.check_vector(*point_id))
             .map(|point_id| {
-                let other_vector = self.vector_storage.get_vector(point_id);
+                let other_vector = self.vector_storage.get_dense(point_id);
                 ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: TMetric::element_similarity(&self.query, other_vector),
                 }
             });
         peek_top_largest_iterable(scores, top)
@@ -243,10 +403,10 @@ where
             .filter(|point_id| self.check_vector(*point_id))
             .map(|point_id| {
                 let point_id = point_id as PointOffsetType;
-                let other_vector = &self.vector_storage.get_vector(point_id);
+                let other_vector = &self.vector_storage.get_dense(point_id);
                 ScoredPointOffset {
                     idx: point_id,
-                    score: TMetric::similarity(&self.query, other_vector),
+                    score: TMetric::element_similarity(&self.query, other_vector),
                 }
             });
         peek_top_largest_iterable(scores, top)
@@ -0,0 +1,282 @@
+use std::borrow::Cow;
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::{BitSlice, BitVec};
+use log::debug;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+
+use super::simple_vector_storage::bitvec_set_deleted;
+use super::vector_storage_base::VectorStorage;
+use super::VectorStorageEnum;
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::Flusher;
+use crate::data_types::vectors::VectorElementType;
+use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
+use crate::types::{Distance, MultiVectorConfig, PointOffsetType, QuantizationConfig};
+use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
+
+/// In-memory storage of multi-vectors with on-update persistence using `store`
+///
+/// Each point holds a variable number of sub-vectors of dimension `dim`,
+/// they are stored concatenated into a single flat vector.
+pub struct SimpleMultiVectorStorage {
+    dim: usize,
+    distance: Distance,
+    /// Sub-vectors are stored without distance preprocessing
+    original_vectors: bool,
+    multivector_config: MultiVectorConfig,
+    /// Concatenated sub-vectors of each point, empty for never inserted points
+    vectors: Vec<Vec<VectorElementType>>,
+    db_wrapper: DatabaseColumnWrapper,
+    /// BitVec for deleted flags. Grows dynamically upto last set flag.
+    deleted: BitVec,
+    /// Current number of deleted vectors.
+    deleted_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct StoredRecord<'a> {
+    pub deleted: bool,
+    pub vector: Cow<'a, [VectorElementType]>,
+}
+
+pub fn open_simple_multi_vector_storage(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+    multivector_config: MultiVectorConfig,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let mut vectors: Vec<Vec<VectorElementType>> = vec![];
+    let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
+
+    let db_wrapper = DatabaseColumnWrapper::new(database, database_column_name);
+
+    for (key, value) in db_wrapper.lock_db().iter()? {
+        let point_id: PointOffsetType = bincode::deserialize(&key)
+            .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
+        let stored_record: StoredRecord = bincode::deserialize(&value)
+            .map_err(|_| OperationError::service_error("cannot deserialize record from db"))?;
+
+        // Propagate deleted flag
+        if stored_record.deleted {
+            bitvec_set_deleted(&mut deleted, point_id, true);
+            deleted_count += 1;
+        }
+        if vectors.len() <= point_id as usize {
+            vectors.resize_with(point_id as usize + 1, Vec::new);
+        }
+        vectors[point_id as usize] = stored_record.vector.into_owned();
+    }
+
+    debug!("Segment multi-vectors: {}", vectors.len());
+    debug!(
+        "Estimated segment size {} MB",
+        vectors.iter().map(Vec::len).sum::<usize>() * size_of::<VectorElementType>() / 1024 / 1024
+    );
+
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::SimpleMulti(SimpleMultiVectorStorage {
+            dim,
+            distance,
+            original_vectors: false,
+            multivector_config,
+            vectors,
+            db_wrapper,
+            deleted,
+            deleted_count,
+        }),
+    )))
+}
+
+impl SimpleMultiVectorStorage {
+    pub fn set_original_vectors(&mut self, original_vectors: bool) {
+        self.original_vectors = original_vectors;
+    }
+
+    pub fn multivector_config(&self) -> &MultiVectorConfig {
+        &self.multivector_config
+    }
+
+    /// Size of the sub-vectors of all points, in bytes
+    pub fn ram_usage_bytes(&self) -> usize {
+        let elements: usize = self.vectors.iter().map(Vec::capacity).sum();
+        elements * std::mem::size_of::<VectorElementType>()
+    }
+
+    /// Get concatenated sub-vectors of the point
+    pub fn get_multi(&self, key: PointOffsetType) -> &[VectorElementType] {
+        self.vectors
+            .get(key as usize)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Set deleted flag for given key. Returns previous deleted state.
+    #[inline]
+    fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> bool {
+        if key as usize >= self.vectors.len() {
+            return false;
+        }
+        let was_deleted = bitvec_set_deleted(&mut self.deleted, key, deleted);
+        if was_deleted != deleted {
+            if !was_deleted {
+                self.deleted_count += 1;
+            } else {
+                self.deleted_count -= 1;
+            }
+        }
+        was_deleted
+    }
+
+    fn update_stored(&self, key: PointOffsetType, deleted: bool) -> OperationResult<()> {
+        let record = StoredRecord {
+            deleted,
+            vector: Cow::Borrowed(self.get_multi(key)),
+        };
+        self.db_wrapper.put(
+            bincode::serialize(&key).unwrap(),
+            bincode::serialize(&record).unwrap(),
+        )?;
+        Ok(())
+    }
+
+    fn set_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &[VectorElementType],
+    ) -> OperationResult<()> {
+        if vector.is_empty() || vector.len() % self.dim != 0 {
+            return Err(OperationError::WrongVectorElement {
+                description: format!(
+                    "multi-vector must consist of sub-vectors of dim {}, got {} components",
+                    self.dim,
+                    vector.len(),
+                ),
+            });
+        }
+        if self.vectors.len() <= key as usize {
+            self.vectors.resize_with(key as usize + 1, Vec::new);
+        }
+        self.vectors[key as usize] = vector.to_vec();
+        Ok(())
+    }
+}
+
+impl VectorStorage for SimpleMultiVectorStorage {
+    /// Dimension of a single sub-vector
+    fn vector_dim(&self) -> usize {
+        self.dim
+    }
+
+    fn distance(&self) -> Distance {
+        self.distance
+    }
+
+    fn stores_original_vectors(&self) -> bool {
+        self.original_vectors
+    }
+
+    fn total_vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    fn get_vector(&self, key: PointOffsetType) -> Cow<'_, [VectorElementType]> {
+        Cow::Borrowed(self.get_multi(key))
+    }
+
+    fn insert_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &[VectorElementType],
+    ) -> OperationResult<()> {
+        self.set_vector(key, vector)?;
+        self.set_deleted(key, false);
+        self.update_stored(key, false)?;
+        Ok(())
+    }
+
+    fn update_from(
+        &mut self,
+        other: &VectorStorageEnum,
+        other_ids: &mut dyn Iterator<Item = PointOffsetType>,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Range<PointOffsetType>> {
+        let start_index = self.vectors.len() as PointOffsetType;
+        for point_id in other_ids {
+            check_process_stopped(stopped)?;
+            // Do not perform preprocessing - vectors should be already processed
+            let new_id = self.vectors.len() as PointOffsetType;
+            let other_deleted = other.is_deleted_vector(point_id);
+            let other_vector = other.get_vector(point_id);
+            if other_vector.is_empty() {
+                // Hole in the other storage, keep internal ids aligned
+                self.vectors.push(vec![]);
+            } else {
+                self.set_vector(new_id, &other_vector)?;
+            }
+            self.set_deleted(new_id, other_deleted);
+            self.update_stored(new_id, other_deleted)?;
+        }
+        let end_index = self.vectors.len() as PointOffsetType;
+        Ok(start_index..end_index)
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    /// Multi-vectors are not quantized, search always uses original vectors
+    fn quantize(
+        &mut self,
+        _data_path: &Path,
+        _quantization_config: &QuantizationConfig,
+        _max_threads: usize,
+        _stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        Ok(())
+    }
+
+    fn load_quantization(&mut self, _data_path: &Path) -> OperationResult<()> {
+        Ok(())
+    }
+
+    fn quantized_storage(&self) -> Option<&QuantizedVectors> {
+        None
+    }
+
+    fn files(&self) -> Vec<std::path::PathBuf> {
+        vec![]
+    }
+
+    fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        let is_deleted = !self.set_deleted(key, true);
+        if is_deleted {
+            self.update_stored(key, true)?;
+        }
+        Ok(is_deleted)
+    }
+
+    fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
+        self.deleted.get(key as usize).map(|b| *b).unwrap_or(false)
+    }
+
+    fn deleted_vector_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    fn deleted_vector_bitslice(&self) -> &BitSlice {
+        self.deleted.as_bitslice()
+    }
+
+    fn is_appendable(&self) -> bool {
+        true
+    }
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::mem::size_of;
 use std::ops::Range;
 use std::path::Path;
@@ -6,29 +7,33 @@ use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
 use bitvec::prelude::{BitSlice, BitVec};
+use half::f16;
 use log::debug;
 use parking_lot::RwLock;
 use rocksdb::DB;
 use serde::{Deserialize, Serialize};
 
 use super::chunked_vectors::ChunkedVectors;
-use super::vector_storage_base::VectorStorage;
+use super::vector_storage_base::{DenseVectorStorage, VectorStorage};
 use super::VectorStorageEnum;
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
 use crate::types::{Distance, PointOffsetType, QuantizationConfig};
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 
 /// In-memory vector storage with on-update persistence using `store`
-pub struct SimpleVectorStorage {
+pub struct SimpleVectorStorage<T: PrimitiveVectorElement> {
     dim: usize,
     distance: Distance,
-    vectors: ChunkedVectors<VectorElementType>,
+    /// Vectors are stored without distance preprocessing
+    original_vectors: bool,
+    vectors: ChunkedVectors<T>,
     quantized_vectors: Option<QuantizedVectors>,
     db_wrapper: DatabaseColumnWrapper,
-    update_buffer: StoredRecord,
+    update_buffer: StoredRecord<T>,
     /// BitVec for deleted flags. Grows dynamically upto last set flag.
     deleted: BitVec,
     /// Current number of deleted vectors.
@@ -36,9 +41,10 @@ pub struct SimpleVectorStorage {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
-struct StoredRecord {
+#[serde(bound = "")]
+struct StoredRecord<T: PrimitiveVectorElement> {
     pub deleted: bool,
-    pub vector: Vec<VectorElementType>,
+    pub vector: Vec<T>,
 }
 
 pub fn open_simple_vector_storage(
@@ -47,6 +53,46 @@ pub fn open_simple_vector_storage(
     dim: usize,
     distance: Distance,
 ) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage = open_simple_vector_storage_impl(database, database_column_name, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Simple(
+        storage,
+    ))))
+}
+
+/// Open in-memory storage, which keeps vectors in half-precision
+pub fn open_simple_vector_storage_half(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage =
+        open_simple_vector_storage_impl::<f16>(database, database_column_name, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::SimpleHalf(
+        storage,
+    ))))
+}
+
+/// Open in-memory storage, which keeps vectors as bytes
+pub fn open_simple_vector_storage_byte(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let storage =
+        open_simple_vector_storage_impl::<u8>(database, database_column_name, dim, distance)?;
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::SimpleByte(
+        storage,
+    ))))
+}
+
+fn open_simple_vector_storage_impl<T: PrimitiveVectorElement>(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<SimpleVectorStorage<T>> {
     let mut vectors = ChunkedVectors::new(dim);
     let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
 
@@ -55,7 +101,7 @@ pub fn open_simple_vector_storage(
     for (key, value) in db_wrapper.lock_db().iter()? {
         let point_id: PointOffsetType = bincode::deserialize(&key)
             .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
-        let stored_record: StoredRecord = bincode::deserialize(&value)
+        let stored_record: StoredRecord<T> = bincode::deserialize(&value)
             .map_err(|_| OperationError::service_error("cannot deserialize record from db"))?;
 
         // Propagate deleted flag
@@ -69,27 +115,30 @@ pub fn open_simple_vector_storage(
     debug!("Segment vectors: {}", vectors.len());
     debug!(
         "Estimated segment size {} MB",
-        vectors.len() * dim * size_of::<VectorElementType>() / 1024 / 1024
+        vectors.len() * dim * size_of::<T>() / 1024 / 1024
     );
 
-    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::Simple(
-        SimpleVectorStorage {
-            dim,
-            distance,
-            vectors,
-            quantized_vectors: None,
-            db_wrapper,
-            update_buffer: StoredRecord {
-                deleted: false,
-                vector: vec![0.; dim],
-            },
-            deleted,
-            deleted_count,
+    Ok(SimpleVectorStorage {
+        dim,
+        distance,
+        original_vectors: false,
+        vectors,
+        quantized_vectors: None,
+        db_wrapper,
+        update_buffer: StoredRecord {
+            deleted: false,
+            vector: vec![T::default(); dim],
         },
-    ))))
+        deleted,
+        deleted_count,
+    })
 }
 
-impl SimpleVectorStorage {
+impl<T: PrimitiveVectorElement> SimpleVectorStorage<T> {
+    pub fn set_original_vectors(&mut self, original_vectors: bool) {
+        self.original_vectors = original_vectors;
+    }
+
     /// Set deleted flag for given key. Returns previous deleted state.
     #[inline]
     fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> bool {
@@ -111,7 +160,7 @@ impl SimpleVectorStorage {
         &mut self,
         key: PointOffsetType,
         deleted: bool,
-        vector: Option<&[VectorElementType]>,
+        vector: Option<&[T]>,
     ) -> OperationResult<()> {
         // Write vector state to buffer record
         let record = &mut self.update_buffer;
@@ -130,7 +179,13 @@ impl SimpleVectorStorage {
     }
 }
 
-impl VectorStorage for SimpleVectorStorage {
+impl<T: PrimitiveVectorElement> DenseVectorStorage<T> for SimpleVectorStorage<T> {
+    fn get_dense(&self, key: PointOffsetType) -> &[T] {
+        self.vectors.get(key)
+    }
+}
+
+impl<T: PrimitiveVectorElement> VectorStorage for SimpleVectorStorage<T> {
     fn vector_dim(&self) -> usize {
         self.dim
     }
@@ -139,12 +194,16 @@ impl VectorStorage for SimpleVectorStorage {
         self.distance
     }
 
+    fn stores_original_vectors(&self) -> bool {
+        self.original_vectors
+    }
+
     fn total_vector_count(&self) -> usize {
         self.vectors.len()
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.vectors.get(key)
+    fn get_vector(&self, key: PointOffsetType) -> Cow<'_, [VectorElementType]> {
+        T::slice_to_float_cow(Cow::Borrowed(self.vectors.get(key)))
     }
 
     fn insert_vector(
@@ -152,9 +211,10 @@ impl VectorStorage for SimpleVectorStorage {
         key: PointOffsetType,
         vector: &[VectorElementType],
     ) -> OperationResult<()> {
-        self.vectors.insert(key, vector)?;
+        let vector = T::slice_from_float_cow(Cow::Borrowed(vector));
+        self.vectors.insert(key, &vector)?;
         self.set_deleted(key, false);
-        self.update_stored(key, false, Some(vector))?;
+        self.update_stored(key, false, Some(&vector))?;
         Ok(())
     }
 
@@ -168,11 +228,11 @@ impl VectorStorage for SimpleVectorStorage {
         for point_id in other_ids {
             check_process_stopped(stopped)?;
             // Do not perform preprocessing - vectors should be already processed
-            let other_vector = other.get_vector(point_id);
+            let other_vector = T::slice_from_float_cow(other.get_vector(point_id));
             let other_deleted = other.is_deleted_vector(point_id);
-            let new_id = self.vectors.push(other_vector)?;
+            let new_id = self.vectors.push(&other_vector)?;
             self.set_deleted(new_id, other_deleted);
-            self.update_stored(new_id, other_deleted, Some(other_vector))?;
+            self.update_stored(new_id, other_deleted, Some(&other_vector))?;
         }
         let end_index = self.vectors.len() as PointOffsetType;
         Ok(start_index..end_index)
@@ -189,9 +249,18 @@ impl VectorStorage for SimpleVectorStorage {
         max_threads: usize,
         stopped: &AtomicBool,
     ) -> OperationResult<()> {
-        let vector_data_iterator = (0..self.vectors.len() as u32).map(|i| self.vectors.get(i));
+        // Quantization consumes f32 slices, vectors of other types are converted upfront
+        let vectors: Vec<_> = (0..self.vectors.len() as u32)
+            .map(|i| {
+                T::quantization_preprocess(
+                    self.distance,
+                    self.original_vectors,
+                    self.vectors.get(i),
+                )
+            })
+            .collect();
         self.quantized_vectors = Some(QuantizedVectors::create(
-            vector_data_iterator,
+            vectors.iter().map(|vector| vector.as_ref()),
             quantization_config,
             self.distance,
             self.dim,
@@ -254,7 +323,11 @@ impl VectorStorage for SimpleVectorStorage {
 ///
 /// Returns previous deleted state of the given point.
 #[inline]
-fn bitvec_set_deleted(bitvec: &mut BitVec, point_id: PointOffsetType, deleted: bool) -> bool {
+pub(super) fn bitvec_set_deleted(
+    bitvec: &mut BitVec,
+    point_id: PointOffsetType,
+    deleted: bool,
+) -> bool {
     // Set deleted flag if bitvec is large enough, no need to check bounds
     if (point_id as usize) < bitvec.len() {
         return unsafe { bitvec.replace_unchecked(point_id as usize, deleted) };
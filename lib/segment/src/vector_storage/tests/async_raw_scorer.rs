@@ -2,12 +2,15 @@ use std::sync::atomic::AtomicBool;
 use std::{error, result};
 
 use bitvec::slice::BitSlice;
-use rand::seq::IteratorRandom as _;
+use rand::seq::{IteratorRandom as _, SliceRandom as _};
 use rand::SeedableRng as _;
 
 use crate::common::rocksdb_wrapper;
 use crate::fixtures::payload_context_fixture::FixtureIdTracker;
 use crate::id_tracker::IdTracker;
+use crate::index::hnsw_index::graph_layers_builder::GraphLayersBuilder;
+use crate::index::hnsw_index::graph_links::GraphLinksRam;
+use crate::index::hnsw_index::point_scorer::FilteredScorer;
 use crate::types::{Distance, PointOffsetType};
 use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage_with_async_io;
 use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
@@ -31,6 +34,83 @@ fn async_raw_scorer_dot() -> Result<()> {
     test_async_raw_scorer_defaults(Distance::Dot)
 }
 
+#[test]
+fn async_prefetch_read_vectors() -> Result<()> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let (dir, _id_tracker) = build_storage(&mut rng, 16, Distance::Dot, 300, 0)?;
+    let storage = open_memmap_vector_storage_with_async_io(dir.path(), 16, Distance::Dot, false)?;
+    let storage = storage.borrow();
+    let VectorStorageEnum::Memmap(storage) = &*storage else {
+        unreachable!();
+    };
+    let mmap_vectors = storage.get_mmap_vectors();
+
+    // More points than a single prefetch batch, in random order and with repeats
+    let mut points: Vec<PointOffsetType> = (0..300).chain(0..50).collect();
+    points.shuffle(&mut rng);
+
+    let mut read = 0;
+    mmap_vectors.process_points_prefetch(points.iter().copied(), |idx, point, vector| {
+        assert_eq!(idx, read);
+        assert_eq!(point, points[idx]);
+        assert_eq!(vector, mmap_vectors.get_vector(point));
+        read += 1;
+    })?;
+    assert_eq!(read, points.len());
+
+    Ok(())
+}
+
+#[test]
+fn async_raw_scorer_hnsw_search() -> Result<()> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let dim = 32;
+    let points = 2000;
+    let (dir, id_tracker) = build_storage(&mut rng, dim, Distance::Cosine, points, 100)?;
+    let deleted_points = id_tracker.deleted_point_bitslice();
+
+    let sync_storage =
+        open_memmap_vector_storage_with_async_io(dir.path(), dim, Distance::Cosine, false)?;
+    let async_storage =
+        open_memmap_vector_storage_with_async_io(dir.path(), dim, Distance::Cosine, true)?;
+    let sync_storage = sync_storage.borrow();
+    let async_storage = async_storage.borrow();
+
+    let mut graph_layers_builder = GraphLayersBuilder::new(points, 16, 32, 100, 10, true, None);
+    for idx in 0..points as PointOffsetType {
+        let raw_scorer = new_raw_scorer(
+            sync_storage.get_vector(idx).to_vec(),
+            &sync_storage,
+            deleted_points,
+        );
+        let scorer = FilteredScorer::new(raw_scorer.as_ref(), None);
+        let level = graph_layers_builder.get_random_layer(&mut rng);
+        graph_layers_builder.set_levels(idx, level);
+        graph_layers_builder.link_new_point(idx, scorer);
+    }
+    let graph = graph_layers_builder.into_graph_layers::<GraphLinksRam>(None)?;
+
+    let search = |storage: &VectorStorageEnum, query: Vec<f32>| {
+        let raw_scorer = new_raw_scorer(query, storage, deleted_points);
+        graph
+            .search(10, 64, FilteredScorer::new(raw_scorer.as_ref(), None))
+            .into_iter()
+            .map(|scored| (scored.idx, scored.score.to_bits()))
+            .collect::<Vec<_>>()
+    };
+
+    for _ in 0..50 {
+        let query: Vec<_> = sampler(&mut rng).take(dim).collect();
+        // Results must be identical bit by bit, not just close
+        assert_eq!(
+            search(&sync_storage, query.clone()),
+            search(&async_storage, query),
+        );
+    }
+
+    Ok(())
+}
+
 fn test_async_raw_scorer_defaults(distance: Distance) -> Result<()> {
     test_async_raw_scorer(6942, 128, distance, 1024, 128, 256)
 }
@@ -45,11 +125,30 @@ fn test_async_raw_scorer(
 ) -> Result<()> {
     let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
 
+    let (dir, id_tracker) = build_storage(&mut rng, dim, distance, points, delete)?;
+    let storage = open_memmap_vector_storage_with_async_io(dir.path(), dim, distance, true)?;
+    let storage = storage.borrow();
+
+    for _ in 0..score {
+        test_random_score(&mut rng, &storage, id_tracker.deleted_point_bitslice())?;
+    }
+
+    Ok(())
+}
+
+/// Build immutable mmap storage with random vectors, some of them deleted
+fn build_storage(
+    rng: &mut impl rand::Rng,
+    dim: usize,
+    distance: Distance,
+    points: usize,
+    delete: usize,
+) -> Result<(tempfile::TempDir, FixtureIdTracker)> {
     let dir = tempfile::Builder::new()
         .prefix("immutable-storage")
         .tempdir()?;
 
-    let storage = open_memmap_vector_storage_with_async_io(dir.path(), dim, distance, true)?;
+    let storage = open_memmap_vector_storage_with_async_io(dir.path(), dim, distance, false)?;
     let mut storage = storage.borrow_mut();
 
     let mut id_tracker = FixtureIdTracker::new(points);
@@ -62,21 +161,19 @@ fn test_async_raw_scorer(
         let db = rocksdb_wrapper::open_db(dir.path(), &[rocksdb_wrapper::DB_VECTOR_CF])?;
 
         let mutable_storage =
-            open_simple_vector_storage(db, rocksdb_wrapper::DB_VECTOR_CF, 4, distance)?;
+            open_simple_vector_storage(db, rocksdb_wrapper::DB_VECTOR_CF, dim, distance)?;
 
         let mut mutable_storage = mutable_storage.borrow_mut();
 
-        insert_random_vectors(&mut rng, &mut *mutable_storage, points)?;
-        delete_random_vectors(&mut rng, &mut *mutable_storage, &mut id_tracker, delete)?;
+        insert_random_vectors(rng, &mut *mutable_storage, points)?;
+        delete_random_vectors(rng, &mut *mutable_storage, &mut id_tracker, delete)?;
 
         storage.update_from(&mutable_storage, &mut (0..points as _), &Default::default())?;
     }
 
-    for _ in 0..score {
-        test_random_score(&mut rng, &storage, id_tracker.deleted_point_bitslice())?;
-    }
+    storage.flusher()()?;
 
-    Ok(())
+    Ok((dir, id_tracker))
 }
 
 fn insert_random_vectors(
@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tempfile::Builder;
+
+use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+use crate::data_types::vectors::VectorElementType;
+use crate::fixtures::payload_context_fixture::FixtureIdTracker;
+use crate::id_tracker::IdTrackerSS;
+use crate::spaces::metric::Metric;
+use crate::spaces::simple::{
+    dot_similarity, euclid_similarity, hamming_similarity, manhattan_similarity, CosineMetric,
+};
+use crate::types::{Distance, PointOffsetType};
+use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage_byte;
+use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage_byte;
+use crate::vector_storage::simple_vector_storage::{
+    open_simple_vector_storage, open_simple_vector_storage_byte,
+};
+use crate::vector_storage::{new_raw_scorer, VectorStorage, VectorStorageEnum};
+
+const DIM: usize = 40;
+const NUM_VECTORS: usize = 50;
+
+fn random_vectors() -> Vec<Vec<VectorElementType>> {
+    let mut rnd = StdRng::seed_from_u64(42);
+    (0..NUM_VECTORS)
+        .map(|_| {
+            (0..DIM)
+                .map(|_| VectorElementType::from(rnd.gen::<u8>()))
+                .collect()
+        })
+        .collect()
+}
+
+fn insert_vectors(storage: &Arc<AtomicRefCell<VectorStorageEnum>>, vectors: &[Vec<f32>]) {
+    let mut borrowed_storage = storage.borrow_mut();
+    for (i, vector) in vectors.iter().enumerate() {
+        borrowed_storage
+            .insert_vector(i as PointOffsetType, vector)
+            .unwrap();
+    }
+}
+
+/// Integer components must come back exactly as they were inserted
+fn check_vectors(storage: &Arc<AtomicRefCell<VectorStorageEnum>>, vectors: &[Vec<f32>]) {
+    let borrowed_storage = storage.borrow();
+    assert_eq!(borrowed_storage.total_vector_count(), vectors.len());
+    for (i, vector) in vectors.iter().enumerate() {
+        assert_eq!(
+            borrowed_storage.get_vector(i as PointOffsetType).as_ref(),
+            vector.as_slice()
+        );
+    }
+}
+
+/// Scores over byte storage must match f32 scores of the same vectors
+fn check_scoring(
+    storage: &Arc<AtomicRefCell<VectorStorageEnum>>,
+    vectors: &[Vec<f32>],
+    distance: Distance,
+) {
+    let id_tracker: Arc<AtomicRefCell<IdTrackerSS>> =
+        Arc::new(AtomicRefCell::new(FixtureIdTracker::new(vectors.len())));
+    let borrowed_id_tracker = id_tracker.borrow();
+    let borrowed_storage = storage.borrow();
+
+    let query = vectors[0].clone();
+    let scorer = new_raw_scorer(
+        query.clone(),
+        &borrowed_storage,
+        borrowed_id_tracker.deleted_point_bitslice(),
+    );
+    for (i, vector) in vectors.iter().enumerate() {
+        let expected = match distance {
+            Distance::Dot => dot_similarity(&query, vector),
+            Distance::Euclid => euclid_similarity(&query, vector),
+            Distance::Manhattan => manhattan_similarity(&query, vector),
+            Distance::Hamming => hamming_similarity(&query, vector),
+            Distance::Cosine => dot_similarity(
+                &CosineMetric::preprocess(&query).unwrap(),
+                &CosineMetric::preprocess(vector).unwrap(),
+            ),
+        };
+        let score = scorer.score_point(i as PointOffsetType);
+        assert!((score - expected).abs() <= expected.abs() * 1e-5 + 1e-6);
+    }
+}
+
+#[test]
+fn test_byte_simple_vector_storage() {
+    let vectors = random_vectors();
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage =
+            open_simple_vector_storage_byte(db, DB_VECTOR_CF, DIM, Distance::Cosine).unwrap();
+        insert_vectors(&storage, &vectors);
+        check_vectors(&storage, &vectors);
+        check_scoring(&storage, &vectors, Distance::Cosine);
+        storage.borrow().flusher()().unwrap();
+    }
+
+    // Reopen, vectors must be restored from the database
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage = open_simple_vector_storage_byte(db, DB_VECTOR_CF, DIM, Distance::Cosine).unwrap();
+    check_vectors(&storage, &vectors);
+}
+
+#[test]
+fn test_byte_hamming_scoring() {
+    // Few distinct values, so that vectors share some components
+    let vectors: Vec<Vec<VectorElementType>> = random_vectors()
+        .into_iter()
+        .map(|vector| vector.into_iter().map(|x| x % 3.0).collect())
+        .collect();
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage =
+        open_simple_vector_storage_byte(db, DB_VECTOR_CF, DIM, Distance::Hamming).unwrap();
+    insert_vectors(&storage, &vectors);
+    check_vectors(&storage, &vectors);
+    check_scoring(&storage, &vectors, Distance::Hamming);
+}
+
+#[test]
+fn test_byte_appendable_mmap_vector_storage() {
+    let vectors = random_vectors();
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let storage =
+            open_appendable_memmap_vector_storage_byte(dir.path(), DIM, Distance::Euclid).unwrap();
+        insert_vectors(&storage, &vectors);
+        check_vectors(&storage, &vectors);
+        check_scoring(&storage, &vectors, Distance::Euclid);
+        storage.borrow().flusher()().unwrap();
+    }
+
+    let storage =
+        open_appendable_memmap_vector_storage_byte(dir.path(), DIM, Distance::Euclid).unwrap();
+    check_vectors(&storage, &vectors);
+}
+
+#[test]
+fn test_byte_memmap_vector_storage() {
+    let vectors = random_vectors();
+    let db_dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+    let db = open_db(db_dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let source = open_simple_vector_storage(db, DB_VECTOR_CF, DIM, Distance::Dot).unwrap();
+    insert_vectors(&source, &vectors);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let storage = open_memmap_vector_storage_byte(dir.path(), DIM, Distance::Dot).unwrap();
+        storage
+            .borrow_mut()
+            .update_from(
+                &source.borrow(),
+                &mut Box::new(0..vectors.len() as PointOffsetType),
+                &Default::default(),
+            )
+            .unwrap();
+        check_vectors(&storage, &vectors);
+        check_scoring(&storage, &vectors, Distance::Dot);
+    }
+
+    let storage = open_memmap_vector_storage_byte(dir.path(), DIM, Distance::Dot).unwrap();
+    check_vectors(&storage, &vectors);
+}
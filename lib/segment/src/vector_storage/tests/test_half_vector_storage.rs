@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use half::f16;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tempfile::Builder;
+
+use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+use crate::data_types::vectors::VectorElementType;
+use crate::fixtures::payload_context_fixture::FixtureIdTracker;
+use crate::id_tracker::IdTrackerSS;
+use crate::spaces::simple::{
+    dot_similarity, euclid_similarity, hamming_similarity, manhattan_similarity,
+};
+use crate::types::{Distance, PointOffsetType};
+use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage_half;
+use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage_half;
+use crate::vector_storage::simple_vector_storage::{
+    open_simple_vector_storage, open_simple_vector_storage_half,
+};
+use crate::vector_storage::{new_raw_scorer, VectorStorage, VectorStorageEnum};
+
+const DIM: usize = 16;
+const NUM_VECTORS: usize = 50;
+
+fn random_vectors() -> Vec<Vec<VectorElementType>> {
+    let mut rnd = StdRng::seed_from_u64(42);
+    (0..NUM_VECTORS)
+        .map(|_| (0..DIM).map(|_| rnd.gen_range(-100.0..100.0)).collect())
+        .collect()
+}
+
+fn insert_vectors(storage: &Arc<AtomicRefCell<VectorStorageEnum>>, vectors: &[Vec<f32>]) {
+    let mut borrowed_storage = storage.borrow_mut();
+    for (i, vector) in vectors.iter().enumerate() {
+        borrowed_storage
+            .insert_vector(i as PointOffsetType, vector)
+            .unwrap();
+    }
+}
+
+/// Stored vectors must come back as the closest half-precision values of the inserted ones
+fn check_half_precision(storage: &Arc<AtomicRefCell<VectorStorageEnum>>, vectors: &[Vec<f32>]) {
+    let borrowed_storage = storage.borrow();
+    assert_eq!(borrowed_storage.total_vector_count(), vectors.len());
+    for (i, vector) in vectors.iter().enumerate() {
+        let stored = borrowed_storage.get_vector(i as PointOffsetType);
+        assert_eq!(stored.len(), DIM);
+        for (stored, original) in stored.iter().zip(vector) {
+            assert_eq!(*stored, f16::from_f32(*original).to_f32());
+            assert!((stored - original).abs() <= original.abs() * 1e-3);
+        }
+    }
+}
+
+/// Values representable in half-precision must survive storage unchanged
+fn check_exact_values(storage: &Arc<AtomicRefCell<VectorStorageEnum>>) {
+    let exact = vec![
+        vec![0.0, 1.0, -2.5, 0.125, 1024.0, -0.0009765625, 65504.0, 3.0],
+        vec![-1.0, 0.5, 2048.0, -65504.0, 0.25, 7.75, -3.0, 0.0],
+    ];
+    let mut borrowed_storage = storage.borrow_mut();
+    let offset = borrowed_storage.total_vector_count() as PointOffsetType;
+    for (i, vector) in exact.iter().enumerate() {
+        let mut vector = vector.clone();
+        vector.resize(DIM, 1.0);
+        borrowed_storage
+            .insert_vector(offset + i as PointOffsetType, &vector)
+            .unwrap();
+        assert_eq!(
+            borrowed_storage
+                .get_vector(offset + i as PointOffsetType)
+                .as_ref(),
+            vector.as_slice()
+        );
+    }
+}
+
+/// Scores over half-precision storage must match f32 scores of the rounded query and vectors
+fn check_scoring(
+    storage: &Arc<AtomicRefCell<VectorStorageEnum>>,
+    vectors: &[Vec<f32>],
+    distance: Distance,
+) {
+    let id_tracker: Arc<AtomicRefCell<IdTrackerSS>> =
+        Arc::new(AtomicRefCell::new(FixtureIdTracker::new(vectors.len())));
+    let borrowed_id_tracker = id_tracker.borrow();
+    let borrowed_storage = storage.borrow();
+
+    let query = vectors[0].clone();
+    let rounded_query: Vec<f32> = query.iter().map(|x| f16::from_f32(*x).to_f32()).collect();
+    let scorer = new_raw_scorer(
+        query,
+        &borrowed_storage,
+        borrowed_id_tracker.deleted_point_bitslice(),
+    );
+    for i in 0..vectors.len() as PointOffsetType {
+        let stored = borrowed_storage.get_vector(i);
+        let expected = match distance {
+            Distance::Dot => dot_similarity(&rounded_query, &stored),
+            Distance::Euclid => euclid_similarity(&rounded_query, &stored),
+            Distance::Manhattan => manhattan_similarity(&rounded_query, &stored),
+            Distance::Hamming => hamming_similarity(&rounded_query, &stored),
+            Distance::Cosine => unreachable!("cosine vectors are normalized before storing"),
+        };
+        let score = scorer.score_point(i);
+        assert!((score - expected).abs() <= expected.abs() * 1e-4 + 1e-3);
+    }
+}
+
+#[test]
+fn test_half_simple_vector_storage() {
+    let vectors = random_vectors();
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage =
+            open_simple_vector_storage_half(db, DB_VECTOR_CF, DIM, Distance::Dot).unwrap();
+        insert_vectors(&storage, &vectors);
+        check_half_precision(&storage, &vectors);
+        check_scoring(&storage, &vectors, Distance::Dot);
+        storage.borrow().flusher()().unwrap();
+    }
+
+    // Reopen, vectors must be restored from the database
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage = open_simple_vector_storage_half(db, DB_VECTOR_CF, DIM, Distance::Dot).unwrap();
+    check_half_precision(&storage, &vectors);
+    check_exact_values(&storage);
+}
+
+#[test]
+fn test_half_appendable_mmap_vector_storage() {
+    let vectors = random_vectors();
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let storage =
+            open_appendable_memmap_vector_storage_half(dir.path(), DIM, Distance::Euclid).unwrap();
+        insert_vectors(&storage, &vectors);
+        check_half_precision(&storage, &vectors);
+        check_scoring(&storage, &vectors, Distance::Euclid);
+        storage.borrow().flusher()().unwrap();
+    }
+
+    let storage =
+        open_appendable_memmap_vector_storage_half(dir.path(), DIM, Distance::Euclid).unwrap();
+    check_half_precision(&storage, &vectors);
+    check_exact_values(&storage);
+}
+
+#[test]
+fn test_half_memmap_vector_storage() {
+    let vectors = random_vectors();
+    let db_dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+    let db = open_db(db_dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let source = open_simple_vector_storage(db, DB_VECTOR_CF, DIM, Distance::Dot).unwrap();
+    insert_vectors(&source, &vectors);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let storage = open_memmap_vector_storage_half(dir.path(), DIM, Distance::Dot).unwrap();
+        storage
+            .borrow_mut()
+            .update_from(
+                &source.borrow(),
+                &mut Box::new(0..vectors.len() as PointOffsetType),
+                &Default::default(),
+            )
+            .unwrap();
+        check_half_precision(&storage, &vectors);
+        check_scoring(&storage, &vectors, Distance::Dot);
+    }
+
+    let storage = open_memmap_vector_storage_half(dir.path(), DIM, Distance::Dot).unwrap();
+    check_half_precision(&storage, &vectors);
+}
@@ -0,0 +1,271 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use bitvec::slice::BitSlice;
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom as _;
+use rand::{Rng, SeedableRng as _};
+use tempfile::Builder;
+
+use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
+use crate::fixtures::payload_context_fixture::FixtureIdTracker;
+use crate::id_tracker::IdTracker;
+use crate::types::{Distance, ExtendedPointId, PointOffsetType, ScoreType};
+use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
+use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage;
+use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
+use crate::vector_storage::{
+    new_raw_scorer, peek_top_batch, RawScorer, ScoredPointOffset, VectorStorage, VectorStorageEnum,
+    DEFAULT_STOPPED,
+};
+
+const DIM: usize = 16;
+const POINTS: usize = 1000;
+const DELETE: usize = 100;
+const TOP: usize = 10;
+
+/// Score points one by one and sort by score, to compare the optimized implementations with
+fn naive_peek_top(
+    scorer: &dyn RawScorer,
+    points: impl Iterator<Item = PointOffsetType>,
+    top: usize,
+) -> Vec<ScoredPointOffset> {
+    let mut scores: Vec<_> = points
+        .filter(|&point_id| scorer.check_vector(point_id))
+        .map(|point_id| ScoredPointOffset {
+            idx: point_id,
+            score: scorer.score_point(point_id),
+        })
+        .collect();
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+    scores.truncate(top);
+    scores
+}
+
+/// Scorer which takes `delay` to score every point, to check how soon a search is stopped
+struct SlowScorer<'a> {
+    scorer: Box<dyn RawScorer + 'a>,
+    delay: Duration,
+}
+
+impl RawScorer for SlowScorer<'_> {
+    fn score_points(&self, points: &[PointOffsetType], scores: &mut [ScoredPointOffset]) -> usize {
+        std::thread::sleep(self.delay * points.len() as u32);
+        self.scorer.score_points(points, scores)
+    }
+
+    fn score_points_unfiltered(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+    ) -> Vec<ScoredPointOffset> {
+        self.scorer.score_points_unfiltered(points)
+    }
+
+    fn check_vector(&self, point: PointOffsetType) -> bool {
+        self.scorer.check_vector(point)
+    }
+
+    fn score_point(&self, point: PointOffsetType) -> ScoreType {
+        self.scorer.score_point(point)
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        self.scorer.score_internal(point_a, point_b)
+    }
+
+    fn peek_top_iter(
+        &self,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+    ) -> Vec<ScoredPointOffset> {
+        self.scorer.peek_top_iter(points, top)
+    }
+
+    fn peek_top_all(&self, top: usize) -> Vec<ScoredPointOffset> {
+        self.scorer.peek_top_all(top)
+    }
+}
+
+fn fill_storage(
+    rng: &mut StdRng,
+    storage: &mut VectorStorageEnum,
+    id_tracker: &mut FixtureIdTracker,
+) {
+    for point_id in 0..POINTS as PointOffsetType {
+        let vector: Vec<_> = (0..DIM).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        storage.insert_vector(point_id, &vector).unwrap();
+    }
+    for point_id in (0..POINTS as PointOffsetType).choose_multiple(rng, DELETE) {
+        storage.delete_vector(point_id).unwrap();
+        id_tracker
+            .drop(ExtendedPointId::NumId(point_id.into()))
+            .unwrap();
+    }
+}
+
+fn check_peek_top(rng: &mut StdRng, storage: &VectorStorageEnum, deleted_points: &BitSlice) {
+    for _ in 0..10 {
+        let query: Vec<_> = (0..DIM).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        let scorer = new_raw_scorer(query, storage, deleted_points);
+
+        let expected = naive_peek_top(scorer.as_ref(), 0..POINTS as PointOffsetType, TOP);
+        assert_eq!(expected.len(), TOP);
+        assert_eq!(scorer.peek_top_all(TOP), expected);
+
+        // Points out of range must be ignored
+        let mut points = (0..POINTS as PointOffsetType + 10).choose_multiple(rng, POINTS / 2);
+        points.sort_unstable();
+        let expected = naive_peek_top(scorer.as_ref(), points.iter().copied(), TOP);
+        assert_eq!(
+            scorer.peek_top_iter(&mut points.iter().copied(), TOP),
+            expected
+        );
+
+        assert!(scorer
+            .peek_top_iter(&mut points.iter().copied(), 0)
+            .is_empty());
+        assert_eq!(
+            scorer.peek_top_iter(&mut points.iter().copied(), POINTS),
+            naive_peek_top(scorer.as_ref(), points.iter().copied(), POINTS)
+        );
+    }
+
+    // Batch of scorers in a single pass gives the same results as every scorer on its own
+    let scorers: Vec<_> = (0..5)
+        .map(|_| {
+            let query: Vec<_> = (0..DIM).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            new_raw_scorer(query, storage, deleted_points)
+        })
+        .collect();
+    let mut points = (0..POINTS as PointOffsetType + 10).choose_multiple(rng, POINTS / 2);
+    points.sort_unstable();
+    for top in [0, TOP, POINTS] {
+        let expected: Vec<_> = scorers
+            .iter()
+            .map(|scorer| naive_peek_top(scorer.as_ref(), points.iter().copied(), top))
+            .collect();
+        let batch = peek_top_batch(&scorers, &mut points.iter().copied(), top, &DEFAULT_STOPPED);
+        assert_eq!(batch, expected);
+    }
+}
+
+fn test_peek_top_simple(distance: Distance) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage = open_simple_vector_storage(db, DB_VECTOR_CF, DIM, distance).unwrap();
+    let mut id_tracker = FixtureIdTracker::new(POINTS);
+
+    fill_storage(&mut rng, &mut storage.borrow_mut(), &mut id_tracker);
+    check_peek_top(
+        &mut rng,
+        &storage.borrow(),
+        id_tracker.deleted_point_bitslice(),
+    );
+}
+
+fn test_peek_top_appendable_memmap(distance: Distance) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let storage = open_appendable_memmap_vector_storage(dir.path(), DIM, distance).unwrap();
+    let mut id_tracker = FixtureIdTracker::new(POINTS);
+
+    fill_storage(&mut rng, &mut storage.borrow_mut(), &mut id_tracker);
+    check_peek_top(
+        &mut rng,
+        &storage.borrow(),
+        id_tracker.deleted_point_bitslice(),
+    );
+}
+
+fn test_peek_top_memmap(distance: Distance) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let storage = open_memmap_vector_storage(dir.path(), DIM, distance).unwrap();
+    let mut id_tracker = FixtureIdTracker::new(POINTS);
+
+    {
+        let db_dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+        let db = open_db(db_dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let mutable_storage = open_simple_vector_storage(db, DB_VECTOR_CF, DIM, distance).unwrap();
+        fill_storage(&mut rng, &mut mutable_storage.borrow_mut(), &mut id_tracker);
+
+        storage
+            .borrow_mut()
+            .update_from(
+                &mutable_storage.borrow(),
+                &mut (0..POINTS as PointOffsetType),
+                &Default::default(),
+            )
+            .unwrap();
+    }
+
+    check_peek_top(
+        &mut rng,
+        &storage.borrow(),
+        id_tracker.deleted_point_bitslice(),
+    );
+}
+
+#[test]
+fn test_peek_top_simple_vector_storage() {
+    for distance in [Distance::Dot, Distance::Cosine, Distance::Euclid] {
+        test_peek_top_simple(distance);
+    }
+}
+
+#[test]
+fn test_peek_top_appendable_memmap_vector_storage() {
+    for distance in [Distance::Dot, Distance::Cosine, Distance::Euclid] {
+        test_peek_top_appendable_memmap(distance);
+    }
+}
+
+#[test]
+fn test_peek_top_memmap_vector_storage() {
+    for distance in [Distance::Dot, Distance::Cosine, Distance::Euclid] {
+        test_peek_top_memmap(distance);
+    }
+}
+
+#[test]
+fn test_peek_top_batch_stopped() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let storage = open_simple_vector_storage(db, DB_VECTOR_CF, DIM, Distance::Dot).unwrap();
+    let mut id_tracker = FixtureIdTracker::new(POINTS);
+    fill_storage(&mut rng, &mut storage.borrow_mut(), &mut id_tracker);
+
+    let storage = storage.borrow();
+    let scorers: Vec<Box<dyn RawScorer>> = (0..2)
+        .map(|_| {
+            let query: Vec<_> = (0..DIM).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let scorer = new_raw_scorer(query, &storage, id_tracker.deleted_point_bitslice());
+            Box::new(SlowScorer {
+                scorer,
+                delay: Duration::from_micros(50),
+            }) as Box<dyn RawScorer>
+        })
+        .collect();
+
+    // Scoring all the points would take 10 seconds
+    let mut points = (0..POINTS as PointOffsetType).cycle().take(100 * POINTS);
+    let deadline = Duration::from_millis(100);
+    let is_stopped = AtomicBool::new(false);
+
+    let start = Instant::now();
+    let results = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(deadline);
+            is_stopped.store(true, Ordering::Relaxed);
+        });
+        peek_top_batch(&scorers, &mut points, TOP, &is_stopped)
+    });
+    let elapsed = start.elapsed();
+
+    // Search stops within a chunk of scored points after the deadline
+    assert!(elapsed >= deadline, "{elapsed:?}");
+    assert!(elapsed < deadline * 5, "{elapsed:?}");
+    assert!(results.iter().all(|result| result.len() == TOP));
+}
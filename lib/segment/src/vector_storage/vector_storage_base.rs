@@ -1,15 +1,19 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 
 use bitvec::prelude::BitSlice;
+use half::f16;
 use ordered_float::OrderedFloat;
 
 use super::memmap_vector_storage::MemmapVectorStorage;
 use super::quantized::quantized_vectors::QuantizedVectors;
+use super::simple_multi_vector_storage::SimpleMultiVectorStorage;
 use super::simple_vector_storage::SimpleVectorStorage;
 use crate::common::Flusher;
+use crate::data_types::primitive::PrimitiveVectorElement;
 use crate::data_types::vectors::VectorElementType;
 use crate::entry::entry_point::OperationResult;
 use crate::types::{Distance, PointOffsetType, QuantizationConfig, ScoreType};
@@ -43,6 +47,11 @@ pub trait VectorStorage {
 
     fn distance(&self) -> Distance;
 
+    /// Whether vectors are stored as they were inserted, without distance preprocessing
+    ///
+    /// Scorers of such storage apply the preprocessing to stored vectors on the fly.
+    fn stores_original_vectors(&self) -> bool;
+
     /// Number of vectors
     ///
     /// - includes soft deleted vectors, as they are still stored
@@ -61,7 +70,9 @@ pub trait VectorStorage {
     }
 
     /// Number of all stored vectors including deleted
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType];
+    ///
+    /// Vectors stored with another element type are converted into [`VectorElementType`].
+    fn get_vector(&self, key: PointOffsetType) -> Cow<'_, [VectorElementType]>;
 
     fn insert_vector(
         &mut self,
@@ -128,42 +139,169 @@ pub trait VectorStorage {
     fn is_appendable(&self) -> bool;
 }
 
+/// Vector storage, which keeps dense vectors with element type `T` in place
+pub trait DenseVectorStorage<T: PrimitiveVectorElement>: VectorStorage {
+    /// Get the stored vector without conversion
+    fn get_dense(&self, key: PointOffsetType) -> &[T];
+}
+
 pub enum VectorStorageEnum {
-    Simple(SimpleVectorStorage),
-    Memmap(Box<MemmapVectorStorage>),
-    AppendableMemmap(Box<AppendableMmapVectorStorage>),
+    Simple(SimpleVectorStorage<VectorElementType>),
+    SimpleHalf(SimpleVectorStorage<f16>),
+    SimpleByte(SimpleVectorStorage<u8>),
+    Memmap(Box<MemmapVectorStorage<VectorElementType>>),
+    MemmapHalf(Box<MemmapVectorStorage<f16>>),
+    MemmapByte(Box<MemmapVectorStorage<u8>>),
+    AppendableMemmap(Box<AppendableMmapVectorStorage<VectorElementType>>),
+    AppendableMemmapHalf(Box<AppendableMmapVectorStorage<f16>>),
+    AppendableMemmapByte(Box<AppendableMmapVectorStorage<u8>>),
+    SimpleMulti(SimpleMultiVectorStorage),
+}
+
+impl VectorStorageEnum {
+    /// Mark vectors of this storage as stored without distance preprocessing
+    ///
+    /// Must match the way inserted vectors are transformed, see [`VectorDataConfig::preprocess_vector`].
+    ///
+    /// [`VectorDataConfig::preprocess_vector`]: crate::types::VectorDataConfig::preprocess_vector
+    pub fn set_original_vectors(&mut self, original_vectors: bool) {
+        match self {
+            VectorStorageEnum::Simple(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::SimpleHalf(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::SimpleByte(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::Memmap(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::MemmapHalf(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::MemmapByte(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::AppendableMemmap(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.set_original_vectors(original_vectors),
+            VectorStorageEnum::SimpleMulti(v) => v.set_original_vectors(original_vectors),
+        }
+    }
+
+    /// Advise the kernel to load vectors of the points from disk in background
+    ///
+    /// Only on-disk storages are affected, vectors of other storages are always in RAM.
+    pub fn prefetch_vectors(&self, points: &[PointOffsetType]) {
+        match self {
+            VectorStorageEnum::Memmap(v) => {
+                let mmap_vectors = v.get_mmap_vectors();
+                points.iter().for_each(|&p| mmap_vectors.prefetch_vector(p));
+            }
+            VectorStorageEnum::MemmapHalf(v) => {
+                let mmap_vectors = v.get_mmap_vectors();
+                points.iter().for_each(|&p| mmap_vectors.prefetch_vector(p));
+            }
+            VectorStorageEnum::MemmapByte(v) => {
+                let mmap_vectors = v.get_mmap_vectors();
+                points.iter().for_each(|&p| mmap_vectors.prefetch_vector(p));
+            }
+            VectorStorageEnum::Simple(_)
+            | VectorStorageEnum::SimpleHalf(_)
+            | VectorStorageEnum::SimpleByte(_)
+            | VectorStorageEnum::AppendableMemmap(_)
+            | VectorStorageEnum::AppendableMemmapHalf(_)
+            | VectorStorageEnum::AppendableMemmapByte(_)
+            | VectorStorageEnum::SimpleMulti(_) => {}
+        }
+    }
+
+    /// Estimated size of the vectors, which this storage holds in RAM, in bytes
+    ///
+    /// Vectors of mmap storages are not counted, their size is the size of the storage files.
+    pub fn ram_usage_bytes(&self) -> usize {
+        fn vectors_size<T>(storage: &impl VectorStorage) -> usize {
+            storage.total_vector_count() * storage.vector_dim() * std::mem::size_of::<T>()
+        }
+
+        match self {
+            VectorStorageEnum::Simple(v) => vectors_size::<VectorElementType>(v),
+            VectorStorageEnum::SimpleHalf(v) => vectors_size::<f16>(v),
+            VectorStorageEnum::SimpleByte(v) => vectors_size::<u8>(v),
+            VectorStorageEnum::SimpleMulti(v) => v.ram_usage_bytes(),
+            VectorStorageEnum::Memmap(_)
+            | VectorStorageEnum::MemmapHalf(_)
+            | VectorStorageEnum::MemmapByte(_)
+            | VectorStorageEnum::AppendableMemmap(_)
+            | VectorStorageEnum::AppendableMemmapHalf(_)
+            | VectorStorageEnum::AppendableMemmapByte(_) => 0,
+        }
+    }
 }
 
 impl VectorStorage for VectorStorageEnum {
     fn vector_dim(&self) -> usize {
         match self {
             VectorStorageEnum::Simple(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleHalf(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleByte(v) => v.vector_dim(),
             VectorStorageEnum::Memmap(v) => v.vector_dim(),
+            VectorStorageEnum::MemmapHalf(v) => v.vector_dim(),
+            VectorStorageEnum::MemmapByte(v) => v.vector_dim(),
             VectorStorageEnum::AppendableMemmap(v) => v.vector_dim(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.vector_dim(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleMulti(v) => v.vector_dim(),
         }
     }
 
     fn distance(&self) -> Distance {
         match self {
             VectorStorageEnum::Simple(v) => v.distance(),
+            VectorStorageEnum::SimpleHalf(v) => v.distance(),
+            VectorStorageEnum::SimpleByte(v) => v.distance(),
             VectorStorageEnum::Memmap(v) => v.distance(),
+            VectorStorageEnum::MemmapHalf(v) => v.distance(),
+            VectorStorageEnum::MemmapByte(v) => v.distance(),
             VectorStorageEnum::AppendableMemmap(v) => v.distance(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.distance(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.distance(),
+            VectorStorageEnum::SimpleMulti(v) => v.distance(),
+        }
+    }
+
+    fn stores_original_vectors(&self) -> bool {
+        match self {
+            VectorStorageEnum::Simple(v) => v.stores_original_vectors(),
+            VectorStorageEnum::SimpleHalf(v) => v.stores_original_vectors(),
+            VectorStorageEnum::SimpleByte(v) => v.stores_original_vectors(),
+            VectorStorageEnum::Memmap(v) => v.stores_original_vectors(),
+            VectorStorageEnum::MemmapHalf(v) => v.stores_original_vectors(),
+            VectorStorageEnum::MemmapByte(v) => v.stores_original_vectors(),
+            VectorStorageEnum::AppendableMemmap(v) => v.stores_original_vectors(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.stores_original_vectors(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.stores_original_vectors(),
+            VectorStorageEnum::SimpleMulti(v) => v.stores_original_vectors(),
         }
     }
 
     fn total_vector_count(&self) -> usize {
         match self {
             VectorStorageEnum::Simple(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleHalf(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleByte(v) => v.total_vector_count(),
             VectorStorageEnum::Memmap(v) => v.total_vector_count(),
+            VectorStorageEnum::MemmapHalf(v) => v.total_vector_count(),
+            VectorStorageEnum::MemmapByte(v) => v.total_vector_count(),
             VectorStorageEnum::AppendableMemmap(v) => v.total_vector_count(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.total_vector_count(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleMulti(v) => v.total_vector_count(),
         }
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
+    fn get_vector(&self, key: PointOffsetType) -> Cow<'_, [VectorElementType]> {
         match self {
             VectorStorageEnum::Simple(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.get_vector(key),
             VectorStorageEnum::Memmap(v) => v.get_vector(key),
+            VectorStorageEnum::MemmapHalf(v) => v.get_vector(key),
+            VectorStorageEnum::MemmapByte(v) => v.get_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.get_vector(key),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.get_vector(key),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleMulti(v) => v.get_vector(key),
         }
     }
 
@@ -174,8 +312,15 @@ impl VectorStorage for VectorStorageEnum {
     ) -> OperationResult<()> {
         match self {
             VectorStorageEnum::Simple(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleHalf(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleByte(v) => v.insert_vector(key, vector),
             VectorStorageEnum::Memmap(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::MemmapHalf(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::MemmapByte(v) => v.insert_vector(key, vector),
             VectorStorageEnum::AppendableMemmap(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleMulti(v) => v.insert_vector(key, vector),
         }
     }
 
@@ -187,16 +332,30 @@ impl VectorStorage for VectorStorageEnum {
     ) -> OperationResult<Range<PointOffsetType>> {
         match self {
             VectorStorageEnum::Simple(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleHalf(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleByte(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::Memmap(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::MemmapHalf(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::MemmapByte(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::AppendableMemmap(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleMulti(v) => v.update_from(other, other_ids, stopped),
         }
     }
 
     fn flusher(&self) -> Flusher {
         match self {
             VectorStorageEnum::Simple(v) => v.flusher(),
+            VectorStorageEnum::SimpleHalf(v) => v.flusher(),
+            VectorStorageEnum::SimpleByte(v) => v.flusher(),
             VectorStorageEnum::Memmap(v) => v.flusher(),
+            VectorStorageEnum::MemmapHalf(v) => v.flusher(),
+            VectorStorageEnum::MemmapByte(v) => v.flusher(),
             VectorStorageEnum::AppendableMemmap(v) => v.flusher(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.flusher(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.flusher(),
+            VectorStorageEnum::SimpleMulti(v) => v.flusher(),
         }
     }
 
@@ -211,76 +370,153 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => {
                 v.quantize(data_path, quantization_config, max_threads, stopped)
             }
+            VectorStorageEnum::SimpleHalf(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::SimpleByte(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
             VectorStorageEnum::Memmap(v) => {
                 v.quantize(data_path, quantization_config, max_threads, stopped)
             }
+            VectorStorageEnum::MemmapHalf(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::MemmapByte(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
             VectorStorageEnum::AppendableMemmap(v) => {
                 v.quantize(data_path, quantization_config, max_threads, stopped)
             }
+            VectorStorageEnum::AppendableMemmapHalf(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::AppendableMemmapByte(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::SimpleMulti(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
         }
     }
 
     fn load_quantization(&mut self, data_path: &Path) -> OperationResult<()> {
         match self {
             VectorStorageEnum::Simple(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleHalf(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleByte(v) => v.load_quantization(data_path),
             VectorStorageEnum::Memmap(v) => v.load_quantization(data_path),
+            VectorStorageEnum::MemmapHalf(v) => v.load_quantization(data_path),
+            VectorStorageEnum::MemmapByte(v) => v.load_quantization(data_path),
             VectorStorageEnum::AppendableMemmap(v) => v.load_quantization(data_path),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.load_quantization(data_path),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleMulti(v) => v.load_quantization(data_path),
         }
     }
 
     fn quantized_storage(&self) -> Option<&QuantizedVectors> {
         match self {
             VectorStorageEnum::Simple(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleHalf(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleByte(v) => v.quantized_storage(),
             VectorStorageEnum::Memmap(v) => v.quantized_storage(),
+            VectorStorageEnum::MemmapHalf(v) => v.quantized_storage(),
+            VectorStorageEnum::MemmapByte(v) => v.quantized_storage(),
             VectorStorageEnum::AppendableMemmap(v) => v.quantized_storage(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.quantized_storage(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleMulti(v) => v.quantized_storage(),
         }
     }
 
     fn files(&self) -> Vec<PathBuf> {
         match self {
             VectorStorageEnum::Simple(v) => v.files(),
+            VectorStorageEnum::SimpleHalf(v) => v.files(),
+            VectorStorageEnum::SimpleByte(v) => v.files(),
             VectorStorageEnum::Memmap(v) => v.files(),
+            VectorStorageEnum::MemmapHalf(v) => v.files(),
+            VectorStorageEnum::MemmapByte(v) => v.files(),
             VectorStorageEnum::AppendableMemmap(v) => v.files(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.files(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.files(),
+            VectorStorageEnum::SimpleMulti(v) => v.files(),
         }
     }
 
     fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
         match self {
             VectorStorageEnum::Simple(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.delete_vector(key),
             VectorStorageEnum::Memmap(v) => v.delete_vector(key),
+            VectorStorageEnum::MemmapHalf(v) => v.delete_vector(key),
+            VectorStorageEnum::MemmapByte(v) => v.delete_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.delete_vector(key),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.delete_vector(key),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleMulti(v) => v.delete_vector(key),
         }
     }
 
     fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
         match self {
             VectorStorageEnum::Simple(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.is_deleted_vector(key),
             VectorStorageEnum::Memmap(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::MemmapHalf(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::MemmapByte(v) => v.is_deleted_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleMulti(v) => v.is_deleted_vector(key),
         }
     }
 
     fn deleted_vector_count(&self) -> usize {
         match self {
             VectorStorageEnum::Simple(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleHalf(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleByte(v) => v.deleted_vector_count(),
             VectorStorageEnum::Memmap(v) => v.deleted_vector_count(),
+            VectorStorageEnum::MemmapHalf(v) => v.deleted_vector_count(),
+            VectorStorageEnum::MemmapByte(v) => v.deleted_vector_count(),
             VectorStorageEnum::AppendableMemmap(v) => v.deleted_vector_count(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.deleted_vector_count(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleMulti(v) => v.deleted_vector_count(),
         }
     }
 
     fn deleted_vector_bitslice(&self) -> &BitSlice {
         match self {
             VectorStorageEnum::Simple(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleHalf(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleByte(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::Memmap(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::MemmapHalf(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::MemmapByte(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::AppendableMemmap(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleMulti(v) => v.deleted_vector_bitslice(),
         }
     }
 
     fn is_appendable(&self) -> bool {
         match self {
             VectorStorageEnum::Simple(v) => v.is_appendable(),
+            VectorStorageEnum::SimpleHalf(v) => v.is_appendable(),
+            VectorStorageEnum::SimpleByte(v) => v.is_appendable(),
             VectorStorageEnum::Memmap(v) => v.is_appendable(),
+            VectorStorageEnum::MemmapHalf(v) => v.is_appendable(),
+            VectorStorageEnum::MemmapByte(v) => v.is_appendable(),
             VectorStorageEnum::AppendableMemmap(v) => v.is_appendable(),
+            VectorStorageEnum::AppendableMemmapHalf(v) => v.is_appendable(),
+            VectorStorageEnum::AppendableMemmapByte(v) => v.is_appendable(),
+            VectorStorageEnum::SimpleMulti(v) => v.is_appendable(),
         }
     }
 }
@@ -38,9 +38,13 @@ fn test_batch_and_single_request_equivalency() {
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
             },
         )]),
         payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
     };
 
     let int_key = "int";
@@ -106,12 +110,16 @@ fn test_batch_and_single_request_equivalency() {
         let batch_res = segment
             .search_batch(
                 DEFAULT_VECTOR_NAME,
-                &[&query_vector_1, &query_vector_2],
+                &[
+                    &query_vector_1.clone().into(),
+                    &query_vector_2.clone().into(),
+                ],
                 &WithPayload::default(),
                 &false.into(),
                 Some(&filter),
                 10,
                 None,
+                None,
                 &false.into(),
             )
             .unwrap();
@@ -0,0 +1,312 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::{only_default_vector, VectorElementType, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::{OperationError, SegmentEntry};
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Distance, HnswConfig, Indexes, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig,
+    VectorStorageDatatype, VectorStorageType,
+};
+use tempfile::Builder;
+
+const DIM: usize = 64;
+const NUM_VECTORS: u64 = 3000;
+
+fn random_byte_vector(rnd: &mut StdRng, dim: usize) -> Vec<VectorElementType> {
+    (0..dim)
+        .map(|_| VectorElementType::from(rnd.gen::<u8>()))
+        .collect()
+}
+
+fn segment_config(
+    dim: usize,
+    distance: Distance,
+    storage_type: VectorStorageType,
+    datatype: VectorStorageDatatype,
+) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance,
+                storage_type,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: Some(datatype),
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+fn build_random_segment(
+    path: &Path,
+    distance: Distance,
+    storage_type: VectorStorageType,
+    datatype: VectorStorageDatatype,
+) -> Segment {
+    let config = segment_config(DIM, distance, storage_type, datatype);
+
+    // Same seed, so every segment gets the same vectors
+    let mut rnd = StdRng::seed_from_u64(42);
+    let mut segment = build_segment(path, &config, true).unwrap();
+    for n in 0..NUM_VECTORS {
+        let vector = random_byte_vector(&mut rnd, DIM);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+    segment
+}
+
+#[test]
+fn byte_vector_validation_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let config = segment_config(
+        4,
+        Distance::Dot,
+        VectorStorageType::Memory,
+        VectorStorageDatatype::Uint8,
+    );
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+    segment
+        .upsert_point(1, 1.into(), only_default_vector(&[0.0, 1.0, 128.0, 255.0]))
+        .unwrap();
+
+    for (op_num, invalid) in [
+        (2, [0.0, 1.5, 2.0, 3.0]),
+        (3, [0.0, 1.0, 256.0, 3.0]),
+        (4, [0.0, 1.0, 2.0, -1.0]),
+        (5, [f32::NAN, 1.0, 2.0, 3.0]),
+    ] {
+        let err = segment
+            .upsert_point(op_num, 2.into(), only_default_vector(&invalid))
+            .unwrap_err();
+        assert!(
+            matches!(err, OperationError::WrongVectorElement { .. }),
+            "unexpected error: {err}"
+        );
+        assert!(err.to_string().contains("point 2"), "{err}");
+
+        // Existing point can't be updated with an invalid vector either
+        let err = segment
+            .update_vectors(op_num, 1.into(), only_default_vector(&invalid))
+            .unwrap_err();
+        assert!(
+            matches!(err, OperationError::WrongVectorElement { .. }),
+            "unexpected error: {err}"
+        );
+        assert!(err.to_string().contains("point 1"), "{err}");
+    }
+
+    // Rejected operations leave no traces
+    assert_eq!(segment.available_point_count(), 1);
+    let stored = segment.vector(DEFAULT_VECTOR_NAME, 1.into()).unwrap();
+    assert_eq!(stored, Some(vec![0.0, 1.0, 128.0, 255.0]));
+}
+
+#[test]
+fn byte_vector_named_validation_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut config = segment_config(
+        2,
+        Distance::Dot,
+        VectorStorageType::Memory,
+        VectorStorageDatatype::Float32,
+    );
+    let byte_config = segment_config(
+        2,
+        Distance::Dot,
+        VectorStorageType::Memory,
+        VectorStorageDatatype::Uint8,
+    )
+    .vector_data
+    .remove(DEFAULT_VECTOR_NAME)
+    .unwrap();
+    config.vector_data.insert("bytes".to_owned(), byte_config);
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+    let mut vectors = NamedVectors::default();
+    vectors.insert(DEFAULT_VECTOR_NAME.to_owned(), vec![0.5, -0.5]);
+    vectors.insert("bytes".to_owned(), vec![3.0, 7.0]);
+    segment.upsert_point(1, 1.into(), vectors).unwrap();
+
+    let mut vectors = NamedVectors::default();
+    vectors.insert(DEFAULT_VECTOR_NAME.to_owned(), vec![0.5, -0.5]);
+    vectors.insert("bytes".to_owned(), vec![3.0, 0.5]);
+    let err = segment.upsert_point(2, 2.into(), vectors).unwrap_err();
+    let description = err.to_string();
+    assert!(description.contains("point 2"), "{description}");
+    assert!(description.contains("position 1"), "{description}");
+    assert!(description.contains("`bytes`"), "{description}");
+}
+
+/// Scores over byte storage must be the same as over f32 storage with the same vectors
+fn byte_scoring_equivalence_test(distance: Distance, storage_type: VectorStorageType) {
+    let stopped = AtomicBool::new(false);
+    let top = NUM_VECTORS as usize;
+
+    let float_dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let float_segment = build_random_segment(
+        float_dir.path(),
+        distance,
+        VectorStorageType::Memory,
+        VectorStorageDatatype::Float32,
+    );
+
+    let byte_dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let byte_segment = build_random_segment(
+        byte_dir.path(),
+        distance,
+        storage_type,
+        VectorStorageDatatype::Uint8,
+    );
+
+    // Vectors come back exactly as they were inserted, f32 cosine vectors are normalized though
+    if distance != Distance::Cosine {
+        for n in [0u64, 1, NUM_VECTORS / 2, NUM_VECTORS - 1] {
+            assert_eq!(
+                byte_segment.vector(DEFAULT_VECTOR_NAME, n.into()).unwrap(),
+                float_segment.vector(DEFAULT_VECTOR_NAME, n.into()).unwrap(),
+            );
+        }
+    }
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    for _ in 0..5 {
+        let query = random_byte_vector(&mut rnd, DIM);
+        let float_result = float_segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&query], None, top, None, &stopped);
+        let byte_result = byte_segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&query], None, top, None, &stopped);
+
+        let float_scores: HashMap<_, _> = float_result[0]
+            .iter()
+            .map(|scored| (scored.idx, scored.score))
+            .collect();
+        assert_eq!(byte_result[0].len(), float_scores.len());
+        for scored in &byte_result[0] {
+            let expected = float_scores[&scored.idx];
+            assert!(
+                (scored.score - expected).abs() <= expected.abs() * 1e-5,
+                "score {} differs from f32 reference {expected}",
+                scored.score,
+            );
+        }
+    }
+}
+
+#[test]
+fn byte_scoring_dot_memory_test() {
+    byte_scoring_equivalence_test(Distance::Dot, VectorStorageType::Memory);
+}
+
+#[test]
+fn byte_scoring_cosine_memory_test() {
+    byte_scoring_equivalence_test(Distance::Cosine, VectorStorageType::Memory);
+}
+
+#[test]
+fn byte_scoring_euclid_chunked_mmap_test() {
+    byte_scoring_equivalence_test(Distance::Euclid, VectorStorageType::ChunkedMmap);
+}
+
+/// HNSW built over byte vectors must find mostly the same neighbours as exact search
+fn hnsw_byte_search_test(distance: Distance) {
+    let stopped = AtomicBool::new(false);
+    let top = 10;
+    let attempts = 10;
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = build_random_segment(
+        dir.path(),
+        distance,
+        VectorStorageType::Memory,
+        VectorStorageDatatype::Uint8,
+    );
+
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 64,
+        full_scan_threshold: usize::MAX,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    let mut sames = 0;
+    for _ in 0..attempts {
+        let query = random_byte_vector(&mut rnd, DIM);
+
+        let index_result = hnsw_index.search(
+            &[&query],
+            None,
+            top,
+            Some(&SearchParams {
+                hnsw_ef: Some(128),
+                ..Default::default()
+            }),
+            &stopped,
+        );
+        let exact_result = segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&query], None, top, None, &stopped);
+
+        let exact_ids: BTreeSet<_> = exact_result[0].iter().map(|x| x.idx).collect();
+        sames += index_result[0]
+            .iter()
+            .filter(|x| exact_ids.contains(&x.idx))
+            .count();
+    }
+
+    let acc = 100.0 * sames as f64 / (attempts * top) as f64;
+    println!("sames = {sames}, attempts = {attempts}, top = {top}, acc = {acc}");
+    assert!(acc > 90.0, "recall {acc} is too low");
+}
+
+#[test]
+fn hnsw_byte_search_dot_test() {
+    hnsw_byte_search_test(Distance::Dot);
+}
+
+#[test]
+fn hnsw_byte_search_cosine_test() {
+    hnsw_byte_search_test(Distance::Cosine);
+}
+
+#[test]
+fn hnsw_byte_search_euclid_test() {
+    hnsw_byte_search_test(Distance::Euclid);
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use segment::data_types::order_by::{Direction, OrderBy, OrderValue};
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::{OperationError, SegmentEntry};
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Condition, DateTimePayloadType, DatetimeRange, Distance, FieldCondition, Filter, Indexes,
+    Payload, PayloadSchemaType, PointIdType, SegmentConfig, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+/// Datetimes of points, in different precisions and timezones
+const DATETIMES: [&str; 6] = [
+    "2023-01-01",
+    "2023-01-01T10:00:00Z",
+    "2023-01-01T12:00:00.123456+02:00",
+    "2023-01-01 23:59",
+    "2023-01-02T00:00:00-01:00",
+    "2022-12-31T23:59:59.999",
+];
+
+fn fixture(path: &std::path::Path) -> Segment {
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: 2,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(path, &config, true).unwrap();
+    segment
+        .create_field_index(0, "date", Some(&PayloadSchemaType::Datetime.into()))
+        .unwrap();
+
+    for (n, datetime) in DATETIMES.iter().enumerate() {
+        let op_num = n as u64 + 1;
+        let point_id = PointIdType::from(n as u64);
+        segment
+            .upsert_point(op_num, point_id, only_default_vector(&[1.0, 0.0]))
+            .unwrap();
+        segment
+            .set_full_payload(op_num, point_id, &json!({ "date": datetime }).into())
+            .unwrap();
+    }
+    segment
+}
+
+fn datetime(value: &str) -> DateTimePayloadType {
+    value.parse().unwrap()
+}
+
+fn datetime_filter(range: DatetimeRange) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_range("date", range)))
+}
+
+#[test]
+fn test_datetime_range_filter() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+
+    let range = DatetimeRange {
+        gte: Some(datetime("2023-01-01")),
+        lt: Some(datetime("2023-01-01T12:00:00Z")),
+        ..Default::default()
+    };
+    let filter = datetime_filter(range.clone());
+    let points = segment.read_filtered(None, None, Some(&filter));
+
+    // Brute force over the parsed datetimes gives the same points
+    let expected = (0..DATETIMES.len() as u64)
+        .filter(|n| range.check_range(datetime(DATETIMES[*n as usize])))
+        .map(PointIdType::from)
+        .collect_vec();
+    assert_eq!(points, expected);
+    assert_eq!(points, [0, 1, 2].map(PointIdType::from));
+
+    // Datetime without a timezone is in UTC
+    let filter = datetime_filter(DatetimeRange {
+        gt: Some(datetime("2023-01-01T23:00:00Z")),
+        ..Default::default()
+    });
+    let points = segment.read_filtered(None, None, Some(&filter));
+    assert_eq!(points, [3, 4].map(PointIdType::from));
+
+    // Retrieved payload is the original string, not the normalized datetime
+    for (n, value) in DATETIMES.iter().enumerate() {
+        let payload = segment.payload(PointIdType::from(n as u64)).unwrap();
+        assert_eq!(payload, json!({ "date": value }).into());
+    }
+}
+
+#[test]
+fn test_datetime_invalid_value() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut segment = fixture(dir.path());
+    let point_id = PointIdType::from(0);
+
+    let payload: Payload = json!({ "date": ["2023-01-01", "not a date"] }).into();
+    let result = segment.set_payload(100, point_id, &payload);
+    match result {
+        Err(OperationError::ValidationError { description }) => {
+            assert!(description.contains("not a date"), "{description}")
+        }
+        other => panic!("Expected validation error, got {other:?}"),
+    }
+    let result = segment.set_full_payload(101, point_id, &payload);
+    assert!(matches!(
+        result,
+        Err(OperationError::ValidationError { .. })
+    ));
+
+    // Payload and index are left unchanged
+    assert_eq!(
+        segment.payload(point_id).unwrap(),
+        json!({ "date": DATETIMES[0] }).into()
+    );
+    let filter = datetime_filter(DatetimeRange {
+        lte: Some(datetime("2023-01-01")),
+        ..Default::default()
+    });
+    let points = segment.read_filtered(None, None, Some(&filter));
+    assert_eq!(points, [0, 5].map(PointIdType::from));
+
+    // Index can't be built over an invalid value either
+    segment
+        .set_payload(102, point_id, &json!({ "other": "not a date" }).into())
+        .unwrap();
+    let result =
+        segment.create_field_index(103, "other", Some(&PayloadSchemaType::Datetime.into()));
+    assert!(matches!(
+        result,
+        Err(OperationError::ValidationError { .. })
+    ));
+}
+
+#[test]
+fn test_datetime_order_by() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+
+    let order_by = OrderBy {
+        key: "date".to_string(),
+        direction: Some(Direction::Desc),
+        start_from: None,
+    };
+    let points = segment
+        .read_ordered_filtered(None, None, None, &order_by)
+        .unwrap();
+
+    let expected = DATETIMES
+        .iter()
+        .enumerate()
+        .map(|(n, value)| {
+            let value = OrderValue::Int(datetime(value).timestamp());
+            (value, PointIdType::from(n as u64))
+        })
+        .sorted_by(|a, b| Direction::Desc.compare(a, b))
+        .collect_vec();
+    assert_eq!(points, expected);
+    assert_eq!(
+        points.iter().map(|(_, id)| *id).collect_vec(),
+        [4, 3, 2, 1, 0, 5].map(PointIdType::from)
+    );
+}
@@ -0,0 +1,147 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, QueryVector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Distance, HnswConfig, Indexes, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig,
+    VectorStorageType,
+};
+use segment::vector_storage::query::discovery_query::{ContextPair, DiscoveryQuery};
+use tempfile::Builder;
+
+fn discovery_search_test(distance: Distance) {
+    let stopped = AtomicBool::new(false);
+
+    let dim = 16;
+    let num_vectors: u64 = 2_000;
+    let num_queries = 10;
+    let top = 10;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..num_vectors {
+        let vector = random_vector(&mut rnd, dim);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 100,
+        full_scan_threshold: 1, // KB
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let plain_index = segment.vector_data[DEFAULT_VECTOR_NAME]
+        .vector_index
+        .borrow();
+
+    let params = SearchParams {
+        hnsw_ef: Some(100),
+        ..Default::default()
+    };
+    let exact_params = SearchParams {
+        exact: true,
+        ..Default::default()
+    };
+
+    let mut sames = 0;
+    for _ in 0..num_queries {
+        let query: QueryVector = DiscoveryQuery::new(
+            random_vector(&mut rnd, dim),
+            vec![ContextPair::new(
+                random_vector(&mut rnd, dim),
+                random_vector(&mut rnd, dim),
+            )],
+        )
+        .into();
+
+        let plain_result = plain_index
+            .search_queries(&[&query], None, top, None, None, &stopped)
+            .remove(0);
+        assert_eq!(plain_result.len(), top);
+
+        // Exact search through the HNSW index scores every point by the query as well
+        let exact_result = hnsw_index
+            .search_queries(&[&query], None, top, None, Some(&exact_params), &stopped)
+            .remove(0);
+        assert_eq!(exact_result, plain_result);
+
+        // Graph search reranks candidates by the query, so scores are comparable with exact ones
+        let index_result = hnsw_index
+            .search_queries(&[&query], None, top, None, Some(&params), &stopped)
+            .remove(0);
+        assert_eq!(index_result.len(), top);
+        assert!(index_result
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.score >= b.score));
+        assert!(index_result[0].score <= plain_result[0].score);
+
+        let expected: BTreeSet<_> = plain_result.iter().map(|scored| scored.idx).collect();
+        sames += index_result
+            .iter()
+            .filter(|scored| expected.contains(&scored.idx))
+            .count();
+    }
+
+    let acc = 100.0 * sames as f64 / (num_queries * top) as f64;
+    println!("sames = {sames}, queries = {num_queries}, top = {top}, acc = {acc}");
+    assert!(acc > 60.0, "{acc}");
+}
+
+#[test]
+fn discovery_search_dot_test() {
+    discovery_search_test(Distance::Dot);
+}
+
+#[test]
+fn discovery_search_euclid_test() {
+    discovery_search_test(Distance::Euclid);
+}
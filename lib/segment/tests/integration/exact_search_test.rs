@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::sync::atomic::AtomicBool;
 
 use itertools::Itertools;
+use rand::rngs::ThreadRng;
 use rand::{thread_rng, Rng};
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
@@ -15,6 +17,7 @@ use segment::types::{
     PointOffsetType, Range, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig,
     VectorStorageType,
 };
+use segment::vector_storage::ScoredPointOffset;
 use serde_json::json;
 use tempfile::Builder;
 
@@ -46,9 +49,13 @@ fn exact_search_test() {
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
             },
         )]),
         payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
     };
 
     let int_key = "int";
@@ -197,3 +204,157 @@ fn exact_search_test() {
         );
     }
 }
+
+/// Exact search through the HNSW index must match a brute force scan over all live points
+#[test]
+fn exact_search_matches_brute_force_test() {
+    let stopped = AtomicBool::new(false);
+
+    let dim = 8;
+    let num_vectors: u64 = 2_000;
+    let distance = Distance::Dot;
+    let int_key = "int";
+    let top = 10;
+    let attempts = 20;
+
+    let mut rnd = thread_rng();
+    // Small integer components, so scores are computed exactly in any summation order
+    let random_int_vector = |rnd: &mut ThreadRng| -> Vec<f32> {
+        (0..dim).map(|_| rnd.gen_range(0..4) as f32).collect()
+    };
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    let mut vectors = vec![];
+    for n in 0..num_vectors {
+        let idx = n.into();
+        let vector = random_int_vector(&mut rnd);
+        let payload: Payload = json!({ int_key: n % 100 }).into();
+        segment
+            .upsert_point(n as SeqNumberType, idx, only_default_vector(&vector))
+            .unwrap();
+        segment
+            .set_full_payload(n as SeqNumberType, idx, &payload)
+            .unwrap();
+        vectors.push(vector);
+    }
+
+    // Deleted points must not be returned by exact search
+    let is_deleted = |n: u64| n % 7 == 0;
+    for n in (0..num_vectors).filter(|n| is_deleted(*n)) {
+        segment.delete_point(num_vectors + n, n.into()).unwrap();
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 8,
+        ef_construct: 16,
+        full_scan_threshold: 4, // KB
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    // Points of a fresh segment get internal ids in insertion order
+    // Scores of top points with payload value in the given range
+    let brute_force = |query: &[f32], range: RangeInclusive<u64>| -> Vec<f32> {
+        let mut scores = (0..num_vectors)
+            .filter(|n| !is_deleted(*n) && range.contains(&(n % 100)))
+            .map(|n| {
+                vectors[n as usize]
+                    .iter()
+                    .zip(query)
+                    .map(|(a, b)| a * b)
+                    .sum::<f32>()
+            })
+            .collect_vec();
+        scores.sort_by(|a, b| b.total_cmp(a));
+        scores.truncate(top);
+        scores
+    };
+    let check_result = |query: &[f32], result: &[ScoredPointOffset], expected_scores: &[f32]| {
+        let scores = result.iter().map(|scored| scored.score).collect_vec();
+        assert_eq!(
+            scores, expected_scores,
+            "exact search differs from brute force"
+        );
+        for scored in result {
+            let n = scored.idx as u64;
+            assert!(!is_deleted(n), "deleted point {n} is returned");
+            let score: f32 = vectors[n as usize]
+                .iter()
+                .zip(query)
+                .map(|(a, b)| a * b)
+                .sum();
+            assert_eq!(score, scored.score, "wrong score of point {n}");
+        }
+    };
+
+    let exact_params = SearchParams {
+        exact: true,
+        ..Default::default()
+    };
+    for _ in 0..attempts {
+        let query = random_int_vector(&mut rnd);
+
+        let exact_result = hnsw_index.search(&[&query], None, top, Some(&exact_params), &stopped);
+        check_result(&query, &exact_result[0], &brute_force(&query, 0..=99));
+
+        // Approximate search returns results of the same shape
+        let approximate_result = hnsw_index.search(&[&query], None, top, None, &stopped);
+        assert_eq!(approximate_result.len(), 1);
+        assert_eq!(approximate_result[0].len(), top);
+
+        let from = rnd.gen_range(0..80);
+        let to = from + 20;
+        let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+            int_key.to_owned(),
+            Range {
+                lt: None,
+                gt: None,
+                gte: Some(from as f64),
+                lte: Some(to as f64),
+            },
+        )));
+        let exact_result =
+            hnsw_index.search(&[&query], Some(&filter), top, Some(&exact_params), &stopped);
+        check_result(&query, &exact_result[0], &brute_force(&query, from..=to));
+    }
+
+    // Exact and approximate searches are counted separately
+    let telemetry = hnsw_index.get_telemetry_data();
+    assert_eq!(telemetry.unfiltered_exact.count, attempts);
+    assert_eq!(telemetry.filtered_exact.count, attempts);
+    assert_eq!(telemetry.unfiltered_hnsw.count, attempts);
+}
@@ -0,0 +1,192 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::search_plan::SearchPath;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::{PayloadIndex, VectorIndex};
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, HnswConfig, Indexes, Payload, PayloadSchemaType,
+    Range, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig, VectorStorageType,
+};
+use segment::vector_storage::ScoredPointOffset;
+use serde_json::json;
+use tempfile::Builder;
+
+const DIM: usize = 8;
+const NUM_VECTORS: u64 = 5_000;
+const TOP: usize = 10;
+const EF: usize = 64;
+const NUM_QUERIES: usize = 50;
+/// Payload values are uniform in `0..NUM_VALUES`, so a `lt: k` range matches `k` percent of points
+const NUM_VALUES: i64 = 100;
+const NUM_KEY: &str = "num";
+
+fn recall(result: &[ScoredPointOffset], expected: &[ScoredPointOffset]) -> f64 {
+    let expected_ids: BTreeSet<_> = expected.iter().map(|x| x.idx).collect();
+    let found = result
+        .iter()
+        .filter(|x| expected_ids.contains(&x.idx))
+        .count();
+    found as f64 / expected.len() as f64
+}
+
+fn percent_filter(percent: i64) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_range(
+        NUM_KEY,
+        Range {
+            lt: Some(percent as f64),
+            ..Default::default()
+        },
+    )))
+}
+
+#[test]
+fn test_filtered_hnsw_traversal_recall() {
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..NUM_VECTORS {
+        let vector = random_vector(&mut rnd, DIM);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+        let payload: Payload = json!({ NUM_KEY: rnd.gen_range(0..NUM_VALUES) }).into();
+        segment
+            .set_full_payload(n as SeqNumberType, n.into(), &payload)
+            .unwrap();
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 8,
+        ef_construct: 32,
+        full_scan_threshold: 1, // KB
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    // Field is indexed after the graph is built, so there are no extra links between points
+    // matching the filters, and filters are estimated precisely
+    hnsw_index.build_index(&stopped).unwrap();
+    segment
+        .payload_index
+        .borrow_mut()
+        .set_indexed(NUM_KEY, PayloadSchemaType::Integer.into())
+        .unwrap();
+
+    let queries = (0..NUM_QUERIES)
+        .map(|_| random_vector(&mut rnd, DIM))
+        .collect_vec();
+    let params = SearchParams {
+        hnsw_ef: Some(EF),
+        ..Default::default()
+    };
+    let plain_index = segment.vector_data[DEFAULT_VECTOR_NAME]
+        .vector_index
+        .borrow();
+
+    // With m0 = 16, links between matching points are followed only if at least half of the
+    // points match, filters matching fewer points are traversed through all points
+    for (percent, search_path) in [
+        (3, SearchPath::FilteredHnswTraversal),
+        (10, SearchPath::FilteredHnswTraversal),
+        (25, SearchPath::FilteredHnswTraversal),
+        (80, SearchPath::FilteredHnsw),
+    ] {
+        let filter = percent_filter(percent);
+        assert_eq!(
+            hnsw_index.search_path(Some(&filter), Some(&params)),
+            search_path,
+            "{percent}%",
+        );
+
+        let mut traversal_recall = 0.0;
+        let mut pruned_recall = 0.0;
+        for query in &queries {
+            let expected = plain_index
+                .search(&[query], Some(&filter), TOP, None, &stopped)
+                .remove(0);
+            assert_eq!(expected.len(), TOP);
+
+            let result = hnsw_index
+                .search(&[query], Some(&filter), TOP, Some(&params), &stopped)
+                .remove(0);
+            let traversal = hnsw_index.search_with_graph(
+                &query.clone().into(),
+                Some(&filter),
+                TOP,
+                None,
+                Some(&params),
+                true,
+                &stopped,
+            );
+            let pruned = hnsw_index.search_with_graph(
+                &query.clone().into(),
+                Some(&filter),
+                TOP,
+                None,
+                Some(&params),
+                false,
+                &stopped,
+            );
+            if search_path == SearchPath::FilteredHnswTraversal {
+                assert_eq!(result, traversal);
+            } else {
+                assert_eq!(result, pruned);
+            }
+
+            traversal_recall += recall(&traversal, &expected) / NUM_QUERIES as f64;
+            pruned_recall += recall(&pruned, &expected) / NUM_QUERIES as f64;
+        }
+        eprintln!(
+            "{percent}%: traversal recall = {traversal_recall}, pruned recall = {pruned_recall}"
+        );
+
+        assert!(traversal_recall > 0.95, "{percent}%: {traversal_recall}");
+        if search_path == SearchPath::FilteredHnswTraversal {
+            assert!(
+                traversal_recall >= pruned_recall,
+                "{percent}%: {traversal_recall} < {pruned_recall}",
+            );
+        }
+    }
+}
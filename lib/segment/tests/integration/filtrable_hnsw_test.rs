@@ -47,9 +47,13 @@ fn test_filterable_hnsw() {
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
             },
         )]),
         payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
     };
 
     let int_key = "int";
@@ -158,13 +162,15 @@ fn test_filterable_hnsw() {
         // let filter_query = None;
 
         let index_result = hnsw_index.search_with_graph(
-            &query,
+            &query.clone().into(),
             filter_query,
             top,
+            None,
             Some(&SearchParams {
                 hnsw_ef: Some(ef),
                 ..Default::default()
             }),
+            false,
             &false.into(),
         );
 
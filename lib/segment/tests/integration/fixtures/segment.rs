@@ -120,6 +120,9 @@ pub fn build_segment_3(path: &Path) -> Segment {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
                 (
@@ -130,6 +133,9 @@ pub fn build_segment_3(path: &Path) -> Segment {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
                 (
@@ -140,10 +146,14 @@ pub fn build_segment_3(path: &Path) -> Segment {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        datatype: None,
+                        multivector_config: None,
+                        store_original_vectors: false,
                     },
                 ),
             ]),
             payload_storage_type: Default::default(),
+            sparse_vector_data: Default::default(),
         },
         true,
     )
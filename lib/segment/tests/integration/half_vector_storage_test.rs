@@ -0,0 +1,148 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Distance, HnswConfig, Indexes, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig,
+    VectorStorageDatatype, VectorStorageType,
+};
+use tempfile::Builder;
+
+const DIM: usize = 64;
+const NUM_VECTORS: u64 = 3000;
+
+fn build_random_segment(
+    path: &Path,
+    distance: Distance,
+    storage_type: VectorStorageType,
+    datatype: VectorStorageDatatype,
+) -> Segment {
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance,
+                storage_type,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: Some(datatype),
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    // Same seed, so every segment gets the same vectors
+    let mut rnd = StdRng::seed_from_u64(42);
+    let mut segment = build_segment(path, &config, true).unwrap();
+    for n in 0..NUM_VECTORS {
+        let vector = random_vector(&mut rnd, DIM);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+    segment
+}
+
+/// HNSW over half-precision vectors must find mostly the same neighbours as exact search in f32
+fn hnsw_half_search_test(distance: Distance, storage_type: VectorStorageType) {
+    let stopped = AtomicBool::new(false);
+    let top = 10;
+    let attempts = 10;
+
+    let float_dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let float_segment = build_random_segment(
+        float_dir.path(),
+        distance,
+        VectorStorageType::Memory,
+        VectorStorageDatatype::Float32,
+    );
+
+    let half_dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let half_segment = build_random_segment(
+        half_dir.path(),
+        distance,
+        storage_type,
+        VectorStorageDatatype::Float16,
+    );
+
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 64,
+        full_scan_threshold: usize::MAX,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        half_segment.id_tracker.clone(),
+        half_segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        half_segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    let mut sames = 0;
+    for _ in 0..attempts {
+        let query = random_vector(&mut rnd, DIM);
+
+        let index_result = hnsw_index.search(
+            &[&query],
+            None,
+            top,
+            Some(&SearchParams {
+                hnsw_ef: Some(128),
+                ..Default::default()
+            }),
+            &stopped,
+        );
+        let exact_result = float_segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&query], None, top, None, &stopped);
+
+        let exact_ids: BTreeSet<_> = exact_result[0].iter().map(|x| x.idx).collect();
+        sames += index_result[0]
+            .iter()
+            .filter(|x| exact_ids.contains(&x.idx))
+            .count();
+    }
+
+    let acc = 100.0 * sames as f64 / (attempts * top) as f64;
+    println!("sames = {sames}, attempts = {attempts}, top = {top}, acc = {acc}");
+    assert!(acc > 90.0, "recall {acc} is too low");
+}
+
+#[test]
+fn hnsw_half_search_dot_memory_test() {
+    hnsw_half_search_test(Distance::Dot, VectorStorageType::Memory);
+}
+
+#[test]
+fn hnsw_half_search_cosine_memory_test() {
+    hnsw_half_search_test(Distance::Cosine, VectorStorageType::Memory);
+}
+
+#[test]
+fn hnsw_half_search_euclid_chunked_mmap_test() {
+    hnsw_half_search_test(Distance::Euclid, VectorStorageType::ChunkedMmap);
+}
@@ -0,0 +1,158 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Distance, HnswConfig, Indexes, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig,
+    VectorStorageType,
+};
+use segment::vector_storage::ScoredPointOffset;
+use tempfile::Builder;
+
+fn recall(result: &[ScoredPointOffset], expected: &[ScoredPointOffset]) -> f64 {
+    let expected_ids: BTreeSet<_> = expected.iter().map(|x| x.idx).collect();
+    let found = result
+        .iter()
+        .filter(|x| expected_ids.contains(&x.idx))
+        .count();
+    found as f64 / expected.len() as f64
+}
+
+#[test]
+fn hnsw_ef_recall_test() {
+    let stopped = AtomicBool::new(false);
+
+    let dim = 16;
+    let num_vectors: u64 = 5_000;
+    let top = 10;
+    let num_queries = 50;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance: Distance::Euclid,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..num_vectors {
+        let vector = random_vector(&mut rnd, dim);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+
+    // Sparse graph, so that small beams miss some of the nearest points
+    let hnsw_config = HnswConfig {
+        m: 8,
+        ef_construct: 16,
+        full_scan_threshold: 1, // KB
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let queries = (0..num_queries)
+        .map(|_| random_vector(&mut rnd, dim))
+        .collect_vec();
+    let plain_index = segment.vector_data[DEFAULT_VECTOR_NAME]
+        .vector_index
+        .borrow();
+    let expected = queries
+        .iter()
+        .map(|query| {
+            plain_index
+                .search(&[query], None, top, None, &stopped)
+                .remove(0)
+        })
+        .collect_vec();
+
+    let search = |hnsw_ef: usize, query: &[f32]| {
+        let params = SearchParams {
+            hnsw_ef: Some(hnsw_ef),
+            ..Default::default()
+        };
+        hnsw_index
+            .search(&[query], None, top, Some(&params), &stopped)
+            .remove(0)
+    };
+
+    // Recall grows with the beam width, up to full recall with a beam over the whole graph
+    let recalls = [
+        top,
+        2 * top,
+        4 * top,
+        8 * top,
+        16 * top,
+        num_vectors as usize,
+    ]
+    .into_iter()
+    .map(|hnsw_ef| {
+        let total: f64 = queries
+            .iter()
+            .zip(&expected)
+            .map(|(query, expected)| recall(&search(hnsw_ef, query), expected))
+            .sum();
+        total / num_queries as f64
+    })
+    .collect_vec();
+    eprintln!("recalls = {recalls:?}");
+    assert!(
+        recalls.windows(2).all(|pair| pair[0] <= pair[1]),
+        "recall doesn't grow with ef: {recalls:?}"
+    );
+    assert!(recalls.first() < recalls.last(), "{recalls:?}");
+    assert!(*recalls.last().unwrap() > 0.99, "{recalls:?}");
+
+    // None of the values above is out of range
+    assert_eq!(hnsw_index.get_telemetry_data().clamped_hnsw_ef, 0);
+
+    // Zero ef is raised to the limit, huge ef is limited by the number of points
+    let query = &queries[0];
+    assert_eq!(search(0, query), search(top, query));
+    assert_eq!(
+        search(usize::MAX, query),
+        search(num_vectors as usize, query)
+    );
+
+    let telemetry = hnsw_index.get_telemetry_data();
+    assert_eq!(telemetry.clamped_hnsw_ef, 2);
+    assert_eq!(telemetry.last_clamped_hnsw_ef, Some(num_vectors as usize));
+}
@@ -1,18 +1,21 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::atomic::AtomicBool;
 
-use rand::thread_rng;
-use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
-use segment::entry::entry_point::SegmentEntry;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use segment::data_types::search_plan::SearchPath;
+use segment::data_types::vectors::{only_default_vector, VectorElementType, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::{OperationError, SegmentEntry};
 use segment::fixtures::payload_fixtures::random_vector;
 use segment::index::hnsw_index::graph_links::GraphLinksRam;
 use segment::index::hnsw_index::hnsw::HNSWIndex;
 use segment::index::VectorIndex;
 use segment::segment_constructor::build_segment;
 use segment::types::{
-    CompressionRatio, Distance, HnswConfig, Indexes, ProductQuantizationConfig, QuantizationConfig,
+    BinaryQuantizationConfig, CompressionRatio, Condition, Distance, Filter, HnswConfig, Indexes,
+    PointIdType, ProductQuantizationConfig, QuantizationConfig, QuantizationSearchParams,
     ScalarQuantizationConfig, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig,
-    VectorStorageType,
+    VectorStorageType, VECTOR_ELEMENT_SIZE,
 };
 use segment::vector_storage::{ScoredPointOffset, VectorStorage};
 use tempfile::Builder;
@@ -53,9 +56,13 @@ fn hnsw_quantized_search_test(
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
             },
         )]),
         payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
     };
 
     let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -151,6 +158,20 @@ fn hnsw_quantized_search_euclid_test() {
     );
 }
 
+#[test]
+fn hnsw_quantized_search_manhattan_test() {
+    hnsw_quantized_search_test(
+        Distance::Manhattan,
+        5003,
+        ScalarQuantizationConfig {
+            r#type: Default::default(),
+            quantile: None,
+            always_ram: None,
+        }
+        .into(),
+    );
+}
+
 #[test]
 fn hnsw_product_quantization_cosine_test() {
     hnsw_quantized_search_test(
@@ -176,3 +197,461 @@ fn hnsw_product_quantization_euclid_test() {
         .into(),
     );
 }
+
+/// Compare recall of HNSW search over quantized vectors with re-scoring
+/// and recall of the same search over the original vectors.
+fn hnsw_quantized_rescore_recall_test(
+    distance: Distance,
+    dim: usize,
+    random_vector: impl Fn(&mut StdRng, usize) -> Vec<VectorElementType>,
+    oversampling: f64,
+    quantization_config: QuantizationConfig,
+) {
+    let stopped = AtomicBool::new(false);
+
+    let num_vectors = 2000;
+    let m = 16;
+    let ef = 64;
+    let ef_construct = 64;
+    let top = 10;
+    let attempts = 50;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let quantized_dir = Builder::new().prefix("quantized_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..num_vectors {
+        let idx = n.into();
+        let vector = random_vector(&mut rnd, dim);
+        segment
+            .upsert_point(n as SeqNumberType, idx, only_default_vector(&vector))
+            .unwrap();
+    }
+    segment.vector_data.values_mut().for_each(|vector_storage| {
+        vector_storage
+            .vector_storage
+            .borrow_mut()
+            .quantize(quantized_dir.path(), &quantization_config, 2, &stopped)
+            .unwrap();
+    });
+
+    let hnsw_config = HnswConfig {
+        m,
+        ef_construct,
+        full_scan_threshold: usize::MAX,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let search_params = |quantization| SearchParams {
+        hnsw_ef: Some(ef),
+        quantization: Some(quantization),
+        ..Default::default()
+    };
+    let full_precision_params = search_params(QuantizationSearchParams {
+        ignore: true,
+        ..Default::default()
+    });
+    let rescore_params = search_params(QuantizationSearchParams {
+        ignore: false,
+        rescore: true,
+        oversampling: Some(oversampling),
+    });
+
+    let mut full_precision_sames: usize = 0;
+    let mut rescore_sames: usize = 0;
+    for _i in 0..attempts {
+        let query = random_vector(&mut rnd, dim);
+
+        let exact_result = segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&query], None, top, None, &false.into());
+        let full_precision_result = hnsw_index.search(
+            &[&query],
+            None,
+            top,
+            Some(&full_precision_params),
+            &false.into(),
+        );
+        let rescore_result =
+            hnsw_index.search(&[&query], None, top, Some(&rescore_params), &false.into());
+
+        // Re-scored results must have exact scores of the original vectors
+        for (rescored, exact) in rescore_result[0].iter().zip(&exact_result[0]) {
+            if rescored.idx == exact.idx {
+                assert!((rescored.score - exact.score).abs() < 1e-4);
+            }
+        }
+
+        full_precision_sames += sames_count(&full_precision_result, &exact_result);
+        rescore_sames += sames_count(&rescore_result, &exact_result);
+    }
+
+    let full_precision_recall = full_precision_sames as f64 / (attempts * top) as f64;
+    let rescore_recall = rescore_sames as f64 / (attempts * top) as f64;
+    println!("full precision recall = {full_precision_recall}, rescore recall = {rescore_recall}");
+    assert!(
+        rescore_recall >= full_precision_recall - 0.05,
+        "recall with quantization {rescore_recall} is much lower than without {full_precision_recall}"
+    );
+}
+
+/// Zero-centered vectors, so that signs of components carry information for binary quantization
+fn random_centered_vector(rnd: &mut StdRng, dim: usize) -> Vec<VectorElementType> {
+    (0..dim).map(|_| rnd.gen_range(-1.0..1.0)).collect()
+}
+
+#[test]
+fn hnsw_quantized_rescore_recall_dot_test() {
+    hnsw_quantized_rescore_recall_test(
+        Distance::Dot,
+        64,
+        random_vector,
+        2.0,
+        ScalarQuantizationConfig {
+            r#type: Default::default(),
+            quantile: Some(0.99),
+            always_ram: Some(true),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn hnsw_quantized_rescore_recall_euclid_test() {
+    hnsw_quantized_rescore_recall_test(
+        Distance::Euclid,
+        64,
+        random_vector,
+        2.0,
+        ScalarQuantizationConfig {
+            r#type: Default::default(),
+            quantile: Some(0.99),
+            always_ram: Some(true),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn hnsw_product_quantization_rescore_recall_dot_test() {
+    hnsw_quantized_rescore_recall_test(
+        Distance::Dot,
+        64,
+        random_centered_vector,
+        2.0,
+        ProductQuantizationConfig {
+            compression: CompressionRatio::X8,
+            always_ram: Some(true),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn hnsw_binary_quantization_rescore_recall_cosine_test() {
+    hnsw_quantized_rescore_recall_test(
+        Distance::Cosine,
+        1024,
+        random_centered_vector,
+        4.0,
+        BinaryQuantizationConfig {
+            min_dim: None,
+            always_ram: Some(true),
+        }
+        .into(),
+    );
+}
+
+#[test]
+fn hnsw_binary_quantization_rescore_recall_dot_test() {
+    hnsw_quantized_rescore_recall_test(
+        Distance::Dot,
+        1024,
+        random_centered_vector,
+        4.0,
+        BinaryQuantizationConfig {
+            min_dim: None,
+            always_ram: Some(true),
+        }
+        .into(),
+    );
+}
+
+/// Compare recall of the quantization modes of a search: quantized vectors only, quantized
+/// vectors with re-scoring and original vectors only. Both the graph search and the plain search
+/// over a small filtered subset are checked.
+#[test]
+fn hnsw_quantization_search_modes_recall_test() {
+    let stopped = AtomicBool::new(false);
+
+    let dim = 1024;
+    let num_vectors: u64 = 2000;
+    let num_filtered: u64 = 300;
+    let m = 16;
+    let ef = 64;
+    let ef_construct = 64;
+    let top = 10;
+    let attempts = 20;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let quantized_dir = Builder::new().prefix("quantized_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..num_vectors {
+        let vector = random_centered_vector(&mut rnd, dim);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+    let quantization_config: QuantizationConfig = BinaryQuantizationConfig {
+        min_dim: None,
+        always_ram: Some(true),
+    }
+    .into();
+    segment.vector_data.values_mut().for_each(|vector_storage| {
+        vector_storage
+            .vector_storage
+            .borrow_mut()
+            .quantize(quantized_dir.path(), &quantization_config, 2, &stopped)
+            .unwrap();
+    });
+
+    // Unfiltered search goes through the graph, the filtered subset is below the threshold
+    let full_scan_threshold_vectors = 1000;
+    let hnsw_config = HnswConfig {
+        m,
+        ef_construct,
+        full_scan_threshold: full_scan_threshold_vectors * dim * VECTOR_ELEMENT_SIZE / 1024,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let filtered_ids: HashSet<PointIdType> = (0..num_filtered).map(PointIdType::from).collect();
+    let filter = Filter::new_must(Condition::HasId(filtered_ids.into()));
+    assert_eq!(hnsw_index.search_path(None, None), SearchPath::Hnsw);
+    assert_eq!(
+        hnsw_index.search_path(Some(&filter), None),
+        SearchPath::FilteredScan,
+    );
+
+    let queries: Vec<_> = (0..attempts)
+        .map(|_| random_centered_vector(&mut rnd, dim))
+        .collect();
+
+    let search_params = |quantization| SearchParams {
+        hnsw_ef: Some(ef),
+        quantization: Some(quantization),
+        ..Default::default()
+    };
+    let quantized_params = search_params(QuantizationSearchParams {
+        ignore: false,
+        rescore: false,
+        oversampling: None,
+    });
+    let rescore_params = search_params(QuantizationSearchParams {
+        ignore: false,
+        rescore: true,
+        oversampling: Some(4.0),
+    });
+    let ignore_params = search_params(QuantizationSearchParams {
+        ignore: true,
+        rescore: false,
+        oversampling: None,
+    });
+
+    // Recall of the mode, and whether all found scores are the exact ones
+    let recall = |filter: Option<&Filter>, params: &SearchParams| {
+        let mut sames = 0;
+        let mut exact_scores = true;
+        for query in &queries {
+            let exact_result = segment.vector_data[DEFAULT_VECTOR_NAME]
+                .vector_index
+                .borrow()
+                .search(&[query.as_slice()], filter, top, None, &false.into());
+            let result = hnsw_index.search(
+                &[query.as_slice()],
+                filter,
+                top,
+                Some(params),
+                &false.into(),
+            );
+            assert_eq!(result[0].len(), top);
+            for scored in &result[0] {
+                let exact_score = exact_result[0]
+                    .iter()
+                    .find(|exact| exact.idx == scored.idx)
+                    .map(|exact| exact.score);
+                if exact_score.map_or(false, |score| (score - scored.score).abs() > 1e-3) {
+                    exact_scores = false;
+                }
+            }
+            sames += sames_count(&result, &exact_result);
+        }
+        (sames as f64 / (attempts * top) as f64, exact_scores)
+    };
+
+    for filter in [None, Some(&filter)] {
+        let (quantized_recall, quantized_exact) = recall(filter, &quantized_params);
+        let (rescore_recall, rescore_exact) = recall(filter, &rescore_params);
+        let (ignore_recall, ignore_exact) = recall(filter, &ignore_params);
+        println!(
+            "filtered = {}, quantized recall = {quantized_recall}, rescore recall = {rescore_recall}, ignore recall = {ignore_recall}",
+            filter.is_some(),
+        );
+
+        // Binary quantized scores are far from the original ones
+        assert!(!quantized_exact);
+        assert!(rescore_exact);
+        assert!(ignore_exact);
+
+        assert!(
+            rescore_recall > quantized_recall,
+            "re-scoring doesn't improve recall {rescore_recall} of quantized search {quantized_recall}",
+        );
+        assert!(
+            ignore_recall >= rescore_recall - 0.05,
+            "recall with original vectors {ignore_recall} is much lower than with re-scoring {rescore_recall}",
+        );
+    }
+
+    // Plain search over original vectors is exact
+    let (filtered_ignore_recall, _) = recall(Some(&filter), &ignore_params);
+    assert_eq!(filtered_ignore_recall, 1.0);
+}
+
+#[test]
+fn binary_quantization_min_dim_test() {
+    let stopped = AtomicBool::new(false);
+    let dim = 128;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let quantized_dir = Builder::new().prefix("quantized_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance: Distance::Cosine,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..100 {
+        let vector = random_centered_vector(&mut rnd, dim);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+
+    let vector_storage = segment.vector_data[DEFAULT_VECTOR_NAME]
+        .vector_storage
+        .clone();
+
+    let default_config: QuantizationConfig = BinaryQuantizationConfig {
+        min_dim: None,
+        always_ram: None,
+    }
+    .into();
+    let result =
+        vector_storage
+            .borrow_mut()
+            .quantize(quantized_dir.path(), &default_config, 1, &stopped);
+    assert!(matches!(
+        result,
+        Err(OperationError::ValidationError { .. })
+    ));
+
+    let lowered_config: QuantizationConfig = BinaryQuantizationConfig {
+        min_dim: Some(dim),
+        always_ram: None,
+    }
+    .into();
+    vector_storage
+        .borrow_mut()
+        .quantize(quantized_dir.path(), &lowered_config, 1, &stopped)
+        .unwrap();
+}
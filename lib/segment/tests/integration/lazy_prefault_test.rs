@@ -0,0 +1,132 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::named_vectors::NamedVectors;
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::segment::Segment;
+use segment::segment_constructor::segment_builder::SegmentBuilder;
+use segment::segment_constructor::{build_segment, get_vector_storage_path, load_segment};
+use segment::types::{
+    Distance, Indexes, SegmentConfig, SeqNumberType, VectorDataConfig, VectorStorageType,
+    WithPayload,
+};
+use tempfile::Builder;
+
+const PRIMARY: &str = "primary";
+const ARCHIVE: &str = "archive";
+const DIM: usize = 256;
+const NUM_VECTORS: u64 = 10_000;
+const PREFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn vector_config(storage_type: VectorStorageType) -> VectorDataConfig {
+    VectorDataConfig {
+        size: DIM,
+        distance: Distance::Dot,
+        storage_type,
+        index: Indexes::Plain {},
+        quantization_config: None,
+        datatype: None,
+        multivector_config: None,
+        store_original_vectors: false,
+    }
+}
+
+/// Resident size of the mappings of the file in this process, in bytes
+fn mapped_rss(file: &Path) -> usize {
+    let file = file.to_str().unwrap();
+    let smaps = fs::read_to_string("/proc/self/smaps").unwrap();
+    let mut in_file_mapping = false;
+    let mut rss_kb = 0;
+    for line in smaps.lines() {
+        let mut fields = line.split_whitespace();
+        let first = fields.next().unwrap_or_default();
+        if !first.ends_with(':') {
+            // Header of the next mapping, the path is the last field
+            in_file_mapping = line.ends_with(file);
+        } else if in_file_mapping && first == "Rss:" {
+            rss_kb += fields.next().unwrap().parse::<usize>().unwrap();
+        }
+    }
+    rss_kb * 1024
+}
+
+fn search(segment: &Segment, vector_name: &str, rnd: &mut StdRng) {
+    let query = random_vector(rnd, DIM);
+    let result = segment
+        .search(
+            vector_name,
+            &query,
+            &WithPayload::default(),
+            &false.into(),
+            None,
+            10,
+            None,
+            &false.into(),
+        )
+        .unwrap();
+    assert_eq!(result.len(), 10);
+}
+
+#[test]
+fn test_on_disk_vector_is_loaded_on_first_search() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let mut config = SegmentConfig {
+        vector_data: HashMap::from([
+            (PRIMARY.to_owned(), vector_config(VectorStorageType::Memory)),
+            (ARCHIVE.to_owned(), vector_config(VectorStorageType::Memory)),
+        ]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+    let mut source = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..NUM_VECTORS {
+        let vectors = NamedVectors::from([
+            (PRIMARY.to_owned(), random_vector(&mut rnd, DIM)),
+            (ARCHIVE.to_owned(), random_vector(&mut rnd, DIM)),
+        ]);
+        source
+            .upsert_point(n as SeqNumberType, n.into(), vectors)
+            .unwrap();
+    }
+
+    // Only the archive vector is stored on disk
+    config.vector_data.get_mut(ARCHIVE).unwrap().storage_type = VectorStorageType::Mmap;
+    let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &config).unwrap();
+    builder.update_from(&source, &stopped).unwrap();
+    let built = builder.build(&stopped).unwrap();
+    let segment_path = built.current_path.clone();
+    drop(built);
+
+    let segment = load_segment(&segment_path).unwrap().unwrap();
+    let archive_file = get_vector_storage_path(&segment_path, ARCHIVE).join("matrix.dat");
+    let archive_size = fs::metadata(&archive_file).unwrap().len() as usize;
+
+    segment.prefault_mmap_pages(&HashSet::from([ARCHIVE.to_owned()]));
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(mapped_rss(&archive_file) < archive_size / 10);
+
+    // Searching other vectors doesn't load the archive
+    search(&segment, PRIMARY, &mut rnd);
+    std::thread::sleep(Duration::from_millis(500));
+    assert!(mapped_rss(&archive_file) < archive_size / 10);
+
+    search(&segment, ARCHIVE, &mut rnd);
+    let start = Instant::now();
+    while mapped_rss(&archive_file) < archive_size * 9 / 10 {
+        assert!(
+            start.elapsed() < PREFAULT_TIMEOUT,
+            "archive vector is not loaded after the first search",
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
@@ -1,28 +1,70 @@
 #[cfg(test)]
 pub mod batch_search_test;
 #[cfg(test)]
+pub mod byte_vector_storage_test;
+#[cfg(test)]
+pub mod datetime_index_test;
+#[cfg(test)]
 pub mod disbalanced_vectors_test;
 #[cfg(test)]
+pub mod discovery_search_test;
+#[cfg(test)]
 pub mod exact_search_test;
 #[cfg(test)]
 pub mod fail_recovery_test;
 #[cfg(test)]
 pub mod filtering_context_check;
 #[cfg(test)]
+pub mod filtered_hnsw_traversal_test;
+#[cfg(test)]
 pub mod filtrable_hnsw_test;
 #[cfg(test)]
 pub mod fixtures;
 #[cfg(test)]
+pub mod half_vector_storage_test;
+#[cfg(test)]
+pub mod hnsw_ef_test;
+#[cfg(test)]
 pub mod hnsw_quantized_search_test;
+#[cfg(all(test, target_os = "linux"))]
+pub mod lazy_prefault_test;
+#[cfg(test)]
+pub mod multi_vector_test;
 #[cfg(test)]
 pub mod nested_filtering_test;
 #[cfg(test)]
+pub mod order_by_test;
+#[cfg(test)]
+pub mod original_vectors_test;
+#[cfg(test)]
+pub mod payload_by_key_test;
+#[cfg(test)]
+pub mod payload_index_rebuild_test;
+#[cfg(test)]
 pub mod payload_index_test;
 #[cfg(test)]
+pub mod point_versions_test;
+#[cfg(test)]
+pub mod product_quantization_test;
+#[cfg(test)]
+pub mod sample_test;
+#[cfg(test)]
+pub mod score_threshold_test;
+#[cfg(test)]
 pub mod scroll_filtering_test;
 #[cfg(test)]
+pub mod search_plan_test;
+#[cfg(test)]
+pub mod search_stats_test;
+#[cfg(test)]
 pub mod segment_builder_test;
 #[cfg(test)]
+pub mod segment_snapshot_test;
+#[cfg(test)]
+pub mod segment_telemetry_test;
+#[cfg(test)]
 pub mod segment_tests;
 #[cfg(test)]
+pub mod sparse_vector_test;
+#[cfg(test)]
 pub mod utils;
@@ -0,0 +1,312 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::vectors::{
+    flatten_multi_dense_vector, only_default_vector, split_multi_dense_vector, MultiDenseVector,
+    VectorElementType, DEFAULT_VECTOR_NAME,
+};
+use segment::entry::entry_point::{OperationError, SegmentEntry};
+use segment::fixtures::index_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment::Segment;
+use segment::segment_constructor::{build_segment, load_segment};
+use segment::spaces::simple::dot_similarity;
+use segment::types::{
+    Distance, HnswConfig, Indexes, MultiVectorConfig, SearchParams, SegmentConfig, SeqNumberType,
+    VectorDataConfig, VectorStorageDatatype, VectorStorageType,
+};
+use tempfile::Builder;
+
+const DIM: usize = 16;
+const NUM_VECTORS: u64 = 1000;
+const MAX_SUB_VECTORS: usize = 5;
+
+fn segment_config(dim: usize, distance: Distance) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance,
+                // Ignored for multi-vectors, they are always kept in memory
+                storage_type: VectorStorageType::Mmap,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: Some(MultiVectorConfig::default()),
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+fn random_multi_vector(rnd: &mut StdRng, dim: usize) -> MultiDenseVector {
+    let count = rnd.gen_range(1..=MAX_SUB_VECTORS);
+    (0..count).map(|_| random_vector(rnd, dim)).collect()
+}
+
+fn normalize(vector: &[VectorElementType]) -> Vec<VectorElementType> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    vector.iter().map(|x| x / norm).collect()
+}
+
+/// Straightforward max-sim over dot products of sub-vectors
+fn reference_max_sim(query: &MultiDenseVector, other: &MultiDenseVector) -> f32 {
+    query
+        .iter()
+        .map(|query_vector| {
+            other
+                .iter()
+                .map(|other_vector| dot_similarity(query_vector, other_vector))
+                .fold(f32::NEG_INFINITY, f32::max)
+        })
+        .sum()
+}
+
+fn build_random_segment(path: &Path, distance: Distance) -> (Segment, Vec<MultiDenseVector>) {
+    let mut rnd = StdRng::seed_from_u64(42);
+    let mut segment = build_segment(path, &segment_config(DIM, distance), true).unwrap();
+    let mut vectors = Vec::new();
+    for n in 0..NUM_VECTORS {
+        let multi_vector = random_multi_vector(&mut rnd, DIM);
+        let flat = flatten_multi_dense_vector(&multi_vector, DIM).unwrap();
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&flat))
+            .unwrap();
+        vectors.push(multi_vector);
+    }
+    (segment, vectors)
+}
+
+#[test]
+fn flatten_multi_vector_test() {
+    let multi_vector = vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]];
+    let flat = flatten_multi_dense_vector(&multi_vector, 2).unwrap();
+    assert_eq!(flat, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(split_multi_dense_vector(&flat, 2), multi_vector);
+
+    let err = flatten_multi_dense_vector(&[], 2).unwrap_err();
+    assert!(matches!(err, OperationError::WrongVectorElement { .. }));
+
+    let err = flatten_multi_dense_vector(&[vec![1.0, 2.0], vec![3.0]], 2).unwrap_err();
+    assert!(matches!(err, OperationError::WrongVectorElement { .. }));
+    assert!(err.to_string().contains("position 1"), "{err}");
+
+    let err = flatten_multi_dense_vector(&[vec![1.0, 2.0], vec![]], 2).unwrap_err();
+    assert!(matches!(err, OperationError::WrongVectorElement { .. }));
+}
+
+#[test]
+fn multi_vector_validation_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut segment = build_segment(dir.path(), &segment_config(4, Distance::Dot), true).unwrap();
+
+    // Any positive number of sub-vectors is allowed
+    segment
+        .upsert_point(1, 1.into(), only_default_vector(&[1.0; 4]))
+        .unwrap();
+    segment
+        .upsert_point(2, 2.into(), only_default_vector(&[1.0; 12]))
+        .unwrap();
+
+    for (op_num, invalid) in [(3, vec![]), (4, vec![1.0; 6]), (5, vec![1.0; 3])] {
+        let err = segment
+            .upsert_point(op_num, 3.into(), only_default_vector(&invalid))
+            .unwrap_err();
+        assert!(
+            matches!(err, OperationError::WrongVectorElement { .. }),
+            "unexpected error: {err}"
+        );
+
+        let err = segment
+            .update_vectors(op_num, 1.into(), only_default_vector(&invalid))
+            .unwrap_err();
+        assert!(
+            matches!(err, OperationError::WrongVectorElement { .. }),
+            "unexpected error: {err}"
+        );
+    }
+
+    // Queries are validated the same way
+    let err = segment
+        .search(
+            DEFAULT_VECTOR_NAME,
+            &[1.0; 6],
+            &Default::default(),
+            &false.into(),
+            None,
+            10,
+            None,
+            &AtomicBool::new(false),
+        )
+        .unwrap_err();
+    assert!(matches!(err, OperationError::WrongVectorElement { .. }));
+
+    // Rejected operations leave no traces
+    assert_eq!(segment.available_point_count(), 2);
+    let stored = segment.vector(DEFAULT_VECTOR_NAME, 1.into()).unwrap();
+    assert_eq!(stored, Some(vec![1.0; 4]));
+}
+
+#[test]
+fn multi_vector_datatype_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut config = segment_config(4, Distance::Dot);
+    config
+        .vector_data
+        .get_mut(DEFAULT_VECTOR_NAME)
+        .unwrap()
+        .datatype = Some(VectorStorageDatatype::Uint8);
+    let Err(err) = build_segment(dir.path(), &config, true) else {
+        panic!("multi-vector segment with uint8 datatype must not be created");
+    };
+    assert!(
+        matches!(err, OperationError::ValidationError { .. }),
+        "unexpected error: {err}"
+    );
+}
+
+/// Exact search over multi-vectors must score every point with max-sim
+fn multi_vector_scoring_test(distance: Distance) {
+    let stopped = AtomicBool::new(false);
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let (segment, vectors) = build_random_segment(dir.path(), distance);
+
+    // Cosine sub-vectors are normalized one by one, dot product of them is the cosine
+    let preprocess = |multi_vector: &MultiDenseVector| -> MultiDenseVector {
+        match distance {
+            Distance::Cosine => multi_vector.iter().map(|v| normalize(v)).collect(),
+            _ => multi_vector.clone(),
+        }
+    };
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    for _ in 0..5 {
+        let query = random_multi_vector(&mut rnd, DIM);
+        let flat_query = flatten_multi_dense_vector(&query, DIM).unwrap();
+        let result = segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&flat_query], None, NUM_VECTORS as usize, None, &stopped);
+        assert_eq!(result[0].len(), NUM_VECTORS as usize);
+
+        let query = preprocess(&query);
+        for scored in &result[0] {
+            let expected = reference_max_sim(&query, &preprocess(&vectors[scored.idx as usize]));
+            assert!(
+                (scored.score - expected).abs() <= expected.abs() * 1e-4 + 1e-4,
+                "score {} differs from reference {expected}",
+                scored.score,
+            );
+        }
+        assert!(result[0]
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+    }
+}
+
+#[test]
+fn multi_vector_scoring_dot_test() {
+    multi_vector_scoring_test(Distance::Dot);
+}
+
+#[test]
+fn multi_vector_scoring_cosine_test() {
+    multi_vector_scoring_test(Distance::Cosine);
+}
+
+#[test]
+fn multi_vector_persistence_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let (path, vectors) = {
+        let (mut segment, vectors) = build_random_segment(dir.path(), Distance::Dot);
+        segment.delete_point(NUM_VECTORS, 0.into()).unwrap();
+        segment.flush(true).unwrap();
+        (segment.current_path.clone(), vectors)
+    };
+
+    let segment = load_segment(&path).unwrap().unwrap();
+    assert_eq!(segment.available_point_count(), NUM_VECTORS as usize - 1);
+    assert_eq!(
+        segment.vector(DEFAULT_VECTOR_NAME, 0.into()).ok().flatten(),
+        None
+    );
+    for n in 1..NUM_VECTORS {
+        let stored = segment
+            .vector(DEFAULT_VECTOR_NAME, n.into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(split_multi_dense_vector(&stored, DIM), vectors[n as usize]);
+    }
+}
+
+/// HNSW built over multi-vectors must find mostly the same neighbours as exact search
+#[test]
+fn hnsw_multi_vector_search_test() {
+    let stopped = AtomicBool::new(false);
+    let top = 10;
+    let attempts = 10;
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let (segment, _) = build_random_segment(dir.path(), Distance::Cosine);
+
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 64,
+        full_scan_threshold: usize::MAX,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    let mut sames = 0;
+    for _ in 0..attempts {
+        let query = random_multi_vector(&mut rnd, DIM);
+        let query = flatten_multi_dense_vector(&query, DIM).unwrap();
+
+        let index_result = hnsw_index.search(
+            &[&query],
+            None,
+            top,
+            Some(&SearchParams {
+                hnsw_ef: Some(128),
+                ..Default::default()
+            }),
+            &stopped,
+        );
+        let exact_result = segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&query], None, top, None, &stopped);
+
+        let exact_ids: BTreeSet<_> = exact_result[0].iter().map(|x| x.idx).collect();
+        sames += index_result[0]
+            .iter()
+            .filter(|x| exact_ids.contains(&x.idx))
+            .count();
+    }
+
+    let acc = 100.0 * sames as f64 / (attempts * top) as f64;
+    println!("sames = {sames}, attempts = {attempts}, top = {top}, acc = {acc}");
+    assert!(acc > 80.0, "recall {acc} is too low");
+}
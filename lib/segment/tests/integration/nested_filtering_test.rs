@@ -103,6 +103,7 @@ fn test_filtering_context_consistency() {
                     Condition::Field(FieldCondition::new_match("c", 1.into())),
                 ]),
                 should: None,
+                min_should: None,
                 must_not: Some(vec![Condition::Field(FieldCondition::new_range(
                     "d",
                     Range {
@@ -145,6 +146,7 @@ fn test_filtering_context_consistency() {
                     Condition::Field(FieldCondition::new_match("d", 0.into())),
                 ]),
                 should: None,
+                min_should: None,
                 must_not: None,
             },
         );
@@ -179,6 +181,7 @@ fn test_filtering_context_consistency() {
                     Condition::Field(FieldCondition::new_match("d", 0.into())),
                 ]),
                 should: None,
+                min_should: None,
                 must_not: None,
             },
         );
@@ -207,6 +210,7 @@ fn test_filtering_context_consistency() {
                     1.into(),
                 ))]),
                 should: None,
+                min_should: None,
                 must_not: None,
             },
         );
@@ -222,10 +226,12 @@ fn test_filtering_context_consistency() {
                             10.into(),
                         ))]),
                         should: None,
+                        min_should: None,
                         must_not: None,
                     },
                 )]),
                 should: None,
+                min_should: None,
                 must_not: None,
             },
         );
@@ -233,6 +239,7 @@ fn test_filtering_context_consistency() {
         let nested_filter_3 = Filter {
             must: Some(vec![nested_condition_3, nester_condition_3_1]),
             should: None,
+            min_should: None,
             must_not: None,
         };
 
@@ -248,3 +255,93 @@ fn test_filtering_context_consistency() {
         assert!(!res3.is_empty());
     }
 }
+
+#[test]
+fn test_nested_conditions_match_single_element() {
+    let payloads: Vec<Payload> = vec![
+        json!({ "items": [{ "color": "red", "size": "L" }] }).into(),
+        json!({ "items": [{ "color": "red", "size": "M" }, { "color": "blue", "size": "L" }] })
+            .into(),
+        json!({ "items": [{ "color": "blue", "size": "M" }, { "color": "red", "size": "L" }] })
+            .into(),
+        json!({ "items": [{ "color": "green", "size": "L" }] }).into(),
+        json!({ "items": [] }).into(),
+        json!({}).into(),
+    ];
+    let num_points = payloads.len();
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let mut payload_storage = InMemoryPayloadStorage::default();
+    for (idx, payload) in payloads.iter().enumerate() {
+        payload_storage
+            .assign(idx as PointOffsetType, payload)
+            .unwrap();
+    }
+
+    let wrapped_payload_storage = Arc::new(AtomicRefCell::new(payload_storage.into()));
+    let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(num_points)));
+
+    let mut index =
+        StructPayloadIndex::open(wrapped_payload_storage, id_tracker, dir.path()).unwrap();
+    index
+        .set_indexed("items[].color", PayloadSchemaType::Keyword.into())
+        .unwrap();
+
+    let red = || Condition::Field(FieldCondition::new_match("color", "red".to_string().into()));
+    let large = || Condition::Field(FieldCondition::new_match("size", "L".to_string().into()));
+
+    let check = |filter: &Filter, expected: Vec<PointOffsetType>| {
+        let res = index.query_points(filter);
+        let filter_context = index.filter_context(filter);
+        let check_res: Vec<_> = (0..num_points as PointOffsetType)
+            .filter(|point_id| filter_context.check(*point_id))
+            .collect();
+        assert_eq!(res, check_res, "{filter:?}");
+        assert_eq!(res, expected, "{filter:?}");
+    };
+
+    // Flat conditions may be satisfied by different elements of the array
+    let flat_filter = Filter {
+        must: Some(vec![
+            Condition::Field(FieldCondition::new_match(
+                "items[].color",
+                "red".to_string().into(),
+            )),
+            Condition::Field(FieldCondition::new_match(
+                "items[].size",
+                "L".to_string().into(),
+            )),
+        ]),
+        should: None,
+        min_should: None,
+        must_not: None,
+    };
+    check(&flat_filter, vec![0, 1, 2]);
+
+    // Nested conditions must be satisfied by a single element
+    let red_and_large = Condition::new_nested(
+        "items",
+        Filter {
+            must: Some(vec![red(), large()]),
+            should: None,
+            min_should: None,
+            must_not: None,
+        },
+    );
+    check(&Filter::new_must(red_and_large.clone()), vec![0, 2]);
+
+    // `must_not` inside of the nested filter is scoped to the element
+    let red_not_large = Condition::new_nested(
+        "items",
+        Filter {
+            must: Some(vec![red()]),
+            should: None,
+            min_should: None,
+            must_not: Some(vec![large()]),
+        },
+    );
+    check(&Filter::new_must(red_not_large), vec![1]);
+
+    // `must_not` outside of the nested filter negates the whole nested condition
+    check(&Filter::new_must_not(red_and_large), vec![1, 3, 4, 5]);
+}
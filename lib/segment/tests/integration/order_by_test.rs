@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use segment::data_types::order_by::{Direction, OrderBy, OrderValue};
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::{OperationError, SegmentEntry};
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, Indexes, Match, Payload, PayloadSchemaType,
+    PointIdType, SegmentConfig, SeqNumberType, ValueVariants, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const NUM_POINTS: u64 = 200;
+
+/// Values of the `num` field of every point `n`, which are `n % 10`. Every 13th point has no
+/// value, and the last point has two.
+fn point_values(n: u64) -> Vec<i64> {
+    if n == NUM_POINTS - 1 {
+        vec![100, -1]
+    } else if n % 13 == 0 {
+        vec![]
+    } else {
+        vec![(n % 10) as i64]
+    }
+}
+
+fn is_deleted(n: u64) -> bool {
+    n % 7 == 0
+}
+
+/// Segment with indexed `num` values and not indexed `even` flags, with every 7th point deleted
+fn fixture(path: &std::path::Path) -> Segment {
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: 2,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(path, &config, true).unwrap();
+    segment
+        .create_field_index(0, "num", Some(&PayloadSchemaType::Integer.into()))
+        .unwrap();
+
+    // Insert in reverse, so internal ids don't follow the external ones
+    for n in (0..NUM_POINTS).rev() {
+        let values = point_values(n);
+        let payload: Payload = match values.as_slice() {
+            [] => json!({ "even": n % 2 == 0 }),
+            [value] => json!({ "num": value, "even": n % 2 == 0 }),
+            values => json!({ "num": values, "even": n % 2 == 0 }),
+        }
+        .into();
+        let op_num = (NUM_POINTS - n) as SeqNumberType;
+        segment
+            .upsert_point(op_num, n.into(), only_default_vector(&[1.0, 0.0]))
+            .unwrap();
+        segment
+            .set_full_payload(op_num, n.into(), &payload)
+            .unwrap();
+    }
+    for n in (0..NUM_POINTS).filter(|n| is_deleted(*n)) {
+        segment.delete_point(NUM_POINTS + n, n.into()).unwrap();
+    }
+    segment
+}
+
+/// Expected order of all points, with every value of a point
+fn expected_order(
+    direction: Direction,
+    predicate: impl Fn(u64) -> bool,
+) -> Vec<(OrderValue, PointIdType)> {
+    (0..NUM_POINTS)
+        .filter(|n| !is_deleted(*n) && predicate(*n))
+        .flat_map(|n| {
+            point_values(n)
+                .into_iter()
+                .map(move |value| (OrderValue::Int(value), PointIdType::from(n)))
+        })
+        .sorted_by(|a, b| direction.compare(a, b))
+        .collect()
+}
+
+fn order_by(direction: Direction, start_from: Option<OrderValue>) -> OrderBy {
+    OrderBy {
+        key: "num".to_string(),
+        direction: Some(direction),
+        start_from,
+    }
+}
+
+#[test]
+fn test_order_by_direction() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+
+    for direction in [Direction::Asc, Direction::Desc] {
+        let points = segment
+            .read_ordered_filtered(None, None, None, &order_by(direction, None))
+            .unwrap();
+        // Points with the same value are ordered by id, the point with two values is returned twice
+        assert_eq!(points, expected_order(direction, |_| true));
+    }
+
+    // Limit doesn't break the order of points with the same value
+    let points = segment
+        .read_ordered_filtered(None, Some(25), None, &order_by(Direction::Asc, None))
+        .unwrap();
+    assert_eq!(points, expected_order(Direction::Asc, |_| true)[..25]);
+}
+
+#[test]
+fn test_order_by_pagination() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+
+    for direction in [Direction::Asc, Direction::Desc] {
+        let page_size = 7;
+        let mut pages = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (start_from, offset) = match cursor {
+                Some((start_from, offset)) => (Some(start_from), Some(offset)),
+                None => (None, None),
+            };
+            let mut page = segment
+                .read_ordered_filtered(
+                    offset,
+                    Some(page_size + 1),
+                    None,
+                    &order_by(direction, start_from),
+                )
+                .unwrap();
+            cursor = (page.len() > page_size).then(|| page.pop().unwrap());
+            pages.extend(page);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(pages, expected_order(direction, |_| true));
+    }
+
+    // Start value which isn't present in the index
+    let points = segment
+        .read_ordered_filtered(
+            None,
+            None,
+            None,
+            &order_by(Direction::Desc, Some(OrderValue::Float(4.5))),
+        )
+        .unwrap();
+    assert!(!points.is_empty());
+    assert!(points.iter().all(|(value, _)| *value <= OrderValue::Int(4)));
+}
+
+#[test]
+fn test_order_by_filter() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "even",
+        Match::new_value(ValueVariants::Bool(true)),
+    )));
+    let points = segment
+        .read_ordered_filtered(None, None, Some(&filter), &order_by(Direction::Asc, None))
+        .unwrap();
+    assert_eq!(points, expected_order(Direction::Asc, |n| n % 2 == 0));
+}
+
+#[test]
+fn test_order_by_missing_index() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+
+    let result = segment.read_ordered_filtered(
+        None,
+        None,
+        None,
+        &OrderBy {
+            key: "even".to_string(),
+            direction: None,
+            start_from: None,
+        },
+    );
+    assert!(matches!(
+        result,
+        Err(OperationError::MissingRangeIndexForOrderBy { key }) if key == "even"
+    ));
+}
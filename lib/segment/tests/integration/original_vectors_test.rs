@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Distance, Indexes, QuantizationConfig, ScalarQuantizationConfig, SegmentConfig, SeqNumberType,
+    VectorDataConfig, VectorStorageDatatype, VectorStorageType,
+};
+use segment::vector_storage::VectorStorage;
+use tempfile::Builder;
+
+const DIM: usize = 32;
+const NUM_VECTORS: u64 = 500;
+
+fn build_random_segment(
+    path: &Path,
+    distance: Distance,
+    storage_type: VectorStorageType,
+    datatype: VectorStorageDatatype,
+    store_original_vectors: bool,
+) -> Segment {
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance,
+                storage_type,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: Some(datatype),
+                multivector_config: None,
+                store_original_vectors,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    // Same seed, so every segment gets the same vectors
+    let mut rnd = StdRng::seed_from_u64(42);
+    let mut segment = build_segment(path, &config, true).unwrap();
+    for n in 0..NUM_VECTORS {
+        // Random components are positive, so vectors are far from normalized
+        let vector = random_vector(&mut rnd, DIM);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+    segment
+}
+
+fn original_vectors_test(storage_type: VectorStorageType) {
+    let stopped = AtomicBool::new(false);
+    let top = 10;
+
+    let legacy_dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let legacy_segment = build_random_segment(
+        legacy_dir.path(),
+        Distance::Cosine,
+        storage_type,
+        VectorStorageDatatype::Float32,
+        false,
+    );
+
+    let original_dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let original_segment = build_random_segment(
+        original_dir.path(),
+        Distance::Cosine,
+        storage_type,
+        VectorStorageDatatype::Float32,
+        true,
+    );
+
+    // Vectors are returned exactly as they were inserted
+    let mut rnd = StdRng::seed_from_u64(42);
+    for n in 0..NUM_VECTORS {
+        let vector = random_vector(&mut rnd, DIM);
+        let stored = original_segment
+            .vector(DEFAULT_VECTOR_NAME, n.into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored, vector);
+
+        let normalized = legacy_segment
+            .vector(DEFAULT_VECTOR_NAME, n.into())
+            .unwrap()
+            .unwrap();
+        assert_ne!(normalized, vector);
+    }
+
+    // Search results are the same as when normalized vectors are stored
+    let mut rnd = StdRng::seed_from_u64(43);
+    for _ in 0..10 {
+        let query = random_vector(&mut rnd, DIM);
+        let legacy_result = legacy_segment
+            .search(
+                DEFAULT_VECTOR_NAME,
+                &query,
+                &false.into(),
+                &false.into(),
+                None,
+                top,
+                None,
+                &stopped,
+            )
+            .unwrap();
+        let original_result = original_segment
+            .search(
+                DEFAULT_VECTOR_NAME,
+                &query,
+                &false.into(),
+                &false.into(),
+                None,
+                top,
+                None,
+                &stopped,
+            )
+            .unwrap();
+
+        assert_eq!(original_result.len(), top);
+        for (legacy, original) in legacy_result.iter().zip(&original_result) {
+            assert_eq!(legacy.id, original.id);
+            assert_eq!(legacy.score, original.score);
+        }
+    }
+}
+
+#[test]
+fn original_vectors_memory_test() {
+    original_vectors_test(VectorStorageType::Memory);
+}
+
+#[test]
+fn original_vectors_chunked_mmap_test() {
+    original_vectors_test(VectorStorageType::ChunkedMmap);
+}
+
+/// Original vectors are normalized for quantization, so quantized scores don't change either
+#[test]
+fn original_vectors_quantization_test() {
+    let stopped = AtomicBool::new(false);
+    let config: QuantizationConfig = ScalarQuantizationConfig {
+        r#type: Default::default(),
+        quantile: None,
+        always_ram: None,
+    }
+    .into();
+
+    let segments: Vec<_> = [false, true]
+        .into_iter()
+        .map(|store_original_vectors| {
+            let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+            let segment = build_random_segment(
+                dir.path(),
+                Distance::Cosine,
+                VectorStorageType::Memory,
+                VectorStorageDatatype::Float32,
+                store_original_vectors,
+            );
+            segment.vector_data[DEFAULT_VECTOR_NAME]
+                .vector_storage
+                .borrow_mut()
+                .quantize(dir.path(), &config, 1, &stopped)
+                .unwrap();
+            (dir, segment)
+        })
+        .collect();
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    let query = random_vector(&mut rnd, DIM);
+    let scores: Vec<Vec<_>> = segments
+        .iter()
+        .map(|(_dir, segment)| {
+            let id_tracker = segment.id_tracker.borrow();
+            let vector_storage = segment.vector_data[DEFAULT_VECTOR_NAME]
+                .vector_storage
+                .borrow();
+            let scorer = vector_storage.quantized_storage().unwrap().raw_scorer(
+                &query,
+                id_tracker.deleted_point_bitslice(),
+                vector_storage.deleted_vector_bitslice(),
+                &stopped,
+            );
+            (0..NUM_VECTORS as u32)
+                .map(|idx| scorer.score_point(idx))
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(scores[0], scores[1]);
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, Indexes, Match, Payload, PayloadSchemaType,
+    PointIdType, SegmentConfig, SeqNumberType, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const TAG_KEY: &str = "metadata.stats.tag";
+const VIEWS_KEY: &str = "items[].views";
+
+fn segment_config() -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: 2,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+fn match_filter(key: &str, r#match: Match) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_match(key, r#match)))
+}
+
+/// Check filtering results and that indexed segment agrees with the reference one
+fn check_filter(segment: &Segment, reference: &Segment, filter: &Filter, expected: &[u64]) {
+    let points = segment.read_filtered(None, None, Some(filter));
+    assert_eq!(points, reference.read_filtered(None, None, Some(filter)));
+    assert_eq!(
+        points,
+        expected
+            .iter()
+            .map(|&id| id.into())
+            .collect::<Vec<PointIdType>>()
+    );
+}
+
+fn set_payload_by_key(
+    segments: [&mut Segment; 2],
+    op_num: SeqNumberType,
+    point_id: u64,
+    payload: Payload,
+    key: &str,
+) {
+    for segment in segments {
+        segment
+            .set_payload_by_key(op_num, point_id.into(), &payload, key)
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_set_payload_by_key() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let reference_dir = Builder::new().prefix("reference_dir").tempdir().unwrap();
+
+    let mut segment = build_segment(dir.path(), &segment_config(), true).unwrap();
+    let mut reference = build_segment(reference_dir.path(), &segment_config(), true).unwrap();
+    segment
+        .create_field_index(0, TAG_KEY, Some(&PayloadSchemaType::Keyword.into()))
+        .unwrap();
+    segment
+        .create_field_index(0, VIEWS_KEY, Some(&PayloadSchemaType::Integer.into()))
+        .unwrap();
+
+    let payloads: [Payload; 2] = [
+        json!({
+            "metadata": { "author": "alice", "stats": "none" },
+            "items": [{ "views": 1 }, { "views": 2, "title": "b" }]
+        })
+        .into(),
+        json!({ "metadata": { "author": "bob" } }).into(),
+    ];
+
+    for (point_id, payload) in payloads.iter().enumerate() {
+        for segment in [&mut segment, &mut reference] {
+            segment
+                .upsert_point(
+                    1,
+                    (point_id as u64).into(),
+                    only_default_vector(&[1.0, 0.0]),
+                )
+                .unwrap();
+            segment
+                .set_full_payload(1, (point_id as u64).into(), payload)
+                .unwrap();
+        }
+    }
+
+    // Scalar is overwritten with an object, siblings are left intact
+    set_payload_by_key(
+        [&mut segment, &mut reference],
+        2,
+        0,
+        json!({ "tag": "hot" }).into(),
+        "metadata.stats",
+    );
+    // Intermediate objects are created
+    set_payload_by_key(
+        [&mut segment, &mut reference],
+        3,
+        1,
+        json!({ "tag": "hot" }).into(),
+        "metadata.stats",
+    );
+    // Applied to every element of the array
+    set_payload_by_key(
+        [&mut segment, &mut reference],
+        4,
+        0,
+        json!({ "views": 10 }).into(),
+        "items[]",
+    );
+
+    assert_eq!(
+        segment.payload(0.into()).unwrap(),
+        json!({
+            "metadata": { "author": "alice", "stats": { "tag": "hot" } },
+            "items": [{ "views": 10 }, { "views": 10, "title": "b" }]
+        })
+        .into(),
+    );
+    assert_eq!(
+        segment.payload(1.into()).unwrap(),
+        json!({ "metadata": { "author": "bob", "stats": { "tag": "hot" } } }).into(),
+    );
+
+    let hot = match_filter(TAG_KEY, "hot".to_string().into());
+    check_filter(&segment, &reference, &hot, &[0, 1]);
+    check_filter(
+        &segment,
+        &reference,
+        &match_filter(VIEWS_KEY, 10.into()),
+        &[0],
+    );
+    check_filter(
+        &segment,
+        &reference,
+        &match_filter(VIEWS_KEY, 1.into()),
+        &[],
+    );
+
+    // Old values of the updated nested field are removed from the index
+    set_payload_by_key(
+        [&mut segment, &mut reference],
+        5,
+        1,
+        json!({ "tag": "cold" }).into(),
+        "metadata.stats",
+    );
+    let cold = match_filter(TAG_KEY, "cold".to_string().into());
+    check_filter(&segment, &reference, &hot, &[0]);
+    check_filter(&segment, &reference, &cold, &[1]);
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+
+use parking_lot::RwLock;
+use rand::prelude::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::keyword_index::{KeywordIndexParams, KeywordIndexType};
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::{OperationError, SegmentEntry};
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::field_index::FieldIndex;
+use segment::segment::Segment;
+use segment::segment_constructor::{build_segment, load_segment};
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, Indexes, Payload, PayloadFieldSchema,
+    PayloadSchemaParams, PayloadSchemaType, SegmentConfig, SeqNumberType, VectorDataConfig,
+    VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+use uuid::Uuid;
+
+const DIM: usize = 4;
+const NUM_POINTS: u64 = 1_000;
+const NUM_UPDATES: u64 = 500;
+const COLOR_KEY: &str = "color";
+const UUID_KEY: &str = "uuid";
+const COLORS: [&str; 5] = ["red", "green", "blue", "white", "black"];
+
+fn segment_config() -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+fn on_disk_keyword_schema() -> PayloadFieldSchema {
+    PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+        r#type: KeywordIndexType::Keyword,
+        on_disk: Some(true),
+    }))
+}
+
+fn match_filter(key: &str, value: &str) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_match(
+        key,
+        value.to_string().into(),
+    )))
+}
+
+fn upsert_point(
+    segment: &mut Segment,
+    opnum: SeqNumberType,
+    point_id: u64,
+    payload: &Payload,
+    rnd: &mut StdRng,
+) {
+    let vector = random_vector(rnd, DIM);
+    segment
+        .upsert_point(opnum, point_id.into(), only_default_vector(&vector))
+        .unwrap();
+    segment
+        .set_full_payload(opnum, point_id.into(), payload)
+        .unwrap();
+}
+
+fn color_payload(rnd: &mut StdRng) -> Payload {
+    json!({ COLOR_KEY: COLORS[rnd.gen_range(0..COLORS.len())] }).into()
+}
+
+/// Compare filtering by the indexed segment with the reference one, which has no indexes
+fn check_color_filters(segment: &Segment, reference: &Segment) {
+    for color in COLORS {
+        let filter = match_filter(COLOR_KEY, color);
+        assert_eq!(
+            segment.read_filtered(None, None, Some(&filter)),
+            reference.read_filtered(None, None, Some(&filter)),
+            "{color}",
+        );
+    }
+}
+
+fn reload(segment: Segment) -> Segment {
+    segment.flush(true).unwrap();
+    let path = segment.current_path.clone();
+    drop(segment);
+    load_segment(&path).unwrap().unwrap()
+}
+
+#[test]
+fn test_rebuild_field_index_with_concurrent_updates() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let reference_dir = Builder::new().prefix("reference_dir").tempdir().unwrap();
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let mut segment = build_segment(dir.path(), &segment_config(), true).unwrap();
+    let mut reference = build_segment(reference_dir.path(), &segment_config(), true).unwrap();
+    segment
+        .create_field_index(0, COLOR_KEY, Some(&PayloadSchemaType::Keyword.into()))
+        .unwrap();
+    for n in 0..NUM_POINTS {
+        let payload = color_payload(&mut rnd);
+        upsert_point(&mut segment, n + 1, n, &payload, &mut rnd);
+        upsert_point(&mut reference, n + 1, n, &payload, &mut rnd);
+    }
+
+    let segment = Arc::new(RwLock::new(segment));
+    let rebuild_opnum = NUM_POINTS + 1;
+    assert!(segment
+        .write()
+        .start_field_index_rebuild(rebuild_opnum, COLOR_KEY, Some(&on_disk_keyword_schema()))
+        .unwrap());
+
+    // Overwrite, delete and insert points, while the index is being rebuilt
+    let updater = {
+        let segment = segment.clone();
+        thread::spawn(move || {
+            let mut rnd = StdRng::seed_from_u64(43);
+            for i in 0..NUM_UPDATES {
+                let opnum = rebuild_opnum + 1 + i;
+                let point_id = rnd.gen_range(0..NUM_POINTS * 2);
+                if rnd.gen_bool(0.2) {
+                    segment
+                        .write()
+                        .delete_point(opnum, point_id.into())
+                        .unwrap();
+                    reference.delete_point(opnum, point_id.into()).unwrap();
+                } else {
+                    let payload = color_payload(&mut rnd);
+                    upsert_point(&mut segment.write(), opnum, point_id, &payload, &mut rnd);
+                    upsert_point(&mut reference, opnum, point_id, &payload, &mut rnd);
+                }
+            }
+            reference
+        })
+    };
+
+    while !segment
+        .read()
+        .continue_field_index_rebuild(COLOR_KEY, 10)
+        .unwrap()
+    {
+        // Current index keeps serving, it is not replaced yet
+        assert_eq!(
+            segment.read().get_indexed_fields().get(COLOR_KEY),
+            Some(&PayloadSchemaType::Keyword.into()),
+        );
+    }
+    let reference = updater.join().unwrap();
+
+    assert!(segment
+        .write()
+        .finish_field_index_rebuild(rebuild_opnum, COLOR_KEY)
+        .unwrap());
+    let segment = Arc::try_unwrap(segment).ok().unwrap().into_inner();
+
+    let is_on_disk = |segment: &Segment| {
+        segment.payload_index.borrow().field_indexes[COLOR_KEY]
+            .iter()
+            .filter_map(FieldIndex::as_keyword)
+            .all(|index| index.is_on_disk())
+    };
+    assert_eq!(
+        segment.get_indexed_fields().get(COLOR_KEY),
+        Some(&on_disk_keyword_schema()),
+    );
+    assert!(is_on_disk(&segment));
+    check_color_filters(&segment, &reference);
+
+    // Rebuilt index is loaded from its new storage
+    let segment = reload(segment);
+    assert!(is_on_disk(&segment));
+    check_color_filters(&segment, &reference);
+}
+
+fn check_uuid_index_intact(segment: &Segment) {
+    assert_eq!(
+        segment.get_indexed_fields().get(UUID_KEY),
+        Some(&PayloadSchemaType::Keyword.into()),
+    );
+    assert!(segment.payload_index.borrow().field_indexes[UUID_KEY]
+        .iter()
+        .all(|index| index.as_keyword().is_some()));
+
+    let invalid_filter = match_filter(UUID_KEY, "not a uuid");
+    assert_eq!(
+        segment.read_filtered(None, None, Some(&invalid_filter)),
+        vec![0.into()],
+    );
+    let valid_filter = match_filter(UUID_KEY, &Uuid::from_u128(1).to_string());
+    assert_eq!(
+        segment.read_filtered(None, None, Some(&valid_filter)),
+        vec![1.into()],
+    );
+}
+
+#[test]
+fn test_failed_field_index_rebuild() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let mut segment = build_segment(dir.path(), &segment_config(), true).unwrap();
+    segment
+        .create_field_index(0, UUID_KEY, Some(&PayloadSchemaType::Keyword.into()))
+        .unwrap();
+    for n in 0..NUM_POINTS {
+        let payload: Payload = json!({ UUID_KEY: Uuid::from_u128(n as u128).to_string() }).into();
+        upsert_point(&mut segment, n + 1, n, &payload, &mut rnd);
+    }
+
+    let uuid_schema = PayloadSchemaType::Uuid.into();
+    let mut opnum = NUM_POINTS + 1;
+    assert!(segment
+        .start_field_index_rebuild(opnum, UUID_KEY, Some(&uuid_schema))
+        .unwrap());
+    while !segment.continue_field_index_rebuild(UUID_KEY, 100).unwrap() {}
+
+    // Value, which is invalid for the new index, is accepted by the current one,
+    // but fails the rebuild
+    let invalid_payload: Payload = json!({ UUID_KEY: "not a uuid" }).into();
+    segment
+        .set_full_payload(opnum + 1, 0.into(), &invalid_payload)
+        .unwrap();
+    let result = segment.finish_field_index_rebuild(opnum, UUID_KEY);
+    assert!(matches!(
+        result,
+        Err(OperationError::ValidationError { .. })
+    ));
+    check_uuid_index_intact(&segment);
+
+    // Existing invalid value fails the rebuild, while adding points to the new index
+    opnum += 2;
+    assert!(segment
+        .start_field_index_rebuild(opnum, UUID_KEY, Some(&uuid_schema))
+        .unwrap());
+    let result = loop {
+        match segment.continue_field_index_rebuild(UUID_KEY, 100) {
+            Ok(true) => panic!("rebuild must fail"),
+            Ok(false) => {}
+            Err(err) => break err,
+        }
+    };
+    assert!(matches!(result, OperationError::ValidationError { .. }));
+    segment.abort_field_index_rebuild(UUID_KEY).unwrap();
+    check_uuid_index_intact(&segment);
+
+    let segment = reload(segment);
+    check_uuid_index_intact(&segment);
+}
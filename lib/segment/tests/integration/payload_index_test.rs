@@ -6,15 +6,17 @@ use atomic_refcell::AtomicRefCell;
 use itertools::Itertools;
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
+use segment::data_types::keyword_index::{KeywordIndexParams, KeywordIndexType};
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::payload_context_fixture::FixtureIdTracker;
 use segment::fixtures::payload_fixtures::{
-    generate_diverse_nested_payload, generate_diverse_payload, random_filter, random_nested_filter,
-    random_vector, FLICKING_KEY, GEO_KEY, INT_KEY, INT_KEY_2, LAT_RANGE, LON_RANGE, STR_KEY,
-    STR_PROJ_KEY, STR_ROOT_PROJ_KEY, TEXT_KEY,
+    generate_diverse_nested_payload, generate_diverse_payload, random_filter, random_keyword,
+    random_keyword_payload, random_min_should_filter, random_nested_filter, random_vector,
+    FLICKING_KEY, GEO_KEY, INT_KEY, INT_KEY_2, LAT_RANGE, LON_RANGE, STR_KEY, STR_PROJ_KEY,
+    STR_ROOT_PROJ_KEY, TEXT_KEY,
 };
-use segment::index::field_index::PrimaryCondition;
+use segment::index::field_index::{FieldIndex, PrimaryCondition};
 use segment::index::struct_payload_index::StructPayloadIndex;
 use segment::index::PayloadIndex;
 use segment::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
@@ -24,9 +26,10 @@ use segment::segment_constructor::build_segment;
 use segment::types::PayloadFieldSchema::FieldType;
 use segment::types::PayloadSchemaType::{Integer, Keyword};
 use segment::types::{
-    Condition, Distance, FieldCondition, Filter, GeoBoundingBox, GeoPoint, GeoPolygon, GeoRadius,
-    Indexes, IsEmptyCondition, Payload, PayloadField, PayloadSchemaType, PointOffsetType, Range,
-    SegmentConfig, VectorDataConfig, VectorStorageType, WithPayload,
+    AnyVariants, Condition, Distance, FieldCondition, Filter, GeoBoundingBox, GeoLineString,
+    GeoPoint, GeoPolygon, GeoRadius, Indexes, IsEmptyCondition, IsNullCondition, Match, Payload,
+    PayloadField, PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType, PointOffsetType,
+    Range, SegmentConfig, VectorDataConfig, VectorStorageType, WithPayload,
 };
 use serde_json::json;
 use tempfile::Builder;
@@ -48,9 +51,13 @@ fn build_test_segments(path_struct: &Path, path_plain: &Path) -> (Segment, Segme
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
             },
         )]),
         payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
     };
 
     let mut plain_segment = build_segment(path_plain, &config, true).unwrap();
@@ -156,9 +163,13 @@ fn build_test_segments_nested_payload(path_struct: &Path, path_plain: &Path) ->
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
             },
         )]),
         payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
     };
 
     let mut plain_segment = build_segment(path_plain, &config, true).unwrap();
@@ -373,6 +384,49 @@ fn test_is_empty_conditions() {
     );
 }
 
+#[test]
+fn test_min_should_conditions() {
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let (struct_segment, plain_segment) = build_test_segments(dir1.path(), dir2.path());
+
+    for _i in 0..ATTEMPTS {
+        let filter = random_min_should_filter(&mut rnd, 3);
+
+        let plain_result = plain_segment
+            .payload_index
+            .borrow()
+            .query_points(&filter)
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        let struct_result = struct_segment
+            .payload_index
+            .borrow()
+            .query_points(&filter)
+            .into_iter()
+            .sorted()
+            .collect_vec();
+        assert_eq!(plain_result, struct_result, "{filter:#?}");
+
+        let estimation = struct_segment
+            .payload_index
+            .borrow()
+            .estimate_cardinality(&filter);
+        assert!(estimation.min <= estimation.exp, "{estimation:#?}");
+        assert!(estimation.exp <= estimation.max, "{estimation:#?}");
+
+        let min_should = filter.min_should.as_ref().unwrap();
+        if min_should.min_count > min_should.conditions.len() {
+            assert!(plain_result.is_empty());
+            assert_eq!(estimation.max, 0);
+        }
+    }
+}
+
 #[test]
 fn test_cardinality_estimation() {
     let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
@@ -650,13 +704,57 @@ fn test_struct_payload_geo_polygon_index() {
 
     let polygon_edge = 5;
 
-    let points: Vec<GeoPoint> = (0..polygon_edge)
+    let mut points: Vec<GeoPoint> = (0..polygon_edge)
         .map(|_| GeoPoint {
             lon: rnd.gen_range(LON_RANGE),
             lat: rnd.gen_range(LAT_RANGE),
         })
         .collect();
-    let geo_polygon = GeoPolygon { points };
+    points.push(points[0].clone());
+    let geo_polygon = GeoPolygon::new(GeoLineString { points }, None).unwrap();
+
+    let condition = Condition::Field(FieldCondition::new_geo_polygon(
+        "geo_key".to_string(),
+        geo_polygon,
+    ));
+
+    let query_filter = Filter::new_must(condition);
+
+    validate_geo_filter(query_filter)
+}
+
+#[test]
+fn test_struct_payload_geo_polygon_with_hole_index() {
+    let ring = |lon: f64, lat: f64, delta: f64| GeoLineString {
+        points: vec![
+            GeoPoint {
+                lon: lon - delta,
+                lat: lat - delta,
+            },
+            GeoPoint {
+                lon: lon + delta,
+                lat: lat - delta,
+            },
+            GeoPoint {
+                lon: lon + delta,
+                lat: lat + delta,
+            },
+            GeoPoint {
+                lon: lon - delta,
+                lat: lat + delta,
+            },
+            GeoPoint {
+                lon: lon - delta,
+                lat: lat - delta,
+            },
+        ],
+    };
+
+    let geo_polygon = GeoPolygon::new(
+        ring(0.0, 0.0, 60.0),
+        Some(vec![ring(-20.0, 10.0, 15.0), ring(25.0, -20.0, 10.0)]),
+    )
+    .unwrap();
 
     let condition = Condition::Field(FieldCondition::new_geo_polygon(
         "geo_key".to_string(),
@@ -795,3 +893,211 @@ fn test_update_payload_index_type() {
     assert_eq!(field_index[0].count_indexed_points(), point_num);
     assert_eq!(field_index[1].count_indexed_points(), point_num);
 }
+
+#[test]
+fn test_is_empty_and_is_null_distinctions() {
+    let payloads: Vec<Payload> = vec![
+        json!({}).into(),
+        json!({ "key": null, "nested": { "arr": null } }).into(),
+        json!({ "key": [], "nested": { "arr": [] } }).into(),
+        json!({ "key": "", "nested": { "arr": [""] } }).into(),
+        json!({ "key": "a", "nested": { "arr": ["a", null] } }).into(),
+        json!({ "key": ["a"], "nested": { "arr": [null] } }).into(),
+    ];
+    let point_num = payloads.len();
+
+    let build_index = |path: &Path, indexed: bool| {
+        let mut payload_storage = InMemoryPayloadStorage::default();
+        for (idx, payload) in payloads.iter().enumerate() {
+            payload_storage
+                .assign(idx as PointOffsetType, payload)
+                .unwrap();
+        }
+        let wrapped_payload_storage = Arc::new(AtomicRefCell::new(payload_storage.into()));
+        let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(point_num)));
+        let mut index =
+            StructPayloadIndex::open(wrapped_payload_storage, id_tracker, path).unwrap();
+        if indexed {
+            index.set_indexed("key", Keyword.into()).unwrap();
+            index.set_indexed("nested.arr[]", Keyword.into()).unwrap();
+        }
+        index
+    };
+
+    let dir_indexed = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let dir_plain = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let indexed = build_index(dir_indexed.path(), true);
+    let plain = build_index(dir_plain.path(), false);
+
+    let is_empty = |key: &str| {
+        Condition::IsEmpty(IsEmptyCondition {
+            is_empty: PayloadField {
+                key: key.to_string(),
+            },
+        })
+    };
+    let is_null = |key: &str| {
+        Condition::IsNull(IsNullCondition {
+            is_null: PayloadField {
+                key: key.to_string(),
+            },
+        })
+    };
+
+    let cases: Vec<(Condition, Vec<PointOffsetType>)> = vec![
+        // Missing key, null and empty array are empty, but empty string is a value
+        (is_empty("key"), vec![0, 1, 2]),
+        // Only an explicit null is null, missing key is not
+        (is_null("key"), vec![1]),
+        // Non-array values are skipped by `[]`, but a lone null element is not a value
+        (is_empty("nested.arr[]"), vec![0, 1, 2, 5]),
+        // Any null element of the array makes it null
+        (is_null("nested.arr[]"), vec![4, 5]),
+    ];
+
+    for (condition, expected) in cases {
+        let must_not_expected = (0..point_num as PointOffsetType)
+            .filter(|idx| !expected.contains(idx))
+            .collect_vec();
+
+        for index in [&indexed, &plain] {
+            let must = Filter::new_must(condition.clone());
+            let result = index.query_points(&must).into_iter().sorted().collect_vec();
+            assert_eq!(result, expected, "{condition:?}");
+
+            let must_not = Filter::new_must_not(condition.clone());
+            let result = index
+                .query_points(&must_not)
+                .into_iter()
+                .sorted()
+                .collect_vec();
+            assert_eq!(result, must_not_expected, "must_not {condition:?}");
+        }
+    }
+}
+
+fn random_keyword_filter<R: Rng + ?Sized>(rnd: &mut R) -> Filter {
+    let keywords = (0..3).map(|_| random_keyword(rnd)).collect_vec();
+    let r#match = match rnd.gen_range(0..3) {
+        0 => keywords[0].clone().into(),
+        1 => Match::new_any(AnyVariants::Keywords(keywords)),
+        _ => Match::new_except(AnyVariants::Keywords(keywords)),
+    };
+    Filter::new_must(Condition::Field(FieldCondition::new_match(
+        STR_KEY, r#match,
+    )))
+}
+
+#[test]
+fn test_on_disk_keyword_index() {
+    // Compare filtering with in-memory and on-disk keyword indexes
+    let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
+    let dir2 = Builder::new().prefix("segment2_dir").tempdir().unwrap();
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut in_memory_segment = build_segment(dir1.path(), &config, true).unwrap();
+    let mut on_disk_segment = build_segment(dir2.path(), &config, true).unwrap();
+
+    let on_disk_schema =
+        PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+            r#type: KeywordIndexType::Keyword,
+            on_disk: Some(true),
+        }));
+
+    let mut opnum = 0;
+    in_memory_segment
+        .create_field_index(opnum, STR_KEY, Some(&Keyword.into()))
+        .unwrap();
+    on_disk_segment
+        .create_field_index(opnum, STR_KEY, Some(&on_disk_schema))
+        .unwrap();
+
+    let num_points = 2000;
+    for n in 0..num_points {
+        opnum += 1;
+        let idx = n.into();
+        let vector = random_vector(&mut rnd, DIM);
+        let payload: Payload = json!({ STR_KEY: random_keyword_payload(&mut rnd, 1..=3) }).into();
+        for segment in [&mut in_memory_segment, &mut on_disk_segment] {
+            segment
+                .upsert_point(opnum, idx, only_default_vector(&vector))
+                .unwrap();
+            segment.set_full_payload(opnum, idx, &payload).unwrap();
+        }
+    }
+
+    for n in (0..num_points).step_by(5) {
+        opnum += 1;
+        let payload: Payload = json!({ STR_KEY: random_keyword(&mut rnd) }).into();
+        for segment in [&mut in_memory_segment, &mut on_disk_segment] {
+            segment.set_full_payload(opnum, n.into(), &payload).unwrap();
+            segment.delete_point(opnum, (n + 1).into()).unwrap();
+        }
+    }
+
+    let check_filters =
+        |in_memory_segment: &Segment, on_disk_segment: &Segment, rnd: &mut StdRng| {
+            for _ in 0..ATTEMPTS {
+                let filter = random_keyword_filter(rnd);
+                assert_eq!(
+                    in_memory_segment.read_filtered(None, None, Some(&filter)),
+                    on_disk_segment.read_filtered(None, None, Some(&filter)),
+                    "{filter:?}",
+                );
+            }
+        };
+    check_filters(&in_memory_segment, &on_disk_segment, &mut rnd);
+
+    // Index is rebuilt aside, the old one keeps serving reads until it is replaced
+    opnum += 1;
+    assert!(in_memory_segment
+        .start_field_index_rebuild(opnum, STR_KEY, Some(&on_disk_schema))
+        .unwrap());
+    while !in_memory_segment
+        .continue_field_index_rebuild(STR_KEY, 500)
+        .unwrap()
+    {
+        check_filters(&in_memory_segment, &on_disk_segment, &mut rnd);
+    }
+    assert!(in_memory_segment
+        .finish_field_index_rebuild(opnum, STR_KEY)
+        .unwrap());
+
+    assert_eq!(
+        in_memory_segment.get_indexed_fields().get(STR_KEY),
+        Some(&on_disk_schema),
+    );
+    let is_on_disk = |segment: &Segment| {
+        segment.payload_index.borrow().field_indexes[STR_KEY]
+            .iter()
+            .filter_map(FieldIndex::as_keyword)
+            .all(|index| index.is_on_disk())
+    };
+    assert!(is_on_disk(&in_memory_segment));
+    assert!(is_on_disk(&on_disk_segment));
+    check_filters(&in_memory_segment, &on_disk_segment, &mut rnd);
+
+    // Same schema doesn't require a rebuild
+    assert!(!on_disk_segment
+        .start_field_index_rebuild(opnum, STR_KEY, Some(&on_disk_schema))
+        .unwrap());
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::id_tracker::PointVersions;
+use segment::segment::Segment;
+use segment::segment_constructor::{build_segment, load_segment};
+use segment::types::{
+    Distance, Indexes, Payload, SegmentConfig, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+fn segment_config() -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: 4,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+/// Flush and drop the segment, and load it again from disk
+fn restart(segment: Segment) -> Segment {
+    segment.flush(true).unwrap();
+    let path = segment.current_path.clone();
+    drop(segment);
+    load_segment(&path).unwrap().unwrap()
+}
+
+#[test]
+fn test_interleaved_vector_and_payload_updates() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut segment = build_segment(dir.path(), &segment_config(), true).unwrap();
+
+    let old_vector = vec![1.0, 0.0, 0.0, 0.0];
+    let new_vector = vec![0.0, 1.0, 0.0, 0.0];
+    let initial_payload: Payload = json!({ "initial": true }).into();
+    for point_id in [1, 2] {
+        segment
+            .upsert_point(1, point_id.into(), only_default_vector(&old_vector))
+            .unwrap();
+        segment
+            .set_full_payload(1, point_id.into(), &initial_payload)
+            .unwrap();
+    }
+
+    // Vector update is persisted before an older payload update of the same point,
+    // which is replayed after the restart
+    assert!(segment
+        .update_vectors(3, 1.into(), only_default_vector(&new_vector))
+        .unwrap());
+    // Payload update is persisted before an older vector update of the same point
+    assert!(segment
+        .set_payload(5, 2.into(), &json!({ "color": "red" }).into())
+        .unwrap());
+
+    let mut segment = restart(segment);
+    assert_eq!(segment.point_version(1.into()), Some(3));
+    assert_eq!(segment.point_version(2.into()), Some(5));
+
+    // Replay all operations starting from the oldest one, which is not persisted
+    assert!(segment
+        .set_payload(2, 1.into(), &json!({ "color": "blue" }).into())
+        .unwrap());
+    segment
+        .update_vectors(3, 1.into(), only_default_vector(&new_vector))
+        .unwrap();
+    assert!(segment
+        .update_vectors(4, 2.into(), only_default_vector(&new_vector))
+        .unwrap());
+    segment
+        .set_payload(5, 2.into(), &json!({ "color": "red" }).into())
+        .unwrap();
+
+    // Older updates of the same component are still skipped
+    assert!(!segment
+        .update_vectors(2, 1.into(), only_default_vector(&old_vector))
+        .unwrap());
+    assert!(!segment
+        .set_payload(4, 2.into(), &json!({ "color": "green" }).into())
+        .unwrap());
+
+    let segment = restart(segment);
+    for (point_id, color) in [(1, "blue"), (2, "red")] {
+        assert_eq!(
+            segment
+                .vector(DEFAULT_VECTOR_NAME, point_id.into())
+                .unwrap(),
+            Some(new_vector.clone()),
+            "point {point_id}",
+        );
+        let expected: Payload = json!({ "initial": true, "color": color }).into();
+        assert_eq!(
+            segment.payload(point_id.into()).unwrap(),
+            expected,
+            "point {point_id}",
+        );
+    }
+    assert_eq!(segment.point_version(1.into()), Some(3));
+    assert_eq!(segment.point_version(2.into()), Some(5));
+}
+
+#[test]
+fn test_recreated_point_skips_older_updates() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut segment = build_segment(dir.path(), &segment_config(), true).unwrap();
+
+    let vector = vec![1.0, 0.0, 0.0, 0.0];
+    segment
+        .upsert_point(1, 1.into(), only_default_vector(&vector))
+        .unwrap();
+    segment
+        .set_payload(2, 1.into(), &json!({ "color": "red" }).into())
+        .unwrap();
+    segment.delete_point(3, 1.into()).unwrap();
+    segment
+        .upsert_point(4, 1.into(), only_default_vector(&vector))
+        .unwrap();
+    assert_eq!(
+        segment.point_versions(1.into()),
+        Some(PointVersions::new(4))
+    );
+
+    // Payload of the deleted point is not restored by WAL replay
+    let mut segment = restart(segment);
+    assert!(!segment
+        .set_payload(2, 1.into(), &json!({ "color": "red" }).into())
+        .unwrap());
+    assert_eq!(segment.payload(1.into()).unwrap(), Payload::default());
+}
@@ -0,0 +1,158 @@
+use std::sync::atomic::AtomicBool;
+
+use bitvec::prelude::BitVec;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::vectors::VectorElementType;
+use segment::types::{
+    CompressionRatio, Distance, PointOffsetType, ProductQuantizationConfig, QuantizationConfig,
+};
+use segment::vector_storage::quantized::quantized_vectors::{
+    QuantizedVectors, QUANTIZED_DATA_PATH, QUANTIZED_META_PATH,
+};
+use tempfile::Builder;
+
+const DIM: usize = 32;
+const NUM_VECTORS: usize = 1000;
+const NUM_QUERIES: usize = 10;
+
+fn random_vectors(rnd: &mut StdRng, count: usize) -> Vec<Vec<VectorElementType>> {
+    (0..count)
+        .map(|_| (0..DIM).map(|_| rnd.gen_range(-1.0..1.0)).collect())
+        .collect()
+}
+
+fn dot(a: &[VectorElementType], b: &[VectorElementType]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn product_quantization_config(always_ram: bool) -> QuantizationConfig {
+    ProductQuantizationConfig {
+        compression: CompressionRatio::X4,
+        always_ram: Some(always_ram),
+    }
+    .into()
+}
+
+fn quantize(
+    vectors: &[Vec<VectorElementType>],
+    quantization_config: &QuantizationConfig,
+    path: &std::path::Path,
+    on_disk_vector_storage: bool,
+) -> QuantizedVectors {
+    QuantizedVectors::create(
+        vectors.iter().map(|vector| vector.as_slice()),
+        quantization_config,
+        Distance::Dot,
+        DIM,
+        vectors.len(),
+        path,
+        on_disk_vector_storage,
+        2,
+        &AtomicBool::new(false),
+    )
+    .unwrap()
+}
+
+fn quantized_scores(
+    quantized: &QuantizedVectors,
+    query: &[VectorElementType],
+    deleted: &BitVec,
+) -> Vec<f32> {
+    let is_stopped = AtomicBool::new(false);
+    let scorer = quantized.raw_scorer(query, deleted, deleted, &is_stopped);
+    (0..NUM_VECTORS as PointOffsetType)
+        .map(|point_id| scorer.score_point(point_id))
+        .collect()
+}
+
+/// Scores over product quantized vectors must stay close to the exact ones
+#[test]
+fn product_quantization_score_error_test() {
+    let mut rnd = StdRng::seed_from_u64(42);
+    let vectors = random_vectors(&mut rnd, NUM_VECTORS);
+    let queries = random_vectors(&mut rnd, NUM_QUERIES);
+    let deleted = BitVec::repeat(false, NUM_VECTORS);
+
+    let dir = Builder::new().prefix("quantized_dir").tempdir().unwrap();
+    let quantized = quantize(
+        &vectors,
+        &product_quantization_config(true),
+        dir.path(),
+        false,
+    );
+
+    let mut total_error = 0.0;
+    let mut total_score = 0.0;
+    for query in &queries {
+        let scores = quantized_scores(&quantized, query, &deleted);
+        for (vector, score) in vectors.iter().zip(scores) {
+            let exact = dot(query, vector);
+            total_error += (score - exact).abs();
+            total_score += exact.abs();
+        }
+    }
+
+    let relative_error = total_error / total_score;
+    println!("relative error = {relative_error}");
+    assert!(
+        relative_error < 0.1,
+        "relative error {relative_error} is too large"
+    );
+}
+
+/// Codes placed into mmap and loaded into RAM must produce the same scores
+#[test]
+fn product_quantization_storage_placement_test() {
+    let mut rnd = StdRng::seed_from_u64(42);
+    let vectors = random_vectors(&mut rnd, NUM_VECTORS);
+    let queries = random_vectors(&mut rnd, NUM_QUERIES);
+    let deleted = BitVec::repeat(false, NUM_VECTORS);
+
+    let dir = Builder::new().prefix("quantized_dir").tempdir().unwrap();
+    let quantization_config = product_quantization_config(false);
+
+    let mmap_quantized = quantize(&vectors, &quantization_config, dir.path(), true);
+    let mmap_scores: Vec<_> = queries
+        .iter()
+        .map(|query| quantized_scores(&mmap_quantized, query, &deleted))
+        .collect();
+    drop(mmap_quantized);
+
+    let ram_quantized = QuantizedVectors::load(dir.path(), false, Distance::Dot).unwrap();
+    let ram_scores: Vec<_> = queries
+        .iter()
+        .map(|query| quantized_scores(&ram_quantized, query, &deleted))
+        .collect();
+
+    assert_eq!(mmap_scores, ram_scores);
+}
+
+/// Centroids and codes, trained on the points generated with the given seed
+fn train_with_seed(seed: u64) -> (Vec<u8>, Vec<u8>) {
+    let mut rnd = StdRng::seed_from_u64(seed);
+    let vectors = random_vectors(&mut rnd, NUM_VECTORS);
+    let dir = Builder::new().prefix("quantized_dir").tempdir().unwrap();
+    quantize(
+        &vectors,
+        &product_quantization_config(true),
+        dir.path(),
+        false,
+    );
+    (
+        std::fs::read(dir.path().join(QUANTIZED_META_PATH)).unwrap(),
+        std::fs::read(dir.path().join(QUANTIZED_DATA_PATH)).unwrap(),
+    )
+}
+
+/// Training on the same points must produce the same centroids and codes
+#[test]
+fn product_quantization_training_determinism_test() {
+    let (centroids, codes) = train_with_seed(42);
+    let (same_centroids, same_codes) = train_with_seed(42);
+    assert!(centroids == same_centroids, "centroids differ");
+    assert!(codes == same_codes, "codes differ");
+
+    let (other_centroids, _) = train_with_seed(43);
+    assert!(centroids != other_centroids);
+}
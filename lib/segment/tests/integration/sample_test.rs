@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, QueryVector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, Indexes, Payload, PointOffsetType, Range, Sample,
+    SegmentConfig, SeqNumberType, VectorDataConfig, VectorStorageType, WithPayload, WithVector,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const NUM_POINTS: u64 = 1_000;
+
+/// Segment with payload value `n % 10` of every point `n`, with every 7th point deleted
+fn fixture(path: &std::path::Path) -> Segment {
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: 2,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(path, &config, true).unwrap();
+    for n in 0..NUM_POINTS {
+        let payload: Payload = json!({ "num": n % 10 }).into();
+        segment
+            .upsert_point(
+                n as SeqNumberType,
+                n.into(),
+                only_default_vector(&[1.0, 0.0]),
+            )
+            .unwrap();
+        segment
+            .set_full_payload(n as SeqNumberType, n.into(), &payload)
+            .unwrap();
+    }
+    for n in (0..NUM_POINTS).step_by(7) {
+        segment.delete_point(NUM_POINTS + n, n.into()).unwrap();
+    }
+    segment
+}
+
+fn num_below(value: f64) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_range(
+        "num",
+        Range {
+            lt: Some(value),
+            gt: None,
+            gte: None,
+            lte: None,
+        },
+    )))
+}
+
+/// Points of a fresh segment get internal ids in insertion order
+fn is_matching(internal_id: PointOffsetType, below: u64) -> bool {
+    let n = internal_id as u64;
+    n % 7 != 0 && n % 10 < below
+}
+
+#[test]
+fn test_sample_respects_filter() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+    let stopped = AtomicBool::new(false);
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let filter = num_below(3.0);
+    let sample = segment
+        .sample_points(DEFAULT_VECTOR_NAME, Some(&filter), 50, &mut rng, &stopped)
+        .unwrap();
+    assert_eq!(sample.len(), 50);
+    assert!(sample.iter().all(|scored| is_matching(scored.idx, 3)));
+    assert_eq!(sample.iter().map(|scored| scored.idx).unique().count(), 50);
+    assert!(sample
+        .iter()
+        .tuple_windows()
+        .all(|(a, b)| a.score >= b.score));
+
+    // Sample is extended until all matching points are exhausted
+    let matching: HashSet<_> = (0..NUM_POINTS as PointOffsetType)
+        .filter(|internal_id| is_matching(*internal_id, 3))
+        .collect();
+    let sample = segment
+        .sample_points(
+            DEFAULT_VECTOR_NAME,
+            Some(&filter),
+            1_000,
+            &mut rng,
+            &stopped,
+        )
+        .unwrap();
+    assert_eq!(
+        sample
+            .iter()
+            .map(|scored| scored.idx)
+            .collect::<HashSet<_>>(),
+        matching
+    );
+
+    // Deleted points are never sampled
+    let sample = segment
+        .sample_points(DEFAULT_VECTOR_NAME, None, 2_000, &mut rng, &stopped)
+        .unwrap();
+    assert_eq!(sample.len(), segment.available_point_count());
+    assert!(sample.iter().all(|scored| is_matching(scored.idx, 10)));
+}
+
+#[test]
+fn test_sample_order_by_seed() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+    let stopped = AtomicBool::new(false);
+
+    let sample = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        segment
+            .sample_points(DEFAULT_VECTOR_NAME, None, 20, &mut rng, &stopped)
+            .unwrap()
+            .into_iter()
+            .map(|scored| scored.idx)
+            .collect_vec()
+    };
+
+    assert_eq!(sample(1), sample(1));
+    assert_ne!(sample(1), sample(2));
+    assert_ne!(sample(2), sample(3));
+}
+
+#[test]
+fn test_sample_query_search() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let segment = fixture(dir.path());
+    let stopped = AtomicBool::new(false);
+
+    let filter = num_below(5.0);
+    let sample_query = QueryVector::Sample(Sample::Random);
+    let nearest_query = QueryVector::from(vec![1.0, 0.0]);
+    let results = segment
+        .search_batch(
+            DEFAULT_VECTOR_NAME,
+            &[&sample_query, &nearest_query],
+            &WithPayload::from(true),
+            &WithVector::Bool(false),
+            Some(&filter),
+            10,
+            None,
+            None,
+            &stopped,
+        )
+        .unwrap();
+
+    // Samples are batched together with regular searches
+    assert_eq!(results.len(), 2);
+    for result in &results {
+        assert_eq!(result.len(), 10);
+        assert!(result.iter().all(|point| {
+            let num = point.payload.as_ref().unwrap().0["num"].as_u64().unwrap();
+            num < 5
+        }));
+    }
+    assert!(results[1].iter().all(|point| point.score == 1.0));
+}
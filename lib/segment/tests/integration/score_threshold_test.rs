@@ -0,0 +1,198 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::AtomicBool;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Distance, HnswConfig, Indexes, PointIdType, SearchParams, SegmentConfig, SeqNumberType,
+    VectorDataConfig, VectorStorageType,
+};
+use tempfile::Builder;
+
+fn score_threshold_test(distance: Distance) {
+    let stopped = AtomicBool::new(false);
+
+    let dim = 16;
+    let num_vectors: u64 = 2_000;
+    let num_queries = 10;
+    // Number of points passing the threshold for every query
+    let qualifying = 50;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..num_vectors {
+        let vector = random_vector(&mut rnd, dim);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 100,
+        full_scan_threshold: 1, // KB
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let plain_index = segment.vector_data[DEFAULT_VECTOR_NAME]
+        .vector_index
+        .borrow();
+
+    for _ in 0..num_queries {
+        let query = random_vector(&mut rnd, dim);
+
+        // Internal scores of all points, best first
+        let all_points = plain_index
+            .search(&[&query], None, num_vectors as usize, None, &stopped)
+            .remove(0);
+        let internal_threshold =
+            (all_points[qualifying - 1].score + all_points[qualifying].score) / 2.0;
+        let expected: BTreeSet<_> = all_points[..qualifying]
+            .iter()
+            .map(|scored| scored.idx)
+            .collect();
+
+        // Limit is reached with qualifying points only
+        let result = hnsw_index
+            .search_with_score_threshold(&[&query], None, 10, internal_threshold, None, &stopped)
+            .remove(0);
+        assert_eq!(result.len(), 10);
+        assert!(result.iter().all(|scored| expected.contains(&scored.idx)));
+
+        // Limit above the number of qualifying points returns only qualifying points
+        let params = SearchParams {
+            hnsw_ef: Some(500),
+            ..Default::default()
+        };
+        let result = hnsw_index
+            .search_with_score_threshold(
+                &[&query],
+                None,
+                100,
+                internal_threshold,
+                Some(&params),
+                &stopped,
+            )
+            .remove(0);
+        assert!(result.len() <= qualifying);
+        assert!(result.len() >= qualifying * 8 / 10, "{}", result.len());
+        assert!(result.iter().all(|scored| expected.contains(&scored.idx)));
+
+        // Threshold above the best score excludes everything
+        let result = hnsw_index
+            .search_with_score_threshold(
+                &[&query],
+                None,
+                10,
+                all_points[0].score + 1.0,
+                None,
+                &stopped,
+            )
+            .remove(0);
+        assert!(result.is_empty());
+
+        // Segment takes the threshold in the user scale, where smaller distances are better
+        let user_threshold = distance.postprocess_score(internal_threshold);
+        let result = segment
+            .search_batch(
+                DEFAULT_VECTOR_NAME,
+                &[&query.clone().into()],
+                &false.into(),
+                &false.into(),
+                None,
+                100,
+                Some(user_threshold),
+                None,
+                &stopped,
+            )
+            .unwrap()
+            .remove(0);
+        let found_ids: BTreeSet<_> = result.iter().map(|point| point.id).collect();
+        let expected_ids: BTreeSet<PointIdType> =
+            expected.iter().map(|&idx| u64::from(idx).into()).collect();
+        assert_eq!(found_ids, expected_ids);
+        assert!(result.iter().all(|point| {
+            distance.check_threshold(distance.postprocess_score(point.score), user_threshold)
+        }));
+
+        let excluding_threshold = match distance {
+            Distance::Cosine | Distance::Dot => {
+                distance.postprocess_score(all_points[0].score) + 1.0
+            }
+            Distance::Euclid | Distance::Manhattan | Distance::Hamming => 0.0,
+        };
+        let result = segment
+            .search_batch(
+                DEFAULT_VECTOR_NAME,
+                &[&query.clone().into()],
+                &false.into(),
+                &false.into(),
+                None,
+                10,
+                Some(excluding_threshold),
+                None,
+                &stopped,
+            )
+            .unwrap()
+            .remove(0);
+        assert_eq!(result.len(), 0);
+    }
+}
+
+#[test]
+fn score_threshold_similarity_test() {
+    score_threshold_test(Distance::Cosine);
+}
+
+#[test]
+fn score_threshold_euclid_test() {
+    score_threshold_test(Distance::Euclid);
+}
+
+#[test]
+fn score_threshold_manhattan_test() {
+    score_threshold_test(Distance::Manhattan);
+}
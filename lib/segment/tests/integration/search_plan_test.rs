@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::search_plan::{
+    FieldIndexType, FilterCardinality, PrimaryClausePlan, SearchPath, SegmentSearchPlan,
+};
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::segment::Segment;
+use segment::segment_constructor::build_segment;
+use segment::segment_constructor::segment_builder::SegmentBuilder;
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, HnswConfig, Indexes, Payload, PayloadSchemaType,
+    SearchParams, SegmentConfig, SegmentType, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const DIM: usize = 8;
+const NUM_POINTS: u64 = 2_000;
+/// Points with the `rare` tag, all other points are tagged `common`
+const NUM_RARE: u64 = 100;
+/// Threshold of 16 KB is 512 vectors of 8 float components
+const FULL_SCAN_THRESHOLD_KB: usize = 16;
+const FULL_SCAN_THRESHOLD: usize = FULL_SCAN_THRESHOLD_KB * 1024 / (DIM * 4);
+
+fn segment_config(index: Indexes) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index,
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+fn tag(n: u64) -> &'static str {
+    if n < NUM_RARE {
+        "rare"
+    } else {
+        "common"
+    }
+}
+
+fn tag_filter(value: &str) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "tag",
+        value.to_string().into(),
+    )))
+}
+
+fn plan(segment: &Segment, filter: Option<&Filter>, exact: bool) -> SegmentSearchPlan {
+    let params = SearchParams {
+        exact,
+        ..Default::default()
+    };
+    let mut plans = segment
+        .search_plan(DEFAULT_VECTOR_NAME, filter, Some(&params))
+        .unwrap();
+    assert_eq!(plans.len(), 1);
+    plans.remove(0)
+}
+
+#[test]
+fn test_search_plan() {
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let mut plain_segment =
+        build_segment(dir.path(), &segment_config(Indexes::Plain {}), true).unwrap();
+    plain_segment
+        .create_field_index(0, "tag", Some(&PayloadSchemaType::Keyword.into()))
+        .unwrap();
+    for n in 0..NUM_POINTS {
+        let vector = random_vector(&mut rnd, DIM);
+        plain_segment
+            .upsert_point(n, n.into(), only_default_vector(&vector))
+            .unwrap();
+        let payload: Payload = json!({ "tag": tag(n) }).into();
+        plain_segment
+            .set_full_payload(n, n.into(), &payload)
+            .unwrap();
+    }
+
+    let rare_filter = tag_filter("rare");
+    let common_filter = tag_filter("common");
+
+    let rare_plan = plan(&plain_segment, Some(&rare_filter), false);
+    assert_eq!(rare_plan.segment_type, SegmentType::Plain);
+    assert_eq!(rare_plan.available_points, NUM_POINTS as usize);
+    assert_eq!(
+        rare_plan.cardinality,
+        Some(FilterCardinality {
+            min: NUM_RARE as usize,
+            exp: NUM_RARE as usize,
+            max: NUM_RARE as usize,
+        }),
+    );
+    assert_eq!(
+        rare_plan.primary_clauses,
+        vec![PrimaryClausePlan {
+            key: Some("tag".to_string()),
+            index: Some(FieldIndexType::Keyword),
+        }],
+    );
+
+    // Plain segments score points one by one, regardless of the filter selectivity
+    assert_eq!(rare_plan.search_path, SearchPath::FilteredScan);
+    assert_eq!(
+        plan(&plain_segment, Some(&common_filter), false).search_path,
+        SearchPath::FilteredScan,
+    );
+    let unfiltered_plan = plan(&plain_segment, None, false);
+    assert_eq!(unfiltered_plan.cardinality, None);
+    assert!(unfiltered_plan.primary_clauses.is_empty());
+    assert_eq!(unfiltered_plan.search_path, SearchPath::FullScan);
+
+    let hnsw_config = HnswConfig {
+        m: 8,
+        ef_construct: 16,
+        full_scan_threshold: FULL_SCAN_THRESHOLD_KB,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+    let mut builder = SegmentBuilder::new(
+        dir.path(),
+        temp_dir.path(),
+        &segment_config(Indexes::Hnsw(hnsw_config)),
+    )
+    .unwrap();
+    builder.update_from(&plain_segment, &stopped).unwrap();
+    let mut hnsw_segment = builder.build(&stopped).unwrap();
+
+    let rare_plan = plan(&hnsw_segment, Some(&rare_filter), false);
+    assert_eq!(rare_plan.segment_type, SegmentType::Indexed);
+    assert!(rare_plan.cardinality.unwrap().max < FULL_SCAN_THRESHOLD);
+    assert_eq!(rare_plan.search_path, SearchPath::FilteredScan);
+
+    let common_plan = plan(&hnsw_segment, Some(&common_filter), false);
+    assert!(common_plan.cardinality.unwrap().min > FULL_SCAN_THRESHOLD);
+    assert_eq!(common_plan.search_path, SearchPath::FilteredHnsw);
+
+    assert_eq!(
+        plan(&hnsw_segment, None, false).search_path,
+        SearchPath::Hnsw
+    );
+    assert_eq!(
+        plan(&hnsw_segment, Some(&common_filter), true).search_path,
+        SearchPath::Exact,
+    );
+    assert_eq!(
+        plan(&hnsw_segment, None, true).search_path,
+        SearchPath::Exact
+    );
+
+    // Delete common points, until fewer of them are left than the threshold
+    let num_left = FULL_SCAN_THRESHOLD as u64 / 2;
+    for n in NUM_RARE..NUM_POINTS - num_left {
+        hnsw_segment.delete_point(NUM_POINTS + n, n.into()).unwrap();
+    }
+
+    let common_plan = plan(&hnsw_segment, Some(&common_filter), false);
+    assert_eq!(common_plan.cardinality.unwrap().max, num_left as usize);
+    assert_eq!(common_plan.search_path, SearchPath::FilteredScan);
+    assert_eq!(
+        plan(&hnsw_segment, None, false).search_path,
+        SearchPath::FullScan,
+    );
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::common::search_stats::SearchStats;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::{PayloadIndex, VectorIndex};
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, HnswConfig, Indexes, Payload, PayloadSchemaType,
+    Range, SearchParams, SegmentConfig, SeqNumberType, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const DIM: usize = 8;
+const NUM_VECTORS: u64 = 2_000;
+const TOP: usize = 10;
+const NUM_QUERIES: usize = 20;
+/// Payload values are uniform in `0..NUM_VALUES`, so a `lt: k` range matches `k` percent of points
+const NUM_VALUES: i64 = 100;
+const NUM_KEY: &str = "num";
+
+fn percent_filter(percent: i64) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_range(
+        NUM_KEY,
+        Range {
+            lt: Some(percent as f64),
+            ..Default::default()
+        },
+    )))
+}
+
+#[test]
+fn test_search_stats() {
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    for n in 0..NUM_VECTORS {
+        let vector = random_vector(&mut rnd, DIM);
+        segment
+            .upsert_point(n as SeqNumberType, n.into(), only_default_vector(&vector))
+            .unwrap();
+        let payload: Payload = json!({ NUM_KEY: rnd.gen_range(0..NUM_VALUES) }).into();
+        segment
+            .set_full_payload(n as SeqNumberType, n.into(), &payload)
+            .unwrap();
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 8,
+        ef_construct: 32,
+        full_scan_threshold: 1, // KB
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_storage
+            .clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+    segment
+        .payload_index
+        .borrow_mut()
+        .set_indexed(NUM_KEY, PayloadSchemaType::Integer.into())
+        .unwrap();
+
+    // Building the graph is not counted
+    assert!(hnsw_index.get_telemetry_data().search_stats.is_empty());
+
+    let queries = (0..NUM_QUERIES)
+        .map(|_| random_vector(&mut rnd, DIM))
+        .collect_vec();
+    let mut total = SearchStats::default();
+    let mut search = |filter: Option<&Filter>, params: SearchParams| {
+        let (_, stats) = SearchStats::collect(|| {
+            for query in &queries {
+                hnsw_index.search(&[query], filter, TOP, Some(&params), &stopped);
+            }
+        });
+        total += stats;
+        stats
+    };
+
+    // Wider beam expands and scores more points of the graph
+    let stats_by_ef = [16, 64, 256]
+        .into_iter()
+        .map(|hnsw_ef| {
+            let params = SearchParams {
+                hnsw_ef: Some(hnsw_ef),
+                ..Default::default()
+            };
+            search(None, params)
+        })
+        .collect_vec();
+    eprintln!("stats by ef = {stats_by_ef:?}");
+    for pair in stats_by_ef.windows(2) {
+        assert!(pair[0].graph_hops < pair[1].graph_hops, "{pair:?}");
+        assert!(pair[0].points_scored < pair[1].points_scored, "{pair:?}");
+        assert_eq!(pair[1].filter_checks, 0);
+    }
+
+    // Exact search scores every point, which matches the filter, and doesn't touch the graph
+    let exact = SearchParams {
+        exact: true,
+        ..Default::default()
+    };
+    let stats = search(None, exact);
+    assert_eq!(stats.points_scored, NUM_VECTORS as usize * NUM_QUERIES);
+    assert_eq!(stats.graph_hops, 0);
+
+    let filter = percent_filter(10);
+    let matching_points = segment.payload_index.borrow().query_points(&filter).len();
+    let stats = search(Some(&filter), exact);
+    assert_eq!(stats.points_scored, matching_points * NUM_QUERIES);
+    assert_eq!(stats.graph_hops, 0);
+
+    // Filtered graph search checks the points against the filter before scoring them
+    let params = SearchParams {
+        hnsw_ef: Some(64),
+        ..Default::default()
+    };
+    let stats = search(Some(&percent_filter(80)), params);
+    assert!(stats.graph_hops > 0);
+    assert!(stats.filter_checks >= stats.points_scored, "{stats:?}");
+
+    // Telemetry of the index sums up all searches
+    assert_eq!(hnsw_index.get_telemetry_data().search_stats, total);
+}
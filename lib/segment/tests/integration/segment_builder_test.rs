@@ -1,14 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::{OperationError, SegmentEntry};
+use segment::fixtures::index_fixtures::random_vector;
 use segment::segment::Segment;
+use segment::segment_constructor::get_vector_storage_path;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
-use segment::types::{Indexes, SegmentConfig, VectorDataConfig, VectorStorageType};
+use segment::types::{
+    Condition, FieldCondition, Filter, Indexes, SegmentConfig, VectorDataConfig, VectorStorageType,
+    WithPayload,
+};
+use serde_json::json;
 use tempfile::Builder;
 
 use crate::fixtures::segment::{build_segment_1, build_segment_2, empty_segment};
@@ -87,9 +97,13 @@ fn estimate_build_time(segment: &Segment, stop_timeout_millis: u64) -> (u64, boo
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Hnsw(Default::default()),
                 quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
             },
         )]),
         payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
     };
 
     let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &segment_config).unwrap();
@@ -151,3 +165,215 @@ fn test_building_cancellation() {
         is_stopped_long
     );
 }
+
+fn build_tenant_segment(path: &Path, ids: Range<u64>, rnd: &mut StdRng) -> Segment {
+    let mut segment = empty_segment(path);
+    for idx in ids {
+        segment
+            .upsert_point(idx, idx.into(), only_default_vector(&random_vector(rnd, 4)))
+            .unwrap();
+        // Some points don't belong to any tenant
+        if idx % 7 != 0 {
+            let tenant = format!("tenant_{}", rnd.gen_range(0..5));
+            let payload = json!({ "tenant": tenant, "number": idx }).into();
+            segment.set_payload(idx, idx.into(), &payload).unwrap();
+        }
+    }
+    segment
+}
+
+fn merge_segments(
+    dir: &Path,
+    segments: &[&Segment],
+    defragment_key: Option<&str>,
+    stopped: &AtomicBool,
+) -> Segment {
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+    let mut builder =
+        SegmentBuilder::new(dir, temp_dir.path(), &segments[0].segment_config).unwrap();
+    builder.set_defragment_key(defragment_key.map(ToOwned::to_owned));
+    assert!(builder.update(segments, stopped).unwrap());
+    builder.build(stopped).unwrap()
+}
+
+#[test]
+fn test_building_defragmented_segment() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let segment1 = build_tenant_segment(dir.path(), 0..100, &mut rnd);
+    let segment2 = build_tenant_segment(dir.path(), 100..250, &mut rnd);
+    let segments = [&segment1, &segment2];
+
+    let merged_segment = merge_segments(dir.path(), &segments, None, &stopped);
+    let defragmented_segment = merge_segments(dir.path(), &segments, Some("tenant"), &stopped);
+
+    assert_eq!(
+        defragmented_segment.available_point_count(),
+        merged_segment.available_point_count(),
+    );
+
+    // Points of each tenant occupy a contiguous range of internal ids
+    let mut tenant_internal_ids: HashMap<Option<String>, Vec<_>> = HashMap::new();
+    let id_tracker = defragmented_segment.id_tracker.borrow();
+    for point_id in defragmented_segment.iter_points() {
+        let payload = defragmented_segment.payload(point_id).unwrap();
+        let tenant = payload
+            .0
+            .get("tenant")
+            .map(|tenant| tenant.as_str().unwrap().to_owned());
+        tenant_internal_ids
+            .entry(tenant)
+            .or_default()
+            .push(id_tracker.internal_id(point_id).unwrap());
+    }
+    assert_eq!(tenant_internal_ids.len(), 6);
+    for (tenant, internal_ids) in &tenant_internal_ids {
+        let (min, max) = internal_ids.iter().minmax().into_option().unwrap();
+        assert_eq!(
+            (max - min) as usize + 1,
+            internal_ids.len(),
+            "points of {tenant:?} are fragmented"
+        );
+    }
+    // Points without tenant go last
+    let untenanted_min = tenant_internal_ids[&None].iter().min().unwrap();
+    assert_eq!(
+        *untenanted_min as usize,
+        defragmented_segment.available_point_count() - tenant_internal_ids[&None].len(),
+    );
+
+    // Reordering is invisible for search
+    for tenant in [None, Some("tenant_0"), Some("tenant_3")] {
+        let filter = tenant.map(|tenant| {
+            Filter::new_must(Condition::Field(FieldCondition::new_match(
+                "tenant",
+                tenant.to_owned().into(),
+            )))
+        });
+        for _ in 0..5 {
+            let query = random_vector(&mut rnd, 4);
+            let search = |segment: &Segment| {
+                segment
+                    .search(
+                        DEFAULT_VECTOR_NAME,
+                        &query,
+                        &WithPayload::from(true),
+                        &false.into(),
+                        filter.as_ref(),
+                        10,
+                        None,
+                        &stopped,
+                    )
+                    .unwrap()
+                    .into_iter()
+                    .map(|scored| (scored.id, scored.score, scored.payload))
+                    .collect_vec()
+            };
+            assert_eq!(search(&defragmented_segment), search(&merged_segment));
+        }
+    }
+}
+
+/// Contents of all files in `dir`, by their paths relative to `dir`
+fn read_files(dir: &Path) -> BTreeMap<PathBuf, Vec<u8>> {
+    let mut files = BTreeMap::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = PathBuf::from(path.file_name().unwrap());
+        if path.is_dir() {
+            for (nested_path, data) in read_files(&path) {
+                files.insert(name.join(nested_path), data);
+            }
+        } else {
+            files.insert(name, std::fs::read(&path).unwrap());
+        }
+    }
+    files
+}
+
+#[test]
+fn test_building_with_small_merge_buffer() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let segment1 = build_tenant_segment(dir.path(), 0..100, &mut rnd);
+    let mut segment2 = build_tenant_segment(dir.path(), 100..250, &mut rnd);
+    // Points of the first segment are replaced by newer versions, some points are deleted
+    for idx in (0..100).step_by(3) {
+        segment2
+            .upsert_point(
+                300,
+                idx.into(),
+                only_default_vector(&random_vector(&mut rnd, 4)),
+            )
+            .unwrap();
+    }
+    for idx in (100..250).step_by(11) {
+        segment2.delete_point(301, idx.into()).unwrap();
+    }
+    let segments = [&segment1, &segment2];
+
+    let mut config = segment1.segment_config.clone();
+    config
+        .vector_data
+        .get_mut(DEFAULT_VECTOR_NAME)
+        .unwrap()
+        .storage_type = VectorStorageType::Mmap;
+
+    let merge = |defragment_key: Option<&str>, merge_buffer_size: Option<usize>| {
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &config).unwrap();
+        builder.set_defragment_key(defragment_key.map(ToOwned::to_owned));
+        if let Some(merge_buffer_size) = merge_buffer_size {
+            builder.set_merge_buffer_size(merge_buffer_size);
+        }
+        assert!(builder.update(&segments, &stopped).unwrap());
+        builder.build(&stopped).unwrap()
+    };
+
+    for defragment_key in [None, Some("tenant")] {
+        let segment = merge(defragment_key, None);
+        // Single point and a few points per batch
+        for merge_buffer_size in [1, 100] {
+            let small_buffer_segment = merge(defragment_key, Some(merge_buffer_size));
+
+            let vector_storage_files = |segment: &Segment| {
+                read_files(&get_vector_storage_path(
+                    &segment.current_path,
+                    DEFAULT_VECTOR_NAME,
+                ))
+            };
+            assert_eq!(
+                vector_storage_files(&small_buffer_segment),
+                vector_storage_files(&segment),
+                "{defragment_key:?}, {merge_buffer_size}",
+            );
+
+            let id_tracker = segment.id_tracker.borrow();
+            let small_buffer_id_tracker = small_buffer_segment.id_tracker.borrow();
+            assert_eq!(
+                small_buffer_id_tracker.iter_internal().collect_vec(),
+                id_tracker.iter_internal().collect_vec(),
+            );
+            for internal_id in id_tracker.iter_internal() {
+                assert_eq!(
+                    small_buffer_id_tracker.external_id(internal_id),
+                    id_tracker.external_id(internal_id),
+                );
+                assert_eq!(
+                    small_buffer_id_tracker.internal_versions(internal_id),
+                    id_tracker.internal_versions(internal_id),
+                );
+            }
+            for point_id in segment.iter_points() {
+                assert_eq!(
+                    small_buffer_segment.payload(point_id).unwrap(),
+                    segment.payload(point_id).unwrap(),
+                );
+            }
+        }
+    }
+}
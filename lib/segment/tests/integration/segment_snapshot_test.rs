@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+use parking_lot::RwLock;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::segment::Segment;
+use segment::segment_constructor::{build_segment, load_segment};
+use segment::types::{
+    Distance, Indexes, Payload, PointIdType, SegmentConfig, VectorDataConfig, VectorStorageType,
+    WithPayload, WithVector,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const DIM: usize = 4;
+const NUM_POINTS: u64 = 500;
+const NUM_CONCURRENT_POINTS: u64 = 1_000;
+
+fn segment_config() -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Euclid,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+fn upsert_point(segment: &mut Segment, point_id: u64, rnd: &mut StdRng) {
+    let vector = random_vector(rnd, DIM);
+    segment
+        .upsert_point(point_id, point_id.into(), only_default_vector(&vector))
+        .unwrap();
+    let payload: Payload = json!({ "num": point_id }).into();
+    segment
+        .set_full_payload(point_id, point_id.into(), &payload)
+        .unwrap();
+}
+
+fn segment_id(segment: &Segment) -> String {
+    segment
+        .current_path
+        .file_stem()
+        .and_then(|f| f.to_str())
+        .unwrap()
+        .to_string()
+}
+
+/// Copy the archive, changing the contents of the `target` file
+fn corrupt_archive(archive: &Path, corrupted: &Path, target: &str) {
+    let mut source = tar::Archive::new(File::open(archive).unwrap());
+    let mut builder = tar::Builder::new(File::create(corrupted).unwrap());
+    let mut is_corrupted = false;
+    for entry in source.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let mut header = entry.header().clone();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        if entry.path().unwrap().ends_with(target) {
+            data[0] ^= 0xFF;
+            is_corrupted = true;
+        }
+        builder
+            .append_data(&mut header, entry.path().unwrap(), data.as_slice())
+            .unwrap();
+    }
+    builder.finish().unwrap();
+    assert!(is_corrupted, "{target} is not archived");
+}
+
+#[test]
+fn test_snapshot_live_segment() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
+    let snapshot_dir = Builder::new().prefix("snapshot_dir").tempdir().unwrap();
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let mut segment = build_segment(dir.path(), &segment_config(), true).unwrap();
+    for n in 0..NUM_POINTS {
+        upsert_point(&mut segment, n, &mut rnd);
+    }
+    let segment_id = segment_id(&segment);
+    let segment = Arc::new(RwLock::new(segment));
+
+    let updater = {
+        let segment = segment.clone();
+        thread::spawn(move || {
+            let mut rnd = StdRng::seed_from_u64(43);
+            for n in NUM_POINTS..NUM_POINTS + NUM_CONCURRENT_POINTS {
+                upsert_point(&mut segment.write(), n, &mut rnd);
+            }
+        })
+    };
+
+    // Points at the moment of the snapshot
+    let (snapshot_points, archive) = {
+        let segment = segment.read();
+        let points: Vec<(PointIdType, Vec<f32>, Payload)> = segment
+            .iter_points()
+            .map(|id| {
+                let vector = segment.vector(DEFAULT_VECTOR_NAME, id).unwrap().unwrap();
+                (id, vector, segment.payload(id).unwrap())
+            })
+            .collect();
+        let archive = segment
+            .take_snapshot(temp_dir.path(), snapshot_dir.path())
+            .unwrap();
+        (points, archive)
+    };
+    updater.join().unwrap();
+    assert!(snapshot_points.len() >= NUM_POINTS as usize);
+
+    Segment::restore_snapshot(&archive, &segment_id).unwrap();
+    let restored = load_segment(&snapshot_dir.path().join(&segment_id))
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored.available_point_count(), snapshot_points.len());
+
+    for (id, vector, payload) in &snapshot_points {
+        let result = restored
+            .search(
+                DEFAULT_VECTOR_NAME,
+                vector,
+                &WithPayload::from(true),
+                &WithVector::Bool(false),
+                None,
+                1,
+                None,
+                &false.into(),
+            )
+            .unwrap();
+        assert_eq!(result[0].id, *id);
+        assert_eq!(result[0].payload.as_ref(), Some(payload));
+    }
+}
+
+#[test]
+fn test_restore_corrupted_snapshot() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
+    let snapshot_dir = Builder::new().prefix("snapshot_dir").tempdir().unwrap();
+    let corrupted_dir = Builder::new().prefix("corrupted_dir").tempdir().unwrap();
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let mut segment = build_segment(dir.path(), &segment_config(), true).unwrap();
+    for n in 0..NUM_POINTS {
+        upsert_point(&mut segment, n, &mut rnd);
+    }
+    let segment_id = segment_id(&segment);
+    let archive = segment
+        .take_snapshot(temp_dir.path(), snapshot_dir.path())
+        .unwrap();
+
+    let corrupted_archive = corrupted_dir.path().join(archive.file_name().unwrap());
+    corrupt_archive(&archive, &corrupted_archive, "segment.json");
+    assert!(Segment::restore_snapshot(&corrupted_archive, &segment_id).is_err());
+    // Nothing is left to be loaded as a segment
+    assert!(!corrupted_dir.path().join(&segment_id).exists());
+
+    Segment::restore_snapshot(&archive, &segment_id).unwrap();
+    let restored = load_segment(&snapshot_dir.path().join(&segment_id))
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored.available_point_count(), NUM_POINTS as usize);
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::random_vector;
+use segment::index::hnsw_index::build_progress::BuildProgress;
+use segment::segment_constructor::build_segment;
+use segment::segment_constructor::segment_builder::SegmentBuilder;
+use segment::types::{
+    Distance, HnswConfig, Indexes, Payload, PayloadSchemaType, SegmentConfig, VectorDataConfig,
+    VectorStorageDatatype, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const DIM: usize = 8;
+const NUM_POINTS: u64 = 1_000;
+const NUM_DELETED: u64 = 100;
+
+fn segment_config(index: Indexes) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: DIM,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index,
+                quantization_config: None,
+                datatype: Some(VectorStorageDatatype::Float16),
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        payload_storage_type: Default::default(),
+        sparse_vector_data: Default::default(),
+    }
+}
+
+#[test]
+fn test_segment_telemetry() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
+    let stopped = AtomicBool::new(false);
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let mut plain_segment =
+        build_segment(dir.path(), &segment_config(Indexes::Plain {}), true).unwrap();
+    plain_segment
+        .create_field_index(0, "tag", Some(&PayloadSchemaType::Keyword.into()))
+        .unwrap();
+    for n in 0..NUM_POINTS {
+        let vector = random_vector(&mut rnd, DIM);
+        plain_segment
+            .upsert_point(n + 1, n.into(), only_default_vector(&vector))
+            .unwrap();
+        let payload: Payload = json!({ "tag": format!("tag-{}", n % 10) }).into();
+        plain_segment
+            .set_full_payload(n + 1, n.into(), &payload)
+            .unwrap();
+    }
+    for n in 0..NUM_DELETED {
+        plain_segment
+            .delete_point(NUM_POINTS + n + 1, n.into())
+            .unwrap();
+    }
+
+    let telemetry = plain_segment.get_telemetry_data();
+    assert_eq!(
+        telemetry.info.num_points,
+        (NUM_POINTS - NUM_DELETED) as usize
+    );
+    assert_eq!(telemetry.info.num_deleted_vectors, NUM_DELETED as usize);
+    assert_eq!(telemetry.optimization_progress, None);
+    assert_eq!(telemetry.vector_data.len(), 1);
+    let vector_data = &telemetry.vector_data[0];
+    assert_eq!(vector_data.vector_name, DEFAULT_VECTOR_NAME);
+    assert_eq!(vector_data.storage_type, VectorStorageType::Memory);
+    assert_eq!(vector_data.vectors_count, NUM_POINTS as usize);
+    assert_eq!(
+        vector_data.vectors_size_bytes,
+        NUM_POINTS as usize * DIM * 2
+    );
+    assert!(!vector_data.hnsw_built);
+    assert!(vector_data.hnsw.is_none());
+    assert_eq!(telemetry.payload_field_indices.len(), 1);
+    assert_eq!(
+        telemetry.payload_field_indices[0].field_name.as_deref(),
+        Some("tag"),
+    );
+
+    let hnsw_config = HnswConfig {
+        m: 8,
+        ef_construct: 32,
+        full_scan_threshold: 1,
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: Some(0),
+    };
+    let mut builder = SegmentBuilder::new(
+        dir.path(),
+        temp_dir.path(),
+        &segment_config(Indexes::Hnsw(hnsw_config)),
+    )
+    .unwrap();
+    let progress = Arc::new(BuildProgress::default());
+    builder.set_build_progress(progress.clone());
+    builder.update_from(&plain_segment, &stopped).unwrap();
+    assert_eq!(progress.fraction(), None);
+    let hnsw_segment = builder.build(&stopped).unwrap();
+
+    // Every point, which is not deleted, is linked into the graph
+    assert_eq!(progress.total_points(), (NUM_POINTS - NUM_DELETED) as usize);
+    assert_eq!(progress.linked_points(), progress.total_points());
+    assert_eq!(progress.fraction(), Some(1.0));
+
+    let telemetry = hnsw_segment.get_telemetry_data();
+    assert_eq!(
+        telemetry.info.num_points,
+        (NUM_POINTS - NUM_DELETED) as usize
+    );
+    assert_eq!(telemetry.info.num_deleted_vectors, 0);
+    let vector_data = &telemetry.vector_data[0];
+    assert_eq!(
+        vector_data.vectors_count,
+        (NUM_POINTS - NUM_DELETED) as usize
+    );
+    assert!(vector_data.hnsw_built);
+    let hnsw = vector_data.hnsw.as_ref().unwrap();
+    assert_eq!(hnsw.m, 8);
+    assert_eq!(hnsw.ef_construct, 32);
+    assert_eq!(telemetry.payload_field_indices.len(), 1);
+}
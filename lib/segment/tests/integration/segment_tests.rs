@@ -44,6 +44,7 @@ fn test_point_exclusion() {
     let frt = Filter {
         should: None,
         must: None,
+        min_should: None,
         must_not: Some(vec![Condition::HasId(ids.into())]),
     };
 
@@ -103,6 +104,7 @@ fn test_named_vector_search() {
     let frt = Filter {
         should: None,
         must: None,
+        min_should: None,
         must_not: Some(vec![Condition::HasId(ids.into())]),
     };
 
@@ -184,6 +186,56 @@ fn test_vector_name_not_exists() {
     }
 }
 
+#[test]
+fn test_non_finite_vector_rejected() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut segment = build_segment_3(dir.path());
+
+    for (op_num, invalid) in [
+        (6, [5., 6., f32::NAN, 8.]),
+        (7, [5., 6., f32::INFINITY, 8.]),
+        (8, [5., 6., f32::NEG_INFINITY, 8.]),
+    ] {
+        let err = segment
+            .upsert_point(
+                op_num,
+                6.into(),
+                NamedVectors::from([
+                    ("vector1".to_owned(), vec![5., 6., 7., 8.]),
+                    ("vector3".to_owned(), invalid.to_vec()),
+                ]),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, OperationError::WrongVectorElement { .. }),
+            "unexpected error: {err}"
+        );
+        let message = err.to_string();
+        assert!(message.contains("point 6"), "{message}");
+        assert!(message.contains("position 2"), "{message}");
+        assert!(message.contains("`vector3`"), "{message}");
+
+        // Existing point can't be updated with an invalid vector either
+        let err = segment
+            .update_vectors(
+                op_num,
+                1.into(),
+                NamedVectors::from([("vector3".to_owned(), invalid.to_vec())]),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, OperationError::WrongVectorElement { .. }),
+            "unexpected error: {err}"
+        );
+        assert!(err.to_string().contains("point 1"), "{err}");
+    }
+
+    // Rejected operations leave no traces
+    assert!(!segment.has_point(6.into()));
+    let stored = segment.vector("vector3", 1.into()).unwrap().unwrap();
+    assert!(stored.iter().all(|value| value.is_finite()));
+}
+
 #[test]
 fn ordered_deletion_test() {
     let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
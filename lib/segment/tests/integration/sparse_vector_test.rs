@@ -0,0 +1,402 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::sparse_vector::{DimId, SparseVector};
+use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::{OperationError, SegmentEntry};
+use segment::fixtures::index_fixtures::random_vector;
+use segment::segment::Segment;
+use segment::segment_constructor::segment_builder::SegmentBuilder;
+use segment::segment_constructor::{build_segment, load_segment};
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, Indexes, Payload, PayloadSchemaType, PointIdType,
+    ScoredPoint, SegmentConfig, SeqNumberType, SparseIndexConfig, SparseIndexType,
+    SparseVectorDataConfig, VectorDataConfig, VectorStorageType,
+};
+use serde_json::json;
+use tempfile::Builder;
+
+const SPARSE_VECTOR_NAME: &str = "sparse";
+const NUM_VECTORS: u64 = 1000;
+const MAX_DIM_ID: DimId = 200;
+const MAX_NNZ: usize = 20;
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+fn segment_config(
+    index_type: SparseIndexType,
+    full_scan_threshold: Option<usize>,
+) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            DEFAULT_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: 4,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                datatype: None,
+                multivector_config: None,
+                store_original_vectors: false,
+            },
+        )]),
+        sparse_vector_data: HashMap::from([(
+            SPARSE_VECTOR_NAME.to_owned(),
+            SparseVectorDataConfig {
+                index: SparseIndexConfig {
+                    full_scan_threshold,
+                    index_type,
+                },
+            },
+        )]),
+        payload_storage_type: Default::default(),
+    }
+}
+
+fn random_sparse_vector(rnd: &mut StdRng) -> SparseVector {
+    let nnz = rnd.gen_range(1..=MAX_NNZ);
+    let indices: BTreeSet<DimId> = (0..nnz).map(|_| rnd.gen_range(0..MAX_DIM_ID)).collect();
+    let values = indices.iter().map(|_| rnd.gen_range(0.0..1.0)).collect();
+    SparseVector::new(indices.into_iter().collect(), values).unwrap()
+}
+
+fn color_filter(color: &str) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "color",
+        color.to_owned().into(),
+    )))
+}
+
+/// Segment with a dense and a sparse vector and a color payload on each point
+fn build_random_segment(path: &Path, config: &SegmentConfig) -> (Segment, Vec<SparseVector>) {
+    let mut rnd = StdRng::seed_from_u64(42);
+    let mut segment = build_segment(path, config, true).unwrap();
+    segment
+        .create_field_index(0, "color", Some(&PayloadSchemaType::Keyword.into()))
+        .unwrap();
+
+    let mut vectors = Vec::new();
+    for n in 0..NUM_VECTORS {
+        let op_num = n as SeqNumberType + 1;
+        let idx: PointIdType = n.into();
+        segment
+            .upsert_point(
+                op_num,
+                idx,
+                only_default_vector(&random_vector(&mut rnd, 4)),
+            )
+            .unwrap();
+        let sparse_vector = random_sparse_vector(&mut rnd);
+        segment
+            .update_sparse_vectors(
+                op_num,
+                idx,
+                &HashMap::from([(SPARSE_VECTOR_NAME.to_owned(), sparse_vector.clone())]),
+            )
+            .unwrap();
+        let payload: Payload = json!({ "color": COLORS[n as usize % COLORS.len()] }).into();
+        segment.set_full_payload(op_num, idx, &payload).unwrap();
+        vectors.push(sparse_vector);
+    }
+    (segment, vectors)
+}
+
+fn search(segment: &Segment, query: &SparseVector, filter: Option<&Filter>) -> Vec<ScoredPoint> {
+    segment
+        .search_sparse(
+            SPARSE_VECTOR_NAME,
+            query,
+            &false.into(),
+            &false.into(),
+            filter,
+            10,
+            &AtomicBool::new(false),
+        )
+        .unwrap()
+}
+
+/// Scores of the top points among `candidates` by scoring each of them
+fn brute_force_scores(
+    vectors: &[SparseVector],
+    candidates: impl Iterator<Item = u64>,
+    query: &SparseVector,
+) -> Vec<f32> {
+    let mut scores: Vec<_> = candidates
+        .filter_map(|n| vectors[n as usize].score(query))
+        .collect();
+    scores.sort_by(|a, b| b.total_cmp(a));
+    scores.truncate(10);
+    scores
+}
+
+fn assert_scores(
+    result: &[ScoredPoint],
+    vectors: &[SparseVector],
+    query: &SparseVector,
+    expected: &[f32],
+) {
+    let scores: Vec<_> = result.iter().map(|point| point.score).collect();
+    assert_eq!(scores.len(), expected.len());
+    for (score, expected) in scores.iter().zip(expected) {
+        assert!(
+            (score - expected).abs() < 1e-5,
+            "{scores:?} != {expected:?}"
+        );
+    }
+    for point in result {
+        let PointIdType::NumId(n) = point.id else {
+            panic!("unexpected point id {}", point.id);
+        };
+        assert_eq!(vectors[n as usize].score(query), Some(point.score));
+    }
+}
+
+#[test]
+fn sparse_vector_validation_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let config = segment_config(SparseIndexType::MutableRam, None);
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+    segment
+        .upsert_point(1, 1.into(), only_default_vector(&[1.0; 4]))
+        .unwrap();
+
+    let invalid_vectors = [
+        SparseVector {
+            indices: vec![1, 2],
+            values: vec![1.0],
+        },
+        SparseVector {
+            indices: vec![2, 1],
+            values: vec![1.0, 1.0],
+        },
+        SparseVector {
+            indices: vec![1, 1],
+            values: vec![1.0, 1.0],
+        },
+        SparseVector {
+            indices: vec![1],
+            values: vec![f32::NAN],
+        },
+    ];
+    for (op_num, invalid) in (2..).zip(invalid_vectors) {
+        let vectors = HashMap::from([(SPARSE_VECTOR_NAME.to_owned(), invalid.clone())]);
+        let err = segment
+            .update_sparse_vectors(op_num, 1.into(), &vectors)
+            .unwrap_err();
+        assert!(
+            matches!(err, OperationError::WrongVectorElement { .. }),
+            "unexpected error: {err}"
+        );
+
+        let err = segment
+            .search_sparse(
+                SPARSE_VECTOR_NAME,
+                &invalid,
+                &false.into(),
+                &false.into(),
+                None,
+                10,
+                &AtomicBool::new(false),
+            )
+            .unwrap_err();
+        assert!(matches!(err, OperationError::WrongVectorElement { .. }));
+    }
+
+    let valid = SparseVector::new(vec![1, 5], vec![1.0, 2.0]).unwrap();
+    let err = segment
+        .update_sparse_vectors(
+            10,
+            1.into(),
+            &HashMap::from([("missing".to_owned(), valid.clone())]),
+        )
+        .unwrap_err();
+    assert!(matches!(err, OperationError::VectorNameNotExists { .. }));
+
+    let vectors = HashMap::from([(SPARSE_VECTOR_NAME.to_owned(), valid.clone())]);
+    let err = segment
+        .update_sparse_vectors(11, 2.into(), &vectors)
+        .unwrap_err();
+    assert!(matches!(err, OperationError::PointIdError { .. }));
+
+    // Rejected operations leave no traces
+    assert_eq!(
+        segment.sparse_vector(SPARSE_VECTOR_NAME, 1.into()).unwrap(),
+        None
+    );
+
+    segment
+        .update_sparse_vectors(12, 1.into(), &vectors)
+        .unwrap();
+    assert_eq!(
+        segment.sparse_vector(SPARSE_VECTOR_NAME, 1.into()).unwrap(),
+        Some(valid)
+    );
+}
+
+/// Search must return the same top as scoring every point, with and without filters
+fn sparse_vector_search_test(full_scan_threshold: Option<usize>) {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let config = segment_config(SparseIndexType::MutableRam, full_scan_threshold);
+    let (segment, vectors) = build_random_segment(dir.path(), &config);
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    for _ in 0..10 {
+        let query = random_sparse_vector(&mut rnd);
+
+        let result = search(&segment, &query, None);
+        let expected = brute_force_scores(&vectors, 0..NUM_VECTORS, &query);
+        assert_scores(&result, &vectors, &query, &expected);
+
+        let filter = color_filter("green");
+        let result = search(&segment, &query, Some(&filter));
+        let expected = brute_force_scores(
+            &vectors,
+            (0..NUM_VECTORS).filter(|n| COLORS[*n as usize % COLORS.len()] == "green"),
+            &query,
+        );
+        assert_scores(&result, &vectors, &query, &expected);
+    }
+}
+
+#[test]
+fn sparse_vector_search_full_scan_test() {
+    // Filtered points are scored one by one
+    sparse_vector_search_test(None);
+}
+
+#[test]
+fn sparse_vector_search_traversal_test() {
+    // Filter is checked while traversing the inverted index
+    sparse_vector_search_test(Some(0));
+}
+
+#[test]
+fn sparse_vector_delete_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let config = segment_config(SparseIndexType::MutableRam, None);
+    let (mut segment, vectors) = build_random_segment(dir.path(), &config);
+
+    let query = vectors[0].clone();
+    let result = search(&segment, &query, None);
+    assert_eq!(result[0].id, 0.into());
+
+    // Deleted sparse vector is not searchable, but the point is kept
+    segment
+        .delete_sparse_vector(NUM_VECTORS + 1, 0.into(), SPARSE_VECTOR_NAME)
+        .unwrap();
+    assert_eq!(
+        segment.sparse_vector(SPARSE_VECTOR_NAME, 0.into()).unwrap(),
+        None
+    );
+    assert!(segment
+        .vector(DEFAULT_VECTOR_NAME, 0.into())
+        .unwrap()
+        .is_some());
+    let result = search(&segment, &query, None);
+    assert!(result.iter().all(|point| point.id != 0.into()));
+
+    // Deleted point is not searchable
+    let top_id = result[0].id;
+    segment.delete_point(NUM_VECTORS + 2, top_id).unwrap();
+    let result = search(&segment, &query, None);
+    assert!(result.iter().all(|point| point.id != top_id));
+
+    // Replaced vector is searched by the new value only
+    let replacement = SparseVector::new(vec![MAX_DIM_ID + 1], vec![1.0]).unwrap();
+    segment
+        .update_sparse_vectors(
+            NUM_VECTORS + 3,
+            1.into(),
+            &HashMap::from([(SPARSE_VECTOR_NAME.to_owned(), replacement.clone())]),
+        )
+        .unwrap();
+    let result = search(&segment, &replacement, None);
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, 1.into());
+    let result = search(&segment, &vectors[1], None);
+    assert!(result.iter().all(|point| point.id != 1.into()));
+}
+
+#[test]
+fn sparse_vector_persistence_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let config = segment_config(SparseIndexType::MutableRam, None);
+    let (path, vectors) = {
+        let (mut segment, vectors) = build_random_segment(dir.path(), &config);
+        segment
+            .delete_sparse_vector(NUM_VECTORS + 1, 0.into(), SPARSE_VECTOR_NAME)
+            .unwrap();
+        segment.flush(true).unwrap();
+        (segment.current_path.clone(), vectors)
+    };
+
+    let segment = load_segment(&path).unwrap().unwrap();
+    assert_eq!(
+        segment.sparse_vector(SPARSE_VECTOR_NAME, 0.into()).unwrap(),
+        None
+    );
+    for n in 1..NUM_VECTORS {
+        let stored = segment.sparse_vector(SPARSE_VECTOR_NAME, n.into()).unwrap();
+        assert_eq!(stored.as_ref(), Some(&vectors[n as usize]));
+    }
+
+    let query = vectors[0].clone();
+    let result = search(&segment, &query, None);
+    let expected = brute_force_scores(&vectors, 1..NUM_VECTORS, &query);
+    assert_scores(&result, &vectors, &query, &expected);
+}
+
+/// Optimized segment with mmap inverted index must search the same as the source one
+#[test]
+fn sparse_vector_mmap_index_test() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+    let stopped = AtomicBool::new(false);
+
+    let config = segment_config(SparseIndexType::MutableRam, None);
+    let (mut segment, vectors) = build_random_segment(dir.path(), &config);
+    segment.delete_point(NUM_VECTORS + 1, 0.into()).unwrap();
+
+    let mmap_config = segment_config(SparseIndexType::Mmap, None);
+    let mut builder = SegmentBuilder::new(dir.path(), temp_dir.path(), &mmap_config).unwrap();
+    assert!(builder.update(&[&segment], &stopped).unwrap());
+    let built_segment = builder.build(&stopped).unwrap();
+
+    assert!(!built_segment.is_appendable());
+    assert_eq!(
+        built_segment.available_point_count(),
+        NUM_VECTORS as usize - 1
+    );
+
+    let mut rnd = StdRng::seed_from_u64(43);
+    for _ in 0..10 {
+        let query = random_sparse_vector(&mut rnd);
+        for filter in [None, Some(color_filter("red"))] {
+            let expected: Vec<_> = search(&segment, &query, filter.as_ref())
+                .iter()
+                .map(|point| point.score)
+                .collect();
+            let result = search(&built_segment, &query, filter.as_ref());
+            assert_scores(&result, &vectors, &query, &expected);
+        }
+    }
+
+    // Index is loaded from disk the same way
+    let path = built_segment.current_path.clone();
+    drop(built_segment);
+    let loaded_segment = load_segment(&path).unwrap().unwrap();
+    let query = vectors[1].clone();
+    let expected: Vec<_> = search(&segment, &query, None)
+        .iter()
+        .map(|point| point.score)
+        .collect();
+    assert_scores(
+        &search(&loaded_segment, &query, None),
+        &vectors,
+        &query,
+        &expected,
+    );
+}
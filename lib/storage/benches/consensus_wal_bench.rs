@@ -0,0 +1,65 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use raft::eraftpb::Entry;
+use storage::content_manager::consensus::consensus_wal::ConsensusOpWal;
+use tempfile::Builder;
+
+const BATCH_SIZE: u64 = 64;
+
+fn entries(first_index: u64) -> Vec<Entry> {
+    (first_index..first_index + BATCH_SIZE)
+        .map(|index| Entry {
+            entry_type: 0,
+            term: 1,
+            index,
+            data: vec![0; 256],
+            context: vec![],
+            sync_log: false,
+        })
+        .collect()
+}
+
+/// Compares appending entries one by one, which flushes WAL for each of them,
+/// with appending them as a single batch, which flushes WAL once.
+fn consensus_wal_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("consensus-wal-bench");
+
+    let wal_dir = Builder::new().prefix("consensus_wal").tempdir().unwrap();
+    let mut wal = ConsensusOpWal::new(wal_dir.path().to_str().unwrap());
+    let mut next_index = 1;
+
+    group.bench_function("append-one-by-one", |b| {
+        b.iter_batched(
+            || {
+                let batch = entries(next_index);
+                next_index += BATCH_SIZE;
+                batch
+            },
+            |batch| {
+                for entry in batch {
+                    wal.append_entries(vec![entry]).unwrap();
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("append-batch", |b| {
+        b.iter_batched(
+            || {
+                let batch = entries(next_index);
+                next_index += BATCH_SIZE;
+                batch
+            },
+            |batch| wal.append_entries(batch).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = consensus_wal_bench,
+}
+
+criterion_main!(benches);
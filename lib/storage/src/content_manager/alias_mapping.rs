@@ -24,6 +24,32 @@ impl AliasMapping {
     pub fn save(&self, path: &Path) -> Result<(), StorageError> {
         Ok(atomic_save_json(path, self)?)
     }
+
+    pub fn get(&self, alias: &str) -> Option<&CollectionId> {
+        self.0.get(alias)
+    }
+
+    pub fn insert(&mut self, alias: String, collection_name: CollectionId) {
+        self.0.insert(alias, collection_name);
+    }
+
+    pub fn remove(&mut self, alias: &str) -> Option<CollectionId> {
+        self.0.remove(alias)
+    }
+
+    pub fn rename(
+        &mut self,
+        old_alias_name: &str,
+        new_alias_name: String,
+    ) -> Result<(), StorageError> {
+        let Some(collection_name) = self.0.remove(old_alias_name) else {
+            return Err(StorageError::NotFound {
+                description: format!("Alias {old_alias_name} does not exists!"),
+            });
+        };
+        self.0.insert(new_alias_name, collection_name);
+        Ok(())
+    }
 }
 
 /// Persists mapping between alias and collection name. The data is assumed to be relatively small.
@@ -100,18 +126,10 @@ impl AliasPersistence {
         old_alias_name: &str,
         new_alias_name: String,
     ) -> Result<(), StorageError> {
-        match self.get(old_alias_name) {
-            None => Err(StorageError::NotFound {
-                description: format!("Alias {old_alias_name} does not exists!"),
-            }),
-            Some(collection_name) => {
-                self.alias_mapping.0.remove(old_alias_name);
-                self.alias_mapping.0.insert(new_alias_name, collection_name);
-                // 'remove' & 'insert' saved atomically
-                self.alias_mapping.save(&self.data_path)?;
-                Ok(())
-            }
-        }
+        self.alias_mapping.rename(old_alias_name, new_alias_name)?;
+        // 'remove' & 'insert' saved atomically
+        self.alias_mapping.save(&self.data_path)?;
+        Ok(())
     }
 
     pub fn collection_aliases(&self, collection_name: &str) -> Vec<String> {
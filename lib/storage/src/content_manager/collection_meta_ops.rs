@@ -1,11 +1,16 @@
-use collection::config::CollectionConfig;
+use std::collections::BTreeMap;
+use std::num::NonZeroU64;
+
+use collection::config::{CollectionConfig, ShardingMethod};
 use collection::operations::config_diff::{
     CollectionParamsDiff, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
     WalConfigDiff,
 };
-use collection::operations::types::{VectorsConfig, VectorsConfigDiff};
+use collection::operations::strict_mode::StrictModeConfig;
+use collection::operations::types::{SparseVectorParams, VectorsConfig, VectorsConfigDiff};
 use collection::shards::replica_set::ReplicaState;
-use collection::shards::shard::{PeerId, ShardId};
+use collection::shards::resharding::{ReshardingState, ShardSplit};
+use collection::shards::shard::{PeerId, ShardId, ShardKey, ShardsPlacement};
 use collection::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
 use collection::shards::{replica_set, CollectionId};
 use schemars::JsonSchema;
@@ -104,11 +109,22 @@ pub struct CreateCollection {
     /// It is possible to provide one config for single vector mode and list of configs for multiple vectors mode.
     #[validate]
     pub vectors: VectorsConfig,
+    /// Sparse vector data config.
+    /// Names of sparse vectors must differ from names of dense vectors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
     /// Number of shards in collection.
     /// Default is 1 for standalone, otherwise equal to the number of nodes
     /// Minimum is 1
+    /// With custom sharding - default number of shards of each shard key
     #[serde(default)]
     pub shard_number: Option<u32>,
+    /// Sharding method. Default is `auto` - points are distributed among all shards by their ids.
+    /// `custom` - the collection is created without shards, shards are created for each shard key
+    /// with `create_sharding_key` operation, and every upsert has to specify the shard key of its
+    /// points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sharding_method: Option<ShardingMethod>,
     /// Number of shards replicas.
     /// Default is 1
     /// Minimum is 1
@@ -126,6 +142,22 @@ pub struct CreateCollection {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default)]
     pub on_disk_payload: Option<bool>,
+    /// Payload key which separates tenants of the collection.
+    /// If set - vectors of points with the same value of this key are stored next to each other
+    /// after optimization, which speeds up searches filtered by tenant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_key: Option<String>,
+    /// If true - vectors are stored and returned exactly as they were inserted.
+    /// Otherwise vectors are stored preprocessed for the distance, e.g. normalized for cosine.
+    /// Search results are the same in both cases, scoring original vectors is slower.
+    /// Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_original_vectors: Option<bool>,
+    /// Default timeout of searches in the collection, in seconds.
+    /// If not set, the search timeout of the service is used.
+    /// A search request may set its own timeout instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_timeout_sec: Option<u64>,
     /// Custom params for HNSW index. If none - values from service configuration file are used.
     #[validate]
     pub hnsw_config: Option<HnswConfigDiff>,
@@ -143,6 +175,10 @@ pub struct CreateCollection {
     #[serde(default, alias = "quantization")]
     #[validate]
     pub quantization_config: Option<QuantizationConfig>,
+    /// Limits of requests to the collection. If none - strict mode is disabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
 }
 
 /// Operation for creating new collection and (optionally) specify index params
@@ -197,6 +233,11 @@ pub struct UpdateCollection {
     #[serde(default, alias = "quantization")]
     #[validate]
     pub quantization_config: Option<QuantizationConfigDiff>,
+    /// Limits of requests to update. Only the specified limits are changed,
+    /// the changes apply to the next requests without a restart of the collection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub strict_mode_config: Option<StrictModeConfig>,
 }
 
 /// Operation for updating parameters of the existing collection
@@ -218,6 +259,7 @@ impl UpdateCollectionOperation {
                 params: None,
                 optimizers_config: None,
                 quantization_config: None,
+                strict_mode_config: None,
             },
             shard_replica_changes: None,
         }
@@ -254,7 +296,7 @@ impl UpdateCollectionOperation {
 
 /// Operation for performing changes of collection aliases.
 /// Alias changes are atomic, meaning that no collection modifications can happen between
-/// alias operations.
+/// alias operations. If any of the operations fails, none of them are applied.
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct ChangeAliasesOperation {
@@ -276,6 +318,29 @@ pub enum ShardTransferOperations {
     },
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub enum ReshardingOperation {
+    Start(ReshardingState),
+    Finish(ShardSplit),
+    Abort { split: ShardSplit, reason: String },
+}
+
+/// Creates shards of a new shard key in a collection with custom sharding
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct CreateShardKey {
+    pub collection_name: String,
+    pub shard_key: ShardKey,
+    /// Peers of the replicas of each new shard
+    pub placement: ShardsPlacement,
+}
+
+/// Removes all shards of the shard key, with all points in them
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct DropShardKey {
+    pub collection_name: String,
+    pub shard_key: ShardKey,
+}
+
 /// Sets the state of shard replica
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct SetShardReplicaState {
@@ -303,6 +368,9 @@ pub enum CollectionMetaOperations {
     ChangeAliases(ChangeAliasesOperation),
     TransferShard(CollectionId, ShardTransferOperations),
     SetShardReplicaState(SetShardReplicaState),
+    CreateShardKey(CreateShardKey),
+    DropShardKey(DropShardKey),
+    Resharding(CollectionId, ReshardingOperation),
     Nop { token: usize }, // Empty operation
 }
 
@@ -312,15 +380,21 @@ impl From<CollectionConfig> for CreateCollection {
     fn from(value: CollectionConfig) -> Self {
         Self {
             vectors: value.params.vectors,
+            sparse_vectors: value.params.sparse_vectors,
             shard_number: Some(value.params.shard_number.get()),
+            sharding_method: value.params.sharding_method,
             replication_factor: Some(value.params.replication_factor.get()),
             write_consistency_factor: Some(value.params.write_consistency_factor.get()),
             on_disk_payload: Some(value.params.on_disk_payload),
+            tenant_key: value.params.tenant_key,
+            store_original_vectors: Some(value.params.store_original_vectors),
+            search_timeout_sec: value.params.search_timeout_sec.map(NonZeroU64::get),
             hnsw_config: Some(value.hnsw_config.into()),
             wal_config: Some(value.wal_config.into()),
             optimizers_config: Some(value.optimizer_config.into()),
             init_from: None,
             quantization_config: value.quantization_config,
+            strict_mode_config: value.strict_mode_config,
         }
     }
 }
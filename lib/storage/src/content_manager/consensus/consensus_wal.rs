@@ -1,6 +1,7 @@
-use std::fs::create_dir_all;
+use std::fs::{create_dir_all, read_dir};
 use std::path::Path;
 
+use collection::wal::{ReadOnlyWal, WalFiles};
 use itertools::Itertools;
 use prost::Message;
 use protobuf::Message as _;
@@ -12,7 +13,7 @@ use crate::{ConsensusOperations, StorageError};
 
 const COLLECTIONS_META_WAL_DIR: &str = "collections_meta_wal";
 
-pub struct ConsensusOpWal(Wal);
+pub struct ConsensusOpWal(WalFiles);
 
 impl ConsensusOpWal {
     pub fn new(storage_path: &str) -> Self {
@@ -20,11 +21,43 @@ impl ConsensusOpWal {
         create_dir_all(&collections_meta_wal_path)
             .expect("Can't create Collections meta Wal directory");
         let wal = Wal::open(collections_meta_wal_path).expect("Can't open Collections meta Wal");
-        ConsensusOpWal(wal)
+        ConsensusOpWal(WalFiles::Writable(wal))
+    }
+
+    /// Open consensus WAL for reading only.
+    ///
+    /// Works for WAL, which is used by a running node: the original directory is neither locked
+    /// nor modified, segments are read in place. Methods, which modify WAL,
+    /// return [`StorageError::Locked`].
+    pub fn open_read_only(storage_path: &str) -> Result<Self, StorageError> {
+        let collections_meta_wal_path = Path::new(storage_path).join(COLLECTIONS_META_WAL_DIR);
+        let wal = ReadOnlyWal::open(&collections_meta_wal_path)?;
+        Ok(ConsensusOpWal(WalFiles::ReadOnly(wal)))
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.0.is_read_only()
+    }
+
+    fn check_writable(&self) -> Result<(), StorageError> {
+        if self.is_read_only() {
+            return Err(Self::read_only_error());
+        }
+        Ok(())
+    }
+
+    fn writable(&mut self) -> Result<&mut Wal, StorageError> {
+        self.0.writable().ok_or_else(Self::read_only_error)
+    }
+
+    fn read_only_error() -> StorageError {
+        StorageError::Locked {
+            description: "Consensus WAL is opened in read-only mode".to_string(),
+        }
     }
 
     pub fn clear(&mut self) -> Result<(), StorageError> {
-        Ok(self.0.clear()?)
+        Ok(self.writable()?.clear()?)
     }
 
     pub fn entry(&self, id: u64) -> raft::Result<RaftEntry> {
@@ -122,6 +155,39 @@ impl ConsensusOpWal {
         Ok(entry.transpose()?)
     }
 
+    /// Remove entries with raft index lower than `index` from WAL.
+    ///
+    /// The last entry is always kept, so the difference between raft index and WAL record number
+    /// stays known. WAL may keep some of the older entries, as it only removes whole segments.
+    pub fn compact_until(&mut self, index: u64) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let (Some(first_entry), Some(last_entry)) = (self.first_entry()?, self.last_entry()?)
+        else {
+            return Ok(());
+        };
+        let index = index.min(last_entry.index);
+        if index <= first_entry.index {
+            return Ok(());
+        }
+        let offset = first_entry.index - self.0.first_index();
+        log::debug!("Compact consensus WAL until raft index {index}");
+        self.writable()?.prefix_truncate(index - offset)?;
+        debug_assert_eq!(self.index_offset()?, Some(offset));
+        Ok(())
+    }
+
+    /// Total size of WAL files on disk
+    pub fn size_bytes(&self) -> Result<u64, StorageError> {
+        let mut size = 0;
+        for entry in read_dir(self.0.path())? {
+            let metadata = entry?.metadata()?;
+            if metadata.is_file() {
+                size += metadata.len();
+            }
+        }
+        Ok(size)
+    }
+
     /// Difference between raft index and WAL record number.
     /// Difference might be different because of consensus snapshot.
     fn index_offset(&self) -> Result<Option<u64>, StorageError> {
@@ -131,53 +197,76 @@ impl ConsensusOpWal {
         Ok(offset)
     }
 
+    /// Append a batch of raft entries and flush WAL to disk once.
+    ///
+    /// Entries of the batch must have consecutive indices. If the batch overlaps with existing
+    /// entries, e.g. when a new leader overwrites uncommitted entries of a previous term,
+    /// the conflicting suffix of WAL is truncated first.
     pub fn append_entries(&mut self, entries: Vec<RaftEntry>) -> Result<(), StorageError> {
-        for entry in entries {
-            let operation_opt = ConsensusOperations::try_from(&entry).ok();
-
-            let index = entry.index;
-            let current_index = self.0.last_index();
-            let index_offset = self.index_offset()?;
-
-            if let Some(offset) = index_offset {
-                // Assume we can't skip index numbers in WAL except for snapshot
-                // Example: 2 <= 0 + 1 + 1
-                debug_assert!(
-                    index <= current_index + offset + 1,
-                    "Expected no index skip: {index} <= {current_index} + {offset}"
-                );
+        self.check_writable()?;
+        let Some(first_entry) = entries.first() else {
+            return Ok(());
+        };
+
+        if let Some((prev, next)) = entries
+            .iter()
+            .tuple_windows()
+            .find(|(prev, next)| next.index != prev.index + 1)
+        {
+            return Err(StorageError::service_error(format!(
+                "Raft entries must have consecutive indices, got {} after {}",
+                next.index, prev.index
+            )));
+        }
 
-                if index < current_index + offset {
-                    // If there is a conflict, example:
-                    // Offset = 1
-                    // raft index = 10
-                    // wal index = 11
-                    // expected_wal_index = 10 - 1 = 9
-                    // 10 < 11 + 1
-                    if index < offset {
-                        return Err(StorageError::service_error(format!(
-                            "Wal index conflict, raft index: {index}, wal index: {current_index}, offset: {offset}"
-                        )));
-                    }
-                    log::debug!(
-                        "Truncate conflicting WAL entries from index {}, raft: {index}",
-                        index - offset,
-                    );
-                    self.0.truncate(index - offset)?;
-                } // else:
-                  // Offset = 1
-                  // raft index = 11
-                  // wal index = 10
-                  // expected_wal_index = 11 - 1 = 10
-                  // 11 < 10 + 1
-            } else {
-                // There is no offset => there are no records in WAL
-                // If there are no records, conflict is impossible
-                // So we do nothing
-            }
+        let index = first_entry.index;
+        let current_index = self.0.last_index();
+        let index_offset = self.index_offset()?;
+
+        let offset = if let Some(offset) = index_offset {
+            // Assume we can't skip index numbers in WAL except for snapshot
+            // Example: 2 <= 0 + 1 + 1
+            debug_assert!(
+                index <= current_index + offset + 1,
+                "Expected no index skip: {index} <= {current_index} + {offset}"
+            );
+
+            if index <= current_index + offset {
+                // If there is a conflict, example:
+                // Offset = 1
+                // raft index = 10
+                // wal index = 11
+                // expected_wal_index = 10 - 1 = 9
+                // 10 <= 11 + 1
+                if index < offset {
+                    return Err(StorageError::service_error(format!(
+                        "Wal index conflict, raft index: {index}, wal index: {current_index}, offset: {offset}"
+                    )));
+                }
+                log::debug!(
+                    "Truncate conflicting WAL entries from index {}, raft: {index}",
+                    index - offset,
+                );
+                self.writable()?.truncate(index - offset)?;
+            } // else:
+              // Offset = 1
+              // raft index = 11
+              // wal index = 9
+              // expected_wal_index = 11 - 1 = 10
+              // 11 > 9 + 1
+
+            offset
+        } else {
+            // There is no offset => there are no records in WAL
+            // If there are no records, conflict is impossible
+            // The first entry of the batch defines the offset
+            index
+        };
 
-            if let Some(operation) = operation_opt {
+        for entry in entries {
+            if let Ok(operation) = ConsensusOperations::try_from(&entry) {
                 let term = entry.term;
+                let index = entry.index;
                 log::debug!(
                     "Appending operation: term: {term}, index: {index} entry: {operation:?}"
                 );
@@ -188,16 +277,11 @@ impl ConsensusOpWal {
             let mut buf = vec![];
             entry.encode(&mut buf)?;
             #[allow(unused_variables)]
-            let wal_index = self.0.append(&buf)?;
-            #[cfg(debug_assertions)]
-            if let Some(offset) = index_offset {
-                debug_assert!(wal_index == index - offset);
-            } else {
-                debug_assert!(wal_index == 0)
-            }
+            let wal_index = self.writable()?.append(&buf)?;
+            debug_assert_eq!(wal_index, entry.index - offset);
         }
-        // flush consensus WAL to disk
-        self.0.flush_open_segment()?;
+        // flush consensus WAL to disk once for the whole batch
+        self.writable()?.flush_open_segment()?;
         Ok(())
     }
 }
@@ -396,4 +480,162 @@ mod tests {
         assert_eq!(wal.first_entry().unwrap().unwrap().index, 1);
         assert_eq!(wal.last_entry().unwrap().unwrap().index, 4);
     }
+
+    #[test]
+    fn test_compact_until() {
+        init_logger();
+        let entries: Vec<_> = (1..=5)
+            .map(|index| Entry {
+                entry_type: 0,
+                term: 1,
+                index,
+                data: vec![index as u8; 3],
+                context: vec![],
+                sync_log: false,
+            })
+            .collect();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut wal = ConsensusOpWal::new(temp_dir.path().to_str().unwrap());
+        wal.append_entries(entries).unwrap();
+
+        // Compaction at the very first index is a no-op
+        wal.compact_until(1).unwrap();
+        assert_eq!(wal.first_entry().unwrap().unwrap().index, 1);
+        assert_eq!(wal.index_offset().unwrap(), Some(1));
+        assert_eq!(wal.entries(1, 6, None).unwrap().len(), 5);
+
+        // Compaction in the middle
+        wal.compact_until(3).unwrap();
+        let first_index = wal.first_entry().unwrap().unwrap().index;
+        // WAL may keep older entries, as it only removes whole segments
+        assert!(first_index <= 3);
+        assert_eq!(wal.index_offset().unwrap(), Some(1));
+        assert_eq!(wal.last_entry().unwrap().unwrap().index, 5);
+        let result_entries = wal.entries(3, 6, None).unwrap();
+        assert_eq!(result_entries.len(), 3);
+        assert_eq!(result_entries[0].data, vec![3, 3, 3]);
+        if first_index > 1 {
+            assert!(matches!(
+                wal.entries(1, 6, None),
+                Err(raft::Error::Store(raft::StorageError::Compacted))
+            ));
+        }
+
+        // Compaction at the last index keeps the last entry
+        wal.compact_until(5).unwrap();
+        assert!(wal.first_entry().unwrap().unwrap().index <= 5);
+        assert_eq!(wal.index_offset().unwrap(), Some(1));
+        let result_entries = wal.entries(5, 6, None).unwrap();
+        assert_eq!(result_entries.len(), 1);
+        assert_eq!(result_entries[0].data, vec![5, 5, 5]);
+
+        // Compaction state survives restart
+        drop(wal);
+        let mut wal = ConsensusOpWal::new(temp_dir.path().to_str().unwrap());
+        assert_eq!(wal.index_offset().unwrap(), Some(1));
+        assert_eq!(wal.last_entry().unwrap().unwrap().index, 5);
+
+        // New entries are appended after compaction
+        wal.append_entries(vec![Entry {
+            entry_type: 0,
+            term: 1,
+            index: 6,
+            data: vec![6, 6, 6],
+            context: vec![],
+            sync_log: false,
+        }])
+        .unwrap();
+        assert_eq!(wal.entry(6).unwrap().data, vec![6, 6, 6]);
+    }
+
+    #[test]
+    fn test_append_conflicting_batch() {
+        init_logger();
+        let entry = |term, index| Entry {
+            entry_type: 0,
+            term,
+            index,
+            data: vec![term as u8; 3],
+            context: vec![],
+            sync_log: false,
+        };
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut wal = ConsensusOpWal::new(temp_dir.path().to_str().unwrap());
+        wal.append_entries((1..=5).map(|index| entry(1, index)).collect())
+            .unwrap();
+
+        // New leader overwrites uncommitted entries of the previous term
+        wal.append_entries(vec![entry(2, 3), entry(2, 4)]).unwrap();
+        assert_eq!(wal.last_entry().unwrap().unwrap().index, 4);
+        let result_entries = wal.entries(1, 5, None).unwrap();
+        let terms: Vec<_> = result_entries.iter().map(|entry| entry.term).collect();
+        assert_eq!(terms, vec![1, 1, 2, 2]);
+        assert!(wal.entries(1, 6, None).is_err());
+
+        // Overwrite only the last entry
+        wal.append_entries(vec![entry(3, 4)]).unwrap();
+        let terms: Vec<_> = wal
+            .entries(1, 5, None)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.term)
+            .collect();
+        assert_eq!(terms, vec![1, 1, 2, 3]);
+
+        // Batch with a gap is rejected and WAL stays unchanged
+        assert!(matches!(
+            wal.append_entries(vec![entry(3, 5), entry(3, 7)]),
+            Err(StorageError::ServiceError { .. })
+        ));
+        assert_eq!(wal.last_entry().unwrap().unwrap().index, 4);
+
+        // Changes are persisted
+        drop(wal);
+        let wal = ConsensusOpWal::new(temp_dir.path().to_str().unwrap());
+        assert_eq!(wal.index_offset().unwrap(), Some(1));
+        assert_eq!(wal.last_entry().unwrap().unwrap().term, 3);
+    }
+
+    #[test]
+    fn test_open_read_only() {
+        init_logger();
+        let entry = |index| Entry {
+            entry_type: 0,
+            term: 1,
+            index,
+            data: vec![index as u8; 3],
+            context: vec![],
+            sync_log: false,
+        };
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_path = temp_dir.path().to_str().unwrap();
+
+        // Writer stays open for the whole test
+        let mut writer = ConsensusOpWal::new(storage_path);
+        writer.append_entries((1..=5).map(entry).collect()).unwrap();
+
+        let mut reader = ConsensusOpWal::open_read_only(storage_path).unwrap();
+        assert!(reader.is_read_only());
+        assert_eq!(reader.index_offset().unwrap(), Some(1));
+        let result_entries = reader.entries(1, 6, None).unwrap();
+        assert_eq!(result_entries.len(), 5);
+        assert_eq!(result_entries[4].data, vec![5, 5, 5]);
+
+        assert!(matches!(
+            reader.append_entries(vec![entry(6)]),
+            Err(StorageError::Locked { .. })
+        ));
+        assert!(matches!(
+            reader.compact_until(3),
+            Err(StorageError::Locked { .. })
+        ));
+        assert!(matches!(reader.clear(), Err(StorageError::Locked { .. })));
+
+        // Writer is not affected by the reader
+        writer.append_entries(vec![entry(6)]).unwrap();
+        assert_eq!(writer.last_entry().unwrap().unwrap().index, 6);
+    }
 }
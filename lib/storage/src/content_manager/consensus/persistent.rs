@@ -1,4 +1,5 @@
 use std::cmp;
+use std::collections::HashSet;
 use std::fs::{create_dir_all, File};
 use std::io::BufWriter;
 use std::path::{Path, PathBuf};
@@ -33,12 +34,18 @@ pub struct Persistent {
     /// for this last snapshot ID (term + commit)
     #[serde(default)] // TODO quick fix to avoid breaking the compat. with 0.8.1
     pub latest_snapshot_meta: SnapshotMetadataSer,
+    /// Received consensus snapshot is saved, but not installed completely yet
+    #[serde(default)]
+    pub snapshot_install_pending: bool,
     /// Operations to applied, consensus consider them committed, but this peer didn't apply them yet
     #[serde(default)]
     pub apply_progress_queue: EntryApplyProgressQueue,
     /// Last known cluster topology
     #[serde(with = "serialize_peer_addresses")]
     pub peer_address_by_id: Arc<RwLock<PeerAddressById>>,
+    /// Peers, which joined as listeners. They stay learners of the consensus.
+    #[serde(default)]
+    pub listener_peers: HashSet<PeerId>,
     pub this_peer_id: PeerId,
     #[serde(skip)]
     pub path: PathBuf,
@@ -60,12 +67,29 @@ impl Persistent {
         &mut self,
         meta: &SnapshotMetadata,
         address_by_id: PeerAddressById,
+        listener_peers: HashSet<PeerId>,
     ) -> Result<(), StorageError> {
         *self.peer_address_by_id.write() = address_by_id;
+        self.listener_peers = listener_peers;
         self.state.conf_state = meta.get_conf_state().clone();
         self.state.hard_state.term = cmp::max(self.state.hard_state.term, meta.term);
         self.state.hard_state.commit = meta.index;
         self.apply_progress_queue.set_from_snapshot(meta.index);
+        self.latest_snapshot_meta = meta.into();
+        self.snapshot_install_pending = false;
+        self.save()
+    }
+
+    pub fn set_snapshot_install_pending(&mut self) -> Result<(), StorageError> {
+        self.snapshot_install_pending = true;
+        self.save()
+    }
+
+    /// Remember the snapshot of the locally applied state, which covers compacted WAL entries
+    pub fn set_latest_snapshot_meta(
+        &mut self,
+        meta: &SnapshotMetadata,
+    ) -> Result<(), StorageError> {
         self.latest_snapshot_meta = meta.into();
         self.save()
     }
@@ -150,6 +174,22 @@ impl Persistent {
         self.save()
     }
 
+    pub fn set_listener_peer(
+        &mut self,
+        peer_id: PeerId,
+        listener: bool,
+    ) -> Result<(), StorageError> {
+        let changed = if listener {
+            self.listener_peers.insert(peer_id)
+        } else {
+            self.listener_peers.remove(&peer_id)
+        };
+        if changed {
+            self.save()?;
+        }
+        Ok(())
+    }
+
     pub fn last_applied_entry(&self) -> Option<u64> {
         self.apply_progress_queue.get_last_applied()
     }
@@ -188,9 +228,11 @@ impl Persistent {
             },
             apply_progress_queue: Default::default(),
             peer_address_by_id: Default::default(),
+            listener_peers: Default::default(),
             this_peer_id,
             path,
             latest_snapshot_meta: Default::default(),
+            snapshot_install_pending: false,
             dirty: AtomicBool::new(false),
         };
         state.save()?;
@@ -1,12 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt::Display;
 use std::future::Future;
+use std::io::Write;
 use std::ops::Deref;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
+use std::{fs, io};
 
 use anyhow::{anyhow, Context};
+use atomicwrites::{AllowOverwrite, AtomicFile};
 use chrono::Utc;
 use collection::collection_state;
 use collection::common::is_ready::IsReady;
@@ -15,7 +19,7 @@ use collection::shards::CollectionId;
 use futures::future::join_all;
 use parking_lot::{Mutex, RwLock};
 use raft::eraftpb::{ConfChangeType, ConfChangeV2, Entry as RaftEntry};
-use raft::{GetEntriesContext, RaftState, RawNode, SoftState, Storage};
+use raft::{GetEntriesContext, RaftState, RawNode, SoftState, StateRole, Storage};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::Receiver;
@@ -31,12 +35,14 @@ use crate::content_manager::consensus::entry_queue::EntryId;
 use crate::content_manager::consensus::operation_sender::OperationSender;
 use crate::content_manager::consensus::persistent::Persistent;
 use crate::types::{
-    ClusterInfo, ClusterStatus, ConsensusThreadStatus, MessageSendErrors, PeerAddressById,
-    PeerInfo, RaftInfo,
+    ClusterInfo, ClusterStatus, ConsensusTelemetry, ConsensusThreadStatus, MessageSendErrors,
+    PeerAddressById, PeerInfo, PeerRole, RaftInfo,
 };
 
 pub const DEFAULT_META_OP_WAIT: Duration = Duration::from_secs(10);
 
+const SNAPSHOT_FILE_NAME: &str = "raft_snapshot";
+
 pub mod prelude {
     use crate::content_manager::toc::TableOfContent;
 
@@ -48,6 +54,37 @@ pub struct SnapshotData {
     pub collections_data: CollectionsSnapshot,
     #[serde(with = "crate::serialize_peer_addresses")]
     pub address_by_id: PeerAddressById,
+    #[serde(default)]
+    pub listener_peers: HashSet<PeerId>,
+}
+
+/// Context of the learner addition of a listener peer.
+/// Other peers are added with their plain uri as a context, for compatibility.
+#[derive(Debug, Deserialize)]
+struct ListenerPeerContext {
+    uri: String,
+    listener: bool,
+}
+
+/// Context of the conf change, which adds a peer as a learner
+pub fn add_peer_context(uri: String, listener: bool) -> Vec<u8> {
+    if listener {
+        serde_json::json!({ "uri": uri, "listener": true })
+            .to_string()
+            .into_bytes()
+    } else {
+        uri.into_bytes()
+    }
+}
+
+/// Uri of the peer and whether it is a listener, from the context of the learner addition
+fn parse_add_peer_context(context: &[u8]) -> Option<(Uri, bool)> {
+    let (uri, listener) = match serde_json::from_slice::<ListenerPeerContext>(context) {
+        Ok(context) => (context.uri, context.listener),
+        Err(_) => (String::from_utf8_lossy(context).into_owned(), false),
+    };
+    let uri = uri.as_str().try_into().ok()?;
+    Some((uri, listener))
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -93,6 +130,10 @@ pub struct ConsensusManager<C: CollectionContainer> {
     consensus_thread_status: RwLock<ConsensusThreadStatus>,
     /// Consensus thread errors, changed by the consensus thread
     message_send_failures: RwLock<HashMap<String, MessageSendErrors>>,
+    /// Cached state of the consensus WAL and state machine, changed by the consensus thread
+    consensus_telemetry: RwLock<ConsensusTelemetry>,
+    /// File with the latest consensus snapshot, which covers the compacted WAL entries
+    snapshot_path: PathBuf,
 }
 
 impl<C: CollectionContainer> ConsensusManager<C> {
@@ -115,6 +156,8 @@ impl<C: CollectionContainer> ConsensusManager<C> {
                 last_update: Utc::now(),
             }),
             message_send_failures: Default::default(),
+            consensus_telemetry: Default::default(),
+            snapshot_path: Path::new(storage_path).join(SNAPSHOT_FILE_NAME),
         }
     }
 
@@ -167,6 +210,39 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         }
     }
 
+    /// Refresh cached consensus telemetry.
+    ///
+    /// Should be called by the consensus thread, so API requests never wait for consensus WAL.
+    pub fn update_consensus_telemetry(
+        &self,
+        pending_conf_change: bool,
+        peers_last_sent_index: HashMap<PeerId, u64>,
+    ) -> Result<(), StorageError> {
+        let (first_wal_index, last_wal_index, wal_size_bytes) = {
+            let wal = self.wal.lock();
+            (
+                wal.first_entry()?.map(|entry| entry.index),
+                wal.last_entry()?.map(|entry| entry.index),
+                wal.size_bytes()?,
+            )
+        };
+        let persistent = self.persistent.read();
+        let telemetry = ConsensusTelemetry {
+            first_wal_index,
+            last_wal_index,
+            wal_size_bytes,
+            term: persistent.state.hard_state.term,
+            commit: persistent.state.hard_state.commit,
+            last_applied: persistent.last_applied_entry(),
+            snapshot_index: persistent.latest_snapshot_meta().index,
+            pending_conf_change,
+            peers_last_sent_index,
+        };
+        drop(persistent);
+        *self.consensus_telemetry.write() = telemetry;
+        Ok(())
+    }
+
     pub fn set_raft_soft_state(&self, state: &SoftState) {
         *self.soft_state.write() = Some(SoftState { ..*state });
     }
@@ -191,14 +267,23 @@ impl<C: CollectionContainer> ConsensusManager<C> {
     pub fn cluster_status(&self) -> ClusterStatus {
         let persistent = self.persistent.read();
         let hard_state = &persistent.state.hard_state;
+        let voters = persistent.state.conf_state.get_voters();
         let peers = persistent
             .peer_address_by_id()
             .into_iter()
             .map(|(peer_id, uri)| {
+                let role = if persistent.listener_peers.contains(&peer_id) {
+                    PeerRole::Listener
+                } else if voters.contains(&peer_id) {
+                    PeerRole::Voter
+                } else {
+                    PeerRole::Learner
+                };
                 (
                     peer_id,
                     PeerInfo {
                         uri: uri.to_string(),
+                        role,
                     },
                 )
             })
@@ -222,6 +307,7 @@ impl<C: CollectionContainer> ConsensusManager<C> {
             },
             consensus_thread_status: self.consensus_thread_status.read().clone(),
             message_send_failures: self.message_send_failures.read().clone(),
+            consensus_telemetry: self.consensus_telemetry.read().clone(),
         })
     }
 
@@ -382,19 +468,20 @@ impl<C: CollectionContainer> ConsensusManager<C> {
                 }
                 ConfChangeType::AddLearnerNode => {
                     log::debug!("Adding learner node {}", single_change.node_id);
-                    if let Ok(peer_uri) = String::from_utf8_lossy(entry.get_context())
-                        .deref()
-                        .try_into()
+                    if let Some((peer_uri, listener)) = parse_add_peer_context(entry.get_context())
                     {
-                        let peer_uri: Uri = peer_uri;
                         // Add peer to state
                         self.add_peer(single_change.node_id, peer_uri.clone())?;
+                        self.persistent
+                            .write()
+                            .set_listener_peer(single_change.node_id, listener)?;
 
                         // Notify the submitter, that operation was performed
                         {
                             let operation = ConsensusOperations::AddPeer {
                                 peer_id: single_change.node_id,
                                 uri: peer_uri.to_string(),
+                                listener,
                             };
                             let on_apply = self.on_consensus_op_apply.lock().remove(&operation);
                             if let Some(on_apply) = on_apply {
@@ -467,16 +554,119 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         &self,
         snapshot: &raft::eraftpb::Snapshot,
     ) -> Result<Result<(), StorageError>, StorageError> {
-        let meta = snapshot.get_metadata();
+        let data: SnapshotData = snapshot.get_data().try_into()?;
+
+        // Snapshot is saved first, so that its installation is resumed if the peer stops midway
+        self.save_snapshot(snapshot)?;
+        self.persistent.write().set_snapshot_install_pending()?;
+        self.install_snapshot(snapshot.get_metadata(), data)?;
+
+        Ok(Ok(()))
+    }
 
+    /// Finish installing the received snapshot, if the peer was stopped in the middle of it
+    pub fn resume_snapshot_install(&self) -> Result<(), StorageError> {
+        if !self.persistent.read().snapshot_install_pending {
+            return Ok(());
+        }
+        let Some(snapshot) = self.load_snapshot()? else {
+            return Err(StorageError::service_error(format!(
+                "Consensus snapshot {} is not found",
+                self.snapshot_path.display()
+            )));
+        };
+        log::info!(
+            "Resuming installation of consensus snapshot at index {}",
+            snapshot.get_metadata().index
+        );
         let data: SnapshotData = snapshot.get_data().try_into()?;
+        self.install_snapshot(snapshot.get_metadata(), data)
+    }
+
+    fn install_snapshot(
+        &self,
+        meta: &raft::eraftpb::SnapshotMetadata,
+        data: SnapshotData,
+    ) -> Result<(), StorageError> {
         self.toc.apply_collections_snapshot(data.collections_data)?;
         self.wal.lock().clear()?;
         self.persistent
             .write()
-            .update_from_snapshot(meta, data.address_by_id)?;
+            .update_from_snapshot(meta, data.address_by_id, data.listener_peers)
+    }
 
-        Ok(Ok(()))
+    /// Snapshot of the state machine at the last applied entry
+    fn create_snapshot(&self) -> Result<Option<raft::eraftpb::Snapshot>, StorageError> {
+        let collections_data = self.toc.collections_snapshot();
+        let persistent = self.persistent.read();
+        let Some(index) = persistent.last_applied_entry() else {
+            return Ok(None);
+        };
+        let data = SnapshotData {
+            collections_data,
+            address_by_id: persistent.peer_address_by_id(),
+            listener_peers: persistent.listener_peers.clone(),
+        };
+        let conf_state = persistent.state.conf_state.clone();
+        drop(persistent); // WAL lock is acquired before the persistent state elsewhere
+
+        Ok(Some(raft::eraftpb::Snapshot {
+            data: serde_cbor::to_vec(&data)?,
+            metadata: Some(raft::eraftpb::SnapshotMetadata {
+                conf_state: Some(conf_state),
+                index,
+                term: self.term(index)?,
+            }),
+        }))
+    }
+
+    fn save_snapshot(&self, snapshot: &raft::eraftpb::Snapshot) -> Result<(), StorageError> {
+        let bytes = prost::Message::encode_to_vec(snapshot);
+        AtomicFile::new(&self.snapshot_path, AllowOverwrite)
+            .write(|file| file.write_all(&bytes))?;
+        Ok(())
+    }
+
+    fn load_snapshot(&self) -> Result<Option<raft::eraftpb::Snapshot>, StorageError> {
+        let bytes = match fs::read(&self.snapshot_path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Some(prost::Message::decode(bytes.as_slice())?))
+    }
+
+    /// Save a snapshot of the applied state and remove the entries it covers from WAL,
+    /// once WAL has at least `min_entries` applied entries.
+    ///
+    /// Peers, which lag behind the compacted entries, e.g. new peers, receive the snapshot
+    /// instead of replaying the whole WAL. Returns `true` if WAL was compacted.
+    pub fn compact_wal(&self, min_entries: u64) -> Result<bool, StorageError> {
+        if min_entries == 0 {
+            return Ok(false);
+        }
+        let Some(last_applied) = self.persistent.read().last_applied_entry() else {
+            return Ok(false);
+        };
+        let Some(first_index) = self.wal.lock().first_entry()?.map(|entry| entry.index) else {
+            return Ok(false);
+        };
+        if last_applied < first_index + min_entries {
+            return Ok(false);
+        }
+
+        let Some(snapshot) = self.create_snapshot()? else {
+            return Ok(false);
+        };
+        self.save_snapshot(&snapshot)?;
+        self.persistent
+            .write()
+            .set_latest_snapshot_meta(snapshot.get_metadata())?;
+
+        let snapshot_index = snapshot.get_metadata().index;
+        log::debug!("Compacting consensus WAL until snapshot at index {snapshot_index}");
+        self.wal.lock().compact_until(snapshot_index + 1)?;
+        Ok(true)
     }
 
     pub fn set_hard_state(&self, hard_state: raft::eraftpb::HardState) -> Result<(), StorageError> {
@@ -491,6 +681,11 @@ impl<C: CollectionContainer> ConsensusManager<C> {
             .apply_state_update(move |state| state.conf_state = conf_state)
     }
 
+    /// Save the consensus state, before the node shuts down
+    pub fn persist(&self) -> Result<(), StorageError> {
+        self.persistent.read().save()
+    }
+
     /// Check if the consensus have empty operations log
     pub fn is_new_deployment(&self) -> bool {
         self.hard_state().term == 0
@@ -520,7 +715,9 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         // plus we need to make additional removing in the `channel_pool`.
         // So we handle `remove_peer` inside the `toc` and persist changes in the `persistent` after that.
         self.toc.remove_peer(peer_id)?;
-        self.persistent.read().save()
+        let mut persistent = self.persistent.write();
+        persistent.listener_peers.remove(&peer_id);
+        persistent.save()
     }
 
     async fn await_receiver(
@@ -648,6 +845,11 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         self.persistent.read().peer_address_by_id()
     }
 
+    /// Peers, which joined as listeners and are never promoted to voters
+    pub fn listener_peers(&self) -> HashSet<PeerId> {
+        self.persistent.read().listener_peers.clone()
+    }
+
     pub fn peer_count(&self) -> usize {
         self.persistent.read().peer_address_by_id.read().len()
     }
@@ -660,8 +862,33 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         self.persistent.read().last_applied_entry()
     }
 
+    /// Number of committed consensus entries, which are not applied on this peer yet
+    pub fn commit_lag(&self) -> u64 {
+        let persistent = self.persistent.read();
+        let commit = persistent.state().hard_state.commit;
+        let applied = persistent.last_applied_entry().unwrap_or(0);
+        commit.saturating_sub(applied)
+    }
+
     pub fn sync_local_state(&self) -> Result<(), StorageError> {
-        self.toc.sync_local_state()
+        self.toc.sync_local_state()?;
+        if self.is_leader() {
+            let peers: Vec<_> = self
+                .persistent
+                .read()
+                .peer_address_by_id()
+                .into_keys()
+                .collect();
+            self.toc.restore_replication_factor(&peers)?;
+        }
+        Ok(())
+    }
+
+    fn is_leader(&self) -> bool {
+        self.soft_state
+            .read()
+            .as_ref()
+            .map_or(false, |state| state.raft_state == StateRole::Leader)
     }
 }
 
@@ -722,26 +949,21 @@ impl<C: CollectionContainer> Storage for ConsensusManager<C> {
     }
 
     fn snapshot(&self, request_index: u64, _to: u64) -> raft::Result<raft::eraftpb::Snapshot> {
-        let collections_data = self.toc.collections_snapshot();
-        let persistent = self.persistent.read();
-        let raft_state = persistent.state().clone();
-        if raft_state.hard_state.commit >= request_index {
-            let snapshot = SnapshotData {
-                collections_data,
-                address_by_id: persistent.peer_address_by_id(),
-            };
-            Ok(raft::eraftpb::Snapshot {
-                data: serde_cbor::to_vec(&snapshot).map_err(raft_error_other)?,
-                metadata: Some(raft::eraftpb::SnapshotMetadata {
-                    conf_state: Some(raft_state.conf_state),
-                    index: raft_state.hard_state.commit,
-                    term: raft_state.hard_state.term,
-                }),
-            })
-        } else {
-            Err(raft::Error::Store(
+        // Saved snapshot is served as is, if it is recent enough
+        if let Some(snapshot) = self.load_snapshot().map_err(raft_error_other)? {
+            if snapshot.get_metadata().index >= request_index {
+                return Ok(snapshot);
+            }
+        }
+
+        match self.create_snapshot().map_err(raft_error_other)? {
+            Some(snapshot) if snapshot.get_metadata().index >= request_index => {
+                self.save_snapshot(&snapshot).map_err(raft_error_other)?;
+                Ok(snapshot)
+            }
+            _ => Err(raft::Error::Store(
                 raft::StorageError::SnapshotTemporarilyUnavailable,
-            ))
+            )),
         }
     }
 }
@@ -813,13 +1035,31 @@ mod tests {
     use raft::storage::{MemStorage, Storage};
     use tempfile::Builder;
 
-    use super::ConsensusManager;
+    use super::{add_peer_context, parse_add_peer_context, ConsensusManager};
     use crate::content_manager::consensus::consensus_wal::ConsensusOpWal;
     use crate::content_manager::consensus::entry_queue::EntryApplyProgressQueue;
     use crate::content_manager::consensus::operation_sender::OperationSender;
     use crate::content_manager::consensus::persistent::Persistent;
     use crate::content_manager::CollectionContainer;
 
+    #[test]
+    fn add_peer_context_keeps_plain_uri() {
+        let uri = "http://127.0.0.1:6335/".to_string();
+
+        let context = add_peer_context(uri.clone(), false);
+        assert_eq!(context, uri.as_bytes());
+        let (parsed, listener) = parse_add_peer_context(&context).unwrap();
+        assert_eq!(parsed.to_string(), uri);
+        assert!(!listener);
+
+        let context = add_peer_context(uri.clone(), true);
+        let (parsed, listener) = parse_add_peer_context(&context).unwrap();
+        assert_eq!(parsed.to_string(), uri);
+        assert!(listener);
+
+        assert!(parse_add_peer_context(b"").is_none());
+    }
+
     #[test]
     fn update_is_applied() {
         let dir = Builder::new().prefix("raft_state_test").tempdir().unwrap();
@@ -943,6 +1183,13 @@ mod tests {
         fn sync_local_state(&self) -> Result<(), crate::content_manager::errors::StorageError> {
             Ok(())
         }
+
+        fn restore_replication_factor(
+            &self,
+            _peers: &[PeerId],
+        ) -> Result<(), crate::content_manager::errors::StorageError> {
+            Ok(())
+        }
     }
 
     fn setup_storages(
@@ -963,6 +1210,85 @@ mod tests {
         (consensus_state, mem_storage)
     }
 
+    #[test]
+    fn compacted_wal_is_replaced_by_snapshot() {
+        let dir = Builder::new().prefix("raft_state_test").tempdir().unwrap();
+        let entries = (1..=10)
+            .map(|index| Entry {
+                index,
+                term: 1,
+                ..Default::default()
+            })
+            .collect();
+        let (leader, _) = setup_storages(entries, dir.path());
+        {
+            let mut persistent = leader.persistent.write();
+            persistent.set_unapplied_entries(1, 10).unwrap();
+            for _ in 0..7 {
+                persistent.entry_applied().unwrap();
+            }
+        }
+
+        assert!(!leader.compact_wal(0).unwrap());
+        assert!(!leader.compact_wal(100).unwrap());
+        assert!(leader.compact_wal(5).unwrap());
+        assert_eq!(leader.persistent.read().latest_snapshot_meta().index, 7);
+        assert!(leader.first_index().unwrap() <= 8);
+        assert_eq!(leader.last_index().unwrap(), 10);
+        assert_eq!(leader.term(7).unwrap(), 1);
+
+        // Snapshot can't cover entries, which are not applied yet
+        assert!(leader.snapshot(8, 0).is_err());
+        let snapshot = leader.snapshot(5, 0).unwrap();
+        assert_eq!(snapshot.get_metadata().index, 7);
+        assert_eq!(snapshot.get_metadata().term, 1);
+
+        let follower_dir = Builder::new().prefix("raft_state_test").tempdir().unwrap();
+        let (follower, _) = setup_storages(vec![], follower_dir.path());
+        follower.apply_snapshot(&snapshot).unwrap().unwrap();
+        assert_eq!(follower.first_index().unwrap(), 8);
+        assert_eq!(follower.last_index().unwrap(), 7);
+        assert_eq!(follower.term(7).unwrap(), 1);
+        assert_eq!(follower.last_applied_entry(), Some(7));
+        assert!(!follower.persistent.read().snapshot_install_pending);
+    }
+
+    #[test]
+    fn interrupted_snapshot_install_is_resumed() {
+        let dir = Builder::new().prefix("raft_state_test").tempdir().unwrap();
+        let snapshot = raft::eraftpb::Snapshot {
+            data: serde_cbor::to_vec(&super::SnapshotData {
+                collections_data: Default::default(),
+                address_by_id: Default::default(),
+                listener_peers: Default::default(),
+            })
+            .unwrap(),
+            metadata: Some(raft::eraftpb::SnapshotMetadata {
+                conf_state: Some(Default::default()),
+                index: 42,
+                term: 3,
+            }),
+        };
+
+        // Peer is stopped after the snapshot is received, but before it is installed
+        let (consensus_state, _) = setup_storages(vec![], dir.path());
+        consensus_state.save_snapshot(&snapshot).unwrap();
+        consensus_state
+            .persistent
+            .write()
+            .set_snapshot_install_pending()
+            .unwrap();
+        drop(consensus_state);
+
+        let (consensus_state, _) = setup_storages(vec![], dir.path());
+        assert_eq!(consensus_state.last_applied_entry(), None);
+        consensus_state.resume_snapshot_install().unwrap();
+        assert_eq!(consensus_state.last_applied_entry(), Some(42));
+        assert_eq!(consensus_state.first_index().unwrap(), 43);
+        assert_eq!(consensus_state.term(42).unwrap(), 3);
+        assert!(!consensus_state.persistent.read().snapshot_install_pending);
+    }
+
     prop_compose! {
         fn gen_entries(min_entries: u64, max_entries: u64)(n in min_entries..max_entries, inc_term_every in 1u64..max_entries) -> Vec<Entry> {
             (1..=n).map(|index| Entry {index, term: 1 + index/inc_term_every, ..Default::default()}).collect::<Vec<Entry>>()
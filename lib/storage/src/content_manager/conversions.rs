@@ -1,3 +1,6 @@
+use collection::operations::conversions::{
+    sharding_method_from_proto, sparse_vectors_config_from_proto,
+};
 use tonic::Status;
 
 use crate::content_manager::collection_meta_ops::{
@@ -16,8 +19,18 @@ pub fn error_to_status(error: StorageError) -> tonic::Status {
         StorageError::BadRequest { .. } => tonic::Code::InvalidArgument,
         StorageError::Locked { .. } => tonic::Code::FailedPrecondition,
         StorageError::Timeout { .. } => tonic::Code::DeadlineExceeded,
+        StorageError::Forbidden { .. } => tonic::Code::PermissionDenied,
+        StorageError::Conflict { .. } => tonic::Code::Aborted,
+        StorageError::BrokenCollection { .. } => tonic::Code::Unavailable,
+        StorageError::RateLimited { .. } => tonic::Code::ResourceExhausted,
     };
-    tonic::Status::new(error_code, format!("{error}"))
+    let mut status = tonic::Status::new(error_code, format!("{error}"));
+    if let Some(retry_after_secs) = error.retry_after_secs() {
+        status
+            .metadata_mut()
+            .insert("retry-after", retry_after_secs.into());
+    }
+    status
 }
 
 impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
@@ -31,11 +44,21 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                     Some(vector_config) => vector_config.try_into()?,
                     None => return Err(Status::invalid_argument("vectors config is required")),
                 },
+                sparse_vectors: value
+                    .sparse_vectors_config
+                    .map(sparse_vectors_config_from_proto),
                 hnsw_config: value.hnsw_config.map(|v| v.into()),
                 wal_config: value.wal_config.map(|v| v.into()),
                 optimizers_config: value.optimizers_config.map(|v| v.into()),
                 shard_number: value.shard_number,
+                sharding_method: value
+                    .sharding_method
+                    .map(sharding_method_from_proto)
+                    .transpose()?,
                 on_disk_payload: value.on_disk_payload,
+                tenant_key: value.tenant_key,
+                store_original_vectors: value.store_original_vectors,
+                search_timeout_sec: value.search_timeout_sec,
                 replication_factor: value.replication_factor,
                 write_consistency_factor: value.write_consistency_factor,
                 init_from: value
@@ -45,6 +68,7 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                     .quantization_config
                     .map(TryInto::try_into)
                     .transpose()?,
+                strict_mode_config: value.strict_mode_config.map(Into::into),
             },
         )))
     }
@@ -69,6 +93,7 @@ impl TryFrom<api::grpc::qdrant::UpdateCollection> for CollectionMetaOperations {
                     .quantization_config
                     .map(TryInto::try_into)
                     .transpose()?,
+                strict_mode_config: value.strict_mode_config.map(Into::into),
             },
         )))
     }
@@ -83,6 +83,8 @@ async fn replicate_shard_data(
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(true),
+            order_by: None,
+            page_token: None,
         };
 
         let collections_read = collections.read().await;
@@ -1,5 +1,6 @@
 use std::backtrace::Backtrace;
 use std::io::Error as IoError;
+use std::time::Duration;
 
 use collection::operations::types::CollectionError;
 use segment::common::file_operations::FileStorageError;
@@ -24,6 +25,17 @@ pub enum StorageError {
     Locked { description: String },
     #[error("Timeout: {description}")]
     Timeout { description: String },
+    #[error("Forbidden: {description}")]
+    Forbidden { description: String },
+    #[error("Version conflict: {description}")]
+    Conflict { description: String },
+    #[error("Collection is broken: {description}")]
+    BrokenCollection { description: String },
+    #[error("Too many requests: {description}")]
+    RateLimited {
+        description: String,
+        retry_after: Duration,
+    },
 }
 
 impl StorageError {
@@ -46,6 +58,16 @@ impl StorageError {
         }
     }
 
+    /// Seconds to wait before retrying a rejected request, rounded up
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            StorageError::RateLimited { retry_after, .. } => {
+                Some(retry_after.as_secs_f64().ceil().max(1.0) as u64)
+            }
+            _ => None,
+        }
+    }
+
     /// Used to override the `description` field of the resulting `StorageError`
     pub fn from_inconsistent_shard_failure(
         err: CollectionError,
@@ -91,6 +113,16 @@ impl StorageError {
             CollectionError::Timeout { .. } => StorageError::Timeout {
                 description: overriding_description,
             },
+            CollectionError::StrictMode { .. } => StorageError::Forbidden {
+                description: overriding_description,
+            },
+            CollectionError::Conflict { .. } => StorageError::Conflict {
+                description: overriding_description,
+            },
+            CollectionError::RateLimited { retry_after, .. } => StorageError::RateLimited {
+                description: overriding_description,
+                retry_after,
+            },
         }
     }
 }
@@ -132,6 +164,14 @@ impl From<CollectionError> for StorageError {
             CollectionError::Timeout { .. } => StorageError::Timeout {
                 description: format!("{err}"),
             },
+            CollectionError::StrictMode { .. } => StorageError::Forbidden {
+                description: format!("{err}"),
+            },
+            CollectionError::Conflict { description } => StorageError::Conflict { description },
+            CollectionError::RateLimited { retry_after, .. } => StorageError::RateLimited {
+                description: format!("{err}"),
+                retry_after,
+            },
         }
     }
 }
@@ -12,6 +12,7 @@ pub mod consensus_manager;
 pub mod conversions;
 mod data_transfer;
 pub mod errors;
+pub mod peer_drain;
 pub mod shard_distribution;
 pub mod snapshots;
 pub mod toc;
@@ -19,6 +20,7 @@ pub mod toc;
 pub mod consensus_ops {
     use collection::shards::replica_set::ReplicaState;
     use collection::shards::replica_set::ReplicaState::Initializing;
+    use collection::shards::resharding::ShardSplit;
     use collection::shards::shard::PeerId;
     use collection::shards::transfer::shard_transfer::ShardTransfer;
     use collection::shards::{replica_set, CollectionId};
@@ -26,8 +28,8 @@ pub mod consensus_ops {
     use serde::{Deserialize, Serialize};
 
     use crate::content_manager::collection_meta_ops::{
-        CollectionMetaOperations, SetShardReplicaState, ShardTransferOperations, UpdateCollection,
-        UpdateCollectionOperation,
+        CollectionMetaOperations, ReshardingOperation, SetShardReplicaState,
+        ShardTransferOperations, UpdateCollection, UpdateCollectionOperation,
     };
 
     /// Operation that should pass consensus
@@ -37,6 +39,9 @@ pub mod consensus_ops {
         AddPeer {
             peer_id: PeerId,
             uri: String,
+            /// Listener peers stay learners and are never promoted to voters
+            #[serde(default)]
+            listener: bool,
         },
         RemovePeer(PeerId),
         RequestSnapshot,
@@ -108,6 +113,7 @@ pub mod consensus_ops {
                     params: None,
                     hnsw_config: None,
                     quantization_config: None,
+                    strict_mode_config: None,
                 },
             );
             operation
@@ -148,6 +154,27 @@ pub mod consensus_ops {
             )))
         }
 
+        pub fn finish_resharding(collection_id: CollectionId, split: ShardSplit) -> Self {
+            ConsensusOperations::CollectionMeta(Box::new(CollectionMetaOperations::Resharding(
+                collection_id,
+                ReshardingOperation::Finish(split),
+            )))
+        }
+
+        pub fn abort_resharding(
+            collection_id: CollectionId,
+            split: ShardSplit,
+            reason: &str,
+        ) -> Self {
+            ConsensusOperations::CollectionMeta(Box::new(CollectionMetaOperations::Resharding(
+                collection_id,
+                ReshardingOperation::Abort {
+                    split,
+                    reason: reason.to_string(),
+                },
+            )))
+        }
+
         pub fn request_snapshot() -> Self {
             Self::RequestSnapshot
         }
@@ -200,4 +227,7 @@ pub trait CollectionContainer {
     fn remove_peer(&self, peer_id: PeerId) -> Result<(), StorageError>;
 
     fn sync_local_state(&self) -> Result<(), StorageError>;
+
+    /// Propose replacement of dead replicas, called on the consensus leader only
+    fn restore_replication_factor(&self, peers: &[PeerId]) -> Result<(), StorageError>;
 }
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use collection::collection_state::State;
+use collection::shards::replica_set::ReplicaState;
+use collection::shards::shard::{PeerId, ShardId};
+use collection::shards::transfer::shard_transfer::ShardTransfer;
+use collection::shards::CollectionId;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::consensus_manager::ConsensusStateRef;
+use super::consensus_ops::ConsensusOperations;
+use super::errors::StorageError;
+use super::toc::TableOfContent;
+
+/// Interval between the checks of the drain progress
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Max number of concurrent transfers of the replicas of the drained peer
+const MAX_DRAIN_TRANSFERS: usize = 1;
+
+/// Max number of attempts to transfer a single replica, before the drain fails
+const MAX_TRANSFER_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerDrainStatus {
+    /// Replicas are being moved off the peer
+    InProgress,
+    /// All replicas are moved and the peer is removed from the cluster
+    Finished,
+    /// Drain is stopped, the peer stays in the cluster
+    Failed,
+}
+
+/// Progress of moving all replicas off the peer, before it is removed from the cluster
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct PeerDrainInfo {
+    /// ID of the drained peer
+    pub peer_id: PeerId,
+    pub status: PeerDrainStatus,
+    /// Number of replicas on the peer, when the drain was started
+    pub total_replicas: usize,
+    /// Number of replicas, which are still on the peer
+    pub remaining_replicas: usize,
+    /// Reason of the failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Drains, started on this peer. Progress is only known on the peer, which drives the drain.
+#[derive(Debug, Clone, Default)]
+pub struct PeerDrains(Arc<Mutex<HashMap<PeerId, PeerDrainInfo>>>);
+
+impl PeerDrains {
+    pub fn get(&self, peer_id: PeerId) -> Option<PeerDrainInfo> {
+        self.0.lock().get(&peer_id).cloned()
+    }
+
+    /// Register a new drain of the peer, unless it is drained already
+    pub fn start(&self, peer_id: PeerId) -> Result<(), StorageError> {
+        let mut drains = self.0.lock();
+        if drains
+            .get(&peer_id)
+            .map_or(false, |info| info.status == PeerDrainStatus::InProgress)
+        {
+            return Err(StorageError::bad_request(&format!(
+                "Peer {peer_id} is being drained already"
+            )));
+        }
+        drains.insert(
+            peer_id,
+            PeerDrainInfo {
+                peer_id,
+                status: PeerDrainStatus::InProgress,
+                total_replicas: 0,
+                remaining_replicas: 0,
+                error: None,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn set_remaining_replicas(&self, peer_id: PeerId, remaining_replicas: usize) {
+        if let Some(info) = self.0.lock().get_mut(&peer_id) {
+            info.total_replicas = info.total_replicas.max(remaining_replicas);
+            info.remaining_replicas = remaining_replicas;
+        }
+    }
+
+    pub fn finish(&self, peer_id: PeerId, result: Result<(), StorageError>) {
+        if let Some(info) = self.0.lock().get_mut(&peer_id) {
+            match result {
+                Ok(()) => info.status = PeerDrainStatus::Finished,
+                Err(err) => {
+                    info.status = PeerDrainStatus::Failed;
+                    info.error = Some(err.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Consensus operation, which moves a replica off the drained peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrainAction {
+    Transfer {
+        collection_name: CollectionId,
+        transfer: ShardTransfer,
+    },
+    RemoveReplica {
+        collection_name: CollectionId,
+        shard_id: ShardId,
+        peer_id: PeerId,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct DrainStep {
+    /// Number of replicas, which are still on the drained peer
+    pub remaining_replicas: usize,
+    pub actions: Vec<DrainAction>,
+}
+
+/// Plan the next operations, which move replicas off the drained peer.
+///
+/// Active replicas are moved to the peers with the least replicas, which do not hold the shard.
+/// Dead replicas are restored from an active replica on another peer first, unless the shard has
+/// enough active replicas already, and are removed after that. Shards, which are being
+/// transferred, are left until the transfer is finished.
+pub fn plan_drain_step(
+    drained_peer_id: PeerId,
+    peers: &[PeerId],
+    collections: &[(CollectionId, State)],
+) -> Result<DrainStep, StorageError> {
+    let mut step = DrainStep::default();
+
+    let mut replicas_per_peer: HashMap<PeerId, usize> = HashMap::new();
+    let mut transfers = 0;
+    for (_, state) in collections {
+        for shard_info in state.shards.values() {
+            for peer_id in shard_info.replicas.keys() {
+                *replicas_per_peer.entry(*peer_id).or_default() += 1;
+            }
+        }
+        transfers += state
+            .transfers
+            .iter()
+            .filter(|transfer| {
+                transfer.from == drained_peer_id
+                    || state
+                        .shards
+                        .get(&transfer.shard_id)
+                        .map_or(false, |shard_info| {
+                            shard_info.replicas.contains_key(&drained_peer_id)
+                        })
+            })
+            .count();
+    }
+
+    for (collection_name, state) in collections {
+        let replication_factor = state.config.params.replication_factor.get() as usize;
+        let mut shard_ids: Vec<_> = state.shards.keys().copied().collect();
+        shard_ids.sort_unstable();
+
+        for shard_id in shard_ids {
+            let replicas = &state.shards[&shard_id].replicas;
+            let Some(replica_state) = replicas.get(&drained_peer_id) else {
+                continue;
+            };
+            step.remaining_replicas += 1;
+
+            if state
+                .transfers
+                .iter()
+                .any(|transfer| transfer.shard_id == shard_id)
+            {
+                continue;
+            }
+
+            let mut active_peers: Vec<_> = replicas
+                .iter()
+                .filter(|(peer_id, state)| {
+                    **peer_id != drained_peer_id && **state == ReplicaState::Active
+                })
+                .map(|(peer_id, _)| *peer_id)
+                .collect();
+            active_peers.sort_unstable();
+
+            let target = peers
+                .iter()
+                .copied()
+                .filter(|peer_id| *peer_id != drained_peer_id && !replicas.contains_key(peer_id))
+                .min_by_key(|peer_id| {
+                    (
+                        replicas_per_peer.get(peer_id).copied().unwrap_or(0),
+                        *peer_id,
+                    )
+                });
+
+            let remove_replica = DrainAction::RemoveReplica {
+                collection_name: collection_name.clone(),
+                shard_id,
+                peer_id: drained_peer_id,
+            };
+
+            let transfer = match replica_state {
+                ReplicaState::Active | ReplicaState::Listener => {
+                    let Some(to) = target else {
+                        if active_peers.len() >= replication_factor {
+                            step.actions.push(remove_replica);
+                            continue;
+                        }
+                        return Err(StorageError::bad_request(&format!(
+                            "Cannot drain peer {drained_peer_id}: there is no peer to move the replica \
+                             of shard {shard_id} of collection {collection_name} to, \
+                             all other peers hold this shard already"
+                        )));
+                    };
+                    ShardTransfer {
+                        shard_id,
+                        from: drained_peer_id,
+                        to,
+                        sync: false,
+                        method: None,
+                    }
+                }
+                ReplicaState::Dead => {
+                    let Some(to) = target.filter(|_| active_peers.len() < replication_factor)
+                    else {
+                        step.actions.push(remove_replica);
+                        continue;
+                    };
+                    let Some(from) = active_peers.first().copied() else {
+                        return Err(StorageError::bad_request(&format!(
+                            "Cannot drain peer {drained_peer_id}: shard {shard_id} \
+                             of collection {collection_name} has no active replica to restore \
+                             the dead replica from"
+                        )));
+                    };
+                    ShardTransfer {
+                        shard_id,
+                        from,
+                        to,
+                        sync: true,
+                        method: None,
+                    }
+                }
+                // Replica is being activated
+                ReplicaState::Partial | ReplicaState::Initializing => continue,
+            };
+
+            if transfers >= MAX_DRAIN_TRANSFERS {
+                continue;
+            }
+            transfers += 1;
+            *replicas_per_peer.entry(transfer.to).or_default() += 1;
+            step.actions.push(DrainAction::Transfer {
+                collection_name: collection_name.clone(),
+                transfer,
+            });
+        }
+    }
+
+    Ok(step)
+}
+
+/// Move all replicas off the peer, then remove the peer from the cluster
+pub async fn drain_peer(
+    toc: &TableOfContent,
+    consensus_state: &ConsensusStateRef,
+    drains: &PeerDrains,
+    drained_peer_id: PeerId,
+    wait_timeout: Option<Duration>,
+) -> Result<(), StorageError> {
+    let mut attempts: HashMap<(CollectionId, ShardId), usize> = HashMap::new();
+
+    loop {
+        let mut collections = Vec::new();
+        for collection_name in toc.all_collections().await {
+            // Collection might be deleted concurrently
+            if let Ok(collection) = toc.get_collection(&collection_name).await {
+                collections.push((collection_name, collection.state().await));
+            }
+        }
+        collections.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let peers: Vec<_> = consensus_state.peer_address_by_id().into_keys().collect();
+
+        let step = plan_drain_step(drained_peer_id, &peers, &collections)?;
+        drains.set_remaining_replicas(drained_peer_id, step.remaining_replicas);
+        if step.remaining_replicas == 0 {
+            break;
+        }
+
+        for action in step.actions {
+            let operation = match action {
+                DrainAction::Transfer {
+                    collection_name,
+                    transfer,
+                } => {
+                    let attempt = attempts
+                        .entry((collection_name.clone(), transfer.shard_id))
+                        .or_default();
+                    *attempt += 1;
+                    if *attempt > MAX_TRANSFER_ATTEMPTS {
+                        return Err(StorageError::service_error(format!(
+                            "Failed to move replica of shard {} of collection {collection_name} \
+                             off peer {drained_peer_id} in {MAX_TRANSFER_ATTEMPTS} attempts",
+                            transfer.shard_id,
+                        )));
+                    }
+                    log::info!(
+                        "Draining peer {drained_peer_id}: transferring shard {} \
+                         of collection {collection_name} from peer {} to peer {}",
+                        transfer.shard_id,
+                        transfer.from,
+                        transfer.to,
+                    );
+                    ConsensusOperations::start_transfer(collection_name, transfer)
+                }
+                DrainAction::RemoveReplica {
+                    collection_name,
+                    shard_id,
+                    peer_id,
+                } => {
+                    log::info!(
+                        "Draining peer {drained_peer_id}: removing replica of shard {shard_id} \
+                         of collection {collection_name}",
+                    );
+                    ConsensusOperations::remove_replica(collection_name, shard_id, peer_id)
+                }
+            };
+            consensus_state
+                .propose_consensus_op_with_await(operation, wait_timeout)
+                .await?;
+        }
+
+        tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+    }
+
+    log::info!("Peer {drained_peer_id} is drained, removing it from the cluster");
+    consensus_state
+        .propose_consensus_op_with_await(
+            ConsensusOperations::RemovePeer(drained_peer_id),
+            wait_timeout,
+        )
+        .await?;
+    Ok(())
+}
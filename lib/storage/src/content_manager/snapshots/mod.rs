@@ -5,7 +5,8 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use collection::operations::snapshot_ops::{
-    get_snapshot_description, list_snapshots_in_directory, SnapshotDescription,
+    get_snapshot_description, list_snapshots_in_directory, remove_snapshot_file,
+    save_snapshot_checksum, snapshot_checksum_path, SnapshotDescription,
 };
 use serde::{Deserialize, Serialize};
 use tar::Builder as TarBuilder;
@@ -60,7 +61,7 @@ async fn _do_delete_full_snapshot(
 ) -> Result<bool, StorageError> {
     let snapshot_dir = get_full_snapshot_path(dispatcher.toc(), snapshot_name).await?;
     log::info!("Deleting full storage snapshot {:?}", snapshot_dir);
-    tokio::fs::remove_file(snapshot_dir).await?;
+    remove_snapshot_file(&snapshot_dir).await?;
     Ok(true)
 }
 
@@ -93,7 +94,7 @@ async fn _do_delete_collection_snapshot(
     let collection = dispatcher.get_collection(collection_name).await?;
     let file_name = collection.get_snapshot_path(snapshot_name).await?;
     log::info!("Deleting collection snapshot {:?}", file_name);
-    tokio::fs::remove_file(file_name).await?;
+    remove_snapshot_file(&file_name).await?;
     Ok(true)
 }
 
@@ -182,7 +183,11 @@ async fn _do_create_full_snapshot(
                 .join(collection_name)
                 .join(&snapshot_details.name);
             builder.append_path_with_name(&snapshot_path, &snapshot_details.name)?;
-            std::fs::remove_file(snapshot_path)?;
+            std::fs::remove_file(&snapshot_path)?;
+            let checksum_path = snapshot_checksum_path(&snapshot_path);
+            if checksum_path.exists() {
+                std::fs::remove_file(checksum_path)?;
+            }
         }
         builder.append_path_with_name(&config_path_clone, "config.json")?;
 
@@ -191,6 +196,7 @@ async fn _do_create_full_snapshot(
     });
     archiving.await??;
     tokio::fs::remove_file(&config_path).await?;
+    save_snapshot_checksum(&full_snapshot_path).await?;
 
     Ok(get_snapshot_description(&full_snapshot_path).await?)
 }
@@ -1,13 +1,16 @@
 use collection::collection::Collection;
 use collection::config::CollectionConfig;
-use collection::operations::snapshot_ops::{SnapshotPriority, SnapshotRecover};
+use collection::operations::snapshot_ops::{
+    load_snapshot_aliases, SnapshotPriority, SnapshotRecover,
+};
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::shard_config::ShardType;
 use collection::shards::shard_versioning::latest_shard_paths;
 
 use crate::content_manager::collection_meta_ops::{
-    CollectionMetaOperations, CreateCollectionOperation,
+    AliasOperations, ChangeAliasesOperation, CollectionMetaOperations, CreateAlias,
+    CreateCollectionOperation,
 };
 use crate::content_manager::snapshots::download::{download_snapshot, downloaded_snapshots_dir};
 use crate::dispatcher::Dispatcher;
@@ -148,6 +151,13 @@ async fn _do_recover_from_snapshot(
             state.config.params.vectors, snapshot_config.params.vectors
         )));
     }
+    // Check sparse vectors config
+    if snapshot_config.params.sparse_vectors != state.config.params.sparse_vectors {
+        return Err(StorageError::bad_input(&format!(
+            "Snapshot is not compatible with existing collection: Collection sparse vectors: {:?} Snapshot sparse vectors: {:?}",
+            state.config.params.sparse_vectors, snapshot_config.params.sparse_vectors
+        )));
+    }
     // Check shard number
     if snapshot_config.params.shard_number != state.config.params.shard_number {
         return Err(StorageError::bad_input(&format!(
@@ -281,6 +291,33 @@ async fn _do_recover_from_snapshot(
         }
     }
 
+    // Restore aliases of the snapshot, unless the names are taken already,
+    // e.g. when the snapshot is recovered under a new name next to the original collection
+    let mut alias_actions: Vec<AliasOperations> = Vec::new();
+    for alias_name in load_snapshot_aliases(&tmp_collection_dir)? {
+        if toc.get_collection(&alias_name).await.is_ok() {
+            log::debug!("Alias {alias_name} of the snapshot is taken already, skipping");
+            continue;
+        }
+        alias_actions.push(
+            CreateAlias {
+                collection_name: collection_name.to_string(),
+                alias_name,
+            }
+            .into(),
+        );
+    }
+    if !alias_actions.is_empty() {
+        dispatcher
+            .submit_collection_meta_op(
+                CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation {
+                    actions: alias_actions,
+                }),
+                None,
+            )
+            .await?;
+    }
+
     // Remove tmp collection dir
     tokio::fs::remove_dir_all(&tmp_collection_dir).await?;
 
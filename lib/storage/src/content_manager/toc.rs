@@ -1,48 +1,64 @@
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
 use std::fs::{create_dir_all, read_dir};
-use std::num::NonZeroU32;
+use std::num::{NonZeroU32, NonZeroU64};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use collection::collection::{Collection, RequestShardTransfer};
+use collection::collection::{
+    Collection, OnReshardingFailure, OnReshardingSuccess, RequestShardTransfer,
+};
 use collection::collection_state;
 use collection::config::{
     default_replication_factor, default_write_consistency_factor, CollectionConfig,
-    CollectionParams,
+    CollectionParams, ShardingMethod,
 };
+use collection::discovery::discover;
 use collection::grouping::group_by::GroupRequest;
 use collection::grouping::GroupBy;
+use collection::operations::cluster_ops::{CreateShardingKey, StartResharding};
 use collection::operations::config_diff::DiffConfig;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::point_ops::WriteOrdering;
+use collection::operations::shard_key_selector::ShardSelectorInternal;
 use collection::operations::snapshot_ops::SnapshotDescription;
 use collection::operations::types::{
-    AliasDescription, CollectionResult, CountRequest, CountResult, GroupsResult, PointRequest,
-    RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult, SearchRequest,
-    SearchRequestBatch, UpdateResult, VectorsConfig,
+    AliasDescription, CollectionResult, CoreSearchRequestBatch, CountRequest, CountResult,
+    DiscoverRequest, FacetRequest, FacetResponse, GroupsResult, PointRequest, QueryRequest,
+    RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult,
+    SearchPlanRequest, SearchPlanResponse, SearchRequest, SearchRequestBatch, UpdateResult,
+    VectorsConfig,
+};
+use collection::operations::{
+    ClientOperationId, CollectionUpdateOperations, OperationWithClientId,
 };
-use collection::operations::CollectionUpdateOperations;
 use collection::recommendations::{recommend_batch_by, recommend_by};
 use collection::shards::channel_service::ChannelService;
 use collection::shards::collection_shard_distribution::CollectionShardDistribution;
+use collection::shards::replica_failover::{ClusterLoad, FAILOVER_CHECK_INTERVAL};
 use collection::shards::replica_set::ReplicaState;
-use collection::shards::shard::{PeerId, ShardId};
+use collection::shards::resharding::{ReshardingState, ShardSplit};
+use collection::shards::shard::{PeerId, ShardId, ShardsPlacement};
 use collection::shards::transfer::shard_transfer::{
     validate_transfer, validate_transfer_exists, ShardTransfer,
 };
 use collection::shards::{replica_set, CollectionId};
 use collection::telemetry::CollectionTelemetry;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use itertools::Itertools;
 use segment::common::cpu::get_num_cpus;
+use segment::common::snapshot_manifest::FileManifest;
 use segment::types::ScoredPoint;
 use tokio::runtime::Runtime;
 use tokio::sync::{Mutex, RwLock, RwLockReadGuard, Semaphore};
 use uuid::Uuid;
 
 use super::collection_meta_ops::{
-    CreateCollectionOperation, SetShardReplicaState, ShardTransferOperations,
-    UpdateCollectionOperation,
+    CreateCollectionOperation, CreateShardKey, DropShardKey, ReshardingOperation,
+    SetShardReplicaState, ShardTransferOperations, UpdateCollectionOperation,
 };
 use super::{consensus_manager, CollectionContainer};
 use crate::content_manager::alias_mapping::AliasPersistence;
@@ -90,6 +106,11 @@ pub struct TableOfContent {
     /// A lock to prevent concurrent collection creation.
     /// Effectively, this lock ensures that `create_collection` is called sequentially.
     collection_create_lock: Mutex<()>,
+    /// Time of the last check for dead replicas to replace, if this peer is the consensus leader
+    last_replica_failover_check: parking_lot::Mutex<Option<Instant>>,
+    /// Collections, which failed to load, with the load errors.
+    /// Only present if `handle_collection_load_errors` is enabled, until recovered or dropped.
+    broken_collections: parking_lot::RwLock<HashMap<CollectionId, String>>,
 }
 
 impl TableOfContent {
@@ -114,6 +135,7 @@ impl TableOfContent {
         let collection_paths =
             read_dir(&collections_path).expect("Can't read Collections directory");
         let mut collections: HashMap<String, Collection> = Default::default();
+        let mut broken_collections: HashMap<String, String> = Default::default();
         for entry in collection_paths {
             let collection_path = entry
                 .expect("Can't access of one of the collection files")
@@ -139,28 +161,33 @@ impl TableOfContent {
                 panic!("Can't create a directory for snapshot of {collection_name}: {e}")
             });
             log::info!("Loading collection: {}", collection_name);
-            let collection = general_runtime.block_on(Collection::load(
-                collection_name.clone(),
-                this_peer_id,
+            let collection = general_runtime.block_on(Self::load_collection(
+                &collection_name,
                 &collection_path,
                 &collection_snapshots_path,
-                storage_config.to_shared_storage_config().into(),
-                channel_service.clone(),
-                Self::change_peer_state_callback(
-                    consensus_proposal_sender.clone(),
-                    collection_name.clone(),
-                    ReplicaState::Dead,
-                    None,
-                ),
-                Self::request_shard_transfer_callback(
-                    consensus_proposal_sender.clone(),
-                    collection_name.clone(),
-                ),
-                Some(search_runtime.handle().clone()),
-                Some(update_runtime.handle().clone()),
+                storage_config,
+                &channel_service,
+                this_peer_id,
+                &consensus_proposal_sender,
+                &search_runtime,
+                &update_runtime,
             ));
 
-            collections.insert(collection_name, collection);
+            match collection {
+                Ok(collection) => {
+                    collections.insert(collection_name, collection);
+                }
+                Err(err) => {
+                    if !storage_config.handle_collection_load_errors {
+                        panic!("Failed to load collection {collection_name}: {err}")
+                    }
+
+                    log::error!(
+                        "Failed to load collection {collection_name}, marking it as broken: {err}"
+                    );
+                    broken_collections.insert(collection_name, err.to_string());
+                }
+            }
         }
         let alias_path = Path::new(&storage_config.storage_path).join(ALIASES_PATH);
         let alias_persistence =
@@ -198,9 +225,47 @@ impl TableOfContent {
             lock_error_message: parking_lot::Mutex::new(None),
             update_rate_limiter: rate_limiter,
             collection_create_lock: Default::default(),
+            last_replica_failover_check: Default::default(),
+            broken_collections: parking_lot::RwLock::new(broken_collections),
         }
     }
 
+    /// Load the collection from its directory in the storage
+    #[allow(clippy::too_many_arguments)]
+    async fn load_collection(
+        collection_name: &str,
+        collection_path: &Path,
+        collection_snapshots_path: &Path,
+        storage_config: &StorageConfig,
+        channel_service: &ChannelService,
+        this_peer_id: PeerId,
+        consensus_proposal_sender: &Option<OperationSender>,
+        search_runtime: &Runtime,
+        update_runtime: &Runtime,
+    ) -> CollectionResult<Collection> {
+        Collection::load(
+            collection_name.to_string(),
+            this_peer_id,
+            collection_path,
+            collection_snapshots_path,
+            storage_config.to_shared_storage_config().into(),
+            channel_service.clone(),
+            Self::change_peer_state_callback(
+                consensus_proposal_sender.clone(),
+                collection_name.to_string(),
+                ReplicaState::Dead,
+                None,
+            ),
+            Self::request_shard_transfer_callback(
+                consensus_proposal_sender.clone(),
+                collection_name.to_string(),
+            ),
+            Some(search_runtime.handle().clone()),
+            Some(update_runtime.handle().clone()),
+        )
+        .await
+    }
+
     /// Return `true` if service is working in distributed mode.
     pub fn is_distributed(&self) -> bool {
         self.consensus_proposal_sender.is_some()
@@ -343,8 +408,13 @@ impl TableOfContent {
 
         let CreateCollection {
             vectors,
+            sparse_vectors,
             shard_number,
+            sharding_method,
             on_disk_payload,
+            tenant_key,
+            store_original_vectors,
+            search_timeout_sec,
             hnsw_config: hnsw_config_diff,
             wal_config: wal_config_diff,
             optimizers_config: optimizers_config_diff,
@@ -352,6 +422,7 @@ impl TableOfContent {
             write_consistency_factor,
             init_from,
             quantization_config,
+            strict_mode_config,
         } = operation;
 
         self.collections
@@ -360,6 +431,12 @@ impl TableOfContent {
             .validate_collection_not_exists(collection_name)
             .await?;
 
+        if self.broken_collections.read().contains_key(collection_name) {
+            return Err(StorageError::bad_input(&format!(
+                "Collection `{collection_name}` already exists, but failed to load. Recover or drop it first",
+            )));
+        }
+
         if self
             .alias_persistence
             .read()
@@ -371,6 +448,14 @@ impl TableOfContent {
             )));
         }
 
+        for sparse_vector_name in sparse_vectors.iter().flat_map(|sparse| sparse.keys()) {
+            if vectors.get_params(sparse_vector_name).is_some() {
+                return Err(StorageError::bad_input(&format!(
+                    "Sparse vector name {sparse_vector_name} is already used by a dense vector",
+                )));
+            }
+        }
+
         if let Some(init_from) = &init_from {
             self.check_collections_compatibility(&vectors, &init_from.collection)
                 .await?;
@@ -379,13 +464,28 @@ impl TableOfContent {
         let collection_path = self.create_collection_path(collection_name).await?;
         let snapshots_path = self.create_snapshots_path(collection_name).await?;
 
-        if let Some(shard_number) = shard_number {
-            debug_assert_eq!(
-                shard_number as usize,
-                collection_shard_distribution.shard_count(),
-                "If shard number was supplied then this exact number should be used in a distribution"
-            )
-        }
+        let (shard_number, collection_shard_distribution) = match sharding_method {
+            None | Some(ShardingMethod::Auto) => {
+                if let Some(shard_number) = shard_number {
+                    debug_assert_eq!(
+                        shard_number as usize,
+                        collection_shard_distribution.shard_count(),
+                        "If shard number was supplied then this exact number should be used in a distribution"
+                    )
+                }
+                (
+                    collection_shard_distribution.shard_count() as u32,
+                    collection_shard_distribution,
+                )
+            }
+            // Shards are created later for each shard key, by default this number of them
+            Some(ShardingMethod::Custom) => (
+                shard_number.unwrap_or(1),
+                CollectionShardDistribution {
+                    shards: HashMap::new(),
+                },
+            ),
+        };
         let replication_factor =
             replication_factor.unwrap_or_else(|| default_replication_factor().get());
 
@@ -394,10 +494,10 @@ impl TableOfContent {
 
         let collection_params = CollectionParams {
             vectors,
-            shard_number: NonZeroU32::new(collection_shard_distribution.shard_count() as u32)
-                .ok_or(StorageError::BadInput {
-                    description: "`shard_number` cannot be 0".to_string(),
-                })?,
+            shard_number: NonZeroU32::new(shard_number).ok_or(StorageError::BadInput {
+                description: "`shard_number` cannot be 0".to_string(),
+            })?,
+            sharding_method,
             on_disk_payload: on_disk_payload.unwrap_or(self.storage_config.on_disk_payload),
             replication_factor: NonZeroU32::new(replication_factor).ok_or(
                 StorageError::BadInput {
@@ -409,6 +509,16 @@ impl TableOfContent {
                     description: "`write_consistency_factor` cannot be 0".to_string(),
                 },
             )?,
+            tenant_key,
+            sparse_vectors,
+            store_original_vectors: store_original_vectors.unwrap_or_default(),
+            search_timeout_sec: search_timeout_sec
+                .map(|timeout| {
+                    NonZeroU64::new(timeout).ok_or(StorageError::BadInput {
+                        description: "`search_timeout_sec` cannot be 0".to_string(),
+                    })
+                })
+                .transpose()?,
         };
         let wal_config = match wal_config_diff {
             None => self.storage_config.wal.clone(),
@@ -436,7 +546,11 @@ impl TableOfContent {
             optimizer_config: optimizers_config,
             hnsw_config,
             quantization_config,
+            strict_mode_config,
         };
+        collection_config.check_quantization()?;
+        collection_config.check_vector_datatypes()?;
+        collection_config.optimizer_config.check_thresholds()?;
         let collection = Collection::new(
             collection_name.to_string(),
             self.this_peer_id,
@@ -639,6 +753,44 @@ impl TableOfContent {
         })
     }
 
+    fn on_resharding_failure_callback(
+        proposal_sender: Option<OperationSender>,
+    ) -> OnReshardingFailure {
+        Arc::new(move |split, collection_name, reason| {
+            if let Some(proposal_sender) = &proposal_sender {
+                let operation =
+                    ConsensusOperations::abort_resharding(collection_name.clone(), split, reason);
+                if let Err(send_error) = proposal_sender.send(operation) {
+                    log::error!(
+                        "Can't send proposal to abort resharding of shard {} of collection {}. Error: {}",
+                        split.source_shard_id,
+                        collection_name,
+                        send_error
+                    );
+                }
+            }
+        })
+    }
+
+    fn on_resharding_success_callback(
+        proposal_sender: Option<OperationSender>,
+    ) -> OnReshardingSuccess {
+        Arc::new(move |split, collection_name| {
+            if let Some(proposal_sender) = &proposal_sender {
+                let operation =
+                    ConsensusOperations::finish_resharding(collection_name.clone(), split);
+                if let Err(send_error) = proposal_sender.send(operation) {
+                    log::error!(
+                        "Can't send proposal to finish resharding of shard {} of collection {}. Error: {}",
+                        split.source_shard_id,
+                        collection_name,
+                        send_error
+                    );
+                }
+            }
+        })
+    }
+
     fn change_peer_state_callback(
         proposal_sender: Option<OperationSender>,
         collection_name: String,
@@ -743,6 +895,7 @@ impl TableOfContent {
                 from: from_peer,
                 to: to_peer,
                 sync,
+                method: None,
             };
             let operation = ConsensusOperations::start_transfer(collection_name, transfer_request);
             proposal_sender.send(operation)?;
@@ -778,6 +931,7 @@ impl TableOfContent {
             params,
             optimizers_config,
             quantization_config,
+            strict_mode_config,
         } = operation.update_collection;
         let collection = self.get_collection(&operation.collection_name).await?;
         let mut recreate_optimizers = false;
@@ -804,6 +958,9 @@ impl TableOfContent {
                 .await?;
             recreate_optimizers = true;
         }
+        if let Some(diff) = strict_mode_config {
+            collection.update_strict_mode_config(diff).await?;
+        }
         if let Some(changes) = replica_changes {
             collection.handle_replica_changes(changes).await?;
         }
@@ -822,37 +979,135 @@ impl TableOfContent {
                 .await
                 .remove_collection(collection_name)?;
 
-            let path = self.get_collection_path(collection_name);
             drop(removed);
+            self.remove_collection_files(collection_name).await?;
+            Ok(true)
+        } else if self
+            .broken_collections
+            .write()
+            .remove(collection_name)
+            .is_some()
+        {
+            self.alias_persistence
+                .write()
+                .await
+                .remove_collection(collection_name)?;
 
-            // Move collection to ".deleted" folder to prevent accidental reuse
-            let uuid = Uuid::new_v4().to_string();
-            let removed_collections_path =
-                Path::new(&self.storage_config.storage_path).join(".deleted");
-            tokio::fs::create_dir_all(&removed_collections_path).await?;
-            let deleted_path = removed_collections_path
-                .join(collection_name)
-                .with_extension(uuid);
-            tokio::fs::rename(path, &deleted_path).await?;
-
-            // At this point collection is removed from memory and moved to ".deleted" folder.
-            // Next time we load service the collection will not appear in the list of collections.
-            // We can take our time to delete the collection from disk.
-            tokio::spawn(async move {
-                if let Err(error) = tokio::fs::remove_dir_all(&deleted_path).await {
-                    log::error!(
-                        "Can't delete collection {} from disk. Error: {}",
-                        deleted_path.display(),
-                        error
-                    );
-                }
-            });
+            self.remove_collection_files(collection_name).await?;
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    async fn remove_collection_files(&self, collection_name: &str) -> Result<(), StorageError> {
+        let path = self.get_collection_path(collection_name);
+
+        // Move collection to ".deleted" folder to prevent accidental reuse
+        let uuid = Uuid::new_v4().to_string();
+        let removed_collections_path =
+            Path::new(&self.storage_config.storage_path).join(".deleted");
+        tokio::fs::create_dir_all(&removed_collections_path).await?;
+        let deleted_path = removed_collections_path
+            .join(collection_name)
+            .with_extension(uuid);
+        tokio::fs::rename(path, &deleted_path).await?;
+
+        // At this point collection is removed from memory and moved to ".deleted" folder.
+        // Next time we load service the collection will not appear in the list of collections.
+        // We can take our time to delete the collection from disk.
+        tokio::spawn(async move {
+            if let Err(error) = tokio::fs::remove_dir_all(&deleted_path).await {
+                log::error!(
+                    "Can't delete collection {} from disk. Error: {}",
+                    deleted_path.display(),
+                    error
+                );
+            }
+        });
+        Ok(())
+    }
+
+    /// Collections, which failed to load, with the load errors
+    pub fn broken_collections(&self) -> Vec<(String, String)> {
+        self.broken_collections
+            .read()
+            .iter()
+            .map(|(name, error)| (name.clone(), error.clone()))
+            .sorted()
+            .collect()
+    }
+
+    fn check_not_broken(&self, collection_name: &str) -> Result<(), StorageError> {
+        match self.broken_collections.read().get(collection_name) {
+            Some(error) => Err(StorageError::BrokenCollection {
+                description: format!(
+                    "Collection `{collection_name}` failed to load: {error}. Recover or drop it"
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Load the broken collection again, e.g. after its files were fixed by the operator
+    pub async fn recover_broken_collection(
+        &self,
+        collection_name: &str,
+    ) -> Result<(), StorageError> {
+        let _collection_create_guard = self.collection_create_lock.lock().await;
+
+        if !self.broken_collections.read().contains_key(collection_name) {
+            return Err(StorageError::NotFound {
+                description: format!("Collection `{collection_name}` is not broken"),
+            });
+        }
+
+        log::info!("Recovering broken collection: {collection_name}");
+        let collection = Self::load_collection(
+            collection_name,
+            &self.get_collection_path(collection_name),
+            &self.snapshots_path_for_collection(collection_name),
+            &self.storage_config,
+            &self.channel_service,
+            self.this_peer_id,
+            &self.consensus_proposal_sender,
+            &self.search_runtime,
+            &self.update_runtime,
+        )
+        .await;
+
+        match collection {
+            Ok(collection) => {
+                let mut collections = self.collections.write().await;
+                collections.insert(collection_name.to_string(), collection);
+                self.broken_collections.write().remove(collection_name);
+                Ok(())
+            }
+            Err(err) => {
+                log::error!("Failed to recover collection {collection_name}: {err}");
+                self.broken_collections
+                    .write()
+                    .insert(collection_name.to_string(), err.to_string());
+                self.check_not_broken(collection_name)
+            }
+        }
+    }
+
+    /// Remove the broken collection with all its files from this peer
+    pub async fn drop_broken_collection(&self, collection_name: &str) -> Result<(), StorageError> {
+        let _collection_create_guard = self.collection_create_lock.lock().await;
+
+        if !self.broken_collections.read().contains_key(collection_name) {
+            return Err(StorageError::NotFound {
+                description: format!("Collection `{collection_name}` is not broken"),
+            });
+        }
+
+        log::info!("Dropping broken collection: {collection_name}");
+        self.delete_collection(collection_name).await?;
+        Ok(())
+    }
+
     /// performs several alias changes in an atomic fashion
     async fn update_aliases(
         &self,
@@ -862,6 +1117,9 @@ impl TableOfContent {
         // Prevent search on partially switched collections
         let collection_lock = self.collections.write().await;
         let mut alias_lock = self.alias_persistence.write().await;
+
+        // Changes are applied to a copy, so a failed action leaves all aliases unchanged
+        let mut aliases = alias_lock.state().clone();
         for action in operation.actions {
             match action {
                 AliasOperations::CreateAlias(CreateAliasOperation {
@@ -878,12 +1136,12 @@ impl TableOfContent {
                         .validate_collection_not_exists(&alias_name)
                         .await?;
 
-                    alias_lock.insert(alias_name, collection_name)?;
+                    aliases.insert(alias_name, collection_name);
                 }
                 AliasOperations::DeleteAlias(DeleteAliasOperation {
                     delete_alias: DeleteAlias { alias_name },
                 }) => {
-                    alias_lock.remove(&alias_name)?;
+                    aliases.remove(&alias_name);
                 }
                 AliasOperations::RenameAlias(RenameAliasOperation {
                     rename_alias:
@@ -892,10 +1150,11 @@ impl TableOfContent {
                             new_alias_name,
                         },
                 }) => {
-                    alias_lock.rename_alias(&old_alias_name, new_alias_name)?;
+                    aliases.rename(&old_alias_name, new_alias_name)?;
                 }
             };
         }
+        alias_lock.apply_state(aliases)?;
         Ok(true)
     }
 
@@ -951,10 +1210,52 @@ impl TableOfContent {
                 log::debug!("Set shard replica state {:?}", operation);
                 self.set_shard_replica_state(operation).await.map(|()| true)
             }
+            CollectionMetaOperations::CreateShardKey(operation) => {
+                log::debug!("Create shard key {:?}", operation);
+                self.create_shard_key(operation).await.map(|()| true)
+            }
+            CollectionMetaOperations::DropShardKey(operation) => {
+                log::debug!("Drop shard key {:?}", operation);
+                self.drop_shard_key(operation).await.map(|()| true)
+            }
+            CollectionMetaOperations::Resharding(collection, operation) => {
+                log::debug!("Resharding {:?} of {}", operation, collection);
+                self.handle_resharding(collection, operation)
+                    .await
+                    .map(|()| true)
+            }
             CollectionMetaOperations::Nop { .. } => Ok(true),
         }
     }
 
+    async fn create_shard_key(&self, operation: CreateShardKey) -> Result<(), StorageError> {
+        let CreateShardKey {
+            collection_name,
+            shard_key,
+            placement,
+        } = operation;
+        let local_shards = self
+            .get_collection(&collection_name)
+            .await?
+            .create_shard_key(shard_key, placement)
+            .await?;
+
+        // Notify the shards are created and ready to use
+        for shard_id in local_shards {
+            self.on_peer_created(collection_name.clone(), self.this_peer_id, shard_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn drop_shard_key(&self, operation: DropShardKey) -> Result<(), StorageError> {
+        self.get_collection(&operation.collection_name)
+            .await?
+            .drop_shard_key(operation.shard_key)
+            .await?;
+        Ok(())
+    }
+
     pub async fn set_shard_replica_state(
         &self,
         operation: SetShardReplicaState,
@@ -1009,6 +1310,9 @@ impl TableOfContent {
                     config: _,
                     shards,
                     transfers,
+                    shards_key_mapping: _,
+                    resharding,
+                    shard_splits: _,
                 } = collection.state().await;
                 let all_peers: HashSet<_> = self
                     .channel_service
@@ -1031,6 +1335,19 @@ impl TableOfContent {
 
                 validate_transfer(&transfer, &all_peers, shard_state, &transfers)?;
 
+                // Points of the resharded shards are being moved, they can't be transferred
+                if let Some(resharding) = resharding {
+                    let split = resharding.split;
+                    if transfer.shard_id == split.source_shard_id
+                        || transfer.shard_id == split.target_shard_id
+                    {
+                        return Err(StorageError::bad_request(&format!(
+                            "Shard {} is being resharded",
+                            transfer.shard_id
+                        )));
+                    }
+                }
+
                 let collection_id_clone = collection_id.clone();
                 let transfer_clone = transfer.clone();
 
@@ -1076,6 +1393,109 @@ impl TableOfContent {
         Ok(())
     }
 
+    /// Callbacks of the resharding task, which finish or abort the resharding
+    fn resharding_callbacks(
+        &self,
+        collection_name: CollectionId,
+        split: ShardSplit,
+    ) -> (BoxFuture<'static, ()>, BoxFuture<'static, ()>) {
+        match self.consensus_proposal_sender.clone() {
+            Some(proposal_sender) => {
+                let on_finish_sender = proposal_sender.clone();
+                let on_finish_collection = collection_name.clone();
+                let on_finish = async move {
+                    let operation =
+                        ConsensusOperations::finish_resharding(on_finish_collection, split);
+                    if let Err(error) = on_finish_sender.send(operation) {
+                        log::error!("Can't report resharding progress to consensus: {error}")
+                    };
+                }
+                .boxed();
+                let on_error = async move {
+                    let operation = ConsensusOperations::abort_resharding(
+                        collection_name,
+                        split,
+                        "migration of points failed",
+                    );
+                    if let Err(error) = proposal_sender.send(operation) {
+                        log::error!("Can't report resharding progress to consensus: {error}")
+                    };
+                }
+                .boxed();
+                (on_finish, on_error)
+            }
+            None => {
+                // There is no consensus in a single node deployment,
+                // so the resharding is completed right away
+                let collections = self.collections.clone();
+                let on_finish_collection = collection_name.clone();
+                let on_finish = async move {
+                    let collections = collections.read().await;
+                    if let Some(collection) = collections.get(&on_finish_collection) {
+                        if let Err(error) = collection.finish_resharding(split).await {
+                            log::error!("Can't finish resharding: {error}");
+                        }
+                    }
+                }
+                .boxed();
+                let collections = self.collections.clone();
+                let on_error = async move {
+                    let collections = collections.read().await;
+                    if let Some(collection) = collections.get(&collection_name) {
+                        if let Err(error) = collection.abort_resharding(split).await {
+                            log::error!("Can't abort resharding: {error}");
+                        }
+                    }
+                }
+                .boxed();
+                (on_finish, on_error)
+            }
+        }
+    }
+
+    /// Resume the resharding driven by this peer in all collections, e.g. after restart
+    pub async fn resume_resharding(&self) {
+        let collections = self.collections.read().await;
+        for collection in collections.values() {
+            let Some(state) = collection.resharding_state().await else {
+                continue;
+            };
+            let (on_finish, on_error) = self.resharding_callbacks(collection.name(), state.split);
+            if let Err(err) = collection.resume_resharding(on_finish, on_error).await {
+                log::error!(
+                    "Can't resume resharding of collection {}: {err}",
+                    collection.name(),
+                );
+            }
+        }
+    }
+
+    pub async fn handle_resharding(
+        &self,
+        collection_id: CollectionId,
+        resharding_operation: ReshardingOperation,
+    ) -> Result<(), StorageError> {
+        let collection = self.get_collection(&collection_id).await?;
+
+        match resharding_operation {
+            ReshardingOperation::Start(state) => {
+                let (on_finish, on_error) =
+                    self.resharding_callbacks(collection.name(), state.split);
+                collection
+                    .start_resharding(state, on_finish, on_error)
+                    .await?;
+            }
+            ReshardingOperation::Finish(split) => {
+                collection.finish_resharding(split).await?;
+            }
+            ReshardingOperation::Abort { split, reason } => {
+                log::warn!("Aborting resharding: {reason}");
+                collection.abort_resharding(split).await?;
+            }
+        }
+        Ok(())
+    }
+
     async fn get_collection_opt(
         &self,
         collection_name: String,
@@ -1091,7 +1511,16 @@ impl TableOfContent {
 
         let real_collection_name = {
             let alias_persistence = self.alias_persistence.read().await;
-            Self::resolve_name(collection_name, &read_collection, &alias_persistence).await?
+            let resolved =
+                Self::resolve_name(collection_name, &read_collection, &alias_persistence).await;
+            if resolved.is_err() {
+                // Report, that the collection exists, but is broken
+                let real_name = alias_persistence
+                    .get(collection_name)
+                    .unwrap_or_else(|| collection_name.to_string());
+                self.check_not_broken(&real_name)?;
+            }
+            resolved?
         };
         // resolve_name already checked collection existence, unwrap is safe here
         Ok(RwLockReadGuard::map(read_collection, |collection| {
@@ -1120,12 +1549,95 @@ impl TableOfContent {
         Ok(())
     }
 
+    /// Client ids of up to `count` last operations of the local shard, used to recover the shard
+    /// from WAL of another replica.
+    ///
+    /// Returns an empty list if there is no local shard
+    pub async fn shard_recovery_point(
+        &self,
+        collection_name: &str,
+        shard_id: ShardId,
+        count: usize,
+    ) -> Result<Vec<ClientOperationId>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(collection.shard_recovery_point(shard_id, count).await?)
+    }
+
+    /// Receive a chunk of the snapshot archive of the shard, transferred to this peer by snapshot.
+    ///
+    /// Returns the number of received bytes of the archive.
+    pub async fn upload_shard_snapshot_chunk(
+        &self,
+        collection_name: &str,
+        shard_id: ShardId,
+        offset: u64,
+        data: &[u8],
+        checksum: u64,
+    ) -> Result<u64, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(collection
+            .upload_shard_snapshot_chunk(shard_id, offset, data, checksum)
+            .await?)
+    }
+
+    /// Recover the local shard, transferred to this peer by snapshot, from the received archive
+    pub async fn recover_shard_snapshot(
+        &self,
+        collection_name: &str,
+        shard_id: ShardId,
+        size: u64,
+        checksum: &str,
+    ) -> Result<(), StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(collection
+            .recover_shard_snapshot(shard_id, size, checksum)
+            .await?)
+    }
+
+    /// Archive the snapshot of the local shard, to be downloaded by another peer
+    pub async fn create_shard_snapshot(
+        &self,
+        collection_name: &str,
+        shard_id: ShardId,
+    ) -> Result<(String, FileManifest), StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        let temp_dir = self.temp_storage_path().join(SNAPSHOTS_TEMP_DIR);
+        tokio::fs::create_dir_all(&temp_dir).await?;
+        Ok(collection
+            .create_shard_snapshot(shard_id, &temp_dir)
+            .await?)
+    }
+
+    pub async fn get_shard_snapshot_chunk(
+        &self,
+        collection_name: &str,
+        shard_id: ShardId,
+        name: &str,
+        offset: u64,
+    ) -> Result<(Vec<u8>, u64), StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(collection
+            .get_shard_snapshot_chunk(shard_id, name, offset)
+            .await?)
+    }
+
+    pub async fn delete_shard_snapshot(
+        &self,
+        collection_name: &str,
+        shard_id: ShardId,
+        name: &str,
+    ) -> Result<(), StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(collection.delete_shard_snapshot(shard_id, name).await?)
+    }
+
     /// Recommend points using positive and negative example from the request
     ///
     /// # Arguments
     ///
     /// * `collection_name` - for what collection do we recommend
     /// * `request` - [`RecommendRequest`]
+    /// * `shard_selection` - which shard keys to use
     ///
     /// # Result
     ///
@@ -1135,6 +1647,7 @@ impl TableOfContent {
         collection_name: &str,
         request: RecommendRequest,
         read_consistency: Option<ReadConsistency>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         recommend_by(
@@ -1142,6 +1655,34 @@ impl TableOfContent {
             &collection,
             |name| self.get_collection_opt(name),
             read_consistency,
+            shard_selection,
+        )
+        .await
+        .map_err(|err| err.into())
+    }
+
+    /// Discover points closest to the target, constrained by the context pairs from the request
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we discover
+    /// * `request` - [`DiscoverRequest`]
+    ///
+    /// # Result
+    ///
+    /// Points with discovery score
+    pub async fn discover(
+        &self,
+        collection_name: &str,
+        request: DiscoverRequest,
+        read_consistency: Option<ReadConsistency>,
+    ) -> Result<Vec<ScoredPoint>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        discover(
+            request,
+            &collection,
+            |name| self.get_collection_opt(name),
+            read_consistency,
         )
         .await
         .map_err(|err| err.into())
@@ -1153,6 +1694,7 @@ impl TableOfContent {
     ///
     /// * `collection_name` - for what collection do we recommend
     /// * `request` - [`RecommendRequestBatch`]
+    /// * `shard_selection` - which shard keys to use
     ///
     /// # Result
     ///
@@ -1162,6 +1704,7 @@ impl TableOfContent {
         collection_name: &str,
         request: RecommendRequestBatch,
         read_consistency: Option<ReadConsistency>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         recommend_batch_by(
@@ -1169,6 +1712,7 @@ impl TableOfContent {
             &collection,
             |name| self.get_collection_opt(name),
             read_consistency,
+            shard_selection,
         )
         .await
         .map_err(|err| err.into())
@@ -1181,7 +1725,8 @@ impl TableOfContent {
     ///
     /// * `collection_name` - in what collection do we search
     /// * `request` - [`SearchRequest`]
-    /// * `shard_selection` - which local shard to use
+    /// * `shard_selection` - which local shard, or shard keys, to use
+    /// * `timeout` - abort the search after it, instead of the default timeout
     /// # Result
     ///
     /// Points with search score
@@ -1190,11 +1735,37 @@ impl TableOfContent {
         collection_name: &str,
         request: SearchRequest,
         read_consistency: Option<ReadConsistency>,
+        shard_selection: impl Into<ShardSelectorInternal>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<ScoredPoint>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .search(request, read_consistency, shard_selection, timeout)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Look for points by the query, which doesn't score them by vector similarity
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we query
+    /// * `request` - [`QueryRequest`]
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// Points in the order defined by the query
+    pub async fn query(
+        &self,
+        collection_name: &str,
+        request: QueryRequest,
+        read_consistency: Option<ReadConsistency>,
         shard_selection: Option<ShardId>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         collection
-            .search(request, read_consistency, shard_selection)
+            .query(request, read_consistency, shard_selection)
             .await
             .map_err(|err| err.into())
     }
@@ -1206,20 +1777,52 @@ impl TableOfContent {
     ///
     /// * `collection_name` - in what collection do we search
     /// * `request` - [`SearchRequestBatch`]
-    /// * `shard_selection` - which local shard to use
+    /// * `shard_selection` - which local shard, or shard keys, to use
+    /// * `timeout` - abort the search after it, instead of the default timeout
     /// # Result
     ///
-    /// Points with search score
+    /// Points with search score, or an error, per each search of the batch
     pub async fn search_batch(
         &self,
         collection_name: &str,
         request: SearchRequestBatch,
         read_consistency: Option<ReadConsistency>,
+        shard_selection: impl Into<ShardSelectorInternal>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Result<Vec<ScoredPoint>, StorageError>>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        let results = collection
+            .search_batch(request, read_consistency, shard_selection, timeout)
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(|result| result.map_err(|err| err.into()))
+            .collect())
+    }
+
+    /// Search in a batching fashion for the points scored best by queries of any kind,
+    /// with given restrictions defined in the request
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`CoreSearchRequestBatch`]
+    /// * `shard_selection` - which local shard to use
+    /// * `timeout` - abort the search after it, instead of the default timeout
+    /// # Result
+    ///
+    /// Points with search score
+    pub async fn core_search_batch(
+        &self,
+        collection_name: &str,
+        request: CoreSearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
         shard_selection: Option<ShardId>,
+        timeout: Option<Duration>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         collection
-            .search_batch(request, read_consistency, shard_selection)
+            .core_search_batch(request, read_consistency, shard_selection, timeout)
             .await
             .map_err(|err| err.into())
     }
@@ -1230,7 +1833,7 @@ impl TableOfContent {
     ///
     /// * `collection_name` - in what collection do we count
     /// * `request` - [`CountRequest`]
-    /// * `shard_selection` - which local shard to use
+    /// * `shard_selection` - which local shard, or shard keys, to use
     ///
     /// # Result
     ///
@@ -1240,7 +1843,7 @@ impl TableOfContent {
         &self,
         collection_name: &str,
         request: CountRequest,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> Result<CountResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         collection
@@ -1249,6 +1852,54 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Count points per value of a keyword-indexed payload field
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we count
+    /// * `request` - [`FacetRequest`]
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// Most frequent values of the field with the number of points having them.
+    ///
+    pub async fn facet(
+        &self,
+        collection_name: &str,
+        request: FacetRequest,
+        shard_selection: Option<ShardId>,
+    ) -> Result<FacetResponse, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .facet(request, shard_selection)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Explain how segments of the local shards would execute a vector search
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`SearchPlanRequest`]
+    ///
+    /// # Result
+    ///
+    /// Estimated filter cardinality, used payload indexes and search path of every segment.
+    ///
+    pub async fn search_plan(
+        &self,
+        collection_name: &str,
+        request: SearchPlanRequest,
+    ) -> Result<SearchPlanResponse, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        collection
+            .search_plan(request)
+            .await
+            .map_err(|err| err.into())
+    }
+
     /// Return specific points by IDs
     ///
     /// # Arguments
@@ -1302,6 +1953,47 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Persist all collections, before the node shuts down.
+    ///
+    /// Queued updates of the local shards are applied, ongoing optimizations are cancelled,
+    /// then segments are flushed and WAL acknowledges the flushed operations.
+    pub async fn shutdown(&self) {
+        let collections = self.collections.read().await;
+        let total = collections.len();
+        for (i, (name, collection)) in collections.iter().enumerate() {
+            log::info!("Persisting collection {name} ({}/{total})", i + 1);
+            if let Err(err) = collection.shutdown().await {
+                log::error!("Failed to persist collection {name}: {err}");
+            }
+        }
+    }
+
+    /// Whether the collection or an alias with the name exists, without reading the collection.
+    /// Broken collections exist as well.
+    pub async fn collection_exists(&self, collection_name: &str) -> bool {
+        let collections = self.collections.read().await;
+        let alias_persistence = self.alias_persistence.read().await;
+        let real_name = alias_persistence
+            .get(collection_name)
+            .unwrap_or_else(|| collection_name.to_string());
+        collections.contains_key(&real_name)
+            || self.broken_collections.read().contains_key(&real_name)
+    }
+
+    /// Collections, which have local shards not ready to serve requests, see [`Collection::is_ready`].
+    /// Broken collections are not loaded, and are not reported here.
+    pub async fn not_ready_collections(&self) -> Vec<String> {
+        let collections = self.collections.read().await;
+        let mut not_ready = vec![];
+        for (name, collection) in collections.iter() {
+            if !collection.is_ready().await {
+                not_ready.push(name.clone());
+            }
+        }
+        not_ready.sort();
+        not_ready
+    }
+
     /// List of all collections
     pub async fn all_collections(&self) -> Vec<String> {
         self.collections.read().await.keys().cloned().collect()
@@ -1316,6 +2008,15 @@ impl TableOfContent {
             .collect()
     }
 
+    /// Name of the collection, which the alias refers to, or the name itself, if it's not an alias
+    pub async fn resolve_alias(&self, name: &str) -> String {
+        self.alias_persistence
+            .read()
+            .await
+            .get(name)
+            .unwrap_or_else(|| name.to_string())
+    }
+
     /// List of all aliases for a given collection
     pub async fn collection_aliases(
         &self,
@@ -1351,7 +2052,7 @@ impl TableOfContent {
     ///
     /// * `collection_name` - which collection to use
     /// * `request` - [`ScrollRequest`]
-    /// * `shard_selection` - which local shard to use
+    /// * `shard_selection` - which local shard, or shard keys, to use
     ///
     /// # Result
     ///
@@ -1361,7 +2062,7 @@ impl TableOfContent {
         collection_name: &str,
         request: ScrollRequest,
         read_consistency: Option<ReadConsistency>,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
     ) -> Result<ScrollResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         collection
@@ -1370,34 +2071,83 @@ impl TableOfContent {
             .map_err(|err| err.into())
     }
 
+    /// Apply the update to the collection.
+    ///
+    /// Operation with `client_op_id` is applied to each shard only once, a retry of it returns
+    /// the result of the first attempt.
     pub async fn update(
         &self,
         collection_name: &str,
         operation: CollectionUpdateOperations,
-        shard_selection: Option<ShardId>,
+        shard_selection: impl Into<ShardSelectorInternal>,
+        client_op_id: Option<ClientOperationId>,
         wait: bool,
         ordering: WriteOrdering,
     ) -> Result<UpdateResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
-        let result = match shard_selection {
-            Some(shard_selection) => {
-                collection
-                    .update_from_peer(operation, shard_selection, wait)
-                    .await
+        let shard_key = match shard_selection.into() {
+            ShardSelectorInternal::ShardId(shard_selection) => {
+                return Ok(collection
+                    .update_from_peer(
+                        OperationWithClientId::new(operation, client_op_id),
+                        shard_selection,
+                        wait,
+                    )
+                    .await?);
             }
-            None => {
-                let _rate_limit = match &self.update_rate_limiter {
-                    None => None,
-                    Some(rate_limiter) => Some(rate_limiter.acquire().await),
-                };
-                if operation.is_write_operation() {
-                    self.check_write_lock()?;
-                }
-                collection
-                    .update_from_client(operation, wait, ordering)
-                    .await
+            ShardSelectorInternal::Empty | ShardSelectorInternal::All => None,
+            ShardSelectorInternal::ShardKey(shard_key) => Some(shard_key),
+            ShardSelectorInternal::ShardKeys(_) => {
+                return Err(StorageError::bad_input(
+                    "Update operation can only be applied to a single shard key",
+                ));
             }
         };
+        let _rate_limit = match &self.update_rate_limiter {
+            None => None,
+            Some(rate_limiter) => Some(rate_limiter.acquire().await),
+        };
+        if operation.is_write_operation() {
+            self.check_write_lock()?;
+        }
+        collection.check_strict_mode_update(&operation).await?;
+        let result = collection
+            .update_from_client_with_shard_key(operation, shard_key, client_op_id, wait, ordering)
+            .await;
+        result.map_err(|err| err.into())
+    }
+
+    /// Apply a batch of updates, with a result per operation of the batch
+    pub async fn update_batch(
+        &self,
+        collection_name: &str,
+        operations: Vec<CollectionUpdateOperations>,
+        shard_selection: Option<ShardId>,
+        wait: bool,
+        ordering: WriteOrdering,
+    ) -> Result<Vec<UpdateResult>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        if let Some(shard_selection) = shard_selection {
+            return Ok(collection
+                .update_batch_from_peer(operations, shard_selection, wait)
+                .await?);
+        }
+        let _rate_limit = match &self.update_rate_limiter {
+            None => None,
+            Some(rate_limiter) => Some(rate_limiter.acquire().await),
+        };
+        if operations
+            .iter()
+            .any(|operation| operation.is_write_operation())
+        {
+            self.check_write_lock()?;
+        }
+        for operation in &operations {
+            collection.check_strict_mode_update(operation).await?;
+        }
+        let result = collection
+            .update_batch_from_client(operations, wait, ordering)
+            .await;
         result.map_err(|err| err.into())
     }
 
@@ -1432,6 +2182,11 @@ impl TableOfContent {
             let mut collections = self.collections.write().await;
 
             for (id, state) in &data.collections {
+                if self.broken_collections.read().contains_key(id) {
+                    log::warn!("Skipping state of broken collection {id}, until it is recovered");
+                    continue;
+                }
+
                 let collection_exists = collections.contains_key(id);
 
                 // Create collection if not present locally
@@ -1545,8 +2300,9 @@ impl TableOfContent {
         // snapshot directory is mounted as network share and multiple writes to it could be slow
         let temp_dir = self.temp_storage_path().join(SNAPSHOTS_TEMP_DIR);
         tokio::fs::create_dir_all(&temp_dir).await?;
+        let aliases = self.collection_aliases(collection_name).await?;
         Ok(collection
-            .create_snapshot(&temp_dir, self.this_peer_id)
+            .create_snapshot(&temp_dir, self.this_peer_id, &aliases)
             .await?)
     }
 
@@ -1555,6 +2311,12 @@ impl TableOfContent {
         op: &CreateCollectionOperation,
         suggested_shard_number: NonZeroU32,
     ) -> ShardDistributionProposal {
+        // Collection with custom sharding has no shards, until shard keys are created
+        if op.create_collection.sharding_method == Some(ShardingMethod::Custom) {
+            return ShardDistributionProposal {
+                distribution: vec![],
+            };
+        }
         let shard_number = op
             .create_collection
             .shard_number
@@ -1588,6 +2350,120 @@ impl TableOfContent {
         shard_distribution
     }
 
+    /// Suggest peers for the replicas of each shard of a new shard key
+    ///
+    /// Validates the shard key, so the operation is not rejected by peers after it is committed.
+    pub async fn suggest_shard_key_placement(
+        &self,
+        collection_name: &str,
+        create_sharding_key: &CreateShardingKey,
+    ) -> Result<ShardsPlacement, StorageError> {
+        let collection_state::State {
+            config,
+            shards_key_mapping,
+            ..
+        } = self.get_collection(collection_name).await?.state().await;
+
+        if config.params.sharding_method != Some(ShardingMethod::Custom) {
+            return Err(StorageError::bad_request(&format!(
+                "Shard key can not be created, \
+                 collection {collection_name} is not created with custom sharding method"
+            )));
+        }
+        let shard_key = &create_sharding_key.shard_key;
+        if shards_key_mapping.values().any(|key| key == shard_key) {
+            return Err(StorageError::bad_request(&format!(
+                "Shard key {shard_key} already exists"
+            )));
+        }
+
+        let mut known_peers: HashSet<_> = self
+            .channel_service
+            .id_to_address
+            .read()
+            .keys()
+            .copied()
+            .collect();
+        known_peers.insert(self.this_peer_id());
+        let peers: Vec<_> = match &create_sharding_key.placement {
+            None => known_peers.into_iter().collect(),
+            Some(placement) => {
+                if placement.is_empty() {
+                    return Err(StorageError::bad_request(
+                        "Placement of shard key must contain at least one peer",
+                    ));
+                }
+                if let Some(peer_id) = placement.iter().find(|peer| !known_peers.contains(peer)) {
+                    return Err(StorageError::bad_request(&format!(
+                        "Peer {peer_id} does not exist"
+                    )));
+                }
+                placement.iter().copied().unique().collect()
+            }
+        };
+
+        let shard_number = create_sharding_key
+            .shards_number
+            .unwrap_or(config.params.shard_number);
+        let replication_factor = create_sharding_key
+            .replication_factor
+            .unwrap_or(config.params.replication_factor);
+        let shard_distribution =
+            ShardDistributionProposal::new(shard_number, replication_factor, &peers);
+
+        Ok(shard_distribution
+            .distribution
+            .into_iter()
+            .map(|(_shard_id, peers)| peers)
+            .collect())
+    }
+
+    /// Select the new shard and the peer, which copies the points into it
+    ///
+    /// Validates the resharding, so the operation is not rejected by peers after it is committed.
+    pub async fn suggest_resharding(
+        &self,
+        collection_name: &str,
+        start_resharding: &StartResharding,
+    ) -> Result<ReshardingState, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        let collection_state::State { shards, .. } = collection.state().await;
+
+        let shard_id = start_resharding.shard_id;
+        let Some(shard_info) = shards.get(&shard_id) else {
+            return Err(StorageError::bad_request(&format!(
+                "Shard {shard_id} of {collection_name} does not exist"
+            )));
+        };
+        let active_peers = shard_info
+            .replicas
+            .iter()
+            .filter(|(_, state)| **state == ReplicaState::Active)
+            .map(|(peer_id, _)| *peer_id);
+        let peer_id = match start_resharding.peer_id {
+            Some(peer_id) => peer_id,
+            // Prefer this peer, if it has an active replica
+            None => active_peers
+                .min_by_key(|peer_id| (*peer_id != self.this_peer_id, *peer_id))
+                .ok_or_else(|| {
+                    StorageError::bad_request(&format!(
+                        "Shard {shard_id} of {collection_name} has no active replicas"
+                    ))
+                })?,
+        };
+
+        let target_shard_id = shards.keys().max().map_or(0, |shard_id| shard_id + 1);
+        let state = ReshardingState {
+            split: ShardSplit {
+                source_shard_id: shard_id,
+                target_shard_id,
+            },
+            peer_id,
+        };
+        collection.check_start_resharding(&state).await?;
+        Ok(state)
+    }
+
     pub async fn get_telemetry_data(&self) -> Vec<CollectionTelemetry> {
         let mut result = Vec::new();
         let all_collections = self.all_collections().await;
@@ -1713,6 +2589,10 @@ impl CollectionContainer for TableOfContent {
                 Self::on_transfer_failure_callback(self.consensus_proposal_sender.clone());
             let transfer_success_callback =
                 Self::on_transfer_success_callback(self.consensus_proposal_sender.clone());
+            let resharding_failure_callback =
+                Self::on_resharding_failure_callback(self.consensus_proposal_sender.clone());
+            let resharding_success_callback =
+                Self::on_resharding_success_callback(self.consensus_proposal_sender.clone());
 
             for collection in collections.values() {
                 let finish_shard_initialize = Self::change_peer_state_callback(
@@ -1727,24 +2607,80 @@ impl CollectionContainer for TableOfContent {
                     ReplicaState::Listener,
                     Some(ReplicaState::Active),
                 );
-                let convert_from_listener_to_active_callback = Self::change_peer_state_callback(
-                    self.consensus_proposal_sender.clone(),
-                    collection.name(),
-                    ReplicaState::Active,
-                    Some(ReplicaState::Listener),
-                );
 
                 collection
                     .sync_local_state(
                         transfer_failure_callback.clone(),
                         transfer_success_callback.clone(),
+                        resharding_failure_callback.clone(),
+                        resharding_success_callback.clone(),
                         finish_shard_initialize,
                         convert_to_listener_callback,
-                        convert_from_listener_to_active_callback,
                     )
                     .await?;
             }
             Ok(())
         })
     }
+
+    fn restore_replication_factor(&self, peers: &[PeerId]) -> Result<(), StorageError> {
+        let config = &self.storage_config.replica_failover;
+        if !config.enabled {
+            return Ok(());
+        }
+        let Some(proposal_sender) = &self.consensus_proposal_sender else {
+            return Ok(());
+        };
+        {
+            let mut last_check = self.last_replica_failover_check.lock();
+            if last_check.map_or(false, |at| at.elapsed() < FAILOVER_CHECK_INTERVAL) {
+                return Ok(());
+            }
+            *last_check = Some(Instant::now());
+        }
+
+        self.general_runtime.block_on(async {
+            let collections = self.collections.read().await;
+            let mut states = Vec::with_capacity(collections.len());
+            for (collection_name, collection) in collections.iter() {
+                states.push((collection_name, collection, collection.state().await));
+            }
+            states.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+            // Transfer limits apply to the whole cluster, so load of all collections is counted
+            let mut load = ClusterLoad::new(peers.iter().copied());
+            for (_, _, state) in &states {
+                load.add_collection(state);
+            }
+
+            for (collection_name, collection, state) in &states {
+                let plan = collection.plan_replica_failover(state, config, &mut load);
+                for transfer in plan.transfers {
+                    log::info!(
+                        "Replacing dead replica of shard {} of collection {collection_name} \
+                         by transferring it from peer {} to peer {}",
+                        transfer.shard_id,
+                        transfer.from,
+                        transfer.to,
+                    );
+                    let operation =
+                        ConsensusOperations::start_transfer((*collection_name).clone(), transfer);
+                    proposal_sender.send(operation)?;
+                }
+                for (shard_id, peer_id) in plan.removed_replicas {
+                    log::info!(
+                        "Removing replaced dead replica of shard {shard_id} \
+                         of collection {collection_name} from peer {peer_id}",
+                    );
+                    Self::send_remove_replica_proposal_op(
+                        proposal_sender,
+                        (*collection_name).clone(),
+                        peer_id,
+                        shard_id,
+                    )?;
+                }
+            }
+            Ok(())
+        })
+    }
 }
@@ -3,6 +3,9 @@ use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
 
+use collection::shards::shard::PeerId;
+
+use crate::content_manager::peer_drain::{drain_peer, PeerDrainInfo, PeerDrains};
 use crate::{
     ClusterStatus, CollectionMetaOperations, ConsensusOperations, ConsensusStateRef, StorageError,
     TableOfContent,
@@ -11,6 +14,7 @@ use crate::{
 pub struct Dispatcher {
     toc: Arc<TableOfContent>,
     consensus_state: Option<ConsensusStateRef>,
+    peer_drains: PeerDrains,
 }
 
 impl Dispatcher {
@@ -18,6 +22,7 @@ impl Dispatcher {
         Self {
             toc,
             consensus_state: None,
+            peer_drains: Default::default(),
         }
     }
 
@@ -118,6 +123,41 @@ impl Dispatcher {
         }
     }
 
+    /// Move all replicas off the peer in background, and remove the peer from the cluster after that.
+    /// This function needs to be called from a runtime with timers enabled.
+    pub fn start_peer_drain(
+        &self,
+        peer_id: PeerId,
+        wait_timeout: Option<Duration>,
+    ) -> Result<(), StorageError> {
+        let Some(consensus_state) = self.consensus_state.clone() else {
+            return Err(StorageError::bad_request("Distributed mode disabled."));
+        };
+        if !consensus_state.peer_address_by_id().contains_key(&peer_id) {
+            return Err(StorageError::NotFound {
+                description: format!("Peer {peer_id} is not part of the cluster"),
+            });
+        }
+        self.peer_drains.start(peer_id)?;
+
+        let toc = self.toc.clone();
+        let peer_drains = self.peer_drains.clone();
+        tokio::spawn(async move {
+            let result =
+                drain_peer(&toc, &consensus_state, &peer_drains, peer_id, wait_timeout).await;
+            if let Err(err) = &result {
+                log::error!("Failed to drain peer {peer_id}: {err}");
+            }
+            peer_drains.finish(peer_id, result);
+        });
+        Ok(())
+    }
+
+    /// Progress of the peer drain, started on this peer
+    pub fn peer_drain_info(&self, peer_id: PeerId) -> Option<PeerDrainInfo> {
+        self.peer_drains.get(peer_id)
+    }
+
     pub fn cluster_status(&self) -> ClusterStatus {
         match self.consensus_state.as_ref() {
             Some(state) => state.cluster_status(),
@@ -139,6 +179,7 @@ impl Clone for Dispatcher {
         Self {
             toc: self.toc.clone(),
             consensus_state: self.consensus_state.clone(),
+            peer_drains: self.peer_drains.clone(),
         }
     }
 }
@@ -6,6 +6,7 @@ use collection::config::WalConfig;
 use collection::operations::shared_storage_config::SharedStorageConfig;
 use collection::operations::types::NodeType;
 use collection::optimizers_builder::OptimizersConfig;
+use collection::shards::replica_failover::ReplicaFailoverConfig;
 use collection::shards::shard::PeerId;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
@@ -26,6 +27,16 @@ pub struct PerformanceConfig {
     pub update_rate_limit: Option<usize>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub search_timeout_sec: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_group_commit_window_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_group_commit_max_ops: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_op_id_window: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimizer_tasks_retention_sec: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_usage_refresh_interval_sec: Option<u64>,
 }
 
 const fn default_max_optimization_threads() -> usize {
@@ -60,6 +71,9 @@ pub struct StorageConfig {
     pub node_type: NodeType,
     #[serde(default)]
     pub update_queue_size: Option<usize>,
+    /// If true - collections and shards, which fail to load, do not prevent the node from starting.
+    /// Failed collections are marked as broken until recovered or dropped,
+    /// failed shards are replaced by dummy shards.
     #[serde(default)]
     pub handle_collection_load_errors: bool,
     #[serde(default)]
@@ -69,6 +83,15 @@ pub struct StorageConfig {
     /// Provided value will be used error message for unavailable requests.
     #[serde(default)]
     pub recovery_mode: Option<String>,
+    /// If true - replicas, which fell behind, are recovered by transferring only missed operations
+    /// from WAL, if WAL of the source replica still contains them.
+    /// Requires `wal_retain_ops` or `wal_retain_secs` to keep acknowledged operations in WAL.
+    #[serde(default)]
+    pub shard_transfer_wal_delta: bool,
+    /// Replace replicas, which stay dead longer than the grace period, by new replicas on other peers
+    #[serde(default)]
+    #[validate]
+    pub replica_failover: ReplicaFailoverConfig,
 }
 
 impl StorageConfig {
@@ -81,6 +104,18 @@ impl StorageConfig {
             self.performance
                 .search_timeout_sec
                 .map(|x| Duration::from_secs(x as u64)),
+            self.performance
+                .update_group_commit_window_ms
+                .map(Duration::from_millis),
+            self.performance.update_group_commit_max_ops,
+            self.shard_transfer_wal_delta,
+            self.performance.client_op_id_window,
+            self.performance
+                .optimizer_tasks_retention_sec
+                .map(Duration::from_secs),
+            self.performance
+                .resource_usage_refresh_interval_sec
+                .map(Duration::from_secs),
         )
     }
 }
@@ -101,10 +136,26 @@ const fn default_mmap_advice() -> madvise::Advice {
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 pub struct PeerInfo {
     pub uri: String,
+    /// Membership of the peer in the consensus
+    #[serde(default)]
+    pub role: PeerRole,
     // ToDo: How long ago was the last communication? In milliseconds
     // pub last_responded_millis: usize
 }
 
+/// Membership of a peer in the consensus
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, JsonSchema, Deserialize)]
+pub enum PeerRole {
+    // The peer votes in elections and counts toward the quorum.
+    #[default]
+    Voter,
+    // The peer catches up with the consensus, before it gets promoted to a voter.
+    Learner,
+    // The peer stays a learner and hosts listener replicas, which serve reads,
+    // but never lead updates. Configured with the `listener` node type.
+    Listener,
+}
+
 /// Summary information about the current raft state
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 pub struct RaftInfo {
@@ -156,6 +207,31 @@ pub struct MessageSendErrors {
     pub latest_error: Option<String>,
 }
 
+/// State of the consensus WAL and the consensus state machine.
+/// Updated by the consensus thread on each tick.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Default, PartialEq, Eq)]
+pub struct ConsensusTelemetry {
+    /// Raft index of the first entry in consensus WAL
+    pub first_wal_index: Option<u64>,
+    /// Raft index of the last entry in consensus WAL
+    pub last_wal_index: Option<u64>,
+    /// Size of consensus WAL files on disk
+    pub wal_size_bytes: u64,
+    /// Term of the persisted raft hard state
+    pub term: u64,
+    /// Commit index of the persisted raft hard state
+    pub commit: u64,
+    /// Index of the last entry, applied to the state machine of this peer
+    pub last_applied: Option<u64>,
+    /// Raft index of the latest consensus snapshot, saved or installed on this peer
+    pub snapshot_index: u64,
+    /// Whether there is a configuration change, which is not applied yet
+    pub pending_conf_change: bool,
+    /// Index of the last entry sent to each peer.
+    /// Only known if this peer is the leader.
+    pub peers_last_sent_index: HashMap<PeerId, u64>,
+}
+
 /// Description of enabled cluster
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 pub struct ClusterInfo {
@@ -170,6 +246,8 @@ pub struct ClusterInfo {
     /// Consequent failures of message send operations in consensus by peer address.
     /// On the first success to send to that peer - entry is removed from this hashmap.
     pub message_send_failures: HashMap<String, MessageSendErrors>,
+    /// State of the consensus WAL and the consensus state machine
+    pub consensus_telemetry: ConsensusTelemetry,
 }
 
 /// Information about current cluster status and structure
@@ -195,6 +273,7 @@ impl Anonymize for PeerInfo {
     fn anonymize(&self) -> Self {
         PeerInfo {
             uri: self.uri.anonymize(),
+            role: self.role,
         }
     }
 }
@@ -224,6 +303,16 @@ impl Anonymize for ClusterInfo {
             raft_info: self.raft_info.anonymize(),
             consensus_thread_status: self.consensus_thread_status.clone(),
             message_send_failures: self.message_send_failures.clone(),
+            consensus_telemetry: self.consensus_telemetry.anonymize(),
+        }
+    }
+}
+
+impl Anonymize for ConsensusTelemetry {
+    fn anonymize(&self) -> Self {
+        ConsensusTelemetry {
+            peers_last_sent_index: Default::default(),
+            ..self.clone()
         }
     }
 }
@@ -39,6 +39,7 @@ fn test_alias_operation() {
             indexing_threshold: Some(100),
             flush_interval_sec: 2,
             max_optimization_threads: 2,
+            merge_buffer_size: None,
         },
         wal: Default::default(),
         performance: PerformanceConfig {
@@ -46,6 +47,11 @@ fn test_alias_operation() {
             max_optimization_threads: 1,
             update_rate_limit: None,
             search_timeout_sec: None,
+            update_group_commit_window_ms: None,
+            update_group_commit_max_ops: None,
+            client_op_id_window: None,
+            optimizer_tasks_retention_sec: None,
+            resource_usage_refresh_interval_sec: None,
         },
         hnsw_index: Default::default(),
         quantization: None,
@@ -55,6 +61,8 @@ fn test_alias_operation() {
         handle_collection_load_errors: false,
         recovery_mode: None,
         async_scorer: false,
+        shard_transfer_wal_delta: false,
+        replica_failover: Default::default(),
     };
 
     let search_runtime = Runtime::new().unwrap();
@@ -90,17 +98,25 @@ fn test_alias_operation() {
                             hnsw_config: None,
                             quantization_config: None,
                             on_disk: None,
+                            datatype: None,
+                            multivector_config: None,
                         }
                         .into(),
                         hnsw_config: None,
                         wal_config: None,
                         optimizers_config: None,
                         shard_number: Some(1),
+                        sharding_method: None,
                         on_disk_payload: None,
+                        tenant_key: None,
                         replication_factor: None,
                         write_consistency_factor: None,
                         init_from: None,
                         quantization_config: None,
+                        sparse_vectors: None,
+                        store_original_vectors: None,
+                        search_timeout_sec: None,
+                        strict_mode_config: None,
                     },
                 )),
                 None,
@@ -148,4 +164,28 @@ fn test_alias_operation() {
     let _ = handle
         .block_on(dispatcher.get_collection("test_alias3"))
         .unwrap();
+
+    // Failed action discards the preceding actions of the same operation
+    assert!(handle
+        .block_on(dispatcher.submit_collection_meta_op(
+            CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation {
+                actions: vec![
+                    DeleteAlias {
+                        alias_name: "test_alias3".to_string(),
+                    }
+                    .into(),
+                    CreateAlias {
+                        collection_name: "missing".to_string(),
+                        alias_name: "test_alias3".to_string(),
+                    }
+                    .into(),
+                ],
+            }),
+            None,
+        ))
+        .is_err());
+
+    let _ = handle
+        .block_on(dispatcher.get_collection("test_alias3"))
+        .unwrap();
 }
@@ -0,0 +1,272 @@
+use std::num::NonZeroU64;
+use std::path::Path;
+use std::sync::Arc;
+
+use collection::config::COLLECTION_CONFIG_FILE;
+use collection::operations::point_ops::{Batch, WriteOrdering};
+use collection::operations::shard_key_selector::ShardSelectorInternal;
+use collection::operations::types::{CountRequest, VectorParams};
+use collection::operations::CollectionUpdateOperations;
+use collection::optimizers_builder::OptimizersConfig;
+use segment::madvise;
+use segment::types::Distance;
+use storage::content_manager::collection_meta_ops::{
+    CollectionMetaOperations, CreateCollection, CreateCollectionOperation,
+};
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::toc::{TableOfContent, COLLECTIONS_DIR};
+use storage::dispatcher::Dispatcher;
+use storage::types::{PerformanceConfig, StorageConfig};
+use tempfile::Builder;
+use tokio::runtime::{Handle, Runtime};
+
+const HEALTHY: &str = "healthy";
+const BROKEN: &str = "broken";
+
+fn storage_config(storage_path: &Path) -> StorageConfig {
+    StorageConfig {
+        storage_path: storage_path.to_str().unwrap().to_string(),
+        snapshots_path: storage_path.join("snapshots").to_str().unwrap().to_string(),
+        temp_path: None,
+        on_disk_payload: false,
+        optimizers: OptimizersConfig {
+            deleted_threshold: 0.5,
+            vacuum_min_vector_number: 100,
+            default_segment_number: 2,
+            max_segment_size: None,
+            memmap_threshold: Some(100),
+            indexing_threshold: Some(100),
+            flush_interval_sec: 2,
+            max_optimization_threads: 2,
+            merge_buffer_size: None,
+        },
+        wal: Default::default(),
+        performance: PerformanceConfig {
+            max_search_threads: 1,
+            max_optimization_threads: 1,
+            update_rate_limit: None,
+            search_timeout_sec: None,
+            update_group_commit_window_ms: None,
+            update_group_commit_max_ops: None,
+            client_op_id_window: None,
+            optimizer_tasks_retention_sec: None,
+            resource_usage_refresh_interval_sec: None,
+        },
+        hnsw_index: Default::default(),
+        quantization: None,
+        mmap_advice: madvise::Advice::Random,
+        node_type: Default::default(),
+        update_queue_size: Default::default(),
+        handle_collection_load_errors: true,
+        recovery_mode: None,
+        async_scorer: false,
+        shard_transfer_wal_delta: false,
+        replica_failover: Default::default(),
+    }
+}
+
+/// Open the storage, returns the handle of the runtime to run requests in
+fn open_storage(storage_path: &Path) -> (Dispatcher, Handle) {
+    let search_runtime = Runtime::new().unwrap();
+    let handle = search_runtime.handle().clone();
+    let toc = Arc::new(TableOfContent::new(
+        &storage_config(storage_path),
+        search_runtime,
+        Runtime::new().unwrap(),
+        Runtime::new().unwrap(),
+        Default::default(),
+        0,
+        None,
+    ));
+    (Dispatcher::new(toc), handle)
+}
+
+fn create_collection(dispatcher: &Dispatcher, handle: &Handle, name: &str) {
+    let operation = CreateCollection {
+        vectors: VectorParams {
+            size: NonZeroU64::new(4).unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            datatype: None,
+            multivector_config: None,
+        }
+        .into(),
+        hnsw_config: None,
+        wal_config: None,
+        optimizers_config: None,
+        shard_number: Some(1),
+        sharding_method: None,
+        on_disk_payload: None,
+        tenant_key: None,
+        replication_factor: None,
+        write_consistency_factor: None,
+        init_from: None,
+        quantization_config: None,
+        sparse_vectors: None,
+        store_original_vectors: None,
+        search_timeout_sec: None,
+        strict_mode_config: None,
+    };
+    handle
+        .block_on(dispatcher.submit_collection_meta_op(
+            CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+                name.to_string(),
+                operation,
+            )),
+            None,
+        ))
+        .unwrap();
+}
+
+fn upsert_points(dispatcher: &Dispatcher, handle: &Handle, name: &str, count: u64) {
+    let operation = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..count).map(|id| id.into()).collect(),
+            vectors: (0..count)
+                .map(|id| vec![1.0, id as f32, 0.0, 1.0])
+                .collect::<Vec<_>>()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    handle
+        .block_on(dispatcher.toc().update(
+            name,
+            operation,
+            ShardSelectorInternal::All,
+            None,
+            true,
+            WriteOrdering::default(),
+        ))
+        .unwrap();
+}
+
+fn count_points(dispatcher: &Dispatcher, handle: &Handle, name: &str) -> usize {
+    let request = CountRequest {
+        filter: None,
+        exact: true,
+    };
+    handle
+        .block_on(dispatcher.toc().count(name, request, None))
+        .unwrap()
+        .count
+}
+
+#[test]
+fn test_broken_collection_does_not_prevent_startup() {
+    let storage_dir = Builder::new().prefix("storage").tempdir().unwrap();
+
+    {
+        let (dispatcher, handle) = open_storage(storage_dir.path());
+        create_collection(&dispatcher, &handle, HEALTHY);
+        create_collection(&dispatcher, &handle, BROKEN);
+        upsert_points(&dispatcher, &handle, HEALTHY, 10);
+        upsert_points(&dispatcher, &handle, BROKEN, 5);
+    }
+
+    let config_path = storage_dir
+        .path()
+        .join(COLLECTIONS_DIR)
+        .join(BROKEN)
+        .join(COLLECTION_CONFIG_FILE);
+    let config = std::fs::read(&config_path).unwrap();
+    std::fs::write(&config_path, b"{ not a json").unwrap();
+
+    let (dispatcher, handle) = open_storage(storage_dir.path());
+    let toc = dispatcher.toc();
+
+    // Healthy collection is loaded and fully functional
+    assert_eq!(handle.block_on(toc.all_collections()), vec![HEALTHY]);
+    assert_eq!(count_points(&dispatcher, &handle, HEALTHY), 10);
+    upsert_points(&dispatcher, &handle, HEALTHY, 20);
+    assert_eq!(count_points(&dispatcher, &handle, HEALTHY), 20);
+
+    // Broken collection is reported with the load error
+    let broken = toc.broken_collections();
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].0, BROKEN);
+    assert!(broken[0].1.contains("config"), "{}", broken[0].1);
+
+    // Operations with the broken collection fail with a clear error
+    let result = handle.block_on(toc.get_collection(BROKEN));
+    assert!(matches!(
+        result.err(),
+        Some(StorageError::BrokenCollection { .. })
+    ));
+    let result = handle.block_on(toc.count(
+        BROKEN,
+        CountRequest {
+            filter: None,
+            exact: true,
+        },
+        None,
+    ));
+    assert!(matches!(result, Err(StorageError::BrokenCollection { .. })));
+
+    // Broken collection can't be replaced by a new one with the same name
+    let result = handle.block_on(
+        dispatcher.submit_collection_meta_op(
+            CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+                BROKEN.to_string(),
+                serde_json::from_value(serde_json::json!({
+                    "vectors": { "size": 4, "distance": "Dot" }
+                }))
+                .unwrap(),
+            )),
+            None,
+        ),
+    );
+    assert!(matches!(result, Err(StorageError::BadInput { .. })));
+
+    // Recovery fails, until the collection is fixed
+    let result = handle.block_on(toc.recover_broken_collection(BROKEN));
+    assert!(matches!(result, Err(StorageError::BrokenCollection { .. })));
+    assert_eq!(toc.broken_collections().len(), 1);
+
+    std::fs::write(&config_path, config).unwrap();
+    handle
+        .block_on(toc.recover_broken_collection(BROKEN))
+        .unwrap();
+    assert!(toc.broken_collections().is_empty());
+    assert_eq!(count_points(&dispatcher, &handle, BROKEN), 5);
+    assert_eq!(count_points(&dispatcher, &handle, HEALTHY), 20);
+
+    // Only broken collections are recovered
+    let result = handle.block_on(toc.recover_broken_collection(HEALTHY));
+    assert!(matches!(result, Err(StorageError::NotFound { .. })));
+}
+
+#[test]
+fn test_drop_broken_collection() {
+    let storage_dir = Builder::new().prefix("storage").tempdir().unwrap();
+
+    {
+        let (dispatcher, handle) = open_storage(storage_dir.path());
+        create_collection(&dispatcher, &handle, HEALTHY);
+        create_collection(&dispatcher, &handle, BROKEN);
+        upsert_points(&dispatcher, &handle, HEALTHY, 10);
+    }
+
+    let collection_path = storage_dir.path().join(COLLECTIONS_DIR).join(BROKEN);
+    std::fs::write(collection_path.join(COLLECTION_CONFIG_FILE), b"").unwrap();
+
+    let (dispatcher, handle) = open_storage(storage_dir.path());
+    let toc = dispatcher.toc();
+    assert_eq!(toc.broken_collections().len(), 1);
+
+    // Only broken collections are dropped by the admin action
+    let result = handle.block_on(toc.drop_broken_collection(HEALTHY));
+    assert!(matches!(result, Err(StorageError::NotFound { .. })));
+
+    handle.block_on(toc.drop_broken_collection(BROKEN)).unwrap();
+    assert!(toc.broken_collections().is_empty());
+    assert!(!collection_path.exists());
+    assert_eq!(count_points(&dispatcher, &handle, HEALTHY), 10);
+
+    // The name is free for a new collection
+    create_collection(&dispatcher, &handle, BROKEN);
+    assert_eq!(count_points(&dispatcher, &handle, BROKEN), 0);
+}
@@ -1,2 +1,4 @@
 #[cfg(test)]
 pub mod alias_tests;
+#[cfg(test)]
+pub mod broken_collection_tests;
@@ -311,3 +311,67 @@ def test_batch_update_payload():
             },
         ]
     )
+
+
+def test_batch_update_statuses():
+    # Later operations rely on the points of the earlier ones, failed operation doesn't stop the batch
+    response = request_with_validation(
+        api="/collections/{collection_name}/points/batch",
+        method="POST",
+        path_params={"collection_name": collection_name},
+        body=[
+            {
+                "upsert": {
+                    "points": [
+                        {
+                            "id": 100,
+                            "vector": [1.0, 2.0, 3.0, 4.0],
+                            "payload": {},
+                        },
+                    ]
+                }
+            },
+            {
+                "set_payload": {
+                    "payload": {"test_payload": "1"},
+                    "points": [100],
+                }
+            },
+            {
+                "update_vectors": {
+                    "points": [
+                        {
+                            "id": 1000,
+                            "vector": [1.0, 2.0, 3.0, 4.0],
+                        },
+                    ]
+                }
+            },
+            {
+                "set_payload": {
+                    "payload": {"test_payload_2": "2"},
+                    "points": [100],
+                }
+            },
+        ],
+        query_params={"wait": "true"},
+    )
+    assert response.ok
+
+    result = response.json()["result"]
+    assert [r["status"] for r in result] == ["completed", "completed", "failed", "completed"]
+    assert "1000" in result[2]["error"]
+    assert all("error" not in r for i, r in enumerate(result) if i != 2)
+
+    assert_points(
+        [
+            {
+                "id": 100,
+                "payload": {
+                    "test_payload": "1",
+                    "test_payload_2": "2",
+                },
+            },
+        ],
+        nonexisting_ids=[1000],
+    )
@@ -276,3 +276,69 @@ def test_payload_operations():
     )
     assert response.ok
     assert len(response.json()['result']['points']) == 0
+
+
+def test_set_payload_by_key():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "payload": {"metadata": {"author": "alice", "stats": "none"}, "items": [{"views": 1}, {"views": 2}]},
+            "points": [6]
+        }
+    )
+    assert response.ok
+
+    # nested key by filter
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "payload": {"views": 10},
+            "key": "metadata.stats",
+            "filter": {"must": [{"has_id": [6]}]}
+        }
+    )
+    assert response.ok
+
+    # every element of the array
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "payload": {"seen": True},
+            "key": "items[]",
+            "points": [6]
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/{id}',
+        method="GET",
+        path_params={'collection_name': collection_name, 'id': 6},
+    )
+    assert response.ok
+    assert response.json()['result']['payload'] == {
+        "metadata": {"author": "alice", "stats": {"views": 10}},
+        "items": [{"views": 1, "seen": True}, {"views": 2, "seen": True}],
+    }
+
+    # key is not allowed for overwrite
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/payload',
+        method="PUT",
+        path_params={'collection_name': collection_name},
+        body={
+            "payload": {"views": 11},
+            "key": "metadata.stats",
+            "points": [6]
+        }
+    )
+    assert response.status_code == 400
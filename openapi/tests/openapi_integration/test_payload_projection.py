@@ -0,0 +1,89 @@
+import pytest
+
+from .helpers.collection_setup import drop_collection
+from .helpers.fixtures import on_disk_vectors, on_disk_payload
+from .helpers.helpers import request_with_validation
+from .test_nested_payload_indexing import nested_payload_collection_setup
+
+collection_name = 'test_collection_payload_projection'
+
+
+@pytest.fixture(autouse=True)
+def setup(on_disk_vectors, on_disk_payload):
+    nested_payload_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors, on_disk_payload=on_disk_payload)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def retrieve_payload(with_payload):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "ids": [1],
+            "with_payload": with_payload,
+        }
+    )
+    assert response.ok
+    return response.json()['result'][0]['payload']
+
+
+def test_nested_include():
+    payload = retrieve_payload({"include": ["country.capital", "country.cities[].name"]})
+    assert payload == {
+        "country": {
+            "capital": "Berlin",
+            "cities": [{"name": "Berlin"}, {"name": "Munich"}, {"name": "Hamburg"}],
+        }
+    }
+
+    payload = retrieve_payload({"include": ["country.cities[1].location.lat"]})
+    assert payload == {"country": {"cities": [{"location": {"lat": 48.13743}}]}}
+
+    # Top-level include keeps the whole value
+    payload = retrieve_payload(["country"])
+    assert payload["country"]["name"] == "Germany"
+    assert len(payload["country"]["cities"]) == 3
+
+
+def test_nested_exclude():
+    payload = retrieve_payload({"exclude": ["country.cities"]})
+    assert payload == {"country": {"name": "Germany", "capital": "Berlin"}}
+
+    payload = retrieve_payload({"exclude": ["country.cities[].location", "country.cities[].sightseeing"]})
+    assert payload["country"]["cities"][0] == {"name": "Berlin", "population": 3.7}
+
+    payload = retrieve_payload({"exclude": ["country"]})
+    assert payload == {}
+
+
+def test_projection_in_search_and_scroll():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": [0.05, 0.61, 0.76, 0.74],
+            "limit": 1,
+            "filter": {"must": [{"has_id": [1]}]},
+            "with_payload": {"include": ["country.cities[].name"]},
+        }
+    )
+    assert response.ok
+    point = response.json()['result'][0]
+    assert point['payload'] == {"country": {"cities": [{"name": "Berlin"}, {"name": "Munich"}, {"name": "Hamburg"}]}}
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/scroll',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "limit": 1,
+            "filter": {"must": [{"has_id": [1]}]},
+            "with_payload": {"exclude": ["country.cities"]},
+        }
+    )
+    assert response.ok
+    point = response.json()['result']['points'][0]
+    assert point['payload'] == {"country": {"name": "Germany", "capital": "Berlin"}}
@@ -29,6 +29,65 @@ def test_metrics():
     assert 'collections_total ' in response.text
 
 
+def parse_metrics(text):
+    """Values of the metric series by their name with labels"""
+    series = {}
+    for line in text.splitlines():
+        if not line or line.startswith('#'):
+            continue
+        name, value = line.rsplit(' ', 1)
+        series[name] = float(value)
+    return series
+
+
+def test_collection_metrics():
+    for _ in range(3):
+        response = request_with_validation(
+            api='/collections/{collection_name}/points/search',
+            method="POST",
+            path_params={'collection_name': collection_name},
+            body={
+                "vector": [0.2, 0.1, 0.9, 0.7],
+                "limit": 3
+            }
+        )
+        assert response.ok
+
+    response = request_with_validation(
+        api='/metrics',
+        method="GET",
+    )
+    assert response.ok
+    series = parse_metrics(response.text)
+
+    labels = f'collection="{collection_name}"'
+    assert series[f'collection_searches_total{{{labels}}}'] >= 3
+    assert series[f'collection_search_duration_seconds_count{{{labels}}}'] >= 3
+    assert series[f'collection_search_duration_seconds_bucket{{{labels},le="+Inf"}}'] >= 3
+    assert 0 < series[f'collection_search_duration_seconds_sum{{{labels}}}'] < 60
+    # Points are upserted by the collection setup
+    assert series[f'collection_updates_total{{{labels}}}'] >= 1
+    assert f'collection_optimizations_total{{{labels}}}' in series
+    assert f'collection_hnsw_build_duration_seconds_count{{{labels}}}' in series
+
+    wal_length = [
+        value for name, value in series.items()
+        if name.startswith(f'collection_shard_wal_length{{{labels},shard=')
+    ]
+    assert len(wal_length) >= 1
+    assert all(value >= 0 for value in wal_length)
+    flush_age = [
+        value for name, value in series.items()
+        if name.startswith(f'collection_shard_last_flush_age_seconds{{{labels},shard=')
+    ]
+    assert all(value >= 0 for value in flush_age)
+
+    endpoint = 'method="POST",endpoint="/collections/{name}/points/search",status="200"'
+    assert series[f'rest_responses_total{{{endpoint}}}'] >= 3
+    assert series[f'rest_responses_duration_seconds_count{{{endpoint}}}'] >= 3
+    assert series[f'rest_responses_duration_seconds_bucket{{{endpoint},le="+Inf"}}'] >= 3
+
+
 def test_telemetry():
     response = request_with_validation(
         api='/telemetry',
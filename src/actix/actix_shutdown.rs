@@ -0,0 +1,76 @@
+use std::future::{ready, Ready};
+
+use actix_web::body::{BoxBody, EitherBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{Error, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+
+use crate::common::shutdown::ShutdownRef;
+
+/// Rejects new requests while the service is shutting down, and tracks the requests in progress
+pub struct ActixShutdownTransform {
+    shutdown: ShutdownRef,
+}
+
+pub struct ActixShutdownService<S> {
+    service: S,
+    shutdown: ShutdownRef,
+}
+
+impl ActixShutdownTransform {
+    pub fn new(shutdown: ShutdownRef) -> Self {
+        Self { shutdown }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ActixShutdownTransform
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type Transform = ActixShutdownService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ActixShutdownService {
+            service,
+            shutdown: self.shutdown.clone(),
+        }))
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for ActixShutdownService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let Some(guard) = self.shutdown.start_request() else {
+            return Box::pin(async {
+                Ok(request
+                    .into_response(
+                        HttpResponse::ServiceUnavailable().body("Service is shutting down"),
+                    )
+                    .map_into_right_body())
+            });
+        };
+
+        let future = self.service.call(request);
+        Box::pin(async move {
+            let response = future.await?;
+            drop(guard);
+            Ok(response.map_into_left_body())
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use std::future::{ready, Ready};
+
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+use tracing::Instrument;
+
+use crate::common::trace_context::{request_span, TRACEPARENT_HEADER};
+
+pub struct ActixTracingService<S> {
+    service: S,
+    sampling_ratio: f64,
+}
+
+pub struct ActixTracingTransform {
+    sampling_ratio: f64,
+}
+
+/// Actix tracing service. It runs every request within a request span,
+/// which continues the trace of the `traceparent` header.
+impl<S, B> Service<ServiceRequest> for ActixTracingService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let match_pattern = request
+            .match_pattern()
+            .unwrap_or_else(|| "unknown".to_owned());
+        let endpoint = format!("{} {}", request.method(), match_pattern);
+        let traceparent = request
+            .headers()
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| value.to_str().ok());
+        let span = request_span("rest", &endpoint, traceparent, self.sampling_ratio);
+        let future = span.in_scope(|| self.service.call(request));
+        Box::pin(future.instrument(span))
+    }
+}
+
+impl ActixTracingTransform {
+    pub fn new(sampling_ratio: f64) -> Self {
+        Self { sampling_ratio }
+    }
+}
+
+/// Actix tracing transform. It's a builder for an actix service
+impl<S, B> Transform<S, ServiceRequest> for ActixTracingTransform
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = ActixTracingService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ActixTracingService {
+            service,
+            sampling_ratio: self.sampling_ratio,
+        }))
+    }
+}
@@ -15,6 +15,8 @@ struct QueryParams {
     #[serde(default)]
     force: bool,
     #[serde(default)]
+    drain: bool,
+    #[serde(default)]
     #[validate(range(min = 1))]
     timeout: Option<u64>,
 }
@@ -42,6 +44,19 @@ async fn remove_peer(
     let dispatcher = dispatcher.into_inner();
     let peer_id = peer_id.into_inner();
 
+    if params.drain {
+        let response = if params.force {
+            Err(StorageError::BadRequest {
+                description: "Cannot both drain and force remove a peer".to_string(),
+            })
+        } else {
+            dispatcher
+                .start_peer_drain(peer_id, params.timeout.map(std::time::Duration::from_secs))
+                .map(|_| true)
+        };
+        return process_response(response, timing);
+    }
+
     let has_shards = dispatcher.peer_has_shards(peer_id).await;
     if !params.force && has_shards {
         return process_response::<()>(
@@ -68,9 +83,25 @@ async fn remove_peer(
     process_response(response, timing)
 }
 
+#[get("/cluster/peer/{peer_id}/drain")]
+async fn peer_drain_info(
+    dispatcher: web::Data<Dispatcher>,
+    peer_id: web::Path<u64>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let peer_id = peer_id.into_inner();
+    let response = dispatcher
+        .peer_drain_info(peer_id)
+        .ok_or_else(|| StorageError::NotFound {
+            description: format!("Peer {peer_id} is not drained by this peer"),
+        });
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_cluster_api(cfg: &mut web::ServiceConfig) {
     cfg.service(cluster_status)
         .service(remove_peer)
+        .service(peer_drain_info)
         .service(recover_current_peer);
 }
@@ -3,6 +3,7 @@ use std::time::Duration;
 use actix_web::rt::time::Instant;
 use actix_web::{delete, get, patch, post, put, web, Responder};
 use actix_web_validator::{Json, Path, Query};
+use collection::common::search_recall::RecallTestRequest;
 use collection::operations::cluster_ops::ClusterOperations;
 use serde::Deserialize;
 use storage::content_manager::collection_meta_ops::{
@@ -16,6 +17,7 @@ use validator::Validate;
 use super::CollectionPath;
 use crate::actix::api::StrictCollectionPath;
 use crate::actix::helpers::process_response;
+use crate::common::auth::Access;
 use crate::common::collections::*;
 
 #[derive(Debug, Deserialize, Validate)]
@@ -31,16 +33,58 @@ impl WaitTimeout {
 }
 
 #[get("/collections")]
-async fn get_collections(toc: web::Data<TableOfContent>) -> impl Responder {
+async fn get_collections(
+    toc: web::Data<TableOfContent>,
+    access: Option<web::ReqData<Access>>,
+) -> impl Responder {
     let timing = Instant::now();
-    let response = Ok(do_list_collections(toc.get_ref()).await);
+    let response = Ok(do_list_collections(toc.get_ref(), access.as_deref()).await);
     process_response(response, timing)
 }
 
 #[get("/aliases")]
-async fn get_aliases(toc: web::Data<TableOfContent>) -> impl Responder {
+async fn get_aliases(
+    toc: web::Data<TableOfContent>,
+    access: Option<web::ReqData<Access>>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_list_aliases(toc.get_ref(), access.as_deref()).await;
+    process_response(response, timing)
+}
+
+#[get("/broken_collections")]
+async fn get_broken_collections(
+    toc: web::Data<TableOfContent>,
+    access: Option<web::ReqData<Access>>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = Ok(do_list_broken_collections(toc.get_ref(), access.as_deref()));
+    process_response(response, timing)
+}
+
+#[post("/broken_collections/{name}/recover")]
+async fn recover_broken_collection(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = toc
+        .recover_broken_collection(&collection.name)
+        .await
+        .map(|()| true);
+    process_response(response, timing)
+}
+
+#[delete("/broken_collections/{name}")]
+async fn drop_broken_collection(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
     let timing = Instant::now();
-    let response = do_list_aliases(toc.get_ref()).await;
+    let response = toc
+        .drop_broken_collection(&collection.name)
+        .await
+        .map(|()| true);
     process_response(response, timing)
 }
 
@@ -54,6 +98,82 @@ async fn get_collection(
     process_response(response, timing)
 }
 
+#[get("/collections/{name}/exists")]
+async fn get_collection_existence(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = Ok(do_collection_exists(toc.get_ref(), &collection.name).await);
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/index_suggestions")]
+async fn get_index_suggestions(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_index_suggestions(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/optimizer_tasks")]
+async fn get_optimization_tasks(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_optimization_tasks(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/resource_usage")]
+async fn get_resource_usage(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_resource_usage(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/recall_test")]
+async fn recall_test(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<RecallTestRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_recall_test(toc.get_ref(), &collection.name, request.into_inner()).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/optimizer_tasks/{task_id}/cancel")]
+async fn cancel_optimization_task(
+    toc: web::Data<TableOfContent>,
+    path: web::Path<(String, u64)>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let (collection_name, task_id) = path.into_inner();
+    let response = do_cancel_optimization_task(toc.get_ref(), &collection_name, task_id)
+        .await
+        .map(|()| true);
+    process_response(response, timing)
+}
+
+#[delete("/collections/{name}/index_suggestions")]
+async fn reset_index_suggestions(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_reset_index_suggestions(toc.get_ref(), &collection.name)
+        .await
+        .map(|()| true);
+    process_response(response, timing)
+}
+
 #[get("/collections/{name}/aliases")]
 async fn get_collection_aliases(
     toc: web::Data<TableOfContent>,
@@ -174,6 +294,13 @@ async fn update_collection_cluster(
 pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
     cfg.service(get_collections)
         .service(get_collection)
+        .service(get_collection_existence)
+        .service(get_index_suggestions)
+        .service(reset_index_suggestions)
+        .service(get_optimization_tasks)
+        .service(cancel_optimization_task)
+        .service(get_resource_usage)
+        .service(recall_test)
         .service(create_collection)
         .service(update_collection)
         .service(delete_collection)
@@ -181,7 +308,10 @@ pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
         .service(get_collection_aliases)
         .service(update_aliases)
         .service(get_cluster_info)
-        .service(update_collection_cluster);
+        .service(update_collection_cluster)
+        .service(get_broken_collections)
+        .service(recover_broken_collection)
+        .service(drop_broken_collection);
 }
 
 #[cfg(test)]
@@ -1,23 +1,32 @@
 use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
 use actix_web_validator::{Json, Path};
-use collection::operations::types::CountRequest;
+use collection::operations::shard_key_selector::ShardSelectorInternal;
 use storage::content_manager::toc::TableOfContent;
 
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
-use crate::common::points::do_count_points;
+use crate::common::points::{do_count_points, CountPoints};
 
 #[post("/collections/{name}/points/count")]
 async fn count_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    request: Json<CountRequest>,
+    request: Json<CountPoints>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let CountPoints {
+        count_request,
+        shard_key,
+    } = request.into_inner();
 
-    let response =
-        do_count_points(toc.get_ref(), &collection.name, request.into_inner(), None).await;
+    let response = do_count_points(
+        toc.get_ref(),
+        &collection.name,
+        count_request,
+        shard_key.map_or(ShardSelectorInternal::Empty, ShardSelectorInternal::from),
+    )
+    .await;
 
     process_response(response, timing)
 }
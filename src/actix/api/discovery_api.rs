@@ -0,0 +1,30 @@
+use actix_web::rt::time::Instant;
+use actix_web::{post, web, Responder};
+use actix_web_validator::{Json, Path, Query};
+use collection::operations::types::DiscoverRequest;
+use storage::content_manager::toc::TableOfContent;
+
+use super::read_params::ReadParams;
+use super::CollectionPath;
+use crate::actix::helpers::process_response;
+
+#[post("/collections/{name}/points/discover")]
+async fn discover_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<DiscoverRequest>,
+    params: Query<ReadParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = toc
+        .discover(&collection.name, request.into_inner(), params.consistency)
+        .await;
+
+    process_response(response, timing)
+}
+
+// Configure services
+pub fn config_discovery_api(cfg: &mut web::ServiceConfig) {
+    cfg.service(discover_points);
+}
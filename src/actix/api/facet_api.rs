@@ -0,0 +1,28 @@
+use actix_web::rt::time::Instant;
+use actix_web::{post, web, Responder};
+use actix_web_validator::{Json, Path};
+use collection::operations::types::FacetRequest;
+use storage::content_manager::toc::TableOfContent;
+
+use super::CollectionPath;
+use crate::actix::helpers::process_response;
+
+#[post("/collections/{name}/facet")]
+async fn facet(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<FacetRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = toc
+        .facet(&collection.name, request.into_inner(), None)
+        .await;
+
+    process_response(response, timing)
+}
+
+// Configure services
+pub fn config_facet_api(cfg: &mut web::ServiceConfig) {
+    cfg.service(facet);
+}
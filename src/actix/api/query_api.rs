@@ -0,0 +1,35 @@
+use actix_web::rt::time::Instant;
+use actix_web::{post, web, Responder};
+use actix_web_validator::{Json, Path, Query};
+use collection::operations::types::QueryRequest;
+use storage::content_manager::toc::TableOfContent;
+
+use super::read_params::ReadParams;
+use super::CollectionPath;
+use crate::actix::helpers::process_response;
+
+#[post("/collections/{name}/points/query")]
+async fn query_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<QueryRequest>,
+    params: Query<ReadParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = toc
+        .query(
+            &collection.name,
+            request.into_inner(),
+            params.consistency,
+            None,
+        )
+        .await;
+
+    process_response(response, timing)
+}
+
+// Configure services
+pub fn config_query_api(cfg: &mut web::ServiceConfig) {
+    cfg.service(query_points);
+}
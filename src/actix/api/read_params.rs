@@ -1,3 +1,6 @@
+use std::num::NonZeroU64;
+use std::time::Duration;
+
 use collection::operations::consistency_params::ReadConsistency;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -8,6 +11,15 @@ pub struct ReadParams {
     #[serde(default, deserialize_with = "deserialize_read_consistency")]
     #[validate]
     pub consistency: Option<ReadConsistency>,
+    /// If set, overrides collection and global timeouts of searches, in seconds
+    pub timeout: Option<NonZeroU64>,
+}
+
+impl ReadParams {
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+            .map(|timeout| Duration::from_secs(timeout.get()))
+    }
 }
 
 fn deserialize_read_consistency<'de, D>(
@@ -67,6 +79,18 @@ mod test {
         assert!(try_deserialize(&str("0")).is_err());
     }
 
+    #[test]
+    fn deserialize_timeout() {
+        let params = deserialize("consistency=all&timeout=5");
+        assert_eq!(
+            params.consistency,
+            from_type(ReadConsistencyType::All).consistency
+        );
+        assert_eq!(params.timeout(), Some(Duration::from_secs(5)));
+
+        assert!(try_deserialize("timeout=0").is_err());
+    }
+
     fn test(value: &str, params: ReadParams) {
         test_str(&str(value), params);
     }
@@ -90,12 +114,14 @@ mod test {
     fn from_type(r#type: ReadConsistencyType) -> ReadParams {
         ReadParams {
             consistency: Some(ReadConsistency::Type(r#type)),
+            timeout: None,
         }
     }
 
     fn from_factor(factor: usize) -> ReadParams {
         ReadParams {
             consistency: Some(ReadConsistency::Factor(factor)),
+            timeout: None,
         }
     }
 }
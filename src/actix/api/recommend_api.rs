@@ -2,6 +2,7 @@ use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::consistency_params::ReadConsistency;
+use collection::operations::shard_key_selector::ShardSelectorInternal;
 use collection::operations::types::{
     RecommendGroupsRequest, RecommendRequest, RecommendRequestBatch,
 };
@@ -12,14 +13,16 @@ use storage::content_manager::toc::TableOfContent;
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
+use crate::common::points::{RecommendBatchPoints, RecommendPoints};
 
 async fn do_recommend_points(
     toc: &TableOfContent,
     collection_name: &str,
     request: RecommendRequest,
     read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
 ) -> Result<Vec<ScoredPoint>, StorageError> {
-    toc.recommend(collection_name, request, read_consistency)
+    toc.recommend(collection_name, request, read_consistency, shard_selection)
         .await
 }
 
@@ -27,16 +30,21 @@ async fn do_recommend_points(
 async fn recommend_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    request: Json<RecommendRequest>,
+    request: Json<RecommendPoints>,
     params: Query<ReadParams>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let RecommendPoints {
+        recommend_request,
+        shard_key,
+    } = request.into_inner();
 
     let response = do_recommend_points(
         toc.get_ref(),
         &collection.name,
-        request.into_inner(),
+        recommend_request,
         params.consistency,
+        shard_key.map_or(ShardSelectorInternal::Empty, ShardSelectorInternal::from),
     )
     .await;
 
@@ -48,8 +56,9 @@ async fn do_recommend_batch_points(
     collection_name: &str,
     request: RecommendRequestBatch,
     read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
 ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
-    toc.recommend_batch(collection_name, request, read_consistency)
+    toc.recommend_batch(collection_name, request, read_consistency, shard_selection)
         .await
 }
 
@@ -57,16 +66,21 @@ async fn do_recommend_batch_points(
 async fn recommend_batch_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    request: Json<RecommendRequestBatch>,
+    request: Json<RecommendBatchPoints>,
     params: Query<ReadParams>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let RecommendBatchPoints {
+        recommend_batch,
+        shard_key,
+    } = request.into_inner();
 
     let response = do_recommend_batch_points(
         toc.get_ref(),
         &collection.name,
-        request.into_inner(),
+        recommend_batch,
         params.consistency,
+        shard_key.map_or(ShardSelectorInternal::Empty, ShardSelectorInternal::from),
     )
     .await;
 
@@ -2,6 +2,7 @@ use actix_web::rt::time::Instant;
 use actix_web::{get, post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::consistency_params::ReadConsistency;
+use collection::operations::shard_key_selector::ShardSelectorInternal;
 use collection::operations::types::{PointRequest, Record, ScrollRequest, ScrollResult};
 use segment::types::{PointIdType, WithPayloadInterface};
 use serde::Deserialize;
@@ -12,7 +13,7 @@ use validator::Validate;
 use super::read_params::ReadParams;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
-use crate::common::points::do_get_points;
+use crate::common::points::{do_get_points, ScrollPoints};
 
 #[derive(Deserialize, Validate)]
 struct PointPath {
@@ -31,6 +32,7 @@ async fn do_get_point(
         ids: vec![point_id],
         with_payload: Some(WithPayloadInterface::Bool(true)),
         with_vector: true.into(),
+        with_version: false,
     };
 
     toc.retrieve(collection_name, request, read_consistency, None)
@@ -43,8 +45,9 @@ async fn scroll_get_points(
     collection_name: &str,
     request: ScrollRequest,
     read_consistency: Option<ReadConsistency>,
+    shard_selection: ShardSelectorInternal,
 ) -> Result<ScrollResult, StorageError> {
-    toc.scroll(collection_name, request, read_consistency, None)
+    toc.scroll(collection_name, request, read_consistency, shard_selection)
         .await
 }
 
@@ -114,16 +117,21 @@ async fn get_points(
 async fn scroll_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    request: Json<ScrollRequest>,
+    request: Json<ScrollPoints>,
     params: Query<ReadParams>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let ScrollPoints {
+        scroll_request,
+        shard_key,
+    } = request.into_inner();
 
     let response = scroll_get_points(
         toc.get_ref(),
         &collection.name,
-        request.into_inner(),
+        scroll_request,
         params.consistency,
+        shard_key.map_or(ShardSelectorInternal::Empty, ShardSelectorInternal::from),
     )
     .await;
     process_response(response, timing)
@@ -1,52 +1,83 @@
 use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
-use collection::operations::types::{SearchGroupsRequest, SearchRequest, SearchRequestBatch};
+use collection::common::search_stats::collect_search_stats;
+use collection::operations::shard_key_selector::ShardSelectorInternal;
+use collection::operations::types::{BatchSearchResult, SearchGroupsRequest, SearchPlanRequest};
 use storage::content_manager::toc::TableOfContent;
 
 use super::read_params::ReadParams;
 use super::CollectionPath;
-use crate::actix::helpers::process_response;
-use crate::common::points::{do_search_batch_points, do_search_point_groups, do_search_points};
+use crate::actix::helpers::{process_response, process_response_with_stats};
+use crate::common::points::{
+    do_search_batch_points, do_search_point_groups, do_search_points, SearchBatchPoints,
+    SearchPoints,
+};
 
 #[post("/collections/{name}/points/search")]
 async fn search_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    request: Json<SearchRequest>,
+    request: Json<SearchPoints>,
     params: Query<ReadParams>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let SearchPoints {
+        search_request,
+        shard_key,
+        with_stats,
+    } = request.into_inner();
 
-    let response = do_search_points(
+    let search = do_search_points(
         toc.get_ref(),
         &collection.name,
-        request.into_inner(),
+        search_request,
         params.consistency,
-        None,
-    )
-    .await;
+        shard_key.map_or(ShardSelectorInternal::Empty, ShardSelectorInternal::from),
+        params.timeout(),
+    );
+    let (response, stats) = collect_search_stats(search).await;
 
-    process_response(response, timing)
+    process_response_with_stats(
+        response,
+        timing,
+        with_stats.unwrap_or(false).then_some(stats),
+    )
 }
 
 #[post("/collections/{name}/points/search/batch")]
 async fn batch_search_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    request: Json<SearchRequestBatch>,
+    request: Json<SearchBatchPoints>,
     params: Query<ReadParams>,
 ) -> impl Responder {
     let timing = Instant::now();
+    let SearchBatchPoints {
+        search_batch,
+        shard_key,
+    } = request.into_inner();
 
     let response = do_search_batch_points(
         toc.get_ref(),
         &collection.name,
-        request.into_inner(),
+        search_batch,
         params.consistency,
-        None,
+        shard_key.map_or(ShardSelectorInternal::Empty, ShardSelectorInternal::from),
+        params.timeout(),
     )
-    .await;
+    .await
+    .map(|results| {
+        results
+            .into_iter()
+            .map(|result| match result {
+                Ok(points) => BatchSearchResult::Points(points),
+                Err(err) => BatchSearchResult::Error {
+                    error: err.to_string(),
+                },
+            })
+            .collect::<Vec<_>>()
+    });
 
     process_response(response, timing)
 }
@@ -72,9 +103,25 @@ async fn search_point_groups(
     process_response(response, timing)
 }
 
+#[post("/collections/{name}/points/search/plan")]
+async fn search_plan(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<SearchPlanRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = toc
+        .search_plan(&collection.name, request.into_inner())
+        .await;
+
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_search_api(cfg: &mut web::ServiceConfig) {
     cfg.service(search_points)
         .service(batch_search_points)
-        .service(search_point_groups);
+        .service(search_point_groups)
+        .service(search_plan);
 }
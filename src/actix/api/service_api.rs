@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use actix_web::http::header::ContentType;
 use actix_web::rt::time::Instant;
 use actix_web::web::Query;
@@ -6,12 +8,16 @@ use actix_web_validator::Json;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
+use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
+use storage::dispatcher::Dispatcher;
 use tokio::sync::Mutex;
 
 use crate::actix::helpers::process_response;
 use crate::common::helpers::LocksOption;
+use crate::common::logger::{self, SetLogFilterRequest};
 use crate::common::metrics::MetricsData;
+use crate::common::readiness::Readiness;
 use crate::common::telemetry::TelemetryCollector;
 
 #[derive(Deserialize, Serialize, JsonSchema)]
@@ -50,7 +56,8 @@ async fn metrics(
 ) -> impl Responder {
     let anonymize = params.anonymize.unwrap_or(false);
     let telemetry_collector = telemetry_collector.lock().await;
-    let telemetry_data = telemetry_collector.prepare_data(1).await;
+    // Full collection telemetry, metrics are reported per collection
+    let telemetry_data = telemetry_collector.prepare_data(2).await;
     let telemetry_data = if anonymize {
         telemetry_data.anonymize()
     } else {
@@ -87,10 +94,49 @@ async fn get_locks(toc: web::Data<TableOfContent>) -> impl Responder {
     process_response(Ok(result), timing)
 }
 
+#[post("/logger")]
+async fn put_logger(request: Json<SetLogFilterRequest>) -> impl Responder {
+    let timing = Instant::now();
+    let ttl = request.ttl_sec.map(Duration::from_secs);
+    let result =
+        logger::set_filter(&request.filter, ttl).map_err(|err| StorageError::bad_input(&err));
+    if let Ok(info) = &result {
+        log::info!("Log filter is set to {}", info.filter);
+    }
+    process_response(result, timing)
+}
+
+#[get("/logger")]
+async fn get_logger() -> impl Responder {
+    let timing = Instant::now();
+    let result = logger::filter_info()
+        .ok_or_else(|| StorageError::service_error("Logger is not initialized"));
+    process_response(result, timing)
+}
+
+/// Ready once all collections are loaded, and the peer caught up with the consensus if configured
+#[get("/readyz")]
+async fn readyz(
+    dispatcher: web::Data<Dispatcher>,
+    readiness: web::Data<Readiness>,
+) -> impl Responder {
+    match readiness.check(dispatcher.get_ref()).await {
+        Ok(()) => HttpResponse::Ok()
+            .content_type(ContentType::plaintext())
+            .body("all shards are ready"),
+        Err(reason) => HttpResponse::ServiceUnavailable()
+            .content_type(ContentType::plaintext())
+            .body(reason),
+    }
+}
+
 // Configure services
 pub fn config_service_api(cfg: &mut web::ServiceConfig) {
     cfg.service(telemetry)
         .service(metrics)
         .service(put_locks)
-        .service(get_locks);
+        .service(get_locks)
+        .service(put_logger)
+        .service(get_logger)
+        .service(readyz);
 }
@@ -1,21 +1,34 @@
+use std::io::{self, Read};
+
+use actix_web::error::PayloadError;
 use actix_web::rt::time::Instant;
-use actix_web::{delete, post, put, web, Responder};
+use actix_web::web::Bytes;
+use actix_web::{delete, patch, post, put, web, HttpResponse, Responder};
 use actix_web_validator::{Json, Path, Query};
+use api::grpc::models::{ApiResponse, ApiStatus};
+use collection::config::ShardingMethod;
 use collection::operations::payload_ops::{DeletePayload, SetPayload};
-use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
+use collection::operations::point_ops::{PointsSelector, WriteOrdering};
+use collection::operations::shard_key_selector::ShardSelectorInternal;
+use collection::operations::validation;
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
+use collection::operations::{client_op_part_id, ClientOperationId};
+use futures::StreamExt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
+use tokio::sync::mpsc;
 use validator::Validate;
 
 use super::CollectionPath;
-use crate::actix::helpers::process_response;
+use crate::actix::helpers::{payload_too_large_message, process_response};
 use crate::common::points::{
     do_batch_update_points, do_clear_payload, do_create_index, do_delete_index, do_delete_payload,
-    do_delete_points, do_delete_vectors, do_overwrite_payload, do_set_payload, do_update_vectors,
-    do_upsert_points, CreateFieldIndex, UpdateOperations,
+    do_delete_points, do_delete_vectors, do_overwrite_payload, do_set_payload, do_update_index,
+    do_update_vectors, do_upsert_points, CreateFieldIndex, UpdateOperations, UpsertPoints,
 };
+use crate::common::upsert_stream::{parse_upsert_stream, UpsertStreamError, UpsertStreamLimits};
 
 #[derive(Deserialize, Validate)]
 struct FieldPath {
@@ -28,30 +41,180 @@ struct FieldPath {
 pub struct UpdateParam {
     pub wait: Option<bool>,
     pub ordering: Option<WriteOrdering>,
+    /// Id of the operation, to apply a retried point operation only once
+    pub client_op_id: Option<ClientOperationId>,
+    /// Report ids of the points deleted by filter, if not more than 10000
+    pub with_deleted_ids: Option<bool>,
 }
 
+/// Number of body chunks, received ahead of the parser
+const UPSERT_BODY_CHUNKS_AHEAD: usize = 16;
+
+/// Upsert the points of the body, which is parsed and applied in batches.
+///
+/// Batches are applied one by one, so if one of them fails, the previous ones stay applied.
 #[put("/collections/{name}/points")]
 async fn upsert_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
-    operation: Json<PointInsertOperations>,
+    mut body: web::Payload,
     params: Query<UpdateParam>,
+    limits: web::Data<UpsertStreamLimits>,
 ) -> impl Responder {
     let timing = Instant::now();
-    let operation = operation.into_inner();
-    let wait = params.wait.unwrap_or(false);
-    let ordering = params.ordering.unwrap_or_default();
 
-    let response = do_upsert_points(
-        toc.get_ref(),
-        &collection.name,
-        operation,
-        None,
-        wait,
-        ordering,
-    )
-    .await;
-    process_response(response, timing)
+    // Points of a collection with custom sharding can't be applied before the shard key is known
+    let wait_for_shard_key = match toc.get_collection(&collection.name).await {
+        Ok(target) => {
+            let sharding_method = target.state().await.config.params.sharding_method;
+            sharding_method == Some(ShardingMethod::Custom)
+        }
+        Err(err) => return process_response::<()>(Err(err), timing),
+    };
+
+    let (chunks_sender, chunks) = mpsc::channel(UPSERT_BODY_CHUNKS_AHEAD);
+    let (upserts_sender, mut upserts) = mpsc::channel(1);
+    let limits = *limits.get_ref();
+    let parse = tokio::task::spawn_blocking(move || {
+        let reader = ChunksReader {
+            chunks,
+            chunk: Bytes::new(),
+            offset: 0,
+        };
+        parse_upsert_stream(reader, limits, wait_for_shard_key, |upsert| {
+            upserts_sender.blocking_send(upsert).is_ok()
+        })
+    });
+
+    let receive_body = async move {
+        while let Some(chunk) = body.next().await {
+            // Parser stops on the first error, and doesn't need the rest of the body
+            if chunks_sender.send(chunk?).await.is_err() {
+                break;
+            }
+        }
+        Ok::<_, PayloadError>(())
+    };
+
+    let apply_upserts = async {
+        let mut result = None;
+        let mut batch_index = 0;
+        while let Some(upsert) = upserts.recv().await {
+            if let Err(errs) = upsert.validate() {
+                let description = validation::label_errors("Validation error in JSON body", &errs);
+                return Err(
+                    HttpResponse::UnprocessableEntity().json(error_body(description, timing))
+                );
+            }
+            let UpsertPoints {
+                operation,
+                shard_key,
+            } = upsert;
+            // Each batch is a separate operation, retried batches are still applied only once
+            let client_op_id = params
+                .client_op_id
+                .map(|id| client_op_part_id(id, batch_index));
+            let batch_result = do_upsert_points(
+                toc.get_ref(),
+                &collection.name,
+                operation,
+                shard_key.map_or(ShardSelectorInternal::Empty, ShardSelectorInternal::from),
+                client_op_id,
+                params.wait.unwrap_or(false),
+                params.ordering.unwrap_or_default(),
+            )
+            .await;
+            match batch_result {
+                Ok(batch_result) => result = Some(batch_result),
+                Err(err) => return Err(process_response::<()>(Err(err), timing)),
+            }
+            batch_index += 1;
+        }
+        Ok(result)
+    };
+
+    let (body_result, upserts_result) = futures::join!(receive_body, apply_upserts);
+    let result = match upserts_result {
+        Ok(result) => result,
+        Err(response) => return response,
+    };
+    if let Err(err) = body_result {
+        let description = format!("Failed to read the request body: {err}");
+        return HttpResponse::BadRequest().json(error_body(description, timing));
+    }
+    let parse_result = match parse.await {
+        Ok(parse_result) => parse_result,
+        Err(err) => {
+            let err = StorageError::service_error(format!("Failed to parse upsert body: {err}"));
+            return process_response::<()>(Err(err), timing);
+        }
+    };
+    match (parse_result, result) {
+        (Ok(()), Some(result)) => process_response(Ok(result), timing),
+        (Err(UpsertStreamError::BodyTooLarge { limit }), _) => {
+            let description =
+                payload_too_large_message("upsert body", None, limit, "max_upsert_request_size_mb");
+            HttpResponse::PayloadTooLarge().json(error_body(description, timing))
+        }
+        (Err(UpsertStreamError::BufferedTooLarge { limit }), _) => {
+            let description = format!(
+                "{}. Points are parsed and applied in batches only from the `points` list, \
+                 and only after the `shard_key`, if the collection has custom sharding",
+                payload_too_large_message(
+                    "part of the upsert body, parsed at once",
+                    None,
+                    limit,
+                    "max_request_size_mb",
+                ),
+            );
+            HttpResponse::PayloadTooLarge().json(error_body(description, timing))
+        }
+        (Err(err), _) => {
+            let description = format!("Format error in JSON body: {err}");
+            HttpResponse::BadRequest().json(error_body(description, timing))
+        }
+        (Ok(()), None) => process_response::<()>(
+            Err(StorageError::service_error(
+                "upsert body parsed without points",
+            )),
+            timing,
+        ),
+    }
+}
+
+fn error_body(description: String, timing: Instant) -> ApiResponse<()> {
+    ApiResponse {
+        result: None,
+        status: ApiStatus::Error(description),
+        time: timing.elapsed().as_secs_f64(),
+        stats: None,
+    }
+}
+
+/// Blocking reader of the body chunks, received by the request handler
+struct ChunksReader {
+    chunks: mpsc::Receiver<Bytes>,
+    chunk: Bytes,
+    /// Position in the current chunk
+    offset: usize,
+}
+
+impl Read for ChunksReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.offset == self.chunk.len() {
+            match self.chunks.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.offset = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let count = buf.len().min(self.chunk.len() - self.offset);
+        buf[..count].copy_from_slice(&self.chunk[self.offset..self.offset + count]);
+        self.offset += count;
+        Ok(count)
+    }
 }
 
 #[post("/collections/{name}/points/delete")]
@@ -71,8 +234,10 @@ async fn delete_points(
         &collection.name,
         operation,
         None,
+        params.client_op_id,
         wait,
         ordering,
+        params.with_deleted_ids.unwrap_or(false),
     )
     .await;
     process_response(response, timing)
@@ -95,6 +260,7 @@ async fn update_vectors(
         &collection.name,
         operation,
         None,
+        params.client_op_id,
         wait,
         ordering,
     )
@@ -119,6 +285,7 @@ async fn delete_vectors(
         &collection.name,
         operation,
         None,
+        params.client_op_id,
         wait,
         ordering,
     )
@@ -143,6 +310,7 @@ async fn set_payload(
         &collection.name,
         operation,
         None,
+        params.client_op_id,
         wait,
         ordering,
     )
@@ -167,6 +335,7 @@ async fn overwrite_payload(
         &collection.name,
         operation,
         None,
+        params.client_op_id,
         wait,
         ordering,
     )
@@ -191,6 +360,7 @@ async fn delete_payload(
         &collection.name,
         operation,
         None,
+        params.client_op_id,
         wait,
         ordering,
     )
@@ -215,6 +385,7 @@ async fn clear_payload(
         &collection.name,
         operation,
         None,
+        params.client_op_id,
         wait,
         ordering,
     )
@@ -269,6 +440,30 @@ async fn create_field_index(
     process_response(response, timing)
 }
 
+#[patch("/collections/{name}/index")]
+async fn update_field_index(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<CreateFieldIndex>,
+    params: Query<UpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_update_index(
+        toc.get_ref(),
+        &collection.name,
+        operation,
+        None,
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
 #[delete("/collections/{name}/index/{field_name}")]
 async fn delete_field_index(
     toc: web::Data<TableOfContent>,
@@ -303,6 +498,7 @@ pub fn config_update_api(cfg: &mut web::ServiceConfig) {
         .service(delete_payload)
         .service(clear_payload)
         .service(create_field_index)
+        .service(update_field_index)
         .service(delete_field_index)
         .service(update_batch);
 }
@@ -1,20 +1,47 @@
 use std::future::{ready, Ready};
+use std::rc::Rc;
 
 use actix_web::body::{BoxBody, EitherBody};
-use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::{Error, HttpResponse};
-use constant_time_eq::constant_time_eq;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform, Url};
+use actix_web::http::Method;
+use actix_web::{web, Error, HttpMessage, HttpResponse};
 use futures_util::future::LocalBoxFuture;
+use storage::content_manager::toc::TableOfContent;
+
+use crate::common::auth::{Access, AccessError, ApiKeys, API_KEY_HEADER};
+
+/// Route segment with the name of the collection
+const COLLECTION_NAME_SEGMENT: &str = "{name}";
+
+/// Routes of `POST` requests, which only read the data
+const READ_POST_ROUTES: &[&str] = &[
+    "/collections/{name}/facet",
+    "/collections/{name}/points",
+    "/collections/{name}/points/count",
+    "/collections/{name}/points/discover",
+    "/collections/{name}/points/query",
+    "/collections/{name}/points/recommend",
+    "/collections/{name}/points/recommend/batch",
+    "/collections/{name}/points/recommend/groups",
+    "/collections/{name}/points/scroll",
+    "/collections/{name}/points/search",
+    "/collections/{name}/points/search/batch",
+    "/collections/{name}/points/search/groups",
+    "/collections/{name}/points/search/plan",
+];
+
+/// Routes, which list all collections. Listings are filtered by the collections of the api-key.
+const COLLECTION_LISTING_ROUTES: &[&str] = &["/aliases", "/broken_collections", "/collections"];
 
 pub struct ApiKey {
-    api_key: String,
+    api_keys: ApiKeys,
     whitelist: Vec<WhitelistItem>,
 }
 
 impl ApiKey {
-    pub fn new(api_key: &str, whitelist: Vec<WhitelistItem>) -> Self {
+    pub fn new(api_keys: ApiKeys, whitelist: Vec<WhitelistItem>) -> Self {
         Self {
-            api_key: api_key.to_string(),
+            api_keys,
             whitelist,
         }
     }
@@ -22,7 +49,8 @@ impl ApiKey {
 
 impl<S, B> Transform<S, ServiceRequest> for ApiKey
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>
+        + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -34,9 +62,9 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(ApiKeyMiddleware {
-            api_key: self.api_key.clone(),
+            api_keys: self.api_keys.clone(),
             whitelist: self.whitelist.clone(),
-            service,
+            service: Rc::new(service),
         }))
     }
 }
@@ -76,10 +104,10 @@ impl PathMode {
 }
 
 pub struct ApiKeyMiddleware<S> {
-    api_key: String,
+    api_keys: ApiKeys,
     /// List of items whitelisted from authentication.
     whitelist: Vec<WhitelistItem>,
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S> ApiKeyMiddleware<S> {
@@ -90,7 +118,8 @@ impl<S> ApiKeyMiddleware<S> {
 
 impl<S, B> Service<ServiceRequest> for ApiKeyMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>
+        + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -107,18 +136,201 @@ where
             return Box::pin(self.service.call(req));
         }
 
-        if let Some(key) = req.headers().get("api-key") {
-            if let Ok(key) = key.to_str() {
-                if constant_time_eq(self.api_key.as_bytes(), key.as_bytes()) {
-                    return Box::pin(self.service.call(req));
-                }
+        let access = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|key| key.to_str().ok())
+            .and_then(|key| self.api_keys.access(key));
+        let Some(access) = access else {
+            return Box::pin(async { Ok(forbidden(req, "Invalid api-key".to_string())) });
+        };
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            let pattern = req.match_pattern();
+            let path = Url::new(req.uri().clone());
+            let mut collection_name = pattern
+                .as_deref()
+                .and_then(|pattern| collection_name(pattern, path.path()))
+                .map(str::to_string);
+            // Collections of the api-key are listed by their names, so aliases are resolved
+            if let (Some(name), Some(toc)) = (
+                collection_name.as_mut(),
+                req.app_data::<web::Data<TableOfContent>>(),
+            ) {
+                *name = toc.resolve_alias(name).await;
+            }
+
+            if let Err(AccessError(message)) = check_request_access(
+                &access,
+                req.method(),
+                pattern.as_deref(),
+                collection_name.as_deref(),
+            ) {
+                return Ok(forbidden(req, message));
             }
-        }
 
-        Box::pin(async {
-            Ok(req
-                .into_response(HttpResponse::Forbidden().body("Invalid api-key"))
-                .map_into_right_body())
+            req.extensions_mut().insert(access);
+            service.call(req).await
         })
     }
 }
+
+fn forbidden<B>(req: ServiceRequest, message: String) -> ServiceResponse<EitherBody<B, BoxBody>> {
+    req.into_response(HttpResponse::Forbidden().body(message))
+        .map_into_right_body()
+}
+
+/// Check, that the access permits the request to the route `pattern`,
+/// targeted at the collection `collection_name`, if any
+fn check_request_access(
+    access: &Access,
+    method: &Method,
+    pattern: Option<&str>,
+    collection_name: Option<&str>,
+) -> Result<(), AccessError> {
+    let is_write = match *method {
+        Method::GET | Method::HEAD => false,
+        Method::POST => !pattern.map_or(false, |pattern| READ_POST_ROUTES.contains(&pattern)),
+        _ => true,
+    };
+    access.check_scope(is_write)?;
+
+    let Some(pattern) = pattern else {
+        return access.check_global();
+    };
+    if let Some(collection_name) = collection_name {
+        return access.check_collection(collection_name);
+    }
+    // Listings are filtered by the handlers
+    if !is_write && COLLECTION_LISTING_ROUTES.contains(&pattern) {
+        return Ok(());
+    }
+    access.check_global()
+}
+
+/// Name of the collection in the `path`, if the route `pattern` is targeted at a collection
+fn collection_name<'a>(pattern: &str, path: &'a str) -> Option<&'a str> {
+    let position = pattern
+        .split('/')
+        .position(|segment| segment == COLLECTION_NAME_SEGMENT)?;
+    path.split('/').nth(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::middleware::Compress;
+    use actix_web::{test, web, App, HttpResponse};
+
+    use super::*;
+    use crate::settings::ApiKeyScope;
+
+    fn api_keys() -> ApiKeys {
+        let config = serde_json::from_value(serde_json::json!({
+            "host": "0.0.0.0",
+            "http_port": 6333,
+            "grpc_port": 6334,
+            "max_request_size_mb": 32,
+            "api_key": "master",
+            "scoped_api_keys": [
+                { "key": "reader", "scope": "read-only" },
+                { "key": "writer", "scope": "read-write", "collections": ["allowed"] },
+                { "key": "limited", "scope": "read-only", "collections": ["allowed"] },
+            ],
+        }))
+        .unwrap();
+        ApiKeys::from_config(&config).unwrap()
+    }
+
+    /// Status of the request with the api-key, `None` for the request without the key
+    async fn request_status(method: Method, path: &str, key: Option<&str>) -> StatusCode {
+        let app = test::init_service(
+            App::new()
+                // Api key middleware expects `EitherBody` responses, like the ones of `Compress`
+                .wrap(Compress::default())
+                .wrap(ApiKey::new(api_keys(), vec![WhitelistItem::exact("/")]))
+                .route("/", web::get().to(HttpResponse::Ok))
+                .route("/collections", web::get().to(HttpResponse::Ok))
+                .route("/collections/{name}", web::put().to(HttpResponse::Ok))
+                .route(
+                    "/collections/{name}/points/search",
+                    web::post().to(HttpResponse::Ok),
+                )
+                .route(
+                    "/collections/{name}/points/delete",
+                    web::post().to(HttpResponse::Ok),
+                )
+                .route("/snapshots", web::post().to(HttpResponse::Ok))
+                .route("/telemetry", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+        let mut request = test::TestRequest::default().method(method).uri(path);
+        if let Some(key) = key {
+            request = request.insert_header((API_KEY_HEADER, key));
+        }
+        test::call_service(&app, request.to_request())
+            .await
+            .status()
+    }
+
+    const SEARCH_ALLOWED: &str = "/collections/allowed/points/search";
+    const SEARCH_OTHER: &str = "/collections/other/points/search";
+    const DELETE_ALLOWED: &str = "/collections/allowed/points/delete";
+    const DELETE_OTHER: &str = "/collections/other/points/delete";
+
+    #[actix_web::test]
+    async fn test_api_key_scopes() {
+        let forbidden = StatusCode::FORBIDDEN;
+        let ok = StatusCode::OK;
+
+        for (method, path, key, expected) in [
+            // Whitelisted path doesn't need a key
+            (Method::GET, "/", None, ok),
+            (Method::GET, "/collections", None, forbidden),
+            (Method::GET, "/collections", Some("invalid"), forbidden),
+            // Master key has the full access
+            (Method::POST, DELETE_OTHER, Some("master"), ok),
+            (Method::POST, "/snapshots", Some("master"), ok),
+            // Read-only key can only read
+            (Method::POST, SEARCH_OTHER, Some("reader"), ok),
+            (Method::GET, "/telemetry", Some("reader"), ok),
+            (Method::POST, DELETE_OTHER, Some("reader"), forbidden),
+            (Method::PUT, "/collections/other", Some("reader"), forbidden),
+            (Method::POST, "/snapshots", Some("reader"), forbidden),
+            // Collections of the key are available, the others and global operations are not
+            (Method::PUT, "/collections/allowed", Some("writer"), ok),
+            (Method::POST, DELETE_ALLOWED, Some("writer"), ok),
+            (Method::POST, SEARCH_OTHER, Some("writer"), forbidden),
+            (Method::GET, "/telemetry", Some("writer"), forbidden),
+            (Method::POST, "/snapshots", Some("writer"), forbidden),
+            // Listing is allowed, it's filtered by the handler
+            (Method::GET, "/collections", Some("writer"), ok),
+            // Both the scope and the collections are checked
+            (Method::POST, SEARCH_ALLOWED, Some("limited"), ok),
+            (Method::POST, DELETE_ALLOWED, Some("limited"), forbidden),
+            (Method::POST, SEARCH_OTHER, Some("limited"), forbidden),
+        ] {
+            assert_eq!(
+                request_status(method.clone(), path, key).await,
+                expected,
+                "{method} {path} with {key:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_collection_name_of_route() {
+        let pattern = "/collections/{name}/points/search";
+        assert_eq!(
+            collection_name(pattern, "/collections/test/points/search"),
+            Some("test")
+        );
+        assert_eq!(collection_name("/collections", "/collections"), None);
+
+        let access = Access::new(ApiKeyScope::ReadOnly, Some(["test".to_string()]));
+        let path = Url::new("/collections/te%73t/points/search".parse().unwrap());
+        let collection = collection_name(pattern, path.path());
+        assert!(check_request_access(&access, &Method::POST, Some(pattern), collection).is_ok());
+    }
+}
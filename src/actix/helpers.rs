@@ -1,9 +1,11 @@
 use std::fmt::Debug;
 
+use actix_web::http::header;
 use actix_web::rt::time::Instant;
 use actix_web::{error, Error, HttpResponse};
 use api::grpc::models::{ApiResponse, ApiStatus};
 use collection::operations::types::CollectionError;
+use segment::common::search_stats::SearchStats;
 use serde::Serialize;
 use storage::content_manager::errors::StorageError;
 
@@ -20,6 +22,10 @@ pub fn storage_into_actix_error(err: StorageError) -> Error {
         StorageError::BadRequest { .. } => error::ErrorBadRequest(format!("{err}")),
         StorageError::Locked { .. } => error::ErrorForbidden(format!("{err}")),
         StorageError::Timeout { .. } => error::ErrorRequestTimeout(format!("{err}")),
+        StorageError::Forbidden { .. } => error::ErrorForbidden(format!("{err}")),
+        StorageError::Conflict { .. } => error::ErrorConflict(format!("{err}")),
+        StorageError::BrokenCollection { .. } => error::ErrorServiceUnavailable(format!("{err}")),
+        StorageError::RateLimited { .. } => error::ErrorTooManyRequests(format!("{err}")),
     }
 }
 
@@ -28,10 +34,23 @@ pub fn accepted_response(timing: Instant) -> HttpResponse {
         result: None,
         status: ApiStatus::Accepted,
         time: timing.elapsed().as_secs_f64(),
+        stats: None,
     })
 }
 
 pub fn process_response<D>(response: Result<D, StorageError>, timing: Instant) -> HttpResponse
+where
+    D: Serialize + Debug,
+{
+    process_response_with_stats(response, timing, None)
+}
+
+/// Same as [`process_response`], but a successful response reports the work of the search
+pub fn process_response_with_stats<D>(
+    response: Result<D, StorageError>,
+    timing: Instant,
+    stats: Option<SearchStats>,
+) -> HttpResponse
 where
     D: Serialize + Debug,
 {
@@ -40,9 +59,11 @@ where
             result: Some(res),
             status: ApiStatus::Ok,
             time: timing.elapsed().as_secs_f64(),
+            stats,
         }),
         Err(err) => {
             let error_description = format!("{err}");
+            let retry_after_secs = err.retry_after_secs();
 
             let mut resp = match err {
                 StorageError::BadInput { .. } => HttpResponse::BadRequest(),
@@ -60,13 +81,35 @@ where
                 StorageError::BadRequest { .. } => HttpResponse::BadRequest(),
                 StorageError::Locked { .. } => HttpResponse::Forbidden(),
                 StorageError::Timeout { .. } => HttpResponse::RequestTimeout(),
+                StorageError::Forbidden { .. } => HttpResponse::Forbidden(),
+                StorageError::Conflict { .. } => HttpResponse::Conflict(),
+                StorageError::BrokenCollection { .. } => HttpResponse::ServiceUnavailable(),
+                StorageError::RateLimited { .. } => HttpResponse::TooManyRequests(),
             };
+            if let Some(retry_after_secs) = retry_after_secs {
+                resp.insert_header((header::RETRY_AFTER, retry_after_secs));
+            }
 
             resp.json(ApiResponse::<()> {
                 result: None,
                 status: ApiStatus::Error(error_description),
                 time: timing.elapsed().as_secs_f64(),
+                stats: None,
             })
         }
     }
 }
+
+/// Describe a request body over the size `limit`, set by the `setting` of the service config
+pub fn payload_too_large_message(
+    name: &str,
+    size: Option<usize>,
+    limit: usize,
+    setting: &str,
+) -> String {
+    let size = size.map_or_else(String::new, |size| format!(" of {size} bytes"));
+    format!(
+        "Payload too large: {name}{size} exceeds the limit of {limit} bytes. \
+         Split the request into smaller ones, or raise `service.{setting}` in the config",
+    )
+}
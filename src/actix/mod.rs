@@ -1,5 +1,9 @@
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod actix_shutdown;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod actix_telemetry;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod actix_tracing;
 pub mod api;
 mod api_key;
 mod certificate_helpers;
@@ -22,6 +26,9 @@ use storage::dispatcher::Dispatcher;
 use crate::actix::api::cluster_api::config_cluster_api;
 use crate::actix::api::collections_api::config_collections_api;
 use crate::actix::api::count_api::count_points;
+use crate::actix::api::discovery_api::config_discovery_api;
+use crate::actix::api::facet_api::config_facet_api;
+use crate::actix::api::query_api::config_query_api;
 use crate::actix::api::recommend_api::config_recommend_api;
 use crate::actix::api::retrieve_api::{get_point, get_points, scroll_points};
 use crate::actix::api::search_api::config_search_api;
@@ -29,7 +36,12 @@ use crate::actix::api::service_api::config_service_api;
 use crate::actix::api::snapshot_api::config_snapshots_api;
 use crate::actix::api::update_api::config_update_api;
 use crate::actix::api_key::{ApiKey, WhitelistItem};
+use crate::actix::helpers::payload_too_large_message;
+use crate::common::auth::ApiKeys;
+use crate::common::readiness::Readiness;
+use crate::common::shutdown::ShutdownRef;
 use crate::common::telemetry::TelemetryCollector;
+use crate::common::upsert_stream::UpsertStreamLimits;
 use crate::settings::{max_web_workers, Settings};
 
 const DEFAULT_STATIC_DIR: &str = "./static";
@@ -45,6 +57,7 @@ pub fn init(
     dispatcher: Arc<Dispatcher>,
     telemetry_collector: Arc<tokio::sync::Mutex<TelemetryCollector>>,
     settings: Settings,
+    shutdown: ShutdownRef,
 ) -> io::Result<()> {
     actix_web::rt::System::new().block_on(async {
         let toc_data = web::Data::from(dispatcher.toc().clone());
@@ -55,7 +68,7 @@ pub fn init(
             .actix_telemetry_collector
             .clone();
         let telemetry_collector_data = web::Data::from(telemetry_collector);
-        let api_key = settings.service.api_key.clone();
+        let api_keys = ApiKeys::from_config(&settings.service);
         let static_folder = settings
             .service
             .static_content_dir
@@ -82,11 +95,26 @@ pub fn init(
             false
         };
 
-        let mut api_key_whitelist = vec![WhitelistItem::exact("/")];
+        // Consensus is checked only in the distributed mode
+        let readiness_data =
+            web::Data::new(Readiness::new(settings.cluster.consensus.readiness_max_lag));
+
+        let mut api_key_whitelist =
+            vec![WhitelistItem::exact("/"), WhitelistItem::exact("/readyz")];
         if web_ui_available {
             api_key_whitelist.push(WhitelistItem::prefix(WEB_UI_PATH));
         }
 
+        let upsert_stream_limits = web::Data::new(UpsertStreamLimits {
+            max_body_size: settings
+                .service
+                .max_upsert_request_size_mb
+                .map(|size_mb| size_mb * 1024 * 1024),
+            max_buffered_size: settings.service.max_request_size_mb * 1024 * 1024,
+            batch_size: settings.service.upsert_batch_size,
+        });
+
+        let server_shutdown = shutdown.clone();
         let mut server = HttpServer::new(move || {
             let cors = Cors::default()
                 .allow_any_origin()
@@ -105,20 +133,28 @@ pub fn init(
                 // api_key middleware
                 // note: the last call to `wrap()` or `wrap_fn()` is executed first
                 .wrap(Condition::new(
-                    api_key.is_some(),
+                    api_keys.is_some(),
                     ApiKey::new(
-                        &api_key.clone().unwrap_or_default(),
+                        api_keys.clone().unwrap_or_default(),
                         api_key_whitelist.clone(),
                     ),
                 ))
+                .wrap(actix_shutdown::ActixShutdownTransform::new(
+                    shutdown.clone(),
+                ))
                 .wrap(Condition::new(settings.service.enable_cors, cors))
-                .wrap(Logger::default().exclude("/")) // Avoid logging healthcheck requests
+                .wrap(Logger::default().exclude("/").exclude("/readyz")) // Avoid logging healthcheck requests
                 .wrap(actix_telemetry::ActixTelemetryTransform::new(
                     actix_telemetry_collector.clone(),
                 ))
+                .wrap(actix_tracing::ActixTracingTransform::new(
+                    settings.tracing.sampling_ratio,
+                ))
                 .app_data(dispatcher_data.clone())
                 .app_data(toc_data.clone())
                 .app_data(telemetry_collector_data.clone())
+                .app_data(readiness_data.clone())
+                .app_data(upsert_stream_limits.clone())
                 .app_data(validate_path_config)
                 .app_data(validate_query_config)
                 .app_data(validate_json_config)
@@ -134,6 +170,9 @@ pub fn init(
                 .configure(config_service_api)
                 .configure(config_search_api)
                 .configure(config_recommend_api)
+                .configure(config_discovery_api)
+                .configure(config_facet_api)
+                .configure(config_query_api)
                 .service(get_point)
                 .service(get_points)
                 .service(scroll_points)
@@ -173,7 +212,15 @@ pub fn init(
         };
 
         log::info!("Qdrant HTTP listening on {}", port);
-        server.run().await
+
+        // Stop signal is handled by the service, so the server stops after requests are drained
+        let server = server.disable_signals().run();
+        let server_handle = server.handle();
+        actix_web::rt::spawn(async move {
+            server_shutdown.wait_stop_servers().await;
+            server_handle.stop(true).await;
+        });
+        server.await
     })
 }
 
@@ -204,18 +251,29 @@ fn validation_error_handler(
         ) => {
             format!("Format error in {name}: {}", err,)
         }
+        actix_web_validator::Error::JsonPayloadError(
+            actix_web::error::JsonPayloadError::OverflowKnownLength { length, limit },
+        ) => payload_too_large_message(name, Some(*length), *limit, "max_request_size_mb"),
+        actix_web_validator::Error::JsonPayloadError(
+            actix_web::error::JsonPayloadError::Overflow { limit },
+        ) => payload_too_large_message(name, None, *limit, "max_request_size_mb"),
         err => err.to_string(),
     };
 
     // Build fitting response
     let response = match &err {
         actix_web_validator::Error::Validate(_) => HttpResponse::UnprocessableEntity(),
+        actix_web_validator::Error::JsonPayloadError(
+            actix_web::error::JsonPayloadError::OverflowKnownLength { .. }
+            | actix_web::error::JsonPayloadError::Overflow { .. },
+        ) => HttpResponse::PayloadTooLarge(),
         _ => HttpResponse::BadRequest(),
     }
     .json(ApiResponse::<()> {
         result: None,
         status: ApiStatus::Error(msg),
         time: 0.0,
+        stats: None,
     });
     error::InternalError::from_response(err, response).into()
 }
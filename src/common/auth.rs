@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use constant_time_eq::constant_time_eq;
+use storage::content_manager::toc::TableOfContent;
+
+use crate::settings::{ApiKeyScope, ServiceConfig};
+
+/// Header (or gRPC metadata key) with the API key
+pub const API_KEY_HEADER: &str = "api-key";
+
+/// Access to the operations, granted by an API key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Access {
+    scope: ApiKeyScope,
+    /// Collections, available with the key. All collections, if `None`
+    collections: Option<HashSet<String>>,
+}
+
+impl Access {
+    /// Access of the master API key
+    pub fn full() -> Self {
+        Self {
+            scope: ApiKeyScope::ReadWrite,
+            collections: None,
+        }
+    }
+
+    pub fn new(scope: ApiKeyScope, collections: Option<impl IntoIterator<Item = String>>) -> Self {
+        Self {
+            scope,
+            collections: collections.map(|collections| collections.into_iter().collect()),
+        }
+    }
+
+    /// Check, that the key can perform a write (or a read) operation
+    pub fn check_scope(&self, write: bool) -> Result<(), AccessError> {
+        if write && self.scope == ApiKeyScope::ReadOnly {
+            return Err(AccessError(
+                "Write operations are forbidden for a read-only api-key".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check, that the key can perform an operation, which is not bound to a single collection
+    pub fn check_global(&self) -> Result<(), AccessError> {
+        if self.collections.is_some() {
+            return Err(AccessError(
+                "Only operations with the allowed collections are permitted for the api-key"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_collection_allowed(&self, collection_name: &str) -> bool {
+        self.collections
+            .as_ref()
+            .map_or(true, |collections| collections.contains(collection_name))
+    }
+
+    /// Check, that the key can access the collection
+    pub fn check_collection(&self, collection_name: &str) -> Result<(), AccessError> {
+        if !self.is_collection_allowed(collection_name) {
+            return Err(AccessError(format!(
+                "Access to collection {collection_name} is forbidden for the api-key"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check, that the key can access the collection, or the collection of the alias.
+    ///
+    /// Collections of the key are listed by their names, so the alias is resolved first.
+    pub async fn check_collection_or_alias(
+        &self,
+        toc: &TableOfContent,
+        collection_name: &str,
+    ) -> Result<(), AccessError> {
+        if self.collections.is_none() {
+            return Ok(());
+        }
+        self.check_collection(&toc.resolve_alias(collection_name).await)
+    }
+}
+
+/// Operation is not permitted for the API key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessError(pub String);
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// API keys, accepted by the service
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    /// Key with the full access
+    master: Option<String>,
+    scoped: Vec<(String, Access)>,
+}
+
+impl ApiKeys {
+    /// Keys of the service, `None` if no keys are configured and authentication is disabled
+    pub fn from_config(config: &ServiceConfig) -> Option<Self> {
+        if config.api_key.is_none() && config.scoped_api_keys.is_empty() {
+            return None;
+        }
+        let scoped = config
+            .scoped_api_keys
+            .iter()
+            .map(|scoped| {
+                let access = Access::new(scoped.scope, scoped.collections.clone());
+                (scoped.key.clone(), access)
+            })
+            .collect();
+        Some(Self {
+            master: config.api_key.clone(),
+            scoped,
+        })
+    }
+
+    /// Access, granted by the key. `None` if the key is not valid.
+    pub fn access(&self, key: &str) -> Option<Access> {
+        let matches = |expected: &str| constant_time_eq(expected.as_bytes(), key.as_bytes());
+        if self.master.as_deref().map_or(false, matches) {
+            return Some(Access::full());
+        }
+        self.scoped
+            .iter()
+            .find(|(scoped_key, _)| matches(scoped_key))
+            .map(|(_, access)| access.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ScopedApiKey;
+
+    fn api_keys(master: Option<&str>) -> ApiKeys {
+        ApiKeys {
+            master: master.map(str::to_string),
+            scoped: [
+                ("reader", ApiKeyScope::ReadOnly, None),
+                (
+                    "writer",
+                    ApiKeyScope::ReadWrite,
+                    Some(vec!["a".to_string()]),
+                ),
+            ]
+            .into_iter()
+            .map(|(key, scope, collections)| (key.to_string(), Access::new(scope, collections)))
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn test_api_key_access() {
+        let keys = api_keys(Some("master"));
+        assert_eq!(keys.access("master"), Some(Access::full()));
+        assert_eq!(keys.access("unknown"), None);
+        assert_eq!(keys.access(""), None);
+
+        let reader = keys.access("reader").unwrap();
+        assert!(reader.check_scope(false).is_ok());
+        assert!(reader.check_scope(true).is_err());
+        assert!(reader.check_global().is_ok());
+        assert!(reader.check_collection("b").is_ok());
+
+        let writer = keys.access("writer").unwrap();
+        assert!(writer.check_scope(true).is_ok());
+        assert!(writer.check_global().is_err());
+        assert!(writer.check_collection("a").is_ok());
+        assert!(writer.check_collection("b").is_err());
+
+        // Scoped keys work without the master key
+        let keys = api_keys(None);
+        assert_eq!(keys.access("master"), None);
+        assert!(keys.access("reader").is_some());
+    }
+
+    #[test]
+    fn test_api_keys_from_config() {
+        let mut config: ServiceConfig = serde_json::from_value(serde_json::json!({
+            "host": "0.0.0.0",
+            "http_port": 6333,
+            "grpc_port": 6334,
+            "max_request_size_mb": 32,
+        }))
+        .unwrap();
+        assert!(ApiKeys::from_config(&config).is_none());
+
+        config.scoped_api_keys.push(ScopedApiKey {
+            key: "reader".to_string(),
+            scope: ApiKeyScope::ReadOnly,
+            collections: None,
+        });
+        let keys = ApiKeys::from_config(&config).unwrap();
+        assert_eq!(
+            keys.access("reader"),
+            Some(Access::new(ApiKeyScope::ReadOnly, None::<Vec<String>>))
+        );
+    }
+}
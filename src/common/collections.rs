@@ -1,26 +1,38 @@
 use std::time::Duration;
 
-use api::grpc::models::{CollectionDescription, CollectionsResponse};
+use api::grpc::models::{CollectionDescription, CollectionExistence, CollectionsResponse};
+use collection::collection_manager::optimizers::optimization_tasks::{
+    OptimizationTaskId, OptimizationTaskInfo,
+};
+use collection::common::filter_usage::PayloadIndexSuggestion;
+use collection::common::resource_usage::CollectionResourceUsage;
+use collection::common::search_recall::{RecallTestReport, RecallTestRequest};
 use collection::operations::cluster_ops::{
-    AbortTransferOperation, ClusterOperations, DropReplicaOperation, MoveShardOperation,
-    ReplicateShardOperation,
+    AbortReshardingOperation, AbortTransferOperation, ClusterOperations, ConvertToActiveOperation,
+    ConvertToListenerOperation, CreateShardingKeyOperation, DropReplicaOperation,
+    DropShardingKeyOperation, MoveShardOperation, ReplicateShardOperation,
+    StartReshardingOperation,
 };
 use collection::operations::snapshot_ops::SnapshotDescription;
 use collection::operations::types::{
     AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
 };
 use collection::shards::replica_set;
+use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::ShardId;
 use collection::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
 use itertools::Itertools;
 use storage::content_manager::collection_meta_ops::ShardTransferOperations::{Abort, Start};
 use storage::content_manager::collection_meta_ops::{
-    CollectionMetaOperations, UpdateCollectionOperation,
+    CollectionMetaOperations, CreateShardKey, DropShardKey, ReshardingOperation,
+    SetShardReplicaState, UpdateCollectionOperation,
 };
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
 
+use crate::common::auth::Access;
+
 pub async fn do_get_collection(
     toc: &TableOfContent,
     name: &str,
@@ -30,17 +42,112 @@ pub async fn do_get_collection(
     Ok(collection.info(shard_selection).await?)
 }
 
-pub async fn do_list_collections(toc: &TableOfContent) -> CollectionsResponse {
-    let collections = toc
+/// Check the existence of the collection or alias, without reading its info
+pub async fn do_collection_exists(toc: &TableOfContent, name: &str) -> CollectionExistence {
+    CollectionExistence {
+        exists: toc.collection_exists(name).await,
+    }
+}
+
+/// Payload fields, filtered without a payload index by reads requested from this peer
+pub async fn do_get_index_suggestions(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<Vec<PayloadIndexSuggestion>, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.index_suggestions().await?)
+}
+
+pub async fn do_reset_index_suggestions(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<(), StorageError> {
+    let collection = toc.get_collection(name).await?;
+    collection.reset_index_suggestions();
+    Ok(())
+}
+
+/// Running and recently finished optimization tasks of the local shards of the collection
+pub async fn do_get_optimization_tasks(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<Vec<OptimizationTaskInfo>, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.optimization_tasks().await)
+}
+
+/// Memory and disk usage of the local shards of the collection, cached for the configured interval
+pub async fn do_get_resource_usage(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<CollectionResourceUsage, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.resource_usage().await?)
+}
+
+/// Recall of approximate search, measured on points of the local shards of the collection
+pub async fn do_recall_test(
+    toc: &TableOfContent,
+    name: &str,
+    request: RecallTestRequest,
+) -> Result<RecallTestReport, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.recall_test(request).await?)
+}
+
+pub async fn do_cancel_optimization_task(
+    toc: &TableOfContent,
+    name: &str,
+    task_id: OptimizationTaskId,
+) -> Result<(), StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.cancel_optimization_task(task_id).await?)
+}
+
+/// List collections, only the ones available with the `access`, if given.
+///
+/// Collections, which failed to load, are listed with the load error.
+pub async fn do_list_collections(
+    toc: &TableOfContent,
+    access: Option<&Access>,
+) -> CollectionsResponse {
+    let loaded = toc
         .all_collections()
         .await
         .into_iter()
-        .map(|name| CollectionDescription { name })
+        .map(|name| CollectionDescription {
+            name,
+            load_error: None,
+        });
+    let collections = loaded
+        .chain(broken_collections(toc))
+        .filter(|desc| access.map_or(true, |access| access.is_collection_allowed(&desc.name)))
+        .collect_vec();
+
+    CollectionsResponse { collections }
+}
+
+/// List collections, which failed to load on this peer
+pub fn do_list_broken_collections(
+    toc: &TableOfContent,
+    access: Option<&Access>,
+) -> CollectionsResponse {
+    let collections = broken_collections(toc)
+        .filter(|desc| access.map_or(true, |access| access.is_collection_allowed(&desc.name)))
         .collect_vec();
 
     CollectionsResponse { collections }
 }
 
+fn broken_collections(toc: &TableOfContent) -> impl Iterator<Item = CollectionDescription> {
+    toc.broken_collections()
+        .into_iter()
+        .map(|(name, error)| CollectionDescription {
+            name,
+            load_error: Some(error),
+        })
+}
+
 pub async fn do_list_collection_aliases(
     toc: &TableOfContent,
     collection_name: &str,
@@ -55,10 +162,15 @@ pub async fn do_list_collection_aliases(
     Ok(CollectionsAliasesResponse { aliases })
 }
 
+/// List aliases, only of the collections available with the `access`, if given
 pub async fn do_list_aliases(
     toc: &TableOfContent,
+    access: Option<&Access>,
 ) -> Result<CollectionsAliasesResponse, StorageError> {
-    let aliases = toc.list_aliases().await?;
+    let mut aliases = toc.list_aliases().await?;
+    if let Some(access) = access {
+        aliases.retain(|alias| access.is_collection_allowed(&alias.collection_name));
+    }
     Ok(CollectionsAliasesResponse { aliases })
 }
 
@@ -88,6 +200,7 @@ pub async fn do_create_snapshot(
             name: "".to_string(),
             creation_time: None,
             size: 0,
+            checksum: None,
         })
     }
 }
@@ -107,6 +220,95 @@ pub async fn do_update_collection_cluster(
     dispatcher: &Dispatcher,
     wait_timeout: Option<Duration>,
 ) -> Result<bool, StorageError> {
+    // Shard keys and resharding are managed both in standalone and in distributed mode
+    let operation = match operation {
+        ClusterOperations::CreateShardingKey(CreateShardingKeyOperation {
+            create_sharding_key,
+        }) => {
+            let placement = toc
+                .suggest_shard_key_placement(&collection_name, &create_sharding_key)
+                .await?;
+            return dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::CreateShardKey(CreateShardKey {
+                        collection_name,
+                        shard_key: create_sharding_key.shard_key,
+                        placement,
+                    }),
+                    wait_timeout,
+                )
+                .await;
+        }
+        ClusterOperations::DropShardingKey(DropShardingKeyOperation { drop_sharding_key }) => {
+            let shard_key = drop_sharding_key.shard_key;
+            let shard_key_exists = toc
+                .get_collection(&collection_name)
+                .await?
+                .state()
+                .await
+                .shards_key_mapping
+                .values()
+                .any(|key| *key == shard_key);
+            if !shard_key_exists {
+                return Err(StorageError::NotFound {
+                    description: format!(
+                        "Shard key {shard_key} of {collection_name} does not exist"
+                    ),
+                });
+            }
+            return dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::DropShardKey(DropShardKey {
+                        collection_name,
+                        shard_key,
+                    }),
+                    wait_timeout,
+                )
+                .await;
+        }
+        ClusterOperations::StartResharding(StartReshardingOperation { start_resharding }) => {
+            let state = toc
+                .suggest_resharding(&collection_name, &start_resharding)
+                .await?;
+            return dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::Resharding(
+                        collection_name,
+                        ReshardingOperation::Start(state),
+                    ),
+                    wait_timeout,
+                )
+                .await;
+        }
+        ClusterOperations::AbortResharding(AbortReshardingOperation {
+            abort_resharding: _,
+        }) => {
+            let Some(state) = toc
+                .get_collection(&collection_name)
+                .await?
+                .resharding_state()
+                .await
+            else {
+                return Err(StorageError::NotFound {
+                    description: format!("Resharding of {collection_name} is not in progress"),
+                });
+            };
+            return dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::Resharding(
+                        collection_name,
+                        ReshardingOperation::Abort {
+                            split: state.split,
+                            reason: "user request".to_string(),
+                        },
+                    ),
+                    wait_timeout,
+                )
+                .await;
+        }
+        operation => operation,
+    };
+
     if dispatcher.consensus_state().is_none() {
         return Err(StorageError::BadRequest {
             description: "Distributed mode disabled".to_string(),
@@ -159,6 +361,7 @@ pub async fn do_update_collection_cluster(
                             to: move_shard.to_peer_id,
                             from: move_shard.from_peer_id,
                             sync: false,
+                            method: move_shard.method,
                         }),
                     ),
                     wait_timeout,
@@ -192,6 +395,7 @@ pub async fn do_update_collection_cluster(
                             to: replicate_shard.to_peer_id,
                             from: replicate_shard.from_peer_id,
                             sync: true,
+                            method: replicate_shard.method,
                         }),
                     ),
                     wait_timeout,
@@ -253,5 +457,113 @@ pub async fn do_update_collection_cluster(
                 )
                 .await
         }
+        ClusterOperations::ConvertToListener(ConvertToListenerOperation {
+            convert_to_listener: replica,
+        }) => {
+            validate_peer_exists(replica.peer_id)?;
+
+            let replicas = collection
+                .state()
+                .await
+                .shards
+                .get(&replica.shard_id)
+                .map(|shard| shard.replicas.clone())
+                .ok_or_else(|| StorageError::BadRequest {
+                    description: format!(
+                        "Shard {} of {collection_name} does not exist",
+                        replica.shard_id
+                    ),
+                })?;
+            if replicas.get(&replica.peer_id) != Some(&ReplicaState::Active) {
+                return Err(StorageError::BadRequest {
+                    description: format!(
+                        "Replica {}:{} of {collection_name} is not active",
+                        replica.peer_id, replica.shard_id
+                    ),
+                });
+            }
+            let active_count = replicas
+                .values()
+                .filter(|state| **state == ReplicaState::Active)
+                .count();
+            if active_count <= 1 {
+                return Err(StorageError::BadRequest {
+                    description: format!(
+                        "Replica {}:{} of {collection_name} is the last active one, it can't become a listener",
+                        replica.peer_id, replica.shard_id
+                    ),
+                });
+            }
+
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::SetShardReplicaState(SetShardReplicaState {
+                        collection_name,
+                        shard_id: replica.shard_id,
+                        peer_id: replica.peer_id,
+                        state: ReplicaState::Listener,
+                        from_state: Some(ReplicaState::Active),
+                    }),
+                    wait_timeout,
+                )
+                .await
+        }
+        ClusterOperations::ConvertToActive(ConvertToActiveOperation {
+            convert_to_active: replica,
+        }) => {
+            validate_peer_exists(replica.peer_id)?;
+
+            // Replicas of listener peers are converted back to listeners
+            if consensus_state.listener_peers().contains(&replica.peer_id) {
+                return Err(StorageError::BadRequest {
+                    description: format!(
+                        "Peer {} is a listener, its replicas can't become active",
+                        replica.peer_id
+                    ),
+                });
+            }
+
+            let state = collection
+                .state()
+                .await
+                .shards
+                .get(&replica.shard_id)
+                .map(|shard| shard.replicas.get(&replica.peer_id).copied())
+                .ok_or_else(|| StorageError::BadRequest {
+                    description: format!(
+                        "Shard {} of {collection_name} does not exist",
+                        replica.shard_id
+                    ),
+                })?;
+            if state != Some(ReplicaState::Listener) {
+                return Err(StorageError::BadRequest {
+                    description: format!(
+                        "Replica {}:{} of {collection_name} is not a listener",
+                        replica.peer_id, replica.shard_id
+                    ),
+                });
+            }
+
+            dispatcher
+                .submit_collection_meta_op(
+                    CollectionMetaOperations::SetShardReplicaState(SetShardReplicaState {
+                        collection_name,
+                        shard_id: replica.shard_id,
+                        peer_id: replica.peer_id,
+                        state: ReplicaState::Active,
+                        from_state: Some(ReplicaState::Listener),
+                    }),
+                    wait_timeout,
+                )
+                .await
+        }
+        ClusterOperations::CreateShardingKey(_)
+        | ClusterOperations::DropShardingKey(_)
+        | ClusterOperations::StartResharding(_)
+        | ClusterOperations::AbortResharding(_) => {
+            unreachable!(
+                "shard key and resharding operations are handled before distributed mode checks"
+            )
+        }
     }
 }
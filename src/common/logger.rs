@@ -0,0 +1,392 @@
+//! Logger with a filter, which can be changed at runtime.
+//!
+//! Filter has the `env_logger` syntax: comma-separated `target=level` directives,
+//! e.g. `collection::shards=debug,segment::index=trace`.
+//! A changed filter may revert to the configured one after a TTL.
+
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use log::{LevelFilter, Log, Metadata, Record};
+use parking_lot::{Mutex, RwLock};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+static LOGGER: OnceLock<ReloadableLogger> = OnceLock::new();
+
+/// State of the log filter
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+pub struct LogFilterInfo {
+    /// Active filter of the log records
+    pub filter: String,
+    /// Filter from the configuration, which is restored after the TTL
+    pub default_filter: String,
+    /// Time, when the active filter reverts to the default one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Change of the log filter
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct SetLogFilterRequest {
+    /// Filter of the log records with comma-separated `target=level` directives,
+    /// e.g. `collection::shards=debug,segment::index=trace`. Empty filter restores the configured one.
+    pub filter: String,
+    /// Restore the configured filter after this number of seconds. Kept until changed, if not set.
+    #[validate(range(min = 1, max = 604800))]
+    pub ttl_sec: Option<u64>,
+}
+
+type LoggerBuilder = Box<dyn Fn(&str) -> env_logger::Logger + Send + Sync>;
+
+/// Delegates records to the logger with the active filter, which is replaced on filter change
+struct ReloadableLogger {
+    build: LoggerBuilder,
+    logger: RwLock<env_logger::Logger>,
+    state: Mutex<FilterState>,
+}
+
+struct FilterState {
+    info: LogFilterInfo,
+    /// Incremented on every change, so an expired TTL doesn't revert a newer filter
+    generation: u64,
+}
+
+impl ReloadableLogger {
+    fn new(default_filter: &str, build: LoggerBuilder) -> Self {
+        Self {
+            logger: RwLock::new(build(default_filter)),
+            build,
+            state: Mutex::new(FilterState {
+                info: LogFilterInfo {
+                    filter: default_filter.to_string(),
+                    default_filter: default_filter.to_string(),
+                    expires_at: None,
+                },
+                generation: 0,
+            }),
+        }
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.logger.read().filter()
+    }
+
+    fn info(&self) -> LogFilterInfo {
+        self.state.lock().info.clone()
+    }
+
+    /// Apply the `filter`, the default filter is restored after `ttl`.
+    /// An empty filter restores the default one.
+    fn set_filter(
+        &'static self,
+        filter: &str,
+        ttl: Option<Duration>,
+    ) -> Result<LogFilterInfo, String> {
+        let filter = filter.trim();
+        validate_filter(filter)?;
+
+        let mut state = self.state.lock();
+        state.generation += 1;
+        if filter.is_empty() {
+            state.info.filter = state.info.default_filter.clone();
+            state.info.expires_at = None;
+        } else {
+            state.info.filter = filter.to_string();
+            state.info.expires_at = ttl
+                .and_then(|ttl| {
+                    Utc::now().checked_add_signed(chrono::Duration::from_std(ttl).ok()?)
+                })
+                .map(|expires_at| expires_at.round_subsecs(0));
+        }
+        self.apply(&state.info.filter);
+
+        if let Some(ttl) = ttl.filter(|_| !filter.is_empty()) {
+            let generation = state.generation;
+            thread::Builder::new()
+                .name("log-filter-ttl".to_string())
+                .spawn(move || {
+                    thread::sleep(ttl);
+                    self.revert(generation);
+                })
+                .map_err(|err| format!("Failed to schedule the revert of the log filter: {err}"))?;
+        }
+
+        Ok(state.info.clone())
+    }
+
+    /// Restore the default filter, unless the filter was changed after the `generation`
+    fn revert(&self, generation: u64) {
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            return;
+        }
+        state.generation += 1;
+        state.info.filter = state.info.default_filter.clone();
+        state.info.expires_at = None;
+        self.apply(&state.info.filter);
+        log::info!(
+            "Log filter TTL expired, restored filter {}",
+            state.info.filter
+        );
+    }
+
+    fn apply(&self, filter: &str) {
+        let logger = (self.build)(filter);
+        let max_level = logger.filter();
+        *self.logger.write() = logger;
+        log::set_max_level(max_level);
+    }
+}
+
+impl Log for ReloadableLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.logger.read().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.logger.read().log(record)
+    }
+
+    fn flush(&self) {
+        self.logger.read().flush()
+    }
+}
+
+/// Logger with the user defined `filter`, verbose modules are limited by default
+fn build_logger(filter: &str) -> env_logger::Logger {
+    logger_builder(filter).build()
+}
+
+fn logger_builder(filter: &str) -> env_logger::Builder {
+    let is_info = filter.to_ascii_uppercase() == "INFO";
+    let mut log_builder = env_logger::Builder::new();
+
+    log_builder
+        // Timestamp in millis
+        .format_timestamp_millis()
+        // Parse user defined log level configuration
+        .parse_filters(filter)
+        // h2 is very verbose and we have many network operations,
+        // so it is limited to only errors
+        .filter_module("h2", LevelFilter::Error)
+        .filter_module("tower", LevelFilter::Warn);
+
+    if is_info {
+        // Additionally filter verbose modules if no extended logging configuration is provided
+        log_builder
+            .filter_module("wal", LevelFilter::Warn)
+            .filter_module("raft::raft", LevelFilter::Warn);
+    };
+
+    log_builder
+}
+
+/// Check the syntax of the filter, `env_logger` ignores invalid directives silently
+fn validate_filter(filter: &str) -> Result<(), String> {
+    let is_valid_target = |target: &str| {
+        !target.is_empty()
+            && target
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':' || c == '-')
+    };
+
+    for directive in filter.split(',').map(str::trim) {
+        if directive.is_empty() {
+            continue;
+        }
+        let is_valid = match directive.split_once('=') {
+            Some((target, level)) => {
+                is_valid_target(target) && level.parse::<LevelFilter>().is_ok()
+            }
+            // Either a level for all targets or a target with all levels
+            None => directive.parse::<LevelFilter>().is_ok() || is_valid_target(directive),
+        };
+        if !is_valid {
+            return Err(format!(
+                "Invalid log filter directive `{directive}`, expected `target=level`"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Install the logger with the configured filter
+pub fn setup_logger(log_level: &str) {
+    let logger = LOGGER.get_or_init(|| ReloadableLogger::new(log_level, Box::new(build_logger)));
+    log::set_logger(logger).expect("Logger is already initialized");
+    log::set_max_level(logger.max_level());
+}
+
+/// State of the log filter, `None` if the logger is not installed
+pub fn filter_info() -> Option<LogFilterInfo> {
+    LOGGER.get().map(ReloadableLogger::info)
+}
+
+/// Change the filter of the installed logger, see [`ReloadableLogger::set_filter`]
+pub fn set_filter(filter: &str, ttl: Option<Duration>) -> Result<LogFilterInfo, String> {
+    let logger = LOGGER
+        .get()
+        .ok_or_else(|| "Logger is not initialized".to_string())?;
+    logger.set_filter(filter, ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Write};
+    use std::sync::Arc;
+
+    use log::Level;
+
+    use super::*;
+
+    /// Collects the output of the logger
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Output {
+        fn take(&self) -> String {
+            String::from_utf8(std::mem::take(&mut *self.0.lock())).unwrap()
+        }
+    }
+
+    fn test_logger() -> (&'static ReloadableLogger, Output) {
+        let output = Output::default();
+        let target = output.clone();
+        let build = move |filter: &str| {
+            logger_builder(filter)
+                .format(|buf, record| writeln!(buf, "{} {}", record.target(), record.args()))
+                .target(env_logger::Target::Pipe(Box::new(target.clone())))
+                .build()
+        };
+        let logger = Box::leak(Box::new(ReloadableLogger::new("info", Box::new(build))));
+        (logger, output)
+    }
+
+    fn log(logger: &ReloadableLogger, target: &str, level: Level) {
+        logger.log(
+            &Record::builder()
+                .target(target)
+                .level(level)
+                .args(format_args!("{level}"))
+                .build(),
+        );
+    }
+
+    fn log_all(logger: &ReloadableLogger) {
+        for target in [
+            "collection::shards::local_shard",
+            "segment::index",
+            "storage",
+        ] {
+            for level in [Level::Info, Level::Debug, Level::Trace] {
+                log(logger, target, level);
+            }
+        }
+    }
+
+    #[test]
+    fn test_change_filter() {
+        let (logger, output) = test_logger();
+
+        log_all(logger);
+        assert_eq!(
+            output.take(),
+            "collection::shards::local_shard INFO\nsegment::index INFO\nstorage INFO\n"
+        );
+
+        // Only the affected modules emit the records at the new level
+        let info = logger
+            .set_filter("info,collection::shards=debug,segment::index=trace", None)
+            .unwrap();
+        assert_eq!(
+            info.filter,
+            "info,collection::shards=debug,segment::index=trace"
+        );
+        assert_eq!(info.default_filter, "info");
+        assert_eq!(info.expires_at, None);
+        assert_eq!(logger.max_level(), LevelFilter::Trace);
+
+        log_all(logger);
+        assert_eq!(
+            output.take(),
+            "collection::shards::local_shard INFO\n\
+             collection::shards::local_shard DEBUG\n\
+             segment::index INFO\n\
+             segment::index DEBUG\n\
+             segment::index TRACE\n\
+             storage INFO\n"
+        );
+
+        // Empty filter restores the default one
+        let info = logger.set_filter("", None).unwrap();
+        assert_eq!(info.filter, "info");
+        log_all(logger);
+        assert_eq!(output.take().lines().count(), 3);
+    }
+
+    #[test]
+    fn test_filter_ttl() {
+        let (logger, output) = test_logger();
+
+        let info = logger
+            .set_filter("storage=debug", Some(Duration::from_millis(200)))
+            .unwrap();
+        assert!(info.expires_at.is_some());
+        log(logger, "storage", Level::Debug);
+        assert_eq!(output.take(), "storage DEBUG\n");
+
+        thread::sleep(Duration::from_millis(500));
+        assert_eq!(logger.info().filter, "info");
+        assert_eq!(logger.info().expires_at, None);
+        log(logger, "storage", Level::Debug);
+        assert_eq!(output.take(), "");
+
+        // Expired TTL of a replaced filter doesn't revert the newer one
+        logger
+            .set_filter("storage=debug", Some(Duration::from_millis(100)))
+            .unwrap();
+        logger.set_filter("storage=trace", None).unwrap();
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(logger.info().filter, "storage=trace");
+    }
+
+    #[test]
+    fn test_invalid_filter() {
+        let (logger, _output) = test_logger();
+
+        for invalid in [
+            "storage=loud",
+            "=debug",
+            "storage/regex",
+            "a b=debug",
+            "storage = debug",
+        ] {
+            assert!(logger.set_filter(invalid, None).is_err(), "{invalid}");
+        }
+        assert_eq!(logger.info().filter, "info");
+
+        for valid in [
+            "debug",
+            "storage",
+            "storage=off",
+            " storage=debug, wal=warn ",
+        ] {
+            assert!(validate_filter(valid).is_ok(), "{valid}");
+        }
+    }
+}
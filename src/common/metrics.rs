@@ -1,5 +1,11 @@
-use prometheus::proto::{Counter, Gauge, LabelPair, Metric, MetricFamily, MetricType};
+use chrono::Utc;
+use collection::shards::telemetry::OptimizerTelemetry;
+use collection::telemetry::CollectionTelemetry;
+use prometheus::proto::{
+    Bucket, Counter, Gauge, Histogram, LabelPair, Metric, MetricFamily, MetricType,
+};
 use prometheus::TextEncoder;
+use segment::common::operation_time_statistics::OperationDurationStatistics;
 
 use crate::common::telemetry::TelemetryData;
 use crate::common::telemetry_ops::app_telemetry::AppBuildTelemetry;
@@ -13,32 +19,70 @@ use crate::common::telemetry_ops::requests_telemetry::{
 
 /// Whitelist for REST endpoints in metrics output.
 ///
-/// Contains selection of search, recommend and upsert endpoints.
+/// Contains collection and point endpoints, so the number of reported series is bounded.
 ///
 /// This array *must* be sorted.
 const REST_ENDPOINT_WHITELIST: &[&str] = &[
+    "/collections",
+    "/collections/{name}",
+    "/collections/{name}/facet",
     "/collections/{name}/index",
     "/collections/{name}/points",
+    "/collections/{name}/points/batch",
+    "/collections/{name}/points/count",
+    "/collections/{name}/points/delete",
+    "/collections/{name}/points/discover",
     "/collections/{name}/points/payload",
+    "/collections/{name}/points/payload/clear",
+    "/collections/{name}/points/payload/delete",
+    "/collections/{name}/points/query",
     "/collections/{name}/points/recommend",
     "/collections/{name}/points/recommend/batch",
+    "/collections/{name}/points/recommend/groups",
+    "/collections/{name}/points/scroll",
     "/collections/{name}/points/search",
     "/collections/{name}/points/search/batch",
+    "/collections/{name}/points/search/groups",
+    "/collections/{name}/points/vectors",
+    "/collections/{name}/points/vectors/delete",
+    "/collections/{name}/points/{id}",
 ];
 
 /// Whitelist for GRPC endpoints in metrics output.
 ///
-/// Contains selection of search, recommend and upsert endpoints.
+/// Contains collection and point endpoints, so the number of reported series is bounded.
 ///
 /// This array *must* be sorted.
 const GRPC_ENDPOINT_WHITELIST: &[&str] = &[
+    "/qdrant.Collections/Create",
+    "/qdrant.Collections/Delete",
+    "/qdrant.Collections/Get",
+    "/qdrant.Collections/List",
+    "/qdrant.Collections/Update",
+    "/qdrant.Points/ClearPayload",
+    "/qdrant.Points/Count",
+    "/qdrant.Points/CreateFieldIndex",
+    "/qdrant.Points/Delete",
+    "/qdrant.Points/DeleteFieldIndex",
+    "/qdrant.Points/DeletePayload",
+    "/qdrant.Points/DeleteVectors",
+    "/qdrant.Points/Discover",
+    "/qdrant.Points/Facet",
+    "/qdrant.Points/Get",
     "/qdrant.Points/OverwritePayload",
     "/qdrant.Points/Recommend",
     "/qdrant.Points/RecommendBatch",
+    "/qdrant.Points/RecommendGroups",
+    "/qdrant.Points/Scroll",
     "/qdrant.Points/Search",
     "/qdrant.Points/SearchBatch",
+    "/qdrant.Points/SearchGroups",
     "/qdrant.Points/SetPayload",
+    "/qdrant.Points/UpdateBatch",
+    "/qdrant.Points/UpdateFieldIndex",
+    "/qdrant.Points/UpdateVectors",
     "/qdrant.Points/Upsert",
+    "/qdrant.Points/UpsertStream",
 ];
 
 /// For REST requests, only report timings when having this HTTP response status.
@@ -114,6 +158,168 @@ impl MetricsProvider for CollectionsTelemetry {
             MetricType::GAUGE,
             vec![gauge(vector_count as f64, &[])],
         ));
+
+        let collections: Vec<_> = self
+            .collections
+            .iter()
+            .flatten()
+            .filter_map(|collection| match collection {
+                CollectionTelemetryEnum::Full(collection) => Some(collection),
+                CollectionTelemetryEnum::Aggregated(_) => None,
+            })
+            .collect();
+        if !collections.is_empty() {
+            add_collection_metrics(&collections, metrics);
+        }
+    }
+}
+
+/// Metrics of each collection, labeled with the collection name only
+fn add_collection_metrics(collections: &[&CollectionTelemetry], metrics: &mut Vec<MetricFamily>) {
+    let (mut searches, mut search_fails, mut search_secs) = (vec![], vec![], vec![]);
+    let (mut graph_hops, mut points_scored, mut filter_checks) = (vec![], vec![], vec![]);
+    let (mut updates, mut update_fails, mut update_secs) = (vec![], vec![], vec![]);
+    let (mut optimizations, mut optimization_fails, mut optimization_secs) =
+        (vec![], vec![], vec![]);
+    let mut index_build_secs = vec![];
+    let (mut wal_length, mut last_flush_age_secs) = (vec![], vec![]);
+
+    let now = Utc::now();
+    for collection in collections {
+        let labels = [("collection", collection.id.as_str())];
+
+        searches.push(counter(collection.searches.count as f64, &labels));
+        search_fails.push(counter(collection.searches.fail_count as f64, &labels));
+        search_secs.push(histogram(&collection.searches, &labels));
+        let search_stats = &collection.search_stats;
+        graph_hops.push(counter(search_stats.graph_hops as f64, &labels));
+        points_scored.push(counter(search_stats.points_scored as f64, &labels));
+        filter_checks.push(counter(search_stats.filter_checks as f64, &labels));
+        updates.push(counter(collection.updates.count as f64, &labels));
+        update_fails.push(counter(collection.updates.fail_count as f64, &labels));
+        update_secs.push(histogram(&collection.updates, &labels));
+
+        let local_shards = collection
+            .shards
+            .iter()
+            .filter_map(|shard| Some((shard.id, shard.local.as_ref()?)));
+        let mut optimizer = OptimizerTelemetry::default();
+        for (shard_id, local_shard) in local_shards {
+            optimizer = optimizer + local_shard.optimizations.clone();
+
+            let Some(flush) = &local_shard.flush else {
+                continue;
+            };
+            let shard_id = shard_id.to_string();
+            let labels = [
+                ("collection", collection.id.as_str()),
+                ("shard", shard_id.as_str()),
+            ];
+            wal_length.push(gauge(flush.wal_length as f64, &labels));
+            if let Some(last_flush) = flush.last_flush {
+                let age = (now - last_flush).num_milliseconds().max(0) as f64 / 1_000.0;
+                last_flush_age_secs.push(gauge(age, &labels));
+            }
+        }
+        optimizations.push(counter(optimizer.optimizations.count as f64, &labels));
+        optimization_fails.push(counter(optimizer.optimizations.fail_count as f64, &labels));
+        optimization_secs.push(histogram(&optimizer.optimizations, &labels));
+        index_build_secs.push(histogram(&optimizer.index_builds, &labels));
+    }
+
+    metrics.push(metric_family(
+        "collection_searches_total",
+        "total number of searches in the collection",
+        MetricType::COUNTER,
+        searches,
+    ));
+    metrics.push(metric_family(
+        "collection_searches_fail_total",
+        "total number of failed searches in the collection",
+        MetricType::COUNTER,
+        search_fails,
+    ));
+    metrics.push(metric_family(
+        "collection_search_duration_seconds",
+        "duration of successful searches in the collection",
+        MetricType::HISTOGRAM,
+        search_secs,
+    ));
+    metrics.push(metric_family(
+        "collection_search_graph_hops_total",
+        "total number of HNSW graph points expanded by searches of the local shards",
+        MetricType::COUNTER,
+        graph_hops,
+    ));
+    metrics.push(metric_family(
+        "collection_search_points_scored_total",
+        "total number of vectors scored by searches of the local shards",
+        MetricType::COUNTER,
+        points_scored,
+    ));
+    metrics.push(metric_family(
+        "collection_search_filter_checks_total",
+        "total number of points checked against filters by searches of the local shards",
+        MetricType::COUNTER,
+        filter_checks,
+    ));
+    metrics.push(metric_family(
+        "collection_updates_total",
+        "total number of updates of the collection",
+        MetricType::COUNTER,
+        updates,
+    ));
+    metrics.push(metric_family(
+        "collection_updates_fail_total",
+        "total number of failed updates of the collection",
+        MetricType::COUNTER,
+        update_fails,
+    ));
+    metrics.push(metric_family(
+        "collection_update_duration_seconds",
+        "duration of successful updates of the collection",
+        MetricType::HISTOGRAM,
+        update_secs,
+    ));
+    metrics.push(metric_family(
+        "collection_optimizations_total",
+        "total number of optimizations of the collection",
+        MetricType::COUNTER,
+        optimizations,
+    ));
+    metrics.push(metric_family(
+        "collection_optimizations_fail_total",
+        "total number of failed optimizations of the collection",
+        MetricType::COUNTER,
+        optimization_fails,
+    ));
+    metrics.push(metric_family(
+        "collection_optimization_duration_seconds",
+        "duration of successful optimizations of the collection",
+        MetricType::HISTOGRAM,
+        optimization_secs,
+    ));
+    metrics.push(metric_family(
+        "collection_hnsw_build_duration_seconds",
+        "duration of HNSW index builds in the collection",
+        MetricType::HISTOGRAM,
+        index_build_secs,
+    ));
+    if !wal_length.is_empty() {
+        metrics.push(metric_family(
+            "collection_shard_wal_length",
+            "number of operations in WAL of the local shard",
+            MetricType::GAUGE,
+            wal_length,
+        ));
+    }
+    if !last_flush_age_secs.is_empty() {
+        metrics.push(metric_family(
+            "collection_shard_last_flush_age_seconds",
+            "time since the last flush of the local shard",
+            MetricType::GAUGE,
+            last_flush_age_secs,
+        ));
     }
 }
 
@@ -181,6 +387,7 @@ impl MetricsProvider for WebApiTelemetry {
     fn add_metrics(&self, metrics: &mut Vec<MetricFamily>) {
         let (mut total, mut fail_total, mut avg_secs, mut min_secs, mut max_secs) =
             (vec![], vec![], vec![], vec![], vec![]);
+        let mut duration_secs = vec![];
         for (endpoint, responses) in &self.responses {
             let (method, endpoint) = endpoint.split_once(' ').unwrap();
 
@@ -211,6 +418,7 @@ impl MetricsProvider for WebApiTelemetry {
                         stats.max_duration_micros.unwrap_or(0.0) as f64 / 1_000_000.0,
                         &labels,
                     ));
+                    duration_secs.push(histogram(stats, &labels));
                 }
             }
         }
@@ -255,6 +463,14 @@ impl MetricsProvider for WebApiTelemetry {
                 max_secs,
             ));
         }
+        if !duration_secs.is_empty() {
+            metrics.push(metric_family(
+                "rest_responses_duration_seconds",
+                "response duration",
+                MetricType::HISTOGRAM,
+                duration_secs,
+            ));
+        }
     }
 }
 
@@ -262,6 +478,7 @@ impl MetricsProvider for GrpcTelemetry {
     fn add_metrics(&self, metrics: &mut Vec<MetricFamily>) {
         let (mut total, mut fail_total, mut avg_secs, mut min_secs, mut max_secs) =
             (vec![], vec![], vec![], vec![], vec![]);
+        let mut duration_secs = vec![];
         for (endpoint, stats) in &self.responses {
             // Endpoint must be whitelisted
             if GRPC_ENDPOINT_WHITELIST
@@ -286,6 +503,7 @@ impl MetricsProvider for GrpcTelemetry {
                 stats.max_duration_micros.unwrap_or(0.0) as f64 / 1_000_000.0,
                 &labels,
             ));
+            duration_secs.push(histogram(stats, &labels));
         }
 
         if !total.is_empty() {
@@ -328,6 +546,14 @@ impl MetricsProvider for GrpcTelemetry {
                 max_secs,
             ));
         }
+        if !duration_secs.is_empty() {
+            metrics.push(metric_family(
+                "grpc_responses_duration_seconds",
+                "response duration",
+                MetricType::HISTOGRAM,
+                duration_secs,
+            ));
+        }
     }
 }
 
@@ -362,6 +588,31 @@ fn gauge(value: f64, labels: &[(&str, &str)]) -> Metric {
     metric
 }
 
+/// Histogram of successful operation durations, in seconds
+fn histogram(stats: &OperationDurationStatistics, labels: &[(&str, &str)]) -> Metric {
+    let mut metric = Metric::default();
+    metric.set_label(labels.iter().map(|(n, v)| label_pair(n, v)).collect());
+    metric.set_histogram({
+        let mut histogram = Histogram::default();
+        histogram.set_sample_count(stats.count as u64);
+        histogram.set_sample_sum(stats.total_duration_micros as f64 / 1_000_000.0);
+        histogram.set_bucket(
+            stats
+                .duration_micros_histogram
+                .iter()
+                .map(|&(upper_bound_micros, count)| {
+                    let mut bucket = Bucket::default();
+                    bucket.set_upper_bound(upper_bound_micros as f64 / 1_000_000.0);
+                    bucket.set_cumulative_count(count as u64);
+                    bucket
+                })
+                .collect(),
+        );
+        histogram
+    });
+    metric
+}
+
 fn label_pair(name: &str, value: &str) -> LabelPair {
     let mut label = LabelPair::default();
     label.set_name(name.into());
@@ -384,4 +635,43 @@ mod tests {
             "GRPC_ENDPOINT_WHITELIST must be sorted in code to allow binary search"
         );
     }
+
+    #[test]
+    fn test_duration_histogram() {
+        use std::time::Duration;
+
+        use prometheus::proto::MetricType;
+        use segment::common::operation_time_statistics::OperationDurationsAggregator;
+
+        use super::{histogram, metric_family, MetricsData};
+
+        let aggregator = OperationDurationsAggregator::new();
+        for millis in [2, 3, 20, 2_000] {
+            aggregator
+                .lock()
+                .add_operation_result(true, Duration::from_millis(millis));
+        }
+        aggregator
+            .lock()
+            .add_operation_result(false, Duration::from_millis(1));
+        let stats = aggregator.lock().get_statistics();
+
+        let metrics = MetricsData {
+            metrics: vec![metric_family(
+                "test_duration_seconds",
+                "test durations",
+                MetricType::HISTOGRAM,
+                vec![histogram(&stats, &[("collection", "test")])],
+            )],
+        };
+        let text = metrics.format_metrics();
+
+        assert!(text.contains(r#"test_duration_seconds_bucket{collection="test",le="0.001"} 0"#));
+        assert!(text.contains(r#"test_duration_seconds_bucket{collection="test",le="0.005"} 2"#));
+        assert!(text.contains(r#"test_duration_seconds_bucket{collection="test",le="0.05"} 3"#));
+        assert!(text.contains(r#"test_duration_seconds_bucket{collection="test",le="5"} 4"#));
+        assert!(text.contains(r#"test_duration_seconds_bucket{collection="test",le="+Inf"} 4"#));
+        assert!(text.contains(r#"test_duration_seconds_sum{collection="test"} 2.025"#));
+        assert!(text.contains(r#"test_duration_seconds_count{collection="test"} 4"#));
+    }
 }
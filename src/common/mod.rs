@@ -1,14 +1,23 @@
+pub mod auth;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod collections;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod error_reporting;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod helpers;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod logger;
 pub mod metrics;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod points;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod readiness;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod shutdown;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod telemetry;
 pub mod telemetry_ops;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod telemetry_reporting;
+pub mod trace_context;
+pub mod upsert_stream;
@@ -1,16 +1,22 @@
+use std::time::Duration;
+
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
 use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointsSelector, WriteOrdering,
 };
+use collection::operations::shard_key_selector::{ShardKeySelector, ShardSelectorInternal};
 use collection::operations::types::{
-    CountRequest, CountResult, GroupsResult, PointRequest, RecommendGroupsRequest, Record,
-    ScrollRequest, ScrollResult, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
-    UpdateResult,
+    CountRequest, CountResult, GroupsResult, PointRequest, RecommendGroupsRequest,
+    RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult,
+    SearchGroupsRequest, SearchRequest, SearchRequestBatch, UpdateResult, UpdateStatus,
 };
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors, VectorOperations};
-use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
-use collection::shards::shard::ShardId;
+use collection::operations::{
+    ClientOperationId, CollectionUpdateOperations, CreateIndex, FieldIndexOperations,
+};
+use collection::shards::shard::{ShardId, ShardKey};
+use itertools::Itertools;
 use schemars::JsonSchema;
 use segment::types::{PayloadFieldSchema, ScoredPoint};
 use serde::{Deserialize, Serialize};
@@ -25,6 +31,93 @@ pub struct CreateFieldIndex {
     pub field_schema: Option<PayloadFieldSchema>,
 }
 
+/// Points to insert into the collection, with the shard key they belong to
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct UpsertPoints {
+    #[serde(flatten)]
+    #[validate]
+    pub operation: PointInsertOperations,
+    /// Shard key of the points, required for collections with custom sharding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKey>,
+}
+
+/// Search request, limited to the shards of the shard keys
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct SearchPoints {
+    #[serde(flatten)]
+    #[validate]
+    pub search_request: SearchRequest,
+    /// Shard key, or list of shard keys, to search in.
+    /// Required for collections with custom sharding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+    /// If true, the response reports the work done by the search of the local shards:
+    /// graph hops, scored points and filter checks. Default: false
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_stats: Option<bool>,
+}
+
+/// Batch of searches, limited to the shards of the shard keys
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct SearchBatchPoints {
+    #[serde(flatten)]
+    #[validate]
+    pub search_batch: SearchRequestBatch,
+    /// Shard key, or list of shard keys, to search in by every search of the batch.
+    /// Required for collections with custom sharding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+}
+
+/// Recommend request, limited to the shards of the shard keys
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct RecommendPoints {
+    #[serde(flatten)]
+    #[validate]
+    pub recommend_request: RecommendRequest,
+    /// Shard key, or list of shard keys, to look up the examples and to search in.
+    /// Required for collections with custom sharding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+}
+
+/// Batch of recommend requests, limited to the shards of the shard keys
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct RecommendBatchPoints {
+    #[serde(flatten)]
+    #[validate]
+    pub recommend_batch: RecommendRequestBatch,
+    /// Shard key, or list of shard keys, to look up the examples and to search in by every
+    /// request of the batch. Required for collections with custom sharding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+}
+
+/// Scroll request, limited to the shards of the shard keys
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct ScrollPoints {
+    #[serde(flatten)]
+    #[validate]
+    pub scroll_request: ScrollRequest,
+    /// Shard key, or list of shard keys, to scroll points of.
+    /// Required for collections with custom sharding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+}
+
+/// Count request, limited to the shards of the shard keys
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct CountPoints {
+    #[serde(flatten)]
+    #[validate]
+    pub count_request: CountRequest,
+    /// Shard key, or list of shard keys, to count points of.
+    /// Required for collections with custom sharding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard_key: Option<ShardKeySelector>,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateOperation {
@@ -63,7 +156,8 @@ pub async fn do_upsert_points(
     toc: &TableOfContent,
     collection_name: &str,
     operation: PointInsertOperations,
-    shard_selection: Option<ShardId>,
+    shard_selection: impl Into<ShardSelectorInternal>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
@@ -73,19 +167,26 @@ pub async fn do_upsert_points(
         collection_name,
         collection_operation,
         shard_selection,
+        client_op_id,
         wait,
         ordering,
     )
     .await
 }
 
+/// Delete points by ids or by filter.
+///
+/// Delete by filter reports the number of deleted points, and their ids if `with_deleted_ids`.
+#[allow(clippy::too_many_arguments)]
 pub async fn do_delete_points(
     toc: &TableOfContent,
     collection_name: &str,
     points: PointsSelector,
     shard_selection: Option<ShardId>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
+    with_deleted_ids: bool,
 ) -> Result<UpdateResult, StorageError> {
     let point_operation = match points {
         PointsSelector::PointIdsSelector(points) => {
@@ -96,14 +197,20 @@ pub async fn do_delete_points(
         }
     };
     let collection_operation = CollectionUpdateOperations::PointOperation(point_operation);
-    toc.update(
-        collection_name,
-        collection_operation,
-        shard_selection,
-        wait,
-        ordering,
-    )
-    .await
+    let mut result = toc
+        .update(
+            collection_name,
+            collection_operation,
+            shard_selection,
+            client_op_id,
+            wait,
+            ordering,
+        )
+        .await?;
+    if !with_deleted_ids {
+        result.deleted_ids = None;
+    }
+    Ok(result)
 }
 
 pub async fn do_update_vectors(
@@ -111,6 +218,7 @@ pub async fn do_update_vectors(
     collection_name: &str,
     operation: UpdateVectors,
     shard_selection: Option<ShardId>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
@@ -120,6 +228,7 @@ pub async fn do_update_vectors(
         collection_name,
         collection_operation,
         shard_selection,
+        client_op_id,
         wait,
         ordering,
     )
@@ -131,9 +240,16 @@ pub async fn do_delete_vectors(
     collection_name: &str,
     operation: DeleteVectors,
     shard_selection: Option<ShardId>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
+    if client_op_id.is_some() && operation.filter.is_some() && operation.points.is_some() {
+        return Err(StorageError::bad_request(
+            "Operation with client_op_id can delete vectors either by filter or by points",
+        ));
+    }
+
     let vector_names: Vec<_> = operation.vector.into_iter().collect();
 
     let mut result = None;
@@ -147,6 +263,7 @@ pub async fn do_delete_vectors(
                 collection_name,
                 collection_operation,
                 shard_selection,
+                client_op_id,
                 wait,
                 ordering,
             )
@@ -162,6 +279,7 @@ pub async fn do_delete_vectors(
                 collection_name,
                 collection_operation,
                 shard_selection,
+                client_op_id,
                 wait,
                 ordering,
             )
@@ -177,6 +295,7 @@ pub async fn do_set_payload(
     collection_name: &str,
     operation: SetPayload,
     shard_selection: Option<ShardId>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
@@ -186,6 +305,7 @@ pub async fn do_set_payload(
         collection_name,
         collection_operation,
         shard_selection,
+        client_op_id,
         wait,
         ordering,
     )
@@ -197,6 +317,7 @@ pub async fn do_overwrite_payload(
     collection_name: &str,
     operation: SetPayload,
     shard_selection: Option<ShardId>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
@@ -206,6 +327,7 @@ pub async fn do_overwrite_payload(
         collection_name,
         collection_operation,
         shard_selection,
+        client_op_id,
         wait,
         ordering,
     )
@@ -217,6 +339,7 @@ pub async fn do_delete_payload(
     collection_name: &str,
     operation: DeletePayload,
     shard_selection: Option<ShardId>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
@@ -226,6 +349,7 @@ pub async fn do_delete_payload(
         collection_name,
         collection_operation,
         shard_selection,
+        client_op_id,
         wait,
         ordering,
     )
@@ -237,6 +361,7 @@ pub async fn do_clear_payload(
     collection_name: &str,
     points: PointsSelector,
     shard_selection: Option<ShardId>,
+    client_op_id: Option<ClientOperationId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
@@ -254,115 +379,139 @@ pub async fn do_clear_payload(
         collection_name,
         collection_operation,
         shard_selection,
+        client_op_id,
         wait,
         ordering,
     )
     .await
 }
 
-pub async fn do_batch_update_points(
-    toc: &TableOfContent,
-    collection_name: &str,
-    operations: Vec<UpdateOperation>,
-    shard_selection: Option<ShardId>,
-    wait: bool,
-    ordering: WriteOrdering,
-) -> Result<Vec<UpdateResult>, StorageError> {
-    let mut results = Vec::with_capacity(operations.len());
-    for operation in operations {
-        let result = match operation {
+impl UpdateOperation {
+    /// Collection operations to perform this operation, in order
+    fn into_collection_operations(self) -> Result<Vec<CollectionUpdateOperations>, StorageError> {
+        let operations = match self {
             UpdateOperation::Upsert(operation) => {
-                do_upsert_points(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                vec![CollectionUpdateOperations::PointOperation(
+                    PointOperations::UpsertPoints(operation),
+                )]
+            }
+            UpdateOperation::Delete(PointsSelector::PointIdsSelector(points)) => {
+                vec![CollectionUpdateOperations::PointOperation(
+                    PointOperations::DeletePoints { ids: points.points },
+                )]
             }
-            UpdateOperation::Delete(operation) => {
-                do_delete_points(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+            UpdateOperation::Delete(PointsSelector::FilterSelector(filter_selector)) => {
+                vec![CollectionUpdateOperations::PointOperation(
+                    PointOperations::DeletePointsByFilter(filter_selector.filter),
+                )]
             }
             UpdateOperation::SetPayload(operation) => {
-                do_set_payload(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                vec![CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::SetPayload(operation),
+                )]
             }
             UpdateOperation::OverwritePayload(operation) => {
-                do_overwrite_payload(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                vec![CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::OverwritePayload(operation),
+                )]
             }
             UpdateOperation::DeletePayload(operation) => {
-                do_delete_payload(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                vec![CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::DeletePayload(operation),
+                )]
             }
-            UpdateOperation::ClearPayload(operation) => {
-                do_clear_payload(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+            UpdateOperation::ClearPayload(PointsSelector::PointIdsSelector(points)) => {
+                vec![CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::ClearPayload {
+                        points: points.points,
+                    },
+                )]
+            }
+            UpdateOperation::ClearPayload(PointsSelector::FilterSelector(filter_selector)) => {
+                vec![CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::ClearPayloadByFilter(filter_selector.filter),
+                )]
             }
             UpdateOperation::UpdateVectors(operation) => {
-                do_update_vectors(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                vec![CollectionUpdateOperations::VectorOperation(
+                    VectorOperations::UpdateVectors(operation),
+                )]
             }
             UpdateOperation::DeleteVectors(operation) => {
-                do_delete_vectors(
-                    toc,
-                    collection_name,
-                    operation,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                let vector_names: Vec<_> = operation.vector.into_iter().collect();
+                let mut operations = Vec::new();
+                if let Some(filter) = operation.filter {
+                    operations.push(CollectionUpdateOperations::VectorOperation(
+                        VectorOperations::DeleteVectorsByFilter(filter, vector_names.clone()),
+                    ));
+                }
+                if let Some(points) = operation.points {
+                    operations.push(CollectionUpdateOperations::VectorOperation(
+                        VectorOperations::DeleteVectors(points.into(), vector_names),
+                    ));
+                }
+                if operations.is_empty() {
+                    return Err(StorageError::bad_request("No filter or points provided"));
+                }
+                operations
             }
-        }?;
-        results.push(result);
+        };
+        Ok(operations)
+    }
+}
+
+/// Apply operations of the batch in order, with a result per operation.
+///
+/// Every shard applies its part of the batch as consecutive operations, so an operation can
+/// rely on the previous operations of the batch. A failed operation doesn't stop the batch,
+/// it is reported with `Failed` status instead.
+pub async fn do_batch_update_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operations: Vec<UpdateOperation>,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<Vec<UpdateResult>, StorageError> {
+    // position of the operation in the batch, for each of the collection operations
+    let mut positions = Vec::with_capacity(operations.len());
+    let mut collection_operations = Vec::with_capacity(operations.len());
+    for (idx, operation) in operations.into_iter().enumerate() {
+        for collection_operation in operation.into_collection_operations()? {
+            positions.push(idx);
+            collection_operations.push(collection_operation);
+        }
     }
-    Ok(results)
+
+    let results = toc
+        .update_batch(
+            collection_name,
+            collection_operations,
+            shard_selection,
+            wait,
+            ordering,
+        )
+        .await?;
+
+    // operation is failed if any of its collection operations failed
+    let mut merged: Vec<UpdateResult> = Vec::with_capacity(results.len());
+    for (_, group) in &positions.into_iter().zip(results).group_by(|(idx, _)| *idx) {
+        let mut errors = Vec::new();
+        let mut last_result = None;
+        for (_, result) in group {
+            errors.extend(result.error.clone());
+            last_result = Some(result);
+        }
+        let mut result = last_result.expect("group is not empty");
+        // Batch reports only the number of points deleted by filter
+        result.deleted_ids = None;
+        if !errors.is_empty() {
+            result.status = UpdateStatus::Failed;
+            result.error = Some(errors.join("; "));
+        }
+        merged.push(result);
+    }
+    Ok(merged)
 }
 
 pub async fn do_create_index(
@@ -383,6 +532,32 @@ pub async fn do_create_index(
         collection_name,
         collection_operation,
         shard_selection,
+        None,
+        wait,
+        ordering,
+    )
+    .await
+}
+
+pub async fn do_update_index(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: CreateFieldIndex,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::UpdateIndex(CreateIndex {
+            field_name: operation.field_name,
+            field_schema: operation.field_schema,
+        }),
+    );
+    toc.update(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        None,
         wait,
         ordering,
     )
@@ -404,6 +579,7 @@ pub async fn do_delete_index(
         collection_name,
         collection_operation,
         shard_selection,
+        None,
         wait,
         ordering,
     )
@@ -415,10 +591,17 @@ pub async fn do_search_points(
     collection_name: &str,
     request: SearchRequest,
     read_consistency: Option<ReadConsistency>,
-    shard_selection: Option<ShardId>,
+    shard_selection: impl Into<ShardSelectorInternal>,
+    timeout: Option<Duration>,
 ) -> Result<Vec<ScoredPoint>, StorageError> {
-    toc.search(collection_name, request, read_consistency, shard_selection)
-        .await
+    toc.search(
+        collection_name,
+        request,
+        read_consistency,
+        shard_selection,
+        timeout,
+    )
+    .await
 }
 
 pub async fn do_search_batch_points(
@@ -426,10 +609,17 @@ pub async fn do_search_batch_points(
     collection_name: &str,
     request: SearchRequestBatch,
     read_consistency: Option<ReadConsistency>,
-    shard_selection: Option<ShardId>,
-) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
-    toc.search_batch(collection_name, request, read_consistency, shard_selection)
-        .await
+    shard_selection: impl Into<ShardSelectorInternal>,
+    timeout: Option<Duration>,
+) -> Result<Vec<Result<Vec<ScoredPoint>, StorageError>>, StorageError> {
+    toc.search_batch(
+        collection_name,
+        request,
+        read_consistency,
+        shard_selection,
+        timeout,
+    )
+    .await
 }
 
 pub async fn do_search_point_groups(
@@ -462,7 +652,7 @@ pub async fn do_count_points(
     toc: &TableOfContent,
     collection_name: &str,
     request: CountRequest,
-    shard_selection: Option<ShardId>,
+    shard_selection: impl Into<ShardSelectorInternal>,
 ) -> Result<CountResult, StorageError> {
     toc.count(collection_name, request, shard_selection).await
 }
@@ -483,7 +673,7 @@ pub async fn do_scroll_points(
     collection_name: &str,
     request: ScrollRequest,
     read_consistency: Option<ReadConsistency>,
-    shard_selection: Option<ShardId>,
+    shard_selection: impl Into<ShardSelectorInternal>,
 ) -> Result<ScrollResult, StorageError> {
     toc.scroll(collection_name, request, read_consistency, shard_selection)
         .await
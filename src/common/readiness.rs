@@ -0,0 +1,67 @@
+//! Readiness of the node to serve requests.
+//!
+//! The node is ready, once the local shards of all collections are loaded with their WAL replayed,
+//! and, if configured, the node has caught up with the consensus.
+//! The API is served only after collections are loaded at startup, so until then probes fail
+//! to connect at all.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use storage::dispatcher::Dispatcher;
+
+pub type ReadinessRef = Arc<Readiness>;
+
+/// Readiness of the node, which stays ready once it got ready
+#[derive(Debug, Default)]
+pub struct Readiness {
+    is_ready: AtomicBool,
+    /// Maximal number of committed consensus entries not applied on this node yet.
+    /// Not checked, if not set.
+    max_commit_lag: Option<u64>,
+}
+
+impl Readiness {
+    pub fn new(max_commit_lag: Option<u64>) -> Self {
+        Self {
+            is_ready: AtomicBool::new(false),
+            max_commit_lag,
+        }
+    }
+
+    /// Check if the node is ready.
+    ///
+    /// Returns the reason, why the node is not ready yet.
+    pub async fn check(&self, dispatcher: &Dispatcher) -> Result<(), String> {
+        if self.is_ready.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let not_ready = dispatcher.toc().not_ready_collections().await;
+        if !not_ready.is_empty() {
+            return Err(format!(
+                "Collections are not ready yet: {}",
+                not_ready.join(", ")
+            ));
+        }
+
+        if let (Some(max_commit_lag), Some(consensus_state)) =
+            (self.max_commit_lag, dispatcher.consensus_state())
+        {
+            if !consensus_state.is_leader_established.check_ready() {
+                return Err("Consensus leader is not established yet".to_string());
+            }
+            let commit_lag = consensus_state.commit_lag();
+            if commit_lag > max_commit_lag {
+                return Err(format!(
+                    "Consensus is behind by {commit_lag} operations, at most {max_commit_lag} allowed"
+                ));
+            }
+        }
+
+        if !self.is_ready.swap(true, Ordering::Relaxed) {
+            log::info!("Node is ready to serve requests");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! Graceful shutdown of the service.
+//!
+//! On the stop signal new requests are rejected, and the requests in progress are given time to
+//! finish. Then API servers are stopped and the storage is persisted.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::signal;
+use tokio::sync::watch;
+
+/// How often the number of requests in progress is checked while draining
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// How often the drain progress is logged
+const DRAIN_LOG_INTERVAL: Duration = Duration::from_secs(1);
+
+pub type ShutdownRef = Arc<Shutdown>;
+
+/// State of the shutdown, shared by the API servers
+#[derive(Debug)]
+pub struct Shutdown {
+    /// New requests are rejected
+    is_shutting_down: AtomicBool,
+    /// Number of requests in progress
+    in_flight: AtomicUsize,
+    /// `true` once servers should stop
+    stop_servers: watch::Sender<bool>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        let (stop_servers, _) = watch::channel(false);
+        Self {
+            is_shutting_down: AtomicBool::new(false),
+            in_flight: AtomicUsize::new(0),
+            stop_servers,
+        }
+    }
+}
+
+impl Shutdown {
+    pub fn is_shutting_down(&self) -> bool {
+        self.is_shutting_down.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Register a new request, which is in progress while the guard is alive.
+    ///
+    /// Returns `None` if the service is shutting down, and the request must be rejected.
+    pub fn start_request(self: &Arc<Self>) -> Option<RequestGuard> {
+        // Counter is incremented first, so the drain can't miss a request, which passed the check
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = RequestGuard {
+            shutdown: self.clone(),
+        };
+        if self.is_shutting_down() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Stop accepting new requests, and wait for the requests in progress to finish.
+    ///
+    /// Returns `false` if some requests are still in progress after the `timeout`.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.is_shutting_down.store(true, Ordering::SeqCst);
+
+        let start = Instant::now();
+        let mut last_log = start;
+        loop {
+            let in_flight = self.in_flight();
+            if in_flight == 0 {
+                log::info!("All requests in progress are finished");
+                return true;
+            }
+            if start.elapsed() >= timeout {
+                log::warn!(
+                    "Drain timeout of {}s expired, {in_flight} requests are still in progress",
+                    timeout.as_secs(),
+                );
+                return false;
+            }
+            if last_log.elapsed() >= DRAIN_LOG_INTERVAL {
+                log::info!("Waiting for {in_flight} requests in progress to finish");
+                last_log = Instant::now();
+            }
+            tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+        }
+    }
+
+    pub fn stop_servers(&self) {
+        self.stop_servers.send_replace(true);
+    }
+
+    /// Resolves, once servers should stop
+    pub async fn wait_stop_servers(&self) {
+        let mut receiver = self.stop_servers.subscribe();
+        while !*receiver.borrow_and_update() {
+            if receiver.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Request in progress, see [`Shutdown::start_request`]
+#[derive(Debug)]
+pub struct RequestGuard {
+    shutdown: ShutdownRef,
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        self.shutdown.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn wait_stop_signal(for_what: &str) {
+    signal::ctrl_c().await.unwrap();
+    log::debug!("Stopping {for_what} on SIGINT");
+}
+
+#[cfg(unix)]
+pub async fn wait_stop_signal(for_what: &str) {
+    let mut term = signal::unix::signal(signal::unix::SignalKind::terminate()).unwrap();
+    let mut inrt = signal::unix::signal(signal::unix::SignalKind::interrupt()).unwrap();
+
+    tokio::select! {
+        _ = term.recv() => log::debug!("Stopping {for_what} on SIGTERM"),
+        _ = inrt.recv() => log::debug!("Stopping {for_what} on SIGINT"),
+    }
+}
+
+/// On the stop signal, drain the requests in progress and stop the API servers
+pub async fn stop_on_signal(shutdown: ShutdownRef, drain_timeout: Duration) {
+    wait_stop_signal("service").await;
+    log::info!(
+        "Shutting down, waiting up to {}s for the requests in progress",
+        drain_timeout.as_secs(),
+    );
+    shutdown.drain(drain_timeout).await;
+    shutdown.stop_servers();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain() {
+        let shutdown = ShutdownRef::default();
+
+        let first = shutdown.start_request().unwrap();
+        let second = shutdown.start_request().unwrap();
+        assert_eq!(shutdown.in_flight(), 2);
+        drop(second);
+
+        let drain = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.drain(Duration::from_secs(10)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // New requests are rejected while draining
+        assert!(shutdown.is_shutting_down());
+        assert!(shutdown.start_request().is_none());
+        assert_eq!(shutdown.in_flight(), 1);
+
+        drop(first);
+        assert!(drain.await.unwrap());
+
+        // Drain gives up after the timeout
+        let shutdown = ShutdownRef::default();
+        let _request = shutdown.start_request().unwrap();
+        assert!(!shutdown.drain(Duration::from_millis(200)).await);
+    }
+
+    #[tokio::test]
+    async fn test_stop_servers() {
+        let shutdown = ShutdownRef::default();
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.wait_stop_servers().await }
+        });
+        shutdown.stop_servers();
+        waiter.await.unwrap();
+
+        // Servers, which start waiting later, stop immediately
+        shutdown.wait_stop_servers().await;
+    }
+}
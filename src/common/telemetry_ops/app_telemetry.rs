@@ -5,6 +5,7 @@ use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
 
+use crate::common::logger::{self, LogFilterInfo};
 use crate::settings::Settings;
 
 pub struct AppBuildTelemetryCollector {
@@ -48,6 +49,10 @@ pub struct AppBuildTelemetry {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub system: Option<RunningEnvironmentTelemetry>,
+    /// Active filter of the log records
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub log_filter: Option<LogFilterInfo>,
     pub startup: DateTime<Utc>,
 }
 
@@ -75,6 +80,11 @@ impl AppBuildTelemetry {
             } else {
                 None
             },
+            log_filter: if level > 0 {
+                logger::filter_info()
+            } else {
+                None
+            },
             startup: collector.startup,
         }
     }
@@ -145,6 +155,7 @@ impl Anonymize for AppBuildTelemetry {
             version: self.version.clone(),
             features: self.features.anonymize(),
             system: self.system.anonymize(),
+            log_filter: self.log_filter.clone(),
             startup: self.startup.anonymize(),
         }
     }
@@ -3,7 +3,7 @@ use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
 use storage::dispatcher::Dispatcher;
-use storage::types::{ClusterStatus, ConsensusThreadStatus, StateRole};
+use storage::types::{ClusterStatus, ConsensusTelemetry, ConsensusThreadStatus, StateRole};
 
 use crate::settings::Settings;
 
@@ -52,6 +52,7 @@ pub struct ClusterStatusTelemetry {
     pub is_voter: bool,
     pub peer_id: Option<PeerId>,
     pub consensus_thread_status: ConsensusThreadStatus,
+    pub consensus_telemetry: ConsensusTelemetry,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -77,6 +78,7 @@ impl ClusterTelemetry {
                     is_voter: cluster_info.raft_info.is_voter,
                     peer_id: Some(cluster_info.peer_id),
                     consensus_thread_status: cluster_info.consensus_thread_status,
+                    consensus_telemetry: cluster_info.consensus_telemetry,
                 }),
             }
         } else {
@@ -118,6 +120,7 @@ impl Anonymize for ClusterStatusTelemetry {
             is_voter: self.is_voter,
             peer_id: None,
             consensus_thread_status: self.consensus_thread_status.clone(),
+            consensus_telemetry: self.consensus_telemetry.anonymize(),
         }
     }
 }
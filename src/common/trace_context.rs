@@ -0,0 +1,177 @@
+//! Propagation of the request trace context.
+//!
+//! Context is accepted in the W3C `traceparent` format, see <https://www.w3.org/TR/trace-context/>
+
+use uuid::Uuid;
+
+/// Header (or gRPC metadata key) with the trace context of the caller
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Trace context of the caller, parsed from the `traceparent` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: u128,
+    /// Id of the caller span, the request span becomes its child
+    pub parent_id: u64,
+    /// Whether the caller records the trace
+    pub sampled: bool,
+}
+
+impl TraceContext {
+    /// Parse the `traceparent` header, `None` if it is malformed
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut parts = traceparent.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+
+        let is_hex = |value: &str, len: usize| {
+            value.len() == len
+                && value
+                    .bytes()
+                    .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        };
+        if !is_hex(version, 2) || !is_hex(trace_id, 32) || !is_hex(parent_id, 16) {
+            return None;
+        }
+        if !is_hex(flags, 2) || version == "ff" {
+            return None;
+        }
+        // Later versions may append fields, version 00 has exactly four
+        if version == "00" && parts.next().is_some() {
+            return None;
+        }
+
+        let trace_id = u128::from_str_radix(trace_id, 16).ok()?;
+        let parent_id = u64::from_str_radix(parent_id, 16).ok()?;
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        if trace_id == 0 || parent_id == 0 {
+            return None;
+        }
+
+        Some(Self {
+            trace_id,
+            parent_id,
+            sampled: flags & 0x01 != 0,
+        })
+    }
+}
+
+/// Whether the request is traced.
+///
+/// A request with a trace context follows the decision of the caller,
+/// `sampling_ratio` of other requests are traced.
+pub fn is_sampled(trace_context: Option<&TraceContext>, sampling_ratio: f64) -> bool {
+    match trace_context {
+        Some(trace_context) => trace_context.sampled,
+        None if sampling_ratio >= 1.0 => true,
+        None if sampling_ratio <= 0.0 => false,
+        None => {
+            let (random, _) = Uuid::new_v4().as_u64_pair();
+            (random as f64) < sampling_ratio * u64::MAX as f64
+        }
+    }
+}
+
+/// Root span of the request.
+///
+/// Spans of the collection and segment operations are created within it.
+/// Not sampled requests get a disabled span, so no spans are recorded for them.
+pub fn request_span(
+    api: &'static str,
+    endpoint: &str,
+    traceparent: Option<&str>,
+    sampling_ratio: f64,
+) -> tracing::Span {
+    let trace_context = traceparent.and_then(TraceContext::parse);
+    if !is_sampled(trace_context.as_ref(), sampling_ratio) {
+        return tracing::Span::none();
+    }
+
+    let span = tracing::info_span!(
+        "request",
+        api,
+        endpoint,
+        trace_id = tracing::field::Empty,
+        parent_span_id = tracing::field::Empty,
+    );
+    if let Some(trace_context) = trace_context {
+        span.record(
+            "trace_id",
+            tracing::field::display(format_args!("{:032x}", trace_context.trace_id)),
+        );
+        span.record(
+            "parent_span_id",
+            tracing::field::display(format_args!("{:016x}", trace_context.parent_id)),
+        );
+        #[cfg(feature = "otlp")]
+        set_remote_parent(&span, &trace_context);
+    }
+    span
+}
+
+/// Make the exported request span a child of the caller span
+#[cfg(feature = "otlp")]
+fn set_remote_parent(span: &tracing::Span, trace_context: &TraceContext) {
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let span_context = SpanContext::new(
+        TraceId::from_bytes(trace_context.trace_id.to_be_bytes()),
+        SpanId::from_bytes(trace_context.parent_id.to_be_bytes()),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    span.set_parent(opentelemetry::Context::new().with_remote_span_context(span_context));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_traceparent() {
+        let trace_context =
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(trace_context.trace_id, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(trace_context.parent_id, 0x00f067aa0ba902b7);
+        assert!(trace_context.sampled);
+
+        let not_sampled =
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00").unwrap();
+        assert!(!not_sampled.sampled);
+        assert!(!is_sampled(Some(&not_sampled), 1.0));
+        assert!(is_sampled(Some(&trace_context), 0.0));
+
+        // Fields of the later versions are ignored
+        assert!(TraceContext::parse(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra"
+        )
+        .is_some());
+
+        for invalid in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(TraceContext::parse(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_sampling_ratio() {
+        assert!(is_sampled(None, 1.0));
+        assert!(!is_sampled(None, 0.0));
+        let sampled = (0..10_000).filter(|_| is_sampled(None, 0.1)).count();
+        assert!((500..1500).contains(&sampled), "{sampled}");
+    }
+}
@@ -0,0 +1,474 @@
+//! Incremental parsing of points upsert bodies.
+//!
+//! Points of the `points` list are parsed one by one and handed over in batches, so the memory
+//! used by an upsert is bounded by the batch size rather than by the size of the body.
+//!
+//! Batches are handed over only once their shard key is known. If the collection requires
+//! a shard key, and it comes after the points in the body, all points are held until the end.
+
+use std::cell::Cell;
+use std::fmt;
+use std::io::{self, Read};
+use std::mem;
+use std::rc::Rc;
+
+use collection::operations::point_ops::{Batch, PointInsertOperations, PointStruct};
+use collection::shards::shard::ShardKey;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserializer as _;
+
+use crate::common::points::UpsertPoints;
+
+/// Limits of an incrementally parsed upsert body
+#[derive(Debug, Clone, Copy)]
+pub struct UpsertStreamLimits {
+    /// Max size of the whole body in bytes
+    pub max_body_size: Option<usize>,
+    /// Max size in bytes of a part of the body, which is held in memory at once
+    pub max_buffered_size: usize,
+    /// Number of points, handed over at once
+    pub batch_size: usize,
+}
+
+#[derive(Debug)]
+pub enum UpsertStreamError {
+    /// Body exceeds [`UpsertStreamLimits::max_body_size`]
+    BodyTooLarge { limit: usize },
+    /// Part of the body, held in memory at once, exceeds [`UpsertStreamLimits::max_buffered_size`]
+    BufferedTooLarge { limit: usize },
+    /// Body is not a valid upsert request
+    Format(serde_json::Error),
+    /// Receiver of the batches refused to take more
+    Aborted,
+}
+
+impl fmt::Display for UpsertStreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BodyTooLarge { limit } => write!(f, "body exceeds {limit} bytes"),
+            Self::BufferedTooLarge { limit } => {
+                write!(f, "part of the body, parsed at once, exceeds {limit} bytes")
+            }
+            Self::Format(err) => write!(f, "{err}"),
+            Self::Aborted => write!(f, "parsing aborted"),
+        }
+    }
+}
+
+/// Parse the upsert body from the `reader`, handing over its points to `on_upsert` in batches.
+///
+/// `on_upsert` returns `false` to stop parsing. The shard key of all batches is waited for,
+/// if `wait_for_shard_key`. Otherwise, batches before the shard key are handed over without it.
+pub fn parse_upsert_stream(
+    reader: impl Read,
+    limits: UpsertStreamLimits,
+    wait_for_shard_key: bool,
+    on_upsert: impl FnMut(UpsertPoints) -> bool,
+) -> Result<(), UpsertStreamError> {
+    let progress = Rc::new(ReadProgress::default());
+    // Not buffered, so the read position is the position of the parser
+    let reader = LimitedReader {
+        inner: reader,
+        limits,
+        progress: progress.clone(),
+    };
+
+    let mut parser = Parser {
+        limits,
+        wait_for_shard_key,
+        on_upsert,
+        progress: progress.clone(),
+        shard_key: None,
+        has_operation: false,
+        handed_over: 0,
+        deferred: Vec::new(),
+        aborted: false,
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let result = deserializer
+        .deserialize_map(&mut parser)
+        .and_then(|()| deserializer.end());
+
+    result.map_err(|err| {
+        if let Some(exceeded) = progress.exceeded.take() {
+            exceeded
+        } else if parser.aborted {
+            UpsertStreamError::Aborted
+        } else {
+            UpsertStreamError::Format(err)
+        }
+    })
+}
+
+#[derive(Default)]
+struct ReadProgress {
+    /// Number of bytes read so far
+    read: Cell<usize>,
+    /// Position, since which the read data is held in memory
+    buffered_since: Cell<Option<usize>>,
+    exceeded: Cell<Option<UpsertStreamError>>,
+}
+
+/// Reader, which fails once the body exceeds the limits
+struct LimitedReader<R> {
+    inner: R,
+    limits: UpsertStreamLimits,
+    progress: Rc<ReadProgress>,
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        let read = self.progress.read.get() + count;
+        self.progress.read.set(read);
+
+        let exceeded = match (
+            self.limits.max_body_size,
+            self.progress.buffered_since.get(),
+        ) {
+            (Some(limit), _) if read > limit => Some(UpsertStreamError::BodyTooLarge { limit }),
+            (_, Some(since)) if read - since > self.limits.max_buffered_size => {
+                Some(UpsertStreamError::BufferedTooLarge {
+                    limit: self.limits.max_buffered_size,
+                })
+            }
+            _ => None,
+        };
+        if let Some(exceeded) = exceeded {
+            let description = exceeded.to_string();
+            self.progress.exceeded.set(Some(exceeded));
+            return Err(io::Error::new(io::ErrorKind::InvalidData, description));
+        }
+
+        Ok(count)
+    }
+}
+
+struct Parser<F> {
+    limits: UpsertStreamLimits,
+    wait_for_shard_key: bool,
+    on_upsert: F,
+    progress: Rc<ReadProgress>,
+    /// Shard key of the body, once it is parsed
+    shard_key: Option<Option<ShardKey>>,
+    has_operation: bool,
+    /// Number of operations handed over so far
+    handed_over: usize,
+    /// Operations, parsed before their shard key
+    deferred: Vec<PointInsertOperations>,
+    aborted: bool,
+}
+
+impl<F: FnMut(UpsertPoints) -> bool> Parser<F> {
+    fn can_hand_over(&self) -> bool {
+        self.shard_key.is_some() || !self.wait_for_shard_key
+    }
+
+    fn start_buffering(&self) {
+        let read = self.progress.read.get();
+        self.progress.buffered_since.set(Some(read));
+    }
+
+    fn stop_buffering(&self) {
+        self.progress.buffered_since.set(None);
+    }
+
+    fn hand_over<E: de::Error>(&mut self, operation: PointInsertOperations) -> Result<(), E> {
+        let upsert = UpsertPoints {
+            operation,
+            shard_key: self.shard_key.clone().flatten(),
+        };
+        self.handed_over += 1;
+        if !(self.on_upsert)(upsert) {
+            self.aborted = true;
+            return Err(E::custom("parsing aborted"));
+        }
+        Ok(())
+    }
+
+    fn hand_over_or_defer<E: de::Error>(
+        &mut self,
+        operation: PointInsertOperations,
+    ) -> Result<(), E> {
+        if self.can_hand_over() {
+            self.stop_buffering();
+            self.hand_over(operation)
+        } else {
+            // Keep buffering till the end of the body
+            self.deferred.push(operation);
+            Ok(())
+        }
+    }
+
+    fn hand_over_deferred<E: de::Error>(&mut self) -> Result<(), E> {
+        for operation in mem::take(&mut self.deferred) {
+            match operation {
+                PointInsertOperations::PointsList(points) if !points.is_empty() => {
+                    let mut points = points.into_iter().peekable();
+                    while points.peek().is_some() {
+                        let batch = points.by_ref().take(self.limits.batch_size).collect();
+                        self.hand_over(PointInsertOperations::PointsList(batch))?;
+                    }
+                }
+                operation => self.hand_over(operation)?,
+            }
+        }
+        self.stop_buffering();
+        Ok(())
+    }
+}
+
+impl<'de, F: FnMut(UpsertPoints) -> bool> Visitor<'de> for &mut Parser<F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("points upsert request")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "shard_key" => {
+                    if self.shard_key.is_some() {
+                        return Err(de::Error::duplicate_field("shard_key"));
+                    }
+                    self.shard_key = Some(map.next_value()?);
+                }
+                "points" | "batch" => {
+                    if self.has_operation {
+                        return Err(de::Error::custom(
+                            "only one of `points` or `batch` is expected",
+                        ));
+                    }
+                    self.has_operation = true;
+                    self.start_buffering();
+                    if key == "points" {
+                        map.next_value_seed(PointsSeed(&mut *self))?;
+                    } else {
+                        let batch: Batch = map.next_value()?;
+                        self.hand_over_or_defer(PointInsertOperations::PointsBatch(batch))?;
+                    }
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !self.has_operation {
+            return Err(de::Error::custom("expected `points` or `batch` field"));
+        }
+        // Shard key is known by now
+        self.shard_key.get_or_insert(None);
+        self.hand_over_deferred()
+    }
+}
+
+/// Parses the `points` list, handing over full batches
+struct PointsSeed<'a, F>(&'a mut Parser<F>);
+
+impl<'de, F: FnMut(UpsertPoints) -> bool> DeserializeSeed<'de> for PointsSeed<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(UpsertPoints) -> bool> Visitor<'de> for PointsSeed<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("list of points")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let parser = self.0;
+        let batch_size = parser.limits.batch_size;
+        let mut batch = Vec::new();
+        while let Some(point) = seq.next_element::<PointStruct>()? {
+            batch.push(point);
+            if batch.len() >= batch_size && parser.can_hand_over() {
+                let points = mem::take(&mut batch);
+                parser.hand_over(PointInsertOperations::PointsList(points))?;
+                parser.start_buffering();
+            }
+        }
+        // Empty list is handed over too, so the request gets the result of an upsert
+        if !batch.is_empty() || parser.handed_over == 0 {
+            parser.hand_over_or_defer(PointInsertOperations::PointsList(batch))?;
+        } else {
+            parser.stop_buffering();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::data_types::vectors::VectorStruct;
+    use serde_json::json;
+
+    use super::*;
+
+    const LIMITS: UpsertStreamLimits = UpsertStreamLimits {
+        max_body_size: None,
+        max_buffered_size: 1024 * 1024,
+        batch_size: 3,
+    };
+
+    fn points_body(count: u64) -> serde_json::Value {
+        let points: Vec<_> = (0..count)
+            .map(|id| json!({"id": id, "vector": [id as f32, 1.0], "payload": {"n": id}}))
+            .collect();
+        json!({ "points": points })
+    }
+
+    /// Parse the `body`, returning the number of points and the shard key of each upsert
+    fn parse(
+        body: &serde_json::Value,
+        limits: UpsertStreamLimits,
+        wait_for_shard_key: bool,
+    ) -> Result<Vec<(usize, Option<ShardKey>)>, UpsertStreamError> {
+        let body = serde_json::to_vec(body).unwrap();
+        let mut upserts = Vec::new();
+        parse_upsert_stream(body.as_slice(), limits, wait_for_shard_key, |upsert| {
+            let count = match upsert.operation {
+                PointInsertOperations::PointsList(points) => points.len(),
+                PointInsertOperations::PointsBatch(batch) => batch.ids.len(),
+            };
+            upserts.push((count, upsert.shard_key));
+            true
+        })?;
+        Ok(upserts)
+    }
+
+    #[test]
+    fn test_points_are_handed_over_in_batches() {
+        let upserts = parse(&points_body(8), LIMITS, false).unwrap();
+        assert_eq!(upserts, vec![(3, None), (3, None), (2, None)]);
+
+        let upserts = parse(&points_body(6), LIMITS, false).unwrap();
+        assert_eq!(upserts, vec![(3, None), (3, None)]);
+
+        let upserts = parse(&points_body(0), LIMITS, false).unwrap();
+        assert_eq!(upserts, vec![(0, None)]);
+
+        // Points are parsed as in a buffered request
+        let body = serde_json::to_vec(&points_body(2)).unwrap();
+        let mut points = Vec::new();
+        parse_upsert_stream(body.as_slice(), LIMITS, false, |upsert| {
+            let PointInsertOperations::PointsList(list) = upsert.operation else {
+                panic!("expected a list of points");
+            };
+            points.extend(list);
+            true
+        })
+        .unwrap();
+        assert_eq!(points[1].id, 1.into());
+        assert!(matches!(
+            &points[1].vector,
+            VectorStruct::Single(vector) if vector == &vec![1.0, 1.0],
+        ));
+    }
+
+    #[test]
+    fn test_shard_key() {
+        let shard_key = ShardKey::from("tenant");
+        let mut body = points_body(7);
+        body["shard_key"] = json!("tenant");
+
+        // Key after the points is waited for
+        let upserts = parse(&body, LIMITS, true).unwrap();
+        assert_eq!(
+            upserts,
+            vec![
+                (3, Some(shard_key.clone())),
+                (3, Some(shard_key.clone())),
+                (1, Some(shard_key.clone())),
+            ],
+        );
+
+        // Key before the points is known for all batches
+        let body = format!(r#"{{"shard_key": "tenant", "points": {}}}"#, body["points"]);
+        let mut upserts = Vec::new();
+        parse_upsert_stream(body.as_bytes(), LIMITS, true, |upsert| {
+            upserts.push(upsert.shard_key);
+            true
+        })
+        .unwrap();
+        assert_eq!(upserts.len(), 3);
+        assert!(upserts.iter().all(|key| key == &Some(shard_key.clone())));
+
+        // Waiting for the key holds all points in memory
+        let limits = UpsertStreamLimits {
+            max_buffered_size: 200,
+            ..LIMITS
+        };
+        let mut body = points_body(100);
+        body["shard_key"] = json!("tenant");
+        assert!(matches!(
+            parse(&body, limits, true),
+            Err(UpsertStreamError::BufferedTooLarge { limit: 200 }),
+        ));
+        assert_eq!(parse(&body, limits, false).unwrap().len(), 34);
+    }
+
+    #[test]
+    fn test_batch() {
+        let body = json!({
+            "batch": {
+                "ids": [1, 2],
+                "vectors": [[1.0, 2.0], [3.0, 4.0]],
+            },
+        });
+        assert_eq!(parse(&body, LIMITS, false).unwrap(), vec![(2, None)]);
+
+        // Batch is held in memory whole
+        let limits = UpsertStreamLimits {
+            max_buffered_size: 16,
+            ..LIMITS
+        };
+        assert!(matches!(
+            parse(&body, limits, false),
+            Err(UpsertStreamError::BufferedTooLarge { .. }),
+        ));
+    }
+
+    #[test]
+    fn test_errors() {
+        let limits = UpsertStreamLimits {
+            max_body_size: Some(100),
+            ..LIMITS
+        };
+        assert!(matches!(
+            parse(&points_body(100), limits, false),
+            Err(UpsertStreamError::BodyTooLarge { limit: 100 }),
+        ));
+
+        for body in [
+            json!({}),
+            json!({ "points": [{ "id": 1 }] }),
+            json!({ "points": [], "batch": { "ids": [], "vectors": [] } }),
+            json!([]),
+        ] {
+            assert!(
+                matches!(
+                    parse(&body, LIMITS, false),
+                    Err(UpsertStreamError::Format(_)),
+                ),
+                "{body}",
+            );
+        }
+
+        let body = serde_json::to_vec(&points_body(10)).unwrap();
+        let mut count = 0;
+        let result = parse_upsert_stream(body.as_slice(), LIMITS, false, |_| {
+            count += 1;
+            false
+        });
+        assert!(matches!(result, Err(UpsertStreamError::Aborted)));
+        assert_eq!(count, 1);
+    }
+}
@@ -10,13 +10,14 @@ use api::grpc::dynamic_channel_pool::make_grpc_channel;
 use api::grpc::qdrant::raft_client::RaftClient;
 use api::grpc::qdrant::{AllPeers, PeerId as GrpcPeerId, RaftMessage as GrpcRaftMessage};
 use api::grpc::transport_channel_pool::TransportChannelPool;
+use collection::operations::types::NodeType;
 use collection::shards::channel_service::ChannelService;
 use collection::shards::shard::PeerId;
 use prost::Message as _;
 use raft::eraftpb::Message as RaftMessage;
 use raft::prelude::*;
 use raft::{SoftState, StateRole, INVALID_ID};
-use storage::content_manager::consensus_manager::ConsensusStateRef;
+use storage::content_manager::consensus_manager::{add_peer_context, ConsensusStateRef};
 use storage::content_manager::consensus_ops::{ConsensusOperations, SnapshotStatus};
 use storage::content_manager::toc::TableOfContent;
 use tokio::runtime::Handle;
@@ -26,6 +27,7 @@ use tokio::time::sleep;
 use tonic::transport::{ClientTlsConfig, Uri};
 
 use crate::common::helpers;
+use crate::common::shutdown::ShutdownRef;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
 use crate::settings::{ConsensusConfig, Settings};
 use crate::tonic::init_internal;
@@ -69,11 +71,13 @@ impl Consensus {
         telemetry_collector: Arc<parking_lot::Mutex<TonicTelemetryCollector>>,
         toc: Arc<TableOfContent>,
         runtime: Handle,
+        shutdown: ShutdownRef,
     ) -> anyhow::Result<JoinHandle<std::io::Result<()>>> {
         let tls_client_config = helpers::load_tls_client_config(&settings)?;
 
         let p2p_host = settings.service.host;
         let p2p_port = settings.cluster.p2p.port.expect("P2P port is not set");
+        let listener = settings.storage.node_type == NodeType::Listener;
         let config = settings.cluster.consensus;
 
         let (mut consensus, message_sender) = Self::new(
@@ -82,6 +86,7 @@ impl Consensus {
             bootstrap_peer,
             uri,
             p2p_port,
+            listener,
             config,
             tls_client_config,
             channel_service,
@@ -138,6 +143,7 @@ impl Consensus {
                     server_tls,
                     message_sender,
                     runtime,
+                    shutdown,
                 )
             })
             .unwrap();
@@ -145,7 +151,8 @@ impl Consensus {
         Ok(handle)
     }
 
-    /// If `bootstrap_peer` peer is supplied, then either `uri` or `p2p_port` should be also supplied
+    /// If `bootstrap_peer` peer is supplied, then either `uri` or `p2p_port` should be also supplied.
+    /// `listener` peers join as learners, which are never promoted to voters.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         logger: &slog::Logger,
@@ -153,11 +160,14 @@ impl Consensus {
         bootstrap_peer: Option<Uri>,
         uri: Option<String>,
         p2p_port: u16,
+        listener: bool,
         config: ConsensusConfig,
         tls_config: Option<ClientTlsConfig>,
         channel_service: ChannelService,
         runtime: Handle,
     ) -> anyhow::Result<(Self, Sender<Message>)> {
+        // Snapshot, which was received before the restart, must be installed before replaying entries
+        state_ref.resume_snapshot_install()?;
         // raft will not return entries to the application smaller or equal to `applied`
         let last_applied = state_ref.last_applied_entry().unwrap_or_default();
         let raft_config = Config {
@@ -187,6 +197,7 @@ impl Consensus {
                 bootstrap_peer.clone(),
                 uri,
                 p2p_port,
+                listener,
                 &config,
                 tls_config.clone(),
                 runtime.clone(),
@@ -199,6 +210,7 @@ impl Consensus {
                     &state_ref,
                     uri.clone(),
                     p2p_port,
+                    listener,
                     &config,
                     tls_config.clone(),
                 ))
@@ -245,6 +257,7 @@ impl Consensus {
         bootstrap_peer: Option<Uri>,
         uri: Option<String>,
         p2p_port: u16,
+        listener: bool,
         config: &ConsensusConfig,
         tls_config: Option<ClientTlsConfig>,
         runtime: Handle,
@@ -257,6 +270,7 @@ impl Consensus {
                 bootstrap_peer,
                 uri,
                 p2p_port,
+                listener,
                 config,
                 tls_config,
             ))?;
@@ -282,6 +296,7 @@ impl Consensus {
         cluster_uri: Uri,
         current_uri: Option<String>,
         p2p_port: u16,
+        listener: bool,
         config: &ConsensusConfig,
         tls_config: Option<ClientTlsConfig>,
     ) -> anyhow::Result<AllPeers> {
@@ -301,6 +316,7 @@ impl Consensus {
                     uri: current_uri,
                     port: Some(p2p_port as u32),
                     id: this_peer_id,
+                    listener: Some(listener),
                 },
             ))
             .await
@@ -315,6 +331,7 @@ impl Consensus {
         state_ref: &ConsensusStateRef,
         uri: Option<String>,
         p2p_port: u16,
+        listener: bool,
         config: &ConsensusConfig,
         tls_config: Option<ClientTlsConfig>,
     ) -> anyhow::Result<()> {
@@ -342,6 +359,7 @@ impl Consensus {
                         peer_uri.clone(),
                         uri.clone(),
                         p2p_port,
+                        listener,
                         config,
                         tls_config.clone(),
                     )
@@ -381,13 +399,14 @@ impl Consensus {
     ///
     /// 1. Add current node as a learner
     /// 2. Start applying entries from consensus
-    /// 3. Eventually leader submits the promotion proposal
+    /// 3. Eventually leader submits the promotion proposal, unless the node is a listener
     /// 4. Learners become voters once they read about the promotion from consensus log
     async fn bootstrap(
         state_ref: &ConsensusStateRef,
         bootstrap_peer: Uri,
         uri: Option<String>,
         p2p_port: u16,
+        listener: bool,
         config: &ConsensusConfig,
         tls_config: Option<ClientTlsConfig>,
     ) -> anyhow::Result<()> {
@@ -397,6 +416,7 @@ impl Consensus {
             bootstrap_peer,
             uri.clone(),
             p2p_port,
+            listener,
             config,
             tls_config,
         )
@@ -443,12 +463,16 @@ impl Consensus {
                 timeout = Duration::from_millis(self.config.tick_period_ms);
                 // We drive Raft every `tick_period_ms`.
                 self.node.tick();
+                self.update_consensus_telemetry();
                 // Try to reapply entries if some were not applied due to errors.
                 let store = self.node.store().clone();
                 let stop_consensus = store.apply_entries(&mut self.node)?;
                 if stop_consensus {
                     return Ok(());
                 }
+                if let Err(err) = store.compact_wal(self.config.compact_wal_entries) {
+                    log::warn!("Failed to compact consensus WAL: {err}");
+                }
             } else {
                 timeout -= d;
             }
@@ -459,6 +483,26 @@ impl Consensus {
         }
     }
 
+    /// Refresh telemetry cached in the consensus state, so it can be read without blocking this thread.
+    fn update_consensus_telemetry(&self) {
+        let raft = &self.node.raft;
+        let peers_last_sent_index = if raft.state == StateRole::Leader {
+            raft.prs()
+                .iter()
+                .filter(|(id, _)| **id != raft.id)
+                .map(|(id, progress)| (*id, progress.next_idx.saturating_sub(1)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        if let Err(err) = self
+            .store()
+            .update_consensus_telemetry(raft.has_pending_conf(), peers_last_sent_index)
+        {
+            log::warn!("Failed to update consensus telemetry: {err}");
+        }
+    }
+
     fn try_sync_local_state(&mut self) -> anyhow::Result<()> {
         if !self.node.has_ready() {
             // No updates to process
@@ -510,14 +554,19 @@ impl Consensus {
                         log::debug!("Proposing network configuration change: {:?}", change);
                         self.node.propose_conf_change(vec![], change)
                     }
-                    ConsensusOperations::AddPeer { peer_id, uri } => {
+                    ConsensusOperations::AddPeer {
+                        peer_id,
+                        uri,
+                        listener,
+                    } => {
                         let mut change = ConfChangeV2::default();
                         change.set_changes(vec![raft_proto::new_conf_change_single(
                             peer_id,
                             ConfChangeType::AddLearnerNode,
                         )]);
                         log::debug!("Proposing network configuration change: {:?}", change);
-                        self.node.propose_conf_change(uri.into_bytes(), change)
+                        self.node
+                            .propose_conf_change(add_peer_context(uri, listener), change)
                     }
                     ConsensusOperations::RequestSnapshot => self.node.request_snapshot(),
                     ConsensusOperations::ReportSnapshot { peer_id, status } => {
@@ -587,14 +636,17 @@ impl Consensus {
         Ok(true)
     }
 
+    /// Listener peers stay learners, they are never promoted
     fn find_learner_to_promote(&self) -> Option<u64> {
         let commit = self.node.store().hard_state().commit;
+        let listener_peers = self.node.store().listener_peers();
         let learners: HashSet<_> = self
             .node
             .store()
             .conf_state()
             .learners
             .into_iter()
+            .filter(|learner| !listener_peers.contains(learner))
             .collect();
         let status = self.node.status();
         status
@@ -1179,6 +1231,7 @@ mod tests {
             None,
             Some("http://127.0.0.1:6335".parse().unwrap()),
             6335,
+            false,
             ConsensusConfig::default(),
             None,
             ChannelService::default(),
@@ -1221,17 +1274,25 @@ mod tests {
                                 hnsw_config: None,
                                 quantization_config: None,
                                 on_disk: None,
+                                datatype: None,
+                                multivector_config: None,
                             }
                             .into(),
                             hnsw_config: None,
                             wal_config: None,
                             optimizers_config: None,
                             shard_number: Some(2),
+                            sharding_method: None,
                             on_disk_payload: None,
+                            tenant_key: None,
                             replication_factor: None,
                             write_consistency_factor: None,
                             init_from: None,
                             quantization_config: None,
+                            sparse_vectors: None,
+                            store_original_vectors: None,
+                            search_timeout_sec: None,
+                            strict_mode_config: None,
                         },
                     )),
                     None,
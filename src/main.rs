@@ -37,13 +37,15 @@ use crate::common::helpers::{
     create_general_purpose_runtime, create_search_runtime, create_update_runtime,
     load_tls_client_config,
 };
+use crate::common::logger::setup_logger;
+use crate::common::shutdown::{stop_on_signal, ShutdownRef};
 use crate::common::telemetry::TelemetryCollector;
 use crate::common::telemetry_reporting::TelemetryReporter;
 use crate::greeting::welcome;
 use crate::migrations::single_to_cluster::handle_existing_collections;
 use crate::settings::Settings;
 use crate::snapshots::{recover_full_snapshot, recover_snapshots};
-use crate::startup::{remove_started_file_indicator, setup_logger, touch_started_file_indicator};
+use crate::startup::{remove_started_file_indicator, touch_started_file_indicator};
 
 #[cfg(not(target_env = "msvc"))]
 #[global_allocator]
@@ -107,8 +109,6 @@ struct Args {
 }
 
 fn main() -> anyhow::Result<()> {
-    tracing::setup()?;
-
     remove_started_file_indicator();
 
     let args = Args::parse();
@@ -121,6 +121,8 @@ fn main() -> anyhow::Result<()> {
     setup_logger(&settings.log_level);
     setup_panic_hook(reporting_enabled, reporting_id.to_string());
 
+    tracing::setup(&settings.tracing)?;
+
     segment::madvise::set_global(settings.storage.mmap_advice);
     segment::vector_storage::common::set_async_scorer(settings.storage.async_scorer);
 
@@ -229,6 +231,7 @@ fn main() -> anyhow::Result<()> {
         for collection in toc.all_collections().await {
             log::debug!("Loaded collection: {}", collection);
         }
+        toc.resume_resharding().await;
     });
 
     let toc_arc = Arc::new(toc);
@@ -237,6 +240,9 @@ fn main() -> anyhow::Result<()> {
     // Holder for all actively running threads of the service: web, gPRC, consensus, etc.
     let mut handles: Vec<JoinHandle<Result<(), Error>>> = vec![];
 
+    // Shared by the API servers, to drain requests and stop on shutdown
+    let shutdown = ShutdownRef::default();
+
     // Router for external queries.
     // It decides if query should go directly to the ToC or through the consensus.
     let mut dispatcher = Dispatcher::new(toc_arc.clone());
@@ -277,6 +283,7 @@ fn main() -> anyhow::Result<()> {
             tonic_telemetry_collector,
             toc_arc.clone(),
             runtime_handle.clone(),
+            shutdown.clone(),
         )
         .expect("Can't initialize consensus");
 
@@ -328,6 +335,7 @@ fn main() -> anyhow::Result<()> {
     };
 
     let tonic_telemetry_collector = telemetry_collector.tonic_telemetry_collector.clone();
+    let consensus_state = dispatcher_arc.consensus_state().cloned();
 
     //
     // Telemetry reporting
@@ -361,12 +369,18 @@ fn main() -> anyhow::Result<()> {
     {
         let dispatcher_arc = dispatcher_arc.clone();
         let settings = settings.clone();
+        let shutdown = shutdown.clone();
         let handle = thread::Builder::new()
             .name("web".to_string())
             .spawn(move || {
                 log_err_if_any(
                     "REST",
-                    actix::init(dispatcher_arc.clone(), telemetry_collector, settings),
+                    actix::init(
+                        dispatcher_arc.clone(),
+                        telemetry_collector,
+                        settings,
+                        shutdown,
+                    ),
                 )
             })
             .unwrap();
@@ -379,6 +393,8 @@ fn main() -> anyhow::Result<()> {
 
     if let Some(grpc_port) = settings.service.grpc_port {
         let settings = settings.clone();
+        let runtime_handle = runtime_handle.clone();
+        let shutdown = shutdown.clone();
         let handle = thread::Builder::new()
             .name("grpc".to_string())
             .spawn(move || {
@@ -390,6 +406,7 @@ fn main() -> anyhow::Result<()> {
                         settings,
                         grpc_port,
                         runtime_handle,
+                        shutdown,
                     ),
                 )
             })
@@ -436,6 +453,12 @@ fn main() -> anyhow::Result<()> {
 
     touch_started_file_indicator();
 
+    // Servers stop once requests are drained after the stop signal
+    runtime_handle.spawn(stop_on_signal(
+        shutdown.clone(),
+        Duration::from_secs(settings.service.shutdown_drain_timeout_sec),
+    ));
+
     for handle in handles.into_iter() {
         log::debug!(
             "Waiting for thread {} to finish",
@@ -443,6 +466,16 @@ fn main() -> anyhow::Result<()> {
         );
         handle.join().expect("thread is not panicking")?;
     }
+
+    // No new updates are accepted at this point, so all applied operations can be persisted
+    runtime_handle.block_on(toc_arc.shutdown());
+    if let Some(consensus_state) = consensus_state {
+        if let Err(err) = consensus_state.persist() {
+            log::error!("Failed to save consensus state: {err}");
+        }
+    }
+    log::info!("Shutdown complete");
+
     drop(toc_arc);
     drop(settings);
     Ok(())
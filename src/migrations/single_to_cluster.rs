@@ -1,3 +1,4 @@
+use std::num::NonZeroU64;
 use std::sync::Arc;
 
 use collection::shards::replica_set::ReplicaState;
@@ -34,7 +35,9 @@ pub async fn handle_existing_collections(
             collection.to_string(),
             CreateCollection {
                 vectors: collection_state.config.params.vectors,
+                sparse_vectors: collection_state.config.params.sparse_vectors,
                 shard_number: Some(shards_number),
+                sharding_method: collection_state.config.params.sharding_method,
                 replication_factor: Some(collection_state.config.params.replication_factor.get()),
                 write_consistency_factor: Some(
                     collection_state
@@ -44,11 +47,19 @@ pub async fn handle_existing_collections(
                         .get(),
                 ),
                 on_disk_payload: Some(collection_state.config.params.on_disk_payload),
+                tenant_key: collection_state.config.params.tenant_key,
+                store_original_vectors: Some(collection_state.config.params.store_original_vectors),
+                search_timeout_sec: collection_state
+                    .config
+                    .params
+                    .search_timeout_sec
+                    .map(NonZeroU64::get),
                 hnsw_config: Some(collection_state.config.hnsw_config.into()),
                 wal_config: Some(collection_state.config.wal_config.into()),
                 optimizers_config: Some(collection_state.config.optimizer_config.into()),
                 init_from: None,
                 quantization_config: collection_state.config.quantization_config,
+                strict_mode_config: collection_state.config.strict_mode_config,
             },
         );
 
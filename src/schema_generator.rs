@@ -1,14 +1,19 @@
-use api::grpc::models::CollectionsResponse;
+use api::grpc::models::{CollectionExistence, CollectionsResponse};
+use collection::collection_manager::optimizers::optimization_tasks::OptimizationTaskInfo;
+use collection::common::resource_usage::CollectionResourceUsage;
+use collection::common::search_recall::{RecallTestReport, RecallTestRequest};
 use collection::operations::cluster_ops::ClusterOperations;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::payload_ops::{DeletePayload, SetPayload};
 use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
 use collection::operations::snapshot_ops::{SnapshotDescription, SnapshotRecover};
 use collection::operations::types::{
-    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
-    CountRequest, CountResult, GroupsResult, PointGroup, PointRequest, RecommendGroupsRequest,
+    AliasDescription, BatchSearchResult, CollectionClusterInfo, CollectionInfo,
+    CollectionsAliasesResponse, CountRequest, CountResult, DiscoverRequest, FacetRequest,
+    FacetResponse, GroupsResult, PointGroup, PointRequest, QueryRequest, RecommendGroupsRequest,
     RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult,
-    SearchGroupsRequest, SearchRequest, SearchRequestBatch, UpdateResult,
+    SearchGroupsRequest, SearchPlanRequest, SearchPlanResponse, SearchRequest, SearchRequestBatch,
+    UpdateResult,
 };
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
 use schemars::gen::SchemaSettings;
@@ -18,10 +23,15 @@ use serde::{Deserialize, Serialize};
 use storage::content_manager::collection_meta_ops::{
     ChangeAliasesOperation, CreateCollection, UpdateCollection,
 };
+use storage::content_manager::peer_drain::PeerDrainInfo;
 use storage::types::ClusterStatus;
 
 use crate::common::helpers::LocksOption;
-use crate::common::points::{CreateFieldIndex, UpdateOperations};
+use crate::common::logger::{LogFilterInfo, SetLogFilterRequest};
+use crate::common::points::{
+    CountPoints, CreateFieldIndex, RecommendBatchPoints, RecommendPoints, ScrollPoints,
+    SearchBatchPoints, SearchPoints, UpdateOperations, UpsertPoints,
+};
 use crate::common::telemetry::TelemetryData;
 
 mod actix;
@@ -72,6 +82,28 @@ struct AllDefinitions {
     b6: RecommendGroupsRequest,
     b7: GroupsResult,
     b8: UpdateOperations,
+    b9: DiscoverRequest,
+    b10: QueryRequest,
+    b11: BatchSearchResult,
+    b12: FacetRequest,
+    b13: FacetResponse,
+    b14: SearchPlanRequest,
+    b15: SearchPlanResponse,
+    b16: UpsertPoints,
+    b17: SearchPoints,
+    b18: PeerDrainInfo,
+    b19: SetLogFilterRequest,
+    b20: LogFilterInfo,
+    b21: CollectionExistence,
+    b22: OptimizationTaskInfo,
+    b23: CollectionResourceUsage,
+    b24: RecallTestRequest,
+    b25: RecallTestReport,
+    b26: SearchBatchPoints,
+    b27: RecommendPoints,
+    b28: RecommendBatchPoints,
+    b29: ScrollPoints,
+    b30: CountPoints,
 }
 
 fn save_schema<T: JsonSchema>() {
@@ -19,6 +19,17 @@ pub struct ServiceConfig {
     pub http_port: u16,
     pub grpc_port: Option<u16>, // None means that gRPC is disabled
     pub max_request_size_mb: usize,
+    /// Maximum size of a points upsert body. The body is parsed and applied in batches,
+    /// so it is not limited by `max_request_size_mb`. Not limited, if not set.
+    #[serde(default)]
+    pub max_upsert_request_size_mb: Option<usize>,
+    /// Number of points of an upsert body, which are parsed and applied at once
+    #[serde(default = "default_upsert_batch_size")]
+    #[validate(range(min = 1))]
+    pub upsert_batch_size: usize,
+    /// Maximum size of a gRPC request message. Not limited, if not set.
+    #[serde(default)]
+    pub max_grpc_request_size_mb: Option<usize>,
     pub max_workers: Option<usize>,
     #[serde(default = "default_cors")]
     pub enable_cors: bool,
@@ -27,6 +38,10 @@ pub struct ServiceConfig {
     #[serde(default)]
     pub verify_https_client_certificate: bool,
     pub api_key: Option<String>,
+    /// Additional API keys with limited access
+    #[serde(default)]
+    #[validate]
+    pub scoped_api_keys: Vec<ScopedApiKey>,
     /// Directory where static files are served from.
     /// For example, the Web-UI should be placed here.
     #[serde(default)]
@@ -36,6 +51,29 @@ pub struct ServiceConfig {
     /// This includes the Web-UI. True by default.
     #[serde(default)]
     pub enable_static_content: Option<bool>,
+
+    /// On shutdown, time to wait for the requests in progress to finish
+    #[serde(default = "default_shutdown_drain_timeout_sec")]
+    pub shutdown_drain_timeout_sec: u64,
+}
+
+/// API key, which grants access to a part of the operations
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct ScopedApiKey {
+    #[validate(length(min = 1))]
+    pub key: String,
+    pub scope: ApiKeyScope,
+    /// Collections, available with the key. All collections, if not set.
+    #[serde(default)]
+    pub collections: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiKeyScope {
+    /// Only operations, which don't change the data
+    ReadOnly,
+    ReadWrite,
 }
 
 #[derive(Debug, Deserialize, Clone, Default, Validate)]
@@ -89,6 +127,14 @@ pub struct ConsensusConfig {
     #[validate(range(min = 1))]
     #[serde(default = "default_message_timeout_tics")]
     pub message_timeout_ticks: u64,
+    /// Number of applied entries in consensus WAL, after which they are replaced by a snapshot.
+    /// 0 disables WAL compaction.
+    #[serde(default = "default_compact_wal_entries")]
+    pub compact_wal_entries: u64,
+    /// Maximal number of committed consensus operations not applied on this peer yet,
+    /// for the peer to report readiness. Not checked, if not set.
+    #[serde(default)]
+    pub readiness_max_lag: Option<u64>,
 }
 
 impl Default for ConsensusConfig {
@@ -98,6 +144,29 @@ impl Default for ConsensusConfig {
             tick_period_ms: default_tick_period_ms(),
             bootstrap_timeout_sec: default_bootstrap_timeout_sec(),
             message_timeout_ticks: default_message_timeout_tics(),
+            compact_wal_entries: default_compact_wal_entries(),
+            readiness_max_lag: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct TracingConfig {
+    /// Fraction of requests to trace, if the request doesn't carry a `traceparent` header.
+    /// Requests with the header follow the sampling decision of the caller.
+    #[serde(default = "default_tracing_sampling_ratio")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub sampling_ratio: f64,
+    /// OTLP gRPC endpoint to export spans to. Requires the `otlp` feature.
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+}
+
+impl Default for TracingConfig {
+    fn default() -> Self {
+        TracingConfig {
+            sampling_ratio: default_tracing_sampling_ratio(),
+            otlp_endpoint: None,
         }
     }
 }
@@ -129,6 +198,9 @@ pub struct Settings {
     pub telemetry_disabled: bool,
     #[validate]
     pub tls: Option<TlsConfig>,
+    #[serde(default)]
+    #[validate]
+    pub tracing: TracingConfig,
     /// A list of messages for errors that happened during loading the configuration. We collect
     /// them and store them here while loading because then our logger is not configured yet.
     /// We therefore need to log these messages later, after the logger is ready.
@@ -221,6 +293,22 @@ const fn default_message_timeout_tics() -> u64 {
     10
 }
 
+const fn default_compact_wal_entries() -> u64 {
+    128
+}
+
+const fn default_tracing_sampling_ratio() -> f64 {
+    0.01
+}
+
+const fn default_shutdown_drain_timeout_sec() -> u64 {
+    30
+}
+
+const fn default_upsert_batch_size() -> usize {
+    1000
+}
+
 const fn default_tls_cert_ttl() -> Option<u64> {
     // Default one hour
     Some(3600)
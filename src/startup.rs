@@ -4,8 +4,6 @@ use std::backtrace::Backtrace;
 use std::panic;
 use std::path::PathBuf;
 
-use log::LevelFilter;
-
 use crate::common::error_reporting::ErrorReporter;
 
 const DEFAULT_INITIALIZED_FILE: &str = ".qdrant-initialized";
@@ -16,30 +14,6 @@ fn get_init_file_path() -> PathBuf {
         .unwrap_or_else(|_| DEFAULT_INITIALIZED_FILE.into())
 }
 
-pub fn setup_logger(log_level: &str) {
-    let is_info = log_level.to_ascii_uppercase() == "INFO";
-    let mut log_builder = env_logger::Builder::new();
-
-    log_builder
-        // Timestamp in millis
-        .format_timestamp_millis()
-        // Parse user defined log level configuration
-        .parse_filters(log_level)
-        // h2 is very verbose and we have many network operations,
-        // so it is limited to only errors
-        .filter_module("h2", LevelFilter::Error)
-        .filter_module("tower", LevelFilter::Warn);
-
-    if is_info {
-        // Additionally filter verbose modules if no extended logging configuration is provided
-        log_builder
-            .filter_module("wal", LevelFilter::Warn)
-            .filter_module("raft::raft", LevelFilter::Warn);
-    };
-
-    log_builder.init();
-}
-
 pub fn setup_panic_hook(reporting_enabled: bool, reporting_id: String) {
     panic::set_hook(Box::new(move |panic_info| {
         let backtrace = Backtrace::force_capture().to_string();
@@ -13,7 +13,8 @@ use storage::content_manager::conversions::error_to_status;
 use storage::dispatcher::Dispatcher;
 use tonic::{Request, Response, Status};
 
-use super::validate;
+use super::{check_collection_access, validate};
+use crate::common::auth::Access;
 use crate::common::collections::*;
 use crate::tonic::api::collections_common::get;
 
@@ -52,15 +53,19 @@ impl CollectionsService {
 
     async fn list_aliases(
         &self,
-        _request: Request<ListAliasesRequest>,
+        request: Request<ListAliasesRequest>,
     ) -> Result<Response<ListAliasesResponse>, Status> {
         let timing = Instant::now();
-        let aliases = self
-            .dispatcher
-            .toc()
-            .list_aliases()
+        let access = request.extensions().get::<Access>();
+        let aliases = do_list_aliases(self.dispatcher.toc(), access)
             .await
-            .map(|aliases| aliases.into_iter().map(|alias| alias.into()).collect())
+            .map(|response| {
+                response
+                    .aliases
+                    .into_iter()
+                    .map(|alias| alias.into())
+                    .collect()
+            })
             .map_err(error_to_status)?;
         let response = ListAliasesResponse {
             aliases,
@@ -105,6 +110,12 @@ impl Collections for CollectionsService {
         request: Request<GetCollectionInfoRequest>,
     ) -> Result<Response<GetCollectionInfoResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         get(self.dispatcher.as_ref(), request.into_inner(), None).await
     }
 
@@ -114,7 +125,8 @@ impl Collections for CollectionsService {
     ) -> Result<Response<ListCollectionsResponse>, Status> {
         validate(request.get_ref())?;
         let timing = Instant::now();
-        let result = do_list_collections(&self.dispatcher).await;
+        let access = request.extensions().get::<Access>();
+        let result = do_list_collections(&self.dispatcher, access).await;
 
         let response = ListCollectionsResponse::from((timing, result));
         Ok(Response::new(response))
@@ -125,6 +137,12 @@ impl Collections for CollectionsService {
         request: Request<CreateCollection>,
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         self.perform_operation(request).await
     }
 
@@ -133,6 +151,12 @@ impl Collections for CollectionsService {
         request: Request<UpdateCollection>,
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         self.perform_operation(request).await
     }
 
@@ -141,6 +165,12 @@ impl Collections for CollectionsService {
         request: Request<DeleteCollection>,
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         self.perform_operation(request).await
     }
 
@@ -157,6 +187,12 @@ impl Collections for CollectionsService {
         request: Request<ListCollectionAliasesRequest>,
     ) -> Result<Response<ListAliasesResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         self.list_collection_aliases(request).await
     }
 
@@ -173,6 +209,12 @@ impl Collections for CollectionsService {
         request: Request<CollectionClusterInfoRequest>,
     ) -> Result<Response<CollectionClusterInfoResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         let response = do_get_collection_cluster(
             self.dispatcher.toc(),
             request.into_inner().collection_name.as_str(),
@@ -189,6 +231,12 @@ impl Collections for CollectionsService {
         request: Request<UpdateCollectionClusterSetupRequest>,
     ) -> Result<Response<UpdateCollectionClusterSetupResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         let UpdateCollectionClusterSetupRequest {
             collection_name,
             operation,
@@ -200,7 +248,7 @@ impl Collections for CollectionsService {
             collection_name,
             operation
                 .ok_or(Status::new(tonic::Code::InvalidArgument, "empty operation"))?
-                .into(),
+                .try_into()?,
             self.dispatcher.as_ref(),
             timeout.map(std::time::Duration::from_secs),
         )
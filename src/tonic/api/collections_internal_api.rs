@@ -3,8 +3,11 @@ use std::time::Instant;
 
 use api::grpc::qdrant::collections_internal_server::CollectionsInternal;
 use api::grpc::qdrant::{
-    CollectionOperationResponse, GetCollectionInfoRequestInternal, GetCollectionInfoResponse,
-    InitiateShardTransferRequest,
+    CollectionOperationResponse, CreateShardSnapshotRequest, CreateShardSnapshotResponse,
+    DeleteShardSnapshotRequest, GetCollectionInfoRequestInternal, GetCollectionInfoResponse,
+    GetShardRecoveryPointRequest, GetShardRecoveryPointResponse, GetShardSnapshotChunkRequest,
+    GetShardSnapshotChunkResponse, InitiateShardTransferRequest, RecoverShardSnapshotRequest,
+    UploadShardSnapshotChunkRequest, UploadShardSnapshotChunkResponse,
 };
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
@@ -68,4 +71,161 @@ impl CollectionsInternal for CollectionsInternalService {
         };
         Ok(Response::new(response))
     }
+
+    async fn recovery_point(
+        &self,
+        request: Request<GetShardRecoveryPointRequest>,
+    ) -> Result<Response<GetShardRecoveryPointResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let timing = Instant::now();
+        let GetShardRecoveryPointRequest {
+            collection_name,
+            shard_id,
+            count,
+        } = request.into_inner();
+
+        let last_client_op_ids = self
+            .toc
+            .shard_recovery_point(&collection_name, shard_id, count as usize)
+            .await
+            .map_err(error_to_status)?;
+
+        let response = GetShardRecoveryPointResponse {
+            time: timing.elapsed().as_secs_f64(),
+            last_client_op_ids: last_client_op_ids
+                .into_iter()
+                .map(|client_op_id| client_op_id.to_string())
+                .collect(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn upload_shard_snapshot_chunk(
+        &self,
+        request: Request<UploadShardSnapshotChunkRequest>,
+    ) -> Result<Response<UploadShardSnapshotChunkResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let timing = Instant::now();
+        let UploadShardSnapshotChunkRequest {
+            collection_name,
+            shard_id,
+            offset,
+            data,
+            checksum,
+        } = request.into_inner();
+
+        let received_bytes = self
+            .toc
+            .upload_shard_snapshot_chunk(&collection_name, shard_id, offset, &data, checksum)
+            .await
+            .map_err(error_to_status)?;
+
+        let response = UploadShardSnapshotChunkResponse {
+            received_bytes,
+            time: timing.elapsed().as_secs_f64(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn recover_shard_snapshot(
+        &self,
+        request: Request<RecoverShardSnapshotRequest>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let timing = Instant::now();
+        let RecoverShardSnapshotRequest {
+            collection_name,
+            shard_id,
+            size,
+            checksum,
+        } = request.into_inner();
+
+        self.toc
+            .recover_shard_snapshot(&collection_name, shard_id, size, &checksum)
+            .await
+            .map_err(error_to_status)?;
+
+        let response = CollectionOperationResponse {
+            result: true,
+            time: timing.elapsed().as_secs_f64(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn create_shard_snapshot(
+        &self,
+        request: Request<CreateShardSnapshotRequest>,
+    ) -> Result<Response<CreateShardSnapshotResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let timing = Instant::now();
+        let CreateShardSnapshotRequest {
+            collection_name,
+            shard_id,
+        } = request.into_inner();
+
+        let (name, manifest) = self
+            .toc
+            .create_shard_snapshot(&collection_name, shard_id)
+            .await
+            .map_err(error_to_status)?;
+
+        let response = CreateShardSnapshotResponse {
+            name,
+            size: manifest.size,
+            checksum: manifest.checksum,
+            time: timing.elapsed().as_secs_f64(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn get_shard_snapshot_chunk(
+        &self,
+        request: Request<GetShardSnapshotChunkRequest>,
+    ) -> Result<Response<GetShardSnapshotChunkResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let timing = Instant::now();
+        let GetShardSnapshotChunkRequest {
+            collection_name,
+            shard_id,
+            name,
+            offset,
+        } = request.into_inner();
+
+        let (data, checksum) = self
+            .toc
+            .get_shard_snapshot_chunk(&collection_name, shard_id, &name, offset)
+            .await
+            .map_err(error_to_status)?;
+
+        let response = GetShardSnapshotChunkResponse {
+            data,
+            checksum,
+            time: timing.elapsed().as_secs_f64(),
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn delete_shard_snapshot(
+        &self,
+        request: Request<DeleteShardSnapshotRequest>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let timing = Instant::now();
+        let DeleteShardSnapshotRequest {
+            collection_name,
+            shard_id,
+            name,
+        } = request.into_inner();
+
+        self.toc
+            .delete_shard_snapshot(&collection_name, shard_id, &name)
+            .await
+            .map_err(error_to_status)?;
+
+        let response = CollectionOperationResponse {
+            result: true,
+            time: timing.elapsed().as_secs_f64(),
+        };
+        Ok(Response::new(response))
+    }
 }
@@ -8,9 +8,12 @@ pub mod raft_api;
 pub mod snapshots_api;
 
 use collection::operations::validation;
-use tonic::Status;
+use storage::content_manager::toc::TableOfContent;
+use tonic::{Request, Status};
 use validator::Validate;
 
+use crate::common::auth::Access;
+
 /// Validate the given request and fail on error.
 ///
 /// Returns validation error on failure.
@@ -20,6 +23,23 @@ fn validate(request: &dyn Validate) -> Result<(), Status> {
     })
 }
 
+/// Check, that the API key of the request can access the collection or the alias.
+///
+/// Access is not restricted, if the request is not authenticated with an API key.
+async fn check_collection_access<T>(
+    toc: &TableOfContent,
+    request: &Request<T>,
+    collection_name: &str,
+) -> Result<(), Status> {
+    match request.extensions().get::<Access>() {
+        Some(access) => access
+            .check_collection_or_alias(toc, collection_name)
+            .await
+            .map_err(|err| Status::permission_denied(err.to_string())),
+        None => Ok(()),
+    }
+}
+
 /// Validate the given request. Returns validation error on failure.
 fn validate_and_log(request: &dyn Validate) {
     if let Err(ref err) = request.validate() {
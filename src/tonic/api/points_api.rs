@@ -1,22 +1,28 @@
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::grpc::qdrant::points_server::Points;
 use api::grpc::qdrant::{
     ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
-    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints, GetPoints,
-    GetResponse, PointsOperationResponse, RecommendBatchPoints, RecommendBatchResponse,
-    RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
-    ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse,
-    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, UpdateBatchPoints,
-    UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
+    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints,
+    DiscoverPoints, DiscoverResponse, FacetCounts, FacetResponse, GetPoints, GetResponse,
+    PointsOperationResponse, RecommendBatchPoints, RecommendBatchResponse, RecommendGroupsResponse,
+    RecommendPointGroups, RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse,
+    SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse, SearchPointGroups, SearchPoints,
+    SearchResponse, SetPayloadPoints, UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors,
+    UpsertPoints, UpsertStreamRequest, UpsertStreamResponse,
 };
+use futures::Stream;
 use storage::content_manager::toc::TableOfContent;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
 
 use super::points_common::{
-    delete_vectors, recommend_groups, search_groups, update_batch, update_vectors,
+    delete_vectors, discover, facet, recommend_groups, search_groups, update_batch,
+    update_field_index, update_vectors, upsert_stream,
 };
-use super::validate;
+use super::{check_collection_access, validate};
+use crate::common::auth::Access;
 use crate::tonic::api::points_common::{
     clear_payload, count, create_field_index, delete, delete_field_index, delete_payload, get,
     overwrite_payload, recommend, recommend_batch, scroll, search, search_batch, set_payload,
@@ -40,6 +46,7 @@ impl Points for PointsService {
         request: Request<UpsertPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         upsert(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -48,11 +55,13 @@ impl Points for PointsService {
         request: Request<DeletePoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         delete(self.toc.as_ref(), request.into_inner(), None).await
     }
 
     async fn get(&self, request: Request<GetPoints>) -> Result<Response<GetResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         get(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -61,6 +70,7 @@ impl Points for PointsService {
         request: Request<UpdatePointVectors>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         update_vectors(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -69,6 +79,7 @@ impl Points for PointsService {
         request: Request<DeletePointVectors>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         delete_vectors(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -77,6 +88,7 @@ impl Points for PointsService {
         request: Request<SetPayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         set_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -85,6 +97,7 @@ impl Points for PointsService {
         request: Request<SetPayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         overwrite_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -93,6 +106,7 @@ impl Points for PointsService {
         request: Request<DeletePayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         delete_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -101,6 +115,7 @@ impl Points for PointsService {
         request: Request<ClearPayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         clear_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -109,22 +124,49 @@ impl Points for PointsService {
         request: Request<UpdateBatchPoints>,
     ) -> Result<Response<UpdateBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         update_batch(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    type UpsertStreamStream =
+        Pin<Box<dyn Stream<Item = Result<UpsertStreamResponse, Status>> + Send>>;
+
+    async fn upsert_stream(
+        &self,
+        request: Request<Streaming<UpsertStreamRequest>>,
+    ) -> Result<Response<Self::UpsertStreamStream>, Status> {
+        let access = request.extensions().get::<Access>().cloned();
+        Ok(Response::new(upsert_stream(
+            self.toc.clone(),
+            request.into_inner(),
+            access,
+        )))
+    }
+
     async fn create_field_index(
         &self,
         request: Request<CreateFieldIndexCollection>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         create_field_index(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    async fn update_field_index(
+        &self,
+        request: Request<CreateFieldIndexCollection>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
+        update_field_index(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
     async fn delete_field_index(
         &self,
         request: Request<DeleteFieldIndexCollection>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         delete_field_index(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -133,6 +175,7 @@ impl Points for PointsService {
         request: Request<SearchPoints>,
     ) -> Result<Response<SearchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         search(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -141,17 +184,24 @@ impl Points for PointsService {
         request: Request<SearchBatchPoints>,
     ) -> Result<Response<SearchBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         let SearchBatchPoints {
             collection_name,
             search_points,
             read_consistency,
+            fail_fast,
+            timeout,
+            shard_key_selector,
         } = request.into_inner();
         search_batch(
             self.toc.as_ref(),
             collection_name,
             search_points,
             read_consistency,
+            fail_fast,
+            shard_key_selector,
             None,
+            timeout.map(Duration::from_secs),
         )
         .await
     }
@@ -161,6 +211,7 @@ impl Points for PointsService {
         request: Request<SearchPointGroups>,
     ) -> Result<Response<SearchGroupsResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         search_groups(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -169,6 +220,7 @@ impl Points for PointsService {
         request: Request<ScrollPoints>,
     ) -> Result<Response<ScrollResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         scroll(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -177,6 +229,7 @@ impl Points for PointsService {
         request: Request<RecommendPoints>,
     ) -> Result<Response<RecommendResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         recommend(self.toc.as_ref(), request.into_inner()).await
     }
 
@@ -185,16 +238,19 @@ impl Points for PointsService {
         request: Request<RecommendBatchPoints>,
     ) -> Result<Response<RecommendBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         let RecommendBatchPoints {
             collection_name,
             recommend_points,
             read_consistency,
+            shard_key_selector,
         } = request.into_inner();
         recommend_batch(
             self.toc.as_ref(),
             collection_name,
             recommend_points,
             read_consistency,
+            shard_key_selector,
         )
         .await
     }
@@ -204,14 +260,34 @@ impl Points for PointsService {
         request: Request<RecommendPointGroups>,
     ) -> Result<Response<RecommendGroupsResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         recommend_groups(self.toc.as_ref(), request.into_inner()).await
     }
 
+    async fn discover(
+        &self,
+        request: Request<DiscoverPoints>,
+    ) -> Result<Response<DiscoverResponse>, Status> {
+        validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
+        discover(self.toc.as_ref(), request.into_inner()).await
+    }
+
     async fn count(
         &self,
         request: Request<CountPoints>,
     ) -> Result<Response<CountResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
         count(self.toc.as_ref(), request.into_inner(), None).await
     }
+
+    async fn facet(
+        &self,
+        request: Request<FacetCounts>,
+    ) -> Result<Response<FacetResponse>, Status> {
+        validate(request.get_ref())?;
+        check_collection_access(&self.toc, &request, &request.get_ref().collection_name).await?;
+        facet(self.toc.as_ref(), request.into_inner(), None).await
+    }
 }
@@ -1,44 +1,54 @@
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use api::grpc::conversions::proto_to_payloads;
 use api::grpc::qdrant::payload_index_params::IndexParams;
 use api::grpc::qdrant::{
-    points_update_operation, BatchResult, ClearPayloadPoints, CountPoints, CountResponse,
-    CreateFieldIndexCollection, DeleteFieldIndexCollection, DeletePayloadPoints,
-    DeletePointVectors, DeletePoints, FieldType, GetPoints, GetResponse, PayloadIndexParams,
-    PointsOperationResponse, PointsSelector, ReadConsistency as ReadConsistencyGrpc,
-    RecommendBatchResponse, RecommendGroupsResponse, RecommendPointGroups, RecommendPoints,
-    RecommendResponse, ScrollPoints, ScrollResponse, SearchBatchResponse, SearchGroupsResponse,
-    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, SyncPoints,
-    UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
+    points_update_operation, upsert_stream_response, BatchResult, ClearPayloadPoints,
+    CoreSearchPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
+    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints,
+    DiscoverPoints, DiscoverResponse, FacetCounts, FacetResponse, FieldType, GetPoints,
+    GetResponse, PayloadIndexParams, PointsOperationResponse, PointsSelector,
+    ReadConsistency as ReadConsistencyGrpc, RecommendBatchResponse, RecommendGroupsResponse,
+    RecommendPointGroups, RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse,
+    SearchBatchResponse, SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse,
+    SetPayloadPoints, ShardKeySelector as ShardKeySelectorGrpc, SyncPoints, UpdateBatchPoints,
+    UpdateBatchResponse, UpdatePointVectors, UpsertPoints, UpsertStreamAck, UpsertStreamRequest,
+    UpsertStreamResponse, UpsertStreamSummary,
 };
+use collection::common::search_stats::collect_search_stats;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::conversions::write_ordering_from_proto;
 use collection::operations::payload_ops::DeletePayload;
 use collection::operations::point_ops::{
     self, PointInsertOperations, PointOperations, PointSyncOperation,
 };
+use collection::operations::shard_key_selector::{ShardKeySelector, ShardSelectorInternal};
 use collection::operations::types::{
-    default_exact_count, PointRequest, RecommendRequestBatch, ScrollRequest, SearchRequest,
-    SearchRequestBatch,
+    default_exact_count, CoreSearchRequestBatch, DiscoverRequest, FacetRequest, PointRequest,
+    RecommendRequest, RecommendRequestBatch, ScrollRequest, SearchRequest, SearchRequestBatch,
 };
 use collection::operations::vector_ops::{DeleteVectors, PointVectors, UpdateVectors};
-use collection::operations::CollectionUpdateOperations;
-use collection::shards::shard::ShardId;
+use collection::operations::{ClientOperationId, CollectionUpdateOperations};
+use collection::shards::shard::{ShardId, ShardKey};
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
 use segment::data_types::vectors::NamedVector;
 use segment::types::{
     ExtendedPointId, Filter, PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType,
 };
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
-use tonic::{Response, Status};
+use tonic::{Response, Status, Streaming};
 
+use crate::common::auth::Access;
 use crate::common::points::{
-    do_clear_payload, do_count_points, do_create_index, do_delete_index, do_delete_payload,
-    do_delete_points, do_delete_vectors, do_get_points, do_overwrite_payload, do_scroll_points,
-    do_search_batch_points, do_search_points, do_set_payload, do_update_vectors, do_upsert_points,
-    CreateFieldIndex,
+    do_batch_update_points, do_clear_payload, do_count_points, do_create_index, do_delete_index,
+    do_delete_payload, do_delete_points, do_delete_vectors, do_get_points, do_overwrite_payload,
+    do_scroll_points, do_search_batch_points, do_search_points, do_set_payload, do_update_index,
+    do_update_vectors, do_upsert_points, CreateFieldIndex, UpdateOperation,
 };
+use crate::tonic::api::validate;
 
 fn extract_points_selector(
     points_selector: Option<PointsSelector>,
@@ -55,6 +65,72 @@ fn extract_points_selector(
     Ok((points, filter))
 }
 
+/// Build list of operation points
+fn point_vectors_from_grpc(
+    points: Vec<api::grpc::qdrant::PointVectors>,
+) -> Result<Vec<PointVectors>, Status> {
+    let mut op_points = Vec::with_capacity(points.len());
+    for point in points {
+        let id = match point.id {
+            Some(id) => id.try_into()?,
+            None => return Err(Status::invalid_argument("id is expected")),
+        };
+        let vector = match point.vectors {
+            Some(vectors) => vectors.try_into()?,
+            None => return Err(Status::invalid_argument("vectors is expected")),
+        };
+        op_points.push(PointVectors {
+            id,
+            vector,
+            if_version: point.if_version,
+        });
+    }
+    Ok(op_points)
+}
+
+fn client_op_id_from_grpc(
+    client_op_id: Option<String>,
+) -> Result<Option<ClientOperationId>, Status> {
+    client_op_id
+        .map(|client_op_id| {
+            ClientOperationId::parse_str(&client_op_id).map_err(|err| {
+                Status::invalid_argument(format!("Invalid client_op_id {client_op_id}: {err}"))
+            })
+        })
+        .transpose()
+}
+
+/// Shards of the request: the shard of this peer, if the request is sent by another peer,
+/// otherwise the shards of the shard keys, selected by the client
+fn shard_selector_from_grpc(
+    shard_selection: Option<ShardId>,
+    shard_key_selector: Option<ShardKeySelectorGrpc>,
+) -> Result<ShardSelectorInternal, Status> {
+    match (shard_selection, shard_key_selector) {
+        (Some(shard_id), _) => Ok(ShardSelectorInternal::ShardId(shard_id)),
+        (None, Some(shard_key_selector)) => {
+            Ok(ShardKeySelector::try_from(shard_key_selector)?.into())
+        }
+        (None, None) => Ok(ShardSelectorInternal::Empty),
+    }
+}
+
+/// Shards of a batch are selected for the whole batch, not by its requests
+fn check_no_shard_keys_in_batch<'a>(
+    shard_key_selectors: impl IntoIterator<Item = &'a Option<ShardKeySelectorGrpc>>,
+) -> Result<(), Status> {
+    if shard_key_selectors
+        .into_iter()
+        .any(|shard_key_selector| shard_key_selector.is_some())
+    {
+        return Err(Status::invalid_argument(
+            "Shard keys of a batch are selected by `shard_key_selector` of the batch, \
+             not of its requests",
+        ));
+    }
+    Ok(())
+}
+
 pub fn points_operation_response(
     timing: Instant,
     update_result: collection::operations::types::UpdateResult,
@@ -75,18 +151,26 @@ pub async fn upsert(
         wait,
         points,
         ordering,
+        client_op_id,
+        shard_key,
     } = upsert_points;
     let points = points
         .into_iter()
         .map(|point| point.try_into())
         .collect::<Result<_, _>>()?;
     let operation = PointInsertOperations::PointsList(points);
+    let shard_selection = match (shard_selection, shard_key) {
+        (Some(shard_id), _) => ShardSelectorInternal::ShardId(shard_id),
+        (None, Some(shard_key)) => ShardKey::try_from(shard_key)?.into(),
+        (None, None) => ShardSelectorInternal::Empty,
+    };
     let timing = Instant::now();
     let result = do_upsert_points(
         toc,
         &collection_name,
         operation,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -130,6 +214,7 @@ pub async fn sync(
             &collection_name,
             collection_operation,
             shard_selection,
+            None,
             wait.unwrap_or(false),
             write_ordering_from_proto(ordering)?,
         )
@@ -150,6 +235,8 @@ pub async fn delete(
         wait,
         points,
         ordering,
+        client_op_id,
+        with_deleted_ids,
     } = delete_points;
 
     let points_selector = match points {
@@ -163,8 +250,10 @@ pub async fn delete(
         &collection_name,
         points_selector,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
+        with_deleted_ids.unwrap_or(false),
     )
     .await
     .map_err(error_to_status)?;
@@ -183,23 +272,12 @@ pub async fn update_vectors(
         wait,
         points,
         ordering,
+        client_op_id,
     } = update_point_vectors;
 
-    // Build list of operation points
-    let mut op_points = Vec::with_capacity(points.len());
-    for point in points {
-        let id = match point.id {
-            Some(id) => id.try_into()?,
-            None => return Err(Status::invalid_argument("id is expected")),
-        };
-        let vector = match point.vectors {
-            Some(vectors) => vectors.try_into()?,
-            None => return Err(Status::invalid_argument("vectors is expected")),
-        };
-        op_points.push(PointVectors { id, vector });
-    }
-
-    let operation = UpdateVectors { points: op_points };
+    let operation = UpdateVectors {
+        points: point_vectors_from_grpc(points)?,
+    };
 
     let timing = Instant::now();
     let result = do_update_vectors(
@@ -207,6 +285,7 @@ pub async fn update_vectors(
         &collection_name,
         operation,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -228,6 +307,7 @@ pub async fn delete_vectors(
         points_selector,
         vectors,
         ordering,
+        client_op_id,
     } = delete_point_vectors;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -248,6 +328,7 @@ pub async fn delete_vectors(
         &collection_name,
         operation,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -269,6 +350,9 @@ pub async fn set_payload(
         payload,
         points_selector,
         ordering,
+        if_version,
+        key,
+        client_op_id,
     } = set_payload_points;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -276,6 +360,8 @@ pub async fn set_payload(
         payload: proto_to_payloads(payload)?,
         points,
         filter,
+        if_version,
+        key,
     };
 
     let timing = Instant::now();
@@ -284,6 +370,7 @@ pub async fn set_payload(
         &collection_name,
         operation,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -305,6 +392,9 @@ pub async fn overwrite_payload(
         payload,
         points_selector,
         ordering,
+        if_version,
+        key,
+        client_op_id,
     } = set_payload_points;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -312,6 +402,8 @@ pub async fn overwrite_payload(
         payload: proto_to_payloads(payload)?,
         points,
         filter,
+        if_version,
+        key,
     };
 
     let timing = Instant::now();
@@ -320,6 +412,7 @@ pub async fn overwrite_payload(
         &collection_name,
         operation,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -341,6 +434,8 @@ pub async fn delete_payload(
         keys,
         points_selector,
         ordering,
+        if_version,
+        client_op_id,
     } = delete_payload_points;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -348,6 +443,7 @@ pub async fn delete_payload(
         keys,
         points,
         filter,
+        if_version,
     };
 
     let timing = Instant::now();
@@ -356,6 +452,7 @@ pub async fn delete_payload(
         &collection_name,
         operation,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -376,6 +473,7 @@ pub async fn clear_payload(
         wait,
         points,
         ordering,
+        client_op_id,
     } = clear_payload_points;
 
     let points_selector = match points {
@@ -389,6 +487,7 @@ pub async fn clear_payload(
         &collection_name,
         points_selector,
         shard_selection,
+        client_op_id_from_grpc(client_op_id)?,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -411,171 +510,272 @@ pub async fn update_batch(
         ordering,
     } = update_batch_points;
 
+    let operations = operations
+        .into_iter()
+        .map(|op| {
+            let operation = op
+                .operation
+                .ok_or(Status::invalid_argument("Operation is missing"))?;
+            update_operation_from_grpc(operation)
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+
     let timing = Instant::now();
-    let mut results = Vec::with_capacity(operations.len());
-    for op in operations {
-        let operation = op
-            .operation
-            .ok_or(Status::invalid_argument("Operation is missing"))?;
-        let collection_name = collection_name.clone();
-        let ordering = ordering.clone();
-        let result = match operation {
-            points_update_operation::Operation::Upsert(points) => {
-                upsert(
-                    toc,
-                    UpsertPoints {
-                        collection_name,
-                        points: points.points,
-                        wait,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::Delete(points) => {
-                delete(
-                    toc,
-                    DeletePoints {
-                        collection_name,
-                        wait,
-                        points: Some(points),
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::SetPayload(
-                points_update_operation::SetPayload {
-                    payload,
-                    points_selector,
-                },
-            ) => {
-                set_payload(
-                    toc,
-                    SetPayloadPoints {
-                        collection_name,
-                        wait,
-                        payload,
-                        points_selector,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::OverwritePayload(
-                points_update_operation::SetPayload {
-                    payload,
-                    points_selector,
-                },
-            ) => {
-                overwrite_payload(
-                    toc,
-                    SetPayloadPoints {
-                        collection_name,
-                        wait,
-                        payload,
-                        points_selector,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::DeletePayload(
-                points_update_operation::DeletePayload {
-                    keys,
-                    points_selector,
-                },
-            ) => {
-                delete_payload(
-                    toc,
-                    DeletePayloadPoints {
-                        collection_name,
-                        wait,
-                        keys,
-                        points_selector,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::ClearPayload(points) => {
-                clear_payload(
-                    toc,
-                    ClearPayloadPoints {
-                        collection_name,
-                        wait,
-                        points: Some(points),
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::UpdateVectors(
-                points_update_operation::UpdateVectors { points },
-            ) => {
-                update_vectors(
-                    toc,
-                    UpdatePointVectors {
-                        collection_name,
-                        wait,
-                        points,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-            points_update_operation::Operation::DeleteVectors(
-                points_update_operation::DeleteVectors {
-                    points_selector,
-                    vectors,
-                },
-            ) => {
-                delete_vectors(
-                    toc,
-                    DeletePointVectors {
-                        collection_name,
-                        wait,
-                        points_selector,
-                        vectors,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
-            }
-        }?;
-        results.push(result);
-    }
+    let results = do_batch_update_points(
+        toc,
+        &collection_name,
+        operations,
+        shard_selection,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
     Ok(Response::new(UpdateBatchResponse {
-        result: results
-            .into_iter()
-            .map(|response| response.into_inner().result.unwrap())
-            .collect(),
+        result: results.into_iter().map(|result| result.into()).collect(),
         time: timing.elapsed().as_secs_f64(),
     }))
 }
 
-pub async fn create_field_index(
-    toc: &TableOfContent,
-    create_field_index_collection: CreateFieldIndexCollection,
-    shard_selection: Option<ShardId>,
-) -> Result<Response<PointsOperationResponse>, Status> {
-    let CreateFieldIndexCollection {
-        collection_name,
-        wait,
-        field_name,
-        field_type,
-        field_index_params,
-        ordering,
-    } = create_field_index_collection;
+struct UpsertStreamState {
+    toc: Arc<TableOfContent>,
+    requests: Streaming<UpsertStreamRequest>,
+    /// Access of the API key of the stream, if authenticated
+    access: Option<Access>,
+    collection_name: Option<String>,
+    batches_count: u64,
+    points_count: u64,
+    last_operation_id: Option<u64>,
+    timing: Instant,
+    done: bool,
+}
+
+impl UpsertStreamState {
+    fn response(&self, response: upsert_stream_response::Response) -> UpsertStreamResponse {
+        UpsertStreamResponse {
+            response: Some(response),
+            time: self.timing.elapsed().as_secs_f64(),
+        }
+    }
+
+    async fn upsert_batch(
+        &mut self,
+        request: UpsertStreamRequest,
+    ) -> Result<UpsertStreamAck, Status> {
+        validate(&request)?;
+        let UpsertStreamRequest {
+            collection_name,
+            wait,
+            points,
+            ordering,
+            shard_key,
+        } = request;
+
+        let expected_name = self
+            .collection_name
+            .get_or_insert_with(|| collection_name.clone());
+        if *expected_name != collection_name {
+            return Err(Status::invalid_argument(format!(
+                "collection name {collection_name} differs from {expected_name} of the first batch",
+            )));
+        }
+        if let Some(access) = &self.access {
+            access
+                .check_collection_or_alias(&self.toc, &collection_name)
+                .await
+                .map_err(|err| Status::permission_denied(err.to_string()))?;
+        }
+
+        let points: Vec<point_ops::PointStruct> = points
+            .into_iter()
+            .map(|point| point.try_into())
+            .collect::<Result<_, _>>()?;
+        let points_count = points.len() as u64;
+        let shard_selection = match shard_key {
+            Some(shard_key) => ShardKey::try_from(shard_key)?.into(),
+            None => ShardSelectorInternal::Empty,
+        };
 
+        let result = do_upsert_points(
+            &self.toc,
+            &collection_name,
+            PointInsertOperations::PointsList(points),
+            shard_selection,
+            None,
+            wait.unwrap_or(false),
+            write_ordering_from_proto(ordering)?,
+        )
+        .await
+        .map_err(error_to_status)?;
+
+        self.last_operation_id = Some(result.operation_id);
+        let ack = UpsertStreamAck {
+            batch_index: self.batches_count,
+            result: Some(result.into()),
+        };
+        self.batches_count += 1;
+        self.points_count += points_count;
+        Ok(ack)
+    }
+}
+
+/// Upsert batches of points from the client stream, one batch at a time.
+///
+/// The next batch is read from the stream only after the previous one has been accepted by the
+/// collection. Together with the bounded update queue of the shards and HTTP/2 flow control,
+/// this slows down a client which produces batches faster than they can be applied.
+///
+/// Each applied batch is acknowledged, the summary is sent once the client closes the stream.
+/// The first failed batch ends the stream with an error, which includes the index of the batch.
+pub fn upsert_stream(
+    toc: Arc<TableOfContent>,
+    requests: Streaming<UpsertStreamRequest>,
+    access: Option<Access>,
+) -> BoxStream<'static, Result<UpsertStreamResponse, Status>> {
+    let state = UpsertStreamState {
+        toc,
+        requests,
+        access,
+        collection_name: None,
+        batches_count: 0,
+        points_count: 0,
+        last_operation_id: None,
+        timing: Instant::now(),
+        done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+
+        let batch_index = state.batches_count;
+        let response = match state.requests.message().await {
+            Ok(Some(request)) => state
+                .upsert_batch(request)
+                .await
+                .map(|ack| state.response(upsert_stream_response::Response::Ack(ack))),
+            Ok(None) => {
+                state.done = true;
+                let summary = UpsertStreamSummary {
+                    batches_count: state.batches_count,
+                    points_count: state.points_count,
+                    last_operation_id: state.last_operation_id,
+                };
+                Ok(state.response(upsert_stream_response::Response::Summary(summary)))
+            }
+            Err(err) => Err(err),
+        };
+
+        let response = response.map_err(|err| {
+            state.done = true;
+            Status::new(
+                err.code(),
+                format!("Batch {batch_index}: {}", err.message()),
+            )
+        });
+        Some((response, state))
+    })
+    .boxed()
+}
+
+fn update_operation_from_grpc(
+    operation: points_update_operation::Operation,
+) -> Result<UpdateOperation, Status> {
+    let operation = match operation {
+        points_update_operation::Operation::Upsert(points) => {
+            let points = points
+                .points
+                .into_iter()
+                .map(|point| point.try_into())
+                .collect::<Result<_, _>>()?;
+            UpdateOperation::Upsert(PointInsertOperations::PointsList(points))
+        }
+        points_update_operation::Operation::Delete(points) => {
+            UpdateOperation::Delete(points.try_into()?)
+        }
+        points_update_operation::Operation::SetPayload(points_update_operation::SetPayload {
+            payload,
+            points_selector,
+            if_version,
+            key,
+        }) => {
+            let (points, filter) = extract_points_selector(points_selector)?;
+            UpdateOperation::SetPayload(collection::operations::payload_ops::SetPayload {
+                payload: proto_to_payloads(payload)?,
+                points,
+                filter,
+                if_version,
+                key,
+            })
+        }
+        points_update_operation::Operation::OverwritePayload(
+            points_update_operation::SetPayload {
+                payload,
+                points_selector,
+                if_version,
+                key,
+            },
+        ) => {
+            let (points, filter) = extract_points_selector(points_selector)?;
+            UpdateOperation::OverwritePayload(collection::operations::payload_ops::SetPayload {
+                payload: proto_to_payloads(payload)?,
+                points,
+                filter,
+                if_version,
+                key,
+            })
+        }
+        points_update_operation::Operation::DeletePayload(
+            points_update_operation::DeletePayload {
+                keys,
+                points_selector,
+                if_version,
+            },
+        ) => {
+            let (points, filter) = extract_points_selector(points_selector)?;
+            UpdateOperation::DeletePayload(DeletePayload {
+                keys,
+                points,
+                filter,
+                if_version,
+            })
+        }
+        points_update_operation::Operation::ClearPayload(points) => {
+            UpdateOperation::ClearPayload(points.try_into()?)
+        }
+        points_update_operation::Operation::UpdateVectors(
+            points_update_operation::UpdateVectors { points },
+        ) => UpdateOperation::UpdateVectors(UpdateVectors {
+            points: point_vectors_from_grpc(points)?,
+        }),
+        points_update_operation::Operation::DeleteVectors(
+            points_update_operation::DeleteVectors {
+                points_selector,
+                vectors,
+            },
+        ) => {
+            let (points, filter) = extract_points_selector(points_selector)?;
+            let vector_names = match vectors {
+                Some(vectors) => vectors.names,
+                None => return Err(Status::invalid_argument("vectors is expected")),
+            };
+            UpdateOperation::DeleteVectors(DeleteVectors {
+                points,
+                filter,
+                vector: vector_names.into_iter().collect(),
+            })
+        }
+    };
+    Ok(operation)
+}
+
+/// Parse payload field schema of create or update field index requests
+fn field_index_schema(
+    field_type: Option<i32>,
+    field_index_params: Option<PayloadIndexParams>,
+) -> Result<Option<PayloadFieldSchema>, Status> {
     let field_type_parsed = field_type
         .map(FieldType::from_i32)
         .ok_or_else(|| Status::invalid_argument("cannot convert field_type"))?;
@@ -596,6 +796,36 @@ pub async fn create_field_index(
                 ))
             }
         },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::UuidIndexParams(uuid_index_params)),
+            }),
+        ) => match v {
+            FieldType::Uuid => Some(PayloadFieldSchema::FieldParams(PayloadSchemaParams::Uuid(
+                uuid_index_params.into(),
+            ))),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::KeywordIndexParams(keyword_index_params)),
+            }),
+        ) => match v {
+            FieldType::Keyword => Some(PayloadFieldSchema::FieldParams(
+                PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            )),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
         (Some(v), None | Some(PayloadIndexParams { index_params: None })) => match v {
             FieldType::Keyword => Some(PayloadSchemaType::Keyword.into()),
             FieldType::Integer => Some(PayloadSchemaType::Integer.into()),
@@ -603,14 +833,33 @@ pub async fn create_field_index(
             FieldType::Geo => Some(PayloadSchemaType::Geo.into()),
             FieldType::Text => Some(PayloadSchemaType::Text.into()),
             FieldType::Bool => Some(PayloadSchemaType::Bool.into()),
+            FieldType::Datetime => Some(PayloadSchemaType::Datetime.into()),
+            FieldType::Uuid => Some(PayloadSchemaType::Uuid.into()),
         },
         (None, Some(_)) => return Err(Status::invalid_argument("field type is missing")),
         (None, None) => None,
     };
 
+    Ok(field_schema)
+}
+
+pub async fn create_field_index(
+    toc: &TableOfContent,
+    create_field_index_collection: CreateFieldIndexCollection,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let CreateFieldIndexCollection {
+        collection_name,
+        wait,
+        field_name,
+        field_type,
+        field_index_params,
+        ordering,
+    } = create_field_index_collection;
+
     let operation = CreateFieldIndex {
         field_name,
-        field_schema,
+        field_schema: field_index_schema(field_type, field_index_params)?,
     };
 
     let timing = Instant::now();
@@ -629,6 +878,41 @@ pub async fn create_field_index(
     Ok(Response::new(response))
 }
 
+pub async fn update_field_index(
+    toc: &TableOfContent,
+    update_field_index_collection: CreateFieldIndexCollection,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let CreateFieldIndexCollection {
+        collection_name,
+        wait,
+        field_name,
+        field_type,
+        field_index_params,
+        ordering,
+    } = update_field_index_collection;
+
+    let operation = CreateFieldIndex {
+        field_name,
+        field_schema: field_index_schema(field_type, field_index_params)?,
+    };
+
+    let timing = Instant::now();
+    let result = do_update_index(
+        toc,
+        &collection_name,
+        operation,
+        shard_selection,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
 pub async fn delete_field_index(
     toc: &TableOfContent,
     delete_field_index_collection: DeleteFieldIndexCollection,
@@ -674,6 +958,9 @@ pub async fn search(
         vector_name,
         with_vectors,
         read_consistency,
+        timeout,
+        with_stats,
+        shard_key_selector,
     } = search_points;
 
     let search_request = SearchRequest {
@@ -695,17 +982,19 @@ pub async fn search(
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let shard_selection = shard_selector_from_grpc(shard_selection, shard_key_selector)?;
 
     let timing = Instant::now();
-    let scored_points = do_search_points(
+    let search = do_search_points(
         toc,
         &collection_name,
         search_request,
         read_consistency,
         shard_selection,
-    )
-    .await
-    .map_err(error_to_status)?;
+        timeout.map(Duration::from_secs),
+    );
+    let (scored_points, stats) = collect_search_stats(search).await;
+    let scored_points = scored_points.map_err(error_to_status)?;
 
     let response = SearchResponse {
         result: scored_points
@@ -713,6 +1002,7 @@ pub async fn search(
             .map(|point| point.into())
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        stats: with_stats.unwrap_or(false).then(|| stats.into()),
     };
 
     Ok(Response::new(response))
@@ -723,8 +1013,16 @@ pub async fn search_batch(
     collection_name: String,
     search_points: Vec<SearchPoints>,
     read_consistency: Option<ReadConsistencyGrpc>,
+    fail_fast: Option<bool>,
+    shard_key_selector: Option<ShardKeySelectorGrpc>,
     shard_selection: Option<ShardId>,
+    timeout: Option<Duration>,
 ) -> Result<Response<SearchBatchResponse>, Status> {
+    check_no_shard_keys_in_batch(
+        search_points
+            .iter()
+            .map(|search| &search.shard_key_selector),
+    )?;
     let searches: Result<Vec<_>, Status> = search_points
         .into_iter()
         .map(|search_point| search_point.try_into())
@@ -732,9 +1030,11 @@ pub async fn search_batch(
 
     let search_requests = SearchRequestBatch {
         searches: searches?,
+        fail_fast,
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let shard_selection = shard_selector_from_grpc(shard_selection, shard_key_selector)?;
 
     let timing = Instant::now();
     let scored_points = do_search_batch_points(
@@ -743,15 +1043,68 @@ pub async fn search_batch(
         search_requests,
         read_consistency,
         shard_selection,
+        timeout,
     )
     .await
     .map_err(error_to_status)?;
 
+    let response = SearchBatchResponse {
+        result: scored_points
+            .into_iter()
+            .map(|result| match result {
+                Ok(points) => BatchResult {
+                    result: points.into_iter().map(|p| p.into()).collect(),
+                    error: None,
+                },
+                Err(err) => BatchResult {
+                    result: vec![],
+                    error: Some(err.to_string()),
+                },
+            })
+            .collect(),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
+pub async fn core_search_batch(
+    toc: &TableOfContent,
+    collection_name: String,
+    search_points: Vec<CoreSearchPoints>,
+    read_consistency: Option<ReadConsistencyGrpc>,
+    shard_selection: Option<ShardId>,
+    timeout: Option<Duration>,
+) -> Result<Response<SearchBatchResponse>, Status> {
+    let searches: Result<Vec<_>, Status> = search_points
+        .into_iter()
+        .map(|search_point| search_point.try_into())
+        .collect();
+
+    let search_requests = CoreSearchRequestBatch {
+        searches: searches?,
+    };
+
+    let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+
+    let timing = Instant::now();
+    let scored_points = toc
+        .core_search_batch(
+            &collection_name,
+            search_requests,
+            read_consistency,
+            shard_selection,
+            timeout,
+        )
+        .await
+        .map_err(error_to_status)?;
+
     let response = SearchBatchResponse {
         result: scored_points
             .into_iter()
             .map(|points| BatchResult {
                 result: points.into_iter().map(|p| p.into()).collect(),
+                error: None,
             })
             .collect(),
         time: timing.elapsed().as_secs_f64(),
@@ -798,51 +1151,17 @@ pub async fn recommend(
     toc: &TableOfContent,
     recommend_points: RecommendPoints,
 ) -> Result<Response<RecommendResponse>, Status> {
-    let RecommendPoints {
-        collection_name,
-        positive,
-        negative,
-        filter,
-        limit,
-        offset,
-        with_payload,
-        params,
-        score_threshold,
-        using,
-        with_vectors,
-        lookup_from,
-        read_consistency,
-    } = recommend_points;
-
-    let request = collection::operations::types::RecommendRequest {
-        positive: positive
-            .into_iter()
-            .map(|p| p.try_into())
-            .collect::<Result<_, _>>()?,
-        negative: negative
-            .into_iter()
-            .map(|p| p.try_into())
-            .collect::<Result<_, _>>()?,
-        filter: filter.map(|f| f.try_into()).transpose()?,
-        params: params.map(|p| p.into()),
-        limit: limit as usize,
-        offset: offset.unwrap_or_default() as usize,
-        with_payload: with_payload.map(|wp| wp.try_into()).transpose()?,
-        with_vector: Some(
-            with_vectors
-                .map(|selector| selector.into())
-                .unwrap_or_default(),
-        ),
-        score_threshold,
-        using: using.map(|u| u.into()),
-        lookup_from: lookup_from.map(|l| l.into()),
-    };
+    let collection_name = recommend_points.collection_name.clone();
+    let read_consistency = recommend_points.read_consistency.clone();
+    let shard_key_selector = recommend_points.shard_key_selector.clone();
+    let request: RecommendRequest = recommend_points.try_into()?;
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let shard_selection = shard_selector_from_grpc(None, shard_key_selector)?;
 
     let timing = Instant::now();
     let recommended_points = toc
-        .recommend(&collection_name, request, read_consistency)
+        .recommend(&collection_name, request, read_consistency, shard_selection)
         .await
         .map_err(error_to_status)?;
 
@@ -857,12 +1176,45 @@ pub async fn recommend(
     Ok(Response::new(response))
 }
 
+pub async fn discover(
+    toc: &TableOfContent,
+    discover_points: DiscoverPoints,
+) -> Result<Response<DiscoverResponse>, Status> {
+    let collection_name = discover_points.collection_name.clone();
+    let read_consistency = discover_points.read_consistency.clone();
+    let request: DiscoverRequest = discover_points.try_into()?;
+
+    let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+
+    let timing = Instant::now();
+    let discovered_points = toc
+        .discover(&collection_name, request, read_consistency)
+        .await
+        .map_err(error_to_status)?;
+
+    let response = DiscoverResponse {
+        result: discovered_points
+            .into_iter()
+            .map(|point| point.into())
+            .collect(),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
 pub async fn recommend_batch(
     toc: &TableOfContent,
     collection_name: String,
     recommend_points: Vec<RecommendPoints>,
     read_consistency: Option<ReadConsistencyGrpc>,
+    shard_key_selector: Option<ShardKeySelectorGrpc>,
 ) -> Result<Response<RecommendBatchResponse>, Status> {
+    check_no_shard_keys_in_batch(
+        recommend_points
+            .iter()
+            .map(|recommend| &recommend.shard_key_selector),
+    )?;
     let searches: Result<Vec<_>, Status> = recommend_points
         .into_iter()
         .map(|recommend_point| recommend_point.try_into())
@@ -872,10 +1224,16 @@ pub async fn recommend_batch(
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let shard_selection = shard_selector_from_grpc(None, shard_key_selector)?;
 
     let timing = Instant::now();
     let scored_points = toc
-        .recommend_batch(&collection_name, recommend_batch, read_consistency)
+        .recommend_batch(
+            &collection_name,
+            recommend_batch,
+            read_consistency,
+            shard_selection,
+        )
         .await
         .map_err(error_to_status)?;
 
@@ -884,6 +1242,7 @@ pub async fn recommend_batch(
             .into_iter()
             .map(|points| BatchResult {
                 result: points.into_iter().map(|p| p.into()).collect(),
+                error: None,
             })
             .collect(),
         time: timing.elapsed().as_secs_f64(),
@@ -936,6 +1295,9 @@ pub async fn scroll(
         with_payload,
         with_vectors,
         read_consistency,
+        order_by,
+        page_token,
+        shard_key_selector,
     } = scroll_points;
 
     let scroll_request = ScrollRequest {
@@ -946,9 +1308,12 @@ pub async fn scroll(
         with_vector: with_vectors
             .map(|selector| selector.into())
             .unwrap_or_default(),
+        order_by: order_by.map(|order_by| order_by.try_into()).transpose()?,
+        page_token,
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let shard_selection = shard_selector_from_grpc(shard_selection, shard_key_selector)?;
 
     let timing = Instant::now();
     let scrolled_points = do_scroll_points(
@@ -969,6 +1334,8 @@ pub async fn scroll(
             .map(|point| point.into())
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        next_page_start_from: scrolled_points.next_page_start_from.map(Into::into),
+        next_page_token: scrolled_points.next_page_token,
     };
 
     Ok(Response::new(response))
@@ -983,12 +1350,14 @@ pub async fn count(
         collection_name,
         filter,
         exact,
+        shard_key_selector,
     } = count_points;
 
     let count_request = collection::operations::types::CountRequest {
         filter: filter.map(|f| f.try_into()).transpose()?,
         exact: exact.unwrap_or_else(default_exact_count),
     };
+    let shard_selection = shard_selector_from_grpc(shard_selection, shard_key_selector)?;
 
     let timing = Instant::now();
     let count_result = do_count_points(toc, &collection_name, count_request, shard_selection)
@@ -1003,6 +1372,44 @@ pub async fn count(
     Ok(Response::new(response))
 }
 
+pub async fn facet(
+    toc: &TableOfContent,
+    facet_counts: FacetCounts,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<FacetResponse>, Status> {
+    let FacetCounts {
+        collection_name,
+        key,
+        filter,
+        limit,
+        exact,
+    } = facet_counts;
+
+    let facet_request = FacetRequest {
+        key,
+        limit: limit.map(|limit| limit as usize),
+        filter: filter.map(|f| f.try_into()).transpose()?,
+        exact: exact.unwrap_or_else(default_exact_count),
+    };
+
+    let timing = Instant::now();
+    let facet_response = toc
+        .facet(&collection_name, facet_request, shard_selection)
+        .await
+        .map_err(error_to_status)?;
+
+    let response = FacetResponse {
+        hits: facet_response
+            .hits
+            .into_iter()
+            .map(|hit| hit.into())
+            .collect(),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
 pub async fn get(
     toc: &TableOfContent,
     get_points: GetPoints,
@@ -1014,6 +1421,7 @@ pub async fn get(
         with_payload,
         with_vectors,
         read_consistency,
+        with_version,
     } = get_points;
 
     let point_request = PointRequest {
@@ -1025,6 +1433,7 @@ pub async fn get(
         with_vector: with_vectors
             .map(|selector| selector.into())
             .unwrap_or_default(),
+        with_version: with_version.unwrap_or_default(),
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
@@ -1,13 +1,15 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::grpc::qdrant::points_internal_server::PointsInternal;
 use api::grpc::qdrant::{
-    ClearPayloadPointsInternal, CountPointsInternal, CountResponse,
+    ClearPayloadPointsInternal, CoreSearchBatchPointsInternal, CountPointsInternal, CountResponse,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollectionInternal,
-    DeletePayloadPointsInternal, DeletePointsInternal, DeleteVectorsInternal, GetPointsInternal,
-    GetResponse, PointsOperationResponse, RecommendPointsInternal, RecommendResponse,
-    ScrollPointsInternal, ScrollResponse, SearchBatchPointsInternal, SearchBatchResponse,
-    SearchPointsInternal, SearchResponse, SetPayloadPointsInternal, SyncPointsInternal,
+    DeletePayloadPointsInternal, DeletePointsInternal, DeleteVectorsInternal, FacetCountsInternal,
+    FacetResponse, GetPointsInternal, GetResponse, PointsOperationResponse,
+    RecommendPointsInternal, RecommendResponse, ScrollPointsInternal, ScrollResponse,
+    SearchBatchPointsInternal, SearchBatchResponse, SearchPointsInternal, SearchResponse,
+    SetPayloadPointsInternal, SyncPointsInternal, UpdateBatchInternal, UpdateBatchResponse,
     UpdateVectorsInternal, UpsertPointsInternal,
 };
 use storage::content_manager::toc::TableOfContent;
@@ -15,9 +17,9 @@ use tonic::{Request, Response, Status};
 
 use super::validate_and_log;
 use crate::tonic::api::points_common::{
-    clear_payload, count, create_field_index, delete, delete_field_index, delete_payload,
-    delete_vectors, get, overwrite_payload, recommend, scroll, search, search_batch, set_payload,
-    sync, update_vectors, upsert,
+    clear_payload, core_search_batch, count, create_field_index, delete, delete_field_index,
+    delete_payload, delete_vectors, facet, get, overwrite_payload, recommend, scroll, search,
+    search_batch, set_payload, sync, update_batch, update_field_index, update_vectors, upsert,
 };
 
 /// This API is intended for P2P communication within a distributed deployment.
@@ -159,6 +161,22 @@ impl PointsInternal for PointsInternalService {
         create_field_index(self.toc.as_ref(), create_field_index_collection, shard_id).await
     }
 
+    async fn update_field_index(
+        &self,
+        request: Request<CreateFieldIndexCollectionInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let CreateFieldIndexCollectionInternal {
+            create_field_index_collection,
+            shard_id,
+        } = request.into_inner();
+
+        let create_field_index_collection = create_field_index_collection
+            .ok_or_else(|| Status::invalid_argument("CreateFieldIndexCollection is missing"))?;
+
+        update_field_index(self.toc.as_ref(), create_field_index_collection, shard_id).await
+    }
+
     async fn delete_field_index(
         &self,
         request: Request<DeleteFieldIndexCollectionInternal>,
@@ -215,7 +233,33 @@ impl PointsInternal for PointsInternalService {
             collection_name,
             search_points,
             None, // *Have* to be `None`!
+            Some(true),
+            None,
             shard_id,
+            None,
+        )
+        .await
+    }
+
+    async fn core_search_batch(
+        &self,
+        request: Request<CoreSearchBatchPointsInternal>,
+    ) -> Result<Response<SearchBatchResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let CoreSearchBatchPointsInternal {
+            collection_name,
+            search_points,
+            shard_id,
+            timeout,
+        } = request.into_inner();
+
+        core_search_batch(
+            self.toc.as_ref(),
+            collection_name,
+            search_points,
+            None, // *Have* to be `None`!
+            shard_id,
+            timeout.map(Duration::from_secs),
         )
         .await
     }
@@ -290,6 +334,36 @@ impl PointsInternal for PointsInternalService {
         count(self.toc.as_ref(), count_points, shard_id).await
     }
 
+    async fn facet(
+        &self,
+        request: Request<FacetCountsInternal>,
+    ) -> Result<Response<FacetResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let FacetCountsInternal {
+            facet_counts,
+            shard_id,
+        } = request.into_inner();
+
+        let facet_counts =
+            facet_counts.ok_or_else(|| Status::invalid_argument("FacetCounts is missing"))?;
+        facet(self.toc.as_ref(), facet_counts, shard_id).await
+    }
+
+    async fn update_batch(
+        &self,
+        request: Request<UpdateBatchInternal>,
+    ) -> Result<Response<UpdateBatchResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let UpdateBatchInternal {
+            update_batch: update_batch_points,
+            shard_id,
+        } = request.into_inner();
+
+        let update_batch_points = update_batch_points
+            .ok_or_else(|| Status::invalid_argument("UpdateBatchPoints is missing"))?;
+        update_batch(self.toc.as_ref(), update_batch_points, shard_id).await
+    }
+
     async fn sync(
         &self,
         request: Request<SyncPointsInternal>,
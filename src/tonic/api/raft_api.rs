@@ -85,6 +85,7 @@ impl Raft for RaftService {
                 ConsensusOperations::AddPeer {
                     peer_id: peer.id,
                     uri: uri.to_string(),
+                    listener: peer.listener.unwrap_or_default(),
                 },
                 None,
             )
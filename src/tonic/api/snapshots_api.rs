@@ -15,7 +15,7 @@ use storage::content_manager::snapshots::{
 use storage::dispatcher::Dispatcher;
 use tonic::{async_trait, Request, Response, Status};
 
-use super::validate;
+use super::{check_collection_access, validate};
 use crate::common::collections::{do_create_snapshot, do_list_snapshots};
 
 pub struct SnapshotsService {
@@ -35,6 +35,12 @@ impl Snapshots for SnapshotsService {
         request: Request<CreateSnapshotRequest>,
     ) -> Result<Response<CreateSnapshotResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         let collection_name = request.into_inner().collection_name;
         let timing = Instant::now();
         let dispatcher = self.dispatcher.clone();
@@ -52,6 +58,12 @@ impl Snapshots for SnapshotsService {
         request: Request<ListSnapshotsRequest>,
     ) -> Result<Response<ListSnapshotsResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         let collection_name = request.into_inner().collection_name;
 
         let timing = Instant::now();
@@ -69,6 +81,12 @@ impl Snapshots for SnapshotsService {
         request: Request<DeleteSnapshotRequest>,
     ) -> Result<Response<DeleteSnapshotResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(
+            self.dispatcher.toc(),
+            &request,
+            &request.get_ref().collection_name,
+        )
+        .await?;
         let DeleteSnapshotRequest {
             collection_name,
             snapshot_name,
@@ -1,6 +1,5 @@
 use std::task::{Context, Poll};
 
-use constant_time_eq::constant_time_eq;
 use futures_util::future::BoxFuture;
 use reqwest::header::HeaderValue;
 use reqwest::StatusCode;
@@ -9,15 +8,52 @@ use tonic::Code;
 use tower::Service;
 use tower_layer::Layer;
 
+use crate::common::auth::{Access, AccessError, ApiKeys, API_KEY_HEADER};
+
+/// Methods, which do not modify any data
+const READ_METHODS: &[&str] = &[
+    "/qdrant.Qdrant/HealthCheck",
+    "/qdrant.Collections/Get",
+    "/qdrant.Collections/List",
+    "/qdrant.Collections/ListCollectionAliases",
+    "/qdrant.Collections/ListAliases",
+    "/qdrant.Collections/CollectionClusterInfo",
+    "/qdrant.Points/Get",
+    "/qdrant.Points/Search",
+    "/qdrant.Points/SearchBatch",
+    "/qdrant.Points/SearchGroups",
+    "/qdrant.Points/Scroll",
+    "/qdrant.Points/Recommend",
+    "/qdrant.Points/RecommendBatch",
+    "/qdrant.Points/RecommendGroups",
+    "/qdrant.Points/Discover",
+    "/qdrant.Points/Count",
+    "/qdrant.Points/Facet",
+    "/qdrant.Snapshots/List",
+    "/qdrant.Snapshots/ListFull",
+];
+
+/// Methods, which are not bound to a single collection.
+///
+/// Listings are not here: their results are filtered by the allowed collections instead.
+/// Access to the collection of other methods is checked by the handlers, as the collection name
+/// is only known from the request message.
+const GLOBAL_METHODS: &[&str] = &[
+    "/qdrant.Collections/UpdateAliases",
+    "/qdrant.Snapshots/CreateFull",
+    "/qdrant.Snapshots/ListFull",
+    "/qdrant.Snapshots/DeleteFull",
+];
+
 #[derive(Clone)]
 pub struct ApiKeyMiddleware<T> {
     service: T,
-    api_key: String,
+    api_keys: ApiKeys,
 }
 
 #[derive(Clone)]
 pub struct ApiKeyMiddlewareLayer {
-    api_key: String,
+    api_keys: ApiKeys,
 }
 
 impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for ApiKeyMiddleware<S>
@@ -38,38 +74,59 @@ where
 
     fn call(
         &mut self,
-        request: tonic::codegen::http::Request<tonic::transport::Body>,
+        mut request: tonic::codegen::http::Request<tonic::transport::Body>,
     ) -> Self::Future {
-        if let Some(key) = request.headers().get("api-key") {
-            if let Ok(key) = key.to_str() {
-                if constant_time_eq(self.api_key.as_bytes(), key.as_bytes()) {
-                    let future = self.service.call(request);
-
-                    return Box::pin(async move {
-                        let response = future.await?;
-                        Ok(response)
-                    });
-                }
-            }
+        let access = request
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|key| key.to_str().ok())
+            .and_then(|key| self.api_keys.access(key));
+
+        let Some(access) = access else {
+            return Box::pin(async move { Ok(permission_denied("Invalid api-key")) });
+        };
+
+        if let Err(AccessError(message)) = check_method_access(&access, request.uri().path()) {
+            return Box::pin(async move { Ok(permission_denied(&message)) });
         }
 
-        let mut response = Self::Response::new(BoxBody::default());
-        *response.status_mut() = StatusCode::FORBIDDEN;
-        response.headers_mut().append(
-            "grpc-status",
-            HeaderValue::from(Code::PermissionDenied as i32),
-        );
-        response
-            .headers_mut()
-            .append("grpc-message", HeaderValue::from_static("Invalid api-key"));
-
-        Box::pin(async move { Ok(response) })
+        // Handlers check access to the requested collection, resolving aliases
+        request.extensions_mut().insert(access);
+        let future = self.service.call(request);
+
+        Box::pin(async move {
+            let response = future.await?;
+            Ok(response)
+        })
+    }
+}
+
+fn permission_denied(message: &str) -> tonic::codegen::http::Response<BoxBody> {
+    let mut response = tonic::codegen::http::Response::new(BoxBody::default());
+    *response.status_mut() = StatusCode::FORBIDDEN;
+    response.headers_mut().append(
+        "grpc-status",
+        HeaderValue::from(Code::PermissionDenied as i32),
+    );
+    response.headers_mut().append(
+        "grpc-message",
+        HeaderValue::from_str(message).unwrap_or(HeaderValue::from_static("Forbidden")),
+    );
+    response
+}
+
+/// Check, that the key can call the method
+fn check_method_access(access: &Access, method: &str) -> Result<(), AccessError> {
+    access.check_scope(!READ_METHODS.contains(&method))?;
+    if GLOBAL_METHODS.contains(&method) {
+        access.check_global()?;
     }
+    Ok(())
 }
 
 impl ApiKeyMiddlewareLayer {
-    pub fn new(api_key: String) -> Self {
-        Self { api_key }
+    pub fn new(api_keys: ApiKeys) -> Self {
+        Self { api_keys }
     }
 }
 
@@ -79,7 +136,50 @@ impl<S> Layer<S> for ApiKeyMiddlewareLayer {
     fn layer(&self, service: S) -> Self::Service {
         ApiKeyMiddleware {
             service,
-            api_key: self.api_key.clone(),
+            api_keys: self.api_keys.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ApiKeyScope;
+
+    #[test]
+    fn test_method_access() {
+        let master = Access::full();
+        let reader = Access::new(ApiKeyScope::ReadOnly, None::<Vec<String>>);
+        let writer = Access::new(ApiKeyScope::ReadWrite, Some(vec!["a".to_string()]));
+
+        let cases = [
+            ("/qdrant.Points/Search", true, true, true),
+            ("/qdrant.Points/Upsert", true, false, true),
+            ("/qdrant.Points/UpsertStream", true, false, true),
+            ("/qdrant.Collections/Delete", true, false, true),
+            ("/qdrant.Collections/List", true, true, true),
+            ("/qdrant.Collections/UpdateAliases", true, false, false),
+            ("/qdrant.Snapshots/ListFull", true, true, false),
+            ("/qdrant.Snapshots/CreateFull", true, false, false),
+            ("/qdrant.Qdrant/HealthCheck", true, true, true),
+        ];
+
+        for (method, master_allowed, reader_allowed, writer_allowed) in cases {
+            assert_eq!(
+                check_method_access(&master, method).is_ok(),
+                master_allowed,
+                "master: {method}"
+            );
+            assert_eq!(
+                check_method_access(&reader, method).is_ok(),
+                reader_allowed,
+                "reader: {method}"
+            );
+            assert_eq!(
+                check_method_access(&writer, method).is_ok(),
+                writer_allowed,
+                "writer: {method}"
+            );
         }
     }
 }
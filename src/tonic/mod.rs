@@ -1,7 +1,9 @@
 mod api;
 mod api_key;
 mod logging;
+mod tonic_shutdown;
 mod tonic_telemetry;
+mod tonic_tracing;
 
 use std::io;
 use std::net::{IpAddr, SocketAddr};
@@ -19,12 +21,13 @@ use storage::content_manager::consensus_manager::ConsensusStateRef;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
 use tokio::runtime::Handle;
-use tokio::signal;
 use tonic::codec::CompressionEncoding;
 use tonic::transport::{Server, ServerTlsConfig};
 use tonic::{Request, Response, Status};
 
+use crate::common::auth::ApiKeys;
 use crate::common::helpers;
+use crate::common::shutdown::ShutdownRef;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
 use crate::settings::Settings;
 use crate::tonic::api::collections_api::CollectionsService;
@@ -46,29 +49,13 @@ impl Qdrant for QdrantService {
     }
 }
 
-#[cfg(not(unix))]
-async fn wait_stop_signal(for_what: &str) {
-    signal::ctrl_c().await.unwrap();
-    log::debug!("Stopping {for_what} on SIGINT");
-}
-
-#[cfg(unix)]
-async fn wait_stop_signal(for_what: &str) {
-    let mut term = signal::unix::signal(signal::unix::SignalKind::terminate()).unwrap();
-    let mut inrt = signal::unix::signal(signal::unix::SignalKind::interrupt()).unwrap();
-
-    tokio::select! {
-        _ = term.recv() => log::debug!("Stopping {for_what} on SIGTERM"),
-        _ = inrt.recv() => log::debug!("Stopping {for_what} on SIGINT"),
-    }
-}
-
 pub fn init(
     dispatcher: Arc<Dispatcher>,
     telemetry_collector: Arc<parking_lot::Mutex<TonicTelemetryCollector>>,
     settings: Settings,
     grpc_port: u16,
     runtime: Handle,
+    shutdown: ShutdownRef,
 ) -> io::Result<()> {
     runtime.block_on(async {
         let socket =
@@ -81,6 +68,11 @@ pub fn init(
 
         log::info!("Qdrant gRPC listening on {}", grpc_port);
 
+        let max_message_size = settings
+            .service
+            .max_grpc_request_size_mb
+            .map_or(usize::MAX, |size_mb| size_mb * 1024 * 1024);
+
         let mut server = Server::builder();
 
         if settings.service.enable_tls {
@@ -97,15 +89,16 @@ pub fn init(
 
         // The stack of middleware that our service will be wrapped in
         let middleware_layer = tower::ServiceBuilder::new()
+            .layer(tonic_tracing::TonicTracingLayer::new(
+                settings.tracing.sampling_ratio,
+            ))
             .layer(logging::LoggingMiddlewareLayer::new())
             .layer(tonic_telemetry::TonicTelemetryLayer::new(
                 telemetry_collector,
             ))
+            .layer(tonic_shutdown::TonicShutdownLayer::new(shutdown.clone()))
             .option_layer(
-                settings
-                    .service
-                    .api_key
-                    .map(api_key::ApiKeyMiddlewareLayer::new),
+                ApiKeys::from_config(&settings.service).map(api_key::ApiKeyMiddlewareLayer::new),
             )
             .into_inner();
 
@@ -115,28 +108,29 @@ pub fn init(
                 QdrantServer::new(qdrant_service)
                     .send_compressed(CompressionEncoding::Gzip)
                     .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
+                    .max_decoding_message_size(max_message_size),
             )
             .add_service(
                 CollectionsServer::new(collections_service)
                     .send_compressed(CompressionEncoding::Gzip)
                     .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
+                    .max_decoding_message_size(max_message_size),
             )
             .add_service(
                 PointsServer::new(points_service)
                     .send_compressed(CompressionEncoding::Gzip)
                     .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
+                    .max_decoding_message_size(max_message_size),
             )
             .add_service(
                 SnapshotsServer::new(snapshot_service)
                     .send_compressed(CompressionEncoding::Gzip)
                     .accept_compressed(CompressionEncoding::Gzip)
-                    .max_decoding_message_size(usize::MAX),
+                    .max_decoding_message_size(max_message_size),
             )
             .serve_with_shutdown(socket, async {
-                wait_stop_signal("gRPC service").await;
+                shutdown.wait_stop_servers().await;
+                log::debug!("Stopping gRPC service");
             })
             .await
             .map_err(helpers::tonic_error_to_io_error)
@@ -155,6 +149,7 @@ pub fn init_internal(
     tls_config: Option<ServerTlsConfig>,
     to_consensus: tokio::sync::mpsc::Sender<crate::consensus::Message>,
     runtime: Handle,
+    shutdown: ShutdownRef,
 ) -> std::io::Result<()> {
     use ::api::grpc::qdrant::raft_server::RaftServer;
 
@@ -223,8 +218,10 @@ pub fn init_internal(
                         .accept_compressed(CompressionEncoding::Gzip)
                         .max_decoding_message_size(usize::MAX),
                 )
+                // Keeps serving other peers until requests to this peer are drained
                 .serve_with_shutdown(socket, async {
-                    wait_stop_signal("internal gRPC").await;
+                    shutdown.wait_stop_servers().await;
+                    log::debug!("Stopping internal gRPC");
                 })
                 .await
         })
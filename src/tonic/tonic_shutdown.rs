@@ -0,0 +1,86 @@
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use reqwest::header::HeaderValue;
+use reqwest::StatusCode;
+use tonic::body::BoxBody;
+use tonic::Code;
+use tower::Service;
+use tower_layer::Layer;
+
+use crate::common::shutdown::ShutdownRef;
+
+/// Rejects new requests while the service is shutting down, and tracks the requests in progress
+#[derive(Clone)]
+pub struct TonicShutdownService<T> {
+    service: T,
+    shutdown: ShutdownRef,
+}
+
+#[derive(Clone)]
+pub struct TonicShutdownLayer {
+    shutdown: ShutdownRef,
+}
+
+impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for TonicShutdownService<S>
+where
+    S: Service<
+        tonic::codegen::http::Request<tonic::transport::Body>,
+        Response = tonic::codegen::http::Response<tonic::body::BoxBody>,
+    >,
+    S::Future: Send + 'static,
+{
+    type Response = tonic::codegen::http::Response<tonic::body::BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(
+        &mut self,
+        request: tonic::codegen::http::Request<tonic::transport::Body>,
+    ) -> Self::Future {
+        let Some(guard) = self.shutdown.start_request() else {
+            return Box::pin(async { Ok(unavailable()) });
+        };
+
+        let future = self.service.call(request);
+        Box::pin(async move {
+            let response = future.await?;
+            drop(guard);
+            Ok(response)
+        })
+    }
+}
+
+fn unavailable() -> tonic::codegen::http::Response<BoxBody> {
+    let mut response = tonic::codegen::http::Response::new(BoxBody::default());
+    *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+    response
+        .headers_mut()
+        .append("grpc-status", HeaderValue::from(Code::Unavailable as i32));
+    response.headers_mut().append(
+        "grpc-message",
+        HeaderValue::from_static("Service is shutting down"),
+    );
+    response
+}
+
+impl TonicShutdownLayer {
+    pub fn new(shutdown: ShutdownRef) -> Self {
+        Self { shutdown }
+    }
+}
+
+impl<S> Layer<S> for TonicShutdownLayer {
+    type Service = TonicShutdownService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        TonicShutdownService {
+            service,
+            shutdown: self.shutdown.clone(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use tower::Service;
+use tower_layer::Layer;
+use tracing::Instrument;
+
+use crate::common::trace_context::{request_span, TRACEPARENT_HEADER};
+
+#[derive(Clone)]
+pub struct TonicTracingService<T> {
+    service: T,
+    sampling_ratio: f64,
+}
+
+#[derive(Clone)]
+pub struct TonicTracingLayer {
+    sampling_ratio: f64,
+}
+
+/// Runs every request within a request span, which continues the trace of the `traceparent`
+/// metadata.
+impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for TonicTracingService<S>
+where
+    S: Service<tonic::codegen::http::Request<tonic::transport::Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(
+        &mut self,
+        request: tonic::codegen::http::Request<tonic::transport::Body>,
+    ) -> Self::Future {
+        let traceparent = request
+            .headers()
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| value.to_str().ok());
+        let span = request_span(
+            "grpc",
+            request.uri().path(),
+            traceparent,
+            self.sampling_ratio,
+        );
+        let future = span.in_scope(|| self.service.call(request));
+        Box::pin(future.instrument(span))
+    }
+}
+
+impl TonicTracingLayer {
+    pub fn new(sampling_ratio: f64) -> TonicTracingLayer {
+        Self { sampling_ratio }
+    }
+}
+
+impl<S> Layer<S> for TonicTracingLayer {
+    type Service = TonicTracingService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        TonicTracingService {
+            service,
+            sampling_ratio: self.sampling_ratio,
+        }
+    }
+}
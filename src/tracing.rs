@@ -1,5 +1,8 @@
-pub fn setup() -> anyhow::Result<()> {
-    // Use `console` and/or `tracy` features to enable both `tracing-subscriber` and the layer(s)
+use crate::settings::TracingConfig;
+
+#[cfg_attr(not(feature = "otlp"), allow(unused_variables))]
+pub fn setup(config: &TracingConfig) -> anyhow::Result<()> {
+    // Use `console`, `tracy` and/or `otlp` features to enable both `tracing-subscriber` and the layer(s)
     #[cfg(feature = "tracing-subscriber")]
     {
         use tracing_subscriber::prelude::*;
@@ -28,8 +31,83 @@ pub fn setup() -> anyhow::Result<()> {
             tracing_subscriber::filter::filter_fn(|metadata| metadata.is_span()),
         ));
 
+        // Use `otlp` feature to export spans of the sampled requests
+        #[cfg(feature = "otlp")]
+        let reg = reg.with(otlp::layer(config)?);
+
         tracing::subscriber::set_global_default(reg)?;
     }
 
+    #[cfg(not(feature = "otlp"))]
+    if config.otlp_endpoint.is_some() {
+        log::warn!(
+            "Tracing OTLP endpoint is configured, but qdrant is built without `otlp` feature"
+        );
+    }
+
     Ok(())
 }
+
+#[cfg(feature = "otlp")]
+mod otlp {
+    use std::sync::OnceLock;
+
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::trace::Sampler;
+    use opentelemetry_sdk::Resource;
+    use tracing::Subscriber;
+    use tracing_subscriber::filter::DynFilterFn;
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    use crate::settings::TracingConfig;
+
+    /// Runtime of the span exporter, other runtimes are created after the tracing is set up
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+    /// Layer, which exports request spans with OTLP, if the endpoint is configured
+    pub fn layer<S>(config: &TracingConfig) -> anyhow::Result<Option<impl Layer<S>>>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        let Some(endpoint) = &config.otlp_endpoint else {
+            return Ok(None);
+        };
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("otlp-exporter")
+            .enable_all()
+            .build()?;
+        let _runtime_guard = RUNTIME.get_or_init(|| runtime).enter();
+
+        let tracer = opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_trace_config(
+                opentelemetry_sdk::trace::config()
+                    // Requests are sampled, when the request span is created
+                    .with_sampler(Sampler::AlwaysOn)
+                    .with_resource(Resource::new([KeyValue::new("service.name", "qdrant")])),
+            )
+            .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+        // Export request spans and spans within them, but not the spans of background tasks
+        let filter = DynFilterFn::new(|metadata, cx| {
+            let is_request_span =
+                metadata.name() == "request" && metadata.target().starts_with("qdrant");
+            is_request_span || cx.lookup_current().is_some()
+        });
+
+        Ok(Some(
+            tracing_opentelemetry::layer()
+                .with_tracer(tracer)
+                .with_filter(filter),
+        ))
+    }
+}
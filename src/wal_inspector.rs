@@ -1,34 +1,215 @@
-use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use collection::operations::CollectionUpdateOperations;
+use clap::{Parser, Subcommand};
+use collection::operations::OperationWithClientId;
 use collection::wal::SerdeWal;
 use wal::WalOptions;
 
 /// Executable to inspect the content of a write ahead log folder.
-/// e.g `cargo run --bin wal_inspector storage/collections/test-collection/0/wal/`
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    let wal_path = Path::new(&args[1]);
-    let wal: Result<SerdeWal<CollectionUpdateOperations>, _> =
-        SerdeWal::new(wal_path.to_str().unwrap(), WalOptions::default());
-
-    match wal {
-        Err(error) => {
-            eprintln!("Unable to open write ahead log in directory {wal_path:?}: {error}.");
+/// e.g `cargo run --bin wal_inspector dump storage/collections/test-collection/0/wal/`
+///
+/// WAL is opened in read-only mode, so it is safe to inspect WAL of a running node.
+///
+/// Exit codes:
+/// - 2 - invalid arguments
+/// - 3 - WAL can't be opened
+/// - 4 - WAL contains corrupted records
+#[derive(Parser, Debug)]
+#[command(about)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print all records
+    Dump {
+        /// Path to the WAL directory
+        path: PathBuf,
+    },
+    /// Print record size statistics
+    Stats {
+        /// Path to the WAL directory
+        path: PathBuf,
+    },
+    /// Check that all records can be read
+    Check {
+        /// Path to the WAL directory
+        path: PathBuf,
+    },
+    /// Remove records starting from the first corrupted one.
+    /// Without `--allow-write` only reports what would be removed.
+    Repair {
+        /// Path to the WAL directory
+        path: PathBuf,
+        /// Open WAL for writing and actually remove corrupted records.
+        /// Never use it on WAL of a running node.
+        #[arg(long, action, default_value_t = false)]
+        allow_write: bool,
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+enum InspectorError {
+    #[error("Unable to open write ahead log in directory {path:?}: {error}")]
+    Open { path: PathBuf, error: String },
+    #[error(
+        "Directory {path:?} does not contain write ahead log segments. \
+         Expected a shard WAL directory, e.g. storage/collections/<collection>/<shard>/wal/"
+    )]
+    NoSegments { path: PathBuf },
+    #[error("Found {count} corrupted records, the first one is {first_op_num}")]
+    Corrupted { count: usize, first_op_num: u64 },
+    #[error("Unable to read records: {error}")]
+    Unreadable { error: String },
+}
+
+impl InspectorError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            InspectorError::Open { .. } | InspectorError::NoSegments { .. } => 3,
+            InspectorError::Corrupted { .. } | InspectorError::Unreadable { .. } => 4,
         }
-        Ok(wal) => {
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    // Exits with code 2 and prints usage on invalid arguments
+    let args = Args::parse();
+
+    match run(args.command) {
+        Err(error) => match error.downcast_ref::<InspectorError>() {
+            Some(inspector_error) => {
+                eprintln!("{inspector_error}");
+                std::process::exit(inspector_error.exit_code());
+            }
+            None => Err(error),
+        },
+        Ok(()) => Ok(()),
+    }
+}
+
+fn run(command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::Dump { path } => {
+            let wal = open_wal(&path, false)?;
             // print all entries
             let mut count = 0;
-            for (idx, op) in wal.read_all() {
+            let mut corrupted = Vec::new();
+            for (idx, op) in wal.try_read_all() {
                 println!("==========================");
                 println!("Entry {}", idx);
-                println!("{:?}", op);
+                match op {
+                    Ok(op) => println!("{:?}", op),
+                    Err(error) => {
+                        println!("Corrupted entry: {error}");
+                        corrupted.push(idx);
+                    }
+                }
                 count += 1;
             }
             println!("==========================");
             println!("End of WAL.");
             println!("Found {} entries.", count);
+            if let Some(&first_op_num) = corrupted.first() {
+                return Err(InspectorError::Corrupted {
+                    count: corrupted.len(),
+                    first_op_num,
+                }
+                .into());
+            }
+        }
+        Command::Stats { path } => {
+            let wal = open_wal(&path, false)?;
+            let stats = wal.stats().map_err(|err| InspectorError::Unreadable {
+                error: err.to_string(),
+            })?;
+            println!("Records: {}", stats.records);
+            println!("Compressed records: {}", stats.compressed_records);
+            println!("Stored bytes: {}", stats.stored_bytes);
+            println!("Uncompressed bytes: {}", stats.uncompressed_bytes);
+        }
+        Command::Check { path } => {
+            let wal = open_wal(&path, false)?;
+            check_records(&wal)?;
+            println!("All {} records are readable.", wal.len());
         }
+        Command::Repair { path, allow_write } => {
+            let mut wal = open_wal(&path, allow_write)?;
+            let Err(error) = check_records(&wal) else {
+                println!("All {} records are readable, nothing to repair.", wal.len());
+                return Ok(());
+            };
+            let Some(InspectorError::Corrupted { first_op_num, .. }) =
+                error.downcast_ref::<InspectorError>()
+            else {
+                return Err(error);
+            };
+            let removed = wal.last_index() + 1 - first_op_num;
+            if allow_write {
+                wal.truncate(*first_op_num)?;
+                wal.flush()?;
+                println!("Removed {removed} records starting from {first_op_num}.");
+            } else {
+                println!(
+                    "Would remove {removed} records starting from {first_op_num}. \
+                     Pass --allow-write to apply."
+                );
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn open_wal(
+    path: &Path,
+    allow_write: bool,
+) -> Result<SerdeWal<OperationWithClientId>, InspectorError> {
+    let has_segments = std::fs::read_dir(path)
+        .map_err(|err| InspectorError::Open {
+            path: path.to_owned(),
+            error: err.to_string(),
+        })?
+        .filter_map(|entry| entry.ok())
+        .any(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("open-") || name.starts_with("closed-")
+        });
+    if !has_segments {
+        return Err(InspectorError::NoSegments {
+            path: path.to_owned(),
+        });
+    }
+
+    let path_str = path.to_string_lossy();
+    let wal = if allow_write {
+        SerdeWal::new(&path_str, WalOptions::default())
+    } else {
+        SerdeWal::open_read_only(&path_str)
+    };
+    wal.map_err(|err| InspectorError::Open {
+        path: path.to_owned(),
+        error: err.to_string(),
+    })
+}
+
+fn check_records(wal: &SerdeWal<OperationWithClientId>) -> anyhow::Result<()> {
+    let mut corrupted = wal.try_read_all().filter_map(|(idx, op)| {
+        op.err().map(|error| {
+            eprintln!("Corrupted entry {idx}: {error}");
+            idx
+        })
+    });
+    let Some(first_op_num) = corrupted.next() else {
+        return Ok(());
+    };
+    let count = 1 + corrupted.count();
+    Err(InspectorError::Corrupted {
+        count,
+        first_op_num,
     }
+    .into())
 }
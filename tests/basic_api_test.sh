@@ -138,3 +138,36 @@ curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search"
       "vector": [0.2, 0.1, 0.9, 0.7],
       "top": 3
   }' | jq
+
+# search with malformed query vectors, rejected with the expected dimension
+for MALFORMED_VECTOR in '[]' '[0.2, 0.1, 0.9]' '[0.2, 0.1, 0.9, 0.7, 0.5]'; do
+  RESPONSE=$(curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search" \
+    -H 'Content-Type: application/json' \
+    -s -w '\n%{http_code}' \
+    --data-raw "{\"vector\": $MALFORMED_VECTOR, \"limit\": 3}")
+  [[ "$(tail -n 1 <<< "$RESPONSE")" == "400" ]] && grep -q 'expected dim: 4' <<< "$RESPONSE" || {
+    echo "check failed - search with vector $MALFORMED_VECTOR must be rejected"
+    exit 1
+  }
+done
+
+# search points batch with a malformed query vector, which fails only its own search
+BATCH_RESULT=$(curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search/batch" \
+  -H 'Content-Type: application/json' \
+  --fail -s \
+  --data-raw '{
+    "searches": [
+      {
+        "vector": [0.2,0.1,0.9,0.7],
+        "limit": 3
+      },
+      {
+        "vector": [0.2,0.1,0.9],
+        "limit": 3
+      }
+    ]
+  }')
+[[ "$(jq '.result[0] | length' <<< "$BATCH_RESULT")" == "3" ]] && jq -e '.result[1].error | contains("expected dim: 4, got 3")' <<< "$BATCH_RESULT" || {
+  echo 'check failed - only the malformed search of the batch must fail'
+  exit 1
+}
@@ -106,6 +106,28 @@ $docker_grpcurl -d '{
   "negative": [{ "num": 2 }]
 }' $QDRANT_HOST qdrant.Points/Recommend
 
+# upsert stream of two batches
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "wait": true,
+  "points": [
+    {"id": { "num": 10 }, "vectors": {"vector": {"data": [0.11, 0.52, 0.33, 0.74]}}},
+    {"id": { "num": 11 }, "vectors": {"vector": {"data": [0.81, 0.12, 0.53, 0.24]}}}
+  ]
+}
+{
+  "collection_name": "test_collection",
+  "wait": true,
+  "points": [
+    {"id": { "num": 12 }, "vectors": {"vector": {"data": [0.41, 0.22, 0.93, 0.14]}}}
+  ]
+}' $QDRANT_HOST qdrant.Points/UpsertStream
+
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "exact": true
+}' $QDRANT_HOST qdrant.Points/Count | grep '"count": "9"'
+
 # create alias
 $docker_grpcurl -d '{
   "actions": [
@@ -165,6 +187,38 @@ $docker_grpcurl -d '{
   "ids": [{ "num": 1 }]
 }' $QDRANT_HOST qdrant.Points/Get
 
+# search with malformed query vectors, rejected with a description of the problem
+for MALFORMED in '[]|expected dim: 4' '[0.2,0.1,0.9]|expected dim: 4, got 3' '[0.2,"NaN",0.9,0.7]|position 1'; do
+  MALFORMED_VECTOR=${MALFORMED%%|*}
+  EXPECTED_ERROR=${MALFORMED#*|}
+  RESPONSE=$($docker_grpcurl -d "{
+    \"collection_name\": \"test_collection\",
+    \"vector\": $MALFORMED_VECTOR,
+    \"limit\": 3
+  }" $QDRANT_HOST qdrant.Points/Search 2>&1 || true)
+  grep -q 'InvalidArgument' <<< "$RESPONSE" && grep -qF "$EXPECTED_ERROR" <<< "$RESPONSE" || {
+    echo "check failed - search with vector $MALFORMED_VECTOR must be rejected"
+    exit 1
+  }
+done
+
+# search batch with a malformed query vector, which fails only its own search
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "search_points": [
+    {
+      "collection_name": "test_collection",
+      "vector": [0.2,0.1,0.9,0.7],
+      "limit": 3
+    },
+    {
+      "collection_name": "test_collection",
+      "vector": [0.2,0.1,0.9],
+      "limit": 3
+    }
+  ]
+}' $QDRANT_HOST qdrant.Points/SearchBatch | grep 'expected dim: 4, got 3'
+
 #SAVED_VECTORS_COUNT=$(curl --fail -s "http://$QDRANT_HOST/collections/test_collection" | jq '.result.vectors_count')
 #[[ "$SAVED_VECTORS_COUNT" == "6" ]] || {
 #  echo 'check failed'
@@ -0,0 +1,79 @@
+import pathlib
+import threading
+
+from .assertions import assert_http_ok
+from .fixtures import create_collection, random_vector, upsert_random_points
+from .utils import *
+
+N_PEERS = 3
+N_SWAPS = 20
+ALIAS_NAME = "test_alias"
+COLLECTION_NAMES = ["test_collection_v1", "test_collection_v2"]
+
+
+def swap_alias(peer_api_uri: str, collection_name: str):
+    r = requests.post(f"{peer_api_uri}/collections/aliases", json={
+        "actions": [
+            {"delete_alias": {"alias_name": ALIAS_NAME}},
+            {"create_alias": {"alias_name": ALIAS_NAME, "collection_name": collection_name}},
+        ]
+    })
+    assert_http_ok(r)
+
+
+def search_by_alias(peer_api_uri: str) -> requests.Response:
+    return requests.post(f"{peer_api_uri}/collections/{ALIAS_NAME}/points/search", json={
+        "vector": random_vector(),
+        "limit": 5,
+    })
+
+
+def check_alias_resolved(peer_api_uri: str) -> bool:
+    return search_by_alias(peer_api_uri).status_code == 200
+
+
+def test_alias_swap(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    for collection_name in COLLECTION_NAMES:
+        create_collection(peer_api_uris[0], collection=collection_name, shard_number=N_PEERS)
+        wait_collection_exists_and_active_on_all_peers(collection_name=collection_name, peer_api_uris=peer_api_uris)
+        upsert_random_points(peer_api_uris[0], 100, collection_name=collection_name)
+
+    swap_alias(peer_api_uris[0], COLLECTION_NAMES[0])
+    for uri in peer_api_uris:
+        wait_for(check_alias_resolved, uri)
+
+    failures = []
+    stop = threading.Event()
+
+    def hammer(peer_api_uri: str):
+        while not stop.is_set():
+            r = search_by_alias(peer_api_uri)
+            if r.status_code != 200:
+                failures.append((peer_api_uri, r.status_code, r.text))
+
+    threads = [threading.Thread(target=hammer, args=(uri,)) for uri in peer_api_uris]
+    for thread in threads:
+        thread.start()
+
+    try:
+        # Swaps are submitted to different peers, each swap is a single consensus operation
+        for i in range(N_SWAPS):
+            swap_alias(peer_api_uris[i % N_PEERS], COLLECTION_NAMES[(i + 1) % 2])
+    finally:
+        stop.set()
+        for thread in threads:
+            thread.join()
+
+    assert failures == []
+
+    # All peers resolve the alias to the same collection after the last swap
+    expected_collection = COLLECTION_NAMES[N_SWAPS % 2]
+    for uri in peer_api_uris:
+        r = requests.get(f"{uri}/aliases")
+        assert_http_ok(r)
+        aliases = {alias["alias_name"]: alias["collection_name"] for alias in r.json()["result"]["aliases"]}
+        assert aliases == {ALIAS_NAME: expected_collection}
@@ -0,0 +1,100 @@
+import pathlib
+
+from .assertions import assert_http_ok
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+
+N_PEERS = 3
+N_SHARDS = 3
+N_POINTS = 100
+COLLECTION_NAME = "test_collection"
+RECOVERED_COLLECTION_NAME = "test_collection_recovered"
+ALIAS_NAME = "test_alias"
+
+
+def create_snapshot(peer_api_uri: str) -> dict:
+    r = requests.post(f"{peer_api_uri}/collections/{COLLECTION_NAME}/snapshots")
+    assert_http_ok(r)
+    return r.json()["result"]
+
+
+def list_snapshots(peer_api_uri: str) -> list:
+    r = requests.get(f"{peer_api_uri}/collections/{COLLECTION_NAME}/snapshots")
+    assert_http_ok(r)
+    return r.json()["result"]
+
+
+def recover_snapshot(peer_api_uri: str, collection_name: str, snapshot_url: str) -> requests.Response:
+    return requests.put(f"{peer_api_uri}/collections/{collection_name}/snapshots/recover",
+                        json={"location": snapshot_url})
+
+
+def count_points(peer_api_uri: str, collection_name: str) -> int:
+    r = requests.post(f"{peer_api_uri}/collections/{collection_name}/points/count", json={"exact": True})
+    assert_http_ok(r)
+    return r.json()["result"]["count"]
+
+
+def get_aliases(peer_api_uri: str) -> dict:
+    r = requests.get(f"{peer_api_uri}/aliases")
+    assert_http_ok(r)
+    return {alias["alias_name"]: alias["collection_name"] for alias in r.json()["result"]["aliases"]}
+
+
+def test_collection_snapshot_round_trip(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    # Each peer holds a single shard, so the snapshot has to gather shards from all peers
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=1)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+    upsert_random_points(peer_api_uris[0], N_POINTS)
+
+    r = requests.post(f"{peer_api_uris[0]}/collections/aliases", json={
+        "actions": [{"create_alias": {"alias_name": ALIAS_NAME, "collection_name": COLLECTION_NAME}}]
+    })
+    assert_http_ok(r)
+
+    snapshot = create_snapshot(peer_api_uris[0])
+    assert snapshot["size"] > 0
+    assert snapshot["checksum"]
+
+    snapshots = list_snapshots(peer_api_uris[0])
+    assert [(s["name"], s["size"], s["checksum"]) for s in snapshots] == \
+           [(snapshot["name"], snapshot["size"], snapshot["checksum"])]
+
+    snapshot_url = f"{peer_api_uris[0]}/collections/{COLLECTION_NAME}/snapshots/{snapshot['name']}"
+    r = requests.get(snapshot_url)
+    assert_http_ok(r)
+    assert len(r.content) == snapshot["size"]
+
+    # Alias is restored with the snapshot, once it is not taken by the original collection
+    r = requests.post(f"{peer_api_uris[0]}/collections/aliases", json={
+        "actions": [{"delete_alias": {"alias_name": ALIAS_NAME}}]
+    })
+    assert_http_ok(r)
+
+    # Every peer recovers its own shards of the new collection from the same snapshot
+    for uri in peer_api_uris:
+        assert_http_ok(recover_snapshot(uri, RECOVERED_COLLECTION_NAME, snapshot_url))
+        wait_collection_exists_and_active_on_all_peers(
+            collection_name=RECOVERED_COLLECTION_NAME, peer_api_uris=peer_api_uris)
+
+    for uri in peer_api_uris:
+        wait_for_all_replicas_active(uri, RECOVERED_COLLECTION_NAME)
+        assert count_points(uri, RECOVERED_COLLECTION_NAME) == N_POINTS
+        assert get_aliases(uri) == {ALIAS_NAME: RECOVERED_COLLECTION_NAME}
+
+    # Snapshot is refused by a collection with a different vector config
+    r = requests.put(f"{peer_api_uris[0]}/collections/mismatched_collection", json={
+        "vectors": {"size": 8, "distance": "Dot"},
+    })
+    assert_http_ok(r)
+    r = recover_snapshot(peer_api_uris[0], "mismatched_collection", snapshot_url)
+    assert r.status_code == 400
+    assert "not compatible" in r.json()["status"]["error"]
+
+    r = requests.delete(snapshot_url)
+    assert_http_ok(r)
+    assert list_snapshots(peer_api_uris[0]) == []
@@ -0,0 +1,119 @@
+import pathlib
+import threading
+
+from .assertions import assert_http_ok
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+
+N_PEERS = 3
+N_POINTS = 20
+N_ROUNDS = 10
+COLLECTION_NAME = "test_collection"
+
+
+def get_point(peer_api_uri: str, point_id: int) -> dict:
+    r = requests.post(f"{peer_api_uri}/collections/{COLLECTION_NAME}/points", json={
+        "ids": [point_id],
+        "with_payload": True,
+        "with_version": True,
+    })
+    assert_http_ok(r)
+    return r.json()["result"][0]
+
+
+def set_payload(peer_api_uri: str, point_ids: list, payload: dict, if_version: int) -> requests.Response:
+    return requests.post(f"{peer_api_uri}/collections/{COLLECTION_NAME}/points/payload?wait=true", json={
+        "points": point_ids,
+        "payload": payload,
+        "if_version": if_version,
+    })
+
+
+def race_conditional_updates(peer_api_uris: list, point_id: int) -> int:
+    """Two clients update the same version of the point at once through different peers,
+    only one of them must succeed. Returns the winning client."""
+    version = get_point(peer_api_uris[0], point_id)["version"]
+
+    barrier = threading.Barrier(2)
+    responses = {}
+
+    def update(client: int):
+        barrier.wait()
+        responses[client] = set_payload(peer_api_uris[client], [point_id], {"client": client}, version)
+
+    threads = [threading.Thread(target=update, args=(client,)) for client in range(2)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+
+    status_codes = sorted(r.status_code for r in responses.values())
+    assert status_codes == [200, 409], [r.text for r in responses.values()]
+
+    winner = next(client for client, r in responses.items() if r.status_code == 200)
+    loser = responses[1 - winner]
+    assert f"point {point_id} has version" in loser.json()["status"]["error"]
+
+    point = get_point(peer_api_uris[2], point_id)
+    assert point["payload"]["client"] == winner
+    assert point["version"] > version
+    return winner
+
+
+def test_conditional_update_race(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_api_uris[0], shard_number=N_PEERS, replication_factor=1)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+    upsert_random_points(peer_api_uris[0], N_POINTS)
+
+    for round_num in range(N_ROUNDS):
+        race_conditional_updates(peer_api_uris, round_num % N_POINTS)
+
+    # Conflict of a single point rejects the whole update, no point of the batch is changed
+    untouched = [get_point(peer_api_uris[0], point_id) for point_id in range(N_ROUNDS, N_POINTS)]
+    first, second = next(
+        (a, b) for a in untouched for b in untouched if a["id"] < b["id"] and a["version"] == b["version"]
+    )
+    version = first["version"]
+    assert_http_ok(set_payload(peer_api_uris[0], [first["id"]], {"client": 0}, version))
+
+    r = set_payload(peer_api_uris[1], [first["id"], second["id"]], {"client": 1}, version)
+    assert r.status_code == 409
+    error = r.json()["status"]["error"]
+    assert f"point {first['id']} has version" in error
+    assert f"point {second['id']} has version" not in error
+
+    assert get_point(peer_api_uris[2], first["id"])["payload"]["client"] == 0
+    assert "client" not in get_point(peer_api_uris[2], second["id"])["payload"]
+
+
+def test_conditional_update_replicated(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_api_uris[0], shard_number=1, replication_factor=N_PEERS)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+    upsert_random_points(peer_api_uris[0], N_POINTS)
+
+    winners = {
+        point_id: race_conditional_updates(peer_api_uris, point_id)
+        for point_id in range(N_ROUNDS)
+    }
+
+    # Rejected updates do not deactivate replicas, and all replicas apply the same updates
+    for peer_api_uri in peer_api_uris:
+        assert check_all_replicas_active(peer_api_uri, COLLECTION_NAME)
+
+    for peer_api_uri in peer_api_uris:
+        r = requests.post(f"{peer_api_uri}/collections/{COLLECTION_NAME}/points/scroll?consistency=all", json={
+            "limit": N_POINTS,
+            "with_payload": True,
+        })
+        assert_http_ok(r)
+        payloads = {point["id"]: point["payload"].get("client") for point in r.json()["result"]["points"]}
+        for point_id, winner in winners.items():
+            assert payloads[point_id] == winner
@@ -0,0 +1,89 @@
+import pathlib
+
+from .assertions import assert_http_ok
+from .fixtures import create_collection, drop_collection, upsert_random_points
+from .utils import *
+
+N_PEERS = 3
+N_COLLECTIONS = 5
+COLLECTION_NAME = "test_collection"
+
+COMPACTION_ENV = {
+    "QDRANT__CLUSTER__CONSENSUS__COMPACT_WAL_ENTRIES": "5",
+}
+
+
+def create_alias(peer_api_uri: str, alias_name: str, collection_name: str):
+    r = requests.post(f"{peer_api_uri}/collections/aliases", json={
+        "actions": [{"create_alias": {"alias_name": alias_name, "collection_name": collection_name}}]
+    })
+    assert_http_ok(r)
+
+
+def get_collection_names(peer_api_uri: str) -> set:
+    r = requests.get(f"{peer_api_uri}/collections")
+    assert_http_ok(r)
+    return {collection["name"] for collection in r.json()["result"]["collections"]}
+
+
+def get_aliases(peer_api_uri: str) -> dict:
+    r = requests.get(f"{peer_api_uri}/aliases")
+    assert_http_ok(r)
+    return {alias["alias_name"]: alias["collection_name"] for alias in r.json()["result"]["aliases"]}
+
+
+def check_wal_compacted(peer_api_uri: str) -> bool:
+    telemetry = get_cluster_info(peer_api_uri)["consensus_telemetry"]
+    return telemetry["snapshot_index"] > 0 and telemetry["first_wal_index"] > 1
+
+
+def check_same_state(peer_api_uri: str, expected_collections: set, expected_aliases: dict) -> bool:
+    return get_collection_names(peer_api_uri) == expected_collections \
+        and get_aliases(peer_api_uri) == expected_aliases
+
+
+def test_new_peer_joins_compacted_cluster(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, _, bootstrap_uri = start_cluster(tmp_path, N_PEERS, extra_env=COMPACTION_ENV)
+
+    # Generate enough consensus operations to compact the WAL
+    for i in range(N_COLLECTIONS):
+        create_collection(peer_api_uris[0], collection=f"{COLLECTION_NAME}_{i}", shard_number=N_PEERS)
+        create_alias(peer_api_uris[0], f"alias_{i}", f"{COLLECTION_NAME}_{i}")
+    drop_collection(peer_api_uris[0], collection=f"{COLLECTION_NAME}_0")
+    r = requests.post(f"{peer_api_uris[0]}/collections/aliases", json={
+        "actions": [
+            {"delete_alias": {"alias_name": "alias_1"}},
+            {"create_alias": {"alias_name": "alias_1", "collection_name": f"{COLLECTION_NAME}_2"}},
+        ]
+    })
+    assert_http_ok(r)
+    upsert_random_points(peer_api_uris[0], 100, collection_name=f"{COLLECTION_NAME}_1")
+
+    for uri in peer_api_uris:
+        wait_for(check_wal_compacted, uri)
+
+    expected_collections = get_collection_names(peer_api_uris[0])
+    expected_aliases = get_aliases(peer_api_uris[0])
+    assert f"{COLLECTION_NAME}_0" not in expected_collections
+    assert expected_aliases["alias_1"] == f"{COLLECTION_NAME}_2"
+
+    # New peer can't replay the compacted entries and receives the snapshot instead
+    new_peer_dir = make_peer_folder(tmp_path, N_PEERS)
+    new_peer_uri = start_peer(new_peer_dir, f"peer_0_{N_PEERS}.log", bootstrap_uri, extra_env=COMPACTION_ENV)
+    wait_peer_added(peer_api_uris[0], expected_size=N_PEERS + 1)
+    wait_for_peer_online(new_peer_uri)
+
+    wait_for(check_same_state, new_peer_uri, expected_collections, expected_aliases)
+    assert get_cluster_info(new_peer_uri)["consensus_telemetry"]["snapshot_index"] > 0
+    assert set(get_cluster_info(new_peer_uri)["peers"]) == set(get_cluster_info(peer_api_uris[0])["peers"])
+
+    for collection_name in expected_collections:
+        new_peer_info = get_collection_cluster_info(new_peer_uri, collection_name)
+        existing_info = get_collection_cluster_info(peer_api_uris[0], collection_name)
+        assert new_peer_info["shard_count"] == existing_info["shard_count"]
+
+    # New peer keeps following the log after the snapshot
+    create_collection(peer_api_uris[0], collection=COLLECTION_NAME)
+    wait_collection_exists_and_active_on_all_peers(COLLECTION_NAME, peer_api_uris + [new_peer_uri])
@@ -0,0 +1,90 @@
+import multiprocessing
+import pathlib
+import random
+
+from .assertions import assert_http_ok
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+
+N_PEERS = 3
+N_SHARDS = 1
+N_REPLICAS = 3
+N_POINTS = 100
+COLLECTION_NAME = "test_collection"
+
+
+def city_filter(city: str) -> dict:
+    return {"must": [{"key": "city", "match": {"value": city}}]}
+
+
+def delete_by_filter(peer_url: str, city: str, wait: str = 'true') -> requests.Response:
+    return requests.post(
+        f"{peer_url}/collections/{COLLECTION_NAME}/points/delete?wait={wait}&with_deleted_ids=true",
+        json={"filter": city_filter(city)},
+    )
+
+
+def update_points_in_loop(peer_url: str):
+    while True:
+        offset = random.randint(0, N_POINTS - 5)
+        upsert_random_points(peer_url, 5, COLLECTION_NAME, offset=offset, wait='false', ordering='medium')
+
+
+def delete_points_in_loop(peer_url: str):
+    while True:
+        delete_by_filter(peer_url, "London", wait='false')
+        time.sleep(0.05)
+
+
+def get_all_points(peer_url: str) -> list:
+    r = requests.post(f"{peer_url}/collections/{COLLECTION_NAME}/points/scroll", json={
+        "limit": N_POINTS,
+        "with_payload": True,
+    })
+    assert_http_ok(r)
+    return r.json()["result"]["points"]
+
+
+def test_delete_by_filter_converges_on_diverged_replicas(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=N_REPLICAS)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+    upsert_random_points(peer_api_uris[0], N_POINTS)
+
+    # Delete through every peer, deletes are forwarded to the leader and resolved there
+    for peer_url in peer_api_uris:
+        london = [point["id"] for point in get_all_points(peer_url) if point["payload"]["city"] == "London"]
+        r = delete_by_filter(peer_url, "London")
+        assert_http_ok(r)
+        result = r.json()["result"]
+        assert result["deleted_count"] == len(london)
+        assert sorted(result["deleted_ids"]) == sorted(london)
+        upsert_random_points(peer_api_uris[0], N_POINTS)
+
+    # Replicas lag behind the leader with unacknowledged upserts through every peer,
+    # while the points are deleted by filter through every peer
+    processes = [
+        multiprocessing.Process(target=target, args=(peer_url,))
+        for peer_url in peer_api_uris
+        for target in (update_points_in_loop, delete_points_in_loop)
+    ]
+    for process in processes:
+        process.start()
+    time.sleep(5)
+    for process in processes:
+        process.kill()
+
+    # Replicas converge, as all of them delete the same points
+    delete_by_filter(peer_api_uris[0], "London")
+    time.sleep(1)
+
+    for peer_url in peer_api_uris:
+        assert check_all_replicas_active(peer_url, COLLECTION_NAME)
+
+    points = [get_all_points(peer_url) for peer_url in peer_api_uris]
+    assert all(point["payload"]["city"] != "London" for point in points[0])
+    for peer_points in points[1:]:
+        assert peer_points == points[0]
@@ -0,0 +1,97 @@
+import pathlib
+
+from .assertions import assert_http_ok
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+
+N_PEERS = 3
+N_SHARDS = 6
+N_POINTS = 1000
+COLLECTION_NAME = "test_collection"
+
+
+def drain_peer(peer_api_uri: str, peer_id: int):
+    r = requests.delete(f"{peer_api_uri}/cluster/peer/{peer_id}?drain=true")
+    assert_http_ok(r)
+
+
+def get_drain_info(peer_api_uri: str, peer_id: int) -> dict:
+    r = requests.get(f"{peer_api_uri}/cluster/peer/{peer_id}/drain")
+    assert_http_ok(r)
+    return r.json()["result"]
+
+
+def check_drain_stopped(peer_api_uri: str, peer_id: int) -> bool:
+    return get_drain_info(peer_api_uri, peer_id)["status"] != "in_progress"
+
+
+def count_points(peer_api_uri: str) -> int:
+    r = requests.post(f"{peer_api_uri}/collections/{COLLECTION_NAME}/points/count", json={"exact": True})
+    assert_http_ok(r)
+    return r.json()["result"]["count"]
+
+
+def peers_with_replicas(peer_api_uri: str) -> set:
+    info = get_collection_cluster_info(peer_api_uri, COLLECTION_NAME)
+    peers = {shard["peer_id"] for shard in info["remote_shards"]}
+    if info["local_shards"]:
+        peers.add(info["peer_id"])
+    return peers
+
+
+def test_drain_peer(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=1)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+    upsert_random_points(peer_api_uris[0], N_POINTS)
+
+    drained_peer_id = get_cluster_info(peer_api_uris[-1])["peer_id"]
+    assert drained_peer_id in peers_with_replicas(peer_api_uris[0])
+
+    # Drain and force removal are exclusive
+    r = requests.delete(f"{peer_api_uris[0]}/cluster/peer/{drained_peer_id}?drain=true&force=true")
+    assert r.status_code == 400
+
+    drain_peer(peer_api_uris[0], drained_peer_id)
+    wait_for(check_drain_stopped, peer_api_uris[0], drained_peer_id)
+
+    info = get_drain_info(peer_api_uris[0], drained_peer_id)
+    assert info["status"] == "finished", info
+    assert info["remaining_replicas"] == 0
+    assert info["total_replicas"] > 0
+
+    # Peer is removed from the cluster, its shards are moved to the remaining peers
+    remaining_uris = peer_api_uris[:-1]
+    for uri in remaining_uris:
+        assert str(drained_peer_id) not in get_cluster_info(uri)["peers"]
+        wait_for_all_replicas_active(uri, COLLECTION_NAME)
+        assert drained_peer_id not in peers_with_replicas(uri)
+        assert get_collection_cluster_info(uri, COLLECTION_NAME)["shard_count"] == N_SHARDS
+        assert count_points(uri) == N_POINTS
+
+
+def test_drain_peer_without_target(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    # Every peer holds every shard, so replicas can't be moved anywhere
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=N_PEERS)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+    upsert_random_points(peer_api_uris[0], N_POINTS)
+
+    drained_peer_id = get_cluster_info(peer_api_uris[-1])["peer_id"]
+    drain_peer(peer_api_uris[0], drained_peer_id)
+    wait_for(check_drain_stopped, peer_api_uris[0], drained_peer_id)
+
+    info = get_drain_info(peer_api_uris[0], drained_peer_id)
+    assert info["status"] == "failed", info
+    assert "no peer to move" in info["error"]
+
+    # Peer stays in the cluster with all its replicas
+    assert str(drained_peer_id) in get_cluster_info(peer_api_uris[0])["peers"]
+    assert drained_peer_id in peers_with_replicas(peer_api_uris[0])
+    assert count_points(peer_api_uris[-1]) == N_POINTS
@@ -0,0 +1,72 @@
+import pathlib
+import signal
+import threading
+
+from .fixtures import create_collection, random_vector
+from .utils import *
+
+COLLECTION_NAME = "test_collection"
+BATCH_SIZE = 100
+
+
+def upsert_batches(peer_url, acknowledged_ids, stop_event):
+    offset = 0
+    while not stop_event.is_set():
+        ids = list(range(offset, offset + BATCH_SIZE))
+        try:
+            r = requests.put(
+                f"{peer_url}/collections/{COLLECTION_NAME}/points?wait=true", json={
+                    "points": [
+                        {"id": i, "vector": random_vector()} for i in ids
+                    ]
+                })
+        except requests.exceptions.ConnectionError:
+            return
+        if r.status_code == 503:
+            # Node is shutting down, new requests are rejected
+            return
+        assert_http_ok(r)
+        acknowledged_ids.extend(ids)
+        offset += BATCH_SIZE
+
+
+def test_graceful_shutdown(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    # Stable port, so the restarted peer keeps its uri
+    first_peer_port = get_port()
+    peer_dirs = make_peer_folders(tmp_path, 1)
+    (peer_url, _bootstrap_uri) = start_first_peer(peer_dirs[0], "peer_0_0.log", port=first_peer_port)
+    wait_peer_added(peer_url)
+
+    create_collection(peer_url)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=[peer_url])
+
+    acknowledged_ids = []
+    stop_event = threading.Event()
+    upsert_thread = threading.Thread(target=upsert_batches, args=(peer_url, acknowledged_ids, stop_event))
+    upsert_thread.start()
+
+    # Shut down in the middle of the batch upserts
+    wait_for(lambda: len(acknowledged_ids) >= 10 * BATCH_SIZE)
+    peer_process = processes.pop()
+    peer_process.send_signal(signal.SIGTERM)
+    assert peer_process.wait(timeout=60) == 0
+
+    stop_event.set()
+    upsert_thread.join()
+    assert len(acknowledged_ids) > 0
+
+    # Restart the peer
+    (peer_url, _bootstrap_uri) = start_first_peer(peer_dirs[0], "peer_0_0_restarted.log", port=first_peer_port)
+    wait_for_peer_online(peer_url)
+
+    # No acknowledged point is lost
+    r = requests.post(
+        f"{peer_url}/collections/{COLLECTION_NAME}/points", json={
+            "ids": acknowledged_ids,
+        })
+    assert_http_ok(r)
+    found_ids = {point["id"] for point in r.json()["result"]}
+    missing_ids = set(acknowledged_ids) - found_ids
+    assert len(missing_ids) == 0, f"{len(missing_ids)} acknowledged points are lost"
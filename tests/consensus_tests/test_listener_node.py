@@ -49,6 +49,26 @@ def wait_no_listener_node(peer_api_uri: str, collection_name: str):
         raise e
 
 
+def local_shard_state(peer_api_uri: str, collection_name: str):
+    res = requests.get(f"{peer_api_uri}/collections/{collection_name}/cluster", timeout=10)
+    assert_http_ok(res)
+    local_shards = res.json()["result"]["local_shards"]
+    return local_shards[0]["state"] if local_shards else None
+
+
+def has_local_shard_state(peer_api_uri: str, collection_name: str, state: str):
+    return local_shard_state(peer_api_uri, collection_name) == state
+
+
+def has_peer_role(peer_api_uri: str, peer_id: int, role: str):
+    peers = get_cluster_info(peer_api_uri)["peers"]
+    return peers.get(str(peer_id), {}).get("role") == role
+
+
+def update_cluster(peer_api_uri: str, collection_name: str, operation: dict):
+    return requests.post(f"{peer_api_uri}/collections/{collection_name}/cluster", json=operation, timeout=10)
+
+
 def test_listener_node(tmp_path: pathlib.Path):
 
     assert_project_root()
@@ -102,9 +122,116 @@ def test_listener_node(tmp_path: pathlib.Path):
         )
     )
 
+    # Listener replica of a normal node is converted back only explicitly
+    wait_for_peer_online(peer_api_uris[-1])
+    assert has_listener_shard(peer_api_uris[0], COLLECTION_NAME)
+    peer_id = get_cluster_info(peer_api_uris[-1])["peer_id"]
+    res = update_cluster(peer_api_uris[0], COLLECTION_NAME, {
+        "convert_to_active": {"shard_id": 0, "peer_id": peer_id}
+    })
+    assert_http_ok(res)
+
     for peer_api_uri in peer_api_uris:
         wait_no_listener_node(peer_api_uri, COLLECTION_NAME)
 
     res2 = search(peer_api_uris[0], query_vector, city="London", collection=COLLECTION_NAME)
 
     assert res1 == res2
+
+
+def test_listener_replicas(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    # Two normal peers and a listener, which joins the cluster as such
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS - 1)
+    leader = get_leader(peer_api_uris[0])
+    listener_dir = make_peer_folder(tmp_path, N_PEERS - 1)
+    listener_api_uri = start_peer(
+        listener_dir,
+        f"peer_0_{N_PEERS - 1}.log",
+        bootstrap_uri,
+        extra_env={
+            "QDRANT__STORAGE__NODE_TYPE": "Listener",
+        }
+    )
+    peer_api_uris.append(listener_api_uri)
+    wait_for_uniform_cluster_status(peer_api_uris, leader)
+
+    # Listener stays a learner of the consensus, the cluster API shows its role
+    listener_peer_id = get_cluster_info(listener_api_uri)["peer_id"]
+    normal_peer_id = get_cluster_info(peer_api_uris[1])["peer_id"]
+    wait_for(has_peer_role, peer_api_uris[0], normal_peer_id, "Voter")
+    for peer_api_uri in peer_api_uris:
+        wait_for(has_peer_role, peer_api_uri, listener_peer_id, "Listener")
+    assert not get_cluster_info(listener_api_uri)["raft_info"]["is_voter"]
+
+    # Writes are acknowledged by all replicas, except for the listener one
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=N_REPLICAS,
+                      write_consistency_factor=N_REPLICAS)
+    wait_collection_on_all_peers(COLLECTION_NAME, peer_api_uris)
+    wait_for(has_local_shard_state, listener_api_uri, COLLECTION_NAME, "Listener")
+    for peer_api_uri in peer_api_uris[:-1]:
+        wait_for(has_local_shard_state, peer_api_uri, COLLECTION_NAME, "Active")
+
+    # Updates, received by the listener, are forwarded with any ordering
+    for offset, ordering in enumerate(["weak", "medium", "strong"]):
+        upsert_random_points(listener_api_uri, 10, offset=offset * 10, ordering=ordering)
+    wait_collection_size(listener_api_uri, COLLECTION_NAME, 30)
+
+    # Writes never route to the listener, they succeed while it is down
+    p = processes.pop()
+    p.kill()
+    offset = 30
+    for ordering in ["weak", "medium", "strong"]:
+        for peer_api_uri in peer_api_uris[:-1]:
+            upsert_random_points(peer_api_uri, 10, offset=offset, ordering=ordering)
+            offset += 10
+    for peer_api_uri in peer_api_uris[:-1]:
+        assert get_collection_info(peer_api_uri, COLLECTION_NAME)["points_count"] == 90
+        assert local_shard_state(peer_api_uri, COLLECTION_NAME) == "Active"
+
+    # Reads from the restarted listener converge
+    listener_api_uri = start_peer(
+        listener_dir,
+        f"peer_0_{N_PEERS - 1}_restart.log",
+        bootstrap_uri,
+        extra_env={
+            "QDRANT__STORAGE__NODE_TYPE": "Listener",
+        }
+    )
+    peer_api_uris[-1] = listener_api_uri
+    wait_for_peer_online(listener_api_uri)
+    wait_collection_size(listener_api_uri, COLLECTION_NAME, 90)
+    wait_for(has_local_shard_state, listener_api_uri, COLLECTION_NAME, "Listener")
+    query_vector = random_vector()
+    for city in ["London", "Berlin"]:
+        listener_res = search(listener_api_uri, query_vector, city=city, collection=COLLECTION_NAME)
+        assert listener_res == search(peer_api_uris[0], query_vector, city=city, collection=COLLECTION_NAME)
+
+    # Replicas of normal peers become listeners and back only explicitly,
+    # the last active replica and replicas of listener peers can't be converted
+    res = update_cluster(peer_api_uris[0], COLLECTION_NAME, {
+        "convert_to_listener": {"shard_id": 0, "peer_id": normal_peer_id}
+    })
+    assert_http_ok(res)
+    wait_for(has_local_shard_state, peer_api_uris[1], COLLECTION_NAME, "Listener")
+
+    first_peer_id = get_cluster_info(peer_api_uris[0])["peer_id"]
+    res = update_cluster(peer_api_uris[0], COLLECTION_NAME, {
+        "convert_to_listener": {"shard_id": 0, "peer_id": first_peer_id}
+    })
+    assert res.status_code == 400
+    res = update_cluster(peer_api_uris[0], COLLECTION_NAME, {
+        "convert_to_active": {"shard_id": 0, "peer_id": listener_peer_id}
+    })
+    assert res.status_code == 400
+
+    upsert_random_points(peer_api_uris[0], 10, offset=90, ordering="strong")
+    assert local_shard_state(peer_api_uris[1], COLLECTION_NAME) == "Listener"
+    wait_collection_size(peer_api_uris[1], COLLECTION_NAME, 100)
+
+    res = update_cluster(peer_api_uris[0], COLLECTION_NAME, {
+        "convert_to_active": {"shard_id": 0, "peer_id": normal_peer_id}
+    })
+    assert_http_ok(res)
+    wait_for(has_local_shard_state, peer_api_uris[1], COLLECTION_NAME, "Active")
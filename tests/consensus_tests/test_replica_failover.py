@@ -0,0 +1,69 @@
+import pathlib
+
+from .assertions import assert_http_ok
+from .fixtures import create_collection, upsert_random_points
+from .utils import *
+
+N_PEERS = 4
+N_SHARDS = 2
+N_REPLICA = 2
+COLLECTION_NAME = "test_collection"
+
+FAILOVER_ENV = {
+    "QDRANT__STORAGE__REPLICA_FAILOVER__ENABLED": "true",
+    "QDRANT__STORAGE__REPLICA_FAILOVER__GRACE_PERIOD_SEC": "1",
+}
+
+
+def all_replicas(peer_api_uri: str) -> dict:
+    """Returns replica states by shard id, replicas are mapped from peer id to state"""
+    info = get_collection_cluster_info(peer_api_uri, COLLECTION_NAME)
+    replicas = {}
+    for shard in info["local_shards"]:
+        replicas.setdefault(shard["shard_id"], {})[info["peer_id"]] = shard["state"]
+    for shard in info["remote_shards"]:
+        replicas.setdefault(shard["shard_id"], {})[shard["peer_id"]] = shard["state"]
+    return replicas
+
+
+def check_replication_factor_restored(peer_api_uri: str, dead_peer_id: int) -> bool:
+    for peers in all_replicas(peer_api_uri).values():
+        if dead_peer_id in peers:
+            return False
+        active = [peer_id for peer_id, state in peers.items() if state == "Active"]
+        if len(active) < N_REPLICA:
+            return False
+    return True
+
+
+def test_replica_failover(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS, extra_env=FAILOVER_ENV)
+
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=N_REPLICA)
+    wait_collection_exists_and_active_on_all_peers(collection_name=COLLECTION_NAME, peer_api_uris=peer_api_uris)
+    upsert_random_points(peer_api_uris[0], 100)
+
+    # Kill a peer, which holds replicas of the collection
+    dead_peer_uri = next(uri for uri in reversed(peer_api_uris)
+                         if get_collection_cluster_info(uri, COLLECTION_NAME)["local_shards"])
+    dead_peer_id = get_cluster_info(dead_peer_uri)["peer_id"]
+    dead_peer_index = peer_api_uris.index(dead_peer_uri)
+    assert dead_peer_index != 0, "consensus leader should stay alive"
+    processes.pop(dead_peer_index).kill()
+    peer_api_uris.pop(dead_peer_index)
+
+    # Replicas of the killed peer are marked dead on the next update
+    upsert_random_points(peer_api_uris[0], 100, offset=100)
+
+    # Dead replicas are replaced by new replicas on the remaining peers
+    wait_for(check_replication_factor_restored, peer_api_uris[0], dead_peer_id)
+
+    for peers in all_replicas(peer_api_uris[0]).values():
+        assert len(peers) == N_REPLICA
+
+    for uri in peer_api_uris:
+        r = requests.post(f"{uri}/collections/{COLLECTION_NAME}/points/count", json={"exact": True})
+        assert_http_ok(r)
+        assert r.json()["result"]["count"] == 200
@@ -0,0 +1,90 @@
+#!/bin/bash
+
+set -xeuo pipefail
+
+cd "$(dirname "${BASH_SOURCE[0]}")"
+
+declare DOCKER_IMAGE_NAME=qdrant-recovery
+
+docker buildx build --build-arg=PROFILE=ci --load ../../ --tag=$DOCKER_IMAGE_NAME
+
+declare container && container=$(
+    docker run --rm -d \
+        -m 128m \
+        -p 127.0.0.1:6333:6333 \
+        -p 127.0.0.1:6334:6334 \
+        $DOCKER_IMAGE_NAME
+)
+
+function cleanup {
+    docker stop $container
+}
+
+trap cleanup EXIT
+
+# Wait (up to ~30 seconds) for the service to start
+declare retry=0
+while [[ $(curl -sS localhost:6333 -w ''%{http_code}'' -o /dev/null) != 200 ]]; do
+    if ((retry++ < 30)); then
+        sleep 1
+    else
+        echo "Service failed to start in ~30 seconds" >&2
+        exit 1
+    fi
+done
+
+curl -sS --fail-with-body -X PUT localhost:6333/collections/large-upsert \
+    -H 'Content-Type: application/json' \
+    --data-raw '{
+        "vectors": { "size": 4, "distance": "Dot", "on_disk": true },
+        "on_disk_payload": true,
+        "optimizers_config": { "indexing_threshold": 0 }
+    }'
+
+# ~256 MB body, twice the memory limit of the container. Streamed with chunked encoding,
+# so that neither the generator nor curl hold it in memory.
+declare POINTS_COUNT=128000
+
+python3 -c "
+import sys
+text = 'x' * 2000
+sys.stdout.write('{\"points\": [')
+for n in range($POINTS_COUNT):
+    if n:
+        sys.stdout.write(',')
+    sys.stdout.write(f'{{\"id\": {n}, \"vector\": [0.1, 0.2, 0.3, {n % 10}], \"payload\": {{\"text\": \"{text}\"}}}}')
+sys.stdout.write(']}')
+" | curl -sS --fail-with-body -T - 'localhost:6333/collections/large-upsert/points?wait=true' \
+    -H 'Content-Type: application/json' \
+    -H 'Transfer-Encoding: chunked'
+
+declare count
+count="$(curl -sS --fail-with-body -X POST localhost:6333/collections/large-upsert/points/count \
+    -H 'Content-Type: application/json' \
+    --data-raw '{"exact": true}' | jq '.result.count')"
+
+if ((count != POINTS_COUNT)); then
+    echo "Expected $POINTS_COUNT points after the upsert, but found $count" >&2
+    exit 2
+fi
+
+# Bodies of other requests are buffered, and limited by `max_request_size_mb`
+declare status
+status="$(python3 -c "
+import sys
+sys.stdout.write('{\"points\": [0], \"payload\": {\"text\": \"' + 'x' * 40_000_000 + '\"}}')
+" | curl -sS -X POST localhost:6333/collections/large-upsert/points/payload \
+    -H 'Content-Type: application/json' \
+    --data-binary @- -w '%{http_code}' -o /dev/null)"
+
+if ((status != 413)); then
+    echo "Expected HTTP status 413 for a body over the limit, but received $status" >&2
+    exit 3
+fi
+
+if [[ "$(docker inspect "$container" --format='{{.State.OOMKilled}}')" != "false" ]]; then
+    echo "Qdrant was killed for running out of memory" >&2
+    exit 4
+fi
+
+echo "Success"